@@ -0,0 +1,398 @@
+use crate::{
+    absm::{
+        command::blend::SetBlendSpacePointPositionCommand,
+        selection::{AbsmSelection, SelectedEntity},
+    },
+    Message,
+};
+use fyrox::{
+    animation::machine::{BlendSpace, PoseNode},
+    core::{algebra::Vector2, color::Color, math::Rect, pool::Handle},
+    gui::{
+        brush::Brush,
+        define_constructor, define_widget_deref,
+        draw::{CommandTexture, Draw, DrawingContext},
+        message::{MessageDirection, MouseButton, UiMessage},
+        widget::{Widget, WidgetBuilder, WidgetMessage},
+        window::{WindowBuilder, WindowTitle},
+        BuildContext, Control, UiNode, UserInterface,
+    },
+    scene::animation::absm::AnimationBlendingStateMachine,
+};
+use std::{
+    any::{Any, TypeId},
+    ops::{Deref, DerefMut},
+    sync::mpsc::Sender,
+};
+
+const PADDING: f32 = 10.0;
+const POINT_RADIUS: f32 = 5.0;
+const GRID_DIVISIONS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlendSpaceFieldData {
+    pub min: Vector2<f32>,
+    pub max: Vector2<f32>,
+    pub points: Vec<Vector2<f32>>,
+    pub triangles: Vec<[usize; 3]>,
+    pub sampling_point: Vector2<f32>,
+    pub one_dimensional: bool,
+}
+
+impl BlendSpaceFieldData {
+    fn from_blend_space(blend_space: &BlendSpace, sampling_point: Vector2<f32>) -> Self {
+        Self {
+            min: blend_space.min_values,
+            max: blend_space.max_values,
+            points: blend_space.points.iter().map(|p| p.position).collect(),
+            triangles: blend_space.triangles(),
+            sampling_point,
+            one_dimensional: blend_space.is_one_dimensional(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlendSpaceFieldMessage {
+    Sync(BlendSpaceFieldData),
+    MovePoint {
+        index: usize,
+        position: Vector2<f32>,
+    },
+}
+
+impl BlendSpaceFieldMessage {
+    define_constructor!(BlendSpaceFieldMessage:Sync => fn sync(BlendSpaceFieldData), layout: false);
+    define_constructor!(BlendSpaceFieldMessage:MovePoint => fn move_point(index: usize, position: Vector2<f32>), layout: false);
+}
+
+#[derive(Clone)]
+pub struct BlendSpaceField {
+    widget: Widget,
+    data: BlendSpaceFieldData,
+    drag_index: Option<usize>,
+}
+
+define_widget_deref!(BlendSpaceField);
+
+impl BlendSpaceField {
+    fn field_rect(&self) -> Rect<f32> {
+        let size = self.actual_local_size();
+        Rect::new(
+            PADDING,
+            PADDING,
+            (size.x - 2.0 * PADDING).max(1.0),
+            (size.y - 2.0 * PADDING).max(1.0),
+        )
+    }
+
+    fn value_to_local(&self, value: Vector2<f32>) -> Vector2<f32> {
+        let rect = self.field_rect();
+        let range = self.data.max - self.data.min;
+        let kx = if range.x != 0.0 {
+            (value.x - self.data.min.x) / range.x
+        } else {
+            0.5
+        };
+        let ky = if self.data.one_dimensional || range.y == 0.0 {
+            0.5
+        } else {
+            (value.y - self.data.min.y) / range.y
+        };
+        Vector2::new(
+            rect.x() + kx * rect.w(),
+            // Y axis is pointing up.
+            rect.y() + (1.0 - ky) * rect.h(),
+        )
+    }
+
+    fn local_to_value(&self, local: Vector2<f32>) -> Vector2<f32> {
+        let rect = self.field_rect();
+        let kx = ((local.x - rect.x()) / rect.w()).clamp(0.0, 1.0);
+        let ky = (1.0 - (local.y - rect.y()) / rect.h()).clamp(0.0, 1.0);
+        let range = self.data.max - self.data.min;
+        Vector2::new(
+            self.data.min.x + kx * range.x,
+            if self.data.one_dimensional {
+                0.0
+            } else {
+                self.data.min.y + ky * range.y
+            },
+        )
+    }
+
+    fn point_under_cursor(&self, local: Vector2<f32>) -> Option<usize> {
+        self.data
+            .points
+            .iter()
+            .position(|p| self.value_to_local(*p).metric_distance(&local) <= POINT_RADIUS * 1.5)
+    }
+}
+
+impl Control for BlendSpaceField {
+    fn query_component(&self, type_id: TypeId) -> Option<&dyn Any> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn draw(&self, ctx: &mut DrawingContext) {
+        let bounds = self.widget.bounding_rect();
+        ctx.push_rect_filled(&bounds, None);
+        ctx.commit(
+            self.clip_bounds(),
+            self.widget.background(),
+            CommandTexture::None,
+            None,
+        );
+
+        let rect = self.field_rect();
+        for i in 0..=GRID_DIVISIONS {
+            let k = i as f32 / GRID_DIVISIONS as f32;
+            let x = rect.x() + k * rect.w();
+            ctx.push_line(
+                Vector2::new(x, rect.y()),
+                Vector2::new(x, rect.y() + rect.h()),
+                1.0,
+            );
+            if !self.data.one_dimensional {
+                let y = rect.y() + k * rect.h();
+                ctx.push_line(
+                    Vector2::new(rect.x(), y),
+                    Vector2::new(rect.x() + rect.w(), y),
+                    1.0,
+                );
+            }
+        }
+        if self.data.one_dimensional {
+            let y = rect.y() + rect.h() * 0.5;
+            ctx.push_line(
+                Vector2::new(rect.x(), y),
+                Vector2::new(rect.x() + rect.w(), y),
+                1.0,
+            );
+        }
+        ctx.commit(
+            self.clip_bounds(),
+            Brush::Solid(Color::opaque(70, 70, 70)),
+            CommandTexture::None,
+            None,
+        );
+
+        for triangle in self.data.triangles.iter() {
+            for &(a, b) in &[
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                if let (Some(a), Some(b)) = (self.data.points.get(a), self.data.points.get(b)) {
+                    ctx.push_line(self.value_to_local(*a), self.value_to_local(*b), 1.0);
+                }
+            }
+        }
+        ctx.commit(
+            self.clip_bounds(),
+            Brush::Solid(Color::opaque(120, 120, 120)),
+            CommandTexture::None,
+            None,
+        );
+
+        for (index, point) in self.data.points.iter().enumerate() {
+            let color = if self.drag_index == Some(index) {
+                Color::opaque(255, 200, 0)
+            } else {
+                Color::WHITE
+            };
+            ctx.push_circle(self.value_to_local(*point), POINT_RADIUS, 16, color);
+        }
+        ctx.push_circle(
+            self.value_to_local(self.data.sampling_point),
+            POINT_RADIUS * 0.75,
+            16,
+            Color::RED,
+        );
+        ctx.commit(
+            self.clip_bounds(),
+            Brush::Solid(Color::WHITE),
+            CommandTexture::None,
+            None,
+        );
+    }
+
+    fn handle_routed_message(&mut self, ui: &mut UserInterface, message: &mut UiMessage) {
+        self.widget.handle_routed_message(ui, message);
+
+        if let Some(msg) = message.data::<WidgetMessage>() {
+            if message.destination() != self.handle() {
+                return;
+            }
+
+            match msg {
+                WidgetMessage::MouseDown { pos, button } if *button == MouseButton::Left => {
+                    self.drag_index = self.point_under_cursor(self.screen_to_local(*pos));
+                    if self.drag_index.is_some() {
+                        ui.capture_mouse(self.handle());
+                        message.set_handled(true);
+                    }
+                }
+                WidgetMessage::MouseMove { pos, .. } => {
+                    if let Some(index) = self.drag_index {
+                        let value = self.local_to_value(self.screen_to_local(*pos));
+                        if let Some(point) = self.data.points.get_mut(index) {
+                            *point = value;
+                        }
+                    }
+                }
+                WidgetMessage::MouseUp { pos, button } if *button == MouseButton::Left => {
+                    if let Some(index) = self.drag_index.take() {
+                        ui.release_mouse_capture();
+                        ui.send_message(BlendSpaceFieldMessage::move_point(
+                            self.handle(),
+                            MessageDirection::FromWidget,
+                            index,
+                            self.local_to_value(self.screen_to_local(*pos)),
+                        ));
+                        message.set_handled(true);
+                    }
+                }
+                _ => (),
+            }
+        } else if let Some(BlendSpaceFieldMessage::Sync(data)) = message.data() {
+            if message.destination() == self.handle()
+                && message.direction() == MessageDirection::ToWidget
+                && self.drag_index.is_none()
+            {
+                self.data = data.clone();
+            }
+        }
+    }
+}
+
+pub struct BlendSpaceFieldBuilder {
+    widget_builder: WidgetBuilder,
+}
+
+impl BlendSpaceFieldBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self { widget_builder }
+    }
+
+    pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let field = BlendSpaceField {
+            widget: self
+                .widget_builder
+                .with_background(Brush::Solid(Color::opaque(40, 40, 40)))
+                .build(),
+            data: Default::default(),
+            drag_index: None,
+        };
+
+        ctx.add_node(UiNode::new(field))
+    }
+}
+
+/// Shows the blend space that is currently selected in the state viewer and allows to move its points.
+pub struct BlendSpaceEditor {
+    pub window: Handle<UiNode>,
+    field: Handle<UiNode>,
+}
+
+fn fetch_selected_blend_space(
+    selection: &AbsmSelection,
+    absm_node: &AnimationBlendingStateMachine,
+) -> Option<(Handle<PoseNode>, usize)> {
+    let layer_index = selection.layer?;
+    if let [SelectedEntity::PoseNode(handle)] = selection.entities.as_slice() {
+        if let Some(PoseNode::BlendSpace(_)) = absm_node
+            .machine()
+            .layers()
+            .get(layer_index)
+            .and_then(|layer| layer.nodes().try_borrow(*handle))
+        {
+            return Some((*handle, layer_index));
+        }
+    }
+    None
+}
+
+impl BlendSpaceEditor {
+    pub fn new(ctx: &mut BuildContext) -> Self {
+        let field = BlendSpaceFieldBuilder::new(WidgetBuilder::new()).build(ctx);
+        let window = WindowBuilder::new(WidgetBuilder::new())
+            .with_title(WindowTitle::text("Blend Space"))
+            .with_content(field)
+            .can_close(false)
+            .can_minimize(false)
+            .build(ctx);
+
+        Self { window, field }
+    }
+
+    pub fn sync_to_model(
+        &self,
+        ui: &UserInterface,
+        absm_node: &AnimationBlendingStateMachine,
+        selection: &AbsmSelection,
+    ) {
+        let data = fetch_selected_blend_space(selection, absm_node)
+            .and_then(|(handle, layer_index)| {
+                if let PoseNode::BlendSpace(blend_space) =
+                    &absm_node.machine().layers()[layer_index].nodes()[handle]
+                {
+                    Some(BlendSpaceFieldData::from_blend_space(
+                        blend_space,
+                        blend_space.sampling_point(absm_node.machine().parameters()),
+                    ))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        ui.send_message(BlendSpaceFieldMessage::sync(
+            self.field,
+            MessageDirection::ToWidget,
+            data,
+        ));
+    }
+
+    pub fn clear(&self, ui: &UserInterface) {
+        ui.send_message(BlendSpaceFieldMessage::sync(
+            self.field,
+            MessageDirection::ToWidget,
+            Default::default(),
+        ));
+    }
+
+    pub fn handle_ui_message(
+        &self,
+        message: &UiMessage,
+        sender: &Sender<Message>,
+        absm_node: &AnimationBlendingStateMachine,
+        selection: &AbsmSelection,
+    ) {
+        if let Some(BlendSpaceFieldMessage::MovePoint { index, position }) = message.data() {
+            if message.destination() == self.field
+                && message.direction() == MessageDirection::FromWidget
+            {
+                if let Some((handle, layer_index)) =
+                    fetch_selected_blend_space(selection, absm_node)
+                {
+                    sender
+                        .send(Message::do_scene_command(
+                            SetBlendSpacePointPositionCommand {
+                                node_handle: selection.absm_node_handle,
+                                layer_index,
+                                handle,
+                                index: *index,
+                                value: *position,
+                            },
+                        ))
+                        .unwrap();
+                }
+            }
+        }
+    }
+}
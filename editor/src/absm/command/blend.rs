@@ -5,9 +5,10 @@ use crate::{
 use fyrox::{
     animation::machine::node::{
         blend::{BlendPose, IndexedBlendInput},
+        blendspace::BlendSpacePoint,
         PoseNode,
     },
-    core::pool::Handle,
+    core::{algebra::Vector2, pool::Handle},
     scene::node::Node,
 };
 
@@ -27,6 +28,14 @@ define_push_element_to_collection_command!(AddPoseSourceCommand<Handle<PoseNode>
     }
 });
 
+define_push_element_to_collection_command!(AddBlendSpacePointCommand<Handle<PoseNode>, BlendSpacePoint>(self, context) {
+    let machine = fetch_machine(context, self.node_handle);
+    match &mut machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+        PoseNode::BlendSpace(definition) => &mut definition.points,
+        _ => unreachable!(),
+    }
+});
+
 define_set_collection_element_command!(
     SetBlendAnimationByIndexInputPoseSourceCommand<Handle<PoseNode>, Handle<PoseNode>>(self, context) {
         let machine = fetch_machine(context, self.node_handle);
@@ -50,3 +59,27 @@ define_set_collection_element_command!(
         }
     }
 );
+
+define_set_collection_element_command!(
+    SetBlendSpacePointPoseSourceCommand<Handle<PoseNode>, Handle<PoseNode>>(self, context) {
+        let machine = fetch_machine(context, self.node_handle);
+        match machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+            PoseNode::BlendSpace(ref mut definition) => {
+                &mut definition.points[self.index].pose_source
+            }
+            _ => unreachable!(),
+        }
+    }
+);
+
+define_set_collection_element_command!(
+    SetBlendSpacePointPositionCommand<Handle<PoseNode>, Vector2<f32>>(self, context) {
+        let machine = fetch_machine(context, self.node_handle);
+        match machine.layers_mut()[self.layer_index].nodes_mut()[self.handle] {
+            PoseNode::BlendSpace(ref mut definition) => {
+                &mut definition.points[self.index].position
+            }
+            _ => unreachable!(),
+        }
+    }
+);
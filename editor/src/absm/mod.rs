@@ -1,7 +1,7 @@
 use crate::{
     absm::{
-        command::blend::{AddInputCommand, AddPoseSourceCommand},
-        node::{AbsmNode, AbsmNodeMessage},
+        blendspace::BlendSpaceEditor,
+        command::blend::{AddBlendSpacePointCommand, AddInputCommand, AddPoseSourceCommand},
         parameter::ParameterPanel,
         selection::AbsmSelection,
         state_graph::StateGraphViewer,
@@ -13,7 +13,9 @@ use crate::{
 };
 use fyrox::fxhash::FxHashSet;
 use fyrox::{
-    animation::machine::{BlendPose, Event, IndexedBlendInput, Machine, PoseNode, State},
+    animation::machine::{
+        BlendPose, BlendSpacePoint, Event, IndexedBlendInput, Machine, PoseNode, State,
+    },
     core::{color::Color, pool::Handle},
     engine::Engine,
     gui::{
@@ -21,6 +23,7 @@ use fyrox::{
         dock::{DockingManagerBuilder, TileBuilder, TileContent},
         grid::{Column, GridBuilder, Row},
         message::{MessageDirection, UiMessage},
+        node_graph::node::{GraphNode, GraphNodeMessage, NORMAL_BACKGROUND, SELECTED_BACKGROUND},
         widget::WidgetBuilder,
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, UiNode, UserInterface,
@@ -33,23 +36,14 @@ use fyrox::{
 };
 use std::sync::mpsc::Sender;
 
-mod canvas;
+mod blendspace;
 pub mod command;
-mod connection;
-mod node;
 mod parameter;
-mod segment;
-mod selectable;
 pub mod selection;
-mod socket;
 mod state_graph;
 mod state_viewer;
 mod toolbar;
-mod transition;
 
-const NORMAL_BACKGROUND: Color = Color::opaque(60, 60, 60);
-const SELECTED_BACKGROUND: Color = Color::opaque(80, 80, 80);
-const BORDER_COLOR: Color = Color::opaque(70, 70, 70);
 const NORMAL_ROOT_COLOR: Color = Color::opaque(40, 80, 0);
 const SELECTED_ROOT_COLOR: Color = Color::opaque(60, 100, 0);
 
@@ -88,6 +82,7 @@ pub struct AbsmEditor {
     state_graph_viewer: StateGraphViewer,
     state_viewer: StateViewer,
     parameter_panel: ParameterPanel,
+    blend_space_editor: BlendSpaceEditor,
     prev_absm: Handle<Node>,
     toolbar: Toolbar,
     preview_mode_data: Option<PreviewModeData>,
@@ -98,6 +93,7 @@ impl AbsmEditor {
         let state_graph_viewer = StateGraphViewer::new(ctx);
         let state_viewer = StateViewer::new(ctx);
         let parameter_panel = ParameterPanel::new(ctx, sender);
+        let blend_space_editor = BlendSpaceEditor::new(ctx);
 
         let docking_manager = DockingManagerBuilder::new(
             WidgetBuilder::new().on_row(1).with_child(
@@ -106,7 +102,21 @@ impl AbsmEditor {
                         splitter: 0.3,
                         tiles: [
                             TileBuilder::new(WidgetBuilder::new())
-                                .with_content(TileContent::Window(parameter_panel.window))
+                                .with_content(TileContent::VerticalTiles {
+                                    splitter: 0.6,
+                                    tiles: [
+                                        TileBuilder::new(WidgetBuilder::new())
+                                            .with_content(TileContent::Window(
+                                                parameter_panel.window,
+                                            ))
+                                            .build(ctx),
+                                        TileBuilder::new(WidgetBuilder::new())
+                                            .with_content(TileContent::Window(
+                                                blend_space_editor.window,
+                                            ))
+                                            .build(ctx),
+                                    ],
+                                })
                                 .build(ctx),
                             TileBuilder::new(WidgetBuilder::new())
                                 .with_content(TileContent::HorizontalTiles {
@@ -153,6 +163,7 @@ impl AbsmEditor {
             state_graph_viewer,
             state_viewer,
             parameter_panel,
+            blend_space_editor,
             prev_absm: Default::default(),
             toolbar,
             preview_mode_data: None,
@@ -283,6 +294,8 @@ impl AbsmEditor {
         if let Some(absm_node) = absm_node {
             self.parameter_panel.sync_to_model(ui, absm_node);
             self.toolbar.sync_to_model(absm_node, ui, &selection);
+            self.blend_space_editor
+                .sync_to_model(ui, absm_node, &selection);
             if let Some(layer_index) = selection.layer {
                 if let Some(layer) = absm_node.machine().layers().get(layer_index) {
                     self.state_graph_viewer
@@ -300,6 +313,7 @@ impl AbsmEditor {
             self.parameter_panel.reset(ui);
             self.state_graph_viewer.clear(ui);
             self.state_viewer.clear(ui);
+            self.blend_space_editor.clear(ui);
         }
     }
 
@@ -313,6 +327,22 @@ impl AbsmEditor {
 
     pub fn update(&mut self, editor_scene: &EditorScene, engine: &mut Engine) {
         self.handle_machine_events(editor_scene, engine);
+
+        // Sampling point of a blend space changes when parameters are changed in preview mode.
+        if self.preview_mode_data.is_some() {
+            let selection = fetch_selection(&editor_scene.selection);
+            if let Some(absm_node) = engine.scenes[editor_scene.scene]
+                .graph
+                .try_get(selection.absm_node_handle)
+                .and_then(|n| n.query_component_ref::<AnimationBlendingStateMachine>())
+            {
+                self.blend_space_editor.sync_to_model(
+                    &engine.user_interface,
+                    absm_node,
+                    &selection,
+                );
+            }
+        }
     }
 
     pub fn handle_machine_events(&self, editor_scene: &EditorScene, engine: &mut Engine) {
@@ -381,6 +411,9 @@ impl AbsmEditor {
                 );
             }
 
+            self.blend_space_editor
+                .handle_ui_message(message, sender, absm_node, &selection);
+
             self.parameter_panel.handle_ui_message(
                 message,
                 sender,
@@ -450,17 +483,17 @@ impl AbsmEditor {
             }
         }
 
-        if let Some(msg) = message.data::<AbsmNodeMessage>() {
+        if let Some(msg) = message.data::<GraphNodeMessage>() {
             if let Some(absm_node) = scene
                 .graph
                 .try_get_mut(selection.absm_node_handle)
                 .and_then(|n| n.query_component_mut::<AnimationBlendingStateMachine>())
             {
                 match msg {
-                    AbsmNodeMessage::Enter => {
+                    GraphNodeMessage::Enter => {
                         if let Some(node) = ui
                             .node(message.destination())
-                            .query_component::<AbsmNode<State>>()
+                            .query_component::<GraphNode<State>>()
                         {
                             if let Some(layer_index) = selection.layer {
                                 self.state_viewer.set_state(
@@ -473,10 +506,10 @@ impl AbsmEditor {
                             }
                         }
                     }
-                    AbsmNodeMessage::AddInput => {
+                    GraphNodeMessage::AddInput => {
                         if let Some(node) = ui
                             .node(message.destination())
-                            .query_component::<AbsmNode<PoseNode>>()
+                            .query_component::<GraphNode<PoseNode>>()
                         {
                             if let Some(layer_index) = selection.layer {
                                 let model_ref = &absm_node.machine().layers()[layer_index].nodes()
@@ -508,6 +541,18 @@ impl AbsmEditor {
                                             )))
                                             .unwrap();
                                     }
+                                    PoseNode::BlendSpace(_) => {
+                                        sender
+                                            .send(Message::do_scene_command(
+                                                AddBlendSpacePointCommand::new(
+                                                    selection.absm_node_handle,
+                                                    node.model_handle,
+                                                    layer_index,
+                                                    BlendSpacePoint::default(),
+                                                ),
+                                            ))
+                                            .unwrap();
+                                    }
                                 }
                             }
                         }
@@ -1,13 +1,10 @@
 use crate::{
     absm::{
-        canvas::{AbsmCanvasMessage, Mode},
         command::{
             AddStateCommand, DeleteStateCommand, DeleteTransitionCommand,
             SetMachineEntryStateCommand,
         },
-        node::AbsmNode,
         selection::SelectedEntity,
-        transition::TransitionView,
     },
     menu::create_menu_item,
     scene::{
@@ -22,6 +19,11 @@ use fyrox::{
     gui::{
         menu::MenuItemMessage,
         message::{MessageDirection, UiMessage},
+        node_graph::{
+            canvas::{Mode, NodeGraphCanvasMessage},
+            node::GraphNode,
+            transition::TransitionView,
+        },
         popup::{Placement, PopupBuilder, PopupMessage},
         stack_panel::StackPanelBuilder,
         widget::WidgetBuilder,
@@ -146,7 +148,7 @@ impl NodeContextMenu {
         let machine = absm_node.machine();
         if let Some(MenuItemMessage::Click) = message.data() {
             if message.destination() == self.create_transition {
-                ui.send_message(AbsmCanvasMessage::switch_mode(
+                ui.send_message(NodeGraphCanvasMessage::switch_mode(
                     self.canvas,
                     MessageDirection::ToWidget,
                     Mode::CreateTransition {
@@ -220,7 +222,7 @@ impl NodeContextMenu {
                         layer: layer_index,
                         entry: ui
                             .node(self.placement_target)
-                            .query_component::<AbsmNode<State>>()
+                            .query_component::<GraphNode<State>>()
                             .unwrap()
                             .model_handle,
                     }))
@@ -288,7 +290,7 @@ impl TransitionContextMenu {
                         SceneCommand::new(DeleteTransitionCommand::new(
                             absm_node_handle,
                             layer_index,
-                            transition_ref.model_handle,
+                            transition_ref.model_handle.into(),
                         )),
                     ];
 
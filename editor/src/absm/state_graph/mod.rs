@@ -1,12 +1,9 @@
 use crate::{
     absm::{
-        canvas::{AbsmCanvas, AbsmCanvasBuilder, AbsmCanvasMessage},
         command::{AddTransitionCommand, MoveStateNodeCommand},
         fetch_selection,
-        node::{AbsmNode, AbsmNodeBuilder, AbsmNodeMessage},
         selection::{AbsmSelection, SelectedEntity},
         state_graph::context::{CanvasContextMenu, NodeContextMenu, TransitionContextMenu},
-        transition::{TransitionBuilder, TransitionMessage, TransitionView},
         NORMAL_BACKGROUND, NORMAL_ROOT_COLOR, SELECTED_BACKGROUND, SELECTED_ROOT_COLOR,
     },
     scene::{
@@ -21,6 +18,11 @@ use fyrox::{
     gui::{
         border::BorderBuilder,
         message::{MessageDirection, UiMessage},
+        node_graph::{
+            canvas::{NodeGraphCanvas, NodeGraphCanvasBuilder, NodeGraphCanvasMessage},
+            node::{GraphNode, GraphNodeBuilder, GraphNodeMessage},
+            transition::{TransitionBuilder, TransitionMessage, TransitionView},
+        },
         widget::{WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowTitle},
         BuildContext, Thickness, UiNode, UserInterface,
@@ -42,7 +44,7 @@ pub struct StateGraphViewer {
 
 fn fetch_state_node_model_handle(handle: Handle<UiNode>, ui: &UserInterface) -> Handle<State> {
     ui.node(handle)
-        .query_component::<AbsmNode<State>>()
+        .query_component::<GraphNode<State>>()
         .unwrap()
         .model_handle
 }
@@ -53,7 +55,7 @@ impl StateGraphViewer {
         let mut canvas_context_menu = CanvasContextMenu::new(ctx);
         let transition_context_menu = TransitionContextMenu::new(ctx);
 
-        let canvas = AbsmCanvasBuilder::new(
+        let canvas = NodeGraphCanvasBuilder::new(
             WidgetBuilder::new().with_context_menu(canvas_context_menu.menu),
         )
         .build(ctx);
@@ -97,7 +99,7 @@ impl StateGraphViewer {
             ui.node(*c)
                 .query_component::<TransitionView>()
                 .map_or(false, |transition_view_ref| {
-                    transition_view_ref.model_handle == transition
+                    transition_view_ref.model_handle == transition.into()
                 })
         }) {
             ui.send_message(TransitionMessage::activate(
@@ -115,11 +117,11 @@ impl StateGraphViewer {
             .cloned()
             .filter_map(|c| {
                 ui.node(c)
-                    .query_component::<AbsmNode<State>>()
+                    .query_component::<GraphNode<State>>()
                     .map(|state_view_ref| (c, state_view_ref))
             })
         {
-            ui.send_message(AbsmNodeMessage::set_active(
+            ui.send_message(GraphNodeMessage::set_active(
                 state_view_handle,
                 MessageDirection::ToWidget,
                 state_view_ref.model_handle == state,
@@ -138,9 +140,9 @@ impl StateGraphViewer {
         editor_scene: &EditorScene,
     ) {
         if message.destination() == self.canvas {
-            if let Some(msg) = message.data::<AbsmCanvasMessage>() {
+            if let Some(msg) = message.data::<NodeGraphCanvasMessage>() {
                 match msg {
-                    AbsmCanvasMessage::CommitTransition {
+                    NodeGraphCanvasMessage::CommitTransition {
                         source_node: source,
                         dest_node: dest,
                     } => {
@@ -157,7 +159,7 @@ impl StateGraphViewer {
                                 .unwrap();
                         }
                     }
-                    AbsmCanvasMessage::CommitDrag { entries } => {
+                    NodeGraphCanvasMessage::CommitDrag { entries } => {
                         let commands = entries
                             .iter()
                             .map(|e| {
@@ -178,7 +180,7 @@ impl StateGraphViewer {
                             .send(Message::do_scene_command(CommandGroup::from(commands)))
                             .unwrap();
                     }
-                    AbsmCanvasMessage::SelectionChanged(selection) => {
+                    NodeGraphCanvasMessage::SelectionChanged(selection) => {
                         if message.direction() == MessageDirection::FromWidget {
                             let selection = Selection::Absm(AbsmSelection {
                                 absm_node_handle,
@@ -189,14 +191,14 @@ impl StateGraphViewer {
                                         let node_ref = ui.node(*n);
 
                                         if let Some(state_node) =
-                                            node_ref.query_component::<AbsmNode<State>>()
+                                            node_ref.query_component::<GraphNode<State>>()
                                         {
                                             Some(SelectedEntity::State(state_node.model_handle))
                                         } else {
                                             node_ref.query_component::<TransitionView>().map(
                                                 |state_node| {
                                                     SelectedEntity::Transition(
-                                                        state_node.model_handle,
+                                                        state_node.model_handle.into(),
                                                     )
                                                 },
                                             )
@@ -254,8 +256,8 @@ impl StateGraphViewer {
     ) {
         let canvas = ui
             .node(self.canvas)
-            .cast::<AbsmCanvas>()
-            .expect("Must be AbsmCanvas!");
+            .cast::<NodeGraphCanvas>()
+            .expect("Must be NodeGraphCanvas!");
 
         let current_selection = fetch_selection(&editor_scene.selection);
 
@@ -272,7 +274,7 @@ impl StateGraphViewer {
                 .children()
                 .iter()
                 .cloned()
-                .filter(|c| ui.node(*c).has_component::<AbsmNode<State>>())
+                .filter(|c| ui.node(*c).has_component::<GraphNode<State>>())
                 .collect::<Vec<_>>();
 
             transitions = canvas
@@ -292,12 +294,12 @@ impl StateGraphViewer {
                 for (state_handle, state) in machine_layer.states().pair_iter() {
                     if states.iter().all(|state_view| {
                         ui.node(*state_view)
-                            .query_component::<AbsmNode<State>>()
+                            .query_component::<GraphNode<State>>()
                             .unwrap()
                             .model_handle
                             != state_handle
                     }) {
-                        let state_view_handle = AbsmNodeBuilder::new(
+                        let state_view_handle = GraphNodeBuilder::new(
                             WidgetBuilder::new()
                                 .with_context_menu(self.node_context_menu.menu)
                                 .with_desired_position(state.position),
@@ -336,7 +338,7 @@ impl StateGraphViewer {
                         (
                             state_view,
                             ui.node(state_view)
-                                .query_component::<AbsmNode<State>>()
+                                .query_component::<GraphNode<State>>()
                                 .unwrap()
                                 .model_handle,
                         )
@@ -366,7 +368,7 @@ impl StateGraphViewer {
         for state in states.iter() {
             let state_node = ui
                 .node(*state)
-                .query_component::<AbsmNode<State>>()
+                .query_component::<GraphNode<State>>()
                 .unwrap();
             let state_model_handle = state_node.model_handle;
             let state_model_ref = &machine_layer.states()[state_node.model_handle];
@@ -374,7 +376,7 @@ impl StateGraphViewer {
             if state_model_ref.name != state_node.name_value {
                 send_sync_message(
                     ui,
-                    AbsmNodeMessage::name(
+                    GraphNodeMessage::name(
                         *state,
                         MessageDirection::ToWidget,
                         state_model_ref.name.clone(),
@@ -393,7 +395,7 @@ impl StateGraphViewer {
 
             send_sync_message(
                 ui,
-                AbsmNodeMessage::normal_color(
+                GraphNodeMessage::normal_color(
                     *state,
                     MessageDirection::ToWidget,
                     if state_model_handle == machine_layer.entry_state() {
@@ -405,7 +407,7 @@ impl StateGraphViewer {
             );
             send_sync_message(
                 ui,
-                AbsmNodeMessage::selected_color(
+                GraphNodeMessage::selected_color(
                     *state,
                     MessageDirection::ToWidget,
                     if state_model_handle == machine_layer.entry_state() {
@@ -433,7 +435,7 @@ impl StateGraphViewer {
                             .query_component::<TransitionView>()
                             .unwrap()
                             .model_handle
-                            != transition_handle
+                            != transition_handle.into()
                     }) {
                         fn find_state_view(
                             state_handle: Handle<State>,
@@ -444,7 +446,7 @@ impl StateGraphViewer {
                                 .iter()
                                 .find(|s| {
                                     ui.node(**s)
-                                        .query_component::<AbsmNode<State>>()
+                                        .query_component::<GraphNode<State>>()
                                         .unwrap()
                                         .model_handle
                                         == state_handle
@@ -496,7 +498,7 @@ impl StateGraphViewer {
                     if machine_layer
                         .transitions()
                         .pair_iter()
-                        .all(|(h, _)| h != transition_model_handle)
+                        .all(|(h, _)| h != transition_model_handle.into())
                     {
                         send_sync_message(
                             ui,
@@ -528,11 +530,11 @@ impl StateGraphViewer {
                         .query_component::<TransitionView>()
                         .unwrap()
                         .model_handle
-                        == *transition
+                        == (*transition).into()
                 }),
                 SelectedEntity::State(state) => states.iter().cloned().find(|s| {
                     ui.node(*s)
-                        .query_component::<AbsmNode<State>>()
+                        .query_component::<GraphNode<State>>()
                         .unwrap()
                         .model_handle
                         == *state
@@ -546,7 +548,7 @@ impl StateGraphViewer {
 
         send_sync_message(
             ui,
-            AbsmCanvasMessage::selection_changed(
+            NodeGraphCanvasMessage::selection_changed(
                 self.canvas,
                 MessageDirection::ToWidget,
                 new_selection,
@@ -555,7 +557,7 @@ impl StateGraphViewer {
 
         send_sync_message(
             ui,
-            AbsmCanvasMessage::force_sync_dependent_objects(
+            NodeGraphCanvasMessage::force_sync_dependent_objects(
                 self.canvas,
                 MessageDirection::ToWidget,
             ),
@@ -2,12 +2,11 @@ use crate::{
     absm::{
         command::{
             blend::{
-                SetBlendAnimationByIndexInputPoseSourceCommand, SetBlendAnimationsPoseSourceCommand,
+                SetBlendAnimationByIndexInputPoseSourceCommand,
+                SetBlendAnimationsPoseSourceCommand, SetBlendSpacePointPoseSourceCommand,
             },
             AddPoseNodeCommand, DeletePoseNodeCommand, SetStateRootPoseCommand,
         },
-        connection::Connection,
-        node::AbsmNode,
         selection::SelectedEntity,
     },
     menu::create_menu_item,
@@ -19,13 +18,14 @@ use crate::{
 };
 use fyrox::{
     animation::machine::{
-        node::BasePoseNode, BlendAnimations, BlendAnimationsByIndex, MachineLayer, PlayAnimation,
-        PoseNode, State,
+        node::BasePoseNode, BlendAnimations, BlendAnimationsByIndex, BlendSpace, MachineLayer,
+        PlayAnimation, PoseNode, State,
     },
     core::pool::Handle,
     gui::{
         menu::MenuItemMessage,
         message::UiMessage,
+        node_graph::{connection::Connection, node::GraphNode},
         popup::{Placement, PopupBuilder, PopupMessage},
         stack_panel::StackPanelBuilder,
         widget::WidgetBuilder,
@@ -39,6 +39,7 @@ pub struct CanvasContextMenu {
     create_play_animation: Handle<UiNode>,
     create_blend_animations: Handle<UiNode>,
     create_blend_by_index: Handle<UiNode>,
+    create_blend_space: Handle<UiNode>,
     pub menu: Handle<UiNode>,
     pub canvas: Handle<UiNode>,
     pub node_context_menu: Handle<UiNode>,
@@ -49,6 +50,7 @@ impl CanvasContextMenu {
         let create_play_animation;
         let create_blend_animations;
         let create_blend_by_index;
+        let create_blend_space;
         let menu = PopupBuilder::new(
             WidgetBuilder::new()
                 .with_enabled(false) // Disabled by default.
@@ -68,6 +70,10 @@ impl CanvasContextMenu {
                     .with_child({
                         create_blend_by_index = create_menu_item("Blend By Index", vec![], ctx);
                         create_blend_by_index
+                    })
+                    .with_child({
+                        create_blend_space = create_menu_item("Blend Space", vec![], ctx);
+                        create_blend_space
                     }),
             )
             .build(ctx),
@@ -78,6 +84,7 @@ impl CanvasContextMenu {
             create_play_animation,
             create_blend_animations,
             create_blend_by_index,
+            create_blend_space,
             menu,
             canvas: Default::default(),
             node_context_menu: Default::default(),
@@ -128,6 +135,13 @@ impl CanvasContextMenu {
                     blend_time: Default::default(),
                     output_pose: Default::default(),
                 }))
+            } else if message.destination() == self.create_blend_space {
+                let mut blend_space = BlendSpace::default();
+                blend_space.base = BasePoseNode {
+                    position,
+                    parent_state: current_state,
+                };
+                Some(PoseNode::BlendSpace(blend_space))
             } else {
                 None
             };
@@ -223,7 +237,7 @@ impl NodeContextMenu {
             } else if message.destination() == self.set_as_root {
                 let root = ui
                     .node(self.placement_target)
-                    .query_component::<AbsmNode<PoseNode>>()
+                    .query_component::<GraphNode<PoseNode>>()
                     .unwrap()
                     .model_handle;
 
@@ -288,7 +302,7 @@ impl ConnectionContextMenu {
 
                 let dest_node_ref = ui
                     .node(connection_ref.dest_node)
-                    .query_component::<AbsmNode<PoseNode>>()
+                    .query_component::<GraphNode<PoseNode>>()
                     .unwrap();
 
                 let index = dest_node_ref
@@ -325,6 +339,17 @@ impl ConnectionContextMenu {
                             },
                         ))
                         .unwrap(),
+                    PoseNode::BlendSpace(_) => sender
+                        .send(Message::do_scene_command(
+                            SetBlendSpacePointPoseSourceCommand {
+                                node_handle: absm_node_handle,
+                                layer_index,
+                                handle: model_handle,
+                                index,
+                                value: Default::default(),
+                            },
+                        ))
+                        .unwrap(),
                 }
             }
         } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) = message.data() {
@@ -1,17 +1,14 @@
 use crate::{
     absm::{
-        canvas::{AbsmCanvasBuilder, AbsmCanvasMessage},
         command::{
             blend::{
-                SetBlendAnimationByIndexInputPoseSourceCommand, SetBlendAnimationsPoseSourceCommand,
+                SetBlendAnimationByIndexInputPoseSourceCommand,
+                SetBlendAnimationsPoseSourceCommand, SetBlendSpacePointPoseSourceCommand,
             },
             MovePoseNodeCommand,
         },
-        connection::{Connection, ConnectionBuilder},
         fetch_selection,
-        node::{AbsmNode, AbsmNodeBuilder, AbsmNodeMessage},
         selection::{AbsmSelection, SelectedEntity},
-        socket::{Socket, SocketBuilder, SocketDirection},
         state_viewer::context::{CanvasContextMenu, ConnectionContextMenu, NodeContextMenu},
         NORMAL_BACKGROUND, NORMAL_ROOT_COLOR, SELECTED_BACKGROUND, SELECTED_ROOT_COLOR,
     },
@@ -30,6 +27,12 @@ use fyrox::{
     gui::{
         border::BorderBuilder,
         message::{MessageDirection, UiMessage},
+        node_graph::{
+            canvas::{NodeGraphCanvasBuilder, NodeGraphCanvasMessage},
+            connection::{Connection, ConnectionBuilder},
+            node::{GraphNode, GraphNodeBuilder, GraphNodeMessage},
+            socket::{Socket, SocketBuilder, SocketDirection},
+        },
         widget::{WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, Thickness, UiNode, UserInterface,
@@ -80,7 +83,7 @@ fn create_sockets(
 
 fn fetch_pose_node_model_handle(handle: Handle<UiNode>, ui: &UserInterface) -> Handle<PoseNode> {
     ui.node(handle)
-        .query_component::<AbsmNode<PoseNode>>()
+        .query_component::<GraphNode<PoseNode>>()
         .unwrap()
         .model_handle
 }
@@ -93,6 +96,7 @@ fn fetch_socket_pose_node_model_handle(
         .query_component::<Socket>()
         .unwrap()
         .parent_node
+        .into()
 }
 
 fn make_play_animation_name(
@@ -126,6 +130,9 @@ fn make_pose_node_name(
             "Blend {} Animations By Index",
             blend_animations_by_index.inputs.len()
         ),
+        PoseNode::BlendSpace(blend_space) => {
+            format!("Blend Space ({} Points)", blend_space.points.len())
+        }
     }
 }
 
@@ -135,7 +142,7 @@ impl StateViewer {
         let mut canvas_context_menu = CanvasContextMenu::new(ctx);
         let connection_context_menu = ConnectionContextMenu::new(ctx);
 
-        let canvas = AbsmCanvasBuilder::new(
+        let canvas = NodeGraphCanvasBuilder::new(
             WidgetBuilder::new().with_context_menu(canvas_context_menu.menu),
         )
         .build(ctx);
@@ -242,9 +249,9 @@ impl StateViewer {
 
         if let Some(layer) = machine.layers().get(layer_index) {
             if message.destination() == self.canvas {
-                if let Some(msg) = message.data::<AbsmCanvasMessage>() {
+                if let Some(msg) = message.data::<NodeGraphCanvasMessage>() {
                     match msg {
-                        AbsmCanvasMessage::CommitDrag { entries } => {
+                        NodeGraphCanvasMessage::CommitDrag { entries } => {
                             let commands = entries
                                 .iter()
                                 .map(|e| {
@@ -265,7 +272,7 @@ impl StateViewer {
                                 .send(Message::do_scene_command(CommandGroup::from(commands)))
                                 .unwrap();
                         }
-                        AbsmCanvasMessage::SelectionChanged(selection) => {
+                        NodeGraphCanvasMessage::SelectionChanged(selection) => {
                             if message.direction() == MessageDirection::FromWidget {
                                 let selection = Selection::Absm(AbsmSelection {
                                     absm_node_handle,
@@ -275,7 +282,7 @@ impl StateViewer {
                                         .filter_map(|n| {
                                             let node_ref = ui.node(*n);
 
-                                            node_ref.query_component::<AbsmNode<PoseNode>>().map(
+                                            node_ref.query_component::<GraphNode<PoseNode>>().map(
                                                 |state_node| {
                                                     SelectedEntity::PoseNode(
                                                         state_node.model_handle,
@@ -298,7 +305,7 @@ impl StateViewer {
                                 }
                             }
                         }
-                        AbsmCanvasMessage::CommitConnection {
+                        NodeGraphCanvasMessage::CommitConnection {
                             source_socket,
                             dest_socket,
                         } => {
@@ -338,6 +345,19 @@ impl StateViewer {
                                         ))
                                         .unwrap();
                                 }
+                                PoseNode::BlendSpace(_) => {
+                                    sender
+                                        .send(Message::do_scene_command(
+                                            SetBlendSpacePointPoseSourceCommand {
+                                                node_handle: absm_node_handle,
+                                                layer_index,
+                                                handle: dest_node,
+                                                index: dest_socket_ref.index,
+                                                value: source_node,
+                                            },
+                                        ))
+                                        .unwrap();
+                                }
                             }
                         }
                         _ => (),
@@ -397,7 +417,8 @@ impl StateViewer {
                     .iter()
                     .cloned()
                     .filter(|h| {
-                        if let Some(pose_node) = ui.node(*h).query_component::<AbsmNode<PoseNode>>()
+                        if let Some(pose_node) =
+                            ui.node(*h).query_component::<GraphNode<PoseNode>>()
                         {
                             if machine_layer
                                 .nodes()
@@ -440,7 +461,7 @@ impl StateViewer {
                     for &pose_definition in models.iter() {
                         if views.iter().all(|v| {
                             ui.node(*v)
-                                .query_component::<AbsmNode<PoseNode>>()
+                                .query_component::<GraphNode<PoseNode>>()
                                 .unwrap()
                                 .model_handle
                                 != pose_definition
@@ -462,9 +483,12 @@ impl StateViewer {
                                     "Blend Animations By Index",
                                     true,
                                 ),
+                                PoseNode::BlendSpace(blend_space) => {
+                                    (blend_space.points.len(), "Blend Space", true)
+                                }
                             };
 
-                            let node_view = AbsmNodeBuilder::new(
+                            let node_view = GraphNodeBuilder::new(
                                 WidgetBuilder::new()
                                     .with_desired_position(node_ref.position)
                                     .with_context_menu(self.node_context_menu.menu),
@@ -515,7 +539,7 @@ impl StateViewer {
                     for &view in views.clone().iter() {
                         let view_ref = ui
                             .node(view)
-                            .query_component::<AbsmNode<PoseNode>>()
+                            .query_component::<GraphNode<PoseNode>>()
                             .unwrap();
 
                         if machine_layer
@@ -541,7 +565,7 @@ impl StateViewer {
             for &view in &views {
                 let view_ref = ui
                     .node(view)
-                    .query_component::<AbsmNode<PoseNode>>()
+                    .query_component::<GraphNode<PoseNode>>()
                     .unwrap();
                 let model_handle = view_ref.model_handle;
                 let model_ref = &machine_layer.nodes()[model_handle];
@@ -552,7 +576,7 @@ impl StateViewer {
                 if new_name != view_ref.name_value {
                     send_sync_message(
                         ui,
-                        AbsmNodeMessage::name(view, MessageDirection::ToWidget, new_name),
+                        GraphNodeMessage::name(view, MessageDirection::ToWidget, new_name),
                     );
                 }
 
@@ -566,7 +590,7 @@ impl StateViewer {
 
                     send_sync_message(
                         ui,
-                        AbsmNodeMessage::input_sockets(
+                        GraphNodeMessage::input_sockets(
                             view,
                             MessageDirection::ToWidget,
                             input_sockets,
@@ -588,7 +612,7 @@ impl StateViewer {
                 if model_ref.parent_state == self.state {
                     send_sync_message(
                         ui,
-                        AbsmNodeMessage::normal_color(
+                        GraphNodeMessage::normal_color(
                             view,
                             MessageDirection::ToWidget,
                             if model_handle == parent_state_ref.root {
@@ -600,7 +624,7 @@ impl StateViewer {
                     );
                     send_sync_message(
                         ui,
-                        AbsmNodeMessage::selected_color(
+                        GraphNodeMessage::selected_color(
                             view,
                             MessageDirection::ToWidget,
                             if model_handle == parent_state_ref.root {
@@ -628,7 +652,7 @@ impl StateViewer {
             for model in models.iter().cloned() {
                 let dest_ref = views
                     .iter()
-                    .filter_map(|v| ui.node(*v).query_component::<AbsmNode<PoseNode>>())
+                    .filter_map(|v| ui.node(*v).query_component::<GraphNode<PoseNode>>())
                     .find(|v| v.model_handle == model)
                     .unwrap();
                 let dest_handle = dest_ref.handle();
@@ -642,7 +666,7 @@ impl StateViewer {
                     if machine_layer.nodes().is_valid_handle(child) {
                         let source = views
                             .iter()
-                            .filter_map(|v| ui.node(*v).query_component::<AbsmNode<PoseNode>>())
+                            .filter_map(|v| ui.node(*v).query_component::<GraphNode<PoseNode>>())
                             .find(|v| v.model_handle == child)
                             .unwrap();
 
@@ -683,7 +707,7 @@ impl StateViewer {
                     }
                     SelectedEntity::PoseNode(pose_node) => views.iter().cloned().find(|s| {
                         ui.node(*s)
-                            .query_component::<AbsmNode<PoseNode>>()
+                            .query_component::<GraphNode<PoseNode>>()
                             .unwrap()
                             .model_handle
                             == *pose_node
@@ -693,7 +717,7 @@ impl StateViewer {
 
             send_sync_message(
                 ui,
-                AbsmCanvasMessage::selection_changed(
+                NodeGraphCanvasMessage::selection_changed(
                     self.canvas,
                     MessageDirection::ToWidget,
                     new_selection,
@@ -702,7 +726,7 @@ impl StateViewer {
 
             send_sync_message(
                 ui,
-                AbsmCanvasMessage::force_sync_dependent_objects(
+                NodeGraphCanvasMessage::force_sync_dependent_objects(
                     self.canvas,
                     MessageDirection::ToWidget,
                 ),
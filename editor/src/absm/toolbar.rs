@@ -21,6 +21,7 @@ use fyrox::{
         button::{ButtonBuilder, ButtonMessage},
         check_box::{CheckBoxBuilder, CheckBoxMessage},
         dropdown_list::{DropdownListBuilder, DropdownListMessage},
+        file_browser::{FileBrowserMode, FileSelectorBuilder, FileSelectorMessage, Filter},
         image::ImageBuilder,
         message::{MessageDirection, UiMessage},
         stack_panel::StackPanelBuilder,
@@ -36,8 +37,15 @@ use fyrox::{
         animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
         graph::Graph,
     },
+    utils::{
+        fsm::{LogicMachine, LOGIC_MACHINE_EXTENSION},
+        log::Log,
+    },
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
 };
-use std::sync::mpsc::Sender;
 
 pub struct Toolbar {
     pub panel: Handle<UiNode>,
@@ -48,6 +56,8 @@ pub struct Toolbar {
     pub remove_layer: Handle<UiNode>,
     pub edit_mask: Handle<UiNode>,
     pub node_selector: Handle<UiNode>,
+    pub export: Handle<UiNode>,
+    pub export_file_selector: Handle<UiNode>,
 }
 
 pub enum ToolbarAction {
@@ -64,6 +74,7 @@ impl Toolbar {
         let add_layer;
         let remove_layer;
         let edit_mask;
+        let export;
         let panel = StackPanelBuilder::new(
             WidgetBuilder::new()
                 .with_child({
@@ -146,11 +157,43 @@ impl Toolbar {
                     )
                     .build(ctx);
                     edit_mask
+                })
+                .with_child({
+                    export = ButtonBuilder::new(
+                        WidgetBuilder::new()
+                            .with_margin(Thickness::uniform(1.0))
+                            .with_tooltip(make_simple_tooltip(
+                                ctx,
+                                "Export the state graph as a logic machine definition, \
+                                it could be used to drive game logic (AI, game flow, etc.).",
+                            )),
+                    )
+                    .with_text("Export...")
+                    .build(ctx);
+                    export
                 }),
         )
         .with_orientation(Orientation::Horizontal)
         .build(ctx);
 
+        let export_file_selector = FileSelectorBuilder::new(
+            WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
+                .open(false)
+                .with_title(WindowTitle::text("Export Logic Machine")),
+        )
+        .with_mode(FileBrowserMode::Save {
+            default_file_name: PathBuf::from(format!("unnamed.{}", LOGIC_MACHINE_EXTENSION)),
+        })
+        .with_path("./")
+        .with_filter(Filter::new(|p: &Path| {
+            if let Some(ext) = p.extension() {
+                ext.to_string_lossy().as_ref() == LOGIC_MACHINE_EXTENSION
+            } else {
+                p.is_dir()
+            }
+        }))
+        .build(ctx);
+
         Self {
             panel,
             preview,
@@ -160,6 +203,8 @@ impl Toolbar {
             remove_layer,
             edit_mask,
             node_selector: Handle::NONE,
+            export,
+            export_file_selector,
         }
     }
 
@@ -298,6 +343,17 @@ impl Toolbar {
                         }
                     }
                 }
+            } else if message.destination() == self.export {
+                ui.send_message(WindowMessage::open_modal(
+                    self.export_file_selector,
+                    MessageDirection::ToWidget,
+                    true,
+                ));
+                ui.send_message(FileSelectorMessage::root(
+                    self.export_file_selector,
+                    MessageDirection::ToWidget,
+                    Some(std::env::current_dir().unwrap()),
+                ));
             } else if message.destination() == self.remove_layer {
                 if let Some(absm_node) = graph
                     .try_get_of_type::<AnimationBlendingStateMachine>(selection.absm_node_handle)
@@ -329,6 +385,24 @@ impl Toolbar {
                     }
                 }
             }
+        } else if let Some(FileSelectorMessage::Commit(path)) = message.data() {
+            if message.destination() == self.export_file_selector {
+                if let Some(absm_node) = graph
+                    .try_get_of_type::<AnimationBlendingStateMachine>(selection.absm_node_handle)
+                {
+                    match LogicMachine::<()>::save_definition(absm_node.machine(), path) {
+                        Ok(_) => Log::info(format!(
+                            "Logic machine was successfully exported to {}",
+                            path.display()
+                        )),
+                        Err(err) => Log::err(format!(
+                            "Failed to export logic machine to {}. Reason: {:?}",
+                            path.display(),
+                            err
+                        )),
+                    }
+                }
+            }
         } else if let Some(NodeSelectorMessage::Selection(mask_selection)) = message.data() {
             if message.destination() == self.node_selector
                 && message.direction() == MessageDirection::FromWidget
@@ -22,7 +22,7 @@ pub struct AssetItem {
     widget: Widget,
     pub path: PathBuf,
     pub kind: AssetKind,
-    preview: Handle<UiNode>,
+    pub preview: Handle<UiNode>,
     selected: bool,
 }
 
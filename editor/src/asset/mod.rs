@@ -15,6 +15,7 @@ use crate::{
     AssetItem, AssetKind, GameEngine, Message, Mode,
 };
 use fyrox::{
+    asset::ResourceState,
     core::{
         color::Color, futures::executor::block_on, make_relative_path, pool::Handle, scope_profile,
     },
@@ -25,6 +26,7 @@ use fyrox::{
         copypasta::ClipboardProvider,
         file_browser::{FileBrowserBuilder, FileBrowserMessage, Filter},
         grid::{Column, GridBuilder, Row},
+        image::ImageMessage,
         menu::{MenuItemBuilder, MenuItemContent, MenuItemMessage},
         message::{MessageDirection, UiMessage},
         popup::{Placement, PopupBuilder, PopupMessage},
@@ -37,7 +39,12 @@ use fyrox::{
         BuildContext, HorizontalAlignment, Orientation, UiNode, UserInterface, VerticalAlignment,
         BRUSH_DARK,
     },
-    utils::log::Log,
+    resource::texture::Texture,
+    utils::{
+        into_gui_texture,
+        log::Log,
+        preview::{PreviewRenderer, PreviewSource},
+    },
 };
 use std::{
     ffi::OsStr,
@@ -48,6 +55,7 @@ use std::{
 
 mod inspector;
 pub mod item;
+pub mod registry;
 
 struct ContextMenu {
     menu: Handle<UiNode>,
@@ -184,11 +192,15 @@ pub struct AssetBrowser {
     item_to_select: Option<PathBuf>,
     inspector: AssetInspector,
     context_menu: ContextMenu,
+    thumbnails: PreviewRenderer,
+    // Preview images of asset items that wait for their thumbnails.
+    pending_thumbnails: Vec<(Handle<UiNode>, Texture)>,
 }
 
 impl AssetBrowser {
     pub fn new(engine: &mut GameEngine) -> Self {
         let preview = PreviewPanel::new(engine, 250, 250);
+        let thumbnails = PreviewRenderer::new(&mut engine.scenes, 128);
         let ctx = &mut engine.user_interface.build_ctx();
 
         let inspector = AssetInspector::new(ctx, 1, 0);
@@ -296,6 +308,8 @@ impl AssetBrowser {
             item_to_select: None,
             inspector,
             context_menu,
+            thumbnails,
+            pending_thumbnails: Default::default(),
         }
     }
 
@@ -387,6 +401,8 @@ impl AssetBrowser {
                 for child in self.items.drain(..) {
                     ui.send_message(WidgetMessage::remove(child, MessageDirection::ToWidget));
                 }
+                self.thumbnails.clear(&mut engine.scenes);
+                self.pending_thumbnails.clear();
 
                 // Get all supported assets from folder and generate previews for them.
                 if let Ok(dir_iter) = std::fs::read_dir(path) {
@@ -420,6 +436,16 @@ impl AssetBrowser {
 
                                 self.items.push(asset_item);
 
+                                if let Some(item) = ui.node(asset_item).cast::<AssetItem>() {
+                                    if item.kind == AssetKind::Model {
+                                        let thumbnail =
+                                            self.thumbnails.request(PreviewSource::Model(
+                                                engine.resource_manager.request_model(&entry_path),
+                                            ));
+                                        self.pending_thumbnails.push((item.preview, thumbnail));
+                                    }
+                                }
+
                                 ui.send_message(WidgetMessage::link(
                                     asset_item,
                                     MessageDirection::ToWidget,
@@ -464,7 +490,27 @@ impl AssetBrowser {
     }
 
     pub fn update(&mut self, engine: &mut GameEngine) {
-        self.preview.update(engine)
+        self.preview.update(engine);
+
+        if let Some(renderer) = engine.renderer.as_mut() {
+            self.thumbnails.update(&mut engine.scenes, renderer);
+        }
+
+        let ui = &engine.user_interface;
+        self.pending_thumbnails.retain(|(image, thumbnail)| {
+            if thumbnail.is_loading() {
+                true
+            } else {
+                if let ResourceState::Ok(_) = *thumbnail.state() {
+                    ui.send_message(ImageMessage::texture(
+                        *image,
+                        MessageDirection::ToWidget,
+                        Some(into_gui_texture(thumbnail.clone())),
+                    ));
+                }
+                false
+            }
+        });
     }
 
     pub fn on_mode_changed(&mut self, ui: &UserInterface, mode: &Mode) {
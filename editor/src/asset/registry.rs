@@ -0,0 +1,114 @@
+use fyrox::{
+    asset::registry::{self, content_hash, AssetRegistry, ASSET_REGISTRY_PATH},
+    core::{futures::executor::block_on, make_relative_path},
+    utils::log::Log,
+};
+use std::path::{Path, PathBuf};
+
+const ASSET_EXTENSIONS: [&str; 16] = [
+    "rgs", "fbx", "jpg", "jpeg", "tga", "png", "bmp", "dds", "gif", "tif", "tiff", "ogg", "wav",
+    "shader", "absm", "curve",
+];
+
+fn is_asset(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| {
+        ASSET_EXTENSIONS
+            .iter()
+            .any(|e| ext.to_string_lossy().eq_ignore_ascii_case(e))
+    })
+}
+
+fn collect_assets(root: &Path, dir: &Path, assets: &mut Vec<(PathBuf, u64)>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            // Build artifacts and hidden directories never contain assets.
+            if name != "target" && !name.starts_with('.') {
+                collect_assets(root, &path, assets);
+            }
+        } else if is_asset(&path) {
+            if let (Ok(data), Ok(relative_path)) = (std::fs::read(&path), path.strip_prefix(root)) {
+                assets.push((relative_path.to_path_buf(), content_hash(&data)));
+            }
+        }
+    }
+}
+
+/// Loads asset registry of the project in the current working directory (or creates a new one),
+/// makes it current and synchronizes it with the actual set of assets. Reading and hashing of
+/// assets is done on a background thread, so large projects do not block the editor. Assets that
+/// are moved while the editor is running are tracked by the resource watcher.
+pub fn sync_asset_registry() {
+    let root = match std::env::current_dir() {
+        Ok(root) => root,
+        Err(e) => {
+            Log::err(format!("Unable to sync asset registry. Reason: {}", e));
+            return;
+        }
+    };
+
+    let registry = if Path::new(ASSET_REGISTRY_PATH).exists() {
+        match block_on(AssetRegistry::load(ASSET_REGISTRY_PATH)) {
+            Ok(registry) => registry,
+            Err(e) => {
+                Log::err(format!("Unable to load asset registry. Reason: {}", e));
+                return;
+            }
+        }
+    } else {
+        AssetRegistry::new()
+    };
+
+    registry::set_asset_registry(Some(registry));
+
+    std::thread::spawn(move || {
+        let mut assets = Vec::new();
+        collect_assets(&root, &root, &mut assets);
+
+        // Another project could be opened in the meantime.
+        if std::env::current_dir().map_or(true, |dir| dir != root) {
+            return;
+        }
+
+        if let Some(registry) = registry::asset_registry_mut().as_mut() {
+            // Assets could be moved while they were hashed, such moves are already handled by
+            // the resource watcher.
+            assets.retain(|(path, _)| root.join(path).exists());
+
+            let report = registry.sync(assets);
+            for (old_path, new_path) in report.moved.iter() {
+                Log::info(format!(
+                    "Asset {} was moved to {}.",
+                    old_path.display(),
+                    new_path.display()
+                ));
+            }
+            for path in report.missing.iter() {
+                Log::warn(format!("Asset {} is missing.", path.display()));
+            }
+
+            Log::verify(registry.save(root.join(ASSET_REGISTRY_PATH)));
+        }
+    });
+}
+
+/// Registers an asset at the given path in current asset registry (if any) and saves the registry.
+pub fn register_asset(path: &Path) {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(_) => return,
+    };
+    let path = make_relative_path(path).unwrap_or_else(|_| path.to_path_buf());
+
+    if let Some(registry) = registry::asset_registry_mut().as_mut() {
+        registry.register(&path, content_hash(&data));
+        Log::verify(registry.save(ASSET_REGISTRY_PATH));
+    }
+}
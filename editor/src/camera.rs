@@ -3,7 +3,7 @@ use crate::{settings::camera::CameraSettings, utils::built_in_skybox, SceneCamer
 use fyrox::{
     core::{
         algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3},
-        math::{plane::Plane, ray::Ray, Matrix4Ext, TriangleDefinition, Vector3Ext},
+        math::{plane::Plane, Matrix4Ext},
         pool::Handle,
     },
     gui::message::{KeyCode, MouseButton},
@@ -11,11 +11,7 @@ use fyrox::{
         base::BaseBuilder,
         camera::{Camera, CameraBuilder, Exposure, Projection},
         graph::Graph,
-        mesh::{
-            buffer::{VertexAttributeUsage, VertexReadTrait},
-            surface::SurfaceData,
-            Mesh,
-        },
+        mesh::Mesh,
         node::Node,
         pivot::PivotBuilder,
         sound::listener::ListenerBuilder,
@@ -423,13 +419,13 @@ impl CameraController {
                     // Do coarse, but fast, intersection test with bounding box first.
                     if let Some(points) = object_space_ray.aabb_intersection_points(&aabb) {
                         if has_hull(node) {
-                            if let Some((closest_distance, position)) =
-                                precise_ray_test(node, &ray, ignore_back_faces)
+                            if let Some(intersection) =
+                                graph.cast_ray_mesh(handle, &ray, ignore_back_faces)
                             {
                                 context.pick_list.push(CameraPickResult {
-                                    position,
+                                    position: intersection.position.coords,
                                     node: handle,
-                                    toi: closest_distance,
+                                    toi: intersection.toi,
                                 });
                             }
                         } else if !only_meshes {
@@ -507,71 +503,10 @@ impl CameraController {
     }
 }
 
-fn read_vertex_position(data: &SurfaceData, i: u32) -> Option<Vector3<f32>> {
-    data.vertex_buffer
-        .get(i as usize)
-        .and_then(|v| v.read_3_f32(VertexAttributeUsage::Position).ok())
-}
-
 fn transform_vertex(vertex: Vector3<f32>, transform: &Matrix4<f32>) -> Vector3<f32> {
     transform.transform_point(&Point3::from(vertex)).coords
 }
 
-fn read_triangle(
-    data: &SurfaceData,
-    triangle: &TriangleDefinition,
-    transform: &Matrix4<f32>,
-) -> Option<[Vector3<f32>; 3]> {
-    let a = transform_vertex(read_vertex_position(data, triangle[0])?, transform);
-    let b = transform_vertex(read_vertex_position(data, triangle[1])?, transform);
-    let c = transform_vertex(read_vertex_position(data, triangle[2])?, transform);
-    Some([a, b, c])
-}
-
 fn has_hull(node: &Node) -> bool {
     node.query_component_ref::<Mesh>().is_some()
 }
-
-fn precise_ray_test(
-    node: &Node,
-    ray: &Ray,
-    ignore_back_faces: bool,
-) -> Option<(f32, Vector3<f32>)> {
-    let mut closest_distance = f32::MAX;
-    let mut closest_point = None;
-
-    if let Some(mesh) = node.query_component_ref::<Mesh>() {
-        let transform = mesh.global_transform();
-
-        for surface in mesh.surfaces().iter() {
-            let data = surface.data();
-            let data = data.lock();
-
-            for triangle in data
-                .geometry_buffer
-                .iter()
-                .filter_map(|t| read_triangle(&data, t, &transform))
-            {
-                if ignore_back_faces {
-                    // If normal of the triangle is facing in the same direction as ray's direction,
-                    // then we skip such triangle.
-                    let normal = (triangle[1] - triangle[0]).cross(&(triangle[2] - triangle[0]));
-                    if normal.dot(&ray.dir) >= 0.0 {
-                        continue;
-                    }
-                }
-
-                if let Some(pt) = ray.triangle_intersection_point(&triangle) {
-                    let distance = ray.origin.sqr_distance(&pt);
-
-                    if distance < closest_distance {
-                        closest_distance = distance;
-                        closest_point = Some(pt);
-                    }
-                }
-            }
-        }
-    }
-
-    closest_point.map(|pt| (closest_distance, pt))
-}
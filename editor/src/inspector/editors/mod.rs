@@ -18,8 +18,8 @@ use fyrox::animation::machine::{PoseNode, State};
 use fyrox::{
     animation::{
         machine::{
-            node::BasePoseNode, BlendAnimations, BlendAnimationsByIndex, BlendPose,
-            IndexedBlendInput, Machine, PlayAnimation, PoseWeight,
+            node::BasePoseNode, BlendAnimations, BlendAnimationsByIndex, BlendPose, BlendSpace,
+            BlendSpacePoint, IndexedBlendInput, Machine, PlayAnimation, PoseWeight,
         },
         AnimationContainer,
     },
@@ -35,23 +35,32 @@ use fyrox::{
     },
     material::{
         shader::{Shader, ShaderError, ShaderState},
-        SharedMaterial,
+        BlendOverride, CullFaceOverride, MaterialRenderState, SharedMaterial, StateOverride,
+        StencilOverride, TransparencyOverride,
+    },
+    renderer::framework::{
+        framebuffer::BlendParameters,
+        state::{
+            BlendEquation, BlendFactor, BlendFunc, BlendMode, CompareFunc, StencilAction,
+            StencilFunc, StencilOp,
+        },
     },
     resource::{
         curve::{CurveResource, CurveResourceError, CurveResourceState},
         model::{MaterialSearchOptions, Model, ModelData, ModelLoadError},
         texture::{
-            CompressionOptions, Texture, TextureMagnificationFilter, TextureMinificationFilter,
-            TextureWrapMode,
+            CompressionOptions, NormalMapMode, Texture, TextureMagnificationFilter,
+            TextureMinificationFilter, TextureWrapMode,
         },
     },
     scene::{
+        animation::lod::{AnimationLod, AnimationLodLevel},
         base::{
             Base, LevelOfDetail, LodControlledObject, LodGroup, Mobility, Property, PropertyValue,
         },
         camera::{
-            ColorGradingLut, Exposure, OrthographicProjection, PerspectiveProjection, Projection,
-            SkyBox,
+            Bloom, ChromaticAberration, ColorGradingLut, Exposure, OrthographicProjection,
+            PerspectiveProjection, PostProcessing, Projection, SkyBox, Vignette,
         },
         collider::{
             BallShape, BitMask, CapsuleShape, ColliderShape, ConeShape, ConvexPolyhedronShape,
@@ -59,13 +68,16 @@ use fyrox::{
             SegmentShape, TriangleShape, TrimeshShape,
         },
         dim2,
+        force_field::{ForceFieldFalloff, ForceFieldKind},
         graph::physics::CoefficientCombineRule,
+        gravity_zone::{GravityZoneKind, GravityZoneShape},
         joint::*,
         light::{
             directional::{CsmOptions, FrustumSplitOptions},
             BaseLight,
         },
         mesh::{
+            instanced::MeshInstance,
             surface::{Surface, SurfaceSharedData},
             RenderPath,
         },
@@ -77,7 +89,7 @@ use fyrox::{
             },
             EmitterWrapper,
         },
-        rigidbody::RigidBodyType,
+        rigidbody::{RigidBodyInterpolation, RigidBodyType},
         sound::{
             self,
             effect::{BaseEffect, Effect, EffectInput, ReverbEffect},
@@ -138,6 +150,7 @@ pub fn make_property_editors_container(
     container.register_inheritable_vec_collection::<NodeHandle>();
 
     container.register_inheritable_vec_collection::<Surface>();
+    container.register_inheritable_vec_collection::<MeshInstance>();
     container.register_inheritable_vec_collection::<Layer>();
     container.register_inheritable_vec_collection::<EmitterWrapper>();
     container.register_inheritable_vec_collection::<LevelOfDetail>();
@@ -205,6 +218,10 @@ pub fn make_property_editors_container(
     container.insert(InheritablePropertyEditorDefinition::<Option<Shader>>::new());
 
     container.register_inheritable_inspectable::<ColorGradingLut>();
+    container.register_inheritable_inspectable::<PostProcessing>();
+    container.register_inheritable_inspectable::<Bloom>();
+    container.register_inheritable_inspectable::<Vignette>();
+    container.register_inheritable_inspectable::<ChromaticAberration>();
     container.register_inheritable_inspectable::<InteractionGroups>();
     container.register_inheritable_inspectable::<GeometrySource>();
 
@@ -236,6 +253,12 @@ pub fn make_property_editors_container(
     container.register_inheritable_inspectable::<OrthographicProjection>();
     container.register_inheritable_inspectable::<Transform>();
     container.register_inheritable_inspectable::<CsmOptions>();
+    container.register_inheritable_inspectable::<MaterialRenderState>();
+    container.register_inheritable_inspectable::<BlendParameters>();
+    container.register_inheritable_inspectable::<BlendFunc>();
+    container.register_inheritable_inspectable::<BlendEquation>();
+    container.register_inheritable_inspectable::<StencilFunc>();
+    container.register_inheritable_inspectable::<StencilOp>();
 
     container.register_inheritable_option::<ColorGradingLut>();
     container.register_inheritable_option::<Biquad>();
@@ -246,6 +269,7 @@ pub fn make_property_editors_container(
     container.register_inheritable_enum::<dim2::collider::ColliderShape, _>();
     container.register_inheritable_enum::<CoefficientCombineRule, _>();
     container.register_inheritable_enum::<CompressionOptions, _>();
+    container.register_inheritable_enum::<NormalMapMode, _>();
     container.register_inheritable_enum::<TextureWrapMode, _>();
     container.register_inheritable_enum::<TextureMagnificationFilter, _>();
     container.register_inheritable_enum::<TextureMinificationFilter, _>();
@@ -254,12 +278,26 @@ pub fn make_property_editors_container(
     container.register_inheritable_enum::<PropertyValue, _>();
     container.register_inheritable_enum::<Mobility, _>();
     container.register_inheritable_enum::<RigidBodyType, _>();
+    container.register_inheritable_enum::<RigidBodyInterpolation, _>();
+    container.register_inheritable_enum::<ForceFieldKind, _>();
+    container.register_inheritable_enum::<ForceFieldFalloff, _>();
+    container.register_inheritable_enum::<GravityZoneKind, _>();
+    container.register_inheritable_enum::<GravityZoneShape, _>();
     container.register_inheritable_enum::<Exposure, _>();
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
+    container.register_inheritable_enum::<StateOverride, _>();
+    container.register_inheritable_enum::<CullFaceOverride, _>();
+    container.register_inheritable_enum::<BlendOverride, _>();
+    container.register_inheritable_enum::<BlendFactor, _>();
+    container.register_inheritable_enum::<BlendMode, _>();
+    container.register_inheritable_enum::<StencilOverride, _>();
+    container.register_inheritable_enum::<TransparencyOverride, _>();
+    container.register_inheritable_enum::<CompareFunc, _>();
+    container.register_inheritable_enum::<StencilAction, _>();
 
     container.insert(ScriptPropertyEditorDefinition {});
     container.insert(BitFieldPropertyEditorDefinition::<BitMask>::new());
@@ -296,6 +334,9 @@ pub fn make_property_editors_container(
     container.insert(InspectablePropertyEditorDefinition::<BlendAnimationsByIndex>::new());
     container.insert(InspectablePropertyEditorDefinition::<BlendAnimations>::new());
     container.insert(InspectablePropertyEditorDefinition::<PlayAnimation>::new());
+    container.insert(InspectablePropertyEditorDefinition::<BlendSpace>::new());
+    container.insert(InspectablePropertyEditorDefinition::<BlendSpacePoint>::new());
+    container.insert(VecCollectionPropertyEditorDefinition::<BlendSpacePoint>::new());
 
     container.insert(InspectablePropertyEditorDefinition::<Handle<PoseNode>>::new());
     container.insert(InspectablePropertyEditorDefinition::<Handle<State>>::new());
@@ -305,6 +346,10 @@ pub fn make_property_editors_container(
     container.insert(AnimationContainerPropertyEditorDefinition);
     container.insert(InheritablePropertyEditorDefinition::<AnimationContainer>::new());
 
+    container.register_inheritable_inspectable::<AnimationLod>();
+    container.insert(InspectablePropertyEditorDefinition::<AnimationLodLevel>::new());
+    container.insert(VecCollectionPropertyEditorDefinition::<AnimationLodLevel>::new());
+
     container.insert(MachinePropertyEditorDefinition);
     container.insert(InheritablePropertyEditorDefinition::<Machine>::new());
 
@@ -16,6 +16,7 @@ pub mod navmesh;
 pub mod plane;
 pub mod rotate_mode;
 pub mod scale_mode;
+pub mod script_gizmo;
 pub mod select_mode;
 pub mod terrain;
 
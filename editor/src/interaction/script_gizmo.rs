@@ -0,0 +1,294 @@
+//! Script gizmos allow editor plugins to visualize and edit user components (scripts) directly in
+//! the scene viewer. See [`ScriptGizmo`] docs for more info.
+
+use crate::{
+    scene::{
+        commands::{graph::SetScriptCommand, SceneCommand},
+        EditorScene,
+    },
+    GameEngine, Message,
+};
+use fyrox::{
+    core::{
+        algebra::{Vector2, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, plane::Plane},
+        pool::Handle,
+        uuid::Uuid,
+    },
+    fxhash::FxHashMap,
+    scene::{
+        camera::Camera,
+        debug::Line,
+        node::{Node, TypeUuidProvider},
+    },
+    script::Script,
+};
+use std::sync::mpsc::Sender;
+
+/// Shape of a gizmo handle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GizmoShape {
+    /// A wire sphere centered at the handle position.
+    Sphere {
+        /// Radius of the sphere.
+        radius: f32,
+    },
+    /// An arrow that points to the handle position, the position could be dragged to change
+    /// direction and length of the arrow.
+    Arrow {
+        /// Normalized direction of the arrow.
+        direction: Vector3<f32>,
+        /// Length of the arrow.
+        length: f32,
+    },
+    /// A wire box (volume) centered at the handle position.
+    Box {
+        /// Half extents of the box.
+        half_extents: Vector3<f32>,
+    },
+}
+
+impl GizmoShape {
+    fn pick_radius(&self) -> f32 {
+        match self {
+            GizmoShape::Sphere { radius } => *radius,
+            GizmoShape::Arrow { length, .. } => (length * 0.1).max(0.05),
+            GizmoShape::Box { half_extents } => {
+                half_extents.x.min(half_extents.y).min(half_extents.z)
+            }
+        }
+    }
+}
+
+/// A handle of a script gizmo, that could be picked and dragged in the scene viewer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GizmoHandle {
+    /// An id of the handle, it is passed back to [`ScriptGizmo::on_drag`] so the gizmo could tell
+    /// which handle is being dragged.
+    pub id: usize,
+    /// World space position of the handle.
+    pub position: Vector3<f32>,
+    /// Visual shape of the handle.
+    pub shape: GizmoShape,
+    /// Color of the handle.
+    pub color: Color,
+}
+
+/// Script gizmo describes how a script of some type should be visualized and edited in the scene
+/// viewer. Gizmos are registered per script type using [`crate::Editor::add_script_gizmo`].
+///
+/// Every frame the editor asks the gizmo for a set of handles of every node with the script of
+/// the type, draws them and lets the user drag them with the mouse. Dragging is done on a plane
+/// that faces the camera and passes through the handle. When dragging is finished, the editor
+/// creates an undoable command with the modified script.
+pub trait ScriptGizmo: 'static {
+    /// Returns a set of handles of the given script instance.
+    fn handles(&self, script: &Script, node: &Node) -> Vec<GizmoHandle>;
+
+    /// Called when a handle with the given id is dragged to the given world space position. The
+    /// gizmo should modify the script accordingly.
+    fn on_drag(&self, script: &mut Script, node: &Node, handle: usize, position: Vector3<f32>);
+}
+
+struct DragContext {
+    node: Handle<Node>,
+    handle: usize,
+    plane: Plane,
+    // Offset from the picked point to the handle position.
+    offset: Vector3<f32>,
+    initial_script: Option<Script>,
+}
+
+/// A container of script gizmos, it also handles picking and dragging of gizmo handles.
+#[derive(Default)]
+pub struct ScriptGizmos {
+    gizmos: FxHashMap<Uuid, Box<dyn ScriptGizmo>>,
+    drag_context: Option<DragContext>,
+}
+
+impl ScriptGizmos {
+    /// Registers a gizmo for scripts of the given type. Previous gizmo for the type (if any) will be
+    /// replaced.
+    pub fn add<T: TypeUuidProvider>(&mut self, gizmo: impl ScriptGizmo) {
+        self.gizmos.insert(T::type_uuid(), Box::new(gizmo));
+    }
+
+    /// Returns `true` if a handle is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag_context.is_some()
+    }
+
+    fn handles_of(&self, node: &Node) -> Option<Vec<GizmoHandle>> {
+        let script = node.script()?;
+        let gizmo = self.gizmos.get(&script.id())?;
+        Some(gizmo.handles(script, node))
+    }
+
+    /// Draws handles of every script that has a gizmo.
+    pub fn draw(&self, editor_scene: &EditorScene, engine: &mut GameEngine) {
+        if self.gizmos.is_empty() {
+            return;
+        }
+
+        let scene = &mut engine.scenes[editor_scene.scene];
+
+        for (node_handle, node) in scene.graph.pair_iter() {
+            if let Some(handles) = self.handles_of(node) {
+                for handle in handles {
+                    let color = match self.drag_context {
+                        Some(ref ctx) if ctx.node == node_handle && ctx.handle == handle.id => {
+                            Color::opaque(255, 255, 0)
+                        }
+                        _ => handle.color,
+                    };
+
+                    let ctx = &mut scene.drawing_context;
+                    match handle.shape {
+                        GizmoShape::Sphere { radius } => {
+                            ctx.draw_wire_sphere(handle.position, radius, 16, color)
+                        }
+                        GizmoShape::Arrow { direction, length } => {
+                            let begin = handle.position - direction.scale(length);
+                            ctx.add_line(Line {
+                                begin,
+                                end: handle.position,
+                                color,
+                            });
+                            ctx.draw_wire_sphere(
+                                handle.position,
+                                handle.shape.pick_radius(),
+                                8,
+                                color,
+                            );
+                        }
+                        GizmoShape::Box { half_extents } => ctx.draw_aabb(
+                            &AxisAlignedBoundingBox::from_min_max(
+                                handle.position - half_extents,
+                                handle.position + half_extents,
+                            ),
+                            color,
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Tries to pick a handle under the cursor and starts dragging it. Returns `true` if a handle
+    /// was picked.
+    pub fn try_begin_drag(
+        &mut self,
+        editor_scene: &EditorScene,
+        engine: &GameEngine,
+        mouse_position: Vector2<f32>,
+        frame_size: Vector2<f32>,
+    ) -> bool {
+        if self.gizmos.is_empty() {
+            return false;
+        }
+
+        let graph = &engine.scenes[editor_scene.scene].graph;
+        let camera_node = &graph[editor_scene.camera_controller.camera];
+        let camera = match camera_node.cast::<Camera>() {
+            Some(camera) => camera,
+            None => return false,
+        };
+        let ray = camera.make_ray(mouse_position, frame_size);
+
+        let mut closest: Option<(f32, Handle<Node>, GizmoHandle)> = None;
+        for (node_handle, node) in graph.pair_iter() {
+            if let Some(handles) = self.handles_of(node) {
+                for handle in handles {
+                    if let Some(result) =
+                        ray.sphere_intersection(&handle.position, handle.shape.pick_radius())
+                    {
+                        if result.min >= 0.0 && closest.map_or(true, |(t, _, _)| result.min < t) {
+                            closest = Some((result.min, node_handle, handle));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some((t, node, handle)) = closest {
+            if let Some(plane) =
+                Plane::from_normal_and_point(&camera_node.look_vector(), &handle.position)
+            {
+                let picked = ray.get_point(t);
+                self.drag_context = Some(DragContext {
+                    node,
+                    handle: handle.id,
+                    plane,
+                    offset: handle.position - picked,
+                    initial_script: graph[node].script_cloned(),
+                });
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Moves currently dragged handle (if any) to a new position under the cursor.
+    pub fn drag(
+        &mut self,
+        editor_scene: &EditorScene,
+        engine: &mut GameEngine,
+        mouse_position: Vector2<f32>,
+        frame_size: Vector2<f32>,
+    ) {
+        let ctx = match self.drag_context {
+            Some(ref ctx) => ctx,
+            None => return,
+        };
+
+        let graph = &mut engine.scenes[editor_scene.scene].graph;
+        let point = match graph[editor_scene.camera_controller.camera]
+            .cast::<Camera>()
+            .and_then(|camera| {
+                camera
+                    .make_ray(mouse_position, frame_size)
+                    .plane_intersection_point(&ctx.plane)
+            }) {
+            Some(point) => point,
+            None => return,
+        };
+
+        // Take the script out temporarily, so the gizmo could borrow the node and the script
+        // at the same time.
+        if let Some(mut script) = graph[ctx.node].script_inner().take() {
+            if let Some(gizmo) = self.gizmos.get(&script.id()) {
+                gizmo.on_drag(
+                    &mut script,
+                    &graph[ctx.node],
+                    ctx.handle,
+                    point + ctx.offset,
+                );
+            }
+            *graph[ctx.node].script_inner() = Some(script);
+        }
+    }
+
+    /// Finishes dragging and commits the changes using an undoable command.
+    pub fn end_drag(
+        &mut self,
+        editor_scene: &EditorScene,
+        engine: &mut GameEngine,
+        sender: &Sender<Message>,
+    ) {
+        if let Some(ctx) = self.drag_context.take() {
+            let graph = &mut engine.scenes[editor_scene.scene].graph;
+            if graph.is_valid_handle(ctx.node) {
+                // Revert the changes made during dragging, the command will apply them again.
+                let new_script =
+                    std::mem::replace(graph[ctx.node].script_inner(), ctx.initial_script);
+                sender
+                    .send(Message::DoSceneCommand(SceneCommand::new(
+                        SetScriptCommand::new(ctx.node, new_script),
+                    )))
+                    .unwrap();
+            }
+        }
+    }
+}
@@ -31,14 +31,23 @@ mod particle;
 mod preview;
 mod scene;
 mod scene_viewer;
+mod script_heatmap;
+mod script_reload;
 mod settings;
 mod utils;
 mod world;
 
+pub use crate::interaction::script_gizmo::{GizmoHandle, GizmoShape, ScriptGizmo};
+
 use crate::{
     absm::AbsmEditor,
     animation::AnimationEditor,
-    asset::{item::AssetItem, item::AssetKind, AssetBrowser},
+    asset::{
+        item::AssetItem,
+        item::AssetKind,
+        registry::{register_asset, sync_asset_registry},
+        AssetBrowser,
+    },
     audio::AudioPanel,
     build::BuildWindow,
     command::{panel::CommandStackViewer, Command, CommandStack},
@@ -70,6 +79,8 @@ use crate::{
         EditorScene, Selection,
     },
     scene_viewer::SceneViewer,
+    script_heatmap::ScriptHeatmap,
+    script_reload::ScriptReloader,
     settings::{camera::SceneCameraSettings, Settings},
     utils::path_fixer::PathFixer,
     world::{graph::selection::GraphSelection, WorldViewer},
@@ -111,10 +122,11 @@ use fyrox::{
     scene::{
         camera::{Camera, Projection},
         mesh::Mesh,
-        node::Node,
+        node::{Node, TypeUuidProvider},
         particle_system::ParticleSystem,
         Scene, SceneLoader,
     },
+    script::profiler::SCRIPT_COST_REPORT_PREFIX,
     utils::{
         into_gui_texture,
         log::{Log, MessageKind},
@@ -492,6 +504,8 @@ pub struct Editor {
     animation_editor: AnimationEditor,
     particle_system_control_panel: ParticleSystemPreviewControlPanel,
     overlay_pass: Rc<RefCell<OverlayRenderPass>>,
+    script_heatmap: ScriptHeatmap,
+    script_reloader: ScriptReloader,
 }
 
 impl Editor {
@@ -535,8 +549,10 @@ impl Editor {
             engine.get_window().scale_factor() as f32,
         );
 
-        let overlay_pass = OverlayRenderPass::new(engine.renderer.pipeline_state());
-        engine.renderer.add_render_pass(overlay_pass.clone());
+        // The editor never creates headless engine, so the renderer is always available.
+        let renderer = engine.renderer.as_mut().unwrap();
+        let overlay_pass = OverlayRenderPass::new(renderer.pipeline_state());
+        renderer.add_render_pass(overlay_pass.clone());
 
         let (message_sender, message_receiver) = mpsc::channel();
 
@@ -564,6 +580,8 @@ impl Editor {
 
                 match engine
                     .renderer
+                    .as_mut()
+                    .unwrap()
                     .set_quality_settings(&settings.graphics.quality)
                 {
                     Ok(_) => {
@@ -755,7 +773,7 @@ impl Editor {
 
         let scene_settings = SceneSettingsWindow::new(ctx, message_sender.clone());
 
-        let material_editor = MaterialEditor::new(&mut engine);
+        let material_editor = MaterialEditor::new(&mut engine, message_sender.clone());
 
         let mut editor = Self {
             animation_editor,
@@ -798,6 +816,8 @@ impl Editor {
             scene_settings,
             particle_system_control_panel,
             overlay_pass,
+            script_heatmap: Default::default(),
+            script_reloader: Default::default(),
         };
 
         editor.set_interaction_mode(Some(InteractionModeKind::Move));
@@ -859,6 +879,8 @@ impl Editor {
         match self
             .engine
             .renderer
+            .as_mut()
+            .unwrap()
             .set_quality_settings(&self.settings.graphics.quality)
         {
             Ok(_) => {
@@ -951,7 +973,9 @@ impl Editor {
         );
         self.scene_viewer
             .reset_camera_projection(&self.engine.user_interface);
-        self.engine.renderer.flush();
+        if let Some(renderer) = self.engine.renderer.as_mut() {
+            renderer.flush();
+        }
     }
 
     fn set_interaction_mode(&mut self, mode: Option<InteractionModeKind>) {
@@ -1157,8 +1181,12 @@ impl Editor {
             self.audio_panel
                 .handle_ui_message(message, editor_scene, &self.message_sender, engine);
 
-            self.scene_settings
-                .handle_ui_message(message, &self.message_sender);
+            self.scene_settings.handle_ui_message(
+                message,
+                &self.message_sender,
+                editor_scene,
+                engine,
+            );
 
             self.navmesh_panel.handle_message(
                 message,
@@ -1247,11 +1275,20 @@ impl Editor {
     fn set_play_mode(&mut self) {
         if let Some(scene) = self.scene.as_ref() {
             if let Some(path) = scene.path.as_ref().cloned() {
-                self.save_current_scene(path.clone());
+                // The game is restarted after reloading of scripts, it must continue from its saved
+                // state. Otherwise, the game starts from the edited scene.
+                let path = match self.script_reloader.take_snapshot() {
+                    Some(snapshot) => snapshot,
+                    None => {
+                        self.save_current_scene(path.clone());
+                        path
+                    }
+                };
 
                 let mut process = std::process::Command::new("cargo");
 
                 process
+                    .stdin(Stdio::piped())
                     .stdout(Stdio::piped())
                     .arg("run")
                     .arg("--package")
@@ -1261,23 +1298,37 @@ impl Editor {
                     process.arg("--release");
                 };
 
-                process.arg("--").arg("--override-scene").arg(path);
+                process
+                    .arg("--")
+                    .arg("--override-scene")
+                    .arg(path)
+                    .arg("--profile-scripts")
+                    .arg("--state-snapshot")
+                    .arg(self.script_reloader.snapshot_path());
 
                 match process.spawn() {
                     Ok(mut process) => {
                         let active = Arc::new(AtomicBool::new(true));
 
-                        // Capture output from child process.
+                        // Capture output from child process. Script cost reports are passed to the
+                        // script heatmap, everything else goes to the log.
                         let mut stdout = process.stdout.take().unwrap();
                         let reader_active = active.clone();
+                        let script_costs_sender = self.script_heatmap.sender();
                         std::thread::spawn(move || {
                             while reader_active.load(Ordering::SeqCst) {
                                 for line in BufReader::new(&mut stdout).lines().take(10).flatten() {
-                                    Log::info(line);
+                                    if line.starts_with(SCRIPT_COST_REPORT_PREFIX) {
+                                        let _ = script_costs_sender.send(line);
+                                    } else {
+                                        Log::info(line);
+                                    }
                                 }
                             }
                         });
 
+                        self.script_reloader.on_game_started(process.stdin.take());
+
                         self.mode = Mode::Play { active, process };
 
                         self.on_mode_changed();
@@ -1337,11 +1388,18 @@ impl Editor {
         {
             Log::verify(process.kill());
 
+            self.script_reloader.reset();
+
             self.on_mode_changed();
         }
     }
 
     fn on_mode_changed(&mut self) {
+        if self.mode.is_edit() {
+            self.script_heatmap
+                .clear(&mut self.world_viewer, &self.engine);
+        }
+
         let engine = &mut self.engine;
         let ui = &engine.user_interface;
         self.scene_viewer.on_mode_changed(ui, &self.mode);
@@ -1518,6 +1576,8 @@ impl Editor {
                     );
                     Log::info(message);
 
+                    register_asset(&path);
+
                     editor_scene.has_unsaved_changes = false;
                 }
                 Err(message) => {
@@ -1623,7 +1683,11 @@ impl Editor {
 
         engine.resource_manager.state().destroy_unused_resources();
 
-        engine.renderer.flush();
+        if let Some(renderer) = engine.renderer.as_mut() {
+            renderer.flush();
+        }
+
+        sync_asset_registry();
 
         self.asset_browser
             .set_working_directory(engine, &working_directory);
@@ -1737,13 +1801,28 @@ impl Editor {
                             active.store(false, Ordering::SeqCst);
 
                             self.mode = Mode::Edit;
-                            self.on_mode_changed();
 
-                            Log::info(format!("Game was closed: {:?}", status))
+                            if self.script_reloader.on_game_closed() {
+                                // Rebuild the game with modified scripts, it will be started again
+                                // when the build is finished.
+                                self.set_build_mode();
+
+                                if self.mode.is_edit() {
+                                    self.script_reloader.reset();
+                                    self.on_mode_changed();
+                                }
+                            } else {
+                                self.script_reloader.reset();
+                                self.on_mode_changed();
+
+                                Log::info(format!("Game was closed: {:?}", status))
+                            }
                         }
                     }
                     Err(err) => Log::err(format!("Failed to wait for game process: {:?}", err)),
                 }
+
+                self.script_reloader.update();
             }
             Mode::Build { ref mut process } => {
                 self.build_window.update(&self.engine.user_interface);
@@ -1758,6 +1837,7 @@ impl Editor {
                             let code = status.code().unwrap_or(err_code);
                             if code == err_code {
                                 Log::info("Failed to build the game!");
+                                self.script_reloader.reset();
                                 self.mode = Mode::Edit;
                                 self.on_mode_changed();
                             } else {
@@ -1950,6 +2030,14 @@ impl Editor {
 
         if let Some(editor_scene) = self.scene.as_mut() {
             editor_scene.update(&mut self.engine, dt, &self.settings);
+            self.scene_viewer
+                .script_gizmos
+                .draw(editor_scene, &mut self.engine);
+
+            if let Mode::Play { .. } = self.mode {
+                self.script_heatmap
+                    .update(editor_scene, &mut self.engine, &mut self.world_viewer);
+            }
 
             self.absm_editor.update(editor_scene, &mut self.engine);
 
@@ -2033,6 +2121,15 @@ impl Editor {
         self.engine.add_plugin_constructor(plugin)
     }
 
+    /// Registers a gizmo for scripts of the given type, so instances of the script could be edited
+    /// visually in the scene viewer.
+    pub fn add_script_gizmo<T>(&mut self, gizmo: impl ScriptGizmo)
+    where
+        T: TypeUuidProvider,
+    {
+        self.scene_viewer.script_gizmos.add::<T>(gizmo)
+    }
+
     pub fn run(mut self, event_loop: EventLoop<()>) -> ! {
         event_loop.run(move |event, _, control_flow| match event {
             Event::MainEventsCleared => {
@@ -3,6 +3,7 @@ use fyrox::{
     core::{pool::Handle, scope_profile},
     gui::{
         button::{ButtonBuilder, ButtonMessage},
+        check_box::{CheckBoxBuilder, CheckBoxMessage},
         grid::{Column, GridBuilder, Row},
         message::{MessageDirection, UiMessage},
         numeric::{NumericUpDownBuilder, NumericUpDownMessage},
@@ -11,23 +12,33 @@ use fyrox::{
         window::{WindowBuilder, WindowTitle},
         Thickness, UiNode, VerticalAlignment,
     },
-    utils::lightmap::Lightmap,
+    utils::{
+        lightmap::{Lightmap, LightmapBackend, LightmapSettings},
+        log::Log,
+    },
 };
 
 pub struct LightPanel {
     pub window: Handle<UiNode>,
     nud_texels_per_unit: Handle<UiNode>,
     nud_spacing: Handle<UiNode>,
+    cb_use_gpu: Handle<UiNode>,
+    cb_denoise: Handle<UiNode>,
     generate: Handle<UiNode>,
-    texels_per_unit: u32,
-    spacing: f32,
+    rebake: Handle<UiNode>,
+    settings: LightmapSettings,
+    use_gpu: bool,
 }
 
 impl LightPanel {
     pub fn new(engine: &mut GameEngine) -> Self {
         let generate;
+        let rebake;
         let nud_texels_per_unit;
         let nud_spacing;
+        let cb_use_gpu;
+        let cb_denoise;
+        let settings = LightmapSettings::default();
         let ctx = &mut engine.user_interface.build_ctx();
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0).with_height(400.0))
             .with_title(WindowTitle::Text("Light Settings".to_owned()))
@@ -56,7 +67,7 @@ impl LightPanel {
                             .with_max_value(256.0)
                             .with_step(4.0)
                             .with_precision(0)
-                            .with_value(128.0)
+                            .with_value(settings.texels_per_unit as f32)
                             .build(ctx);
                             nud_texels_per_unit
                         })
@@ -81,20 +92,73 @@ impl LightPanel {
                             .with_max_value(0.1)
                             .with_step(0.001)
                             .with_precision(3)
-                            .with_value(0.02)
+                            .with_value(settings.spacing)
                             .build(ctx);
                             nud_spacing
                         })
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(2)
+                                    .on_column(0)
+                                    .with_vertical_alignment(VerticalAlignment::Center),
+                            )
+                            .with_text("Use GPU")
+                            .build(ctx),
+                        )
                         .with_child({
-                            generate = ButtonBuilder::new(
+                            cb_use_gpu = CheckBoxBuilder::new(
                                 WidgetBuilder::new()
                                     .on_row(2)
                                     .on_column(1)
                                     .with_margin(Thickness::uniform(1.0)),
                             )
+                            .checked(Some(true))
+                            .build(ctx);
+                            cb_use_gpu
+                        })
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(3)
+                                    .on_column(0)
+                                    .with_vertical_alignment(VerticalAlignment::Center),
+                            )
+                            .with_text("Denoise")
+                            .build(ctx),
+                        )
+                        .with_child({
+                            cb_denoise = CheckBoxBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(3)
+                                    .on_column(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .checked(Some(settings.denoise))
+                            .build(ctx);
+                            cb_denoise
+                        })
+                        .with_child({
+                            generate = ButtonBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(4)
+                                    .on_column(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
                             .with_text("Generate Lightmap")
                             .build(ctx);
                             generate
+                        })
+                        .with_child({
+                            rebake = ButtonBuilder::new(
+                                WidgetBuilder::new()
+                                    .on_row(5)
+                                    .on_column(1)
+                                    .with_margin(Thickness::uniform(1.0)),
+                            )
+                            .with_text("Rebake Changed")
+                            .build(ctx);
+                            rebake
                         }),
                 )
                 .add_column(Column::strict(100.0))
@@ -102,6 +166,9 @@ impl LightPanel {
                 .add_row(Row::strict(25.0))
                 .add_row(Row::strict(25.0))
                 .add_row(Row::strict(25.0))
+                .add_row(Row::strict(25.0))
+                .add_row(Row::strict(25.0))
+                .add_row(Row::strict(25.0))
                 .add_row(Row::stretch())
                 .build(ctx),
             )
@@ -110,10 +177,13 @@ impl LightPanel {
         Self {
             window,
             generate,
+            rebake,
             nud_texels_per_unit,
-            texels_per_unit: 128,
             nud_spacing,
-            spacing: 0.02,
+            cb_use_gpu,
+            cb_denoise,
+            settings,
+            use_gpu: true,
         }
     }
 
@@ -126,29 +196,57 @@ impl LightPanel {
         scope_profile!();
 
         if let Some(ButtonMessage::Click) = message.data::<ButtonMessage>() {
-            if message.destination() == self.generate {
+            if message.destination() == self.generate || message.destination() == self.rebake {
                 let scene = &mut engine.scenes[editor_scene.scene];
 
-                let lightmap = Lightmap::new(
+                // Rebake starts from the current lightmap, so only changed nodes are baked again.
+                let mut lightmap = if message.destination() == self.rebake {
+                    scene.lightmap().cloned().unwrap_or_default()
+                } else {
+                    Lightmap::default()
+                };
+
+                let backend = match engine.renderer.as_mut() {
+                    Some(renderer) if self.use_gpu => LightmapBackend::Gpu(renderer),
+                    _ => LightmapBackend::Cpu,
+                };
+
+                match lightmap.rebake(
                     scene,
-                    self.texels_per_unit,
+                    &self.settings,
+                    backend,
                     Default::default(),
                     Default::default(),
-                )
-                .unwrap();
-                lightmap
-                    .save("./", engine.resource_manager.clone())
-                    .unwrap();
-                scene.set_lightmap(lightmap).unwrap();
+                ) {
+                    Ok(count) => {
+                        Log::info(format!("{} node(s) were baked.", count));
+
+                        if let Err(e) = lightmap.save("./", engine.resource_manager.clone()) {
+                            Log::err(format!("Failed to save lightmap. Reason: {:?}", e));
+                        }
+                        if let Err(e) = scene.set_lightmap(lightmap) {
+                            Log::err(format!("Failed to set lightmap. Reason: {}", e));
+                        }
+                    }
+                    Err(e) => Log::err(format!("Failed to bake lightmap. Reason: {}", e)),
+                }
+            }
+        } else if let Some(CheckBoxMessage::Check(Some(value))) = message.data() {
+            if message.direction() == MessageDirection::FromWidget {
+                if message.destination() == self.cb_use_gpu {
+                    self.use_gpu = *value;
+                } else if message.destination() == self.cb_denoise {
+                    self.settings.denoise = *value;
+                }
             }
         } else if let Some(&NumericUpDownMessage::Value(value)) =
             message.data::<NumericUpDownMessage<f32>>()
         {
             if message.direction() == MessageDirection::FromWidget {
                 if message.destination() == self.nud_texels_per_unit {
-                    self.texels_per_unit = value as u32;
+                    self.settings.texels_per_unit = value as u32;
                 } else if message.destination() == self.nud_spacing {
-                    self.spacing = value;
+                    self.settings.spacing = value;
                 }
             }
         }
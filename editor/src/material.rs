@@ -1,9 +1,12 @@
 use crate::{
     asset::item::AssetItem,
     gui::make_dropdown_list_option,
+    inspector::editors::make_property_editors_container,
     preview::PreviewPanel,
-    scene::commands::material::{SetMaterialPropertyValueCommand, SetMaterialShaderCommand},
-    send_sync_message, GameEngine, Message,
+    scene::commands::material::{
+        SetMaterialPropertyValueCommand, SetMaterialRenderStateCommand, SetMaterialShaderCommand,
+    },
+    send_sync_message, GameEngine, Message, MSG_SYNC_FLAG,
 };
 use fyrox::{
     core::{
@@ -23,6 +26,10 @@ use fyrox::{
         dropdown_list::{DropdownListBuilder, DropdownListMessage},
         grid::{Column, GridBuilder, Row},
         image::{Image, ImageBuilder, ImageMessage},
+        inspector::{
+            editors::PropertyEditorDefinitionContainer, Inspector, InspectorBuilder,
+            InspectorContext, InspectorMessage, PropertyAction,
+        },
         list_view::{ListViewBuilder, ListViewMessage},
         menu::{MenuItemBuilder, MenuItemContent, MenuItemMessage},
         message::{MessageDirection, UiMessage},
@@ -49,9 +56,9 @@ use fyrox::{
             MeshBuilder,
         },
     },
-    utils::into_gui_texture,
+    utils::{into_gui_texture, log::Log},
 };
-use std::sync::mpsc::Sender;
+use std::{rc::Rc, sync::mpsc::Sender};
 
 struct TextureContextMenu {
     popup: Handle<UiNode>,
@@ -86,6 +93,8 @@ pub struct MaterialEditor {
     pub window: Handle<UiNode>,
     properties_panel: Handle<UiNode>,
     properties: BiDirHashMap<ImmutableString, Handle<UiNode>>,
+    render_state_inspector: Handle<UiNode>,
+    property_editors: Rc<PropertyEditorDefinitionContainer>,
     preview: PreviewPanel,
     material: Option<SharedMaterial>,
     available_shaders: Handle<UiNode>,
@@ -234,7 +243,7 @@ fn sync_array_of_arrays<'a, T, I, B>(
 }
 
 impl MaterialEditor {
-    pub fn new(engine: &mut GameEngine) -> Self {
+    pub fn new(engine: &mut GameEngine, sender: Sender<Message>) -> Self {
         let mut preview = PreviewPanel::new(engine, 300, 400);
 
         let graph = &mut engine.scenes[preview.scene()].graph;
@@ -250,6 +259,7 @@ impl MaterialEditor {
 
         let panel;
         let properties_panel;
+        let render_state_inspector;
         let available_shaders;
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(300.0))
             .open(false)
@@ -285,11 +295,26 @@ impl MaterialEditor {
                         )
                         .with_child(
                             ScrollViewerBuilder::new(WidgetBuilder::new().on_row(1))
-                                .with_content({
-                                    properties_panel =
-                                        StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
-                                    properties_panel
-                                })
+                                .with_content(
+                                    StackPanelBuilder::new(
+                                        WidgetBuilder::new()
+                                            .with_child({
+                                                render_state_inspector = InspectorBuilder::new(
+                                                    WidgetBuilder::new()
+                                                        .with_margin(Thickness::uniform(1.0)),
+                                                )
+                                                .build(ctx);
+                                                render_state_inspector
+                                            })
+                                            .with_child({
+                                                properties_panel =
+                                                    StackPanelBuilder::new(WidgetBuilder::new())
+                                                        .build(ctx);
+                                                properties_panel
+                                            }),
+                                    )
+                                    .build(ctx),
+                                )
                                 .build(ctx),
                         )
                         .with_child({
@@ -314,6 +339,8 @@ impl MaterialEditor {
             preview,
             properties_panel,
             properties: Default::default(),
+            render_state_inspector,
+            property_editors: Rc::new(make_property_editors_container(sender)),
             material: None,
             available_shaders,
             shaders_list: Default::default(),
@@ -381,16 +408,53 @@ impl MaterialEditor {
                 .surfaces_mut()
                 .first_mut()
                 .unwrap()
-                .set_material(material);
+                .set_material(material.clone());
+
+            self.rebuild_render_state_inspector(&mut engine.user_interface, &material.lock());
         }
 
         self.sync_to_model(&mut engine.user_interface);
     }
 
+    fn rebuild_render_state_inspector(&self, ui: &mut UserInterface, material: &Material) {
+        let context = InspectorContext::from_object(
+            material.render_state(),
+            &mut ui.build_ctx(),
+            self.property_editors.clone(),
+            None,
+            MSG_SYNC_FLAG,
+            0,
+            true,
+        );
+        send_sync_message(
+            ui,
+            InspectorMessage::context(
+                self.render_state_inspector,
+                MessageDirection::ToWidget,
+                context,
+            ),
+        );
+    }
+
+    fn sync_render_state(&self, ui: &mut UserInterface, material: &Material) {
+        let ctx = ui
+            .node(self.render_state_inspector)
+            .cast::<Inspector>()
+            .unwrap()
+            .context()
+            .clone();
+
+        if ctx.sync(material.render_state(), ui, 0, true).is_err() {
+            self.rebuild_render_state_inspector(ui, material);
+        }
+    }
+
     pub fn sync_to_model(&mut self, ui: &mut UserInterface) {
         if let Some(material) = self.material.as_ref() {
             let material = material.lock();
 
+            self.sync_render_state(ui, &material);
+
             // Remove properties from ui.
             for name in self
                 .properties
@@ -660,6 +724,27 @@ impl MaterialEditor {
                         _ => (),
                     }
                 }
+            } else if let Some(InspectorMessage::PropertyChanged(args)) =
+                message.data::<InspectorMessage>()
+            {
+                if message.destination() == self.render_state_inspector
+                    && message.direction() == MessageDirection::FromWidget
+                {
+                    let mut render_state = material.lock().render_state().clone();
+                    match PropertyAction::from_field_kind(&args.value)
+                        .apply(&args.path(), &mut render_state)
+                    {
+                        Ok(_) => sender
+                            .send(Message::do_scene_command(
+                                SetMaterialRenderStateCommand::new(material.clone(), render_state),
+                            ))
+                            .unwrap(),
+                        Err(e) => Log::err(format!(
+                            "Unable to change material render state. Reason: {:?}",
+                            e
+                        )),
+                    }
+                }
             } else if let Some(PopupMessage::Placement(Placement::Cursor(target))) =
                 message.data::<PopupMessage>()
             {
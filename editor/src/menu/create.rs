@@ -32,8 +32,10 @@ use fyrox::{
         },
         pivot::PivotBuilder,
         sound::{listener::ListenerBuilder, SoundBuilder},
+        spawn_point::SpawnPointBuilder,
         sprite::SpriteBuilder,
         terrain::{LayerDefinition, TerrainBuilder},
+        text3d::Text3DBuilder,
     },
 };
 use std::sync::mpsc::Sender;
@@ -76,6 +78,7 @@ impl CreateEntityRootMenu {
 
 pub struct CreateEntityMenu {
     create_pivot: Handle<UiNode>,
+    create_spawn_point: Handle<UiNode>,
     create_cube: Handle<UiNode>,
     create_cone: Handle<UiNode>,
     create_sphere: Handle<UiNode>,
@@ -88,6 +91,7 @@ pub struct CreateEntityMenu {
     create_terrain: Handle<UiNode>,
     create_camera: Handle<UiNode>,
     create_sprite: Handle<UiNode>,
+    create_text3d: Handle<UiNode>,
     create_particle_system: Handle<UiNode>,
     create_listener: Handle<UiNode>,
     create_sound_source: Handle<UiNode>,
@@ -109,10 +113,12 @@ impl CreateEntityMenu {
         let create_directional_light;
         let create_camera;
         let create_sprite;
+        let create_text3d;
         let create_decal;
         let create_particle_system;
         let create_terrain;
         let create_pivot;
+        let create_spawn_point;
         let create_sound_source;
         let create_listener;
         let physics_menu = PhysicsMenu::new(ctx);
@@ -196,6 +202,10 @@ impl CreateEntityMenu {
                 create_sprite = create_menu_item("Sprite (3D)", vec![], ctx);
                 create_sprite
             },
+            {
+                create_text3d = create_menu_item("Text (3D)", vec![], ctx);
+                create_text3d
+            },
             {
                 create_particle_system = create_menu_item("Particle System", vec![], ctx);
                 create_particle_system
@@ -208,6 +218,10 @@ impl CreateEntityMenu {
                 create_decal = create_menu_item("Decal", vec![], ctx);
                 create_decal
             },
+            {
+                create_spawn_point = create_menu_item("Spawn Point", vec![], ctx);
+                create_spawn_point
+            },
         ];
 
         (
@@ -222,8 +236,10 @@ impl CreateEntityMenu {
                 create_directional_light,
                 create_camera,
                 create_sprite,
+                create_text3d,
                 create_particle_system,
                 create_pivot,
+                create_spawn_point,
                 create_terrain,
                 create_sound_source,
                 create_listener,
@@ -265,6 +281,11 @@ impl CreateEntityMenu {
                         )
                     } else if message.destination() == self.create_pivot {
                         Some(PivotBuilder::new(BaseBuilder::new().with_name("Pivot")).build_node())
+                    } else if message.destination() == self.create_spawn_point {
+                        Some(
+                            SpawnPointBuilder::new(BaseBuilder::new().with_name("SpawnPoint"))
+                                .build_node(),
+                        )
                     } else if message.destination() == self.create_point_light {
                         Some(
                             PointLightBuilder::new(BaseLightBuilder::new(
@@ -326,6 +347,12 @@ impl CreateEntityMenu {
                         Some(
                             SpriteBuilder::new(BaseBuilder::new().with_name("Sprite")).build_node(),
                         )
+                    } else if message.destination() == self.create_text3d {
+                        Some(
+                            Text3DBuilder::new(BaseBuilder::new().with_name("Text3D"))
+                                .with_text("Text")
+                                .build_node(),
+                        )
                     } else if message.destination() == self.create_sound_source {
                         Some(SoundBuilder::new(BaseBuilder::new().with_name("Sound")).build_node())
                     } else if message.destination() == self.create_particle_system {
@@ -6,7 +6,8 @@ use crate::{
     GameEngine, Message, Mode, Panels, SaveSceneConfirmationDialogAction,
 };
 use fyrox::{
-    core::pool::Handle,
+    core::{make_relative_path, pool::Handle},
+    engine::resource_manager::archive::ArchiveWriter,
     gui::{
         file_browser::{FileSelectorBuilder, FileSelectorMessage},
         menu::MenuItemMessage,
@@ -16,8 +17,12 @@ use fyrox::{
         window::{WindowBuilder, WindowMessage, WindowTitle},
         BuildContext, UiNode, UserInterface,
     },
+    utils::{
+        cook::{cook_scene_blocking, CookSettings},
+        log::Log,
+    },
 };
-use std::sync::mpsc::Sender;
+use std::{path::Path, sync::mpsc::Sender};
 
 pub struct FileMenu {
     pub menu: Handle<UiNode>,
@@ -25,6 +30,7 @@ pub struct FileMenu {
     pub save: Handle<UiNode>,
     pub save_as: Handle<UiNode>,
     load: Handle<UiNode>,
+    cook: Handle<UiNode>,
     pub close_scene: Handle<UiNode>,
     exit: Handle<UiNode>,
     pub open_settings: Handle<UiNode>,
@@ -56,6 +62,7 @@ impl FileMenu {
         let save_as;
         let close_scene;
         let load;
+        let cook;
         let open_settings;
         let open_scene_settings;
         let configure;
@@ -95,6 +102,10 @@ impl FileMenu {
                     load = create_menu_item_shortcut("Load Scene...", "Ctrl+L", vec![], ctx);
                     load
                 },
+                {
+                    cook = create_menu_item("Cook Scene", vec![], ctx);
+                    cook
+                },
                 {
                     close_scene = create_menu_item_shortcut("Close Scene", "Ctrl+Q", vec![], ctx);
                     close_scene
@@ -143,6 +154,7 @@ impl FileMenu {
             save_as,
             close_scene,
             load,
+            cook,
             exit,
             open_settings,
             configure,
@@ -253,6 +265,12 @@ impl FileMenu {
                 } else {
                     self.open_load_file_selector(&mut engine.user_interface);
                 }
+            } else if message.destination() == self.cook {
+                if let Some(scene_path) = editor_scene.as_ref().and_then(|s| s.path.as_ref()) {
+                    cook(scene_path, engine);
+                } else {
+                    Log::warn("Scene must be saved before cooking!");
+                }
             } else if message.destination() == self.close_scene {
                 if is_scene_needs_to_be_saved(editor_scene.as_deref()) {
                     sender
@@ -329,3 +347,41 @@ impl FileMenu {
         ));
     }
 }
+
+// Cooks the last saved version of the scene into an archive next to the scene file.
+fn cook(scene_path: &Path, engine: &GameEngine) {
+    // Archives can store only relative paths.
+    let relative_path = make_relative_path(scene_path).unwrap_or_else(|_| scene_path.to_owned());
+    let archive_path = relative_path.with_extension("pak");
+
+    let mut archive = ArchiveWriter::new();
+    match cook_scene_blocking(
+        &relative_path,
+        &mut archive,
+        &CookSettings::default(),
+        engine.serialization_context.clone(),
+    ) {
+        Ok(statistics) => match archive.save(&archive_path) {
+            Ok(_) => Log::info(format!(
+                "Scene {} was cooked into {}: {} file(s), {} editor-only node(s) were removed, \
+                {} mesh(es) were batched, {} texture(s) were compressed.",
+                relative_path.display(),
+                archive_path.display(),
+                statistics.files,
+                statistics.stripped_nodes,
+                statistics.batched_meshes,
+                statistics.compressed_textures
+            )),
+            Err(e) => Log::err(format!(
+                "Unable to save archive {}. Reason: {:?}",
+                archive_path.display(),
+                e
+            )),
+        },
+        Err(e) => Log::err(format!(
+            "Unable to cook scene {}. Reason: {}",
+            relative_path.display(),
+            e
+        )),
+    }
+}
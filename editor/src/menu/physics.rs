@@ -2,7 +2,11 @@ use crate::menu::create_menu_item;
 use fyrox::{
     core::pool::Handle,
     gui::{menu::MenuItemMessage, message::UiMessage, BuildContext, UiNode},
-    scene::{base::BaseBuilder, collider::*, joint::*, node::Node, rigidbody::RigidBodyBuilder},
+    scene::{
+        base::BaseBuilder, buoyancy::BuoyancyVolumeBuilder, collider::*,
+        force_field::ForceFieldBuilder, gravity_zone::GravityZoneBuilder, joint::*, node::Node,
+        rigidbody::RigidBodyBuilder, rope::RopeBuilder,
+    },
 };
 
 pub struct PhysicsMenu {
@@ -13,6 +17,10 @@ pub struct PhysicsMenu {
     create_prismatic_joint: Handle<UiNode>,
     create_fixed_joint: Handle<UiNode>,
     create_collider: Handle<UiNode>,
+    create_buoyancy_volume: Handle<UiNode>,
+    create_force_field: Handle<UiNode>,
+    create_gravity_zone: Handle<UiNode>,
+    create_rope: Handle<UiNode>,
 }
 
 impl PhysicsMenu {
@@ -23,6 +31,10 @@ impl PhysicsMenu {
         let create_ball_joint;
         let create_prismatic_joint;
         let create_fixed_joint;
+        let create_buoyancy_volume;
+        let create_force_field;
+        let create_gravity_zone;
+        let create_rope;
         let menu = create_menu_item(
             "Physics",
             vec![
@@ -50,6 +62,22 @@ impl PhysicsMenu {
                     create_fixed_joint = create_menu_item("Fixed Joint", vec![], ctx);
                     create_fixed_joint
                 },
+                {
+                    create_buoyancy_volume = create_menu_item("Buoyancy Volume", vec![], ctx);
+                    create_buoyancy_volume
+                },
+                {
+                    create_force_field = create_menu_item("Force Field", vec![], ctx);
+                    create_force_field
+                },
+                {
+                    create_gravity_zone = create_menu_item("Gravity Zone", vec![], ctx);
+                    create_gravity_zone
+                },
+                {
+                    create_rope = create_menu_item("Rope", vec![], ctx);
+                    create_rope
+                },
             ],
             ctx,
         );
@@ -62,6 +90,10 @@ impl PhysicsMenu {
             create_prismatic_joint,
             create_fixed_joint,
             create_collider,
+            create_buoyancy_volume,
+            create_force_field,
+            create_gravity_zone,
+            create_rope,
         }
     }
 
@@ -99,6 +131,23 @@ impl PhysicsMenu {
                         .with_shape(ColliderShape::Cuboid(Default::default()))
                         .build_node(),
                 )
+            } else if message.destination == self.create_buoyancy_volume {
+                Some(
+                    BuoyancyVolumeBuilder::new(BaseBuilder::new().with_name("Buoyancy Volume"))
+                        .build_node(),
+                )
+            } else if message.destination == self.create_force_field {
+                Some(
+                    ForceFieldBuilder::new(BaseBuilder::new().with_name("Force Field"))
+                        .build_node(),
+                )
+            } else if message.destination == self.create_gravity_zone {
+                Some(
+                    GravityZoneBuilder::new(BaseBuilder::new().with_name("Gravity Zone"))
+                        .build_node(),
+                )
+            } else if message.destination == self.create_rope {
+                Some(RopeBuilder::new(BaseBuilder::new().with_name("Rope")).build_node())
             } else {
                 None
             }
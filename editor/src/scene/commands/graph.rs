@@ -9,6 +9,7 @@ use fyrox::{
         graph::{Graph, SubGraph},
         node::Node,
     },
+    script::Script,
 };
 
 #[derive(Debug)]
@@ -180,6 +181,39 @@ impl Command for LinkNodesCommand {
     }
 }
 
+#[derive(Debug)]
+pub struct SetScriptCommand {
+    handle: Handle<Node>,
+    script: Option<Script>,
+}
+
+impl SetScriptCommand {
+    pub fn new(handle: Handle<Node>, script: Option<Script>) -> Self {
+        Self { handle, script }
+    }
+
+    fn swap(&mut self, context: &mut SceneContext) {
+        std::mem::swap(
+            context.scene.graph[self.handle].script_inner(),
+            &mut self.script,
+        );
+    }
+}
+
+impl Command for SetScriptCommand {
+    fn name(&mut self, _context: &SceneContext) -> String {
+        "Set Script".to_owned()
+    }
+
+    fn execute(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+
+    fn revert(&mut self, context: &mut SceneContext) {
+        self.swap(context);
+    }
+}
+
 #[derive(Debug)]
 pub struct DeleteNodeCommand {
     handle: Handle<Node>,
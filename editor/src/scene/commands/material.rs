@@ -1,7 +1,7 @@
 use crate::{command::Command, scene::commands::SceneContext};
 use fyrox::{
     core::sstorage::ImmutableString,
-    material::{shader::Shader, Material, PropertyValue, SharedMaterial},
+    material::{shader::Shader, Material, MaterialRenderState, PropertyValue, SharedMaterial},
 };
 
 #[derive(Debug)]
@@ -45,6 +45,43 @@ impl Command for SetMaterialPropertyValueCommand {
     }
 }
 
+#[derive(Debug)]
+pub struct SetMaterialRenderStateCommand {
+    material: SharedMaterial,
+    render_state: MaterialRenderState,
+}
+
+impl SetMaterialRenderStateCommand {
+    pub fn new(material: SharedMaterial, render_state: MaterialRenderState) -> Self {
+        Self {
+            material,
+            render_state,
+        }
+    }
+
+    fn swap(&mut self) {
+        let old_render_state = self
+            .material
+            .lock()
+            .set_render_state(self.render_state.clone());
+        self.render_state = old_render_state;
+    }
+}
+
+impl Command for SetMaterialRenderStateCommand {
+    fn name(&mut self, _: &SceneContext) -> String {
+        "Set Material Render State".to_owned()
+    }
+
+    fn execute(&mut self, _: &mut SceneContext) {
+        self.swap();
+    }
+
+    fn revert(&mut self, _: &mut SceneContext) {
+        self.swap();
+    }
+}
+
 #[derive(Debug)]
 enum SetMaterialShaderCommandState {
     Undefined,
@@ -25,7 +25,9 @@ use fyrox::{
         base::BaseBuilder,
         camera::Camera,
         debug::{Line, SceneDrawingContext},
-        graph::{Graph, GraphUpdateSwitches},
+        force_field::{ForceField, ForceFieldKind},
+        graph::{map::NodeHandleMap, Graph, GraphUpdateSwitches},
+        gravity_zone::{GravityZone, GravityZoneShape},
         light::{point::PointLight, spot::SpotLight},
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
@@ -33,6 +35,7 @@ use fyrox::{
         },
         node::Node,
         pivot::PivotBuilder,
+        spawn_point::SpawnPoint,
         Scene,
     },
 };
@@ -58,6 +61,8 @@ pub struct EditorScene {
     pub navmeshes: NavmeshContainer,
     pub preview_camera: Handle<Node>,
     pub graph_switches: GraphUpdateSwitches,
+    // Maps handles of nodes in the last saved scene to handles of nodes in the editor's scene.
+    pub saved_node_map: HashMap<Handle<Node>, Handle<Node>>,
 }
 
 pub fn is_scene_needs_to_be_saved(editor_scene: Option<&EditorScene>) -> bool {
@@ -128,14 +133,15 @@ impl EditorScene {
                 // Update only editor's camera.
                 node_overrides: Some(Default::default()),
             },
+            saved_node_map: Default::default(),
         }
     }
 
-    pub fn make_purified_scene(&self, engine: &mut GameEngine) -> Scene {
+    pub fn make_purified_scene(&self, engine: &mut GameEngine) -> (Scene, NodeHandleMap) {
         let scene = &mut engine.scenes[self.scene];
 
         let editor_root = self.editor_objects_root;
-        let (mut pure_scene, _) = scene.clone(&mut |node, _| node != editor_root);
+        let (mut pure_scene, old_new_map) = scene.clone(&mut |node, _| node != editor_root);
 
         pure_scene.navmeshes.clear();
 
@@ -170,7 +176,7 @@ impl EditorScene {
                 .add(fyrox::utils::navmesh::Navmesh::new(&triangles, &vertices));
         }
 
-        pure_scene
+        (pure_scene, old_new_map)
     }
 
     pub fn save(&mut self, path: PathBuf, engine: &mut GameEngine) -> Result<String, String> {
@@ -181,7 +187,13 @@ impl EditorScene {
         if valid {
             self.path = Some(path.clone());
 
-            let mut pure_scene = self.make_purified_scene(engine);
+            let (mut pure_scene, old_new_map) = self.make_purified_scene(engine);
+
+            self.saved_node_map = old_new_map
+                .into_inner()
+                .into_iter()
+                .map(|(old, new)| (new, old))
+                .collect();
 
             let mut visitor = Visitor::new();
             pure_scene.save("Scene", &mut visitor).unwrap();
@@ -342,6 +354,67 @@ impl EditorScene {
                     Color::GREEN,
                     false,
                 );
+            } else if let Some(field) = node.query_component_ref::<ForceField>() {
+                let position = field.global_position();
+                ctx.draw_wire_sphere(position, field.radius(), 30, Color::opaque(0, 162, 232));
+                let direction = match field.kind() {
+                    ForceFieldKind::Directional => field.look_vector(),
+                    ForceFieldKind::Radial | ForceFieldKind::Vortex => field.up_vector(),
+                }
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_default()
+                .scale(field.radius());
+                ctx.add_line(Line {
+                    begin: position,
+                    end: position + direction,
+                    color: Color::opaque(0, 162, 232),
+                });
+            } else if let Some(zone) = node.query_component_ref::<GravityZone>() {
+                let color = Color::opaque(163, 73, 164);
+                match zone.shape() {
+                    GravityZoneShape::Sphere { radius } => {
+                        ctx.draw_wire_sphere(zone.global_position(), *radius, 30, color)
+                    }
+                    GravityZoneShape::Cuboid { half_extents } => ctx.draw_oob(
+                        &AxisAlignedBoundingBox::from_min_max(-*half_extents, *half_extents),
+                        zone.global_transform(),
+                        color,
+                    ),
+                }
+            } else if let Some(spawn_point) = node.query_component_ref::<SpawnPoint>() {
+                let color = Color::opaque(255, 127, 39);
+                let position = spawn_point.global_position();
+                let up = spawn_point
+                    .up_vector()
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                let look = spawn_point
+                    .look_vector()
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                let side = spawn_point
+                    .side_vector()
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default();
+                // Height of an average actor and an orientation arrow at its feet.
+                ctx.add_line(Line {
+                    begin: position,
+                    end: position + up.scale(1.8),
+                    color,
+                });
+                ctx.draw_wire_sphere(position, 0.3, 16, color);
+                let tip = position + look;
+                for end in [
+                    position,
+                    tip - look.scale(0.25) + side.scale(0.15),
+                    tip - look.scale(0.25) - side.scale(0.15),
+                ] {
+                    ctx.add_line(Line {
+                        begin: tip,
+                        end,
+                        color,
+                    });
+                }
             }
 
             for &child in node.children() {
@@ -4,49 +4,77 @@ use crate::{
     MessageDirection, MSG_SYNC_FLAG,
 };
 use fyrox::{
-    core::pool::Handle,
+    core::{pool::Handle, uuid::Uuid},
     engine::Engine,
     gui::{
         inspector::{
             editors::{
                 inspectable::InspectablePropertyEditorDefinition, PropertyEditorDefinitionContainer,
             },
-            InspectorBuilder, InspectorContext, InspectorMessage,
+            InspectorBuilder, InspectorContext, InspectorMessage, PropertyAction,
         },
         message::UiMessage,
         scroll_viewer::ScrollViewerBuilder,
-        widget::WidgetBuilder,
+        stack_panel::StackPanelBuilder,
+        text::TextBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
         window::{WindowBuilder, WindowMessage, WindowTitle},
-        BuildContext, UiNode, UserInterface,
+        BuildContext, Thickness, UiNode, UserInterface,
     },
+    plugin::settings::PluginSettings,
     scene::{
         dim2,
         graph::{
-            physics::{IntegrationParameters, PhysicsWorld},
+            physics::{ContactEventFilter, IntegrationParameters, PhysicsSettings, PhysicsWorld},
             Graph,
         },
+        Scene,
     },
+    utils::log::Log,
 };
 use std::{rc::Rc, sync::mpsc::Sender};
 
 mod command;
 
+struct PluginSettingsEntry {
+    id: Uuid,
+    settings: Box<dyn PluginSettings>,
+    root: Handle<UiNode>,
+    inspector: Handle<UiNode>,
+}
+
 pub struct SceneSettingsWindow {
     pub window: Handle<UiNode>,
     inspector: Handle<UiNode>,
     property_definitions: Rc<PropertyEditorDefinitionContainer>,
+    plugins_panel: Handle<UiNode>,
+    plugin_settings: Vec<PluginSettingsEntry>,
+    plugin_settings_scene: Handle<Scene>,
 }
 
 impl SceneSettingsWindow {
     pub fn new(ctx: &mut BuildContext, sender: Sender<Message>) -> Self {
         let inspector;
+        let plugins_panel;
         let window = WindowBuilder::new(WidgetBuilder::new().with_width(400.0).with_height(500.0))
             .with_content(
                 ScrollViewerBuilder::new(WidgetBuilder::new())
-                    .with_content({
-                        inspector = InspectorBuilder::new(WidgetBuilder::new()).build(ctx);
-                        inspector
-                    })
+                    .with_content(
+                        StackPanelBuilder::new(
+                            WidgetBuilder::new()
+                                .with_child({
+                                    inspector =
+                                        InspectorBuilder::new(WidgetBuilder::new()).build(ctx);
+                                    inspector
+                                })
+                                .with_child({
+                                    plugins_panel =
+                                        StackPanelBuilder::new(WidgetBuilder::new()).build(ctx);
+                                    plugins_panel
+                                }),
+                        )
+                        .build(ctx),
+                    )
                     .build(ctx),
             )
             .open(false)
@@ -58,6 +86,8 @@ impl SceneSettingsWindow {
 
         container.insert(InspectablePropertyEditorDefinition::<Graph>::new());
         container.insert(InspectablePropertyEditorDefinition::<IntegrationParameters>::new());
+        container.insert(InspectablePropertyEditorDefinition::<ContactEventFilter>::new());
+        container.insert(InspectablePropertyEditorDefinition::<PhysicsSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<PhysicsWorld>::new());
         container.insert(InspectablePropertyEditorDefinition::<
             dim2::physics::PhysicsWorld,
@@ -67,6 +97,9 @@ impl SceneSettingsWindow {
             window,
             inspector,
             property_definitions: Rc::new(container),
+            plugins_panel,
+            plugin_settings: Default::default(),
+            plugin_settings_scene: Default::default(),
         }
     }
 
@@ -78,7 +111,7 @@ impl SceneSettingsWindow {
         ));
     }
 
-    pub fn sync_to_model(&self, editor_scene: &EditorScene, engine: &mut Engine) {
+    pub fn sync_to_model(&mut self, editor_scene: &EditorScene, engine: &mut Engine) {
         let ui = &mut engine.user_interface;
         let scene = &engine.scenes[editor_scene.scene];
 
@@ -97,14 +130,106 @@ impl SceneSettingsWindow {
             MessageDirection::ToWidget,
             context,
         ));
+
+        // Plugin settings are not affected by scene commands, so there is no need to re-create
+        // their inspectors on every sync.
+        if self.plugin_settings_scene != editor_scene.scene {
+            self.plugin_settings_scene = editor_scene.scene;
+            self.rebuild_plugin_settings(editor_scene, engine);
+        }
+    }
+
+    fn rebuild_plugin_settings(&mut self, editor_scene: &EditorScene, engine: &mut Engine) {
+        for entry in self.plugin_settings.drain(..) {
+            engine.user_interface.send_message(WidgetMessage::remove(
+                entry.root,
+                MessageDirection::ToWidget,
+            ));
+        }
+
+        let settings = engine
+            .plugin_constructors()
+            .iter()
+            .filter_map(|constructor| constructor.create_settings())
+            .collect::<Vec<_>>();
+
+        let scene = &engine.scenes[editor_scene.scene];
+        let ctx = &mut engine.user_interface.build_ctx();
+
+        for (id, mut settings) in settings {
+            {
+                if let Err(e) = scene.plugin_settings.restore(id, &mut *settings) {
+                    Log::err(format!(
+                        "Unable to load settings of plugin {}. Reason: {:?}",
+                        id, e
+                    ));
+                }
+
+                let context = InspectorContext::from_object(
+                    settings.as_reflect(),
+                    ctx,
+                    self.property_definitions.clone(),
+                    None,
+                    MSG_SYNC_FLAG,
+                    0,
+                    false,
+                );
+
+                let inspector = InspectorBuilder::new(WidgetBuilder::new())
+                    .with_context(context)
+                    .build(ctx);
+                let root = StackPanelBuilder::new(
+                    WidgetBuilder::new()
+                        .with_child(
+                            TextBuilder::new(
+                                WidgetBuilder::new().with_margin(Thickness::uniform(2.0)),
+                            )
+                            .with_text(format!(
+                                "Plugin Settings - {}",
+                                settings.as_reflect().type_name()
+                            ))
+                            .build(ctx),
+                        )
+                        .with_child(inspector),
+                )
+                .build(ctx);
+
+                ctx.link(root, self.plugins_panel);
+
+                self.plugin_settings.push(PluginSettingsEntry {
+                    id,
+                    settings,
+                    root,
+                    inspector,
+                });
+            }
+        }
     }
 
-    pub fn handle_ui_message(&self, message: &UiMessage, sender: &Sender<Message>) {
+    pub fn handle_ui_message(
+        &mut self,
+        message: &UiMessage,
+        sender: &Sender<Message>,
+        editor_scene: &EditorScene,
+        engine: &mut Engine,
+    ) {
         if let Some(InspectorMessage::PropertyChanged(property_changed)) = message.data() {
             if message.destination() == self.inspector {
                 if let Some(command) = make_set_scene_property_command((), property_changed) {
                     sender.send(Message::DoSceneCommand(command)).unwrap();
                 }
+            } else if let Some(entry) = self
+                .plugin_settings
+                .iter_mut()
+                .find(|entry| entry.inspector == message.destination())
+            {
+                Log::verify(
+                    PropertyAction::from_field_kind(&property_changed.value)
+                        .apply(&property_changed.path(), entry.settings.as_reflect_mut()),
+                );
+
+                let scene = &mut engine.scenes[editor_scene.scene];
+                Log::verify(scene.plugin_settings.store(entry.id, &mut *entry.settings));
             }
         }
     }
@@ -1,10 +1,10 @@
 use crate::{
     camera::PickingOptions, gui::make_dropdown_list_option,
-    gui::make_dropdown_list_option_with_height, load_image, settings::keys::KeyBindings,
-    utils::enable_widget, AddModelCommand, AssetItem, AssetKind, BuildProfile,
-    ChangeSelectionCommand, CommandGroup, DropdownListBuilder, EditorScene, GameEngine,
-    GraphSelection, InteractionMode, InteractionModeKind, Message, Mode, SceneCommand, Selection,
-    SetMeshTextureCommand, Settings,
+    gui::make_dropdown_list_option_with_height, interaction::script_gizmo::ScriptGizmos,
+    load_image, settings::keys::KeyBindings, utils::enable_widget, AddModelCommand, AssetItem,
+    AssetKind, BuildProfile, ChangeSelectionCommand, CommandGroup, DropdownListBuilder,
+    EditorScene, GameEngine, GraphSelection, InteractionMode, InteractionModeKind, Message, Mode,
+    SceneCommand, Selection, SetMeshTextureCommand, Settings,
 };
 use fyrox::{
     core::{
@@ -70,6 +70,7 @@ pub struct SceneViewer {
     contextual_actions: Handle<UiNode>,
     global_position_display: Handle<UiNode>,
     preview_instance: Option<PreviewInstance>,
+    pub script_gizmos: ScriptGizmos,
 }
 
 fn make_interaction_mode_button(
@@ -397,6 +398,7 @@ impl SceneViewer {
             global_position_display,
             build_profile,
             preview_instance: None,
+            script_gizmos: Default::default(),
         }
     }
 }
@@ -801,7 +803,10 @@ impl SceneViewer {
             .on_mouse_move(mouse_offset, &settings.camera);
         let rel_pos = pos - screen_bounds.position;
 
-        if let Some(interaction_mode) = active_interaction_mode {
+        if self.script_gizmos.is_dragging() {
+            self.script_gizmos
+                .drag(editor_scene, engine, rel_pos, screen_bounds.size);
+        } else if let Some(interaction_mode) = active_interaction_mode {
             interaction_mode.on_mouse_move(
                 mouse_offset,
                 rel_pos,
@@ -831,7 +836,10 @@ impl SceneViewer {
 
         if button == MouseButton::Left {
             self.click_mouse_pos = None;
-            if let Some(current_im) = active_interaction_mode {
+            if self.script_gizmos.is_dragging() {
+                self.script_gizmos
+                    .end_drag(editor_scene, engine, &self.sender);
+            } else if let Some(current_im) = active_interaction_mode {
                 let rel_pos = pos - screen_bounds.position;
                 current_im.on_left_mouse_button_up(
                     editor_scene,
@@ -860,9 +868,13 @@ impl SceneViewer {
         let screen_bounds = self.frame_bounds(&engine.user_interface);
 
         if button == MouseButton::Left {
-            if let Some(current_im) = active_interaction_mode {
-                let rel_pos = pos - screen_bounds.position;
-
+            let rel_pos = pos - screen_bounds.position;
+            if self
+                .script_gizmos
+                .try_begin_drag(editor_scene, engine, rel_pos, screen_bounds.size)
+            {
+                // Script gizmo has priority over interaction modes.
+            } else if let Some(current_im) = active_interaction_mode {
                 self.click_mouse_pos = Some(rel_pos);
 
                 current_im.on_left_mouse_button_down(
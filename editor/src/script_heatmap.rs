@@ -0,0 +1,107 @@
+//! Script heatmap shows how much time scripts of every node take in a running game (play mode). Costs
+//! are reported by the game process to its stdout (see [`fyrox::script::profiler::ScriptCostProfiler`])
+//! and shown in the world viewer and in the scene viewer.
+
+use crate::{scene::EditorScene, world::WorldViewer, GameEngine};
+use fyrox::{
+    core::{color::Color, pool::Handle},
+    fxhash::FxHashMap,
+    scene::node::Node,
+    script::profiler::parse_script_cost_report,
+};
+use std::{
+    sync::mpsc::{channel, Receiver, Sender},
+    time::Duration,
+};
+
+/// Maps relative cost of a node (in `[0; 1]` range) to a color - from yellow for cheap scripts to red
+/// for the most expensive ones.
+pub fn heat_color(heat: f32) -> Color {
+    let heat = heat.clamp(0.0, 1.0);
+    Color::opaque(
+        255,
+        (220.0 * (1.0 - heat)) as u8,
+        (40.0 * (1.0 - heat)) as u8,
+    )
+}
+
+pub struct ScriptHeatmap {
+    sender: Sender<String>,
+    receiver: Receiver<String>,
+    // Costs of the nodes of the editor's scene over the last second.
+    costs: FxHashMap<Handle<Node>, Duration>,
+}
+
+impl Default for ScriptHeatmap {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            sender,
+            receiver,
+            costs: Default::default(),
+        }
+    }
+}
+
+impl ScriptHeatmap {
+    /// Returns a sender, that should be used to pass script cost reports from the game process.
+    pub fn sender(&self) -> Sender<String> {
+        self.sender.clone()
+    }
+
+    fn max_cost(&self) -> Duration {
+        self.costs.values().max().cloned().unwrap_or_default()
+    }
+
+    pub fn update(
+        &mut self,
+        editor_scene: &EditorScene,
+        engine: &mut GameEngine,
+        world_viewer: &mut WorldViewer,
+    ) {
+        // Only the most recent report matters.
+        if let Some(report) = self
+            .receiver
+            .try_iter()
+            .filter_map(|line| parse_script_cost_report(&line))
+            .last()
+        {
+            // Handles in the game are the handles of the saved scene, map them back to the editor's
+            // scene.
+            self.costs = report
+                .into_iter()
+                .filter_map(|(handle, cost)| {
+                    editor_scene
+                        .saved_node_map
+                        .get(&handle)
+                        .map(|editor_handle| (*editor_handle, cost))
+                })
+                .collect();
+
+            world_viewer.set_script_costs(&self.costs, self.max_cost(), &engine.user_interface);
+        }
+
+        let max_cost = self.max_cost();
+        if max_cost.is_zero() {
+            return;
+        }
+
+        let scene = &mut engine.scenes[editor_scene.scene];
+        for (&handle, cost) in self.costs.iter() {
+            if let Some(node) = scene.graph.try_get(handle) {
+                let heat = cost.as_secs_f32() / max_cost.as_secs_f32();
+                scene
+                    .drawing_context
+                    .draw_aabb(&node.world_bounding_box(), heat_color(heat));
+            }
+        }
+    }
+
+    pub fn clear(&mut self, world_viewer: &mut WorldViewer, engine: &GameEngine) {
+        // Discard reports, that were sent before the game was closed.
+        for _ in self.receiver.try_iter() {}
+
+        self.costs.clear();
+        world_viewer.set_script_costs(&self.costs, Default::default(), &engine.user_interface);
+    }
+}
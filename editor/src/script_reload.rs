@@ -0,0 +1,119 @@
+//! Script reloading in play mode. When a source file of the game is modified while the game is running,
+//! the editor asks the game to save its state and to exit, then it rebuilds the game and runs it again
+//! from the saved state. The edited scene is not affected.
+
+use fyrox::{
+    engine::executor::SAVE_STATE_COMMAND,
+    utils::{log::Log, watcher::FileSystemWatcher},
+};
+use std::{
+    io::Write,
+    path::{Component, Path, PathBuf},
+    process::ChildStdin,
+    time::Duration,
+};
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "rs")
+        && !path
+            .components()
+            .any(|c| c == Component::Normal("target".as_ref()))
+}
+
+pub struct ScriptReloader {
+    watcher: Option<FileSystemWatcher>,
+    stdin: Option<ChildStdin>,
+    reload_requested: bool,
+    // `true` if the next launch of the game should use the saved state.
+    snapshot_pending: bool,
+    snapshot_path: PathBuf,
+}
+
+impl Default for ScriptReloader {
+    fn default() -> Self {
+        Self {
+            watcher: None,
+            stdin: None,
+            reload_requested: false,
+            snapshot_pending: false,
+            snapshot_path: std::env::temp_dir().join("fyrox_play_mode_state.rgs"),
+        }
+    }
+}
+
+impl ScriptReloader {
+    /// Path to the file, where the game saves its state before reloading.
+    pub fn snapshot_path(&self) -> &Path {
+        &self.snapshot_path
+    }
+
+    /// Returns path to the saved state of the game, if the game should be started from it.
+    pub fn take_snapshot(&mut self) -> Option<PathBuf> {
+        if std::mem::take(&mut self.snapshot_pending) {
+            Some(self.snapshot_path.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Starts watching source files of the game, that was just started.
+    pub fn on_game_started(&mut self, stdin: Option<ChildStdin>) {
+        self.stdin = stdin;
+        self.reload_requested = false;
+
+        if self.watcher.is_none() {
+            match FileSystemWatcher::new(".", Duration::from_secs(1)) {
+                Ok(watcher) => self.watcher = Some(watcher),
+                Err(e) => Log::err(format!(
+                    "Unable to watch source files of the game, scripts won't be reloaded. Reason: {:?}",
+                    e
+                )),
+            }
+        }
+    }
+
+    /// Checks whether some source file was modified and asks the game to save its state and exit.
+    pub fn update(&mut self) {
+        let mut modified = false;
+        if let Some(watcher) = self.watcher.as_ref() {
+            while let Some(event) = watcher.try_get_event() {
+                modified |= event.paths.iter().any(|path| is_source_file(path));
+            }
+        }
+
+        if !modified || self.reload_requested {
+            return;
+        }
+
+        if let Some(stdin) = self.stdin.as_mut() {
+            // Previous state must not be loaded if the game fails to save a new one.
+            let _ = std::fs::remove_file(&self.snapshot_path);
+
+            match writeln!(stdin, "{}", SAVE_STATE_COMMAND) {
+                Ok(_) => {
+                    Log::info("Source files of the game were modified, reloading scripts...");
+                    self.reload_requested = true;
+                }
+                Err(e) => Log::err(format!("Unable to reload scripts. Reason: {:?}", e)),
+            }
+        }
+    }
+
+    /// Must be called when the game process is closed. Returns `true` if the game was closed because
+    /// of reloading and the saved state is available, so the game should be rebuilt and started again.
+    pub fn on_game_closed(&mut self) -> bool {
+        self.stdin = None;
+
+        self.snapshot_pending =
+            std::mem::take(&mut self.reload_requested) && self.snapshot_path.exists();
+        self.snapshot_pending
+    }
+
+    /// Stops reloading, should be called when the editor leaves play mode.
+    pub fn reset(&mut self) {
+        self.watcher = None;
+        self.stdin = None;
+        self.reload_requested = false;
+        self.snapshot_pending = false;
+    }
+}
@@ -29,7 +29,7 @@ use fyrox::{
         window::{WindowBuilder, WindowMessage, WindowTitle},
         HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface,
     },
-    renderer::{CsmSettings, QualitySettings, ShadowMapPrecision},
+    renderer::{CsmSettings, QualitySettings, ShadowMapPrecision, SsrSettings, UpscalingFilter},
     utils::log::Log,
 };
 use ron::ser::PrettyConfig;
@@ -120,8 +120,10 @@ impl Settings {
         container.insert(InspectablePropertyEditorDefinition::<GraphicsSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<SelectionSettings>::new());
         container.insert(EnumPropertyEditorDefinition::<ShadowMapPrecision>::new());
+        container.insert(EnumPropertyEditorDefinition::<UpscalingFilter>::new());
         container.insert(InspectablePropertyEditorDefinition::<DebuggingSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<CsmSettings>::new());
+        container.insert(InspectablePropertyEditorDefinition::<SsrSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<QualitySettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<CameraSettings>::new());
         container.insert(InspectablePropertyEditorDefinition::<
@@ -272,17 +274,16 @@ impl SettingsWindow {
 
         // Apply only if anything changed.
         if settings != &old_settings {
-            if settings.graphics.quality != engine.renderer.get_quality_settings() {
-                if let Err(e) = engine
-                    .renderer
-                    .set_quality_settings(&settings.graphics.quality)
-                {
-                    Log::err(format!(
-                        "An error occurred at attempt to set new graphics settings: {:?}",
-                        e
-                    ));
-                } else {
-                    Log::info("New graphics quality settings were successfully set!");
+            if let Some(renderer) = engine.renderer.as_mut() {
+                if settings.graphics.quality != renderer.get_quality_settings() {
+                    if let Err(e) = renderer.set_quality_settings(&settings.graphics.quality) {
+                        Log::err(format!(
+                            "An error occurred at attempt to set new graphics settings: {:?}",
+                            e
+                        ));
+                    } else {
+                        Log::info("New graphics quality settings were successfully set!");
+                    }
                 }
             }
 
@@ -1,5 +1,5 @@
 use fyrox::{
-    core::{algebra::Vector2, pool::ErasedHandle, pool::Handle},
+    core::{pool::ErasedHandle, pool::Handle},
     gui::{
         file_browser::{FileBrowserMode, FileSelectorBuilder, Filter},
         message::MessageDirection,
@@ -68,24 +68,6 @@ pub fn create_file_selector(
     .build(ctx)
 }
 
-pub fn fetch_node_center(handle: Handle<UiNode>, ctx: &BuildContext) -> Vector2<f32> {
-    ctx.try_get_node(handle)
-        .map(|node| node.center())
-        .unwrap_or_default()
-}
-
-pub fn fetch_node_screen_center(handle: Handle<UiNode>, ctx: &BuildContext) -> Vector2<f32> {
-    ctx.try_get_node(handle)
-        .map(|node| node.screen_bounds().center())
-        .unwrap_or_default()
-}
-
-pub fn fetch_node_screen_center_ui(handle: Handle<UiNode>, ui: &UserInterface) -> Vector2<f32> {
-    ui.try_get_node(handle)
-        .map(|node| node.screen_bounds().center())
-        .unwrap_or_default()
-}
-
 fn load_texture(data: &[u8]) -> Texture {
     Texture::load_from_memory(data, CompressionOptions::NoCompression, false)
         .ok()
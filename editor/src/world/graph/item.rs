@@ -1,7 +1,7 @@
 use crate::load_image;
 use crate::utils::make_node_name;
 use fyrox::{
-    core::{algebra::Vector2, pool::Handle},
+    core::{algebra::Vector2, color::Color, pool::Handle},
     gui::{
         brush::Brush,
         define_constructor,
@@ -24,15 +24,19 @@ use std::{
     sync::mpsc::Sender,
 };
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SceneItemMessage {
     Name(String),
     Validate(Result<(), String>),
+    // Script cost of the entity (in milliseconds per second) and the color of the item. `None` resets
+    // the item to its normal state.
+    ScriptCost(Option<(f32, Color)>),
 }
 
 impl SceneItemMessage {
     define_constructor!(SceneItemMessage:Name => fn name(String), layout: false);
     define_constructor!(SceneItemMessage:Validate => fn validate(Result<(), String>), layout: false);
+    define_constructor!(SceneItemMessage:ScriptCost => fn script_cost(Option<(f32, Color)>), layout: false);
 }
 
 pub struct SceneItem<T> {
@@ -43,6 +47,9 @@ pub struct SceneItem<T> {
     pub entity_handle: Handle<T>,
     // Can be unassigned if there's no warning.
     pub warning_icon: Handle<UiNode>,
+    text_brush: Brush,
+    // Can be unassigned if script cost is not shown.
+    script_cost_text: Handle<UiNode>,
 }
 
 impl<T> SceneItem<T> {
@@ -60,6 +67,8 @@ impl<T> Clone for SceneItem<T> {
             grid: self.grid,
             entity_handle: self.entity_handle,
             warning_icon: self.warning_icon,
+            text_brush: self.text_brush.clone(),
+            script_cost_text: self.script_cost_text,
         }
     }
 }
@@ -98,6 +107,7 @@ impl<T: 'static> Control for SceneItem<T> {
     fn resolve(&mut self, node_map: &NodeHandleMapping) {
         self.tree.resolve(node_map);
         node_map.resolve(&mut self.text_name);
+        node_map.resolve(&mut self.script_cost_text);
     }
 
     fn measure_override(&self, ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
@@ -162,6 +172,60 @@ impl<T: 'static> Control for SceneItem<T> {
                     }
                 }
             }
+        } else if let Some(SceneItemMessage::ScriptCost(cost)) = message.data() {
+            if message.destination() == self.handle() {
+                match cost {
+                    Some((cost, color)) => {
+                        let brush = Brush::Solid(*color);
+
+                        if self.script_cost_text.is_none() {
+                            self.script_cost_text = TextBuilder::new(
+                                WidgetBuilder::new()
+                                    .with_margin(Thickness::uniform(1.0))
+                                    .on_row(0)
+                                    .on_column(3)
+                                    .with_vertical_alignment(VerticalAlignment::Center),
+                            )
+                            .build(&mut ui.build_ctx());
+
+                            ui.send_message(WidgetMessage::link(
+                                self.script_cost_text,
+                                MessageDirection::ToWidget,
+                                self.grid,
+                            ));
+                        }
+
+                        ui.send_message(TextMessage::text(
+                            self.script_cost_text,
+                            MessageDirection::ToWidget,
+                            format!("{:.2} ms", cost),
+                        ));
+                        ui.send_message(WidgetMessage::foreground(
+                            self.script_cost_text,
+                            MessageDirection::ToWidget,
+                            brush.clone(),
+                        ));
+                        ui.send_message(WidgetMessage::foreground(
+                            self.text_name,
+                            MessageDirection::ToWidget,
+                            brush,
+                        ));
+                    }
+                    None => {
+                        ui.send_message(WidgetMessage::remove(
+                            self.script_cost_text,
+                            MessageDirection::ToWidget,
+                        ));
+                        self.script_cost_text = Handle::NONE;
+
+                        ui.send_message(WidgetMessage::foreground(
+                            self.text_name,
+                            MessageDirection::ToWidget,
+                            self.text_brush.clone(),
+                        ));
+                    }
+                }
+            }
         }
     }
 
@@ -219,6 +283,10 @@ impl<T: 'static> SceneItemBuilder<T> {
     }
 
     pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
+        let text_brush = self
+            .text_brush
+            .unwrap_or(Brush::Solid(fyrox::gui::COLOR_FOREGROUND));
+
         let text_name;
         let content = GridBuilder::new(
             WidgetBuilder::new()
@@ -236,10 +304,7 @@ impl<T: 'static> SceneItemBuilder<T> {
                 .with_child({
                     text_name = TextBuilder::new(
                         WidgetBuilder::new()
-                            .with_foreground(
-                                self.text_brush
-                                    .unwrap_or(Brush::Solid(fyrox::gui::COLOR_FOREGROUND)),
-                            )
+                            .with_foreground(text_brush.clone())
                             .with_margin(Thickness::uniform(1.0))
                             .on_column(1)
                             .with_vertical_alignment(VerticalAlignment::Center),
@@ -258,6 +323,7 @@ impl<T: 'static> SceneItemBuilder<T> {
         .add_column(Column::auto())
         .add_column(Column::stretch())
         .add_column(Column::auto())
+        .add_column(Column::auto())
         .build(ctx);
 
         let tree = self.tree_builder.with_content(content).build_tree(ctx);
@@ -269,6 +335,8 @@ impl<T: 'static> SceneItemBuilder<T> {
             text_name,
             grid: content,
             warning_icon: Default::default(),
+            text_brush,
+            script_cost_text: Default::default(),
         };
 
         ctx.add_node(UiNode::new(item))
@@ -5,6 +5,7 @@ use crate::{
         commands::{graph::LinkNodesCommand, ChangeSelectionCommand, CommandGroup, SceneCommand},
         EditorScene, Selection,
     },
+    script_heatmap::heat_color,
     send_sync_message,
     utils::window_content,
     world::{
@@ -24,6 +25,7 @@ use fyrox::{
         scope_profile,
     },
     engine::Engine,
+    fxhash::FxHashMap,
     gui::{
         border::BorderBuilder,
         brush::Brush,
@@ -49,7 +51,7 @@ use fyrox::{
     scene::{graph::Graph, node::Node, Scene},
     utils::log::Log,
 };
-use std::{any::TypeId, cmp::Ordering, collections::HashMap, sync::mpsc::Sender};
+use std::{any::TypeId, cmp::Ordering, collections::HashMap, sync::mpsc::Sender, time::Duration};
 
 pub mod graph;
 pub mod search;
@@ -76,6 +78,8 @@ pub struct WorldViewer {
     item_context_menu: ItemContextMenu,
     node_to_view_map: HashMap<Handle<Node>, Handle<UiNode>>,
     small_font: SharedFont,
+    // Items, that show script costs at the moment.
+    script_cost_items: Vec<Handle<UiNode>>,
 }
 
 fn make_graph_node_item(
@@ -308,6 +312,7 @@ impl WorldViewer {
             node_to_view_map: Default::default(),
             filter: Default::default(),
             small_font,
+            script_cost_items: Default::default(),
         }
     }
 
@@ -844,6 +849,39 @@ impl WorldViewer {
         ));
     }
 
+    pub fn set_script_costs(
+        &mut self,
+        costs: &FxHashMap<Handle<Node>, Duration>,
+        max_cost: Duration,
+        ui: &UserInterface,
+    ) {
+        for item in self.script_cost_items.drain(..) {
+            if ui.try_get_node(item).is_some() {
+                send_sync_message(
+                    ui,
+                    SceneItemMessage::script_cost(item, MessageDirection::ToWidget, None),
+                );
+            }
+        }
+
+        for (node, cost) in costs.iter() {
+            if let Some(&view) = self.node_to_view_map.get(node) {
+                let heat = cost.as_secs_f32() / max_cost.as_secs_f32().max(f32::EPSILON);
+
+                send_sync_message(
+                    ui,
+                    SceneItemMessage::script_cost(
+                        view,
+                        MessageDirection::ToWidget,
+                        Some((cost.as_secs_f32() * 1000.0, heat_color(heat))),
+                    ),
+                );
+
+                self.script_cost_items.push(view);
+            }
+        }
+    }
+
     pub fn validate(&self, editor_scene: &EditorScene, engine: &Engine) {
         let scene = &engine.scenes[editor_scene.scene];
         let graph = &scene.graph;
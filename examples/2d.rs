@@ -153,11 +153,13 @@ impl Plugin for Game {
             * UnitQuaternion::from_euler_angles(0.0, 0.0, 1.0f32.to_radians());
         local_transform.set_rotation(new_rotation);
 
-        context.user_interface.send_message(TextMessage::text(
-            self.debug_text,
-            MessageDirection::ToWidget,
-            format!("Example - 2D\n{}", context.renderer.get_statistics()),
-        ));
+        if let Some(renderer) = context.renderer.as_ref() {
+            context.user_interface.send_message(TextMessage::text(
+                self.debug_text,
+                MessageDirection::ToWidget,
+                format!("Example - 2D\n{}", renderer.get_statistics()),
+            ));
+        }
     }
 
     fn on_os_event(
@@ -92,11 +92,13 @@ impl Plugin for Game {
                 .offset(offset.scale(0.1));
         }
 
-        context.user_interface.send_message(TextMessage::text(
-            self.debug_text,
-            MessageDirection::ToWidget,
-            format!("Example - 2D\n{}", context.renderer.get_statistics()),
-        ));
+        if let Some(renderer) = context.renderer.as_ref() {
+            context.user_interface.send_message(TextMessage::text(
+                self.debug_text,
+                MessageDirection::ToWidget,
+                format!("Example - 2D\n{}", renderer.get_statistics()),
+            ));
+        }
     }
 
     fn on_os_event(
@@ -136,7 +136,7 @@ fn main() {
                         [W][S][A][D] - walk, [SPACE] - jump.\n\
                         Use [1][2][3][4] to select graphics quality.\n\
                         {}",
-                        game.engine.renderer.get_statistics()
+                        game.engine.renderer.as_ref().unwrap().get_statistics()
                     );
                     game.engine.user_interface.send_message(TextMessage::text(
                         interface.debug_text,
@@ -220,6 +220,8 @@ fn main() {
                             if let Some(settings) = settings {
                                 game.engine
                                     .renderer
+                                    .as_mut()
+                                    .unwrap()
                                     .set_quality_settings(&fix_shadows_distance(settings))
                                     .unwrap();
                             }
@@ -288,7 +288,10 @@ impl Plugin for Game {
         }
 
         // While scene is loading, we will update progress bar.
-        let fps = context.renderer.get_statistics().frames_per_second;
+        let fps = context
+            .renderer
+            .as_ref()
+            .map_or(0, |renderer| renderer.get_statistics().frames_per_second);
         let debug_text = format!(
             "Example 02 - Asynchronous Scene Loading\nUse [A][D] keys to rotate model.\nFPS: {}",
             fps
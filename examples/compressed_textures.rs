@@ -36,9 +36,9 @@ impl PluginConstructor for GameConstructor {
                 TextureImportOptions::default().with_compression(CompressionOptions::Quality),
             );
 
-        context
-            .renderer
-            .set_backbuffer_clear_color(Color::opaque(120, 120, 120));
+        if let Some(renderer) = context.renderer {
+            renderer.set_backbuffer_clear_color(Color::opaque(120, 120, 120));
+        }
 
         ImageBuilder::new(
             WidgetBuilder::new()
@@ -0,0 +1,92 @@
+//! Example - Compute shaders.
+//!
+//! Difficulty: Advanced.
+//!
+//! This example shows how to run a compute shader on a storage buffer and read the results back.
+//! Compute shaders require OpenGL 4.3 or OpenGL ES 3.1.
+
+use fyrox::{
+    core::algebra::Vector3,
+    engine::{resource_manager::ResourceManager, Engine, EngineInitParams, SerializationContext},
+    event_loop::EventLoop,
+    renderer::framework::{
+        gpu_buffer::{BufferKind, BufferUsage, GpuBuffer},
+        gpu_program::GpuProgram,
+        state::MemoryBarrier,
+    },
+};
+use std::sync::Arc;
+
+const GROUP_SIZE: u32 = 64;
+
+// Squares every number of the buffer in-place.
+const COMPUTE_SHADER: &str = r#"
+layout(local_size_x = 64) in;
+
+layout(std430, binding = 0) buffer Numbers
+{
+    float numbers[];
+};
+
+void main()
+{
+    uint i = gl_GlobalInvocationID.x;
+    if (i < uint(numbers.length())) {
+        numbers[i] = numbers[i] * numbers[i];
+    }
+}
+"#;
+
+fn main() {
+    let event_loop = EventLoop::new();
+
+    let window_builder = fyrox::window::WindowBuilder::new()
+        .with_title("Example - Compute Shaders")
+        .with_visible(false);
+
+    let serialization_context = Arc::new(SerializationContext::new());
+    let mut engine = Engine::new(EngineInitParams {
+        window_builder,
+        resource_manager: ResourceManager::new(serialization_context.clone()),
+        serialization_context,
+        events_loop: &event_loop,
+        vsync: false,
+        headless: false,
+    })
+    .unwrap();
+
+    let state = engine.renderer.as_mut().unwrap().pipeline_state();
+
+    if !state.is_compute_supported() {
+        println!("Compute shaders are not supported by your GPU.");
+        return;
+    }
+
+    let program = GpuProgram::from_compute_source(state, "Square", COMPUTE_SHADER).unwrap();
+
+    let input = (0..1000).map(|i| i as f32).collect::<Vec<_>>();
+    let buffer = GpuBuffer::from_data(
+        state,
+        BufferKind::ShaderStorage,
+        BufferUsage::DynamicRead,
+        &input,
+    )
+    .unwrap();
+
+    let group_count = (input.len() as u32 + GROUP_SIZE - 1) / GROUP_SIZE;
+    program.dispatch(state, Vector3::new(group_count, 1, 1), |mut binding| {
+        binding.set_storage_buffer(0, &buffer);
+    });
+
+    // Make results of the dispatch visible for read-back.
+    state.memory_barrier(MemoryBarrier::BUFFER_UPDATE);
+
+    let mut output = vec![0.0f32; input.len()];
+    buffer.read_data(state, 0, &mut output).unwrap();
+
+    for (x, squared) in input.iter().zip(output.iter()) {
+        assert_eq!(x * x, *squared);
+    }
+
+    println!("First squares: {:?}", &output[..10]);
+}
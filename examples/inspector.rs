@@ -174,7 +174,12 @@ fn main() {
                     // Put your game logic here.
                     // ************************
 
-                    let fps = engine.renderer.get_statistics().frames_per_second;
+                    let fps = engine
+                        .renderer
+                        .as_ref()
+                        .unwrap()
+                        .get_statistics()
+                        .frames_per_second;
                     engine.user_interface.send_message(TextMessage::text(
                         interface.debug_text,
                         MessageDirection::ToWidget,
@@ -196,16 +196,18 @@ impl Plugin for Game {
             UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.camera_angle),
         );
 
-        context.user_interface.send_message(TextMessage::text(
-            self.debug_text,
-            MessageDirection::ToWidget,
-            format!(
-                "Example 10 - Instancing\n\
+        if let Some(renderer) = context.renderer.as_ref() {
+            context.user_interface.send_message(TextMessage::text(
+                self.debug_text,
+                MessageDirection::ToWidget,
+                format!(
+                    "Example 10 - Instancing\n\
                     Use [A][D] keys to rotate camera.\n\
                     {}",
-                context.renderer.get_statistics()
-            ),
-        ));
+                    renderer.get_statistics()
+                ),
+            ));
+        }
     }
 
     fn on_os_event(
@@ -244,7 +246,9 @@ impl PluginConstructor for GameConstructor {
     ) -> Box<dyn Plugin> {
         let mut settings = QualitySettings::ultra();
         settings.point_shadows_distance = 1000.0;
-        context.renderer.set_quality_settings(&settings).unwrap();
+        if let Some(renderer) = context.renderer {
+            renderer.set_quality_settings(&settings).unwrap();
+        }
 
         // Create test scene.
         let loader = fyrox::core::futures::executor::block_on(SceneLoader::load_with(
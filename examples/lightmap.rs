@@ -453,7 +453,7 @@ fn main() {
                     // While scene is loading, we will update progress bar.
                     let debug_text = format!(
                         "Example 09 - Lightmap\nUse [A][D] keys to rotate model.\n{}",
-                        engine.renderer.get_statistics()
+                        engine.renderer.as_ref().unwrap().get_statistics()
                     );
                     engine.user_interface.send_message(TextMessage::text(
                         interface.debug_text,
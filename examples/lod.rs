@@ -234,11 +234,11 @@ fn main() {
                         .local_transform_mut()
                         .set_position(Vector3::new(0.0, 1.5, -distance));
 
-                    let fps = engine.renderer.get_statistics().frames_per_second;
+                    let fps = engine.renderer.as_ref().unwrap().get_statistics().frames_per_second;
                     let text = format!(
                         "Example 08 - Level of Detail\nUse [A][D] keys to rotate model, [W][S] to zoom in/out.\nFPS: {}\nTriangles rendered: {}",
                         fps,
-                        engine.renderer.get_statistics().geometry.triangles_rendered
+                        engine.renderer.as_ref().unwrap().get_statistics().geometry.triangles_rendered
                     );
                     engine.user_interface.send_message(TextMessage::text(
                         debug_text,
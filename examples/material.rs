@@ -57,14 +57,16 @@ impl Plugin for Game {
 
         self.time += context.dt;
 
-        context.user_interface.send_message(TextMessage::text(
-            self.debug_text,
-            MessageDirection::ToWidget,
-            format!(
-                "Example - Materials and Shaders\nFPS: {}",
-                context.renderer.get_statistics().frames_per_second
-            ),
-        ));
+        if let Some(renderer) = context.renderer.as_ref() {
+            context.user_interface.send_message(TextMessage::text(
+                self.debug_text,
+                MessageDirection::ToWidget,
+                format!(
+                    "Example - Materials and Shaders\nFPS: {}",
+                    renderer.get_statistics().frames_per_second
+                ),
+            ));
+        }
     }
 }
 
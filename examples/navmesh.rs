@@ -156,9 +156,14 @@ impl Plugin for Game {
 
         scene.drawing_context.clear_lines();
 
-        let ray = scene.graph[self.camera]
-            .as_camera()
-            .make_ray(self.mouse_position, context.renderer.get_frame_bounds());
+        let ray = scene.graph[self.camera].as_camera().make_ray(
+            self.mouse_position,
+            context
+                .renderer
+                .as_ref()
+                .map(|renderer| renderer.get_frame_bounds())
+                .unwrap_or_default(),
+        );
 
         let mut buffer = ArrayVec::<Intersection, 64>::new();
         scene.graph.physics.cast_ray(
@@ -209,7 +214,10 @@ impl Plugin for Game {
             });
         }
 
-        let fps = context.renderer.get_statistics().frames_per_second;
+        let fps = context
+            .renderer
+            .as_ref()
+            .map_or(0, |renderer| renderer.get_statistics().frames_per_second);
         let text = format!(
             "Example 12 - Navigation Mesh\nFPS: {}\nAgent time: {:?}",
             fps, agent_time
@@ -20,9 +20,13 @@ impl Plugin for Game {
         self.hue += 24.0 * context.dt;
 
         // Slowly change color of the window.
-        context
-            .renderer
-            .set_backbuffer_clear_color(Color::from(Hsv::new(self.hue % 360.0, 100.0, 100.0)))
+        if let Some(renderer) = context.renderer.as_mut() {
+            renderer.set_backbuffer_clear_color(Color::from(Hsv::new(
+                self.hue % 360.0,
+                100.0,
+                100.0,
+            )))
+        }
     }
 }
 
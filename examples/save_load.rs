@@ -235,7 +235,7 @@ fn main() {
                         game_scene.player.update(scene, fixed_timestep);
                     }
 
-                    let fps = game.engine.renderer.get_statistics().frames_per_second;
+                    let fps = game.engine.renderer.as_ref().unwrap().get_statistics().frames_per_second;
                     let debug_text = format!(
                         "Example 06 - Save/load\n[W][S][A][D] - walk, [SPACE] - jump.\nFPS: {}\nUse [1][2][3][4] to select graphics quality.\nUse F5 to save game, F9 to load.",
                         fps
@@ -315,6 +315,8 @@ fn main() {
                             if let Some(settings) = settings {
                                 game.engine
                                     .renderer
+                                    .as_mut()
+                                    .unwrap()
                                     .set_quality_settings(&fix_shadows_distance(settings))
                                     .unwrap();
                             }
@@ -149,7 +149,12 @@ fn main() {
                         UnitQuaternion::from_axis_angle(&Vector3::y_axis(), model_angle),
                     );
 
-                    let fps = engine.renderer.get_statistics().frames_per_second;
+                    let fps = engine
+                        .renderer
+                        .as_ref()
+                        .unwrap()
+                        .get_statistics()
+                        .frames_per_second;
                     let text = format!(
                         "Example 05 - Scene\nUse [A][D] keys to rotate camera.\nFPS: {}",
                         fps
@@ -140,6 +140,8 @@ impl Game {
 
         engine
             .renderer
+            .as_mut()
+            .unwrap()
             .set_quality_settings(&fix_shadows_distance(QualitySettings::high()))
             .unwrap();
 
@@ -168,14 +168,16 @@ impl Plugin for Game {
                 self.model_angle,
             ));
 
-        context.user_interface.send_message(TextMessage::text(
-            self.debug_text,
-            MessageDirection::ToWidget,
-            format!(
-                "Example 01 - Simple Scene\nUse [A][D] keys to rotate model.\nFPS: {}",
-                context.renderer.get_statistics().frames_per_second
-            ),
-        ));
+        if let Some(renderer) = context.renderer.as_ref() {
+            context.user_interface.send_message(TextMessage::text(
+                self.debug_text,
+                MessageDirection::ToWidget,
+                format!(
+                    "Example 01 - Simple Scene\nUse [A][D] keys to rotate model.\nFPS: {}",
+                    renderer.get_statistics().frames_per_second
+                ),
+            ));
+        }
     }
 
     fn on_os_event(
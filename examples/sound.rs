@@ -242,7 +242,13 @@ fn main() {
                         }
                     }
 
-                    let fps = game.engine.renderer.get_statistics().frames_per_second;
+                    let fps = game
+                        .engine
+                        .renderer
+                        .as_ref()
+                        .unwrap()
+                        .get_statistics()
+                        .frames_per_second;
                     let debug_text = format!(
                         "Example 07 - Sound\n[W][S][A][D] - walk, [SPACE] - jump.\n\
                         FPS: {}\nUse [1][2][3][4] to select graphics quality.",
@@ -331,6 +337,8 @@ fn main() {
                             if let Some(settings) = settings {
                                 game.engine
                                     .renderer
+                                    .as_mut()
+                                    .unwrap()
                                     .set_quality_settings(&fix_shadows_distance(settings))
                                     .unwrap();
                             }
@@ -49,9 +49,9 @@ impl PluginConstructor for GameConstructor {
         _override_scene: Handle<Scene>,
         context: PluginContext,
     ) -> Box<dyn Plugin> {
-        context
-            .renderer
-            .set_backbuffer_clear_color(Color::TRANSPARENT);
+        if let Some(renderer) = context.renderer {
+            renderer.set_backbuffer_clear_color(Color::TRANSPARENT);
+        }
 
         let mut scene = Scene::new();
 
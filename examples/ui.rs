@@ -66,7 +66,7 @@ struct Interface {
 // complex layout system was borrowed from WPF framework. You can read more here:
 // https://docs.microsoft.com/en-us/dotnet/framework/wpf/advanced/layout
 fn create_ui(engine: &mut Engine) -> Interface {
-    let window_width = engine.renderer.get_frame_size().0 as f32;
+    let window_width = engine.renderer.as_ref().unwrap().get_frame_size().0 as f32;
 
     // Gather all suitable video modes, we'll use them to fill combo box of
     // available resolutions.
@@ -388,7 +388,12 @@ fn main() {
                             model_angle.to_radians(),
                         ));
 
-                    let fps = engine.renderer.get_statistics().frames_per_second;
+                    let fps = engine
+                        .renderer
+                        .as_ref()
+                        .unwrap()
+                        .get_statistics()
+                        .frames_per_second;
                     engine.user_interface.send_message(TextMessage::text(
                         interface.debug_text,
                         MessageDirection::ToWidget,
@@ -288,6 +288,8 @@ pub fn main_js() {
 
     engine
         .renderer
+        .as_mut()
+        .unwrap()
         .set_backbuffer_clear_color(Color::opaque(150, 150, 255));
 
     let load_context = Arc::new(Mutex::new(SceneContext { data: None }));
@@ -352,7 +354,12 @@ pub fn main_js() {
                             ));
                     }
 
-                    let fps = engine.renderer.get_statistics().frames_per_second;
+                    let fps = engine
+                        .renderer
+                        .as_ref()
+                        .unwrap()
+                        .get_statistics()
+                        .frames_per_second;
                     let text = format!(
                         "Example - WASM\nUse [A][D] keys to rotate model.\nFPS: {}\nAngle: {}",
                         fps, model_angle
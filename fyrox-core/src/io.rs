@@ -1,5 +1,8 @@
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
 use std::io::Error;
 use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub enum FileLoadError {
@@ -24,7 +27,38 @@ impl From<wasm_bindgen::JsValue> for FileLoadError {
     }
 }
 
+/// Resource IO backend allows you to override the way how the engine loads files. It could be used,
+/// for example, to load resources from an archive or to fetch them from a remote server. Once set
+/// via [`set_resource_io`], the backend is used by [`load_file`] and [`exists`] instead of the file
+/// system (or `fetch` on WebAssembly).
+pub trait ResourceIo: Send + Sync + 'static {
+    /// Loads the entire file at the given path.
+    fn load_file(&self, path: &Path) -> Result<Vec<u8>, FileLoadError>;
+
+    /// Checks whether the file at the given path exists.
+    fn exists(&self, path: &Path) -> bool;
+}
+
+lazy_static! {
+    static ref RESOURCE_IO: RwLock<Option<Arc<dyn ResourceIo>>> = RwLock::new(None);
+}
+
+/// Sets a new resource IO backend, that will be used to load every file. `None` restores the default
+/// behaviour. See [`ResourceIo`] docs for more info.
+pub fn set_resource_io(io: Option<Arc<dyn ResourceIo>>) {
+    *RESOURCE_IO.write() = io;
+}
+
+/// Returns current resource IO backend (if any).
+pub fn resource_io() -> Option<Arc<dyn ResourceIo>> {
+    RESOURCE_IO.read().clone()
+}
+
 pub async fn load_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, FileLoadError> {
+    if let Some(io) = resource_io() {
+        return io.load_file(path.as_ref());
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     {
         use std::fs::File;
@@ -58,6 +92,10 @@ pub async fn load_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, FileLoadError
 }
 
 pub async fn exists<P: AsRef<Path>>(path: P) -> bool {
+    if let Some(io) = resource_io() {
+        return io.exists(path.as_ref());
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     {
         path.as_ref().exists()
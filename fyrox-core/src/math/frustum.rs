@@ -243,6 +243,29 @@ impl Frustum {
         true
     }
 
+    /// Checks whether the bounding box is fully inside the frustum or not.
+    #[inline]
+    pub fn is_contains_aabb(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        let corners = [
+            Vector3::new(aabb.min.x, aabb.min.y, aabb.min.z),
+            Vector3::new(aabb.min.x, aabb.min.y, aabb.max.z),
+            Vector3::new(aabb.max.x, aabb.min.y, aabb.max.z),
+            Vector3::new(aabb.max.x, aabb.min.y, aabb.min.z),
+            Vector3::new(aabb.min.x, aabb.max.y, aabb.min.z),
+            Vector3::new(aabb.min.x, aabb.max.y, aabb.max.z),
+            Vector3::new(aabb.max.x, aabb.max.y, aabb.max.z),
+            Vector3::new(aabb.max.x, aabb.max.y, aabb.min.z),
+        ];
+
+        corners.iter().all(|corner| self.is_contains_point(*corner))
+    }
+
+    /// Checks whether the sphere is fully inside the frustum or not.
+    #[inline]
+    pub fn is_contains_sphere(&self, p: Vector3<f32>, r: f32) -> bool {
+        self.planes.iter().all(|plane| plane.dot(&p) >= r)
+    }
+
     #[inline]
     pub fn is_intersects_sphere(&self, p: Vector3<f32>, r: f32) -> bool {
         for plane in self.planes.iter() {
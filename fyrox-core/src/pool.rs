@@ -917,6 +917,67 @@ where
         }
     }
 
+    /// Tries to borrow mutable references to two objects at the same time. Unlike
+    /// [`borrow_two_mut`](Self::borrow_two_mut), this method does not panic, instead it returns
+    /// `None` if any of the handles is invalid or if the handles point to the same object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyrox_core::pool::Pool;
+    /// let mut pool = Pool::<u32>::new();
+    /// let a = pool.spawn(1);
+    /// let b = pool.spawn(2);
+    /// if let Some((a, b)) = pool.try_borrow_two_mut((a, b)) {
+    ///     std::mem::swap(a, b);
+    /// }
+    /// assert!(pool.try_borrow_two_mut((a, a)).is_none());
+    /// ```
+    #[inline]
+    pub fn try_borrow_two_mut(
+        &mut self,
+        handles: (Handle<T>, Handle<T>),
+    ) -> Option<(&mut T, &mut T)> {
+        let [first, second] = self.try_borrow_many_mut([handles.0, handles.1])?;
+        Some((first, second))
+    }
+
+    /// Tries to borrow mutable references to `N` objects at the same time. Returns `None` if any
+    /// of the handles is invalid or if some handles point to the same object. The method has
+    /// `O(N^2)` complexity, so it is meant to be used for small amount of handles, use
+    /// [`begin_multi_borrow`](Self::begin_multi_borrow) if you need to borrow objects one-by-one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fyrox_core::pool::Pool;
+    /// let mut pool = Pool::<u32>::new();
+    /// let a = pool.spawn(1);
+    /// let b = pool.spawn(2);
+    /// let c = pool.spawn(3);
+    /// let [a, b, c] = pool.try_borrow_many_mut([a, b, c]).unwrap();
+    /// *a += *b + *c;
+    /// ```
+    pub fn try_borrow_many_mut<const N: usize>(
+        &mut self,
+        handles: [Handle<T>; N],
+    ) -> Option<[&mut T; N]> {
+        // Prevent giving multiple mutable references to same record.
+        for (i, handle) in handles.iter().enumerate() {
+            if handles[..i].iter().any(|other| other.index == handle.index) {
+                return None;
+            }
+        }
+
+        let this = self as *mut Self;
+        let mut references = ArrayVec::<&mut T, N>::new();
+        for handle in handles {
+            // SAFETY: Every handle points to a unique record, so the references do not alias.
+            references.push(unsafe { (*this).try_borrow_mut(handle)? });
+        }
+        references.into_inner().ok()
+    }
+
     /// Tries to borrow two objects when a handle to the second object stored in the first object.
     pub fn try_borrow_dependant_mut<F>(
         &mut self,
@@ -1614,6 +1675,31 @@ mod test {
         assert_eq!(pool.borrow(bar_handle), "Bar");
     }
 
+    #[test]
+    fn pool_try_borrow_many_mut_test() {
+        let mut pool: Pool<u32> = Pool::new();
+        let a = pool.spawn(1);
+        let b = pool.spawn(2);
+        let c = pool.spawn(3);
+
+        let [ra, rb, rc] = pool.try_borrow_many_mut([a, b, c]).unwrap();
+        *ra = 10;
+        *rb = 20;
+        *rc = 30;
+        assert_eq!(pool[a], 10);
+        assert_eq!(pool[b], 20);
+        assert_eq!(pool[c], 30);
+
+        // Same handle twice.
+        assert!(pool.try_borrow_many_mut([a, b, a]).is_none());
+        assert!(pool.try_borrow_two_mut((c, c)).is_none());
+
+        // Invalid handle.
+        pool.free(b);
+        assert!(pool.try_borrow_two_mut((a, b)).is_none());
+        assert!(pool.try_borrow_many_mut([c, Handle::NONE]).is_none());
+    }
+
     #[test]
     fn pool_iterator_mut_test() {
         let mut pool: Pool<String> = Pool::new();
@@ -4,6 +4,7 @@
 
 use crate::core::{
     parking_lot::{Mutex, MutexGuard},
+    uuid::Uuid,
     visitor::prelude::*,
 };
 use std::fmt::Formatter;
@@ -21,6 +22,8 @@ use std::{
 
 pub use fyrox_core as core;
 
+pub mod registry;
+
 /// A trait for resource data.
 pub trait ResourceData: 'static + Default + Debug + Visit + Send {
     /// Returns path of resource data.
@@ -95,6 +98,27 @@ where
             Self::Ok(details) => details.visit("Details", &mut region)?,
         }
 
+        // GUID of the asset is optional, it allows to find the asset even if it was moved or
+        // renamed after the resource was saved.
+        if region.is_reading() {
+            let mut uuid = Uuid::nil();
+            if uuid.visit("Uuid", &mut region).is_ok() {
+                let actual_path = registry::asset_registry()
+                    .as_ref()
+                    .and_then(|r| r.path_of(uuid).map(|p| p.to_path_buf()));
+                if let Some(actual_path) = actual_path {
+                    self.set_path(actual_path);
+                }
+            }
+        } else {
+            let uuid = registry::asset_registry()
+                .as_ref()
+                .and_then(|r| r.uuid_of(self.path()));
+            if let Some(mut uuid) = uuid {
+                uuid.visit("Uuid", &mut region)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -417,6 +441,16 @@ impl<T: ResourceData, E: ResourceLoadError> ResourceState<T, E> {
         }
     }
 
+    /// Changes path of the resource source.
+    #[inline]
+    pub fn set_path(&mut self, new_path: PathBuf) {
+        match self {
+            Self::Pending { path, .. } => *path = new_path,
+            Self::LoadError { path, .. } => *path = new_path,
+            Self::Ok(details) => details.set_path(new_path),
+        }
+    }
+
     /// Changes ResourceState::Pending state to ResourceState::Ok(data) with given `data`.
     /// Additionally it wakes all futures.
     #[inline]
@@ -503,3 +537,67 @@ macro_rules! define_new_resource {
         }
     };
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::visitor::prelude::*,
+        registry::{self, AssetRegistry},
+        ResourceData, ResourceState,
+    };
+    use std::{
+        borrow::Cow,
+        path::{Path, PathBuf},
+    };
+
+    #[derive(Debug, Default)]
+    struct Stub {
+        path: PathBuf,
+    }
+
+    impl Visit for Stub {
+        fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+            self.path.visit(name, visitor)
+        }
+    }
+
+    impl ResourceData for Stub {
+        fn path(&self) -> Cow<Path> {
+            Cow::Borrowed(&self.path)
+        }
+
+        fn set_path(&mut self, path: PathBuf) {
+            self.path = path;
+        }
+    }
+
+    #[test]
+    fn test_resource_reference_survives_asset_rename() {
+        let mut registry = AssetRegistry::new();
+        registry.register("data/wall.png", 1);
+        registry::set_asset_registry(Some(registry));
+
+        // Save a scene, that references the asset.
+        let mut resource = ResourceState::<Stub, ()>::Ok(Stub {
+            path: PathBuf::from("data/wall.png"),
+        });
+        let mut visitor = Visitor::new();
+        resource.visit("Resource", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        // Rename the asset.
+        assert!(registry::asset_registry_mut()
+            .as_mut()
+            .unwrap()
+            .move_asset("data/wall.png", "data/textures/brick_wall.png"));
+
+        // Load the scene, the reference must point to the new path.
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        let mut loaded = ResourceState::<Stub, ()>::default();
+        loaded.visit("Resource", &mut visitor).unwrap();
+
+        registry::set_asset_registry(None);
+
+        assert_eq!(loaded.path(), Path::new("data/textures/brick_wall.png"));
+    }
+}
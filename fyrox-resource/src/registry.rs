@@ -0,0 +1,481 @@
+//! Asset registry maps file paths of assets to stable identifiers (GUIDs). Every resource reference,
+//! that is saved in a scene or a prefab, stores the GUID of the asset along with its path. When the
+//! scene is loaded, the GUID is used to find the actual path of the asset, so moving or renaming an
+//! asset does not break scenes that use it.
+//!
+//! GUIDs are deterministic - they're derived from the path at which an asset was registered for the
+//! first time, so the same project produces the same GUIDs on every machine. Once registered, the
+//! GUID of an asset never changes, even if the asset is moved.
+//!
+//! The registry is usually maintained by the editor and stored in the [`ASSET_REGISTRY_PATH`] file
+//! in the root of a project. It is a simple text file, each line of which has the following form:
+//! `<guid> <content hash> <path>`.
+
+use crate::core::{
+    io::{self, FileLoadError},
+    parking_lot::{const_rwlock, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    uuid::Uuid,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Formatter},
+    path::{Component, Path, PathBuf},
+};
+
+/// Default path of the asset registry file (relative to the working directory).
+pub const ASSET_REGISTRY_PATH: &str = "assets.registry";
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(bytes: &[u8], seed: u64) -> u64 {
+    bytes.iter().fold(seed, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Calculates a hash of the contents of an asset. The hash is stable across platforms and compiler
+/// versions, it is used to detect moved assets.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    fnv1a(bytes, FNV_OFFSET)
+}
+
+// Converts a path to the form, that does not depend on the platform.
+fn normalize_path(path: &Path) -> String {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn make_uuid(key: &str) -> Uuid {
+    let lo = fnv1a(key.as_bytes(), FNV_OFFSET);
+    let hi = fnv1a(key.as_bytes(), lo);
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&lo.to_le_bytes());
+    bytes[8..].copy_from_slice(&hi.to_le_bytes());
+    // Mark the identifier as a custom (version 8) RFC 4122 UUID.
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Uuid::from_bytes(bytes)
+}
+
+/// An error, that may occur during loading of the asset registry.
+#[derive(Debug)]
+pub enum AssetRegistryError {
+    /// An i/o error has occurred.
+    FileLoad(FileLoadError),
+    /// The registry has invalid line.
+    InvalidLine {
+        /// Number of the line (starting from 1).
+        line: usize,
+    },
+}
+
+impl Display for AssetRegistryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetRegistryError::FileLoad(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            AssetRegistryError::InvalidLine { line } => {
+                write!(f, "Asset registry has invalid line {line}")
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for AssetRegistryError {
+    fn from(e: FileLoadError) -> Self {
+        Self::FileLoad(e)
+    }
+}
+
+/// A record of a single asset in the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetEntry {
+    /// Current path of the asset.
+    pub path: PathBuf,
+    /// Hash of the asset contents at the moment of last synchronization.
+    pub hash: u64,
+}
+
+/// Results of [`AssetRegistry::sync`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Paths of assets, that were registered for the first time.
+    pub added: Vec<PathBuf>,
+    /// Assets, that were moved (or renamed). Each pair is `(old path, new path)`.
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    /// Paths of registered assets, that no longer exist. Such assets are kept in the registry, so
+    /// they could be found again if they'll be restored.
+    pub missing: Vec<PathBuf>,
+}
+
+/// Asset registry is a two-way mapping between paths of assets and their GUIDs. See module docs
+/// for more info.
+#[derive(Debug, Default, Clone)]
+pub struct AssetRegistry {
+    entries: BTreeMap<Uuid, AssetEntry>,
+    paths: HashMap<String, Uuid>,
+}
+
+impl AssetRegistry {
+    /// Creates new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the registry from its text representation.
+    pub fn parse(text: &str) -> Result<Self, AssetRegistryError> {
+        let mut registry = Self::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || AssetRegistryError::InvalidLine { line: n + 1 };
+            let (uuid, rest) = line.split_once(' ').ok_or_else(invalid)?;
+            let (hash, path) = rest.split_once(' ').ok_or_else(invalid)?;
+            let uuid = Uuid::parse_str(uuid).map_err(|_| invalid())?;
+            let hash = u64::from_str_radix(hash, 16).map_err(|_| invalid())?;
+
+            registry.insert(uuid, Path::new(path), hash);
+        }
+        Ok(registry)
+    }
+
+    /// Loads the registry from the given file.
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self, AssetRegistryError> {
+        let bytes = io::load_file(path).await?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Saves the registry to the given file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    fn insert(&mut self, uuid: Uuid, path: &Path, hash: u64) {
+        let key = normalize_path(path);
+        if let Some(previous) = self.entries.insert(
+            uuid,
+            AssetEntry {
+                path: PathBuf::from(&key),
+                hash,
+            },
+        ) {
+            self.paths.remove(&normalize_path(&previous.path));
+        }
+        if let Some(previous) = self.paths.insert(key, uuid) {
+            if previous != uuid {
+                self.entries.remove(&previous);
+            }
+        }
+    }
+
+    /// Registers an asset at the given path and returns its GUID. If the asset is already
+    /// registered, its content hash is updated and the existing GUID is returned.
+    pub fn register<P: AsRef<Path>>(&mut self, path: P, hash: u64) -> Uuid {
+        let path = path.as_ref();
+        if let Some(uuid) = self.uuid_of(path) {
+            if let Some(entry) = self.entries.get_mut(&uuid) {
+                entry.hash = hash;
+            }
+            return uuid;
+        }
+
+        // A GUID may be already taken if an asset was moved and a new one was created at its
+        // old path, in this case pick the next free GUID in a deterministic manner.
+        let key = normalize_path(path);
+        let mut uuid = make_uuid(&key);
+        let mut n = 1;
+        while self.entries.contains_key(&uuid) {
+            uuid = make_uuid(&format!("{key}#{n}"));
+            n += 1;
+        }
+
+        self.insert(uuid, path, hash);
+        uuid
+    }
+
+    /// Changes the path of a registered asset, its GUID is preserved. Returns `false` if there is
+    /// no asset at `old_path`.
+    pub fn move_asset<P: AsRef<Path>, Q: AsRef<Path>>(&mut self, old_path: P, new_path: Q) -> bool {
+        match self.uuid_of(old_path) {
+            Some(uuid) => {
+                let hash = self.entries[&uuid].hash;
+                self.insert(uuid, new_path.as_ref(), hash);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Changes paths of every registered asset in the given directory (including nested ones),
+    /// their GUIDs are preserved. Returns the amount of moved assets.
+    pub fn move_directory<P: AsRef<Path>, Q: AsRef<Path>>(
+        &mut self,
+        old_path: P,
+        new_path: Q,
+    ) -> usize {
+        let prefix = format!("{}/", normalize_path(old_path.as_ref()));
+        let new_path = normalize_path(new_path.as_ref());
+
+        let moved = self
+            .entries
+            .iter()
+            .filter_map(|(uuid, entry)| {
+                normalize_path(&entry.path)
+                    .strip_prefix(&prefix)
+                    .map(|rest| (*uuid, format!("{new_path}/{rest}"), entry.hash))
+            })
+            .collect::<Vec<_>>();
+
+        for (uuid, path, hash) in moved.iter() {
+            self.insert(*uuid, Path::new(path), *hash);
+        }
+
+        moved.len()
+    }
+
+    /// Removes an asset from the registry and returns its GUID (if any).
+    pub fn unregister<P: AsRef<Path>>(&mut self, path: P) -> Option<Uuid> {
+        let uuid = self.paths.remove(&normalize_path(path.as_ref()))?;
+        self.entries.remove(&uuid);
+        Some(uuid)
+    }
+
+    /// Returns GUID of an asset at the given path.
+    pub fn uuid_of<P: AsRef<Path>>(&self, path: P) -> Option<Uuid> {
+        self.paths.get(&normalize_path(path.as_ref())).cloned()
+    }
+
+    /// Returns current path of an asset with the given GUID.
+    pub fn path_of(&self, uuid: Uuid) -> Option<&Path> {
+        self.entries.get(&uuid).map(|e| e.path.as_path())
+    }
+
+    /// Returns total amount of registered assets.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no registered assets.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over every registered asset.
+    pub fn iter(&self) -> impl Iterator<Item = (&Uuid, &AssetEntry)> {
+        self.entries.iter()
+    }
+
+    /// Synchronizes the registry with the actual set of assets. `assets` must yield every asset of
+    /// a project with the hash of its contents (see [`content_hash`]). New assets, whose contents
+    /// match the contents of a registered asset, that no longer exists, are treated as moved ones
+    /// and inherit the GUID of the missing asset.
+    pub fn sync<I>(&mut self, assets: I) -> SyncReport
+    where
+        I: IntoIterator<Item = (PathBuf, u64)>,
+    {
+        let mut report = SyncReport::default();
+
+        let mut existing = HashMap::new();
+        let mut new = Vec::new();
+        for (path, hash) in assets {
+            match self.uuid_of(&path) {
+                Some(uuid) => {
+                    existing.insert(uuid, hash);
+                }
+                None => new.push((path, hash)),
+            }
+        }
+
+        for (uuid, hash) in existing.iter() {
+            if let Some(entry) = self.entries.get_mut(uuid) {
+                entry.hash = *hash;
+            }
+        }
+
+        let mut missing = self
+            .entries
+            .iter()
+            .filter(|(uuid, _)| !existing.contains_key(*uuid))
+            .map(|(uuid, entry)| (*uuid, entry.clone()))
+            .collect::<Vec<_>>();
+
+        new.sort();
+        for (path, hash) in new {
+            if let Some(index) = missing.iter().position(|(_, entry)| entry.hash == hash) {
+                let (uuid, entry) = missing.remove(index);
+                self.insert(uuid, &path, hash);
+                report.moved.push((entry.path, path));
+            } else {
+                self.register(&path, hash);
+                report.added.push(path);
+            }
+        }
+
+        report.missing = missing.into_iter().map(|(_, entry)| entry.path).collect();
+
+        report
+    }
+}
+
+impl Display for AssetRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Fyrox asset registry. Do not edit manually.")?;
+        for (uuid, entry) in self.entries.iter() {
+            writeln!(f, "{} {:016x} {}", uuid, entry.hash, entry.path.display())?;
+        }
+        Ok(())
+    }
+}
+
+static ASSET_REGISTRY: RwLock<Option<AssetRegistry>> = const_rwlock(None);
+
+/// Sets a new global asset registry, that will be used to resolve resource references. `None`
+/// disables resolving, resources will be loaded by the paths stored in scenes.
+pub fn set_asset_registry(registry: Option<AssetRegistry>) {
+    *ASSET_REGISTRY.write() = registry;
+}
+
+/// Returns a reference to current global asset registry (if any).
+pub fn asset_registry() -> RwLockReadGuard<'static, Option<AssetRegistry>> {
+    ASSET_REGISTRY.read()
+}
+
+/// Returns a mutable reference to current global asset registry (if any).
+pub fn asset_registry_mut() -> RwLockWriteGuard<'static, Option<AssetRegistry>> {
+    ASSET_REGISTRY.write()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::registry::{content_hash, make_uuid, AssetRegistry, AssetRegistryError};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_registry_text_round_trip() {
+        let mut registry = AssetRegistry::new();
+        let wall = registry.register("data/wall.png", content_hash(b"wall"));
+        let music = registry.register("data/sounds/music.ogg", content_hash(b"music"));
+
+        let parsed = AssetRegistry::parse(&registry.to_string()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.uuid_of("data/wall.png"), Some(wall));
+        assert_eq!(parsed.uuid_of("data/sounds/music.ogg"), Some(music));
+        assert_eq!(parsed.to_string(), registry.to_string());
+    }
+
+    #[test]
+    fn test_registry_parse_errors() {
+        let uuid = make_uuid("a.png");
+        assert!(
+            AssetRegistry::parse(&format!("# Comment\n\n{uuid} 0000000000000001 a.png\n")).is_ok()
+        );
+        assert!(matches!(
+            AssetRegistry::parse(&format!("{uuid} 1 a.png\nbroken")),
+            Err(AssetRegistryError::InvalidLine { line: 2 })
+        ));
+        assert!(matches!(
+            AssetRegistry::parse(&format!("{uuid} not_a_hash a.png")),
+            Err(AssetRegistryError::InvalidLine { line: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_register_is_deterministic() {
+        let mut registry = AssetRegistry::new();
+        let uuid = registry.register("./data/wall.png", 1);
+        assert_eq!(uuid, make_uuid("data/wall.png"));
+        assert_eq!(registry.register("data/wall.png", 2), uuid);
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.iter().next().unwrap().1.hash, 2);
+    }
+
+    #[test]
+    fn test_register_collision() {
+        let mut registry = AssetRegistry::new();
+        let first = registry.register("a.png", 1);
+        assert!(registry.move_asset("a.png", "b.png"));
+
+        // A new asset at the old path must not steal the GUID of the moved one.
+        let second = registry.register("a.png", 2);
+        assert_ne!(first, second);
+        assert_eq!(second, make_uuid("a.png#1"));
+        assert_eq!(registry.path_of(first), Some(Path::new("b.png")));
+        assert_eq!(registry.path_of(second), Some(Path::new("a.png")));
+    }
+
+    #[test]
+    fn test_move_asset() {
+        let mut registry = AssetRegistry::new();
+        let uuid = registry.register("data/wall.png", 1);
+
+        assert!(registry.move_asset("data/wall.png", "data/textures/wall.png"));
+        assert_eq!(registry.uuid_of("data/wall.png"), None);
+        assert_eq!(registry.uuid_of("data/textures/wall.png"), Some(uuid));
+        assert_eq!(
+            registry.path_of(uuid),
+            Some(Path::new("data/textures/wall.png"))
+        );
+        assert!(!registry.move_asset("data/wall.png", "data/other.png"));
+
+        assert_eq!(registry.unregister("data/textures/wall.png"), Some(uuid));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_move_directory() {
+        let mut registry = AssetRegistry::new();
+        let wall = registry.register("data/textures/wall.png", 1);
+        let floor = registry.register("data/textures/stone/floor.png", 2);
+        let other = registry.register("data/textures_old/wall.png", 3);
+
+        assert_eq!(registry.move_directory("data/textures", "data/tex"), 2);
+        assert_eq!(registry.path_of(wall), Some(Path::new("data/tex/wall.png")));
+        assert_eq!(
+            registry.path_of(floor),
+            Some(Path::new("data/tex/stone/floor.png"))
+        );
+        assert_eq!(
+            registry.path_of(other),
+            Some(Path::new("data/textures_old/wall.png"))
+        );
+    }
+
+    #[test]
+    fn test_sync_detects_moved_assets() {
+        let mut registry = AssetRegistry::new();
+        let wall = registry.register("data/wall.png", content_hash(b"wall"));
+        let floor = registry.register("data/floor.png", content_hash(b"floor"));
+        let ceiling = registry.register("data/ceiling.png", content_hash(b"ceiling"));
+
+        let report = registry.sync(vec![
+            (PathBuf::from("data/floor.png"), content_hash(b"floor")),
+            (PathBuf::from("data/stone/wall.png"), content_hash(b"wall")),
+            (PathBuf::from("data/new.png"), content_hash(b"new")),
+        ]);
+
+        assert_eq!(
+            report.moved,
+            vec![(
+                PathBuf::from("data/wall.png"),
+                PathBuf::from("data/stone/wall.png")
+            )]
+        );
+        assert_eq!(report.added, vec![PathBuf::from("data/new.png")]);
+        assert_eq!(report.missing, vec![PathBuf::from("data/ceiling.png")]);
+
+        assert_eq!(registry.uuid_of("data/stone/wall.png"), Some(wall));
+        assert_eq!(registry.uuid_of("data/floor.png"), Some(floor));
+        // Missing assets are kept, so they could be found if restored.
+        assert_eq!(registry.uuid_of("data/ceiling.png"), Some(ceiling));
+        assert!(registry.uuid_of("data/new.png").is_some());
+    }
+}
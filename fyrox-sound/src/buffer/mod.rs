@@ -95,6 +95,13 @@ pub trait RawStreamingDataSource: Iterator<Item = f32> + Send + Sync + Debug {
     fn duration(&self) -> Option<Duration> {
         None
     }
+
+    /// Returns amount of samples `per channel` that is read from the source at once. Smaller blocks
+    /// decrease playback latency (which is important for real-time sources, such as voice chat) at
+    /// the cost of more frequent reads. Default value is `StreamingBuffer::STREAM_SAMPLE_COUNT`.
+    fn block_sample_count(&self) -> usize {
+        StreamingBuffer::STREAM_SAMPLE_COUNT
+    }
 }
 
 impl DataSource {
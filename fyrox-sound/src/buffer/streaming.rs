@@ -93,6 +93,14 @@ impl StreamingSource {
         }
     }
 
+    #[inline]
+    fn block_sample_count(&self) -> usize {
+        match self {
+            StreamingSource::Raw(raw) => raw.block_sample_count(),
+            _ => StreamingBuffer::STREAM_SAMPLE_COUNT,
+        }
+    }
+
     fn duration(&self) -> Option<Duration> {
         match self {
             StreamingSource::Null => None,
@@ -120,7 +128,7 @@ impl StreamingSource {
     #[inline]
     fn read_next_samples_block_into(&mut self, buffer: &mut Vec<f32>) -> usize {
         buffer.clear();
-        let count = StreamingBuffer::STREAM_SAMPLE_COUNT * self.channel_count();
+        let count = self.block_sample_count() * self.channel_count();
         match self {
             StreamingSource::Decoder(decoder) => {
                 for _ in 0..count {
@@ -190,6 +198,14 @@ impl StreamingBuffer {
         self.streaming_source.duration()
     }
 
+    /// Returns amount of samples `per channel` that is read from the data source at once. It is
+    /// `StreamingBuffer::STREAM_SAMPLE_COUNT` for every data source, except raw streaming sources,
+    /// see [`RawStreamingDataSource::block_sample_count`].
+    #[inline]
+    pub fn block_sample_count(&self) -> usize {
+        self.streaming_source.block_sample_count()
+    }
+
     #[inline]
     pub(crate) fn read_next_block(&mut self) {
         self.streaming_source
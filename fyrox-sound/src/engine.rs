@@ -4,15 +4,26 @@
 //!
 //! Sound engine manages contexts, feeds output device with data.
 
-use crate::{context::SoundContext, device};
+use crate::{
+    context::SoundContext,
+    device,
+    error::SoundError,
+    recorder::{Recorder, RecordingSource},
+};
 use fyrox_core::visitor::{Visit, VisitResult, Visitor};
-use std::sync::{Arc, Mutex};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
 
 /// Internal state of sound engine.
 #[derive(Default)]
 pub struct SoundEngine {
     contexts: Vec<SoundContext>,
     master_gain: f32,
+    recorder: Option<Recorder>,
+    recording_buffer: Vec<(f32, f32)>,
+    recording_error: Option<SoundError>,
 }
 
 impl SoundEngine {
@@ -33,6 +44,9 @@ impl SoundEngine {
         let engine = Arc::new(Mutex::new(Self {
             contexts: Default::default(),
             master_gain: 1.0,
+            recorder: None,
+            recording_buffer: Default::default(),
+            recording_error: None,
         }));
 
         // Run the default output device. Internally it creates separate thread, so we have
@@ -56,6 +70,9 @@ impl SoundEngine {
         Arc::new(Mutex::new(Self {
             contexts: Default::default(),
             master_gain: 1.0,
+            recorder: None,
+            recording_buffer: Default::default(),
+            recording_error: None,
         }))
     }
 
@@ -117,10 +134,82 @@ impl SoundEngine {
         self.render_inner(buf);
     }
 
+    /// Starts recording of the given source to a WAV file at the given path. Samples are written
+    /// in stereo, 32-bit float format with [`crate::context::SAMPLE_RATE`] sample rate. Recording
+    /// that is already running will be stopped first, an error that interrupted previous recording
+    /// (if any) is discarded.
+    pub fn start_recording<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        source: RecordingSource,
+    ) -> Result<(), SoundError> {
+        self.recording_error = None;
+        self.stop_recording()?;
+        self.recorder = Some(Recorder::new(path, source)?);
+        Ok(())
+    }
+
+    /// Stops current recording (if any) and finalizes the file. If the recording was interrupted
+    /// by an error (see [`Self::recording_error`]), the error is returned.
+    pub fn stop_recording(&mut self) -> Result<(), SoundError> {
+        if let Some(error) = self.recording_error.take() {
+            return Err(error);
+        }
+
+        match self.recorder.take() {
+            Some(recorder) => recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the engine is recording its output.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Returns an error that interrupted current recording. Recording is stopped on the first
+    /// error (for example, when there's no space left on the disk), samples that were written
+    /// before the error are kept in the file. The error is cleared by [`Self::stop_recording`] or
+    /// [`Self::start_recording`].
+    pub fn recording_error(&self) -> Option<&SoundError> {
+        self.recording_error.as_ref()
+    }
+
     fn render_inner(&mut self, buf: &mut [(f32, f32)]) {
         let master_gain = self.master_gain;
-        for context in self.contexts.iter_mut() {
-            context.state().render(master_gain, buf);
+        self.recording_buffer.clear();
+        self.recording_buffer.resize(buf.len(), (0.0, 0.0));
+        for context in self.contexts.iter() {
+            match self.recorder {
+                Some(Recorder {
+                    source: RecordingSource::Context(ref recorded),
+                    ..
+                }) if recorded == context => {
+                    // Render the context separately, so its output could be recorded alone.
+                    context
+                        .state()
+                        .render(master_gain, &mut self.recording_buffer);
+                    for (out, sample) in buf.iter_mut().zip(self.recording_buffer.iter()) {
+                        out.0 += sample.0;
+                        out.1 += sample.1;
+                    }
+                }
+                _ => context.state().render(master_gain, buf),
+            }
+        }
+
+        if let Some(recorder) = self.recorder.as_mut() {
+            let samples = match recorder.source {
+                RecordingSource::Master => &*buf,
+                RecordingSource::Context(_) => &self.recording_buffer,
+            };
+            if let Err(e) = recorder.write(samples) {
+                if let Some(recorder) = self.recorder.take() {
+                    // Try to keep the file readable, the original error is more important.
+                    let _ = recorder.finish();
+                }
+                self.recording_error = Some(e);
+            }
         }
     }
 }
@@ -53,6 +53,10 @@ pub enum SoundError {
 
     /// A buffer is not loaded yet, consider to `await` it before use.
     BufferIsNotLoaded,
+
+    /// An error occurred on a running device (for example, the device was disconnected), exact
+    /// reason stored in inner value.
+    DeviceError(String),
 }
 
 impl From<std::io::Error> for SoundError {
@@ -93,6 +97,9 @@ impl Display for SoundError {
             SoundError::DecoderError(de) => write!(f, "internal decoder error: {:?}", de),
             SoundError::BufferFailedToLoad => write!(f, "a buffer failed to load"),
             SoundError::BufferIsNotLoaded => write!(f, "a buffer is not loaded yet"),
+            SoundError::DeviceError(reason) => {
+                write!(f, "device error has occurred. reason: {}", reason)
+            }
         }
     }
 }
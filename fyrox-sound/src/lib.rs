@@ -74,8 +74,10 @@ pub mod effects;
 pub mod engine;
 pub mod error;
 pub mod listener;
+pub mod recorder;
 pub mod renderer;
 pub mod source;
+pub mod voice;
 
 // Reexport some modules because there some types of them in public API.
 pub use fyrox_core::algebra;
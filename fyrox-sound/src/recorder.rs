@@ -0,0 +1,94 @@
+//! Recorder module allows you to record output of the sound engine to a WAV file.
+//!
+//! # Overview
+//!
+//! Recording could be useful to capture audio for trailers or to debug audio issues. Recorder writes
+//! samples exactly as they're sent to the output device (before conversion to device format), so the
+//! recorded file is not affected by any lags of a screen capture software. Recorder could record
+//! either the final mix of the engine or the output of a single sound context (for example, only the
+//! sounds of a particular scene).
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::{engine::SoundEngine, recorder::RecordingSource};
+//!
+//! let engine = SoundEngine::new();
+//!
+//! engine
+//!     .lock()
+//!     .unwrap()
+//!     .start_recording("trailer.wav", RecordingSource::Master)
+//!     .unwrap();
+//!
+//! // ...
+//!
+//! engine.lock().unwrap().stop_recording().unwrap();
+//! ```
+
+use crate::{
+    context::{SoundContext, SAMPLE_RATE},
+    error::SoundError,
+};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::{fs::File, io::BufWriter, path::Path};
+
+/// Defines which samples are recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordingSource {
+    /// The final mix of every sound context of the engine.
+    Master,
+    /// Output of a single sound context.
+    Context(SoundContext),
+}
+
+pub(crate) struct Recorder {
+    pub(crate) source: RecordingSource,
+    writer: WavWriter<BufWriter<File>>,
+}
+
+fn hound_to_sound_error(error: hound::Error) -> SoundError {
+    match error {
+        hound::Error::IoError(e) => SoundError::Io(e),
+        e => SoundError::Io(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            e.to_string(),
+        )),
+    }
+}
+
+impl Recorder {
+    pub(crate) fn new<P: AsRef<Path>>(
+        path: P,
+        source: RecordingSource,
+    ) -> Result<Self, SoundError> {
+        let writer = WavWriter::create(
+            path,
+            WavSpec {
+                channels: 2,
+                sample_rate: SAMPLE_RATE,
+                bits_per_sample: 32,
+                sample_format: SampleFormat::Float,
+            },
+        )
+        .map_err(hound_to_sound_error)?;
+
+        Ok(Self { source, writer })
+    }
+
+    pub(crate) fn write(&mut self, samples: &[(f32, f32)]) -> Result<(), SoundError> {
+        for &(left, right) in samples {
+            self.writer
+                .write_sample(left.clamp(-1.0, 1.0))
+                .map_err(hound_to_sound_error)?;
+            self.writer
+                .write_sample(right.clamp(-1.0, 1.0))
+                .map_err(hound_to_sound_error)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> Result<(), SoundError> {
+        self.writer.finalize().map_err(hound_to_sound_error)
+    }
+}
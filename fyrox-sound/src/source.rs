@@ -461,7 +461,7 @@ impl SoundSource {
                     streaming.read_next_block();
                     // Streaming sources has different buffer read position because
                     // buffer contains only small portion of data.
-                    self.playback_pos % (StreamingBuffer::STREAM_SAMPLE_COUNT as f64)
+                    self.playback_pos % (streaming.block_sample_count() as f64)
                 }
                 SoundBufferState::Generic(_) => self.playback_pos,
             };
@@ -503,7 +503,7 @@ impl SoundSource {
             let mut end_reached = true;
             if let SoundBufferState::Streaming(streaming) = buffer {
                 // Means that this is the last available block.
-                if len != channel_count * StreamingBuffer::STREAM_SAMPLE_COUNT {
+                if len != channel_count * streaming.block_sample_count() {
                     let _ = streaming.rewind();
                 } else {
                     end_reached = false;
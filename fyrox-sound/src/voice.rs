@@ -0,0 +1,263 @@
+//! Voice module contains audio plumbing for voice chat.
+//!
+//! # Overview
+//!
+//! Voice chat consists of two parts - capturing of samples from a microphone and playback of samples
+//! that were received from other players. This module does not do any networking or compression, it
+//! only provides a way to get samples from a microphone ([`VoiceCapture`], requires `voice-capture`
+//! feature) and a way to play incoming samples with low latency ([`voice_channel`]).
+//!
+//! Incoming samples are played using a [`VoiceStream`], which is a raw streaming data source with
+//! small blocks. It could be used with any sound source, so voice of a player could be spatialized
+//! like any other sound in a scene.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox_sound::{
+//!     buffer::{DataSource, SoundBufferResource},
+//!     context::SoundContext,
+//!     pool::Handle,
+//!     source::{SoundSource, SoundSourceBuilder, Status},
+//!     voice::voice_channel,
+//! };
+//! use std::time::Duration;
+//!
+//! fn add_voice_of_player(context: &SoundContext) -> Handle<SoundSource> {
+//!     let (sender, stream) = voice_channel(48000, Duration::from_millis(200));
+//!
+//!     let buffer =
+//!         SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(stream))).unwrap();
+//!     let source = SoundSourceBuilder::new()
+//!         .with_buffer(buffer)
+//!         .with_status(Status::Playing)
+//!         .build()
+//!         .unwrap();
+//!
+//!     // Store the sender somewhere and feed it with samples received from the network:
+//!     // sender.send(&samples);
+//!     # let _ = sender;
+//!
+//!     context.state().add_source(source)
+//! }
+//! ```
+
+use crate::buffer::RawStreamingDataSource;
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Formatter},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Debug)]
+struct VoiceQueue {
+    samples: VecDeque<f32>,
+    max_len: usize,
+}
+
+/// Creates a new voice channel with the given sample rate of incoming (mono) samples. `max_latency`
+/// defines how much samples could be queued for playback, older samples are dropped when there is
+/// more samples than that (for example, when network packets came in a burst). Returns a sender, that
+/// should be used to feed samples and a stream, that should be used as a data source of a sound
+/// source (see [`crate::buffer::DataSource::RawStreaming`]).
+pub fn voice_channel(sample_rate: usize, max_latency: Duration) -> (VoiceSender, VoiceStream) {
+    let queue = Arc::new(Mutex::new(VoiceQueue {
+        samples: Default::default(),
+        max_len: ((sample_rate as f64 * max_latency.as_secs_f64()) as usize).max(1),
+    }));
+
+    (
+        VoiceSender {
+            queue: queue.clone(),
+        },
+        VoiceStream {
+            queue,
+            sample_rate,
+            // 20 ms blocks, it is a common frame duration for voice codecs.
+            block_sample_count: (sample_rate / 50).max(1),
+        },
+    )
+}
+
+/// Sending side of a voice channel, see [`voice_channel`] for more info.
+#[derive(Clone, Debug)]
+pub struct VoiceSender {
+    queue: Arc<Mutex<VoiceQueue>>,
+}
+
+impl VoiceSender {
+    /// Queues the given mono samples for playback.
+    pub fn send(&self, samples: &[f32]) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.samples.extend(samples.iter().cloned());
+        let len = queue.samples.len();
+        if len > queue.max_len {
+            let excess = len - queue.max_len;
+            queue.samples.drain(..excess);
+        }
+    }
+
+    /// Returns amount of samples that are waiting for playback.
+    pub fn queued_sample_count(&self) -> usize {
+        self.queue.lock().unwrap().samples.len()
+    }
+
+    /// Removes every sample that is waiting for playback.
+    pub fn clear(&self) {
+        self.queue.lock().unwrap().samples.clear();
+    }
+}
+
+/// Receiving side of a voice channel, see [`voice_channel`] for more info. The stream never ends, it
+/// produces silence when there is no samples to play.
+pub struct VoiceStream {
+    queue: Arc<Mutex<VoiceQueue>>,
+    sample_rate: usize,
+    block_sample_count: usize,
+}
+
+impl Debug for VoiceStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "VoiceStream {{ sample_rate: {} }}", self.sample_rate)
+    }
+}
+
+impl Iterator for VoiceStream {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(
+            self.queue
+                .lock()
+                .unwrap()
+                .samples
+                .pop_front()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl RawStreamingDataSource for VoiceStream {
+    fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    fn channel_count(&self) -> usize {
+        1
+    }
+
+    fn block_sample_count(&self) -> usize {
+        self.block_sample_count
+    }
+}
+
+#[cfg(feature = "voice-capture")]
+pub use capture::VoiceCapture;
+
+#[cfg(feature = "voice-capture")]
+mod capture {
+    use crate::error::SoundError;
+    use cpal::{
+        traits::{DeviceTrait, HostTrait, StreamTrait},
+        Sample, SampleFormat, Stream, StreamConfig,
+    };
+    use std::sync::mpsc::{self, Receiver, Sender};
+
+    /// Captures samples from the default input device (microphone) of the system. Captured samples
+    /// are converted to mono. Capturing stops when the instance is dropped.
+    pub struct VoiceCapture {
+        stream: Stream,
+        receiver: Receiver<Result<Vec<f32>, cpal::StreamError>>,
+        sample_rate: usize,
+    }
+
+    fn build_stream<T: Sample>(
+        device: &cpal::Device,
+        config: &StreamConfig,
+        sender: Sender<Result<Vec<f32>, cpal::StreamError>>,
+    ) -> Result<Stream, cpal::BuildStreamError> {
+        let channel_count = config.channels.max(1) as usize;
+        let error_sender = sender.clone();
+        device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let samples = data
+                    .chunks(channel_count)
+                    .map(|frame| frame.iter().map(|s| s.to_f32()).sum::<f32>() / frame.len() as f32)
+                    .collect();
+                // Receiver could be destroyed already, this is fine.
+                let _ = sender.send(Ok(samples));
+            },
+            move |err| {
+                // Errors are reported by `VoiceCapture::read`.
+                let _ = error_sender.send(Err(err));
+            },
+        )
+    }
+
+    impl VoiceCapture {
+        /// Starts capturing from the default input device of the system.
+        pub fn new() -> Result<Self, SoundError> {
+            let device = cpal::default_host().default_input_device().ok_or_else(|| {
+                SoundError::FailedToInitializeDevice("There is no input device!".to_string())
+            })?;
+            let supported_config = device
+                .default_input_config()
+                .map_err(|e| SoundError::FailedToInitializeDevice(e.to_string()))?;
+            let sample_format = supported_config.sample_format();
+            let config: StreamConfig = supported_config.into();
+
+            let (sender, receiver) = mpsc::channel();
+            let stream = match sample_format {
+                SampleFormat::F32 => build_stream::<f32>(&device, &config, sender),
+                SampleFormat::I16 => build_stream::<i16>(&device, &config, sender),
+                SampleFormat::U16 => build_stream::<u16>(&device, &config, sender),
+            }
+            .map_err(|e| SoundError::FailedToInitializeDevice(e.to_string()))?;
+            stream
+                .play()
+                .map_err(|e| SoundError::FailedToInitializeDevice(e.to_string()))?;
+
+            Ok(Self {
+                stream,
+                receiver,
+                sample_rate: config.sample_rate.0 as usize,
+            })
+        }
+
+        /// Returns sample rate of captured samples.
+        pub fn sample_rate(&self) -> usize {
+            self.sample_rate
+        }
+
+        /// Moves every sample, that was captured since the last call, to the end of the given buffer.
+        /// Returns amount of added samples or an error, that occurred during capturing (for example,
+        /// when the device was disconnected). Samples, that were captured before the error, are added
+        /// to the buffer anyway, samples that were captured after the error are kept for the next call.
+        pub fn read(&self, samples: &mut Vec<f32>) -> Result<usize, SoundError> {
+            let len = samples.len();
+            for chunk in self.receiver.try_iter() {
+                match chunk {
+                    Ok(chunk) => samples.extend_from_slice(&chunk),
+                    Err(e) => return Err(SoundError::DeviceError(e.to_string())),
+                }
+            }
+            Ok(samples.len() - len)
+        }
+
+        /// Pauses capturing, could be used to implement push-to-talk.
+        pub fn pause(&self) -> Result<(), SoundError> {
+            self.stream
+                .pause()
+                .map_err(|e| SoundError::FailedToInitializeDevice(e.to_string()))
+        }
+
+        /// Resumes capturing after [`Self::pause`].
+        pub fn resume(&self) -> Result<(), SoundError> {
+            self.stream
+                .play()
+                .map_err(|e| SoundError::FailedToInitializeDevice(e.to_string()))
+        }
+    }
+}
@@ -542,6 +542,9 @@ impl DrawingContext {
         formatted_text: &FormattedText,
     ) {
         let font = formatted_text.get_font();
+        // Texture coordinates are taken from the font directly, because its atlas could be
+        // re-packed after the text was built.
+        let font_ref = font.0.lock();
 
         // Draw shadow, if any.
         if formatted_text.shadow {
@@ -560,7 +563,7 @@ impl DrawingContext {
                 )
                 .translate(formatted_text.shadow_offset);
 
-                self.push_rect_filled(&final_bounds, Some(element.get_tex_coords()));
+                self.push_rect_filled(&final_bounds, Some(&element.tex_coords(&font_ref)));
             }
 
             self.commit(
@@ -581,9 +584,11 @@ impl DrawingContext {
                 bounds.h(),
             );
 
-            self.push_rect_filled(&final_bounds, Some(element.get_tex_coords()));
+            self.push_rect_filled(&final_bounds, Some(&element.tex_coords(&font_ref)));
         }
 
+        drop(font_ref);
+
         self.commit(
             clip_bounds,
             formatted_text.brush(),
@@ -2,7 +2,7 @@ use crate::{
     brush::Brush,
     core::{algebra::Vector2, color::Color, math::Rect},
     ttf::SharedFont,
-    Font, HorizontalAlignment, VerticalAlignment,
+    FlowDirection, Font, HorizontalAlignment, VerticalAlignment,
 };
 use std::ops::Range;
 
@@ -10,6 +10,7 @@ use std::ops::Range;
 pub struct TextGlyph {
     bounds: Rect<f32>,
     tex_coords: [Vector2<f32>; 4],
+    glyph_index: Option<usize>,
 }
 
 impl TextGlyph {
@@ -17,9 +18,24 @@ impl TextGlyph {
         self.bounds
     }
 
+    /// Returns texture coordinates of the glyph at the moment when the text was built. Keep in
+    /// mind that they could be outdated, if the atlas of the font has grown since then, use
+    /// [`Self::tex_coords`] to get actual ones.
     pub fn get_tex_coords(&self) -> &[Vector2<f32>; 4] {
         &self.tex_coords
     }
+
+    /// Returns index of the glyph in the font, `None` for invalid symbols.
+    pub fn glyph_index(&self) -> Option<usize> {
+        self.glyph_index
+    }
+
+    /// Returns actual texture coordinates of the glyph in the atlas of the given font.
+    pub fn tex_coords(&self, font: &Font) -> [Vector2<f32>; 4] {
+        self.glyph_index
+            .and_then(|index| font.glyphs().get(index))
+            .map_or(self.tex_coords, |glyph| glyph.tex_coords)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -79,10 +95,11 @@ pub struct Character {
 }
 
 impl Character {
-    pub fn from_char_with_font(char_code: u32, font: &Font) -> Self {
+    /// Creates new character, its glyph is loaded on demand if the font does not have it yet.
+    pub fn from_char_with_font(char_code: u32, font: &mut Font) -> Self {
         Self {
             char_code,
-            glyph_index: font.glyph_index(char_code).unwrap_or_default() as u32,
+            glyph_index: font.glyph_index_or_load(char_code).unwrap_or_default() as u32,
         }
     }
 
@@ -106,6 +123,7 @@ pub struct FormattedText {
     glyphs: Vec<TextGlyph>,
     vertical_alignment: VerticalAlignment,
     horizontal_alignment: HorizontalAlignment,
+    flow_direction: FlowDirection,
     brush: Brush,
     constraint: Vector2<f32>,
     wrap: WrapMode,
@@ -161,6 +179,17 @@ impl FormattedText {
         self.horizontal_alignment
     }
 
+    /// Sets flow direction of the text. In right-to-left mode, characters of each line are placed from
+    /// right to left and horizontal alignment is mirrored.
+    pub fn set_flow_direction(&mut self, flow_direction: FlowDirection) -> &mut Self {
+        self.flow_direction = flow_direction;
+        self
+    }
+
+    pub fn flow_direction(&self) -> FlowDirection {
+        self.flow_direction
+    }
+
     pub fn set_brush(&mut self, brush: Brush) -> &mut Self {
         self.brush = brush;
         self
@@ -199,10 +228,11 @@ impl FormattedText {
         // Convert text to UTF32.
         self.text.clear();
 
-        let font = self.font.0.lock();
+        let mut font = self.font.0.lock();
 
         for code in text.as_ref().chars().map(|c| c as u32) {
-            self.text.push(Character::from_char_with_font(code, &font));
+            self.text
+                .push(Character::from_char_with_font(code, &mut font));
         }
 
         drop(font);
@@ -245,10 +275,12 @@ impl FormattedText {
     }
 
     pub fn insert_char(&mut self, code: char, index: usize) -> &mut Self {
-        let font = self.font.0.lock();
+        let mut font = self.font.0.lock();
 
-        self.text
-            .insert(index, Character::from_char_with_font(code as u32, &font));
+        self.text.insert(
+            index,
+            Character::from_char_with_font(code as u32, &mut font),
+        );
 
         drop(font);
 
@@ -256,12 +288,12 @@ impl FormattedText {
     }
 
     pub fn insert_str(&mut self, str: &str, position: usize) -> &mut Self {
-        let font = self.font.0.lock();
+        let mut font = self.font.0.lock();
 
         for (i, code) in str.chars().enumerate() {
             self.text.insert(
                 position + i,
-                Character::from_char_with_font(code as u32, &font),
+                Character::from_char_with_font(code as u32, &mut font),
             );
         }
 
@@ -404,8 +436,9 @@ impl FormattedText {
         }
 
         // Align lines according to desired alignment.
+        let horizontal_alignment = self.flow_direction.apply(self.horizontal_alignment);
         for line in self.lines.iter_mut() {
-            match self.horizontal_alignment {
+            match horizontal_alignment {
                 HorizontalAlignment::Left => line.x_offset = 0.0,
                 HorizontalAlignment::Center => {
                     if self.constraint.x.is_infinite() {
@@ -453,16 +486,27 @@ impl FormattedText {
             self.constraint.x
         };
 
+        let right_to_left = self.flow_direction.is_right_to_left();
         let mut cursor = Vector2::new(cursor_x_start, cursor_y_start);
         for line in self.lines.iter_mut() {
-            cursor.x = line.x_offset;
+            cursor.x = if right_to_left {
+                line.x_offset + line.width
+            } else {
+                line.x_offset
+            };
 
             for &character in text.iter().take(line.end).skip(line.begin) {
                 match font.glyphs().get(character.glyph_index as usize) {
                     Some(glyph) => {
-                        // Insert glyph
+                        if right_to_left {
+                            cursor.x -= glyph.advance;
+                        }
+
+                        // Insert glyph. Pen position is accumulated with sub-pixel precision
+                        // to keep spacing accurate, but glyphs are snapped to pixels to not
+                        // blur them.
                         let rect = Rect::new(
-                            cursor.x + glyph.left.floor(),
+                            (cursor.x + glyph.left).round(),
                             cursor.y + font.ascender().floor()
                                 - glyph.top.floor()
                                 - glyph.bitmap_height as f32,
@@ -472,12 +516,18 @@ impl FormattedText {
                         let text_glyph = TextGlyph {
                             bounds: rect,
                             tex_coords: glyph.tex_coords,
+                            glyph_index: Some(character.glyph_index as usize),
                         };
                         self.glyphs.push(text_glyph);
 
-                        cursor.x += glyph.advance;
+                        if !right_to_left {
+                            cursor.x += glyph.advance;
+                        }
                     }
                     None => {
+                        if right_to_left {
+                            cursor.x -= font.height();
+                        }
                         // Insert invalid symbol
                         let rect = Rect::new(
                             cursor.x,
@@ -488,8 +538,11 @@ impl FormattedText {
                         self.glyphs.push(TextGlyph {
                             bounds: rect,
                             tex_coords: [Vector2::default(); 4],
+                            glyph_index: None,
                         });
-                        cursor.x += rect.w();
+                        if !right_to_left {
+                            cursor.x += rect.w();
+                        }
                     }
                 }
             }
@@ -602,23 +655,24 @@ impl FormattedTextBuilder {
     }
 
     pub fn build(self) -> FormattedText {
-        let font = self.font.0.lock();
+        let mut font = self.font.0.lock();
         FormattedText {
             text: self
                 .text
                 .chars()
-                .map(|c| Character::from_char_with_font(c as u32, &font))
+                .map(|c| Character::from_char_with_font(c as u32, &mut font))
                 .collect(),
             lines: Vec::new(),
             glyphs: Vec::new(),
             vertical_alignment: self.vertical_alignment,
             horizontal_alignment: self.horizontal_alignment,
+            flow_direction: Default::default(),
             brush: self.brush,
             constraint: self.constraint,
             wrap: self.wrap,
             mask_char: self
                 .mask_char
-                .map(|code| Character::from_char_with_font(u32::from(code), &font)),
+                .map(|code| Character::from_char_with_font(u32::from(code), &mut font)),
             shadow: self.shadow,
             shadow_brush: self.shadow_brush,
             font: {
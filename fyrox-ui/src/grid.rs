@@ -339,18 +339,21 @@ impl Control for Grid {
         arrange_dims(&mut columns, final_size.x);
         arrange_dims(&mut rows, final_size.y);
 
+        let right_to_left = ui.flow_direction().is_right_to_left();
+
         for child_handle in self.widget.children() {
             let child = ui.nodes.borrow(*child_handle);
             if let Some(column) = columns.get(child.column()) {
                 if let Some(row) = rows.get(child.row()) {
+                    // Columns are mirrored in right-to-left mode.
+                    let x = if right_to_left {
+                        final_size.x - column.location - column.actual_size
+                    } else {
+                        column.location
+                    };
                     ui.arrange_node(
                         *child_handle,
-                        &Rect::new(
-                            column.location,
-                            row.location,
-                            column.actual_size,
-                            row.actual_size,
-                        ),
+                        &Rect::new(x, row.location, column.actual_size, row.actual_size),
                     );
                 }
             }
@@ -35,7 +35,9 @@ pub mod list_view;
 pub mod menu;
 pub mod message;
 pub mod messagebox;
+pub mod node_graph;
 pub mod numeric;
+pub mod platform;
 pub mod popup;
 pub mod progress_bar;
 pub mod range;
@@ -47,6 +49,7 @@ pub mod stack_panel;
 pub mod tab_control;
 pub mod text;
 pub mod text_box;
+pub mod theme;
 pub mod tree;
 pub mod ttf;
 pub mod utils;
@@ -63,16 +66,21 @@ use crate::{
     core::{
         algebra::{Matrix3, Vector2},
         color::Color,
+        instant,
         math::Rect,
         pool::{Handle, Pool},
         scope_profile,
+        visitor::prelude::*,
     },
     draw::{CommandTexture, Draw, DrawingContext},
     message::{
-        ButtonState, CursorIcon, KeyboardModifiers, MessageDirection, MouseButton, OsEvent,
-        UiMessage,
+        ButtonState, CursorIcon, ImeEvent, KeyboardModifiers, MessageDirection, MouseButton,
+        OsEvent, UiMessage,
     },
+    platform::PlatformServices,
     popup::{Placement, PopupMessage},
+    text::TextMessage,
+    theme::Theme,
     ttf::{Font, FontBuilder, SharedFont},
     widget::{Widget, WidgetBuilder, WidgetMessage},
 };
@@ -80,12 +88,13 @@ use copypasta::ClipboardContext;
 use fxhash::{FxHashMap, FxHashSet};
 use std::{
     any::{Any, TypeId},
-    cell::{Cell, Ref, RefCell, RefMut},
+    cell::{Cell, Ref, RefMut},
     collections::{hash_map::Entry, VecDeque},
     fmt::Debug,
     ops::{Deref, DerefMut, Index, IndexMut},
     rc::Rc,
     sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    time::Duration,
 };
 
 // TODO: Make this part of UserInterface struct.
@@ -129,7 +138,7 @@ pub enum VerticalAlignment {
     Bottom,
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Debug, Visit)]
 pub struct Thickness {
     pub left: f32,
     pub top: f32,
@@ -149,6 +158,65 @@ pub enum Orientation {
     Horizontal,
 }
 
+/// Direction of text and of horizontal arrangement of panels. It is defined by the locale of the user
+/// interface (see [`UserInterface::set_locale`]), but could be also set explicitly using
+/// [`UserInterface::set_flow_direction`].
+///
+/// In right-to-left mode text lines are laid out from right to left and aligned to the right, horizontal
+/// stack panels and grids place their children in mirrored order. Text is not reordered (there is no
+/// bidirectional text support), which means that it is expected to be in the order of reading.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlowDirection {
+    /// Default direction for most of the languages.
+    LeftToRight,
+    /// Direction for Arabic, Hebrew, Persian, etc.
+    RightToLeft,
+}
+
+impl Default for FlowDirection {
+    fn default() -> Self {
+        Self::LeftToRight
+    }
+}
+
+impl FlowDirection {
+    /// Returns flow direction for the given locale in `language[-_]region` format, for example `ar-EG`
+    /// or `he_IL`.
+    pub fn from_locale(locale: &str) -> Self {
+        const RIGHT_TO_LEFT_LANGUAGES: [&str; 12] = [
+            "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ps", "sd", "ug", "ur", "yi",
+        ];
+
+        let language = locale
+            .split(|c| c == '-' || c == '_')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        if RIGHT_TO_LEFT_LANGUAGES.contains(&language.as_str()) {
+            Self::RightToLeft
+        } else {
+            Self::LeftToRight
+        }
+    }
+
+    /// Returns `true` if the direction is [`FlowDirection::RightToLeft`].
+    pub fn is_right_to_left(self) -> bool {
+        self == Self::RightToLeft
+    }
+
+    /// Mirrors the given horizontal alignment if the direction is right-to-left. Stretch alignment is
+    /// treated as left alignment.
+    pub fn apply(self, alignment: HorizontalAlignment) -> HorizontalAlignment {
+        match (self, alignment) {
+            (Self::LeftToRight, _) => alignment,
+            (Self::RightToLeft, HorizontalAlignment::Left)
+            | (Self::RightToLeft, HorizontalAlignment::Stretch) => HorizontalAlignment::Right,
+            (Self::RightToLeft, HorizontalAlignment::Right) => HorizontalAlignment::Left,
+            (Self::RightToLeft, HorizontalAlignment::Center) => HorizontalAlignment::Center,
+        }
+    }
+}
+
 impl Thickness {
     pub fn zero() -> Self {
         Self {
@@ -592,6 +660,85 @@ struct DoubleClickEntry {
     click_count: u32,
 }
 
+/// State of input method editor (IME) that is requested by widgets. It should be passed to the OS
+/// window by the host application, see [`UserInterface::poll_ime_state`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ImeState {
+    /// Whether IME should be enabled or not. Usually it is enabled when a text box is focused.
+    pub allowed: bool,
+    /// Screen position of the caret, IME candidate window should be placed near it.
+    pub position: Vector2<f32>,
+}
+
+/// Defines how the user interface is scaled when the size of the screen changes. The resulting scale is
+/// multiplied with the scale factor set by [`UserInterface::set_scale_factor`], so both could be used at
+/// the same time (for example a scaling mode to support various resolutions and a scale factor as an
+/// "UI scale" option in settings).
+///
+/// Every mode, except [`ScalingMode::ConstantPixelSize`], makes the layout independent of the actual
+/// resolution: widgets are laid out in units of the reference resolution and then scaled to the screen.
+/// It means that a HUD made for 1920x1080 will look the same on 720p and 4K screens.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScalingMode {
+    /// One unit of the layout is always one pixel of the screen. This is the default mode.
+    ConstantPixelSize,
+    /// The user interface is scaled proportionally to the height of the screen, so the height of the root
+    /// canvas is always equal to the reference height while its width depends on the aspect ratio of
+    /// the screen.
+    ScaleWithHeight {
+        /// Height of the screen at which the scale is 1.0.
+        reference_height: f32,
+    },
+    /// The user interface is scaled so the reference resolution fits the screen entirely. The root canvas
+    /// could be larger than the reference resolution along one of the axes, if the aspect ratio of the
+    /// screen differs from the aspect ratio of the reference resolution.
+    Fit {
+        /// Resolution of the screen at which the scale is 1.0.
+        reference_resolution: Vector2<f32>,
+    },
+    /// The user interface is scaled so the reference resolution fills the screen entirely. The root canvas
+    /// could be smaller than the reference resolution along one of the axes, if the aspect ratio of the
+    /// screen differs from the aspect ratio of the reference resolution.
+    Fill {
+        /// Resolution of the screen at which the scale is 1.0.
+        reference_resolution: Vector2<f32>,
+    },
+}
+
+impl Default for ScalingMode {
+    fn default() -> Self {
+        Self::ConstantPixelSize
+    }
+}
+
+impl ScalingMode {
+    /// Calculates scale of the user interface for the given screen size.
+    pub fn scale(&self, screen_size: Vector2<f32>) -> f32 {
+        fn ratio(size: f32, reference: f32) -> f32 {
+            if reference > 0.0 {
+                size / reference
+            } else {
+                1.0
+            }
+        }
+
+        match *self {
+            ScalingMode::ConstantPixelSize => 1.0,
+            ScalingMode::ScaleWithHeight { reference_height } => {
+                ratio(screen_size.y, reference_height)
+            }
+            ScalingMode::Fit {
+                reference_resolution,
+            } => ratio(screen_size.x, reference_resolution.x)
+                .min(ratio(screen_size.y, reference_resolution.y)),
+            ScalingMode::Fill {
+                reference_resolution,
+            } => ratio(screen_size.x, reference_resolution.x)
+                .max(ratio(screen_size.y, reference_resolution.y)),
+        }
+    }
+}
+
 pub struct UserInterface {
     screen_size: Vector2<f32>,
     nodes: Pool<UiNode>,
@@ -612,15 +759,24 @@ pub struct UserInterface {
     mouse_state: MouseState,
     keyboard_modifiers: KeyboardModifiers,
     cursor_icon: CursorIcon,
+    ime_state: ImeState,
+    ime_state_changed: bool,
     active_tooltip: Option<TooltipEntry>,
     preview_set: FxHashSet<Handle<UiNode>>,
-    clipboard: Option<RefCell<ClipboardContext>>,
+    platform: PlatformServices,
     layout_events_receiver: Receiver<LayoutEvent>,
     layout_events_sender: Sender<LayoutEvent>,
     need_update_global_transform: bool,
     pub default_font: SharedFont,
     double_click_entries: FxHashMap<MouseButton, DoubleClickEntry>,
     pub double_click_time_slice: f32,
+    scale_factor: f32,
+    scaling_mode: ScalingMode,
+    effective_scale_factor: f32,
+    theme: Theme,
+    locale: String,
+    flow_direction: FlowDirection,
+    layout_time: Duration,
 }
 
 fn is_on_screen(node: &UiNode, nodes: &Pool<UiNode>) -> bool {
@@ -732,15 +888,24 @@ impl UserInterface {
             mouse_state: Default::default(),
             keyboard_modifiers: Default::default(),
             cursor_icon: Default::default(),
+            ime_state: Default::default(),
+            ime_state_changed: false,
             active_tooltip: Default::default(),
             preview_set: Default::default(),
-            clipboard: ClipboardContext::new().ok().map(RefCell::new),
+            platform: PlatformServices::new(),
             layout_events_receiver,
             layout_events_sender,
             need_update_global_transform: Default::default(),
+            layout_time: Default::default(),
             default_font,
             double_click_entries: Default::default(),
             double_click_time_slice: 0.5, // 500 ms is standard in most operating systems.
+            scale_factor: 1.0,
+            scaling_mode: Default::default(),
+            effective_scale_factor: 1.0,
+            theme: Default::default(),
+            locale: "en".to_string(),
+            flow_direction: Default::default(),
         };
         ui.root_canvas = ui.add_node(UiNode::new(Canvas::new(WidgetBuilder::new().build())));
         ui.keyboard_focus_node = ui.root_canvas;
@@ -820,6 +985,8 @@ impl UserInterface {
     fn update_visual_transform(&mut self) {
         scope_profile!();
 
+        let scale = Matrix3::new_scaling(self.effective_scale_factor);
+
         self.stack.clear();
         self.stack.push(self.root_canvas);
         while let Some(node_handle) = self.stack.pop() {
@@ -840,7 +1007,7 @@ impl UserInterface {
                 let visual_transform = if let Some(parent) = parent {
                     parent.visual_transform * widget.render_transform * layout_transform
                 } else {
-                    widget.render_transform * layout_transform
+                    scale * widget.render_transform * layout_transform
                 };
 
                 widget.visual_transform = visual_transform;
@@ -852,6 +1019,173 @@ impl UserInterface {
         self.screen_size
     }
 
+    /// Returns amount of time that was needed to measure and arrange widgets on the last update.
+    pub fn layout_time(&self) -> Duration {
+        self.layout_time
+    }
+
+    /// Returns size of the screen in units of the root canvas, which is the physical screen size
+    /// divided by the current effective scale factor (see [`Self::effective_scale_factor`]).
+    pub fn logical_screen_size(&self) -> Vector2<f32> {
+        self.screen_size / self.effective_scale_factor
+    }
+
+    /// Sets global scale factor of the user interface. It is independent of the DPI of the OS and
+    /// scales layout, fonts and everything else of every widget. It could be used to offer "UI scale"
+    /// accessibility option in games. Every widget will receive [`WidgetMessage::ScaleFactorChanged`]
+    /// message.
+    ///
+    /// The scale factor is multiplied with the scale of the current [`ScalingMode`].
+    ///
+    /// # Important notes
+    ///
+    /// Text is rasterized at the height of its font, so it could become blurry on large scale factors.
+    /// Use fonts with larger height if you plan to use large scale factors.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor.max(0.01);
+        self.update_effective_scale_factor();
+    }
+
+    /// Returns current global scale factor of the user interface.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Sets new scaling mode of the user interface. See [`ScalingMode`] docs for more info.
+    pub fn set_scaling_mode(&mut self, scaling_mode: ScalingMode) {
+        self.scaling_mode = scaling_mode;
+        self.update_effective_scale_factor();
+    }
+
+    /// Returns current scaling mode of the user interface.
+    pub fn scaling_mode(&self) -> ScalingMode {
+        self.scaling_mode
+    }
+
+    /// Returns the scale that is actually applied to the user interface, it is a product of the scale
+    /// factor and the scale of the current scaling mode for the current screen size.
+    pub fn effective_scale_factor(&self) -> f32 {
+        self.effective_scale_factor
+    }
+
+    fn update_effective_scale_factor(&mut self) {
+        // Keep current scale while the screen is degenerate (for example when a window is minimized),
+        // there is no need to re-layout everything in this case.
+        if self.screen_size.x <= 0.0 || self.screen_size.y <= 0.0 {
+            return;
+        }
+
+        let effective_scale_factor =
+            (self.scale_factor * self.scaling_mode.scale(self.screen_size)).max(0.01);
+        if self.effective_scale_factor != effective_scale_factor {
+            self.effective_scale_factor = effective_scale_factor;
+            self.need_update_global_transform = true;
+            self.nodes[self.root_canvas].invalidate_layout();
+            self.broadcast(|handle| {
+                WidgetMessage::scale_factor_changed(
+                    handle,
+                    MessageDirection::FromWidget,
+                    effective_scale_factor,
+                )
+            });
+        }
+    }
+
+    /// Sets new theme of the user interface. Styles of the theme are applied to every widget that uses
+    /// them, then every widget will receive [`WidgetMessage::ThemeChanged`] message, so it could update
+    /// its appearance. See [`Theme`] docs for more info.
+    pub fn set_theme(&mut self, theme: Theme) {
+        if self.theme != theme {
+            self.theme = theme;
+            if let Some(font) = self.theme.font.clone() {
+                self.default_font = font;
+            }
+            for (handle, _) in self.nodes.pair_iter() {
+                self.apply_style(handle);
+            }
+            self.broadcast(|handle| {
+                WidgetMessage::theme_changed(handle, MessageDirection::FromWidget)
+            });
+        }
+    }
+
+    fn apply_style(&self, handle: Handle<UiNode>) {
+        let style = match self.nodes[handle]
+            .style()
+            .and_then(|name| self.theme.style(name))
+        {
+            Some(style) => style,
+            None => return,
+        };
+
+        if let Some(background) = style.background {
+            self.send_message(WidgetMessage::background(
+                handle,
+                MessageDirection::ToWidget,
+                Brush::Solid(background),
+            ));
+        }
+        if let Some(foreground) = style.foreground {
+            self.send_message(WidgetMessage::foreground(
+                handle,
+                MessageDirection::ToWidget,
+                Brush::Solid(foreground),
+            ));
+        }
+        if let Some(margin) = style.margin {
+            self.send_message(WidgetMessage::margin(
+                handle,
+                MessageDirection::ToWidget,
+                margin,
+            ));
+        }
+        if let Some(font) = style.font.clone() {
+            self.send_message(TextMessage::font(handle, MessageDirection::ToWidget, font));
+        }
+    }
+
+    /// Returns current theme of the user interface.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Sets new locale of the user interface (for example `en-US` or `ar-EG`), it also sets flow direction
+    /// that is used by the locale. See [`FlowDirection`] docs for more info.
+    pub fn set_locale<S: AsRef<str>>(&mut self, locale: S) {
+        self.locale = locale.as_ref().to_owned();
+        self.set_flow_direction(FlowDirection::from_locale(&self.locale));
+    }
+
+    /// Returns current locale of the user interface.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Sets new flow direction of the user interface, the layout of every widget will be recalculated.
+    /// This method could be used to override flow direction defined by the locale.
+    pub fn set_flow_direction(&mut self, flow_direction: FlowDirection) {
+        if self.flow_direction != flow_direction {
+            self.flow_direction = flow_direction;
+            for node in self.nodes.iter() {
+                node.invalidate_layout();
+            }
+        }
+    }
+
+    /// Returns current flow direction of the user interface.
+    pub fn flow_direction(&self) -> FlowDirection {
+        self.flow_direction
+    }
+
+    fn broadcast<F>(&self, make_message: F)
+    where
+        F: Fn(Handle<UiNode>) -> UiMessage,
+    {
+        for (handle, _) in self.nodes.pair_iter() {
+            self.send_message(make_message(handle));
+        }
+    }
+
     fn handle_layout_events(&mut self) {
         fn invalidate_recursive_up(
             nodes: &Pool<UiNode>,
@@ -889,18 +1223,24 @@ impl UserInterface {
     pub fn update(&mut self, screen_size: Vector2<f32>, dt: f32) {
         scope_profile!();
 
-        self.screen_size = screen_size;
+        if self.screen_size != screen_size {
+            self.screen_size = screen_size;
+            self.update_effective_scale_factor();
+        }
 
         for entry in self.double_click_entries.values_mut() {
             entry.timer -= dt;
         }
 
+        let layout_start_time = instant::Instant::now();
+
         self.handle_layout_events();
 
-        self.measure_node(self.root_canvas, screen_size);
+        let logical_size = self.logical_screen_size();
+        self.measure_node(self.root_canvas, logical_size);
         let arrangement_changed = self.arrange_node(
             self.root_canvas,
-            &Rect::new(0.0, 0.0, screen_size.x, screen_size.y),
+            &Rect::new(0.0, 0.0, logical_size.x, logical_size.y),
         );
 
         if self.need_update_global_transform {
@@ -915,6 +1255,8 @@ impl UserInterface {
             );
         }
 
+        self.layout_time = instant::Instant::now() - layout_start_time;
+
         let sender = self.sender.clone();
         for node in self.nodes.iter_mut() {
             node.update(dt, &sender)
@@ -942,6 +1284,30 @@ impl UserInterface {
         self.cursor_icon
     }
 
+    /// Sets new state of input method editor (IME), it is used by text input widgets.
+    pub fn set_ime_state(&mut self, state: ImeState) {
+        if self.ime_state != state {
+            self.ime_state = state;
+            self.ime_state_changed = true;
+        }
+    }
+
+    /// Returns current state of input method editor (IME).
+    pub fn ime_state(&self) -> ImeState {
+        self.ime_state
+    }
+
+    /// Returns new state of input method editor (IME) if it has changed since last call. The state
+    /// should be passed to the OS window.
+    pub fn poll_ime_state(&mut self) -> Option<ImeState> {
+        if self.ime_state_changed {
+            self.ime_state_changed = false;
+            Some(self.ime_state)
+        } else {
+            None
+        }
+    }
+
     pub fn draw(&mut self) -> &DrawingContext {
         scope_profile!();
 
@@ -1002,11 +1368,16 @@ impl UserInterface {
     }
 
     pub fn clipboard(&self) -> Option<Ref<ClipboardContext>> {
-        self.clipboard.as_ref().map(|v| v.borrow())
+        self.platform.clipboard()
     }
 
     pub fn clipboard_mut(&self) -> Option<RefMut<ClipboardContext>> {
-        self.clipboard.as_ref().map(|v| v.borrow_mut())
+        self.platform.clipboard_mut()
+    }
+
+    /// Returns a reference to platform services (clipboard and file dialogs).
+    pub fn platform(&self) -> &PlatformServices {
+        &self.platform
     }
 
     pub fn arrange_node(&self, handle: Handle<UiNode>, final_rect: &Rect<f32>) -> bool {
@@ -1625,7 +1996,7 @@ impl UserInterface {
                                 let parent_size = if parent.is_some() {
                                     self.node(parent).actual_initial_size()
                                 } else {
-                                    self.screen_size
+                                    self.logical_screen_size()
                                 };
 
                                 self.send_message(WidgetMessage::desired_position(
@@ -2067,6 +2438,40 @@ impl UserInterface {
                     event_processed = true;
                 }
             }
+            OsEvent::Ime(ime_event) => {
+                if self.keyboard_focus_node.is_some() {
+                    match ime_event {
+                        ImeEvent::Preedit { text, cursor } => {
+                            self.send_message(WidgetMessage::ime_preedit(
+                                self.keyboard_focus_node,
+                                MessageDirection::FromWidget,
+                                text.clone(),
+                                *cursor,
+                            ));
+                        }
+                        ImeEvent::Commit(text) => {
+                            // Committed text is handled the same way as regular typing.
+                            for unicode in text.chars() {
+                                self.send_message(WidgetMessage::text(
+                                    self.keyboard_focus_node,
+                                    MessageDirection::FromWidget,
+                                    unicode,
+                                ));
+                            }
+                        }
+                        ImeEvent::Enabled | ImeEvent::Disabled => {
+                            self.send_message(WidgetMessage::ime_preedit(
+                                self.keyboard_focus_node,
+                                MessageDirection::FromWidget,
+                                Default::default(),
+                                None,
+                            ));
+                        }
+                    }
+
+                    event_processed = true;
+                }
+            }
             &OsEvent::KeyboardModifiers(modifiers) => {
                 // TODO: Is message needed for focused node?
                 self.keyboard_modifiers = modifiers;
@@ -2116,6 +2521,7 @@ impl UserInterface {
             self.preview_set.insert(node_handle);
         }
         node.handle = node_handle;
+        self.apply_style(node_handle);
         node_handle
     }
 
@@ -2518,13 +2924,20 @@ fn transform_size(transform_space_bounds: Vector2<f32>, matrix: &Matrix3<f32>) -
 mod test {
     use crate::{
         border::BorderBuilder,
-        core::algebra::{Rotation2, UnitComplex, Vector2},
+        brush::Brush,
+        core::{
+            algebra::{Rotation2, UnitComplex, Vector2},
+            color::Color,
+        },
+        message::ImeEvent,
         message::MessageDirection,
+        stack_panel::StackPanelBuilder,
         text::TextMessage,
-        text_box::TextBoxBuilder,
+        text_box::{TextBox, TextBoxBuilder},
+        theme::{Style, Theme},
         transform_size,
         widget::{WidgetBuilder, WidgetMessage},
-        OsEvent, UserInterface,
+        FlowDirection, Orientation, OsEvent, ScalingMode, Thickness, UserInterface,
     };
 
     #[test]
@@ -2556,6 +2969,166 @@ mod test {
         assert_eq!(actual_position, expected_position);
     }
 
+    #[test]
+    fn test_scale_factor() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let widget = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_desired_position(Vector2::new(100.0, 100.0))
+                .with_width(100.0)
+                .with_height(100.0),
+        )
+        .build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        ui.draw(); // Hit testing uses drawing commands.
+        assert_ne!(ui.hit_test(Vector2::new(250.0, 250.0)), widget);
+
+        ui.set_scale_factor(2.0);
+        ui.update(screen_size, 0.0);
+        ui.draw();
+        assert_eq!(ui.logical_screen_size(), Vector2::new(500.0, 500.0));
+        assert_eq!(
+            ui.node(widget).actual_local_size(),
+            Vector2::new(100.0, 100.0)
+        );
+        assert_eq!(
+            ui.node(widget).screen_bounds().size,
+            Vector2::new(200.0, 200.0)
+        );
+        assert_eq!(ui.hit_test(Vector2::new(250.0, 250.0)), widget);
+        assert_eq!(
+            ui.screen_to_root_canvas_space(Vector2::new(250.0, 250.0)),
+            Vector2::new(125.0, 125.0)
+        );
+    }
+
+    #[test]
+    fn test_scaling_mode() {
+        let mut ui = UserInterface::new(Vector2::new(1280.0, 720.0));
+        ui.set_scaling_mode(ScalingMode::ScaleWithHeight {
+            reference_height: 1080.0,
+        });
+        ui.update(Vector2::new(1280.0, 720.0), 0.0);
+        assert_eq!(ui.logical_screen_size().y, 1080.0);
+
+        // 4K screen.
+        ui.update(Vector2::new(3840.0, 2160.0), 0.0);
+        assert_eq!(ui.effective_scale_factor(), 2.0);
+        assert_eq!(ui.logical_screen_size(), Vector2::new(1920.0, 1080.0));
+
+        let reference_resolution = Vector2::new(1920.0, 1080.0);
+        ui.set_scaling_mode(ScalingMode::Fit {
+            reference_resolution,
+        });
+        ui.update(Vector2::new(1920.0, 2160.0), 0.0);
+        assert_eq!(ui.effective_scale_factor(), 1.0);
+
+        ui.set_scaling_mode(ScalingMode::Fill {
+            reference_resolution,
+        });
+        assert_eq!(ui.effective_scale_factor(), 2.0);
+
+        // Scale factor is applied on top of the scaling mode.
+        ui.set_scale_factor(0.5);
+        assert_eq!(ui.effective_scale_factor(), 1.0);
+
+        ui.set_scaling_mode(ScalingMode::ConstantPixelSize);
+        assert_eq!(ui.effective_scale_factor(), 0.5);
+    }
+
+    #[test]
+    fn test_theme_switching() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let widget = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+        while ui.poll_message().is_some() {}
+
+        ui.set_theme(Theme::dark());
+        assert!(ui.poll_message().is_none());
+
+        ui.set_theme(Theme::high_contrast());
+        assert_eq!(ui.theme(), &Theme::high_contrast());
+        let mut notified = Vec::new();
+        while let Some(message) = ui.poll_message() {
+            if let Some(WidgetMessage::ThemeChanged) = message.data() {
+                notified.push(message.destination());
+            }
+        }
+        assert!(notified.contains(&widget));
+        assert!(notified.contains(&ui.root()));
+    }
+
+    #[test]
+    fn test_right_to_left() {
+        assert_eq!(
+            FlowDirection::from_locale("ar-EG"),
+            FlowDirection::RightToLeft
+        );
+        assert_eq!(
+            FlowDirection::from_locale("he_IL"),
+            FlowDirection::RightToLeft
+        );
+        assert_eq!(
+            FlowDirection::from_locale("en-US"),
+            FlowDirection::LeftToRight
+        );
+
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+        let ctx = &mut ui.build_ctx();
+        let first = BorderBuilder::new(WidgetBuilder::new().with_width(100.0)).build(ctx);
+        let second = BorderBuilder::new(WidgetBuilder::new().with_width(50.0)).build(ctx);
+        StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_width(400.0)
+                .with_children([first, second]),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.node(first).actual_local_position().x, 0.0);
+        assert_eq!(ui.node(second).actual_local_position().x, 100.0);
+
+        ui.set_locale("ar");
+        assert_eq!(ui.flow_direction(), FlowDirection::RightToLeft);
+        ui.update(screen_size, 0.0);
+        assert_eq!(ui.node(first).actual_local_position().x, 300.0);
+        assert_eq!(ui.node(second).actual_local_position().x, 250.0);
+    }
+
+    #[test]
+    fn test_theme_styles() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+
+        let style = |color: Color| Style {
+            background: Some(color),
+            margin: Some(Thickness::uniform(2.0)),
+            ..Default::default()
+        };
+
+        let mut theme = Theme::dark();
+        theme.set_style("Panel", style(Color::RED));
+        ui.set_theme(theme);
+
+        let widget =
+            BorderBuilder::new(WidgetBuilder::new().with_style("Panel")).build(&mut ui.build_ctx());
+        let plain = BorderBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(widget).background(), Brush::Solid(Color::RED));
+        assert_eq!(ui.node(widget).margin(), Thickness::uniform(2.0));
+        assert_ne!(ui.node(plain).background(), Brush::Solid(Color::RED));
+
+        let mut theme = Theme::light();
+        theme.set_style("Panel", style(Color::GREEN));
+        ui.set_theme(theme);
+        while ui.poll_message().is_some() {}
+        assert_eq!(ui.node(widget).background(), Brush::Solid(Color::GREEN));
+    }
+
     #[test]
     fn test_keyboard_focus() {
         let screen_size = Vector2::new(1000.0, 1000.0);
@@ -2611,4 +3184,44 @@ mod test {
 
         assert!(ui.poll_message().is_none());
     }
+
+    #[test]
+    fn test_ime_input() {
+        let screen_size = Vector2::new(1000.0, 1000.0);
+        let mut ui = UserInterface::new(screen_size);
+
+        let text_box = TextBoxBuilder::new(WidgetBuilder::new()).build(&mut ui.build_ctx());
+        ui.update(screen_size, 0.0);
+
+        ui.send_message(WidgetMessage::focus(text_box, MessageDirection::ToWidget));
+        while ui.poll_message().is_some() {}
+
+        // Focused text box must request IME.
+        assert!(ui.poll_ime_state().unwrap().allowed);
+        assert!(ui.poll_ime_state().is_none());
+
+        ui.process_os_event(&OsEvent::Ime(ImeEvent::Preedit {
+            text: "ni".to_string(),
+            cursor: Some((2, 2)),
+        }));
+        while ui.poll_message().is_some() {}
+        let text_box_ref = ui.node(text_box).query_component::<TextBox>().unwrap();
+        assert_eq!(text_box_ref.ime_preedit, "ni");
+        assert_eq!(text_box_ref.ime_preedit_cursor, Some(2));
+        assert_eq!(text_box_ref.text(), "");
+
+        ui.process_os_event(&OsEvent::Ime(ImeEvent::Preedit {
+            text: Default::default(),
+            cursor: None,
+        }));
+        ui.process_os_event(&OsEvent::Ime(ImeEvent::Commit("你好".to_string())));
+        while ui.poll_message().is_some() {}
+        let text_box_ref = ui.node(text_box).query_component::<TextBox>().unwrap();
+        assert!(text_box_ref.ime_preedit.is_empty());
+        assert_eq!(text_box_ref.text(), "你好");
+
+        ui.send_message(WidgetMessage::unfocus(text_box, MessageDirection::ToWidget));
+        while ui.poll_message().is_some() {}
+        assert!(!ui.ime_state().allowed);
+    }
 }
@@ -271,6 +271,27 @@ pub enum MouseButton {
     Other(u16),
 }
 
+/// An event of input method editor (IME). IME is used to type text in languages with large amount of
+/// characters (Chinese, Japanese, Korean, etc.) - a user types a sequence of keys, which is then
+/// converted to one or more characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// IME was enabled.
+    Enabled,
+    /// A user is composing text, it should be shown at the caret position, but not inserted yet.
+    /// Empty text means that the composition was cancelled.
+    Preedit {
+        /// Text that is being composed.
+        text: String,
+        /// Byte range of the cursor in the composed text, `None` means that the cursor is hidden.
+        cursor: Option<(usize, usize)>,
+    },
+    /// Composition is finished and the text must be inserted.
+    Commit(String),
+    /// IME was disabled.
+    Disabled,
+}
+
 pub enum OsEvent {
     MouseInput {
         button: MouseButton,
@@ -286,6 +307,7 @@ pub enum OsEvent {
     Character(char),
     KeyboardModifiers(KeyboardModifiers),
     MouseWheel(f32, f32),
+    Ime(ImeEvent),
 }
 
 #[derive(
@@ -1,29 +1,25 @@
 use crate::{
-    absm::{
-        connection::{self, Connection},
-        node::AbsmBaseNode,
-        segment::SegmentMessage,
-        selectable::{Selectable, SelectableMessage},
-        socket::{Socket, SocketDirection, SocketMessage},
-        transition::{self, TransitionView},
-    },
-    utils::fetch_node_screen_center_ui,
-};
-use fyrox::{
+    brush::Brush,
     core::{
         algebra::{Matrix3, Point2, Vector2},
         color::Color,
         math::{round_to_step, Rect},
         pool::Handle,
     },
-    gui::{
-        brush::Brush,
-        define_constructor, define_widget_deref,
-        draw::{CommandTexture, Draw, DrawingContext},
-        message::{MessageDirection, MouseButton, UiMessage},
-        widget::{Widget, WidgetBuilder, WidgetMessage},
-        BuildContext, Control, UiNode, UserInterface,
+    define_constructor, define_widget_deref,
+    draw::{CommandTexture, Draw, DrawingContext},
+    message::{MessageDirection, MouseButton, UiMessage},
+    node_graph::{
+        connection::{self, Connection},
+        fetch_node_screen_center_ui,
+        node::GraphBaseNode,
+        segment::SegmentMessage,
+        selectable::{Selectable, SelectableMessage},
+        socket::{Socket, SocketDirection, SocketMessage},
+        transition::{self, TransitionView},
     },
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
 };
 use std::{
     any::{Any, TypeId},
@@ -32,19 +28,19 @@ use std::{
 };
 
 #[derive(Debug, Clone, PartialEq)]
-pub(super) struct Entry {
+pub struct Entry {
     pub node: Handle<UiNode>,
     pub initial_position: Vector2<f32>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub(super) struct DragContext {
+pub struct DragContext {
     initial_cursor_position: Vector2<f32>,
     entries: Vec<Entry>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub(super) enum Mode {
+pub enum Mode {
     Normal,
     Drag {
         drag_context: DragContext,
@@ -62,7 +58,7 @@ pub(super) enum Mode {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub(super) enum AbsmCanvasMessage {
+pub enum NodeGraphCanvasMessage {
     SwitchMode(Mode),
     CommitTransition {
         source_node: Handle<UiNode>,
@@ -79,17 +75,17 @@ pub(super) enum AbsmCanvasMessage {
     ForceSyncDependentObjects,
 }
 
-impl AbsmCanvasMessage {
-    define_constructor!(AbsmCanvasMessage:SwitchMode => fn switch_mode(Mode), layout: false);
-    define_constructor!(AbsmCanvasMessage:CommitTransition => fn commit_transition(source_node: Handle<UiNode>, dest_node: Handle<UiNode>), layout: false);
-    define_constructor!(AbsmCanvasMessage:CommitConnection => fn commit_connection(source_socket: Handle<UiNode>, dest_socket: Handle<UiNode>), layout: false);
-    define_constructor!(AbsmCanvasMessage:CommitDrag => fn commit_drag(entries: Vec<Entry>), layout: false);
-    define_constructor!(AbsmCanvasMessage:SelectionChanged => fn selection_changed(Vec<Handle<UiNode>>), layout: false);
-    define_constructor!(AbsmCanvasMessage:ForceSyncDependentObjects => fn force_sync_dependent_objects(), layout: true);
+impl NodeGraphCanvasMessage {
+    define_constructor!(NodeGraphCanvasMessage:SwitchMode => fn switch_mode(Mode), layout: false);
+    define_constructor!(NodeGraphCanvasMessage:CommitTransition => fn commit_transition(source_node: Handle<UiNode>, dest_node: Handle<UiNode>), layout: false);
+    define_constructor!(NodeGraphCanvasMessage:CommitConnection => fn commit_connection(source_socket: Handle<UiNode>, dest_socket: Handle<UiNode>), layout: false);
+    define_constructor!(NodeGraphCanvasMessage:CommitDrag => fn commit_drag(entries: Vec<Entry>), layout: false);
+    define_constructor!(NodeGraphCanvasMessage:SelectionChanged => fn selection_changed(Vec<Handle<UiNode>>), layout: false);
+    define_constructor!(NodeGraphCanvasMessage:ForceSyncDependentObjects => fn force_sync_dependent_objects(), layout: true);
 }
 
 #[derive(Clone)]
-pub struct AbsmCanvas {
+pub struct NodeGraphCanvas {
     widget: Widget,
     selection: Vec<Handle<UiNode>>,
     view_position: Vector2<f32>,
@@ -102,9 +98,9 @@ pub struct AbsmCanvas {
     lmb_released_node: Cell<Handle<UiNode>>,
 }
 
-define_widget_deref!(AbsmCanvas);
+define_widget_deref!(NodeGraphCanvas);
 
-impl AbsmCanvas {
+impl NodeGraphCanvas {
     pub fn point_to_local_space(&self, point: Vector2<f32>) -> Vector2<f32> {
         self.visual_transform()
             .try_inverse()
@@ -157,7 +153,7 @@ impl AbsmCanvas {
 
             self.selection = new_selection.to_vec();
 
-            ui.send_message(AbsmCanvasMessage::selection_changed(
+            ui.send_message(NodeGraphCanvasMessage::selection_changed(
                 self.handle(),
                 MessageDirection::FromWidget,
                 self.selection.clone(),
@@ -294,7 +290,7 @@ impl AbsmCanvas {
     }
 }
 
-impl Control for AbsmCanvas {
+impl Control for NodeGraphCanvas {
     fn query_component(&self, type_id: TypeId) -> Option<&dyn Any> {
         if type_id == TypeId::of::<Self>() {
             Some(self)
@@ -445,13 +441,13 @@ impl Control for AbsmCanvas {
                 ui.capture_mouse(self.handle());
             } else if *button == MouseButton::Left && !message.handled() {
                 let dest_node_handle =
-                    self.fetch_dest_node_component::<AbsmBaseNode>(message.destination(), ui);
+                    self.fetch_dest_node_component::<GraphBaseNode>(message.destination(), ui);
 
                 match self.mode {
                     Mode::CreateTransition { source, .. } => {
                         if dest_node_handle.is_some() {
                             // Commit creation.
-                            ui.send_message(AbsmCanvasMessage::commit_transition(
+                            ui.send_message(NodeGraphCanvasMessage::commit_transition(
                                 self.handle(),
                                 MessageDirection::FromWidget,
                                 source,
@@ -482,7 +478,7 @@ impl Control for AbsmCanvas {
                 match self.mode {
                     Mode::Drag { ref drag_context } => {
                         if self.screen_to_local(*pos) != drag_context.initial_cursor_position {
-                            ui.send_message(AbsmCanvasMessage::commit_drag(
+                            ui.send_message(NodeGraphCanvasMessage::commit_drag(
                                 self.handle(),
                                 MessageDirection::FromWidget,
                                 drag_context.entries.clone(),
@@ -517,7 +513,7 @@ impl Control for AbsmCanvas {
                                     SocketDirection::Output => (dest_socket_handle, source),
                                 };
 
-                                ui.send_message(AbsmCanvasMessage::commit_connection(
+                                ui.send_message(NodeGraphCanvasMessage::commit_connection(
                                     self.handle(),
                                     MessageDirection::FromWidget,
                                     child,
@@ -576,19 +572,19 @@ impl Control for AbsmCanvas {
             self.view_position -= (new_cursor_pos - cursor_pos).scale(self.zoom);
 
             self.update_transform(ui);
-        } else if let Some(msg) = message.data::<AbsmCanvasMessage>() {
+        } else if let Some(msg) = message.data::<NodeGraphCanvasMessage>() {
             if message.direction() == MessageDirection::ToWidget
                 && message.destination() == self.handle()
             {
                 match msg {
-                    AbsmCanvasMessage::SwitchMode(mode) => {
+                    NodeGraphCanvasMessage::SwitchMode(mode) => {
                         // TODO: Check if other mode is active.
                         self.mode = mode.clone();
                     }
-                    AbsmCanvasMessage::SelectionChanged(new_selection) => {
+                    NodeGraphCanvasMessage::SelectionChanged(new_selection) => {
                         self.set_selection(new_selection, ui);
                     }
-                    AbsmCanvasMessage::ForceSyncDependentObjects => {
+                    NodeGraphCanvasMessage::ForceSyncDependentObjects => {
                         self.force_sync_dependent_objects(ui);
                     }
                     _ => (),
@@ -601,7 +597,7 @@ impl Control for AbsmCanvas {
                     .query_component::<Socket>()
                     .unwrap();
 
-                ui.send_message(AbsmCanvasMessage::switch_mode(
+                ui.send_message(NodeGraphCanvasMessage::switch_mode(
                     self.handle(),
                     MessageDirection::ToWidget,
                     Mode::CreateConnection {
@@ -614,7 +610,7 @@ impl Control for AbsmCanvas {
         } else if let Some(WidgetMessage::DesiredPosition(_)) = message.data() {
             if ui
                 .node(message.destination())
-                .has_component::<AbsmBaseNode>()
+                .has_component::<GraphBaseNode>()
             {
                 let moved_node = message.destination();
                 self.sync_connections_ends(moved_node, ui, false);
@@ -632,17 +628,17 @@ impl Control for AbsmCanvas {
     }
 }
 
-pub struct AbsmCanvasBuilder {
+pub struct NodeGraphCanvasBuilder {
     widget_builder: WidgetBuilder,
 }
 
-impl AbsmCanvasBuilder {
+impl NodeGraphCanvasBuilder {
     pub fn new(widget_builder: WidgetBuilder) -> Self {
         Self { widget_builder }
     }
 
     pub fn build(self, ctx: &mut BuildContext) -> Handle<UiNode> {
-        let canvas = AbsmCanvas {
+        let canvas = NodeGraphCanvas {
             widget: self
                 .widget_builder
                 .with_preview_messages(true)
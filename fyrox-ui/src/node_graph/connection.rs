@@ -1,14 +1,12 @@
-use crate::{absm::segment::Segment, utils::fetch_node_screen_center};
-use fyrox::{
+use crate::{
+    brush::Brush,
     core::{algebra::Vector2, color::Color, math::Rect, pool::Handle},
-    gui::{
-        brush::Brush,
-        define_widget_deref,
-        draw::{CommandTexture, Draw, DrawingContext},
-        message::{MessageDirection, UiMessage},
-        widget::{Widget, WidgetBuilder, WidgetMessage},
-        BuildContext, Control, UiNode, UserInterface,
-    },
+    define_widget_deref,
+    draw::{CommandTexture, Draw, DrawingContext},
+    message::{MessageDirection, UiMessage},
+    node_graph::{fetch_node_screen_center, segment::Segment},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
 };
 use std::{
     any::{Any, TypeId},
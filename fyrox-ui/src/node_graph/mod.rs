@@ -0,0 +1,45 @@
+//! Node graph is a set of widgets for visual graph editors (state machines, material graphs, dialogue graphs,
+//! behavior trees, etc.). It contains the following parts:
+//!
+//! - [`canvas::NodeGraphCanvas`] - an infinite canvas with grid, panning (middle mouse button), zooming (mouse
+//!   wheel), selection and dragging of nodes, interactive creation of connections and transitions.
+//! - [`node::GraphNode`] - a node with a name, input sockets and an output socket.
+//! - [`socket::Socket`] - an input or output pin of a node, it is used to create connections.
+//! - [`connection::Connection`] - a bezier curve between two sockets.
+//! - [`transition::TransitionView`] - a directed arrow between two nodes.
+//!
+//! Widgets do not modify any data model by themselves, instead the canvas sends `Commit*` messages (see
+//! [`canvas::NodeGraphCanvasMessage`]) and a user should modify the data model and synchronize the widgets
+//! with it. Sockets and transitions store type-erased handles to entities of the data model, nodes store
+//! typed handles.
+
+use crate::{
+    core::{algebra::Vector2, pool::Handle},
+    BuildContext, UiNode, UserInterface,
+};
+
+pub mod canvas;
+pub mod connection;
+pub mod node;
+pub mod segment;
+pub mod selectable;
+pub mod socket;
+pub mod transition;
+
+fn fetch_node_center(handle: Handle<UiNode>, ctx: &BuildContext) -> Vector2<f32> {
+    ctx.try_get_node(handle)
+        .map(|node| node.center())
+        .unwrap_or_default()
+}
+
+fn fetch_node_screen_center(handle: Handle<UiNode>, ctx: &BuildContext) -> Vector2<f32> {
+    ctx.try_get_node(handle)
+        .map(|node| node.screen_bounds().center())
+        .unwrap_or_default()
+}
+
+fn fetch_node_screen_center_ui(handle: Handle<UiNode>, ui: &UserInterface) -> Vector2<f32> {
+    ui.try_get_node(handle)
+        .map(|node| node.screen_bounds().center())
+        .unwrap_or_default()
+}
@@ -1,35 +1,37 @@
-use crate::absm::{
-    selectable::{Selectable, SelectableMessage},
-    BORDER_COLOR, NORMAL_BACKGROUND, SELECTED_BACKGROUND,
-};
-use fyrox::{
+use crate::{
+    border::{BorderBuilder, BorderMessage},
+    brush::Brush,
+    button::{ButtonBuilder, ButtonMessage},
     core::{color::Color, pool::Handle},
-    gui::{
-        border::{BorderBuilder, BorderMessage},
-        brush::Brush,
-        button::{ButtonBuilder, ButtonMessage},
-        define_constructor,
-        grid::{Column, GridBuilder, Row},
-        message::{MessageDirection, MouseButton, UiMessage},
-        stack_panel::StackPanelBuilder,
-        text::{TextBuilder, TextMessage},
-        widget::{Widget, WidgetBuilder, WidgetMessage},
-        BuildContext, Control, HorizontalAlignment, Thickness, UiNode, UserInterface,
-        VerticalAlignment,
-    },
+    define_constructor,
+    grid::{Column, GridBuilder, Row},
+    message::{MessageDirection, MouseButton, UiMessage},
+    node_graph::selectable::{Selectable, SelectableMessage},
+    stack_panel::StackPanelBuilder,
+    text::{TextBuilder, TextMessage},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, HorizontalAlignment, Thickness, UiNode, UserInterface,
+    VerticalAlignment,
 };
 use std::{
     any::{Any, TypeId},
     ops::{Deref, DerefMut},
 };
 
+/// Default background color of a graph node.
+pub const NORMAL_BACKGROUND: Color = Color::opaque(60, 60, 60);
+/// Default background color of a selected graph node.
+pub const SELECTED_BACKGROUND: Color = Color::opaque(80, 80, 80);
+/// Default border color of a graph node.
+pub const BORDER_COLOR: Color = Color::opaque(70, 70, 70);
+
 #[derive(Clone)]
-pub struct AbsmBaseNode {
+pub struct GraphBaseNode {
     pub input_sockets: Vec<Handle<UiNode>>,
     pub output_socket: Handle<UiNode>,
 }
 
-pub struct AbsmNode<T>
+pub struct GraphNode<T>
 where
     T: 'static,
 {
@@ -38,7 +40,7 @@ where
     selectable: Selectable,
     pub name_value: String,
     pub model_handle: Handle<T>,
-    pub base: AbsmBaseNode,
+    pub base: GraphBaseNode,
     pub add_input: Handle<UiNode>,
     input_sockets_panel: Handle<UiNode>,
     normal_color: Color,
@@ -46,7 +48,7 @@ where
     name: Handle<UiNode>,
 }
 
-impl<T> Clone for AbsmNode<T>
+impl<T> Clone for GraphNode<T>
 where
     T: 'static,
 {
@@ -67,7 +69,7 @@ where
     }
 }
 
-impl<T> Deref for AbsmNode<T>
+impl<T> Deref for GraphNode<T>
 where
     T: 'static,
 {
@@ -78,7 +80,7 @@ where
     }
 }
 
-impl<T> DerefMut for AbsmNode<T>
+impl<T> DerefMut for GraphNode<T>
 where
     T: 'static,
 {
@@ -87,7 +89,7 @@ where
     }
 }
 
-impl<T> AbsmNode<T>
+impl<T> GraphNode<T>
 where
     T: 'static,
 {
@@ -105,7 +107,7 @@ where
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum AbsmNodeMessage {
+pub enum GraphNodeMessage {
     Name(String),
     Enter,
     AddInput,
@@ -115,17 +117,17 @@ pub enum AbsmNodeMessage {
     SetActive(bool),
 }
 
-impl AbsmNodeMessage {
-    define_constructor!(AbsmNodeMessage:Name => fn name(String), layout: false);
-    define_constructor!(AbsmNodeMessage:Enter => fn enter(), layout: false);
-    define_constructor!(AbsmNodeMessage:AddInput => fn add_input(), layout: false);
-    define_constructor!(AbsmNodeMessage:InputSockets => fn input_sockets(Vec<Handle<UiNode>>), layout: false);
-    define_constructor!(AbsmNodeMessage:NormalColor => fn normal_color(Color), layout: false);
-    define_constructor!(AbsmNodeMessage:SelectedColor => fn selected_color(Color), layout: false);
-    define_constructor!(AbsmNodeMessage:SetActive => fn set_active(bool), layout: false);
+impl GraphNodeMessage {
+    define_constructor!(GraphNodeMessage:Name => fn name(String), layout: false);
+    define_constructor!(GraphNodeMessage:Enter => fn enter(), layout: false);
+    define_constructor!(GraphNodeMessage:AddInput => fn add_input(), layout: false);
+    define_constructor!(GraphNodeMessage:InputSockets => fn input_sockets(Vec<Handle<UiNode>>), layout: false);
+    define_constructor!(GraphNodeMessage:NormalColor => fn normal_color(Color), layout: false);
+    define_constructor!(GraphNodeMessage:SelectedColor => fn selected_color(Color), layout: false);
+    define_constructor!(GraphNodeMessage:SetActive => fn set_active(bool), layout: false);
 }
 
-impl<T> Control for AbsmNode<T>
+impl<T> Control for GraphNode<T>
 where
     T: 'static,
 {
@@ -134,7 +136,7 @@ where
             Some(self)
         } else if type_id == TypeId::of::<Selectable>() {
             Some(&self.selectable)
-        } else if type_id == TypeId::of::<AbsmBaseNode>() {
+        } else if type_id == TypeId::of::<GraphBaseNode>() {
             Some(&self.base)
         } else {
             None
@@ -160,67 +162,61 @@ where
             }
         } else if let Some(WidgetMessage::DoubleClick { button }) = message.data() {
             if !message.handled() && *button == MouseButton::Left {
-                ui.send_message(AbsmNodeMessage::enter(
+                ui.send_message(GraphNodeMessage::enter(
                     self.handle(),
                     MessageDirection::FromWidget,
                 ));
             }
         } else if let Some(ButtonMessage::Click) = message.data() {
             if message.destination() == self.add_input {
-                ui.send_message(AbsmNodeMessage::add_input(
+                ui.send_message(GraphNodeMessage::add_input(
                     self.handle(),
                     MessageDirection::FromWidget,
                 ));
             }
-        } else if let Some(msg) = message.data::<AbsmNodeMessage>() {
+        } else if let Some(msg) = message.data::<GraphNodeMessage>() {
             if message.destination == self.handle()
                 && message.direction() == MessageDirection::ToWidget
             {
                 match msg {
-                    AbsmNodeMessage::InputSockets(input_sockets) => {
-                        if input_sockets != &self.base.input_sockets {
-                            for &child in ui.node(self.input_sockets_panel).children() {
-                                ui.send_message(WidgetMessage::remove(
-                                    child,
-                                    MessageDirection::ToWidget,
-                                ));
-                            }
-
-                            for &socket in input_sockets {
-                                ui.send_message(WidgetMessage::link(
-                                    socket,
-                                    MessageDirection::ToWidget,
-                                    self.input_sockets_panel,
-                                ));
-                            }
-
-                            self.base.input_sockets = input_sockets.clone();
-                        }
-                    }
-                    AbsmNodeMessage::NormalColor(color) => {
-                        if &self.normal_color != color {
-                            self.normal_color = *color;
-                            self.update_colors(ui);
-                        }
-                    }
-                    AbsmNodeMessage::SelectedColor(color) => {
-                        if &self.selected_color != color {
-                            self.selected_color = *color;
-                            self.update_colors(ui);
+                    GraphNodeMessage::InputSockets(input_sockets)
+                        if input_sockets != &self.base.input_sockets =>
+                    {
+                        for &child in ui.node(self.input_sockets_panel).children() {
+                            ui.send_message(WidgetMessage::remove(
+                                child,
+                                MessageDirection::ToWidget,
+                            ));
                         }
-                    }
-                    AbsmNodeMessage::Name(name) => {
-                        if &self.name_value != name {
-                            self.name_value = name.clone();
 
-                            ui.send_message(TextMessage::text(
-                                self.name,
+                        for &socket in input_sockets {
+                            ui.send_message(WidgetMessage::link(
+                                socket,
                                 MessageDirection::ToWidget,
-                                format!("{} ({})", self.name_value, self.model_handle),
+                                self.input_sockets_panel,
                             ));
                         }
+
+                        self.base.input_sockets = input_sockets.clone();
+                    }
+                    GraphNodeMessage::NormalColor(color) if &self.normal_color != color => {
+                        self.normal_color = *color;
+                        self.update_colors(ui);
+                    }
+                    GraphNodeMessage::SelectedColor(color) if &self.selected_color != color => {
+                        self.selected_color = *color;
+                        self.update_colors(ui);
+                    }
+                    GraphNodeMessage::Name(name) if &self.name_value != name => {
+                        self.name_value = name.clone();
+
+                        ui.send_message(TextMessage::text(
+                            self.name,
+                            MessageDirection::ToWidget,
+                            format!("{} ({})", self.name_value, self.model_handle),
+                        ));
                     }
-                    AbsmNodeMessage::SetActive(active) => {
+                    GraphNodeMessage::SetActive(active) => {
                         let (thickness, color) = if *active {
                             (Thickness::uniform(3.0), Color::opaque(120, 80, 60))
                         } else {
@@ -245,7 +241,7 @@ where
     }
 }
 
-pub struct AbsmNodeBuilder<T>
+pub struct GraphNodeBuilder<T>
 where
     T: 'static,
 {
@@ -260,7 +256,7 @@ where
     selected_color: Color,
 }
 
-impl<T> AbsmNodeBuilder<T>
+impl<T> GraphNodeBuilder<T>
 where
     T: 'static,
 {
@@ -430,13 +426,13 @@ where
         )
         .build(ctx);
 
-        let node = AbsmNode {
+        let node = GraphNode {
             widget: self.widget_builder.with_child(background).build(),
             background,
             selectable: Default::default(),
             model_handle: self.model_handle,
             name_value: self.name,
-            base: AbsmBaseNode {
+            base: GraphBaseNode {
                 input_sockets: self.input_sockets,
                 output_socket: self.output_socket,
             },
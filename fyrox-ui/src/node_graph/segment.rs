@@ -1,10 +1,8 @@
-use fyrox::{
+use crate::{
     core::{algebra::Vector2, pool::Handle},
-    gui::{
-        define_constructor,
-        message::{MessageDirection, UiMessage},
-        UiNode,
-    },
+    define_constructor,
+    message::{MessageDirection, UiMessage},
+    UiNode,
 };
 
 #[derive(Debug, Clone, PartialEq)]
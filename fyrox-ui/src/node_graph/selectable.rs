@@ -1,10 +1,11 @@
 //! A mixin that provides selection functionality for a widget.
 
-use fyrox::{
+use crate::{
     core::pool::Handle,
-    gui::message::{MessageDirection, MouseButton, UiMessage},
-    gui::widget::WidgetMessage,
-    gui::{define_constructor, UiNode, UserInterface},
+    define_constructor,
+    message::{MessageDirection, MouseButton, UiMessage},
+    widget::WidgetMessage,
+    UiNode, UserInterface,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,23 +31,22 @@ impl Selectable {
     ) {
         if let Some(msg) = message.data::<WidgetMessage>() {
             match msg {
-                WidgetMessage::MouseDown { button, .. } => {
+                WidgetMessage::MouseDown { button, .. }
                     if (*button == MouseButton::Left || *button == MouseButton::Right)
-                        && !self.selected
-                    {
-                        ui.send_message(SelectableMessage::select(
-                            self_handle,
-                            MessageDirection::ToWidget,
-                            true,
-                        ));
+                        && !self.selected =>
+                {
+                    ui.send_message(SelectableMessage::select(
+                        self_handle,
+                        MessageDirection::ToWidget,
+                        true,
+                    ));
 
-                        ui.capture_mouse(self_handle);
-                    }
+                    ui.capture_mouse(self_handle);
                 }
-                WidgetMessage::MouseUp { button, .. } => {
-                    if *button == MouseButton::Left || *button == MouseButton::Right {
-                        ui.release_mouse_capture();
-                    }
+                WidgetMessage::MouseUp { button, .. }
+                    if *button == MouseButton::Left || *button == MouseButton::Right =>
+                {
+                    ui.release_mouse_capture();
                 }
                 _ => {}
             }
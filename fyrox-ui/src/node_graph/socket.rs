@@ -1,15 +1,16 @@
-use fyrox::animation::machine::PoseNode;
-use fyrox::{
-    core::{algebra::Vector2, color::Color, pool::Handle},
-    gui::{
-        brush::Brush,
-        define_constructor, define_widget_deref,
-        grid::{Column, GridBuilder, Row},
-        message::{MessageDirection, MouseButton, UiMessage},
-        vector_image::{Primitive, VectorImageBuilder},
-        widget::{Widget, WidgetBuilder, WidgetMessage},
-        BuildContext, Control, UiNode, UserInterface,
+use crate::{
+    brush::Brush,
+    core::{
+        algebra::Vector2,
+        color::Color,
+        pool::{ErasedHandle, Handle},
     },
+    define_constructor, define_widget_deref,
+    grid::{Column, GridBuilder, Row},
+    message::{MessageDirection, MouseButton, UiMessage},
+    vector_image::{Primitive, VectorImageBuilder},
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
 };
 use std::{
     any::{Any, TypeId},
@@ -39,7 +40,7 @@ pub enum SocketDirection {
 pub struct Socket {
     widget: Widget,
     click_position: Option<Vector2<f32>>,
-    pub parent_node: Handle<PoseNode>,
+    pub parent_node: ErasedHandle,
     pub direction: SocketDirection,
     #[allow(dead_code)] // TODO
     editor: Handle<UiNode>,
@@ -65,23 +66,21 @@ impl Control for Socket {
 
         if let Some(msg) = message.data::<WidgetMessage>() {
             match msg {
-                WidgetMessage::MouseDown { button, pos } => {
-                    if *button == MouseButton::Left && message.destination() == self.pin {
-                        self.click_position = Some(*pos);
+                WidgetMessage::MouseDown { button, pos }
+                    if *button == MouseButton::Left && message.destination() == self.pin =>
+                {
+                    self.click_position = Some(*pos);
 
-                        ui.capture_mouse(self.handle());
+                    ui.capture_mouse(self.handle());
 
-                        message.set_handled(true);
-                    }
+                    message.set_handled(true);
                 }
-                WidgetMessage::MouseUp { button, .. } => {
-                    if *button == MouseButton::Left {
-                        self.click_position = None;
+                WidgetMessage::MouseUp { button, .. } if *button == MouseButton::Left => {
+                    self.click_position = None;
 
-                        ui.release_mouse_capture();
+                    ui.release_mouse_capture();
 
-                        message.set_handled(true);
-                    }
+                    message.set_handled(true);
                 }
                 WidgetMessage::MouseMove { pos, .. } => {
                     if let Some(click_position) = self.click_position {
@@ -117,7 +116,7 @@ impl Control for Socket {
 
 pub struct SocketBuilder {
     widget_builder: WidgetBuilder,
-    parent_node: Handle<PoseNode>,
+    parent_node: ErasedHandle,
     direction: SocketDirection,
     editor: Handle<UiNode>,
     index: usize,
@@ -134,8 +133,8 @@ impl SocketBuilder {
         }
     }
 
-    pub fn with_parent_node(mut self, parent_node: Handle<PoseNode>) -> Self {
-        self.parent_node = parent_node;
+    pub fn with_parent_node<T>(mut self, parent_node: Handle<T>) -> Self {
+        self.parent_node = parent_node.into();
         self
     }
 
@@ -1,21 +1,21 @@
 use crate::{
-    absm::{
+    brush::Brush,
+    core::{
+        algebra::Vector2,
+        color::{Color, Hsv},
+        math::Rect,
+        pool::{ErasedHandle, Handle},
+    },
+    define_constructor, define_widget_deref,
+    draw::{CommandTexture, Draw, DrawingContext},
+    message::{MessageDirection, UiMessage},
+    node_graph::{
+        fetch_node_center,
         segment::Segment,
         selectable::{Selectable, SelectableMessage},
     },
-    utils::fetch_node_center,
-};
-use fyrox::animation::machine::Transition;
-use fyrox::{
-    core::{algebra::Vector2, color::Color, color::Hsv, math::Rect, pool::Handle},
-    gui::{
-        brush::Brush,
-        define_constructor, define_widget_deref,
-        draw::{CommandTexture, Draw, DrawingContext},
-        message::{MessageDirection, UiMessage},
-        widget::{Widget, WidgetBuilder, WidgetMessage},
-        BuildContext, Control, UiNode, UserInterface,
-    },
+    widget::{Widget, WidgetBuilder, WidgetMessage},
+    BuildContext, Control, UiNode, UserInterface,
 };
 use std::{
     any::{Any, TypeId},
@@ -44,7 +44,7 @@ impl TransitionMessage {
 pub struct TransitionView {
     widget: Widget,
     pub segment: Segment,
-    pub model_handle: Handle<Transition>,
+    pub model_handle: ErasedHandle,
     selectable: Selectable,
     activity_factor: f32,
 }
@@ -178,7 +178,7 @@ impl TransitionBuilder {
         self
     }
 
-    pub fn build(self, model_handle: Handle<Transition>, ctx: &mut BuildContext) -> Handle<UiNode> {
+    pub fn build<T>(self, model_handle: Handle<T>, ctx: &mut BuildContext) -> Handle<UiNode> {
         let transition = TransitionView {
             widget: self
                 .widget_builder
@@ -191,7 +191,7 @@ impl TransitionBuilder {
                 dest: self.dest,
                 dest_pos: fetch_node_center(self.dest, ctx),
             },
-            model_handle,
+            model_handle: model_handle.into(),
             selectable: Selectable::default(),
             activity_factor: 0.0,
         };
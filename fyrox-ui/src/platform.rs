@@ -0,0 +1,282 @@
+//! Access to platform services - system clipboard and native file dialogs. See [`PlatformServices`]
+//! docs for more info.
+
+use copypasta::{ClipboardContext, ClipboardProvider};
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    fmt::{Debug, Formatter},
+    path::PathBuf,
+};
+
+/// A filter for file dialogs, that allows to show only the files with specific extensions.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct FileDialogFilter {
+    /// Human-readable name of the filter, for example `Scenes`.
+    pub name: String,
+    /// A set of extensions (without leading dot), for example `["rgs"]`.
+    pub extensions: Vec<String>,
+}
+
+impl FileDialogFilter {
+    /// Creates new filter with the given name and a set of extensions.
+    pub fn new<S: AsRef<str>>(name: S, extensions: &[&str]) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            extensions: extensions.iter().map(|e| (*e).to_owned()).collect(),
+        }
+    }
+}
+
+/// A set of options for file dialogs.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct FileDialogOptions {
+    /// Title of a dialog window.
+    pub title: Option<String>,
+    /// Initial directory of a dialog.
+    pub directory: Option<PathBuf>,
+    /// Default file name, it is used only by save dialogs.
+    pub file_name: Option<String>,
+    /// A set of filters, empty set means that every file is shown.
+    pub filters: Vec<FileDialogFilter>,
+}
+
+impl FileDialogOptions {
+    /// Creates new default set of options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets desired title of a dialog window.
+    pub fn with_title<S: AsRef<str>>(mut self, title: S) -> Self {
+        self.title = Some(title.as_ref().to_owned());
+        self
+    }
+
+    /// Sets initial directory of a dialog.
+    pub fn with_directory<P: Into<PathBuf>>(mut self, directory: P) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Sets default file name, that will be used by save dialogs.
+    pub fn with_file_name<S: AsRef<str>>(mut self, file_name: S) -> Self {
+        self.file_name = Some(file_name.as_ref().to_owned());
+        self
+    }
+
+    /// Adds a new filter to the set of filters.
+    pub fn with_filter(mut self, filter: FileDialogFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+}
+
+/// A provider of native file dialogs. The engine does not depend on any particular dialog library,
+/// so the implementation must be supplied by an application (for example, by wrapping `rfd` crate or
+/// platform-specific API) via [`PlatformServices::set_file_dialog_provider`].
+///
+/// # Important notes
+///
+/// Native dialogs are modal on most of the platforms, so every method is expected to block until
+/// the dialog is closed.
+pub trait FileDialogProvider {
+    /// Shows "open file" dialog and returns selected path, or `None` if the dialog was cancelled.
+    fn open_file(&mut self, options: &FileDialogOptions) -> Option<PathBuf>;
+
+    /// Shows "open files" dialog with multiple selection. Default implementation falls back to
+    /// [`Self::open_file`].
+    fn open_files(&mut self, options: &FileDialogOptions) -> Vec<PathBuf> {
+        self.open_file(options).into_iter().collect()
+    }
+
+    /// Shows "save file" dialog and returns selected path, or `None` if the dialog was cancelled.
+    fn save_file(&mut self, options: &FileDialogOptions) -> Option<PathBuf>;
+
+    /// Shows "pick folder" dialog and returns selected path, or `None` if the dialog was cancelled.
+    fn pick_folder(&mut self, options: &FileDialogOptions) -> Option<PathBuf>;
+}
+
+/// Platform services is a single access point to the system clipboard and native file dialogs. It
+/// is owned by [`crate::UserInterface`] and could be accessed via [`crate::UserInterface::platform`]
+/// by widgets, the editor and game code (scripts have it in their context).
+///
+/// # Clipboard
+///
+/// Clipboard could be unavailable on some platforms (for example, on a headless server), in this
+/// case every clipboard method will do nothing and return `None` or `false`.
+///
+/// ```rust
+/// # use fyrox_ui::platform::PlatformServices;
+/// fn share_level_code(platform: &PlatformServices, code: &str) {
+///     if !platform.set_clipboard_text(code) {
+///         println!("Clipboard is unavailable, the code is: {}", code);
+///     }
+/// }
+/// ```
+///
+/// # File dialogs
+///
+/// There is no built-in dialog backend, dialog methods will return `None` until a provider is set
+/// via [`Self::set_file_dialog_provider`].
+pub struct PlatformServices {
+    clipboard: Option<RefCell<ClipboardContext>>,
+    file_dialog_provider: RefCell<Option<Box<dyn FileDialogProvider>>>,
+}
+
+impl Debug for PlatformServices {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlatformServices")
+            .field("clipboard", &self.clipboard.is_some())
+            .field("file_dialogs", &self.has_file_dialogs())
+            .finish()
+    }
+}
+
+impl Default for PlatformServices {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlatformServices {
+    /// Creates new platform services and tries to connect to the system clipboard.
+    pub fn new() -> Self {
+        Self {
+            clipboard: ClipboardContext::new().ok().map(RefCell::new),
+            file_dialog_provider: Default::default(),
+        }
+    }
+
+    /// Returns `true` if the system clipboard is available.
+    pub fn has_clipboard(&self) -> bool {
+        self.clipboard.is_some()
+    }
+
+    /// Returns a reference to the underlying clipboard context (if any).
+    pub fn clipboard(&self) -> Option<Ref<'_, ClipboardContext>> {
+        self.clipboard.as_ref().map(|v| v.borrow())
+    }
+
+    /// Returns a reference to the underlying clipboard context (if any).
+    pub fn clipboard_mut(&self) -> Option<RefMut<'_, ClipboardContext>> {
+        self.clipboard.as_ref().map(|v| v.borrow_mut())
+    }
+
+    /// Returns current text content of the system clipboard, `None` if the clipboard is unavailable
+    /// or does not contain text.
+    pub fn clipboard_text(&self) -> Option<String> {
+        self.clipboard_mut()
+            .and_then(|mut clipboard| clipboard.get_contents().ok())
+    }
+
+    /// Puts the given text to the system clipboard. Returns `true` on success.
+    pub fn set_clipboard_text<S: AsRef<str>>(&self, text: S) -> bool {
+        self.clipboard_mut().map_or(false, |mut clipboard| {
+            clipboard.set_contents(text.as_ref().to_owned()).is_ok()
+        })
+    }
+
+    /// Sets new file dialog provider, returns previous one. Use `None` to remove current provider.
+    pub fn set_file_dialog_provider(
+        &self,
+        provider: Option<Box<dyn FileDialogProvider>>,
+    ) -> Option<Box<dyn FileDialogProvider>> {
+        std::mem::replace(&mut *self.file_dialog_provider.borrow_mut(), provider)
+    }
+
+    /// Returns `true` if there is a file dialog provider.
+    pub fn has_file_dialogs(&self) -> bool {
+        self.file_dialog_provider.borrow().is_some()
+    }
+
+    /// Shows "open file" dialog. See [`FileDialogProvider::open_file`] for more info.
+    pub fn open_file_dialog(&self, options: &FileDialogOptions) -> Option<PathBuf> {
+        self.file_dialog_provider
+            .borrow_mut()
+            .as_mut()
+            .and_then(|provider| provider.open_file(options))
+    }
+
+    /// Shows "open files" dialog. See [`FileDialogProvider::open_files`] for more info.
+    pub fn open_files_dialog(&self, options: &FileDialogOptions) -> Vec<PathBuf> {
+        self.file_dialog_provider
+            .borrow_mut()
+            .as_mut()
+            .map(|provider| provider.open_files(options))
+            .unwrap_or_default()
+    }
+
+    /// Shows "save file" dialog. See [`FileDialogProvider::save_file`] for more info.
+    pub fn save_file_dialog(&self, options: &FileDialogOptions) -> Option<PathBuf> {
+        self.file_dialog_provider
+            .borrow_mut()
+            .as_mut()
+            .and_then(|provider| provider.save_file(options))
+    }
+
+    /// Shows "pick folder" dialog. See [`FileDialogProvider::pick_folder`] for more info.
+    pub fn pick_folder_dialog(&self, options: &FileDialogOptions) -> Option<PathBuf> {
+        self.file_dialog_provider
+            .borrow_mut()
+            .as_mut()
+            .and_then(|provider| provider.pick_folder(options))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::platform::{
+        FileDialogFilter, FileDialogOptions, FileDialogProvider, PlatformServices,
+    };
+    use std::path::PathBuf;
+
+    struct MockProvider;
+
+    impl FileDialogProvider for MockProvider {
+        fn open_file(&mut self, options: &FileDialogOptions) -> Option<PathBuf> {
+            let ext = options.filters.first()?.extensions.first()?;
+            Some(PathBuf::from(format!("level.{}", ext)))
+        }
+
+        fn save_file(&mut self, options: &FileDialogOptions) -> Option<PathBuf> {
+            options.file_name.as_ref().map(PathBuf::from)
+        }
+
+        fn pick_folder(&mut self, _options: &FileDialogOptions) -> Option<PathBuf> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_file_dialog_provider() {
+        let platform = PlatformServices::new();
+        let options = FileDialogOptions::new()
+            .with_title("Open")
+            .with_file_name("save.bin")
+            .with_filter(FileDialogFilter::new("Levels", &["lvl"]));
+
+        assert!(!platform.has_file_dialogs());
+        assert_eq!(platform.open_file_dialog(&options), None);
+
+        assert!(platform
+            .set_file_dialog_provider(Some(Box::new(MockProvider)))
+            .is_none());
+        assert!(platform.has_file_dialogs());
+        assert_eq!(
+            platform.open_file_dialog(&options),
+            Some(PathBuf::from("level.lvl"))
+        );
+        assert_eq!(
+            platform.open_files_dialog(&options),
+            vec![PathBuf::from("level.lvl")]
+        );
+        assert_eq!(
+            platform.save_file_dialog(&options),
+            Some(PathBuf::from("save.bin"))
+        );
+        assert_eq!(platform.pick_folder_dialog(&options), None);
+
+        assert!(platform.set_file_dialog_provider(None).is_some());
+        assert!(!platform.has_file_dialogs());
+    }
+}
@@ -91,6 +91,22 @@ impl Control for StackPanel {
             Orientation::Horizontal => width = 0.0,
         }
 
+        // Children of horizontal panels are placed from right to left in right-to-left mode, so the
+        // total width is needed to mirror their positions.
+        let mirror_width = if self.orientation == Orientation::Horizontal
+            && ui.flow_direction().is_right_to_left()
+        {
+            let total_width = self
+                .widget
+                .children()
+                .iter()
+                .map(|child| ui.node(*child).desired_size().x)
+                .sum::<f32>();
+            Some(total_width.max(final_size.x))
+        } else {
+            None
+        };
+
         for child_handle in self.widget.children() {
             let child = ui.node(*child_handle);
             match self.orientation {
@@ -106,8 +122,12 @@ impl Control for StackPanel {
                     height += child.desired_size().y;
                 }
                 Orientation::Horizontal => {
+                    let x = match mirror_width {
+                        Some(mirror_width) => mirror_width - width - child.desired_size().x,
+                        None => width,
+                    };
                     let child_bounds = Rect::new(
-                        width,
+                        x,
                         0.0,
                         child.desired_size().x,
                         height.max(child.desired_size().y),
@@ -57,10 +57,11 @@ impl Control for Text {
         }
     }
 
-    fn measure_override(&self, _: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
+    fn measure_override(&self, ui: &UserInterface, available_size: Vector2<f32>) -> Vector2<f32> {
         self.formatted_text
             .borrow_mut()
             .set_constraint(available_size)
+            .set_flow_direction(ui.flow_direction())
             .set_brush(self.widget.foreground())
             .build()
     }
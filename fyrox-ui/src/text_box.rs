@@ -13,7 +13,7 @@ use crate::{
     text::TextMessage,
     ttf::SharedFont,
     widget::{Widget, WidgetBuilder, WidgetMessage},
-    BuildContext, Control, HorizontalAlignment, UiNode, UserInterface, VerticalAlignment,
+    BuildContext, Control, HorizontalAlignment, ImeState, UiNode, UserInterface, VerticalAlignment,
     BRUSH_DARKER, BRUSH_TEXT,
 };
 use copypasta::ClipboardProvider;
@@ -138,6 +138,10 @@ pub struct TextBox {
     pub editable: bool,
     pub view_position: Vector2<f32>,
     pub skip_chars: Vec<u32>,
+    /// Text that is being composed using input method editor (IME).
+    pub ime_preedit: String,
+    /// Position (in characters) of the cursor in IME text.
+    pub ime_preedit_cursor: Option<usize>,
 }
 
 impl Debug for TextBox {
@@ -437,6 +441,22 @@ impl TextBox {
         caret_pos
     }
 
+    /// Returns screen position of the bottom of the caret, IME candidate window is placed there.
+    fn ime_screen_position(&self) -> Vector2<f32> {
+        let height = self.formatted_text.borrow().get_font().0.lock().height();
+        let caret = self.point_to_view_pos(self.caret_local_position()) + Vector2::new(0.0, height);
+        self.visual_transform()
+            .transform_point(&Point2::from(caret))
+            .coords
+    }
+
+    fn update_ime_state(&self, ui: &mut UserInterface) {
+        ui.set_ime_state(ImeState {
+            allowed: self.has_focus && self.editable,
+            position: self.ime_screen_position(),
+        });
+    }
+
     fn point_to_view_pos(&self, position: Vector2<f32>) -> Vector2<f32> {
         position - self.view_position
     }
@@ -791,8 +811,49 @@ impl Control for TextBox {
             &self.formatted_text.borrow(),
         );
 
-        if self.caret_visible {
+        let mut caret_offset = 0.0;
+        if !self.ime_preedit.is_empty() {
+            // Composed text is shown over the text at the caret position and underlined.
             let caret_pos = self.point_to_view_pos(self.caret_local_position());
+            let font = self.formatted_text.borrow().get_font();
+            let height = font.0.lock().height();
+            let mut preedit = FormattedTextBuilder::new(font)
+                .with_text(self.ime_preedit.clone())
+                .build();
+            preedit.set_brush(self.widget.foreground());
+            let size = preedit.build();
+
+            drawing_context
+                .push_rect_filled(&Rect::new(caret_pos.x, caret_pos.y, size.x, height), None);
+            drawing_context.commit(
+                self.clip_bounds(),
+                self.widget.background(),
+                CommandTexture::None,
+                None,
+            );
+            drawing_context.draw_text(self.clip_bounds(), caret_pos, &preedit);
+            drawing_context.push_rect_filled(
+                &Rect::new(caret_pos.x, caret_pos.y + height, size.x, 1.0),
+                None,
+            );
+            drawing_context.commit(
+                self.clip_bounds(),
+                self.widget.foreground(),
+                CommandTexture::None,
+                None,
+            );
+
+            caret_offset = preedit.get_range_width(
+                0..self
+                    .ime_preedit_cursor
+                    .unwrap_or_else(|| preedit.get_raw_text().len())
+                    .min(preedit.get_raw_text().len()),
+            );
+        }
+
+        if self.caret_visible {
+            let caret_pos = self.point_to_view_pos(self.caret_local_position())
+                + Vector2::new(caret_offset, 0.0);
             let caret_bounds = Rect::new(
                 caret_pos.x,
                 caret_pos.y,
@@ -1061,6 +1122,15 @@ impl Control for TextBox {
                         // others are used directly to enter text.
                         message.set_handled(true);
                     }
+                    WidgetMessage::ImePreedit { text, cursor } => {
+                        if self.editable {
+                            self.ime_preedit = text.clone();
+                            self.ime_preedit_cursor = cursor.and_then(|(begin, _)| {
+                                text.get(..begin).map(|prefix| prefix.chars().count())
+                            });
+                            self.reset_blink();
+                        }
+                    }
                     WidgetMessage::Focus => {
                         if message.direction() == MessageDirection::FromWidget {
                             self.reset_blink();
@@ -1072,6 +1142,8 @@ impl Control for TextBox {
                         if message.direction() == MessageDirection::FromWidget {
                             self.selection_range = None;
                             self.has_focus = false;
+                            self.ime_preedit.clear();
+                            self.ime_preedit_cursor = None;
 
                             if self.commit_mode == TextCommitMode::LostFocus
                                 || self.commit_mode == TextCommitMode::LostFocusPlusEnter
@@ -1126,6 +1198,11 @@ impl Control for TextBox {
                     }
                     _ => {}
                 }
+
+                // Keep IME candidate window near the caret.
+                if self.has_focus || matches!(msg, WidgetMessage::Unfocus) {
+                    self.update_ime_state(ui);
+                }
             } else if let Some(msg) = message.data::<TextMessage>() {
                 if message.direction() == MessageDirection::ToWidget {
                     let mut text = self.formatted_text.borrow_mut();
@@ -1436,6 +1513,8 @@ impl TextBoxBuilder {
             editable: self.editable,
             view_position: Default::default(),
             skip_chars: self.skip_chars,
+            ime_preedit: Default::default(),
+            ime_preedit_cursor: None,
         };
 
         ctx.add_node(UiNode::new(text_box))
@@ -0,0 +1,219 @@
+//! Theme is a set of colors, fonts and styles, that is used by the user interface. See [`Theme`] docs for
+//! more info.
+
+use crate::{
+    core::{color::Color, visitor::prelude::*},
+    ttf::SharedFont,
+    Thickness, COLOR_BRIGHT, COLOR_BRIGHT_BLUE, COLOR_DARK, COLOR_DARKER, COLOR_DARKEST,
+    COLOR_FOREGROUND, COLOR_LIGHT, COLOR_LIGHTER, COLOR_LIGHTEST, COLOR_PRIMARY, COLOR_TEXT,
+};
+use fxhash::FxHashMap;
+use std::path::Path;
+
+/// Style is a named set of overrides for widgets. A widget uses a style by its name (see
+/// [`crate::widget::WidgetBuilder::with_style`]), the style is applied when the widget is added to the
+/// user interface and every time when the theme is changed. It means that widgets with styles do not
+/// need any hardcoded brushes, they will change their appearance when the theme is switched at runtime.
+///
+/// Every property is optional, `None` means that the respective property of a widget won't be changed.
+#[derive(Clone, Debug, PartialEq, Default, Visit)]
+pub struct Style {
+    /// Background of a widget.
+    pub background: Option<Color>,
+    /// Foreground of a widget.
+    pub foreground: Option<Color>,
+    /// Margin (padding around a widget).
+    pub margin: Option<Thickness>,
+    /// Font of a widget. It is applied only to widgets with text (for example, text and text box).
+    /// Fonts are not serialized, so they must be set manually after loading a theme from a file.
+    #[visit(skip)]
+    pub font: Option<SharedFont>,
+}
+
+/// Theme is a set of colors, fonts and styles, that is used by the user interface. Current theme could be
+/// changed at runtime using [`crate::UserInterface::set_theme`], every widget will then receive
+/// [`crate::widget::WidgetMessage::ThemeChanged`] message, so it could update its brushes by fetching
+/// new colors from [`crate::UserInterface::theme`]. This is the hook for accessibility options,
+/// such as high-contrast mode or colorblind palettes.
+///
+/// # Styles
+///
+/// Theme contains a set of named [`Style`]s, which are applied automatically to every widget that
+/// uses them. This is the preferred way of styling widgets, because there is no need to handle
+/// theme changes manually.
+///
+/// # Files
+///
+/// Theme could be saved to a file and loaded back using [`Theme::save`] and [`Theme::from_file`], so
+/// it could be edited and shipped as a separate asset. Fonts are not saved.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox_ui::{
+///     core::color::Color,
+///     theme::{Style, Theme},
+///     widget::WidgetBuilder,
+///     border::BorderBuilder,
+///     UserInterface,
+///     core::algebra::Vector2,
+/// };
+///
+/// let mut ui = UserInterface::new(Vector2::new(100.0, 100.0));
+///
+/// let mut theme = Theme::dark();
+/// theme.set_style(
+///     "Panel",
+///     Style {
+///         background: Some(Color::opaque(30, 30, 30)),
+///         ..Default::default()
+///     },
+/// );
+/// ui.set_theme(theme);
+///
+/// let panel =
+///     BorderBuilder::new(WidgetBuilder::new().with_style("Panel")).build(&mut ui.build_ctx());
+///
+/// // Switch the theme at runtime, the panel will change its background on the next update.
+/// let mut theme = Theme::light();
+/// theme.set_style(
+///     "Panel",
+///     Style {
+///         background: Some(Color::opaque(220, 220, 220)),
+///         ..Default::default()
+///     },
+/// );
+/// ui.set_theme(theme);
+/// ```
+#[derive(Clone, Debug, PartialEq, Visit)]
+pub struct Theme {
+    /// The darkest color of the theme.
+    pub darkest: Color,
+    /// A color, that is lighter than [`Self::darkest`].
+    pub darker: Color,
+    /// A color, that is lighter than [`Self::darker`].
+    pub dark: Color,
+    /// Primary color of the theme, it is used as a background for most of the widgets.
+    pub primary: Color,
+    /// A color, that is lighter than [`Self::primary`].
+    pub light: Color,
+    /// A color, that is lighter than [`Self::light`].
+    pub lighter: Color,
+    /// A color, that is lighter than [`Self::lighter`].
+    pub lightest: Color,
+    /// A color for highlighted elements.
+    pub bright: Color,
+    /// A color for selected elements.
+    pub bright_blue: Color,
+    /// A color of text.
+    pub text: Color,
+    /// A color for foreground elements.
+    pub foreground: Color,
+    /// A set of named styles, see [`Style`] docs for more info.
+    pub styles: FxHashMap<String, Style>,
+    /// Default font of the theme. If it is set, it will be used as default font of the user interface
+    /// for every widget created after the theme was applied. Fonts are not serialized.
+    #[visit(skip)]
+    pub font: Option<SharedFont>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Default dark theme.
+    pub fn dark() -> Self {
+        Self {
+            darkest: COLOR_DARKEST,
+            darker: COLOR_DARKER,
+            dark: COLOR_DARK,
+            primary: COLOR_PRIMARY,
+            light: COLOR_LIGHT,
+            lighter: COLOR_LIGHTER,
+            lightest: COLOR_LIGHTEST,
+            bright: COLOR_BRIGHT,
+            bright_blue: COLOR_BRIGHT_BLUE,
+            text: COLOR_TEXT,
+            foreground: COLOR_FOREGROUND,
+            styles: Default::default(),
+            font: None,
+        }
+    }
+
+    /// Default light theme.
+    pub fn light() -> Self {
+        Self {
+            darkest: Color::opaque(235, 235, 235),
+            darker: Color::opaque(225, 225, 225),
+            dark: Color::opaque(215, 215, 215),
+            primary: Color::opaque(205, 205, 205),
+            light: Color::opaque(180, 180, 180),
+            lighter: Color::opaque(160, 160, 160),
+            lightest: Color::opaque(140, 140, 140),
+            bright: Color::opaque(90, 90, 90),
+            bright_blue: Color::opaque(60, 120, 210),
+            text: Color::opaque(20, 20, 20),
+            foreground: Color::opaque(60, 60, 60),
+            styles: Default::default(),
+            font: None,
+        }
+    }
+
+    /// Dark theme with highlight colors from the Okabe-Ito palette, that could be distinguished by people
+    /// with most common types of color vision deficiency.
+    pub fn colorblind() -> Self {
+        Self {
+            bright: Color::opaque(230, 159, 0),
+            bright_blue: Color::opaque(86, 180, 233),
+            ..Self::dark()
+        }
+    }
+
+    /// High-contrast theme - pure black backgrounds, white text and bright yellow highlights.
+    pub fn high_contrast() -> Self {
+        Self {
+            darkest: Color::BLACK,
+            darker: Color::BLACK,
+            dark: Color::BLACK,
+            primary: Color::opaque(10, 10, 10),
+            light: Color::opaque(200, 200, 200),
+            lighter: Color::opaque(230, 230, 230),
+            lightest: Color::WHITE,
+            bright: Color::opaque(255, 220, 0),
+            bright_blue: Color::opaque(0, 200, 255),
+            text: Color::WHITE,
+            foreground: Color::WHITE,
+            styles: Default::default(),
+            font: None,
+        }
+    }
+
+    /// Adds a new style or replaces an existing one with the same name.
+    pub fn set_style<S: AsRef<str>>(&mut self, name: S, style: Style) -> &mut Self {
+        self.styles.insert(name.as_ref().to_owned(), style);
+        self
+    }
+
+    /// Returns a reference to a style with the given name.
+    pub fn style(&self, name: &str) -> Option<&Style> {
+        self.styles.get(name)
+    }
+
+    /// Loads a theme from the given file.
+    pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, VisitError> {
+        let mut visitor = Visitor::load_binary(path).await?;
+        let mut theme = Theme::default();
+        theme.visit("Theme", &mut visitor)?;
+        Ok(theme)
+    }
+
+    /// Saves the theme to the given file, so it could be loaded later using [`Theme::from_file`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.clone().visit("Theme", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     core::{algebra::Vector2, io, parking_lot::Mutex, rectpack::RectPacker},
     draw::SharedTexture,
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use std::{
     borrow::Cow,
     fmt::{Debug, Formatter},
@@ -22,6 +22,26 @@ pub struct FontGlyph {
     pub pixels: Vec<u8>,
 }
 
+/// Font is a set of rasterized glyphs packed in a single atlas.
+///
+/// # Fallback fonts
+///
+/// Font could have any number of fallback faces (see [`FontBuilder::with_fallback`]). Glyphs of
+/// characters that are not present in the main face are taken from the first fallback face that
+/// has them. This allows to mix, for example, CJK and Latin text in a single piece of text.
+///
+/// # Dynamic atlas
+///
+/// Only the characters of the char set are rasterized when a font is created, every other
+/// character is rasterized on demand (see [`Font::glyph_index_or_load`]). The atlas grows
+/// automatically when there is no space left for new glyphs, this invalidates the texture
+/// of the font and changes texture coordinates of glyphs, so they must not be cached.
+///
+/// # Emoji
+///
+/// Emoji are supported only as monochrome outlines (for example by adding an emoji font as a
+/// fallback), they're tinted with the brush of the text as any other glyph. Color emoji
+/// (bitmap or layered glyphs) are not supported, because the atlas is single-channel.
 pub struct Font {
     height: f32,
     glyphs: Vec<FontGlyph>,
@@ -30,6 +50,12 @@ pub struct Font {
     char_map: FxHashMap<u32, usize>,
     atlas: Vec<u8>,
     atlas_size: usize,
+    atlas_revision: u64,
+    packer: RectPacker<usize>,
+    // Main face goes first, then fallback faces in the order of priority.
+    faces: Vec<fontdue::Font>,
+    // Characters that are absent in every face, to not search for them again and again.
+    missing: FxHashSet<u32>,
     pub texture: Option<SharedTexture>,
 }
 
@@ -148,8 +174,19 @@ impl Font {
         height: f32,
         char_set: &[Range<u32>],
     ) -> Result<Self, &'static str> {
-        let fontdue_font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())?;
-        let font_metrics = fontdue_font.horizontal_line_metrics(height).unwrap();
+        let face = fontdue::Font::from_bytes(data, fontdue::FontSettings::default())?;
+        Self::from_faces(vec![face], height, char_set)
+    }
+
+    fn from_faces(
+        faces: Vec<fontdue::Font>,
+        height: f32,
+        char_set: &[Range<u32>],
+    ) -> Result<Self, &'static str> {
+        let font_metrics = faces
+            .first()
+            .and_then(|face| face.horizontal_line_metrics(height))
+            .ok_or("Font has no horizontal line metrics")?;
 
         let mut font = Font {
             height,
@@ -159,36 +196,90 @@ impl Font {
             char_map: FxHashMap::default(),
             atlas: Vec::new(),
             atlas_size: 0,
+            atlas_revision: 0,
+            packer: RectPacker::new(0, 0),
+            faces,
+            missing: Default::default(),
             texture: None,
         };
 
-        let mut index = 0;
         for range in char_set {
             for unicode in range.start..range.end {
-                if let Some(character) = std::char::from_u32(unicode) {
-                    let (metrics, bitmap) = fontdue_font.rasterize(character, height);
-
-                    font.glyphs.push(FontGlyph {
-                        left: metrics.xmin as f32,
-                        top: metrics.ymin as f32,
-                        pixels: bitmap,
-                        advance: metrics.advance_width,
-                        tex_coords: Default::default(),
-                        bitmap_width: metrics.width,
-                        bitmap_height: metrics.height,
-                    });
-
-                    font.char_map.insert(unicode, index);
-                    index += 1;
-                }
+                font.rasterize_glyph(unicode);
             }
         }
 
-        font.pack();
+        font.pack(0);
 
         Ok(font)
     }
 
+    /// Adds new fallback face, it will be used for the characters that are not present in the
+    /// main face and in the fallback faces that were added before.
+    pub fn add_fallback_from_memory(
+        &mut self,
+        data: impl Deref<Target = [u8]>,
+    ) -> Result<(), &'static str> {
+        self.faces.push(fontdue::Font::from_bytes(
+            data,
+            fontdue::FontSettings::default(),
+        )?);
+        self.missing.clear();
+        Ok(())
+    }
+
+    // Rasterizes the glyph using the first face that has it, the glyph is not packed in the atlas.
+    fn rasterize_glyph(&mut self, unicode: u32) -> Option<usize> {
+        let character = std::char::from_u32(unicode)?;
+        let face = self
+            .faces
+            .iter()
+            .find(|face| face.lookup_glyph_index(character) != 0)?;
+        let (metrics, bitmap) = face.rasterize(character, self.height);
+
+        let index = self.glyphs.len();
+        self.glyphs.push(FontGlyph {
+            left: metrics.xmin as f32,
+            top: metrics.ymin as f32,
+            pixels: bitmap,
+            advance: metrics.advance_width,
+            tex_coords: Default::default(),
+            bitmap_width: metrics.width,
+            bitmap_height: metrics.height,
+        });
+        self.char_map.insert(unicode, index);
+
+        Some(index)
+    }
+
+    /// Returns index of the glyph of the given character. If the character is not loaded yet, it
+    /// will be rasterized (using fallback faces if needed) and added to the atlas. Returns `None`
+    /// if there is no such character in any face of the font.
+    pub fn glyph_index_or_load(&mut self, unicode: u32) -> Option<usize> {
+        if let Some(index) = self.char_map.get(&unicode) {
+            return Some(*index);
+        }
+
+        if self.missing.contains(&unicode) {
+            return None;
+        }
+
+        match self.rasterize_glyph(unicode) {
+            Some(index) => {
+                if !self.copy_glyph_to_atlas(index) {
+                    // No space left, the atlas must be at least twice as big.
+                    self.pack(self.atlas_size * 2);
+                }
+                self.invalidate_atlas();
+                Some(index)
+            }
+            None => {
+                self.missing.insert(unicode);
+                None
+            }
+        }
+    }
+
     pub async fn from_file<P: AsRef<Path>>(
         path: P,
         height: f32,
@@ -244,6 +335,18 @@ impl Font {
         self.atlas_size
     }
 
+    /// Returns a number that is changed every time when the atlas is modified (new glyphs were
+    /// added or the atlas was re-packed).
+    #[inline]
+    pub fn atlas_revision(&self) -> u64 {
+        self.atlas_revision
+    }
+
+    fn invalidate_atlas(&mut self) {
+        self.atlas_revision += 1;
+        self.texture = None;
+    }
+
     #[inline]
     pub fn glyph_advance(&self, c: u32) -> f32 {
         self.glyph(c).map_or(self.height(), |glyph| glyph.advance)
@@ -258,45 +361,68 @@ impl Font {
         (1.3 * area.sqrt()) as usize
     }
 
-    fn pack(&mut self) {
-        let border = 2;
-        self.atlas_size = self.compute_atlas_size(border);
-        self.atlas = vec![0; self.atlas_size * self.atlas_size];
-        let k = 1.0 / self.atlas_size as f32;
-        let mut rect_packer = RectPacker::new(self.atlas_size, self.atlas_size);
-        for glyph in self.glyphs.iter_mut() {
-            if let Some(bounds) =
-                rect_packer.find_free(glyph.bitmap_width + border, glyph.bitmap_height + border)
-            {
-                let bw = bounds.w() - border;
-                let bh = bounds.h() - border;
-                let bx = bounds.x() + border / 2;
-                let by = bounds.y() + border / 2;
-
-                let tw = bw as f32 * k;
-                let th = bh as f32 * k;
-                let tx = bx as f32 * k;
-                let ty = by as f32 * k;
-
-                glyph.tex_coords[0] = Vector2::new(tx, ty);
-                glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
-                glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
-                glyph.tex_coords[3] = Vector2::new(tx, ty + th);
-
-                let row_end = by + bh;
-                let col_end = bx + bw;
-
-                // Copy glyph pixels to atlas pixels
-                for (src_row, row) in (by..row_end).enumerate() {
-                    for (src_col, col) in (bx..col_end).enumerate() {
-                        self.atlas[row * self.atlas_size + col] =
-                            glyph.pixels[src_row * bw + src_col];
-                    }
+    const ATLAS_BORDER: usize = 2;
+
+    fn pack(&mut self, min_size: usize) {
+        let mut size = self
+            .compute_atlas_size(Self::ATLAS_BORDER)
+            .max(min_size)
+            .max(1);
+
+        'outer: loop {
+            self.atlas_size = size;
+            self.atlas = vec![0; size * size];
+            self.packer = RectPacker::new(size, size);
+            for index in 0..self.glyphs.len() {
+                if !self.copy_glyph_to_atlas(index) {
+                    size *= 2;
+                    continue 'outer;
                 }
-            } else {
-                println!("Insufficient atlas size!");
+            }
+            break;
+        }
+    }
+
+    fn copy_glyph_to_atlas(&mut self, index: usize) -> bool {
+        let border = Self::ATLAS_BORDER;
+        let glyph = &mut self.glyphs[index];
+
+        let bounds = match self
+            .packer
+            .find_free(glyph.bitmap_width + border, glyph.bitmap_height + border)
+        {
+            Some(bounds) => bounds,
+            None => return false,
+        };
+
+        let k = 1.0 / self.atlas_size as f32;
+
+        let bw = bounds.w() - border;
+        let bh = bounds.h() - border;
+        let bx = bounds.x() + border / 2;
+        let by = bounds.y() + border / 2;
+
+        let tw = bw as f32 * k;
+        let th = bh as f32 * k;
+        let tx = bx as f32 * k;
+        let ty = by as f32 * k;
+
+        glyph.tex_coords[0] = Vector2::new(tx, ty);
+        glyph.tex_coords[1] = Vector2::new(tx + tw, ty);
+        glyph.tex_coords[2] = Vector2::new(tx + tw, ty + th);
+        glyph.tex_coords[3] = Vector2::new(tx, ty + th);
+
+        let row_end = by + bh;
+        let col_end = bx + bw;
+
+        // Copy glyph pixels to atlas pixels
+        for (src_row, row) in (by..row_end).enumerate() {
+            for (src_col, col) in (bx..col_end).enumerate() {
+                self.atlas[row * self.atlas_size + col] = glyph.pixels[src_row * bw + src_col];
             }
         }
+
+        true
     }
 }
 
@@ -304,6 +430,7 @@ impl Font {
 pub struct FontBuilder<'a> {
     height: Option<f32>,
     char_set: Option<Cow<'a, [Range<u32>]>>,
+    fallbacks: Vec<Cow<'a, [u8]>>,
 }
 impl<'a> FontBuilder<'a> {
     const DEFAULT_HEIGHT: f32 = 16.0;
@@ -313,6 +440,7 @@ impl<'a> FontBuilder<'a> {
         Self {
             height: None,
             char_set: None,
+            fallbacks: Vec::new(),
         }
     }
 
@@ -330,14 +458,37 @@ impl<'a> FontBuilder<'a> {
         self
     }
 
+    /// Adds a fallback face (raw TTF/OTF data), which will be used for the characters that are
+    /// not present in the main face. Fallback faces are searched in the order of addition, so
+    /// it is possible to create fallback chains, for example Latin -> CJK -> Emoji.
+    #[inline]
+    pub fn with_fallback(mut self, data: impl Into<Cow<'a, [u8]>>) -> Self {
+        self.fallbacks.push(data.into());
+        self
+    }
+
     /// Creates a new font from the data at the specified path.
     pub async fn build_from_file(self, path: impl AsRef<Path>) -> Result<Font, &'static str> {
-        Font::from_file(path, self.height(), self.char_set()).await
+        if let Ok(file_content) = io::load_file(path).await {
+            self.build_from_memory(file_content)
+        } else {
+            Err("Unable to read file")
+        }
     }
 
     /// Creates a new font from bytes in memory.
     pub fn build_from_memory(self, data: impl Deref<Target = [u8]>) -> Result<Font, &'static str> {
-        Font::from_memory(data, self.height(), self.char_set())
+        let mut faces = vec![fontdue::Font::from_bytes(
+            data,
+            fontdue::FontSettings::default(),
+        )?];
+        for fallback in self.fallbacks.iter() {
+            faces.push(fontdue::Font::from_bytes(
+                fallback.as_ref(),
+                fontdue::FontSettings::default(),
+            )?);
+        }
+        Font::from_faces(faces, self.height(), self.char_set())
     }
 
     /// Creates a new font using the built-in font face.
@@ -359,3 +510,40 @@ impl<'a> FontBuilder<'a> {
             .unwrap_or_else(|| Font::default_char_set())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::ttf::FontBuilder;
+
+    #[test]
+    fn test_glyphs_are_loaded_on_demand() {
+        let char_set = [0x0041..0x0042];
+        let mut font = FontBuilder::new()
+            .with_height(16.0)
+            .with_char_set(&char_set[..])
+            .build_builtin()
+            .unwrap();
+
+        assert_eq!(font.glyphs().len(), 1);
+        let initial_size = font.atlas_size();
+        let initial_revision = font.atlas_revision();
+
+        // Load enough glyphs to force the atlas to grow.
+        for unicode in 0x0021..0x007F {
+            assert!(font.glyph_index_or_load(unicode).is_some());
+        }
+
+        assert!(font.atlas_size() > initial_size);
+        assert_ne!(font.atlas_revision(), initial_revision);
+        for glyph in font.glyphs() {
+            for tex_coord in glyph.tex_coords.iter() {
+                assert!((0.0..=1.0).contains(&tex_coord.x));
+                assert!((0.0..=1.0).contains(&tex_coord.y));
+            }
+        }
+
+        // Private use area is not present in the built-in font.
+        assert_eq!(font.glyph_index_or_load(0xF8FF), None);
+        assert_eq!(font.glyph_index(0x0041), Some(0));
+    }
+}
@@ -73,6 +73,18 @@ pub enum WidgetMessage {
     /// Direction: **From/To UI**.
     Text(char),
 
+    /// Initiated when widget is in focus and user composes text using input method editor (IME).
+    /// The text is not committed yet, it should be just shown at the caret position. Committed
+    /// text comes as a sequence of [`WidgetMessage::Text`] messages.
+    ///
+    /// Direction: **From UI**.
+    ImePreedit {
+        /// Text that is being composed, empty text means that composition was cancelled.
+        text: String,
+        /// Byte range of the cursor in the composed text.
+        cursor: Option<(usize, usize)>,
+    },
+
     /// Initiated when widget is in focus and user presses a button on a keyboard.
     ///
     /// Direction: **From UI**.
@@ -280,6 +292,18 @@ pub enum WidgetMessage {
 
     /// A request to set new context menu for a widget. Old context menu will be removed.
     ContextMenu(Handle<UiNode>),
+
+    /// Current theme of the user interface was changed, a widget could fetch new colors using
+    /// [`crate::UserInterface::theme`].
+    ///
+    /// Direction: **From UI**.
+    ThemeChanged,
+
+    /// Effective scale factor of the user interface was changed. See [`crate::UserInterface::set_scale_factor`]
+    /// and [`crate::UserInterface::set_scaling_mode`].
+    ///
+    /// Direction: **From UI**.
+    ScaleFactorChanged(f32),
 }
 
 impl WidgetMessage {
@@ -314,6 +338,8 @@ impl WidgetMessage {
     define_constructor!(WidgetMessage:ContextMenu => fn context_menu(Handle<UiNode>), layout: false);
     define_constructor!(WidgetMessage:Focus => fn focus(), layout: false);
     define_constructor!(WidgetMessage:Unfocus => fn unfocus(), layout: false);
+    define_constructor!(WidgetMessage:ThemeChanged => fn theme_changed(), layout: false);
+    define_constructor!(WidgetMessage:ScaleFactorChanged => fn scale_factor_changed(f32), layout: false);
 
     // Internal messages. Do not use.
     define_constructor!(WidgetMessage:MouseDown => fn mouse_down(pos: Vector2<f32>, button: MouseButton), layout: false);
@@ -323,6 +349,7 @@ impl WidgetMessage {
     define_constructor!(WidgetMessage:MouseLeave => fn mouse_leave(), layout: false);
     define_constructor!(WidgetMessage:MouseEnter => fn mouse_enter(), layout: false);
     define_constructor!(WidgetMessage:Text => fn text(char), layout: false);
+    define_constructor!(WidgetMessage:ImePreedit => fn ime_preedit(text: String, cursor: Option<(usize, usize)>), layout: false);
     define_constructor!(WidgetMessage:KeyDown => fn key_down(KeyCode), layout: false);
     define_constructor!(WidgetMessage:KeyUp => fn key_up(KeyCode), layout: false);
     define_constructor!(WidgetMessage:DragStarted => fn drag_started(Handle<UiNode>), layout: false);
@@ -384,6 +411,9 @@ pub struct Widget {
     pub preview_messages: bool,
     pub handle_os_events: bool,
     pub layout_events_sender: Option<Sender<LayoutEvent>>,
+    /// Name of a style of the current theme, that will be applied to the widget. See
+    /// [`crate::theme::Style`] docs for more info.
+    pub style: Option<String>,
 
     /// Layout. Interior mutability is a must here because layout performed in
     /// a series of recursive calls.
@@ -883,6 +913,12 @@ impl Widget {
         self.margin
     }
 
+    /// Returns a name of a style of the current theme, that is applied to the widget.
+    #[inline]
+    pub fn style(&self) -> Option<&str> {
+        self.style.as_deref()
+    }
+
     #[inline]
     pub fn measure_override(
         &self,
@@ -1114,6 +1150,7 @@ pub struct WidgetBuilder {
     pub layout_transform: Matrix3<f32>,
     pub render_transform: Matrix3<f32>,
     pub clip_to_bounds: bool,
+    pub style: Option<String>,
 }
 
 impl Default for WidgetBuilder {
@@ -1157,6 +1194,7 @@ impl WidgetBuilder {
             layout_transform: Matrix3::identity(),
             render_transform: Matrix3::identity(),
             clip_to_bounds: true,
+            style: None,
         }
     }
 
@@ -1342,6 +1380,13 @@ impl WidgetBuilder {
         self
     }
 
+    /// Sets a name of a style of the current theme, that will be applied to the widget. See
+    /// [`crate::theme::Style`] docs for more info.
+    pub fn with_style<S: AsRef<str>>(mut self, style: S) -> Self {
+        self.style = Some(style.as_ref().to_owned());
+        self
+    }
+
     pub fn build(self) -> Widget {
         Widget {
             handle: Default::default(),
@@ -1394,6 +1439,7 @@ impl WidgetBuilder {
             render_transform: self.render_transform,
             visual_transform: Matrix3::identity(),
             clip_to_bounds: self.clip_to_bounds,
+            style: self.style,
         }
     }
 }
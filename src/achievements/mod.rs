@@ -0,0 +1,628 @@
+//! Platform-agnostic achievements and statistics. See [`Achievements`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        futures::executor::block_on,
+        parking_lot::Mutex,
+        visitor::{prelude::*, VisitError},
+    },
+    utils::log::Log,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A value of a statistic.
+#[derive(Copy, Clone, Debug, PartialEq, Visit)]
+pub enum StatValue {
+    /// Integer value.
+    Int(i32),
+    /// Floating-point value.
+    Float(f32),
+}
+
+impl Default for StatValue {
+    fn default() -> Self {
+        Self::Int(0)
+    }
+}
+
+/// Progress of an achievement.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Visit)]
+pub struct AchievementProgress {
+    /// Current progress.
+    pub current: u32,
+    /// Progress at which the achievement is unlocked.
+    pub max: u32,
+}
+
+/// An operation that changes achievements or stats. Operations are cached until they are successfully
+/// sent to a backend.
+#[derive(Clone, Debug, PartialEq, Visit)]
+pub enum AchievementOperation {
+    /// Unlocks an achievement with the given id.
+    Unlock(String),
+    /// Locks an achievement with the given id.
+    Clear(String),
+    /// Sets progress of an achievement.
+    Progress {
+        /// Id of an achievement.
+        id: String,
+        /// New progress.
+        progress: AchievementProgress,
+    },
+    /// Sets a value of a stat.
+    SetStat {
+        /// Name of a stat.
+        name: String,
+        /// New value.
+        value: StatValue,
+    },
+}
+
+impl Default for AchievementOperation {
+    fn default() -> Self {
+        Self::Unlock(Default::default())
+    }
+}
+
+/// An error that may occur when working with achievements.
+#[derive(Debug)]
+pub enum AchievementsError {
+    /// A backend is unreachable at the moment (for example, there is no network connection or
+    /// a storefront client is not running). Operations will be kept in the cache.
+    Offline,
+    /// A backend rejected an operation.
+    Backend(String),
+    /// Unable to read or write a file.
+    Io(VisitError),
+}
+
+impl Display for AchievementsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AchievementsError::Offline => write!(f, "Achievements backend is offline."),
+            AchievementsError::Backend(v) => write!(f, "Achievements backend error: {}", v),
+            AchievementsError::Io(v) => write!(f, "Achievements i/o error: {:?}", v),
+        }
+    }
+}
+
+impl From<VisitError> for AchievementsError {
+    fn from(e: VisitError) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// A snapshot of achievements and stats.
+#[derive(Clone, Debug, Default, PartialEq, Visit)]
+pub struct AchievementsData {
+    /// A set of ids of unlocked achievements.
+    pub unlocked: HashSet<String>,
+    /// Progress of achievements.
+    pub progress: HashMap<String, AchievementProgress>,
+    /// Values of stats.
+    pub stats: HashMap<String, StatValue>,
+}
+
+impl AchievementsData {
+    /// Applies an operation to the data.
+    pub fn apply(&mut self, operation: &AchievementOperation) {
+        match operation {
+            AchievementOperation::Unlock(id) => {
+                self.unlocked.insert(id.clone());
+            }
+            AchievementOperation::Clear(id) => {
+                self.unlocked.remove(id);
+                self.progress.remove(id);
+            }
+            AchievementOperation::Progress { id, progress } => {
+                self.progress.insert(id.clone(), *progress);
+                if progress.current >= progress.max {
+                    self.unlocked.insert(id.clone());
+                }
+            }
+            AchievementOperation::SetStat { name, value } => {
+                self.stats.insert(name.clone(), *value);
+            }
+        }
+    }
+
+    /// Loads data from the given file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, AchievementsError> {
+        let mut data = Self::default();
+        let mut visitor = block_on(Visitor::load_binary(path))?;
+        data.visit("Data", &mut visitor)?;
+        Ok(data)
+    }
+
+    /// Saves data to the given file.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<(), AchievementsError> {
+        let mut visitor = Visitor::new();
+        self.visit("Data", &mut visitor)?;
+        visitor.save_binary(path)?;
+        Ok(())
+    }
+}
+
+/// Achievements backend is a bridge between [`Achievements`] service and a particular storefront
+/// (Steam, consoles, etc.) or a local storage.
+pub trait AchievementsBackend: Send {
+    /// Sends an operation to the backend. Return [`AchievementsError::Offline`] if the backend is
+    /// unreachable at the moment, the operation will be kept in the cache and sent later.
+    fn apply(&mut self, operation: &AchievementOperation) -> Result<(), AchievementsError>;
+
+    /// Commits every applied operation (for example, stores stats on a server).
+    fn flush(&mut self) -> Result<(), AchievementsError>;
+
+    /// Returns current state of achievements and stats known by the backend, if any. It is used to
+    /// synchronize local state when the backend is set.
+    fn fetch(&mut self) -> Option<AchievementsData> {
+        None
+    }
+}
+
+/// Backend that stores achievements and stats in a local file. It could be used for storefronts
+/// without achievements or for testing.
+pub struct LocalFileBackend {
+    path: PathBuf,
+    data: AchievementsData,
+}
+
+impl LocalFileBackend {
+    /// Creates new backend that stores data in the given file. Existing data is loaded from the file.
+    /// If the file cannot be loaded, the backend starts with empty data and the file is renamed
+    /// to `<file name>.bak`, so it won't be overwritten on the next flush.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_owned();
+
+        let data = if path.exists() {
+            match AchievementsData::load(&path) {
+                Ok(data) => data,
+                Err(e) => {
+                    let backup_path = Self::backup_path(&path);
+                    Log::err(format!(
+                        "Unable to load achievements from {}, the file was moved to {}. Reason: {}",
+                        path.display(),
+                        backup_path.display(),
+                        e
+                    ));
+                    Log::verify(std::fs::rename(&path, &backup_path));
+                    Default::default()
+                }
+            }
+        } else {
+            Default::default()
+        };
+
+        Self { path, data }
+    }
+
+    fn backup_path(path: &Path) -> PathBuf {
+        let mut backup_path = path.as_os_str().to_owned();
+        backup_path.push(".bak");
+        PathBuf::from(backup_path)
+    }
+
+    /// Returns current data of the backend.
+    pub fn data(&self) -> &AchievementsData {
+        &self.data
+    }
+}
+
+impl AchievementsBackend for LocalFileBackend {
+    fn apply(&mut self, operation: &AchievementOperation) -> Result<(), AchievementsError> {
+        self.data.apply(operation);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), AchievementsError> {
+        self.data.save(&self.path)
+    }
+
+    fn fetch(&mut self) -> Option<AchievementsData> {
+        Some(self.data.clone())
+    }
+}
+
+/// Backend that uses Steam achievements and stats. Achievement ids and stat names must match API
+/// names in Steamworks.
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+pub struct SteamAchievementsBackend {
+    steam: crate::steam::Steam,
+}
+
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+impl SteamAchievementsBackend {
+    /// Creates new backend.
+    pub fn new(steam: crate::steam::Steam) -> Self {
+        Self { steam }
+    }
+}
+
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+impl AchievementsBackend for SteamAchievementsBackend {
+    fn apply(&mut self, operation: &AchievementOperation) -> Result<(), AchievementsError> {
+        let ok = match operation {
+            AchievementOperation::Unlock(id) => self.steam.unlock_achievement(id),
+            AchievementOperation::Clear(id) => self.steam.clear_achievement(id),
+            AchievementOperation::Progress { id, progress } => {
+                if progress.current >= progress.max {
+                    self.steam.unlock_achievement(id)
+                } else {
+                    self.steam
+                        .indicate_achievement_progress(id, progress.current, progress.max)
+                }
+            }
+            AchievementOperation::SetStat { name, value } => match *value {
+                StatValue::Int(value) => self.steam.set_stat_i32(name, value),
+                StatValue::Float(value) => self.steam.set_stat_f32(name, value),
+            },
+        };
+
+        if ok {
+            Ok(())
+        } else {
+            Err(AchievementsError::Backend(format!(
+                "Steam rejected {:?}",
+                operation
+            )))
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), AchievementsError> {
+        if self.steam.store_stats() {
+            Ok(())
+        } else {
+            Err(AchievementsError::Offline)
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    backend: Option<Box<dyn AchievementsBackend>>,
+    data: AchievementsData,
+    pending: Vec<AchievementOperation>,
+    cache_path: Option<PathBuf>,
+}
+
+#[derive(Default, Visit)]
+struct Cache {
+    data: AchievementsData,
+    pending: Vec<AchievementOperation>,
+}
+
+impl State {
+    fn push(&mut self, operation: AchievementOperation) {
+        self.data.apply(&operation);
+        self.pending.push(operation);
+    }
+
+    fn save_cache(&mut self) -> Result<(), AchievementsError> {
+        if let Some(path) = self.cache_path.as_ref() {
+            let mut cache = Cache {
+                data: std::mem::take(&mut self.data),
+                pending: std::mem::take(&mut self.pending),
+            };
+            let mut visitor = Visitor::new();
+            let result = cache.visit("Cache", &mut visitor);
+            self.data = cache.data;
+            self.pending = cache.pending;
+            result?;
+            visitor.save_binary(path)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), AchievementsError> {
+        let mut result = Ok(());
+
+        if let Some(backend) = self.backend.as_mut() {
+            let mut sent = 0;
+            for operation in self.pending.iter() {
+                if let Err(e) = backend.apply(operation) {
+                    result = Err(e);
+                    break;
+                }
+                sent += 1;
+            }
+            if sent > 0 {
+                match backend.flush() {
+                    Ok(_) => {
+                        self.pending.drain(..sent);
+                    }
+                    Err(e) => result = Err(e),
+                }
+            }
+        } else {
+            result = Err(AchievementsError::Offline);
+        }
+
+        // Keep unsent operations on disk, so they won't be lost if the game is closed.
+        self.save_cache().and(result)
+    }
+}
+
+/// Achievements service allows you to unlock achievements, track their progress and change stats
+/// without depending on a particular storefront. Every change is applied to the local state
+/// immediately and cached until it is sent to a backend by [`Self::flush`], so the game could work
+/// offline. The cache could be stored on disk (see [`Self::set_cache_path`]), so the changes will
+/// not be lost if the game is closed before flushing.
+///
+/// Clones share the same local state and the same cache of unsent operations.
+///
+/// ```rust
+/// use fyrox::achievements::{Achievements, StatValue};
+///
+/// fn on_enemy_killed(achievements: &Achievements, total_kills: u32) {
+///     achievements.set_stat("kills", StatValue::Int(total_kills as i32));
+///     achievements.set_progress("ACH_KILL_100", total_kills, 100);
+///     if total_kills == 1 {
+///         achievements.unlock("ACH_FIRST_BLOOD");
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Achievements {
+    state: Arc<Mutex<State>>,
+}
+
+impl Debug for Achievements {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Achievements")
+            .field("has_backend", &state.backend.is_some())
+            .field("data", &state.data)
+            .field("pending", &state.pending)
+            .finish()
+    }
+}
+
+impl Achievements {
+    /// Creates new achievements service without a backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets new backend, returns previous one. Local state is merged with the state fetched from the
+    /// new backend (if any), cached operations will be sent on next [`Self::flush`].
+    pub fn set_backend(
+        &self,
+        backend: Option<Box<dyn AchievementsBackend>>,
+    ) -> Option<Box<dyn AchievementsBackend>> {
+        let mut state = self.state.lock();
+        let prev = std::mem::replace(&mut state.backend, backend);
+        if let Some(mut data) = state.backend.as_mut().and_then(|b| b.fetch()) {
+            for operation in state.pending.iter() {
+                data.apply(operation);
+            }
+            state.data = data;
+        }
+        prev
+    }
+
+    /// Sets a path to the file, that will be used to store cached operations. If the file exists,
+    /// cached state and operations will be loaded from it.
+    pub fn set_cache_path<P: AsRef<Path>>(&self, path: P) -> Result<(), AchievementsError> {
+        let mut state = self.state.lock();
+        let path = path.as_ref().to_owned();
+        if path.exists() {
+            let mut cache = Cache::default();
+            let mut visitor = block_on(Visitor::load_binary(&path))?;
+            cache.visit("Cache", &mut visitor)?;
+
+            // Cached data already includes cached operations, apply the recent ones on top of it.
+            let mut data = cache.data;
+            for operation in state.pending.iter() {
+                data.apply(operation);
+            }
+            state.data = data;
+
+            let recent = std::mem::take(&mut state.pending);
+            state.pending = cache.pending;
+            state.pending.extend(recent);
+        }
+        state.cache_path = Some(path);
+        Ok(())
+    }
+
+    /// Unlocks an achievement with the given id.
+    pub fn unlock(&self, id: &str) {
+        let mut state = self.state.lock();
+        if !state.data.unlocked.contains(id) {
+            state.push(AchievementOperation::Unlock(id.to_owned()));
+        }
+    }
+
+    /// Locks an achievement with the given id and resets its progress (useful for testing).
+    pub fn clear(&self, id: &str) {
+        self.state
+            .lock()
+            .push(AchievementOperation::Clear(id.to_owned()));
+    }
+
+    /// Sets progress of an achievement, the achievement will be unlocked when `current >= max`.
+    pub fn set_progress(&self, id: &str, current: u32, max: u32) {
+        let mut state = self.state.lock();
+        let progress = AchievementProgress { current, max };
+        if !state.data.unlocked.contains(id) && state.data.progress.get(id) != Some(&progress) {
+            state.push(AchievementOperation::Progress {
+                id: id.to_owned(),
+                progress,
+            });
+        }
+    }
+
+    /// Sets a value of a stat.
+    pub fn set_stat(&self, name: &str, value: StatValue) {
+        self.state.lock().push(AchievementOperation::SetStat {
+            name: name.to_owned(),
+            value,
+        });
+    }
+
+    /// Returns `true` if an achievement with the given id is unlocked.
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.state.lock().data.unlocked.contains(id)
+    }
+
+    /// Returns progress of an achievement with the given id.
+    pub fn progress(&self, id: &str) -> Option<AchievementProgress> {
+        self.state.lock().data.progress.get(id).cloned()
+    }
+
+    /// Returns a value of a stat with the given name.
+    pub fn stat(&self, name: &str) -> Option<StatValue> {
+        self.state.lock().data.stats.get(name).cloned()
+    }
+
+    /// Returns a copy of current local state.
+    pub fn data(&self) -> AchievementsData {
+        self.state.lock().data.clone()
+    }
+
+    /// Returns amount of operations, that were not sent to the backend yet.
+    pub fn pending_count(&self) -> usize {
+        self.state.lock().pending.len()
+    }
+
+    /// Sends every cached operation to the backend. If the backend is offline, the operations stay
+    /// in the cache (and in the cache file) and will be sent on next call.
+    pub fn flush(&self) -> Result<(), AchievementsError> {
+        self.state.lock().flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::achievements::{
+        AchievementOperation, Achievements, AchievementsBackend, AchievementsError,
+        LocalFileBackend, StatValue,
+    };
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    struct FlakyBackend {
+        online: Arc<AtomicBool>,
+        received: Vec<AchievementOperation>,
+    }
+
+    impl AchievementsBackend for FlakyBackend {
+        fn apply(&mut self, operation: &AchievementOperation) -> Result<(), AchievementsError> {
+            if self.online.load(Ordering::SeqCst) {
+                self.received.push(operation.clone());
+                Ok(())
+            } else {
+                Err(AchievementsError::Offline)
+            }
+        }
+
+        fn flush(&mut self) -> Result<(), AchievementsError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_offline_caching() {
+        let online = Arc::new(AtomicBool::new(false));
+        let achievements = Achievements::new();
+        achievements.set_backend(Some(Box::new(FlakyBackend {
+            online: online.clone(),
+            received: Default::default(),
+        })));
+
+        achievements.unlock("ACH_FIRST");
+        achievements.unlock("ACH_FIRST");
+        achievements.set_progress("ACH_TEN", 5, 10);
+        achievements.set_stat("kills", StatValue::Int(5));
+
+        // State is available immediately even if the backend is offline.
+        assert!(achievements.is_unlocked("ACH_FIRST"));
+        assert!(!achievements.is_unlocked("ACH_TEN"));
+        assert_eq!(achievements.stat("kills"), Some(StatValue::Int(5)));
+        assert_eq!(achievements.pending_count(), 3);
+
+        assert!(matches!(
+            achievements.flush(),
+            Err(AchievementsError::Offline)
+        ));
+        assert_eq!(achievements.pending_count(), 3);
+
+        online.store(true, Ordering::SeqCst);
+        assert!(achievements.flush().is_ok());
+        assert_eq!(achievements.pending_count(), 0);
+
+        achievements.set_progress("ACH_TEN", 10, 10);
+        assert!(achievements.is_unlocked("ACH_TEN"));
+    }
+
+    #[test]
+    fn test_cache_file() {
+        let path = std::env::temp_dir().join("fyrox_test_achievements_cache.bin");
+        let _ = std::fs::remove_file(&path);
+
+        let achievements = Achievements::new();
+        achievements.set_cache_path(&path).unwrap();
+        achievements.unlock("ACH_OFFLINE");
+        // There is no backend, so the operation is stored in the cache file.
+        assert!(achievements.flush().is_err());
+
+        let achievements = Achievements::new();
+        achievements.set_cache_path(&path).unwrap();
+        assert!(achievements.is_unlocked("ACH_OFFLINE"));
+        assert_eq!(achievements.pending_count(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_local_file_backend() {
+        let path = std::env::temp_dir().join("fyrox_test_achievements.bin");
+        let _ = std::fs::remove_file(&path);
+
+        let achievements = Achievements::new();
+        achievements.set_backend(Some(Box::new(LocalFileBackend::new(&path))));
+        achievements.unlock("ACH_WIN");
+        achievements.set_stat("time", StatValue::Float(1.5));
+        achievements.flush().unwrap();
+
+        let backend = LocalFileBackend::new(&path);
+        assert!(backend.data().unlocked.contains("ACH_WIN"));
+        assert_eq!(
+            backend.data().stats.get("time"),
+            Some(&StatValue::Float(1.5))
+        );
+
+        // State is fetched from the backend when it is set.
+        let achievements = Achievements::new();
+        achievements.set_backend(Some(Box::new(backend)));
+        assert!(achievements.is_unlocked("ACH_WIN"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_local_file_backend_corrupted_file() {
+        let path = std::env::temp_dir().join("fyrox_test_achievements_corrupted.bin");
+        let backup_path = std::env::temp_dir().join("fyrox_test_achievements_corrupted.bin.bak");
+        let _ = std::fs::remove_file(&backup_path);
+        std::fs::write(&path, b"garbage").unwrap();
+
+        let backend = LocalFileBackend::new(&path);
+        assert_eq!(backend.data(), &Default::default());
+
+        // Corrupted file must be kept aside instead of being overwritten.
+        assert!(!path.exists());
+        assert_eq!(std::fs::read(&backup_path).unwrap(), b"garbage");
+
+        let _ = std::fs::remove_file(&backup_path);
+    }
+}
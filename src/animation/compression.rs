@@ -0,0 +1,340 @@
+//! Animation compression allows you to reduce memory usage of animation tracks by removing redundant keys and
+//! quantizing the rest. See [`CompressedCurve`] docs for more info.
+
+use crate::core::{
+    curve::{Curve, CurveKey, CurveKeyKind},
+    math::lerpf,
+    visitor::{prelude::*, PodVecView},
+};
+
+const QUANTIZATION_LEVELS: f32 = u16::MAX as f32;
+
+fn quantize(value: f32, min: f32, scale: f32) -> u16 {
+    if scale > 0.0 {
+        ((value - min) / scale)
+            .round()
+            .clamp(0.0, QUANTIZATION_LEVELS) as u16
+    } else {
+        0
+    }
+}
+
+fn dequantize(value: u16, min: f32, scale: f32) -> f32 {
+    min + value as f32 * scale
+}
+
+/// A curve with linear or constant keys stored in quantized form: every key takes 5 bytes (16-bit time, 16-bit
+/// value and 1 byte for the kind) instead of the full [`CurveKey`]. Values are decoded on sampling.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuantizedCurve {
+    time_min: f32,
+    time_scale: f32,
+    value_min: f32,
+    value_scale: f32,
+    times: Vec<u16>,
+    values: Vec<u16>,
+    // 1 - linear, 0 - constant.
+    kinds: Vec<u8>,
+}
+
+impl Visit for QuantizedCurve {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.time_min.visit("TimeMin", &mut region)?;
+        self.time_scale.visit("TimeScale", &mut region)?;
+        self.value_min.visit("ValueMin", &mut region)?;
+        self.value_scale.visit("ValueScale", &mut region)?;
+        PodVecView::from_pod_vec(&mut self.times).visit("Times", &mut region)?;
+        PodVecView::from_pod_vec(&mut self.values).visit("Values", &mut region)?;
+        PodVecView::from_pod_vec(&mut self.kinds).visit("Kinds", &mut region)?;
+
+        Ok(())
+    }
+}
+
+impl QuantizedCurve {
+    fn from_keys(keys: &[&CurveKey]) -> Self {
+        let (mut time_min, mut time_max) = (f32::MAX, f32::MIN);
+        let (mut value_min, mut value_max) = (f32::MAX, f32::MIN);
+        for key in keys {
+            time_min = time_min.min(key.location());
+            time_max = time_max.max(key.location());
+            value_min = value_min.min(key.value);
+            value_max = value_max.max(key.value);
+        }
+
+        if keys.is_empty() {
+            return Default::default();
+        }
+
+        let time_scale = (time_max - time_min) / QUANTIZATION_LEVELS;
+        let value_scale = (value_max - value_min) / QUANTIZATION_LEVELS;
+
+        Self {
+            time_min,
+            time_scale,
+            value_min,
+            value_scale,
+            times: keys
+                .iter()
+                .map(|k| quantize(k.location(), time_min, time_scale))
+                .collect(),
+            values: keys
+                .iter()
+                .map(|k| quantize(k.value, value_min, value_scale))
+                .collect(),
+            kinds: keys
+                .iter()
+                .map(|k| matches!(k.kind, CurveKeyKind::Linear) as u8)
+                .collect(),
+        }
+    }
+
+    /// Returns amount of keys in the curve.
+    pub fn keys_count(&self) -> usize {
+        self.times.len()
+    }
+
+    fn location(&self, index: usize) -> f32 {
+        dequantize(self.times[index], self.time_min, self.time_scale)
+    }
+
+    fn value(&self, index: usize) -> f32 {
+        dequantize(self.values[index], self.value_min, self.value_scale)
+    }
+
+    fn key(&self, index: usize) -> CurveKey {
+        CurveKey::new(
+            self.location(index),
+            self.value(index),
+            if self.kinds[index] != 0 {
+                CurveKeyKind::Linear
+            } else {
+                CurveKeyKind::Constant
+            },
+        )
+    }
+
+    /// Returns location of the last key of the curve.
+    pub fn max_location(&self) -> f32 {
+        if self.times.is_empty() {
+            0.0
+        } else {
+            self.location(self.times.len() - 1)
+        }
+    }
+
+    /// Samples the curve at the given location. It behaves exactly like [`Curve::value_at`].
+    pub fn value_at(&self, location: f32) -> f32 {
+        let count = self.times.len();
+        if count == 0 {
+            return 0.0;
+        }
+
+        if location <= self.location(0) {
+            self.value(0)
+        } else if location >= self.location(count - 1) {
+            self.value(count - 1)
+        } else {
+            // Times are sorted, so it is possible to search in quantized domain.
+            let quantized = (location - self.time_min) / self.time_scale;
+            let right = self.times.partition_point(|&t| (t as f32) < quantized);
+            let left = right.saturating_sub(1);
+            let left_location = self.location(left);
+            let right_location = self.location(right);
+            let span = right_location - left_location;
+            let t = if span > 0.0 {
+                (location - left_location) / span
+            } else {
+                1.0
+            };
+            if self.kinds[left] != 0 {
+                lerpf(self.value(left), self.value(right), t)
+            } else if t.eq(&1.0) {
+                self.value(right)
+            } else {
+                self.value(left)
+            }
+        }
+    }
+
+    /// Returns approximate amount of memory (in bytes) used by the keys of the curve.
+    pub fn memory_usage(&self) -> usize {
+        self.times.len() * std::mem::size_of::<u16>()
+            + self.values.len() * std::mem::size_of::<u16>()
+            + self.kinds.len() * std::mem::size_of::<u8>()
+    }
+}
+
+/// Compressed form of a [`Curve`]. Curves with linear and constant keys are stored in quantized form, curves with
+/// cubic keys cannot be quantized (tangents are too sensitive to precision loss) and stored as is.
+#[derive(Debug, Clone, PartialEq, Visit)]
+pub enum CompressedCurve {
+    /// Quantized curve, see [`QuantizedCurve`] docs for more info.
+    Quantized(QuantizedCurve),
+    /// Raw curve, that could not be compressed.
+    Raw(Curve),
+}
+
+impl Default for CompressedCurve {
+    fn default() -> Self {
+        Self::Raw(Default::default())
+    }
+}
+
+impl CompressedCurve {
+    /// Compresses the given curve. At first, every key that could be restored by interpolation of its neighbours
+    /// with an error less or equal than `tolerance` is removed. Then, the rest of the keys are quantized to 16-bit
+    /// integers.
+    pub fn compress(curve: &Curve, tolerance: f32) -> Self {
+        let keys = curve.keys();
+        if keys
+            .iter()
+            .any(|k| matches!(k.kind, CurveKeyKind::Cubic { .. }))
+        {
+            return Self::Raw(curve.clone());
+        }
+
+        let kept = reduce_keys(keys, tolerance.max(0.0));
+        Self::Quantized(QuantizedCurve::from_keys(
+            &kept.into_iter().map(|i| &keys[i]).collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Restores a curve from its compressed form. Removed keys won't be restored and the values of the rest
+    /// will have quantization error.
+    pub fn decompress(&self) -> Curve {
+        match self {
+            CompressedCurve::Quantized(quantized) => Curve::from(
+                (0..quantized.keys_count())
+                    .map(|i| quantized.key(i))
+                    .collect::<Vec<_>>(),
+            ),
+            CompressedCurve::Raw(curve) => curve.clone(),
+        }
+    }
+
+    /// Samples the curve at the given location.
+    pub fn value_at(&self, location: f32) -> f32 {
+        match self {
+            CompressedCurve::Quantized(quantized) => quantized.value_at(location),
+            CompressedCurve::Raw(curve) => curve.value_at(location),
+        }
+    }
+
+    /// Returns location of the last key of the curve.
+    pub fn max_location(&self) -> f32 {
+        match self {
+            CompressedCurve::Quantized(quantized) => quantized.max_location(),
+            CompressedCurve::Raw(curve) => curve.max_location(),
+        }
+    }
+
+    /// Returns approximate amount of memory (in bytes) used by the keys of the curve.
+    pub fn memory_usage(&self) -> usize {
+        match self {
+            CompressedCurve::Quantized(quantized) => quantized.memory_usage(),
+            CompressedCurve::Raw(curve) => curve_memory_usage(curve),
+        }
+    }
+}
+
+pub(crate) fn curve_memory_usage(curve: &Curve) -> usize {
+    std::mem::size_of_val(curve.keys())
+}
+
+/// Returns indices of keys that must be kept to represent the curve with the given tolerance.
+fn reduce_keys(keys: &[CurveKey], tolerance: f32) -> Vec<usize> {
+    let count = keys.len();
+    if count <= 2 {
+        return (0..count).collect();
+    }
+
+    // Checks whether every key between `anchor` and `end` could be restored by interpolation.
+    let can_skip_between = |anchor: usize, end: usize| {
+        let a = &keys[anchor];
+        let b = &keys[end];
+        keys[(anchor + 1)..end].iter().all(|k| {
+            if k.kind != a.kind {
+                return false;
+            }
+            let span = b.location() - a.location();
+            let t = if span > 0.0 {
+                (k.location() - a.location()) / span
+            } else {
+                0.0
+            };
+            (a.interpolate(b, t) - k.value).abs() <= tolerance
+        })
+    };
+
+    let mut kept = vec![0];
+    let mut anchor = 0;
+    let mut end = 2;
+    while end < count {
+        if can_skip_between(anchor, end) {
+            end += 1;
+        } else {
+            anchor = end - 1;
+            kept.push(anchor);
+            end = anchor + 2;
+        }
+    }
+    kept.push(count - 1);
+    kept
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::compression::CompressedCurve,
+        core::curve::{Curve, CurveKey, CurveKeyKind},
+    };
+
+    #[test]
+    fn test_curve_compression() {
+        // Sampled sine wave with a long constant part, which is typical for imported animations.
+        let mut keys = (0..120)
+            .map(|i| {
+                let t = i as f32 / 30.0;
+                CurveKey::new(t, t.sin(), CurveKeyKind::Linear)
+            })
+            .collect::<Vec<_>>();
+        keys.extend(
+            (120..240).map(|i| CurveKey::new(i as f32 / 30.0, 4.0f32.sin(), CurveKeyKind::Linear)),
+        );
+        let curve = Curve::from(keys);
+
+        let tolerance = 0.001;
+        let compressed = CompressedCurve::compress(&curve, tolerance);
+        assert!(matches!(compressed, CompressedCurve::Quantized(_)));
+        assert!(compressed.memory_usage() * 3 <= super::curve_memory_usage(&curve));
+        assert_eq!(compressed.max_location(), curve.max_location());
+
+        for i in 0..800 {
+            let t = i as f32 / 100.0;
+            assert!(
+                (compressed.value_at(t) - curve.value_at(t)).abs() <= tolerance * 2.0,
+                "{} {} {}",
+                t,
+                compressed.value_at(t),
+                curve.value_at(t)
+            );
+        }
+
+        let decompressed = compressed.decompress();
+        assert!(decompressed.keys().len() < curve.keys().len());
+        assert!((decompressed.value_at(1.5) - curve.value_at(1.5)).abs() <= tolerance * 2.0);
+
+        // Cubic curves are stored as is.
+        let cubic = Curve::from(vec![
+            CurveKey::new(0.0, 0.0, CurveKeyKind::new_cubic(0.0, 0.0)),
+            CurveKey::new(1.0, 1.0, CurveKeyKind::new_cubic(0.0, 0.0)),
+        ]);
+        assert_eq!(
+            CompressedCurve::compress(&cubic, tolerance),
+            CompressedCurve::Raw(cubic)
+        );
+    }
+}
@@ -2,7 +2,10 @@
 //! of parametric curves. See [`TrackDataContainer`] docs for more info.
 
 use crate::{
-    animation::value::TrackValue,
+    animation::{
+        compression::{curve_memory_usage, CompressedCurve},
+        value::TrackValue,
+    },
     core::{
         algebra::{Vector2, Vector3, Vector4},
         curve::Curve,
@@ -64,10 +67,19 @@ impl Default for TrackValueKind {
 ///
 /// Each component is bound to a specific curve. For example, in case of [`Vector3`] its components bound
 /// to the following curve indices: `X = 0`, `Y = 1`, `Z = 2`. This order cannot be changed.
+///
+/// ## Compression
+///
+/// Imported animations usually have a key per frame for every curve, which takes a lot of memory for large
+/// animation sets. The container could be compressed using [`Self::compress`], compressed curves are decoded
+/// on sampling. Compressed curves cannot be edited, use [`Self::decompress`] to restore editable curves.
 #[derive(Visit, Reflect, Debug, Clone, Default, PartialEq)]
 pub struct TrackDataContainer {
     curves: Vec<Curve>,
     kind: TrackValueKind,
+    #[visit(optional)] // Backward compatibility
+    #[reflect(hidden)]
+    compressed: Vec<CompressedCurve>,
 }
 
 impl TrackDataContainer {
@@ -84,6 +96,7 @@ impl TrackDataContainer {
             curves: (0..kind.components_count())
                 .map(|_| Curve::default())
                 .collect(),
+            compressed: Default::default(),
         }
     }
 
@@ -124,32 +137,79 @@ impl TrackDataContainer {
         self.kind
     }
 
+    /// Compresses every curve of the container with the given tolerance, see [`CompressedCurve::compress`] for
+    /// more info. After compression, the container has no editable curves (methods like [`Self::curve`] will
+    /// return nothing), but it still can produce values. Compressing already compressed container has no effect.
+    pub fn compress(&mut self, tolerance: f32) {
+        if !self.is_compressed() {
+            self.compressed = self
+                .curves
+                .drain(..)
+                .map(|curve| CompressedCurve::compress(&curve, tolerance))
+                .collect();
+        }
+    }
+
+    /// Restores editable curves from their compressed form. Keys removed by compression won't be restored.
+    pub fn decompress(&mut self) {
+        if self.is_compressed() {
+            self.curves = self
+                .compressed
+                .drain(..)
+                .map(|curve| curve.decompress())
+                .collect();
+        }
+    }
+
+    /// Returns `true` if the container is compressed, `false` - otherwise.
+    pub fn is_compressed(&self) -> bool {
+        !self.compressed.is_empty()
+    }
+
+    /// Returns approximate amount of memory (in bytes) used by the keys of the curves of the container.
+    pub fn memory_usage(&self) -> usize {
+        self.curves.iter().map(curve_memory_usage).sum::<usize>()
+            + self
+                .compressed
+                .iter()
+                .map(|c| c.memory_usage())
+                .sum::<usize>()
+    }
+
+    fn value_at(&self, index: usize, time: f32) -> Option<f32> {
+        if self.is_compressed() {
+            self.compressed.get(index).map(|c| c.value_at(time))
+        } else {
+            self.curves.get(index).map(|c| c.value_at(time))
+        }
+    }
+
     /// Tries to get a value at a given time. The method could fail if the internal set of curves is malformed
     /// and cannot produce a desired value (for example, [`Vector3`] can be fetched only if the amount of curves
     /// is 3).
     pub fn fetch(&self, time: f32) -> Option<TrackValue> {
         match self.kind {
-            TrackValueKind::Real => Some(TrackValue::Real(self.curves.get(0)?.value_at(time))),
+            TrackValueKind::Real => Some(TrackValue::Real(self.value_at(0, time)?)),
             TrackValueKind::Vector2 => Some(TrackValue::Vector2(Vector2::new(
-                self.curves.get(0)?.value_at(time),
-                self.curves.get(1)?.value_at(time),
+                self.value_at(0, time)?,
+                self.value_at(1, time)?,
             ))),
             TrackValueKind::Vector3 => Some(TrackValue::Vector3(Vector3::new(
-                self.curves.get(0)?.value_at(time),
-                self.curves.get(1)?.value_at(time),
-                self.curves.get(2)?.value_at(time),
+                self.value_at(0, time)?,
+                self.value_at(1, time)?,
+                self.value_at(2, time)?,
             ))),
             TrackValueKind::Vector4 => Some(TrackValue::Vector4(Vector4::new(
-                self.curves.get(0)?.value_at(time),
-                self.curves.get(1)?.value_at(time),
-                self.curves.get(2)?.value_at(time),
-                self.curves.get(3)?.value_at(time),
+                self.value_at(0, time)?,
+                self.value_at(1, time)?,
+                self.value_at(2, time)?,
+                self.value_at(3, time)?,
             ))),
             TrackValueKind::UnitQuaternion => {
                 // Convert Euler angles to quaternion
-                let x = self.curves.get(0)?.value_at(time);
-                let y = self.curves.get(1)?.value_at(time);
-                let z = self.curves.get(2)?.value_at(time);
+                let x = self.value_at(0, time)?;
+                let y = self.value_at(1, time)?;
+                let z = self.value_at(2, time)?;
 
                 Some(TrackValue::UnitQuaternion(quat_from_euler(
                     Vector3::new(x, y, z),
@@ -163,8 +223,12 @@ impl TrackDataContainer {
     /// can be treated as a maximum "length" of the container.
     pub fn time_length(&self) -> f32 {
         let mut length = 0.0;
-        for curve in self.curves.iter() {
-            let max_location = curve.max_location();
+        let locations = self
+            .curves
+            .iter()
+            .map(|c| c.max_location())
+            .chain(self.compressed.iter().map(|c| c.max_location()));
+        for max_location in locations {
             if max_location > length {
                 length = max_location;
             }
@@ -3,9 +3,12 @@
 
 use crate::{
     animation::machine::{State, Transition},
-    core::pool::Handle,
+    core::{parking_lot::Mutex, pool::Handle},
+};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
 };
-use std::collections::VecDeque;
 
 /// Specific state machine event.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -67,3 +70,89 @@ impl FixedEventQueue {
         self.queue.pop_front()
     }
 }
+
+/// Kind of [`StateEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateEventKind {
+    /// A state was entered.
+    Enter,
+    /// A state was left.
+    Leave,
+}
+
+/// An event that is delivered to every subscriber of a state machine (see
+/// [`crate::animation::machine::Machine::subscribe_state_events`]) when a state is entered or left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateEvent {
+    /// Index of a layer of the state.
+    pub layer: usize,
+    /// A handle of the state.
+    pub state: Handle<State>,
+    /// Name of the state.
+    pub state_name: String,
+    /// Kind of the event.
+    pub kind: StateEventKind,
+}
+
+type SharedStateEventQueue = Arc<Mutex<VecDeque<StateEvent>>>;
+
+/// Receiving end of a state events subscription. Clones of the receiver share the same queue, so the receiver
+/// could be stored in a script. The subscription ends when every clone of the receiver is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct StateEventReceiver {
+    queue: SharedStateEventQueue,
+}
+
+impl StateEventReceiver {
+    /// Extracts a first event from the queue.
+    pub fn pop(&self) -> Option<StateEvent> {
+        self.queue.lock().pop_front()
+    }
+
+    /// Extracts every event from the queue.
+    pub fn drain(&self) -> Vec<StateEvent> {
+        self.queue.lock().drain(..).collect()
+    }
+}
+
+/// A set of subscribers of state events. Subscribers are not cloned (a clone of a machine has no subscribers)
+/// and not compared.
+#[derive(Debug, Default)]
+pub(crate) struct StateEventSubscribers {
+    subscribers: Vec<Weak<Mutex<VecDeque<StateEvent>>>>,
+}
+
+impl Clone for StateEventSubscribers {
+    fn clone(&self) -> Self {
+        Default::default()
+    }
+}
+
+impl PartialEq for StateEventSubscribers {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl StateEventSubscribers {
+    pub(crate) fn subscribe(&mut self) -> StateEventReceiver {
+        let receiver = StateEventReceiver::default();
+        self.subscribers.push(Arc::downgrade(&receiver.queue));
+        receiver
+    }
+
+    pub(crate) fn notify(&mut self, event: StateEvent) {
+        self.subscribers.retain(|subscriber| {
+            if let Some(queue) = subscriber.upgrade() {
+                let mut queue = queue.lock();
+                // Prevent unbounded growth if the receiver is never polled.
+                if queue.len() < 1024 {
+                    queue.push_back(event.clone());
+                }
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
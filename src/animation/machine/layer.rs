@@ -5,10 +5,10 @@ use crate::utils::NameProvider;
 use crate::{
     animation::{
         machine::{
-            event::FixedEventQueue, Event, LayerMask, Parameter, ParameterContainer, PoseNode,
-            State, Transition,
+            event::{FixedEventQueue, StateEvent, StateEventKind, StateEventSubscribers},
+            Event, LayerMask, Parameter, ParameterContainer, PoseNode, State, Transition,
         },
-        AnimationContainer, AnimationPose,
+        Animation, AnimationContainer, AnimationPose,
     },
     core::{
         pool::{Handle, Pool},
@@ -20,6 +20,7 @@ use crate::{
         log::{Log, MessageKind},
     },
 };
+use fxhash::FxHashMap;
 
 /// Layer is a separate state graph. Layers mainly used to animate different parts of humanoid (but not only) characters. For
 /// example there could a layer for upper body and a layer for lower body. Upper body layer could contain animations for aiming,
@@ -387,12 +388,40 @@ impl MachineLayer {
         &self.mask
     }
 
+    /// Accumulates effective weights of the animations used by active state (or both states of active
+    /// transition) of the layer. Every weight is multiplied by the weight of the layer.
+    pub fn collect_animation_weights(
+        &self,
+        parameters: &ParameterContainer,
+        weights: &mut FxHashMap<Handle<Animation>, f32>,
+    ) {
+        let mut collect_state = |state: Handle<State>, weight: f32| {
+            if let Some(root) = self
+                .states
+                .try_borrow(state)
+                .and_then(|state| self.nodes.try_borrow(state.root))
+            {
+                root.collect_animation_weights(&self.nodes, parameters, weight, weights);
+            }
+        };
+
+        if let Some(transition) = self.transitions.try_borrow(self.active_transition) {
+            let blend_factor = transition.blend_factor();
+            collect_state(transition.source(), self.weight * (1.0 - blend_factor));
+            collect_state(transition.dest(), self.weight * blend_factor);
+        } else {
+            collect_state(self.active_state, self.weight);
+        }
+    }
+
     #[inline]
     pub(super) fn evaluate_pose(
         &mut self,
         animations: &AnimationContainer,
         parameters: &ParameterContainer,
         dt: f32,
+        layer_index: usize,
+        subscribers: &mut StateEventSubscribers,
     ) -> &AnimationPose {
         self.final_pose.reset();
 
@@ -429,17 +458,31 @@ impl MachineLayer {
                                 );
                             }
 
-                            self.events.push(Event::StateEnter(transition.source()));
+                            self.events.push(Event::StateEnter(transition.dest()));
                             if self.debug {
                                 Log::writeln(
                                     MessageKind::Information,
                                     format!(
                                         "Entering state: {}",
-                                        self.states[transition.source()].name
+                                        self.states[transition.dest()].name
                                     ),
                                 );
                             }
 
+                            for (state, kind) in [
+                                (self.active_state, StateEventKind::Leave),
+                                (transition.dest(), StateEventKind::Enter),
+                            ] {
+                                if let Some(state_ref) = self.states.try_borrow(state) {
+                                    subscribers.notify(StateEvent {
+                                        layer: layer_index,
+                                        state,
+                                        state_name: state_ref.name.clone(),
+                                        kind,
+                                    });
+                                }
+                            }
+
                             self.active_state = Handle::NONE;
 
                             self.active_transition = handle;
@@ -6,23 +6,27 @@
 #![warn(missing_docs)]
 
 use crate::{
-    animation::{AnimationContainer, AnimationPose},
+    animation::{Animation, AnimationContainer, AnimationPose},
     core::{
+        pool::Handle,
         reflect::prelude::*,
         visitor::{Visit, VisitResult, Visitor},
     },
     utils,
 };
+use event::StateEventSubscribers;
+use fxhash::FxHashMap;
 
-pub use event::Event;
+pub use event::{Event, StateEvent, StateEventKind, StateEventReceiver};
 pub use layer::MachineLayer;
 pub use mask::LayerMask;
 pub use node::{
     blend::{BlendAnimations, BlendAnimationsByIndex, BlendPose, IndexedBlendInput},
+    blendspace::{BlendSpace, BlendSpacePoint},
     play::PlayAnimation,
     EvaluatePose, PoseNode,
 };
-pub use parameter::{Parameter, ParameterContainer, PoseWeight};
+pub use parameter::{Parameter, ParameterContainer, ParameterError, PoseWeight};
 pub use state::State;
 pub use transition::Transition;
 
@@ -176,6 +180,10 @@ pub struct Machine {
     #[visit(skip)]
     #[reflect(hidden)]
     final_pose: AnimationPose,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    subscribers: StateEventSubscribers,
 }
 
 impl Machine {
@@ -186,6 +194,7 @@ impl Machine {
             parameters: Default::default(),
             layers: vec![MachineLayer::new()],
             final_pose: Default::default(),
+            subscribers: Default::default(),
         }
     }
 
@@ -215,6 +224,126 @@ impl Machine {
         self
     }
 
+    fn typed_parameter_mut(
+        &mut self,
+        name: &str,
+        expected: &'static str,
+    ) -> Result<&mut Parameter, ParameterError> {
+        match self.parameters.get_mut(name) {
+            Some(parameter) if parameter.as_ref() == expected => Ok(parameter),
+            Some(parameter) => Err(ParameterError::TypeMismatch {
+                name: name.to_owned(),
+                expected,
+                actual: parameter.as_ref().to_owned(),
+            }),
+            None => Err(ParameterError::NotFound {
+                name: name.to_owned(),
+            }),
+        }
+    }
+
+    fn typed_parameter(
+        &self,
+        name: &str,
+        expected: &'static str,
+    ) -> Result<Parameter, ParameterError> {
+        match self.parameters.get(name) {
+            Some(parameter) if parameter.as_ref() == expected => Ok(*parameter),
+            Some(parameter) => Err(ParameterError::TypeMismatch {
+                name: name.to_owned(),
+                expected,
+                actual: parameter.as_ref().to_owned(),
+            }),
+            None => Err(ParameterError::NotFound {
+                name: name.to_owned(),
+            }),
+        }
+    }
+
+    /// Sets a new value of an existing Weight parameter. Unlike [`Self::set_parameter`], the method does not
+    /// register new parameters, instead it returns an error if there's no such parameter or it has different
+    /// type. It is useful in scripts, where a typo in a parameter name should not be silently ignored:
+    ///
+    /// ```rust
+    /// use fyrox::animation::machine::{Machine, Parameter};
+    ///
+    /// let mut machine = Machine::new();
+    /// machine.set_parameter("Speed", Parameter::Weight(0.0));
+    ///
+    /// assert!(machine.set_float("Speed", 1.5).is_ok());
+    /// assert!(machine.set_float("Sped", 1.5).is_err());
+    /// assert_eq!(machine.get_float("Speed"), Ok(1.5));
+    /// ```
+    #[inline]
+    pub fn set_float(&mut self, name: &str, value: f32) -> Result<(), ParameterError> {
+        *self.typed_parameter_mut(name, "Weight")? = Parameter::Weight(value);
+        Ok(())
+    }
+
+    /// Returns a value of an existing Weight parameter. See [`Self::set_float`] for more info.
+    #[inline]
+    pub fn get_float(&self, name: &str) -> Result<f32, ParameterError> {
+        match self.typed_parameter(name, "Weight")? {
+            Parameter::Weight(value) => Ok(value),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets a new value of an existing Rule parameter. See [`Self::set_float`] for more info.
+    #[inline]
+    pub fn set_bool(&mut self, name: &str, value: bool) -> Result<(), ParameterError> {
+        *self.typed_parameter_mut(name, "Rule")? = Parameter::Rule(value);
+        Ok(())
+    }
+
+    /// Returns a value of an existing Rule parameter. See [`Self::set_float`] for more info.
+    #[inline]
+    pub fn get_bool(&self, name: &str) -> Result<bool, ParameterError> {
+        match self.typed_parameter(name, "Rule")? {
+            Parameter::Rule(value) => Ok(value),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sets a new value of an existing Index parameter. See [`Self::set_float`] for more info.
+    #[inline]
+    pub fn set_index(&mut self, name: &str, value: u32) -> Result<(), ParameterError> {
+        *self.typed_parameter_mut(name, "Index")? = Parameter::Index(value);
+        Ok(())
+    }
+
+    /// Returns a value of an existing Index parameter. See [`Self::set_float`] for more info.
+    #[inline]
+    pub fn get_index(&self, name: &str) -> Result<u32, ParameterError> {
+        match self.typed_parameter(name, "Index")? {
+            Parameter::Index(value) => Ok(value),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Creates new subscription to state enter/leave events of every layer of the machine. The events are
+    /// delivered to the returned receiver until every clone of it is dropped. Unlike [`MachineLayer::pop_event`],
+    /// every subscriber receives its own copy of each event, so multiple scripts could listen to the same
+    /// machine:
+    ///
+    /// ```rust
+    /// use fyrox::animation::machine::{Machine, StateEventKind};
+    ///
+    /// let mut machine = Machine::new();
+    /// let receiver = machine.subscribe_state_events();
+    ///
+    /// // Somewhere later, once per frame.
+    /// while let Some(event) = receiver.pop() {
+    ///     if event.kind == StateEventKind::Enter && event.state_name == "Jump" {
+    ///         // Play jump sound, etc.
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn subscribe_state_events(&mut self) -> StateEventReceiver {
+        self.subscribers.subscribe()
+    }
+
     /// Returns a shared reference to the container with all parameters used by the animation blending state machine.
     #[inline]
     pub fn parameters(&self) -> &ParameterContainer {
@@ -278,14 +407,30 @@ impl Machine {
         utils::find_by_name_mut(self.layers.iter_mut().enumerate(), name)
     }
 
+    /// Returns effective weights of the animations that are currently used by the machine. The weights are
+    /// used to synchronize animations, see [`AnimationContainer::synchronize`] docs for more info.
+    pub fn animation_weights(&self) -> FxHashMap<Handle<Animation>, f32> {
+        let mut weights = FxHashMap::default();
+        for layer in self.layers.iter() {
+            layer.collect_animation_weights(&self.parameters, &mut weights);
+        }
+        weights
+    }
+
     /// Computes final animation pose that could be then applied to a scene graph.
     #[inline]
     pub fn evaluate_pose(&mut self, animations: &AnimationContainer, dt: f32) -> &AnimationPose {
         self.final_pose.reset();
 
-        for layer in self.layers.iter_mut() {
+        for (layer_index, layer) in self.layers.iter_mut().enumerate() {
             let weight = layer.weight();
-            let pose = layer.evaluate_pose(animations, &self.parameters, dt);
+            let pose = layer.evaluate_pose(
+                animations,
+                &self.parameters,
+                dt,
+                layer_index,
+                &mut self.subscribers,
+            );
 
             self.final_pose.blend_with(pose, weight);
         }
@@ -293,3 +438,70 @@ impl Machine {
         &self.final_pose
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::{
+            machine::{Machine, Parameter, ParameterError, State, StateEventKind, Transition},
+            AnimationContainer,
+        },
+        core::pool::Handle,
+    };
+
+    #[test]
+    fn test_typed_parameters() {
+        let mut machine = Machine::new();
+        machine
+            .set_parameter("Speed", Parameter::Weight(0.0))
+            .set_parameter("Jump", Parameter::Rule(false));
+
+        machine.set_float("Speed", 2.0).unwrap();
+        assert_eq!(machine.get_float("Speed"), Ok(2.0));
+        machine.set_bool("Jump", true).unwrap();
+        assert_eq!(machine.get_bool("Jump"), Ok(true));
+
+        assert_eq!(
+            machine.set_float("Sped", 1.0),
+            Err(ParameterError::NotFound {
+                name: "Sped".to_string()
+            })
+        );
+        assert_eq!(
+            machine.get_index("Speed"),
+            Err(ParameterError::TypeMismatch {
+                name: "Speed".to_string(),
+                expected: "Index",
+                actual: "Weight".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_state_event_subscription() {
+        let mut machine = Machine::new();
+        machine.set_parameter("Jump", Parameter::Rule(false));
+
+        let layer = &mut machine.layers_mut()[0];
+        let idle = layer.add_state(State::new("Idle", Handle::NONE));
+        let jump = layer.add_state(State::new("Jump", Handle::NONE));
+        layer.add_transition(Transition::new("IdleToJump", idle, jump, 0.1, "Jump"));
+        layer.set_entry_state(idle);
+
+        let receiver = machine.subscribe_state_events();
+        let animations = AnimationContainer::new();
+
+        machine.evaluate_pose(&animations, 0.016);
+        assert_eq!(receiver.pop(), None);
+
+        machine.set_bool("Jump", true).unwrap();
+        machine.evaluate_pose(&animations, 0.016);
+
+        let events = receiver.drain();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, StateEventKind::Leave);
+        assert_eq!(events[0].state, idle);
+        assert_eq!(events[1].kind, StateEventKind::Enter);
+        assert_eq!(events[1].state_name, "Jump");
+    }
+}
@@ -119,16 +119,7 @@ impl EvaluatePose for BlendAnimations {
     ) -> Ref<AnimationPose> {
         self.output_pose.borrow_mut().reset();
         for blend_pose in self.pose_sources.iter() {
-            let weight = match blend_pose.weight {
-                PoseWeight::Constant(value) => value,
-                PoseWeight::Parameter(ref param_id) => {
-                    if let Some(Parameter::Weight(weight)) = params.get(param_id) {
-                        *weight
-                    } else {
-                        0.0
-                    }
-                }
-            };
+            let weight = blend_pose.weight.value(params);
 
             if let Some(pose_source) = nodes
                 .try_borrow(blend_pose.pose_source)
@@ -0,0 +1,475 @@
+//! Blend space is a node that blends multiple poses placed at parameter coordinates. See [`BlendSpace`] docs for
+//! more info.
+
+use crate::{
+    animation::{
+        machine::{node::BasePoseNode, EvaluatePose, Parameter, ParameterContainer, PoseNode},
+        AnimationContainer, AnimationPose,
+    },
+    core::{
+        algebra::Vector2,
+        math::get_barycentric_coords_2d,
+        pool::{Handle, Pool},
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+};
+use std::{
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    ops::{Deref, DerefMut},
+};
+
+/// A pose source placed at some coordinates of a blend space.
+#[derive(Default, Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct BlendSpacePoint {
+    /// Coordinates of the point in the blend space. Only X coordinate is used in one-dimensional blend spaces.
+    pub position: Vector2<f32>,
+
+    /// A source of animation pose.
+    #[reflect(hidden)]
+    pub pose_source: Handle<PoseNode>,
+}
+
+impl BlendSpacePoint {
+    /// Creates new blend space point.
+    pub fn new(position: Vector2<f32>, pose_source: Handle<PoseNode>) -> Self {
+        Self {
+            position,
+            pose_source,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+struct Triangulation {
+    positions: Vec<Vector2<f32>>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// Blend space is a node that blends multiple poses, that are placed at some coordinates in one or two-dimensional
+/// space. A sampling point is taken from Weight parameters of the machine (for example, speed and direction of a
+/// character) and the weights of the poses are calculated using its position relative to the points:
+///
+/// - In one-dimensional blend spaces (when `y_parameter` is empty), the two closest points on both sides of the
+///   sampling point are linearly interpolated.
+/// - In two-dimensional blend spaces, the points are triangulated (Delaunay triangulation) and the weights are
+///   barycentric coordinates of the sampling point in a triangle that contains it. If the sampling point is
+///   outside of the triangulation, it is projected on the closest edge.
+///
+/// Typical usage of blend spaces is locomotion: you can place idle animation at (0, 0), walk forward at (0, 1),
+/// run forward at (0, 2), strafe left at (-1, 0) and so on, and the blend space will smoothly mix them depending
+/// on actual velocity of your character.
+#[derive(Debug, Visit, Clone, Reflect, PartialEq)]
+pub struct BlendSpace {
+    /// Base node.
+    pub base: BasePoseNode,
+
+    /// A name of Weight parameter that will be used as X coordinate of the sampling point.
+    pub x_parameter: String,
+
+    /// A name of Weight parameter that will be used as Y coordinate of the sampling point. Leave it empty to make
+    /// one-dimensional blend space.
+    pub y_parameter: String,
+
+    /// Minimal coordinates of the blend space. It is used only by the editor to show the blend space.
+    pub min_values: Vector2<f32>,
+
+    /// Maximal coordinates of the blend space. It is used only by the editor to show the blend space.
+    pub max_values: Vector2<f32>,
+
+    /// A set of points of the blend space. See [`BlendSpacePoint`] docs for more info.
+    pub points: Vec<BlendSpacePoint>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    triangulation: RefCell<Triangulation>,
+
+    /// Output pose of the node, contains final result of blending all input poses.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub output_pose: RefCell<AnimationPose>,
+}
+
+impl Default for BlendSpace {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            x_parameter: Default::default(),
+            y_parameter: Default::default(),
+            min_values: Vector2::new(-1.0, -1.0),
+            max_values: Vector2::new(1.0, 1.0),
+            points: Default::default(),
+            triangulation: Default::default(),
+            output_pose: Default::default(),
+        }
+    }
+}
+
+impl Deref for BlendSpace {
+    type Target = BasePoseNode;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for BlendSpace {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl BlendSpace {
+    /// Creates new blend space that uses given parameters and a set of points. Pass an empty string as
+    /// `y_parameter` to make one-dimensional blend space.
+    pub fn new(x_parameter: String, y_parameter: String, points: Vec<BlendSpacePoint>) -> Self {
+        Self {
+            x_parameter,
+            y_parameter,
+            points,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a set of handles to children pose nodes.
+    pub fn children(&self) -> Vec<Handle<PoseNode>> {
+        self.points.iter().map(|p| p.pose_source).collect()
+    }
+
+    /// Returns `true` if the blend space uses only X coordinate, `false` - otherwise.
+    pub fn is_one_dimensional(&self) -> bool {
+        self.y_parameter.is_empty()
+    }
+
+    /// Fetches a sampling point from the given parameters.
+    pub fn sampling_point(&self, params: &ParameterContainer) -> Vector2<f32> {
+        let fetch = |name: &str| {
+            if let Some(Parameter::Weight(value)) = params.get(name) {
+                *value
+            } else {
+                0.0
+            }
+        };
+
+        Vector2::new(
+            fetch(&self.x_parameter),
+            if self.is_one_dimensional() {
+                0.0
+            } else {
+                fetch(&self.y_parameter)
+            },
+        )
+    }
+
+    /// Returns current triangulation of the points of the blend space. Every triangle is a triple of indices of
+    /// the points. One-dimensional blend spaces are not triangulated.
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        if self.is_one_dimensional() {
+            return Default::default();
+        }
+        self.update_triangulation();
+        self.triangulation.borrow().triangles.clone()
+    }
+
+    fn update_triangulation(&self) {
+        let mut triangulation = self.triangulation.borrow_mut();
+        if triangulation.positions.len() != self.points.len()
+            || triangulation
+                .positions
+                .iter()
+                .zip(self.points.iter())
+                .any(|(a, b)| *a != b.position)
+        {
+            triangulation.positions = self.points.iter().map(|p| p.position).collect();
+            triangulation.triangles = triangulate(&triangulation.positions);
+        }
+    }
+
+    /// Calculates weights of the points for the given sampling point. Returns a set of pairs `(point index,
+    /// weight)`, the sum of weights is always equal to one (if there's at least one point).
+    pub fn weights(&self, sampling_point: Vector2<f32>) -> Vec<(usize, f32)> {
+        match self.points.len() {
+            0 => Default::default(),
+            1 => vec![(0, 1.0)],
+            _ => {
+                if self.is_one_dimensional() {
+                    self.weights_1d(sampling_point.x)
+                } else {
+                    self.weights_2d(sampling_point)
+                }
+            }
+        }
+    }
+
+    fn weights_1d(&self, x: f32) -> Vec<(usize, f32)> {
+        let mut sorted = (0..self.points.len()).collect::<Vec<_>>();
+        sorted.sort_by(|a, b| {
+            self.points[*a]
+                .position
+                .x
+                .partial_cmp(&self.points[*b].position.x)
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let first = sorted[0];
+        let last = sorted[sorted.len() - 1];
+        if x <= self.points[first].position.x {
+            return vec![(first, 1.0)];
+        }
+        if x >= self.points[last].position.x {
+            return vec![(last, 1.0)];
+        }
+
+        for pair in sorted.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let left_x = self.points[left].position.x;
+            let right_x = self.points[right].position.x;
+            if x >= left_x && x <= right_x {
+                let span = right_x - left_x;
+                let t = if span > 0.0 { (x - left_x) / span } else { 0.0 };
+                return vec![(left, 1.0 - t), (right, t)];
+            }
+        }
+
+        vec![(last, 1.0)]
+    }
+
+    fn weights_2d(&self, p: Vector2<f32>) -> Vec<(usize, f32)> {
+        self.update_triangulation();
+        let triangulation = self.triangulation.borrow();
+        let positions = &triangulation.positions;
+
+        for triangle in triangulation.triangles.iter() {
+            let (u, v, w) = get_barycentric_coords_2d(
+                p,
+                positions[triangle[0]],
+                positions[triangle[1]],
+                positions[triangle[2]],
+            );
+            let eps = -1.0e-5;
+            if u >= eps && v >= eps && w >= eps {
+                return vec![(triangle[0], u), (triangle[1], v), (triangle[2], w)];
+            }
+        }
+
+        // The point is outside of the triangulation (or the points are collinear), project it on the closest edge.
+        let mut edges = Vec::new();
+        if triangulation.triangles.is_empty() {
+            for a in 0..positions.len() {
+                for b in (a + 1)..positions.len() {
+                    edges.push((a, b));
+                }
+            }
+        } else {
+            for triangle in triangulation.triangles.iter() {
+                edges.push((triangle[0], triangle[1]));
+                edges.push((triangle[1], triangle[2]));
+                edges.push((triangle[2], triangle[0]));
+            }
+        }
+
+        let mut closest = (0, 0, 0.0, f32::MAX);
+        for (a, b) in edges {
+            let (pa, pb) = (positions[a], positions[b]);
+            let edge = pb - pa;
+            let length_sqr = edge.norm_squared();
+            let t = if length_sqr > 0.0 {
+                ((p - pa).dot(&edge) / length_sqr).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let distance = (pa + edge.scale(t)).metric_distance(&p);
+            if distance < closest.3 {
+                closest = (a, b, t, distance);
+            }
+        }
+
+        vec![(closest.0, 1.0 - closest.2), (closest.1, closest.2)]
+    }
+}
+
+fn circumcircle_contains(
+    a: Vector2<f32>,
+    b: Vector2<f32>,
+    c: Vector2<f32>,
+    p: Vector2<f32>,
+) -> bool {
+    let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    if d.abs() <= f32::EPSILON {
+        // Degenerated triangle must be removed.
+        return true;
+    }
+
+    let (a2, b2, c2) = (a.norm_squared(), b.norm_squared(), c.norm_squared());
+    let center = Vector2::new(
+        (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+        (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+    );
+
+    p.metric_distance(&center) <= a.metric_distance(&center)
+}
+
+/// Bowyer-Watson triangulation of a point set.
+fn triangulate(points: &[Vector2<f32>]) -> Vec<[usize; 3]> {
+    let count = points.len();
+    if count < 3 {
+        return Default::default();
+    }
+
+    let (mut min, mut max) = (points[0], points[0]);
+    for point in points {
+        min = min.inf(point);
+        max = max.sup(point);
+    }
+    let size = (max - min).amax().max(1.0) * 20.0;
+    let center = (min + max).scale(0.5);
+
+    let mut vertices = points.to_vec();
+    vertices.push(center + Vector2::new(-size, -size));
+    vertices.push(center + Vector2::new(0.0, size));
+    vertices.push(center + Vector2::new(size, -size));
+
+    let mut triangles = vec![[count, count + 1, count + 2]];
+    for (i, &point) in points.iter().enumerate() {
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) =
+            triangles.into_iter().partition(|t| {
+                circumcircle_contains(vertices[t[0]], vertices[t[1]], vertices[t[2]], point)
+            });
+
+        // Find the boundary of the polygonal hole.
+        let mut edges = Vec::<(usize, usize)>::new();
+        for triangle in bad.iter() {
+            for &(a, b) in &[
+                (triangle[0], triangle[1]),
+                (triangle[1], triangle[2]),
+                (triangle[2], triangle[0]),
+            ] {
+                if let Some(position) = edges
+                    .iter()
+                    .position(|&(c, d)| (c == a && d == b) || (c == b && d == a))
+                {
+                    edges.remove(position);
+                } else {
+                    edges.push((a, b));
+                }
+            }
+        }
+
+        triangles = good;
+        triangles.extend(edges.into_iter().map(|(a, b)| [a, b, i]));
+    }
+
+    triangles.retain(|t| {
+        t.iter().all(|&i| i < count) && {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            ((b - a).perp(&(c - a))).abs() > f32::EPSILON
+        }
+    });
+    triangles
+}
+
+impl EvaluatePose for BlendSpace {
+    fn eval_pose(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        animations: &AnimationContainer,
+        dt: f32,
+    ) -> Ref<'_, AnimationPose> {
+        self.output_pose.borrow_mut().reset();
+
+        for (index, weight) in self.weights(self.sampling_point(params)) {
+            if weight <= f32::EPSILON {
+                continue;
+            }
+
+            if let Some(pose_source) = nodes
+                .try_borrow(self.points[index].pose_source)
+                .map(|pose_source| pose_source.eval_pose(nodes, params, animations, dt))
+            {
+                self.output_pose
+                    .borrow_mut()
+                    .blend_with(&pose_source, weight);
+            }
+        }
+
+        self.output_pose.borrow()
+    }
+
+    fn pose(&self) -> Ref<'_, AnimationPose> {
+        self.output_pose.borrow()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::machine::node::blendspace::{BlendSpace, BlendSpacePoint},
+        core::{algebra::Vector2, pool::Handle},
+    };
+
+    fn weight_of(weights: &[(usize, f32)], index: usize) -> f32 {
+        weights
+            .iter()
+            .filter(|(i, _)| *i == index)
+            .map(|(_, w)| *w)
+            .sum()
+    }
+
+    #[test]
+    fn test_blend_space_1d() {
+        let blend_space = BlendSpace::new(
+            "Speed".to_string(),
+            Default::default(),
+            vec![
+                BlendSpacePoint::new(Vector2::new(2.0, 0.0), Handle::NONE),
+                BlendSpacePoint::new(Vector2::new(0.0, 0.0), Handle::NONE),
+                BlendSpacePoint::new(Vector2::new(1.0, 0.0), Handle::NONE),
+            ],
+        );
+
+        let weights = blend_space.weights(Vector2::new(1.25, 0.0));
+        assert!((weight_of(&weights, 2) - 0.75).abs() < 1.0e-5);
+        assert!((weight_of(&weights, 0) - 0.25).abs() < 1.0e-5);
+        assert_eq!(blend_space.weights(Vector2::new(-1.0, 0.0)), vec![(1, 1.0)]);
+        assert_eq!(blend_space.weights(Vector2::new(3.0, 0.0)), vec![(0, 1.0)]);
+    }
+
+    #[test]
+    fn test_blend_space_2d() {
+        let blend_space = BlendSpace::new(
+            "X".to_string(),
+            "Y".to_string(),
+            vec![
+                BlendSpacePoint::new(Vector2::new(0.0, 0.0), Handle::NONE),
+                BlendSpacePoint::new(Vector2::new(1.0, 0.0), Handle::NONE),
+                BlendSpacePoint::new(Vector2::new(0.0, 1.0), Handle::NONE),
+                BlendSpacePoint::new(Vector2::new(1.0, 1.0), Handle::NONE),
+                BlendSpacePoint::new(Vector2::new(0.5, 0.5), Handle::NONE),
+            ],
+        );
+
+        assert_eq!(blend_space.triangles().len(), 4);
+
+        for &(x, y) in &[(0.1, 0.2), (0.5, 0.5), (0.9, 0.3), (0.7, 0.95)] {
+            let p = Vector2::new(x, y);
+            let weights = blend_space.weights(p);
+            let sum = weights.iter().map(|(_, w)| *w).sum::<f32>();
+            assert!((sum - 1.0).abs() < 1.0e-5);
+            let restored = weights.iter().fold(Vector2::default(), |acc, (i, w)| {
+                acc + blend_space.points[*i].position.scale(*w)
+            });
+            assert!(restored.metric_distance(&p) < 1.0e-4);
+        }
+
+        // Exactly at a point.
+        let weights = blend_space.weights(Vector2::new(0.5, 0.5));
+        assert!((weight_of(&weights, 4) - 1.0).abs() < 1.0e-5);
+
+        // Outside of the triangulation the point is projected on the closest edge.
+        let weights = blend_space.weights(Vector2::new(0.5, -1.0));
+        assert!((weight_of(&weights, 0) - 0.5).abs() < 1.0e-5);
+        assert!((weight_of(&weights, 1) - 0.5).abs() < 1.0e-5);
+    }
+}
@@ -4,8 +4,9 @@
 use crate::{
     animation::{
         machine::{
-            node::{blend::BlendAnimations, play::PlayAnimation},
-            BlendAnimationsByIndex, BlendPose, IndexedBlendInput, ParameterContainer, State,
+            node::{blend::BlendAnimations, blendspace::BlendSpace, play::PlayAnimation},
+            BlendAnimationsByIndex, BlendPose, BlendSpacePoint, IndexedBlendInput, Parameter,
+            ParameterContainer, State,
         },
         Animation, AnimationContainer, AnimationPose,
     },
@@ -16,12 +17,14 @@ use crate::{
         visitor::prelude::*,
     },
 };
+use fxhash::FxHashMap;
 use std::{
     cell::Ref,
     ops::{Deref, DerefMut},
 };
 
 pub mod blend;
+pub mod blendspace;
 pub mod play;
 
 /// A set of common data fields that is used in every node.
@@ -46,6 +49,9 @@ pub enum PoseNode {
 
     /// See docs for [`BlendAnimationsByIndex`].
     BlendAnimationsByIndex(BlendAnimationsByIndex),
+
+    /// See docs for [`BlendSpace`].
+    BlendSpace(BlendSpace),
 }
 
 impl Default for PoseNode {
@@ -74,6 +80,16 @@ impl PoseNode {
         Self::BlendAnimationsByIndex(BlendAnimationsByIndex::new(index_parameter, inputs))
     }
 
+    /// Creates new node that blends poses placed at some coordinates using a sampling point taken from the
+    /// given parameters. Pass an empty string as `y_parameter` to make one-dimensional blend space.
+    pub fn make_blend_space(
+        x_parameter: String,
+        y_parameter: String,
+        points: Vec<BlendSpacePoint>,
+    ) -> Self {
+        Self::BlendSpace(BlendSpace::new(x_parameter, y_parameter, points))
+    }
+
     /// Returns a set of handles to children pose nodes.
     pub fn children(&self) -> Vec<Handle<PoseNode>> {
         match self {
@@ -83,6 +99,66 @@ impl PoseNode {
             }
             Self::BlendAnimations(definition) => definition.children(),
             Self::BlendAnimationsByIndex(definition) => definition.children(),
+            Self::BlendSpace(definition) => definition.children(),
+        }
+    }
+}
+
+impl PoseNode {
+    /// Accumulates effective weights of the animations used by the node and its descendants. Every weight is
+    /// multiplied by the given `weight` of the node itself. The weights are used to synchronize animations, see
+    /// [`AnimationContainer::synchronize`] docs for more info.
+    pub fn collect_animation_weights(
+        &self,
+        nodes: &Pool<PoseNode>,
+        params: &ParameterContainer,
+        weight: f32,
+        weights: &mut FxHashMap<Handle<Animation>, f32>,
+    ) {
+        let mut collect_child = |handle: Handle<PoseNode>, child_weight: f32| {
+            if let Some(child) = nodes.try_borrow(handle) {
+                child.collect_animation_weights(nodes, params, weight * child_weight, weights);
+            }
+        };
+
+        match self {
+            Self::PlayAnimation(play_animation) => {
+                *weights.entry(play_animation.animation).or_default() += weight;
+            }
+            Self::BlendAnimations(blend_animations) => {
+                for blend_pose in blend_animations.pose_sources.iter() {
+                    collect_child(blend_pose.pose_source, blend_pose.weight.value(params));
+                }
+            }
+            Self::BlendAnimationsByIndex(blend_by_index) => {
+                if let Some(&Parameter::Index(current_index)) =
+                    params.get(&blend_by_index.index_parameter)
+                {
+                    let current = blend_by_index.inputs.get(current_index as usize);
+                    let prev = blend_by_index
+                        .prev_index
+                        .get()
+                        .filter(|prev_index| *prev_index != current_index)
+                        .and_then(|prev_index| blend_by_index.inputs.get(prev_index as usize));
+
+                    match (prev, current) {
+                        (Some(prev), Some(current)) if current.blend_time > 0.0 => {
+                            let interpolator =
+                                (blend_by_index.blend_time.get() / current.blend_time).min(1.0);
+                            collect_child(prev.pose_source, 1.0 - interpolator);
+                            collect_child(current.pose_source, interpolator);
+                        }
+                        (_, Some(current)) => collect_child(current.pose_source, 1.0),
+                        _ => (),
+                    }
+                }
+            }
+            Self::BlendSpace(blend_space) => {
+                for (index, point_weight) in blend_space.weights(blend_space.sampling_point(params))
+                {
+                    collect_child(blend_space.points[index].pose_source, point_weight);
+                }
+            }
         }
     }
 }
@@ -93,6 +169,7 @@ macro_rules! static_dispatch {
             PoseNode::PlayAnimation(v) => v.$func($($args),*),
             PoseNode::BlendAnimations(v) => v.$func($($args),*),
             PoseNode::BlendAnimationsByIndex(v) => v.$func($($args),*),
+            PoseNode::BlendSpace(v) => v.$func($($args),*),
         }
     };
 }
@@ -4,6 +4,7 @@ use crate::core::{reflect::prelude::*, visitor::prelude::*};
 use fxhash::FxHashMap;
 use std::{
     cell::{Cell, RefCell},
+    fmt::{Display, Formatter},
     ops::{Deref, DerefMut},
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
@@ -29,6 +30,48 @@ impl Default for Parameter {
     }
 }
 
+/// An error that may occur when accessing machine parameters using typed API (for example,
+/// [`crate::animation::machine::Machine::set_float`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParameterError {
+    /// There's no parameter with the given name.
+    NotFound {
+        /// Name of the parameter.
+        name: String,
+    },
+    /// The parameter exists, but it has different type.
+    TypeMismatch {
+        /// Name of the parameter.
+        name: String,
+        /// Expected type of the parameter.
+        expected: &'static str,
+        /// Actual type of the parameter.
+        actual: String,
+    },
+}
+
+impl Display for ParameterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParameterError::NotFound { name } => {
+                write!(f, "There's no parameter with name {name}")
+            }
+            ParameterError::TypeMismatch {
+                name,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "Parameter {name} has type {actual}, but {expected} was expected"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
 /// Specific animation pose weight.
 #[derive(Debug, Visit, Clone, PartialEq, Reflect, EnumVariantNames, EnumString, AsRefStr)]
 pub enum PoseWeight {
@@ -45,6 +88,23 @@ impl Default for PoseWeight {
     }
 }
 
+impl PoseWeight {
+    /// Returns actual value of the weight. Parametrized weight will be fetched from the given parameters,
+    /// if there's no such parameter (or it has different type), the value will be zero.
+    pub fn value(&self, params: &ParameterContainer) -> f32 {
+        match self {
+            PoseWeight::Constant(value) => *value,
+            PoseWeight::Parameter(param_id) => {
+                if let Some(Parameter::Weight(weight)) = params.get(param_id) {
+                    *weight
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
 /// A parameter value with its name.
 #[derive(Reflect, Visit, Default, Debug, Clone, PartialEq)]
 pub struct ParameterDefinition {
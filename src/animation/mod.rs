@@ -18,6 +18,7 @@ use crate::{
     },
     utils::{self, NameProvider},
 };
+use fxhash::FxHashMap;
 use std::{
     collections::VecDeque,
     fmt::Debug,
@@ -27,6 +28,7 @@ use std::{
 pub use pose::{AnimationPose, NodePose};
 pub use signal::{AnimationEvent, AnimationSignal};
 
+pub mod compression;
 pub mod container;
 pub mod machine;
 pub mod pose;
@@ -185,6 +187,8 @@ pub struct Animation {
     looped: bool,
     enabled: bool,
     signals: Vec<AnimationSignal>,
+    #[visit(optional)]
+    sync_group: String,
 
     // Non-serialized
     #[reflect(hidden)]
@@ -215,6 +219,7 @@ impl Clone for Animation {
             signals: self.signals.clone(),
             events: Default::default(),
             time_slice: self.time_slice.clone(),
+            sync_group: self.sync_group.clone(),
         }
     }
 }
@@ -411,11 +416,58 @@ impl Animation {
         self.enabled
     }
 
+    /// Sets a name of synchronization group of the animation. Animations of the same group are kept
+    /// phase-aligned while being blended, see [`AnimationContainer::synchronize`] docs for more info. Empty
+    /// name means that the animation does not belong to any group (default).
+    pub fn set_sync_group<S: AsRef<str>>(&mut self, sync_group: S) -> &mut Self {
+        self.sync_group = sync_group.as_ref().to_owned();
+        self
+    }
+
+    /// Returns a name of synchronization group of the animation.
+    pub fn sync_group(&self) -> &str {
+        &self.sync_group
+    }
+
+    /// Returns normalized (in `[0; 1]` range) playback position of the animation in its time slice.
+    pub fn phase(&self) -> f32 {
+        let length = self.length();
+        if length > 0.0 {
+            (self.time_position - self.time_slice.start) / length
+        } else {
+            0.0
+        }
+    }
+
     /// Returns a mutable reference to the track container.
     pub fn tracks_mut(&mut self) -> &mut [Track] {
         &mut self.tracks
     }
 
+    /// Compresses data of every track of the animation with the given tolerance. See
+    /// [`container::TrackDataContainer::compress`] docs for more info.
+    pub fn compress(&mut self, tolerance: f32) {
+        for track in self.tracks.iter_mut() {
+            track.data_container_mut().compress(tolerance);
+        }
+    }
+
+    /// Restores editable data of every track of the animation. See [`container::TrackDataContainer::decompress`]
+    /// docs for more info.
+    pub fn decompress(&mut self) {
+        for track in self.tracks.iter_mut() {
+            track.data_container_mut().decompress();
+        }
+    }
+
+    /// Returns approximate amount of memory (in bytes) used by the data of the tracks of the animation.
+    pub fn memory_usage(&self) -> usize {
+        self.tracks
+            .iter()
+            .map(|t| t.data_container().memory_usage())
+            .sum()
+    }
+
     /// Adds a new animation signal to the animation. See [`AnimationSignal`] docs for more info and examples.
     pub fn add_signal(&mut self, signal: AnimationSignal) -> &mut Self {
         self.signals.push(signal);
@@ -570,6 +622,7 @@ impl Default for Animation {
             signals: Default::default(),
             events: Default::default(),
             time_slice: Default::default(),
+            sync_group: Default::default(),
         }
     }
 }
@@ -724,6 +777,45 @@ impl AnimationContainer {
         }
     }
 
+    /// Synchronizes animations of each synchronization group (see [`Animation::set_sync_group`]) using the given
+    /// effective weights of the animations (for example, [`crate::animation::machine::Machine::animation_weights`]).
+    /// An animation with the highest weight in a group becomes a leader and the rest of the animations of the
+    /// group (followers) are moved to the same normalized playback position (phase). It keeps blended
+    /// locomotion clips of different lengths aligned on foot plants, so mixing walk and run animations won't
+    /// result in sliding. Keep in mind that the clips must be authored so that the foot plants happen at the
+    /// same phase. Animations with zero weight are not synchronized.
+    pub fn synchronize(&mut self, weights: &FxHashMap<Handle<Animation>, f32>) {
+        let mut leaders = FxHashMap::<&str, (Handle<Animation>, f32)>::default();
+        for (handle, animation) in self.pool.pair_iter() {
+            if animation.sync_group.is_empty() || !animation.enabled {
+                continue;
+            }
+            if let Some(&weight) = weights.get(&handle).filter(|w| **w > 0.0) {
+                let leader = leaders
+                    .entry(animation.sync_group.as_str())
+                    .or_insert((handle, weight));
+                if weight > leader.1 {
+                    *leader = (handle, weight);
+                }
+            }
+        }
+
+        let phases = leaders
+            .into_iter()
+            .map(|(group, (leader, _))| (group.to_owned(), (leader, self.pool[leader].phase())))
+            .collect::<FxHashMap<_, _>>();
+
+        for (handle, animation) in self.pool.pair_iter_mut() {
+            if let Some(&(leader, phase)) = phases.get(&animation.sync_group) {
+                if leader != handle && weights.get(&handle).map_or(false, |w| *w > 0.0) {
+                    let time = animation.time_slice.start + phase * animation.length();
+                    animation.set_time_position(time);
+                    animation.update_pose();
+                }
+            }
+        }
+    }
+
     /// Removes queued animation events from every animation in the container.
     ///
     /// # Potential use cases
@@ -766,3 +858,47 @@ impl IndexMut<Handle<Animation>> for AnimationContainer {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::animation::{Animation, AnimationContainer};
+    use fxhash::FxHashMap;
+
+    #[test]
+    fn test_animation_synchronization() {
+        let mut container = AnimationContainer::new();
+
+        let mut walk = Animation::default();
+        walk.set_time_slice(0.0..1.0);
+        walk.set_sync_group("Locomotion");
+        walk.set_time_position(0.25);
+        let walk = container.add(walk);
+
+        let mut run = Animation::default();
+        run.set_time_slice(0.0..2.0);
+        run.set_sync_group("Locomotion");
+        let run = container.add(run);
+
+        let mut idle = Animation::default();
+        idle.set_time_slice(0.0..4.0);
+        let idle = container.add(idle);
+
+        let weights = [(walk, 0.7), (run, 0.3), (idle, 0.5)]
+            .into_iter()
+            .collect::<FxHashMap<_, _>>();
+        container.synchronize(&weights);
+
+        // Walk is the leader, run follows its phase, idle is not in the group.
+        assert_eq!(container.get(walk).time_position(), 0.25);
+        assert_eq!(container.get(run).time_position(), 0.5);
+        assert_eq!(container.get(idle).time_position(), 0.0);
+
+        // Leadership changes with weights.
+        container.get_mut(run).set_time_position(1.5);
+        let weights = [(walk, 0.2), (run, 0.8)]
+            .into_iter()
+            .collect::<FxHashMap<_, _>>();
+        container.synchronize(&weights);
+        assert_eq!(container.get(walk).time_position(), 0.75);
+    }
+}
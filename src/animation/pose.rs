@@ -6,7 +6,7 @@ use crate::{
     scene::{graph::Graph, graph::NodePool, node::Node},
     utils::log::{Log, MessageKind},
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use std::collections::hash_map::Entry;
 
 /// A "captured" state of properties of some animated scene node. The pose can be considered as container of values of some
@@ -119,6 +119,27 @@ impl AnimationPose {
         }
     }
 
+    pub(crate) fn apply_internal_filtered(
+        &self,
+        nodes: &mut NodePool,
+        skipped: &FxHashSet<Handle<Node>>,
+    ) {
+        if skipped.is_empty() {
+            self.apply_internal(nodes);
+            return;
+        }
+
+        for (node, local_pose) in self.poses.iter() {
+            if node.is_none() {
+                Log::writeln(MessageKind::Error, "Invalid node handle found for animation pose, most likely it means that animation retargeting failed!");
+            } else if skipped.contains(node) {
+                continue;
+            } else if let Some(node) = nodes.try_borrow_mut(*node) {
+                local_pose.values.apply(node);
+            }
+        }
+    }
+
     /// Tries to set each value to the each property from the animation pose to respective scene nodes.
     pub fn apply(&self, graph: &mut Graph) {
         for (node, local_pose) in self.poses.iter() {
@@ -1,29 +1,98 @@
 //! Executor is a small wrapper that manages plugins and scripts for your game.
 
 use crate::{
-    core::instant::Instant,
-    engine::{resource_manager::ResourceManager, Engine, EngineInitParams, SerializationContext},
+    core::{instant::Instant, pool::Handle, visitor::Visitor},
+    engine::{
+        launch::LaunchOptions, resource_manager::ResourceManager, Engine, EngineInitParams,
+        SerializationContext,
+    },
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     plugin::PluginConstructor,
-    scene::loader::AsyncSceneLoader,
+    scene::{loader::AsyncSceneLoader, Scene},
     utils::{
         log::{Log, MessageKind},
         translate_event,
     },
-    window::WindowBuilder,
+    window::{Fullscreen, WindowBuilder},
 };
 use clap::Parser;
 use std::{
+    io::BufRead,
     ops::{Deref, DerefMut},
-    sync::Arc,
+    path::{Path, PathBuf},
+    sync::{mpsc::channel, Arc},
+    time::Duration,
 };
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    #[clap(short, long, default_value = "")]
+    /// Path to a scene, that will be loaded on start instead of the scene created by plugins.
+    #[clap(short, long, visible_alias = "scene", default_value = "")]
     override_scene: String,
+    /// Forces windowed mode.
+    #[clap(long, conflicts_with = "fullscreen")]
+    windowed: bool,
+    /// Forces borderless fullscreen mode.
+    #[clap(long)]
+    fullscreen: bool,
+    /// Runs the game as a dedicated server - the window is hidden, graphics context and the renderer
+    /// are not created and sound output is disabled.
+    #[clap(long)]
+    server: bool,
+    /// Custom option for plugins in `key=value` or `key` format, could be specified multiple times.
+    #[clap(long = "set", value_name = "KEY[=VALUE]")]
+    custom_options: Vec<String>,
+    /// Print script costs of the override scene to stdout every second. Used by the editor.
+    #[clap(long)]
+    profile_scripts: bool,
+    /// Path to a file, where the state of the override scene is saved when a save state command is
+    /// received from stdin. Used by the editor to restart the game with modified scripts.
+    #[clap(long, default_value = "")]
+    state_snapshot: String,
+}
+
+impl Args {
+    fn launch_options(&self) -> LaunchOptions {
+        let mut options = LaunchOptions {
+            scene: if self.override_scene.is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(&self.override_scene))
+            },
+            windowed: self.windowed,
+            fullscreen: self.fullscreen,
+            server: self.server,
+            custom: Default::default(),
+        };
+        for option in self.custom_options.iter() {
+            options.add_custom_option(option);
+        }
+        options
+    }
+}
+
+/// A command, that tells the executor to save the state of the override scene to the file specified
+/// by `--state-snapshot` argument and exit. The command should be written to stdin of the executor
+/// process as a separate line.
+pub const SAVE_STATE_COMMAND: &str = "[SaveState]";
+
+/// A prefix of the line, that is printed to stdout by the executor when the state of the override
+/// scene was saved. The prefix is followed by the path to the saved state.
+pub const STATE_SAVED_PREFIX: &str = "[StateSaved]";
+
+fn save_state(engine: &mut Engine, scene: Handle<Scene>, path: &Path) -> Result<(), String> {
+    let scene = engine
+        .scenes
+        .try_get_mut(scene)
+        .ok_or_else(|| "There is no scene to save!".to_owned())?;
+
+    let mut visitor = Visitor::new();
+    scene
+        .save("Scene", &mut visitor)
+        .map_err(|e| e.to_string())?;
+    visitor.save_binary(path).map_err(|e| e.to_string())
 }
 
 /// Executor is a small wrapper that manages plugins and scripts for your game.
@@ -32,6 +101,7 @@ pub struct Executor {
     engine: Engine,
     desired_update_rate: f32,
     loader: Option<AsyncSceneLoader>,
+    args: Args,
 }
 
 impl Deref for Executor {
@@ -59,25 +129,38 @@ impl Executor {
     pub const DEFAULT_UPDATE_RATE: f32 = 60.0;
 
     /// Creates new game executor using specified set of parameters. Much more flexible version of
-    /// [`Executor::new`].
-    pub fn from_params(window_builder: WindowBuilder, vsync: bool) -> Self {
+    /// [`Executor::new`]. Command-line arguments are parsed here, launch options (see
+    /// [`LaunchOptions`]) may override some of the parameters - for example `--fullscreen` forces
+    /// fullscreen mode and `--server` makes the engine headless (see [`EngineInitParams::headless`]).
+    pub fn from_params(mut window_builder: WindowBuilder, vsync: bool) -> Self {
+        let args = Args::parse();
+        let launch_options = args.launch_options();
+
+        if launch_options.fullscreen {
+            window_builder = window_builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+        } else if launch_options.windowed {
+            window_builder = window_builder.with_fullscreen(None);
+        }
+
         let event_loop = EventLoop::new();
         let serialization_context = Arc::new(SerializationContext::new());
-        let engine = Engine::new(EngineInitParams {
+        let mut engine = Engine::new(EngineInitParams {
             window_builder,
             resource_manager: ResourceManager::new(serialization_context.clone()),
             serialization_context,
             events_loop: &event_loop,
             vsync,
-            headless: false,
+            headless: launch_options.server,
         })
         .unwrap();
+        engine.launch_options = launch_options;
 
         Self {
             event_loop,
             engine,
             desired_update_rate: Self::DEFAULT_UPDATE_RATE,
             loader: None,
+            args,
         }
     }
 
@@ -115,12 +198,29 @@ impl Executor {
         let mut engine = self.engine;
         let event_loop = self.event_loop;
 
-        let args = Args::parse();
+        let args = self.args;
+        let server = engine.launch_options.server;
+        let profile_scripts = args.profile_scripts;
+        let mut override_scene = Handle::NONE;
+        let mut last_script_cost_report = Instant::now();
+
+        let state_snapshot = PathBuf::from(args.state_snapshot);
+        let (command_sender, command_receiver) = channel();
+        if !state_snapshot.as_os_str().is_empty() {
+            // Listen for commands from the editor.
+            std::thread::spawn(move || {
+                for line in std::io::stdin().lock().lines().flatten() {
+                    if command_sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
 
-        if !args.override_scene.is_empty() {
+        if let Some(scene) = engine.launch_options.scene.clone() {
             // Try to load specified scene in a separate thread.
             self.loader = Some(AsyncSceneLoader::begin_loading(
-                args.override_scene.into(),
+                scene,
                 engine.serialization_context.clone(),
                 engine.resource_manager.clone(),
             ));
@@ -136,7 +236,7 @@ impl Executor {
         event_loop.run(move |event, _, control_flow| {
             if let Some(loader) = self.loader.as_ref() {
                 if let Some(result) = loader.fetch_result() {
-                    let override_scene = match result {
+                    override_scene = match result {
                         Ok(scene) => engine.scenes.add(scene),
                         Err(e) => {
                             Log::err(e);
@@ -168,6 +268,24 @@ impl Executor {
 
             match event {
                 Event::MainEventsCleared => {
+                    if command_receiver
+                        .try_iter()
+                        .any(|command| command.trim() == SAVE_STATE_COMMAND)
+                    {
+                        match save_state(&mut engine, override_scene, &state_snapshot) {
+                            Ok(_) => {
+                                println!("{} {}", STATE_SAVED_PREFIX, state_snapshot.display())
+                            }
+                            Err(e) => Log::err(format!("Unable to save game state. Reason: {}", e)),
+                        }
+
+                        *control_flow = ControlFlow::Exit;
+                        return;
+                    }
+
+                    #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+                    engine.reload_dynamic_plugins();
+
                     let elapsed = previous.elapsed();
                     previous = Instant::now();
                     lag += elapsed.as_secs_f32();
@@ -177,10 +295,31 @@ impl Executor {
                         lag -= fixed_time_step;
                     }
 
-                    engine.get_window().request_redraw();
+                    engine.variable_update(elapsed.as_secs_f32(), lag / fixed_time_step);
+
+                    if profile_scripts && last_script_cost_report.elapsed().as_secs_f32() >= 1.0 {
+                        if let Some(profiler) = engine.script_cost_profiler(override_scene) {
+                            println!("{}", profiler.report());
+                        }
+                        last_script_cost_report = Instant::now();
+                    }
+
+                    if server {
+                        // Dedicated server does not render anything, so there's nothing to do until
+                        // the next fixed update.
+                        let time_to_next_update = (fixed_time_step - lag).max(0.0);
+                        *control_flow = ControlFlow::WaitUntil(
+                            previous + Duration::from_secs_f32(time_to_next_update),
+                        );
+                    } else {
+                        engine.get_window().request_redraw();
+                    }
                 }
-                Event::RedrawRequested(_) => {
-                    engine.render().unwrap();
+                Event::RedrawRequested(_) if !server => {
+                    engine.interpolate_rigid_bodies(lag / fixed_time_step);
+                    if let Err(e) = engine.render() {
+                        Log::err(format!("Unable to render a frame. Reason: {:?}", e));
+                    }
                 }
                 Event::WindowEvent { event, .. } => {
                     match event {
@@ -197,10 +336,22 @@ impl Executor {
                     }
 
                     if let Some(os_event) = translate_event(&event) {
+                        engine.services.debug_ui.process_os_event(&os_event);
                         engine.user_interface.process_os_event(&os_event);
                     }
                 }
-                _ => *control_flow = ControlFlow::Poll,
+                Event::LoopDestroyed => {
+                    if engine.services.settings.is_modified()
+                        && engine.services.settings.path().is_some()
+                    {
+                        if let Err(e) = engine.services.settings.save() {
+                            Log::err(format!("Unable to save settings. Reason: {}", e));
+                        }
+                    }
+                }
+                // Dedicated server keeps waiting for the next fixed update.
+                _ if !server => *control_flow = ControlFlow::Poll,
+                _ => (),
             }
         })
     }
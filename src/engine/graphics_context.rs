@@ -0,0 +1,243 @@
+//! OpenGL context of the main window. The context is kept separately from the window, so it could
+//! be destroyed and created again for the same window after context loss.
+
+use crate::{
+    engine::error::EngineError,
+    event_loop::EventLoop,
+    renderer::framework::{
+        error::FrameworkError,
+        state::{GetGraphicsResetStatus, GetProgramParameter},
+    },
+    window::{Window, WindowBuilder},
+};
+use glutin::{
+    ContextBuilder, ContextError, CreationError, NotCurrent, PossiblyCurrent, RawContext,
+};
+use std::ffi::c_void;
+
+fn make_context_builder<'a>(vsync: bool) -> ContextBuilder<'a, NotCurrent> {
+    ContextBuilder::new()
+        .with_vsync(vsync)
+        .with_gl_profile(glutin::GlProfile::Core)
+        // Stencil buffer is required for UI clipping and user effects.
+        .with_depth_buffer(24)
+        .with_stencil_buffer(8)
+        // Allows to detect context loss instead of crashing on GPU resets.
+        .with_gl_robustness(glutin::Robustness::TryRobustLoseContextOnReset)
+        .with_gl(glutin::GlRequest::GlThenGles {
+            opengl_version: (3, 3),
+            opengles_version: (3, 0),
+        })
+}
+
+#[cfg(target_os = "windows")]
+fn is_recreation_supported(_window: &Window) -> bool {
+    true
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn build_raw_context(
+    builder: ContextBuilder<NotCurrent>,
+    window: &Window,
+) -> Result<RawContext<NotCurrent>, CreationError> {
+    use glutin::platform::windows::{RawContextExt, WindowExtWindows};
+
+    builder.build_raw_context(window.hwnd() as *mut _)
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn is_recreation_supported(window: &Window) -> bool {
+    use glutin::platform::unix::WindowExtUnix;
+
+    (window.xlib_xconnection().is_some() && window.xlib_window().is_some())
+        || (window.wayland_display().is_some() && window.wayland_surface().is_some())
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+unsafe fn build_raw_context(
+    builder: ContextBuilder<NotCurrent>,
+    window: &Window,
+) -> Result<RawContext<NotCurrent>, CreationError> {
+    use glutin::platform::unix::{RawContextExt, WindowExtUnix};
+
+    if let (Some(xconn), Some(xlib_window)) = (window.xlib_xconnection(), window.xlib_window()) {
+        builder.build_raw_x11_context(xconn, xlib_window)
+    } else if let (Some(display), Some(surface)) =
+        (window.wayland_display(), window.wayland_surface())
+    {
+        let size = window.inner_size();
+        builder.build_raw_wayland_context(display as _, surface, size.width, size.height)
+    } else {
+        Err(CreationError::NotSupported(
+            "unknown windowing system".to_owned(),
+        ))
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn is_recreation_supported(_window: &Window) -> bool {
+    false
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+unsafe fn build_raw_context(
+    _builder: ContextBuilder<NotCurrent>,
+    _window: &Window,
+) -> Result<RawContext<NotCurrent>, CreationError> {
+    Err(CreationError::NotSupported(
+        "context re-creation is not supported on this platform".to_owned(),
+    ))
+}
+
+pub(crate) struct GraphicsContext {
+    // Context must be destroyed before the window, so it goes first.
+    context: Option<RawContext<PossiblyCurrent>>,
+    window: Window,
+    vsync: bool,
+}
+
+impl GraphicsContext {
+    pub fn new(
+        window_builder: WindowBuilder,
+        events_loop: &EventLoop<()>,
+        vsync: bool,
+    ) -> Result<Self, EngineError> {
+        let context = make_context_builder(vsync).build_windowed(window_builder, events_loop)?;
+
+        let context = match unsafe { context.make_current() } {
+            Ok(context) => context,
+            Err((_, e)) => return Err(EngineError::from(e)),
+        };
+
+        // The window is kept separately, so the context could be re-created for it later on.
+        let (context, window) = unsafe { context.split() };
+
+        Ok(Self {
+            context: Some(context),
+            window,
+            vsync,
+        })
+    }
+
+    /// Creates a hidden window without graphics context, it is used by headless engine.
+    pub fn new_headless(
+        window_builder: WindowBuilder,
+        events_loop: &EventLoop<()>,
+    ) -> Result<Self, EngineError> {
+        let window = window_builder
+            .with_visible(false)
+            .build(events_loop)
+            .map_err(|e| EngineError::Custom(format!("Unable to create a window: {:?}", e)))?;
+
+        Ok(Self {
+            context: None,
+            window,
+            vsync: false,
+        })
+    }
+
+    pub fn window(&self) -> &Window {
+        &self.window
+    }
+
+    pub fn get_proc_address(&self, name: &str) -> *const c_void {
+        self.context
+            .as_ref()
+            .map_or(std::ptr::null(), |context| context.get_proc_address(name))
+    }
+
+    /// Returns a pointer to `glGetGraphicsResetStatus` function (or one of its aliases) if the
+    /// context supports it.
+    pub fn graphics_reset_status_fn(&self) -> Option<GetGraphicsResetStatus> {
+        [
+            "glGetGraphicsResetStatus",
+            "glGetGraphicsResetStatusARB",
+            "glGetGraphicsResetStatusEXT",
+            "glGetGraphicsResetStatusKHR",
+        ]
+        .iter()
+        .map(|name| self.get_proc_address(name))
+        .find(|address| !address.is_null())
+        .map(|address| unsafe {
+            std::mem::transmute::<*const c_void, GetGraphicsResetStatus>(address)
+        })
+    }
+
+    /// Returns a pointer to `glGetProgramiv` function, it is used to query completion status of
+    /// programs compiled in parallel.
+    pub fn program_parameter_fn(&self) -> Option<GetProgramParameter> {
+        let address = self.get_proc_address("glGetProgramiv");
+        if address.is_null() {
+            None
+        } else {
+            Some(unsafe { std::mem::transmute::<*const c_void, GetProgramParameter>(address) })
+        }
+    }
+
+    pub fn resize(&self, size: glutin::dpi::PhysicalSize<u32>) {
+        if let Some(context) = self.context.as_ref() {
+            context.resize(size);
+        }
+    }
+
+    pub fn swap_buffers(&self) -> Result<(), ContextError> {
+        match self.context.as_ref() {
+            Some(context) => context.swap_buffers(),
+            None => Ok(()),
+        }
+    }
+
+    /// Destroys current context and creates a new one for the same window. Every GPU object, that
+    /// was created using the old context, becomes invalid and must be created again. The current
+    /// context is left intact if the platform does not support context re-creation.
+    pub fn recreate(&mut self) -> Result<(), FrameworkError> {
+        if !is_recreation_supported(&self.window) {
+            return Err(FrameworkError::Custom(
+                "Graphics context re-creation is not supported on this platform".to_owned(),
+            ));
+        }
+
+        // The old context must be destroyed first, some platforms (EGL) do not allow to create
+        // more than one surface for a window.
+        self.context = None;
+
+        let context = unsafe { build_raw_context(make_context_builder(self.vsync), &self.window) }
+            .map_err(|e| {
+                FrameworkError::Custom(format!("Unable to re-create graphics context: {:?}", e))
+            })?;
+
+        let context = unsafe { context.make_current() }.map_err(|(_, e)| {
+            FrameworkError::Custom(format!("Unable to make graphics context current: {:?}", e))
+        })?;
+
+        self.context = Some(context);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! Launch options of a game. See [`LaunchOptions`] docs for more info.
+
+#![warn(missing_docs)]
+
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
+
+/// Launch options define a configuration in which a game was started. When a game is started by
+/// [`crate::engine::executor::Executor`], the options are taken from command-line arguments:
+///
+/// - `--scene <path>` (or `--override-scene <path>`) - a scene to load on start.
+/// - `--windowed` - forces windowed mode.
+/// - `--fullscreen` - forces borderless fullscreen mode.
+/// - `--server` - dedicated server mode, the window is hidden, graphics context and the renderer are
+///   not created (see [`crate::plugin::PluginContext::renderer`]) and sound output is disabled.
+/// - `--set <key>[=<value>]` - a custom option for plugins, could be specified multiple times.
+///
+/// Plugins could access the options using [`crate::plugin::PluginContext::launch_options`], for
+/// example to skip main menu when a specific scene is requested or to start hosting a game in
+/// server mode:
+///
+/// ```rust
+/// use fyrox::plugin::PluginContext;
+///
+/// fn on_init(context: &mut PluginContext) {
+///     let options = context.launch_options;
+///     if options.server {
+///         let port = options.get_parsed::<u16>("port").unwrap_or(7777);
+///         // Start listening on the port.
+///     }
+///     if options.is_set("god_mode") {
+///         // Make the player invulnerable.
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// A scene that should be loaded on start instead of the scene created by plugins.
+    pub scene: Option<PathBuf>,
+    /// Whether the game was asked to start in windowed mode.
+    pub windowed: bool,
+    /// Whether the game was asked to start in fullscreen mode.
+    pub fullscreen: bool,
+    /// Whether the game was started as a dedicated server.
+    pub server: bool,
+    /// Custom options for plugins. Options without a value have an empty string as value.
+    pub custom: BTreeMap<String, String>,
+}
+
+impl LaunchOptions {
+    /// Adds a custom option in `key=value` or `key` format.
+    pub fn add_custom_option(&mut self, option: &str) {
+        let (key, value) = match option.split_once('=') {
+            Some((key, value)) => (key, value),
+            None => (option, ""),
+        };
+        self.custom
+            .insert(key.trim().to_owned(), value.trim().to_owned());
+    }
+
+    /// Returns `true` if there is a custom option with the given key.
+    pub fn is_set(&self, key: &str) -> bool {
+        self.custom.contains_key(key)
+    }
+
+    /// Returns a value of a custom option with the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.custom.get(key).map(|v| v.as_str())
+    }
+
+    /// Returns a value of a custom option with the given key, parsed to the given type. `None` is
+    /// returned if there is no such option or if its value cannot be parsed.
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|v| v.parse().ok())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::engine::launch::LaunchOptions;
+
+    #[test]
+    fn test_custom_launch_options() {
+        let mut options = LaunchOptions::default();
+        options.add_custom_option("port=7777");
+        options.add_custom_option("god_mode");
+        options.add_custom_option("name = Player=1");
+
+        assert!(options.is_set("god_mode"));
+        assert_eq!(options.get("god_mode"), Some(""));
+        assert_eq!(options.get_parsed::<u16>("port"), Some(7777));
+        assert_eq!(options.get_parsed::<u16>("name"), None);
+        assert_eq!(options.get("name"), Some("Player=1"));
+        assert!(!options.is_set("missing"));
+    }
+}
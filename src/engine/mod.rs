@@ -5,34 +5,59 @@
 
 pub mod error;
 pub mod executor;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) mod graphics_context;
+pub mod launch;
 pub mod resource_manager;
+pub mod services;
 
 use crate::{
     asset::ResourceState,
-    core::{algebra::Vector2, futures::executor::block_on, instant, pool::Handle},
+    core::{
+        algebra::Vector2,
+        futures::executor::block_on,
+        instant,
+        pool::Handle,
+        rand::{rngs::StdRng, SeedableRng},
+        reflect::Reflect,
+        uuid::Uuid,
+    },
+    dpi::PhysicalPosition,
     engine::{
         error::EngineError,
+        launch::LaunchOptions,
         resource_manager::{container::event::ResourceEvent, ResourceManager, ResourceWaitContext},
+        services::EngineServices,
     },
     event::Event,
     event_loop::{ControlFlow, EventLoop},
-    gui::UserInterface,
+    gui::{platform::PlatformServices, UserInterface},
     plugin::{
-        Plugin, PluginConstructor, PluginContext, PluginRegistrationContext, SoundEngineHelper,
+        message::PluginMessageDispatcher,
+        sort_by_dependencies,
+        statistics::{PluginStatistics, TimingScope},
+        Plugin, PluginConstructor, PluginContext, PluginInitState, PluginRegistrationContext,
+        SoundEngineHelper,
     },
     renderer::{framework::error::FrameworkError, Renderer},
     resource::{model::Model, texture::TextureKind},
     scene::{
         base::NodeScriptMessage,
-        graph::GraphUpdateSwitches,
+        graph::{
+            physics::{ContactEvent, ContactEventPhase},
+            GraphUpdateSwitches,
+        },
         node::{constructor::NodeConstructorContainer, Node},
+        rigidbody::RigidBody,
         sound::SoundEngine,
         Scene, SceneContainer,
     },
     script::{
-        constructor::ScriptConstructorContainer, RoutingStrategy, Script, ScriptContext,
-        ScriptDeinitContext, ScriptMessage, ScriptMessageContext, ScriptMessageKind,
-        ScriptMessageSender,
+        constructor::ScriptConstructorContainer,
+        coroutine::{resume_coroutines, CoroutineScheduler},
+        profiler::ScriptCostProfiler,
+        RoutingStrategy, Script, ScriptContext, ScriptDeinitContext, ScriptMessage,
+        ScriptMessageContext, ScriptMessageKind, ScriptMessageSender,
     },
     utils::log::Log,
     window::{Window, WindowBuilder},
@@ -67,9 +92,18 @@ impl Default for SerializationContext {
 impl SerializationContext {
     /// Creates default serialization context.
     pub fn new() -> Self {
+        let script_constructors = ScriptConstructorContainer::new();
+
+        script_constructors
+            .add::<crate::script::occlusion_fade::CameraOcclusionFader>("Camera Occlusion Fader")
+            .add::<crate::script::visual::VisualScript>("Visual Script");
+
+        #[cfg(all(feature = "lua-scripting", not(target_arch = "wasm32")))]
+        script_constructors.add::<crate::script::lua::LuaScript>("Lua Script");
+
         Self {
             node_constructors: NodeConstructorContainer::new(),
-            script_constructors: ScriptConstructorContainer::new(),
+            script_constructors,
         }
     }
 }
@@ -77,12 +111,12 @@ impl SerializationContext {
 /// See module docs.
 pub struct Engine {
     #[cfg(not(target_arch = "wasm32"))]
-    context: glutin::WindowedContext<glutin::PossiblyCurrent>,
+    context: graphics_context::GraphicsContext,
     #[cfg(target_arch = "wasm32")]
     window: winit::window::Window,
     /// Current renderer. You should call at least [render](Self::render) method to see your scene on
-    /// screen.
-    pub renderer: Renderer,
+    /// screen. It is `None` if the engine is headless (see [`EngineInitParams::headless`]).
+    pub renderer: Option<Renderer>,
     /// User interface allows you to build interface of any kind.
     pub user_interface: UserInterface,
     /// Current resource manager. Resource manager can be cloned (it does clone only ref) to be able to
@@ -92,6 +126,12 @@ pub struct Engine {
     pub resource_manager: ResourceManager,
     /// All available scenes in the engine.
     pub scenes: SceneContainer,
+    /// Force feedback, achievements, telemetry, debug overlay, settings and Steam. See
+    /// [`EngineServices`] docs for more info.
+    pub services: EngineServices,
+    /// Options the game was launched with, usually parsed from command-line arguments by
+    /// [`executor::Executor`]. See [`LaunchOptions`] docs for more info.
+    pub launch_options: LaunchOptions,
     /// The time user interface took for internal needs. TODO: This is not the right place
     /// for such statistics, probably it is best to make separate structure to hold all
     /// such data.
@@ -107,19 +147,60 @@ pub struct Engine {
     // A set of plugin constructors.
     plugin_constructors: Vec<Box<dyn PluginConstructor>>,
 
+    // Type UUIDs of scripts registered by every plugin constructor, the order is the same as in
+    // `plugin_constructors`.
+    plugin_constructor_script_types: Vec<Vec<Uuid>>,
+
     // A set of plugins used by the engine.
     plugins: Vec<Box<dyn Plugin>>,
 
     plugins_enabled: bool,
 
+    // A scene, that was passed to plugin constructors when plugins were enabled. It is used to
+    // re-create instances of reloaded dynamic plugins.
+    #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+    plugins_override_scene: Handle<Scene>,
+
+    plugin_message_dispatcher: PluginMessageDispatcher,
+
+    // Initialization states of plugins, the order is the same as in `plugins`.
+    plugin_init_states: Vec<PluginInitState>,
+
+    // Flags that indicate whether a plugin is enabled or not, the order is the same as in `plugins`.
+    plugin_enabled: Vec<bool>,
+
+    // Indices of constructors of plugins, the order is the same as in `plugins`.
+    plugin_constructor_indices: Vec<usize>,
+
+    // Statistics of plugins for the last frame, the order is the same as in `plugins`.
+    plugin_statistics: Vec<PluginStatistics>,
+
+    // Statistics of plugins that is being collected on the current frame.
+    frame_plugin_statistics: Vec<PluginStatistics>,
+
+    // True if graphics context was lost and could not be restored, the error is reported only once
+    // and nothing is rendered after that.
+    graphics_context_unrecoverable: bool,
+
     // Amount of time (in seconds) that passed from creation of the engine.
     elapsed_time: f32,
 
+    // Amount of update ticks that passed from creation of the engine.
+    tick_count: u64,
+
     /// A special container that is able to create nodes by their type UUID. Use a copy of this
     /// value whenever you need it as a parameter in other parts of the engine.
     pub serialization_context: Arc<SerializationContext>,
 
     script_processor: ScriptProcessor,
+
+    // Must be dropped last, because plugins and constructors use the code of the libraries.
+    #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+    dynamic_plugins: Vec<crate::plugin::dylib::DynamicPlugin>,
+
+    // Must be dropped after everything else, so the report shows memory that outlives the engine.
+    #[cfg(feature = "memory-tracking")]
+    memory_report: crate::utils::memory::ShutdownReport,
 }
 
 /// Performs dispatch of script messages.
@@ -168,7 +249,10 @@ impl ScriptMessageDispatcher {
         resource_manager: &ResourceManager,
         dt: f32,
         elapsed_time: f32,
+        tick_count: u64,
+        rng: &mut StdRng,
         message_sender: &ScriptMessageSender,
+        script_times: &mut FxHashMap<Uuid, Duration>,
     ) {
         while let Ok(message) = self.message_receiver.try_recv() {
             let mut payload = message.payload;
@@ -179,6 +263,8 @@ impl ScriptMessageDispatcher {
                             let mut context = ScriptMessageContext {
                                 dt,
                                 elapsed_time,
+                                tick_count,
+                                rng,
                                 plugins,
                                 handle: target,
                                 scene,
@@ -186,7 +272,7 @@ impl ScriptMessageDispatcher {
                                 message_sender,
                             };
 
-                            process_node_message(&mut context, &mut |s, ctx| {
+                            process_node_message(&mut context, script_times, &mut |s, ctx| {
                                 s.on_message(&mut *payload, ctx)
                             })
                         }
@@ -200,6 +286,8 @@ impl ScriptMessageDispatcher {
                                 let mut context = ScriptMessageContext {
                                     dt,
                                     elapsed_time,
+                                    tick_count,
+                                    rng,
                                     plugins,
                                     handle: node,
                                     scene,
@@ -208,9 +296,11 @@ impl ScriptMessageDispatcher {
                                 };
 
                                 if receivers.contains(&node) {
-                                    process_node_message(&mut context, &mut |s, ctx| {
-                                        s.on_message(&mut *payload, ctx)
-                                    });
+                                    process_node_message(
+                                        &mut context,
+                                        script_times,
+                                        &mut |s, ctx| s.on_message(&mut *payload, ctx),
+                                    );
                                 }
 
                                 node = parent;
@@ -221,6 +311,8 @@ impl ScriptMessageDispatcher {
                                 let mut context = ScriptMessageContext {
                                     dt,
                                     elapsed_time,
+                                    tick_count,
+                                    rng,
                                     plugins,
                                     handle: node,
                                     scene,
@@ -229,9 +321,11 @@ impl ScriptMessageDispatcher {
                                 };
 
                                 if receivers.contains(&node) {
-                                    process_node_message(&mut context, &mut |s, ctx| {
-                                        s.on_message(&mut *payload, ctx)
-                                    });
+                                    process_node_message(
+                                        &mut context,
+                                        script_times,
+                                        &mut |s, ctx| s.on_message(&mut *payload, ctx),
+                                    );
                                 }
                             }
                         }
@@ -241,6 +335,8 @@ impl ScriptMessageDispatcher {
                             let mut context = ScriptMessageContext {
                                 dt,
                                 elapsed_time,
+                                tick_count,
+                                rng,
                                 plugins,
                                 handle: node,
                                 scene,
@@ -248,7 +344,7 @@ impl ScriptMessageDispatcher {
                                 message_sender,
                             };
 
-                            process_node_message(&mut context, &mut |s, ctx| {
+                            process_node_message(&mut context, script_times, &mut |s, ctx| {
                                 s.on_message(&mut *payload, ctx)
                             });
                         }
@@ -263,12 +359,18 @@ pub(crate) struct ScriptedScene {
     handle: Handle<Scene>,
     message_sender: ScriptMessageSender,
     message_dispatcher: ScriptMessageDispatcher,
+    coroutines: CoroutineScheduler,
+    rng: StdRng,
+    script_costs: ScriptCostProfiler,
 }
 
 #[derive(Default)]
 struct ScriptProcessor {
     wait_list: Vec<ResourceWaitContext>,
     scripted_scenes: Vec<ScriptedScene>,
+    rng_seed: u64,
+    // Time spent by scripts of each type on the current frame.
+    script_times: FxHashMap<Uuid, Duration>,
 }
 
 impl ScriptProcessor {
@@ -290,6 +392,9 @@ impl ScriptProcessor {
             handle: scene,
             message_sender: ScriptMessageSender { sender: tx },
             message_dispatcher: ScriptMessageDispatcher::new(rx),
+            coroutines: Default::default(),
+            rng: StdRng::seed_from_u64(self.rng_seed),
+            script_costs: Default::default(),
         });
 
         let graph = &mut scenes[scene].graph;
@@ -307,13 +412,24 @@ impl ScriptProcessor {
             .push(resource_manager.state().containers_mut().get_wait_context());
     }
 
+    fn set_rng_seed(&mut self, seed: u64) {
+        self.rng_seed = seed;
+
+        for scripted_scene in self.scripted_scenes.iter_mut() {
+            scripted_scene.rng = StdRng::seed_from_u64(seed);
+        }
+    }
+
     fn handle_scripts(
         &mut self,
         scenes: &mut SceneContainer,
         plugins: &mut Vec<Box<dyn Plugin>>,
         resource_manager: &ResourceManager,
+        platform: &PlatformServices,
+        services: &EngineServices,
         dt: f32,
         elapsed_time: f32,
+        tick_count: u64,
     ) {
         self.wait_list
             .retain_mut(|context| !context.is_all_loaded());
@@ -325,14 +441,19 @@ impl ScriptProcessor {
         self.scripted_scenes
             .retain(|s| scenes.is_valid_handle(s.handle));
 
+        let script_times = &mut self.script_times;
+
         'scene_loop: for scripted_scene in self.scripted_scenes.iter_mut() {
             let scene = &mut scenes[scripted_scene.handle];
 
             // Disabled scenes should not update their scripts.
             if !scene.enabled {
+                scene.performance_statistics.script_time = Duration::default();
                 continue 'scene_loop;
             }
 
+            let scripts_start_time = instant::Instant::now();
+
             // Fill in initial handles to nodes to update.
             let mut update_queue = VecDeque::new();
             for (handle, node) in scene.graph.pair_iter() {
@@ -343,6 +464,32 @@ impl ScriptProcessor {
                 }
             }
 
+            {
+                let mut context = ScriptContext {
+                    dt,
+                    elapsed_time,
+                    tick_count,
+                    rng: &mut scripted_scene.rng,
+                    plugins,
+                    handle: Default::default(),
+                    scene,
+                    resource_manager,
+                    message_sender: &scripted_scene.message_sender,
+                    message_dispatcher: &mut scripted_scene.message_dispatcher,
+                    coroutines: &mut scripted_scene.coroutines,
+                    platform,
+                    services,
+                };
+
+                // Deliver contact events of the last physics step first, so scripts could react to
+                // them in `on_update` on the same frame.
+                dispatch_contact_events(&mut context, script_times);
+
+                // Resume coroutines before `on_update`, new scripts created by coroutines will be
+                // initialized and updated on the same frame.
+                resume_coroutines(&mut context);
+            }
+
             // We'll gather all scripts queued for destruction and destroy them all at once at the
             // end of the frame.
             let mut destruction_queue = VecDeque::new();
@@ -353,12 +500,17 @@ impl ScriptProcessor {
                 let mut context = ScriptContext {
                     dt,
                     elapsed_time,
+                    tick_count,
+                    rng: &mut scripted_scene.rng,
                     plugins,
                     handle: Default::default(),
                     scene,
                     resource_manager,
                     message_sender: &scripted_scene.message_sender,
                     message_dispatcher: &mut scripted_scene.message_dispatcher,
+                    coroutines: &mut scripted_scene.coroutines,
+                    platform,
+                    services,
                 };
 
                 'init_loop: for init_loop_iteration in 0..max_iterations {
@@ -371,7 +523,7 @@ impl ScriptProcessor {
                             NodeScriptMessage::InitializeScript { handle } => {
                                 context.handle = handle;
 
-                                process_node(&mut context, &mut |script, context| {
+                                process_node(&mut context, script_times, &mut |script, context| {
                                     if !script.initialized {
                                         script.on_init(context);
                                         script.initialized = true;
@@ -398,7 +550,7 @@ impl ScriptProcessor {
                         while let Some(node) = start_queue.pop_front() {
                             context.handle = node;
 
-                            process_node(&mut context, &mut |script, context| {
+                            process_node(&mut context, script_times, &mut |script, context| {
                                 if !script.started {
                                     script.on_start(context);
                                     script.started = true;
@@ -424,8 +576,10 @@ impl ScriptProcessor {
                     while let Some(handle) = update_queue.pop_front() {
                         context.handle = handle;
 
-                        process_node(&mut context, &mut |script, context| {
-                            script.on_update(context);
+                        process_node(&mut context, script_times, &mut |script, context| {
+                            if script.is_enabled() {
+                                script.on_update(context);
+                            }
                         });
                     }
 
@@ -438,7 +592,10 @@ impl ScriptProcessor {
                         resource_manager,
                         dt,
                         elapsed_time,
+                        tick_count,
+                        &mut scripted_scene.rng,
                         &scripted_scene.message_sender,
+                        script_times,
                     );
                 }
 
@@ -453,6 +610,8 @@ impl ScriptProcessor {
             // As the last step, destroy queued scripts.
             let mut context = ScriptDeinitContext {
                 elapsed_time,
+                tick_count,
+                rng: &mut scripted_scene.rng,
                 plugins,
                 resource_manager,
                 scene,
@@ -464,18 +623,29 @@ impl ScriptProcessor {
 
                 // Unregister self in message dispatcher.
                 scripted_scene.message_dispatcher.unsubscribe(handle);
+                scripted_scene.coroutines.stop_all(handle);
 
                 // `on_deinit` could also spawn new nodes, but we won't take those into account on
                 // this frame. They'll be correctly handled on next frame.
                 script.on_deinit(&mut context);
             }
+
+            scene.performance_statistics.script_time = instant::Instant::now() - scripts_start_time;
+
+            scripted_scene
+                .script_costs
+                .collect(&mut scene.graph, elapsed_time);
         }
 
         // Process scripts from destroyed scenes.
         for (handle, mut detached_scene) in scenes.destruction_list.drain(..) {
-            if let Some(scripted_scene) = self.scripted_scenes.iter().find(|s| s.handle == handle) {
+            if let Some(scripted_scene) =
+                self.scripted_scenes.iter_mut().find(|s| s.handle == handle)
+            {
                 let mut context = ScriptDeinitContext {
                     elapsed_time,
+                    tick_count,
+                    rng: &mut scripted_scene.rng,
                     plugins,
                     resource_manager,
                     scene: &mut detached_scene,
@@ -590,8 +760,9 @@ pub struct EngineInitParams<'a> {
     /// vertical synchronization might not be available on your OS and engine might fail to
     /// initialize if v-sync is on.
     pub vsync: bool,
-    /// (experimental) Run the engine without opening a window (TODO) and without sound.
-    /// Useful for dedicated game servers or running on CI.
+    /// (experimental) Run the engine without graphics context, renderer and sound. The window is still
+    /// created (it is hidden), because it is required by the event loop. Useful for dedicated game
+    /// servers or running on CI.
     ///
     /// Headless support is incomplete, for progress see
     /// <https://github.com/FyroxEngine/Fyrox/issues/222>.
@@ -600,8 +771,11 @@ pub struct EngineInitParams<'a> {
 
 macro_rules! define_process_node {
     ($name:ident, $ctx_type:ty) => {
-        fn $name<T>(context: &mut $ctx_type, func: &mut T)
-        where
+        fn $name<T>(
+            context: &mut $ctx_type,
+            script_times: &mut FxHashMap<Uuid, Duration>,
+            func: &mut T,
+        ) where
             T: FnMut(&mut Script, &mut $ctx_type),
         {
             // Take a script from node. We're temporarily taking ownership over script
@@ -625,7 +799,9 @@ macro_rules! define_process_node {
                 }
             };
 
+            let scope = TimingScope::new(Reflect::type_name(&script), line!());
             func(&mut script, context);
+            script.frame_time += scope.finish(script_times.entry(script.id()).or_default());
 
             // Put the script back to the node. We must do a checked borrow, because it is possible
             // that the node is already destroyed by script logic.
@@ -639,14 +815,65 @@ macro_rules! define_process_node {
 define_process_node!(process_node, ScriptContext);
 define_process_node!(process_node_message, ScriptMessageContext);
 
+// Returns every node that should receive the contact event - the colliders of the pair and the rigid
+// bodies the colliders are attached to.
+fn contact_event_receivers(scene: &Scene, event: &ContactEvent) -> Vec<Handle<Node>> {
+    let mut receivers = Vec::new();
+    for collider in [event.collider1, event.collider2] {
+        if let Some(collider_ref) = scene.graph.try_get(collider) {
+            let parent = collider_ref.parent();
+            if !receivers.contains(&collider) {
+                receivers.push(collider);
+            }
+            if scene
+                .graph
+                .try_get(parent)
+                .map_or(false, |p| p.cast::<RigidBody>().is_some())
+                && !receivers.contains(&parent)
+            {
+                receivers.push(parent);
+            }
+        }
+    }
+    receivers
+}
+
+fn dispatch_contact_events(
+    context: &mut ScriptContext,
+    script_times: &mut FxHashMap<Uuid, Duration>,
+) {
+    let events = context.scene.graph.physics.contact_events().to_vec();
+    for event in events.iter() {
+        for receiver in contact_event_receivers(context.scene, event) {
+            context.handle = receiver;
+
+            process_node(context, script_times, &mut |script, context| {
+                if script.started {
+                    match event.phase {
+                        ContactEventPhase::Begin => script.on_collision_started(event, context),
+                        ContactEventPhase::Persist => script.on_contact(event, context),
+                        ContactEventPhase::End => script.on_collision_ended(event, context),
+                    }
+                }
+            });
+        }
+    }
+}
+
 pub(crate) fn process_scripts<T>(
     scene: &mut Scene,
     plugins: &mut [Box<dyn Plugin>],
     resource_manager: &ResourceManager,
+    platform: &PlatformServices,
+    services: &EngineServices,
     message_sender: &ScriptMessageSender,
     message_dispatcher: &mut ScriptMessageDispatcher,
+    coroutines: &mut CoroutineScheduler,
+    rng: &mut StdRng,
+    script_times: &mut FxHashMap<Uuid, Duration>,
     dt: f32,
     elapsed_time: f32,
+    tick_count: u64,
     mut func: T,
 ) where
     T: FnMut(&mut Script, &mut ScriptContext),
@@ -654,18 +881,23 @@ pub(crate) fn process_scripts<T>(
     let mut context = ScriptContext {
         dt,
         elapsed_time,
+        tick_count,
+        rng,
         plugins,
         handle: Default::default(),
         scene,
         resource_manager,
         message_sender,
         message_dispatcher,
+        coroutines,
+        platform,
+        services,
     };
 
     for node_index in 0..context.scene.graph.capacity() {
         context.handle = context.scene.graph.handle_from_index(node_index);
 
-        process_node(&mut context, &mut func);
+        process_node(&mut context, script_times, &mut func);
     }
 }
 
@@ -726,23 +958,14 @@ impl Engine {
 
         #[cfg(not(target_arch = "wasm32"))]
         let (context, client_size) = {
-            let context_wrapper: glutin::WindowedContext<glutin::NotCurrent> =
-                glutin::ContextBuilder::new()
-                    .with_vsync(vsync)
-                    .with_gl_profile(glutin::GlProfile::Core)
-                    .with_gl(glutin::GlRequest::GlThenGles {
-                        opengl_version: (3, 3),
-                        opengles_version: (3, 0),
-                    })
-                    .build_windowed(window_builder, events_loop)?;
-
-            let ctx = match unsafe { context_wrapper.make_current() } {
-                Ok(context) => context,
-                Err((_, e)) => return Err(EngineError::from(e)),
+            let context = if headless {
+                graphics_context::GraphicsContext::new_headless(window_builder, events_loop)?
+            } else {
+                graphics_context::GraphicsContext::new(window_builder, events_loop, vsync)?
             };
-            let inner_size = ctx.window().inner_size();
+            let inner_size = context.window().inner_size();
             (
-                ctx,
+                context,
                 Vector2::new(inner_size.width as f32, inner_size.height as f32),
             )
         };
@@ -769,7 +992,7 @@ impl Engine {
                 .unwrap()
                 .dyn_into::<crate::core::web_sys::WebGl2RenderingContext>()
                 .unwrap();
-            let glow_context = glow::Context::from_webgl2_context(webgl2_context);
+            let glow_context = Some(glow::Context::from_webgl2_context(webgl2_context));
 
             let inner_size = winit_window.inner_size();
             (
@@ -779,9 +1002,13 @@ impl Engine {
             )
         };
 
+        // Headless engine does not have graphics context and the renderer.
         #[cfg(not(target_arch = "wasm32"))]
-        let glow_context =
-            { unsafe { glow::Context::from_loader_function(|s| context.get_proc_address(s)) } };
+        let glow_context = if headless {
+            None
+        } else {
+            Some(unsafe { glow::Context::from_loader_function(|s| context.get_proc_address(s)) })
+        };
 
         let sound_engine = if headless {
             SoundEngine::new_headless()
@@ -789,11 +1016,26 @@ impl Engine {
             SoundEngine::new()
         };
 
-        let renderer = Renderer::new(
-            glow_context,
-            (client_size.x as u32, client_size.y as u32),
-            &resource_manager,
-        )?;
+        let renderer = match glow_context {
+            Some(glow_context) => {
+                #[cfg(feature = "memory-tracking")]
+                let _memory_tag = crate::utils::memory::MemoryTag::RENDERER.enter();
+                #[allow(unused_mut)]
+                let mut renderer = Renderer::new(
+                    glow_context,
+                    (client_size.x as u32, client_size.y as u32),
+                    &resource_manager,
+                )?;
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let state = renderer.pipeline_state();
+                    state.set_graphics_reset_status_fn(context.graphics_reset_status_fn());
+                    state.set_program_parameter_fn(context.program_parameter_fn());
+                }
+                Some(renderer)
+            }
+            None => None,
+        };
 
         let (rx, tx) = channel();
         resource_manager
@@ -803,12 +1045,26 @@ impl Engine {
             .event_broadcaster
             .add(rx);
 
+        // Asset registry allows to find assets by their GUIDs, even if they were moved or renamed.
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use crate::asset::registry::{self, AssetRegistry, ASSET_REGISTRY_PATH};
+
+            if registry::asset_registry().is_none() {
+                if let Ok(loaded) = block_on(AssetRegistry::load(ASSET_REGISTRY_PATH)) {
+                    registry::set_asset_registry(Some(loaded));
+                }
+            }
+        }
+
         Ok(Self {
             model_events_receiver: tx,
             resource_manager,
             renderer,
             scenes: SceneContainer::new(sound_engine.clone()),
             sound_engine,
+            services: EngineServices::new(),
+            launch_options: Default::default(),
             user_interface: UserInterface::new(Vector2::new(client_size.x, client_size.y)),
             ui_time: Default::default(),
             #[cfg(not(target_arch = "wasm32"))]
@@ -819,15 +1075,32 @@ impl Engine {
             serialization_context: node_constructors,
             script_processor: Default::default(),
             plugins_enabled: false,
+            #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+            plugins_override_scene: Default::default(),
+            plugin_message_dispatcher: Default::default(),
+            plugin_init_states: Default::default(),
+            plugin_enabled: Default::default(),
+            plugin_constructor_indices: Default::default(),
+            plugin_statistics: Default::default(),
+            frame_plugin_statistics: Default::default(),
             plugin_constructors: Default::default(),
+            plugin_constructor_script_types: Default::default(),
+            graphics_context_unrecoverable: false,
             elapsed_time: 0.0,
+            tick_count: 0,
+            #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+            dynamic_plugins: Default::default(),
+            #[cfg(feature = "memory-tracking")]
+            memory_report: Default::default(),
         })
     }
 
     /// Adjust size of the frame to be rendered. Must be called after the window size changes.
     /// Will update the renderer and GL context frame size.
     pub fn set_frame_size(&mut self, new_size: (u32, u32)) -> Result<(), FrameworkError> {
-        self.renderer.set_frame_size(new_size)?;
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_frame_size(new_size)?;
+        }
 
         #[cfg(not(target_arch = "wasm32"))]
         self.context.resize(new_size.into());
@@ -842,6 +1115,26 @@ impl Engine {
         self.elapsed_time
     }
 
+    /// Amount of update ticks that passed from creation of the engine. Unlike [`Self::elapsed_time`],
+    /// this value is an integer counter and it is not affected by floating-point accumulation errors,
+    /// which makes it suitable for deterministic game logic.
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    /// Sets a seed for random number generators that are passed to scripts (see [`ScriptContext::rng`]).
+    /// The seed is applied to every registered scripted scene and to all scenes that will be registered
+    /// later. Two runs of the game with the same seed and the same input will produce the same sequences
+    /// of random numbers in scripts.
+    pub fn set_script_rng_seed(&mut self, seed: u64) {
+        self.script_processor.set_rng_seed(seed);
+    }
+
+    /// Returns current seed of random number generators of scripts.
+    pub fn script_rng_seed(&self) -> u64 {
+        self.script_processor.rng_seed
+    }
+
     /// Returns reference to main window. Could be useful to set fullscreen mode, change
     /// size of window, its title, etc.
     #[inline]
@@ -897,8 +1190,20 @@ impl Engine {
         let inner_size = self.get_window().inner_size();
         let window_size = Vector2::new(inner_size.width as f32, inner_size.height as f32);
 
-        self.resource_manager.state().update(dt);
-        self.renderer.update_caches(dt);
+        self.services.debug_ui.begin_frame();
+        {
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::RESOURCES.enter();
+            self.resource_manager.state().update(dt);
+        }
+        {
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::RENDERER.enter();
+            if let Some(renderer) = self.renderer.as_mut() {
+                renderer.update_caches(dt);
+            }
+        }
+        self.services.update(dt);
         self.handle_model_events();
 
         for (handle, scene) in self.scenes.pair_iter_mut().filter(|(_, s)| s.enabled) {
@@ -910,6 +1215,8 @@ impl Engine {
                 }
             });
 
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::SCENE.enter();
             scene.update(
                 frame_size,
                 dt,
@@ -917,8 +1224,23 @@ impl Engine {
             );
         }
 
-        self.update_plugins(dt, control_flow, lag);
-        self.handle_scripts(dt);
+        {
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::PLUGINS.enter();
+            self.update_plugins(dt, control_flow, lag);
+        }
+        {
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::SCRIPTS.enter();
+            self.handle_scripts(dt);
+        }
+        {
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::PLUGINS.enter();
+            self.post_update_plugins(dt, control_flow, lag);
+        }
+        self.collect_plugin_statistics();
+        self.services.debug_ui.end_frame();
     }
 
     /// Performs post update for the engine.
@@ -930,9 +1252,22 @@ impl Engine {
         let window_size = Vector2::new(inner_size.width as f32, inner_size.height as f32);
 
         let time = instant::Instant::now();
-        self.user_interface.update(window_size, dt);
+        {
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::UI.enter();
+            self.user_interface.update(window_size, dt);
+        }
         self.ui_time = instant::Instant::now() - time;
+
+        if let Some(ime) = self.user_interface.poll_ime_state() {
+            let window = self.get_window();
+            window.set_ime_allowed(ime.allowed);
+            if ime.allowed {
+                window.set_ime_position(PhysicalPosition::new(ime.position.x, ime.position.y));
+            }
+        }
         self.elapsed_time += dt;
+        self.tick_count += 1;
     }
 
     /// Returns true if the scene is registered for script processing.
@@ -940,6 +1275,16 @@ impl Engine {
         self.script_processor.has_scripted_scene(scene)
     }
 
+    /// Returns script cost profiler of the given scene, or `None` if the scene is not registered for
+    /// script processing. See [`ScriptCostProfiler`] docs for more info.
+    pub fn script_cost_profiler(&self, scene: Handle<Scene>) -> Option<&ScriptCostProfiler> {
+        self.script_processor
+            .scripted_scenes
+            .iter()
+            .find(|s| s.handle == scene)
+            .map(|s| &s.script_costs)
+    }
+
     /// Registers a scene for script processing.
     pub fn register_scripted_scene(&mut self, scene: Handle<Scene>) {
         self.script_processor.register_scripted_scene(
@@ -954,50 +1299,166 @@ impl Engine {
             &mut self.scenes,
             &mut self.plugins,
             &self.resource_manager,
+            self.user_interface.platform(),
+            &self.services,
             dt,
             self.elapsed_time,
+            self.tick_count,
         );
     }
 
+    fn collect_plugin_statistics(&mut self) {
+        for (statistics, &constructor_index) in self
+            .frame_plugin_statistics
+            .iter_mut()
+            .zip(self.plugin_constructor_indices.iter())
+        {
+            statistics.collect_script_time(
+                &self.plugin_constructor_script_types[constructor_index],
+                &self.script_processor.script_times,
+            );
+        }
+        self.script_processor.script_times.clear();
+
+        self.plugin_statistics
+            .clone_from(&self.frame_plugin_statistics);
+        for statistics in self.frame_plugin_statistics.iter_mut() {
+            statistics.reset();
+        }
+    }
+
+    /// Returns timing statistics of every plugin for the last frame, the order is the same as the order
+    /// of plugin updates. It could be used to find plugins (and their scripts) that take the most of
+    /// frame time. See [`PluginStatistics`] docs for more info.
+    pub fn plugin_statistics(&self) -> &[PluginStatistics] {
+        &self.plugin_statistics
+    }
+
+    // Creates a context for plugin methods. The context borrows the whole engine, so plugins must be
+    // taken out of the engine while their methods are called.
+    fn plugin_context<'a>(&'a mut self, dt: f32, lag: &'a mut f32) -> PluginContext<'a, 'a> {
+        let init_progress = self.plugins_init_progress();
+
+        PluginContext {
+            scenes: &mut self.scenes,
+            resource_manager: &self.resource_manager,
+            renderer: self.renderer.as_mut(),
+            dt,
+            lag,
+            user_interface: &mut self.user_interface,
+            serialization_context: &self.serialization_context,
+            window: get_window!(self),
+            sound_engine: SoundEngineHelper {
+                engine: &self.sound_engine,
+            },
+            message_dispatcher: &mut self.plugin_message_dispatcher,
+            services: &self.services,
+            launch_options: &self.launch_options,
+            init_progress,
+        }
+    }
+
+    fn set_render_pass_owner(&mut self, owner: Option<usize>) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.render_pass_owner = owner;
+        }
+    }
+
+    fn remove_render_passes_of_owner(&mut self, owner: usize) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.remove_render_passes_of_owner(owner);
+        }
+    }
+
     fn update_plugins(&mut self, dt: f32, control_flow: &mut ControlFlow, lag: &mut f32) {
         if self.plugins_enabled {
-            let mut context = PluginContext {
-                scenes: &mut self.scenes,
-                resource_manager: &self.resource_manager,
-                renderer: &mut self.renderer,
-                dt,
-                lag,
-                user_interface: &mut self.user_interface,
-                serialization_context: &self.serialization_context,
-                window: get_window!(self),
-                sound_engine: SoundEngineHelper {
-                    engine: &self.sound_engine,
-                },
-            };
+            self.plugin_message_dispatcher.dispatch();
+
+            let mut plugins = std::mem::take(&mut self.plugins);
+
+            for (index, plugin) in plugins.iter_mut().enumerate() {
+                if !self.plugin_enabled[index] {
+                    continue;
+                }
+
+                self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                let scope = TimingScope::new(plugin.plugin_type_name(), line!());
+
+                let mut init_state = self.plugin_init_states[index];
+                let mut context = self.plugin_context(dt, lag);
+
+                if !init_state.is_ready() {
+                    init_state = plugin.poll_init(&mut context);
+                }
+
+                if init_state.is_ready() {
+                    plugin.update(&mut context, control_flow);
+                }
 
-            for plugin in self.plugins.iter_mut() {
-                plugin.update(&mut context, control_flow);
+                self.plugin_init_states[index] = init_state;
+                scope.finish(&mut self.frame_plugin_statistics[index].update_time);
             }
 
             while let Some(message) = self.user_interface.poll_message() {
-                let mut context = PluginContext {
-                    scenes: &mut self.scenes,
-                    resource_manager: &self.resource_manager,
-                    renderer: &mut self.renderer,
-                    dt,
-                    lag,
-                    user_interface: &mut self.user_interface,
-                    serialization_context: &self.serialization_context,
-                    window: get_window!(self),
-                    sound_engine: SoundEngineHelper {
-                        engine: &self.sound_engine,
-                    },
-                };
+                for (index, plugin) in plugins.iter_mut().enumerate() {
+                    if !self.plugin_enabled[index] {
+                        continue;
+                    }
+
+                    self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                    let scope = TimingScope::new(plugin.plugin_type_name(), line!());
+                    plugin.on_ui_message(&mut self.plugin_context(dt, lag), &message, control_flow);
+                    scope.finish(&mut self.frame_plugin_statistics[index].ui_message_time);
+                }
+            }
+
+            self.set_render_pass_owner(None);
+            self.plugins = plugins;
+        }
+    }
+
+    fn post_update_plugins(&mut self, dt: f32, control_flow: &mut ControlFlow, lag: &mut f32) {
+        if self.plugins_enabled {
+            let mut plugins = std::mem::take(&mut self.plugins);
+
+            for (index, plugin) in plugins.iter_mut().enumerate() {
+                if self.plugin_enabled[index] && self.plugin_init_states[index].is_ready() {
+                    self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                    let scope = TimingScope::new(plugin.plugin_type_name(), line!());
+                    plugin.post_update(&mut self.plugin_context(dt, lag), control_flow);
+                    scope.finish(&mut self.frame_plugin_statistics[index].post_update_time);
+                }
+            }
+
+            self.set_render_pass_owner(None);
+            self.plugins = plugins;
+        }
+    }
 
-                for plugin in self.plugins.iter_mut() {
-                    plugin.on_ui_message(&mut context, &message, control_flow);
+    /// Calls [`Plugin::variable_update`] of every enabled plugin, including the ones that are still being
+    /// initialized (see [`Plugin::poll_init`]). Unlike [`Self::update`], it must be called exactly once per
+    /// rendered frame (right before [`Self::render`]), `dt` is the amount of time that passed since the
+    /// previous frame and `interpolation_alpha` is a fraction of the fixed time step, that has passed since
+    /// the last call of [`Self::update`] (`lag / fixed_time_step`). It allows you to interpolate visual-only
+    /// state (for example, camera position) at render rate, while keeping the simulation deterministic.
+    pub fn variable_update(&mut self, dt: f32, interpolation_alpha: f32) {
+        if self.plugins_enabled {
+            let mut plugins = std::mem::take(&mut self.plugins);
+
+            for (index, plugin) in plugins.iter_mut().enumerate() {
+                if self.plugin_enabled[index] {
+                    self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                    let scope = TimingScope::new(plugin.plugin_type_name(), line!());
+                    plugin.variable_update(
+                        &mut self.plugin_context(dt, &mut 0.0),
+                        interpolation_alpha,
+                    );
+                    scope.finish(&mut self.frame_plugin_statistics[index].variable_update_time);
                 }
             }
+
+            self.set_render_pass_owner(None);
+            self.plugins = plugins;
         }
     }
 
@@ -1010,25 +1471,21 @@ impl Engine {
         lag: &mut f32,
     ) {
         if self.plugins_enabled {
-            for plugin in self.plugins.iter_mut() {
-                plugin.on_os_event(
-                    event,
-                    PluginContext {
-                        scenes: &mut self.scenes,
-                        resource_manager: &self.resource_manager,
-                        renderer: &mut self.renderer,
-                        dt,
-                        lag,
-                        user_interface: &mut self.user_interface,
-                        serialization_context: &self.serialization_context,
-                        window: get_window!(self),
-                        sound_engine: SoundEngineHelper {
-                            engine: &self.sound_engine,
-                        },
-                    },
-                    control_flow,
-                );
+            let mut plugins = std::mem::take(&mut self.plugins);
+
+            for (index, plugin) in plugins.iter_mut().enumerate() {
+                if !self.plugin_enabled[index] {
+                    continue;
+                }
+
+                self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                let scope = TimingScope::new(plugin.plugin_type_name(), line!());
+                plugin.on_os_event(event, self.plugin_context(dt, lag), control_flow);
+                scope.finish(&mut self.frame_plugin_statistics[index].os_event_time);
             }
+
+            self.set_render_pass_owner(None);
+            self.plugins = plugins;
         }
     }
 
@@ -1057,12 +1514,18 @@ impl Engine {
                     scene,
                     &mut self.plugins,
                     &self.resource_manager,
+                    self.user_interface.platform(),
+                    &self.services,
                     &scripted_scene.message_sender,
                     &mut scripted_scene.message_dispatcher,
+                    &mut scripted_scene.coroutines,
+                    &mut scripted_scene.rng,
+                    &mut self.script_processor.script_times,
                     dt,
                     self.elapsed_time,
+                    self.tick_count,
                     |script, context| {
-                        if script.initialized {
+                        if script.initialized && script.is_enabled() {
                             script.on_os_event(event, context);
                         }
                     },
@@ -1098,15 +1561,80 @@ impl Engine {
         }
     }
 
+    /// Interpolates (or extrapolates) rendered transforms of rigid bodies of every enabled scene.
+    /// `alpha` is a fraction of the fixed time step, that has passed since the last call of
+    /// [`Self::update`]. It should be called right before [`Self::render`] if your game loop uses
+    /// fixed time step and renders more frequently than it updates. See
+    /// [`crate::scene::rigidbody::RigidBodyInterpolation`] docs for more info.
+    pub fn interpolate_rigid_bodies(&mut self, alpha: f32) {
+        for scene in self.scenes.iter().filter(|s| s.enabled) {
+            scene.graph.interpolate_rigid_bodies(alpha);
+        }
+    }
+
     /// Performs rendering of single frame, must be called from your game loop, otherwise you won't
-    /// see anything.
+    /// see anything. Lost graphics context is restored automatically, if it cannot be restored (for
+    /// example, context re-creation is not supported on the platform) the error is returned once and
+    /// every next call does nothing.
     #[inline]
     pub fn render(&mut self) -> Result<(), FrameworkError> {
+        #[cfg(feature = "memory-tracking")]
+        let _memory_tag = crate::utils::memory::MemoryTag::RENDERER.enter();
+
+        let renderer = match self.renderer.as_mut() {
+            Some(renderer) => renderer,
+            // Headless engine does not render anything.
+            None => return Ok(()),
+        };
+
+        if renderer.is_context_lost() {
+            if self.graphics_context_unrecoverable {
+                // The error was reported already, there's nothing to render with.
+                return Ok(());
+            }
+
+            match self.restore_graphics_context() {
+                Ok(true) => (),
+                // Context is not available yet, skip the frame.
+                Ok(false) => return Ok(()),
+                Err(e) => {
+                    self.graphics_context_unrecoverable = true;
+                    return Err(e);
+                }
+            }
+        }
+
+        if self.plugins_enabled {
+            let mut plugins = std::mem::take(&mut self.plugins);
+
+            for (index, plugin) in plugins.iter_mut().enumerate() {
+                if self.plugin_enabled[index] {
+                    self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                    plugin.before_rendering(self.plugin_context(0.0, &mut 0.0));
+                }
+            }
+
+            self.set_render_pass_owner(None);
+            self.plugins = plugins;
+        }
+
         self.user_interface.draw();
+        let font = self.user_interface.default_font.clone();
+        let screen_size = self.user_interface.screen_size();
+        self.services.debug_ui.draw(
+            self.user_interface.get_drawing_context_mut(),
+            font,
+            screen_size,
+        );
+
+        let renderer = match self.renderer.as_mut() {
+            Some(renderer) => renderer,
+            None => return Ok(()),
+        };
 
         #[cfg(not(target_arch = "wasm32"))]
         {
-            self.renderer.render_and_swap_buffers(
+            renderer.render_and_swap_buffers(
                 &self.scenes,
                 self.user_interface.get_drawing_context(),
                 &self.context,
@@ -1114,11 +1642,63 @@ impl Engine {
         }
         #[cfg(target_arch = "wasm32")]
         {
-            self.renderer
+            renderer
                 .render_and_swap_buffers(&self.scenes, &self.user_interface.get_drawing_context())
         }
     }
 
+    /// Tries to re-create graphics context and the renderer after context loss. Returns `false` if
+    /// the context is not available yet (WebGL context must be restored by the browser first).
+    fn restore_graphics_context(&mut self) -> Result<bool, FrameworkError> {
+        // A lost context cannot be used anymore, so it is destroyed and a new one is created for
+        // the same window.
+        #[cfg(not(target_arch = "wasm32"))]
+        let glow_context = {
+            self.context.recreate()?;
+            unsafe { glow::Context::from_loader_function(|s| self.context.get_proc_address(s)) }
+        };
+
+        #[cfg(target_arch = "wasm32")]
+        let glow_context = {
+            use crate::core::wasm_bindgen::JsCast;
+            use crate::platform::web::WindowExtWebSys;
+
+            let webgl2_context = self
+                .window
+                .canvas()
+                .get_context("webgl2")
+                .ok()
+                .flatten()
+                .and_then(|ctx| {
+                    ctx.dyn_into::<crate::core::web_sys::WebGl2RenderingContext>()
+                        .ok()
+                });
+
+            match webgl2_context {
+                Some(webgl2_context) if !webgl2_context.is_context_lost() => {
+                    glow::Context::from_webgl2_context(webgl2_context)
+                }
+                _ => return Ok(false),
+            }
+        };
+
+        let renderer = match self.renderer.as_mut() {
+            Some(renderer) => renderer,
+            None => return Ok(true),
+        };
+
+        renderer.on_context_restored(glow_context, &self.resource_manager)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let state = renderer.pipeline_state();
+            state.set_graphics_reset_status_fn(self.context.graphics_reset_status_fn());
+            state.set_program_parameter_fn(self.context.program_parameter_fn());
+        }
+
+        Ok(true)
+    }
+
     /// Sets master gain of the sound engine. Can be used to control overall gain of all sound
     /// scenes at once.
     pub fn set_sound_gain(&mut self, gain: f32) {
@@ -1136,96 +1716,428 @@ impl Engine {
             self.plugins_enabled = enabled;
 
             if self.plugins_enabled {
+                #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+                {
+                    self.plugins_override_scene = override_scene;
+                }
+
                 // Create and initialize instances.
-                for constructor in self.plugin_constructors.iter() {
-                    self.plugins.push(constructor.create_instance(
-                        override_scene,
-                        PluginContext {
-                            scenes: &mut self.scenes,
-                            resource_manager: &self.resource_manager,
-                            renderer: &mut self.renderer,
-                            dt: 0.0,
-                            lag: &mut 0.0,
-                            user_interface: &mut self.user_interface,
-                            serialization_context: &self.serialization_context,
-                            window: get_window!(self),
-                            sound_engine: SoundEngineHelper {
-                                engine: &self.sound_engine,
-                            },
-                        },
-                    ));
+                let constructors = std::mem::take(&mut self.plugin_constructors);
+                for (constructor_index, constructor) in constructors.iter().enumerate() {
+                    self.set_render_pass_owner(Some(constructor_index));
+                    let plugin = constructor
+                        .create_instance(override_scene, self.plugin_context(0.0, &mut 0.0));
+                    self.plugins.push(plugin);
+                    self.plugin_init_states.push(PluginInitState::default());
+                    self.plugin_enabled.push(true);
+                }
+                self.plugin_constructors = constructors;
+
+                // Sort instances, so every plugin will be initialized and updated after its dependencies.
+                let order = sort_by_dependencies(&self.plugins);
+                let mut plugins = std::mem::take(&mut self.plugins)
+                    .into_iter()
+                    .map(Some)
+                    .collect::<Vec<_>>();
+                self.plugins = order
+                    .iter()
+                    .filter_map(|&index| plugins[index].take())
+                    .collect();
+                self.plugin_constructor_indices = order;
+                self.frame_plugin_statistics = self
+                    .plugins
+                    .iter()
+                    .map(|plugin| PluginStatistics::new(plugin.plugin_type_name(), plugin.id()))
+                    .collect();
+                self.plugin_statistics = self.frame_plugin_statistics.clone();
+
+                if self.scenes.is_valid_handle(override_scene) {
+                    self.load_plugin_settings(override_scene);
+                }
+
+                let mut plugins = std::mem::take(&mut self.plugins);
+                for (index, plugin) in plugins.iter_mut().enumerate() {
+                    self.set_render_pass_owner(Some(self.plugin_constructor_indices[index]));
+                    plugin.on_init(self.plugin_context(0.0, &mut 0.0));
                 }
+                self.set_render_pass_owner(None);
+                self.plugins = plugins;
             } else {
                 self.handle_scripts(0.0);
 
-                for mut plugin in self.plugins.drain(..) {
+                let plugins = std::mem::take(&mut self.plugins);
+                let enabled = self.plugin_enabled.clone();
+                let constructor_indices = self.plugin_constructor_indices.clone();
+
+                for ((mut plugin, enabled), constructor_index) in
+                    plugins.into_iter().zip(enabled).zip(constructor_indices)
+                {
+                    if !enabled {
+                        // Disabled plugins were deinitialized already.
+                        continue;
+                    }
+
                     // Deinit plugin first.
-                    plugin.on_deinit(PluginContext {
-                        scenes: &mut self.scenes,
-                        resource_manager: &self.resource_manager,
-                        renderer: &mut self.renderer,
-                        dt: 0.0,
-                        lag: &mut 0.0,
-                        user_interface: &mut self.user_interface,
-                        serialization_context: &self.serialization_context,
-                        window: get_window!(self),
-                        sound_engine: SoundEngineHelper {
-                            engine: &self.sound_engine,
-                        },
-                    });
+                    plugin.on_deinit(self.plugin_context(0.0, &mut 0.0));
+
+                    self.remove_render_passes_of_owner(constructor_index);
                 }
+
+                self.plugin_init_states.clear();
+                self.plugin_enabled.clear();
+                self.plugin_constructor_indices.clear();
+                self.plugin_statistics.clear();
+                self.frame_plugin_statistics.clear();
             }
         }
     }
 
-    /// Adds new plugin plugin constructor.
-    pub fn add_plugin_constructor<P>(&mut self, constructor: P)
-    where
-        P: PluginConstructor + 'static,
-    {
-        constructor.register(PluginRegistrationContext {
-            serialization_context: &self.serialization_context,
-        });
+    /// Returns initialization states of every plugin. See [`Plugin::poll_init`] for more info.
+    pub fn plugin_init_states(&self) -> &[PluginInitState] {
+        &self.plugin_init_states
+    }
 
-        self.plugin_constructors.push(Box::new(constructor));
+    /// Returns overall initialization progress of every plugin in `[0; 1]` range. It is `1.0` if every
+    /// plugin is ready (or if there's no plugins).
+    pub fn plugins_init_progress(&self) -> f32 {
+        let (count, sum) = self
+            .plugin_init_states
+            .iter()
+            .zip(self.plugin_enabled.iter())
+            .filter(|(_, enabled)| **enabled)
+            .fold((0, 0.0), |(count, sum), (state, _)| {
+                (count + 1, sum + state.progress())
+            });
+
+        if count == 0 {
+            1.0
+        } else {
+            sum / count as f32
+        }
     }
-}
 
-impl Drop for Engine {
-    fn drop(&mut self) {
-        // Destroy all scenes first and correctly destroy all script instances.
-        // This will ensure that any `on_destroy` logic will be executed before
-        // engine destroyed.
-        let scenes = self
-            .scenes
-            .pair_iter()
-            .map(|(h, _)| h)
-            .collect::<Vec<Handle<Scene>>>();
+    /// Enables or disables a plugin of the given type. Disabled plugin is deinitialized using [`Plugin::on_deinit`]
+    /// and it does not receive any updates or events until it is enabled back, which initializes it again using
+    /// [`Plugin::on_init`] (and [`Plugin::poll_init`]). Returns `false` if there's no plugin of the given type.
+    pub fn set_plugin_enabled<T: Plugin>(&mut self, enabled: bool) -> bool {
+        let index = match self.plugins.iter().position(|p| p.cast::<T>().is_some()) {
+            Some(index) => index,
+            None => return false,
+        };
 
-        for handle in scenes {
-            self.scenes.remove(handle);
+        if self.plugin_enabled[index] != enabled {
+            self.plugin_enabled[index] = enabled;
+
+            let constructor_index = self.plugin_constructor_indices[index];
+            let mut plugins = std::mem::take(&mut self.plugins);
+            let plugin = &mut plugins[index];
+            if enabled {
+                self.plugin_init_states[index] = PluginInitState::default();
+                self.set_render_pass_owner(Some(constructor_index));
+                plugin.on_init(self.plugin_context(0.0, &mut 0.0));
+                self.set_render_pass_owner(None);
+            } else {
+                plugin.on_deinit(self.plugin_context(0.0, &mut 0.0));
+                self.remove_render_passes_of_owner(constructor_index);
+            }
+            self.plugins = plugins;
         }
 
-        // Finally disable plugins.
-        self.enable_plugins(Default::default(), false);
+        true
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::script::{ScriptMessageContext, ScriptMessagePayload};
-    use crate::{
-        core::{pool::Handle, reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
-        engine::{resource_manager::ResourceManager, ScriptProcessor},
-        impl_component_provider,
-        scene::{base::BaseBuilder, node::Node, pivot::PivotBuilder, Scene, SceneContainer},
-        script::{Script, ScriptContext, ScriptDeinitContext, ScriptTrait},
-    };
-    use std::sync::mpsc::{self, Sender, TryRecvError};
+    /// Returns `Some(true)` if a plugin of the given type is enabled, `Some(false)` if it is disabled and `None`
+    /// if there's no such plugin.
+    pub fn is_plugin_enabled<T: Plugin>(&self) -> Option<bool> {
+        self.plugins
+            .iter()
+            .position(|p| p.cast::<T>().is_some())
+            .map(|index| self.plugin_enabled[index])
+    }
 
-    #[derive(PartialEq, Eq, Clone, Debug)]
-    enum Event {
-        Initialized(Handle<Node>),
+    /// Saves settings of every plugin (see [`Plugin::settings`]) in the given scene, so they will be
+    /// saved along with the scene.
+    pub fn save_plugin_settings(&mut self, scene: Handle<Scene>) {
+        let scene = &mut self.scenes[scene];
+        for plugin in self.plugins.iter_mut() {
+            let id = plugin.id();
+            if let Some(settings) = plugin.settings_mut() {
+                if id.is_nil() {
+                    Log::warn("Unable to save settings of a plugin without id!");
+                } else if let Err(e) = scene.plugin_settings.store(id, settings) {
+                    Log::err(format!(
+                        "Unable to save settings of plugin {}. Reason: {:?}",
+                        id, e
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Restores settings of every plugin (see [`Plugin::settings`]) from the given scene. Plugins,
+    /// which settings are not stored in the scene, keep their current settings. It is called
+    /// automatically for an override scene (see [`PluginConstructor::create_instance`]).
+    pub fn load_plugin_settings(&mut self, scene: Handle<Scene>) {
+        let scene = &self.scenes[scene];
+        for plugin in self.plugins.iter_mut() {
+            let id = plugin.id();
+            if let Some(settings) = plugin.settings_mut() {
+                if let Err(e) = scene.plugin_settings.restore(id, settings) {
+                    Log::err(format!(
+                        "Unable to load settings of plugin {}. Reason: {:?}",
+                        id, e
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Returns a list of registered plugin constructors.
+    pub fn plugin_constructors(&self) -> &[Box<dyn PluginConstructor>] {
+        &self.plugin_constructors
+    }
+
+    /// Loads a plugin from a shared library and adds its constructor to the engine, like
+    /// [`Self::add_plugin_constructor`] does. The library must define entry points using
+    /// [`crate::define_dynamic_plugin`] macro. This is a development-only feature, see
+    /// [`crate::plugin::dylib`] module docs for more info.
+    ///
+    /// # Safety
+    ///
+    /// The library must be compiled with the same compiler, the same engine version and the same set of
+    /// features as the host executable.
+    #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+    pub unsafe fn add_dynamic_plugin<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(), crate::plugin::dylib::DynamicPluginError> {
+        let (mut dynamic_plugin, constructor) =
+            crate::plugin::dylib::DynamicPlugin::load(path.as_ref())?;
+
+        let (script_types, node_types) = self.register_plugin_constructor(&*constructor);
+        self.plugin_constructor_script_types
+            .push(script_types.clone());
+        dynamic_plugin.script_types = script_types;
+        dynamic_plugin.node_types = node_types;
+        dynamic_plugin.constructor_index = self.plugin_constructors.len();
+
+        self.plugin_constructors.push(constructor);
+        self.dynamic_plugins.push(dynamic_plugin);
+
+        Ok(())
+    }
+
+    // Registers the constructor and returns type UUIDs of scripts and nodes registered by it.
+    fn register_plugin_constructor(
+        &self,
+        constructor: &dyn PluginConstructor,
+    ) -> (Vec<Uuid>, Vec<Uuid>) {
+        let scripts = &self.serialization_context.script_constructors;
+        let nodes = &self.serialization_context.node_constructors;
+
+        let scripts_before = scripts.map().keys().cloned().collect::<FxHashSet<_>>();
+        let nodes_before = nodes.type_uuids().into_iter().collect::<FxHashSet<_>>();
+
+        constructor.register(PluginRegistrationContext {
+            serialization_context: &self.serialization_context,
+        });
+
+        let script_types = scripts
+            .map()
+            .keys()
+            .filter(|uuid| !scripts_before.contains(uuid))
+            .cloned()
+            .collect();
+        let node_types = nodes
+            .type_uuids()
+            .into_iter()
+            .filter(|uuid| !nodes_before.contains(uuid))
+            .collect();
+
+        (script_types, node_types)
+    }
+
+    /// Reloads every dynamic plugin (see [`Self::add_dynamic_plugin`]) which library was modified since
+    /// last load, the state of plugin instances is preserved. See [`crate::plugin::dylib`] module docs
+    /// for more info. It is called by [`crate::engine::executor::Executor`] automatically. Returns amount
+    /// of reloaded plugins.
+    #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+    pub fn reload_dynamic_plugins(&mut self) -> usize {
+        let mut reloaded = 0;
+
+        for i in 0..self.dynamic_plugins.len() {
+            if !self.dynamic_plugins[i].is_modified() {
+                continue;
+            }
+
+            let dynamic_plugin = &mut self.dynamic_plugins[i];
+            let constructor = match unsafe { dynamic_plugin.reload() } {
+                Ok(constructor) => constructor,
+                Err(e) => {
+                    // The library could be not fully written yet, try again later.
+                    Log::warn(format!(
+                        "Unable to reload plugin {}. Reason: {}",
+                        dynamic_plugin.path.display(),
+                        e
+                    ));
+                    continue;
+                }
+            };
+
+            for uuid in dynamic_plugin.script_types.drain(..) {
+                self.serialization_context.script_constructors.remove(uuid);
+            }
+            for uuid in dynamic_plugin.node_types.drain(..) {
+                self.serialization_context.node_constructors.remove(uuid);
+            }
+
+            let (script_types, node_types) = self.register_plugin_constructor(&*constructor);
+            let dynamic_plugin = &mut self.dynamic_plugins[i];
+            let constructor_index = dynamic_plugin.constructor_index;
+            self.plugin_constructor_script_types[constructor_index] = script_types.clone();
+            dynamic_plugin.script_types = script_types;
+            dynamic_plugin.node_types = node_types;
+
+            self.plugin_constructors[constructor_index] = constructor;
+
+            // Swap instances, if the plugins are enabled.
+            for plugin_index in 0..self.plugins.len() {
+                if self.plugin_constructor_indices[plugin_index] == constructor_index {
+                    self.reload_plugin_instance(plugin_index);
+                }
+            }
+
+            Log::info(format!(
+                "Plugin {} was reloaded successfully!",
+                self.dynamic_plugins[i].path.display()
+            ));
+
+            reloaded += 1;
+        }
+
+        reloaded
+    }
+
+    #[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+    fn reload_plugin_instance(&mut self, plugin_index: usize) {
+        let constructor_index = self.plugin_constructor_indices[plugin_index];
+        let enabled = self.plugin_enabled[plugin_index];
+
+        let mut plugins = std::mem::take(&mut self.plugins);
+
+        // Disabled plugins were deinitialized already.
+        if enabled {
+            self.set_render_pass_owner(Some(constructor_index));
+            plugins[plugin_index].on_deinit(self.plugin_context(0.0, &mut 0.0));
+        }
+
+        // Render passes were created by the code of the previous version of the library.
+        self.remove_render_passes_of_owner(constructor_index);
+        self.set_render_pass_owner(Some(constructor_index));
+
+        let constructors = std::mem::take(&mut self.plugin_constructors);
+        let constructor = &constructors[constructor_index];
+
+        let mut state = None;
+        if let Some(mut new_plugin) = constructor.default_boxed() {
+            let mut visitor = crate::core::visitor::Visitor::new();
+            let data = plugins[plugin_index]
+                .visit_state(&mut visitor)
+                .and_then(|_| visitor.save_binary_to_vec())
+                .and_then(crate::core::visitor::Visitor::load_from_memory)
+                .and_then(|mut visitor| new_plugin.visit_state(&mut visitor));
+            match data {
+                Ok(_) => state = Some(new_plugin),
+                Err(e) => Log::err(format!(
+                    "Unable to transfer plugin state, the plugin will be re-created. Reason: {:?}",
+                    e
+                )),
+            }
+        }
+
+        let mut new_plugin = match state {
+            Some(new_plugin) => new_plugin,
+            None => constructor.create_instance(
+                self.plugins_override_scene,
+                self.plugin_context(0.0, &mut 0.0),
+            ),
+        };
+        self.plugin_constructors = constructors;
+
+        if enabled {
+            self.plugin_init_states[plugin_index] = PluginInitState::default();
+            new_plugin.on_init(self.plugin_context(0.0, &mut 0.0));
+        }
+
+        self.set_render_pass_owner(None);
+        plugins[plugin_index] = new_plugin;
+        self.plugins = plugins;
+    }
+
+    /// Adds new plugin plugin constructor.
+    pub fn add_plugin_constructor<P>(&mut self, constructor: P)
+    where
+        P: PluginConstructor + 'static,
+    {
+        let (script_types, _) = self.register_plugin_constructor(&constructor);
+        self.plugin_constructor_script_types.push(script_types);
+
+        self.plugin_constructors.push(Box::new(constructor));
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        // Destroy all scenes first and correctly destroy all script instances.
+        // This will ensure that any `on_destroy` logic will be executed before
+        // engine destroyed.
+        let scenes = self
+            .scenes
+            .pair_iter()
+            .map(|(h, _)| h)
+            .collect::<Vec<Handle<Scene>>>();
+
+        for handle in scenes {
+            self.scenes.remove(handle);
+        }
+
+        // Finally disable plugins.
+        self.enable_plugins(Default::default(), false);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rand::Rng;
+    use crate::script::{ScriptMessageContext, ScriptMessagePayload};
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+            reflect::prelude::*,
+            uuid::{uuid, Uuid},
+            visitor::prelude::*,
+        },
+        engine::{resource_manager::ResourceManager, ScriptProcessor},
+        gui::platform::PlatformServices,
+        impl_component_provider,
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::physics::{ContactEvent, ContactEventPhase},
+            node::Node,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+            Scene, SceneContainer,
+        },
+        script::{RoutingStrategy, Script, ScriptContext, ScriptDeinitContext, ScriptTrait},
+    };
+    use std::sync::mpsc::{self, Sender, TryRecvError};
+
+    #[derive(PartialEq, Eq, Clone, Debug)]
+    enum Event {
+        Initialized(Handle<Node>),
         Started(Handle<Node>),
         Updated(Handle<Node>),
         Destroyed(Handle<Node>),
@@ -1286,7 +2198,7 @@ mod test {
         }
 
         fn id(&self) -> Uuid {
-            Uuid::new_v4()
+            uuid!("a6fb1b4c-4f51-4a4d-9a2b-8f7c3e5b0d21")
         }
     }
 
@@ -1317,7 +2229,7 @@ mod test {
         }
 
         fn id(&self) -> Uuid {
-            Uuid::new_v4()
+            uuid!("1d2e8a37-0c6b-4f9e-b5d4-3a7f6c2e9b18")
         }
     }
 
@@ -1357,8 +2269,11 @@ mod test {
                 &mut scene_container,
                 &mut Default::default(),
                 &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
                 0.0,
                 0.0,
+                0,
             );
 
             match iteration {
@@ -1380,6 +2295,12 @@ mod test {
                     assert_eq!(rx.try_recv(), Ok(Event::Started(handle_on_update1)));
 
                     assert_eq!(rx.try_recv(), Ok(Event::Updated(handle_on_update1)));
+
+                    // Time of every script call is recorded per script type.
+                    assert_eq!(script_processor.script_times.len(), 2);
+                    assert!(script_processor
+                        .script_times
+                        .contains_key(&uuid!("a6fb1b4c-4f51-4a4d-9a2b-8f7c3e5b0d21")));
                 }
                 1 => {
                     assert_eq!(rx.try_recv(), Ok(Event::Updated(node_handle)));
@@ -1524,8 +2445,11 @@ mod test {
                 &mut scene_container,
                 &mut Default::default(),
                 &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
                 0.0,
                 0.0,
+                0,
             );
 
             match iteration {
@@ -1541,4 +2465,542 @@ mod test {
             }
         }
     }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptSendingMessagesFromContext {
+        index: u32,
+        target: Handle<Node>,
+    }
+
+    impl_component_provider!(ScriptSendingMessagesFromContext);
+
+    impl ScriptTrait for ScriptSendingMessagesFromContext {
+        fn on_update(&mut self, ctx: &mut ScriptContext) {
+            match self.index {
+                0 => ctx.broadcast_message(RoutingStrategy::Down, MyMessage::Foo(123)),
+                1 => ctx.send_message(self.target, MyMessage::Bar("Foobar".to_string())),
+                _ => (),
+            }
+            self.index += 1;
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_context_messages() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let receiver_messages = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(
+            ScriptListeningToMessages {
+                sender: tx,
+                index: 0,
+            },
+        )))
+        .build(&mut scene.graph);
+
+        PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[receiver_messages])
+                .with_script(Script::new(ScriptSendingMessagesFromContext {
+                    index: 0,
+                    target: receiver_messages,
+                })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for _ in 0..2 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                0.0,
+                0.0,
+                0,
+            );
+
+            assert_eq!(rx.try_recv(), Ok(Event::EventReceived(receiver_messages)));
+            assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        }
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptListeningToContacts {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<ContactEventPhase>,
+    }
+
+    impl_component_provider!(ScriptListeningToContacts);
+
+    impl ScriptTrait for ScriptListeningToContacts {
+        fn on_collision_started(&mut self, event: &ContactEvent, _ctx: &mut ScriptContext) {
+            self.sender.send(event.phase).unwrap();
+        }
+
+        fn on_collision_ended(&mut self, event: &ContactEvent, _ctx: &mut ScriptContext) {
+            self.sender.send(event.phase).unwrap();
+        }
+
+        fn on_contact(&mut self, event: &ContactEvent, _ctx: &mut ScriptContext) {
+            self.sender.send(event.phase).unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_contact_callbacks() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let floor = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 0.1, 5.0))
+            .build(&mut scene.graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[floor]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut scene.graph);
+
+        let cube = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.25, 0.25, 0.25))
+            .build(&mut scene.graph);
+        // The script is assigned to the rigid body, not to the collider.
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[cube])
+                .with_script(Script::new(ScriptListeningToContacts { sender: tx })),
+        )
+        .build(&mut scene.graph);
+
+        scene.graph.physics.contact_event_filter.enabled = true;
+        scene.graph.physics.contact_event_filter.report_persist = true;
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for i in 0..120 {
+            if i == 60 {
+                // Throw the cube away from the floor.
+                scene_container[scene_handle].graph[body]
+                    .as_rigid_body_mut()
+                    .set_lin_vel(Vector3::new(0.0, 50.0, 0.0));
+            }
+
+            scene_container[scene_handle].graph.update(
+                Vector2::new(800.0, 600.0),
+                1.0 / 60.0,
+                Default::default(),
+            );
+
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                1.0 / 60.0,
+                0.0,
+                i,
+            );
+        }
+
+        let phases = rx.try_iter().collect::<Vec<_>>();
+        assert_eq!(phases.first(), Some(&ContactEventPhase::Begin));
+        assert!(phases.contains(&ContactEventPhase::Persist));
+        assert_eq!(phases.last(), Some(&ContactEventPhase::End));
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptWithCoroutine {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<u32>,
+    }
+
+    impl_component_provider!(ScriptWithCoroutine);
+
+    impl ScriptTrait for ScriptWithCoroutine {
+        fn on_start(&mut self, ctx: &mut ScriptContext) {
+            let sender = self.sender.clone();
+            ctx.start_coroutine(|co| async move {
+                co.wait_frames(1).await;
+                let first = sender.clone();
+                co.with(move |_| first.send(1).unwrap()).await;
+                co.wait_seconds(1.0).await;
+                co.with(move |_| sender.send(2).unwrap()).await;
+            });
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn test_coroutines() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        PivotBuilder::new(
+            BaseBuilder::new().with_script(Script::new(ScriptWithCoroutine { sender: tx })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        let expected = [None, None, Some(1), None, Some(2), None];
+        for (tick_count, expected) in expected.into_iter().enumerate() {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                0.5,
+                tick_count as f32 * 0.5,
+                tick_count as u64,
+            );
+
+            assert_eq!(rx.try_recv().ok(), expected);
+        }
+
+        assert!(script_processor.scripted_scenes[0].coroutines.is_empty());
+    }
+
+    #[cfg(all(feature = "lua-scripting", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_lua_script() {
+        use crate::{resource::script_source::ScriptSource, script::lua::LuaScript};
+
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let source = ScriptSource::new(
+            r#"
+            function on_start()
+                fyrox.send_message(fyrox.handle(), "jump")
+            end
+
+            function on_message(text, sender)
+                if text == "jump" and sender == fyrox.handle() then
+                    local x, y, z = fyrox.get_position(fyrox.handle())
+                    fyrox.set_position(fyrox.handle(), x, y + 10, z)
+                end
+            end
+
+            function on_update(dt)
+                local x, y, z = fyrox.get_position(fyrox.handle())
+                fyrox.set_position(fyrox.handle(), x + dt, y, z)
+            end
+            "#,
+        );
+
+        let node =
+            PivotBuilder::new(BaseBuilder::new().with_script(Script::new(LuaScript::new(source))))
+                .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for tick_count in 0..2 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                0.5,
+                tick_count as f32 * 0.5,
+                tick_count as u64,
+            );
+        }
+
+        assert_eq!(
+            **scene_container[scene_handle].graph[node]
+                .local_transform()
+                .position(),
+            Vector3::new(1.0, 10.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_visual_script() {
+        use crate::{
+            resource::visual_script::{
+                VisualNodeKind, VisualScriptGraph, VisualScriptResource, VisualValue,
+            },
+            script::visual::VisualScript,
+        };
+
+        let mut graph = VisualScriptGraph::default();
+
+        // On start: send "ping" message with payload 2 to itself.
+        let on_start = graph.add_node(VisualNodeKind::OnStart);
+        let this = graph.add_node(VisualNodeKind::SelfNode);
+        let send = graph.add_node(VisualNodeKind::SendMessage {
+            name: "ping".to_string(),
+        });
+        graph.link(this, 0, send, 0);
+        graph.set_input_value(send, 1, VisualValue::Number(2.0));
+        graph.connect(on_start, 0, send);
+
+        // On "ping": hide the node and remember the payload.
+        let on_ping = graph.add_node(VisualNodeKind::OnMessage {
+            name: "ping".to_string(),
+        });
+        let hide = graph.add_node(VisualNodeKind::SetProperty {
+            path: "base.visibility".to_string(),
+        });
+        graph.link(on_ping, 0, hide, 0);
+        graph.set_input_value(hide, 1, VisualValue::Bool(false));
+        graph.connect(on_ping, 0, hide);
+        let set_payload = graph.add_node(VisualNodeKind::SetVariable {
+            name: "payload".to_string(),
+        });
+        graph.link(on_ping, 1, set_payload, 0);
+        graph.connect(hide, 0, set_payload);
+
+        // On update: position = position + (0, payload, 0).
+        let on_update = graph.add_node(VisualNodeKind::OnUpdate);
+        let payload = graph.add_node(VisualNodeKind::GetVariable {
+            name: "payload".to_string(),
+        });
+        let offset = graph.add_node(VisualNodeKind::Multiply);
+        graph.set_input_value(offset, 0, VisualValue::Vector(Vector3::new(0.0, 1.0, 0.0)));
+        graph.link(payload, 0, offset, 1);
+        let position = graph.add_node(VisualNodeKind::GetPosition);
+        graph.link(this, 0, position, 0);
+        let new_position = graph.add_node(VisualNodeKind::Add);
+        graph.link(position, 0, new_position, 0);
+        graph.link(offset, 0, new_position, 1);
+        let set_position = graph.add_node(VisualNodeKind::SetPosition);
+        graph.link(this, 0, set_position, 0);
+        graph.link(new_position, 0, set_position, 1);
+        graph.connect(on_update, 0, set_position);
+
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let node = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(
+            VisualScript::new(VisualScriptResource::new(graph)),
+        )))
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for _ in 0..2 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                0.0,
+                0.0,
+                0,
+            );
+        }
+
+        let node_ref = &scene_container[scene_handle].graph[node];
+        assert!(!node_ref.visibility());
+        assert_eq!(
+            node_ref
+                .try_get_script::<VisualScript>()
+                .unwrap()
+                .variable("payload"),
+            Some(&VisualValue::Number(2.0))
+        );
+        // The message is delivered after the first update, so the node moves only once.
+        assert_eq!(
+            **node_ref.local_transform().position(),
+            Vector3::new(0.0, 2.0, 0.0)
+        );
+    }
+
+    #[derive(Debug, Clone, Reflect, Visit)]
+    struct ScriptUsingRng {
+        #[reflect(hidden)]
+        #[visit(skip)]
+        sender: Sender<(u64, u32)>,
+    }
+
+    impl_component_provider!(ScriptUsingRng);
+
+    impl ScriptTrait for ScriptUsingRng {
+        fn on_update(&mut self, ctx: &mut ScriptContext) {
+            self.sender
+                .send((ctx.tick_count, ctx.rng.gen::<u32>()))
+                .unwrap();
+        }
+
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    fn run_rng_script(seed: u64) -> Vec<(u64, u32)> {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        PivotBuilder::new(
+            BaseBuilder::new().with_script(Script::new(ScriptUsingRng { sender: tx })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.set_rng_seed(seed);
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for tick_count in 0..4 {
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                0.0,
+                0.0,
+                tick_count,
+            );
+        }
+
+        rx.try_iter().collect()
+    }
+
+    #[test]
+    fn test_disabled_script() {
+        let resource_manager = ResourceManager::new(Default::default());
+        let mut scene = Scene::new();
+
+        let (tx, rx) = mpsc::channel();
+
+        let node = PivotBuilder::new(
+            BaseBuilder::new().with_script(Script::new(ScriptUsingRng { sender: tx })),
+        )
+        .build(&mut scene.graph);
+
+        let mut scene_container = SceneContainer::new(Default::default());
+        let scene_handle = scene_container.add(scene);
+
+        let mut script_processor = ScriptProcessor::default();
+        script_processor.register_scripted_scene(
+            scene_handle,
+            &mut scene_container,
+            &resource_manager,
+        );
+
+        for tick_count in 0..4 {
+            if tick_count == 2 {
+                scene_container[scene_handle].graph[node]
+                    .script_mut()
+                    .unwrap()
+                    .set_enabled(false);
+            }
+
+            script_processor.handle_scripts(
+                &mut scene_container,
+                &mut Default::default(),
+                &resource_manager,
+                &PlatformServices::new(),
+                &Default::default(),
+                0.0,
+                0.0,
+                tick_count,
+            );
+        }
+
+        assert_eq!(
+            rx.try_iter().map(|(tick, _)| tick).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+
+        // A disabled script keeps its state and its flag survives cloning.
+        let script = scene_container[scene_handle].graph[node]
+            .script_cloned()
+            .unwrap();
+        assert!(!script.is_enabled());
+        assert!(script.cast::<ScriptUsingRng>().is_some());
+    }
+
+    #[test]
+    fn test_deterministic_script_rng() {
+        let first = run_rng_script(123);
+        let second = run_rng_script(123);
+        assert_eq!(first.len(), 4);
+        assert_eq!(first, second);
+        assert_eq!(
+            first.iter().map(|(tick, _)| *tick).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_ne!(first, run_rng_script(321));
+    }
 }
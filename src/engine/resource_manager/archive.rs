@@ -0,0 +1,265 @@
+//! Packed archives allow you to ship game data as a single file. Archives are usually produced by
+//! the cooking step (see [`crate::utils::cook`]), but they could be made manually with
+//! [`ArchiveWriter`] as well.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox::{core::io, engine::resource_manager::archive::ArchiveResourceIo};
+//! use std::sync::Arc;
+//!
+//! let archive = ArchiveResourceIo::open("data.pak").unwrap();
+//! io::set_resource_io(Some(Arc::new(archive)));
+//! ```
+//!
+//! Every file, that is stored in the archive, is loaded from the archive; every other file is loaded
+//! from the local file system.
+//!
+//! # Format
+//!
+//! An archive starts with a header: `FPAK` magic, format version (`u32`) and entry count (`u32`). The
+//! header is followed by the table of entries, each entry is a path (length as `u32` and UTF-8 bytes,
+//! components are separated by `/`), an offset of the data from the beginning of the archive (`u64`)
+//! and the size of the data (`u64`). Contents of the files are stored after the table. Every number is
+//! little-endian.
+
+use crate::core::{
+    io::{FileLoadError, ResourceIo},
+    parking_lot::Mutex,
+};
+use fxhash::FxHashMap;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+};
+
+const MAGIC: &[u8; 4] = b"FPAK";
+const VERSION: u32 = 1;
+
+// Converts a path to a form, that is used as a key in the archive.
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => (),
+            // Absolute paths and paths outside of the base directory can't be stored.
+            _ => return None,
+        }
+    }
+    Some(normalized)
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Archive writer collects files and writes them as a single archive. See module docs for more info.
+#[derive(Default)]
+pub struct ArchiveWriter {
+    entries: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl ArchiveWriter {
+    /// Creates a new empty archive writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file to the archive, previous contents of the file (if any) are replaced. Returns
+    /// `false` if the path cannot be stored in the archive (only relative paths inside of the working
+    /// directory are allowed).
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, data: Vec<u8>) -> bool {
+        match normalize_path(path.as_ref()) {
+            Some(path) => {
+                self.entries.insert(path, data);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the archive contains a file with the given path.
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        normalize_path(path.as_ref()).map_or(false, |p| self.entries.contains_key(&p))
+    }
+
+    /// Returns total amount of files in the archive.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no files in the archive.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the archive using the given writer.
+    pub fn write<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let paths = self
+            .entries
+            .keys()
+            .map(|p| path_to_string(p))
+            .collect::<Vec<_>>();
+
+        let table_size = paths
+            .iter()
+            .map(|p| 4 + p.len() as u64 + 8 + 8)
+            .sum::<u64>();
+        let mut offset = MAGIC.len() as u64 + 4 + 4 + table_size;
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        for (path, data) in paths.iter().zip(self.entries.values()) {
+            writer.write_all(&(path.len() as u32).to_le_bytes())?;
+            writer.write_all(path.as_bytes())?;
+            writer.write_all(&offset.to_le_bytes())?;
+            writer.write_all(&(data.len() as u64).to_le_bytes())?;
+            offset += data.len() as u64;
+        }
+        for data in self.entries.values() {
+            writer.write_all(data)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the archive to the given file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Resource IO backend, that loads files from a packed archive. See module docs for more info.
+pub struct ArchiveResourceIo {
+    file: Mutex<File>,
+    entries: FxHashMap<PathBuf, (u64, u64)>,
+}
+
+impl ArchiveResourceIo {
+    /// Opens an archive at the given path and reads its table of entries.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, FileLoadError> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(FileLoadError::Custom("Not an archive!".to_owned()));
+        }
+
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(FileLoadError::Custom(format!(
+                "Unsupported archive version {}!",
+                version
+            )));
+        }
+
+        let count = read_u32(&mut reader)?;
+        let mut entries = FxHashMap::default();
+        for _ in 0..count {
+            let mut path = vec![0; read_u32(&mut reader)? as usize];
+            reader.read_exact(&mut path)?;
+            let path = String::from_utf8(path)
+                .map_err(|_| FileLoadError::Custom("Invalid path in archive!".to_owned()))?;
+            let offset = read_u64(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            entries.insert(path.split('/').collect::<PathBuf>(), (offset, size));
+        }
+
+        Ok(Self {
+            file: Mutex::new(reader.into_inner()),
+            entries,
+        })
+    }
+
+    /// Returns `true` if the archive contains a file with the given path.
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        normalize_path(path.as_ref()).map_or(false, |p| self.entries.contains_key(&p))
+    }
+
+    /// Returns an iterator over paths of every file in the archive.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(|p| p.as_path())
+    }
+}
+
+impl ResourceIo for ArchiveResourceIo {
+    fn load_file(&self, path: &Path) -> Result<Vec<u8>, FileLoadError> {
+        if let Some(&(offset, size)) = normalize_path(path).and_then(|p| self.entries.get(&p)) {
+            let mut file = self.file.lock();
+            file.seek(SeekFrom::Start(offset))?;
+            let mut buffer = vec![0; size as usize];
+            file.read_exact(&mut buffer)?;
+            return Ok(buffer);
+        }
+
+        let mut file = File::open(path)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.contains(path) || path.exists()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::io::ResourceIo,
+        engine::resource_manager::archive::{ArchiveResourceIo, ArchiveWriter},
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_archive() {
+        let mut writer = ArchiveWriter::new();
+        assert!(writer.add_file("data/a.bin", b"first".to_vec()));
+        assert!(writer.add_file("./data/b.bin", b"second".to_vec()));
+        assert!(writer.add_file("data/a.bin", b"replaced".to_vec()));
+        assert!(writer.add_file("empty.bin", Vec::new()));
+        assert!(!writer.add_file("../outside.bin", Vec::new()));
+        assert_eq!(writer.len(), 3);
+
+        std::fs::create_dir_all("test_output").unwrap();
+        let path = Path::new("test_output/test.pak");
+        writer.save(path).unwrap();
+
+        let archive = ArchiveResourceIo::open(path).unwrap();
+        assert!(archive.contains("data/b.bin"));
+        assert!(archive.exists(Path::new("./data/a.bin")));
+        assert!(!archive.contains("data/c.bin"));
+        assert_eq!(
+            archive.load_file(Path::new("data/a.bin")).unwrap(),
+            b"replaced"
+        );
+        assert_eq!(
+            archive.load_file(Path::new("data/b.bin")).unwrap(),
+            b"second"
+        );
+        assert!(archive
+            .load_file(Path::new("empty.bin"))
+            .unwrap()
+            .is_empty());
+        assert!(archive.load_file(Path::new("data/c.bin")).is_err());
+    }
+}
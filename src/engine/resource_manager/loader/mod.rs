@@ -7,9 +7,12 @@ use std::{future::Future, pin::Pin};
 
 pub mod curve;
 pub mod model;
+pub mod script_source;
 pub mod shader;
 pub mod sound;
+pub mod sound_event;
 pub mod texture;
+pub mod visual_script;
 
 /// Future type for resource loading. See 'ResourceLoader'.
 #[cfg(target_arch = "wasm32")]
@@ -0,0 +1,45 @@
+//! Script source loader.
+
+use crate::{
+    engine::resource_manager::{
+        container::event::ResourceEventBroadcaster,
+        loader::{BoxedLoaderFuture, ResourceLoader},
+    },
+    resource::script_source::{ScriptSource, ScriptSourceImportOptions, ScriptSourceState},
+    utils::log::Log,
+};
+
+/// Default implementation for script source loading.
+pub struct ScriptSourceLoader;
+
+impl ResourceLoader<ScriptSource, ScriptSourceImportOptions> for ScriptSourceLoader {
+    fn load(
+        &self,
+        source: ScriptSource,
+        _default_import_options: ScriptSourceImportOptions,
+        event_broadcaster: ResourceEventBroadcaster<ScriptSource>,
+        reload: bool,
+    ) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let path = source.state().path().to_path_buf();
+
+            match ScriptSourceState::from_file(&path).await {
+                Ok(source_state) => {
+                    Log::info(format!("Script source {:?} is loaded!", path));
+
+                    source.state().commit_ok(source_state);
+
+                    event_broadcaster.broadcast_loaded_or_reloaded(source, reload);
+                }
+                Err(error) => {
+                    Log::err(format!(
+                        "Unable to load script source from {:?}! Reason {:?}",
+                        path, error
+                    ));
+
+                    source.state().commit_error(path, error);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,60 @@
+//! Sound event loader.
+
+use crate::{
+    engine::resource_manager::{
+        container::event::ResourceEventBroadcaster,
+        loader::{BoxedLoaderFuture, ResourceLoader},
+        ResourceManager,
+    },
+    resource::sound_event::{SoundEventImportOptions, SoundEventResource, SoundEventResourceState},
+    utils::log::Log,
+};
+
+/// Default implementation for sound event loading.
+pub struct SoundEventLoader {
+    /// Resource manager to restore sound buffers of loaded sound events.
+    pub resource_manager: ResourceManager,
+}
+
+impl ResourceLoader<SoundEventResource, SoundEventImportOptions> for SoundEventLoader {
+    fn load(
+        &self,
+        sound_event: SoundEventResource,
+        _default_import_options: SoundEventImportOptions,
+        event_broadcaster: ResourceEventBroadcaster<SoundEventResource>,
+        reload: bool,
+    ) -> BoxedLoaderFuture {
+        let resource_manager = self.resource_manager.clone();
+
+        Box::pin(async move {
+            let path = sound_event.state().path().to_path_buf();
+
+            match SoundEventResourceState::from_file(&path).await {
+                Ok(mut sound_event_state) => {
+                    // Sound event file stores only paths to sound buffers, restore real buffers.
+                    for buffer in sound_event_state.event.buffers.iter_mut() {
+                        resource_manager
+                            .state()
+                            .containers_mut()
+                            .sound_buffers
+                            .try_restore_optional_resource(buffer);
+                    }
+
+                    Log::info(format!("Sound event {:?} is loaded!", path));
+
+                    sound_event.state().commit_ok(sound_event_state);
+
+                    event_broadcaster.broadcast_loaded_or_reloaded(sound_event, reload);
+                }
+                Err(error) => {
+                    Log::err(format!(
+                        "Unable to load sound event from {:?}! Reason {:?}",
+                        path, error
+                    ));
+
+                    sound_event.state().commit_error(path, error);
+                }
+            }
+        })
+    }
+}
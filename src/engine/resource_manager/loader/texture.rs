@@ -29,11 +29,8 @@ impl ResourceLoader<Texture, TextureImportOptions> for TextureLoader {
                 .await
                 .unwrap_or(default_import_options);
 
-            let gen_mip_maps = import_options.minification_filter.is_using_mip_mapping();
-
             let time = instant::Instant::now();
-            match TextureData::load_from_file(&path, import_options.compression, gen_mip_maps).await
-            {
+            match TextureData::load_from_file(&path, &import_options).await {
                 Ok(mut raw_texture) => {
                     Log::info(format!(
                         "Texture {:?} is loaded in {:?}!",
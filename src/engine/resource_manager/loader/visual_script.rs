@@ -0,0 +1,47 @@
+//! Visual script loader.
+
+use crate::{
+    engine::resource_manager::{
+        container::event::ResourceEventBroadcaster,
+        loader::{BoxedLoaderFuture, ResourceLoader},
+    },
+    resource::visual_script::{
+        VisualScriptImportOptions, VisualScriptResource, VisualScriptResourceState,
+    },
+    utils::log::Log,
+};
+
+/// Default implementation for visual script loading.
+pub struct VisualScriptLoader;
+
+impl ResourceLoader<VisualScriptResource, VisualScriptImportOptions> for VisualScriptLoader {
+    fn load(
+        &self,
+        visual_script: VisualScriptResource,
+        _default_import_options: VisualScriptImportOptions,
+        event_broadcaster: ResourceEventBroadcaster<VisualScriptResource>,
+        reload: bool,
+    ) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let path = visual_script.state().path().to_path_buf();
+
+            match VisualScriptResourceState::from_file(&path).await {
+                Ok(visual_script_state) => {
+                    Log::info(format!("Visual script {:?} is loaded!", path));
+
+                    visual_script.state().commit_ok(visual_script_state);
+
+                    event_broadcaster.broadcast_loaded_or_reloaded(visual_script, reload);
+                }
+                Err(error) => {
+                    Log::err(format!(
+                        "Unable to load visual script from {:?}! Reason {:?}",
+                        path, error
+                    ));
+
+                    visual_script.state().commit_error(path, error);
+                }
+            }
+        })
+    }
+}
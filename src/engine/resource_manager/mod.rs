@@ -1,7 +1,7 @@
 //! Resource manager controls loading and lifetime of resource in the engine.
 
 use crate::{
-    asset::{Resource, ResourceData, ResourceLoadError, ResourceState},
+    asset::{registry, Resource, ResourceData, ResourceLoadError, ResourceState},
     core::{
         futures::future::join_all,
         make_relative_path,
@@ -13,9 +13,12 @@ use crate::{
             loader::{
                 curve::CurveLoader,
                 model::ModelLoader,
+                script_source::ScriptSourceLoader,
                 shader::ShaderLoader,
                 sound::{SoundBufferImportOptions, SoundBufferLoader},
+                sound_event::SoundEventLoader,
                 texture::TextureLoader,
+                visual_script::VisualScriptLoader,
                 ResourceLoader,
             },
             task::TaskPool,
@@ -26,7 +29,10 @@ use crate::{
     resource::{
         curve::{CurveImportOptions, CurveResource},
         model::{Model, ModelImportOptions},
+        script_source::{ScriptSource, ScriptSourceImportOptions},
+        sound_event::{SoundEventImportOptions, SoundEventResource},
         texture::{Texture, TextureError, TextureImportOptions, TextureState},
+        visual_script::{VisualScriptImportOptions, VisualScriptResource},
     },
     utils::{log::Log, watcher::FileSystemWatcher},
 };
@@ -39,9 +45,13 @@ use std::{
     sync::Arc,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod archive;
 pub mod container;
 pub mod loader;
 pub mod options;
+#[cfg(all(feature = "remote-resources", not(target_arch = "wasm32")))]
+pub mod remote;
 mod task;
 
 /// Storage of resource containers.
@@ -60,6 +70,15 @@ pub struct ContainersStorage {
 
     /// Container for curve resources.
     pub curves: ResourceContainer<CurveResource, CurveImportOptions>,
+
+    /// Container for sound event resources.
+    pub sound_events: ResourceContainer<SoundEventResource, SoundEventImportOptions>,
+
+    /// Container for script source resources.
+    pub script_sources: ResourceContainer<ScriptSource, ScriptSourceImportOptions>,
+
+    /// Container for visual script resources.
+    pub visual_scripts: ResourceContainer<VisualScriptResource, VisualScriptImportOptions>,
 }
 
 impl ContainersStorage {
@@ -103,6 +122,30 @@ impl ContainersStorage {
         self.curves.set_loader(loader);
     }
 
+    /// Sets a custom sound event loader.
+    pub fn set_sound_event_loader<L>(&mut self, loader: L)
+    where
+        L: 'static + ResourceLoader<SoundEventResource, SoundEventImportOptions>,
+    {
+        self.sound_events.set_loader(loader);
+    }
+
+    /// Sets a custom script source loader.
+    pub fn set_script_source_loader<L>(&mut self, loader: L)
+    where
+        L: 'static + ResourceLoader<ScriptSource, ScriptSourceImportOptions>,
+    {
+        self.script_sources.set_loader(loader);
+    }
+
+    /// Sets a custom visual script loader.
+    pub fn set_visual_script_loader<L>(&mut self, loader: L)
+    where
+        L: 'static + ResourceLoader<VisualScriptResource, VisualScriptImportOptions>,
+    {
+        self.visual_scripts.set_loader(loader);
+    }
+
     /// Wait until all resources are loaded (or failed to load).
     pub fn get_wait_context(&self) -> ResourceWaitContext {
         ResourceWaitContext {
@@ -111,6 +154,9 @@ impl ContainersStorage {
             shaders: self.shaders.resources(),
             textures: self.textures.resources(),
             sound_buffers: self.sound_buffers.resources(),
+            sound_events: self.sound_events.resources(),
+            script_sources: self.script_sources.resources(),
+            visual_scripts: self.visual_scripts.resources(),
         }
     }
 }
@@ -123,6 +169,9 @@ pub struct ResourceWaitContext {
     shaders: Vec<Shader>,
     textures: Vec<Texture>,
     sound_buffers: Vec<SoundBufferResource>,
+    sound_events: Vec<SoundEventResource>,
+    script_sources: Vec<ScriptSource>,
+    visual_scripts: Vec<VisualScriptResource>,
 }
 
 impl ResourceWaitContext {
@@ -154,6 +203,9 @@ impl ResourceWaitContext {
             && check_container(&self.shaders)
             && check_container(&self.textures)
             && check_container(&self.sound_buffers)
+            && check_container(&self.sound_events)
+            && check_container(&self.script_sources)
+            && check_container(&self.visual_scripts)
     }
 }
 /// See module docs.
@@ -219,7 +271,15 @@ impl ResourceManager {
             ),
             sound_buffers: ResourceContainer::new(task_pool.clone(), Box::new(SoundBufferLoader)),
             shaders: ResourceContainer::new(task_pool.clone(), Box::new(ShaderLoader)),
-            curves: ResourceContainer::new(task_pool, Box::new(CurveLoader)),
+            curves: ResourceContainer::new(task_pool.clone(), Box::new(CurveLoader)),
+            sound_events: ResourceContainer::new(
+                task_pool.clone(),
+                Box::new(SoundEventLoader {
+                    resource_manager: resource_manager.clone(),
+                }),
+            ),
+            script_sources: ResourceContainer::new(task_pool.clone(), Box::new(ScriptSourceLoader)),
+            visual_scripts: ResourceContainer::new(task_pool, Box::new(VisualScriptLoader)),
         });
 
         resource_manager
@@ -349,6 +409,39 @@ impl ResourceManager {
         self.state().containers_mut().curves.request(path)
     }
 
+    /// Tries to load a new sound event resource from given path or get instance of existing, if any.
+    /// This method is asynchronous, it immediately returns a sound event which can be shared across
+    /// multiple places, the loading may fail, but it is internal state of the sound event resource.
+    ///
+    /// # Async/.await
+    ///
+    /// Each sound event implements Future trait and can be used in async contexts.
+    pub fn request_sound_event<P: AsRef<Path>>(&self, path: P) -> SoundEventResource {
+        self.state().containers_mut().sound_events.request(path)
+    }
+
+    /// Tries to load a new script source resource from given path or get instance of existing, if any.
+    /// This method is asynchronous, it immediately returns a script source which can be shared across
+    /// multiple places, the loading may fail, but it is internal state of the script source resource.
+    ///
+    /// # Async/.await
+    ///
+    /// Each script source implements Future trait and can be used in async contexts.
+    pub fn request_script_source<P: AsRef<Path>>(&self, path: P) -> ScriptSource {
+        self.state().containers_mut().script_sources.request(path)
+    }
+
+    /// Tries to load a new visual script resource from given path or get instance of existing, if any.
+    /// This method is asynchronous, it immediately returns a visual script which can be shared across
+    /// multiple places, the loading may fail, but it is internal state of the visual script resource.
+    ///
+    /// # Async/.await
+    ///
+    /// Each visual script implements Future trait and can be used in async contexts.
+    pub fn request_visual_script<P: AsRef<Path>>(&self, path: P) -> VisualScriptResource {
+        self.state().containers_mut().visual_scripts.request(path)
+    }
+
     /// Reloads every loaded texture. This method is asynchronous, internally it uses thread pool
     /// to run reload on separate thread per texture.
     pub async fn reload_textures(&self) {
@@ -388,6 +481,39 @@ impl ResourceManager {
         join_all(resources).await;
     }
 
+    /// Reloads every loaded sound event. This method is asynchronous, internally it uses thread pool
+    /// to run reload on separate thread per sound event.
+    pub async fn reload_sound_events(&self) {
+        let resources = self
+            .state()
+            .containers_mut()
+            .sound_events
+            .reload_resources();
+        join_all(resources).await;
+    }
+
+    /// Reloads every loaded script source. This method is asynchronous, internally it uses thread pool
+    /// to run reload on separate thread per script source.
+    pub async fn reload_script_sources(&self) {
+        let resources = self
+            .state()
+            .containers_mut()
+            .script_sources
+            .reload_resources();
+        join_all(resources).await;
+    }
+
+    /// Reloads every loaded visual script. This method is asynchronous, internally it uses thread pool
+    /// to run reload on separate thread per visual script.
+    pub async fn reload_visual_scripts(&self) {
+        let resources = self
+            .state()
+            .containers_mut()
+            .visual_scripts
+            .reload_resources();
+        join_all(resources).await;
+    }
+
     /// Reloads all loaded resources. Normally it should never be called, because it is **very** heavy
     /// method! This method is asynchronous, it uses all available CPU power to reload resources as
     /// fast as possible.
@@ -398,10 +524,45 @@ impl ResourceManager {
             self.reload_sound_buffers(),
             self.reload_shaders(),
             self.reload_curve_resources(),
+            self.reload_sound_events(),
+            self.reload_script_sources(),
+            self.reload_visual_scripts(),
         );
     }
 }
 
+// Keeps asset registry in sync with the file system, so GUIDs of moved (or renamed) assets are
+// preserved. Moves, that were missed (for example, made while the editor was closed), are detected
+// by contents of assets on next synchronization of the registry.
+fn move_registered_assets(old_path: &Path, new_path: &Path) {
+    // Old path does not exist anymore, so it cannot be canonicalized, but its parent can.
+    let old_path = match (old_path.parent(), old_path.file_name()) {
+        (Some(parent), Some(name)) => match make_relative_path(parent) {
+            Ok(parent) => parent.join(name),
+            Err(_) => return,
+        },
+        _ => return,
+    };
+    let new_path = match make_relative_path(new_path) {
+        Ok(new_path) => new_path,
+        Err(_) => return,
+    };
+
+    if let Some(asset_registry) = registry::asset_registry_mut().as_mut() {
+        if asset_registry.move_asset(&old_path, &new_path)
+            || asset_registry.move_directory(&old_path, &new_path) > 0
+        {
+            Log::info(format!(
+                "Asset registry: {} was moved to {}.",
+                old_path.display(),
+                new_path.display()
+            ));
+            #[cfg(not(target_arch = "wasm32"))]
+            Log::verify(asset_registry.save(registry::ASSET_REGISTRY_PATH));
+        }
+    }
+}
+
 impl ResourceManagerState {
     pub(in crate::engine) fn new() -> Self {
         Self {
@@ -440,6 +601,9 @@ impl ResourceManagerState {
             + containers.models.count_pending_resources()
             + containers.shaders.count_pending_resources()
             + containers.curves.count_pending_resources()
+            + containers.sound_events.count_pending_resources()
+            + containers.script_sources.count_pending_resources()
+            + containers.visual_scripts.count_pending_resources()
     }
 
     /// Returns total amount of loaded resources.
@@ -450,6 +614,9 @@ impl ResourceManagerState {
             + containers.models.count_loaded_resources()
             + containers.shaders.count_loaded_resources()
             + containers.curves.count_loaded_resources()
+            + containers.sound_events.count_loaded_resources()
+            + containers.script_sources.count_loaded_resources()
+            + containers.visual_scripts.count_loaded_resources()
     }
 
     /// Returns total amount of registered resources.
@@ -460,6 +627,9 @@ impl ResourceManagerState {
             + containers.models.len()
             + containers.shaders.len()
             + containers.curves.len()
+            + containers.sound_events.len()
+            + containers.script_sources.len()
+            + containers.visual_scripts.len()
     }
 
     /// Returns percentage of loading progress. This method is useful to show progress on
@@ -483,6 +653,9 @@ impl ResourceManagerState {
         containers.textures.destroy_unused();
         containers.shaders.destroy_unused();
         containers.curves.destroy_unused();
+        containers.sound_events.destroy_unused();
+        containers.script_sources.destroy_unused();
+        containers.visual_scripts.destroy_unused();
     }
 
     /// Update resource containers and do hot-reloading.
@@ -499,10 +672,21 @@ impl ResourceManagerState {
         containers.sound_buffers.update(dt);
         containers.shaders.update(dt);
         containers.curves.update(dt);
+        containers.sound_events.update(dt);
+        containers.script_sources.update(dt);
+        containers.visual_scripts.update(dt);
 
         if let Some(watcher) = self.watcher.as_ref() {
             if let Some(evt) = watcher.try_get_event() {
-                if let notify::EventKind::Modify(_) = evt.kind {
+                if let notify::EventKind::Modify(kind) = evt.kind {
+                    if let (
+                        notify::event::ModifyKind::Name(notify::event::RenameMode::Both),
+                        [old_path, new_path],
+                    ) = (kind, evt.paths.as_slice())
+                    {
+                        move_registered_assets(old_path, new_path);
+                    }
+
                     for path in evt.paths {
                         if let Ok(relative_path) = make_relative_path(path) {
                             let containers = self.containers_mut();
@@ -512,6 +696,9 @@ impl ResourceManagerState {
                                 &mut containers.sound_buffers as &mut dyn Container,
                                 &mut containers.shaders as &mut dyn Container,
                                 &mut containers.curves as &mut dyn Container,
+                                &mut containers.sound_events as &mut dyn Container,
+                                &mut containers.script_sources as &mut dyn Container,
+                                &mut containers.visual_scripts as &mut dyn Container,
                             ] {
                                 if container.try_reload_resource_from_path(&relative_path) {
                                     Log::info(format!(
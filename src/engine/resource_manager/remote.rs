@@ -0,0 +1,248 @@
+//! Remote resource IO allows you to fetch resources from an HTTP server (for example, a CDN) with
+//! local caching and checksum validation. It could be used to deliver content updates without
+//! shipping full patches of a game. Requires `remote-resources` feature.
+//!
+//! # Manifest
+//!
+//! The set of remote files is defined by a manifest, which is a text file in the format produced by
+//! `sha256sum` utility - each line contains a SHA-256 checksum of a file and its path relative to the
+//! base URL, separated by whitespace:
+//!
+//! ```text
+//! 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08  data/levels/level1.rgs
+//! 60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752  data/textures/grass.png
+//! ```
+//!
+//! Files listed in the manifest are loaded from the cache directory, if the cached file has the
+//! correct checksum, otherwise they're downloaded and cached. Every other file is loaded from the
+//! local file system.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox::{core::io, engine::resource_manager::remote::RemoteResourceIo};
+//! use std::sync::Arc;
+//!
+//! let manifest = std::fs::read_to_string("manifest.txt").unwrap();
+//! let remote = RemoteResourceIo::new("https://cdn.example.com/my_game", "cache")
+//!     .with_manifest(&manifest)
+//!     .unwrap();
+//! io::set_resource_io(Some(Arc::new(remote)));
+//! ```
+
+use crate::core::io::{FileLoadError, ResourceIo};
+use fxhash::FxHashMap;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+/// Resource IO backend, that fetches files from an HTTP server. See module docs for more info.
+pub struct RemoteResourceIo {
+    base_url: String,
+    cache_dir: PathBuf,
+    checksums: FxHashMap<PathBuf, String>,
+}
+
+fn checksum(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+// Converts a path to a form, that is used as a key in the manifest and as a part of the URL.
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => (),
+            // Absolute paths and paths outside of the base directory can't be remote.
+            _ => return None,
+        }
+    }
+    Some(normalized)
+}
+
+impl RemoteResourceIo {
+    /// Creates a new remote resource IO, that downloads files from the given base URL and stores them in
+    /// the given cache directory. The manifest is empty, so every file is loaded from the local file
+    /// system. Use [`Self::with_manifest`] to define the set of remote files.
+    pub fn new<S: AsRef<str>, P: AsRef<Path>>(base_url: S, cache_dir: P) -> Self {
+        Self {
+            base_url: base_url.as_ref().trim_end_matches('/').to_owned(),
+            cache_dir: cache_dir.as_ref().to_owned(),
+            checksums: Default::default(),
+        }
+    }
+
+    /// Adds every entry of the given manifest. See module docs for the format of the manifest.
+    pub fn with_manifest(mut self, manifest: &str) -> Result<Self, FileLoadError> {
+        for (line_number, line) in manifest.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_line = || {
+                FileLoadError::Custom(format!(
+                    "Invalid manifest entry at line {}: {}",
+                    line_number + 1,
+                    line
+                ))
+            };
+
+            let (checksum, path) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(invalid_line)?;
+            // `sha256sum` marks files read in binary mode with `*`.
+            let path = path.trim_start().trim_start_matches('*');
+            if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid_line());
+            }
+
+            self = self.with_checksum(path, checksum);
+        }
+        Ok(self)
+    }
+
+    /// Adds a single remote file with the given SHA-256 checksum (in hex form).
+    pub fn with_checksum<P: AsRef<Path>, S: AsRef<str>>(mut self, path: P, checksum: S) -> Self {
+        if let Some(path) = normalize_path(path.as_ref()) {
+            self.checksums
+                .insert(path, checksum.as_ref().to_ascii_lowercase());
+        }
+        self
+    }
+
+    /// Returns `true` if the given file is listed in the manifest.
+    pub fn is_remote<P: AsRef<Path>>(&self, path: P) -> bool {
+        normalize_path(path.as_ref()).map_or(false, |p| self.checksums.contains_key(&p))
+    }
+
+    /// Removes every cached file, that is listed in the manifest.
+    pub fn clear_cache(&self) {
+        for path in self.checksums.keys() {
+            let _ = std::fs::remove_file(self.cache_dir.join(path));
+        }
+    }
+
+    fn url_of(&self, path: &Path) -> String {
+        let mut url = self.base_url.clone();
+        for component in path.components() {
+            url.push('/');
+            url.push_str(&component.as_os_str().to_string_lossy());
+        }
+        url
+    }
+
+    fn load_cached(&self, path: &Path, expected_checksum: &str) -> Option<Vec<u8>> {
+        let data = std::fs::read(self.cache_dir.join(path)).ok()?;
+        if checksum(&data) == expected_checksum {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
+    fn download(&self, path: &Path, expected_checksum: &str) -> Result<Vec<u8>, FileLoadError> {
+        let url = self.url_of(path);
+
+        let response = ureq::get(&url)
+            .call()
+            .map_err(|e| FileLoadError::Custom(format!("Failed to download {}: {}", url, e)))?;
+
+        let mut data = Vec::new();
+        response.into_reader().read_to_end(&mut data)?;
+
+        let actual_checksum = checksum(&data);
+        if actual_checksum != expected_checksum {
+            return Err(FileLoadError::Custom(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                url, expected_checksum, actual_checksum
+            )));
+        }
+
+        let cache_path = self.cache_dir.join(path);
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(cache_path, &data)?;
+
+        Ok(data)
+    }
+}
+
+impl ResourceIo for RemoteResourceIo {
+    fn load_file(&self, path: &Path) -> Result<Vec<u8>, FileLoadError> {
+        if let Some(normalized) = normalize_path(path) {
+            if let Some(expected_checksum) = self.checksums.get(&normalized) {
+                return match self.load_cached(&normalized, expected_checksum) {
+                    Some(data) => Ok(data),
+                    None => self.download(&normalized, expected_checksum),
+                };
+            }
+        }
+
+        let mut file = File::open(path)?;
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.is_remote(path) || path.exists()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::io::ResourceIo,
+        engine::resource_manager::remote::{checksum, RemoteResourceIo},
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_manifest() {
+        let remote = RemoteResourceIo::new("http://localhost/", "cache")
+            .with_manifest(
+                "# Comment\n\
+                 9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08  data/a.png\n\
+                 60303ae22b998861bce3b28f33eec1be758a213c86c93c076dbe9f558c11c752 *./data/b.rgs\n",
+            )
+            .unwrap();
+
+        assert!(remote.is_remote("data/a.png"));
+        assert!(remote.is_remote("./data/b.rgs"));
+        assert!(!remote.is_remote("data/c.png"));
+        assert!(!remote.is_remote("/data/a.png"));
+        assert_eq!(
+            remote.url_of(Path::new("data/a.png")),
+            "http://localhost/data/a.png"
+        );
+
+        assert!(RemoteResourceIo::new("http://localhost", "cache")
+            .with_manifest("123 data/a.png")
+            .is_err());
+    }
+
+    #[test]
+    fn test_cached_file_validation() {
+        let cache_dir = Path::new("test_output/remote_cache");
+        std::fs::create_dir_all(cache_dir.join("data")).unwrap();
+        std::fs::write(cache_dir.join("data/file.bin"), b"content").unwrap();
+
+        // Nothing listens on the port, so every download fails.
+        let valid = RemoteResourceIo::new("http://127.0.0.1:1", cache_dir)
+            .with_checksum("data/file.bin", checksum(b"content"));
+        assert_eq!(
+            valid.load_file(Path::new("data/file.bin")).unwrap(),
+            b"content"
+        );
+
+        let corrupted = RemoteResourceIo::new("http://127.0.0.1:1", cache_dir)
+            .with_checksum("data/file.bin", checksum(b"other content"));
+        assert!(corrupted.load_file(Path::new("data/file.bin")).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Engine services are the parts of the engine, that are not tied to a scene and are used by the
+//! game logic directly - force feedback, achievements, telemetry, debug overlay, settings and Steam.
+//! See [`EngineServices`] docs for more info.
+
+use crate::{
+    achievements::Achievements, input::force_feedback::ForceFeedback, settings::Settings,
+    telemetry::Telemetry, utils::debug_ui::DebugUi,
+};
+
+/// A set of engine services. The engine owns a single instance (see
+/// [`crate::engine::Engine::services`]) and passes it to plugins and scripts by reference (see
+/// [`crate::plugin::PluginContext::services`] and [`crate::script::ScriptContext::services`]).
+/// Every service could be cloned, if it is needed outside of plugin or script methods.
+pub struct EngineServices {
+    /// Gamepad rumble and adaptive triggers. Effects are updated by the engine every frame. See
+    /// [`ForceFeedback`] docs for more info.
+    pub force_feedback: ForceFeedback,
+
+    /// Achievements and stats. When Steam integration is enabled and Steam is running, Steam is
+    /// used as a backend. See [`Achievements`] docs for more info.
+    pub achievements: Achievements,
+
+    /// Opt-in telemetry, use it to record gameplay events for playtests. See [`Telemetry`] docs
+    /// for more info.
+    pub telemetry: Telemetry,
+
+    /// Immediate-mode debug overlay, it is drawn on top of everything. See [`DebugUi`] docs for
+    /// more info.
+    pub debug_ui: DebugUi,
+
+    /// Persistent engine and user settings, use it to store game options. See [`Settings`] docs
+    /// for more info.
+    pub settings: Settings,
+
+    /// Steam service, it is `None` if Steam is not running. The engine runs Steam callbacks
+    /// automatically. See [`crate::steam`] module docs for more info.
+    #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+    pub steam: Option<crate::steam::Steam>,
+
+    #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+    steam_callbacks: Option<crate::steam::SteamCallbacks>,
+}
+
+impl Default for EngineServices {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EngineServices {
+    /// Creates a new set of services. Steam is initialized here, if Steam integration is enabled.
+    pub fn new() -> Self {
+        let achievements = Achievements::default();
+
+        #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+        let (steam, steam_callbacks) = match crate::steam::Steam::init() {
+            Ok((steam, callbacks)) => {
+                achievements.set_backend(Some(Box::new(
+                    crate::achievements::SteamAchievementsBackend::new(steam.clone()),
+                )));
+                (Some(steam), Some(callbacks))
+            }
+            Err(e) => {
+                crate::utils::log::Log::warn(format!(
+                    "Steam integration is disabled. Reason: {}",
+                    e
+                ));
+                (None, None)
+            }
+        };
+
+        Self {
+            force_feedback: Default::default(),
+            achievements,
+            telemetry: Default::default(),
+            debug_ui: Default::default(),
+            settings: Default::default(),
+            #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+            steam,
+            #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+            steam_callbacks,
+        }
+    }
+
+    pub(crate) fn update(&self, dt: f32) {
+        self.force_feedback.update(dt);
+        self.telemetry.update(dt);
+        #[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+        if let Some(callbacks) = self.steam_callbacks.as_ref() {
+            callbacks.run();
+        }
+    }
+}
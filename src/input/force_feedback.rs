@@ -0,0 +1,536 @@
+//! Force feedback (rumble and adaptive triggers) abstraction for gamepads.
+//!
+//! The engine does not talk to gamepads directly, instead it uses [`ForceFeedbackBackend`] that
+//! should be provided by a gamepad integration (for example, a wrapper over `gilrs` or platform API).
+//! [`ForceFeedback`] stores active rumble effects, evaluates their envelopes every frame and sends
+//! resulting motor speeds to the backend.
+//!
+//! ```rust
+//! use fyrox::input::force_feedback::{ForceFeedback, GamepadId, RumbleEnvelope};
+//!
+//! fn on_hit(force_feedback: &ForceFeedback, gamepad: GamepadId) {
+//!     if force_feedback.capabilities(gamepad).rumble {
+//!         force_feedback.rumble(
+//!             gamepad,
+//!             RumbleEnvelope::new(0.8, 0.4, 0.25)
+//!                 .with_attack(0.02)
+//!                 .with_release(0.1),
+//!         );
+//!     }
+//! }
+//! ```
+
+use crate::core::{
+    parking_lot::Mutex,
+    pool::{Handle, Pool},
+};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+/// Unique identifier of a gamepad, it is assigned by a backend.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct GamepadId(pub usize);
+
+/// A set of force feedback features supported by a gamepad.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct ForceFeedbackCapabilities {
+    /// Gamepad has strong (low-frequency) and weak (high-frequency) rumble motors.
+    pub rumble: bool,
+    /// Gamepad has rumble motors in triggers (so called "impulse triggers").
+    pub trigger_rumble: bool,
+    /// Gamepad has triggers with programmable resistance (so called "adaptive triggers").
+    pub adaptive_triggers: bool,
+}
+
+/// Speeds of every motor of a gamepad, every value is in `[0; 1]` range.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct MotorSpeeds {
+    /// Speed of strong (low-frequency) motor.
+    pub strong: f32,
+    /// Speed of weak (high-frequency) motor.
+    pub weak: f32,
+    /// Speed of left trigger motor. Ignored if the gamepad does not support trigger rumble.
+    pub left_trigger: f32,
+    /// Speed of right trigger motor. Ignored if the gamepad does not support trigger rumble.
+    pub right_trigger: f32,
+}
+
+impl MotorSpeeds {
+    fn scale(self, k: f32) -> Self {
+        Self {
+            strong: self.strong * k,
+            weak: self.weak * k,
+            left_trigger: self.left_trigger * k,
+            right_trigger: self.right_trigger * k,
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            strong: self.strong.max(other.strong),
+            weak: self.weak.max(other.weak),
+            left_trigger: self.left_trigger.max(other.left_trigger),
+            right_trigger: self.right_trigger.max(other.right_trigger),
+        }
+    }
+
+    fn clamped(self) -> Self {
+        Self {
+            strong: self.strong.clamp(0.0, 1.0),
+            weak: self.weak.clamp(0.0, 1.0),
+            left_trigger: self.left_trigger.clamp(0.0, 1.0),
+            right_trigger: self.right_trigger.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Rumble envelope defines how motor speeds change over time. Envelope consists of three stages:
+/// attack (speeds grow from zero to peak), sustain (speeds are at peak) and release (speeds fall from
+/// peak to zero).
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct RumbleEnvelope {
+    /// Peak speeds of the motors.
+    pub peak: MotorSpeeds,
+    /// Duration of attack stage in seconds.
+    pub attack: f32,
+    /// Duration of sustain stage in seconds.
+    pub sustain: f32,
+    /// Duration of release stage in seconds.
+    pub release: f32,
+}
+
+impl RumbleEnvelope {
+    /// Creates new envelope with the given peak speeds of strong and weak motors and sustain duration
+    /// (in seconds).
+    pub fn new(strong: f32, weak: f32, duration: f32) -> Self {
+        Self {
+            peak: MotorSpeeds {
+                strong,
+                weak,
+                ..Default::default()
+            },
+            attack: 0.0,
+            sustain: duration,
+            release: 0.0,
+        }
+    }
+
+    /// Sets peak speeds of trigger motors.
+    pub fn with_triggers(mut self, left: f32, right: f32) -> Self {
+        self.peak.left_trigger = left;
+        self.peak.right_trigger = right;
+        self
+    }
+
+    /// Sets duration of attack stage (in seconds).
+    pub fn with_attack(mut self, attack: f32) -> Self {
+        self.attack = attack.max(0.0);
+        self
+    }
+
+    /// Sets duration of release stage (in seconds).
+    pub fn with_release(mut self, release: f32) -> Self {
+        self.release = release.max(0.0);
+        self
+    }
+
+    /// Returns total duration of the envelope (in seconds).
+    pub fn duration(&self) -> f32 {
+        self.attack + self.sustain + self.release
+    }
+
+    /// Calculates motor speeds at the given time (in seconds) from the beginning of the envelope.
+    pub fn sample(&self, time: f32) -> MotorSpeeds {
+        let k = if time < 0.0 || time >= self.duration() {
+            0.0
+        } else if time < self.attack {
+            time / self.attack
+        } else if time < self.attack + self.sustain {
+            1.0
+        } else {
+            1.0 - (time - self.attack - self.sustain) / self.release
+        };
+        self.peak.scale(k).clamped()
+    }
+}
+
+/// A side of a trigger.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// Left trigger.
+    Left,
+    /// Right trigger.
+    Right,
+}
+
+/// An effect of an adaptive trigger. Every position is in `[0; 1]` range, where `0` is released trigger
+/// and `1` is fully pressed trigger.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TriggerEffect {
+    /// No effect, the trigger moves freely.
+    Off,
+    /// Constant resistance starting from the given position.
+    Resistance {
+        /// Position where the resistance begins.
+        start: f32,
+        /// Strength of the resistance in `[0; 1]` range.
+        strength: f32,
+    },
+    /// Resistance in a section of a trigger with a "snap" at the end, like a trigger of a gun.
+    Weapon {
+        /// Position where the resistance begins.
+        start: f32,
+        /// Position where the resistance ends.
+        end: f32,
+        /// Strength of the resistance in `[0; 1]` range.
+        strength: f32,
+    },
+    /// Vibration of a trigger starting from the given position.
+    Vibration {
+        /// Position where the vibration begins.
+        start: f32,
+        /// Amplitude of the vibration in `[0; 1]` range.
+        amplitude: f32,
+        /// Frequency of the vibration in Hz.
+        frequency: f32,
+    },
+}
+
+/// Force feedback backend is a bridge between the engine and a gamepad integration.
+pub trait ForceFeedbackBackend: Send {
+    /// Returns capabilities of the given gamepad. Disconnected gamepads must return default (empty)
+    /// capabilities.
+    fn capabilities(&self, gamepad: GamepadId) -> ForceFeedbackCapabilities;
+
+    /// Sets speeds of the motors of the given gamepad.
+    fn set_motor_speeds(&mut self, gamepad: GamepadId, speeds: MotorSpeeds);
+
+    /// Sets an effect of an adaptive trigger of the given gamepad.
+    fn set_trigger_effect(&mut self, gamepad: GamepadId, trigger: Trigger, effect: TriggerEffect);
+}
+
+/// An active rumble effect.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rumble {
+    gamepad: GamepadId,
+    envelope: RumbleEnvelope,
+    elapsed: f32,
+}
+
+impl Rumble {
+    /// Returns a gamepad that plays the effect.
+    pub fn gamepad(&self) -> GamepadId {
+        self.gamepad
+    }
+
+    /// Returns an envelope of the effect.
+    pub fn envelope(&self) -> &RumbleEnvelope {
+        &self.envelope
+    }
+
+    /// Returns amount of time (in seconds) that passed since the effect was started.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+}
+
+#[derive(Default)]
+struct State {
+    backend: Option<Box<dyn ForceFeedbackBackend>>,
+    rumbles: Pool<Rumble>,
+    active_gamepads: Vec<GamepadId>,
+    enabled: bool,
+}
+
+impl State {
+    fn update(&mut self, dt: f32) {
+        let mut speeds = Vec::<(GamepadId, MotorSpeeds)>::new();
+
+        for rumble in self.rumbles.iter_mut() {
+            rumble.elapsed += dt;
+            let sample = rumble.envelope.sample(rumble.elapsed);
+            if let Some((_, gamepad_speeds)) = speeds.iter_mut().find(|(g, _)| *g == rumble.gamepad)
+            {
+                *gamepad_speeds = gamepad_speeds.max(sample);
+            } else {
+                speeds.push((rumble.gamepad, sample));
+            }
+        }
+
+        self.rumbles
+            .retain(|rumble| rumble.elapsed < rumble.envelope.duration());
+
+        let backend = match self.backend.as_mut() {
+            Some(backend) => backend,
+            None => return,
+        };
+
+        // Gamepads without active effects must be stopped once.
+        for gamepad in self.active_gamepads.iter() {
+            if !speeds.iter().any(|(g, _)| g == gamepad) {
+                backend.set_motor_speeds(*gamepad, Default::default());
+            }
+        }
+
+        self.active_gamepads.clear();
+        for (gamepad, gamepad_speeds) in speeds {
+            let speeds = if self.enabled {
+                gamepad_speeds
+            } else {
+                Default::default()
+            };
+            backend.set_motor_speeds(gamepad, speeds);
+            self.active_gamepads.push(gamepad);
+        }
+    }
+}
+
+/// Force feedback service, see [module docs](self) for more info. Clones share the same set of
+/// playing effects.
+#[derive(Clone)]
+pub struct ForceFeedback {
+    state: Arc<Mutex<State>>,
+}
+
+impl Debug for ForceFeedback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("ForceFeedback")
+            .field("has_backend", &state.backend.is_some())
+            .field("rumbles", &state.rumbles.alive_count())
+            .field("enabled", &state.enabled)
+            .finish()
+    }
+}
+
+impl Default for ForceFeedback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ForceFeedback {
+    /// Creates new force feedback service without a backend.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                enabled: true,
+                ..Default::default()
+            })),
+        }
+    }
+
+    /// Sets new backend, returns previous one. Use `None` to remove current backend.
+    pub fn set_backend(
+        &self,
+        backend: Option<Box<dyn ForceFeedbackBackend>>,
+    ) -> Option<Box<dyn ForceFeedbackBackend>> {
+        let mut state = self.state.lock();
+        state.active_gamepads.clear();
+        std::mem::replace(&mut state.backend, backend)
+    }
+
+    /// Returns `true` if there is a backend.
+    pub fn has_backend(&self) -> bool {
+        self.state.lock().backend.is_some()
+    }
+
+    /// Enables or disables force feedback globally (for example, from game settings). Active effects
+    /// keep playing silently while force feedback is disabled.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.lock().enabled = enabled;
+    }
+
+    /// Returns `true` if force feedback is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().enabled
+    }
+
+    /// Returns capabilities of the given gamepad. Returns empty capabilities if there is no backend.
+    pub fn capabilities(&self, gamepad: GamepadId) -> ForceFeedbackCapabilities {
+        self.state
+            .lock()
+            .backend
+            .as_ref()
+            .map(|b| b.capabilities(gamepad))
+            .unwrap_or_default()
+    }
+
+    /// Starts new rumble effect on the given gamepad. Multiple effects on the same gamepad are mixed,
+    /// the strongest speed of each motor wins.
+    pub fn rumble(&self, gamepad: GamepadId, envelope: RumbleEnvelope) -> Handle<Rumble> {
+        self.state.lock().rumbles.spawn(Rumble {
+            gamepad,
+            envelope,
+            elapsed: 0.0,
+        })
+    }
+
+    /// Returns a copy of an active rumble effect, `None` if the effect has finished.
+    pub fn rumble_ref(&self, handle: Handle<Rumble>) -> Option<Rumble> {
+        self.state.lock().rumbles.try_borrow(handle).cloned()
+    }
+
+    /// Stops the given rumble effect.
+    pub fn stop(&self, handle: Handle<Rumble>) {
+        let mut state = self.state.lock();
+        if state.rumbles.is_valid_handle(handle) {
+            state.rumbles.free(handle);
+        }
+    }
+
+    /// Stops every rumble effect of the given gamepad.
+    pub fn stop_all(&self, gamepad: GamepadId) {
+        self.state
+            .lock()
+            .rumbles
+            .retain(|rumble| rumble.gamepad != gamepad);
+    }
+
+    /// Sets an effect of an adaptive trigger of the given gamepad. Returns `false` if the gamepad
+    /// does not support adaptive triggers.
+    pub fn set_trigger_effect(
+        &self,
+        gamepad: GamepadId,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> bool {
+        let mut state = self.state.lock();
+        let enabled = state.enabled;
+        if let Some(backend) = state.backend.as_mut() {
+            if backend.capabilities(gamepad).adaptive_triggers {
+                let effect = if enabled { effect } else { TriggerEffect::Off };
+                backend.set_trigger_effect(gamepad, trigger, effect);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Advances every active effect and sends motor speeds to the backend. It is called by the engine
+    /// automatically.
+    pub fn update(&self, dt: f32) {
+        self.state.lock().update(dt);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::input::force_feedback::{
+        ForceFeedback, ForceFeedbackBackend, ForceFeedbackCapabilities, GamepadId, MotorSpeeds,
+        RumbleEnvelope, Trigger, TriggerEffect,
+    };
+    use std::sync::mpsc::{self, Sender};
+
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Speeds(GamepadId, MotorSpeeds),
+        Trigger(GamepadId, Trigger, TriggerEffect),
+    }
+
+    struct MockBackend {
+        sender: Sender<Call>,
+    }
+
+    impl ForceFeedbackBackend for MockBackend {
+        fn capabilities(&self, gamepad: GamepadId) -> ForceFeedbackCapabilities {
+            ForceFeedbackCapabilities {
+                rumble: true,
+                trigger_rumble: false,
+                adaptive_triggers: gamepad == GamepadId(0),
+            }
+        }
+
+        fn set_motor_speeds(&mut self, gamepad: GamepadId, speeds: MotorSpeeds) {
+            self.sender.send(Call::Speeds(gamepad, speeds)).unwrap();
+        }
+
+        fn set_trigger_effect(
+            &mut self,
+            gamepad: GamepadId,
+            trigger: Trigger,
+            effect: TriggerEffect,
+        ) {
+            self.sender
+                .send(Call::Trigger(gamepad, trigger, effect))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_envelope() {
+        let envelope = RumbleEnvelope::new(1.0, 0.5, 1.0)
+            .with_attack(1.0)
+            .with_release(1.0);
+        assert_eq!(envelope.duration(), 3.0);
+        assert_eq!(envelope.sample(0.5).strong, 0.5);
+        assert_eq!(envelope.sample(0.5).weak, 0.25);
+        assert_eq!(envelope.sample(1.5).strong, 1.0);
+        assert_eq!(envelope.sample(2.5).strong, 0.5);
+        assert_eq!(envelope.sample(3.5), MotorSpeeds::default());
+    }
+
+    #[test]
+    fn test_rumble() {
+        let (sender, receiver) = mpsc::channel();
+        let force_feedback = ForceFeedback::new();
+        force_feedback.set_backend(Some(Box::new(MockBackend { sender })));
+
+        let gamepad = GamepadId(0);
+        let long = force_feedback.rumble(gamepad, RumbleEnvelope::new(0.5, 0.0, 1.0));
+        force_feedback.rumble(gamepad, RumbleEnvelope::new(1.0, 0.2, 0.5));
+
+        force_feedback.update(0.25);
+        assert_eq!(
+            receiver.try_recv(),
+            Ok(Call::Speeds(
+                gamepad,
+                MotorSpeeds {
+                    strong: 1.0,
+                    weak: 0.2,
+                    ..Default::default()
+                }
+            ))
+        );
+
+        // Short effect is finished, only long one remains.
+        force_feedback.update(0.5);
+        assert_eq!(
+            receiver.try_recv(),
+            Ok(Call::Speeds(
+                gamepad,
+                MotorSpeeds {
+                    strong: 0.5,
+                    ..Default::default()
+                }
+            ))
+        );
+
+        force_feedback.stop(long);
+        assert!(force_feedback.rumble_ref(long).is_none());
+        force_feedback.update(0.1);
+        assert_eq!(
+            receiver.try_recv(),
+            Ok(Call::Speeds(gamepad, Default::default()))
+        );
+        force_feedback.update(0.1);
+        assert!(receiver.try_recv().is_err());
+
+        assert!(force_feedback.set_trigger_effect(
+            gamepad,
+            Trigger::Right,
+            TriggerEffect::Resistance {
+                start: 0.2,
+                strength: 1.0
+            }
+        ));
+        assert!(matches!(receiver.try_recv(), Ok(Call::Trigger(..))));
+        assert!(!force_feedback.set_trigger_effect(
+            GamepadId(1),
+            Trigger::Right,
+            TriggerEffect::Off
+        ));
+    }
+}
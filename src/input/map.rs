@@ -0,0 +1,83 @@
+//! Input map allows you to bind keys to named actions, so they could be changed by players at runtime. See
+//! [`InputMap`] docs for more info.
+
+use crate::{
+    event::{ElementState, KeyboardInput},
+    gui::{key::KeyBinding, message::KeyCode},
+    utils::translate_key,
+};
+use serde::{Deserialize, Serialize};
+
+/// Input map is an ordered set of named actions with their key bindings. It decouples game logic from
+/// physical keys, so the keys could be rebound by players (for example using
+/// [`crate::utils::options::OptionsMenu`]) and saved along with other settings.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     gui::{key::KeyBinding, message::KeyCode},
+///     input::map::InputMap,
+/// };
+///
+/// let mut input_map = InputMap::default();
+/// input_map
+///     .bind("Jump", KeyBinding::Some(KeyCode::Space))
+///     .bind("Crouch", KeyBinding::Some(KeyCode::C));
+///
+/// assert_eq!(input_map.action(KeyCode::Space), Some("Jump"));
+/// assert_eq!(input_map.binding("Crouch"), Some(&KeyBinding::Some(KeyCode::C)));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputMap {
+    bindings: Vec<(String, KeyBinding)>,
+}
+
+impl InputMap {
+    /// Binds a key to an action with the given name. If there is an action with the same name, its binding
+    /// will be replaced, otherwise a new action will be added to the end of the map.
+    pub fn bind<S: AsRef<str>>(&mut self, action: S, binding: KeyBinding) -> &mut Self {
+        let action = action.as_ref();
+        match self.bindings.iter_mut().find(|(name, _)| name == action) {
+            Some((_, existing)) => *existing = binding,
+            None => self.bindings.push((action.to_owned(), binding)),
+        }
+        self
+    }
+
+    /// Removes an action with the given name and returns its binding.
+    pub fn unbind(&mut self, action: &str) -> Option<KeyBinding> {
+        let index = self.bindings.iter().position(|(name, _)| name == action)?;
+        Some(self.bindings.remove(index).1)
+    }
+
+    /// Returns a binding of an action with the given name.
+    pub fn binding(&self, action: &str) -> Option<&KeyBinding> {
+        self.bindings
+            .iter()
+            .find(|(name, _)| name == action)
+            .map(|(_, binding)| binding)
+    }
+
+    /// Returns an iterator over every action and its binding in the order of addition.
+    pub fn bindings(&self) -> impl Iterator<Item = (&str, &KeyBinding)> {
+        self.bindings
+            .iter()
+            .map(|(name, binding)| (name.as_str(), binding))
+    }
+
+    /// Returns a name of the first action that is bound to the given key.
+    pub fn action(&self, key: KeyCode) -> Option<&str> {
+        self.bindings
+            .iter()
+            .find(|(_, binding)| *binding == key)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Returns a name of an action that is bound to the key of the given keyboard input, along with the
+    /// state of the key. It could be used directly in `on_os_event` of a plugin.
+    pub fn action_from_input(&self, input: &KeyboardInput) -> Option<(&str, ElementState)> {
+        self.action(translate_key(input.virtual_keycode?))
+            .map(|action| (action, input.state))
+    }
+}
@@ -0,0 +1,8 @@
+//! Input subsystem. Currently it contains force feedback (rumble and adaptive triggers) abstraction
+//! for gamepads, see [`force_feedback`] module docs for more info, and input map (rebindable actions),
+//! see [`map`] module docs for more info.
+
+#![warn(missing_docs)]
+
+pub mod force_feedback;
+pub mod map;
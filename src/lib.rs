@@ -9,14 +9,20 @@
 #![allow(clippy::from_over_into)]
 #![allow(clippy::approx_constant)]
 
+pub mod achievements;
 pub mod animation;
 pub mod engine;
+pub mod input;
 pub mod material;
 pub mod plugin;
 pub mod renderer;
 pub mod resource;
 pub mod scene;
 pub mod script;
+pub mod settings;
+#[cfg(all(feature = "steam", not(target_arch = "wasm32")))]
+pub mod steam;
+pub mod telemetry;
 pub mod utils;
 
 pub use crate::core::rand;
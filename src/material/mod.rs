@@ -15,7 +15,13 @@ use crate::{
     },
     engine::resource_manager::ResourceManager,
     material::shader::{PropertyKind, SamplerFallback, Shader},
-    renderer::framework::framebuffer::DrawParameters,
+    renderer::{
+        framework::{
+            framebuffer::{BlendParameters, CullFace, DrawParameters},
+            state::{BlendFactor, StencilFunc, StencilOp},
+        },
+        QualitySettings,
+    },
     resource::texture::Texture,
 };
 use fxhash::FxHashMap;
@@ -23,6 +29,7 @@ use fyrox_core::parking_lot::{Mutex, MutexGuard};
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 use std::sync::Arc;
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 pub mod shader;
 
@@ -346,10 +353,214 @@ impl Default for PropertyValue {
 #[derive(Default, Debug, Visit, Clone)]
 pub struct Material {
     shader: Shader,
-    draw_parameters: DrawParameters,
+    #[visit(optional)] // Backward compatibility.
+    render_state: MaterialRenderState,
     properties: FxHashMap<ImmutableString, PropertyValue>,
 }
 
+/// Defines whether a boolean pipeline state (such as depth test) should be taken from a shader
+/// or forcibly enabled or disabled.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum StateOverride {
+    /// A value defined in a render pass of the shader will be used.
+    FromShader,
+    /// The state will be enabled.
+    Enabled,
+    /// The state will be disabled.
+    Disabled,
+}
+
+impl Default for StateOverride {
+    fn default() -> Self {
+        Self::FromShader
+    }
+}
+
+impl StateOverride {
+    /// Applies the override to the given value.
+    pub fn apply(self, value: bool) -> bool {
+        match self {
+            StateOverride::FromShader => value,
+            StateOverride::Enabled => true,
+            StateOverride::Disabled => false,
+        }
+    }
+}
+
+/// Defines which faces of polygons should be culled.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum CullFaceOverride {
+    /// A value defined in a render pass of the shader will be used.
+    FromShader,
+    /// No faces will be culled, polygons will be visible from both sides.
+    Disabled,
+    /// Back faces will be culled.
+    Back,
+    /// Front faces will be culled.
+    Front,
+}
+
+impl Default for CullFaceOverride {
+    fn default() -> Self {
+        Self::FromShader
+    }
+}
+
+/// Defines blending of a material.
+#[derive(Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames)]
+pub enum BlendOverride {
+    /// Blending options defined in a render pass of the shader will be used.
+    FromShader,
+    /// Blending will be disabled.
+    Disabled,
+    /// Blending will be enabled with the given blending function and equation.
+    Enabled(BlendParameters),
+}
+
+impl Default for BlendOverride {
+    fn default() -> Self {
+        Self::FromShader
+    }
+}
+
+/// Defines stencil test and stencil operations of a material. Stencil buffer allows to implement
+/// portals, masking, outlines and similar effects.
+///
+/// # Limitations
+///
+/// Stencil buffer is used by the deferred lighting, so it is reset after `GBuffer` pass and the
+/// values written there are not available later. Every object drawn in `Forward` pass (as well as
+/// custom scene render passes) of a camera shares the same stencil buffer, which is initialized
+/// with zeros.
+#[derive(Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames)]
+pub enum StencilOverride {
+    /// Stencil options defined in a render pass of the shader will be used.
+    FromShader,
+    /// Stencil test will be disabled.
+    Disabled,
+    /// Stencil test will be enabled with the given parameters.
+    Enabled {
+        /// Comparison function, reference value and read mask of the stencil test.
+        test: StencilFunc,
+        /// Actions performed with stencil buffer values and write mask.
+        op: StencilOp,
+    },
+}
+
+impl Default for StencilOverride {
+    fn default() -> Self {
+        Self::FromShader
+    }
+}
+
+/// Defines how transparent surfaces of a material are blended with each other. See
+/// [`QualitySettings::use_order_independent_transparency`] for more info.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum TransparencyOverride {
+    /// Global setting ([`QualitySettings::use_order_independent_transparency`]) will be used. The
+    /// setting affects only alpha-blended surfaces (`SrcAlpha, OneMinusSrcAlpha`), surfaces with
+    /// other blending (additive particles, for example) or without blending are left as is.
+    FromSettings,
+    /// Surfaces will be sorted back-to-front and blended using blending options of the material.
+    Sorted,
+    /// Surfaces will be blended using order-independent transparency, blending options of the
+    /// material are ignored.
+    OrderIndependent,
+}
+
+impl Default for TransparencyOverride {
+    fn default() -> Self {
+        Self::FromSettings
+    }
+}
+
+impl TransparencyOverride {
+    /// Returns `true` if surfaces should be rendered using order-independent transparency.
+    /// `draw_parameters` are the parameters of the render pass with material overrides applied.
+    pub fn is_order_independent(
+        self,
+        settings: &QualitySettings,
+        draw_parameters: &DrawParameters,
+    ) -> bool {
+        match self {
+            TransparencyOverride::FromSettings => {
+                settings.use_order_independent_transparency
+                    && draw_parameters.blend.as_ref().map_or(false, |blend| {
+                        blend.func.sfactor == BlendFactor::SrcAlpha
+                            && blend.func.dfactor == BlendFactor::OneMinusSrcAlpha
+                    })
+            }
+            TransparencyOverride::Sorted => false,
+            TransparencyOverride::OrderIndependent => true,
+        }
+    }
+}
+
+/// Render state of a material allows to change draw order and to override pipeline state of a
+/// shader per material, without a need to create a separate shader. It is useful for special
+/// effects like force fields (which require specific blending and drawing after everything else)
+/// or x-ray effects (which require disabled depth test).
+///
+/// Overrides are applied to `GBuffer` and `Forward` render passes, shadow passes always use
+/// parameters defined in the shader.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Visit, Reflect)]
+pub struct MaterialRenderState {
+    /// Draw order priority. Objects with lower priority are drawn first. Default value is taken
+    /// from `render_priority` field of the shader definition.
+    pub priority: i32,
+    /// Depth test override.
+    pub depth_test: StateOverride,
+    /// Depth write override.
+    pub depth_write: StateOverride,
+    /// Face culling override.
+    pub cull_face: CullFaceOverride,
+    /// Blending override.
+    pub blend: BlendOverride,
+    /// Stencil override.
+    #[visit(optional)] // Backward compatibility.
+    pub stencil: StencilOverride,
+    /// Transparency override, it is used only in `Forward` render pass.
+    #[visit(optional)] // Backward compatibility.
+    pub transparency: TransparencyOverride,
+}
+
+impl MaterialRenderState {
+    /// Applies overrides to the draw parameters of a render pass.
+    pub fn apply(&self, draw_parameters: &DrawParameters) -> DrawParameters {
+        DrawParameters {
+            cull_face: match self.cull_face {
+                CullFaceOverride::FromShader => draw_parameters.cull_face,
+                CullFaceOverride::Disabled => None,
+                CullFaceOverride::Back => Some(CullFace::Back),
+                CullFaceOverride::Front => Some(CullFace::Front),
+            },
+            depth_write: self.depth_write.apply(draw_parameters.depth_write),
+            depth_test: self.depth_test.apply(draw_parameters.depth_test),
+            blend: match self.blend {
+                BlendOverride::FromShader => draw_parameters.blend.clone(),
+                BlendOverride::Disabled => None,
+                BlendOverride::Enabled(ref blend) => Some(blend.clone()),
+            },
+            stencil_test: match self.stencil {
+                StencilOverride::FromShader => draw_parameters.stencil_test,
+                StencilOverride::Disabled => None,
+                StencilOverride::Enabled { test, .. } => Some(test),
+            },
+            stencil_op: match self.stencil {
+                StencilOverride::Enabled { op, .. } => op,
+                _ => draw_parameters.stencil_op,
+            },
+            ..draw_parameters.clone()
+        }
+    }
+}
+
 /// A set of possible errors that can occur when working with materials.
 #[derive(Clone, Debug)]
 pub enum MaterialError {
@@ -502,11 +713,16 @@ impl Material {
             property_values.insert(ImmutableString::new(&property_definition.name), value);
         }
 
+        let render_state = MaterialRenderState {
+            priority: data.definition.render_priority,
+            ..Default::default()
+        };
+
         drop(data);
 
         Self {
             shader,
-            draw_parameters: Default::default(),
+            render_state,
             properties: property_values,
         }
     }
@@ -682,6 +898,21 @@ impl Material {
     pub fn properties(&self) -> &FxHashMap<ImmutableString, PropertyValue> {
         &self.properties
     }
+
+    /// Returns a reference to current render state. See [`MaterialRenderState`] docs for more info.
+    pub fn render_state(&self) -> &MaterialRenderState {
+        &self.render_state
+    }
+
+    /// Sets new render state and returns old one. See [`MaterialRenderState`] docs for more info.
+    pub fn set_render_state(&mut self, render_state: MaterialRenderState) -> MaterialRenderState {
+        std::mem::replace(&mut self.render_state, render_state)
+    }
+
+    /// Returns draw order priority of the material. See [`MaterialRenderState::priority`].
+    pub fn render_priority(&self) -> i32 {
+        self.render_state.priority
+    }
 }
 
 /// Shared material is a material instance that can be used across multiple objects. It is useful
@@ -739,3 +970,234 @@ impl SharedMaterial {
         Self::new(self.0.lock().clone())
     }
 }
+
+/// Material property block is a set of per-instance overrides of material properties. It allows you to
+/// change properties of a material (for example, emission color or a dissolve factor) for a single
+/// object, without making a unique copy of the material. Objects with property blocks still share the
+/// same material, so they can be batched together with other objects.
+///
+/// Unlike [`Material::set_property`], property block does not check types of properties, since it is not
+/// bound to a particular material. A property is silently ignored if the shader of a material does not
+/// have such property.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{color::Color, sstorage::ImmutableString},
+///     material::PropertyValue,
+///     scene::mesh::Mesh,
+/// };
+///
+/// fn highlight(mesh: &mut Mesh, amount: f32) {
+///     mesh.property_block_mut().set_property(
+///         &ImmutableString::new("emissionStrength"),
+///         PropertyValue::Vector3([amount; 3].into()),
+///     );
+/// }
+/// ```
+#[derive(Default, Debug, Visit, Clone)]
+pub struct MaterialPropertyBlock {
+    properties: FxHashMap<ImmutableString, PropertyValue>,
+}
+
+impl MaterialPropertyBlock {
+    /// Creates new empty property block.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a property override and returns self, useful for chaining.
+    pub fn with_property(mut self, name: &ImmutableString, value: PropertyValue) -> Self {
+        self.set_property(name, value);
+        self
+    }
+
+    /// Sets an override of the property with given name. Returns previous value of the override (if
+    /// any).
+    pub fn set_property(
+        &mut self,
+        name: &ImmutableString,
+        value: PropertyValue,
+    ) -> Option<PropertyValue> {
+        self.properties.insert(name.clone(), value)
+    }
+
+    /// Returns a reference to the override of the property with given name.
+    pub fn property_ref(&self, name: &ImmutableString) -> Option<&PropertyValue> {
+        self.properties.get(name)
+    }
+
+    /// Removes the override of the property with given name, so the value of the material will be used
+    /// instead.
+    pub fn remove_property(&mut self, name: &ImmutableString) -> Option<PropertyValue> {
+        self.properties.remove(name)
+    }
+
+    /// Removes every override.
+    pub fn clear(&mut self) {
+        self.properties.clear()
+    }
+
+    /// Returns `true` if the block has no overrides.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Returns immutable reference to internal property storage.
+    pub fn properties(&self) -> &FxHashMap<ImmutableString, PropertyValue> {
+        &self.properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            sstorage::ImmutableString,
+        },
+        material::{
+            BlendOverride, CullFaceOverride, Material, MaterialRenderState, PropertyValue,
+            SharedMaterial, StateOverride, StencilOverride, TransparencyOverride,
+        },
+        renderer::batch::BatchStorage,
+        renderer::framework::{
+            framebuffer::{BlendParameters, CullFace, DrawParameters},
+            state::{BlendFactor, BlendFunc, CompareFunc, StencilAction, StencilFunc, StencilOp},
+        },
+        renderer::QualitySettings,
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+        },
+    };
+
+    #[test]
+    fn test_material_render_state_overrides() {
+        let draw_parameters = DrawParameters::default();
+
+        assert_eq!(
+            MaterialRenderState::default().apply(&draw_parameters),
+            draw_parameters
+        );
+
+        let blend = BlendParameters {
+            func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+            equation: Default::default(),
+        };
+        let render_state = MaterialRenderState {
+            priority: 10,
+            depth_test: StateOverride::Disabled,
+            depth_write: StateOverride::FromShader,
+            cull_face: CullFaceOverride::Front,
+            blend: BlendOverride::Enabled(blend.clone()),
+            stencil: StencilOverride::Enabled {
+                test: StencilFunc {
+                    func: CompareFunc::Equal,
+                    ref_value: 1,
+                    mask: 0xFF,
+                },
+                op: StencilOp {
+                    zpass: StencilAction::Replace,
+                    ..Default::default()
+                },
+            },
+            transparency: TransparencyOverride::OrderIndependent,
+        };
+
+        let overridden = render_state.apply(&draw_parameters);
+        assert!(!overridden.depth_test);
+        assert_eq!(overridden.depth_write, draw_parameters.depth_write);
+        assert_eq!(overridden.cull_face, Some(CullFace::Front));
+        assert_eq!(overridden.blend, Some(blend));
+        assert_eq!(overridden.color_write, draw_parameters.color_write);
+        assert_eq!(overridden.stencil_test.unwrap().ref_value, 1);
+        assert_eq!(overridden.stencil_op.zpass, StencilAction::Replace);
+    }
+
+    #[test]
+    fn test_transparency_override() {
+        let settings = QualitySettings {
+            use_order_independent_transparency: true,
+            ..Default::default()
+        };
+        let with_blend = |sfactor, dfactor| DrawParameters {
+            blend: Some(BlendParameters {
+                func: BlendFunc::new(sfactor, dfactor),
+                equation: Default::default(),
+            }),
+            ..Default::default()
+        };
+        let alpha_blended = with_blend(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha);
+        let additive = with_blend(BlendFactor::SrcAlpha, BlendFactor::One);
+        let opaque = DrawParameters {
+            blend: None,
+            ..Default::default()
+        };
+
+        let from_settings = TransparencyOverride::FromSettings;
+        assert!(from_settings.is_order_independent(&settings, &alpha_blended));
+        assert!(!from_settings.is_order_independent(&settings, &additive));
+        assert!(!from_settings.is_order_independent(&settings, &opaque));
+        assert!(!from_settings.is_order_independent(&Default::default(), &alpha_blended));
+
+        assert!(TransparencyOverride::OrderIndependent.is_order_independent(&settings, &additive));
+        assert!(!TransparencyOverride::Sorted.is_order_independent(&settings, &alpha_blended));
+    }
+
+    #[test]
+    fn test_property_block_does_not_break_batching() {
+        let mut graph = Graph::new();
+        let material = SharedMaterial::new(Material::standard());
+        let data = SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity()));
+
+        let mut make_mesh = || {
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(data.clone())
+                    .with_material(material.clone())
+                    .build()])
+                .build(&mut graph)
+        };
+        let plain = make_mesh();
+        let highlighted = make_mesh();
+
+        let emission = ImmutableString::new("emissionStrength");
+        graph[highlighted]
+            .as_mesh_mut()
+            .property_block_mut()
+            .set_property(
+                &emission,
+                PropertyValue::Vector3(Vector3::new(1.0, 0.0, 0.0)),
+            );
+
+        let mut batch_storage = BatchStorage::default();
+        batch_storage.generate_batches(&graph, true);
+
+        assert_eq!(batch_storage.batches.len(), 1);
+        let instances = &batch_storage.batches[0].instances;
+        assert_eq!(instances.len(), 2);
+        for instance in instances {
+            let overridden = instance
+                .property_block
+                .as_ref()
+                .and_then(|block| block.property_ref(&emission))
+                .is_some();
+            assert_eq!(overridden, instance.owner == highlighted);
+            assert!(instance.owner == plain || instance.owner == highlighted);
+        }
+
+        // The material itself stays untouched.
+        assert_ne!(
+            material
+                .lock()
+                .property_ref(&emission)
+                .and_then(|v| v.as_vector3()),
+            Some(Vector3::new(1.0, 0.0, 0.0))
+        );
+    }
+}
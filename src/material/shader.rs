@@ -35,6 +35,9 @@ pub const STANDARD_TWOSIDES_SHADER_SRC: &str = include_str!("standard/standard-t
 /// A source code of the standard terrain shader.
 pub const STANDARD_TERRAIN_SHADER_SRC: &str = include_str!("standard/terrain.shader");
 
+/// A source code of the placeholder shader.
+pub const PLACEHOLDER_SHADER_SRC: &str = include_str!("standard/placeholder.shader");
+
 /// Internal state of the shader.
 ///
 /// # Notes
@@ -233,6 +236,10 @@ pub struct ShaderDefinition {
     pub passes: Vec<RenderPassDefinition>,
     /// A set of property definitions.
     pub properties: Vec<PropertyDefinition>,
+    /// Default draw order priority of materials that use the shader, objects with lower priority
+    /// are drawn first. See [`super::MaterialRenderState`] for more info.
+    #[serde(default)]
+    pub render_priority: i32,
 }
 
 impl ShaderDefinition {
@@ -330,6 +337,10 @@ define_new_resource!(
     ///         )
     ///     ],
     ///
+    ///     // Optional draw order priority, objects with lower priority are drawn first.
+    ///     // Materials could override it. Default value is 0.
+    ///     render_priority: 0,
+    ///
     ///     // A set of render passes (see a section `Render pass` for more info)
     ///     passes: [
     ///         (
@@ -469,6 +480,57 @@ define_new_resource!(
     /// | fyrox_cameraPosition       | `Vector3`       | Position of the camera.
     /// | fyrox_usePOM               | `bool`          | Whether to use parallax mapping or not.
     /// | fyrox_lightPosition        | `Vector3`       | Light position.
+    /// | fyrox_clusterLights        | `sampler2D`     | Parameters of clustered lights (forward pass only).
+    /// | fyrox_clusters             | `usampler2D`    | Light masks of froxels (forward pass only).
+    /// | fyrox_clusterParameters    | `Vector4`       | (zNear, zFar, lightCount, 0) of clustered lighting.
+    /// | fyrox_clusterViewProjection| `Matrix4`       | View-projection matrix used for clustering.
+    /// | fyrox_useBoneMatricesTexture | `bool`        | Whether bone matrices are stored in a texture or not.
+    /// | fyrox_boneMatricesTexture  | `sampler2D`     | Bone matrices of every skinned surface of a frame.
+    /// | fyrox_boneMatricesOffset   | `int`           | Index of the first bone matrix of a surface in the texture.
+    /// | fyrox_useInstancing        | `bool`          | Whether multiple instances are rendered in a single draw call.
+    ///
+    /// Bone matrices are stored in a texture when
+    /// [`crate::renderer::QualitySettings::use_bone_matrices_texture`] is on, this lifts the limit
+    /// of 60 bones per surface. Use `S_FetchMatrix` function to fetch a matrix of a bone:
+    ///
+    /// ```glsl
+    /// mat4 boneMatrix = fyrox_useBoneMatricesTexture
+    ///     ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + boneIndex)
+    ///     : fyrox_boneMatrices[boneIndex];
+    /// ```
+    ///
+    /// Shaders that use `fyrox_useInstancing` are able to render multiple instances of a surface in
+    /// a single draw call (see [`crate::renderer::QualitySettings::use_instancing`]). In this case
+    /// `fyrox_worldMatrix` is identity, `fyrox_worldViewProjection` contains view-projection matrix
+    /// and world matrix and color of each instance must be taken from vertex attributes:
+    ///
+    /// ```glsl
+    /// layout(location = 10) in mat4 fyrox_instanceWorldMatrix;
+    /// layout(location = 14) in vec4 fyrox_instanceColor;
+    ///
+    /// uniform bool fyrox_useInstancing;
+    ///
+    /// mat4 instanceMatrix = fyrox_useInstancing ? fyrox_instanceWorldMatrix : mat4(1.0);
+    /// gl_Position = fyrox_worldViewProjection * instanceMatrix * vec4(vertexPosition, 1.0);
+    /// ```
+    ///
+    /// Shaders that do not use `fyrox_useInstancing` are always rendered one instance at a time.
+    ///
+    /// Clustered variables allow forward pass shaders to be lit by the lights that were clustered
+    /// when [`crate::renderer::QualitySettings::use_clustered_lighting`] is on. Pass them to
+    /// `S_ClusteredLighting` function along with world space position of a fragment (forward pass of
+    /// the built-in standard shaders does exactly this):
+    ///
+    /// ```glsl
+    /// uniform sampler2D fyrox_clusterLights;
+    /// uniform highp usampler2D fyrox_clusters;
+    /// uniform vec4 fyrox_clusterParameters;
+    /// uniform mat4 fyrox_clusterViewProjection;
+    ///
+    /// // ... fill TPBRContext ctx ...
+    /// vec3 lighting = S_ClusteredLighting(ctx, worldPosition, fyrox_clusterViewProjection,
+    ///     fyrox_clusterParameters, fyrox_clusterLights, fyrox_clusters);
+    /// ```
     ///
     /// To use any of the variables, just define a uniform with appropriate name:
     ///
@@ -520,6 +582,11 @@ define_new_resource!(
     ///      - Stencil options.
     ///      - **Possible values:** [StencilOp](crate::renderer::framework::state::StencilOp)
     ///
+    /// Every scene render target has 8-bit stencil buffer. Keep in mind that the stencil buffer is
+    /// used by the deferred lighting, so values written in `GBuffer` pass are reset before `Forward`
+    /// pass. Stencil parameters could also be overridden per material, see
+    /// [`super::MaterialRenderState`].
+    ///
     /// # Standard shader
     ///
     /// By default Fyrox uses standard material for rendering, it covers 95% of uses cases and it is very
@@ -562,6 +629,13 @@ impl Shader {
         STANDARD_TWOSIDES.clone()
     }
 
+    /// Returns an instance of placeholder shader. The renderer uses it to draw objects, which shaders
+    /// are not compiled yet, see [`crate::renderer::Renderer::set_shader_compilation_budget`] for
+    /// more info.
+    pub fn placeholder() -> Self {
+        PLACEHOLDER.clone()
+    }
+
     /// Returns a list of standard shader.
     pub fn standard_shaders() -> Vec<Shader> {
         vec![
@@ -596,6 +670,12 @@ lazy_static! {
     )));
 }
 
+lazy_static! {
+    static ref PLACEHOLDER: Shader = Shader(Resource::new(ResourceState::Ok(
+        ShaderState::from_str(PLACEHOLDER_SHADER_SRC, "Placeholder").unwrap(),
+    )));
+}
+
 #[cfg(test)]
 mod test {
     use crate::material::shader::{
@@ -663,8 +743,26 @@ mod test {
                 vertex_shader: "<CODE>".to_string(),
                 fragment_shader: "<CODE>".to_string(),
             }],
+            render_priority: 0,
         };
 
         assert_eq!(data.definition, reference_definition);
     }
+
+    #[test]
+    fn test_placeholder_shader() {
+        let shader = Shader::placeholder();
+        let data = shader.data_ref();
+
+        assert!(data.definition.properties.is_empty());
+        for pass in [
+            "GBuffer",
+            "Forward",
+            "DirectionalShadow",
+            "SpotShadow",
+            "PointShadow",
+        ] {
+            assert!(data.definition.passes.iter().any(|p| p.name == pass));
+        }
+    }
 }
@@ -0,0 +1,480 @@
+(
+    // Neutral shader, that is used to render objects while their actual shaders are being compiled.
+    // It has no properties and renders everything with plain gray color.
+    name: "PlaceholderShader",
+
+    properties: [],
+
+    passes: [
+        (
+            name: "GBuffer",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useSkeletalAnimation;
+
+                out vec3 normal;
+                out vec3 worldPosition;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                void main()
+                {
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+                    if (fyrox_useSkeletalAnimation)
+                    {
+                        vec4 vertex = vec4(vertexPosition, 1.0);
+
+                        mat4 m0 = BoneMatrix(int(boneIndices.x));
+                        mat4 m1 = BoneMatrix(int(boneIndices.y));
+                        mat4 m2 = BoneMatrix(int(boneIndices.z));
+                        mat4 m3 = BoneMatrix(int(boneIndices.w));
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+
+                        localNormal += mat3(m0) * vertexNormal * boneWeights.x;
+                        localNormal += mat3(m1) * vertexNormal * boneWeights.y;
+                        localNormal += mat3(m2) * vertexNormal * boneWeights.z;
+                        localNormal += mat3(m3) * vertexNormal * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = vec4(vertexPosition, 1.0);
+                        localNormal = vertexNormal;
+                    }
+
+                    normal = normalize(mat3(fyrox_worldMatrix) * localNormal);
+                    worldPosition = (fyrox_worldMatrix * localPosition).xyz;
+                    gl_Position = fyrox_worldViewProjection * localPosition;
+                }
+                "#,
+            fragment_shader:
+                r#"
+                layout(location = 0) out vec4 outColor;
+                layout(location = 1) out vec4 outNormal;
+                layout(location = 2) out vec4 outAmbient;
+                layout(location = 3) out vec4 outMaterial;
+                layout(location = 4) out uint outDecalMask;
+
+                in vec3 normal;
+                in vec3 worldPosition;
+
+                void main()
+                {
+                    outColor = vec4(0.5, 0.5, 0.5, 1.0);
+                    outNormal = vec4(normalize(normal) * 0.5 + 0.5, 1.0);
+                    outAmbient = vec4(0.0, 0.0, 0.0, 1.0);
+                    // Metallic, roughness, ambient occlusion.
+                    outMaterial = vec4(0.0, 1.0, 1.0, 1.0);
+                    outDecalMask = 0u;
+                }
+                "#,
+        ),
+        (
+            name: "Forward",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useSkeletalAnimation;
+
+                out vec3 normal;
+                out vec3 worldPosition;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                void main()
+                {
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+                    if (fyrox_useSkeletalAnimation)
+                    {
+                        vec4 vertex = vec4(vertexPosition, 1.0);
+
+                        mat4 m0 = BoneMatrix(int(boneIndices.x));
+                        mat4 m1 = BoneMatrix(int(boneIndices.y));
+                        mat4 m2 = BoneMatrix(int(boneIndices.z));
+                        mat4 m3 = BoneMatrix(int(boneIndices.w));
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+
+                        localNormal += mat3(m0) * vertexNormal * boneWeights.x;
+                        localNormal += mat3(m1) * vertexNormal * boneWeights.y;
+                        localNormal += mat3(m2) * vertexNormal * boneWeights.z;
+                        localNormal += mat3(m3) * vertexNormal * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = vec4(vertexPosition, 1.0);
+                        localNormal = vertexNormal;
+                    }
+
+                    normal = normalize(mat3(fyrox_worldMatrix) * localNormal);
+                    worldPosition = (fyrox_worldMatrix * localPosition).xyz;
+                    gl_Position = fyrox_worldViewProjection * localPosition;
+                }
+                "#,
+            fragment_shader:
+                r#"
+                out vec4 FragColor;
+
+                in vec3 normal;
+                in vec3 worldPosition;
+
+                void main()
+                {
+                    FragColor = vec4(0.5, 0.5, 0.5, 1.0);
+                }
+                "#,
+        ),
+        (
+            name: "DirectionalShadow",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(
+                    red: false,
+                    green: false,
+                    blue: false,
+                    alpha: false,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useSkeletalAnimation;
+
+                out vec3 normal;
+                out vec3 worldPosition;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                void main()
+                {
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+                    if (fyrox_useSkeletalAnimation)
+                    {
+                        vec4 vertex = vec4(vertexPosition, 1.0);
+
+                        mat4 m0 = BoneMatrix(int(boneIndices.x));
+                        mat4 m1 = BoneMatrix(int(boneIndices.y));
+                        mat4 m2 = BoneMatrix(int(boneIndices.z));
+                        mat4 m3 = BoneMatrix(int(boneIndices.w));
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+
+                        localNormal += mat3(m0) * vertexNormal * boneWeights.x;
+                        localNormal += mat3(m1) * vertexNormal * boneWeights.y;
+                        localNormal += mat3(m2) * vertexNormal * boneWeights.z;
+                        localNormal += mat3(m3) * vertexNormal * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = vec4(vertexPosition, 1.0);
+                        localNormal = vertexNormal;
+                    }
+
+                    normal = normalize(mat3(fyrox_worldMatrix) * localNormal);
+                    worldPosition = (fyrox_worldMatrix * localPosition).xyz;
+                    gl_Position = fyrox_worldViewProjection * localPosition;
+                }
+                "#,
+            fragment_shader:
+                r#"
+                in vec3 normal;
+                in vec3 worldPosition;
+
+                void main()
+                {
+                }
+                "#,
+        ),
+        (
+            name: "SpotShadow",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(
+                    red: false,
+                    green: false,
+                    blue: false,
+                    alpha: false,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useSkeletalAnimation;
+
+                out vec3 normal;
+                out vec3 worldPosition;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                void main()
+                {
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+                    if (fyrox_useSkeletalAnimation)
+                    {
+                        vec4 vertex = vec4(vertexPosition, 1.0);
+
+                        mat4 m0 = BoneMatrix(int(boneIndices.x));
+                        mat4 m1 = BoneMatrix(int(boneIndices.y));
+                        mat4 m2 = BoneMatrix(int(boneIndices.z));
+                        mat4 m3 = BoneMatrix(int(boneIndices.w));
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+
+                        localNormal += mat3(m0) * vertexNormal * boneWeights.x;
+                        localNormal += mat3(m1) * vertexNormal * boneWeights.y;
+                        localNormal += mat3(m2) * vertexNormal * boneWeights.z;
+                        localNormal += mat3(m3) * vertexNormal * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = vec4(vertexPosition, 1.0);
+                        localNormal = vertexNormal;
+                    }
+
+                    normal = normalize(mat3(fyrox_worldMatrix) * localNormal);
+                    worldPosition = (fyrox_worldMatrix * localPosition).xyz;
+                    gl_Position = fyrox_worldViewProjection * localPosition;
+                }
+                "#,
+            fragment_shader:
+                r#"
+                in vec3 normal;
+                in vec3 worldPosition;
+
+                void main()
+                {
+                }
+                "#,
+        ),
+        (
+            name: "PointShadow",
+            draw_parameters: DrawParameters(
+                cull_face: Some(Back),
+                color_write: ColorMask(
+                    red: true,
+                    green: true,
+                    blue: true,
+                    alpha: true,
+                ),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: StencilOp(
+                    fail: Keep,
+                    zfail: Keep,
+                    zpass: Keep,
+                    write_mask: 0xFFFF_FFFF,
+                ),
+            ),
+            vertex_shader:
+                r#"
+                layout(location = 0) in vec3 vertexPosition;
+                layout(location = 2) in vec3 vertexNormal;
+                layout(location = 4) in vec4 boneWeights;
+                layout(location = 5) in vec4 boneIndices;
+
+                uniform mat4 fyrox_worldMatrix;
+                uniform mat4 fyrox_worldViewProjection;
+                uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useSkeletalAnimation;
+
+                out vec3 normal;
+                out vec3 worldPosition;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                void main()
+                {
+                    vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
+                    if (fyrox_useSkeletalAnimation)
+                    {
+                        vec4 vertex = vec4(vertexPosition, 1.0);
+
+                        mat4 m0 = BoneMatrix(int(boneIndices.x));
+                        mat4 m1 = BoneMatrix(int(boneIndices.y));
+                        mat4 m2 = BoneMatrix(int(boneIndices.z));
+                        mat4 m3 = BoneMatrix(int(boneIndices.w));
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+
+                        localNormal += mat3(m0) * vertexNormal * boneWeights.x;
+                        localNormal += mat3(m1) * vertexNormal * boneWeights.y;
+                        localNormal += mat3(m2) * vertexNormal * boneWeights.z;
+                        localNormal += mat3(m3) * vertexNormal * boneWeights.w;
+                    }
+                    else
+                    {
+                        localPosition = vec4(vertexPosition, 1.0);
+                        localNormal = vertexNormal;
+                    }
+
+                    normal = normalize(mat3(fyrox_worldMatrix) * localNormal);
+                    worldPosition = (fyrox_worldMatrix * localPosition).xyz;
+                    gl_Position = fyrox_worldViewProjection * localPosition;
+                }
+                "#,
+            fragment_shader:
+                r#"
+                uniform vec3 fyrox_lightPosition;
+
+                in vec3 normal;
+                in vec3 worldPosition;
+
+                layout(location = 0) out float depth;
+
+                void main()
+                {
+                    depth = length(fyrox_lightPosition - worldPosition);
+                }
+                "#,
+        ),
+    ],
+)
@@ -84,12 +84,18 @@
                 layout(location = 4) in vec4 boneWeights;
                 layout(location = 5) in vec4 boneIndices;
                 layout(location = 6) in vec2 vertexSecondTexCoord;
+                layout(location = 10) in mat4 fyrox_instanceWorldMatrix;
+                layout(location = 14) in vec4 fyrox_instanceColor;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
                 // required data to these uniforms.
                 uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
                 uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useInstancing;
                 uniform bool fyrox_useSkeletalAnimation;
 
                 out vec3 position;
@@ -98,6 +104,19 @@
                 out vec3 tangent;
                 out vec3 binormal;
                 out vec2 secondTexCoord;
+                out vec4 instanceColor;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                mat4 InstanceMatrix()
+                {
+                    return fyrox_useInstancing ? fyrox_instanceWorldMatrix : mat4(1.0);
+                }
 
                 void main()
                 {
@@ -114,10 +133,10 @@
                         int i2 = int(boneIndices.z);
                         int i3 = int(boneIndices.w);
 
-                        mat4 m0 = fyrox_boneMatrices[i0];
-                        mat4 m1 = fyrox_boneMatrices[i1];
-                        mat4 m2 = fyrox_boneMatrices[i2];
-                        mat4 m3 = fyrox_boneMatrices[i3];
+                        mat4 m0 = BoneMatrix(i0);
+                        mat4 m1 = BoneMatrix(i1);
+                        mat4 m2 = BoneMatrix(i2);
+                        mat4 m3 = BoneMatrix(i3);
 
                         localPosition += m0 * vertex * boneWeights.x;
                         localPosition += m1 * vertex * boneWeights.y;
@@ -141,15 +160,16 @@
                         localTangent = vertexTangent.xyz;
                     }
 
-                    mat3 nm = mat3(fyrox_worldMatrix);
+                    mat3 nm = mat3(fyrox_worldMatrix * InstanceMatrix());
                     normal = normalize(nm * localNormal);
                     tangent = normalize(nm * localTangent);
                     binormal = normalize(vertexTangent.w * cross(tangent, normal));
                     texCoord = vertexTexCoord;
-                    position = vec3(fyrox_worldMatrix * localPosition);
+                    position = vec3(fyrox_worldMatrix * InstanceMatrix() * localPosition);
                     secondTexCoord = vertexSecondTexCoord;
+                    instanceColor = fyrox_useInstancing ? fyrox_instanceColor : vec4(1.0);
 
-                    gl_Position = fyrox_worldViewProjection * localPosition;
+                    gl_Position = fyrox_worldViewProjection * InstanceMatrix() * localPosition;
                 }
                 "#,
             fragment_shader:
@@ -178,6 +198,7 @@
                 // required data to these uniforms.
                 uniform vec3 fyrox_cameraPosition;
                 uniform bool fyrox_usePOM;
+                uniform float fyrox_opacity;
 
                 in vec3 position;
                 in vec3 normal;
@@ -185,6 +206,7 @@
                 in vec3 tangent;
                 in vec3 binormal;
                 in vec2 secondTexCoord;
+                in vec4 instanceColor;
 
                 void main()
                 {
@@ -199,12 +221,17 @@
                         tc = texCoord * texCoordScale;
                     }
 
-                    outColor = diffuseColor * texture(diffuseTexture, tc);
+                    outColor = diffuseColor * instanceColor * texture(diffuseTexture, tc);
 
                     // Alpha test.
                     if (outColor.a < 0.5) {
                         discard;
                     }
+
+                    // Per-object opacity.
+                    if (S_DitherDiscard(gl_FragCoord.xy, fyrox_opacity)) {
+                        discard;
+                    }
                     outColor.a = 1.0;
 
                     vec4 n = normalize(texture(normalTexture, tc) * 2.0 - 1.0);
@@ -258,19 +285,42 @@
                r#"
                 layout(location = 0) in vec3 vertexPosition;
                 layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 2) in vec3 vertexNormal;
                 layout(location = 5) in vec4 boneWeights;
                 layout(location = 6) in vec4 boneIndices;
+                layout(location = 10) in mat4 fyrox_instanceWorldMatrix;
+                layout(location = 14) in vec4 fyrox_instanceColor;
 
+                uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
                 uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useInstancing;
 
                 out vec3 position;
+                out vec3 normal;
                 out vec2 texCoord;
+                out vec4 instanceColor;
+
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                mat4 InstanceMatrix()
+                {
+                    return fyrox_useInstancing ? fyrox_instanceWorldMatrix : mat4(1.0);
+                }
 
                 void main()
                 {
                     vec4 localPosition = vec4(0);
+                    vec3 localNormal = vec3(0);
                     if (fyrox_useSkeletalAnimation)
                     {
                         vec4 vertex = vec4(vertexPosition, 1.0);
@@ -280,32 +330,72 @@
                         int i2 = int(boneIndices.z);
                         int i3 = int(boneIndices.w);
 
-                        localPosition += fyrox_boneMatrices[i0] * vertex * boneWeights.x;
-                        localPosition += fyrox_boneMatrices[i1] * vertex * boneWeights.y;
-                        localPosition += fyrox_boneMatrices[i2] * vertex * boneWeights.z;
-                        localPosition += fyrox_boneMatrices[i3] * vertex * boneWeights.w;
+                        mat4 m0 = BoneMatrix(i0);
+                        mat4 m1 = BoneMatrix(i1);
+                        mat4 m2 = BoneMatrix(i2);
+                        mat4 m3 = BoneMatrix(i3);
+
+                        localPosition += m0 * vertex * boneWeights.x;
+                        localPosition += m1 * vertex * boneWeights.y;
+                        localPosition += m2 * vertex * boneWeights.z;
+                        localPosition += m3 * vertex * boneWeights.w;
+
+                        localNormal += mat3(m0) * vertexNormal * boneWeights.x;
+                        localNormal += mat3(m1) * vertexNormal * boneWeights.y;
+                        localNormal += mat3(m2) * vertexNormal * boneWeights.z;
+                        localNormal += mat3(m3) * vertexNormal * boneWeights.w;
                     }
                     else
                     {
                         localPosition = vec4(vertexPosition, 1.0);
+                        localNormal = vertexNormal;
                     }
-                    gl_Position = fyrox_worldViewProjection * localPosition;
+                    gl_Position = fyrox_worldViewProjection * InstanceMatrix() * localPosition;
+                    position = vec3(fyrox_worldMatrix * InstanceMatrix() * localPosition);
+                    normal = normalize(mat3(fyrox_worldMatrix * InstanceMatrix()) * localNormal);
                     texCoord = vertexTexCoord;
+                    instanceColor = fyrox_useInstancing ? fyrox_instanceColor : vec4(1.0);
                 }
                "#,
 
            fragment_shader:
                r#"
                 uniform sampler2D diffuseTexture;
+                uniform sampler2D metallicTexture;
+                uniform sampler2D roughnessTexture;
                 uniform vec4 diffuseColor;
 
+                uniform float fyrox_opacity;
+                uniform vec3 fyrox_cameraPosition;
+
+                // Point and spot lights, that were clustered for the current camera (see
+                // `QualitySettings::use_clustered_lighting`).
+                uniform sampler2D fyrox_clusterLights;
+                uniform highp usampler2D fyrox_clusters;
+                uniform vec4 fyrox_clusterParameters;
+                uniform mat4 fyrox_clusterViewProjection;
+
                 out vec4 FragColor;
 
+                in vec3 position;
+                in vec3 normal;
                 in vec2 texCoord;
+                in vec4 instanceColor;
 
                 void main()
                 {
-                    FragColor = diffuseColor * texture(diffuseTexture, texCoord);
+                    FragColor = diffuseColor * instanceColor * texture(diffuseTexture, texCoord);
+
+                    TPBRContext ctx;
+                    ctx.viewVector = normalize(fyrox_cameraPosition - position);
+                    ctx.fragmentNormal = normalize(gl_FrontFacing ? normal : -normal);
+                    ctx.metallic = texture(metallicTexture, texCoord).r;
+                    ctx.roughness = texture(roughnessTexture, texCoord).r;
+                    ctx.albedo = FragColor.rgb;
+                    FragColor.rgb += S_ClusteredLighting(ctx, position, fyrox_clusterViewProjection,
+                        fyrox_clusterParameters, fyrox_clusterLights, fyrox_clusters);
+
+                    FragColor.a *= fyrox_opacity;
                 }
                "#,
         ),
@@ -338,13 +428,30 @@
                 layout(location = 1) in vec2 vertexTexCoord;
                 layout(location = 4) in vec4 boneWeights;
                 layout(location = 5) in vec4 boneIndices;
+                layout(location = 10) in mat4 fyrox_instanceWorldMatrix;
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
                 uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useInstancing;
 
                 out vec2 texCoord;
 
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                mat4 InstanceMatrix()
+                {
+                    return fyrox_useInstancing ? fyrox_instanceWorldMatrix : mat4(1.0);
+                }
+
                 void main()
                 {
                     vec4 localPosition = vec4(0);
@@ -353,17 +460,17 @@
                     {
                         vec4 vertex = vec4(vertexPosition, 1.0);
 
-                        localPosition += fyrox_boneMatrices[int(boneIndices.x)] * vertex * boneWeights.x;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.y)] * vertex * boneWeights.y;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.z)] * vertex * boneWeights.z;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.w)] * vertex * boneWeights.w;
+                        localPosition += BoneMatrix(int(boneIndices.x)) * vertex * boneWeights.x;
+                        localPosition += BoneMatrix(int(boneIndices.y)) * vertex * boneWeights.y;
+                        localPosition += BoneMatrix(int(boneIndices.z)) * vertex * boneWeights.z;
+                        localPosition += BoneMatrix(int(boneIndices.w)) * vertex * boneWeights.w;
                     }
                     else
                     {
                         localPosition = vec4(vertexPosition, 1.0);
                     }
 
-                    gl_Position = fyrox_worldViewProjection * localPosition;
+                    gl_Position = fyrox_worldViewProjection * InstanceMatrix() * localPosition;
                     texCoord = vertexTexCoord;
                 }
                 "#,
@@ -372,11 +479,14 @@
                 r#"
                 uniform sampler2D diffuseTexture;
 
+                uniform float fyrox_opacity;
+
                 in vec2 texCoord;
 
                 void main()
                 {
                     if (texture(diffuseTexture, texCoord).a < 0.2) discard;
+                    if (S_DitherDiscard(gl_FragCoord.xy, fyrox_opacity)) discard;
                 }
                 "#,
         ),
@@ -409,13 +519,30 @@
                 layout(location = 1) in vec2 vertexTexCoord;
                 layout(location = 4) in vec4 boneWeights;
                 layout(location = 5) in vec4 boneIndices;
+                layout(location = 10) in mat4 fyrox_instanceWorldMatrix;
 
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
                 uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useInstancing;
 
                 out vec2 texCoord;
 
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                mat4 InstanceMatrix()
+                {
+                    return fyrox_useInstancing ? fyrox_instanceWorldMatrix : mat4(1.0);
+                }
+
                 void main()
                 {
                     vec4 localPosition = vec4(0);
@@ -424,17 +551,17 @@
                     {
                         vec4 vertex = vec4(vertexPosition, 1.0);
 
-                        localPosition += fyrox_boneMatrices[int(boneIndices.x)] * vertex * boneWeights.x;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.y)] * vertex * boneWeights.y;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.z)] * vertex * boneWeights.z;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.w)] * vertex * boneWeights.w;
+                        localPosition += BoneMatrix(int(boneIndices.x)) * vertex * boneWeights.x;
+                        localPosition += BoneMatrix(int(boneIndices.y)) * vertex * boneWeights.y;
+                        localPosition += BoneMatrix(int(boneIndices.z)) * vertex * boneWeights.z;
+                        localPosition += BoneMatrix(int(boneIndices.w)) * vertex * boneWeights.w;
                     }
                     else
                     {
                         localPosition = vec4(vertexPosition, 1.0);
                     }
 
-                    gl_Position = fyrox_worldViewProjection * localPosition;
+                    gl_Position = fyrox_worldViewProjection * InstanceMatrix() * localPosition;
                     texCoord = vertexTexCoord;
                 }
                 "#,
@@ -443,11 +570,14 @@
                 r#"
                 uniform sampler2D diffuseTexture;
 
+                uniform float fyrox_opacity;
+
                 in vec2 texCoord;
 
                 void main()
                 {
                     if (texture(diffuseTexture, texCoord).a < 0.2) discard;
+                    if (S_DitherDiscard(gl_FragCoord.xy, fyrox_opacity)) discard;
                 }
                 "#,
         ),
@@ -480,15 +610,32 @@
                 layout(location = 1) in vec2 vertexTexCoord;
                 layout(location = 4) in vec4 boneWeights;
                 layout(location = 5) in vec4 boneIndices;
+                layout(location = 10) in mat4 fyrox_instanceWorldMatrix;
 
                 uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
                 uniform bool fyrox_useSkeletalAnimation;
                 uniform mat4 fyrox_boneMatrices[60];
+                uniform bool fyrox_useBoneMatricesTexture;
+                uniform sampler2D fyrox_boneMatricesTexture;
+                uniform int fyrox_boneMatricesOffset;
+                uniform bool fyrox_useInstancing;
 
                 out vec2 texCoord;
                 out vec3 worldPosition;
 
+                mat4 BoneMatrix(int index)
+                {
+                    return fyrox_useBoneMatricesTexture
+                        ? S_FetchMatrix(fyrox_boneMatricesTexture, fyrox_boneMatricesOffset + index)
+                        : fyrox_boneMatrices[index];
+                }
+
+                mat4 InstanceMatrix()
+                {
+                    return fyrox_useInstancing ? fyrox_instanceWorldMatrix : mat4(1.0);
+                }
+
                 void main()
                 {
                     vec4 localPosition = vec4(0);
@@ -497,18 +644,18 @@
                     {
                         vec4 vertex = vec4(vertexPosition, 1.0);
 
-                        localPosition += fyrox_boneMatrices[int(boneIndices.x)] * vertex * boneWeights.x;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.y)] * vertex * boneWeights.y;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.z)] * vertex * boneWeights.z;
-                        localPosition += fyrox_boneMatrices[int(boneIndices.w)] * vertex * boneWeights.w;
+                        localPosition += BoneMatrix(int(boneIndices.x)) * vertex * boneWeights.x;
+                        localPosition += BoneMatrix(int(boneIndices.y)) * vertex * boneWeights.y;
+                        localPosition += BoneMatrix(int(boneIndices.z)) * vertex * boneWeights.z;
+                        localPosition += BoneMatrix(int(boneIndices.w)) * vertex * boneWeights.w;
                     }
                     else
                     {
                         localPosition = vec4(vertexPosition, 1.0);
                     }
 
-                    gl_Position = fyrox_worldViewProjection * localPosition;
-                    worldPosition = (fyrox_worldMatrix * localPosition).xyz;
+                    gl_Position = fyrox_worldViewProjection * InstanceMatrix() * localPosition;
+                    worldPosition = (fyrox_worldMatrix * InstanceMatrix() * localPosition).xyz;
                     texCoord = vertexTexCoord;
                 }
                 "#,
@@ -518,6 +665,7 @@
                 uniform sampler2D diffuseTexture;
 
                 uniform vec3 fyrox_lightPosition;
+                uniform float fyrox_opacity;
 
                 in vec2 texCoord;
                 in vec3 worldPosition;
@@ -527,6 +675,7 @@
                 void main()
                 {
                     if (texture(diffuseTexture, texCoord).a < 0.2) discard;
+                    if (S_DitherDiscard(gl_FragCoord.xy, fyrox_opacity)) discard;
                     depth = length(fyrox_lightPosition - worldPosition);
                 }
                 "#,
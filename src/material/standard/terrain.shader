@@ -232,15 +232,20 @@
                r#"
                 layout(location = 0) in vec3 vertexPosition;
                 layout(location = 1) in vec2 vertexTexCoord;
+                layout(location = 2) in vec3 vertexNormal;
 
+                uniform mat4 fyrox_worldMatrix;
                 uniform mat4 fyrox_worldViewProjection;
 
                 out vec3 position;
+                out vec3 normal;
                 out vec2 texCoord;
 
                 void main()
                 {
                     gl_Position = fyrox_worldViewProjection * vec4(vertexPosition, 1.0);
+                    position = vec3(fyrox_worldMatrix * vec4(vertexPosition, 1.0));
+                    normal = normalize(mat3(fyrox_worldMatrix) * vertexNormal);
                     texCoord = vertexTexCoord;
                 }
                "#,
@@ -248,15 +253,37 @@
            fragment_shader:
                r#"
                 uniform sampler2D diffuseTexture;
+                uniform sampler2D metallicTexture;
+                uniform sampler2D roughnessTexture;
                 uniform vec4 diffuseColor;
 
+                uniform vec3 fyrox_cameraPosition;
+
+                // Point and spot lights, that were clustered for the current camera (see
+                // `QualitySettings::use_clustered_lighting`).
+                uniform sampler2D fyrox_clusterLights;
+                uniform highp usampler2D fyrox_clusters;
+                uniform vec4 fyrox_clusterParameters;
+                uniform mat4 fyrox_clusterViewProjection;
+
                 out vec4 FragColor;
 
+                in vec3 position;
+                in vec3 normal;
                 in vec2 texCoord;
 
                 void main()
                 {
                     FragColor = diffuseColor * texture(diffuseTexture, texCoord);
+
+                    TPBRContext ctx;
+                    ctx.viewVector = normalize(fyrox_cameraPosition - position);
+                    ctx.fragmentNormal = normalize(normal);
+                    ctx.metallic = texture(metallicTexture, texCoord).r;
+                    ctx.roughness = texture(roughnessTexture, texCoord).r;
+                    ctx.albedo = FragColor.rgb;
+                    FragColor.rgb += S_ClusteredLighting(ctx, position, fyrox_clusterViewProjection,
+                        fyrox_clusterParameters, fyrox_clusterLights, fyrox_clusters);
                 }
                "#,
         ),
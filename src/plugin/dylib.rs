@@ -0,0 +1,252 @@
+//! Dynamic plugins - plugins that are loaded from shared libraries (`.dll`, `.so`, `.dylib`) and can be
+//! hot-reloaded while the game is running.
+//!
+//! # Development only
+//!
+//! Read the [`crate::plugin::Plugin`] docs first - Rust does not have stable ABI, the only stable part
+//! of the interface is a thin C entry point (see [`crate::define_dynamic_plugin`]), everything else
+//! (trait objects, engine structures) is passed "as is". This means that a shared library **must**
+//! be compiled with exactly the same compiler, the same engine version and the same set of features as
+//! the host executable, otherwise the behaviour is undefined. The engine checks the engine version only,
+//! everything else is on you. Use dynamic plugins only during development to speed up iterations, and
+//! link your plugins statically in release builds.
+//!
+//! # Hot reloading
+//!
+//! [`crate::engine::Engine::reload_dynamic_plugins`] checks whether a shared library was modified and
+//! reloads it. The state of every plugin instance is preserved: it is serialized using
+//! [`crate::plugin::Plugin::visit_state`], then a new instance is created using
+//! [`crate::plugin::PluginConstructor::default_boxed`] from the new library and the state is
+//! deserialized into it. If a plugin does not support `default_boxed`, it will be re-created from
+//! scratch using [`crate::plugin::PluginConstructor::create_instance`].
+//!
+//! An old instance is deinitialized using [`crate::plugin::Plugin::on_deinit`] before its state is
+//! transferred, and a new instance is initialized using [`crate::plugin::Plugin::on_init`] after that,
+//! so everything that is created in these methods (render passes, for example) is created by the code of
+//! the new library. Disabled plugins are not initialized until they're enabled back.
+//!
+//! Script and node constructors are re-registered on reload, so new script instances will use new
+//! code. Existing script instances keep using the code of the old library, that's why old libraries
+//! are never unloaded until the engine is destroyed.
+
+use crate::{core::uuid::Uuid, plugin::PluginConstructor};
+use libloading::Library;
+use std::{
+    ffi::{c_void, CStr},
+    fmt::{Display, Formatter},
+    os::raw::c_char,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Version of the engine, it is used to check compatibility of dynamic plugins.
+#[doc(hidden)]
+pub const ENGINE_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+#[doc(hidden)]
+pub const ENGINE_VERSION_SYMBOL: &str = "fyrox_plugin_engine_version";
+
+#[doc(hidden)]
+pub const CONSTRUCTOR_SYMBOL: &str = "fyrox_plugin_constructor";
+
+/// Defines C entry points of a dynamic plugin. The macro must be used once in a crate that is compiled
+/// as a shared library (`crate-type = ["cdylib"]`). The type must implement
+/// [`crate::plugin::PluginConstructor`] and [`Default`].
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     define_dynamic_plugin,
+///     plugin::{Plugin, PluginConstructor, PluginContext},
+///     scene::Scene,
+/// };
+///
+/// #[derive(Default)]
+/// struct MyPlugin;
+///
+/// impl Plugin for MyPlugin {}
+///
+/// #[derive(Default)]
+/// struct MyPluginConstructor;
+///
+/// impl PluginConstructor for MyPluginConstructor {
+///     fn create_instance(&self, _: Handle<Scene>, _: PluginContext) -> Box<dyn Plugin> {
+///         Box::new(MyPlugin)
+///     }
+/// }
+///
+/// define_dynamic_plugin!(MyPluginConstructor);
+/// ```
+#[macro_export]
+macro_rules! define_dynamic_plugin {
+    ($constructor:ty) => {
+        #[no_mangle]
+        pub extern "C" fn fyrox_plugin_engine_version() -> *const std::os::raw::c_char {
+            $crate::plugin::dylib::ENGINE_VERSION.as_ptr() as *const std::os::raw::c_char
+        }
+
+        #[no_mangle]
+        pub extern "C" fn fyrox_plugin_constructor() -> *mut std::ffi::c_void {
+            let constructor: Box<dyn $crate::plugin::PluginConstructor> =
+                Box::new(<$constructor as Default>::default());
+            Box::into_raw(Box::new(constructor)) as *mut std::ffi::c_void
+        }
+    };
+}
+
+/// An error that may occur during loading of a dynamic plugin.
+#[derive(Debug)]
+pub enum DynamicPluginError {
+    /// Unable to copy or read the library.
+    Io(std::io::Error),
+    /// Unable to load the library.
+    Library(String),
+    /// The library does not have a required entry point, most likely [`crate::define_dynamic_plugin`]
+    /// is not used.
+    MissingSymbol(String),
+    /// The library was compiled with other version of the engine.
+    VersionMismatch {
+        /// Version of the engine of the host executable.
+        engine: String,
+        /// Version of the engine the library was compiled with.
+        plugin: String,
+    },
+}
+
+impl Display for DynamicPluginError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DynamicPluginError::Io(e) => write!(f, "I/O error: {}", e),
+            DynamicPluginError::Library(v) => write!(f, "Unable to load library. Reason: {}", v),
+            DynamicPluginError::MissingSymbol(v) => {
+                write!(
+                    f,
+                    "Library does not have {} function. Use define_dynamic_plugin macro.",
+                    v
+                )
+            }
+            DynamicPluginError::VersionMismatch { engine, plugin } => {
+                write!(
+                    f,
+                    "Plugin was compiled with engine {}, but the engine is {}.",
+                    plugin, engine
+                )
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for DynamicPluginError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+fn modification_time(path: &Path) -> Result<SystemTime, DynamicPluginError> {
+    Ok(std::fs::metadata(path)?.modified()?)
+}
+
+/// A dynamic plugin loaded by the engine.
+pub(crate) struct DynamicPlugin {
+    pub path: PathBuf,
+    pub modified: SystemTime,
+    /// Index of the constructor of the plugin in the list of plugin constructors of the engine.
+    pub constructor_index: usize,
+    /// Script and node types registered by the library.
+    pub script_types: Vec<Uuid>,
+    pub node_types: Vec<Uuid>,
+    /// Every loaded version of the library, the last one is current. Libraries are kept alive,
+    /// because existing objects (script instances for example) may still use their code.
+    libraries: Vec<(Library, PathBuf)>,
+}
+
+impl DynamicPlugin {
+    /// Loads a library and creates the plugin constructor from it.
+    ///
+    /// # Safety
+    ///
+    /// The library must be compiled with the same compiler and the same engine as the host.
+    pub unsafe fn load(
+        path: &Path,
+    ) -> Result<(Self, Box<dyn PluginConstructor>), DynamicPluginError> {
+        let mut plugin = Self {
+            path: path.to_owned(),
+            modified: modification_time(path)?,
+            constructor_index: 0,
+            script_types: Default::default(),
+            node_types: Default::default(),
+            libraries: Default::default(),
+        };
+        let constructor = plugin.load_library()?;
+        Ok((plugin, constructor))
+    }
+
+    /// Returns `true` if the library was modified since last load.
+    pub fn is_modified(&self) -> bool {
+        modification_time(&self.path).map_or(false, |modified| modified != self.modified)
+    }
+
+    /// Loads a new version of the library and creates the plugin constructor from it.
+    ///
+    /// # Safety
+    ///
+    /// See [`Self::load`].
+    pub unsafe fn reload(&mut self) -> Result<Box<dyn PluginConstructor>, DynamicPluginError> {
+        let modified = modification_time(&self.path)?;
+        let constructor = self.load_library()?;
+        self.modified = modified;
+        Ok(constructor)
+    }
+
+    unsafe fn load_library(&mut self) -> Result<Box<dyn PluginConstructor>, DynamicPluginError> {
+        // Load a copy of the library, so the original file could be overwritten by the compiler while
+        // the library is loaded (it is locked on Windows).
+        let file_name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let copy_path = std::env::temp_dir().join(format!(
+            "fyrox-{}-{}-{}",
+            std::process::id(),
+            self.libraries.len(),
+            file_name
+        ));
+        std::fs::copy(&self.path, &copy_path)?;
+
+        let library =
+            Library::new(&copy_path).map_err(|e| DynamicPluginError::Library(e.to_string()))?;
+
+        let version = library
+            .get::<unsafe extern "C" fn() -> *const c_char>(ENGINE_VERSION_SYMBOL.as_bytes())
+            .map_err(|_| DynamicPluginError::MissingSymbol(ENGINE_VERSION_SYMBOL.to_owned()))?;
+        let plugin_version = CStr::from_ptr(version()).to_string_lossy().to_string();
+        let engine_version = ENGINE_VERSION.trim_end_matches('\0');
+        if plugin_version != engine_version {
+            return Err(DynamicPluginError::VersionMismatch {
+                engine: engine_version.to_owned(),
+                plugin: plugin_version,
+            });
+        }
+
+        let constructor = library
+            .get::<unsafe extern "C" fn() -> *mut c_void>(CONSTRUCTOR_SYMBOL.as_bytes())
+            .map_err(|_| DynamicPluginError::MissingSymbol(CONSTRUCTOR_SYMBOL.to_owned()))?;
+        let constructor = *Box::from_raw(constructor() as *mut Box<dyn PluginConstructor>);
+
+        self.libraries.push((library, copy_path));
+
+        Ok(constructor)
+    }
+}
+
+impl Drop for DynamicPlugin {
+    fn drop(&mut self) {
+        for (library, path) in self.libraries.drain(..) {
+            drop(library);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Plugin-to-plugin message bus. See [`PluginMessageDispatcher`] docs for more info.
+
+use crate::core::parking_lot::Mutex;
+use fxhash::FxHashMap;
+use std::{
+    any::{Any, TypeId},
+    collections::VecDeque,
+    fmt::{Debug, Formatter},
+    sync::{Arc, Weak},
+};
+
+/// A receiver of messages of a particular type. It is created by [`PluginMessageDispatcher::subscribe`]
+/// and it stays subscribed while it (or any of its clones) is alive.
+pub struct PluginMessageReceiver<T> {
+    queue: Arc<Mutex<VecDeque<T>>>,
+}
+
+impl<T> Clone for PluginMessageReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<T> Debug for PluginMessageReceiver<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PluginMessageReceiver({} messages)",
+            self.queue.lock().len()
+        )
+    }
+}
+
+impl<T> PluginMessageReceiver<T> {
+    /// Extracts the oldest delivered message.
+    pub fn pop(&self) -> Option<T> {
+        self.queue.lock().pop_front()
+    }
+
+    /// Extracts every delivered message in the order of publishing.
+    pub fn drain(&self) -> Vec<T> {
+        self.queue.lock().drain(..).collect()
+    }
+
+    /// Returns `true` if there are no delivered messages.
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().is_empty()
+    }
+}
+
+trait Subscriber: Send {
+    /// Delivers a message to the subscriber, returns `false` if the subscriber is dead and must be removed.
+    fn deliver(&self, message: &dyn Any) -> bool;
+}
+
+impl<T> Subscriber for Weak<Mutex<VecDeque<T>>>
+where
+    T: Clone + Send + 'static,
+{
+    fn deliver(&self, message: &dyn Any) -> bool {
+        match self.upgrade() {
+            Some(queue) => {
+                if let Some(message) = message.downcast_ref::<T>() {
+                    queue.lock().push_back(message.clone());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Message dispatcher allows plugins to communicate with each other using typed messages, without any need
+/// to know concrete types of other plugins (and thus to downcast them). It is available in every plugin
+/// method via [`super::PluginContext::message_dispatcher`].
+///
+/// # Delivery
+///
+/// Messages are queued on [`Self::publish`] and delivered to every subscriber of the message type at the
+/// beginning of the next update of plugins. It means that a message published during the update of a frame
+/// will be received on the next frame by every plugin, regardless of the order of plugins.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::plugin::message::{PluginMessageDispatcher, PluginMessageReceiver};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct LevelLoaded {
+///     name: String,
+/// }
+///
+/// let mut dispatcher = PluginMessageDispatcher::default();
+///
+/// // Usually a receiver is created on plugin creation and stored in the plugin.
+/// let receiver: PluginMessageReceiver<LevelLoaded> = dispatcher.subscribe();
+///
+/// dispatcher.publish(LevelLoaded {
+///     name: "Level1".to_string(),
+/// });
+///
+/// // The engine does this at the beginning of every update.
+/// dispatcher.dispatch();
+///
+/// assert_eq!(receiver.pop().unwrap().name, "Level1");
+/// ```
+#[derive(Default)]
+pub struct PluginMessageDispatcher {
+    queue: Vec<(TypeId, Box<dyn Any + Send>)>,
+    subscribers: FxHashMap<TypeId, Vec<Box<dyn Subscriber>>>,
+}
+
+impl Debug for PluginMessageDispatcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PluginMessageDispatcher({} queued messages)",
+            self.queue.len()
+        )
+    }
+}
+
+impl PluginMessageDispatcher {
+    /// Queues a message for delivery. Every subscriber of the message type will receive its own copy of
+    /// the message on the next update of plugins.
+    pub fn publish<T>(&mut self, message: T)
+    where
+        T: Clone + Send + 'static,
+    {
+        self.queue.push((TypeId::of::<T>(), Box::new(message)));
+    }
+
+    /// Creates a new receiver for messages of the given type. Only messages that will be published after
+    /// the subscription will be delivered to the receiver.
+    pub fn subscribe<T>(&mut self) -> PluginMessageReceiver<T>
+    where
+        T: Clone + Send + 'static,
+    {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        self.subscribers
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(Box::new(Arc::downgrade(&queue)));
+        PluginMessageReceiver { queue }
+    }
+
+    /// Returns amount of messages waiting for delivery.
+    pub fn queued_count(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Delivers every queued message to its subscribers. Messages without subscribers are discarded.
+    /// This method is called automatically by the engine at the beginning of every update of plugins.
+    pub fn dispatch(&mut self) {
+        for (type_id, message) in self.queue.drain(..) {
+            if let Some(subscribers) = self.subscribers.get_mut(&type_id) {
+                subscribers.retain(|subscriber| subscriber.deliver(message.as_ref()));
+            }
+        }
+        self.subscribers
+            .retain(|_, subscribers| !subscribers.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::plugin::message::PluginMessageDispatcher;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Foo(u32);
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Bar(&'static str);
+
+    #[test]
+    fn test_plugin_message_dispatcher() {
+        let mut dispatcher = PluginMessageDispatcher::default();
+
+        let foo_a = dispatcher.subscribe::<Foo>();
+        let foo_b = dispatcher.subscribe::<Foo>();
+        let bar = dispatcher.subscribe::<Bar>();
+
+        dispatcher.publish(Foo(1));
+        dispatcher.publish(Bar("bar"));
+        dispatcher.publish(Foo(2));
+
+        // Nothing is delivered until dispatch.
+        assert!(foo_a.is_empty());
+        assert_eq!(dispatcher.queued_count(), 3);
+
+        dispatcher.dispatch();
+
+        assert_eq!(foo_a.drain(), vec![Foo(1), Foo(2)]);
+        assert_eq!(foo_b.drain(), vec![Foo(1), Foo(2)]);
+        assert_eq!(bar.pop(), Some(Bar("bar")));
+        assert_eq!(bar.pop(), None);
+
+        // Dropped receivers are unsubscribed.
+        drop(foo_b);
+        dispatcher.publish(Foo(3));
+        dispatcher.dispatch();
+        assert_eq!(foo_a.pop(), Some(Foo(3)));
+        assert_eq!(
+            dispatcher.subscribers[&std::any::TypeId::of::<Foo>()].len(),
+            1
+        );
+    }
+}
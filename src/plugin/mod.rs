@@ -2,18 +2,36 @@
 
 #![warn(missing_docs)]
 
+#[cfg(all(feature = "dylib-plugins", not(target_arch = "wasm32")))]
+pub mod dylib;
+pub mod message;
+pub mod settings;
+pub mod statistics;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+
 use crate::{
-    core::pool::Handle,
-    engine::{resource_manager::ResourceManager, SerializationContext},
+    core::{
+        pool::Handle,
+        uuid::Uuid,
+        visitor::{VisitResult, Visitor},
+    },
+    engine::{
+        launch::LaunchOptions, resource_manager::ResourceManager, services::EngineServices,
+        SerializationContext,
+    },
     event::Event,
     event_loop::ControlFlow,
     gui::{message::UiMessage, UserInterface},
+    plugin::{message::PluginMessageDispatcher, settings::PluginSettings},
     renderer::Renderer,
     scene::{Scene, SceneContainer},
+    utils::log::Log,
     window::Window,
 };
+use fxhash::FxHashMap;
 use fyrox_sound::engine::SoundEngine;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 use std::{any::Any, sync::Arc};
 
 /// Plugin constructor is a first step of 2-stage plugin initialization. It is responsible for plugin script
@@ -41,6 +59,21 @@ pub trait PluginConstructor {
         #[allow(unused_variables)] override_scene: Handle<Scene>,
         context: PluginContext,
     ) -> Box<dyn Plugin>;
+
+    /// Creates default settings of the plugin along with the plugin id (it must match [`Plugin::id`]). The
+    /// editor uses this method to show and edit plugin settings of a scene without creating plugin
+    /// instances. See [`Plugin::settings`] for more info.
+    fn create_settings(&self) -> Option<(Uuid, Box<dyn PluginSettings>)> {
+        None
+    }
+
+    /// Creates plugin instance in its default state, without any initialization. It is used to hot-reload
+    /// dynamic plugins: the state of an old instance is serialized using [`Plugin::visit_state`] and then
+    /// deserialized into a new instance created by this method. Default implementation returns `None`,
+    /// which means that the plugin will be re-created from scratch using [`Self::create_instance`].
+    fn default_boxed(&self) -> Option<Box<dyn Plugin>> {
+        None
+    }
 }
 
 /// Contains plugin environment for the registration stage.
@@ -50,7 +83,25 @@ pub struct PluginRegistrationContext<'a> {
     pub serialization_context: &'a Arc<SerializationContext>,
 }
 
-/// A small wrapper that provides limited access to inner sound engine.
+/// A small wrapper that provides access to inner sound engine. Most of the common operations are available
+/// directly, full access to the sound engine (sound contexts of every scene, their listeners, effects, etc.)
+/// could be obtained using [`Self::lock`].
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::plugin::PluginContext;
+///
+/// fn pause_game(context: &mut PluginContext) {
+///     // Pause every sound context, for example when the game is paused.
+///     context.sound_engine.set_paused(true);
+///
+///     // Change listener parameters of every sound context.
+///     for sound_context in context.sound_engine.lock().contexts() {
+///         sound_context.state().listener_mut().set_position(Default::default());
+///     }
+/// }
+/// ```
 pub struct SoundEngineHelper<'a> {
     pub(crate) engine: &'a Arc<Mutex<SoundEngine>>,
 }
@@ -66,6 +117,56 @@ impl<'a> SoundEngineHelper<'a> {
     pub fn sound_gain(&self) -> f32 {
         self.engine.lock().unwrap().master_gain()
     }
+
+    /// Pauses or resumes every sound context of the sound engine.
+    pub fn set_paused(&mut self, paused: bool) {
+        for context in self.engine.lock().unwrap().contexts() {
+            context.state().pause(paused);
+        }
+    }
+
+    /// Locks the sound engine and returns a guard, that provides full access to the sound engine. Keep
+    /// in mind that the sound engine is used by the mixer thread, so the guard should be kept for as short
+    /// as possible.
+    pub fn lock(&mut self) -> MutexGuard<'_, SoundEngine> {
+        self.engine.lock().unwrap()
+    }
+
+    /// Returns a shared reference to the sound engine. It could be stored to access the sound engine
+    /// outside of plugin methods (for example, from a separate thread).
+    pub fn shared(&self) -> Arc<Mutex<SoundEngine>> {
+        self.engine.clone()
+    }
+}
+
+/// Initialization state of a plugin. See [`Plugin::poll_init`] docs for more info.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PluginInitState {
+    /// The plugin is still loading. Inner value is loading progress in `[0; 1]` range.
+    Loading(f32),
+    /// The plugin is fully initialized.
+    Ready,
+}
+
+impl Default for PluginInitState {
+    fn default() -> Self {
+        Self::Loading(0.0)
+    }
+}
+
+impl PluginInitState {
+    /// Returns `true` if the plugin is fully initialized.
+    pub fn is_ready(&self) -> bool {
+        matches!(self, PluginInitState::Ready)
+    }
+
+    /// Returns loading progress in `[0; 1]` range.
+    pub fn progress(&self) -> f32 {
+        match self {
+            PluginInitState::Loading(progress) => progress.clamp(0.0, 1.0),
+            PluginInitState::Ready => 1.0,
+        }
+    }
 }
 
 /// Contains plugin environment.
@@ -82,11 +183,14 @@ pub struct PluginContext<'a, 'b> {
     pub user_interface: &'a mut UserInterface,
 
     /// A reference to the renderer, it can be used to add custom render passes (for example to
-    /// render custom effects and so on).
-    pub renderer: &'a mut Renderer,
+    /// render custom effects and so on). Use [`Renderer::add_render_pass_for_scene`] to add a pass,
+    /// that will be removed automatically when the plugin is deinitialized. It is `None` if the engine
+    /// is headless (for example, when the game is launched as a dedicated server).
+    pub renderer: Option<&'a mut Renderer>,
 
     /// The time (in seconds) that passed since last call of a method in which the context was
-    /// passed. It has fixed value that is defined by a caller (in most cases it is `Executor`).
+    /// passed. It has fixed value that is defined by a caller (in most cases it is `Executor`), except
+    /// for [`Plugin::variable_update`] where it is the duration of the last frame.
     pub dt: f32,
 
     /// A reference to time accumulator, that holds remaining amount of time that should be used
@@ -103,8 +207,25 @@ pub struct PluginContext<'a, 'b> {
     /// A reference to the main application window.
     pub window: &'a Window,
 
-    /// Sound engine allows you to change global sound parameters, such as master gain, etc.
+    /// Sound engine allows you to change global sound parameters, such as master gain, and to access sound
+    /// contexts of every scene. See [`SoundEngineHelper`] docs for more info.
     pub sound_engine: SoundEngineHelper<'a>,
+
+    /// Message dispatcher allows you to publish typed messages to other plugins and to subscribe to
+    /// messages from them. See [`PluginMessageDispatcher`] docs for more info.
+    pub message_dispatcher: &'a mut PluginMessageDispatcher,
+
+    /// Engine services - force feedback, achievements, telemetry, debug overlay, settings and
+    /// Steam. See [`EngineServices`] docs for more info.
+    pub services: &'a EngineServices,
+
+    /// Options the game was launched with (a scene to load, server mode, custom options, etc.).
+    /// See [`LaunchOptions`] docs for more info.
+    pub launch_options: &'a LaunchOptions,
+
+    /// Overall initialization progress of every plugin in `[0; 1]` range. It could be used to show a
+    /// loading screen while plugins are initializing. See [`Plugin::poll_init`] for more info.
+    pub init_progress: f32,
 }
 
 /// Base plugin automatically implements type casting for plugins.
@@ -114,6 +235,9 @@ pub trait BasePlugin: Any + 'static {
 
     /// Returns a reference to Any trait. It is used for type casting.
     fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Returns type name of the plugin. It is used for profiling.
+    fn plugin_type_name(&self) -> &'static str;
 }
 
 impl<T> BasePlugin for T
@@ -127,6 +251,10 @@ where
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn plugin_type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
 }
 
 impl dyn Plugin {
@@ -157,12 +285,20 @@ impl dyn Plugin {
 /// `#[repr(C)]` attribute which is not always easy and even possible (because some structures could
 /// be re-exported from dependencies). These are the main reasons why the engine uses static plugins.
 ///
+/// However, hot reloading is very handy during development, so there is an opt-in `dylib-plugins`
+/// feature that allows you to load plugins from shared libraries, keeping in mind all the issues
+/// above. See `plugin::dylib` module docs for more info.
+///
+/// If you need to load plugins made by players (mods), there is an opt-in `wasm-plugins` feature that
+/// allows you to run plugins compiled to WebAssembly in a sandbox. See `plugin::wasm` module docs
+/// for more info.
+///
 /// # Example
 ///
 /// ```rust
 /// use fyrox::{
 ///     core::{pool::Handle},
-///     plugin::{Plugin, PluginContext, PluginRegistrationContext},
+///     plugin::{Plugin, PluginContext, PluginInitState, PluginRegistrationContext},
 ///     scene::Scene,
 ///     event::Event
 /// };
@@ -178,21 +314,93 @@ impl dyn Plugin {
 ///         // The implementation is optional.
 ///     }
 ///
+///     fn poll_init(&mut self, context: &mut PluginContext) -> PluginInitState {
+///         // The method is called on every frame until the plugin is ready, it could be used
+///         // to check loading progress of heavy resources.
+///         // The implementation is optional.
+///         PluginInitState::Ready
+///     }
+///
 ///     fn update(&mut self, context: &mut PluginContext, control_flow: &mut ControlFlow) {
 ///         // The method is called on every frame, it is guaranteed to have fixed update rate.
 ///         // The implementation is optional.
 ///     }
 ///
+///     fn variable_update(&mut self, context: &mut PluginContext, interpolation_alpha: f32) {
+///         // The method is called once per rendered frame, it could be used to interpolate
+///         // visual-only state between fixed updates.
+///         // The implementation is optional.
+///     }
+///
+///     fn post_update(&mut self, context: &mut PluginContext, control_flow: &mut ControlFlow) {
+///         // The method is called on every frame after scenes, scripts and plugins were updated,
+///         // it could be used for camera-follow logic.
+///         // The implementation is optional.
+///     }
+///
 ///     fn on_os_event(&mut self, event: &Event<()>, context: PluginContext, control_flow: &mut ControlFlow) {
 ///         // The method is called when the main window receives an event from the OS.
 ///     }
 /// }
 /// ```
 pub trait Plugin: BasePlugin {
+    /// Returns unique id of the plugin. It is used by other plugins to declare dependencies on this
+    /// plugin (see [`Plugin::dependencies`]). Default implementation returns nil id, which means that
+    /// no other plugin could depend on this plugin.
+    fn id(&self) -> Uuid {
+        Uuid::nil()
+    }
+
+    /// Returns ids of plugins that must be initialized and updated before this plugin. The engine sorts
+    /// plugins so every plugin is processed after its dependencies, plugins without dependencies between
+    /// each other are processed in registration order. Unknown dependencies are ignored, cyclic
+    /// dependencies are reported to the log and such plugins are processed in registration order.
+    fn dependencies(&self) -> Vec<Uuid> {
+        Default::default()
+    }
+
+    /// Returns settings of the plugin. Settings are saved in a scene by
+    /// [`crate::engine::Engine::save_plugin_settings`] and restored right before [`Plugin::on_init`]
+    /// when the plugin is created for an override scene (or manually by
+    /// [`crate::engine::Engine::load_plugin_settings`]). The plugin must have unique [`Plugin::id`],
+    /// otherwise its settings won't be persisted. See [`PluginSettings`] docs for more info.
+    fn settings(&self) -> Option<&dyn PluginSettings> {
+        None
+    }
+
+    /// Returns settings of the plugin. See [`Plugin::settings`] for more info.
+    fn settings_mut(&mut self) -> Option<&mut dyn PluginSettings> {
+        None
+    }
+
+    /// Serializes or deserializes the state of the plugin. It is used to preserve the state of dynamic
+    /// plugins on hot reloading, see [`PluginConstructor::default_boxed`] for more info.
+    fn visit_state(&mut self, #[allow(unused_variables)] visitor: &mut Visitor) -> VisitResult {
+        Ok(())
+    }
+
+    /// The method is called right after the plugin was created and when the plugin was enabled back after
+    /// it was disabled using [`crate::engine::Engine::set_plugin_enabled`]. It is paired with
+    /// [`Plugin::on_deinit`].
+    fn on_init(&mut self, #[allow(unused_variables)] context: PluginContext) {}
+
     /// The method is called before plugin will be disabled. It should be used for clean up, or some
     /// additional actions.
     fn on_deinit(&mut self, #[allow(unused_variables)] context: PluginContext) {}
 
+    /// The method is called on every frame until it returns [`PluginInitState::Ready`]. It should be used
+    /// to perform heavy initialization (for example, loading of large scenes) without blocking the main
+    /// loop: start loading in [`PluginConstructor::create_instance`] (for example, by requesting resources
+    /// from the resource manager) and check the progress here. [`Plugin::update`] and [`Plugin::post_update`]
+    /// won't be called until the plugin is ready, other methods (including [`Plugin::variable_update`] and
+    /// [`Plugin::before_rendering`]) are called as usual, so the plugin could show a loading screen.
+    fn poll_init(
+        &mut self,
+        #[allow(unused_variables)] context: &mut PluginContext,
+    ) -> PluginInitState {
+        PluginInitState::Ready
+    }
+
     /// Updates the plugin internals at fixed rate (see [`PluginContext::dt`] parameter for more
     /// info).
     fn update(
@@ -202,6 +410,35 @@ pub trait Plugin: BasePlugin {
     ) {
     }
 
+    /// The method is called once per rendered frame, right before rendering and after all fixed updates
+    /// of the frame (there could be zero or more of them). Unlike [`Plugin::update`], its update rate is
+    /// not fixed, [`PluginContext::dt`] is the amount of time that passed since the previous frame.
+    /// `interpolation_alpha` is a fraction of the fixed time step (in `[0; 1]` range), that has passed
+    /// since the last fixed update. It should be used to interpolate visual-only state (for example,
+    /// camera position) between fixed updates, while keeping the simulation deterministic.
+    fn variable_update(
+        &mut self,
+        #[allow(unused_variables)] context: &mut PluginContext,
+        #[allow(unused_variables)] interpolation_alpha: f32,
+    ) {
+    }
+
+    /// The method is called on every frame after [`Plugin::update`] of every plugin, when scenes (including
+    /// physics) and scripts were updated. It should be used for logic that depends on final state of the
+    /// frame, for example a camera that follows a physical body. The user interface is updated after this
+    /// method, so any changes of widgets will be shown on the same frame.
+    fn post_update(
+        &mut self,
+        #[allow(unused_variables)] context: &mut PluginContext,
+        #[allow(unused_variables)] control_flow: &mut ControlFlow,
+    ) {
+    }
+
+    /// The method is called right before rendering of a frame. It is the last chance to modify scenes and the
+    /// user interface before they'll be shown on screen. [`PluginContext::dt`] is zero here, because rendering
+    /// rate is not fixed.
+    fn before_rendering(&mut self, #[allow(unused_variables)] context: PluginContext) {}
+
     /// The method is called when the main window receives an event from the OS. The main use of
     /// the method is to respond to some external events, for example an event from keyboard or
     /// gamepad. See [`Event`] docs for more info.
@@ -223,3 +460,117 @@ pub trait Plugin: BasePlugin {
     ) {
     }
 }
+
+/// Returns indices of the given plugins in the order that satisfies their dependencies (see
+/// [`Plugin::dependencies`]). The sorting is stable, plugins without dependencies between each other
+/// keep their relative order.
+pub(crate) fn sort_by_dependencies(plugins: &[Box<dyn Plugin>]) -> Vec<usize> {
+    let mut ids = FxHashMap::default();
+    for (index, plugin) in plugins.iter().enumerate() {
+        let id = plugin.id();
+        if !id.is_nil() && ids.insert(id, index).is_some() {
+            Log::warn(format!("There are multiple plugins with {} id!", id));
+        }
+    }
+
+    let dependencies = plugins
+        .iter()
+        .map(|plugin| {
+            plugin
+                .dependencies()
+                .into_iter()
+                .filter_map(|id| {
+                    let index = ids.get(&id).cloned();
+                    if index.is_none() {
+                        Log::warn(format!(
+                            "Plugin dependency {} is not registered, it will be ignored.",
+                            id
+                        ));
+                    }
+                    index
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut order = Vec::with_capacity(plugins.len());
+    let mut sorted = vec![false; plugins.len()];
+    while order.len() < plugins.len() {
+        // Take the first plugin (in registration order) with every dependency processed already.
+        let next = (0..plugins.len()).find(|&index| {
+            !sorted[index]
+                && dependencies[index]
+                    .iter()
+                    .all(|&dep| sorted[dep] || dep == index)
+        });
+
+        match next {
+            Some(index) => {
+                sorted[index] = true;
+                order.push(index);
+            }
+            None => {
+                Log::err(
+                    "Plugins have cyclic dependencies! Remaining plugins will be processed \
+                    in registration order.",
+                );
+                order.extend((0..plugins.len()).filter(|&index| !sorted[index]));
+            }
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::uuid::{uuid, Uuid},
+        plugin::{sort_by_dependencies, Plugin},
+    };
+
+    struct TestPlugin {
+        id: Uuid,
+        dependencies: Vec<Uuid>,
+    }
+
+    impl Plugin for TestPlugin {
+        fn id(&self) -> Uuid {
+            self.id
+        }
+
+        fn dependencies(&self) -> Vec<Uuid> {
+            self.dependencies.clone()
+        }
+    }
+
+    fn plugin(id: Uuid, dependencies: &[Uuid]) -> Box<dyn Plugin> {
+        Box::new(TestPlugin {
+            id,
+            dependencies: dependencies.to_vec(),
+        })
+    }
+
+    #[test]
+    fn test_sort_by_dependencies() {
+        let input = uuid!("d5b7a2b2-3e1c-4b4f-9b6d-1f0c6a3e2f01");
+        let physics = uuid!("d5b7a2b2-3e1c-4b4f-9b6d-1f0c6a3e2f02");
+        let game = uuid!("d5b7a2b2-3e1c-4b4f-9b6d-1f0c6a3e2f03");
+        let unknown = uuid!("d5b7a2b2-3e1c-4b4f-9b6d-1f0c6a3e2f04");
+
+        let plugins = vec![
+            plugin(game, &[physics, input]),
+            plugin(Uuid::nil(), &[]),
+            plugin(physics, &[input, unknown]),
+            plugin(input, &[]),
+        ];
+        assert_eq!(sort_by_dependencies(&plugins), vec![1, 3, 2, 0]);
+
+        // Cycles do not lose plugins.
+        let plugins = vec![
+            plugin(input, &[physics]),
+            plugin(physics, &[input]),
+            plugin(game, &[]),
+        ];
+        assert_eq!(sort_by_dependencies(&plugins), vec![2, 0, 1]);
+    }
+}
@@ -0,0 +1,126 @@
+//! Plugin settings are persisted along with scenes. See [`PluginSettings`] docs for more info.
+
+use crate::core::{
+    reflect::Reflect,
+    uuid::Uuid,
+    visitor::{Visit, VisitError, VisitResult, Visitor},
+};
+use fxhash::FxHashMap;
+
+/// Plugin settings is an arbitrary object that could be serialized (via [`Visit`]) and edited in
+/// the editor (via [`Reflect`]). Plugin exposes its settings using [`crate::plugin::Plugin::settings`]
+/// and [`crate::plugin::Plugin::settings_mut`] methods, the engine saves them in a scene and restores
+/// them when the scene is loaded. The trait is implemented automatically for every type that
+/// implements both [`Visit`] and [`Reflect`].
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::core::{reflect::prelude::*, visitor::prelude::*};
+///
+/// #[derive(Default, Debug, Visit, Reflect)]
+/// struct MyPluginSettings {
+///     difficulty: u32,
+///     enemy_speed: f32,
+/// }
+/// ```
+pub trait PluginSettings: Visit + Reflect {}
+
+impl<T: Visit + Reflect> PluginSettings for T {}
+
+/// A container of serialized settings of plugins, every entry is identified by an id of a plugin
+/// (see [`crate::plugin::Plugin::id`]). Settings are stored in serialized form, so a scene could be
+/// loaded even if a plugin, that stored its settings in the scene, does not exist anymore.
+#[derive(Default, Clone, Debug, PartialEq, Visit)]
+pub struct PluginSettingsContainer {
+    data: FxHashMap<Uuid, Vec<u8>>,
+}
+
+impl PluginSettingsContainer {
+    const REGION_NAME: &'static str = "Settings";
+
+    /// Serializes the given settings and stores them in the container.
+    pub fn store(&mut self, id: Uuid, settings: &mut dyn PluginSettings) -> VisitResult {
+        let mut visitor = Visitor::new();
+        settings.visit(Self::REGION_NAME, &mut visitor)?;
+        self.data.insert(id, visitor.save_binary_to_vec()?);
+        Ok(())
+    }
+
+    /// Deserializes settings with the given id into the given settings instance. Returns `Ok(false)`
+    /// if there is no settings with the given id, the instance is left untouched in this case.
+    pub fn restore(&self, id: Uuid, settings: &mut dyn PluginSettings) -> Result<bool, VisitError> {
+        match self.data.get(&id) {
+            Some(data) => {
+                let mut visitor = Visitor::load_from_memory(data.clone())?;
+                settings.visit(Self::REGION_NAME, &mut visitor)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Returns `true` if there is settings with the given id.
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.data.contains_key(&id)
+    }
+
+    /// Removes settings with the given id.
+    pub fn remove(&mut self, id: Uuid) {
+        self.data.remove(&id);
+    }
+
+    /// Returns an iterator over ids of stored settings.
+    pub fn ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.data.keys()
+    }
+
+    /// Returns `true` if the container is empty.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
+        plugin::settings::PluginSettingsContainer,
+    };
+
+    #[derive(Default, Debug, PartialEq, Visit, Reflect)]
+    struct Settings {
+        difficulty: u32,
+        name: String,
+    }
+
+    #[test]
+    fn test_plugin_settings_container() {
+        let id = Uuid::new_v4();
+
+        let mut settings = Settings {
+            difficulty: 3,
+            name: "Hard".to_owned(),
+        };
+
+        let mut container = PluginSettingsContainer::default();
+        container.store(id, &mut settings).unwrap();
+        assert!(container.contains(id));
+
+        // Round trip through the visitor, like it happens when a scene is saved and loaded.
+        let mut visitor = Visitor::new();
+        container.visit("PluginSettings", &mut visitor).unwrap();
+        let mut visitor = Visitor::load_from_memory(visitor.save_binary_to_vec().unwrap()).unwrap();
+        let mut loaded = PluginSettingsContainer::default();
+        loaded.visit("PluginSettings", &mut visitor).unwrap();
+        assert_eq!(loaded, container);
+
+        let mut restored = Settings::default();
+        assert!(loaded.restore(id, &mut restored).unwrap());
+        assert_eq!(restored, settings);
+
+        let mut untouched = Settings::default();
+        assert!(!loaded.restore(Uuid::new_v4(), &mut untouched).unwrap());
+        assert_eq!(untouched, Settings::default());
+    }
+}
@@ -0,0 +1,164 @@
+//! Plugin statistics allows you to find out how much time every plugin takes per frame. See
+//! [`PluginStatistics`] docs for more info.
+
+use crate::core::{instant, uuid::Uuid};
+use fxhash::FxHashMap;
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+/// Amount of time that a plugin (and scripts registered by it) took on the last frame. Statistics
+/// of every plugin could be fetched using [`crate::engine::Engine::plugin_statistics`].
+///
+/// If the engine is compiled with `enable_profiler` feature, every plugin call is also shown as a
+/// separate scope (named after the plugin type) in the profiler output.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{engine::Engine, utils::log::Log};
+///
+/// fn print_slowest_plugin(engine: &Engine) {
+///     if let Some(statistics) = engine
+///         .plugin_statistics()
+///         .iter()
+///         .max_by_key(|statistics| statistics.total_time())
+///     {
+///         Log::info(statistics.to_string());
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PluginStatistics {
+    /// Type name of the plugin.
+    pub name: &'static str,
+    /// Id of the plugin (see [`crate::plugin::Plugin::id`]).
+    pub id: Uuid,
+    /// Time spent in [`crate::plugin::Plugin::poll_init`] and [`crate::plugin::Plugin::update`].
+    pub update_time: Duration,
+    /// Time spent in [`crate::plugin::Plugin::variable_update`]. It is added to the statistics of the
+    /// next fixed update.
+    pub variable_update_time: Duration,
+    /// Time spent in [`crate::plugin::Plugin::post_update`].
+    pub post_update_time: Duration,
+    /// Time spent in [`crate::plugin::Plugin::on_os_event`].
+    pub os_event_time: Duration,
+    /// Time spent in [`crate::plugin::Plugin::on_ui_message`].
+    pub ui_message_time: Duration,
+    /// Time spent in methods of scripts, that were registered by the plugin constructor.
+    pub script_time: Duration,
+}
+
+impl PluginStatistics {
+    pub(crate) fn new(name: &'static str, id: Uuid) -> Self {
+        Self {
+            name,
+            id,
+            ..Default::default()
+        }
+    }
+
+    /// Returns total amount of time the plugin took on the last frame.
+    pub fn total_time(&self) -> Duration {
+        self.update_time
+            + self.variable_update_time
+            + self.post_update_time
+            + self.os_event_time
+            + self.ui_message_time
+            + self.script_time
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new(self.name, self.id);
+    }
+
+    pub(crate) fn collect_script_time(
+        &mut self,
+        script_types: &[Uuid],
+        script_times: &FxHashMap<Uuid, Duration>,
+    ) {
+        self.script_time = script_types
+            .iter()
+            .filter_map(|uuid| script_times.get(uuid))
+            .sum();
+    }
+}
+
+impl Display for PluginStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {:.3} ms (update: {:.3} ms, variable update: {:.3} ms, post update: {:.3} ms, os events: {:.3} ms, \
+            ui messages: {:.3} ms, scripts: {:.3} ms)",
+            self.name,
+            self.total_time().as_secs_f64() * 1000.0,
+            self.update_time.as_secs_f64() * 1000.0,
+            self.variable_update_time.as_secs_f64() * 1000.0,
+            self.post_update_time.as_secs_f64() * 1000.0,
+            self.os_event_time.as_secs_f64() * 1000.0,
+            self.ui_message_time.as_secs_f64() * 1000.0,
+            self.script_time.as_secs_f64() * 1000.0,
+        )
+    }
+}
+
+/// Measures time of a single call and registers a profiler scope (if profiler is enabled).
+pub(crate) struct TimingScope {
+    start: instant::Instant,
+    #[cfg(feature = "enable_profiler")]
+    _profiler_scope: crate::core::profiler::ScopeDefinition,
+}
+
+impl TimingScope {
+    #[allow(unused_variables)]
+    pub(crate) fn new(name: &'static str, line: u32) -> Self {
+        Self {
+            #[cfg(feature = "enable_profiler")]
+            _profiler_scope: crate::core::profiler::ScopeDefinition::new(name, line),
+            start: instant::Instant::now(),
+        }
+    }
+
+    /// Adds elapsed time to the given accumulator and returns the elapsed time.
+    pub(crate) fn finish(self, accumulator: &mut Duration) -> Duration {
+        let elapsed = instant::Instant::now() - self.start;
+        *accumulator += elapsed;
+        elapsed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::uuid::Uuid,
+        plugin::statistics::{PluginStatistics, TimingScope},
+    };
+    use fxhash::FxHashMap;
+    use std::time::Duration;
+
+    #[test]
+    fn test_plugin_statistics() {
+        let script_a = Uuid::new_v4();
+        let script_b = Uuid::new_v4();
+        let foreign_script = Uuid::new_v4();
+
+        let mut script_times = FxHashMap::default();
+        script_times.insert(script_a, Duration::from_millis(2));
+        script_times.insert(script_b, Duration::from_millis(3));
+        script_times.insert(foreign_script, Duration::from_millis(100));
+
+        let id = Uuid::new_v4();
+        let mut statistics = PluginStatistics::new("Foo", id);
+        statistics.update_time = Duration::from_millis(1);
+        statistics.collect_script_time(&[script_a, script_b], &script_times);
+        assert_eq!(statistics.script_time, Duration::from_millis(5));
+        assert_eq!(statistics.total_time(), Duration::from_millis(6));
+
+        TimingScope::new("Foo", line!()).finish(&mut statistics.os_event_time);
+        assert!(statistics.total_time() >= Duration::from_millis(6));
+
+        statistics.reset();
+        assert_eq!(statistics, PluginStatistics::new("Foo", id));
+    }
+}
@@ -0,0 +1,541 @@
+//! WebAssembly plugins - plugins that are compiled to WebAssembly and executed in a sandbox. They
+//! could be used for user-made mods in shipped games, because a WebAssembly module cannot access
+//! anything except a small set of functions provided by the engine.
+//!
+//! # Sandbox
+//!
+//! A module is executed by an interpreter, it has no access to the file system, network or memory of
+//! the host. Every call of a module is limited by the amount of "fuel" (roughly - a number of executed
+//! instructions), so an infinite loop in a mod cannot freeze the game, also the size of linear memory
+//! of a module is limited. See [`WasmPluginLimits`]. If a module traps (runs out of fuel, divides by
+//! zero, etc.), the error is written to the log and the plugin is disabled.
+//!
+//! A plugin works with a single scene - the scene, that was passed to
+//! [`crate::plugin::PluginConstructor::create_instance`]. If there is no such scene, the first scene
+//! of the scene container is used.
+//!
+//! # Exports
+//!
+//! Every export is optional:
+//!
+//! - `memory` - linear memory of the module, it is required by functions that pass strings or
+//!   write results to the memory of the module.
+//! - `fyrox_init()` - called when the plugin is initialized.
+//! - `fyrox_update(dt: f32)` - called every frame with fixed time step.
+//! - `fyrox_variable_update(dt: f32, interpolation_alpha: f32)` - called once per rendered frame.
+//! - `fyrox_deinit()` - called when the plugin is destroyed.
+//!
+//! # Imports
+//!
+//! The engine provides the following functions in the `fyrox` module. Scene nodes are passed as
+//! `i64` values, zero means "no node". Functions that return `i32` return `1` on success and `0`
+//! on failure (invalid node, out-of-bounds pointer, etc.).
+//!
+//! - `log(ptr: i32, len: i32)` - writes an UTF-8 string to the log.
+//! - `find_node(ptr: i32, len: i32) -> i64` - searches a node with the given name in the scene.
+//! - `is_node_valid(node: i64) -> i32` - checks whether the node exists.
+//! - `get_position(node: i64, out_ptr: i32) -> i32` - writes local position of the node to the
+//!   memory of the module as three `f32` values.
+//! - `set_position(node: i64, x: f32, y: f32, z: f32) -> i32` - sets local position of the node.
+//! - `set_enabled(node: i64, enabled: i32) -> i32` - enables or disables the node.
+
+use crate::{
+    core::{algebra::Vector3, pool::Handle, uuid::Uuid},
+    event_loop::ControlFlow,
+    plugin::{Plugin, PluginConstructor, PluginContext},
+    scene::{node::Node, Scene, SceneContainer},
+    utils::log::Log,
+};
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
+use wasmi::{
+    core::F32, Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store,
+    StoreLimits, StoreLimitsBuilder, Value,
+};
+
+/// Resource limits of a WebAssembly plugin.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WasmPluginLimits {
+    /// Amount of fuel given to every call of a module. One unit of fuel is roughly equal to one
+    /// executed instruction.
+    pub fuel_per_call: u64,
+    /// Maximum size of linear memory of a module in bytes.
+    pub max_memory: usize,
+}
+
+impl Default for WasmPluginLimits {
+    fn default() -> Self {
+        Self {
+            fuel_per_call: 10_000_000,
+            max_memory: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// An error that may occur during loading of a WebAssembly plugin.
+#[derive(Debug)]
+pub enum WasmPluginError {
+    /// Unable to read a module.
+    Io(std::io::Error),
+    /// A module is malformed or it imports unknown functions.
+    Wasm(String),
+}
+
+impl Display for WasmPluginError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WasmPluginError::Io(e) => write!(f, "I/O error: {}", e),
+            WasmPluginError::Wasm(e) => write!(f, "WebAssembly error: {}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for WasmPluginError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<wasmi::Error> for WasmPluginError {
+    fn from(e: wasmi::Error) -> Self {
+        Self::Wasm(e.to_string())
+    }
+}
+
+struct HostState {
+    // The scene is moved in the store for the duration of a call, so host functions could access it.
+    scene: Option<Scene>,
+    limits: StoreLimits,
+}
+
+fn encode_handle(handle: Handle<Node>) -> i64 {
+    (((handle.generation() as u64) << 32) | handle.index() as u64) as i64
+}
+
+fn decode_handle(value: i64) -> Handle<Node> {
+    let value = value as u64;
+    Handle::new(value as u32, (value >> 32) as u32)
+}
+
+fn memory(caller: &Caller<HostState>) -> Option<Memory> {
+    caller.get_export("memory").and_then(Extern::into_memory)
+}
+
+fn read_string(caller: &Caller<HostState>, ptr: i32, len: i32) -> Option<String> {
+    let memory = memory(caller)?;
+    let mut buffer = vec![0; usize::try_from(len).ok()?];
+    memory
+        .read(caller, usize::try_from(ptr).ok()?, &mut buffer)
+        .ok()?;
+    String::from_utf8(buffer).ok()
+}
+
+fn with_node<R>(
+    caller: &mut Caller<HostState>,
+    node: i64,
+    func: impl FnOnce(&mut Node) -> R,
+) -> Option<R> {
+    let graph = &mut caller.data_mut().scene.as_mut()?.graph;
+    graph.try_get_mut(decode_handle(node)).map(func)
+}
+
+fn make_linker(engine: &Engine) -> Result<Linker<HostState>, WasmPluginError> {
+    let mut linker = Linker::new(engine);
+
+    linker
+        .func_wrap(
+            "fyrox",
+            "log",
+            |caller: Caller<HostState>, ptr: i32, len: i32| {
+                if let Some(message) = read_string(&caller, ptr, len) {
+                    Log::info(format!("[WASM] {}", message));
+                }
+            },
+        )
+        .map_err(|e| WasmPluginError::Wasm(e.to_string()))?
+        .func_wrap(
+            "fyrox",
+            "find_node",
+            |caller: Caller<HostState>, ptr: i32, len: i32| -> i64 {
+                read_string(&caller, ptr, len)
+                    .and_then(|name| {
+                        caller
+                            .data()
+                            .scene
+                            .as_ref()?
+                            .graph
+                            .find_by_name_from_root(&name)
+                            .map(|(handle, _)| encode_handle(handle))
+                    })
+                    .unwrap_or_default()
+            },
+        )
+        .map_err(|e| WasmPluginError::Wasm(e.to_string()))?
+        .func_wrap(
+            "fyrox",
+            "is_node_valid",
+            |mut caller: Caller<HostState>, node: i64| -> i32 {
+                with_node(&mut caller, node, |_| ()).is_some() as i32
+            },
+        )
+        .map_err(|e| WasmPluginError::Wasm(e.to_string()))?
+        .func_wrap(
+            "fyrox",
+            "get_position",
+            |mut caller: Caller<HostState>, node: i64, out_ptr: i32| -> i32 {
+                let position = match with_node(&mut caller, node, |node| {
+                    **node.local_transform().position()
+                }) {
+                    Some(position) => position,
+                    None => return 0,
+                };
+                let mut bytes = [0u8; 12];
+                for (chunk, value) in bytes.chunks_mut(4).zip(position.iter()) {
+                    chunk.copy_from_slice(&value.to_le_bytes());
+                }
+                match (memory(&caller), usize::try_from(out_ptr)) {
+                    (Some(memory), Ok(offset)) => {
+                        memory.write(&mut caller, offset, &bytes).is_ok() as i32
+                    }
+                    _ => 0,
+                }
+            },
+        )
+        .map_err(|e| WasmPluginError::Wasm(e.to_string()))?
+        .func_wrap(
+            "fyrox",
+            "set_position",
+            |mut caller: Caller<HostState>, node: i64, x: F32, y: F32, z: F32| -> i32 {
+                with_node(&mut caller, node, |node| {
+                    node.local_transform_mut().set_position(Vector3::new(
+                        x.into(),
+                        y.into(),
+                        z.into(),
+                    ));
+                })
+                .is_some() as i32
+            },
+        )
+        .map_err(|e| WasmPluginError::Wasm(e.to_string()))?
+        .func_wrap(
+            "fyrox",
+            "set_enabled",
+            |mut caller: Caller<HostState>, node: i64, enabled: i32| -> i32 {
+                with_node(&mut caller, node, |node| node.set_enabled(enabled != 0)).is_some() as i32
+            },
+        )
+        .map_err(|e| WasmPluginError::Wasm(e.to_string()))?;
+
+    Ok(linker)
+}
+
+/// Plugin constructor for a WebAssembly module. It compiles and validates the module once, every
+/// plugin instance created by the constructor has its own sandbox.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use fyrox::{
+///     core::uuid::{uuid, Uuid},
+///     engine::Engine,
+///     plugin::wasm::WasmPluginConstructor,
+/// };
+///
+/// fn load_mods(engine: &mut Engine) {
+///     match WasmPluginConstructor::from_file(
+///         uuid!("a7d2c6b0-3c2f-4d8e-8f0a-1b5e9c4d7e21"),
+///         "mods/my_mod.wasm",
+///     ) {
+///         Ok(constructor) => engine.add_plugin_constructor(constructor),
+///         Err(e) => println!("Unable to load the mod: {}", e),
+///     }
+/// }
+/// ```
+pub struct WasmPluginConstructor {
+    id: Uuid,
+    engine: Engine,
+    module: Module,
+    linker: Linker<HostState>,
+    limits: WasmPluginLimits,
+}
+
+impl WasmPluginConstructor {
+    /// Compiles a module from the given bytes, both binary and text (`.wat`) formats are supported.
+    /// The id is used as id of every plugin instance (see [`crate::plugin::Plugin::id`]), it allows
+    /// other plugins to depend on the module and it is used to store settings of the module in
+    /// scenes.
+    pub fn from_bytes(id: Uuid, bytes: &[u8]) -> Result<Self, WasmPluginError> {
+        let bytes = wat::parse_bytes(bytes).map_err(|e| WasmPluginError::Wasm(e.to_string()))?;
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &*bytes)?;
+        let linker = make_linker(&engine)?;
+
+        // Check that every import of the module is provided by the engine. Instantiation without
+        // start does not execute any code of the module.
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                scene: None,
+                limits: Default::default(),
+            },
+        );
+        linker.instantiate(&mut store, &module)?;
+
+        Ok(Self {
+            id,
+            engine,
+            module,
+            linker,
+            limits: Default::default(),
+        })
+    }
+
+    /// Reads and compiles a module from the given file (`.wasm` or `.wat`).
+    pub fn from_file<P: AsRef<Path>>(id: Uuid, path: P) -> Result<Self, WasmPluginError> {
+        Self::from_bytes(id, &std::fs::read(path)?)
+    }
+
+    /// Sets resource limits for every instance of the module.
+    pub fn with_limits(mut self, limits: WasmPluginLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Creates a new plugin instance that will work with the given scene.
+    pub fn instantiate(&self, scene: Handle<Scene>) -> WasmPlugin {
+        let mut store = Store::new(
+            &self.engine,
+            HostState {
+                scene: None,
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(self.limits.max_memory)
+                    .instances(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+
+        let mut plugin = WasmPlugin {
+            id: self.id,
+            scene,
+            fuel_per_call: self.limits.fuel_per_call,
+            store,
+            instance: None,
+        };
+
+        if plugin.refuel() {
+            match self
+                .linker
+                .instantiate(&mut plugin.store, &self.module)
+                .and_then(|pre| pre.start(&mut plugin.store))
+            {
+                Ok(instance) => plugin.instance = Some(instance),
+                Err(e) => Log::err(format!(
+                    "Unable to instantiate WebAssembly plugin {}. Reason: {}",
+                    self.id, e
+                )),
+            }
+        }
+
+        plugin
+    }
+}
+
+impl PluginConstructor for WasmPluginConstructor {
+    fn create_instance(
+        &self,
+        override_scene: Handle<Scene>,
+        _context: PluginContext,
+    ) -> Box<dyn Plugin> {
+        Box::new(self.instantiate(override_scene))
+    }
+}
+
+/// An instance of a WebAssembly module, see [module docs](self) for more info.
+pub struct WasmPlugin {
+    id: Uuid,
+    scene: Handle<Scene>,
+    fuel_per_call: u64,
+    store: Store<HostState>,
+    instance: Option<Instance>,
+}
+
+impl WasmPlugin {
+    /// Returns `true` if the module is running, `false` - if it failed to instantiate or trapped.
+    pub fn is_running(&self) -> bool {
+        self.instance.is_some()
+    }
+
+    /// Returns a handle of the scene the plugin works with.
+    pub fn scene(&self) -> Handle<Scene> {
+        self.scene
+    }
+
+    /// Sets a scene the plugin should work with.
+    pub fn set_scene(&mut self, scene: Handle<Scene>) {
+        self.scene = scene;
+    }
+
+    fn refuel(&mut self) -> bool {
+        let remaining = self.store.consume_fuel(0).unwrap_or_default();
+        self.store
+            .add_fuel(self.fuel_per_call.saturating_sub(remaining))
+            .is_ok()
+    }
+
+    fn stop(&mut self, reason: impl Display) {
+        Log::err(format!(
+            "WebAssembly plugin {} was stopped. Reason: {}",
+            self.id, reason
+        ));
+        self.instance = None;
+    }
+
+    /// Calls an exported function of the module, if it exists.
+    fn call(&mut self, scenes: &mut SceneContainer, name: &str, args: &[Value]) {
+        let instance = match self.instance {
+            Some(instance) => instance,
+            None => return,
+        };
+
+        let func = match instance.get_func(&self.store, name) {
+            Some(func) => func,
+            None => return,
+        };
+
+        if !self.refuel() {
+            return;
+        }
+
+        let scene = if scenes.is_valid_handle(self.scene) {
+            self.scene
+        } else {
+            scenes
+                .pair_iter()
+                .next()
+                .map(|(handle, _)| handle)
+                .unwrap_or_default()
+        };
+
+        let ticket = if scene.is_some() {
+            let (ticket, scene) = scenes.take_reserve(scene);
+            self.store.data_mut().scene = Some(scene);
+            Some(ticket)
+        } else {
+            None
+        };
+
+        let result = func.call(&mut self.store, args, &mut []);
+
+        if let Some(ticket) = ticket {
+            let scene = self.store.data_mut().scene.take().unwrap();
+            scenes.put_back(ticket, scene);
+        }
+
+        if let Err(e) = result {
+            self.stop(e);
+        }
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn on_init(&mut self, context: PluginContext) {
+        self.call(context.scenes, "fyrox_init", &[]);
+    }
+
+    fn on_deinit(&mut self, context: PluginContext) {
+        self.call(context.scenes, "fyrox_deinit", &[]);
+    }
+
+    fn update(&mut self, context: &mut PluginContext, _control_flow: &mut ControlFlow) {
+        self.call(
+            context.scenes,
+            "fyrox_update",
+            &[Value::F32(F32::from(context.dt))],
+        );
+    }
+
+    fn variable_update(&mut self, context: &mut PluginContext, interpolation_alpha: f32) {
+        self.call(
+            context.scenes,
+            "fyrox_variable_update",
+            &[
+                Value::F32(F32::from(context.dt)),
+                Value::F32(F32::from(interpolation_alpha)),
+            ],
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, uuid::Uuid},
+        plugin::wasm::{WasmPluginConstructor, WasmPluginLimits},
+        scene::{base::BaseBuilder, pivot::PivotBuilder, Scene, SceneContainer},
+    };
+    use wasmi::{core::F32, Value};
+
+    const MODULE: &str = r#"
+        (module
+            (import "fyrox" "find_node" (func $find_node (param i32 i32) (result i64)))
+            (import "fyrox" "set_position" (func $set_position (param i64 f32 f32 f32) (result i32)))
+            (memory (export "memory") 1)
+            (data (i32.const 0) "Cube")
+            (global $time (mut f32) (f32.const 0))
+            (func (export "fyrox_update") (param $dt f32)
+                (global.set $time (f32.add (global.get $time) (local.get $dt)))
+                (drop (call $set_position
+                    (call $find_node (i32.const 0) (i32.const 4))
+                    (global.get $time) (f32.const 0) (f32.const 0))))
+            (func (export "fyrox_init")
+                (loop $forever (br $forever))))
+    "#;
+
+    #[test]
+    fn test_wasm_plugin() {
+        let constructor = WasmPluginConstructor::from_bytes(Uuid::new_v4(), MODULE.as_bytes())
+            .unwrap()
+            .with_limits(WasmPluginLimits {
+                fuel_per_call: 100_000,
+                ..Default::default()
+            });
+
+        let mut scenes = SceneContainer::new(Default::default());
+        let mut scene = Scene::new();
+        let cube = PivotBuilder::new(BaseBuilder::new().with_name("Cube")).build(&mut scene.graph);
+        let scene = scenes.add(scene);
+
+        let mut plugin = constructor.instantiate(scene);
+        assert!(plugin.is_running());
+
+        for _ in 0..2 {
+            plugin.call(&mut scenes, "fyrox_update", &[Value::F32(F32::from(0.5))]);
+        }
+        assert!(plugin.is_running());
+        assert_eq!(
+            **scenes[scene].graph[cube].local_transform().position(),
+            Vector3::new(1.0, 0.0, 0.0)
+        );
+
+        // Infinite loop must run out of fuel and stop the plugin instead of freezing the host.
+        plugin.call(&mut scenes, "fyrox_init", &[]);
+        assert!(!plugin.is_running());
+        assert!(scenes.is_valid_handle(scene));
+    }
+
+    #[test]
+    fn test_unknown_import() {
+        let module = r#"(module (import "env" "system" (func (param i32))))"#;
+        assert!(WasmPluginConstructor::from_bytes(Uuid::new_v4(), module.as_bytes()).is_err());
+    }
+}
@@ -2,28 +2,72 @@
 
 use crate::{
     core::{
-        algebra::Matrix4, arrayvec::ArrayVec, math::aabb::AxisAlignedBoundingBox, pool::Handle,
+        algebra::Matrix4, color::Color, math::aabb::AxisAlignedBoundingBox, pool::Handle,
         scope_profile, sstorage::ImmutableString,
     },
-    material::{PropertyValue, SharedMaterial},
+    material::{MaterialPropertyBlock, PropertyValue, SharedMaterial},
+    renderer::framework::{
+        error::FrameworkError,
+        gpu_texture::{
+            GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+        },
+        state::PipelineState,
+    },
     scene::{
         graph::Graph,
-        mesh::{surface::SurfaceSharedData, Mesh, RenderPath},
+        mesh::{instanced::InstancedMesh, surface::SurfaceSharedData, Mesh, RenderPath},
         node::Node,
+        rope::Rope,
         terrain::Terrain,
     },
-    utils::log::{Log, MessageKind},
+    utils::{
+        array_as_u8_slice,
+        log::{Log, MessageKind},
+    },
 };
 use bitflags::bitflags;
 use fxhash::{FxHashMap, FxHasher};
 use std::{
+    cell::RefCell,
     fmt::{Debug, Formatter},
     hash::Hasher,
+    rc::Rc,
 };
 
-/// Maximum amount of bone matrices per instance.
+/// Maximum amount of bone matrices per instance, that could be passed to shaders using uniforms.
+/// There is no such limit when bone matrices are stored in a texture (see
+/// [`crate::renderer::QualitySettings::use_bone_matrices_texture`]).
 pub const BONE_MATRICES_COUNT: usize = 64;
 
+// Width of the texture with bone matrices in texels, each matrix takes 4 texels.
+const BONE_MATRICES_TEXTURE_WIDTH: usize = 1024;
+
+/// Index of the first vertex attribute that is used to pass per-instance data to shaders when
+/// hardware instancing is used. World matrix of an instance takes four consecutive locations
+/// (`10..=13`), color of an instance is passed using location `14`. Vertex buffers must not use
+/// these locations.
+pub const INSTANCE_ATTRIBUTES_LOCATION: u32 = 10;
+
+/// Per-instance data, that is passed to shaders using vertex attributes when hardware instancing
+/// is used.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct InstanceData {
+    /// A world matrix of the instance.
+    pub world_matrix: Matrix4<f32>,
+    /// A color of the instance.
+    pub color: Color,
+}
+
+impl Default for InstanceData {
+    fn default() -> Self {
+        Self {
+            world_matrix: Matrix4::identity(),
+            color: Color::WHITE,
+        }
+    }
+}
+
 bitflags! {
     /// A set of flags for surface instance. It is just a compact way for storing multiple boolean
     /// flags.
@@ -36,6 +80,10 @@ bitflags! {
         const CAST_SHADOWS = 0b0000_0010;
         /// Whether the isntance should use frustum culling or not.
         const FRUSTUM_CULLING = 0b0000_0100;
+        /// Whether the instance can hide other instances behind it or not.
+        const OCCLUDER = 0b0000_1000;
+        /// Whether the instance can be hidden by occluders or not.
+        const OCCLUDEE = 0b0001_0000;
     }
 }
 
@@ -47,12 +95,18 @@ impl SurfaceInstanceFlags {
         if node.cast_shadows() {
             flags.insert(SurfaceInstanceFlags::CAST_SHADOWS);
         }
-        if node.global_visibility() {
+        if node.global_visibility() && node.global_opacity() > 0.0 {
             flags.insert(SurfaceInstanceFlags::IS_VISIBLE);
         }
         if node.frustum_culling() {
             flags.insert(SurfaceInstanceFlags::FRUSTUM_CULLING);
         }
+        if node.is_occluder() {
+            flags.insert(SurfaceInstanceFlags::OCCLUDER);
+        }
+        if node.is_occludee() {
+            flags.insert(SurfaceInstanceFlags::OCCLUDEE);
+        }
 
         flags
     }
@@ -69,9 +123,31 @@ pub struct SurfaceInstance {
     /// World space axis-aligned bounding box.
     pub world_aabb: AxisAlignedBoundingBox,
     /// A set of bone matrices.
-    pub bone_matrices: ArrayVec<Matrix4<f32>, BONE_MATRICES_COUNT>,
+    pub bone_matrices: Vec<Matrix4<f32>>,
+    /// Index of the first bone matrix of the instance in the texture with bone matrices of every
+    /// skinned instance (see [`BatchStorage::bone_matrices_texture`]).
+    pub bone_matrices_offset: usize,
     /// A depth-hack value.
     pub depth_offset: f32,
+    /// Per-instance overrides of material properties.
+    pub property_block: Option<MaterialPropertyBlock>,
+    /// Opacity of the instance, see [`crate::scene::base::Base::set_visibility_opacity`].
+    pub opacity: f32,
+    /// Color of the instance. It is applied only when the instance is rendered using hardware
+    /// instancing.
+    pub color: Color,
+}
+
+impl SurfaceInstance {
+    /// Checks whether the instance could be rendered together with other instances of a batch
+    /// using hardware instancing. Skinned instances, instances with per-instance material
+    /// properties, depth offset or opacity must be rendered separately.
+    pub fn is_instanceable(&self) -> bool {
+        self.bone_matrices.is_empty()
+            && self.property_block.is_none()
+            && self.depth_offset == 0.0
+            && self.opacity >= 1.0
+    }
 }
 
 /// A set of surface instances that share the same vertex/index data and a material.
@@ -89,6 +165,11 @@ pub struct Batch {
     pub render_path: RenderPath,
     /// A decal layer index of the batch.
     pub decal_layer_index: u8,
+    /// Draw order priority of the batch, it is taken from the material.
+    pub priority: i32,
+    /// Whether the instances of the batch could be rendered using hardware instancing or not.
+    /// See [`SurfaceInstance::is_instanceable`] for more info.
+    pub use_instancing: bool,
     sort_index: u64,
 }
 
@@ -111,12 +192,108 @@ pub struct BatchStorage {
     batch_map: FxHashMap<u64, usize>,
     /// Sorted list of batches.
     pub batches: Vec<Batch>,
+    bone_matrices: Vec<Matrix4<f32>>,
+    bone_matrices_texture: Option<Rc<RefCell<GpuTexture>>>,
+    bone_matrices_texture_enabled: bool,
 }
 
 impl BatchStorage {
-    pub(crate) fn generate_batches(&mut self, graph: &Graph) {
+    /// Returns a texture with bone matrices of every skinned instance, if bone matrices texture
+    /// is enabled and was successfully uploaded for the current frame. Each matrix takes four
+    /// consecutive RGBA32F texels.
+    pub fn bone_matrices_texture(&self) -> Option<Rc<RefCell<GpuTexture>>> {
+        if self.bone_matrices_texture_enabled {
+            self.bone_matrices_texture.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Uploads bone matrices of every skinned instance to GPU. If the upload fails (for example
+    /// when vertex shaders are unable to fetch floating-point textures), bone matrices will be
+    /// passed using uniforms.
+    pub(crate) fn upload_bone_matrices(&mut self, state: &mut PipelineState, enabled: bool) {
+        self.bone_matrices_texture_enabled = false;
+
+        if !enabled || self.bone_matrices.is_empty() {
+            return;
+        }
+
+        match self.upload_bone_matrices_internal(state) {
+            Ok(_) => self.bone_matrices_texture_enabled = true,
+            Err(e) => Log::err(format!(
+                "Unable to upload bone matrices, falling back to uniforms. Reason: {:?}",
+                e
+            )),
+        }
+    }
+
+    fn upload_bone_matrices_internal(
+        &mut self,
+        state: &mut PipelineState,
+    ) -> Result<(), FrameworkError> {
+        let matrices_per_row = BONE_MATRICES_TEXTURE_WIDTH / 4;
+        let height = (self.bone_matrices.len() + matrices_per_row - 1) / matrices_per_row;
+        self.bone_matrices
+            .resize(height * matrices_per_row, Matrix4::identity());
+
+        let kind = GpuTextureKind::Rectangle {
+            width: BONE_MATRICES_TEXTURE_WIDTH,
+            height,
+        };
+
+        if let Some(texture) = self.bone_matrices_texture.as_ref() {
+            texture.borrow_mut().bind_mut(state, 0).set_data(
+                kind,
+                PixelKind::RGBA32F,
+                1,
+                Some(array_as_u8_slice(&self.bone_matrices)),
+            )?;
+        } else {
+            self.bone_matrices_texture = Some(Rc::new(RefCell::new(GpuTexture::new(
+                state,
+                kind,
+                PixelKind::RGBA32F,
+                MinificationFilter::Nearest,
+                MagnificationFilter::Nearest,
+                1,
+                Some(array_as_u8_slice(&self.bone_matrices)),
+            )?)));
+        }
+
+        Ok(())
+    }
+
+    // Returns a batch with the id of the given template, the batch is created from the template if
+    // there's no such batch yet. Material and sort index of an existing batch are taken from the
+    // template, so material changes are picked up every frame.
+    fn batch_mut(&mut self, template: Batch) -> &mut Batch {
+        let priority = template.material.lock().render_priority();
+
+        let batch = if let Some(&batch_index) = self.batch_map.get(&template.id) {
+            let batch = &mut self.batches[batch_index];
+            batch.sort_index = template.sort_index;
+            batch.material = template.material;
+            batch
+        } else {
+            self.batch_map.insert(template.id, self.batches.len());
+            let instances = self.buffers.remove(&template.id).unwrap_or_default();
+            self.batches.push(Batch {
+                instances,
+                ..template
+            });
+            self.batches.last_mut().unwrap()
+        };
+
+        batch.priority = priority;
+        batch
+    }
+
+    pub(crate) fn generate_batches(&mut self, graph: &Graph, use_instancing: bool) {
         scope_profile!();
 
+        self.bone_matrices.clear();
+
         for batch in self.batches.iter_mut() {
             batch.instances.clear();
             self.buffers
@@ -140,55 +317,121 @@ impl BatchStorage {
                         mesh.global_transform()
                     };
 
-                    let data = surface.data();
-                    let batch_id = surface.batch_id();
-
-                    let batch = if let Some(&batch_index) = self.batch_map.get(&batch_id) {
-                        self.batches.get_mut(batch_index).unwrap()
-                    } else {
-                        self.batch_map.insert(batch_id, self.batches.len());
-                        self.batches.push(Batch {
-                            id: batch_id,
-                            data,
-                            // Batches from meshes will be sorted using materials.
-                            // This will significantly reduce pipeline state changes.
-                            sort_index: surface.material_id(),
-                            instances: self
-                                .buffers
-                                .remove_entry(&batch_id)
-                                .map(|(_, buf)| buf)
-                                .unwrap_or_default(),
-                            material: surface.material().clone(),
-                            is_skinned: !surface.bones.is_empty(),
-                            render_path: mesh.render_path(),
-                            decal_layer_index: mesh.decal_layer_index(),
-                        });
-                        self.batches.last_mut().unwrap()
-                    };
-
-                    batch.sort_index = surface.material_id();
-                    batch.material = surface.material().clone();
+                    let bone_matrices = surface
+                        .bones
+                        .iter()
+                        .map(|&bone_handle| {
+                            if let Some(bone_node) = graph.try_get(bone_handle) {
+                                bone_node.global_transform() * bone_node.inv_bind_pose_transform()
+                            } else {
+                                Matrix4::identity()
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    let bone_matrices_offset = self.bone_matrices.len();
+                    self.bone_matrices.extend_from_slice(&bone_matrices);
 
-                    batch.instances.push(SurfaceInstance {
+                    self.batch_mut(Batch {
+                        id: surface.batch_id(),
+                        data: surface.data(),
+                        // Batches from meshes will be sorted using materials.
+                        // This will significantly reduce pipeline state changes.
+                        sort_index: surface.material_id(),
+                        instances: Default::default(),
+                        material: surface.material().clone(),
+                        is_skinned,
+                        render_path: mesh.render_path(),
+                        decal_layer_index: mesh.decal_layer_index(),
+                        priority: 0,
+                        use_instancing: false,
+                    })
+                    .instances
+                    .push(SurfaceInstance {
                         world_transform: world,
                         flags: SurfaceInstanceFlags::from_node(node),
                         world_aabb: node.world_bounding_box(),
-                        bone_matrices: surface
-                            .bones
-                            .iter()
-                            .map(|&bone_handle| {
-                                if let Some(bone_node) = graph.try_get(bone_handle) {
-                                    bone_node.global_transform()
-                                        * bone_node.inv_bind_pose_transform()
-                                } else {
-                                    Matrix4::identity()
-                                }
-                            })
-                            .collect(),
+                        bone_matrices,
+                        bone_matrices_offset,
                         owner: handle,
                         depth_offset: mesh.depth_offset_factor(),
+                        opacity: mesh.global_opacity(),
+                        property_block: if mesh.property_block().is_empty() {
+                            None
+                        } else {
+                            Some(mesh.property_block().clone())
+                        },
+                        color: Color::WHITE,
+                    });
+                }
+            } else if let Some(instanced_mesh) = node.cast::<InstancedMesh>() {
+                let surfaces_bounding_box = instanced_mesh.surfaces_bounding_box();
+                let global_transform = instanced_mesh.global_transform();
+
+                for surface in instanced_mesh.surfaces().iter() {
+                    let batch = self.batch_mut(Batch {
+                        id: surface.batch_id(),
+                        data: surface.data(),
+                        sort_index: surface.material_id(),
+                        instances: Default::default(),
+                        material: surface.material().clone(),
+                        is_skinned: false,
+                        render_path: instanced_mesh.render_path(),
+                        decal_layer_index: 0,
+                        priority: 0,
+                        use_instancing: false,
                     });
+
+                    for instance in instanced_mesh.instances().iter() {
+                        let world_transform = global_transform * instance.matrix();
+
+                        batch.instances.push(SurfaceInstance {
+                            world_transform,
+                            flags: SurfaceInstanceFlags::from_node(node),
+                            world_aabb: surfaces_bounding_box.transform(&world_transform),
+                            bone_matrices: Default::default(),
+                            bone_matrices_offset: 0,
+                            owner: handle,
+                            depth_offset: instanced_mesh.depth_offset_factor(),
+                            opacity: instanced_mesh.global_opacity(),
+                            property_block: None,
+                            color: instance.color,
+                        });
+                    }
                 }
+            } else if let Some(rope) = node.cast::<Rope>() {
+                let data = rope.surface_data().clone();
+                let material = rope.material().clone();
+
+                let mut hasher = FxHasher::default();
+                hasher.write_u64(material.key());
+                hasher.write_u64(data.key());
+
+                // Rope geometry is already in world coordinates.
+                self.batch_mut(Batch {
+                    id: hasher.finish(),
+                    data,
+                    instances: Default::default(),
+                    sort_index: material.key(),
+                    material,
+                    is_skinned: false,
+                    render_path: RenderPath::Deferred,
+                    decal_layer_index: 0,
+                    priority: 0,
+                    use_instancing: false,
+                })
+                .instances
+                .push(SurfaceInstance {
+                    world_transform: Matrix4::identity(),
+                    flags: SurfaceInstanceFlags::from_node(node),
+                    world_aabb: rope.world_bounding_box(),
+                    bone_matrices: Default::default(),
+                    bone_matrices_offset: 0,
+                    owner: handle,
+                    depth_offset: rope.depth_offset_factor(),
+                    opacity: rope.global_opacity(),
+                    property_block: None,
+                    color: Color::WHITE,
+                });
             } else if let Some(terrain) = node.cast::<Terrain>() {
                 for (layer_index, layer) in terrain.layers().iter().enumerate() {
                     for (chunk_index, chunk) in terrain.chunks_ref().iter().enumerate() {
@@ -211,39 +454,30 @@ impl BatchStorage {
                                 hasher.write_u64(material.key());
                                 hasher.write_u64(data_key);
 
-                                let key = hasher.finish();
-
-                                let batch = if let Some(&batch_index) = self.batch_map.get(&key) {
-                                    self.batches.get_mut(batch_index).unwrap()
-                                } else {
-                                    self.batch_map.insert(key, self.batches.len());
-                                    self.batches.push(Batch {
-                                        id: key,
-                                        data: data.clone(),
-                                        instances: self
-                                            .buffers
-                                            .remove_entry(&key)
-                                            .map(|(_, buf)| buf)
-                                            .unwrap_or_default(),
-                                        material: material.clone(),
-                                        is_skinned: false,
-                                        render_path: RenderPath::Deferred,
-                                        sort_index: layer_index as u64,
-                                        decal_layer_index: terrain.decal_layer_index(),
-                                    });
-                                    self.batches.last_mut().unwrap()
-                                };
-
-                                batch.sort_index = layer_index as u64;
-                                batch.material = material;
-
-                                batch.instances.push(SurfaceInstance {
+                                self.batch_mut(Batch {
+                                    id: hasher.finish(),
+                                    data: data.clone(),
+                                    instances: Default::default(),
+                                    material,
+                                    is_skinned: false,
+                                    render_path: RenderPath::Deferred,
+                                    sort_index: layer_index as u64,
+                                    decal_layer_index: terrain.decal_layer_index(),
+                                    priority: 0,
+                                    use_instancing: false,
+                                })
+                                .instances
+                                .push(SurfaceInstance {
                                     world_transform: terrain.global_transform(),
                                     flags: SurfaceInstanceFlags::from_node(node),
                                     world_aabb: terrain.world_bounding_box(),
                                     bone_matrices: Default::default(),
+                                    bone_matrices_offset: 0,
                                     owner: handle,
                                     depth_offset: terrain.depth_offset_factor(),
+                                    opacity: terrain.global_opacity(),
+                                    property_block: None,
+                                    color: Color::WHITE,
                                 });
                             }
                             Err(e) => Log::writeln(
@@ -266,8 +500,11 @@ impl BatchStorage {
             if batch.instances.capacity() >= 3 * batch.instances.len() {
                 batch.instances.shrink_to_fit();
             }
+
+            batch.use_instancing = use_instancing && !batch.is_skinned;
         }
 
-        self.batches.sort_unstable_by_key(|b| b.sort_index);
+        self.batches
+            .sort_unstable_by_key(|b| (b.priority, b.sort_index));
     }
 }
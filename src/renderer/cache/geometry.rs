@@ -2,15 +2,62 @@ use crate::{
     core::{scope_profile, sparse::SparseBuffer},
     engine::resource_manager::container::entry::DEFAULT_RESOURCE_LIFETIME,
     renderer::{
+        batch::{InstanceData, INSTANCE_ATTRIBUTES_LOCATION},
         cache::CacheEntry,
         framework::{
-            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            geometry_buffer::{
+                AttributeDefinition, AttributeKind, BufferBuilder, ElementKind, GeometryBuffer,
+                GeometryBufferBuilder, GeometryBufferKind,
+            },
             state::PipelineState,
         },
     },
-    scene::mesh::surface::SurfaceSharedData,
+    scene::mesh::surface::{SurfaceData, SurfaceSharedData},
 };
 
+/// Index of the buffer with per-instance data in geometry buffers of the cache.
+pub const INSTANCE_BUFFER_INDEX: usize = 1;
+
+// Creates a geometry buffer with vertex data of the surface and a buffer with per-instance data,
+// that is used for hardware instancing. Instance buffer always contains at least one instance,
+// because attributes with non-zero divisor are fetched even for non-instanced draw calls.
+fn create_geometry_buffer(data: &SurfaceData, state: &mut PipelineState) -> GeometryBuffer {
+    let default_instance = [InstanceData::default()];
+    let mut instance_buffer =
+        BufferBuilder::new(GeometryBufferKind::DynamicDraw, Some(&default_instance));
+    // World matrix.
+    for i in 0..4 {
+        instance_buffer = instance_buffer.with_attribute(AttributeDefinition {
+            location: INSTANCE_ATTRIBUTES_LOCATION + i,
+            kind: AttributeKind::Float4,
+            normalized: false,
+            divisor: 1,
+        });
+    }
+    // Color.
+    instance_buffer = instance_buffer.with_attribute(AttributeDefinition {
+        location: INSTANCE_ATTRIBUTES_LOCATION + 4,
+        kind: AttributeKind::UnsignedByte4,
+        normalized: true,
+        divisor: 1,
+    });
+
+    let geometry_buffer = GeometryBufferBuilder::new(ElementKind::Triangle)
+        .with_buffer_builder(BufferBuilder::from_vertex_buffer(
+            &data.vertex_buffer,
+            GeometryBufferKind::StaticDraw,
+        ))
+        .with_buffer_builder(instance_buffer)
+        .build(state)
+        .unwrap();
+
+    geometry_buffer
+        .bind(state)
+        .set_triangles(data.geometry_buffer.triangles_ref());
+
+    geometry_buffer
+}
+
 #[derive(Default)]
 pub struct GeometryCache {
     buffer: SparseBuffer<CacheEntry<GeometryBuffer>>,
@@ -43,8 +90,7 @@ impl GeometryCache {
             entry.time_to_live = DEFAULT_RESOURCE_LIFETIME;
             entry
         } else {
-            let geometry_buffer =
-                GeometryBuffer::from_surface_data(&data, GeometryBufferKind::StaticDraw, state);
+            let geometry_buffer = create_geometry_buffer(&data, state);
 
             let index = self.buffer.spawn(CacheEntry {
                 value: geometry_buffer,
@@ -77,4 +123,9 @@ impl GeometryCache {
     pub fn clear(&mut self) {
         self.buffer.clear();
     }
+
+    /// Returns an estimate of the amount of video memory (in bytes) used by the cached geometry.
+    pub fn size_bytes(&self) -> usize {
+        self.buffer.iter().map(|entry| entry.size_bytes()).sum()
+    }
 }
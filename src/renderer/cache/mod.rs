@@ -5,6 +5,7 @@ use std::ops::{Deref, DerefMut};
 pub mod geometry;
 pub mod shader;
 pub mod texture;
+pub mod texture_array;
 
 pub struct CacheEntry<T> {
     pub value: T,
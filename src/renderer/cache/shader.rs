@@ -1,26 +1,102 @@
 use crate::{
     asset::ResourceState,
-    core::{scope_profile, sparse::SparseBuffer, sstorage::ImmutableString},
+    core::{instant, scope_profile, sparse::SparseBuffer, sstorage::ImmutableString},
     engine::resource_manager::container::entry::DEFAULT_RESOURCE_LIFETIME,
-    material::shader::{Shader, ShaderState},
+    material::shader::{RenderPassDefinition, Shader, ShaderState},
     renderer::{
         cache::CacheEntry,
-        framework::{framebuffer::DrawParameters, gpu_program::GpuProgram, state::PipelineState},
+        framework::{
+            framebuffer::DrawParameters,
+            gpu_program::{GpuProgram, PendingGpuProgram},
+            state::PipelineState,
+        },
+        oit::make_oit_fragment_shader,
     },
     utils::log::{Log, MessageKind},
 };
-use fxhash::FxHashMap;
-use std::ops::Deref;
+use fxhash::{FxHashMap, FxHashSet};
+use std::{cell::RefCell, collections::VecDeque, ops::Deref, rc::Rc, time::Duration};
+
+enum OitProgram {
+    /// The program is compiled on first use, most of the shaders are never rendered with
+    /// order-independent transparency.
+    Pending {
+        name: String,
+        vertex_shader: String,
+        fragment_shader: String,
+    },
+    Compiled(Rc<GpuProgram>),
+    Unavailable,
+}
 
 pub struct RenderPassData {
     pub program: GpuProgram,
     pub draw_params: DrawParameters,
+    oit_program: RefCell<OitProgram>,
+}
+
+impl RenderPassData {
+    /// Returns a variant of `Forward` render pass program, that writes its output to
+    /// order-independent transparency render targets. It is `None` for other render passes and
+    /// for shaders that cannot be wrapped (see [`make_oit_fragment_shader`]).
+    pub fn oit_program(&self, state: &mut PipelineState) -> Option<Rc<GpuProgram>> {
+        let mut oit_program = self.oit_program.borrow_mut();
+
+        let compiled = if let OitProgram::Pending {
+            name,
+            vertex_shader,
+            fragment_shader,
+        } = &*oit_program
+        {
+            Some(
+                match make_oit_fragment_shader(fragment_shader).map(|fragment_shader| {
+                    GpuProgram::from_source(state, name, vertex_shader, &fragment_shader)
+                }) {
+                    Some(Ok(program)) => OitProgram::Compiled(Rc::new(program)),
+                    Some(Err(e)) => {
+                        Log::writeln(
+                            MessageKind::Error,
+                            format!(
+                                "Failed to create {} shader' GPU program. Reason: {:?}",
+                                name, e
+                            ),
+                        );
+                        OitProgram::Unavailable
+                    }
+                    None => OitProgram::Unavailable,
+                },
+            )
+        } else {
+            None
+        };
+
+        if let Some(compiled) = compiled {
+            *oit_program = compiled;
+        }
+
+        match &*oit_program {
+            OitProgram::Compiled(program) => Some(program.clone()),
+            _ => None,
+        }
+    }
 }
 
 pub struct ShaderSet {
     pub render_passes: FxHashMap<ImmutableString, RenderPassData>,
 }
 
+fn make_oit_program(program_name: &str, render_pass: &RenderPassDefinition) -> OitProgram {
+    if render_pass.name == "Forward" {
+        OitProgram::Pending {
+            name: format!("{}_Oit", program_name),
+            vertex_shader: render_pass.vertex_shader.clone(),
+            fragment_shader: render_pass.fragment_shader.clone(),
+        }
+    } else {
+        OitProgram::Unavailable
+    }
+}
+
 impl ShaderSet {
     pub fn new(state: &mut PipelineState, shader: &ShaderState) -> Option<Self> {
         let mut map = FxHashMap::default();
@@ -38,6 +114,7 @@ impl ShaderSet {
                         RenderPassData {
                             program: gpu_program,
                             draw_params: render_pass.draw_parameters.clone(),
+                            oit_program: RefCell::new(make_oit_program(&program_name, render_pass)),
                         },
                     );
                 }
@@ -58,27 +135,177 @@ impl ShaderSet {
     }
 }
 
+struct PendingRenderPass {
+    name: ImmutableString,
+    program_name: String,
+    program: PendingGpuProgram,
+    draw_params: DrawParameters,
+    oit_program: OitProgram,
+}
+
+/// A shader set, which programs are being compiled by the driver in parallel.
+struct PendingShaderSet {
+    render_passes: Vec<PendingRenderPass>,
+}
+
+impl PendingShaderSet {
+    fn begin(state: &mut PipelineState, shader: &ShaderState) -> Option<Self> {
+        let mut render_passes = Vec::new();
+        for render_pass in shader.definition.passes.iter() {
+            let program_name = format!("{}_{}", shader.definition.name, render_pass.name);
+            match GpuProgram::begin_from_source(
+                state,
+                &program_name,
+                &render_pass.vertex_shader,
+                &render_pass.fragment_shader,
+            ) {
+                Ok(program) => render_passes.push(PendingRenderPass {
+                    name: ImmutableString::new(&render_pass.name),
+                    oit_program: make_oit_program(&program_name, render_pass),
+                    program_name,
+                    program,
+                    draw_params: render_pass.draw_parameters.clone(),
+                }),
+                Err(e) => {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!(
+                            "Failed to create {} shader' GPU program. Reason: {:?}",
+                            program_name, e
+                        ),
+                    );
+                    return None;
+                }
+            }
+        }
+        Some(Self { render_passes })
+    }
+
+    fn is_completed(&self, state: &PipelineState) -> bool {
+        self.render_passes
+            .iter()
+            .all(|pass| pass.program.is_completed(state))
+    }
+
+    fn finish(self, state: &mut PipelineState) -> Option<ShaderSet> {
+        let mut map = FxHashMap::default();
+        for pass in self.render_passes {
+            match pass.program.finish(state) {
+                Ok(program) => {
+                    map.insert(
+                        pass.name,
+                        RenderPassData {
+                            program,
+                            draw_params: pass.draw_params,
+                            oit_program: RefCell::new(pass.oit_program),
+                        },
+                    );
+                }
+                Err(e) => {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!(
+                            "Failed to create {} shader' GPU program. Reason: {:?}",
+                            pass.program_name, e
+                        ),
+                    );
+                    return None;
+                }
+            }
+        }
+        Some(ShaderSet { render_passes: map })
+    }
+}
+
+/// An event of background shader compilation, see
+/// [`crate::renderer::Renderer::set_shader_compilation_budget`] for more info.
+#[derive(Clone, Debug)]
+pub enum ShaderCompilationEvent {
+    /// Shader was successfully compiled, objects that use it are now rendered with their actual
+    /// materials.
+    Compiled(Shader),
+    /// Shader failed to compile, objects that use it won't be rendered.
+    Failed(Shader),
+}
+
 #[derive(Default)]
 pub struct ShaderCache {
     pub(super) buffer: SparseBuffer<CacheEntry<ShaderSet>>,
+    compilation_budget: Option<Duration>,
+    compilation_queue: VecDeque<Shader>,
+    in_flight: Vec<(Shader, PendingShaderSet)>,
+    failed: FxHashSet<u64>,
+    placeholder: Option<ShaderSet>,
 }
 
 impl ShaderCache {
     pub fn remove(&mut self, shader: &Shader) {
+        self.failed.remove(&(shader.key() as u64));
+        self.in_flight
+            .retain(|(in_flight, _)| in_flight.key() != shader.key());
         let shader = shader.state();
         if let ResourceState::Ok(shader_state) = shader.deref() {
             self.buffer.free(&shader_state.cache_index);
         }
     }
 
+    fn is_cached(&self, shader_state: &ShaderState, key: u64) -> bool {
+        // Index could point to an entry of other shader if the cache was re-created, so
+        // check the key too.
+        self.buffer
+            .get(&shader_state.cache_index)
+            .map_or(false, |entry| entry.value_hash == key)
+    }
+
+    /// Returns a shader set of the given shader. If compilation budget is set and the shader is not
+    /// compiled yet, the shader is queued for compilation and the placeholder shader set is returned.
     pub fn get(&mut self, state: &mut PipelineState, shader: &Shader) -> Option<&ShaderSet> {
         scope_profile!();
 
+        if self.compilation_budget.is_none() {
+            return self.get_or_compile(state, shader);
+        }
+
+        let key = shader.key() as u64;
+        let is_cached = match *shader.state() {
+            ResourceState::Ok(ref shader_state) => self.is_cached(shader_state, key),
+            _ => return None,
+        };
+
+        if is_cached {
+            self.get_or_compile(state, shader)
+        } else if self.failed.contains(&key) {
+            None
+        } else {
+            if !self.compilation_queue.iter().any(|s| s.key() as u64 == key)
+                && !self.in_flight.iter().any(|(s, _)| s.key() as u64 == key)
+            {
+                self.compilation_queue.push_back(shader.clone());
+            }
+
+            if self.placeholder.is_none() {
+                if let ResourceState::Ok(ref placeholder_state) = *Shader::placeholder().state() {
+                    self.placeholder = ShaderSet::new(state, placeholder_state);
+                }
+            }
+
+            self.placeholder.as_ref()
+        }
+    }
+
+    /// Returns a shader set of the given shader, compiles the shader immediately if needed.
+    pub fn get_or_compile(
+        &mut self,
+        state: &mut PipelineState,
+        shader: &Shader,
+    ) -> Option<&ShaderSet> {
+        scope_profile!();
+
         let key = shader.key();
         let shader = shader.state();
 
         if let ResourceState::Ok(shader_state) = shader.deref() {
-            if self.buffer.is_index_valid(&shader_state.cache_index) {
+            if self.is_cached(shader_state, key as u64) {
                 let entry = self.buffer.get_mut(&shader_state.cache_index).unwrap();
 
                 // ShaderSet won't be destroyed while it used.
@@ -99,6 +326,98 @@ impl ShaderCache {
         }
     }
 
+    pub fn set_compilation_budget(&mut self, budget: Option<Duration>) {
+        self.compilation_budget = budget;
+    }
+
+    pub fn compilation_budget(&self) -> Option<Duration> {
+        self.compilation_budget
+    }
+
+    /// Returns amount of shaders waiting for compilation or being compiled.
+    pub fn compilation_queue_len(&self) -> usize {
+        self.compilation_queue.len() + self.in_flight.len()
+    }
+
+    fn insert(&mut self, shader: &Shader, shader_set: ShaderSet) {
+        if let ResourceState::Ok(ref shader_state) = *shader.state() {
+            let index = self.buffer.spawn(CacheEntry {
+                value: shader_set,
+                time_to_live: DEFAULT_RESOURCE_LIFETIME,
+                value_hash: shader.key() as u64,
+            });
+            shader_state.cache_index.set(index.get());
+        }
+    }
+
+    /// Processes queued shaders. If the driver supports parallel compilation, every queued shader
+    /// is sent to the driver, that compiles it on its own threads, and every shader which
+    /// compilation is finished is swapped in. Otherwise queued shaders are compiled on the calling
+    /// thread until the compilation budget is exceeded. At least one shader is compiled per call,
+    /// so the queue will be eventually processed even with zero budget.
+    pub fn process_compilation_queue(
+        &mut self,
+        state: &mut PipelineState,
+    ) -> Vec<ShaderCompilationEvent> {
+        scope_profile!();
+
+        let mut events = Vec::new();
+
+        if state.is_parallel_shader_compile_supported() {
+            while let Some(shader) = self.compilation_queue.pop_front() {
+                let pending = match *shader.state() {
+                    ResourceState::Ok(ref shader_state) => {
+                        PendingShaderSet::begin(state, shader_state)
+                    }
+                    _ => None,
+                };
+                match pending {
+                    Some(pending) => self.in_flight.push((shader, pending)),
+                    None => {
+                        self.failed.insert(shader.key() as u64);
+                        events.push(ShaderCompilationEvent::Failed(shader));
+                    }
+                }
+            }
+
+            let mut i = 0;
+            while i < self.in_flight.len() {
+                if self.in_flight[i].1.is_completed(state) {
+                    let (shader, pending) = self.in_flight.remove(i);
+                    if let Some(shader_set) = pending.finish(state) {
+                        self.insert(&shader, shader_set);
+                        events.push(ShaderCompilationEvent::Compiled(shader));
+                    } else {
+                        self.failed.insert(shader.key() as u64);
+                        events.push(ShaderCompilationEvent::Failed(shader));
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+
+            return events;
+        }
+
+        let budget = self.compilation_budget.unwrap_or_default();
+        let start = instant::Instant::now();
+
+        while let Some(shader) = self.compilation_queue.pop_front() {
+            if self.get_or_compile(state, &shader).is_some() {
+                events.push(ShaderCompilationEvent::Compiled(shader));
+            } else {
+                self.failed.insert(shader.key() as u64);
+                events.push(ShaderCompilationEvent::Failed(shader));
+            }
+
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+
+        events
+    }
+
     pub fn update(&mut self, dt: f32) {
         scope_profile!();
 
@@ -117,5 +436,9 @@ impl ShaderCache {
 
     pub fn clear(&mut self) {
         self.buffer.clear();
+        self.compilation_queue.clear();
+        self.in_flight.clear();
+        self.failed.clear();
+        self.placeholder = None;
     }
 }
@@ -3,7 +3,7 @@ use crate::{
     core::scope_profile,
     engine::resource_manager::container::entry::DEFAULT_RESOURCE_LIFETIME,
     renderer::{
-        cache::CacheEntry,
+        cache::{texture_array::TextureArrayCache, CacheEntry},
         framework::{
             gpu_texture::{Coordinate, GpuTexture, PixelKind},
             state::PipelineState,
@@ -18,6 +18,8 @@ use std::{cell::RefCell, collections::hash_map::Entry, ops::Deref, rc::Rc};
 #[derive(Default)]
 pub struct TextureCache {
     pub(crate) map: FxHashMap<usize, CacheEntry<Rc<RefCell<GpuTexture>>>>,
+    /// Textures that are sampled from texture arrays.
+    pub arrays: TextureArrayCache,
 }
 
 impl TextureCache {
@@ -185,13 +187,26 @@ impl TextureCache {
         }
 
         self.map.retain(|_, v| v.time_to_live > 0.0);
+
+        self.arrays.update(dt);
     }
 
     pub fn clear(&mut self) {
         self.map.clear();
+        self.arrays.clear();
+    }
+
+    /// Returns an estimate of the amount of video memory (in bytes) used by the cached textures.
+    pub fn size_bytes(&self) -> usize {
+        self.map
+            .values()
+            .map(|entry| entry.borrow().size_bytes())
+            .sum::<usize>()
+            + self.arrays.size_bytes()
     }
 
     pub fn unload(&mut self, texture: Texture) {
         self.map.remove(&texture.key());
+        self.arrays.unload(texture.key());
     }
 }
@@ -0,0 +1,509 @@
+//! Texture array cache packs material textures of the same size, format and sampling parameters
+//! into shared GPU texture arrays. Materials that sample from such arrays differ only in a layer
+//! index, so switching between them re-binds the same texture array, which is cheaper for the
+//! driver than binding a separate texture per material. Draw calls of different materials are
+//! not merged, every material is still rendered with its own draw call.
+//!
+//! Packing is opt-in and driven by shaders: a material texture is packed only if the shader
+//! declares its sampler as `sampler2DArray`. Built-in shaders (standard, terrain, etc.) use
+//! plain `sampler2D` samplers, so only custom shaders benefit from packing. Layer index of the
+//! texture is passed in an integer uniform with `Layer` suffix (see [`layer_uniform_name`]), for
+//! example:
+//!
+//! ```glsl
+//! uniform sampler2DArray diffuseTexture;
+//! uniform int diffuseTextureLayer;
+//!
+//! ...
+//!
+//! vec4 diffuse = texture(diffuseTexture, vec3(texCoord, float(diffuseTextureLayer)));
+//! ```
+
+use crate::{
+    core::scope_profile,
+    engine::resource_manager::container::entry::DEFAULT_RESOURCE_LIFETIME,
+    renderer::{
+        cache::CacheEntry,
+        framework::{
+            error::FrameworkError,
+            gpu_texture::{
+                mip_chain_size_bytes, Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter,
+                MinificationFilter, PixelKind,
+            },
+            state::PipelineState,
+        },
+    },
+    resource::texture::{Texture, TextureData, TextureKind, TextureState},
+    utils::log::{Log, MessageKind},
+};
+use fxhash::FxHashMap;
+use std::{cell::RefCell, ops::Deref, rc::Rc};
+
+/// Amount of memory (in bytes) that a single texture array page tries to fit in.
+pub const PAGE_SIZE_BUDGET: usize = 64 * 1024 * 1024;
+
+/// Maximum amount of layers in a single texture array page. OpenGL guarantees that at least
+/// 256 layers are supported, but smaller pages waste less memory.
+pub const MAX_LAYERS_PER_PAGE: usize = 64;
+
+/// A set of texture properties that must match for textures to be packed into the same
+/// texture array.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct TextureArrayKey {
+    /// Width of every layer.
+    pub width: u32,
+    /// Height of every layer.
+    pub height: u32,
+    /// Pixel kind of every layer.
+    pub pixel_kind: u32,
+    /// Amount of mips of every layer.
+    pub mip_count: u32,
+    /// Minification filter of the array.
+    pub min_filter: u32,
+    /// Magnification filter of the array.
+    pub mag_filter: u32,
+    /// Wrap mode on S axis.
+    pub s_wrap_mode: u32,
+    /// Wrap mode on T axis.
+    pub t_wrap_mode: u32,
+}
+
+impl TextureArrayKey {
+    /// Creates a key for the given texture. Only rectangle textures can be packed into arrays,
+    /// `None` is returned for any other kind.
+    pub fn from_texture(texture: &TextureData) -> Option<Self> {
+        if let TextureKind::Rectangle { width, height } = texture.kind() {
+            Some(Self {
+                width,
+                height,
+                pixel_kind: texture.pixel_kind() as u32,
+                mip_count: texture.mip_count(),
+                min_filter: texture.minification_filter() as u32,
+                mag_filter: texture.magnification_filter() as u32,
+                s_wrap_mode: texture.s_wrap_mode() as u32,
+                t_wrap_mode: texture.t_wrap_mode() as u32,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns a name of the uniform, that holds layer index of a texture bound to the texture array
+/// sampler with the given name.
+pub fn layer_uniform_name(sampler_name: &str) -> String {
+    format!("{}Layer", sampler_name)
+}
+
+/// Returns amount of layers for a texture array page with layers of the given size.
+pub fn layers_per_page(layer_size_bytes: usize) -> usize {
+    (PAGE_SIZE_BUDGET / layer_size_bytes.max(1)).clamp(1, MAX_LAYERS_PER_PAGE)
+}
+
+/// Simple allocator of texture array layers. Freed layers are reused first.
+#[derive(Debug)]
+pub struct LayerAllocator {
+    capacity: usize,
+    next: usize,
+    free: Vec<usize>,
+}
+
+impl LayerAllocator {
+    /// Creates new allocator for the given amount of layers.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            next: 0,
+            free: Default::default(),
+        }
+    }
+
+    /// Tries to allocate a layer. Returns `None` if there is no free layers.
+    pub fn allocate(&mut self) -> Option<usize> {
+        if let Some(layer) = self.free.pop() {
+            Some(layer)
+        } else if self.next < self.capacity {
+            self.next += 1;
+            Some(self.next - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the layer back to the allocator.
+    pub fn free(&mut self, layer: usize) {
+        debug_assert!(layer < self.next && !self.free.contains(&layer));
+        self.free.push(layer);
+    }
+
+    /// Returns total amount of layers.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns amount of allocated layers.
+    pub fn used(&self) -> usize {
+        self.next - self.free.len()
+    }
+}
+
+/// A layer of a texture array that holds some texture.
+#[derive(Clone)]
+pub struct TextureArrayLayer {
+    /// A texture array.
+    pub array: Rc<RefCell<GpuTexture>>,
+    /// Index of the layer in the array.
+    pub layer: usize,
+}
+
+struct TextureArrayPage {
+    array: Rc<RefCell<GpuTexture>>,
+    allocator: LayerAllocator,
+}
+
+struct Resident {
+    page: usize,
+    layer: usize,
+}
+
+#[derive(Default)]
+struct TextureArrayGroup {
+    pages: Vec<TextureArrayPage>,
+    residents: FxHashMap<usize, CacheEntry<Resident>>,
+}
+
+impl TextureArrayGroup {
+    fn allocate(
+        &mut self,
+        state: &mut PipelineState,
+        texture: &TextureData,
+    ) -> Result<(usize, usize), FrameworkError> {
+        for (page_index, page) in self.pages.iter_mut().enumerate() {
+            if let Some(layer) = page.allocator.allocate() {
+                return Ok((page_index, layer));
+            }
+        }
+
+        let (width, height) = match texture.kind() {
+            TextureKind::Rectangle { width, height } => (width as usize, height as usize),
+            _ => unreachable!(),
+        };
+        let pixel_kind = PixelKind::from(texture.pixel_kind());
+        let mip_count = texture.mip_count() as usize;
+
+        let layers = layers_per_page(mip_chain_size_bytes(
+            GpuTextureKind::Rectangle { width, height },
+            pixel_kind,
+            mip_count,
+        ));
+        let kind = GpuTextureKind::RectangleArray {
+            width,
+            height,
+            layers,
+        };
+
+        // Compressed textures cannot be created without data.
+        let initial_data = if pixel_kind.is_compressed() {
+            Some(vec![0; mip_chain_size_bytes(kind, pixel_kind, mip_count)])
+        } else {
+            None
+        };
+
+        let mut array = GpuTexture::new(
+            state,
+            kind,
+            pixel_kind,
+            texture.minification_filter().into(),
+            texture.magnification_filter().into(),
+            mip_count,
+            initial_data.as_deref(),
+        )?;
+        array
+            .bind_mut(state, 0)
+            .set_wrap(Coordinate::S, texture.s_wrap_mode().into())
+            .set_wrap(Coordinate::T, texture.t_wrap_mode().into())
+            .set_anisotropy(texture.anisotropy_level());
+
+        let mut allocator = LayerAllocator::new(layers);
+        let layer = allocator.allocate().unwrap();
+        self.pages.push(TextureArrayPage {
+            array: Rc::new(RefCell::new(array)),
+            allocator,
+        });
+
+        Ok((self.pages.len() - 1, layer))
+    }
+}
+
+/// See module docs.
+#[derive(Default)]
+pub struct TextureArrayCache {
+    groups: FxHashMap<TextureArrayKey, TextureArrayGroup>,
+    fallbacks: FxHashMap<[u8; 4], Rc<RefCell<GpuTexture>>>,
+}
+
+impl TextureArrayCache {
+    /// Returns a layer of a texture array that holds the given texture. The texture will be
+    /// packed into an array if it is not resident yet. Returns `None` if the texture is not
+    /// loaded or cannot be packed.
+    pub fn get(
+        &mut self,
+        state: &mut PipelineState,
+        texture: &Texture,
+    ) -> Option<TextureArrayLayer> {
+        scope_profile!();
+
+        let texture_key = texture.key();
+        let texture = texture.state();
+
+        if let TextureState::Ok(texture) = texture.deref() {
+            let group = self
+                .groups
+                .entry(TextureArrayKey::from_texture(texture)?)
+                .or_default();
+
+            let data_hash = texture.data_hash();
+
+            let needs_upload = if let Some(resident) = group.residents.get_mut(&texture_key) {
+                resident.time_to_live = DEFAULT_RESOURCE_LIFETIME;
+                resident.value_hash != data_hash
+            } else {
+                match group.allocate(state, texture) {
+                    Ok((page, layer)) => {
+                        group.residents.insert(
+                            texture_key,
+                            CacheEntry {
+                                value: Resident { page, layer },
+                                value_hash: data_hash,
+                                time_to_live: DEFAULT_RESOURCE_LIFETIME,
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        Log::writeln(
+                            MessageKind::Error,
+                            format!("Failed to create GPU texture array. Reason: {:?}", e),
+                        );
+                        return None;
+                    }
+                }
+                true
+            };
+
+            let resident = group.residents.get_mut(&texture_key)?;
+            let page = &group.pages[resident.page];
+
+            if needs_upload {
+                if let Err(e) = page.array.borrow_mut().bind_mut(state, 0).set_layer_data(
+                    resident.layer,
+                    texture.mip_count() as usize,
+                    texture.data(),
+                ) {
+                    Log::writeln(
+                        MessageKind::Error,
+                        format!(
+                            "Unable to upload texture data to texture array layer. Reason: {:?}",
+                            e
+                        ),
+                    );
+                } else {
+                    resident.value_hash = data_hash;
+                }
+            }
+
+            Some(TextureArrayLayer {
+                array: page.array.clone(),
+                layer: resident.layer,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a single-layer 1x1 texture array filled with the given color. It is used when
+    /// a material has no texture for a texture array sampler.
+    pub fn fallback(
+        &mut self,
+        state: &mut PipelineState,
+        color: [u8; 4],
+    ) -> Option<Rc<RefCell<GpuTexture>>> {
+        if let Some(fallback) = self.fallbacks.get(&color) {
+            return Some(fallback.clone());
+        }
+
+        match GpuTexture::new(
+            state,
+            GpuTextureKind::RectangleArray {
+                width: 1,
+                height: 1,
+                layers: 1,
+            },
+            PixelKind::RGBA8,
+            MinificationFilter::Linear,
+            MagnificationFilter::Linear,
+            1,
+            Some(&color),
+        ) {
+            Ok(texture) => {
+                let texture = Rc::new(RefCell::new(texture));
+                self.fallbacks.insert(color, texture.clone());
+                Some(texture)
+            }
+            Err(e) => {
+                Log::writeln(
+                    MessageKind::Error,
+                    format!("Failed to create fallback texture array. Reason: {:?}", e),
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns total amount of texture array pages.
+    pub fn page_count(&self) -> usize {
+        self.groups.values().map(|g| g.pages.len()).sum()
+    }
+
+    /// Returns total amount of textures packed into arrays.
+    pub fn resident_count(&self) -> usize {
+        self.groups.values().map(|g| g.residents.len()).sum()
+    }
+
+    pub(crate) fn update(&mut self, dt: f32) {
+        scope_profile!();
+
+        for group in self.groups.values_mut() {
+            let pages = &mut group.pages;
+            group.residents.retain(|_, resident| {
+                resident.time_to_live -= dt;
+                if resident.time_to_live > 0.0 {
+                    true
+                } else {
+                    pages[resident.page].allocator.free(resident.layer);
+                    false
+                }
+            });
+
+            // Pages are addressed by index, so only trailing empty pages can be removed.
+            while group
+                .pages
+                .last()
+                .map_or(false, |page| page.allocator.used() == 0)
+            {
+                group.pages.pop();
+            }
+        }
+
+        self.groups.retain(|_, group| !group.pages.is_empty());
+    }
+
+    pub(crate) fn unload(&mut self, texture_key: usize) {
+        for group in self.groups.values_mut() {
+            if let Some(resident) = group.residents.remove(&texture_key) {
+                group.pages[resident.page].allocator.free(resident.layer);
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.groups.clear();
+        self.fallbacks.clear();
+    }
+
+    /// Returns an estimate of the amount of video memory (in bytes) used by the texture arrays.
+    pub fn size_bytes(&self) -> usize {
+        self.groups
+            .values()
+            .flat_map(|group| group.pages.iter())
+            .map(|page| page.array.borrow().size_bytes())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        renderer::cache::texture_array::{
+            layer_uniform_name, layers_per_page, LayerAllocator, TextureArrayKey,
+            MAX_LAYERS_PER_PAGE,
+        },
+        resource::texture::{TextureData, TextureKind, TexturePixelKind, TextureWrapMode},
+    };
+
+    fn make_texture(kind: TextureKind, size: usize) -> TextureData {
+        TextureData::from_bytes(kind, TexturePixelKind::RGBA8, vec![0; size * 4], false).unwrap()
+    }
+
+    #[test]
+    fn test_layer_allocator() {
+        let mut allocator = LayerAllocator::new(3);
+
+        assert_eq!(allocator.allocate(), Some(0));
+        assert_eq!(allocator.allocate(), Some(1));
+        assert_eq!(allocator.allocate(), Some(2));
+        assert_eq!(allocator.allocate(), None);
+        assert_eq!(allocator.used(), 3);
+
+        allocator.free(1);
+        assert_eq!(allocator.used(), 2);
+        assert_eq!(allocator.allocate(), Some(1));
+        assert_eq!(allocator.allocate(), None);
+        assert_eq!(allocator.capacity(), 3);
+    }
+
+    #[test]
+    fn test_layers_per_page() {
+        // Small textures are limited by max layer count.
+        assert_eq!(layers_per_page(64 * 64 * 4), MAX_LAYERS_PER_PAGE);
+        // 2048x2048 RGBA8 without mips - 16 Mb.
+        assert_eq!(layers_per_page(2048 * 2048 * 4), 4);
+        // Huge textures still get at least one layer.
+        assert_eq!(layers_per_page(usize::MAX), 1);
+        assert_eq!(layers_per_page(0), MAX_LAYERS_PER_PAGE);
+    }
+
+    #[test]
+    fn test_texture_array_key() {
+        let rectangle = TextureKind::Rectangle {
+            width: 2,
+            height: 2,
+        };
+
+        let a = make_texture(rectangle, 4);
+        let mut b = make_texture(rectangle, 4);
+        let key = TextureArrayKey::from_texture(&a).unwrap();
+        assert_eq!(key.width, 2);
+        assert_eq!(key.height, 2);
+        // Textures with the same parameters are packed into the same array.
+        assert_eq!(Some(key), TextureArrayKey::from_texture(&b));
+
+        // Sampling parameters are shared by every layer of an array.
+        b.set_s_wrap_mode(TextureWrapMode::ClampToEdge);
+        assert_ne!(Some(key), TextureArrayKey::from_texture(&b));
+
+        let c = make_texture(
+            TextureKind::Rectangle {
+                width: 4,
+                height: 2,
+            },
+            8,
+        );
+        assert_ne!(Some(key), TextureArrayKey::from_texture(&c));
+
+        // Only rectangle textures could be packed.
+        let volume = make_texture(
+            TextureKind::Volume {
+                width: 2,
+                height: 2,
+                depth: 2,
+            },
+            8,
+        );
+        assert_eq!(TextureArrayKey::from_texture(&volume), None);
+    }
+
+    #[test]
+    fn test_layer_uniform_name() {
+        assert_eq!(layer_uniform_name("diffuseTexture"), "diffuseTextureLayer");
+    }
+}
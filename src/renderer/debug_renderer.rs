@@ -2,10 +2,18 @@
 //! in its name its purpose - output debug information. It can be used to render collision
 //! shapes, contact information (normals, positions, etc.), paths build by navmesh and so
 //! on. It contains implementations to draw most common shapes (line, box, oob, frustum, etc).
+//!
+//! Wide lines and points are expanded into screen-aligned quads in vertex shader, because support
+//! of line width and point size varies a lot between platforms.
 
 use crate::core::sstorage::ImmutableString;
 use crate::{
-    core::{algebra::Vector3, math::Rect, scope_profile},
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        color::Color,
+        math::{Rect, TriangleDefinition},
+        scope_profile,
+    },
     renderer::framework::{
         error::FrameworkError,
         framebuffer::{DrawParameters, FrameBuffer},
@@ -26,12 +34,26 @@ struct Vertex {
     color: u32,
 }
 
+#[repr(C)]
+struct WideVertex {
+    begin: Vector3<f32>,
+    end: Vector3<f32>,
+    color: u32,
+    // x - interpolation factor between begin and end, yz - corner offset, w - size in pixels.
+    params: Vector4<f32>,
+    is_point: f32,
+}
+
 /// See module docs.
 pub struct DebugRenderer {
     geometry: GeometryBuffer,
     vertices: Vec<Vertex>,
     line_indices: Vec<[u32; 2]>,
     shader: DebugShader,
+    wide_geometry: GeometryBuffer,
+    wide_vertices: Vec<WideVertex>,
+    wide_triangles: Vec<TriangleDefinition>,
+    wide_shader: WideDebugShader,
 }
 
 pub(crate) struct DebugShader {
@@ -53,6 +75,46 @@ impl DebugShader {
     }
 }
 
+struct WideDebugShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    viewport_size: UniformLocation,
+}
+
+impl WideDebugShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/wide_debug_fs.glsl");
+        let vertex_source = include_str!("shaders/wide_debug_vs.glsl");
+        let program =
+            GpuProgram::from_source(state, "WideDebugShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            viewport_size: program
+                .uniform_location(state, &ImmutableString::new("viewportSize"))?,
+            program,
+        })
+    }
+}
+
+impl WideVertex {
+    fn corner(
+        begin: Vector3<f32>,
+        end: Vector3<f32>,
+        color: Color,
+        params: Vector4<f32>,
+        is_point: bool,
+    ) -> Self {
+        Self {
+            begin,
+            end,
+            color: color.into(),
+            params,
+            is_point: if is_point { 1.0 } else { 0.0 },
+        }
+    }
+}
+
 impl DebugRenderer {
     pub(crate) fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
         let geometry = GeometryBufferBuilder::new(ElementKind::Line)
@@ -73,14 +135,90 @@ impl DebugRenderer {
             )
             .build(state)?;
 
+        let wide_geometry = GeometryBufferBuilder::new(ElementKind::Triangle)
+            .with_buffer_builder(
+                BufferBuilder::new::<WideVertex>(GeometryBufferKind::DynamicDraw, None)
+                    .with_attribute(AttributeDefinition {
+                        location: 0,
+                        divisor: 0,
+                        kind: AttributeKind::Float3,
+                        normalized: false,
+                    })
+                    .with_attribute(AttributeDefinition {
+                        location: 1,
+                        divisor: 0,
+                        kind: AttributeKind::Float3,
+                        normalized: false,
+                    })
+                    .with_attribute(AttributeDefinition {
+                        location: 2,
+                        kind: AttributeKind::UnsignedByte4,
+                        normalized: true,
+                        divisor: 0,
+                    })
+                    .with_attribute(AttributeDefinition {
+                        location: 3,
+                        divisor: 0,
+                        kind: AttributeKind::Float4,
+                        normalized: false,
+                    })
+                    .with_attribute(AttributeDefinition {
+                        location: 4,
+                        divisor: 0,
+                        kind: AttributeKind::Float,
+                        normalized: false,
+                    }),
+            )
+            .build(state)?;
+
         Ok(Self {
             geometry,
             shader: DebugShader::new(state)?,
             vertices: Default::default(),
             line_indices: Default::default(),
+            wide_geometry,
+            wide_vertices: Default::default(),
+            wide_triangles: Default::default(),
+            wide_shader: WideDebugShader::new(state)?,
         })
     }
 
+    fn push_quad(&mut self, corners: [WideVertex; 4]) {
+        let first = self.wide_vertices.len() as u32;
+        self.wide_vertices.extend(corners);
+        self.wide_triangles
+            .push(TriangleDefinition([first, first + 1, first + 2]));
+        self.wide_triangles
+            .push(TriangleDefinition([first + 2, first + 3, first]));
+    }
+
+    fn prepare_wide_geometry(&mut self, drawing_context: &SceneDrawingContext) {
+        self.wide_vertices.clear();
+        self.wide_triangles.clear();
+
+        for line in drawing_context.wide_lines.iter() {
+            let (begin, end, color, w) = (line.begin, line.end, line.color, line.width);
+            // Each line is extended by half of its width on both ends, this way segments of
+            // polylines are joined without gaps.
+            self.push_quad([
+                WideVertex::corner(begin, end, color, Vector4::new(0.0, -1.0, -1.0, w), false),
+                WideVertex::corner(begin, end, color, Vector4::new(0.0, -1.0, 1.0, w), false),
+                WideVertex::corner(begin, end, color, Vector4::new(1.0, 1.0, 1.0, w), false),
+                WideVertex::corner(begin, end, color, Vector4::new(1.0, 1.0, -1.0, w), false),
+            ]);
+        }
+
+        for point in drawing_context.points.iter() {
+            let (p, color, s) = (point.position, point.color, point.size);
+            self.push_quad([
+                WideVertex::corner(p, p, color, Vector4::new(0.0, -1.0, -1.0, s), true),
+                WideVertex::corner(p, p, color, Vector4::new(0.0, -1.0, 1.0, s), true),
+                WideVertex::corner(p, p, color, Vector4::new(0.0, 1.0, 1.0, s), true),
+                WideVertex::corner(p, p, color, Vector4::new(0.0, 1.0, -1.0, s), true),
+            ]);
+        }
+    }
+
     pub(crate) fn render(
         &mut self,
         state: &mut PipelineState,
@@ -135,6 +273,42 @@ impl DebugRenderer {
 
         statistics.draw_calls += 1;
 
+        if !drawing_context.wide_lines.is_empty() || !drawing_context.points.is_empty() {
+            self.prepare_wide_geometry(drawing_context);
+
+            self.wide_geometry
+                .set_buffer_data(state, 0, &self.wide_vertices);
+            self.wide_geometry
+                .bind(state)
+                .set_triangles(&self.wide_triangles);
+
+            let viewport_size = Vector2::new(viewport.w() as f32, viewport.h() as f32);
+
+            statistics += framebuffer.draw(
+                &self.wide_geometry,
+                state,
+                viewport,
+                &self.wide_shader.program,
+                &DrawParameters {
+                    cull_face: None,
+                    color_write: Default::default(),
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: true,
+                    blend: None,
+                    stencil_op: Default::default(),
+                },
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(
+                            &self.wide_shader.wvp_matrix,
+                            &camera.view_projection_matrix(),
+                        )
+                        .set_vector2(&self.wide_shader.viewport_size, &viewport_size);
+                },
+            );
+        }
+
         statistics
     }
 }
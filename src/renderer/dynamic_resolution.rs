@@ -0,0 +1,205 @@
+//! Dynamic resolution scaling allows to keep frame rate stable by rendering 3D scenes at lower
+//! internal resolution when the frame takes too long to render. The frame is then upscaled to
+//! the native resolution, user interface is always rendered at native resolution.
+//!
+//! Render scale is changed in discrete steps and not more often than once per adjustment interval,
+//! because each change requires to re-create render targets of every scene.
+
+use crate::core::{algebra::Vector2, reflect::prelude::*};
+use serde::{Deserialize, Serialize};
+
+/// Settings of dynamic resolution scaling.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct DynamicResolutionSettings {
+    /// Whether the dynamic resolution is enabled or not. Scenes are rendered at native
+    /// resolution, if disabled.
+    pub enabled: bool,
+    /// Desired frame rate. The render scale will be decreased if frame rate is lower than that
+    /// and increased back if there is enough headroom.
+    pub target_fps: f32,
+    /// Minimum render scale, in `(0; 1]` range.
+    pub min_scale: f32,
+    /// Maximum render scale, in `(0; 1]` range.
+    pub max_scale: f32,
+    /// Granularity of render scale changes.
+    pub scale_step: f32,
+    /// Minimum time (in seconds) between two render scale changes.
+    pub adjustment_interval: f32,
+}
+
+impl Default for DynamicResolutionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_fps: 60.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            scale_step: 0.05,
+            adjustment_interval: 0.5,
+        }
+    }
+}
+
+/// Frame time controller for dynamic resolution, see module docs for more info.
+#[derive(Debug, Clone)]
+pub struct DynamicResolution {
+    settings: DynamicResolutionSettings,
+    scale: f32,
+    average_frame_time: f32,
+    time_since_adjustment: f32,
+}
+
+impl Default for DynamicResolution {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl DynamicResolution {
+    // Frame time must be this much longer than target frame time to decrease the scale.
+    const DECREASE_THRESHOLD: f32 = 1.05;
+    // Frame time must be this much shorter than target frame time to increase the scale. Gap
+    // between the thresholds prevents oscillations.
+    const INCREASE_THRESHOLD: f32 = 0.85;
+    // Smoothing factor of exponential moving average of frame time.
+    const SMOOTHING: f32 = 0.1;
+
+    /// Creates new controller with the given settings.
+    pub fn new(settings: DynamicResolutionSettings) -> Self {
+        Self {
+            scale: settings.max_scale,
+            settings,
+            average_frame_time: 0.0,
+            time_since_adjustment: 0.0,
+        }
+    }
+
+    /// Sets new settings, the scale is reset to the maximum one.
+    pub fn set_settings(&mut self, settings: DynamicResolutionSettings) {
+        *self = Self::new(settings);
+    }
+
+    /// Returns current settings.
+    pub fn settings(&self) -> &DynamicResolutionSettings {
+        &self.settings
+    }
+
+    /// Returns current render scale. It is always `1.0` if the dynamic resolution is disabled.
+    pub fn scale(&self) -> f32 {
+        if self.settings.enabled {
+            self.scale
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns smoothed frame time (in seconds) that is used to drive the scale.
+    pub fn average_frame_time(&self) -> f32 {
+        self.average_frame_time
+    }
+
+    /// Feeds the controller with the time (in seconds) of the last frame and adjusts the render
+    /// scale if needed.
+    pub fn update(&mut self, frame_time: f32) {
+        if !self.settings.enabled || self.settings.target_fps <= 0.0 {
+            return;
+        }
+
+        self.average_frame_time = if self.average_frame_time == 0.0 {
+            frame_time
+        } else {
+            self.average_frame_time + (frame_time - self.average_frame_time) * Self::SMOOTHING
+        };
+
+        self.time_since_adjustment += frame_time;
+        if self.time_since_adjustment < self.settings.adjustment_interval {
+            return;
+        }
+
+        let target_frame_time = 1.0 / self.settings.target_fps;
+        let new_scale = if self.average_frame_time > target_frame_time * Self::DECREASE_THRESHOLD {
+            self.scale - self.settings.scale_step
+        } else if self.average_frame_time < target_frame_time * Self::INCREASE_THRESHOLD {
+            self.scale + self.settings.scale_step
+        } else {
+            self.scale
+        };
+
+        let min_scale = self.settings.min_scale.clamp(0.1, 1.0);
+        let max_scale = self.settings.max_scale.clamp(min_scale, 1.0);
+        let new_scale = new_scale.clamp(min_scale, max_scale);
+        if new_scale != self.scale {
+            self.scale = new_scale;
+            self.time_since_adjustment = 0.0;
+        }
+    }
+
+    /// Calculates scaled size of a frame. Scaled size is at least 1x1 pixels.
+    pub fn scaled_size(&self, size: Vector2<f32>) -> Vector2<f32> {
+        (size * self.scale()).map(|c| c.round().max(1.0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        renderer::dynamic_resolution::{DynamicResolution, DynamicResolutionSettings},
+    };
+
+    fn settings() -> DynamicResolutionSettings {
+        DynamicResolutionSettings {
+            enabled: true,
+            target_fps: 50.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            scale_step: 0.1,
+            adjustment_interval: 0.1,
+        }
+    }
+
+    #[test]
+    fn test_disabled_dynamic_resolution_keeps_native_scale() {
+        let mut dr = DynamicResolution::new(DynamicResolutionSettings::default());
+        for _ in 0..100 {
+            dr.update(1.0);
+        }
+        assert_eq!(dr.scale(), 1.0);
+        assert_eq!(
+            dr.scaled_size(Vector2::new(1920.0, 1080.0)),
+            Vector2::new(1920.0, 1080.0)
+        );
+    }
+
+    #[test]
+    fn test_dynamic_resolution_scales_down_and_up() {
+        let mut dr = DynamicResolution::new(settings());
+
+        // 25 FPS - twice slower than desired.
+        for _ in 0..100 {
+            dr.update(0.04);
+        }
+        assert_eq!(dr.scale(), 0.5);
+        assert_eq!(
+            dr.scaled_size(Vector2::new(1920.0, 1080.0)),
+            Vector2::new(960.0, 540.0)
+        );
+
+        // 100 FPS - lots of headroom.
+        for _ in 0..200 {
+            dr.update(0.01);
+        }
+        assert_eq!(dr.scale(), 1.0);
+    }
+
+    #[test]
+    fn test_dynamic_resolution_is_stable_near_target() {
+        let mut dr = DynamicResolution::new(settings());
+
+        // 52 FPS - inside the dead zone, nothing must change.
+        for _ in 0..100 {
+            dr.update(1.0 / 52.0);
+        }
+        assert_eq!(dr.scale(), 1.0);
+    }
+}
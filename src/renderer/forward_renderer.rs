@@ -5,23 +5,35 @@
 //! This renderer eventually will replace deferred renderer, because deferred renderer is too restrictive.
 //! For now it is used **only** to render transparent meshes (or any other mesh that has Forward render
 //! path).
+//!
+//! Transparent surfaces could be rendered using order-independent transparency (see
+//! [`QualitySettings::use_order_independent_transparency`]), such surfaces are rendered after
+//! every other surface and then composed over the frame.
 
 use crate::core::sstorage::ImmutableString;
 use crate::{
-    core::{math::Rect, scope_profile},
+    core::{algebra::Matrix4, math::Rect, scope_profile},
     renderer::{
         apply_material,
-        batch::BatchStorage,
-        cache::{shader::ShaderCache, texture::TextureCache},
-        framework::{framebuffer::FrameBuffer, gpu_texture::GpuTexture, state::PipelineState},
+        batch::{BatchStorage, InstanceData},
+        cache::{geometry::INSTANCE_BUFFER_INDEX, shader::ShaderCache, texture::TextureCache},
+        framework::{
+            error::FrameworkError, framebuffer::FrameBuffer, gpu_texture::GpuTexture,
+            state::PipelineState,
+        },
+        light::clustered::ClusteredLighting,
+        occlusion::OcclusionTester,
+        oit::{self, OitRenderer},
         GeometryCache, MaterialContext, QualitySettings, RenderPassStatistics,
     },
     scene::{camera::Camera, mesh::RenderPath},
+    utils::log::Log,
 };
 use std::{cell::RefCell, rc::Rc};
 
 pub(crate) struct ForwardRenderer {
     render_pass_name: ImmutableString,
+    oit_renderer: OitRenderer,
 }
 
 pub(crate) struct ForwardRenderContext<'a, 'b> {
@@ -32,18 +44,24 @@ pub(crate) struct ForwardRenderContext<'a, 'b> {
     pub shader_cache: &'a mut ShaderCache,
     pub batch_storage: &'a BatchStorage,
     pub framebuffer: &'a mut FrameBuffer,
+    /// Created on demand, when there is at least one surface that should be rendered using
+    /// order-independent transparency.
+    pub oit_framebuffer: &'a mut Option<FrameBuffer>,
     pub viewport: Rect<i32>,
     pub quality_settings: &'a QualitySettings,
+    pub clustered_lighting: &'a ClusteredLighting,
     pub white_dummy: Rc<RefCell<GpuTexture>>,
     pub normal_dummy: Rc<RefCell<GpuTexture>>,
     pub black_dummy: Rc<RefCell<GpuTexture>>,
+    pub occlusion_tester: Option<&'a OcclusionTester>,
 }
 
 impl ForwardRenderer {
-    pub(crate) fn new() -> Self {
-        Self {
+    pub(crate) fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
             render_pass_name: ImmutableString::new("Forward"),
-        }
+            oit_renderer: OitRenderer::new(state)?,
+        })
     }
 
     pub(crate) fn render(&self, args: ForwardRenderContext) -> RenderPassStatistics {
@@ -59,55 +77,172 @@ impl ForwardRenderer {
             shader_cache,
             batch_storage,
             framebuffer,
+            oit_framebuffer,
             viewport,
             quality_settings,
+            clustered_lighting,
             white_dummy,
             normal_dummy,
             black_dummy,
+            occlusion_tester,
         } = args;
 
         let initial_view_projection = camera.view_projection_matrix();
 
-        for batch in batch_storage
-            .batches
-            .iter()
-            .filter(|b| b.render_path == RenderPath::Forward)
-        {
-            let material = batch.material.lock();
-            let geometry = geom_cache.get(state, &batch.data);
-
-            if let Some(render_pass) = shader_cache
-                .get(state, material.shader())
-                .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
+        let mut instance_data = Vec::new();
+
+        // Surfaces with sorted blending are rendered first, then the ones with order-independent
+        // transparency are accumulated in a separate frame buffer and composed over the frame.
+        let mut has_oit_batches = false;
+        for oit_pass in [false, true] {
+            if oit_pass {
+                if !has_oit_batches {
+                    break;
+                }
+
+                if oit_framebuffer.is_none() {
+                    match oit::make_oit_framebuffer(state, framebuffer) {
+                        Ok(new_framebuffer) => *oit_framebuffer = Some(new_framebuffer),
+                        Err(e) => {
+                            Log::err(format!(
+                                "Unable to create order-independent transparency frame buffer. \
+                                Reason: {:?}",
+                                e
+                            ));
+                            has_oit_batches = false;
+                            break;
+                        }
+                    }
+                }
+            }
+
+            let target = match oit_framebuffer.as_mut() {
+                Some(oit_framebuffer) if oit_pass => {
+                    oit::clear_oit_framebuffer(oit_framebuffer, state, viewport);
+                    oit_framebuffer
+                }
+                _ => &mut *framebuffer,
+            };
+
+            for batch in batch_storage
+                .batches
+                .iter()
+                .filter(|b| b.render_path == RenderPath::Forward)
             {
-                for instance in batch.instances.iter() {
-                    if camera.visibility_cache.is_visible(instance.owner) {
-                        let view_projection = if instance.depth_offset != 0.0 {
-                            let mut projection = camera.projection_matrix();
-                            projection[14] -= instance.depth_offset;
-                            projection * camera.view_matrix()
-                        } else {
-                            initial_view_projection
-                        };
-
-                        statistics += framebuffer.draw(
+                let material = batch.material.lock();
+                let geometry = geom_cache.get(state, &batch.data);
+
+                if let Some(render_pass) = shader_cache
+                    .get(state, material.shader())
+                    .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
+                {
+                    let draw_params = material.render_state().apply(&render_pass.draw_params);
+
+                    let oit_program = if material
+                        .render_state()
+                        .transparency
+                        .is_order_independent(quality_settings, &draw_params)
+                    {
+                        render_pass.oit_program(state)
+                    } else {
+                        None
+                    };
+                    if oit_program.is_some() != oit_pass {
+                        has_oit_batches |= oit_program.is_some();
+                        continue;
+                    }
+                    let (program, draw_params) = match oit_program.as_deref() {
+                        Some(oit_program) => (oit_program, oit::oit_draw_parameters(&draw_params)),
+                        None => (&render_pass.program, draw_params),
+                    };
+
+                    let use_instancing = batch.use_instancing && program.is_instancing_supported();
+
+                    instance_data.clear();
+
+                    for instance in batch.instances.iter() {
+                        if camera.visibility_cache.is_visible(instance.owner)
+                            && occlusion_tester
+                                .map_or(true, |tester| tester.is_visible(instance.owner))
+                        {
+                            if use_instancing && instance.is_instanceable() {
+                                instance_data.push(InstanceData {
+                                    world_matrix: instance.world_transform,
+                                    color: instance.color,
+                                });
+                                continue;
+                            }
+
+                            let view_projection = if instance.depth_offset != 0.0 {
+                                let mut projection = camera.projection_matrix();
+                                projection[14] -= instance.depth_offset;
+                                projection * camera.view_matrix()
+                            } else {
+                                initial_view_projection
+                            };
+
+                            statistics += target.draw(
+                                geometry,
+                                state,
+                                viewport,
+                                program,
+                                &draw_params,
+                                |mut program_binding| {
+                                    apply_material(MaterialContext {
+                                        material: &material,
+                                        property_block: instance.property_block.as_ref(),
+                                        program_binding: &mut program_binding,
+                                        texture_cache,
+                                        world_matrix: &instance.world_transform,
+                                        wvp_matrix: &(view_projection * instance.world_transform),
+                                        bone_matrices: &instance.bone_matrices,
+                                        bone_matrices_offset: instance.bone_matrices_offset,
+                                        bone_matrices_texture: batch_storage
+                                            .bone_matrices_texture(),
+                                        use_skeletal_animation: batch.is_skinned,
+                                        camera_position: &camera.global_position(),
+                                        use_pom: quality_settings.use_parallax_mapping,
+                                        light_position: &Default::default(),
+                                        opacity: instance.opacity,
+                                        clustered_lighting: Some(clustered_lighting),
+                                        use_instancing: false,
+                                        normal_dummy: normal_dummy.clone(),
+                                        white_dummy: white_dummy.clone(),
+                                        black_dummy: black_dummy.clone(),
+                                    });
+                                },
+                            );
+                        }
+                    }
+
+                    if !instance_data.is_empty() {
+                        geometry.set_buffer_data(state, INSTANCE_BUFFER_INDEX, &instance_data);
+
+                        statistics += target.draw_instances(
+                            instance_data.len(),
                             geometry,
                             state,
                             viewport,
-                            &render_pass.program,
-                            &render_pass.draw_params,
+                            program,
+                            &draw_params,
                             |mut program_binding| {
                                 apply_material(MaterialContext {
                                     material: &material,
+                                    property_block: None,
                                     program_binding: &mut program_binding,
                                     texture_cache,
-                                    world_matrix: &instance.world_transform,
-                                    wvp_matrix: &(view_projection * instance.world_transform),
-                                    bone_matrices: &instance.bone_matrices,
-                                    use_skeletal_animation: batch.is_skinned,
+                                    world_matrix: &Matrix4::identity(),
+                                    wvp_matrix: &initial_view_projection,
+                                    bone_matrices: &[],
+                                    bone_matrices_offset: 0,
+                                    bone_matrices_texture: None,
+                                    use_skeletal_animation: false,
                                     camera_position: &camera.global_position(),
                                     use_pom: quality_settings.use_parallax_mapping,
                                     light_position: &Default::default(),
+                                    opacity: 1.0,
+                                    clustered_lighting: Some(clustered_lighting),
+                                    use_instancing: true,
                                     normal_dummy: normal_dummy.clone(),
                                     white_dummy: white_dummy.clone(),
                                     black_dummy: black_dummy.clone(),
@@ -119,6 +254,14 @@ impl ForwardRenderer {
             }
         }
 
+        if has_oit_batches {
+            if let Some(oit_framebuffer) = oit_framebuffer.as_ref() {
+                statistics +=
+                    self.oit_renderer
+                        .compose(state, viewport, oit_framebuffer, framebuffer);
+            }
+        }
+
         statistics
     }
 }
@@ -0,0 +1,987 @@
+//! Frame graph (also known as render graph) is an explicit description of rendering passes of a
+//! frame and the resources they use. See [`FrameGraph`] docs for more info.
+//!
+//! The graph orders and culls passes, calculates lifetimes of resources and assigns GPU textures
+//! to transient textures, so textures with non-overlapping lifetimes share the same memory. GPU
+//! textures and frame buffers for them are owned by [`FrameGraphResourcePool`]. OpenGL does not
+//! need explicit barriers between a pass that renders to a texture and a pass that samples it, so
+//! the graph does not emit any.
+
+use crate::{
+    core::pool::{Handle, Pool},
+    renderer::framework::{
+        error::FrameworkError,
+        framebuffer::{Attachment, AttachmentKind, FrameBuffer},
+        gpu_texture::{
+            Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+            PixelKind, WrapMode,
+        },
+        state::PipelineState,
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, BTreeMap},
+    fmt::{Debug, Display, Formatter, Write},
+    rc::Rc,
+};
+
+/// A set of possible errors that may occur during frame graph compilation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameGraphError {
+    /// A resource is written by more than one pass. Every resource of a frame graph must have
+    /// exactly one producer, if you need to modify a resource in multiple passes, create a new
+    /// resource for each modification.
+    MultipleWriters {
+        /// Name of the resource.
+        resource: String,
+        /// Names of the passes that write the resource.
+        passes: Vec<String>,
+    },
+    /// A pass reads a transient resource that is not written by any pass.
+    UnwrittenResource {
+        /// Name of the resource.
+        resource: String,
+        /// Name of the pass that reads the resource.
+        pass: String,
+    },
+    /// A pass refers to a resource that does not belong to the graph.
+    InvalidResource {
+        /// Name of the pass.
+        pass: String,
+    },
+    /// Passes have cyclic dependencies, there is no valid execution order for them.
+    CycleDetected {
+        /// Names of the passes that form a cycle (or depend on a cycle).
+        passes: Vec<String>,
+    },
+}
+
+impl Display for FrameGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameGraphError::MultipleWriters { resource, passes } => {
+                write!(
+                    f,
+                    "Resource {resource} is written by multiple passes: {passes:?}"
+                )
+            }
+            FrameGraphError::UnwrittenResource { resource, pass } => {
+                write!(
+                    f,
+                    "Pass {pass} reads resource {resource}, but it is not written by any pass"
+                )
+            }
+            FrameGraphError::InvalidResource { pass } => {
+                write!(f, "Pass {pass} refers to an invalid resource")
+            }
+            FrameGraphError::CycleDetected { passes } => {
+                write!(f, "Cyclic dependency detected between passes: {passes:?}")
+            }
+        }
+    }
+}
+
+impl From<FrameGraphError> for FrameworkError {
+    fn from(e: FrameGraphError) -> Self {
+        FrameworkError::Custom(e.to_string())
+    }
+}
+
+/// Description of a transient texture of a frame graph. Transient textures always have the size
+/// of the frame, see [`FrameGraphResourcePool::new`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameGraphTextureDescriptor {
+    /// Pixel format of the texture.
+    pub pixel_kind: PixelKind,
+    /// Minification filter of the texture.
+    pub min_filter: MinificationFilter,
+    /// Magnification filter of the texture.
+    pub mag_filter: MagnificationFilter,
+}
+
+/// A virtual resource of a frame graph. It does not hold any GPU data, it is just a "name" that
+/// allows the graph to track dependencies between passes.
+#[derive(Debug)]
+pub struct FrameGraphResource {
+    name: String,
+    external: bool,
+    lifetime: Option<(usize, usize)>,
+    texture: Option<FrameGraphTextureDescriptor>,
+    slot: Option<usize>,
+}
+
+impl FrameGraphResource {
+    /// Returns name of the resource.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the resource is owned by something outside of the graph (for example a
+    /// back buffer or a render target of a scene). Writes to external resources are always
+    /// considered as "used", so passes that write them will never be culled.
+    pub fn is_external(&self) -> bool {
+        self.external
+    }
+
+    /// Returns a range of indices (inclusive) in the execution order in which the resource is used.
+    /// It could be used to reuse (alias) memory of transient resources whose lifetimes does not
+    /// overlap. Returns `None` if the graph is not compiled or the resource is not used by any
+    /// alive pass.
+    pub fn lifetime(&self) -> Option<(usize, usize)> {
+        self.lifetime
+    }
+
+    /// Returns description of the texture, if the resource is a transient texture (see
+    /// [`FrameGraph::add_texture`]).
+    pub fn texture_descriptor(&self) -> Option<FrameGraphTextureDescriptor> {
+        self.texture
+    }
+
+    /// Returns index of a GPU texture (see [`FrameGraph::texture_slots`]) that was assigned to the
+    /// transient texture on last compilation. Returns `None` if the resource is not a transient
+    /// texture or it is not used by any alive pass.
+    pub fn slot(&self) -> Option<usize> {
+        self.slot
+    }
+}
+
+/// A pass of a frame graph. It declares which resources it reads and writes, the actual work is
+/// defined by the payload and it is up to the user of the graph how to execute it.
+#[derive(Debug)]
+pub struct FrameGraphPass<P> {
+    name: String,
+    reads: Vec<Handle<FrameGraphResource>>,
+    writes: Vec<Handle<FrameGraphResource>>,
+    side_effects: bool,
+    culled: bool,
+    payload: P,
+}
+
+impl<P> FrameGraphPass<P> {
+    /// Returns name of the pass.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns a slice of resources that are read by the pass.
+    pub fn reads(&self) -> &[Handle<FrameGraphResource>] {
+        &self.reads
+    }
+
+    /// Returns a slice of resources that are written by the pass.
+    pub fn writes(&self) -> &[Handle<FrameGraphResource>] {
+        &self.writes
+    }
+
+    /// Returns `true` if the pass has side effects and must not be culled.
+    pub fn has_side_effects(&self) -> bool {
+        self.side_effects
+    }
+
+    /// Returns `true` if the pass was culled on last compilation, because none of its outputs are
+    /// used.
+    pub fn is_culled(&self) -> bool {
+        self.culled
+    }
+
+    /// Returns a reference to the payload of the pass.
+    pub fn payload(&self) -> &P {
+        &self.payload
+    }
+
+    /// Returns a reference to the payload of the pass.
+    pub fn payload_mut(&mut self) -> &mut P {
+        &mut self.payload
+    }
+}
+
+/// Allows you to build a pass for a frame graph in declarative manner.
+pub struct FrameGraphPassBuilder<P> {
+    name: String,
+    reads: Vec<Handle<FrameGraphResource>>,
+    writes: Vec<Handle<FrameGraphResource>>,
+    side_effects: bool,
+    payload: P,
+}
+
+impl<P: 'static> FrameGraphPassBuilder<P> {
+    /// Creates new pass builder with the given name and payload.
+    pub fn new<S: AsRef<str>>(name: S, payload: P) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            reads: Default::default(),
+            writes: Default::default(),
+            side_effects: false,
+            payload,
+        }
+    }
+
+    /// Declares that the pass reads the given resource.
+    pub fn with_read(mut self, resource: Handle<FrameGraphResource>) -> Self {
+        self.reads.push(resource);
+        self
+    }
+
+    /// Declares that the pass writes the given resource.
+    pub fn with_write(mut self, resource: Handle<FrameGraphResource>) -> Self {
+        self.writes.push(resource);
+        self
+    }
+
+    /// Marks the pass as pass with side effects, such passes will never be culled.
+    pub fn with_side_effects(mut self, side_effects: bool) -> Self {
+        self.side_effects = side_effects;
+        self
+    }
+
+    /// Adds the pass to the given graph.
+    pub fn build(self, graph: &mut FrameGraph<P>) -> Handle<FrameGraphPass<P>> {
+        graph.passes.spawn(FrameGraphPass {
+            name: self.name,
+            reads: self.reads,
+            writes: self.writes,
+            side_effects: self.side_effects,
+            culled: false,
+            payload: self.payload,
+        })
+    }
+}
+
+/// Frame graph is an explicit description of rendering passes of a frame and the resources they
+/// read and write. It allows you to:
+///
+/// - Define passes in any order - the graph will find correct execution order for them using
+///   dependencies between passes.
+/// - Cull passes which outputs are not used by any other pass.
+/// - Find lifetimes of transient resources and share GPU textures between transient textures
+///   which lifetimes do not overlap (see [`FrameGraphResourcePool`]).
+/// - Visualize the frame for debugging purposes (see [`FrameGraph::to_dot`]).
+///
+/// Each resource must be written by exactly one pass, if a pass modifies a resource, it should
+/// read the resource and write a new one (even if both resources share the same GPU memory).
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::renderer::framegraph::{FrameGraph, FrameGraphPassBuilder};
+///
+/// let mut graph = FrameGraph::new();
+///
+/// let gbuffer = graph.add_resource("GBuffer");
+/// let lit_frame = graph.add_resource("LitFrame");
+/// let frame = graph.import_resource("Backbuffer");
+///
+/// // Passes could be added in any order.
+/// FrameGraphPassBuilder::new("Lighting", 1)
+///     .with_read(gbuffer)
+///     .with_write(lit_frame)
+///     .build(&mut graph);
+/// FrameGraphPassBuilder::new("Blit", 2)
+///     .with_read(lit_frame)
+///     .with_write(frame)
+///     .build(&mut graph);
+/// FrameGraphPassBuilder::new("GBuffer", 0)
+///     .with_write(gbuffer)
+///     .build(&mut graph);
+///
+/// graph.compile().unwrap();
+///
+/// let order = graph
+///     .execution_order()
+///     .iter()
+///     .map(|p| *graph.pass(*p).payload())
+///     .collect::<Vec<_>>();
+/// assert_eq!(order, [0, 1, 2]);
+/// ```
+pub struct FrameGraph<P> {
+    resources: Pool<FrameGraphResource>,
+    passes: Pool<FrameGraphPass<P>>,
+    execution_order: Vec<Handle<FrameGraphPass<P>>>,
+    texture_slots: Vec<FrameGraphTextureDescriptor>,
+}
+
+impl<P: 'static> Default for FrameGraph<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: 'static> Debug for FrameGraph<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_dot())
+    }
+}
+
+impl<P: 'static> FrameGraph<P> {
+    /// Creates new empty frame graph.
+    pub fn new() -> Self {
+        Self {
+            resources: Pool::new(),
+            passes: Pool::new(),
+            execution_order: Default::default(),
+            texture_slots: Default::default(),
+        }
+    }
+
+    /// Adds a new transient resource to the graph. Transient resources live only within a frame.
+    pub fn add_resource<S: AsRef<str>>(&mut self, name: S) -> Handle<FrameGraphResource> {
+        self.resources.spawn(FrameGraphResource {
+            name: name.as_ref().to_owned(),
+            external: false,
+            lifetime: None,
+            texture: None,
+            slot: None,
+        })
+    }
+
+    /// Adds a new transient texture to the graph. Unlike [`Self::add_resource`], GPU memory for
+    /// the texture is managed by the graph: it is allocated by [`FrameGraphResourcePool`] and
+    /// shared with other transient textures with the same description, if their lifetimes do not
+    /// overlap.
+    pub fn add_texture<S: AsRef<str>>(
+        &mut self,
+        name: S,
+        descriptor: FrameGraphTextureDescriptor,
+    ) -> Handle<FrameGraphResource> {
+        self.resources.spawn(FrameGraphResource {
+            name: name.as_ref().to_owned(),
+            external: false,
+            lifetime: None,
+            texture: Some(descriptor),
+            slot: None,
+        })
+    }
+
+    /// Adds a new external resource to the graph. External resources are owned by something outside
+    /// of the graph, passes that write such resources will never be culled.
+    pub fn import_resource<S: AsRef<str>>(&mut self, name: S) -> Handle<FrameGraphResource> {
+        self.resources.spawn(FrameGraphResource {
+            name: name.as_ref().to_owned(),
+            external: true,
+            lifetime: None,
+            texture: None,
+            slot: None,
+        })
+    }
+
+    /// Returns a reference to a resource.
+    pub fn resource(&self, handle: Handle<FrameGraphResource>) -> &FrameGraphResource {
+        self.resources.borrow(handle)
+    }
+
+    /// Returns a reference to a pass.
+    pub fn pass(&self, handle: Handle<FrameGraphPass<P>>) -> &FrameGraphPass<P> {
+        self.passes.borrow(handle)
+    }
+
+    /// Returns a reference to a pass.
+    pub fn pass_mut(&mut self, handle: Handle<FrameGraphPass<P>>) -> &mut FrameGraphPass<P> {
+        self.passes.borrow_mut(handle)
+    }
+
+    /// Returns an iterator over every pass of the graph (including culled).
+    pub fn passes(&self) -> impl Iterator<Item = (Handle<FrameGraphPass<P>>, &FrameGraphPass<P>)> {
+        self.passes.pair_iter()
+    }
+
+    /// Returns execution order of the passes that was calculated on last [`Self::compile`] call.
+    /// Culled passes are not included.
+    pub fn execution_order(&self) -> &[Handle<FrameGraphPass<P>>] {
+        &self.execution_order
+    }
+
+    /// Returns descriptions of GPU textures that are needed to execute the graph, that was compiled
+    /// on last [`Self::compile`] call. Every transient texture refers to one of them by its slot
+    /// (see [`FrameGraphResource::slot`]).
+    pub fn texture_slots(&self) -> &[FrameGraphTextureDescriptor] {
+        &self.texture_slots
+    }
+
+    /// Removes every pass and resource from the graph.
+    pub fn clear(&mut self) {
+        self.resources.clear();
+        self.passes.clear();
+        self.execution_order.clear();
+        self.texture_slots.clear();
+    }
+
+    /// Validates the graph, culls unused passes, calculates execution order of the passes,
+    /// lifetimes of the resources and assigns GPU textures to transient textures.
+    pub fn compile(&mut self) -> Result<(), FrameGraphError> {
+        self.execution_order.clear();
+        self.texture_slots.clear();
+
+        // Find producer for each resource.
+        let mut writers = FxHashMap::<_, Vec<Handle<FrameGraphPass<P>>>>::default();
+        for (pass_handle, pass) in self.passes.pair_iter() {
+            for &resource in pass.reads.iter().chain(pass.writes.iter()) {
+                if !self.resources.is_valid_handle(resource) {
+                    return Err(FrameGraphError::InvalidResource {
+                        pass: pass.name.clone(),
+                    });
+                }
+            }
+
+            for &resource in pass.writes.iter() {
+                writers.entry(resource).or_default().push(pass_handle);
+            }
+        }
+
+        for (resource, passes) in writers.iter() {
+            if passes.len() > 1 {
+                return Err(FrameGraphError::MultipleWriters {
+                    resource: self.resources[*resource].name.clone(),
+                    passes: passes
+                        .iter()
+                        .map(|p| self.passes[*p].name.clone())
+                        .collect(),
+                });
+            }
+        }
+
+        let writer_of =
+            |resource: &Handle<FrameGraphResource>| -> Option<Handle<FrameGraphPass<P>>> {
+                writers.get(resource).map(|w| w[0])
+            };
+
+        // Cull passes which outputs are not used. Start from the passes which must be executed in
+        // any case and mark every pass they depend on as alive.
+        let mut alive = FxHashSet::default();
+        let mut stack = self
+            .passes
+            .pair_iter()
+            .filter(|(_, pass)| {
+                pass.side_effects || pass.writes.iter().any(|r| self.resources[*r].external)
+            })
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        while let Some(pass_handle) = stack.pop() {
+            if alive.insert(pass_handle) {
+                let pass = &self.passes[pass_handle];
+                for resource in pass.reads.iter() {
+                    match writer_of(resource) {
+                        Some(writer) => stack.push(writer),
+                        None => {
+                            if !self.resources[*resource].external {
+                                return Err(FrameGraphError::UnwrittenResource {
+                                    resource: self.resources[*resource].name.clone(),
+                                    pass: pass.name.clone(),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Sort alive passes topologically, ties are resolved using declaration order to make the
+        // order stable.
+        let mut dependencies = FxHashMap::default();
+        let mut dependants = FxHashMap::<_, Vec<_>>::default();
+        for &pass_handle in alive.iter() {
+            let mut pass_dependencies = FxHashSet::default();
+            for resource in self.passes[pass_handle].reads.iter() {
+                if let Some(writer) = writer_of(resource) {
+                    if writer != pass_handle && pass_dependencies.insert(writer) {
+                        dependants.entry(writer).or_default().push(pass_handle);
+                    }
+                }
+            }
+            dependencies.insert(pass_handle, pass_dependencies.len());
+        }
+
+        let mut ready = dependencies
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(handle, _)| (handle.index(), *handle))
+            .collect::<BTreeMap<_, _>>();
+        while let Some((&index, &pass_handle)) = ready.iter().next() {
+            ready.remove(&index);
+            self.execution_order.push(pass_handle);
+            if let Some(pass_dependants) = dependants.get(&pass_handle) {
+                for dependant in pass_dependants {
+                    let count = dependencies.get_mut(dependant).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.insert(dependant.index(), *dependant);
+                    }
+                }
+            }
+        }
+
+        if self.execution_order.len() != alive.len() {
+            let mut passes = alive
+                .iter()
+                .filter(|p| !self.execution_order.contains(p))
+                .map(|p| self.passes[*p].name.clone())
+                .collect::<Vec<_>>();
+            passes.sort();
+            self.execution_order.clear();
+            return Err(FrameGraphError::CycleDetected { passes });
+        }
+
+        for (pass_handle, pass) in self.passes.pair_iter_mut() {
+            pass.culled = !alive.contains(&pass_handle);
+        }
+
+        // Calculate lifetimes of the resources.
+        for resource in self.resources.iter_mut() {
+            resource.lifetime = None;
+        }
+        for (index, pass_handle) in self.execution_order.iter().enumerate() {
+            let pass = &self.passes[*pass_handle];
+            for resource in pass.reads.iter().chain(pass.writes.iter()) {
+                let lifetime = &mut self.resources[*resource].lifetime;
+                *lifetime = Some(match *lifetime {
+                    Some((first, last)) => (first.min(index), last.max(index)),
+                    None => (index, index),
+                });
+            }
+        }
+
+        // Assign GPU textures to transient textures. Textures are processed in order of their first
+        // use, a texture takes a slot with the same description, that is not used anymore.
+        let mut textures = self
+            .resources
+            .pair_iter()
+            .filter_map(
+                |(handle, resource)| match (resource.texture, resource.lifetime) {
+                    (Some(descriptor), Some(lifetime)) => Some((handle, descriptor, lifetime)),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>();
+        textures.sort_by_key(|(handle, _, (first, _))| (*first, handle.index()));
+        for resource in self.resources.iter_mut() {
+            resource.slot = None;
+        }
+        let mut slot_last_use = Vec::new();
+        for (handle, descriptor, (first, last)) in textures {
+            let free_slot = self
+                .texture_slots
+                .iter()
+                .zip(slot_last_use.iter())
+                .position(|(slot_descriptor, slot_last)| {
+                    *slot_descriptor == descriptor && *slot_last < first
+                });
+            let slot = match free_slot {
+                Some(slot) => {
+                    slot_last_use[slot] = last;
+                    slot
+                }
+                None => {
+                    self.texture_slots.push(descriptor);
+                    slot_last_use.push(last);
+                    self.texture_slots.len() - 1
+                }
+            };
+            self.resources[handle].slot = Some(slot);
+        }
+
+        Ok(())
+    }
+
+    /// Writes the graph in [DOT](https://graphviz.org/doc/info/lang.html) format. It could be
+    /// visualized by Graphviz or any online DOT viewer. Culled passes are drawn using dashed
+    /// lines, external resources are drawn with double borders.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph FrameGraph {{").unwrap();
+        writeln!(dot, "\trankdir=LR;").unwrap();
+
+        for (handle, resource) in self.resources.pair_iter() {
+            writeln!(
+                dot,
+                "\tr{} [label=\"{}\", shape=ellipse{}];",
+                handle.index(),
+                resource.name,
+                if resource.external {
+                    ", peripheries=2"
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+        }
+
+        for (handle, pass) in self.passes.pair_iter() {
+            let order = self
+                .execution_order
+                .iter()
+                .position(|p| *p == handle)
+                .map(|i| format!("#{} ", i))
+                .unwrap_or_default();
+
+            writeln!(
+                dot,
+                "\tp{} [label=\"{}{}\", shape=box{}];",
+                handle.index(),
+                order,
+                pass.name,
+                if pass.culled { ", style=dashed" } else { "" }
+            )
+            .unwrap();
+
+            for resource in pass.reads.iter() {
+                writeln!(dot, "\tr{} -> p{};", resource.index(), handle.index()).unwrap();
+            }
+
+            for resource in pass.writes.iter() {
+                writeln!(dot, "\tp{} -> r{};", handle.index(), resource.index()).unwrap();
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+
+        dot
+    }
+}
+
+/// Owns GPU textures and frame buffers of transient textures of a frame graph. GPU textures are
+/// created on demand and reused between frames, transient textures with non-overlapping lifetimes
+/// share the same GPU texture (see [`FrameGraph::texture_slots`]).
+///
+/// The pool could be used with any graph, that was compiled, but it is cheaper to use the same
+/// graph every frame: GPU textures and frame buffers are re-created only when texture slots of
+/// the graph change.
+pub struct FrameGraphResourcePool {
+    width: usize,
+    height: usize,
+    textures: Vec<(FrameGraphTextureDescriptor, Rc<RefCell<GpuTexture>>)>,
+    framebuffers: FxHashMap<Vec<usize>, FrameBuffer>,
+}
+
+impl FrameGraphResourcePool {
+    /// Creates new empty pool for frames of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            textures: Default::default(),
+            framebuffers: Default::default(),
+        }
+    }
+
+    /// Makes sure, that there is a GPU texture for every texture slot of the given graph. Must be
+    /// called before the graph is executed.
+    pub fn prepare<P: 'static>(
+        &mut self,
+        state: &mut PipelineState,
+        graph: &FrameGraph<P>,
+    ) -> Result<(), FrameworkError> {
+        let slots = graph.texture_slots();
+
+        if self.textures.len() == slots.len()
+            && self
+                .textures
+                .iter()
+                .zip(slots)
+                .all(|((descriptor, _), slot)| descriptor == slot)
+        {
+            return Ok(());
+        }
+
+        // Frame buffers refer textures by their slots, so they are not valid anymore.
+        self.framebuffers.clear();
+        self.textures.truncate(slots.len());
+
+        for (index, descriptor) in slots.iter().enumerate() {
+            if matches!(self.textures.get(index), Some((existing, _)) if existing == descriptor) {
+                continue;
+            }
+
+            let mut texture = GpuTexture::new(
+                state,
+                GpuTextureKind::Rectangle {
+                    width: self.width,
+                    height: self.height,
+                },
+                descriptor.pixel_kind,
+                descriptor.min_filter,
+                descriptor.mag_filter,
+                1,
+                None,
+            )?;
+            texture
+                .bind_mut(state, 0)
+                .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+                .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
+            let entry = (*descriptor, Rc::new(RefCell::new(texture)));
+            if index < self.textures.len() {
+                self.textures[index] = entry;
+            } else {
+                self.textures.push(entry);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns GPU texture of the given transient texture. Returns `None` if the resource is not a
+    /// transient texture, it was culled or the pool was not prepared for the graph.
+    pub fn texture<P: 'static>(
+        &self,
+        graph: &FrameGraph<P>,
+        resource: Handle<FrameGraphResource>,
+    ) -> Option<Rc<RefCell<GpuTexture>>> {
+        graph
+            .resource(resource)
+            .slot
+            .and_then(|slot| self.textures.get(slot))
+            .map(|(_, texture)| texture.clone())
+    }
+
+    /// Returns a frame buffer that renders to the given transient textures. Frame buffers are
+    /// created on first request and cached.
+    pub fn framebuffer<P: 'static>(
+        &mut self,
+        state: &mut PipelineState,
+        graph: &FrameGraph<P>,
+        color_attachments: &[Handle<FrameGraphResource>],
+    ) -> Result<&mut FrameBuffer, FrameworkError> {
+        let mut slots = Vec::with_capacity(color_attachments.len());
+        for &resource in color_attachments {
+            let resource = graph.resource(resource);
+            match resource.slot.filter(|slot| *slot < self.textures.len()) {
+                Some(slot) => slots.push(slot),
+                None => {
+                    return Err(FrameworkError::Custom(format!(
+                        "Resource {} does not have a GPU texture",
+                        resource.name
+                    )))
+                }
+            }
+        }
+
+        match self.framebuffers.entry(slots) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let color_attachments = entry
+                    .key()
+                    .iter()
+                    .map(|slot| Attachment {
+                        kind: AttachmentKind::Color,
+                        texture: self.textures[*slot].1.clone(),
+                    })
+                    .collect();
+                Ok(entry.insert(FrameBuffer::new(state, None, color_attachments)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::{
+        framegraph::{
+            FrameGraph, FrameGraphError, FrameGraphPassBuilder, FrameGraphTextureDescriptor,
+        },
+        framework::gpu_texture::{MagnificationFilter, MinificationFilter, PixelKind},
+        make_scene_frame_graph, QualitySettings, ScenePass,
+    };
+
+    fn order(graph: &FrameGraph<&'static str>) -> Vec<&'static str> {
+        graph
+            .execution_order()
+            .iter()
+            .map(|p| *graph.pass(*p).payload())
+            .collect()
+    }
+
+    #[test]
+    fn test_order_and_culling() {
+        let mut graph = FrameGraph::new();
+
+        let a = graph.add_resource("A");
+        let b = graph.add_resource("B");
+        let unused = graph.add_resource("Unused");
+        let output = graph.import_resource("Output");
+
+        FrameGraphPassBuilder::new("Final", "Final")
+            .with_read(b)
+            .with_write(output)
+            .build(&mut graph);
+        let culled = FrameGraphPassBuilder::new("Unused", "Unused")
+            .with_read(a)
+            .with_write(unused)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("Second", "Second")
+            .with_read(a)
+            .with_write(b)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("First", "First")
+            .with_write(a)
+            .build(&mut graph);
+
+        graph.compile().unwrap();
+
+        assert_eq!(order(&graph), ["First", "Second", "Final"]);
+        assert!(graph.pass(culled).is_culled());
+        assert_eq!(graph.resource(a).lifetime(), Some((0, 1)));
+        assert_eq!(graph.resource(output).lifetime(), Some((2, 2)));
+        assert_eq!(graph.resource(unused).lifetime(), None);
+    }
+
+    #[test]
+    fn test_side_effects() {
+        let mut graph = FrameGraph::new();
+
+        let a = graph.add_resource("A");
+
+        FrameGraphPassBuilder::new("Producer", "Producer")
+            .with_write(a)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("Readback", "Readback")
+            .with_read(a)
+            .with_side_effects(true)
+            .build(&mut graph);
+
+        graph.compile().unwrap();
+
+        assert_eq!(order(&graph), ["Producer", "Readback"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut graph = FrameGraph::new();
+        let a = graph.add_resource("A");
+        let b = graph.add_resource("B");
+        FrameGraphPassBuilder::new("P1", "P1")
+            .with_read(a)
+            .with_write(b)
+            .with_side_effects(true)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("P2", "P2")
+            .with_read(b)
+            .with_write(a)
+            .build(&mut graph);
+        assert_eq!(
+            graph.compile(),
+            Err(FrameGraphError::CycleDetected {
+                passes: vec!["P1".to_string(), "P2".to_string()]
+            })
+        );
+
+        graph.clear();
+        let a = graph.add_resource("A");
+        FrameGraphPassBuilder::new("P1", "P1")
+            .with_write(a)
+            .with_side_effects(true)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("P2", "P2")
+            .with_write(a)
+            .build(&mut graph);
+        assert!(matches!(
+            graph.compile(),
+            Err(FrameGraphError::MultipleWriters { .. })
+        ));
+
+        graph.clear();
+        let a = graph.add_resource("A");
+        FrameGraphPassBuilder::new("P1", "P1")
+            .with_read(a)
+            .with_side_effects(true)
+            .build(&mut graph);
+        assert!(matches!(
+            graph.compile(),
+            Err(FrameGraphError::UnwrittenResource { .. })
+        ));
+    }
+
+    #[test]
+    fn test_texture_aliasing() {
+        let descriptor = |pixel_kind| FrameGraphTextureDescriptor {
+            pixel_kind,
+            min_filter: MinificationFilter::Linear,
+            mag_filter: MagnificationFilter::Linear,
+        };
+
+        let mut graph = FrameGraph::new();
+
+        let a = graph.add_texture("A", descriptor(PixelKind::RGBA8));
+        let b = graph.add_texture("B", descriptor(PixelKind::RGBA8));
+        let c = graph.add_texture("C", descriptor(PixelKind::RGBA8));
+        let hdr = graph.add_texture("Hdr", descriptor(PixelKind::RGBA16F));
+        let unused = graph.add_texture("Unused", descriptor(PixelKind::RGBA8));
+        let output = graph.import_resource("Output");
+
+        FrameGraphPassBuilder::new("P1", "P1")
+            .with_write(a)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("P2", "P2")
+            .with_read(a)
+            .with_write(b)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("P3", "P3")
+            .with_read(b)
+            .with_write(hdr)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("P4", "P4")
+            .with_read(hdr)
+            .with_write(c)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("P5", "P5")
+            .with_read(c)
+            .with_write(output)
+            .build(&mut graph);
+        FrameGraphPassBuilder::new("Culled", "Culled")
+            .with_read(a)
+            .with_write(unused)
+            .build(&mut graph);
+
+        graph.compile().unwrap();
+
+        // A is not used after P2, so C shares its texture. B overlaps with both of them, Hdr has
+        // different format.
+        assert_eq!(graph.resource(a).slot(), Some(0));
+        assert_eq!(graph.resource(b).slot(), Some(1));
+        assert_eq!(graph.resource(c).slot(), Some(0));
+        assert_eq!(graph.resource(hdr).slot(), Some(2));
+        assert_eq!(graph.resource(unused).slot(), None);
+        assert_eq!(graph.resource(output).slot(), None);
+        assert_eq!(
+            graph.texture_slots(),
+            [
+                descriptor(PixelKind::RGBA8),
+                descriptor(PixelKind::RGBA8),
+                descriptor(PixelKind::RGBA16F)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scene_frame_graph() {
+        let scene_passes = |settings: &QualitySettings| {
+            let graph = make_scene_frame_graph(settings);
+            graph
+                .execution_order()
+                .iter()
+                .map(|p| *graph.pass(*p).payload())
+                .collect::<Vec<_>>()
+        };
+
+        let passes = scene_passes(&QualitySettings::high());
+        assert_eq!(passes.first(), Some(&ScenePass::GBuffer));
+        assert_eq!(passes.last(), Some(&ScenePass::CustomLdr));
+        assert!(passes.contains(&ScenePass::Bloom));
+        assert!(passes.contains(&ScenePass::Fxaa));
+        assert_eq!(
+            make_scene_frame_graph(&QualitySettings::high())
+                .texture_slots()
+                .len(),
+            1
+        );
+
+        // Bloom pass must be culled, because nothing uses its result.
+        let passes = scene_passes(&QualitySettings::low());
+        assert!(!passes.contains(&ScenePass::Bloom));
+        assert!(!passes.contains(&ScenePass::Fxaa));
+        assert!(make_scene_frame_graph(&QualitySettings::low())
+            .texture_slots()
+            .is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 //! Contains all possible errors that may occur during rendering, initialization of
 //! renderer structures, or GAPI.
 
+use crate::renderer::framework::gpu_texture::PixelKind;
 use std::ffi::NulError;
 use std::fmt::{Display, Formatter};
 
@@ -54,6 +55,20 @@ pub enum FrameworkError {
     InvalidFrameBuffer,
     /// OpenGL failed to construct framebuffer.
     FailedToConstructFBO,
+    /// Compute shaders (and shader storage buffers) are not supported by current OpenGL context.
+    ComputeShadersUnsupported,
+    /// Means that you tried to read or write a range of a GPU buffer, that is out of its bounds.
+    InvalidBufferRange {
+        /// Offset of the range in bytes.
+        offset: usize,
+        /// Size of the range in bytes.
+        size: usize,
+        /// Total size of the buffer in bytes.
+        total: usize,
+    },
+    /// Means that you tried to bind a texture as an image, but its pixel kind cannot be used for
+    /// image load/store operations.
+    UnsupportedImageFormat(PixelKind),
     /// Custom error. Usually used for internal errors.
     Custom(String),
 }
@@ -121,6 +136,29 @@ impl Display for FrameworkError {
             FrameworkError::FailedToConstructFBO => {
                 write!(f, "OpenGL failed to construct framebuffer.")
             }
+            FrameworkError::ComputeShadersUnsupported => {
+                write!(
+                    f,
+                    "Compute shaders are not supported, OpenGL 4.3 or OpenGL ES 3.1 is required."
+                )
+            }
+            FrameworkError::InvalidBufferRange {
+                offset,
+                size,
+                total,
+            } => {
+                write!(
+                    f,
+                    "Tried to access a range of GPU buffer that is out of its bounds: \
+                    offset: {offset}, size: {size}, total: {total}",
+                )
+            }
+            FrameworkError::UnsupportedImageFormat(pixel_kind) => {
+                write!(
+                    f,
+                    "Pixel kind {pixel_kind:?} cannot be used for image load/store operations."
+                )
+            }
             FrameworkError::Custom(v) => {
                 write!(f, "Custom error: {v}")
             }
@@ -1,6 +1,6 @@
 use crate::renderer::framework::state::BlendEquation;
 use crate::{
-    core::{color::Color, math::Rect, scope_profile, visitor::prelude::*},
+    core::{color::Color, math::Rect, reflect::prelude::*, scope_profile, visitor::prelude::*},
     renderer::framework::{
         error::FrameworkError,
         geometry_buffer::{DrawCallStatistics, GeometryBuffer},
@@ -12,6 +12,7 @@ use crate::{
 use glow::HasContext;
 use serde::Deserialize;
 use std::{cell::RefCell, rc::Rc};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 #[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Eq)]
 pub enum AttachmentKind {
@@ -32,7 +33,21 @@ pub struct FrameBuffer {
     color_attachments: Vec<Attachment>,
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Deserialize, Visit, Eq)]
+#[derive(
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Hash,
+    Debug,
+    Deserialize,
+    Visit,
+    Eq,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
 #[repr(u32)]
 pub enum CullFace {
     Back = glow::BACK,
@@ -45,7 +60,7 @@ impl Default for CullFace {
     }
 }
 
-#[derive(Deserialize, Default, Visit, Debug, PartialEq, Clone, Eq)]
+#[derive(Deserialize, Default, Visit, Debug, PartialEq, Clone, Eq, Reflect)]
 pub struct BlendParameters {
     pub func: BlendFunc,
     pub equation: BlendEquation,
@@ -104,6 +119,15 @@ unsafe fn set_attachment(state: &mut PipelineState, gl_attachment_kind: u32, tex
                 0,
             );
         }
+        GpuTextureKind::RectangleArray { .. } => {
+            state.gl.framebuffer_texture_layer(
+                glow::FRAMEBUFFER,
+                gl_attachment_kind,
+                Some(texture.id()),
+                0,
+                0,
+            );
+        }
         GpuTextureKind::Volume { .. } => {
             state.gl.framebuffer_texture_3d(
                 glow::FRAMEBUFFER,
@@ -215,6 +239,62 @@ impl FrameBuffer {
         self
     }
 
+    /// Reads RGBA8 pixels of the first color attachment in the given rectangle. Rows are returned
+    /// from bottom to top, as OpenGL stores them.
+    pub fn read_pixels(&self, state: &mut PipelineState, rect: Rect<i32>) -> Vec<u8> {
+        let mut pixels = vec![0u8; (rect.size.x.max(0) * rect.size.y.max(0) * 4) as usize];
+
+        state.set_framebuffer(self.id());
+
+        unsafe {
+            if self.fbo.is_some() {
+                state.gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            }
+            state.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            state.gl.read_pixels(
+                rect.position.x,
+                rect.position.y,
+                rect.size.x,
+                rect.size.y,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(&mut pixels),
+            );
+        }
+
+        pixels
+    }
+
+    /// Reads pixels of the first color attachment in the given rectangle as RGBA32F values. It is
+    /// intended to be used with floating-point attachments. Rows are returned from bottom to top,
+    /// as OpenGL stores them.
+    pub fn read_pixels_f32(&self, state: &mut PipelineState, rect: Rect<i32>) -> Vec<f32> {
+        let mut pixels = vec![0.0f32; (rect.size.x.max(0) * rect.size.y.max(0) * 4) as usize];
+
+        state.set_framebuffer(self.id());
+
+        unsafe {
+            if self.fbo.is_some() {
+                state.gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            }
+            state.gl.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
+            state.gl.read_pixels(
+                rect.position.x,
+                rect.position.y,
+                rect.size.x,
+                rect.size.y,
+                glow::RGBA,
+                glow::FLOAT,
+                glow::PixelPackData::Slice(std::slice::from_raw_parts_mut(
+                    pixels.as_mut_ptr() as *mut u8,
+                    pixels.len() * std::mem::size_of::<f32>(),
+                )),
+            );
+        }
+
+        pixels
+    }
+
     /// None is possible only for back buffer.
     pub fn id(&self) -> Option<glow::Framebuffer> {
         self.fbo
@@ -405,8 +485,10 @@ fn pre_draw<F: FnOnce(GpuProgramBinding<'_, '_>)>(
 impl Drop for FrameBuffer {
     fn drop(&mut self) {
         unsafe {
-            if let Some(id) = self.fbo {
-                (*self.state).gl.delete_framebuffer(id);
+            if !(*self.state).is_context_lost() {
+                if let Some(id) = self.fbo {
+                    (*self.state).gl.delete_framebuffer(id);
+                }
             }
         }
     }
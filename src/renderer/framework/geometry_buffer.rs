@@ -21,7 +21,9 @@ struct NativeBuffer {
 impl Drop for NativeBuffer {
     fn drop(&mut self) {
         unsafe {
-            (*self.state).gl.delete_buffer(self.id);
+            if !(*self.state).is_context_lost() {
+                (*self.state).gl.delete_buffer(self.id);
+            }
         }
     }
 }
@@ -335,6 +337,13 @@ impl GeometryBuffer {
         buffer.size_bytes = size;
     }
 
+    /// Returns an estimate of the amount of video memory (in bytes) used by the vertex and index
+    /// buffers.
+    pub fn size_bytes(&self) -> usize {
+        self.buffers.iter().map(|b| b.size_bytes).sum::<usize>()
+            + self.element_count.get() * self.element_kind.index_per_element() * size_of::<u32>()
+    }
+
     pub fn bind<'a>(&'a self, state: &'a mut PipelineState) -> GeometryBufferBinding<'a> {
         scope_profile!();
 
@@ -360,10 +369,12 @@ impl Drop for GeometryBuffer {
         unsafe {
             self.buffers.clear();
 
-            (*self.state).gl.delete_buffer(self.element_buffer_object);
-            (*self.state)
-                .gl
-                .delete_vertex_array(self.vertex_array_object);
+            if !(*self.state).is_context_lost() {
+                (*self.state).gl.delete_buffer(self.element_buffer_object);
+                (*self.state)
+                    .gl
+                    .delete_vertex_array(self.vertex_array_object);
+            }
         }
     }
 }
@@ -0,0 +1,211 @@
+//! GPU buffers, that could be accessed from shaders. Uniform buffers are read-only and have small
+//! size limit (usually 16-64 KiB), shader storage buffers could be read and written by shaders
+//! (including compute shaders, see [`crate::renderer::framework::gpu_program::GpuProgram::dispatch`])
+//! and have no practical size limit.
+//!
+//! Data layout of a buffer must match the layout of the respective block in a shader. Use `std140`
+//! layout for uniform blocks and `std430` layout for storage blocks, keep in mind that `vec3`
+//! members are aligned as `vec4` in both layouts.
+
+use crate::{
+    renderer::framework::{error::FrameworkError, state::PipelineState},
+    utils::array_as_u8_slice,
+};
+use glow::HasContext;
+use std::marker::PhantomData;
+
+/// Kind of a GPU buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BufferKind {
+    /// Uniform buffer (`uniform` blocks in shaders).
+    Uniform = glow::UNIFORM_BUFFER,
+    /// Shader storage buffer (`buffer` blocks in shaders). Requires compute shaders support, see
+    /// [`PipelineState::is_compute_supported`].
+    ShaderStorage = glow::SHADER_STORAGE_BUFFER,
+}
+
+/// A hint for the driver, that describes how the data of a buffer will be accessed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum BufferUsage {
+    /// The data is set once and used many times.
+    StaticDraw = glow::STATIC_DRAW,
+    /// The data is changed often and used many times.
+    DynamicDraw = glow::DYNAMIC_DRAW,
+    /// The data is changed every time it is used.
+    StreamDraw = glow::STREAM_DRAW,
+    /// The data is written by shaders and read back by the application.
+    DynamicRead = glow::DYNAMIC_READ,
+    /// The data is written and read only by shaders, for example state of GPU particles.
+    DynamicCopy = glow::DYNAMIC_COPY,
+}
+
+/// A buffer in video memory. See module docs for more info.
+pub struct GpuBuffer {
+    state: *mut PipelineState,
+    id: glow::Buffer,
+    kind: BufferKind,
+    usage: BufferUsage,
+    size: usize,
+    // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
+    thread_mark: PhantomData<*const u8>,
+}
+
+impl GpuBuffer {
+    /// Creates a new buffer of the given size in bytes. Contents of the buffer are undefined.
+    pub fn new(
+        state: &mut PipelineState,
+        kind: BufferKind,
+        usage: BufferUsage,
+        size: usize,
+    ) -> Result<Self, FrameworkError> {
+        if kind == BufferKind::ShaderStorage && !state.is_compute_supported() {
+            return Err(FrameworkError::ComputeShadersUnsupported);
+        }
+
+        unsafe {
+            let id = state.gl.create_buffer()?;
+            state.gl.bind_buffer(kind as u32, Some(id));
+            state
+                .gl
+                .buffer_data_size(kind as u32, size as i32, usage as u32);
+            state.gl.bind_buffer(kind as u32, None);
+
+            Ok(Self {
+                state,
+                id,
+                kind,
+                usage,
+                size,
+                thread_mark: PhantomData,
+            })
+        }
+    }
+
+    /// Creates a new buffer and fills it with the given data.
+    pub fn from_data<T: Copy>(
+        state: &mut PipelineState,
+        kind: BufferKind,
+        usage: BufferUsage,
+        data: &[T],
+    ) -> Result<Self, FrameworkError> {
+        let mut buffer = Self::new(state, kind, usage, 0)?;
+        buffer.set_data(state, data);
+        Ok(buffer)
+    }
+
+    /// Returns kind of the buffer.
+    pub fn kind(&self) -> BufferKind {
+        self.kind
+    }
+
+    /// Returns size of the buffer in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns internal OpenGL name of the buffer.
+    pub fn id(&self) -> glow::Buffer {
+        self.id
+    }
+
+    /// Replaces contents of the buffer with the given data, the buffer is resized if needed.
+    pub fn set_data<T: Copy>(&mut self, state: &mut PipelineState, data: &[T]) {
+        let bytes = array_as_u8_slice(data);
+        unsafe {
+            state.gl.bind_buffer(self.kind as u32, Some(self.id));
+            if bytes.len() == self.size {
+                state
+                    .gl
+                    .buffer_sub_data_u8_slice(self.kind as u32, 0, bytes);
+            } else {
+                state
+                    .gl
+                    .buffer_data_u8_slice(self.kind as u32, bytes, self.usage as u32);
+                self.size = bytes.len();
+            }
+            state.gl.bind_buffer(self.kind as u32, None);
+        }
+    }
+
+    /// Writes the given data to the buffer starting from the given offset in bytes. The buffer is
+    /// not resized, an error is returned if the data does not fit.
+    pub fn write_data<T: Copy>(
+        &mut self,
+        state: &mut PipelineState,
+        offset: usize,
+        data: &[T],
+    ) -> Result<(), FrameworkError> {
+        let bytes = array_as_u8_slice(data);
+        if offset + bytes.len() > self.size {
+            return Err(FrameworkError::InvalidBufferRange {
+                offset,
+                size: bytes.len(),
+                total: self.size,
+            });
+        }
+
+        unsafe {
+            state.gl.bind_buffer(self.kind as u32, Some(self.id));
+            state
+                .gl
+                .buffer_sub_data_u8_slice(self.kind as u32, offset as i32, bytes);
+            state.gl.bind_buffer(self.kind as u32, None);
+        }
+
+        Ok(())
+    }
+
+    /// Reads contents of the buffer starting from the given offset in bytes. This method stalls
+    /// the pipeline until every command that writes to the buffer is finished, so it should be
+    /// used sparingly. Do not forget to issue [`crate::renderer::framework::state::MemoryBarrier::BUFFER_UPDATE`]
+    /// barrier after a dispatch that writes to the buffer.
+    pub fn read_data<T: Copy>(
+        &self,
+        state: &mut PipelineState,
+        offset: usize,
+        data: &mut [T],
+    ) -> Result<(), FrameworkError> {
+        let size = std::mem::size_of::<T>() * data.len();
+        if offset + size > self.size {
+            return Err(FrameworkError::InvalidBufferRange {
+                offset,
+                size,
+                total: self.size,
+            });
+        }
+
+        unsafe {
+            // SAFETY: `T` is `Copy`, so it is safe to overwrite its bytes.
+            let bytes = std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, size);
+            state.gl.bind_buffer(self.kind as u32, Some(self.id));
+            state
+                .gl
+                .get_buffer_sub_data(self.kind as u32, offset as i32, bytes);
+            state.gl.bind_buffer(self.kind as u32, None);
+        }
+
+        Ok(())
+    }
+
+    /// Binds the buffer to an indexed binding point of its kind. Uniform and storage blocks of
+    /// shaders refer to buffers by the index of a binding point (`layout(binding = N)`).
+    pub fn bind_base(&self, state: &mut PipelineState, index: u32) {
+        unsafe {
+            state
+                .gl
+                .bind_buffer_base(self.kind as u32, index, Some(self.id));
+        }
+    }
+}
+
+impl Drop for GpuBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            if !(*self.state).is_context_lost() {
+                (*self.state).gl.delete_buffer(self.id);
+            }
+        }
+    }
+}
@@ -5,7 +5,15 @@ use crate::{
         algebra::{Matrix3, Matrix4, Vector2, Vector3, Vector4},
         color::Color,
     },
-    renderer::framework::{error::FrameworkError, gpu_texture::GpuTexture, state::PipelineState},
+    renderer::{
+        cache::texture_array::layer_uniform_name,
+        framework::{
+            error::FrameworkError,
+            gpu_buffer::{BufferKind, GpuBuffer},
+            gpu_texture::{GpuTexture, GpuTextureKind},
+            state::PipelineState,
+        },
+    },
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashMap;
@@ -21,6 +29,15 @@ pub struct GpuProgram {
     uniform_locations: RefCell<FxHashMap<ImmutableString, Option<UniformLocation>>>,
     pub(crate) built_in_uniform_locations:
         [Option<UniformLocation>; BuiltInUniform::Count as usize],
+    texture_array_samplers: FxHashMap<ImmutableString, TextureArraySampler>,
+    is_compute: bool,
+}
+
+/// A `sampler2DArray` uniform of a program. Material textures bound to such samplers are packed
+/// into shared texture arrays by the renderer, an index of a layer is passed using `<name>Layer`
+/// integer uniform.
+pub struct TextureArraySampler {
+    pub layer_location: Option<UniformLocation>,
 }
 
 #[repr(usize)]
@@ -32,6 +49,15 @@ pub enum BuiltInUniform {
     CameraPosition,
     UsePOM,
     LightPosition,
+    Opacity,
+    ClusterLights,
+    Clusters,
+    ClusterParameters,
+    ClusterViewProjection,
+    UseBoneMatricesTexture,
+    BoneMatricesTexture,
+    BoneMatricesOffset,
+    UseInstancing,
     // Must be last.
     Count,
 }
@@ -43,18 +69,27 @@ pub struct UniformLocation {
     thread_mark: PhantomData<*const u8>,
 }
 
-unsafe fn create_shader(
+unsafe fn compile_shader(
     state: &mut PipelineState,
-    name: String,
     actual_type: u32,
     source: &str,
 ) -> Result<glow::Shader, FrameworkError> {
-    let merged_source = prepare_source_code(source);
+    let mut merged_source = prepare_source_code(source);
+    if actual_type == glow::COMPUTE_SHADER {
+        merged_source = prepare_compute_source_code(merged_source, state.is_gles());
+    }
 
     let shader = state.gl.create_shader(actual_type)?;
     state.gl.shader_source(shader, &merged_source);
     state.gl.compile_shader(shader);
+    Ok(shader)
+}
 
+unsafe fn check_shader(
+    state: &mut PipelineState,
+    name: String,
+    shader: glow::Shader,
+) -> Result<glow::Shader, FrameworkError> {
     let status = state.gl.get_shader_compile_status(shader);
     let compilation_message = state.gl.get_shader_info_log(shader);
 
@@ -85,6 +120,81 @@ unsafe fn create_shader(
     }
 }
 
+unsafe fn create_shader(
+    state: &mut PipelineState,
+    name: String,
+    actual_type: u32,
+    source: &str,
+) -> Result<glow::Shader, FrameworkError> {
+    let shader = compile_shader(state, actual_type, source)?;
+    let result = check_shader(state, name, shader);
+    if result.is_err() {
+        state.gl.delete_shader(shader);
+    }
+    result
+}
+
+/// A program, that is being compiled and linked by the driver in parallel (see
+/// [`PipelineState::is_parallel_shader_compile_supported`]). Use [`Self::is_completed`] to check
+/// whether the program could be finished without blocking and [`Self::finish`] to get the actual
+/// program.
+pub struct PendingGpuProgram {
+    state: *mut PipelineState,
+    name: String,
+    program: Option<glow::Program>,
+    vertex_shader: glow::Shader,
+    fragment_shader: glow::Shader,
+    // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
+    thread_mark: PhantomData<*const u8>,
+}
+
+impl PendingGpuProgram {
+    /// Returns `true` if the driver has finished compilation and linking of the program.
+    pub fn is_completed(&self, state: &PipelineState) -> bool {
+        self.program
+            .map_or(true, |program| state.is_program_completed(program))
+    }
+
+    /// Checks compilation and linking status of the program and creates the actual program. It
+    /// blocks until the driver finishes compilation, if it wasn't finished yet.
+    pub fn finish(mut self, state: &mut PipelineState) -> Result<GpuProgram, FrameworkError> {
+        unsafe {
+            let program = self.program.take().unwrap();
+            let vertex_status = check_shader(
+                state,
+                format!("{}_VertexShader", self.name),
+                self.vertex_shader,
+            );
+            let fragment_status = check_shader(
+                state,
+                format!("{}_FragmentShader", self.name),
+                self.fragment_shader,
+            );
+            state.gl.delete_shader(self.vertex_shader);
+            state.gl.delete_shader(self.fragment_shader);
+            if let Err(e) = vertex_status.and(fragment_status) {
+                state.gl.delete_program(program);
+                return Err(e);
+            }
+            GpuProgram::from_linked_program(state, &self.name, program)
+        }
+    }
+}
+
+impl Drop for PendingGpuProgram {
+    fn drop(&mut self) {
+        if let Some(program) = self.program {
+            unsafe {
+                if !(*self.state).is_context_lost() {
+                    (*self.state).gl.delete_shader(self.vertex_shader);
+                    (*self.state).gl.delete_shader(self.fragment_shader);
+                    (*self.state).gl.delete_program(program);
+                }
+            }
+        }
+    }
+}
+
 #[allow(clippy::let_and_return)]
 fn prepare_source_code(code: &str) -> String {
     let mut full_source_code = "#version 330 core\n// include 'shared.glsl'\n".to_owned();
@@ -94,6 +204,7 @@ fn prepare_source_code(code: &str) -> String {
     {
         full_source_code += r#"    
             precision highp float;
+            precision highp int;
             precision lowp usampler2D;
             precision lowp sampler3D;
         "#;
@@ -113,9 +224,96 @@ fn prepare_source_code(code: &str) -> String {
     full_source_code
 }
 
+// Compute shaders were introduced in GLSL 4.30 and GLSL ES 3.10. Unlike desktop GLSL, GLSL ES has
+// no default precision for images and some samplers, so it must be specified explicitly.
+fn prepare_compute_source_code(source: String, gles: bool) -> String {
+    if gles {
+        source.replacen(
+            "#version 330 core",
+            r#"#version 310 es
+            precision highp float;
+            precision highp int;
+            precision highp image2D;
+            precision highp image3D;
+            precision highp imageCube;
+            precision highp image2DArray;
+            precision highp uimage2D;
+            precision highp iimage2D;
+            precision highp usampler2D;
+            precision highp sampler3D;
+            precision highp sampler2DArray;
+            "#,
+            1,
+        )
+    } else {
+        source.replacen("#version 330 core", "#version 430 core", 1)
+    }
+}
+
+// Only a subset of internal formats could be used for image load/store, there are no 3-component
+// formats for example. OpenGL ES has even fewer formats.
+fn is_valid_image_format(internal_format: u32, gles: bool) -> bool {
+    match internal_format {
+        glow::RGBA32F
+        | glow::RGBA16F
+        | glow::R32F
+        | glow::RGBA32UI
+        | glow::RGBA16UI
+        | glow::RGBA8UI
+        | glow::R32UI
+        | glow::RGBA32I
+        | glow::RGBA16I
+        | glow::RGBA8I
+        | glow::R32I
+        | glow::RGBA8
+        | glow::RGBA8_SNORM => true,
+        glow::RG32F
+        | glow::RG16F
+        | glow::R11F_G11F_B10F
+        | glow::R16F
+        | glow::RGB10_A2UI
+        | glow::RG32UI
+        | glow::RG16UI
+        | glow::RG8UI
+        | glow::R16UI
+        | glow::R8UI
+        | glow::RG32I
+        | glow::RG16I
+        | glow::RG8I
+        | glow::R16I
+        | glow::R8I
+        | glow::RGBA16
+        | glow::RGB10_A2
+        | glow::RG16
+        | glow::RG8
+        | glow::R16
+        | glow::R8
+        | glow::RGBA16_SNORM
+        | glow::RG16_SNORM
+        | glow::RG8_SNORM
+        | glow::R16_SNORM
+        | glow::R8_SNORM => !gles,
+        _ => false,
+    }
+}
+
+/// Defines which operations could be performed with an image, that is bound to a program using
+/// [`GpuProgramBinding::set_image`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ImageAccess {
+    /// Shaders can only load texels of the image.
+    ReadOnly = glow::READ_ONLY,
+    /// Shaders can only store texels to the image.
+    WriteOnly = glow::WRITE_ONLY,
+    /// Shaders can load and store texels of the image.
+    ReadWrite = glow::READ_WRITE,
+}
+
 pub struct GpuProgramBinding<'a, 'b> {
     pub state: &'a mut PipelineState,
     active_sampler: u32,
+    active_image_unit: u32,
     pub(crate) program: &'b GpuProgram,
 }
 
@@ -140,6 +338,68 @@ impl<'a, 'b> GpuProgramBinding<'a, 'b> {
         self
     }
 
+    /// Binds a level of the texture as an image for load/store operations (`image2D`, `image3D`,
+    /// etc. uniforms). Every layer of array, cube and volume textures is bound. Requires compute
+    /// shaders support, see [`PipelineState::is_compute_supported`]. Returns an error if pixel
+    /// kind of the texture cannot be used for load/store operations (3-component formats, sRGB
+    /// formats, depth formats, compressed formats, etc.).
+    #[inline(always)]
+    pub fn set_image(
+        &mut self,
+        location: &UniformLocation,
+        texture: &Rc<RefCell<GpuTexture>>,
+        level: usize,
+        access: ImageAccess,
+    ) -> Result<&mut Self, FrameworkError> {
+        let texture = texture.borrow();
+        let pixel_kind = texture.pixel_kind();
+        let (_, _, internal_format, _) = pixel_kind.gl_formats();
+        if !is_valid_image_format(internal_format, self.state.is_gles()) {
+            return Err(FrameworkError::UnsupportedImageFormat(pixel_kind));
+        }
+
+        let layered = matches!(
+            texture.kind(),
+            GpuTextureKind::Cube { .. }
+                | GpuTextureKind::Volume { .. }
+                | GpuTextureKind::RectangleArray { .. }
+        );
+        unsafe {
+            self.state
+                .gl
+                .uniform_1_i32(Some(&location.id), self.active_image_unit as i32);
+            self.state.gl.bind_image_texture(
+                self.active_image_unit,
+                texture.id(),
+                level as i32,
+                layered,
+                0,
+                access as u32,
+                internal_format,
+            );
+        }
+        self.active_image_unit += 1;
+        Ok(self)
+    }
+
+    /// Binds the buffer to the given binding point of shader storage blocks
+    /// (`layout(std430, binding = N) buffer`).
+    #[inline(always)]
+    pub fn set_storage_buffer(&mut self, binding: u32, buffer: &GpuBuffer) -> &mut Self {
+        debug_assert_eq!(buffer.kind(), BufferKind::ShaderStorage);
+        buffer.bind_base(self.state, binding);
+        self
+    }
+
+    /// Binds the buffer to the given binding point of uniform blocks
+    /// (`layout(std140, binding = N) uniform`), see also [`GpuProgram::set_uniform_block_binding`].
+    #[inline(always)]
+    pub fn set_uniform_buffer(&mut self, binding: u32, buffer: &GpuBuffer) -> &mut Self {
+        debug_assert_eq!(buffer.kind(), BufferKind::Uniform);
+        buffer.bind_base(self.state, binding);
+        self
+    }
+
     #[inline(always)]
     pub fn set_bool(&mut self, location: &UniformLocation, value: bool) -> &mut Self {
         unsafe {
@@ -431,10 +691,52 @@ fn fetch_built_in_uniform_locations(
         fetch_uniform_location(state, program, "fyrox_usePOM");
     locations[BuiltInUniform::LightPosition as usize] =
         fetch_uniform_location(state, program, "fyrox_lightPosition");
+    locations[BuiltInUniform::Opacity as usize] =
+        fetch_uniform_location(state, program, "fyrox_opacity");
+    locations[BuiltInUniform::ClusterLights as usize] =
+        fetch_uniform_location(state, program, "fyrox_clusterLights");
+    locations[BuiltInUniform::Clusters as usize] =
+        fetch_uniform_location(state, program, "fyrox_clusters");
+    locations[BuiltInUniform::ClusterParameters as usize] =
+        fetch_uniform_location(state, program, "fyrox_clusterParameters");
+    locations[BuiltInUniform::ClusterViewProjection as usize] =
+        fetch_uniform_location(state, program, "fyrox_clusterViewProjection");
+    locations[BuiltInUniform::UseBoneMatricesTexture as usize] =
+        fetch_uniform_location(state, program, "fyrox_useBoneMatricesTexture");
+    locations[BuiltInUniform::BoneMatricesTexture as usize] =
+        fetch_uniform_location(state, program, "fyrox_boneMatricesTexture");
+    locations[BuiltInUniform::BoneMatricesOffset as usize] =
+        fetch_uniform_location(state, program, "fyrox_boneMatricesOffset");
+    locations[BuiltInUniform::UseInstancing as usize] =
+        fetch_uniform_location(state, program, "fyrox_useInstancing");
 
     locations
 }
 
+fn fetch_texture_array_samplers(
+    state: &PipelineState,
+    program: glow::Program,
+) -> FxHashMap<ImmutableString, TextureArraySampler> {
+    let mut samplers = FxHashMap::default();
+
+    unsafe {
+        for index in 0..state.gl.get_active_uniforms(program) {
+            if let Some(uniform) = state.gl.get_active_uniform(program, index) {
+                if uniform.utype == glow::SAMPLER_2D_ARRAY {
+                    let layer_location =
+                        fetch_uniform_location(state, program, &layer_uniform_name(&uniform.name));
+                    samplers.insert(
+                        ImmutableString::new(&uniform.name),
+                        TextureArraySampler { layer_location },
+                    );
+                }
+            }
+        }
+    }
+
+    samplers
+}
+
 impl GpuProgram {
     pub fn from_source(
         state: &mut PipelineState,
@@ -461,40 +763,175 @@ impl GpuProgram {
             state.gl.attach_shader(program, fragment_shader);
             state.gl.delete_shader(fragment_shader);
             state.gl.link_program(program);
-            let status = state.gl.get_program_link_status(program);
-            let link_message = state.gl.get_program_info_log(program);
+            Self::from_linked_program(state, name, program)
+        }
+    }
 
-            if !status {
-                Log::writeln(
-                    MessageKind::Error,
-                    format!("Failed to link {} shader: {}", name, link_message),
-                );
-                Err(FrameworkError::ShaderLinkingFailed {
-                    shader_name: name.to_owned(),
-                    error_message: link_message,
-                })
+    /// Starts compilation and linking of a program without waiting for the results. If the driver
+    /// supports parallel compilation (see [`PipelineState::is_parallel_shader_compile_supported`]),
+    /// the program is compiled on driver threads, otherwise the compilation happens when the
+    /// program is finished.
+    pub fn begin_from_source(
+        state: &mut PipelineState,
+        name: &str,
+        vertex_source: &str,
+        fragment_source: &str,
+    ) -> Result<PendingGpuProgram, FrameworkError> {
+        unsafe {
+            let vertex_shader = compile_shader(state, glow::VERTEX_SHADER, vertex_source)?;
+            let fragment_shader =
+                match compile_shader(state, glow::FRAGMENT_SHADER, fragment_source) {
+                    Ok(fragment_shader) => fragment_shader,
+                    Err(e) => {
+                        state.gl.delete_shader(vertex_shader);
+                        return Err(e);
+                    }
+                };
+            let program = match state.gl.create_program() {
+                Ok(program) => program,
+                Err(e) => {
+                    state.gl.delete_shader(vertex_shader);
+                    state.gl.delete_shader(fragment_shader);
+                    return Err(e.into());
+                }
+            };
+            state.gl.attach_shader(program, vertex_shader);
+            state.gl.attach_shader(program, fragment_shader);
+            state.gl.link_program(program);
+            Ok(PendingGpuProgram {
+                state,
+                name: name.to_owned(),
+                program: Some(program),
+                vertex_shader,
+                fragment_shader,
+                thread_mark: PhantomData,
+            })
+        }
+    }
+
+    /// Creates a compute program from the given source. Compute shaders require OpenGL 4.3 or
+    /// OpenGL ES 3.1 (see [`PipelineState::is_compute_supported`]), so the source is compiled with
+    /// `#version 430 core` (or `#version 310 es` on OpenGL ES). Use [`GpuProgram::dispatch`] to
+    /// run the program.
+    pub fn from_compute_source(
+        state: &mut PipelineState,
+        name: &str,
+        compute_source: &str,
+    ) -> Result<GpuProgram, FrameworkError> {
+        if !state.is_compute_supported() {
+            return Err(FrameworkError::ComputeShadersUnsupported);
+        }
+
+        unsafe {
+            let compute_shader = create_shader(
+                state,
+                format!("{}_ComputeShader", name),
+                glow::COMPUTE_SHADER,
+                compute_source,
+            )?;
+            let program = state.gl.create_program()?;
+            state.gl.attach_shader(program, compute_shader);
+            state.gl.delete_shader(compute_shader);
+            state.gl.link_program(program);
+            let mut program = Self::from_linked_program(state, name, program)?;
+            program.is_compute = true;
+            Ok(program)
+        }
+    }
+
+    unsafe fn from_linked_program(
+        state: &mut PipelineState,
+        name: &str,
+        program: glow::Program,
+    ) -> Result<GpuProgram, FrameworkError> {
+        let status = state.gl.get_program_link_status(program);
+        let link_message = state.gl.get_program_info_log(program);
+
+        if !status {
+            Log::writeln(
+                MessageKind::Error,
+                format!("Failed to link {} shader: {}", name, link_message),
+            );
+            state.gl.delete_program(program);
+            Err(FrameworkError::ShaderLinkingFailed {
+                shader_name: name.to_owned(),
+                error_message: link_message,
+            })
+        } else {
+            let msg = if link_message.is_empty() || link_message.chars().all(|c| c.is_whitespace())
+            {
+                format!("Shader {} linked successfully!", name)
             } else {
-                let msg =
-                    if link_message.is_empty() || link_message.chars().all(|c| c.is_whitespace()) {
-                        format!("Shader {} linked successfully!", name)
-                    } else {
-                        format!(
-                            "Shader {} linked successfully!\nAdditional info: {}",
-                            name, link_message
-                        )
-                    };
-
-                Log::writeln(MessageKind::Information, msg);
-
-                Ok(Self {
-                    state,
-                    id: program,
-                    thread_mark: PhantomData,
-                    uniform_locations: Default::default(),
-                    built_in_uniform_locations: fetch_built_in_uniform_locations(state, program),
-                })
-            }
+                format!(
+                    "Shader {} linked successfully!\nAdditional info: {}",
+                    name, link_message
+                )
+            };
+
+            Log::writeln(MessageKind::Information, msg);
+
+            Ok(Self {
+                state,
+                id: program,
+                thread_mark: PhantomData,
+                uniform_locations: Default::default(),
+                built_in_uniform_locations: fetch_built_in_uniform_locations(state, program),
+                texture_array_samplers: fetch_texture_array_samplers(state, program),
+                is_compute: false,
+            })
+        }
+    }
+
+    /// Returns `true` if the program was created using [`GpuProgram::from_compute_source`].
+    pub fn is_compute(&self) -> bool {
+        self.is_compute
+    }
+
+    /// Runs the compute program with the given amount of work groups in each dimension. Uniforms,
+    /// buffers and images must be set in `apply_uniforms` closure. Results of the dispatch are not
+    /// visible to subsequent commands until a respective barrier is issued, see
+    /// [`PipelineState::memory_barrier`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the program is not a compute program.
+    pub fn dispatch<F: FnOnce(GpuProgramBinding<'_, '_>)>(
+        &self,
+        state: &mut PipelineState,
+        group_count: Vector3<u32>,
+        apply_uniforms: F,
+    ) {
+        assert!(
+            self.is_compute,
+            "Attempt to dispatch a program without compute shader!"
+        );
+
+        apply_uniforms(self.bind(state));
+
+        unsafe {
+            state
+                .gl
+                .dispatch_compute(group_count.x, group_count.y, group_count.z);
+        }
+    }
+
+    /// Assigns a binding point to a uniform block with the given name. It is needed only for
+    /// shaders, that can't specify binding points using `layout(binding = N)` qualifier (it is
+    /// unavailable in `#version 330 core` shaders).
+    pub fn set_uniform_block_binding(
+        &self,
+        state: &mut PipelineState,
+        block_name: &str,
+        binding: u32,
+    ) -> Result<(), FrameworkError> {
+        unsafe {
+            let index = state
+                .gl
+                .get_uniform_block_index(self.id, block_name)
+                .ok_or_else(|| FrameworkError::UnableToFindShaderUniform(block_name.to_owned()))?;
+            state.gl.uniform_block_binding(self.id, index, binding);
         }
+        Ok(())
     }
 
     pub fn uniform_location_internal(
@@ -515,6 +952,18 @@ impl GpuProgram {
         }
     }
 
+    /// Checks whether the program is able to render multiple instances in a single draw call or
+    /// not. Programs that support hardware instancing must use `fyrox_useInstancing` built-in
+    /// uniform and take per-instance data from vertex attributes.
+    pub fn is_instancing_supported(&self) -> bool {
+        self.built_in_uniform_locations[BuiltInUniform::UseInstancing as usize].is_some()
+    }
+
+    /// Returns a texture array sampler with the given name, if the program has one.
+    pub fn texture_array_sampler(&self, name: &ImmutableString) -> Option<&TextureArraySampler> {
+        self.texture_array_samplers.get(name)
+    }
+
     pub fn uniform_location(
         &self,
         state: &PipelineState,
@@ -529,6 +978,7 @@ impl GpuProgram {
         GpuProgramBinding {
             state,
             active_sampler: 0,
+            active_image_unit: 0,
             program: self,
         }
     }
@@ -537,7 +987,45 @@ impl GpuProgram {
 impl Drop for GpuProgram {
     fn drop(&mut self) {
         unsafe {
-            (*self.state).gl.delete_program(self.id);
+            if !(*self.state).is_context_lost() {
+                (*self.state).gl.delete_program(self.id);
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::framework::gpu_program::{
+        is_valid_image_format, prepare_compute_source_code,
+    };
+
+    #[test]
+    fn test_compute_source_version() {
+        let source = "#version 330 core\nlayout(local_size_x = 64) in;\nvoid main() {}".to_owned();
+
+        let desktop = prepare_compute_source_code(source.clone(), false);
+        assert!(desktop.starts_with("#version 430 core"));
+
+        let gles = prepare_compute_source_code(source, true);
+        assert!(gles.starts_with("#version 310 es"));
+        assert!(gles.contains("precision highp image2D;"));
+        assert!(!gles.contains("#version 330 core"));
+    }
+
+    #[test]
+    fn test_image_formats() {
+        for gles in [false, true] {
+            assert!(is_valid_image_format(glow::RGBA8, gles));
+            assert!(is_valid_image_format(glow::RGBA16F, gles));
+            assert!(is_valid_image_format(glow::R32F, gles));
+            assert!(!is_valid_image_format(glow::RGB8, gles));
+            assert!(!is_valid_image_format(glow::RGB32F, gles));
+            assert!(!is_valid_image_format(glow::SRGB8_ALPHA8, gles));
+            assert!(!is_valid_image_format(glow::DEPTH24_STENCIL8, gles));
+        }
+
+        assert!(is_valid_image_format(glow::R16F, false));
+        assert!(!is_valid_image_format(glow::R16F, true));
+    }
+}
@@ -6,7 +6,10 @@ use crate::{
         TextureWrapMode,
     },
 };
-use glow::{HasContext, COMPRESSED_RED_RGTC1, COMPRESSED_RG_RGTC2};
+use glow::{
+    CompressedPixelUnpackData, HasContext, PixelUnpackData, COMPRESSED_RED_RGTC1,
+    COMPRESSED_RG_RGTC2,
+};
 use std::marker::PhantomData;
 
 #[derive(Copy, Clone)]
@@ -22,6 +25,13 @@ pub enum GpuTextureKind {
         width: usize,
         height: usize,
     },
+    /// A set of 2D images of the same size and format. Every layer can be addressed separately
+    /// from shaders using `sampler2DArray`.
+    RectangleArray {
+        width: usize,
+        height: usize,
+        layers: usize,
+    },
     Volume {
         width: usize,
         height: usize,
@@ -62,12 +72,13 @@ impl GpuTextureKind {
             Self::Line { .. } => glow::TEXTURE_1D,
             Self::Rectangle { .. } => glow::TEXTURE_2D,
             Self::Cube { .. } => glow::TEXTURE_CUBE_MAP,
+            Self::RectangleArray { .. } => glow::TEXTURE_2D_ARRAY,
             Self::Volume { .. } => glow::TEXTURE_3D,
         }
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum PixelKind {
     F32,
     F16,
@@ -97,6 +108,7 @@ pub enum PixelKind {
     DXT5RGBA,
     RGB32F,
     RGBA32F,
+    RGBA32UI,
     RGBA16F,
     R8RGTC,
     RG8RGTC,
@@ -143,7 +155,12 @@ pub enum PixelElementKind {
 impl PixelKind {
     pub fn unpack_alignment(self) -> Option<i32> {
         match self {
-            Self::RGBA16 | Self::RGBA16F | Self::RGB16 | Self::RGBA32F | Self::RGB32F => Some(8),
+            Self::RGBA16
+            | Self::RGBA16F
+            | Self::RGB16
+            | Self::RGBA32F
+            | Self::RGBA32UI
+            | Self::RGB32F => Some(8),
             Self::RGBA8
             | Self::SRGBA8
             | Self::SRGB8
@@ -197,6 +214,7 @@ impl PixelKind {
             | Self::R8UI
             | Self::RGB32F
             | Self::RGBA32F
+            | Self::RGBA32UI
             | Self::R11G11B10F
             | Self::RGB10A2
             | Self::L8
@@ -206,6 +224,107 @@ impl PixelKind {
         }
     }
 
+    /// Returns a tuple `(type, format, internal format, swizzle mask)` of OpenGL enums that
+    /// describes the pixel kind.
+    pub(crate) fn gl_formats(self) -> (u32, u32, u32, Option<[i32; 4]>) {
+        match self {
+            Self::F32 => (glow::FLOAT, glow::RED, glow::R32F, None),
+            Self::F16 => (glow::FLOAT, glow::RED, glow::R16F, None),
+            Self::D32F => (
+                glow::FLOAT,
+                glow::DEPTH_COMPONENT,
+                glow::DEPTH_COMPONENT32F,
+                None,
+            ),
+            Self::D16 => (
+                glow::UNSIGNED_SHORT,
+                glow::DEPTH_COMPONENT,
+                glow::DEPTH_COMPONENT16,
+                None,
+            ),
+            Self::D24S8 => (
+                glow::UNSIGNED_INT_24_8,
+                glow::DEPTH_STENCIL,
+                glow::DEPTH24_STENCIL8,
+                None,
+            ),
+            Self::RGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::RGBA8, None),
+            Self::SRGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::SRGB8_ALPHA8, None),
+            Self::RGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::RGB8, None),
+            Self::SRGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::SRGB8, None),
+            Self::RG8 => (glow::UNSIGNED_BYTE, glow::RG, glow::RG8, None),
+            Self::R8 => (glow::UNSIGNED_BYTE, glow::RED, glow::R8, None),
+            Self::R8UI => (glow::UNSIGNED_BYTE, glow::RED_INTEGER, glow::R8UI, None),
+            Self::BGRA8 => (glow::UNSIGNED_BYTE, glow::BGRA, glow::RGBA8, None),
+            Self::BGR8 => (glow::UNSIGNED_BYTE, glow::BGR, glow::RGB8, None),
+            Self::RG16 => (glow::UNSIGNED_SHORT, glow::RG, glow::RG16, None),
+            Self::R16 => (glow::UNSIGNED_SHORT, glow::RED, glow::R16, None),
+            Self::RGB16 => (glow::UNSIGNED_SHORT, glow::RGB, glow::RGB16, None),
+            Self::RGBA16 => (glow::UNSIGNED_SHORT, glow::RGBA, glow::RGBA16, None),
+            Self::RGB10A2 => (
+                glow::UNSIGNED_INT_2_10_10_10_REV,
+                glow::RGBA,
+                glow::RGB10_A2,
+                None,
+            ),
+            Self::DXT1RGB => (0, 0, GL_COMPRESSED_RGB_S3TC_DXT1_EXT, None),
+            Self::DXT1RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, None),
+            Self::DXT3RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, None),
+            Self::DXT5RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
+            Self::R8RGTC => (0, 0, COMPRESSED_RED_RGTC1, None),
+            Self::RG8RGTC => (0, 0, COMPRESSED_RG_RGTC2, None),
+            Self::RGB32F => (glow::FLOAT, glow::RGB, glow::RGB32F, None),
+            Self::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F, None),
+            Self::RGBA32UI => (glow::UNSIGNED_INT, glow::RGBA_INTEGER, glow::RGBA32UI, None),
+            Self::RGBA16F => (glow::FLOAT, glow::RGBA, glow::RGBA16F, None),
+            Self::R11G11B10F => (glow::FLOAT, glow::RGB, glow::R11F_G11F_B10F, None),
+            Self::L8 => (
+                glow::UNSIGNED_BYTE,
+                glow::RED,
+                glow::R8,
+                Some([
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::ONE as i32,
+                ]),
+            ),
+            Self::LA8 => (
+                glow::UNSIGNED_BYTE,
+                glow::RG,
+                glow::RG8,
+                Some([
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::GREEN as i32,
+                ]),
+            ),
+            Self::LA16 => (
+                glow::UNSIGNED_SHORT,
+                glow::RG,
+                glow::RG16,
+                Some([
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::GREEN as i32,
+                ]),
+            ),
+            Self::L16 => (
+                glow::UNSIGNED_SHORT,
+                glow::RED,
+                glow::R16,
+                Some([
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::RED as i32,
+                    glow::ONE as i32,
+                ]),
+            ),
+        }
+    }
+
     pub fn element_kind(self) -> PixelElementKind {
         match self {
             Self::F32
@@ -240,11 +359,84 @@ impl PixelKind {
             | Self::L8
             | Self::LA16
             | Self::L16 => PixelElementKind::NormalizedUnsignedInteger,
-            Self::R8UI => PixelElementKind::UnsignedInteger,
+            Self::R8UI | Self::RGBA32UI => PixelElementKind::UnsignedInteger,
         }
     }
 }
 
+/// Calculates total size (in bytes) of a texture of the given kind with the given amount of
+/// mips. Degenerated mips are not counted.
+pub fn mip_chain_size_bytes(
+    kind: GpuTextureKind,
+    pixel_kind: PixelKind,
+    mip_count: usize,
+) -> usize {
+    let mut desired_byte_count = 0;
+
+    'mip_loop: for mip in 0..mip_count {
+        match kind {
+            GpuTextureKind::Line { length } => {
+                if let Some(length) = length.checked_shr(mip as u32) {
+                    desired_byte_count += image_1d_size_bytes(pixel_kind, length);
+                } else {
+                    break 'mip_loop;
+                }
+            }
+            GpuTextureKind::Rectangle { width, height } => {
+                if let (Some(width), Some(height)) = (
+                    width.checked_shr(mip as u32),
+                    height.checked_shr(mip as u32),
+                ) {
+                    desired_byte_count += image_2d_size_bytes(pixel_kind, width, height);
+                } else {
+                    break 'mip_loop;
+                }
+            }
+            GpuTextureKind::Cube { width, height } => {
+                if let (Some(width), Some(height)) = (
+                    width.checked_shr(mip as u32),
+                    height.checked_shr(mip as u32),
+                ) {
+                    desired_byte_count += 6 * image_2d_size_bytes(pixel_kind, width, height);
+                } else {
+                    break 'mip_loop;
+                }
+            }
+            GpuTextureKind::RectangleArray {
+                width,
+                height,
+                layers,
+            } => {
+                if let (Some(width), Some(height)) = (
+                    width.checked_shr(mip as u32),
+                    height.checked_shr(mip as u32),
+                ) {
+                    desired_byte_count += layers * image_2d_size_bytes(pixel_kind, width, height);
+                } else {
+                    break 'mip_loop;
+                }
+            }
+            GpuTextureKind::Volume {
+                width,
+                height,
+                depth,
+            } => {
+                if let (Some(width), Some(height), Some(depth)) = (
+                    width.checked_shr(mip as u32),
+                    height.checked_shr(mip as u32),
+                    depth.checked_shr(mip as u32),
+                ) {
+                    desired_byte_count += image_3d_size_bytes(pixel_kind, width, height, depth);
+                } else {
+                    break 'mip_loop;
+                }
+            }
+        };
+    }
+
+    desired_byte_count
+}
+
 pub struct GpuTexture {
     state: *mut PipelineState,
     texture: glow::Texture,
@@ -256,6 +448,7 @@ pub struct GpuTexture {
     r_wrap_mode: WrapMode,
     anisotropy: f32,
     pixel_kind: PixelKind,
+    mip_count: usize,
     // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
     thread_mark: PhantomData<*const u8>,
 }
@@ -267,7 +460,7 @@ fn ceil_div_4(x: usize) -> usize {
 fn image_3d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize, depth: usize) -> usize {
     let pixel_count = width * height * depth;
     match pixel_kind {
-        PixelKind::RGBA32F => 16 * pixel_count,
+        PixelKind::RGBA32F | PixelKind::RGBA32UI => 16 * pixel_count,
         PixelKind::RGB32F => 12 * pixel_count,
         PixelKind::RGBA16 | PixelKind::RGBA16F => 8 * pixel_count,
         PixelKind::RGB16 => 6 * pixel_count,
@@ -303,7 +496,7 @@ fn image_3d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize, depth
 fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> usize {
     let pixel_count = width * height;
     match pixel_kind {
-        PixelKind::RGBA32F => 16 * pixel_count,
+        PixelKind::RGBA32F | PixelKind::RGBA32UI => 16 * pixel_count,
         PixelKind::RGB32F => 12 * pixel_count,
         PixelKind::RGBA16 | PixelKind::RGBA16F => 8 * pixel_count,
         PixelKind::RGB16 => 6 * pixel_count,
@@ -338,7 +531,7 @@ fn image_2d_size_bytes(pixel_kind: PixelKind, width: usize, height: usize) -> us
 
 fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
     match pixel_kind {
-        PixelKind::RGBA32F => 16 * length,
+        PixelKind::RGBA32F | PixelKind::RGBA32UI => 16 * length,
         PixelKind::RGB32F => 12 * length,
         PixelKind::RGBA16 | PixelKind::RGBA16F => 8 * length,
         PixelKind::RGB16 => 6 * length,
@@ -371,7 +564,7 @@ fn image_1d_size_bytes(pixel_kind: PixelKind, length: usize) -> usize {
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialOrd, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum MagnificationFilter {
     Nearest,
@@ -396,7 +589,7 @@ impl From<TextureMagnificationFilter> for MagnificationFilter {
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialOrd, PartialEq, Eq, Hash)]
 #[repr(u32)]
 pub enum MinificationFilter {
     Nearest = glow::NEAREST,
@@ -584,54 +777,7 @@ impl<'a> TextureBinding<'a> {
     ) -> Result<Self, FrameworkError> {
         let mip_count = mip_count.max(1);
 
-        let mut desired_byte_count = 0;
-
-        'mip_loop: for mip in 0..mip_count {
-            match kind {
-                GpuTextureKind::Line { length } => {
-                    if let Some(length) = length.checked_shr(mip as u32) {
-                        desired_byte_count += image_1d_size_bytes(pixel_kind, length);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-                GpuTextureKind::Rectangle { width, height } => {
-                    if let (Some(width), Some(height)) = (
-                        width.checked_shr(mip as u32),
-                        height.checked_shr(mip as u32),
-                    ) {
-                        desired_byte_count += image_2d_size_bytes(pixel_kind, width, height);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-                GpuTextureKind::Cube { width, height } => {
-                    if let (Some(width), Some(height)) = (
-                        width.checked_shr(mip as u32),
-                        height.checked_shr(mip as u32),
-                    ) {
-                        desired_byte_count += 6 * image_2d_size_bytes(pixel_kind, width, height);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-                GpuTextureKind::Volume {
-                    width,
-                    height,
-                    depth,
-                } => {
-                    if let (Some(width), Some(height), Some(depth)) = (
-                        width.checked_shr(mip as u32),
-                        height.checked_shr(mip as u32),
-                        depth.checked_shr(mip as u32),
-                    ) {
-                        desired_byte_count += image_3d_size_bytes(pixel_kind, width, height, depth);
-                    } else {
-                        break 'mip_loop;
-                    }
-                }
-            };
-        }
+        let desired_byte_count = mip_chain_size_bytes(kind, pixel_kind, mip_count);
 
         if let Some(data) = data {
             let actual_data_size = data.len();
@@ -645,6 +791,7 @@ impl<'a> TextureBinding<'a> {
 
         self.texture.kind = kind;
         self.texture.pixel_kind = pixel_kind;
+        self.texture.mip_count = mip_count;
 
         let target = kind.gl_texture_target();
 
@@ -652,101 +799,7 @@ impl<'a> TextureBinding<'a> {
             self.state
                 .set_texture(0, target, Some(self.texture.texture));
 
-            let (type_, format, internal_format, swizzle_mask) = match pixel_kind {
-                PixelKind::F32 => (glow::FLOAT, glow::RED, glow::R32F, None),
-                PixelKind::F16 => (glow::FLOAT, glow::RED, glow::R16F, None),
-                PixelKind::D32F => (
-                    glow::FLOAT,
-                    glow::DEPTH_COMPONENT,
-                    glow::DEPTH_COMPONENT32F,
-                    None,
-                ),
-                PixelKind::D16 => (
-                    glow::UNSIGNED_SHORT,
-                    glow::DEPTH_COMPONENT,
-                    glow::DEPTH_COMPONENT16,
-                    None,
-                ),
-                PixelKind::D24S8 => (
-                    glow::UNSIGNED_INT_24_8,
-                    glow::DEPTH_STENCIL,
-                    glow::DEPTH24_STENCIL8,
-                    None,
-                ),
-                PixelKind::RGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::RGBA8, None),
-                PixelKind::SRGBA8 => (glow::UNSIGNED_BYTE, glow::RGBA, glow::SRGB8_ALPHA8, None),
-                PixelKind::RGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::RGB8, None),
-                PixelKind::SRGB8 => (glow::UNSIGNED_BYTE, glow::RGB, glow::SRGB8, None),
-                PixelKind::RG8 => (glow::UNSIGNED_BYTE, glow::RG, glow::RG8, None),
-                PixelKind::R8 => (glow::UNSIGNED_BYTE, glow::RED, glow::R8, None),
-                PixelKind::R8UI => (glow::UNSIGNED_BYTE, glow::RED_INTEGER, glow::R8UI, None),
-                PixelKind::BGRA8 => (glow::UNSIGNED_BYTE, glow::BGRA, glow::RGBA8, None),
-                PixelKind::BGR8 => (glow::UNSIGNED_BYTE, glow::BGR, glow::RGB8, None),
-                PixelKind::RG16 => (glow::UNSIGNED_SHORT, glow::RG, glow::RG16, None),
-                PixelKind::R16 => (glow::UNSIGNED_SHORT, glow::RED, glow::R16, None),
-                PixelKind::RGB16 => (glow::UNSIGNED_SHORT, glow::RGB, glow::RGB16, None),
-                PixelKind::RGBA16 => (glow::UNSIGNED_SHORT, glow::RGBA, glow::RGBA16, None),
-                PixelKind::RGB10A2 => (
-                    glow::UNSIGNED_INT_2_10_10_10_REV,
-                    glow::RGBA,
-                    glow::RGB10_A2,
-                    None,
-                ),
-                PixelKind::DXT1RGB => (0, 0, GL_COMPRESSED_RGB_S3TC_DXT1_EXT, None),
-                PixelKind::DXT1RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, None),
-                PixelKind::DXT3RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, None),
-                PixelKind::DXT5RGBA => (0, 0, GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, None),
-                PixelKind::R8RGTC => (0, 0, COMPRESSED_RED_RGTC1, None),
-                PixelKind::RG8RGTC => (0, 0, COMPRESSED_RG_RGTC2, None),
-                PixelKind::RGB32F => (glow::FLOAT, glow::RGB, glow::RGB32F, None),
-                PixelKind::RGBA32F => (glow::FLOAT, glow::RGBA, glow::RGBA32F, None),
-                PixelKind::RGBA16F => (glow::FLOAT, glow::RGBA, glow::RGBA16F, None),
-                PixelKind::R11G11B10F => (glow::FLOAT, glow::RGB, glow::R11F_G11F_B10F, None),
-                PixelKind::L8 => (
-                    glow::UNSIGNED_BYTE,
-                    glow::RED,
-                    glow::R8,
-                    Some([
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::ONE as i32,
-                    ]),
-                ),
-                PixelKind::LA8 => (
-                    glow::UNSIGNED_BYTE,
-                    glow::RG,
-                    glow::RG8,
-                    Some([
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::GREEN as i32,
-                    ]),
-                ),
-                PixelKind::LA16 => (
-                    glow::UNSIGNED_SHORT,
-                    glow::RG,
-                    glow::RG16,
-                    Some([
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::GREEN as i32,
-                    ]),
-                ),
-                PixelKind::L16 => (
-                    glow::UNSIGNED_SHORT,
-                    glow::RED,
-                    glow::R16,
-                    Some([
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::RED as i32,
-                        glow::ONE as i32,
-                    ]),
-                ),
-            };
+            let (type_, format, internal_format, swizzle_mask) = pixel_kind.gl_formats();
 
             let is_compressed = pixel_kind.is_compressed();
 
@@ -895,6 +948,54 @@ impl<'a> TextureBinding<'a> {
                             break 'mip_loop2;
                         }
                     }
+                    GpuTextureKind::RectangleArray {
+                        width,
+                        height,
+                        layers,
+                    } => {
+                        if let (Some(width), Some(height)) = (
+                            width.checked_shr(mip as u32),
+                            height.checked_shr(mip as u32),
+                        ) {
+                            let size =
+                                (layers * image_2d_size_bytes(pixel_kind, width, height)) as i32;
+                            let pixels = data.map(|data| {
+                                &data[mip_byte_offset..(mip_byte_offset + size as usize)]
+                            });
+
+                            if is_compressed {
+                                self.state.gl.compressed_tex_image_3d(
+                                    glow::TEXTURE_2D_ARRAY,
+                                    mip as i32,
+                                    internal_format as i32,
+                                    width as i32,
+                                    height as i32,
+                                    layers as i32,
+                                    0,
+                                    size,
+                                    pixels.ok_or(FrameworkError::EmptyTextureData)?,
+                                );
+                            } else {
+                                self.state.gl.tex_image_3d(
+                                    glow::TEXTURE_2D_ARRAY,
+                                    mip as i32,
+                                    internal_format as i32,
+                                    width as i32,
+                                    height as i32,
+                                    layers as i32,
+                                    0,
+                                    format,
+                                    type_,
+                                    pixels,
+                                );
+                            }
+
+                            mip_byte_offset += size as usize;
+                        } else {
+                            // No need to add degenerated mips (0x1, 0x2, 4x0, etc).
+                            break 'mip_loop2;
+                        }
+                    }
                     GpuTextureKind::Volume {
                         width,
                         height,
@@ -949,6 +1050,110 @@ impl<'a> TextureBinding<'a> {
 
         Ok(self)
     }
+
+    /// Uploads all mips of a single layer of a [`GpuTextureKind::RectangleArray`] texture. The
+    /// data must have the same pixel kind as the array, mips must follow one after another.
+    pub fn set_layer_data(
+        self,
+        layer: usize,
+        mip_count: usize,
+        data: &[u8],
+    ) -> Result<Self, FrameworkError> {
+        let (width, height, layers) = match self.texture.kind {
+            GpuTextureKind::RectangleArray {
+                width,
+                height,
+                layers,
+            } => (width, height, layers),
+            _ => {
+                return Err(FrameworkError::Custom(
+                    "Layer data can be set only for texture arrays!".to_string(),
+                ))
+            }
+        };
+
+        if layer >= layers {
+            return Err(FrameworkError::Custom(format!(
+                "Layer index {} is out of bounds, texture array has {} layers!",
+                layer, layers
+            )));
+        }
+
+        let pixel_kind = self.texture.pixel_kind;
+        let mip_count = mip_count.max(1);
+
+        let desired_byte_count = mip_chain_size_bytes(
+            GpuTextureKind::Rectangle { width, height },
+            pixel_kind,
+            mip_count,
+        );
+
+        if data.len() != desired_byte_count {
+            return Err(FrameworkError::InvalidTextureData {
+                expected_data_size: desired_byte_count,
+                actual_data_size: data.len(),
+            });
+        }
+
+        unsafe {
+            self.state
+                .set_texture(0, glow::TEXTURE_2D_ARRAY, Some(self.texture.texture));
+
+            let (type_, format, internal_format, _) = pixel_kind.gl_formats();
+
+            if let Some(alignment) = pixel_kind.unpack_alignment() {
+                self.state
+                    .gl
+                    .pixel_store_i32(glow::UNPACK_ALIGNMENT, alignment);
+            }
+
+            let mut mip_byte_offset = 0;
+            for mip in 0..mip_count {
+                if let (Some(width), Some(height)) = (
+                    width.checked_shr(mip as u32),
+                    height.checked_shr(mip as u32),
+                ) {
+                    let size = image_2d_size_bytes(pixel_kind, width, height);
+                    let pixels = &data[mip_byte_offset..(mip_byte_offset + size)];
+
+                    if pixel_kind.is_compressed() {
+                        self.state.gl.compressed_tex_sub_image_3d(
+                            glow::TEXTURE_2D_ARRAY,
+                            mip as i32,
+                            0,
+                            0,
+                            layer as i32,
+                            width as i32,
+                            height as i32,
+                            1,
+                            internal_format,
+                            CompressedPixelUnpackData::Slice(pixels),
+                        );
+                    } else {
+                        self.state.gl.tex_sub_image_3d(
+                            glow::TEXTURE_2D_ARRAY,
+                            mip as i32,
+                            0,
+                            0,
+                            layer as i32,
+                            width as i32,
+                            height as i32,
+                            1,
+                            format,
+                            type_,
+                            PixelUnpackData::Slice(pixels),
+                        );
+                    }
+
+                    mip_byte_offset += size;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        Ok(self)
+    }
 }
 
 const GL_COMPRESSED_RGB_S3TC_DXT1_EXT: u32 = 0x83F0;
@@ -999,6 +1204,7 @@ impl GpuTexture {
                 r_wrap_mode: WrapMode::Repeat,
                 anisotropy: 1.0,
                 pixel_kind,
+                mip_count,
                 thread_mark: PhantomData,
             };
 
@@ -1084,12 +1290,23 @@ impl GpuTexture {
     pub fn pixel_kind(&self) -> PixelKind {
         self.pixel_kind
     }
+
+    pub fn mip_count(&self) -> usize {
+        self.mip_count
+    }
+
+    /// Returns an estimate of the amount of video memory (in bytes) used by the texture.
+    pub fn size_bytes(&self) -> usize {
+        mip_chain_size_bytes(self.kind, self.pixel_kind, self.mip_count)
+    }
 }
 
 impl Drop for GpuTexture {
     fn drop(&mut self) {
         unsafe {
-            (*self.state).gl.delete_texture(self.texture);
+            if !(*self.state).is_context_lost() {
+                (*self.state).gl.delete_texture(self.texture);
+            }
         }
     }
 }
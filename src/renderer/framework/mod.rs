@@ -3,6 +3,8 @@
 pub mod error;
 pub mod framebuffer;
 pub mod geometry_buffer;
+pub mod gpu_buffer;
 pub mod gpu_program;
 pub mod gpu_texture;
+pub mod query;
 pub mod state;
@@ -0,0 +1,94 @@
+use crate::renderer::framework::{error::FrameworkError, state::PipelineState};
+use glow::HasContext;
+use std::marker::PhantomData;
+
+/// Kind of a GPU query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum QueryKind {
+    /// Checks whether any sample passed depth and stencil tests or not.
+    AnySamplesPassed = glow::ANY_SAMPLES_PASSED,
+    /// Same as [`QueryKind::AnySamplesPassed`], but allows the implementation to use less precise
+    /// and faster test, that may give false positives.
+    AnySamplesPassedConservative = glow::ANY_SAMPLES_PASSED_CONSERVATIVE,
+}
+
+/// A query object allows you to fetch some information from GPU about rendering commands issued
+/// between [`Query::begin`] and [`Query::end`]. Results of a query are available asynchronously,
+/// usually a few frames later.
+pub struct Query {
+    state: *mut PipelineState,
+    id: glow::Query,
+    kind: QueryKind,
+    active: bool,
+    // Force compiler to not implement Send and Sync, because OpenGL is not thread-safe.
+    thread_mark: PhantomData<*const u8>,
+}
+
+impl Query {
+    pub fn new(state: &mut PipelineState, kind: QueryKind) -> Result<Self, FrameworkError> {
+        let id = unsafe { state.gl.create_query()? };
+        Ok(Self {
+            state,
+            id,
+            kind,
+            active: false,
+            thread_mark: PhantomData,
+        })
+    }
+
+    /// Starts the query, every rendering command issued until [`Query::end`] will be accounted.
+    pub fn begin(&mut self, state: &mut PipelineState) {
+        unsafe {
+            state.gl.begin_query(self.kind as u32, self.id);
+        }
+        self.active = true;
+    }
+
+    /// Ends the query.
+    pub fn end(&mut self, state: &mut PipelineState) {
+        unsafe {
+            state.gl.end_query(self.kind as u32);
+        }
+    }
+
+    /// Returns `true` if the query was started and its result is still not fetched.
+    pub fn is_pending(&self) -> bool {
+        self.active
+    }
+
+    /// Tries to fetch result of the query. It never blocks, `None` will be returned if the result
+    /// is not available yet or the query was never started.
+    pub fn try_get_result(&mut self, state: &mut PipelineState) -> Option<u32> {
+        if !self.active {
+            return None;
+        }
+
+        unsafe {
+            if state
+                .gl
+                .get_query_parameter_u32(self.id, glow::QUERY_RESULT_AVAILABLE)
+                != 0
+            {
+                self.active = false;
+                Some(
+                    state
+                        .gl
+                        .get_query_parameter_u32(self.id, glow::QUERY_RESULT),
+                )
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl Drop for Query {
+    fn drop(&mut self) {
+        unsafe {
+            if !(*self.state).is_context_lost() {
+                (*self.state).gl.delete_query(self.id);
+            }
+        }
+    }
+}
@@ -332,4 +332,131 @@ vec4 S_SRGBToLinear(vec4 color) {
 
 float S_Luminance(vec3 x) {
     return dot(x, vec3(0.299, 0.587, 0.114));
-}
\ No newline at end of file
+}
+
+// Fetches a matrix from a texture, where each matrix is stored as 4 consecutive RGBA32F texels (one
+// texel per column). Width of the texture must be a multiple of 4.
+mat4 S_FetchMatrix(sampler2D storage, int index) {
+    int width = textureSize(storage, 0).x;
+    int x = (index * 4) % width;
+    int y = (index * 4) / width;
+    return mat4(
+        texelFetch(storage, ivec2(x, y), 0),
+        texelFetch(storage, ivec2(x + 1, y), 0),
+        texelFetch(storage, ivec2(x + 2, y), 0),
+        texelFetch(storage, ivec2(x + 3, y), 0)
+    );
+}
+// Screen-door transparency. Returns true if a fragment at the given window coordinates must be
+// discarded to make an object look semi-transparent with the given opacity. Uses 4x4 ordered
+// dithering, so it works with opaque render paths that do not support blending.
+bool S_DitherDiscard(vec2 fragCoord, float opacity) {
+    const float thresholds[16] = float[](
+        0.5, 8.5, 2.5, 10.5,
+        12.5, 4.5, 14.5, 6.5,
+        3.5, 11.5, 1.5, 9.5,
+        15.5, 7.5, 13.5, 5.5
+    );
+    int x = int(fragCoord.x) % 4;
+    int y = int(fragCoord.y) % 4;
+    return opacity * 16.0 < thresholds[y * 4 + x];
+}
+
+// Clustered lighting. The view frustum is split into froxels (16x9 tiles on screen and 24 exponential
+// slices in depth), every froxel has a bit mask of lights that affect it. Must be in sync with the
+// constants in `renderer/light/clustered.rs`.
+const int S_CLUSTER_COUNT_X = 16;
+const int S_CLUSTER_COUNT_Y = 9;
+const int S_CLUSTER_COUNT_Z = 24;
+const int S_LIGHTS_PER_CLUSTER_GROUP = 128;
+
+// Returns coordinates of a froxel that contains the given point in world space. Cluster parameters
+// are (zNear, zFar, lightCount, unused).
+ivec3 S_ClusterCoords(vec3 worldPosition, mat4 viewProjection, vec4 clusterParameters)
+{
+    float zNear = clusterParameters.x;
+    float zFar = clusterParameters.y;
+    vec4 clipSpacePosition = viewProjection * vec4(worldPosition, 1.0);
+    // For perspective projection w is the distance to the point along the view direction.
+    float viewDepth = clipSpacePosition.w;
+    vec2 screenPosition = clipSpacePosition.xy / viewDepth * 0.5 + 0.5;
+    float slice = log(max(viewDepth, zNear) / zNear) / log(zFar / zNear);
+    return clamp(
+        ivec3(
+            int(screenPosition.x * float(S_CLUSTER_COUNT_X)),
+            int(screenPosition.y * float(S_CLUSTER_COUNT_Y)),
+            int(slice * float(S_CLUSTER_COUNT_Z))
+        ),
+        ivec3(0),
+        ivec3(S_CLUSTER_COUNT_X - 1, S_CLUSTER_COUNT_Y - 1, S_CLUSTER_COUNT_Z - 1)
+    );
+}
+
+// Calculates lighting from every clustered light that affects a fragment at the given position.
+// Light color and light direction of the context are ignored. Light texture contains 3 texels per
+// light: (position, radius), (color * intensity, cos of half hotspot angle) and (emit direction,
+// cos of half cone angle). Cluster texture contains the bit masks of the froxels.
+vec3 S_ClusteredLighting(
+    TPBRContext ctx,
+    vec3 fragmentPosition,
+    mat4 viewProjection,
+    vec4 clusterParameters,
+    sampler2D lightTexture,
+    highp usampler2D clusterTexture)
+{
+    vec3 lighting = vec3(0.0);
+
+    int lightCount = int(clusterParameters.z);
+    if (lightCount <= 0) {
+        return lighting;
+    }
+
+    ivec3 cluster = S_ClusterCoords(fragmentPosition, viewProjection, clusterParameters);
+    int groupCount = (lightCount + S_LIGHTS_PER_CLUSTER_GROUP - 1) / S_LIGHTS_PER_CLUSTER_GROUP;
+
+    for (int group = 0; group < groupCount; ++group) {
+        uvec4 mask = texelFetch(
+            clusterTexture,
+            ivec2(cluster.x + cluster.y * S_CLUSTER_COUNT_X, cluster.z + group * S_CLUSTER_COUNT_Z),
+            0);
+
+        for (int word = 0; word < 4; ++word) {
+            uint bits = mask[word];
+            int bit = 0;
+            while (bits != 0u) {
+                if ((bits & 1u) != 0u) {
+                    int index = group * S_LIGHTS_PER_CLUSTER_GROUP + word * 32 + bit;
+
+                    vec4 positionRadius = texelFetch(lightTexture, ivec2(index, 0), 0);
+                    vec4 colorHotspot = texelFetch(lightTexture, ivec2(index, 1), 0);
+                    vec4 directionCone = texelFetch(lightTexture, ivec2(index, 2), 0);
+
+                    vec3 fragmentToLight = positionRadius.xyz - fragmentPosition;
+                    float distance = length(fragmentToLight);
+
+                    ctx.fragmentToLight = fragmentToLight / max(distance, 0.0001);
+                    ctx.lightColor = colorHotspot.rgb;
+
+                    float distanceAttenuation = S_LightDistanceAttenuation(distance, positionRadius.w);
+                    float coneFactor = smoothstep(
+                        directionCone.w, colorHotspot.w, dot(directionCone.xyz, ctx.fragmentToLight));
+
+                    lighting += distanceAttenuation * coneFactor * S_PBR_CalculateLight(ctx);
+                }
+
+                bits >>= 1u;
+                ++bit;
+            }
+        }
+    }
+
+    return lighting;
+}
+
+// Weight of a fragment for weighted blended order-independent transparency (equation 10 from
+// "Weighted Blended Order-Independent Transparency" by McGuire and Bavoil). Closer and more
+// opaque fragments have greater weight.
+float S_OitWeight(float depth, float alpha)
+{
+    return clamp(pow(min(1.0, alpha * 10.0) + 0.01, 3.0) * 1e8 * pow(1.0 - depth * 0.9, 3.0), 1e-2, 3e3);
+}
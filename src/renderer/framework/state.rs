@@ -1,11 +1,13 @@
 use crate::{
-    core::{color::Color, math::Rect, visitor::prelude::*},
+    core::{color::Color, math::Rect, reflect::prelude::*, visitor::prelude::*},
     renderer::framework::framebuffer::{CullFace, DrawParameters},
     utils::log::{Log, MessageKind},
 };
+use bitflags::bitflags;
 use glow::{Framebuffer, HasContext};
 use serde::Deserialize;
 use std::fmt::{Display, Formatter};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 #[derive(Debug, Default, Copy, Clone)]
 pub struct PipelineStatistics {
@@ -38,7 +40,22 @@ impl Display for PipelineStatistics {
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Ord, Hash, Visit, Deserialize, Debug)]
+#[derive(
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Hash,
+    Visit,
+    Deserialize,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
 #[repr(u32)]
 pub enum CompareFunc {
     /// Never passes.
@@ -72,7 +89,22 @@ impl Default for CompareFunc {
     }
 }
 
-#[derive(Copy, Clone, Hash, PartialOrd, PartialEq, Eq, Ord, Deserialize, Visit, Debug)]
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Deserialize,
+    Visit,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
 #[repr(u32)]
 pub enum BlendFactor {
     Zero = glow::ZERO,
@@ -102,7 +134,22 @@ impl Default for BlendFactor {
     }
 }
 
-#[derive(Copy, Clone, Hash, PartialOrd, PartialEq, Eq, Ord, Deserialize, Visit, Debug)]
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Deserialize,
+    Visit,
+    Debug,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
 #[repr(u32)]
 pub enum BlendMode {
     Add = glow::FUNC_ADD,
@@ -118,13 +165,15 @@ impl Default for BlendMode {
     }
 }
 
-#[derive(Copy, Clone, Default, PartialOrd, PartialEq, Ord, Eq, Hash, Deserialize, Visit, Debug)]
+#[derive(
+    Copy, Clone, Default, PartialOrd, PartialEq, Ord, Eq, Hash, Deserialize, Visit, Debug, Reflect,
+)]
 pub struct BlendEquation {
-    rgb: BlendMode,
-    alpha: BlendMode,
+    pub rgb: BlendMode,
+    pub alpha: BlendMode,
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Deserialize, Visit, Debug)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Ord, Eq, Hash, Deserialize, Visit, Debug, Reflect)]
 pub struct BlendFunc {
     pub sfactor: BlendFactor,
     pub dfactor: BlendFactor,
@@ -168,6 +217,22 @@ impl Default for BlendFunc {
     }
 }
 
+// Desktop robust contexts report GL_CONTEXT_LOST, WebGL reports CONTEXT_LOST_WEBGL.
+const CONTEXT_LOST: u32 = 0x0507;
+const CONTEXT_LOST_WEBGL: u32 = 0x9242;
+
+/// A pointer to `glGetGraphicsResetStatus` function (or one of its ARB/EXT/KHR aliases). It returns
+/// non-zero reset status if the context was lost.
+pub type GetGraphicsResetStatus = unsafe extern "system" fn() -> u32;
+
+/// A pointer to `glGetProgramiv` function. It is used to query completion status of programs,
+/// that are compiled and linked in parallel by the driver.
+pub type GetProgramParameter = unsafe extern "system" fn(program: u32, name: u32, value: *mut i32);
+
+// GL_COMPLETION_STATUS_KHR (and GL_COMPLETION_STATUS_ARB, which has the same value).
+#[cfg(not(target_arch = "wasm32"))]
+const COMPLETION_STATUS: u32 = 0x91B1;
+
 pub struct PipelineState {
     pub gl: glow::Context,
 
@@ -203,6 +268,90 @@ pub struct PipelineState {
     vbo: Option<glow::Buffer>,
 
     frame_statistics: PipelineStatistics,
+
+    context_lost: bool,
+    get_graphics_reset_status: Option<GetGraphicsResetStatus>,
+    get_program_parameter: Option<GetProgramParameter>,
+
+    gles: bool,
+    compute_supported: bool,
+    parallel_shader_compile: bool,
+}
+
+bitflags! {
+    /// A set of memory barriers, that defines which operations must see the results of writes,
+    /// made by compute shaders (or any other shaders) to storage buffers and images. See
+    /// [`PipelineState::memory_barrier`].
+    pub struct MemoryBarrier: u32 {
+        /// Vertex data sourced from buffers, that were written by shaders.
+        const VERTEX_ATTRIB_ARRAY = glow::VERTEX_ATTRIB_ARRAY_BARRIER_BIT;
+        /// Indices sourced from buffers, that were written by shaders.
+        const ELEMENT_ARRAY = glow::ELEMENT_ARRAY_BARRIER_BIT;
+        /// Uniform blocks sourced from buffers, that were written by shaders.
+        const UNIFORM = glow::UNIFORM_BARRIER_BIT;
+        /// Texture fetches from textures, that were written by shaders using image store.
+        const TEXTURE_FETCH = glow::TEXTURE_FETCH_BARRIER_BIT;
+        /// Image load, store and atomic operations.
+        const SHADER_IMAGE_ACCESS = glow::SHADER_IMAGE_ACCESS_BARRIER_BIT;
+        /// Indirect draw and dispatch commands sourced from buffers, that were written by shaders.
+        const COMMAND = glow::COMMAND_BARRIER_BIT;
+        /// Reads and writes of buffers using pixel pack and unpack operations.
+        const PIXEL_BUFFER = glow::PIXEL_BUFFER_BARRIER_BIT;
+        /// Texture uploads and read-backs.
+        const TEXTURE_UPDATE = glow::TEXTURE_UPDATE_BARRIER_BIT;
+        /// Buffer uploads and read-backs.
+        const BUFFER_UPDATE = glow::BUFFER_UPDATE_BARRIER_BIT;
+        /// Reads and writes of frame buffer attachments.
+        const FRAMEBUFFER = glow::FRAMEBUFFER_BARRIER_BIT;
+        /// Shader storage buffer accesses.
+        const SHADER_STORAGE = glow::SHADER_STORAGE_BARRIER_BIT;
+        /// Every possible barrier.
+        const ALL = glow::ALL_BARRIER_BITS;
+    }
+}
+
+#[allow(unused_variables)]
+fn is_gles(context: &glow::Context) -> bool {
+    // WebGL 2 is based on OpenGL ES 3.0.
+    #[cfg(target_arch = "wasm32")]
+    {
+        true
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    unsafe {
+        context
+            .get_parameter_string(glow::VERSION)
+            .contains("OpenGL ES")
+    }
+}
+
+#[allow(unused_variables)]
+fn is_compute_supported(context: &glow::Context, gles: bool) -> bool {
+    // WebGL 2 does not support compute shaders at all.
+    #[cfg(target_arch = "wasm32")]
+    {
+        false
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    unsafe {
+        let version = (
+            context.get_parameter_i32(glow::MAJOR_VERSION),
+            context.get_parameter_i32(glow::MINOR_VERSION),
+        );
+        if gles {
+            version >= (3, 1)
+        } else {
+            version >= (4, 3)
+        }
+    }
+}
+
+fn is_parallel_shader_compile_supported(context: &glow::Context) -> bool {
+    let extensions = context.supported_extensions();
+    extensions.contains("GL_KHR_parallel_shader_compile")
+        || extensions.contains("GL_ARB_parallel_shader_compile")
 }
 
 #[derive(Copy, Clone)]
@@ -250,7 +399,7 @@ impl ColorMask {
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Deserialize, Visit, Eq)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Deserialize, Visit, Eq, Reflect)]
 pub struct StencilFunc {
     pub func: CompareFunc,
     pub ref_value: u32,
@@ -267,7 +416,21 @@ impl Default for StencilFunc {
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Deserialize, Visit, Eq)]
+#[derive(
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Hash,
+    Debug,
+    Deserialize,
+    Visit,
+    Eq,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
 #[repr(u32)]
 pub enum StencilAction {
     /// Keeps the current value.
@@ -307,7 +470,7 @@ impl Default for StencilAction {
     }
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Deserialize, Visit, Eq)]
+#[derive(Copy, Clone, PartialOrd, PartialEq, Hash, Debug, Deserialize, Visit, Eq, Reflect)]
 pub struct StencilOp {
     pub fail: StencilAction,
     pub zfail: StencilAction,
@@ -332,6 +495,10 @@ impl PipelineState {
             context.depth_func(CompareFunc::default() as u32);
         }
 
+        let gles = is_gles(&context);
+        let compute_supported = is_compute_supported(&context, gles);
+        let parallel_shader_compile = is_parallel_shader_compile_supported(&context);
+
         Self {
             gl: context,
             blend: false,
@@ -358,6 +525,108 @@ impl PipelineState {
             vbo: Default::default(),
             frame_statistics: Default::default(),
             blend_equation: Default::default(),
+            context_lost: false,
+            get_graphics_reset_status: None,
+            get_program_parameter: None,
+            gles,
+            compute_supported,
+            parallel_shader_compile,
+        }
+    }
+
+    /// Checks whether the underlying context was lost (GPU reset, driver update, GPU switch,
+    /// browser reclaimed WebGL context, etc.). Once the context is lost, every GPU object that
+    /// was created using it becomes invalid and must be re-created using new context.
+    pub fn check_context_loss(&mut self) -> bool {
+        if !self.context_lost {
+            if let Some(get_graphics_reset_status) = self.get_graphics_reset_status {
+                if unsafe { get_graphics_reset_status() } != glow::NO_ERROR {
+                    self.context_lost = true;
+                }
+            }
+
+            if !self.context_lost {
+                // Also detects loss of WebGL contexts and reports every other error.
+                self.check_error();
+            }
+        }
+        self.context_lost
+    }
+
+    /// Sets a function, that will be used to query reset status of the context. Without it, the
+    /// context loss is detected only by `GL_CONTEXT_LOST` error code.
+    pub fn set_graphics_reset_status_fn(&mut self, func: Option<GetGraphicsResetStatus>) {
+        self.get_graphics_reset_status = func;
+    }
+
+    /// Sets a function, that will be used to query completion status of programs. Without it, the
+    /// programs are always compiled and linked synchronously, see
+    /// [`Self::is_parallel_shader_compile_supported`].
+    pub fn set_program_parameter_fn(&mut self, func: Option<GetProgramParameter>) {
+        self.get_program_parameter = func;
+    }
+
+    /// Returns `true` if the driver is able to compile and link programs on its own threads
+    /// (`KHR_parallel_shader_compile` extension), so the renderer could keep rendering while
+    /// programs are compiling and check their status without blocking.
+    pub fn is_parallel_shader_compile_supported(&self) -> bool {
+        self.parallel_shader_compile && self.get_program_parameter.is_some()
+    }
+
+    /// Returns `true` if compilation and linking of the program is finished (successfully or not),
+    /// so its status could be queried without blocking. Always `true` if parallel compilation is
+    /// not supported.
+    #[allow(unused_variables)]
+    pub fn is_program_completed(&self, program: glow::Program) -> bool {
+        match self.get_program_parameter {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(get_program_parameter) if self.parallel_shader_compile => {
+                // `glow` does not expose names of its objects, but a native program is just a
+                // wrapper around non-zero name.
+                let name = unsafe {
+                    std::mem::transmute::<glow::Program, std::num::NonZeroU32>(program).get()
+                };
+                let mut status = 0;
+                unsafe { get_program_parameter(name, COMPLETION_STATUS, &mut status) };
+                status != 0
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns `true` if the context was lost. See [`Self::check_context_loss`].
+    pub fn is_context_lost(&self) -> bool {
+        self.context_lost
+    }
+
+    /// Marks the context as lost. GPU objects won't be deleted on drop after this call, because
+    /// their names are meaningless now.
+    pub fn set_context_lost(&mut self) {
+        self.context_lost = true;
+    }
+
+    /// Returns `true` if the context is OpenGL ES (or WebGL) context.
+    pub fn is_gles(&self) -> bool {
+        self.gles
+    }
+
+    /// Returns `true` if compute shaders (along with shader storage buffers and image load/store)
+    /// are supported. They require OpenGL 4.3 or OpenGL ES 3.1, compute shaders are not available
+    /// on WebAssembly.
+    pub fn is_compute_supported(&self) -> bool {
+        self.compute_supported
+    }
+
+    /// Defines a barrier ordering memory transactions. Writes to storage buffers and images made
+    /// by shaders are not visible to subsequent commands until the respective barrier is issued.
+    /// For example, if a compute shader writes vertices of particles to a storage buffer, that is
+    /// then used as a vertex buffer, [`MemoryBarrier::VERTEX_ATTRIB_ARRAY`] must be issued after
+    /// the dispatch.
+    pub fn memory_barrier(&mut self, barrier: MemoryBarrier) {
+        if self.compute_supported {
+            unsafe {
+                self.gl.memory_barrier(barrier.bits());
+            }
         }
     }
 
@@ -611,10 +880,11 @@ impl PipelineState {
                     self.stencil_op.zfail as u32,
                     self.stencil_op.zpass as u32,
                 );
-
-                self.gl.stencil_mask(self.stencil_op.write_mask);
             }
         }
+
+        // Write mask is shared with clearing routines, so it must be tracked separately.
+        self.set_stencil_mask(op.write_mask);
     }
 
     pub fn set_vertex_array_object(&mut self, vao: Option<glow::VertexArray>) {
@@ -747,10 +1017,13 @@ impl PipelineState {
     }
 
     /// Checks for errors, returns true if any error has occurred.
-    pub fn check_error(&self) -> bool {
+    pub fn check_error(&mut self) -> bool {
         unsafe {
             let error_code = self.gl.get_error();
-            if error_code != glow::NO_ERROR {
+            if error_code == CONTEXT_LOST || error_code == CONTEXT_LOST_WEBGL {
+                self.context_lost = true;
+                true
+            } else if error_code != glow::NO_ERROR {
                 let code = match error_code {
                     glow::INVALID_ENUM => "GL_INVALID_ENUM",
                     glow::INVALID_VALUE => "GL_INVALID_VALUE",
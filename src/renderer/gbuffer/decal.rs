@@ -12,6 +12,7 @@ pub struct DecalShader {
     pub normal_texture: UniformLocation,
     pub inv_view_proj: UniformLocation,
     pub inv_world_decal: UniformLocation,
+    pub decal_axis: UniformLocation,
     pub resolution: UniformLocation,
     pub color: UniformLocation,
     pub layer_index: UniformLocation,
@@ -37,6 +38,7 @@ impl DecalShader {
             inv_view_proj: program.uniform_location(state, &ImmutableString::new("invViewProj"))?,
             inv_world_decal: program
                 .uniform_location(state, &ImmutableString::new("invWorldDecal"))?,
+            decal_axis: program.uniform_location(state, &ImmutableString::new("decalAxis"))?,
             resolution: program.uniform_location(state, &ImmutableString::new("resolution"))?,
             color: program.uniform_location(state, &ImmutableString::new("color"))?,
             layer_index: program.uniform_location(state, &ImmutableString::new("layerIndex"))?,
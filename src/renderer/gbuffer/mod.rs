@@ -15,15 +15,15 @@ use crate::renderer::framework::geometry_buffer::{GeometryBuffer, GeometryBuffer
 use crate::scene::decal::Decal;
 use crate::{
     core::{
-        algebra::{Matrix4, Vector2},
+        algebra::{Matrix4, Vector2, Vector3},
         color::Color,
         math::Rect,
         scope_profile,
     },
     renderer::{
         apply_material,
-        batch::BatchStorage,
-        cache::shader::ShaderCache,
+        batch::{BatchStorage, InstanceData},
+        cache::{geometry::INSTANCE_BUFFER_INDEX, shader::ShaderCache},
         framework::{
             error::FrameworkError,
             framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
@@ -35,6 +35,7 @@ use crate::{
             state::{BlendFactor, BlendFunc, PipelineState},
         },
         gbuffer::decal::DecalShader,
+        occlusion::OcclusionTester,
         GeometryCache, MaterialContext, RenderPassStatistics, TextureCache,
     },
     scene::{camera::Camera, graph::Graph, mesh::surface::SurfaceData, mesh::RenderPath},
@@ -67,6 +68,7 @@ pub(crate) struct GBufferRenderContext<'a, 'b> {
     pub black_dummy: Rc<RefCell<GpuTexture>>,
     pub use_parallax_mapping: bool,
     pub graph: &'b Graph,
+    pub occlusion_tester: Option<&'a OcclusionTester>,
 }
 
 impl GBuffer {
@@ -271,6 +273,7 @@ impl GBuffer {
             normal_dummy,
             black_dummy,
             graph,
+            occlusion_tester,
             ..
         } = args;
 
@@ -285,6 +288,8 @@ impl GBuffer {
 
         let initial_view_projection = camera.view_projection_matrix();
 
+        let mut instance_data = Vec::new();
+
         for batch in batch_storage
             .batches
             .iter()
@@ -297,8 +302,24 @@ impl GBuffer {
                 .get(state, material.shader())
                 .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
             {
+                let draw_params = material.render_state().apply(&render_pass.draw_params);
+                let use_instancing =
+                    batch.use_instancing && render_pass.program.is_instancing_supported();
+
+                instance_data.clear();
+
                 for instance in batch.instances.iter() {
-                    if camera.visibility_cache.is_visible(instance.owner) {
+                    if camera.visibility_cache.is_visible(instance.owner)
+                        && occlusion_tester.map_or(true, |tester| tester.is_visible(instance.owner))
+                    {
+                        if use_instancing && instance.is_instanceable() {
+                            instance_data.push(InstanceData {
+                                world_matrix: instance.world_transform,
+                                color: instance.color,
+                            });
+                            continue;
+                        }
+
                         let apply_uniforms = |mut program_binding: GpuProgramBinding| {
                             let view_projection = if instance.depth_offset != 0.0 {
                                 let mut projection = camera.projection_matrix();
@@ -310,15 +331,21 @@ impl GBuffer {
 
                             apply_material(MaterialContext {
                                 material: &material,
+                                property_block: instance.property_block.as_ref(),
                                 program_binding: &mut program_binding,
                                 texture_cache,
                                 world_matrix: &instance.world_transform,
                                 wvp_matrix: &(view_projection * instance.world_transform),
                                 bone_matrices: &instance.bone_matrices,
+                                bone_matrices_offset: instance.bone_matrices_offset,
+                                bone_matrices_texture: batch_storage.bone_matrices_texture(),
                                 use_skeletal_animation: batch.is_skinned,
                                 camera_position: &camera.global_position(),
                                 use_pom: use_parallax_mapping,
                                 light_position: &Default::default(),
+                                opacity: instance.opacity,
+                                clustered_lighting: None,
+                                use_instancing: false,
                                 normal_dummy: normal_dummy.clone(),
                                 white_dummy: white_dummy.clone(),
                                 black_dummy: black_dummy.clone(),
@@ -330,11 +357,49 @@ impl GBuffer {
                             state,
                             viewport,
                             &render_pass.program,
-                            &render_pass.draw_params,
+                            &draw_params,
                             apply_uniforms,
                         );
                     }
                 }
+
+                // Every instanceable instance is drawn at once, world matrices and colors of the
+                // instances are taken from the instance buffer.
+                if !instance_data.is_empty() {
+                    geometry.set_buffer_data(state, INSTANCE_BUFFER_INDEX, &instance_data);
+
+                    statistics += self.framebuffer.draw_instances(
+                        instance_data.len(),
+                        geometry,
+                        state,
+                        viewport,
+                        &render_pass.program,
+                        &draw_params,
+                        |mut program_binding| {
+                            apply_material(MaterialContext {
+                                material: &material,
+                                property_block: None,
+                                program_binding: &mut program_binding,
+                                texture_cache,
+                                world_matrix: &Matrix4::identity(),
+                                wvp_matrix: &initial_view_projection,
+                                bone_matrices: &[],
+                                bone_matrices_offset: 0,
+                                bone_matrices_texture: None,
+                                use_skeletal_animation: false,
+                                camera_position: &camera.global_position(),
+                                use_pom: use_parallax_mapping,
+                                light_position: &Default::default(),
+                                opacity: 1.0,
+                                clustered_lighting: None,
+                                use_instancing: true,
+                                normal_dummy: normal_dummy.clone(),
+                                white_dummy: white_dummy.clone(),
+                                black_dummy: black_dummy.clone(),
+                            });
+                        },
+                    );
+                }
             }
         }
 
@@ -362,6 +427,10 @@ impl GBuffer {
                 .unwrap_or_else(|| normal_dummy.clone());
 
             let world_view_proj = initial_view_projection * decal.global_transform();
+            let decal_axis = decal
+                .up_vector()
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::y);
 
             statistics += self.decal_framebuffer.draw(
                 unit_cube,
@@ -388,6 +457,7 @@ impl GBuffer {
                             &shader.inv_world_decal,
                             &decal.global_transform().try_inverse().unwrap_or_default(),
                         )
+                        .set_vector3(&shader.decal_axis, &decal_axis)
                         .set_vector2(&shader.resolution, &resolution)
                         .set_texture(&shader.scene_depth, &depth)
                         .set_texture(&shader.diffuse_texture, &diffuse_texture)
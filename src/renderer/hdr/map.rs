@@ -18,6 +18,11 @@ pub struct MapShader {
     pub max_luminance: UniformLocation,
     pub auto_exposure: UniformLocation,
     pub fixed_exposure: UniformLocation,
+    pub bloom_intensity: UniformLocation,
+    pub vignette_intensity: UniformLocation,
+    pub vignette_radius: UniformLocation,
+    pub vignette_smoothness: UniformLocation,
+    pub chromatic_aberration: UniformLocation,
 }
 
 impl MapShader {
@@ -48,6 +53,16 @@ impl MapShader {
                 .uniform_location(state, &ImmutableString::new("autoExposure"))?,
             fixed_exposure: program
                 .uniform_location(state, &ImmutableString::new("fixedExposure"))?,
+            bloom_intensity: program
+                .uniform_location(state, &ImmutableString::new("bloomIntensity"))?,
+            vignette_intensity: program
+                .uniform_location(state, &ImmutableString::new("vignetteIntensity"))?,
+            vignette_radius: program
+                .uniform_location(state, &ImmutableString::new("vignetteRadius"))?,
+            vignette_smoothness: program
+                .uniform_location(state, &ImmutableString::new("vignetteSmoothness"))?,
+            chromatic_aberration: program
+                .uniform_location(state, &ImmutableString::new("chromaticAberration"))?,
             program,
         })
     }
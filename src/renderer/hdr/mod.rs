@@ -23,7 +23,7 @@ use crate::{
         },
         make_viewport_matrix, RenderPassStatistics,
     },
-    scene::camera::{ColorGradingLut, Exposure},
+    scene::camera::{ColorGradingLut, Exposure, PostProcessing},
 };
 use std::{cell::RefCell, rc::Rc};
 
@@ -257,6 +257,7 @@ impl HighDynamicRangeRenderer {
         exposure: Exposure,
         color_grading_lut: Option<&ColorGradingLut>,
         use_color_grading: bool,
+        post_processing: &PostProcessing,
         texture_cache: &mut TextureCache,
     ) -> DrawCallStatistics {
         let shader = &self.map_shader;
@@ -291,7 +292,31 @@ impl HighDynamicRangeRenderer {
                         &shader.use_color_grading,
                         use_color_grading && color_grading_lut.is_some(),
                     )
-                    .set_texture(&shader.color_map_sampler, &color_grading_lut_tex);
+                    .set_texture(&shader.color_map_sampler, &color_grading_lut_tex)
+                    .set_f32(&shader.bloom_intensity, post_processing.bloom.intensity);
+
+                let vignette = &post_processing.vignette;
+                program_binding
+                    .set_f32(
+                        &shader.vignette_intensity,
+                        if vignette.enabled {
+                            vignette.intensity
+                        } else {
+                            0.0
+                        },
+                    )
+                    .set_f32(&shader.vignette_radius, vignette.radius)
+                    .set_f32(&shader.vignette_smoothness, vignette.smoothness);
+
+                let chromatic_aberration = &post_processing.chromatic_aberration;
+                program_binding.set_f32(
+                    &shader.chromatic_aberration,
+                    if chromatic_aberration.enabled {
+                        chromatic_aberration.strength
+                    } else {
+                        0.0
+                    },
+                );
 
                 match exposure {
                     Exposure::Auto {
@@ -327,6 +352,7 @@ impl HighDynamicRangeRenderer {
         exposure: Exposure,
         color_grading_lut: Option<&ColorGradingLut>,
         use_color_grading: bool,
+        post_processing: &PostProcessing,
         texture_cache: &mut TextureCache,
     ) -> RenderPassStatistics {
         let mut stats = RenderPassStatistics::default();
@@ -343,6 +369,7 @@ impl HighDynamicRangeRenderer {
             exposure,
             color_grading_lut,
             use_color_grading,
+            post_processing,
             texture_cache,
         );
         stats
@@ -0,0 +1,454 @@
+//! Clustered lighting splits the view frustum into a grid of froxels (frustum-aligned voxels) and
+//! finds lights that affect every froxel on GPU. Then every lit fragment is shaded in a single pass
+//! using only the lights of its froxel, instead of drawing every light separately. This makes scenes
+//! with hundreds of small lights much cheaper to render.
+//!
+//! Only lights without shadows and cookies could be clustered, other lights use the usual per-light
+//! path. The same cluster data is used to light forward pass of the built-in standard and terrain
+//! shaders (transparent surfaces), so they are lit by clustered lights too. Custom forward pass
+//! shaders can use it as well, see [`crate::material::shader::Shader`] docs for the list of
+//! built-in variables.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3, Vector4},
+        math::Rect,
+        scope_profile,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{
+                Attachment, AttachmentKind, BlendParameters, DrawParameters, FrameBuffer,
+            },
+            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+            },
+            state::{BlendFactor, BlendFunc, PipelineState},
+        },
+        gbuffer::GBuffer,
+        RenderPassStatistics,
+    },
+    scene::{
+        camera::{Camera, Projection},
+        light::{point::PointLight, spot::SpotLight},
+        mesh::surface::SurfaceData,
+        node::Node,
+    },
+    utils::{array_as_u8_slice, log::Log},
+};
+use std::{cell::RefCell, rc::Rc};
+
+// Must be in sync with the constants in `shared.glsl`.
+/// Amount of screen tiles along X axis.
+pub const CLUSTER_COUNT_X: usize = 16;
+/// Amount of screen tiles along Y axis.
+pub const CLUSTER_COUNT_Y: usize = 9;
+/// Amount of depth slices.
+pub const CLUSTER_COUNT_Z: usize = 24;
+/// Amount of lights, that could be stored in a single texel of the cluster texture.
+pub const LIGHTS_PER_CLUSTER_GROUP: usize = 128;
+/// Maximum amount of lights, that could be clustered per camera. Other lights will be rendered using
+/// per-light path.
+pub const MAX_CLUSTERED_LIGHTS: usize = 1024;
+
+const MAX_CLUSTER_GROUPS: usize = MAX_CLUSTERED_LIGHTS / LIGHTS_PER_CLUSTER_GROUP;
+const LIGHT_DATA_ROWS: usize = 3;
+
+struct ClusterBinningShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    light_texture: UniformLocation,
+    view_matrix: UniformLocation,
+    inv_projection: UniformLocation,
+    cluster_parameters: UniformLocation,
+}
+
+impl ClusterBinningShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/cluster_binning_fs.glsl");
+        let vertex_source = include_str!("../shaders/deferred_light_vs.glsl");
+        let program = GpuProgram::from_source(
+            state,
+            "ClusterBinningShader",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            light_texture: program
+                .uniform_location(state, &ImmutableString::new("lightTexture"))?,
+            view_matrix: program.uniform_location(state, &ImmutableString::new("viewMatrix"))?,
+            inv_projection: program
+                .uniform_location(state, &ImmutableString::new("invProjection"))?,
+            cluster_parameters: program
+                .uniform_location(state, &ImmutableString::new("clusterParameters"))?,
+            program,
+        })
+    }
+}
+
+struct ClusteredLightShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    depth_sampler: UniformLocation,
+    color_sampler: UniformLocation,
+    normal_sampler: UniformLocation,
+    material_sampler: UniformLocation,
+    light_texture: UniformLocation,
+    cluster_texture: UniformLocation,
+    inv_view_proj_matrix: UniformLocation,
+    view_projection_matrix: UniformLocation,
+    camera_position: UniformLocation,
+    cluster_parameters: UniformLocation,
+}
+
+impl ClusteredLightShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("../shaders/deferred_clustered_light_fs.glsl");
+        let vertex_source = include_str!("../shaders/deferred_light_vs.glsl");
+        let program = GpuProgram::from_source(
+            state,
+            "ClusteredLightShader",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthTexture"))?,
+            color_sampler: program
+                .uniform_location(state, &ImmutableString::new("colorTexture"))?,
+            normal_sampler: program
+                .uniform_location(state, &ImmutableString::new("normalTexture"))?,
+            material_sampler: program
+                .uniform_location(state, &ImmutableString::new("materialTexture"))?,
+            light_texture: program
+                .uniform_location(state, &ImmutableString::new("lightTexture"))?,
+            cluster_texture: program
+                .uniform_location(state, &ImmutableString::new("clusterTexture"))?,
+            inv_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("invViewProj"))?,
+            view_projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("viewProjection"))?,
+            camera_position: program
+                .uniform_location(state, &ImmutableString::new("cameraPosition"))?,
+            cluster_parameters: program
+                .uniform_location(state, &ImmutableString::new("clusterParameters"))?,
+            program,
+        })
+    }
+}
+
+/// Parameters of a light, that is rendered using clustered lighting.
+#[derive(Debug, Clone)]
+pub(crate) struct ClusteredLight {
+    pub position: Vector3<f32>,
+    pub radius: f32,
+    /// Linear color multiplied by the intensity of the light.
+    pub color: Vector3<f32>,
+    pub direction: Vector3<f32>,
+    pub half_hotspot_cone_angle_cos: f32,
+    pub half_cone_angle_cos: f32,
+}
+
+impl ClusteredLight {
+    /// Creates clustered light from the given scene node. Returns `None` if the node is not a point or
+    /// a spot light, or if it is a spot light with cookie texture.
+    pub fn from_node(
+        node: &Node,
+        position: Vector3<f32>,
+        radius: f32,
+        direction: Vector3<f32>,
+    ) -> Option<Self> {
+        let (base_light, half_hotspot_cone_angle_cos, half_cone_angle_cos) =
+            if let Some(spot_light) = node.cast::<SpotLight>() {
+                if spot_light.cookie_texture_ref().is_some() {
+                    return None;
+                }
+                (
+                    spot_light.base_light_ref(),
+                    (spot_light.hotspot_cone_angle() * 0.5).cos(),
+                    (spot_light.full_cone_angle() * 0.5).cos(),
+                )
+            } else if let Some(point_light) = node.cast::<PointLight>() {
+                // Any direction is inside such "cone".
+                (point_light.base_light_ref(), -1.0, -2.0)
+            } else {
+                return None;
+            };
+
+        Some(Self {
+            position,
+            radius,
+            color: base_light.color().srgb_to_linear_f32().xyz() * base_light.intensity(),
+            direction,
+            half_hotspot_cone_angle_cos,
+            half_cone_angle_cos,
+        })
+    }
+}
+
+/// See module docs.
+pub struct ClusteredLighting {
+    binning_shader: ClusterBinningShader,
+    light_shader: ClusteredLightShader,
+    cluster_framebuffer: FrameBuffer,
+    light_texture: Rc<RefCell<GpuTexture>>,
+    quad: GeometryBuffer,
+    light_data: Vec<Vector4<f32>>,
+    light_count: usize,
+    parameters: Vector4<f32>,
+    view_projection: Matrix4<f32>,
+}
+
+impl ClusteredLighting {
+    pub fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let cluster_texture = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle {
+                width: CLUSTER_COUNT_X * CLUSTER_COUNT_Y,
+                height: CLUSTER_COUNT_Z * MAX_CLUSTER_GROUPS,
+            },
+            PixelKind::RGBA32UI,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+
+        let light_texture = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle {
+                width: MAX_CLUSTERED_LIGHTS,
+                height: LIGHT_DATA_ROWS,
+            },
+            PixelKind::RGBA32F,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+
+        Ok(Self {
+            binning_shader: ClusterBinningShader::new(state)?,
+            light_shader: ClusteredLightShader::new(state)?,
+            cluster_framebuffer: FrameBuffer::new(
+                state,
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: Rc::new(RefCell::new(cluster_texture)),
+                }],
+            )?,
+            light_texture: Rc::new(RefCell::new(light_texture)),
+            quad: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                GeometryBufferKind::StaticDraw,
+                state,
+            ),
+            light_data: vec![Default::default(); MAX_CLUSTERED_LIGHTS * LIGHT_DATA_ROWS],
+            light_count: 0,
+            parameters: Default::default(),
+            view_projection: Matrix4::identity(),
+        })
+    }
+
+    /// Removes every light and prepares clusters for the given camera. Only cameras with perspective
+    /// projection are supported.
+    pub(crate) fn begin(&mut self, camera: &Camera) {
+        self.light_count = 0;
+        self.view_projection = camera.view_projection_matrix();
+
+        let projection = camera.projection();
+        let z_near = projection.z_near().max(f32::EPSILON);
+        self.parameters = Vector4::new(z_near, projection.z_far().max(z_near * 2.0), 0.0, 0.0);
+    }
+
+    /// Returns `true` if lights could be clustered for the given camera.
+    pub(crate) fn is_supported(camera: &Camera) -> bool {
+        matches!(camera.projection(), Projection::Perspective(_))
+    }
+
+    /// Adds new light to the clusters. Returns `false` if there is no more space for lights.
+    pub(crate) fn push_light(&mut self, light: ClusteredLight) -> bool {
+        if self.light_count >= MAX_CLUSTERED_LIGHTS {
+            return false;
+        }
+
+        let index = self.light_count;
+        let (position, color, direction) = (light.position, light.color, light.direction);
+        self.light_data[index] = Vector4::new(position.x, position.y, position.z, light.radius);
+        self.light_data[MAX_CLUSTERED_LIGHTS + index] =
+            Vector4::new(color.x, color.y, color.z, light.half_hotspot_cone_angle_cos);
+        self.light_data[2 * MAX_CLUSTERED_LIGHTS + index] = Vector4::new(
+            direction.x,
+            direction.y,
+            direction.z,
+            light.half_cone_angle_cos,
+        );
+
+        self.light_count += 1;
+        self.parameters.z = self.light_count as f32;
+
+        true
+    }
+
+    /// Amount of lights, that were clustered for the last camera.
+    pub fn light_count(&self) -> usize {
+        self.light_count
+    }
+
+    /// Returns a texture with parameters of the clustered lights.
+    pub fn light_texture(&self) -> Rc<RefCell<GpuTexture>> {
+        self.light_texture.clone()
+    }
+
+    /// Returns a texture with bit masks of the lights of every froxel.
+    pub fn cluster_texture(&self) -> Rc<RefCell<GpuTexture>> {
+        self.cluster_framebuffer.color_attachments()[0]
+            .texture
+            .clone()
+    }
+
+    /// Returns (z_near, z_far, light_count, 0) of the last camera.
+    pub fn parameters(&self) -> &Vector4<f32> {
+        &self.parameters
+    }
+
+    /// Returns view-projection matrix of the last camera.
+    pub fn view_projection(&self) -> &Matrix4<f32> {
+        &self.view_projection
+    }
+
+    /// Finds lights of every froxel and then calculates lighting for every pixel of the G-Buffer.
+    pub(crate) fn render(
+        &mut self,
+        state: &mut PipelineState,
+        camera: &Camera,
+        gbuffer: &GBuffer,
+        frame_buffer: &mut FrameBuffer,
+        viewport: Rect<i32>,
+    ) -> RenderPassStatistics {
+        scope_profile!();
+
+        let mut stats = RenderPassStatistics::default();
+
+        if self.light_count == 0 {
+            return stats;
+        }
+
+        if let Err(e) = self.light_texture.borrow_mut().bind_mut(state, 0).set_data(
+            GpuTextureKind::Rectangle {
+                width: MAX_CLUSTERED_LIGHTS,
+                height: LIGHT_DATA_ROWS,
+            },
+            PixelKind::RGBA32F,
+            1,
+            Some(array_as_u8_slice(&self.light_data)),
+        ) {
+            Log::err(format!(
+                "Unable to upload clustered lights. Reason: {:?}",
+                e
+            ));
+            return stats;
+        }
+
+        let group_count =
+            (self.light_count + LIGHTS_PER_CLUSTER_GROUP - 1) / LIGHTS_PER_CLUSTER_GROUP;
+        let cluster_viewport = Rect::new(
+            0,
+            0,
+            (CLUSTER_COUNT_X * CLUSTER_COUNT_Y) as i32,
+            (CLUSTER_COUNT_Z * group_count) as i32,
+        );
+
+        let shader = &self.binning_shader;
+        let light_texture = &self.light_texture;
+        let parameters = &self.parameters;
+        let view_matrix = camera.view_matrix();
+        let inv_projection = camera.projection_matrix().try_inverse().unwrap_or_default();
+        stats += self.cluster_framebuffer.draw(
+            &self.quad,
+            state,
+            cluster_viewport,
+            &shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&shader.wvp_matrix, &make_frame_matrix(cluster_viewport))
+                    .set_texture(&shader.light_texture, light_texture)
+                    .set_matrix4(&shader.view_matrix, &view_matrix)
+                    .set_matrix4(&shader.inv_projection, &inv_projection)
+                    .set_vector4(&shader.cluster_parameters, parameters);
+            },
+        );
+
+        let shader = &self.light_shader;
+        let cluster_texture = self.cluster_texture();
+        let view_projection = &self.view_projection;
+        let inv_view_projection = view_projection.try_inverse().unwrap_or_default();
+        stats += frame_buffer.draw(
+            &self.quad,
+            state,
+            viewport,
+            &shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: Some(BlendParameters {
+                    func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                    ..Default::default()
+                }),
+                stencil_op: Default::default(),
+            },
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&shader.wvp_matrix, &make_frame_matrix(viewport))
+                    .set_matrix4(&shader.inv_view_proj_matrix, &inv_view_projection)
+                    .set_matrix4(&shader.view_projection_matrix, view_projection)
+                    .set_vector3(&shader.camera_position, &camera.global_position())
+                    .set_vector4(&shader.cluster_parameters, parameters)
+                    .set_texture(&shader.depth_sampler, &gbuffer.depth())
+                    .set_texture(&shader.color_sampler, &gbuffer.diffuse_texture())
+                    .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
+                    .set_texture(&shader.material_sampler, &gbuffer.material_texture())
+                    .set_texture(&shader.light_texture, light_texture)
+                    .set_texture(&shader.cluster_texture, &cluster_texture);
+            },
+        );
+
+        stats
+    }
+}
+
+fn make_frame_matrix(viewport: Rect<i32>) -> Matrix4<f32> {
+    Matrix4::new_orthographic(
+        0.0,
+        viewport.w() as f32,
+        viewport.h() as f32,
+        0.0,
+        -1.0,
+        1.0,
+    ) * Matrix4::new_nonuniform_scaling(&Vector3::new(
+        viewport.w() as f32,
+        viewport.h() as f32,
+        0.0,
+    ))
+}
@@ -26,8 +26,11 @@ use crate::{
         },
         gbuffer::GBuffer,
         light::{
-            ambient::AmbientLightShader, directional::DirectionalLightShader,
-            point::PointLightShader, spot::SpotLightShader,
+            ambient::AmbientLightShader,
+            clustered::{ClusteredLight, ClusteredLighting},
+            directional::DirectionalLightShader,
+            point::PointLightShader,
+            spot::SpotLightShader,
         },
         light_volume::LightVolumeRenderer,
         shadow::{
@@ -37,8 +40,10 @@ use crate::{
         },
         skybox_shader::SkyboxShader,
         ssao::ScreenSpaceAmbientOcclusionRenderer,
+        ssr::{ScreenSpaceReflectionsRenderer, SsrRenderContext},
         GeometryCache, QualitySettings, RenderPassStatistics, TextureCache,
     },
+    resource::texture::TextureKind,
     scene::{
         camera::Camera,
         mesh::{
@@ -48,6 +53,7 @@ use crate::{
         },
         Scene,
     },
+    utils::log::Log,
 };
 use fyrox_core::algebra::Vector2;
 use std::{
@@ -58,6 +64,7 @@ use std::{
 };
 
 pub mod ambient;
+pub mod clustered;
 pub mod directional;
 pub mod point;
 pub mod spot;
@@ -70,6 +77,7 @@ pub struct LightingStatistics {
     pub spot_lights_rendered: usize,
     pub spot_shadow_maps_rendered: usize,
     pub directional_lights_rendered: usize,
+    pub clustered_lights_rendered: usize,
 }
 
 impl AddAssign for LightingStatistics {
@@ -80,6 +88,7 @@ impl AddAssign for LightingStatistics {
         self.spot_shadow_maps_rendered += rhs.spot_shadow_maps_rendered;
         self.directional_lights_rendered += rhs.directional_lights_rendered;
         self.csm_rendered += rhs.csm_rendered;
+        self.clustered_lights_rendered += rhs.clustered_lights_rendered;
     }
 }
 
@@ -93,19 +102,22 @@ impl Display for LightingStatistics {
             \tDirectional Lights: {}\n\
             \tPoint Shadow Maps: {}\n\
             \tSpot Shadow Maps: {}\n\
-            \tSpot Shadow Maps: {}\n",
+            \tSpot Shadow Maps: {}\n\
+            \tClustered Lights: {}\n",
             self.point_lights_rendered,
             self.spot_lights_rendered,
             self.directional_lights_rendered,
             self.point_shadow_maps_rendered,
             self.spot_shadow_maps_rendered,
-            self.csm_rendered
+            self.csm_rendered,
+            self.clustered_lights_rendered
         )
     }
 }
 
 pub struct DeferredLightRenderer {
     pub ssao_renderer: ScreenSpaceAmbientOcclusionRenderer,
+    ssr_renderer: ScreenSpaceReflectionsRenderer,
     spot_light_shader: SpotLightShader,
     point_light_shader: PointLightShader,
     directional_light_shader: DirectionalLightShader,
@@ -119,6 +131,7 @@ pub struct DeferredLightRenderer {
     point_shadow_map_renderer: PointShadowMapRenderer,
     csm_renderer: CsmRenderer,
     light_volume: LightVolumeRenderer,
+    pub clustered_lighting: ClusteredLighting,
 }
 
 pub(crate) struct DeferredRendererContext<'a> {
@@ -185,6 +198,7 @@ impl DeferredLightRenderer {
                 frame_size.0 as usize,
                 frame_size.1 as usize,
             )?,
+            ssr_renderer: ScreenSpaceReflectionsRenderer::new(state)?,
             spot_light_shader: SpotLightShader::new(state)?,
             point_light_shader: PointLightShader::new(state)?,
             directional_light_shader: DirectionalLightShader::new(state)?,
@@ -234,6 +248,7 @@ impl DeferredLightRenderer {
                 quality_defaults.point_shadow_map_precision,
             )?,
             light_volume: LightVolumeRenderer::new(state)?,
+            clustered_lighting: ClusteredLighting::new(state)?,
             csm_renderer: CsmRenderer::new(
                 state,
                 quality_defaults.csm_settings.size,
@@ -340,6 +355,10 @@ impl DeferredLightRenderer {
         let inv_view_projection = view_projection.try_inverse().unwrap_or_default();
         let camera_global_position = camera.global_position();
 
+        let use_clustered_lighting =
+            settings.use_clustered_lighting && ClusteredLighting::is_supported(camera);
+        self.clustered_lighting.begin(camera);
+
         // Fill SSAO map.
         if settings.use_ssao {
             pass_stats += self.ssao_renderer.render(
@@ -486,6 +505,32 @@ impl DeferredLightRenderer {
                 continue;
             }
 
+            // Lights without shadows are rendered all at once after the loop, if possible.
+            if use_clustered_lighting && !shadows_enabled {
+                if let Some(clustered_light) =
+                    ClusteredLight::from_node(light, light_position, light_radius, emit_direction)
+                {
+                    if self.clustered_lighting.push_light(clustered_light) {
+                        if settings.light_scatter_enabled {
+                            pass_stats += self.light_volume.render_volume(
+                                state,
+                                light,
+                                light_handle,
+                                gbuffer,
+                                &self.quad,
+                                camera.view_matrix(),
+                                inv_projection,
+                                view_projection,
+                                viewport,
+                                &scene.graph,
+                                frame_buffer,
+                            );
+                        }
+                        continue;
+                    }
+                }
+            }
+
             let b1 = shadows_distance * 0.2;
             let b2 = shadows_distance * 0.4;
             let cascade_index =
@@ -831,7 +876,10 @@ impl DeferredLightRenderer {
                             )
                             .set_f32_slice(&shader.cascade_distances, &distances)
                             .set_matrix4(&shader.view_matrix, &camera.view_matrix())
-                            .set_f32(&shader.shadow_bias, directional.csm_options.shadow_bias())
+                            .set_f32_slice(
+                                &shader.shadow_biases,
+                                directional.csm_options.shadow_biases(),
+                            )
                             .set_bool(&shader.shadows_enabled, shadows_enabled)
                             .set_bool(&shader.soft_shadows, settings.csm_settings.pcf)
                             .set_f32(&shader.shadow_map_inv_size, 1.0 / csm_map_size);
@@ -858,6 +906,69 @@ impl DeferredLightRenderer {
             }
         }
 
+        if self.clustered_lighting.light_count() > 0 {
+            light_stats.clustered_lights_rendered += self.clustered_lighting.light_count();
+
+            pass_stats +=
+                self.clustered_lighting
+                    .render(state, camera, gbuffer, frame_buffer, viewport);
+        }
+
+        // Add reflections on top of lit frame.
+        if settings.ssr_settings.enabled {
+            let environment = if settings.ssr_settings.environment_fallback {
+                camera
+                    .environment_ref()
+                    .or_else(|| camera.skybox_ref().and_then(|skybox| skybox.cubemap_ref()))
+                    .filter(|texture| matches!(texture.data_ref().kind(), TextureKind::Cube { .. }))
+                    .and_then(|texture| textures.get(state, texture))
+            } else {
+                None
+            };
+
+            match self.ssr_renderer.render(SsrRenderContext {
+                state,
+                gbuffer,
+                frame_texture: frame_buffer.color_attachments()[0].texture.clone(),
+                environment,
+                view_projection,
+                camera_position: camera_global_position,
+                settings: &settings.ssr_settings,
+            }) {
+                Ok(stats) => pass_stats += stats,
+                Err(e) => Log::err(format!(
+                    "Unable to render screen space reflections: {:?}",
+                    e
+                )),
+            }
+
+            if let Some(reflections) = self.ssr_renderer.reflections() {
+                pass_stats += frame_buffer.draw(
+                    &self.quad,
+                    state,
+                    viewport,
+                    &self.flat_shader.program,
+                    &DrawParameters {
+                        cull_face: None,
+                        color_write: Default::default(),
+                        depth_write: false,
+                        stencil_test: None,
+                        depth_test: false,
+                        blend: Some(BlendParameters {
+                            func: BlendFunc::new(BlendFactor::One, BlendFactor::One),
+                            ..Default::default()
+                        }),
+                        stencil_op: Default::default(),
+                    },
+                    |mut program_binding| {
+                        program_binding
+                            .set_matrix4(&self.flat_shader.wvp_matrix, &frame_matrix)
+                            .set_texture(&self.flat_shader.diffuse_texture, &reflections);
+                    },
+                );
+            }
+        }
+
         (pass_stats, light_stats)
     }
 }
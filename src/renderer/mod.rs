@@ -16,8 +16,11 @@ pub mod framework;
 pub mod batch;
 pub mod cache;
 pub mod debug_renderer;
+pub mod dynamic_resolution;
+pub mod framegraph;
 pub mod renderer2d;
 pub mod ui_renderer;
+pub mod warmup;
 
 mod bloom;
 mod flat_shader;
@@ -27,11 +30,16 @@ mod gbuffer;
 mod hdr;
 mod light;
 mod light_volume;
+mod occlusion;
+mod oit;
 mod particle_system_renderer;
 mod shadow;
 mod skybox_shader;
 mod sprite_renderer;
 mod ssao;
+mod ssr;
+mod text3d_renderer;
+mod upscale;
 
 use crate::{
     core::{
@@ -42,20 +50,33 @@ use crate::{
         pool::Handle,
         reflect::prelude::*,
         scope_profile,
+        sstorage::ImmutableString,
+    },
+    engine::resource_manager::{
+        container::event::{ResourceEvent, ResourceEventSender},
+        ResourceManager,
     },
-    engine::resource_manager::{container::event::ResourceEvent, ResourceManager},
     gui::{draw::DrawingContext, UserInterface},
     material::{
         shader::{SamplerFallback, Shader},
-        Material, PropertyValue,
+        Material, MaterialPropertyBlock, PropertyValue,
     },
     renderer::{
-        batch::BatchStorage,
+        batch::{BatchStorage, BONE_MATRICES_COUNT},
         bloom::BloomRenderer,
-        cache::{geometry::GeometryCache, shader::ShaderCache, texture::TextureCache, CacheEntry},
+        cache::{
+            geometry::GeometryCache,
+            shader::{ShaderCache, ShaderCompilationEvent},
+            texture::TextureCache,
+            CacheEntry,
+        },
         debug_renderer::DebugRenderer,
+        dynamic_resolution::{DynamicResolution, DynamicResolutionSettings},
         flat_shader::FlatShader,
         forward_renderer::{ForwardRenderContext, ForwardRenderer},
+        framegraph::{
+            FrameGraph, FrameGraphPassBuilder, FrameGraphResourcePool, FrameGraphTextureDescriptor,
+        },
         framework::{
             error::FrameworkError,
             framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
@@ -70,25 +91,36 @@ use crate::{
         fxaa::FxaaRenderer,
         gbuffer::{GBuffer, GBufferRenderContext},
         hdr::HighDynamicRangeRenderer,
-        light::{DeferredLightRenderer, DeferredRendererContext, LightingStatistics},
+        light::{
+            clustered::ClusteredLighting, DeferredLightRenderer, DeferredRendererContext,
+            LightingStatistics,
+        },
+        occlusion::{OcclusionRenderContext, OcclusionTester},
         particle_system_renderer::{ParticleSystemRenderContext, ParticleSystemRenderer},
         renderer2d::Renderer2d,
         sprite_renderer::{SpriteRenderContext, SpriteRenderer},
+        text3d_renderer::{Text3DRenderContext, Text3DRenderer},
         ui_renderer::{UiRenderContext, UiRenderer},
+        upscale::UpscaleRenderer,
+        warmup::{WarmUpProgress, WarmUpResources, WarmUpStage, WarmUpStatistics},
     },
     resource::texture::{Texture, TextureKind},
-    scene::{camera::Camera, mesh::surface::SurfaceData, Scene, SceneContainer},
+    scene::{
+        camera::Camera, graph::Graph, mesh::surface::SurfaceData, node::Node, Scene, SceneContainer,
+    },
     utils::log::{Log, MessageKind},
 };
 use fxhash::FxHashMap;
 use glow::HasContext;
+use image::RgbaImage;
 use serde::{Deserialize, Serialize};
 use std::{
     cell::RefCell,
     collections::hash_map::Entry,
     fmt::{Display, Formatter},
     rc::Rc,
-    sync::mpsc::Receiver,
+    sync::mpsc::{Receiver, Sender},
+    time::Duration,
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
@@ -110,6 +142,8 @@ pub struct Statistics {
     pub capped_frame_time: f32,
     /// Total amount of frames been rendered in one second.
     pub frames_per_second: usize,
+    /// An estimate of video memory used by cached resources.
+    pub gpu_memory: GpuMemoryStatistics,
     frame_counter: usize,
     frame_start_time: instant::Instant,
     last_fps_commit_time: instant::Instant,
@@ -124,13 +158,46 @@ impl Display for Statistics {
             Capped Frame Time: {:.2} ms\n\
             {}\n\
             {}\n\
+            {}\n\
             {}\n",
             self.frames_per_second,
             self.pure_frame_time * 1000.0,
             self.capped_frame_time * 1000.0,
             self.geometry,
             self.lighting,
-            self.pipeline
+            self.pipeline,
+            self.gpu_memory
+        )
+    }
+}
+
+/// An estimate of the amount of video memory used by the renderer. Only cached textures (including
+/// render targets of scenes) and meshes are counted, internal buffers of the renderer are not.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GpuMemoryStatistics {
+    /// Amount of memory (in bytes) used by textures.
+    pub textures: usize,
+    /// Amount of memory (in bytes) used by vertex and index buffers.
+    pub geometry: usize,
+}
+
+impl GpuMemoryStatistics {
+    /// Returns total amount of memory (in bytes).
+    pub fn total(&self) -> usize {
+        self.textures + self.geometry
+    }
+}
+
+impl Display for GpuMemoryStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const MEGABYTE: f32 = 1024.0 * 1024.0;
+        write!(
+            f,
+            "GPU Memory Estimate:\n\
+            \tTextures: {:.2} Mb\n\
+            \tGeometry: {:.2} Mb",
+            self.textures as f32 / MEGABYTE,
+            self.geometry as f32 / MEGABYTE,
         )
     }
 }
@@ -201,6 +268,39 @@ pub enum ShadowMapPrecision {
     Full,
 }
 
+/// A filter that is used to upscale frames that were rendered at lower resolution (see
+/// [`dynamic_resolution`] module docs).
+#[derive(
+    Copy,
+    Clone,
+    Hash,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Serialize,
+    Deserialize,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum UpscalingFilter {
+    /// Fastest filter, but produces blurry images.
+    Bilinear = 0,
+    /// Bicubic Catmull-Rom filter, produces sharper images than bilinear filter at the cost of
+    /// a few more texture fetches.
+    CatmullRom = 1,
+}
+
+impl Default for UpscalingFilter {
+    fn default() -> Self {
+        Self::CatmullRom
+    }
+}
+
 /// Cascaded-shadow maps settings.
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect, Eq)]
 pub struct CsmSettings {
@@ -229,6 +329,46 @@ impl Default for CsmSettings {
     }
 }
 
+/// Screen space reflections settings. Reflections are found by marching reflected rays against the
+/// depth buffer, so only objects that are visible on screen can be reflected. Rays that miss take
+/// their color from the environment map of a camera (or from its skybox).
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct SsrSettings {
+    /// Whether screen space reflections enabled or not.
+    pub enabled: bool,
+
+    /// Maximum amount of steps of a reflected ray. The more steps, the more precise reflections
+    /// are, but the slower rendering is.
+    pub max_steps: u32,
+
+    /// Maximum length of a reflected ray in world units.
+    pub max_distance: f32,
+
+    /// Maximum distance between a ray and a surface behind it at which the ray is still considered
+    /// as intersecting the surface.
+    pub thickness: f32,
+
+    /// Surfaces with roughness greater than this value do not have reflections. Reflections fade
+    /// out as the roughness approaches this value.
+    pub roughness_cutoff: f32,
+
+    /// Whether to take colors of missed rays from environment map of a camera or not.
+    pub environment_fallback: bool,
+}
+
+impl Default for SsrSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_steps: 32,
+            max_distance: 20.0,
+            thickness: 0.5,
+            roughness_cutoff: 0.6,
+            environment_fallback: true,
+        }
+    }
+}
+
 /// Quality settings allows you to find optimal balance between performance and
 /// graphics quality.
 #[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Reflect)]
@@ -280,6 +420,60 @@ pub struct QualitySettings {
 
     /// Whether to use bloom effect.
     pub use_bloom: bool,
+
+    /// A filter that is used to upscale scenes rendered at lower resolution.
+    #[serde(default)]
+    pub upscaling_filter: UpscalingFilter,
+
+    /// Strength of contrast-adaptive sharpening in `[0; 1]` range, zero disables sharpening.
+    /// Sharpening compensates blurriness of upscaled frames, but could be used at native
+    /// resolution too. It is applied to scenes that are rendered into the back buffer.
+    #[serde(default)]
+    pub sharpening: f32,
+
+    /// Whether to render point and spot lights without shadows using clustered lighting. Lights are
+    /// assigned to cells of the view frustum on GPU and then shaded in a single pass, which is much
+    /// faster for scenes with lots of small lights. Works only for cameras with perspective projection.
+    #[serde(default)]
+    pub use_clustered_lighting: bool,
+
+    /// Screen space reflections settings.
+    #[serde(default)]
+    pub ssr_settings: SsrSettings,
+
+    /// Whether to store bone matrices of skinned meshes in a texture or not. The texture is
+    /// uploaded once per frame and has no limit on amount of bones, while the fallback passes bone
+    /// matrices of each surface using uniforms and is limited to 60 bones per surface. Disable
+    /// it on old GPUs that are unable to fetch floating-point textures in vertex shaders.
+    #[serde(default)]
+    pub use_bone_matrices_texture: bool,
+
+    /// Whether to render instances of the same surface data with the same material using hardware
+    /// instancing or not. Instances that could be rendered together (non-skinned, fully opaque
+    /// and without per-instance material properties) are drawn in a single draw call, which
+    /// significantly reduces CPU load for scenes with lots of identical objects.
+    #[serde(default)]
+    pub use_instancing: bool,
+
+    /// Whether to use occlusion culling or not. When enabled, nodes that are completely hidden
+    /// behind occluders (see [`crate::scene::base::Base::set_occluder`]) are not rendered. It
+    /// requires an additional depth pass for occluders and an occlusion query per visible node,
+    /// so it is beneficial only for scenes with lots of objects hidden behind large occluders
+    /// (interiors, cities, etc.). Results of the tests are one frame late, which could cause
+    /// short "pop-ins" of nodes when they become visible.
+    #[serde(default)]
+    pub use_occlusion_culling: bool,
+
+    /// Whether to render alpha-blended surfaces of `Forward` render path using order-independent
+    /// transparency or not. Sorted blending produces wrong results when transparent surfaces
+    /// intersect, while order-independent transparency does not depend on draw order at all. The
+    /// renderer uses weighted blended order-independent transparency, which works on every
+    /// supported GL version, but it is an approximation: colors of overlapping surfaces are
+    /// averaged with weights based on their depth and opacity. Each material can override this
+    /// setting (see [`crate::material::TransparencyOverride`]). Shaders with more than one output
+    /// in `Forward` render pass are always rendered using sorted blending.
+    #[serde(default)]
+    pub use_order_independent_transparency: bool,
 }
 
 impl Default for QualitySettings {
@@ -313,6 +507,18 @@ impl QualitySettings {
             fxaa: true,
 
             use_bloom: true,
+            upscaling_filter: UpscalingFilter::CatmullRom,
+            sharpening: 0.0,
+            use_clustered_lighting: false,
+            use_bone_matrices_texture: true,
+            use_instancing: true,
+            use_occlusion_culling: false,
+            use_order_independent_transparency: false,
+            ssr_settings: SsrSettings {
+                enabled: true,
+                max_steps: 64,
+                ..Default::default()
+            },
 
             use_parallax_mapping: false, // TODO: Enable when it is fixed!
 
@@ -344,6 +550,17 @@ impl QualitySettings {
             fxaa: true,
 
             use_bloom: true,
+            upscaling_filter: UpscalingFilter::CatmullRom,
+            sharpening: 0.0,
+            use_clustered_lighting: false,
+            use_bone_matrices_texture: true,
+            use_instancing: true,
+            use_occlusion_culling: false,
+            use_order_independent_transparency: false,
+            ssr_settings: SsrSettings {
+                enabled: true,
+                ..Default::default()
+            },
 
             use_parallax_mapping: false, // TODO: Enable when it is fixed!
 
@@ -380,6 +597,14 @@ impl QualitySettings {
             fxaa: true,
 
             use_bloom: true,
+            upscaling_filter: UpscalingFilter::CatmullRom,
+            sharpening: 0.0,
+            use_clustered_lighting: false,
+            use_bone_matrices_texture: true,
+            use_instancing: true,
+            use_occlusion_culling: false,
+            use_order_independent_transparency: false,
+            ssr_settings: Default::default(),
 
             use_parallax_mapping: false,
 
@@ -416,6 +641,14 @@ impl QualitySettings {
             fxaa: false,
 
             use_bloom: false,
+            upscaling_filter: UpscalingFilter::Bilinear,
+            sharpening: 0.0,
+            use_clustered_lighting: false,
+            use_bone_matrices_texture: true,
+            use_instancing: true,
+            use_occlusion_culling: false,
+            use_order_independent_transparency: false,
+            ssr_settings: Default::default(),
 
             use_parallax_mapping: false,
 
@@ -474,6 +707,7 @@ impl Default for Statistics {
             pure_frame_time: 0.0,
             capped_frame_time: 0.0,
             frames_per_second: 0,
+            gpu_memory: Default::default(),
             frame_counter: 0,
             frame_start_time: instant::Instant::now(),
             last_fps_commit_time: instant::Instant::now(),
@@ -492,16 +726,27 @@ pub struct AssociatedSceneData {
     /// Final frame of the scene. Tone mapped + gamma corrected.
     pub ldr_scene_framebuffer: FrameBuffer,
 
-    /// Additional frame buffer for post processing.
-    pub ldr_temp_framebuffer: FrameBuffer,
+    /// GPU textures and frame buffers of transient textures of the scene frame graph (see
+    /// [`Renderer::scene_frame_graph`]).
+    pub transient_resources: FrameGraphResourcePool,
+
+    /// HDR renderers have to be created per camera, because each of them contains luminance of
+    /// the camera's view, which is used for eye adaptation. Renderers are created on demand and
+    /// removed when the respective camera is not rendered anymore.
+    pub hdr_renderers: FxHashMap<Handle<Node>, HighDynamicRangeRenderer>,
 
-    /// HDR renderer has be created per scene, because it contains
-    /// scene luminance.
-    pub hdr_renderer: HighDynamicRangeRenderer,
+    /// Occlusion testers are created per camera, because each of them contains results of the
+    /// occlusion tests for the camera's view. Testers exist only if occlusion culling is enabled
+    /// (see [`QualitySettings::use_occlusion_culling`]).
+    pub occlusion_testers: FxHashMap<Handle<Node>, OcclusionTester>,
 
     /// Bloom contains only overly bright pixels that creates light
     /// bleeding effect (glow effect).
     pub bloom_renderer: BloomRenderer,
+
+    /// Frame buffer for order-independent transparency, it is created on demand (see
+    /// [`QualitySettings::use_order_independent_transparency`]).
+    pub oit_framebuffer: Option<FrameBuffer>,
 }
 
 impl AssociatedSceneData {
@@ -562,29 +807,6 @@ impl AssociatedSceneData {
         )?;
 
         let ldr_scene_framebuffer = FrameBuffer::new(
-            state,
-            Some(Attachment {
-                kind: AttachmentKind::DepthStencil,
-                texture: depth_stencil.clone(),
-            }),
-            vec![Attachment {
-                kind: AttachmentKind::Color,
-                texture: Rc::new(RefCell::new(ldr_frame_texture)),
-            }],
-        )?;
-
-        let ldr_temp_texture = GpuTexture::new(
-            state,
-            GpuTextureKind::Rectangle { width, height },
-            // Final scene frame is in standard sRGB space.
-            PixelKind::RGBA8,
-            MinificationFilter::Linear,
-            MagnificationFilter::Linear,
-            1,
-            None,
-        )?;
-
-        let ldr_temp_framebuffer = FrameBuffer::new(
             state,
             Some(Attachment {
                 kind: AttachmentKind::DepthStencil,
@@ -592,17 +814,19 @@ impl AssociatedSceneData {
             }),
             vec![Attachment {
                 kind: AttachmentKind::Color,
-                texture: Rc::new(RefCell::new(ldr_temp_texture)),
+                texture: Rc::new(RefCell::new(ldr_frame_texture)),
             }],
         )?;
 
         Ok(Self {
             gbuffer: GBuffer::new(state, width, height)?,
-            hdr_renderer: HighDynamicRangeRenderer::new(state)?,
+            hdr_renderers: Default::default(),
+            occlusion_testers: Default::default(),
             bloom_renderer: BloomRenderer::new(state, width, height)?,
             hdr_scene_framebuffer,
             ldr_scene_framebuffer,
-            ldr_temp_framebuffer,
+            transient_resources: FrameGraphResourcePool::new(width, height),
+            oit_framebuffer: None,
         })
     }
 
@@ -637,13 +861,21 @@ impl AssociatedSceneData {
             .texture
             .clone()
     }
+}
 
-    /// Returns low-dynamic range frame buffer texture (accumulation frame).
-    pub fn ldr_temp_frame_texture(&self) -> Rc<RefCell<GpuTexture>> {
-        self.ldr_temp_framebuffer.color_attachments()[0]
-            .texture
-            .clone()
-    }
+/// Collects every enabled camera of the graph and sorts them by their render order. The sort is
+/// stable, so cameras with the same order keep the order of their appearance in the graph.
+pub(crate) fn enabled_cameras_in_render_order(graph: &Graph) -> Vec<(Handle<Node>, &Camera)> {
+    let mut cameras = graph
+        .pair_iter()
+        .filter_map(|(handle, node)| {
+            node.cast::<Camera>()
+                .filter(|camera| camera.is_enabled())
+                .map(|camera| (handle, camera))
+        })
+        .collect::<Vec<_>>();
+    cameras.sort_by_key(|(_, camera)| camera.render_order());
+    cameras
 }
 
 pub(crate) fn make_viewport_matrix(viewport: Rect<i32>) -> Matrix4<f32> {
@@ -661,13 +893,153 @@ pub(crate) fn make_viewport_matrix(viewport: Rect<i32>) -> Matrix4<f32> {
     ))
 }
 
+/// A built-in pass of scene rendering pipeline. See [`Renderer::scene_frame_graph`] for more info.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScenePass {
+    /// Fills G-Buffer with opaque geometry.
+    GBuffer,
+    /// Deferred lighting (including shadows, SSAO and light scattering).
+    Lighting,
+    /// Renders particle systems.
+    ParticleSystems,
+    /// Renders sprites.
+    Sprites,
+    /// Renders 2D scene content.
+    Renderer2d,
+    /// Renders transparent geometry using forward renderer.
+    Forward,
+    /// Custom render passes (see [`SceneRenderPass::on_hdr_render`]).
+    CustomHdr,
+    /// Extracts overly bright pixels of the frame and blurs them.
+    Bloom,
+    /// Converts high dynamic range frame to low dynamic range (sRGB) with tone mapping and
+    /// gamma correction.
+    ToneMapping,
+    /// Fast approximate anti-aliasing.
+    Fxaa,
+    /// Renders 3D text (see [`crate::scene::text3d::Text3D`]).
+    Text3D,
+    /// Renders debug geometry.
+    Debug,
+    /// Custom render passes (see [`SceneRenderPass::on_ldr_render`]).
+    CustomLdr,
+}
+
+fn make_scene_frame_graph(settings: &QualitySettings) -> FrameGraph<ScenePass> {
+    let mut graph = FrameGraph::new();
+
+    let gbuffer = graph.add_resource("GBuffer");
+    let hdr_lit = graph.add_resource("HdrFrame (lit)");
+    let hdr_particles = graph.add_resource("HdrFrame (particles)");
+    let hdr_sprites = graph.add_resource("HdrFrame (sprites)");
+    let hdr_2d = graph.add_resource("HdrFrame (2d)");
+    let hdr_forward = graph.add_resource("HdrFrame (forward)");
+    let hdr_final = graph.add_resource("HdrFrame (final)");
+    let bloom = graph.add_resource("Bloom");
+    let ldr_tone_mapped = graph.add_resource("LdrFrame (tone mapped)");
+    let ldr_anti_aliased = graph.add_texture(
+        "LdrFrame (anti-aliased)",
+        FrameGraphTextureDescriptor {
+            pixel_kind: PixelKind::RGBA8,
+            min_filter: MinificationFilter::Linear,
+            mag_filter: MagnificationFilter::Linear,
+        },
+    );
+    let ldr_text = graph.add_resource("LdrFrame (text)");
+    let ldr_debug = graph.add_resource("LdrFrame (debug)");
+    let ldr_final = graph.import_resource("LdrFrame (final)");
+
+    FrameGraphPassBuilder::new("GBuffer", ScenePass::GBuffer)
+        .with_write(gbuffer)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("Lighting", ScenePass::Lighting)
+        .with_read(gbuffer)
+        .with_write(hdr_lit)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("ParticleSystems", ScenePass::ParticleSystems)
+        .with_read(gbuffer)
+        .with_read(hdr_lit)
+        .with_write(hdr_particles)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("Sprites", ScenePass::Sprites)
+        .with_read(hdr_particles)
+        .with_write(hdr_sprites)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("Renderer2d", ScenePass::Renderer2d)
+        .with_read(hdr_sprites)
+        .with_write(hdr_2d)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("Forward", ScenePass::Forward)
+        .with_read(hdr_2d)
+        .with_write(hdr_forward)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("CustomHdr", ScenePass::CustomHdr)
+        .with_read(gbuffer)
+        .with_read(hdr_forward)
+        .with_write(hdr_final)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("Bloom", ScenePass::Bloom)
+        .with_read(hdr_final)
+        .with_write(bloom)
+        .build(&mut graph);
+
+    let mut tone_mapping =
+        FrameGraphPassBuilder::new("ToneMapping", ScenePass::ToneMapping).with_read(hdr_final);
+    if settings.use_bloom {
+        tone_mapping = tone_mapping.with_read(bloom);
+    }
+    tone_mapping.with_write(ldr_tone_mapped).build(&mut graph);
+
+    let ldr_frame = if settings.fxaa {
+        FrameGraphPassBuilder::new("Fxaa", ScenePass::Fxaa)
+            .with_read(ldr_tone_mapped)
+            .with_write(ldr_anti_aliased)
+            .build(&mut graph);
+        ldr_anti_aliased
+    } else {
+        ldr_tone_mapped
+    };
+
+    FrameGraphPassBuilder::new("Text3D", ScenePass::Text3D)
+        .with_read(ldr_frame)
+        .with_write(ldr_text)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("Debug", ScenePass::Debug)
+        .with_read(ldr_text)
+        .with_write(ldr_debug)
+        .build(&mut graph);
+    FrameGraphPassBuilder::new("CustomLdr", ScenePass::CustomLdr)
+        .with_read(gbuffer)
+        .with_read(ldr_debug)
+        .with_write(ldr_final)
+        .build(&mut graph);
+
+    graph
+        .compile()
+        .expect("built-in scene frame graph must be valid");
+
+    graph
+}
+
+// A custom render pass that is used only for a particular scene.
+struct ScopedRenderPass {
+    scene: Handle<Scene>,
+    // Index of a plugin constructor of a plugin, that registered the pass.
+    owner: Option<usize>,
+    pass: Rc<RefCell<dyn SceneRenderPass>>,
+}
+
 /// See module docs.
 pub struct Renderer {
     backbuffer: FrameBuffer,
     scene_render_passes: Vec<Rc<RefCell<dyn SceneRenderPass>>>,
+    scoped_render_passes: Vec<ScopedRenderPass>,
+    // Index of a plugin constructor of a plugin, that is being called by the engine.
+    pub(crate) render_pass_owner: Option<usize>,
     deferred_light_renderer: DeferredLightRenderer,
     flat_shader: FlatShader,
     sprite_renderer: SpriteRenderer,
+    text3d_renderer: Text3DRenderer,
     particle_system_renderer: ParticleSystemRenderer,
     /// Dummy white one pixel texture which will be used as stub when rendering
     /// something without texture specified.
@@ -685,6 +1057,7 @@ pub struct Renderer {
     statistics: Statistics,
     quad: GeometryBuffer,
     frame_size: (u32, u32),
+    frame_number: u64,
     quality_settings: QualitySettings,
     /// Debug renderer instance can be used for debugging purposes
     pub debug_renderer: DebugRenderer,
@@ -698,9 +1071,16 @@ pub struct Renderer {
     batch_storage: BatchStorage,
     forward_renderer: ForwardRenderer,
     fxaa_renderer: FxaaRenderer,
+    upscale_renderer: UpscaleRenderer,
+    dynamic_resolution: DynamicResolution,
     renderer2d: Renderer2d,
+    scene_frame_graph: FrameGraph<ScenePass>,
+    scene_pass_order: Vec<ScenePass>,
     texture_event_receiver: Receiver<ResourceEvent<Texture>>,
+    texture_event_sender: Handle<ResourceEventSender<Texture>>,
     shader_event_receiver: Receiver<ResourceEvent<Shader>>,
+    shader_event_sender: Handle<ResourceEventSender<Shader>>,
+    shader_compilation_listeners: Vec<Sender<ShaderCompilationEvent>>,
     // TextureId -> FrameBuffer mapping. This mapping is used for temporal frame buffers
     // like ones used to render UI instances.
     ui_frame_buffers: FxHashMap<usize, FrameBuffer>,
@@ -854,6 +1234,13 @@ pub trait SceneRenderPass {
     ) -> Result<RenderPassStatistics, FrameworkError> {
         Ok(RenderPassStatistics::default())
     }
+
+    /// Called when the graphics context was lost and then restored, see
+    /// [`Renderer::on_context_restored`] for more info. Every GPU object that was created by the
+    /// pass before is invalid at this point and must be re-created using the given state.
+    fn on_context_restored(&mut self, _state: &mut PipelineState) -> Result<(), FrameworkError> {
+        Ok(())
+    }
 }
 
 fn blit_pixels(
@@ -901,6 +1288,7 @@ fn blit_pixels(
 
 pub(crate) struct MaterialContext<'a, 'b, 'c> {
     pub material: &'a Material,
+    pub property_block: Option<&'a MaterialPropertyBlock>,
     pub program_binding: &'a mut GpuProgramBinding<'b, 'c>,
     pub texture_cache: &'a mut TextureCache,
 
@@ -908,10 +1296,15 @@ pub(crate) struct MaterialContext<'a, 'b, 'c> {
     pub world_matrix: &'a Matrix4<f32>,
     pub wvp_matrix: &'a Matrix4<f32>,
     pub bone_matrices: &'a [Matrix4<f32>],
+    pub bone_matrices_offset: usize,
+    pub bone_matrices_texture: Option<Rc<RefCell<GpuTexture>>>,
     pub use_skeletal_animation: bool,
     pub camera_position: &'a Vector3<f32>,
     pub use_pom: bool,
     pub light_position: &'a Vector3<f32>,
+    pub opacity: f32,
+    pub clustered_lighting: Option<&'a ClusteredLighting>,
+    pub use_instancing: bool,
 
     // Fallback samplers.
     pub normal_dummy: Rc<RefCell<GpuTexture>>,
@@ -919,7 +1312,7 @@ pub(crate) struct MaterialContext<'a, 'b, 'c> {
     pub black_dummy: Rc<RefCell<GpuTexture>>,
 }
 
-pub(crate) fn apply_material(ctx: MaterialContext) {
+pub(crate) fn apply_material(mut ctx: MaterialContext) {
     let built_in_uniforms = &ctx.program_binding.program.built_in_uniform_locations;
 
     // Apply values for built-in uniforms.
@@ -929,9 +1322,31 @@ pub(crate) fn apply_material(ctx: MaterialContext) {
     if let Some(location) = &built_in_uniforms[BuiltInUniform::WorldViewProjectionMatrix as usize] {
         ctx.program_binding.set_matrix4(location, ctx.wvp_matrix);
     }
-    if let Some(location) = &built_in_uniforms[BuiltInUniform::BoneMatrices as usize] {
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::UseBoneMatricesTexture as usize] {
+        ctx.program_binding
+            .set_bool(location, ctx.bone_matrices_texture.is_some());
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::BoneMatricesTexture as usize] {
+        let texture = ctx
+            .bone_matrices_texture
+            .clone()
+            .unwrap_or_else(|| ctx.black_dummy.clone());
+        ctx.program_binding.set_texture(location, &texture);
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::BoneMatricesOffset as usize] {
         ctx.program_binding
-            .set_matrix4_array(location, ctx.bone_matrices);
+            .set_i32(location, ctx.bone_matrices_offset as i32);
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::BoneMatrices as usize] {
+        // There is no need to pass matrices using uniforms if they're already in the texture and
+        // the shader is able to fetch them.
+        if ctx.bone_matrices_texture.is_none()
+            || built_in_uniforms[BuiltInUniform::UseBoneMatricesTexture as usize].is_none()
+        {
+            let count = ctx.bone_matrices.len().min(BONE_MATRICES_COUNT);
+            ctx.program_binding
+                .set_matrix4_array(location, &ctx.bone_matrices[..count]);
+        }
     }
     if let Some(location) = &built_in_uniforms[BuiltInUniform::UseSkeletalAnimation as usize] {
         ctx.program_binding
@@ -948,83 +1363,144 @@ pub(crate) fn apply_material(ctx: MaterialContext) {
         ctx.program_binding
             .set_vector3(location, ctx.light_position);
     }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::Opacity as usize] {
+        ctx.program_binding.set_f32(location, ctx.opacity);
+    }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::UseInstancing as usize] {
+        ctx.program_binding.set_bool(location, ctx.use_instancing);
+    }
+    if let Some(clustered_lighting) = ctx.clustered_lighting {
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::ClusterLights as usize] {
+            ctx.program_binding
+                .set_texture(location, &clustered_lighting.light_texture());
+        }
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::Clusters as usize] {
+            ctx.program_binding
+                .set_texture(location, &clustered_lighting.cluster_texture());
+        }
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::ClusterParameters as usize] {
+            ctx.program_binding
+                .set_vector4(location, clustered_lighting.parameters());
+        }
+        if let Some(location) = &built_in_uniforms[BuiltInUniform::ClusterViewProjection as usize] {
+            ctx.program_binding
+                .set_matrix4(location, clustered_lighting.view_projection());
+        }
+    }
 
-    // Apply material properties.
-    for (name, value) in ctx.material.properties() {
-        if let Some(uniform) = ctx.program_binding.uniform_location(name) {
-            match value {
-                PropertyValue::Float(v) => {
-                    ctx.program_binding.set_f32(&uniform, *v);
-                }
-                PropertyValue::Int(v) => {
-                    ctx.program_binding.set_i32(&uniform, *v);
-                }
-                PropertyValue::UInt(v) => {
-                    ctx.program_binding.set_u32(&uniform, *v);
-                }
-                PropertyValue::Vector2(v) => {
-                    ctx.program_binding.set_vector2(&uniform, v);
-                }
-                PropertyValue::Vector3(v) => {
-                    ctx.program_binding.set_vector3(&uniform, v);
-                }
-                PropertyValue::Vector4(v) => {
-                    ctx.program_binding.set_vector4(&uniform, v);
-                }
-                PropertyValue::Matrix2(v) => {
-                    ctx.program_binding.set_matrix2(&uniform, v);
-                }
-                PropertyValue::Matrix3(v) => {
-                    ctx.program_binding.set_matrix3(&uniform, v);
-                }
-                PropertyValue::Matrix4(v) => {
-                    ctx.program_binding.set_matrix4(&uniform, v);
-                }
-                PropertyValue::Color(v) => {
-                    ctx.program_binding.set_srgb_color(&uniform, v);
-                }
-                PropertyValue::Bool(v) => {
-                    ctx.program_binding.set_bool(&uniform, *v);
-                }
-                PropertyValue::Sampler { value, fallback } => {
-                    let texture = value
+    // Apply material properties, per-instance overrides are applied last.
+    let material = ctx.material;
+    for (name, value) in material.properties() {
+        apply_property(&mut ctx, name, value);
+    }
+    if let Some(property_block) = ctx.property_block {
+        for (name, value) in property_block.properties() {
+            apply_property(&mut ctx, name, value);
+        }
+    }
+}
+
+fn apply_property(ctx: &mut MaterialContext, name: &ImmutableString, value: &PropertyValue) {
+    if let Some(uniform) = ctx.program_binding.uniform_location(name) {
+        match value {
+            PropertyValue::Float(v) => {
+                ctx.program_binding.set_f32(&uniform, *v);
+            }
+            PropertyValue::Int(v) => {
+                ctx.program_binding.set_i32(&uniform, *v);
+            }
+            PropertyValue::UInt(v) => {
+                ctx.program_binding.set_u32(&uniform, *v);
+            }
+            PropertyValue::Vector2(v) => {
+                ctx.program_binding.set_vector2(&uniform, v);
+            }
+            PropertyValue::Vector3(v) => {
+                ctx.program_binding.set_vector3(&uniform, v);
+            }
+            PropertyValue::Vector4(v) => {
+                ctx.program_binding.set_vector4(&uniform, v);
+            }
+            PropertyValue::Matrix2(v) => {
+                ctx.program_binding.set_matrix2(&uniform, v);
+            }
+            PropertyValue::Matrix3(v) => {
+                ctx.program_binding.set_matrix3(&uniform, v);
+            }
+            PropertyValue::Matrix4(v) => {
+                ctx.program_binding.set_matrix4(&uniform, v);
+            }
+            PropertyValue::Color(v) => {
+                ctx.program_binding.set_srgb_color(&uniform, v);
+            }
+            PropertyValue::Bool(v) => {
+                ctx.program_binding.set_bool(&uniform, *v);
+            }
+            PropertyValue::Sampler { value, fallback } => {
+                let program = ctx.program_binding.program;
+                if let Some(array_sampler) = program.texture_array_sampler(name) {
+                    let state = &mut *ctx.program_binding.state;
+                    let (array, layer) = match value
                         .as_ref()
-                        .and_then(|t| ctx.texture_cache.get(ctx.program_binding.state, t))
-                        .unwrap_or_else(|| match fallback {
-                            SamplerFallback::White => ctx.white_dummy.clone(),
-                            SamplerFallback::Normal => ctx.normal_dummy.clone(),
-                            SamplerFallback::Black => ctx.black_dummy.clone(),
-                        });
-
-                    ctx.program_binding.set_texture(&uniform, &texture);
-                }
-                PropertyValue::FloatArray(v) => {
-                    ctx.program_binding.set_f32_slice(&uniform, v);
-                }
-                PropertyValue::IntArray(v) => {
-                    ctx.program_binding.set_i32_slice(&uniform, v);
-                }
-                PropertyValue::UIntArray(v) => {
-                    ctx.program_binding.set_u32_slice(&uniform, v);
-                }
-                PropertyValue::Vector2Array(v) => {
-                    ctx.program_binding.set_vector2_slice(&uniform, v);
-                }
-                PropertyValue::Vector3Array(v) => {
-                    ctx.program_binding.set_vector3_slice(&uniform, v);
-                }
-                PropertyValue::Vector4Array(v) => {
-                    ctx.program_binding.set_vector4_slice(&uniform, v);
-                }
-                PropertyValue::Matrix2Array(v) => {
-                    ctx.program_binding.set_matrix2_array(&uniform, v);
-                }
-                PropertyValue::Matrix3Array(v) => {
-                    ctx.program_binding.set_matrix3_array(&uniform, v);
-                }
-                PropertyValue::Matrix4Array(v) => {
-                    ctx.program_binding.set_matrix4_array(&uniform, v);
+                        .and_then(|t| ctx.texture_cache.arrays.get(state, t))
+                    {
+                        Some(packed) => (Some(packed.array), packed.layer),
+                        None => {
+                            let color = match fallback {
+                                SamplerFallback::White => [255, 255, 255, 255],
+                                SamplerFallback::Normal => [128, 128, 255, 255],
+                                SamplerFallback::Black => [0, 0, 0, 255],
+                            };
+                            (ctx.texture_cache.arrays.fallback(state, color), 0)
+                        }
+                    };
+
+                    if let Some(array) = array {
+                        ctx.program_binding.set_texture(&uniform, &array);
+                    }
+                    if let Some(layer_location) = array_sampler.layer_location.as_ref() {
+                        ctx.program_binding.set_i32(layer_location, layer as i32);
+                    }
+                    return;
                 }
+
+                let texture = value
+                    .as_ref()
+                    .and_then(|t| ctx.texture_cache.get(ctx.program_binding.state, t))
+                    .unwrap_or_else(|| match fallback {
+                        SamplerFallback::White => ctx.white_dummy.clone(),
+                        SamplerFallback::Normal => ctx.normal_dummy.clone(),
+                        SamplerFallback::Black => ctx.black_dummy.clone(),
+                    });
+
+                ctx.program_binding.set_texture(&uniform, &texture);
+            }
+            PropertyValue::FloatArray(v) => {
+                ctx.program_binding.set_f32_slice(&uniform, v);
+            }
+            PropertyValue::IntArray(v) => {
+                ctx.program_binding.set_i32_slice(&uniform, v);
+            }
+            PropertyValue::UIntArray(v) => {
+                ctx.program_binding.set_u32_slice(&uniform, v);
+            }
+            PropertyValue::Vector2Array(v) => {
+                ctx.program_binding.set_vector2_slice(&uniform, v);
+            }
+            PropertyValue::Vector3Array(v) => {
+                ctx.program_binding.set_vector3_slice(&uniform, v);
+            }
+            PropertyValue::Vector4Array(v) => {
+                ctx.program_binding.set_vector4_slice(&uniform, v);
+            }
+            PropertyValue::Matrix2Array(v) => {
+                ctx.program_binding.set_matrix2_array(&uniform, v);
+            }
+            PropertyValue::Matrix3Array(v) => {
+                ctx.program_binding.set_matrix3_array(&uniform, v);
+            }
+            PropertyValue::Matrix4Array(v) => {
+                ctx.program_binding.set_matrix4_array(&uniform, v);
             }
         }
     }
@@ -1040,7 +1516,7 @@ impl Renderer {
 
         let (texture_event_sender, texture_event_receiver) = std::sync::mpsc::channel();
 
-        resource_manager
+        let texture_event_sender = resource_manager
             .state()
             .containers_mut()
             .textures
@@ -1049,7 +1525,7 @@ impl Renderer {
 
         let (shader_event_sender, shader_event_receiver) = std::sync::mpsc::channel();
 
-        resource_manager
+        let shader_event_sender = resource_manager
             .state()
             .containers_mut()
             .shaders
@@ -1069,9 +1545,11 @@ impl Renderer {
         Ok(Self {
             backbuffer: FrameBuffer::backbuffer(&mut state),
             frame_size,
+            frame_number: 0,
             deferred_light_renderer: DeferredLightRenderer::new(&mut state, frame_size, &settings)?,
             flat_shader: FlatShader::new(&mut state)?,
             sprite_renderer: SpriteRenderer::new(&mut state)?,
+            text3d_renderer: Text3DRenderer::new(&mut state)?,
             white_dummy: Rc::new(RefCell::new(GpuTexture::new(
                 &mut state,
                 GpuTextureKind::Rectangle {
@@ -1153,17 +1631,49 @@ impl Renderer {
             texture_cache: Default::default(),
             geometry_cache: Default::default(),
             batch_storage: Default::default(),
-            forward_renderer: ForwardRenderer::new(),
+            forward_renderer: ForwardRenderer::new(&mut state)?,
             ui_frame_buffers: Default::default(),
             fxaa_renderer: FxaaRenderer::new(&mut state)?,
+            upscale_renderer: UpscaleRenderer::new(&mut state)?,
+            dynamic_resolution: Default::default(),
             statistics: Statistics::default(),
             renderer2d: Renderer2d::new(&mut state)?,
             shader_event_receiver,
+            shader_event_sender,
+            shader_compilation_listeners: Default::default(),
             texture_event_receiver,
+            texture_event_sender,
             state,
             shader_cache: ShaderCache::default(),
             scene_render_passes: Default::default(),
-        })
+            scoped_render_passes: Default::default(),
+            render_pass_owner: None,
+            scene_frame_graph: Default::default(),
+            scene_pass_order: Default::default(),
+        }
+        .with_rebuilt_scene_frame_graph())
+    }
+
+    fn with_rebuilt_scene_frame_graph(mut self) -> Self {
+        self.rebuild_scene_frame_graph();
+        self
+    }
+
+    fn rebuild_scene_frame_graph(&mut self) {
+        self.scene_frame_graph = make_scene_frame_graph(&self.quality_settings);
+        self.scene_pass_order = self
+            .scene_frame_graph
+            .execution_order()
+            .iter()
+            .map(|p| *self.scene_frame_graph.pass(*p).payload())
+            .collect();
+    }
+
+    /// Returns a frame graph that is used to render each camera of a scene. The graph depends on
+    /// current quality settings, for example there will be no FXAA pass if it is disabled. Use
+    /// [`FrameGraph::to_dot`] to visualize the graph for debugging purposes.
+    pub fn scene_frame_graph(&self) -> &FrameGraph<ScenePass> {
+        &self.scene_frame_graph
     }
 
     /// Adds a custom render pass.
@@ -1171,6 +1681,37 @@ impl Renderer {
         self.scene_render_passes.push(pass);
     }
 
+    /// Adds a custom render pass, that will be used only for the given scene. Unlike
+    /// [`Self::add_render_pass`], the pass is removed automatically when the scene is destroyed. If
+    /// the pass was added by a plugin (in any of [`crate::plugin::Plugin`] methods), it is also
+    /// removed when the plugin is deinitialized (see [`crate::plugin::Plugin::on_deinit`]), so render
+    /// effects of plugins can't leak across scene reloads. Returns a shared reference to the pass,
+    /// it could be used to modify the pass later or to remove it using [`Self::remove_render_pass`].
+    pub fn add_render_pass_for_scene<P>(&mut self, scene: Handle<Scene>, pass: P) -> Rc<RefCell<P>>
+    where
+        P: SceneRenderPass + 'static,
+    {
+        let pass = Rc::new(RefCell::new(pass));
+        self.scoped_render_passes.push(ScopedRenderPass {
+            scene,
+            owner: self.render_pass_owner,
+            pass: pass.clone(),
+        });
+        pass
+    }
+
+    /// Returns an iterator over every render pass that was added for the given scene using
+    /// [`Self::add_render_pass_for_scene`].
+    pub fn render_passes_for_scene(
+        &self,
+        scene: Handle<Scene>,
+    ) -> impl Iterator<Item = &Rc<RefCell<dyn SceneRenderPass>>> {
+        self.scoped_render_passes
+            .iter()
+            .filter(move |p| p.scene == scene)
+            .map(|p| &p.pass)
+    }
+
     /// Removes specified render pass.
     pub fn remove_render_pass(&mut self, pass: Rc<RefCell<dyn SceneRenderPass>>) {
         if let Some(index) = self
@@ -1180,6 +1721,13 @@ impl Renderer {
         {
             self.scene_render_passes.remove(index);
         }
+        self.scoped_render_passes
+            .retain(|p| !Rc::ptr_eq(&p.pass, &pass));
+    }
+
+    // Removes every scene render pass, that was added by a plugin with the given constructor index.
+    pub(crate) fn remove_render_passes_of_owner(&mut self, owner: usize) {
+        self.scoped_render_passes.retain(|p| p.owner != Some(owner));
     }
 
     /// Returns a slice with every registered render passes.
@@ -1189,7 +1737,40 @@ impl Renderer {
 
     /// Removes all render passes from the renderer.
     pub fn clear_render_passes(&mut self) {
-        self.scene_render_passes.clear()
+        self.scene_render_passes.clear();
+        self.scoped_render_passes.clear();
+    }
+
+    /// Enables background shader compilation with the given time budget per frame. When the budget
+    /// is set, shaders are no longer compiled on first use (which blocks rendering), instead they're
+    /// queued and objects with materials, which shaders are not compiled yet, are rendered with
+    /// neutral placeholder shader (see [`Shader::placeholder`]). When a shader is compiled, it is
+    /// swapped in and a [`ShaderCompilationEvent`] is sent to every listener (see
+    /// [`Self::add_shader_compilation_listener`]).
+    ///
+    /// If the driver supports parallel compilation (`KHR_parallel_shader_compile` extension),
+    /// queued shaders are compiled on driver threads and the budget is not used. Otherwise, they're
+    /// compiled on the main thread over multiple frames, until the budget is exceeded (at least one
+    /// shader per frame). `None` (default) restores blocking compilation.
+    pub fn set_shader_compilation_budget(&mut self, budget: Option<Duration>) {
+        self.shader_cache.set_compilation_budget(budget);
+    }
+
+    /// Returns current time budget for shader compilation per frame, `None` if background
+    /// compilation is disabled.
+    pub fn shader_compilation_budget(&self) -> Option<Duration> {
+        self.shader_cache.compilation_budget()
+    }
+
+    /// Returns amount of shaders waiting for compilation or being compiled.
+    pub fn pending_shader_count(&self) -> usize {
+        self.shader_cache.compilation_queue_len()
+    }
+
+    /// Adds a new listener, that will receive an event every time a queued shader is compiled. See
+    /// [`Self::set_shader_compilation_budget`] for more info.
+    pub fn add_shader_compilation_listener(&mut self, sender: Sender<ShaderCompilationEvent>) {
+        self.shader_compilation_listeners.push(sender);
     }
 
     /// Returns statistics for last frame.
@@ -1202,6 +1783,172 @@ impl Renderer {
         self.texture_cache.unload(texture)
     }
 
+    /// Prepares every shader program, pipeline state, texture and font atlas used by the scene, so
+    /// the first frame with some material won't cause a hitch because of lazy shader compilation
+    /// or texture uploading. It is meant to be called during loading, after the scene and its
+    /// resources were fully loaded - resources that are still loading are skipped. The progress
+    /// callback is called after each processed resource and could be used to update a loading
+    /// screen.
+    ///
+    /// Shader programs are not only compiled, but also used in a draw call with the draw
+    /// parameters of each render pass, because most drivers finish compilation only when a
+    /// program is used for the first time.
+    pub fn warm_up_scene<F>(
+        &mut self,
+        scene: &Scene,
+        mut progress: F,
+    ) -> Result<WarmUpStatistics, FrameworkError>
+    where
+        F: FnMut(WarmUpProgress),
+    {
+        scope_profile!();
+
+        let resources = WarmUpResources::from_graph(&scene.graph);
+        let total = resources.len();
+        let mut statistics = WarmUpStatistics::default();
+
+        let mut target = make_ui_frame_buffer(Vector2::new(1.0, 1.0), &mut self.state)?;
+        let viewport = Rect::new(0, 0, 1, 1);
+
+        for (i, shader) in resources.shaders.iter().enumerate() {
+            if let Some(shader_set) = self.shader_cache.get_or_compile(&mut self.state, shader) {
+                for pass in shader_set.render_passes.values() {
+                    target.draw(
+                        &self.quad,
+                        &mut self.state,
+                        viewport,
+                        &pass.program,
+                        &pass.draw_params,
+                        |_| {},
+                    );
+                    statistics.programs += 1;
+                }
+                statistics.shaders += 1;
+            } else {
+                statistics.skipped += 1;
+            }
+
+            progress(WarmUpProgress {
+                stage: WarmUpStage::Shaders,
+                processed: i + 1,
+                total,
+            });
+        }
+
+        for (i, texture) in resources.textures.iter().enumerate() {
+            if self.texture_cache.get(&mut self.state, texture).is_some() {
+                statistics.textures += 1;
+            } else {
+                statistics.skipped += 1;
+            }
+
+            progress(WarmUpProgress {
+                stage: WarmUpStage::Textures,
+                processed: resources.shaders.len() + i + 1,
+                total,
+            });
+        }
+
+        for (i, font) in resources.fonts.iter().enumerate() {
+            if self
+                .text3d_renderer
+                .prepare_atlas(&mut self.state, font)
+                .is_ok()
+            {
+                statistics.fonts += 1;
+            } else {
+                statistics.skipped += 1;
+            }
+
+            progress(WarmUpProgress {
+                stage: WarmUpStage::Fonts,
+                processed: resources.shaders.len() + resources.textures.len() + i + 1,
+                total,
+            });
+        }
+
+        Log::writeln(
+            MessageKind::Information,
+            format!("Scene warm-up finished: {:?}", statistics),
+        );
+
+        Ok(statistics)
+    }
+
+    /// Checks whether the graphics context was lost (GPU reset, driver update, switching between
+    /// GPUs, browser reclaimed WebGL context, etc.). Rendering with lost context does nothing,
+    /// the renderer must be restored using [`Self::on_context_restored`] first.
+    pub fn is_context_lost(&mut self) -> bool {
+        self.state.check_context_loss()
+    }
+
+    /// Re-creates every GPU resource of the renderer using the given context, it must be called
+    /// after the previous context was lost. GPU resources of engine's resources (textures,
+    /// shaders, surfaces) are re-created on demand from their CPU-side data, quality settings and
+    /// custom render passes are kept. Custom passes are notified about restoration using
+    /// [`SceneRenderPass::on_context_restored`].
+    pub fn on_context_restored(
+        &mut self,
+        context: glow::Context,
+        resource_manager: &ResourceManager,
+    ) -> Result<(), FrameworkError> {
+        // Prevent old GPU objects from deleting anything, their names are meaningless now and
+        // could be re-used by new context.
+        self.state.set_context_lost();
+
+        {
+            let state = resource_manager.state();
+            let containers = state.containers();
+            containers
+                .textures
+                .event_broadcaster
+                .remove(self.texture_event_sender);
+            containers
+                .shaders
+                .event_broadcaster
+                .remove(self.shader_event_sender);
+        }
+
+        let mut renderer = Renderer::new(context, self.frame_size, resource_manager)?;
+        renderer.set_quality_settings(&self.quality_settings)?;
+        renderer.backbuffer_clear_color = self.backbuffer_clear_color;
+        renderer.scene_render_passes = std::mem::take(&mut self.scene_render_passes);
+        renderer.scoped_render_passes = std::mem::take(&mut self.scoped_render_passes);
+
+        *self = renderer;
+
+        for pass in self
+            .scene_render_passes
+            .iter()
+            .chain(self.scoped_render_passes.iter().map(|p| &p.pass))
+        {
+            pass.borrow_mut().on_context_restored(&mut self.state)?;
+        }
+
+        Log::writeln(
+            MessageKind::Warning,
+            "Graphics context was lost and successfully restored.",
+        );
+
+        Ok(())
+    }
+
+    /// Sets new dynamic resolution settings. See [`dynamic_resolution`] module docs for more info.
+    pub fn set_dynamic_resolution_settings(&mut self, settings: DynamicResolutionSettings) {
+        self.dynamic_resolution.set_settings(settings);
+    }
+
+    /// Returns current dynamic resolution settings.
+    pub fn dynamic_resolution_settings(&self) -> &DynamicResolutionSettings {
+        self.dynamic_resolution.settings()
+    }
+
+    /// Returns current render scale of scenes that are rendered into the back buffer. It is
+    /// always `1.0` if dynamic resolution is disabled.
+    pub fn render_scale(&self) -> f32 {
+        self.dynamic_resolution.scale()
+    }
+
     /// Sets color which will be used to fill screen when there is nothing to render.
     pub fn set_backbuffer_clear_color(&mut self, color: Color) {
         self.backbuffer_clear_color = color;
@@ -1235,11 +1982,41 @@ impl Renderer {
         self.frame_size
     }
 
+    /// Returns total amount of frames rendered since the renderer was created.
+    pub fn frame_number(&self) -> u64 {
+        self.frame_number
+    }
+
     /// Returns current bounds of back buffer.
     pub fn get_frame_bounds(&self) -> Vector2<f32> {
         Vector2::new(self.frame_size.0 as f32, self.frame_size.1 as f32)
     }
 
+    /// Reads back the final (tone mapped and gamma corrected) frame of the given scene from the GPU.
+    /// Returns `None` if the scene was not rendered yet. Keep in mind that this method stalls the
+    /// pipeline until the GPU finishes rendering, so it is intended for offline capturing (see
+    /// [`crate::utils::capture`]) and screenshots, not for per-frame use in real-time.
+    pub fn read_scene_frame(&mut self, scene: Handle<Scene>) -> Option<RgbaImage> {
+        let data = self.scene_data_map.get(&scene)?;
+        let width = data.gbuffer.width;
+        let height = data.gbuffer.height;
+
+        let pixels = data
+            .ldr_scene_framebuffer
+            .read_pixels(&mut self.state, Rect::new(0, 0, width, height));
+
+        // OpenGL stores rows from bottom to top, images expect the opposite.
+        let row_size = width as usize * 4;
+        let flipped = pixels
+            .chunks_exact(row_size)
+            .rev()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        RgbaImage::from_raw(width as u32, height as u32, flipped)
+    }
+
     /// Sets new quality settings for renderer. Never call this method in a loop, otherwise
     /// you may get **significant** lags. Always check if current quality setting differs
     /// from new!
@@ -1248,6 +2025,7 @@ impl Renderer {
         settings: &QualitySettings,
     ) -> Result<(), FrameworkError> {
         self.quality_settings = *settings;
+        self.rebuild_scene_frame_graph();
         self.deferred_light_renderer
             .set_quality_settings(&mut self.state, settings)
     }
@@ -1374,6 +2152,13 @@ impl Renderer {
             }
         }
 
+        let events = self.shader_cache.process_compilation_queue(&mut self.state);
+        for event in events {
+            // Listeners could be destroyed already, remove them.
+            self.shader_compilation_listeners
+                .retain(|listener| listener.send(event.clone()).is_ok());
+        }
+
         self.shader_cache.update(dt)
     }
 
@@ -1398,6 +2183,8 @@ impl Renderer {
         // Make sure to drop associated data for destroyed scenes.
         self.scene_data_map
             .retain(|h, _| scenes.is_valid_handle(*h));
+        self.scoped_render_passes
+            .retain(|p| scenes.is_valid_handle(p.scene));
 
         // We have to invalidate resource bindings cache because some textures or programs,
         // or other GL resources can be destroyed and then on their "names" some new resource
@@ -1405,7 +2192,11 @@ impl Renderer {
         // object have same name.
         self.state.invalidate_resource_bindings_cache();
         let dt = self.statistics.capped_frame_time;
+        // Capped frame time includes waiting for v-sync (or frame limiter), which must not be
+        // treated as rendering load, otherwise resolution will be lowered without a reason.
+        let render_time = self.statistics.pure_frame_time;
         self.statistics.begin_frame();
+        self.frame_number += 1;
 
         let window_viewport = Rect::new(0, 0, self.frame_size.0 as i32, self.frame_size.1 as i32);
         self.backbuffer.clear(
@@ -1419,6 +2210,11 @@ impl Renderer {
         let backbuffer_width = self.frame_size.0 as f32;
         let backbuffer_height = self.frame_size.1 as f32;
 
+        self.dynamic_resolution.update(render_time);
+        let scaled_backbuffer_size = self
+            .dynamic_resolution
+            .scaled_size(Vector2::new(backbuffer_width, backbuffer_height));
+
         for (scene_handle, scene) in scenes.pair_iter().filter(|(_, s)| s.enabled) {
             let graph = &scene.graph;
 
@@ -1426,8 +2222,8 @@ impl Renderer {
                 .render_target
                 .as_ref()
                 .map_or_else(
-                    // Use either backbuffer size
-                    || Vector2::new(backbuffer_width, backbuffer_height),
+                    // Use either (scaled) backbuffer size
+                    || scaled_backbuffer_size,
                     // Or framebuffer size
                     |rt| {
                         if let TextureKind::Rectangle { width, height } = rt.data_ref().kind() {
@@ -1442,7 +2238,10 @@ impl Renderer {
 
             let state = &mut self.state;
 
-            self.batch_storage.generate_batches(graph);
+            self.batch_storage
+                .generate_batches(graph, self.quality_settings.use_instancing);
+            self.batch_storage
+                .upload_bone_matrices(state, self.quality_settings.use_bone_matrices_texture);
 
             let scene_associated_data = self
                 .scene_data_map
@@ -1491,232 +2290,382 @@ impl Renderer {
                 );
             }
 
-            for camera in graph
-                .linear_iter()
-                .filter_map(|node| node.cast::<Camera>().filter(|&camera| camera.is_enabled()))
-            {
+            // Clear the whole final frame first, viewports of the cameras may not cover it
+            // entirely (for example in split-screen mode with gaps between views).
+            scene_associated_data.ldr_scene_framebuffer.clear(
+                state,
+                Rect::new(0, 0, frame_size.x as i32, frame_size.y as i32),
+                Some(self.backbuffer_clear_color),
+                None,
+                None,
+            );
+
+            let cameras = enabled_cameras_in_render_order(graph);
+
+            // Remove HDR renderers of cameras that are not rendered anymore.
+            scene_associated_data
+                .hdr_renderers
+                .retain(|handle, _| cameras.iter().any(|(camera, _)| camera == handle));
+
+            // Same for occlusion testers, they are removed completely if occlusion culling is off.
+            let use_occlusion_culling = self.quality_settings.use_occlusion_culling;
+            scene_associated_data.occlusion_testers.retain(|handle, _| {
+                use_occlusion_culling && cameras.iter().any(|(camera, _)| camera == handle)
+            });
+
+            for (camera_handle, camera) in cameras {
                 let viewport = camera.viewport_pixels(frame_size);
 
-                self.statistics += scene_associated_data.gbuffer.fill(GBufferRenderContext {
-                    state,
-                    camera,
-                    geom_cache: &mut self.geometry_cache,
-                    batch_storage: &self.batch_storage,
-                    texture_cache: &mut self.texture_cache,
-                    shader_cache: &mut self.shader_cache,
-                    environment_dummy: self.environment_dummy.clone(),
-                    use_parallax_mapping: self.quality_settings.use_parallax_mapping,
-                    normal_dummy: self.normal_dummy.clone(),
-                    white_dummy: self.white_dummy.clone(),
-                    black_dummy: self.black_dummy.clone(),
-                    graph,
-                });
-
-                scene_associated_data.copy_depth_stencil_to_scene_framebuffer(state);
-
-                scene_associated_data.hdr_scene_framebuffer.clear(
-                    state,
-                    viewport,
-                    Some(self.backbuffer_clear_color),
-                    None, // Keep depth, we've just copied valid data in it.
-                    Some(0),
-                );
-
-                let (pass_stats, light_stats) =
-                    self.deferred_light_renderer
-                        .render(DeferredRendererContext {
-                            state,
-                            scene,
-                            camera,
-                            gbuffer: &mut scene_associated_data.gbuffer,
-                            white_dummy: self.white_dummy.clone(),
-                            ambient_color: scene.ambient_lighting_color,
-                            settings: &self.quality_settings,
-                            textures: &mut self.texture_cache,
-                            geometry_cache: &mut self.geometry_cache,
-                            batch_storage: &self.batch_storage,
-                            frame_buffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                            shader_cache: &mut self.shader_cache,
-                            normal_dummy: self.normal_dummy.clone(),
-                            black_dummy: self.black_dummy.clone(),
-                        });
-
-                self.statistics.lighting += light_stats;
-                self.statistics.geometry += pass_stats;
-
-                let depth = scene_associated_data.gbuffer.depth();
-
-                self.statistics +=
-                    self.particle_system_renderer
-                        .render(ParticleSystemRenderContext {
-                            state,
-                            framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                            graph,
-                            camera,
-                            white_dummy: self.white_dummy.clone(),
-                            depth,
-                            frame_width: frame_size.x,
-                            frame_height: frame_size.y,
-                            viewport,
-                            texture_cache: &mut self.texture_cache,
-                        });
-
-                self.statistics += self.sprite_renderer.render(SpriteRenderContext {
-                    state,
-                    framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                    graph,
-                    camera,
-                    white_dummy: self.white_dummy.clone(),
-                    viewport,
-                    textures: &mut self.texture_cache,
-                });
+                if use_occlusion_culling {
+                    let occlusion_tester =
+                        match scene_associated_data.occlusion_testers.entry(camera_handle) {
+                            Entry::Occupied(entry) => entry.into_mut(),
+                            Entry::Vacant(entry) => entry.insert(OcclusionTester::new(
+                                state,
+                                frame_size.x as usize,
+                                frame_size.y as usize,
+                            )?),
+                        };
+
+                    self.statistics += occlusion_tester.run(OcclusionRenderContext {
+                        state,
+                        camera,
+                        geom_cache: &mut self.geometry_cache,
+                        batch_storage: &self.batch_storage,
+                    });
+                }
 
-                self.statistics += self.renderer2d.render(
-                    state,
-                    camera,
-                    &mut scene_associated_data.hdr_scene_framebuffer,
-                    viewport,
-                    graph,
-                    &mut self.texture_cache,
-                    self.white_dummy.clone(),
-                    scene.ambient_lighting_color,
-                )?;
-
-                self.statistics += self.forward_renderer.render(ForwardRenderContext {
-                    state,
-                    camera,
-                    geom_cache: &mut self.geometry_cache,
-                    texture_cache: &mut self.texture_cache,
-                    shader_cache: &mut self.shader_cache,
-                    batch_storage: &self.batch_storage,
-                    framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                    viewport,
-                    quality_settings: &self.quality_settings,
-                    white_dummy: self.white_dummy.clone(),
-                    normal_dummy: self.normal_dummy.clone(),
-                    black_dummy: self.black_dummy.clone(),
-                });
+                scene_associated_data
+                    .transient_resources
+                    .prepare(state, &self.scene_frame_graph)?;
+
+                for pass in self.scene_pass_order.iter() {
+                    match pass {
+                        ScenePass::GBuffer => {
+                            self.statistics +=
+                                scene_associated_data.gbuffer.fill(GBufferRenderContext {
+                                    state,
+                                    camera,
+                                    geom_cache: &mut self.geometry_cache,
+                                    batch_storage: &self.batch_storage,
+                                    texture_cache: &mut self.texture_cache,
+                                    shader_cache: &mut self.shader_cache,
+                                    environment_dummy: self.environment_dummy.clone(),
+                                    use_parallax_mapping: self
+                                        .quality_settings
+                                        .use_parallax_mapping,
+                                    normal_dummy: self.normal_dummy.clone(),
+                                    white_dummy: self.white_dummy.clone(),
+                                    black_dummy: self.black_dummy.clone(),
+                                    graph,
+                                    occlusion_tester: scene_associated_data
+                                        .occlusion_testers
+                                        .get(&camera_handle),
+                                });
+                        }
+                        ScenePass::Lighting => {
+                            scene_associated_data.copy_depth_stencil_to_scene_framebuffer(state);
 
-                for render_pass in self.scene_render_passes.iter() {
-                    self.statistics +=
-                        render_pass
-                            .borrow_mut()
-                            .on_hdr_render(SceneRenderPassContext {
-                                pipeline_state: state,
+                            scene_associated_data.hdr_scene_framebuffer.clear(
+                                state,
+                                viewport,
+                                Some(self.backbuffer_clear_color),
+                                None, // Keep depth, we've just copied valid data in it.
+                                Some(0),
+                            );
+
+                            let (pass_stats, light_stats) =
+                                self.deferred_light_renderer
+                                    .render(DeferredRendererContext {
+                                        state,
+                                        scene,
+                                        camera,
+                                        gbuffer: &mut scene_associated_data.gbuffer,
+                                        white_dummy: self.white_dummy.clone(),
+                                        ambient_color: scene.ambient_lighting_color,
+                                        settings: &self.quality_settings,
+                                        textures: &mut self.texture_cache,
+                                        geometry_cache: &mut self.geometry_cache,
+                                        batch_storage: &self.batch_storage,
+                                        frame_buffer: &mut scene_associated_data
+                                            .hdr_scene_framebuffer,
+                                        shader_cache: &mut self.shader_cache,
+                                        normal_dummy: self.normal_dummy.clone(),
+                                        black_dummy: self.black_dummy.clone(),
+                                    });
+
+                            self.statistics.lighting += light_stats;
+                            self.statistics.geometry += pass_stats;
+                        }
+                        ScenePass::ParticleSystems => {
+                            let depth = scene_associated_data.gbuffer.depth();
+
+                            self.statistics +=
+                                self.particle_system_renderer
+                                    .render(ParticleSystemRenderContext {
+                                        state,
+                                        framebuffer: &mut scene_associated_data
+                                            .hdr_scene_framebuffer,
+                                        graph,
+                                        camera,
+                                        white_dummy: self.white_dummy.clone(),
+                                        depth,
+                                        frame_width: frame_size.x,
+                                        frame_height: frame_size.y,
+                                        viewport,
+                                        texture_cache: &mut self.texture_cache,
+                                    });
+                        }
+                        ScenePass::Sprites => {
+                            self.statistics += self.sprite_renderer.render(SpriteRenderContext {
+                                state,
+                                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                                graph,
+                                camera,
+                                white_dummy: self.white_dummy.clone(),
+                                viewport,
+                                textures: &mut self.texture_cache,
+                            });
+                        }
+                        ScenePass::Renderer2d => {
+                            self.statistics += self.renderer2d.render(
+                                state,
+                                camera,
+                                &mut scene_associated_data.hdr_scene_framebuffer,
+                                viewport,
+                                graph,
+                                &mut self.texture_cache,
+                                self.white_dummy.clone(),
+                                scene.ambient_lighting_color,
+                            )?;
+                        }
+                        ScenePass::Forward => {
+                            self.statistics += self.forward_renderer.render(ForwardRenderContext {
+                                state,
+                                camera,
+                                geom_cache: &mut self.geometry_cache,
                                 texture_cache: &mut self.texture_cache,
-                                geometry_cache: &mut self.geometry_cache,
-                                quality_settings: &self.quality_settings,
+                                shader_cache: &mut self.shader_cache,
                                 batch_storage: &self.batch_storage,
+                                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                                oit_framebuffer: &mut scene_associated_data.oit_framebuffer,
                                 viewport,
-                                scene,
-                                camera,
-                                scene_handle,
+                                quality_settings: &self.quality_settings,
+                                clustered_lighting: &self
+                                    .deferred_light_renderer
+                                    .clustered_lighting,
                                 white_dummy: self.white_dummy.clone(),
                                 normal_dummy: self.normal_dummy.clone(),
-                                metallic_dummy: self.metallic_dummy.clone(),
-                                environment_dummy: self.environment_dummy.clone(),
                                 black_dummy: self.black_dummy.clone(),
-                                depth_texture: scene_associated_data.gbuffer.depth(),
-                                normal_texture: scene_associated_data.gbuffer.normal_texture(),
-                                ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
-                                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                                ui_renderer: &mut self.ui_renderer,
-                            })?;
+                                occlusion_tester: scene_associated_data
+                                    .occlusion_testers
+                                    .get(&camera_handle),
+                            });
+                        }
+                        ScenePass::CustomHdr => {
+                            for render_pass in self.scene_render_passes.iter().chain(
+                                self.scoped_render_passes
+                                    .iter()
+                                    .filter(|p| p.scene == scene_handle)
+                                    .map(|p| &p.pass),
+                            ) {
+                                self.statistics += render_pass.borrow_mut().on_hdr_render(
+                                    SceneRenderPassContext {
+                                        pipeline_state: state,
+                                        texture_cache: &mut self.texture_cache,
+                                        geometry_cache: &mut self.geometry_cache,
+                                        quality_settings: &self.quality_settings,
+                                        batch_storage: &self.batch_storage,
+                                        viewport,
+                                        scene,
+                                        camera,
+                                        scene_handle,
+                                        white_dummy: self.white_dummy.clone(),
+                                        normal_dummy: self.normal_dummy.clone(),
+                                        metallic_dummy: self.metallic_dummy.clone(),
+                                        environment_dummy: self.environment_dummy.clone(),
+                                        black_dummy: self.black_dummy.clone(),
+                                        depth_texture: scene_associated_data.gbuffer.depth(),
+                                        normal_texture: scene_associated_data
+                                            .gbuffer
+                                            .normal_texture(),
+                                        ambient_texture: scene_associated_data
+                                            .gbuffer
+                                            .ambient_texture(),
+                                        framebuffer: &mut scene_associated_data
+                                            .hdr_scene_framebuffer,
+                                        ui_renderer: &mut self.ui_renderer,
+                                    },
+                                )?;
+                            }
+                        }
+                        ScenePass::Bloom => {
+                            // Prepare glow map.
+                            let bloom = &camera.post_processing().bloom;
+                            if bloom.enabled {
+                                self.statistics.geometry +=
+                                    scene_associated_data.bloom_renderer.render(
+                                        state,
+                                        &self.quad,
+                                        scene_associated_data.hdr_scene_frame_texture(),
+                                        bloom.threshold,
+                                    );
+                            }
+                        }
+                        ScenePass::ToneMapping => {
+                            let bloom_texture = if self.quality_settings.use_bloom
+                                && camera.post_processing().bloom.enabled
+                            {
+                                scene_associated_data.bloom_renderer.result()
+                            } else {
+                                self.black_dummy.clone()
+                            };
+
+                            let hdr_scene_frame_texture =
+                                scene_associated_data.hdr_scene_frame_texture();
+                            let hdr_renderer =
+                                match scene_associated_data.hdr_renderers.entry(camera_handle) {
+                                    Entry::Occupied(entry) => entry.into_mut(),
+                                    Entry::Vacant(entry) => {
+                                        entry.insert(HighDynamicRangeRenderer::new(state)?)
+                                    }
+                                };
+
+                            // Convert high dynamic range frame to low dynamic range (sRGB) with tone mapping and gamma correction.
+                            self.statistics.geometry += hdr_renderer.render(
+                                state,
+                                hdr_scene_frame_texture,
+                                bloom_texture,
+                                &mut scene_associated_data.ldr_scene_framebuffer,
+                                viewport,
+                                &self.quad,
+                                dt,
+                                camera.exposure(),
+                                camera.color_grading_lut_ref(),
+                                camera.color_grading_enabled(),
+                                camera.post_processing(),
+                                &mut self.texture_cache,
+                            );
+                        }
+                        ScenePass::Fxaa => {
+                            let anti_aliased = self
+                                .scene_frame_graph
+                                .passes()
+                                .find(|(_, p)| *p.payload() == ScenePass::Fxaa)
+                                .map(|(_, p)| p.writes()[0])
+                                .unwrap();
+                            let ldr_scene_frame_texture =
+                                scene_associated_data.ldr_scene_frame_texture();
+                            let temp_framebuffer = scene_associated_data
+                                .transient_resources
+                                .framebuffer(state, &self.scene_frame_graph, &[anti_aliased])?;
+
+                            self.statistics.geometry += self.fxaa_renderer.render(
+                                state,
+                                viewport,
+                                ldr_scene_frame_texture,
+                                temp_framebuffer,
+                            );
+
+                            let temp_frame_texture =
+                                temp_framebuffer.color_attachments()[0].texture.clone();
+                            self.statistics.geometry += blit_pixels(
+                                state,
+                                &mut scene_associated_data.ldr_scene_framebuffer,
+                                temp_frame_texture,
+                                &self.flat_shader,
+                                viewport,
+                                &self.quad,
+                            );
+                        }
+                        ScenePass::Text3D => {
+                            self.statistics += self.text3d_renderer.render(Text3DRenderContext {
+                                state,
+                                framebuffer: &mut scene_associated_data.ldr_scene_framebuffer,
+                                graph,
+                                camera,
+                                viewport,
+                            });
+                        }
+                        ScenePass::Debug => {
+                            // Render debug geometry in the LDR frame buffer.
+                            self.statistics += self.debug_renderer.render(
+                                state,
+                                viewport,
+                                &mut scene_associated_data.ldr_scene_framebuffer,
+                                &scene.drawing_context,
+                                camera,
+                            );
+                        }
+                        ScenePass::CustomLdr => {
+                            for render_pass in self.scene_render_passes.iter().chain(
+                                self.scoped_render_passes
+                                    .iter()
+                                    .filter(|p| p.scene == scene_handle)
+                                    .map(|p| &p.pass),
+                            ) {
+                                self.statistics += render_pass.borrow_mut().on_ldr_render(
+                                    SceneRenderPassContext {
+                                        pipeline_state: state,
+                                        texture_cache: &mut self.texture_cache,
+                                        geometry_cache: &mut self.geometry_cache,
+                                        quality_settings: &self.quality_settings,
+                                        batch_storage: &self.batch_storage,
+                                        viewport,
+                                        scene,
+                                        camera,
+                                        scene_handle,
+                                        white_dummy: self.white_dummy.clone(),
+                                        normal_dummy: self.normal_dummy.clone(),
+                                        metallic_dummy: self.metallic_dummy.clone(),
+                                        environment_dummy: self.environment_dummy.clone(),
+                                        black_dummy: self.black_dummy.clone(),
+                                        depth_texture: scene_associated_data.gbuffer.depth(),
+                                        normal_texture: scene_associated_data
+                                            .gbuffer
+                                            .normal_texture(),
+                                        ambient_texture: scene_associated_data
+                                            .gbuffer
+                                            .ambient_texture(),
+                                        framebuffer: &mut scene_associated_data
+                                            .ldr_scene_framebuffer,
+                                        ui_renderer: &mut self.ui_renderer,
+                                    },
+                                )?;
+                            }
+                        }
+                    }
                 }
+            }
 
+            // Optionally render everything into back buffer.
+            if scene.render_target.is_none() {
                 let quad = &self.quad;
-
-                // Prepare glow map.
-                self.statistics.geometry += scene_associated_data.bloom_renderer.render(
-                    state,
-                    quad,
-                    scene_associated_data.hdr_scene_frame_texture(),
-                );
-
-                // Convert high dynamic range frame to low dynamic range (sRGB) with tone mapping and gamma correction.
-                self.statistics.geometry += scene_associated_data.hdr_renderer.render(
-                    state,
-                    scene_associated_data.hdr_scene_frame_texture(),
-                    scene_associated_data.bloom_renderer.result(),
-                    &mut scene_associated_data.ldr_scene_framebuffer,
-                    viewport,
-                    quad,
-                    dt,
-                    camera.exposure(),
-                    camera.color_grading_lut_ref(),
-                    camera.color_grading_enabled(),
-                    &mut self.texture_cache,
-                );
-
-                // Apply FXAA if needed.
-                if self.quality_settings.fxaa {
-                    self.statistics.geometry += self.fxaa_renderer.render(
+                if frame_size.x != backbuffer_width
+                    || frame_size.y != backbuffer_height
+                    || self.quality_settings.sharpening > 0.0
+                {
+                    // Scene was rendered at lower resolution (or should be sharpened), upscale it.
+                    self.statistics.geometry += self.upscale_renderer.render(
                         state,
-                        viewport,
+                        quad,
+                        window_viewport,
                         scene_associated_data.ldr_scene_frame_texture(),
-                        &mut scene_associated_data.ldr_temp_framebuffer,
+                        frame_size,
+                        &mut self.backbuffer,
+                        self.quality_settings.upscaling_filter,
+                        self.quality_settings.sharpening,
                     );
-
-                    let quad = &self.quad;
-                    let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
+                } else {
                     self.statistics.geometry += blit_pixels(
                         state,
-                        &mut scene_associated_data.ldr_scene_framebuffer,
-                        temp_frame_texture,
+                        &mut self.backbuffer,
+                        scene_associated_data.ldr_scene_frame_texture(),
                         &self.flat_shader,
-                        viewport,
+                        window_viewport,
                         quad,
                     );
                 }
-
-                // Render debug geometry in the LDR frame buffer.
-                self.statistics += self.debug_renderer.render(
-                    state,
-                    viewport,
-                    &mut scene_associated_data.ldr_scene_framebuffer,
-                    &scene.drawing_context,
-                    camera,
-                );
-
-                for render_pass in self.scene_render_passes.iter() {
-                    self.statistics +=
-                        render_pass
-                            .borrow_mut()
-                            .on_ldr_render(SceneRenderPassContext {
-                                pipeline_state: state,
-                                texture_cache: &mut self.texture_cache,
-                                geometry_cache: &mut self.geometry_cache,
-                                quality_settings: &self.quality_settings,
-                                batch_storage: &self.batch_storage,
-                                viewport,
-                                scene,
-                                camera,
-                                scene_handle,
-                                white_dummy: self.white_dummy.clone(),
-                                normal_dummy: self.normal_dummy.clone(),
-                                metallic_dummy: self.metallic_dummy.clone(),
-                                environment_dummy: self.environment_dummy.clone(),
-                                black_dummy: self.black_dummy.clone(),
-                                depth_texture: scene_associated_data.gbuffer.depth(),
-                                normal_texture: scene_associated_data.gbuffer.normal_texture(),
-                                ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
-                                framebuffer: &mut scene_associated_data.ldr_scene_framebuffer,
-                                ui_renderer: &mut self.ui_renderer,
-                            })?;
-                }
-            }
-
-            // Optionally render everything into back buffer.
-            if scene.render_target.is_none() {
-                let quad = &self.quad;
-                self.statistics.geometry += blit_pixels(
-                    state,
-                    &mut self.backbuffer,
-                    scene_associated_data.ldr_scene_frame_texture(),
-                    &self.flat_shader,
-                    window_viewport,
-                    quad,
-                );
             }
         }
 
@@ -1732,6 +2681,11 @@ impl Renderer {
             texture_cache: &mut self.texture_cache,
         })?;
 
+        self.statistics.gpu_memory = GpuMemoryStatistics {
+            textures: self.texture_cache.size_bytes(),
+            geometry: self.geometry_cache.size_bytes(),
+        };
+
         Ok(())
     }
 
@@ -1740,7 +2694,7 @@ impl Renderer {
         &mut self,
         scenes: &SceneContainer,
         drawing_context: &DrawingContext,
-        context: &glutin::WindowedContext<glutin::PossiblyCurrent>,
+        context: &crate::engine::graphics_context::GraphicsContext,
     ) -> Result<(), FrameworkError> {
         self.render_frame(scenes, drawing_context)?;
         self.statistics.end_frame();
@@ -1765,3 +2719,30 @@ impl Renderer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        renderer::enabled_cameras_in_render_order,
+        scene::{base::BaseBuilder, camera::CameraBuilder, graph::Graph},
+    };
+
+    #[test]
+    fn test_cameras_render_order() {
+        let mut graph = Graph::new();
+        let a = CameraBuilder::new(BaseBuilder::new())
+            .with_render_order(1)
+            .build(&mut graph);
+        let b = CameraBuilder::new(BaseBuilder::new()).build(&mut graph);
+        CameraBuilder::new(BaseBuilder::new())
+            .enabled(false)
+            .build(&mut graph);
+        let c = CameraBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let order = enabled_cameras_in_render_order(&graph)
+            .into_iter()
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![b, c, a]);
+    }
+}
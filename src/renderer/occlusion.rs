@@ -0,0 +1,277 @@
+//! Occlusion culling based on hardware occlusion queries. See [`OcclusionTester`] docs for more
+//! info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, Rect},
+        pool::Handle,
+        scope_profile,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        batch::{BatchStorage, SurfaceInstanceFlags},
+        cache::geometry::GeometryCache,
+        framework::{
+            error::FrameworkError,
+            framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
+            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+            },
+            query::{Query, QueryKind},
+            state::{ColorMask, PipelineState},
+        },
+        RenderPassStatistics,
+    },
+    scene::{camera::Camera, mesh::surface::SurfaceData, node::Node},
+};
+use fxhash::FxHashMap;
+use std::{cell::RefCell, collections::hash_map::Entry, rc::Rc};
+
+struct Shader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+}
+
+impl Shader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/occlusion_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+        let program =
+            GpuProgram::from_source(state, "OcclusionShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            program,
+        })
+    }
+}
+
+pub(crate) struct OcclusionRenderContext<'a, 'b> {
+    pub state: &'a mut PipelineState,
+    pub camera: &'b Camera,
+    pub geom_cache: &'a mut GeometryCache,
+    pub batch_storage: &'a BatchStorage,
+}
+
+/// Occlusion tester decides which nodes are hidden behind other nodes from a point of view of a
+/// camera. At first, every instance of an occluder node (see [`crate::scene::base::Base::set_occluder`])
+/// is rendered in a depth-only frame buffer. Then bounding boxes of occludee nodes (see
+/// [`crate::scene::base::Base::set_occludee`]) are tested against the depth buffer using occlusion
+/// queries. A node is considered occluded if no pixel of its bounding box passed the depth test.
+///
+/// Results of the queries are fetched without stalling the pipeline, so they are available with
+/// a latency of at least one frame. Nodes without known results are always considered visible,
+/// which may cause very short "pop-ins" of nodes that became visible after they were occluded.
+pub struct OcclusionTester {
+    framebuffer: FrameBuffer,
+    shader: Shader,
+    cube: GeometryBuffer,
+    width: i32,
+    height: i32,
+    queries: FxHashMap<Handle<Node>, Query>,
+    visibility: FxHashMap<Handle<Node>, bool>,
+    occludees: FxHashMap<Handle<Node>, AxisAlignedBoundingBox>,
+}
+
+impl OcclusionTester {
+    /// Creates new occlusion tester with a depth buffer of the given size.
+    pub fn new(
+        state: &mut PipelineState,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, FrameworkError> {
+        let depth = GpuTexture::new(
+            state,
+            GpuTextureKind::Rectangle { width, height },
+            PixelKind::D32F,
+            MinificationFilter::Nearest,
+            MagnificationFilter::Nearest,
+            1,
+            None,
+        )?;
+
+        Ok(Self {
+            framebuffer: FrameBuffer::new(
+                state,
+                Some(Attachment {
+                    kind: AttachmentKind::Depth,
+                    texture: Rc::new(RefCell::new(depth)),
+                }),
+                vec![],
+            )?,
+            shader: Shader::new(state)?,
+            cube: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_cube(Matrix4::identity()),
+                GeometryBufferKind::StaticDraw,
+                state,
+            ),
+            width: width as i32,
+            height: height as i32,
+            queries: Default::default(),
+            visibility: Default::default(),
+            occludees: Default::default(),
+        })
+    }
+
+    /// Returns `false` if the node was hidden behind occluders on the last tested frame, `true` -
+    /// otherwise. Nodes that were never tested are always visible.
+    pub fn is_visible(&self, node: Handle<Node>) -> bool {
+        self.visibility.get(&node).cloned().unwrap_or(true)
+    }
+
+    pub(crate) fn run(&mut self, args: OcclusionRenderContext) -> RenderPassStatistics {
+        scope_profile!();
+
+        let mut statistics = RenderPassStatistics::default();
+
+        let OcclusionRenderContext {
+            state,
+            camera,
+            geom_cache,
+            batch_storage,
+        } = args;
+
+        // Fetch results of the queries issued on previous frames.
+        for (handle, query) in self.queries.iter_mut() {
+            if let Some(samples_passed) = query.try_get_result(state) {
+                self.visibility.insert(*handle, samples_passed > 0);
+            }
+        }
+
+        // Gather world-space bounds of every occludee that passed frustum culling.
+        self.occludees.clear();
+        for batch in batch_storage.batches.iter() {
+            for instance in batch.instances.iter() {
+                if instance
+                    .flags
+                    .contains(SurfaceInstanceFlags::IS_VISIBLE | SurfaceInstanceFlags::OCCLUDEE)
+                    && camera.visibility_cache.is_visible(instance.owner)
+                {
+                    self.occludees
+                        .entry(instance.owner)
+                        .or_default()
+                        .add_box(instance.world_aabb);
+                }
+            }
+        }
+
+        // Forget everything about nodes that left the frustum, they will be tested again when
+        // they're back.
+        let occludees = &self.occludees;
+        self.queries
+            .retain(|handle, _| occludees.contains_key(handle));
+        self.visibility
+            .retain(|handle, _| occludees.contains_key(handle));
+
+        let viewport = Rect::new(0, 0, self.width, self.height);
+        let view_projection = camera.view_projection_matrix();
+        let shader = &self.shader;
+        let framebuffer = &mut self.framebuffer;
+
+        framebuffer.clear(state, viewport, None, Some(1.0), None);
+
+        // Fill the depth buffer with occluders. Skinned meshes are ignored, because their vertices
+        // must be transformed by bones first.
+        let occluder_draw_params = DrawParameters {
+            cull_face: None,
+            color_write: ColorMask::all(false),
+            depth_write: true,
+            stencil_test: None,
+            depth_test: true,
+            blend: None,
+            stencil_op: Default::default(),
+        };
+
+        for batch in batch_storage.batches.iter().filter(|b| !b.is_skinned) {
+            let geometry = geom_cache.get(state, &batch.data);
+
+            for instance in batch.instances.iter() {
+                if instance
+                    .flags
+                    .contains(SurfaceInstanceFlags::IS_VISIBLE | SurfaceInstanceFlags::OCCLUDER)
+                    && instance.opacity >= 1.0
+                    && camera.visibility_cache.is_visible(instance.owner)
+                {
+                    statistics += framebuffer.draw(
+                        geometry,
+                        state,
+                        viewport,
+                        &shader.program,
+                        &occluder_draw_params,
+                        |mut program_binding| {
+                            program_binding.set_matrix4(
+                                &shader.wvp_matrix,
+                                &(view_projection * instance.world_transform),
+                            );
+                        },
+                    );
+                }
+            }
+        }
+
+        // Test bounding boxes of occludees against the depth buffer.
+        let occludee_draw_params = DrawParameters {
+            cull_face: None,
+            color_write: ColorMask::all(false),
+            depth_write: false,
+            stencil_test: None,
+            depth_test: true,
+            blend: None,
+            stencil_op: Default::default(),
+        };
+
+        let camera_position = camera.global_position();
+        let z_near = camera.projection().z_near();
+
+        for (handle, aabb) in self.occludees.iter() {
+            // Enlarge the box a bit, otherwise it could be occluded by surfaces of the node itself.
+            let mut aabb = *aabb;
+            aabb.inflate((aabb.max - aabb.min).scale(0.02).add_scalar(0.02));
+
+            // Bounding box cannot be tested if the camera is inside it or the box is clipped by
+            // the near clipping plane, such nodes are always visible.
+            let mut near_aabb = aabb;
+            near_aabb.inflate(Vector3::repeat(4.0 * z_near));
+            if near_aabb.is_contains_point(camera_position) {
+                self.queries.remove(handle);
+                self.visibility.insert(*handle, true);
+                continue;
+            }
+
+            let query = match self.queries.entry(*handle) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => match Query::new(state, QueryKind::AnySamplesPassed) {
+                    Ok(query) => entry.insert(query),
+                    Err(_) => continue,
+                },
+            };
+
+            // Wait until the result of the previous query is available.
+            if query.is_pending() {
+                continue;
+            }
+
+            let world_matrix = Matrix4::new_translation(&aabb.center())
+                * Matrix4::new_nonuniform_scaling(&(aabb.max - aabb.min));
+
+            query.begin(state);
+            statistics += framebuffer.draw(
+                &self.cube,
+                state,
+                viewport,
+                &shader.program,
+                &occludee_draw_params,
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(&shader.wvp_matrix, &(view_projection * world_matrix));
+                },
+            );
+            query.end(state);
+        }
+
+        statistics
+    }
+}
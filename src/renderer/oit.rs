@@ -0,0 +1,340 @@
+//! Weighted blended order-independent transparency (OIT). Transparent surfaces are rendered into an
+//! off-screen frame buffer with two render targets - the first one accumulates premultiplied and
+//! weighted colors of fragments (RGB) and the product of their transmittances (A), the second one
+//! accumulates weights of fragments. Accumulated colors are then normalized by the sum of weights
+//! and composed over the scene frame. See "Weighted Blended Order-Independent Transparency" paper
+//! by McGuire and Bavoil for more info.
+//!
+//! Custom shaders do not need to be changed to be rendered with OIT - fragment shader of `Forward`
+//! render pass is automatically wrapped to write its output to OIT render targets. Both targets
+//! use the same blending function (no need for per-target blending, which is unavailable in
+//! GL 3.3 and GLES 3.0), that's why the sum of weights is stored in a separate target.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        color::Color,
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{
+                Attachment, AttachmentKind, BlendParameters, DrawParameters, FrameBuffer,
+            },
+            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+                PixelKind, WrapMode,
+            },
+            state::{BlendFactor, BlendFunc, PipelineState},
+        },
+        RenderPassStatistics,
+    },
+    scene::mesh::surface::SurfaceData,
+};
+use std::{cell::RefCell, rc::Rc};
+
+const FORWARD_MAIN: &str = "fyrox_forwardMain";
+
+// Extracts a name of the output from a declaration like `layout(location = 0) out vec4 FragColor;`.
+fn output_name(line: &str) -> Option<&str> {
+    let mut declaration = line.trim();
+    if declaration.starts_with("layout") {
+        declaration = declaration[declaration.find(')')? + 1..].trim_start();
+    }
+    let name = declaration
+        .strip_prefix("out")?
+        .trim_start()
+        .strip_prefix("vec4")?
+        .trim()
+        .strip_suffix(';')?
+        .trim();
+    if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+// Finds the position of the name of the `main` function.
+fn find_main(source: &str) -> Option<usize> {
+    source.match_indices("main").map(|(i, _)| i).find(|&i| {
+        let before = &source[..i];
+        let after = &source[i + "main".len()..];
+        before.trim_end().ends_with("void")
+            && before.ends_with(char::is_whitespace)
+            && after.trim_start().starts_with('(')
+    })
+}
+
+/// Wraps a fragment shader of `Forward` render pass, so it will write its output to OIT render
+/// targets. Returns `None` if the shader has more than one output.
+pub(crate) fn make_oit_fragment_shader(source: &str) -> Option<String> {
+    let mut output = None;
+    let mut lines = Vec::new();
+    for line in source.lines() {
+        match output_name(line) {
+            Some(name) => {
+                if output.replace(name).is_some() {
+                    return None;
+                }
+                // The output becomes a global variable, which is then used by the wrapper.
+                lines.push(format!("vec4 {};", name));
+            }
+            None => lines.push(line.to_owned()),
+        }
+    }
+    let output = output?;
+
+    let mut wrapped = lines.join("\n");
+    let main = find_main(&wrapped)?;
+    wrapped.replace_range(main..main + "main".len(), FORWARD_MAIN);
+
+    wrapped += &format!(
+        r#"
+layout(location = 0) out vec4 fyrox_oitAccumulation;
+layout(location = 1) out vec4 fyrox_oitWeight;
+
+void main()
+{{
+    {FORWARD_MAIN}();
+
+    float alpha = clamp({output}.a, 0.0, 1.0);
+    float weight = alpha * S_OitWeight(gl_FragCoord.z, alpha);
+
+    fyrox_oitAccumulation = vec4({output}.rgb * weight, alpha);
+    fyrox_oitWeight = vec4(weight);
+}}
+"#
+    );
+
+    Some(wrapped)
+}
+
+/// Modifies draw parameters of `Forward` render pass to render into OIT frame buffer. Transparent
+/// surfaces are tested against depth of opaque geometry, but they do not write depth.
+pub(crate) fn oit_draw_parameters(draw_params: &DrawParameters) -> DrawParameters {
+    DrawParameters {
+        depth_write: false,
+        blend: Some(BlendParameters {
+            func: BlendFunc::new_separate(
+                BlendFactor::One,
+                BlendFactor::One,
+                BlendFactor::Zero,
+                BlendFactor::OneMinusSrcAlpha,
+            ),
+            equation: Default::default(),
+        }),
+        ..draw_params.clone()
+    }
+}
+
+fn make_target(
+    state: &mut PipelineState,
+    width: usize,
+    height: usize,
+    pixel_kind: PixelKind,
+) -> Result<Rc<RefCell<GpuTexture>>, FrameworkError> {
+    let mut texture = GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle { width, height },
+        pixel_kind,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        None,
+    )?;
+    texture
+        .bind_mut(state, 0)
+        .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+        .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+    Ok(Rc::new(RefCell::new(texture)))
+}
+
+/// Creates OIT frame buffer of the same size as the given scene frame buffer. Depth buffer is
+/// shared with the scene frame buffer, so transparent surfaces are occluded by opaque geometry.
+pub(crate) fn make_oit_framebuffer(
+    state: &mut PipelineState,
+    scene_framebuffer: &FrameBuffer,
+) -> Result<FrameBuffer, FrameworkError> {
+    let (width, height) = match scene_framebuffer
+        .color_attachments()
+        .first()
+        .map(|a| a.texture.borrow().kind())
+    {
+        Some(GpuTextureKind::Rectangle { width, height }) => (width, height),
+        _ => return Err(FrameworkError::FailedToConstructFBO),
+    };
+
+    let accumulation = make_target(state, width, height, PixelKind::RGBA16F)?;
+    let weight = make_target(state, width, height, PixelKind::F16)?;
+
+    FrameBuffer::new(
+        state,
+        scene_framebuffer
+            .depth_attachment()
+            .map(|depth| Attachment {
+                kind: depth.kind,
+                texture: depth.texture.clone(),
+            }),
+        vec![
+            Attachment {
+                kind: AttachmentKind::Color,
+                texture: accumulation,
+            },
+            Attachment {
+                kind: AttachmentKind::Color,
+                texture: weight,
+            },
+        ],
+    )
+}
+
+/// Prepares OIT frame buffer for rendering: accumulated colors and weights are set to zero,
+/// transmittance is set to one.
+pub(crate) fn clear_oit_framebuffer(
+    framebuffer: &mut FrameBuffer,
+    state: &mut PipelineState,
+    viewport: Rect<i32>,
+) {
+    framebuffer.clear(
+        state,
+        viewport,
+        Some(Color::from_rgba(0, 0, 0, 255)),
+        None,
+        None,
+    );
+}
+
+struct OitCompositeShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    accumulation_texture: UniformLocation,
+    weight_texture: UniformLocation,
+}
+
+impl OitCompositeShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/oit_composite_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+
+        let program =
+            GpuProgram::from_source(state, "OitCompositeShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            accumulation_texture: program
+                .uniform_location(state, &ImmutableString::new("accumulationTexture"))?,
+            weight_texture: program
+                .uniform_location(state, &ImmutableString::new("weightTexture"))?,
+            program,
+        })
+    }
+}
+
+/// Composes accumulated transparent surfaces over the scene frame.
+pub(crate) struct OitRenderer {
+    shader: OitCompositeShader,
+    quad: GeometryBuffer,
+}
+
+impl OitRenderer {
+    pub(crate) fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: OitCompositeShader::new(state)?,
+            quad: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                GeometryBufferKind::StaticDraw,
+                state,
+            ),
+        })
+    }
+
+    pub(crate) fn compose(
+        &self,
+        state: &mut PipelineState,
+        viewport: Rect<i32>,
+        oit_framebuffer: &FrameBuffer,
+        framebuffer: &mut FrameBuffer,
+    ) -> RenderPassStatistics {
+        let mut statistics = RenderPassStatistics::default();
+
+        let frame_matrix = Matrix4::new_orthographic(
+            0.0,
+            viewport.w() as f32,
+            viewport.h() as f32,
+            0.0,
+            -1.0,
+            1.0,
+        ) * Matrix4::new_nonuniform_scaling(&Vector3::new(
+            viewport.w() as f32,
+            viewport.h() as f32,
+            0.0,
+        ));
+
+        let targets = oit_framebuffer.color_attachments();
+        let accumulation = targets[0].texture.clone();
+        let weight = targets[1].texture.clone();
+
+        statistics += framebuffer.draw(
+            &self.quad,
+            state,
+            viewport,
+            &self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: Some(BlendParameters {
+                    func: BlendFunc::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha),
+                    equation: Default::default(),
+                }),
+                stencil_op: Default::default(),
+            },
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&self.shader.wvp_matrix, &frame_matrix)
+                    .set_texture(&self.shader.accumulation_texture, &accumulation)
+                    .set_texture(&self.shader.weight_texture, &weight);
+            },
+        );
+
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::oit::make_oit_fragment_shader;
+
+    #[test]
+    fn test_oit_fragment_shader() {
+        let source = r#"
+            uniform vec4 diffuseColor;
+            out vec4 FragColor;
+            void main()
+            {
+                FragColor = diffuseColor;
+            }
+        "#;
+
+        let wrapped = make_oit_fragment_shader(source).unwrap();
+        assert!(!wrapped.contains("out vec4 FragColor;"));
+        assert!(wrapped.contains("vec4 FragColor;"));
+        assert!(wrapped.contains("void fyrox_forwardMain()"));
+        assert!(wrapped.contains("fyrox_oitAccumulation = vec4(FragColor.rgb * weight, alpha);"));
+        assert_eq!(wrapped.matches("void main()").count(), 1);
+
+        let multiple_outputs = r#"
+            layout(location = 0) out vec4 color;
+            layout(location = 1) out vec4 normal;
+            void main() { color = vec4(1.0); normal = vec4(0.0); }
+        "#;
+        assert!(make_oit_fragment_shader(multiple_outputs).is_none());
+    }
+}
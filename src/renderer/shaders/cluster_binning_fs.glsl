@@ -0,0 +1,65 @@
+// Fills bit masks of the lights that affect every froxel. Every texel of the render target is a
+// froxel: x = tileX + tileY * S_CLUSTER_COUNT_X, y = slice + lightGroup * S_CLUSTER_COUNT_Z.
+
+uniform sampler2D lightTexture;
+uniform mat4 viewMatrix;
+uniform mat4 invProjection;
+uniform vec4 clusterParameters;
+
+out highp uvec4 FragColor;
+
+// Returns view space point on a ray that goes through the given point in normalized device
+// coordinates at the given depth.
+vec3 ViewSpacePoint(vec2 ndc, float depth)
+{
+    vec4 nearPlanePoint = invProjection * vec4(ndc, -1.0, 1.0);
+    vec3 direction = nearPlanePoint.xyz / nearPlanePoint.w;
+    return direction * (depth / -direction.z);
+}
+
+void main()
+{
+    ivec2 texel = ivec2(gl_FragCoord.xy);
+    int tileX = texel.x % S_CLUSTER_COUNT_X;
+    int tileY = texel.x / S_CLUSTER_COUNT_X;
+    int slice = texel.y % S_CLUSTER_COUNT_Z;
+    int group = texel.y / S_CLUSTER_COUNT_Z;
+
+    float zNear = clusterParameters.x;
+    float zFar = clusterParameters.y;
+    int lightCount = int(clusterParameters.z);
+
+    float sliceNear = zNear * pow(zFar / zNear, float(slice) / float(S_CLUSTER_COUNT_Z));
+    float sliceFar = zNear * pow(zFar / zNear, float(slice + 1) / float(S_CLUSTER_COUNT_Z));
+
+    vec2 clusterCount = vec2(float(S_CLUSTER_COUNT_X), float(S_CLUSTER_COUNT_Y));
+    vec2 tileMin = vec2(float(tileX), float(tileY)) / clusterCount * 2.0 - 1.0;
+    vec2 tileMax = vec2(float(tileX + 1), float(tileY + 1)) / clusterCount * 2.0 - 1.0;
+
+    // Bounds of the froxel in view space.
+    vec3 aabbMin = vec3(3.4e38);
+    vec3 aabbMax = vec3(-3.4e38);
+    for (int i = 0; i < 4; ++i) {
+        vec2 corner = vec2(i % 2 == 0 ? tileMin.x : tileMax.x, i / 2 == 0 ? tileMin.y : tileMax.y);
+        vec3 nearCorner = ViewSpacePoint(corner, sliceNear);
+        vec3 farCorner = ViewSpacePoint(corner, sliceFar);
+        aabbMin = min(aabbMin, min(nearCorner, farCorner));
+        aabbMax = max(aabbMax, max(nearCorner, farCorner));
+    }
+
+    highp uint mask[4] = uint[4](0u, 0u, 0u, 0u);
+
+    int first = group * S_LIGHTS_PER_CLUSTER_GROUP;
+    int last = min(first + S_LIGHTS_PER_CLUSTER_GROUP, lightCount);
+    for (int index = first; index < last; ++index) {
+        vec4 positionRadius = texelFetch(lightTexture, ivec2(index, 0), 0);
+        vec3 center = (viewMatrix * vec4(positionRadius.xyz, 1.0)).xyz;
+        vec3 delta = clamp(center, aabbMin, aabbMax) - center;
+        if (dot(delta, delta) <= positionRadius.w * positionRadius.w) {
+            int bit = index - first;
+            mask[bit / 32] |= 1u << uint(bit % 32);
+        }
+    }
+
+    FragColor = uvec4(mask[0], mask[1], mask[2], mask[3]);
+}
@@ -4,6 +4,7 @@ uniform sampler2D normalTexture;
 uniform usampler2D decalMask;
 uniform mat4 invViewProj;
 uniform mat4 invWorldDecal;
+uniform vec3 decalAxis;
 uniform vec2 resolution;
 uniform vec4 color;
 uniform uint layerIndex;
@@ -49,6 +50,12 @@ void main()
     vec3 fragmentBinormal = dFdy(sceneWorldPosition);
     vec3 fragmentNormal = cross(fragmentTangent, fragmentBinormal);
 
+    // Fade out the decal on surfaces that are almost parallel to the projection axis (world-space
+    // Y axis of the decal), otherwise the texture is stretched over the sides of objects. The angle
+    // is measured in world space, because decal space is non-uniformly scaled and would make the
+    // fade depend on proportions of the decal.
+    outDiffuseMap.a *= smoothstep(0.2, 0.4, abs(dot(normalize(fragmentNormal), decalAxis)));
+
     mat3 tangentToWorld;
     tangentToWorld[0] = normalize(fragmentTangent); // Tangent
     tangentToWorld[1] = normalize(fragmentBinormal); // Binormal
@@ -56,5 +63,7 @@ void main()
 
     vec3 rawNormal = (texture(normalTexture, decalTexCoord) * 2.0 - 1.0).xyz;
     vec3 worldSpaceNormal = tangentToWorld * rawNormal;
+    // Normals are blended with the same (faded) alpha, so faded parts of the decal do not
+    // change lighting of the surface either.
     outNormalMap = vec4(worldSpaceNormal * 0.5 + 0.5, outDiffuseMap.a);
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+uniform sampler2D depthTexture;
+uniform sampler2D colorTexture;
+uniform sampler2D normalTexture;
+uniform sampler2D materialTexture;
+uniform sampler2D lightTexture;
+uniform highp usampler2D clusterTexture;
+
+uniform mat4 invViewProj;
+uniform mat4 viewProjection;
+uniform vec3 cameraPosition;
+uniform vec4 clusterParameters;
+
+in vec2 texCoord;
+out vec4 FragColor;
+
+void main()
+{
+    float depth = texture(depthTexture, texCoord).r;
+    // Skip background.
+    if (depth >= 1.0) {
+        discard;
+    }
+
+    vec3 material = texture(materialTexture, texCoord).rgb;
+
+    vec3 fragmentPosition = S_UnProject(vec3(texCoord, depth), invViewProj);
+
+    TPBRContext ctx;
+    ctx.albedo = texture(colorTexture, texCoord).rgb;
+    ctx.fragmentNormal = normalize(texture(normalTexture, texCoord).xyz * 2.0 - 1.0);
+    ctx.metallic = material.x;
+    ctx.roughness = material.y;
+    ctx.viewVector = normalize(cameraPosition - fragmentPosition);
+
+    vec3 lighting = S_ClusteredLighting(
+        ctx, fragmentPosition, viewProjection, clusterParameters, lightTexture, clusterTexture);
+
+    FragColor = vec4(lighting, 1.0);
+}
@@ -20,7 +20,7 @@ uniform sampler2D shadowCascade1;
 uniform sampler2D shadowCascade2;
 
 uniform bool shadowsEnabled;
-uniform float shadowBias;
+uniform float shadowBiases[NUM_CASCADES];
 uniform bool softShadows;
 uniform float shadowMapInvSize;
 
@@ -28,7 +28,7 @@ in vec2 texCoord;
 out vec4 FragColor;
 
 // Returns **inverted** shadow factor where 1 - fully bright, 0 - fully in shadow.
-float CsmGetShadow(in sampler2D sampler, in vec3 fragmentPosition, in mat4 lightViewProjMatrix)
+float CsmGetShadow(in sampler2D sampler, in float shadowBias, in vec3 fragmentPosition, in mat4 lightViewProjMatrix)
 {
     return S_SpotShadowFactor(shadowsEnabled, softShadows, shadowBias, fragmentPosition, lightViewProjMatrix, shadowMapInvSize, sampler);
 }
@@ -54,11 +54,11 @@ void main()
 
     float shadow = 1.0;
     if (fragmentZViewSpace <= cascadeDistances[0]) {
-        shadow = CsmGetShadow(shadowCascade0, fragmentPosition, lightViewProjMatrices[0]);
+        shadow = CsmGetShadow(shadowCascade0, shadowBiases[0], fragmentPosition, lightViewProjMatrices[0]);
     } else if (fragmentZViewSpace <= cascadeDistances[1]) {
-        shadow = CsmGetShadow(shadowCascade1, fragmentPosition, lightViewProjMatrices[1]);
+        shadow = CsmGetShadow(shadowCascade1, shadowBiases[1], fragmentPosition, lightViewProjMatrices[1]);
     } else if (fragmentZViewSpace <= cascadeDistances[2]) {
-        shadow = CsmGetShadow(shadowCascade2, fragmentPosition, lightViewProjMatrices[2]);
+        shadow = CsmGetShadow(shadowCascade2, shadowBiases[2], fragmentPosition, lightViewProjMatrices[2]);
     }
 
     FragColor = shadow * vec4(lightIntensity * lighting, 1.0);
@@ -8,6 +8,13 @@ uniform float minLuminance;
 uniform float maxLuminance;
 uniform bool autoExposure;
 uniform float fixedExposure;
+uniform float bloomIntensity;
+// Zero intensity disables vignette.
+uniform float vignetteIntensity;
+uniform float vignetteRadius;
+uniform float vignetteSmoothness;
+// Zero strength disables chromatic aberration.
+uniform float chromaticAberration;
 
 in vec2 texCoord;
 
@@ -22,10 +29,23 @@ vec3 ColorGrading(vec3 color) {
     return texture(colorMapSampler, scale * color + offset).rgb;
 }
 
+vec4 SampleHdrColor() {
+    if (chromaticAberration > 0.0) {
+        // Red and blue channels are shifted in opposite directions, the shift grows towards edges.
+        vec2 offset = (texCoord - 0.5) * 2.0 * chromaticAberration;
+        vec4 color = texture(hdrSampler, texCoord);
+        color.r = texture(hdrSampler, texCoord - offset).r;
+        color.b = texture(hdrSampler, texCoord + offset).b;
+        return color;
+    } else {
+        return texture(hdrSampler, texCoord);
+    }
+}
+
 void main() {
-    vec4 hdrColor = texture(hdrSampler, texCoord);
+    vec4 hdrColor = SampleHdrColor();
 
-    hdrColor += texture(bloomSampler, texCoord);
+    hdrColor += texture(bloomSampler, texCoord) * bloomIntensity;
 
     float luminance = texture(lumSampler, vec2(0.5, 0.5)).r;
 
@@ -38,6 +58,10 @@ void main() {
 
     vec4 ldrColor = vec4(vec3(1.0) - exp(-hdrColor.rgb * exposure), hdrColor.a);
 
+    float distanceToCenter = length(texCoord - 0.5);
+    float vignette = smoothstep(vignetteRadius, vignetteRadius + vignetteSmoothness, distanceToCenter);
+    ldrColor.rgb *= 1.0 - vignette * vignetteIntensity;
+
     if (useColorGrading) {
         outLdrColor = vec4(ColorGrading(S_LinearToSRGB(ldrColor).rgb), ldrColor.a);
     } else {
@@ -0,0 +1,106 @@
+// Screen space reflections. Reflected rays are marched in world space and projected on the screen
+// to find intersections with the depth buffer. Rays that leave the screen or do not hit anything
+// take reflected color from the environment cube map (if any).
+
+uniform sampler2D depthSampler;
+uniform sampler2D normalSampler;
+uniform sampler2D materialSampler;
+uniform sampler2D diffuseSampler;
+uniform sampler2D frameSampler;
+uniform samplerCube environmentSampler;
+
+uniform bool environmentEnabled;
+uniform mat4 invViewProj;
+uniform mat4 viewProj;
+uniform vec3 cameraPosition;
+uniform int maxSteps;
+uniform float maxDistance;
+uniform float thickness;
+uniform float roughnessCutoff;
+
+out vec4 FragColor;
+
+in vec2 texCoord;
+
+// Amount of binary search steps to refine an intersection.
+#define REFINE_STEPS 5
+
+bool IsOnScreen(vec3 screenPosition)
+{
+    return all(greaterThanEqual(screenPosition, vec3(0.0))) && all(lessThanEqual(screenPosition, vec3(1.0)));
+}
+
+// Returns positive value if the point is behind the depth buffer.
+float DepthDelta(vec3 screenPosition, vec3 worldPosition)
+{
+    vec3 scenePosition = S_UnProject(vec3(screenPosition.xy, texture(depthSampler, screenPosition.xy).r), invViewProj);
+    return distance(cameraPosition, worldPosition) - distance(cameraPosition, scenePosition);
+}
+
+void main()
+{
+    FragColor = vec4(0.0);
+
+    float depth = texture(depthSampler, texCoord).r;
+    vec3 material = texture(materialSampler, texCoord).rgb;
+    float metallic = material.x;
+    float roughness = material.y;
+
+    if (depth >= 1.0 || roughness >= roughnessCutoff) {
+        return;
+    }
+
+    vec3 position = S_UnProject(vec3(texCoord, depth), invViewProj);
+    vec3 normal = normalize(texture(normalSampler, texCoord).xyz * 2.0 - 1.0);
+    vec3 viewVector = normalize(position - cameraPosition);
+    vec3 reflectionVector = normalize(reflect(viewVector, normal));
+
+    vec3 reflection = environmentEnabled ? texture(environmentSampler, reflectionVector).rgb : vec3(0.0);
+
+    float stepLength = maxDistance / float(max(maxSteps, 1));
+    float previousT = 0.0;
+    for (int i = 1; i <= maxSteps; ++i) {
+        float t = stepLength * float(i);
+        vec3 screenPosition = S_Project(position + reflectionVector * t, viewProj);
+        if (!IsOnScreen(screenPosition)) {
+            break;
+        }
+
+        float delta = DepthDelta(screenPosition, position + reflectionVector * t);
+        if (delta > 0.0) {
+            if (delta < thickness) {
+                // Refine the intersection between the last two steps.
+                float minT = previousT;
+                float maxT = t;
+                for (int j = 0; j < REFINE_STEPS; ++j) {
+                    float midT = (minT + maxT) * 0.5;
+                    vec3 midScreenPosition = S_Project(position + reflectionVector * midT, viewProj);
+                    if (DepthDelta(midScreenPosition, position + reflectionVector * midT) > 0.0) {
+                        maxT = midT;
+                    } else {
+                        minT = midT;
+                    }
+                }
+
+                vec2 hitCoord = S_Project(position + reflectionVector * maxT, viewProj).xy;
+
+                // Fade reflections near the edges of the screen and at the end of the ray to hide the
+                // transition to the environment.
+                vec2 edgeFade = smoothstep(0.0, 0.1, hitCoord) * (1.0 - smoothstep(0.9, 1.0, hitCoord));
+                float fade = edgeFade.x * edgeFade.y * (1.0 - maxT / maxDistance);
+
+                reflection = mix(reflection, texture(frameSampler, hitCoord).rgb, fade);
+            }
+            break;
+        }
+
+        previousT = t;
+    }
+
+    vec3 albedo = texture(diffuseSampler, texCoord).rgb;
+    vec3 F0 = mix(vec3(0.04), albedo, metallic);
+    vec3 fresnel = S_FresnelSchlick(max(dot(normal, -viewVector), 0.0), F0);
+    float roughnessFade = 1.0 - smoothstep(0.0, roughnessCutoff, roughness);
+
+    FragColor = vec4(reflection * fresnel * roughnessFade, 1.0);
+}
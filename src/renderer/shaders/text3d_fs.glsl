@@ -0,0 +1,34 @@
+uniform sampler2D fontAtlas;
+uniform vec4 textColor;
+uniform vec4 outlineColor;
+uniform float outlineWidth;
+
+in vec2 texCoord;
+
+out vec4 FragColor;
+
+void main()
+{
+    // Edge of a glyph is at 0.5, values greater than that are inside of the glyph.
+    float distance = texture(fontAtlas, texCoord).r;
+    float smoothing = max(fwidth(distance), 0.0001);
+    float fill = smoothstep(0.5 - smoothing, 0.5 + smoothing, distance);
+
+    vec4 color;
+    if (outlineWidth > 0.0) {
+        // Outline grows outwards from the edge, its maximum width is limited by the spread of the field.
+        float outlineEdge = 0.5 - clamp(outlineWidth, 0.0, 1.0) * 0.45;
+        float outline = smoothstep(outlineEdge - smoothing, outlineEdge + smoothing, distance);
+        color = mix(outlineColor, textColor, fill);
+        color.a *= outline;
+    } else {
+        color = textColor;
+        color.a *= fill;
+    }
+
+    if (color.a < 0.005) {
+        discard;
+    }
+
+    FragColor = color;
+}
@@ -0,0 +1,18 @@
+layout(location = 0) in vec2 vertexPosition;
+layout(location = 1) in vec2 vertexTexCoord;
+
+uniform mat4 viewProjectionMatrix;
+uniform vec3 origin;
+uniform vec3 sideVector;
+uniform vec3 upVector;
+uniform vec2 offset;
+
+out vec2 texCoord;
+
+void main()
+{
+    texCoord = vertexTexCoord;
+    vec2 position = vertexPosition + offset;
+    vec3 worldPosition = origin + sideVector * position.x + upVector * position.y;
+    gl_Position = viewProjectionMatrix * vec4(worldPosition, 1.0);
+}
@@ -0,0 +1,79 @@
+// Upscales frames rendered at lower resolution and optionally applies contrast-adaptive
+// sharpening to them.
+
+uniform sampler2D frameTexture;
+uniform vec2 frameSize;
+// 0 - bilinear, 1 - Catmull-Rom. Must be in sync with UpscalingFilter.
+uniform int filterKind;
+// Sharpening strength in [0; 1] range, 0 - no sharpening.
+uniform float sharpening;
+
+out vec4 FragColor;
+
+in vec2 texCoord;
+
+// Catmull-Rom filter is sharper than bilinear filtering and produces less blurry results. It
+// uses 9 bilinear taps instead of 16 nearest ones by merging center weights.
+vec4 SampleCatmullRom(vec2 uv)
+{
+    vec2 samplePos = uv * frameSize;
+    vec2 texPos1 = floor(samplePos - 0.5) + 0.5;
+
+    vec2 f = samplePos - texPos1;
+
+    vec2 w0 = f * (-0.5 + f * (1.0 - 0.5 * f));
+    vec2 w1 = 1.0 + f * f * (-2.5 + 1.5 * f);
+    vec2 w2 = f * (0.5 + f * (2.0 - 1.5 * f));
+    vec2 w3 = f * f * (-0.5 + 0.5 * f);
+
+    vec2 w12 = w1 + w2;
+    vec2 offset12 = w2 / w12;
+
+    vec2 texPos0 = (texPos1 - 1.0) / frameSize;
+    vec2 texPos3 = (texPos1 + 2.0) / frameSize;
+    vec2 texPos12 = (texPos1 + offset12) / frameSize;
+
+    vec4 result = vec4(0.0);
+    result += texture(frameTexture, vec2(texPos0.x, texPos0.y)) * w0.x * w0.y;
+    result += texture(frameTexture, vec2(texPos12.x, texPos0.y)) * w12.x * w0.y;
+    result += texture(frameTexture, vec2(texPos3.x, texPos0.y)) * w3.x * w0.y;
+
+    result += texture(frameTexture, vec2(texPos0.x, texPos12.y)) * w0.x * w12.y;
+    result += texture(frameTexture, vec2(texPos12.x, texPos12.y)) * w12.x * w12.y;
+    result += texture(frameTexture, vec2(texPos3.x, texPos12.y)) * w3.x * w12.y;
+
+    result += texture(frameTexture, vec2(texPos0.x, texPos3.y)) * w0.x * w3.y;
+    result += texture(frameTexture, vec2(texPos12.x, texPos3.y)) * w12.x * w3.y;
+    result += texture(frameTexture, vec2(texPos3.x, texPos3.y)) * w3.x * w3.y;
+
+    // Catmull-Rom has negative lobes, so clamp to avoid dark halos.
+    return max(result, vec4(0.0));
+}
+
+void main()
+{
+    vec4 center = filterKind == 1 ? SampleCatmullRom(texCoord) : texture(frameTexture, texCoord);
+
+    if (sharpening > 0.0)
+    {
+        // Contrast-adaptive sharpening: sharpening is reduced in areas of high contrast to
+        // prevent ringing and over-sharpening of edges.
+        vec2 texelSize = 1.0 / frameSize;
+        vec3 n = texture(frameTexture, texCoord + vec2(0.0, -texelSize.y)).rgb;
+        vec3 s = texture(frameTexture, texCoord + vec2(0.0, texelSize.y)).rgb;
+        vec3 w = texture(frameTexture, texCoord + vec2(-texelSize.x, 0.0)).rgb;
+        vec3 e = texture(frameTexture, texCoord + vec2(texelSize.x, 0.0)).rgb;
+
+        vec3 minRgb = min(center.rgb, min(min(n, s), min(w, e)));
+        vec3 maxRgb = max(center.rgb, max(max(n, s), max(w, e)));
+
+        vec3 amplitude = sqrt(clamp(min(minRgb, 1.0 - maxRgb) / max(maxRgb, vec3(0.0001)), 0.0, 1.0));
+        vec3 weight = -amplitude / mix(8.0, 5.0, clamp(sharpening, 0.0, 1.0));
+
+        vec3 sharpened = (center.rgb + (n + s + w + e) * weight) / (1.0 + 4.0 * weight);
+
+        center.rgb = clamp(sharpened, 0.0, 1.0);
+    }
+
+    FragColor = center;
+}
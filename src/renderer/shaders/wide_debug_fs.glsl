@@ -0,0 +1,15 @@
+out vec4 FragColor;
+
+in vec4 color;
+in vec2 localPosition;
+in float isPoint;
+
+void main()
+{
+    // Points are round.
+    if (isPoint > 0.5 && dot(localPosition, localPosition) > 1.0) {
+        discard;
+    }
+
+    FragColor = color;
+}
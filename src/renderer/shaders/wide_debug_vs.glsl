@@ -0,0 +1,36 @@
+layout(location = 0) in vec3 vertexBegin;
+layout(location = 1) in vec3 vertexEnd;
+layout(location = 2) in vec4 vertexColor;
+// x - interpolation factor between begin and end, yz - corner offset, w - size in pixels.
+layout(location = 3) in vec4 vertexParams;
+layout(location = 4) in float vertexIsPoint;
+
+uniform mat4 worldViewProjection;
+uniform vec2 viewportSize;
+
+out vec4 color;
+out vec2 localPosition;
+out float isPoint;
+
+void main()
+{
+    vec4 clipBegin = worldViewProjection * vec4(vertexBegin, 1.0);
+    vec4 clipEnd = worldViewProjection * vec4(vertexEnd, 1.0);
+
+    // Direction of the line in screen space, points use screen axes.
+    vec2 screenBegin = clipBegin.xy / max(clipBegin.w, 0.0001) * viewportSize;
+    vec2 screenEnd = clipEnd.xy / max(clipEnd.w, 0.0001) * viewportSize;
+    vec2 delta = screenEnd - screenBegin;
+    vec2 direction = length(delta) > 0.0001 ? normalize(delta) : vec2(1.0, 0.0);
+    vec2 normal = vec2(-direction.y, direction.x);
+
+    vec2 pixelOffset = (direction * vertexParams.y + normal * vertexParams.z) * vertexParams.w * 0.5;
+
+    vec4 position = mix(clipBegin, clipEnd, vertexParams.x);
+    position.xy += pixelOffset * 2.0 / viewportSize * position.w;
+
+    color = vertexColor;
+    localPosition = vertexParams.yz;
+    isPoint = vertexIsPoint;
+    gl_Position = position;
+}
@@ -6,8 +6,12 @@ use crate::{
     },
     renderer::{
         apply_material,
-        batch::BatchStorage,
-        cache::{geometry::GeometryCache, shader::ShaderCache, texture::TextureCache},
+        batch::{BatchStorage, InstanceData},
+        cache::{
+            geometry::{GeometryCache, INSTANCE_BUFFER_INDEX},
+            shader::ShaderCache,
+            texture::TextureCache,
+        },
         framework::{
             error::FrameworkError,
             framebuffer::{Attachment, AttachmentKind, CullFace, DrawParameters, FrameBuffer},
@@ -22,8 +26,8 @@ use crate::{
     scene::{
         camera::Camera,
         graph::Graph,
-        light::directional::{DirectionalLight, FrustumSplitOptions, CSM_NUM_CASCADES},
-        mesh::Mesh,
+        light::directional::{DirectionalLight, CSM_NUM_CASCADES},
+        mesh::{instanced::InstancedMesh, Mesh},
         terrain::Terrain,
     },
 };
@@ -167,24 +171,24 @@ impl CsmRenderer {
             .try_normalize(f32::EPSILON)
             .unwrap_or_else(Vector3::z);
 
-        let z_values = match light.csm_options.split_options {
-            FrustumSplitOptions::Absolute { far_planes } => [
-                camera.projection().z_near(),
-                far_planes[0],
-                far_planes[1],
-                far_planes[2],
-            ],
-            FrustumSplitOptions::Relative { fractions } => [
-                camera.projection().z_near(),
-                camera.projection().z_far() * fractions[0],
-                camera.projection().z_far() * fractions[1],
-                camera.projection().z_far() * fractions[2],
-            ],
-        };
+        let z_near = camera.projection().z_near();
+        let far_planes = light
+            .csm_options
+            .cascade_far_planes(z_near, camera.projection().z_far());
+        let cascade_count = light.csm_options.cascade_count();
+        let stabilize = light.csm_options.is_stabilized();
+
+        let mut instance_data = Vec::new();
 
         for i in 0..CSM_NUM_CASCADES {
-            let znear = z_values[i];
-            let mut zfar = z_values[i + 1];
+            if i >= cascade_count {
+                // Unused cascades must not affect lighting.
+                self.cascades[i].z_far = 0.0;
+                continue;
+            }
+
+            let znear = if i == 0 { z_near } else { far_planes[i - 1] };
+            let mut zfar = far_planes[i];
 
             if zfar.eq(&znear) {
                 zfar += 10.0 * f32::EPSILON;
@@ -201,19 +205,59 @@ impl CsmRenderer {
                 Frustum::from(projection_matrix * camera.view_matrix()).unwrap_or_default();
 
             let center = frustum.center();
-            let light_view_matrix = Matrix4::look_at_lh(
-                &Point3::from(center + light_direction),
-                &Point3::from(center),
-                &light_up_vec,
-            );
 
-            let mut aabb = AxisAlignedBoundingBox::default();
-            for corner in frustum.corners() {
-                let light_space_corner = light_view_matrix
-                    .transform_point(&Point3::from(corner))
+            let (light_view_matrix, mut aabb) = if stabilize {
+                // Stabilized cascade is a bounding sphere of the sub-frustum, so its size does not
+                // depend on camera orientation. The view matrix is fixed and the sphere moves in
+                // texel-sized increments in light space, this removes shimmering of shadow edges.
+                let light_view_matrix = Matrix4::look_at_lh(
+                    &Point3::from(light_direction),
+                    &Point3::origin(),
+                    &light_up_vec,
+                );
+
+                let radius = frustum
+                    .corners()
+                    .iter()
+                    .map(|corner| (corner - center).norm())
+                    .fold(0.0f32, f32::max);
+                // Round the radius to prevent size changes caused by floating-point errors.
+                let radius = (radius * 16.0).ceil() / 16.0;
+
+                let texel_size = 2.0 * radius / self.size as f32;
+                let light_space_center = light_view_matrix
+                    .transform_point(&Point3::from(center))
                     .coords;
-                aabb.add_point(light_space_corner);
-            }
+                let snapped_center = Vector3::new(
+                    (light_space_center.x / texel_size).floor() * texel_size,
+                    (light_space_center.y / texel_size).floor() * texel_size,
+                    light_space_center.z,
+                );
+
+                (
+                    light_view_matrix,
+                    AxisAlignedBoundingBox::from_min_max(
+                        snapped_center - Vector3::repeat(radius),
+                        snapped_center + Vector3::repeat(radius),
+                    ),
+                )
+            } else {
+                let light_view_matrix = Matrix4::look_at_lh(
+                    &Point3::from(center + light_direction),
+                    &Point3::from(center),
+                    &light_up_vec,
+                );
+
+                let mut aabb = AxisAlignedBoundingBox::default();
+                for corner in frustum.corners() {
+                    let light_space_corner = light_view_matrix
+                        .transform_point(&Point3::from(corner))
+                        .coords;
+                    aabb.add_point(light_space_corner);
+                }
+
+                (light_view_matrix, aabb)
+            };
 
             // Make sure most of the objects outside of the frustum will cast shadows.
             let z_mult = 10.0;
@@ -240,6 +284,16 @@ impl CsmRenderer {
             let framebuffer = &mut self.cascades[i].frame_buffer;
             framebuffer.clear(state, viewport, None, Some(1.0), None);
 
+            let draw_params = DrawParameters {
+                cull_face: Some(CullFace::Back),
+                color_write: ColorMask::all(false),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: true,
+                blend: None,
+                stencil_op: Default::default(),
+            };
+
             for batch in batch_storage.batches.iter() {
                 let material = batch.material.lock();
                 let geometry = geom_cache.get(state, &batch.data);
@@ -248,11 +302,18 @@ impl CsmRenderer {
                     .get(state, material.shader())
                     .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
                 {
+                    let use_instancing =
+                        batch.use_instancing && render_pass.program.is_instancing_supported();
+
+                    instance_data.clear();
+
                     for instance in batch.instances.iter() {
                         let node = &graph[instance.owner];
 
                         let visible = if let Some(mesh) = node.cast::<Mesh>() {
                             mesh.global_visibility() && mesh.cast_shadows()
+                        } else if let Some(instanced_mesh) = node.cast::<InstancedMesh>() {
+                            instanced_mesh.global_visibility() && instanced_mesh.cast_shadows()
                         } else if let Some(terrain) = node.cast::<Terrain>() {
                             terrain.global_visibility() && terrain.cast_shadows()
                         } else {
@@ -263,32 +324,74 @@ impl CsmRenderer {
                             continue;
                         }
 
+                        if use_instancing && instance.is_instanceable() {
+                            instance_data.push(InstanceData {
+                                world_matrix: instance.world_transform,
+                                color: instance.color,
+                            });
+                            continue;
+                        }
+
                         stats += framebuffer.draw(
                             geometry,
                             state,
                             viewport,
                             &render_pass.program,
-                            &DrawParameters {
-                                cull_face: Some(CullFace::Back),
-                                color_write: ColorMask::all(false),
-                                depth_write: true,
-                                stencil_test: None,
-                                depth_test: true,
-                                blend: None,
-                                stencil_op: Default::default(),
-                            },
+                            &draw_params,
                             |mut program_binding| {
                                 apply_material(MaterialContext {
                                     material: &material,
+                                    property_block: instance.property_block.as_ref(),
                                     program_binding: &mut program_binding,
                                     texture_cache,
                                     world_matrix: &instance.world_transform,
                                     wvp_matrix: &(light_view_projection * instance.world_transform),
                                     bone_matrices: &instance.bone_matrices,
+                                    bone_matrices_offset: instance.bone_matrices_offset,
+                                    bone_matrices_texture: batch_storage.bone_matrices_texture(),
                                     use_skeletal_animation: batch.is_skinned,
                                     camera_position: &camera.global_position(),
                                     use_pom: false,
                                     light_position: &Default::default(),
+                                    opacity: instance.opacity,
+                                    clustered_lighting: None,
+                                    use_instancing: false,
+                                    normal_dummy: normal_dummy.clone(),
+                                    white_dummy: white_dummy.clone(),
+                                    black_dummy: black_dummy.clone(),
+                                });
+                            },
+                        );
+                    }
+
+                    if !instance_data.is_empty() {
+                        geometry.set_buffer_data(state, INSTANCE_BUFFER_INDEX, &instance_data);
+
+                        stats += framebuffer.draw_instances(
+                            instance_data.len(),
+                            geometry,
+                            state,
+                            viewport,
+                            &render_pass.program,
+                            &draw_params,
+                            |mut program_binding| {
+                                apply_material(MaterialContext {
+                                    material: &material,
+                                    property_block: None,
+                                    program_binding: &mut program_binding,
+                                    texture_cache,
+                                    world_matrix: &Matrix4::identity(),
+                                    wvp_matrix: &light_view_projection,
+                                    bone_matrices: &[],
+                                    bone_matrices_offset: 0,
+                                    bone_matrices_texture: None,
+                                    use_skeletal_animation: false,
+                                    camera_position: &camera.global_position(),
+                                    use_pom: false,
+                                    light_position: &Default::default(),
+                                    opacity: 1.0,
+                                    clustered_lighting: None,
+                                    use_instancing: true,
                                     normal_dummy: normal_dummy.clone(),
                                     white_dummy: white_dummy.clone(),
                                     black_dummy: black_dummy.clone(),
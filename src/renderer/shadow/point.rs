@@ -8,8 +8,8 @@ use crate::{
     },
     renderer::{
         apply_material,
-        batch::BatchStorage,
-        cache::{shader::ShaderCache, texture::TextureCache},
+        batch::{BatchStorage, InstanceData},
+        cache::{geometry::INSTANCE_BUFFER_INDEX, shader::ShaderCache, texture::TextureCache},
         framework::{
             error::FrameworkError,
             framebuffer::{Attachment, AttachmentKind, FrameBuffer},
@@ -210,6 +210,8 @@ impl PointShadowMapRenderer {
         let light_projection_matrix =
             Matrix4::new_perspective(1.0, std::f32::consts::FRAC_PI_2, 0.01, light_radius);
 
+        let mut instance_data = Vec::new();
+
         for face in self.faces.iter() {
             framebuffer.set_cubemap_face(state, 0, face.face).clear(
                 state,
@@ -237,8 +239,21 @@ impl PointShadowMapRenderer {
                     .get(state, material.shader())
                     .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
                 {
+                    let use_instancing =
+                        batch.use_instancing && render_pass.program.is_instancing_supported();
+
+                    instance_data.clear();
+
                     for instance in batch.instances.iter() {
                         if should_cast_shadows(instance, &frustum) {
+                            if use_instancing && instance.is_instanceable() {
+                                instance_data.push(InstanceData {
+                                    world_matrix: instance.world_transform,
+                                    color: instance.color,
+                                });
+                                continue;
+                            }
+
                             statistics += framebuffer.draw(
                                 geometry,
                                 state,
@@ -248,16 +263,23 @@ impl PointShadowMapRenderer {
                                 |mut program_binding| {
                                     apply_material(MaterialContext {
                                         material: &material,
+                                        property_block: instance.property_block.as_ref(),
                                         program_binding: &mut program_binding,
                                         texture_cache,
                                         world_matrix: &instance.world_transform,
                                         wvp_matrix: &(light_view_projection_matrix
                                             * instance.world_transform),
                                         bone_matrices: &instance.bone_matrices,
+                                        bone_matrices_offset: instance.bone_matrices_offset,
+                                        bone_matrices_texture: batch_storage
+                                            .bone_matrices_texture(),
                                         use_skeletal_animation: batch.is_skinned,
                                         camera_position: &Default::default(),
                                         use_pom: false,
                                         light_position: &light_pos,
+                                        opacity: instance.opacity,
+                                        clustered_lighting: None,
+                                        use_instancing: false,
                                         normal_dummy: normal_dummy.clone(),
                                         white_dummy: white_dummy.clone(),
                                         black_dummy: black_dummy.clone(),
@@ -266,6 +288,42 @@ impl PointShadowMapRenderer {
                             );
                         }
                     }
+
+                    if !instance_data.is_empty() {
+                        geometry.set_buffer_data(state, INSTANCE_BUFFER_INDEX, &instance_data);
+
+                        statistics += framebuffer.draw_instances(
+                            instance_data.len(),
+                            geometry,
+                            state,
+                            viewport,
+                            &render_pass.program,
+                            &render_pass.draw_params,
+                            |mut program_binding| {
+                                apply_material(MaterialContext {
+                                    material: &material,
+                                    property_block: None,
+                                    program_binding: &mut program_binding,
+                                    texture_cache,
+                                    world_matrix: &Matrix4::identity(),
+                                    wvp_matrix: &light_view_projection_matrix,
+                                    bone_matrices: &[],
+                                    bone_matrices_offset: 0,
+                                    bone_matrices_texture: None,
+                                    use_skeletal_animation: false,
+                                    camera_position: &Default::default(),
+                                    use_pom: false,
+                                    light_position: &light_pos,
+                                    opacity: 1.0,
+                                    clustered_lighting: None,
+                                    use_instancing: true,
+                                    normal_dummy: normal_dummy.clone(),
+                                    white_dummy: white_dummy.clone(),
+                                    black_dummy: black_dummy.clone(),
+                                });
+                            },
+                        );
+                    }
                 }
             }
         }
@@ -8,8 +8,8 @@ use crate::{
     },
     renderer::{
         apply_material,
-        batch::BatchStorage,
-        cache::{shader::ShaderCache, texture::TextureCache},
+        batch::{BatchStorage, InstanceData},
+        cache::{geometry::INSTANCE_BUFFER_INDEX, shader::ShaderCache, texture::TextureCache},
         framework::{
             error::FrameworkError,
             framebuffer::{Attachment, AttachmentKind, CullFace, DrawParameters, FrameBuffer},
@@ -140,6 +140,18 @@ impl SpotShadowMapRenderer {
         framebuffer.clear(state, viewport, None, Some(1.0), None);
         let frustum = Frustum::from(*light_view_projection).unwrap_or_default();
 
+        let draw_params = DrawParameters {
+            cull_face: Some(CullFace::Back),
+            color_write: ColorMask::all(false),
+            depth_write: true,
+            stencil_test: None,
+            depth_test: true,
+            blend: None,
+            stencil_op: Default::default(),
+        };
+
+        let mut instance_data = Vec::new();
+
         for batch in batches.batches.iter() {
             let material = batch.material.lock();
             let geometry = geom_cache.get(state, &batch.data);
@@ -148,34 +160,45 @@ impl SpotShadowMapRenderer {
                 .get(state, material.shader())
                 .and_then(|shader_set| shader_set.render_passes.get(&self.render_pass_name))
             {
+                let use_instancing =
+                    batch.use_instancing && render_pass.program.is_instancing_supported();
+
+                instance_data.clear();
+
                 for instance in batch.instances.iter() {
                     if should_cast_shadows(instance, &frustum) {
+                        if use_instancing && instance.is_instanceable() {
+                            instance_data.push(InstanceData {
+                                world_matrix: instance.world_transform,
+                                color: instance.color,
+                            });
+                            continue;
+                        }
+
                         statistics += framebuffer.draw(
                             geometry,
                             state,
                             viewport,
                             &render_pass.program,
-                            &DrawParameters {
-                                cull_face: Some(CullFace::Back),
-                                color_write: ColorMask::all(false),
-                                depth_write: true,
-                                stencil_test: None,
-                                depth_test: true,
-                                blend: None,
-                                stencil_op: Default::default(),
-                            },
+                            &draw_params,
                             |mut program_binding| {
                                 apply_material(MaterialContext {
                                     material: &material,
+                                    property_block: instance.property_block.as_ref(),
                                     program_binding: &mut program_binding,
                                     texture_cache,
                                     world_matrix: &instance.world_transform,
                                     wvp_matrix: &(light_view_projection * instance.world_transform),
                                     bone_matrices: &instance.bone_matrices,
+                                    bone_matrices_offset: instance.bone_matrices_offset,
+                                    bone_matrices_texture: batches.bone_matrices_texture(),
                                     use_skeletal_animation: batch.is_skinned,
                                     camera_position: &Default::default(),
                                     use_pom: false,
                                     light_position: &Default::default(),
+                                    opacity: instance.opacity,
+                                    clustered_lighting: None,
+                                    use_instancing: false,
                                     normal_dummy: normal_dummy.clone(),
                                     white_dummy: white_dummy.clone(),
                                     black_dummy: black_dummy.clone(),
@@ -184,6 +207,42 @@ impl SpotShadowMapRenderer {
                         );
                     }
                 }
+
+                if !instance_data.is_empty() {
+                    geometry.set_buffer_data(state, INSTANCE_BUFFER_INDEX, &instance_data);
+
+                    statistics += framebuffer.draw_instances(
+                        instance_data.len(),
+                        geometry,
+                        state,
+                        viewport,
+                        &render_pass.program,
+                        &draw_params,
+                        |mut program_binding| {
+                            apply_material(MaterialContext {
+                                material: &material,
+                                property_block: None,
+                                program_binding: &mut program_binding,
+                                texture_cache,
+                                world_matrix: &Matrix4::identity(),
+                                wvp_matrix: light_view_projection,
+                                bone_matrices: &[],
+                                bone_matrices_offset: 0,
+                                bone_matrices_texture: None,
+                                use_skeletal_animation: false,
+                                camera_position: &Default::default(),
+                                use_pom: false,
+                                light_position: &Default::default(),
+                                opacity: 1.0,
+                                clustered_lighting: None,
+                                use_instancing: true,
+                                normal_dummy: normal_dummy.clone(),
+                                white_dummy: white_dummy.clone(),
+                                black_dummy: black_dummy.clone(),
+                            });
+                        },
+                    );
+                }
             }
         }
 
@@ -0,0 +1,264 @@
+//! Screen space reflections. See [`crate::renderer::SsrSettings`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector3},
+        color::Color,
+        math::Rect,
+        scope_profile,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
+            geometry_buffer::{GeometryBuffer, GeometryBufferKind},
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter, PixelKind,
+            },
+            state::PipelineState,
+        },
+        gbuffer::GBuffer,
+        RenderPassStatistics, SsrSettings,
+    },
+    scene::mesh::surface::SurfaceData,
+};
+use std::{cell::RefCell, rc::Rc};
+
+struct Shader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    depth_sampler: UniformLocation,
+    normal_sampler: UniformLocation,
+    material_sampler: UniformLocation,
+    diffuse_sampler: UniformLocation,
+    frame_sampler: UniformLocation,
+    environment_sampler: UniformLocation,
+    environment_enabled: UniformLocation,
+    inv_view_proj_matrix: UniformLocation,
+    view_proj_matrix: UniformLocation,
+    camera_position: UniformLocation,
+    max_steps: UniformLocation,
+    max_distance: UniformLocation,
+    thickness: UniformLocation,
+    roughness_cutoff: UniformLocation,
+}
+
+impl Shader {
+    pub fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/ssr_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+        let program = GpuProgram::from_source(state, "SsrShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            depth_sampler: program
+                .uniform_location(state, &ImmutableString::new("depthSampler"))?,
+            normal_sampler: program
+                .uniform_location(state, &ImmutableString::new("normalSampler"))?,
+            material_sampler: program
+                .uniform_location(state, &ImmutableString::new("materialSampler"))?,
+            diffuse_sampler: program
+                .uniform_location(state, &ImmutableString::new("diffuseSampler"))?,
+            frame_sampler: program
+                .uniform_location(state, &ImmutableString::new("frameSampler"))?,
+            environment_sampler: program
+                .uniform_location(state, &ImmutableString::new("environmentSampler"))?,
+            environment_enabled: program
+                .uniform_location(state, &ImmutableString::new("environmentEnabled"))?,
+            inv_view_proj_matrix: program
+                .uniform_location(state, &ImmutableString::new("invViewProj"))?,
+            view_proj_matrix: program.uniform_location(state, &ImmutableString::new("viewProj"))?,
+            camera_position: program
+                .uniform_location(state, &ImmutableString::new("cameraPosition"))?,
+            max_steps: program.uniform_location(state, &ImmutableString::new("maxSteps"))?,
+            max_distance: program.uniform_location(state, &ImmutableString::new("maxDistance"))?,
+            thickness: program.uniform_location(state, &ImmutableString::new("thickness"))?,
+            roughness_cutoff: program
+                .uniform_location(state, &ImmutableString::new("roughnessCutoff"))?,
+            program,
+        })
+    }
+}
+
+pub(crate) struct SsrRenderContext<'a> {
+    pub state: &'a mut PipelineState,
+    pub gbuffer: &'a GBuffer,
+    pub frame_texture: Rc<RefCell<GpuTexture>>,
+    pub environment: Option<Rc<RefCell<GpuTexture>>>,
+    pub view_projection: Matrix4<f32>,
+    pub camera_position: Vector3<f32>,
+    pub settings: &'a SsrSettings,
+}
+
+pub struct ScreenSpaceReflectionsRenderer {
+    shader: Shader,
+    framebuffer: Option<FrameBuffer>,
+    quad: GeometryBuffer,
+    black_cube_map: Rc<RefCell<GpuTexture>>,
+    width: usize,
+    height: usize,
+}
+
+impl ScreenSpaceReflectionsRenderer {
+    pub fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: Shader::new(state)?,
+            framebuffer: None,
+            quad: GeometryBuffer::from_surface_data(
+                &SurfaceData::make_unit_xy_quad(),
+                GeometryBufferKind::StaticDraw,
+                state,
+            ),
+            black_cube_map: Rc::new(RefCell::new(GpuTexture::new(
+                state,
+                GpuTextureKind::Cube {
+                    width: 1,
+                    height: 1,
+                },
+                PixelKind::RGBA8,
+                MinificationFilter::Nearest,
+                MagnificationFilter::Nearest,
+                1,
+                Some(&[0u8; 6 * 4]),
+            )?)),
+            width: 0,
+            height: 0,
+        })
+    }
+
+    // Reflections are rendered in half resolution of the G-Buffer, the buffer is re-created when the
+    // G-Buffer changes its size.
+    fn ensure_framebuffer(
+        &mut self,
+        state: &mut PipelineState,
+        width: usize,
+        height: usize,
+    ) -> Result<(), FrameworkError> {
+        if self.framebuffer.is_none() || self.width != width || self.height != height {
+            let reflections = GpuTexture::new(
+                state,
+                GpuTextureKind::Rectangle { width, height },
+                PixelKind::RGBA16F,
+                MinificationFilter::Linear,
+                MagnificationFilter::Linear,
+                1,
+                None,
+            )?;
+
+            self.framebuffer = Some(FrameBuffer::new(
+                state,
+                None,
+                vec![Attachment {
+                    kind: AttachmentKind::Color,
+                    texture: Rc::new(RefCell::new(reflections)),
+                }],
+            )?);
+            self.width = width;
+            self.height = height;
+        }
+
+        Ok(())
+    }
+
+    /// Returns a texture with reflections of the last rendered frame. The texture must be added on
+    /// top of lit frame.
+    pub fn reflections(&self) -> Option<Rc<RefCell<GpuTexture>>> {
+        self.framebuffer
+            .as_ref()
+            .map(|framebuffer| framebuffer.color_attachments()[0].texture.clone())
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        args: SsrRenderContext,
+    ) -> Result<RenderPassStatistics, FrameworkError> {
+        scope_profile!();
+
+        let SsrRenderContext {
+            state,
+            gbuffer,
+            frame_texture,
+            environment,
+            view_projection,
+            camera_position,
+            settings,
+        } = args;
+
+        let width = (gbuffer.width as usize / 2).max(1);
+        let height = (gbuffer.height as usize / 2).max(1);
+        let viewport = Rect::new(0, 0, width as i32, height as i32);
+
+        let frame_matrix = Matrix4::new_orthographic(
+            0.0,
+            viewport.w() as f32,
+            viewport.h() as f32,
+            0.0,
+            -1.0,
+            1.0,
+        ) * Matrix4::new_nonuniform_scaling(&Vector3::new(
+            viewport.w() as f32,
+            viewport.h() as f32,
+            0.0,
+        ));
+
+        let (environment_enabled, environment) = match environment {
+            Some(environment) => (true, environment),
+            None => (false, self.black_cube_map.clone()),
+        };
+        let inv_view_projection = view_projection.try_inverse().unwrap_or_default();
+
+        self.ensure_framebuffer(state, width, height)?;
+
+        let shader = &self.shader;
+        let quad = &self.quad;
+        let framebuffer = self.framebuffer.as_mut().unwrap();
+
+        framebuffer.clear(
+            state,
+            viewport,
+            Some(Color::from_rgba(0, 0, 0, 0)),
+            None,
+            None,
+        );
+
+        let mut statistics = RenderPassStatistics::default();
+
+        statistics += framebuffer.draw(
+            quad,
+            state,
+            viewport,
+            &shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: false,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&shader.wvp_matrix, &frame_matrix)
+                    .set_texture(&shader.depth_sampler, &gbuffer.depth())
+                    .set_texture(&shader.normal_sampler, &gbuffer.normal_texture())
+                    .set_texture(&shader.material_sampler, &gbuffer.material_texture())
+                    .set_texture(&shader.diffuse_sampler, &gbuffer.diffuse_texture())
+                    .set_texture(&shader.frame_sampler, &frame_texture)
+                    .set_texture(&shader.environment_sampler, &environment)
+                    .set_bool(&shader.environment_enabled, environment_enabled)
+                    .set_matrix4(&shader.inv_view_proj_matrix, &inv_view_projection)
+                    .set_matrix4(&shader.view_proj_matrix, &view_projection)
+                    .set_vector3(&shader.camera_position, &camera_position)
+                    .set_i32(&shader.max_steps, settings.max_steps as i32)
+                    .set_f32(&shader.max_distance, settings.max_distance)
+                    .set_f32(&shader.thickness, settings.thickness)
+                    .set_f32(&shader.roughness_cutoff, settings.roughness_cutoff);
+            },
+        );
+
+        Ok(statistics)
+    }
+}
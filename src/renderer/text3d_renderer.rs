@@ -0,0 +1,664 @@
+//! Renderer of 3D text (see [`crate::scene::text3d::Text3D`]).
+//!
+//! Glyphs of every font that is used by at least one text node are converted to signed distance
+//! fields (SDF) once and packed into a single atlas per font. Distance to the edge of a glyph is
+//! stored in a texel, which allows to render sharp text of any size and to make outlines and drop
+//! shadows without any additional textures.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2},
+        color::Color,
+        math::{Matrix4Ext, Rect, TriangleDefinition},
+        parking_lot::Mutex,
+        rectpack::RectPacker,
+        scope_profile,
+        sstorage::ImmutableString,
+    },
+    gui::ttf::{Font, FontGlyph, SharedFont},
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{BlendParameters, DrawParameters, FrameBuffer},
+            geometry_buffer::{
+                AttributeDefinition, AttributeKind, BufferBuilder, ElementKind, GeometryBuffer,
+                GeometryBufferBuilder, GeometryBufferKind,
+            },
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::{
+                Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+                PixelKind, WrapMode,
+            },
+            state::{BlendFactor, BlendFunc, PipelineState},
+        },
+        RenderPassStatistics,
+    },
+    scene::{camera::Camera, graph::Graph, text3d::Text3D},
+};
+use fxhash::FxHashMap;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Weak},
+};
+
+/// Amount of pixels around each glyph that is used to store distance to its edge.
+const SDF_SPREAD: usize = 6;
+
+struct Text3DShader {
+    program: GpuProgram,
+    view_projection_matrix: UniformLocation,
+    origin: UniformLocation,
+    side_vector: UniformLocation,
+    up_vector: UniformLocation,
+    offset: UniformLocation,
+    font_atlas: UniformLocation,
+    text_color: UniformLocation,
+    outline_color: UniformLocation,
+    outline_width: UniformLocation,
+}
+
+impl Text3DShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/text3d_fs.glsl");
+        let vertex_source = include_str!("shaders/text3d_vs.glsl");
+        let program =
+            GpuProgram::from_source(state, "Text3DShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            view_projection_matrix: program
+                .uniform_location(state, &ImmutableString::new("viewProjectionMatrix"))?,
+            origin: program.uniform_location(state, &ImmutableString::new("origin"))?,
+            side_vector: program.uniform_location(state, &ImmutableString::new("sideVector"))?,
+            up_vector: program.uniform_location(state, &ImmutableString::new("upVector"))?,
+            offset: program.uniform_location(state, &ImmutableString::new("offset"))?,
+            font_atlas: program.uniform_location(state, &ImmutableString::new("fontAtlas"))?,
+            text_color: program.uniform_location(state, &ImmutableString::new("textColor"))?,
+            outline_color: program
+                .uniform_location(state, &ImmutableString::new("outlineColor"))?,
+            outline_width: program
+                .uniform_location(state, &ImmutableString::new("outlineWidth"))?,
+            program,
+        })
+    }
+}
+
+#[repr(C)]
+struct TextVertex {
+    // Position in the plane of the text, in world units.
+    position: Vector2<f32>,
+    tex_coord: Vector2<f32>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct SdfGlyph {
+    // Normalized texture coordinates of the top-left corner and the size of the glyph in the atlas.
+    tex_coords: Rect<f32>,
+    // Metrics of the glyph (including spread), in pixels of the source font.
+    left: f32,
+    bottom: f32,
+    width: f32,
+    height: f32,
+    advance: f32,
+}
+
+struct SdfFontAtlas {
+    // Glyphs in the same order as in the source font.
+    glyphs: Vec<SdfGlyph>,
+    pixels: Vec<u8>,
+    size: usize,
+}
+
+/// Calculates distance (in pixels) from every pixel of the grid to the closest "inside" pixel, using
+/// two-pass 8-points signed sequential Euclidean distance transform (8SSEDT).
+fn distance_transform(inside: &[bool], width: usize, height: usize) -> Vec<f32> {
+    const FAR: i32 = 1 << 14;
+
+    let mut grid = inside
+        .iter()
+        .map(|inside| if *inside { (0, 0) } else { (FAR, FAR) })
+        .collect::<Vec<(i32, i32)>>();
+
+    let w = width as i32;
+    let h = height as i32;
+
+    let compare = |grid: &mut Vec<(i32, i32)>, x: i32, y: i32, ox: i32, oy: i32| {
+        let (nx, ny) = (x + ox, y + oy);
+        if nx < 0 || ny < 0 || nx >= w || ny >= h {
+            return;
+        }
+        let (dx, dy) = grid[(ny * w + nx) as usize];
+        let other = (dx + ox, dy + oy);
+        let current = &mut grid[(y * w + x) as usize];
+        if other.0 * other.0 + other.1 * other.1 < current.0 * current.0 + current.1 * current.1 {
+            *current = other;
+        }
+    };
+
+    for y in 0..h {
+        for x in 0..w {
+            compare(&mut grid, x, y, -1, 0);
+            compare(&mut grid, x, y, 0, -1);
+            compare(&mut grid, x, y, -1, -1);
+            compare(&mut grid, x, y, 1, -1);
+        }
+        for x in (0..w).rev() {
+            compare(&mut grid, x, y, 1, 0);
+        }
+    }
+
+    for y in (0..h).rev() {
+        for x in (0..w).rev() {
+            compare(&mut grid, x, y, 1, 0);
+            compare(&mut grid, x, y, 0, 1);
+            compare(&mut grid, x, y, -1, 1);
+            compare(&mut grid, x, y, 1, 1);
+        }
+        for x in 0..w {
+            compare(&mut grid, x, y, -1, 0);
+        }
+    }
+
+    grid.into_iter()
+        .map(|(dx, dy)| ((dx * dx + dy * dy) as f32).sqrt())
+        .collect()
+}
+
+/// Generates signed distance field for the given coverage bitmap. Resulting field is larger than
+/// the source bitmap by `spread` pixels on each side. Edge of the glyph is mapped to 128, values
+/// greater than that are inside of the glyph.
+fn generate_sdf(pixels: &[u8], width: usize, height: usize, spread: usize) -> Vec<u8> {
+    let out_width = width + 2 * spread;
+    let out_height = height + 2 * spread;
+
+    let mut inside = vec![false; out_width * out_height];
+    for y in 0..height {
+        for x in 0..width {
+            inside[(y + spread) * out_width + x + spread] = pixels[y * width + x] >= 128;
+        }
+    }
+    let outside = inside.iter().map(|inside| !inside).collect::<Vec<_>>();
+
+    let distance_to_inside = distance_transform(&inside, out_width, out_height);
+    let distance_to_outside = distance_transform(&outside, out_width, out_height);
+
+    distance_to_outside
+        .iter()
+        .zip(distance_to_inside.iter())
+        .map(|(to_outside, to_inside)| {
+            let signed_distance = to_outside - to_inside;
+            let normalized = 0.5 + signed_distance / (2.0 * spread as f32);
+            (normalized.clamp(0.0, 1.0) * 255.0) as u8
+        })
+        .collect()
+}
+
+impl SdfFontAtlas {
+    fn new(font: &Font) -> Self {
+        let border = 2;
+
+        let sdf_glyphs = font
+            .glyphs()
+            .iter()
+            .map(|glyph| {
+                let sdf = if glyph.bitmap_width > 0 && glyph.bitmap_height > 0 {
+                    generate_sdf(
+                        &glyph.pixels,
+                        glyph.bitmap_width,
+                        glyph.bitmap_height,
+                        SDF_SPREAD,
+                    )
+                } else {
+                    Vec::new()
+                };
+                (glyph, sdf)
+            })
+            .collect::<Vec<_>>();
+
+        let area = sdf_glyphs
+            .iter()
+            .map(|(glyph, _)| {
+                (glyph.bitmap_width + 2 * SDF_SPREAD + border)
+                    * (glyph.bitmap_height + 2 * SDF_SPREAD + border)
+            })
+            .sum::<usize>();
+        let mut size = ((1.3 * (area as f32).sqrt()) as usize)
+            .next_power_of_two()
+            .max(64);
+
+        loop {
+            if let Some(atlas) = Self::pack(&sdf_glyphs, size, border) {
+                return atlas;
+            }
+            size *= 2;
+        }
+    }
+
+    fn pack(sdf_glyphs: &[(&FontGlyph, Vec<u8>)], size: usize, border: usize) -> Option<Self> {
+        let mut packer = RectPacker::new(size, size);
+        let mut pixels = vec![0; size * size];
+        let mut glyphs = Vec::with_capacity(sdf_glyphs.len());
+
+        for (glyph, sdf) in sdf_glyphs {
+            let mut sdf_glyph = SdfGlyph {
+                advance: glyph.advance,
+                ..Default::default()
+            };
+
+            if !sdf.is_empty() {
+                let width = glyph.bitmap_width + 2 * SDF_SPREAD;
+                let height = glyph.bitmap_height + 2 * SDF_SPREAD;
+
+                let bounds = packer.find_free(width + border, height + border)?;
+
+                for y in 0..height {
+                    let src = y * width;
+                    let dest = (bounds.y() + y) * size + bounds.x();
+                    pixels[dest..(dest + width)].copy_from_slice(&sdf[src..(src + width)]);
+                }
+
+                let k = 1.0 / size as f32;
+                sdf_glyph.tex_coords = Rect::new(
+                    bounds.x() as f32 * k,
+                    bounds.y() as f32 * k,
+                    width as f32 * k,
+                    height as f32 * k,
+                );
+                sdf_glyph.left = glyph.left - SDF_SPREAD as f32;
+                sdf_glyph.bottom = glyph.top - SDF_SPREAD as f32;
+                sdf_glyph.width = width as f32;
+                sdf_glyph.height = height as f32;
+            }
+
+            glyphs.push(sdf_glyph);
+        }
+
+        Some(Self {
+            glyphs,
+            pixels,
+            size,
+        })
+    }
+
+    /// Generates quads for every visible glyph of the text. The text block is centered around the
+    /// origin, `line_height` defines height of a line in world units.
+    fn layout(
+        &self,
+        font: &Font,
+        text: &str,
+        line_height: f32,
+        vertices: &mut Vec<TextVertex>,
+        triangles: &mut Vec<TriangleDefinition>,
+    ) {
+        if font.height() <= 0.0 {
+            return;
+        }
+
+        let scale = line_height / font.height();
+        let line_count = text.lines().count();
+        let block_height = line_count as f32 * font.height();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line_width = line
+                .chars()
+                .map(|c| {
+                    font.glyph_index(c as u32)
+                        .map_or(0.0, |index| self.glyphs[index].advance)
+                })
+                .sum::<f32>();
+
+            let baseline = 0.5 * block_height - line_index as f32 * font.height() - font.ascender();
+            let mut pen_x = -0.5 * line_width;
+
+            for c in line.chars() {
+                let glyph = match font.glyph_index(c as u32) {
+                    Some(index) => &self.glyphs[index],
+                    None => continue,
+                };
+
+                if glyph.width > 0.0 && glyph.height > 0.0 {
+                    let x0 = (pen_x + glyph.left) * scale;
+                    let y0 = (baseline + glyph.bottom) * scale;
+                    let x1 = x0 + glyph.width * scale;
+                    let y1 = y0 + glyph.height * scale;
+
+                    let tc = glyph.tex_coords;
+                    let (u0, v0) = (tc.x(), tc.y());
+                    let (u1, v1) = (tc.x() + tc.w(), tc.y() + tc.h());
+
+                    // Rows of the atlas go from top to bottom.
+                    let first = vertices.len() as u32;
+                    vertices.push(TextVertex {
+                        position: Vector2::new(x0, y0),
+                        tex_coord: Vector2::new(u0, v1),
+                    });
+                    vertices.push(TextVertex {
+                        position: Vector2::new(x1, y0),
+                        tex_coord: Vector2::new(u1, v1),
+                    });
+                    vertices.push(TextVertex {
+                        position: Vector2::new(x1, y1),
+                        tex_coord: Vector2::new(u1, v0),
+                    });
+                    vertices.push(TextVertex {
+                        position: Vector2::new(x0, y1),
+                        tex_coord: Vector2::new(u0, v0),
+                    });
+                    triangles.push(TriangleDefinition([first, first + 1, first + 2]));
+                    triangles.push(TriangleDefinition([first + 2, first + 3, first]));
+                }
+
+                pen_x += glyph.advance;
+            }
+        }
+    }
+}
+
+struct CachedAtlas {
+    font: Weak<Mutex<Font>>,
+    revision: u64,
+    atlas: SdfFontAtlas,
+    texture: Rc<RefCell<GpuTexture>>,
+}
+
+pub(crate) struct Text3DRenderContext<'a, 'b, 'c> {
+    pub state: &'a mut PipelineState,
+    pub framebuffer: &'b mut FrameBuffer,
+    pub graph: &'c Graph,
+    pub camera: &'c Camera,
+    pub viewport: Rect<i32>,
+}
+
+pub(crate) struct Text3DRenderer {
+    shader: Text3DShader,
+    geometry: GeometryBuffer,
+    atlases: FxHashMap<usize, CachedAtlas>,
+    vertices: Vec<TextVertex>,
+    triangles: Vec<TriangleDefinition>,
+}
+
+pub(crate) fn font_key(font: &SharedFont) -> usize {
+    Arc::as_ptr(&font.0) as usize
+}
+
+impl Text3DRenderer {
+    pub fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let geometry = GeometryBufferBuilder::new(ElementKind::Triangle)
+            .with_buffer_builder(
+                BufferBuilder::new::<TextVertex>(GeometryBufferKind::DynamicDraw, None)
+                    .with_attribute(AttributeDefinition {
+                        location: 0,
+                        divisor: 0,
+                        kind: AttributeKind::Float2,
+                        normalized: false,
+                    })
+                    .with_attribute(AttributeDefinition {
+                        location: 1,
+                        divisor: 0,
+                        kind: AttributeKind::Float2,
+                        normalized: false,
+                    }),
+            )
+            .build(state)?;
+
+        Ok(Self {
+            shader: Text3DShader::new(state)?,
+            geometry,
+            atlases: Default::default(),
+            vertices: Default::default(),
+            triangles: Default::default(),
+        })
+    }
+
+    fn atlas(
+        &mut self,
+        state: &mut PipelineState,
+        font: &SharedFont,
+    ) -> Result<&CachedAtlas, FrameworkError> {
+        let key = font_key(font);
+
+        let font_ref = font.0.lock();
+
+        // The same address could be reused by another font, if previous one was destroyed. Also
+        // the atlas must be re-created if new glyphs were added to the font.
+        if self.atlases.get(&key).map_or(false, |entry| {
+            entry.font.upgrade().is_none() || entry.revision != font_ref.atlas_revision()
+        }) {
+            self.atlases.remove(&key);
+        }
+
+        if let std::collections::hash_map::Entry::Vacant(entry) = self.atlases.entry(key) {
+            let atlas = SdfFontAtlas::new(&font_ref);
+
+            let mut texture = GpuTexture::new(
+                state,
+                GpuTextureKind::Rectangle {
+                    width: atlas.size,
+                    height: atlas.size,
+                },
+                PixelKind::R8,
+                MinificationFilter::Linear,
+                MagnificationFilter::Linear,
+                1,
+                Some(&atlas.pixels),
+            )?;
+            texture
+                .bind_mut(state, 0)
+                .set_wrap(Coordinate::S, WrapMode::ClampToEdge)
+                .set_wrap(Coordinate::T, WrapMode::ClampToEdge);
+
+            entry.insert(CachedAtlas {
+                font: Arc::downgrade(&font.0),
+                revision: font_ref.atlas_revision(),
+                atlas,
+                texture: Rc::new(RefCell::new(texture)),
+            });
+        }
+
+        Ok(&self.atlases[&key])
+    }
+
+    /// Builds the atlas of the font and uploads it to GPU, if it wasn't done yet.
+    pub(crate) fn prepare_atlas(
+        &mut self,
+        state: &mut PipelineState,
+        font: &SharedFont,
+    ) -> Result<(), FrameworkError> {
+        self.atlas(state, font).map(|_| ())
+    }
+
+    #[must_use]
+    pub(crate) fn render(&mut self, args: Text3DRenderContext) -> RenderPassStatistics {
+        scope_profile!();
+
+        let mut statistics = RenderPassStatistics::default();
+
+        let Text3DRenderContext {
+            state,
+            framebuffer,
+            graph,
+            camera,
+            viewport,
+        } = args;
+
+        // Atlases of destroyed fonts are not needed anymore.
+        self.atlases
+            .retain(|_, entry| entry.font.upgrade().is_some());
+
+        let view_projection = camera.view_projection_matrix();
+        let inv_view = camera.inv_view_matrix().unwrap_or_else(Matrix4::identity);
+        let camera_side = inv_view.side();
+        let camera_up = inv_view.up();
+
+        for text in graph.linear_iter().filter_map(|node| {
+            if !node.global_visibility() {
+                return None;
+            }
+
+            node.cast::<Text3D>()
+        }) {
+            if text.text().is_empty() {
+                continue;
+            }
+
+            {
+                // Make sure that every glyph of the text is loaded.
+                let mut font = text.font().0.lock();
+                for c in text.text().chars() {
+                    font.glyph_index_or_load(c as u32);
+                }
+            }
+
+            let mut vertices = std::mem::take(&mut self.vertices);
+            let mut triangles = std::mem::take(&mut self.triangles);
+            vertices.clear();
+            triangles.clear();
+
+            let texture = match self.atlas(state, text.font()) {
+                Ok(cached) => {
+                    cached.atlas.layout(
+                        &text.font().0.lock(),
+                        text.text(),
+                        text.font_size(),
+                        &mut vertices,
+                        &mut triangles,
+                    );
+                    cached.texture.clone()
+                }
+                Err(_) => continue,
+            };
+
+            if !triangles.is_empty() {
+                self.geometry.set_buffer_data(state, 0, &vertices);
+                self.geometry.bind(state).set_triangles(&triangles);
+
+                let global_transform = text.global_transform();
+                let origin = text.global_position();
+                let (side, up) = if text.is_billboard() {
+                    (camera_side, camera_up)
+                } else {
+                    // Local X axis goes to the left in right-handed coordinate system, text is
+                    // readable when looking along local Z axis.
+                    (-global_transform.side(), global_transform.up())
+                };
+
+                let draw_params = DrawParameters {
+                    cull_face: None,
+                    color_write: Default::default(),
+                    depth_write: false,
+                    stencil_test: None,
+                    depth_test: !text.is_draw_on_top(),
+                    blend: Some(BlendParameters {
+                        func: BlendFunc::new(BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha),
+                        ..Default::default()
+                    }),
+                    stencil_op: Default::default(),
+                };
+
+                let mut draw = |color: Color, outline_color: Color, offset: Vector2<f32>| {
+                    framebuffer.draw(
+                        &self.geometry,
+                        state,
+                        viewport,
+                        &self.shader.program,
+                        &draw_params,
+                        |mut program_binding| {
+                            program_binding
+                                .set_matrix4(&self.shader.view_projection_matrix, &view_projection)
+                                .set_vector3(&self.shader.origin, &origin)
+                                .set_vector3(&self.shader.side_vector, &side)
+                                .set_vector3(&self.shader.up_vector, &up)
+                                .set_vector2(&self.shader.offset, &offset)
+                                .set_texture(&self.shader.font_atlas, &texture)
+                                .set_srgb_color(&self.shader.text_color, &color)
+                                .set_srgb_color(&self.shader.outline_color, &outline_color)
+                                .set_f32(&self.shader.outline_width, text.outline_width());
+                        },
+                    )
+                };
+
+                if text.is_shadow_enabled() {
+                    statistics += draw(
+                        text.shadow_color(),
+                        text.shadow_color(),
+                        text.shadow_offset(),
+                    );
+                }
+
+                statistics += draw(text.color(), text.outline_color(), Vector2::default());
+            }
+
+            self.vertices = vertices;
+            self.triangles = triangles;
+        }
+
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::math::TriangleDefinition,
+        gui::ttf::FontBuilder,
+        renderer::text3d_renderer::{generate_sdf, SdfFontAtlas, SDF_SPREAD},
+    };
+
+    #[test]
+    fn test_sdf_generation() {
+        // 4x4 filled square in the center of 8x8 bitmap.
+        let mut pixels = vec![0u8; 64];
+        for y in 2..6 {
+            for x in 2..6 {
+                pixels[y * 8 + x] = 255;
+            }
+        }
+
+        let spread = 2;
+        let sdf = generate_sdf(&pixels, 8, 8, spread);
+        let width = 8 + 2 * spread;
+        assert_eq!(sdf.len(), width * width);
+
+        let at = |x: usize, y: usize| sdf[(y + spread) * width + x + spread];
+
+        // Center of the square is inside, far corners are outside.
+        assert!(at(3, 3) > 128);
+        assert!(at(0, 0) < 128);
+        // Distance grows monotonically towards the center.
+        assert!(at(1, 3) < at(2, 3));
+        assert!(at(2, 3) < at(3, 3));
+        // Padding pixels are far outside.
+        assert_eq!(sdf[0], 0);
+    }
+
+    #[test]
+    fn test_text_layout() {
+        let font = FontBuilder::new()
+            .with_height(32.0)
+            .build_builtin()
+            .unwrap();
+        let atlas = SdfFontAtlas::new(&font);
+
+        assert_eq!(atlas.glyphs.len(), font.glyphs().len());
+        assert!(atlas.size.is_power_of_two());
+
+        let mut vertices = Vec::new();
+        let mut triangles: Vec<TriangleDefinition> = Vec::new();
+        atlas.layout(&font, "AB C\nD", 1.0, &mut vertices, &mut triangles);
+
+        // Space does not produce any geometry.
+        assert_eq!(vertices.len(), 4 * 4);
+        assert_eq!(triangles.len(), 4 * 2);
+
+        // The text block is centered around the origin.
+        let (min, max) = vertices.iter().fold((f32::MAX, f32::MIN), |(min, max), v| {
+            (min.min(v.position.x), max.max(v.position.x))
+        });
+        let spread = SDF_SPREAD as f32 / 32.0;
+        assert!((min + max).abs() < 2.0 * spread);
+        for v in vertices.iter() {
+            assert!(v.position.y.abs() < 1.0 + spread);
+            assert!((0.0..=1.0).contains(&v.tex_coord.x));
+            assert!((0.0..=1.0).contains(&v.tex_coord.y));
+        }
+    }
+}
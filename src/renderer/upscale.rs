@@ -0,0 +1,115 @@
+use crate::{
+    core::{
+        algebra::{Matrix4, Vector2, Vector3},
+        math::Rect,
+        sstorage::ImmutableString,
+    },
+    renderer::{
+        framework::{
+            error::FrameworkError,
+            framebuffer::{DrawParameters, FrameBuffer},
+            geometry_buffer::GeometryBuffer,
+            gpu_program::{GpuProgram, UniformLocation},
+            gpu_texture::GpuTexture,
+            state::PipelineState,
+        },
+        RenderPassStatistics, UpscalingFilter,
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+struct UpscaleShader {
+    program: GpuProgram,
+    wvp_matrix: UniformLocation,
+    frame_texture: UniformLocation,
+    frame_size: UniformLocation,
+    filter_kind: UniformLocation,
+    sharpening: UniformLocation,
+}
+
+impl UpscaleShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/upscale_fs.glsl");
+        let vertex_source = include_str!("shaders/flat_vs.glsl");
+
+        let program =
+            GpuProgram::from_source(state, "UpscaleShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            wvp_matrix: program
+                .uniform_location(state, &ImmutableString::new("worldViewProjection"))?,
+            frame_texture: program
+                .uniform_location(state, &ImmutableString::new("frameTexture"))?,
+            frame_size: program.uniform_location(state, &ImmutableString::new("frameSize"))?,
+            filter_kind: program.uniform_location(state, &ImmutableString::new("filterKind"))?,
+            sharpening: program.uniform_location(state, &ImmutableString::new("sharpening"))?,
+            program,
+        })
+    }
+}
+
+/// Upscales frames rendered at lower resolution to the native resolution and applies optional
+/// sharpening.
+pub struct UpscaleRenderer {
+    shader: UpscaleShader,
+}
+
+impl UpscaleRenderer {
+    pub fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        Ok(Self {
+            shader: UpscaleShader::new(state)?,
+        })
+    }
+
+    pub(crate) fn render(
+        &self,
+        state: &mut PipelineState,
+        quad: &GeometryBuffer,
+        viewport: Rect<i32>,
+        frame_texture: Rc<RefCell<GpuTexture>>,
+        frame_size: Vector2<f32>,
+        frame_buffer: &mut FrameBuffer,
+        filter: UpscalingFilter,
+        sharpening: f32,
+    ) -> RenderPassStatistics {
+        let mut statistics = RenderPassStatistics::default();
+
+        let frame_matrix = Matrix4::new_orthographic(
+            0.0,
+            viewport.w() as f32,
+            viewport.h() as f32,
+            0.0,
+            -1.0,
+            1.0,
+        ) * Matrix4::new_nonuniform_scaling(&Vector3::new(
+            viewport.w() as f32,
+            viewport.h() as f32,
+            0.0,
+        ));
+
+        statistics += frame_buffer.draw(
+            quad,
+            state,
+            viewport,
+            &self.shader.program,
+            &DrawParameters {
+                cull_face: None,
+                color_write: Default::default(),
+                depth_write: true,
+                stencil_test: None,
+                depth_test: false,
+                blend: None,
+                stencil_op: Default::default(),
+            },
+            |mut program_binding| {
+                program_binding
+                    .set_matrix4(&self.shader.wvp_matrix, &frame_matrix)
+                    .set_vector2(&self.shader.frame_size, &frame_size)
+                    .set_i32(&self.shader.filter_kind, filter as i32)
+                    .set_f32(&self.shader.sharpening, sharpening)
+                    .set_texture(&self.shader.frame_texture, &frame_texture);
+            },
+        );
+
+        statistics
+    }
+}
@@ -0,0 +1,284 @@
+//! Warm-up stage allows to prepare all GPU resources of a scene (shader programs, pipeline states,
+//! textures and font atlases) at loading time, instead of doing that lazily on first use. Lazy preparation
+//! causes noticeable hitches during gameplay, because shader compilation could take tens of
+//! milliseconds. See [`super::Renderer::warm_up_scene`] for more info.
+
+use crate::{
+    gui::ttf::SharedFont,
+    material::{shader::Shader, PropertyValue, SharedMaterial},
+    renderer::text3d_renderer::font_key,
+    resource::texture::Texture,
+    scene::{
+        decal::Decal,
+        dim2::rectangle::Rectangle,
+        graph::Graph,
+        mesh::{instanced::InstancedMesh, Mesh},
+        particle_system::ParticleSystem,
+        rope::Rope,
+        sprite::Sprite,
+        terrain::Terrain,
+        text3d::Text3D,
+    },
+};
+use fxhash::FxHashSet;
+
+/// Current stage of the warm-up process.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WarmUpStage {
+    /// Shader programs are compiled and pipeline states are prepared.
+    Shaders,
+    /// Textures are uploaded to GPU.
+    Textures,
+    /// Font atlases of 3D texts are built and uploaded to GPU.
+    Fonts,
+}
+
+/// Progress of the warm-up process, it is passed to a progress callback after each processed
+/// resource.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WarmUpProgress {
+    /// Current stage.
+    pub stage: WarmUpStage,
+    /// Amount of processed resources (across all stages).
+    pub processed: usize,
+    /// Total amount of resources to process.
+    pub total: usize,
+}
+
+impl WarmUpProgress {
+    /// Returns progress in `[0; 1]` range.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.processed as f32 / self.total as f32
+        }
+    }
+}
+
+/// Results of the warm-up process.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WarmUpStatistics {
+    /// Amount of shaders that were prepared.
+    pub shaders: usize,
+    /// Amount of GPU programs (one per render pass of each shader) that were prepared.
+    pub programs: usize,
+    /// Amount of textures that were uploaded to GPU.
+    pub textures: usize,
+    /// Amount of font atlases that were uploaded to GPU.
+    pub fonts: usize,
+    /// Amount of resources that were skipped, because they are not loaded yet or failed to load.
+    pub skipped: usize,
+}
+
+/// A set of unique resources referenced by a scene.
+#[derive(Default)]
+pub struct WarmUpResources {
+    /// Unique shaders.
+    pub shaders: Vec<Shader>,
+    /// Unique textures.
+    pub textures: Vec<Texture>,
+    /// Unique fonts of 3D texts.
+    pub fonts: Vec<SharedFont>,
+    shader_keys: FxHashSet<usize>,
+    texture_keys: FxHashSet<usize>,
+    font_keys: FxHashSet<usize>,
+}
+
+impl WarmUpResources {
+    /// Collects every shader, texture and font that is used by renderable nodes of the graph
+    /// (meshes, terrains, particle systems, sprites, decals, ropes, rectangles and 3D texts).
+    /// Glyphs of 3D texts are loaded here, so their font atlases won't be re-created on first use.
+    pub fn from_graph(graph: &Graph) -> Self {
+        let mut resources = Self::default();
+
+        for node in graph.linear_iter() {
+            if let Some(mesh) = node.cast::<Mesh>() {
+                for surface in mesh.surfaces() {
+                    resources.add_material(surface.material());
+                }
+            } else if let Some(instanced_mesh) = node.cast::<InstancedMesh>() {
+                for surface in instanced_mesh.surfaces() {
+                    resources.add_material(surface.material());
+                }
+            } else if let Some(terrain) = node.cast::<Terrain>() {
+                for layer in terrain.layers() {
+                    resources.add_material(&layer.material);
+                }
+            } else if let Some(rope) = node.cast::<Rope>() {
+                resources.add_material(rope.material());
+            } else if let Some(particle_system) = node.cast::<ParticleSystem>() {
+                resources.add_texture(particle_system.texture_ref());
+            } else if let Some(sprite) = node.cast::<Sprite>() {
+                resources.add_texture(sprite.texture_ref());
+            } else if let Some(decal) = node.cast::<Decal>() {
+                resources.add_texture(decal.diffuse_texture());
+                resources.add_texture(decal.normal_texture());
+            } else if let Some(rectangle) = node.cast::<Rectangle>() {
+                resources.add_texture(rectangle.texture());
+            } else if let Some(text) = node.cast::<Text3D>() {
+                {
+                    let mut font = text.font().0.lock();
+                    for c in text.text().chars() {
+                        font.glyph_index_or_load(c as u32);
+                    }
+                }
+                resources.add_font(text.font());
+            }
+        }
+
+        resources
+    }
+
+    /// Adds the texture, if any.
+    pub fn add_texture(&mut self, texture: Option<&Texture>) {
+        if let Some(texture) = texture {
+            if self.texture_keys.insert(texture.key()) {
+                self.textures.push(texture.clone());
+            }
+        }
+    }
+
+    /// Adds the font.
+    pub fn add_font(&mut self, font: &SharedFont) {
+        if self.font_keys.insert(font_key(font)) {
+            self.fonts.push(font.clone());
+        }
+    }
+
+    /// Adds the shader and the textures of the material.
+    pub fn add_material(&mut self, material: &SharedMaterial) {
+        let material = material.lock();
+
+        let shader = material.shader();
+        if self.shader_keys.insert(shader.key()) {
+            self.shaders.push(shader.clone());
+        }
+
+        for value in material.properties().values() {
+            if let PropertyValue::Sampler {
+                value: Some(texture),
+                ..
+            } = value
+            {
+                self.add_texture(Some(texture));
+            }
+        }
+    }
+
+    /// Returns total amount of resources.
+    pub fn len(&self) -> usize {
+        self.shaders.len() + self.textures.len() + self.fonts.len()
+    }
+
+    /// Returns `true` if there is no resources.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Matrix4, sstorage::ImmutableString},
+        material::{Material, PropertyValue, SharedMaterial},
+        renderer::warmup::{WarmUpProgress, WarmUpResources, WarmUpStage},
+        resource::texture::{Texture, TextureKind, TexturePixelKind},
+        scene::{
+            base::BaseBuilder,
+            decal::DecalBuilder,
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            sprite::SpriteBuilder,
+            text3d::Text3DBuilder,
+        },
+    };
+
+    fn make_textured_material(texture: &Texture) -> SharedMaterial {
+        let mut material = Material::standard();
+        material
+            .set_property(
+                &ImmutableString::new("diffuseTexture"),
+                PropertyValue::Sampler {
+                    value: Some(texture.clone()),
+                    fallback: Default::default(),
+                },
+            )
+            .unwrap();
+        SharedMaterial::new(material)
+    }
+
+    fn make_texture() -> Texture {
+        Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: 1,
+                height: 1,
+            },
+            TexturePixelKind::RGBA8,
+            vec![255; 4],
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_warm_up_resources_are_unique() {
+        let texture = make_texture();
+
+        let mut graph = Graph::new();
+
+        for _ in 0..3 {
+            let data = SurfaceSharedData::new(SurfaceData::make_quad(&Matrix4::identity()));
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(data)
+                    .with_material(make_textured_material(&texture))
+                    .build()])
+                .build(&mut graph);
+        }
+
+        let resources = WarmUpResources::from_graph(&graph);
+        assert_eq!(resources.shaders.len(), 1);
+        assert_eq!(resources.textures.len(), 1);
+        assert_eq!(resources.len(), 2);
+
+        let progress = WarmUpProgress {
+            stage: WarmUpStage::Textures,
+            processed: 1,
+            total: resources.len(),
+        };
+        assert_eq!(progress.fraction(), 0.5);
+    }
+
+    #[test]
+    fn test_warm_up_resources_of_other_nodes() {
+        let texture = make_texture();
+        let other_texture = make_texture();
+
+        let mut graph = Graph::new();
+
+        SpriteBuilder::new(BaseBuilder::new())
+            .with_texture(texture.clone())
+            .build(&mut graph);
+        DecalBuilder::new(BaseBuilder::new())
+            .with_diffuse_texture(texture)
+            .build(&mut graph);
+        DecalBuilder::new(BaseBuilder::new())
+            .with_diffuse_texture(other_texture)
+            .build(&mut graph);
+        Text3DBuilder::new(BaseBuilder::new())
+            .with_text("foo")
+            .build(&mut graph);
+        Text3DBuilder::new(BaseBuilder::new())
+            .with_text("bar")
+            .build(&mut graph);
+
+        let resources = WarmUpResources::from_graph(&graph);
+        assert_eq!(resources.shaders.len(), 0);
+        assert_eq!(resources.textures.len(), 2);
+        assert_eq!(resources.fonts.len(), 1);
+        assert_eq!(resources.len(), 3);
+    }
+}
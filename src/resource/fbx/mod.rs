@@ -724,6 +724,10 @@ async fn convert(
 
     // Do not create animation player if there's no animation content.
     if !animation.tracks().is_empty() {
+        if let Some(tolerance) = model_import_options.animation_compression_tolerance {
+            animation.compress(tolerance.0);
+        }
+
         let mut animations_container = AnimationContainer::new();
         animations_container.add(animation);
         AnimationPlayerBuilder::new(BaseBuilder::new().with_name("AnimationPlayer"))
@@ -5,4 +5,7 @@
 pub mod curve;
 pub mod fbx;
 pub mod model;
+pub mod script_source;
+pub mod sound_event;
 pub mod texture;
+pub mod visual_script;
@@ -352,6 +352,27 @@ impl MaterialSearchOptions {
     }
 }
 
+/// Tolerance of animation compression, see [`crate::animation::compression`] docs for more info.
+/// Tolerances are compared by their bit patterns, which allows [`ModelImportOptions`] to implement
+/// `Eq`. It is serialized as a plain number.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Reflect)]
+#[serde(transparent)]
+pub struct AnimationCompressionTolerance(pub f32);
+
+impl PartialEq for AnimationCompressionTolerance {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for AnimationCompressionTolerance {}
+
+impl From<f32> for AnimationCompressionTolerance {
+    fn from(tolerance: f32) -> Self {
+        Self(tolerance)
+    }
+}
+
 /// A set of options that will be applied to a model resource when loading it from external source.
 ///
 /// # Details
@@ -362,7 +383,8 @@ impl MaterialSearchOptions {
 ///
 /// ```text
 /// (
-///     material_search_options: RecursiveUp
+///     material_search_options: RecursiveUp,
+///     animation_compression_tolerance: Some(0.001),
 /// )
 /// ```
 ///
@@ -372,6 +394,12 @@ pub struct ModelImportOptions {
     /// See [`MaterialSearchOptions`] docs for more info.
     #[serde(default)]
     pub material_search_options: MaterialSearchOptions,
+
+    /// Tolerance of animation compression. If set, imported animations will be compressed: keys that could be
+    /// restored with an error less than the tolerance will be removed, and the rest will be quantized. See
+    /// [`crate::animation::compression`] docs for more info. Default is `None` (no compression).
+    #[serde(default)]
+    pub animation_compression_tolerance: Option<AnimationCompressionTolerance>,
 }
 
 impl ImportOptions for ModelImportOptions {}
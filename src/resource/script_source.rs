@@ -0,0 +1,141 @@
+//! Script source resource holds source code of a script written in a scripting language (for example,
+//! Lua, see [`crate::script::lua`]). The resource is loaded through the resource manager, so it is
+//! hot-reloaded when the file changes (if the resource manager has a watcher).
+
+use crate::{
+    asset::{define_new_resource, Resource, ResourceData, ResourceState},
+    core::{io::FileLoadError, reflect::prelude::*, visitor::prelude::*},
+    engine::resource_manager::options::ImportOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// An error that may occur during script source resource loading.
+#[derive(Debug)]
+pub enum ScriptSourceError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+
+    /// The file is not a valid UTF-8 text.
+    Encoding(FromUtf8Error),
+}
+
+impl Display for ScriptSourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptSourceError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            ScriptSourceError::Encoding(v) => {
+                write!(f, "The file is not a valid UTF-8 text. {v}")
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for ScriptSourceError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<FromUtf8Error> for ScriptSourceError {
+    fn from(e: FromUtf8Error) -> Self {
+        Self::Encoding(e)
+    }
+}
+
+static REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// State of the [`ScriptSource`].
+#[derive(Debug, Visit, Default)]
+pub struct ScriptSourceState {
+    pub(crate) path: PathBuf,
+    /// Source code of the script.
+    #[visit(skip)]
+    pub source: String,
+    #[visit(skip)]
+    revision: u64,
+}
+
+impl ResourceData for ScriptSourceState {
+    fn path(&self) -> Cow<Path> {
+        Cow::Borrowed(&self.path)
+    }
+
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
+impl ScriptSourceState {
+    /// Creates a new script source with the given source code.
+    pub fn new<S: Into<String>>(source: S) -> Self {
+        Self {
+            path: Default::default(),
+            source: source.into(),
+            revision: REVISION.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Load a script source from the specific file path.
+    pub async fn from_file(path: &Path) -> Result<Self, ScriptSourceError> {
+        let data = crate::core::io::load_file(path).await?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            ..Self::new(String::from_utf8(data)?)
+        })
+    }
+
+    /// Returns a unique number of this instance of the source. Every load (or reload) of a script source
+    /// produces a new number, so it could be used to check whether the source was changed.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+define_new_resource!(
+    /// See module docs.
+    #[derive(Reflect)]
+    #[reflect(hide_all)]
+    ScriptSource<ScriptSourceState, ScriptSourceError>
+);
+
+impl ScriptSource {
+    /// Creates a new, embedded, script source with the given source code.
+    pub fn new<S: Into<String>>(source: S) -> Self {
+        Self(Resource::new(ResourceState::Ok(ScriptSourceState::new(
+            source,
+        ))))
+    }
+}
+
+/// Import options for script source resource.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct ScriptSourceImportOptions {}
+
+impl ImportOptions for ScriptSourceImportOptions {}
+
+#[cfg(test)]
+mod test {
+    use crate::{asset::ResourceState, resource::script_source::ScriptSource};
+
+    #[test]
+    fn test_script_source_revision() {
+        let a = ScriptSource::new("print('a')");
+        let b = ScriptSource::new("print('b')");
+
+        let revision = |source: &ScriptSource| match *source.state() {
+            ResourceState::Ok(ref state) => state.revision(),
+            _ => unreachable!(),
+        };
+
+        assert_ne!(revision(&a), revision(&b));
+    }
+}
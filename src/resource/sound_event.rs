@@ -0,0 +1,390 @@
+//! Sound event resource holds a [`SoundEvent`] - a set of sound buffers with rules of how to pick
+//! and play them. It is useful for sounds that must not sound the same every time they're played,
+//! for example footsteps, impacts, gun shots, etc.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fyrox::{
+//!     core::algebra::Vector3,
+//!     engine::resource_manager::ResourceManager,
+//!     resource::sound_event::{SoundEvent, SoundEventResource, SoundEventSelection},
+//!     scene::Scene,
+//! };
+//!
+//! fn add_footsteps(scene: &mut Scene, resource_manager: &ResourceManager) {
+//!     let footsteps = SoundEventResource::new(SoundEvent {
+//!         buffers: vec![
+//!             Some(resource_manager.request_sound_buffer("data/sounds/step1.ogg")),
+//!             Some(resource_manager.request_sound_buffer("data/sounds/step2.ogg")),
+//!             Some(resource_manager.request_sound_buffer("data/sounds/step3.ogg")),
+//!         ],
+//!         selection: SoundEventSelection::Random,
+//!         pitch: 0.9..1.1,
+//!         gain: 0.8..1.0,
+//!         cooldown: 0.25,
+//!         ..Default::default()
+//!     });
+//!
+//!     scene.sound_events.add("Footsteps", footsteps);
+//! }
+//!
+//! // Somewhere in a script:
+//! // ctx.scene.play_sound_event("Footsteps", position, ctx.elapsed_time, ctx.rng);
+//! ```
+
+use crate::{
+    asset::{define_new_resource, Resource, ResourceData, ResourceState},
+    core::{
+        algebra::Vector3, io::FileLoadError, pool::Handle, reflect::prelude::*, visitor::prelude::*,
+    },
+    engine::resource_manager::options::ImportOptions,
+    rand::Rng,
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        node::Node,
+        sound::{SoundBufferResource, SoundBuilder, Status},
+        transform::TransformBuilder,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// An error that may occur during sound event resource loading.
+#[derive(Debug)]
+pub enum SoundEventResourceError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+}
+
+impl Display for SoundEventResourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SoundEventResourceError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            SoundEventResourceError::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for SoundEventResourceError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for SoundEventResourceError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+/// Defines how a sound event picks a buffer to play.
+#[derive(
+    Copy, Clone, Debug, Reflect, Visit, PartialEq, Eq, Hash, AsRefStr, EnumString, EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum SoundEventSelection {
+    /// A random buffer is picked every time, the same buffer is never picked twice in a row (unless
+    /// there is only one buffer).
+    Random = 0,
+    /// Buffers are picked one after another in the order they're defined, starting over when the
+    /// last one was played.
+    Sequence = 1,
+}
+
+impl Default for SoundEventSelection {
+    fn default() -> Self {
+        Self::Random
+    }
+}
+
+/// A set of sound buffers with rules of how to pick and play them. See module docs for more info.
+#[derive(Clone, Debug, Visit, Reflect)]
+pub struct SoundEvent {
+    /// A set of buffers to pick from. Empty entries are ignored.
+    pub buffers: Vec<Option<SoundBufferResource>>,
+    /// Defines how a buffer is picked.
+    pub selection: SoundEventSelection,
+    /// A range of pitch, a random value from the range is used every time the event is played.
+    pub pitch: Range<f32>,
+    /// A range of gain, a random value from the range is used every time the event is played.
+    pub gain: Range<f32>,
+    /// Minimal amount of time (in seconds) between two plays of the event. Attempts to play the
+    /// event more often are ignored.
+    pub cooldown: f32,
+    /// Radius of sound sources created by the event. See [`crate::scene::sound::Sound::set_radius`]
+    /// for more info.
+    pub radius: f32,
+    /// Spatial blend factor of sound sources created by the event. See
+    /// [`crate::scene::sound::Sound::set_spatial_blend`] for more info.
+    pub spatial_blend: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_play_time: Option<f32>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    last_index: Option<usize>,
+}
+
+impl Default for SoundEvent {
+    fn default() -> Self {
+        Self {
+            buffers: Default::default(),
+            selection: Default::default(),
+            pitch: 1.0..1.0,
+            gain: 1.0..1.0,
+            cooldown: 0.0,
+            radius: 10.0,
+            spatial_blend: 1.0,
+            last_play_time: None,
+            last_index: None,
+        }
+    }
+}
+
+fn sample_range<R: Rng + ?Sized>(range: &Range<f32>, rng: &mut R) -> f32 {
+    rng.gen_range(range.start.min(range.end)..=range.start.max(range.end))
+}
+
+impl SoundEvent {
+    /// Returns `true` if the event was played less than [`Self::cooldown`] seconds ago. `time` is
+    /// current time in seconds (for example [`crate::script::ScriptContext::elapsed_time`]).
+    pub fn is_cooling_down(&self, time: f32) -> bool {
+        self.last_play_time.map_or(false, |last_play_time| {
+            time - last_play_time < self.cooldown
+        })
+    }
+
+    /// Picks next buffer according to [`Self::selection`]. Returns `None` if there are no buffers.
+    pub fn select_buffer<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<SoundBufferResource> {
+        let indices = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.as_ref().map(|_| i))
+            .collect::<Vec<_>>();
+
+        let index = match self.selection {
+            SoundEventSelection::Random => {
+                let candidates = if indices.len() > 1 {
+                    indices
+                        .iter()
+                        .cloned()
+                        .filter(|i| Some(*i) != self.last_index)
+                        .collect()
+                } else {
+                    indices
+                };
+                if candidates.is_empty() {
+                    return None;
+                }
+                candidates[rng.gen_range(0..candidates.len())]
+            }
+            SoundEventSelection::Sequence => *indices
+                .iter()
+                .find(|i| self.last_index.map_or(true, |last| **i > last))
+                .or_else(|| indices.first())?,
+        };
+
+        self.last_index = Some(index);
+
+        self.buffers[index].clone()
+    }
+
+    /// Plays the event at the given position by adding a play-once [`crate::scene::sound::Sound`]
+    /// node to the graph, the node is removed automatically when it stops playing. `time` is current
+    /// time in seconds (for example [`crate::script::ScriptContext::elapsed_time`]), it is used to
+    /// track cooldowns. Returns a handle of the new node or [`Handle::NONE`] if the event is cooling
+    /// down or there is nothing to play.
+    pub fn play<R: Rng + ?Sized>(
+        &mut self,
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        time: f32,
+        rng: &mut R,
+    ) -> Handle<Node> {
+        if self.is_cooling_down(time) {
+            return Handle::NONE;
+        }
+
+        let buffer = match self.select_buffer(rng) {
+            Some(buffer) => buffer,
+            None => return Handle::NONE,
+        };
+
+        self.last_play_time = Some(time);
+
+        SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_buffer(Some(buffer))
+        .with_play_once(true)
+        .with_status(Status::Playing)
+        .with_pitch(sample_range(&self.pitch, rng) as f64)
+        .with_gain(sample_range(&self.gain, rng))
+        .with_radius(self.radius)
+        .with_spatial_blend_factor(self.spatial_blend)
+        .build(graph)
+    }
+}
+
+/// State of the [`SoundEventResource`]
+#[derive(Debug, Visit, Default)]
+pub struct SoundEventResourceState {
+    pub(crate) path: PathBuf,
+    /// Actual sound event.
+    pub event: SoundEvent,
+}
+
+impl ResourceData for SoundEventResourceState {
+    fn path(&self) -> Cow<Path> {
+        Cow::Borrowed(&self.path)
+    }
+
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
+impl SoundEventResourceState {
+    /// Load a sound event resource from the specific file path.
+    pub async fn from_file(path: &Path) -> Result<Self, SoundEventResourceError> {
+        let mut visitor = Visitor::load_binary(path).await?;
+        let mut event = SoundEvent::default();
+        event.visit("SoundEvent", &mut visitor)?;
+        Ok(Self {
+            event,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Saves the sound event to the specific file path.
+    pub fn save(&mut self, path: &Path) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.event.visit("SoundEvent", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+}
+
+define_new_resource!(
+    /// See module docs.
+    #[derive(Reflect)]
+    #[reflect(hide_all)]
+    SoundEventResource<SoundEventResourceState, SoundEventResourceError>
+);
+
+impl SoundEventResource {
+    /// Creates a new sound event resource, that is not backed by a file.
+    pub fn new(event: SoundEvent) -> Self {
+        Self(Resource::new(ResourceState::Ok(SoundEventResourceState {
+            path: Default::default(),
+            event,
+        })))
+    }
+}
+
+/// Import options for sound event resource.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SoundEventImportOptions {}
+
+impl ImportOptions for SoundEventImportOptions {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, pool::Handle},
+        rand::{rngs::StdRng, SeedableRng},
+        resource::sound_event::{SoundEvent, SoundEventSelection},
+        scene::{
+            graph::Graph,
+            sound::{DataSource, Sound, SoundBufferResource},
+        },
+    };
+
+    fn buffer() -> Option<SoundBufferResource> {
+        SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.0; 441],
+        })
+        .ok()
+    }
+
+    fn event(selection: SoundEventSelection) -> SoundEvent {
+        SoundEvent {
+            buffers: vec![buffer(), None, buffer(), buffer()],
+            selection,
+            pitch: 0.5..1.5,
+            cooldown: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sound_event_selection() {
+        let mut rng = StdRng::seed_from_u64(123);
+
+        let mut sequence = event(SoundEventSelection::Sequence);
+        let mut indices = Vec::new();
+        for _ in 0..4 {
+            sequence.select_buffer(&mut rng);
+            indices.push(sequence.last_index.unwrap());
+        }
+        assert_eq!(indices, [0, 2, 3, 0]);
+
+        let mut random = event(SoundEventSelection::Random);
+        let mut last = None;
+        for _ in 0..32 {
+            assert!(random.select_buffer(&mut rng).is_some());
+            let index = random.last_index;
+            assert_ne!(index, Some(1));
+            assert_ne!(index, last);
+            last = index;
+        }
+
+        assert!(SoundEvent::default().select_buffer(&mut rng).is_none());
+    }
+
+    #[test]
+    fn test_sound_event_play() {
+        let mut rng = StdRng::seed_from_u64(123);
+        let mut graph = Graph::new();
+        let mut event = event(SoundEventSelection::Random);
+
+        let position = Vector3::new(1.0, 2.0, 3.0);
+        let sound = event.play(&mut graph, position, 0.0, &mut rng);
+        assert!(sound.is_some());
+        let sound_ref = graph[sound].cast::<Sound>().unwrap();
+        assert!(sound_ref.is_play_once());
+        assert!((0.5..=1.5).contains(&sound_ref.pitch()));
+        assert_eq!(**graph[sound].local_transform().position(), position);
+
+        // Cooldown.
+        assert_eq!(
+            event.play(&mut graph, position, 0.5, &mut rng),
+            Handle::NONE
+        );
+        assert!(event.play(&mut graph, position, 1.0, &mut rng).is_some());
+    }
+}
@@ -32,7 +32,11 @@ use crate::{
 };
 use ddsfile::{Caps2, D3DFormat};
 use fxhash::FxHasher;
-use image::{imageops::FilterType, ColorType, DynamicImage, ImageError, ImageFormat};
+use image::{
+    imageops::{self, FilterType},
+    ColorType, DynamicImage, GenericImageView, GrayImage, ImageError, ImageFormat, Luma, Rgb,
+    RgbImage,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::{
@@ -200,6 +204,7 @@ pub struct TextureData {
     serialize_content: bool,
     data_hash: u64,
     is_render_target: bool,
+    is_normal_map: bool,
 }
 
 impl ResourceData for TextureData {
@@ -236,6 +241,7 @@ impl Visit for TextureData {
         let _ = self
             .serialize_content
             .visit("SerializeContent", &mut region);
+        let _ = self.is_normal_map.visit("IsNormalMap", &mut region);
 
         if self.serialize_content {
             let mut bytes_view = PodVecView::from_pod_vec(&mut self.bytes);
@@ -268,6 +274,7 @@ impl Default for TextureData {
             serialize_content: false,
             data_hash: 0,
             is_render_target: false,
+            is_normal_map: false,
         }
     }
 }
@@ -287,6 +294,23 @@ impl Default for TextureData {
 ///     t_wrap_mode: ClampToEdge,
 ///     anisotropy: 8.0,
 ///     compression: NoCompression,    
+///     normal_map: Auto,
+///     flip_normal_map_y: false,
+/// )
+/// ```
+///
+/// A texture could also be built from a set of other textures, see [`ChannelPackingOptions`]. In this
+/// case the settings file describes the sources of the texture and the file of the texture itself is
+/// not required to exist:
+///
+/// ```text
+/// (
+///     channel_packing: Some((
+///         template: OcclusionRoughnessMetallic,
+///         metallic: Some("rock_metallic.png"),
+///         roughness: Some("rock_roughness.png"),
+///         ambient_occlusion: Some("rock_ao.png"),
+///     )),
 /// )
 /// ```
 #[derive(Clone, Deserialize, Serialize, Debug, Reflect)]
@@ -303,6 +327,13 @@ pub struct TextureImportOptions {
     pub(crate) anisotropy: f32,
     #[serde(default)]
     pub(crate) compression: CompressionOptions,
+    #[serde(default)]
+    pub(crate) normal_map: NormalMapMode,
+    #[serde(default)]
+    pub(crate) flip_normal_map_y: bool,
+    #[serde(default)]
+    #[reflect(hidden)]
+    pub(crate) channel_packing: Option<ChannelPackingOptions>,
 }
 
 impl Default for TextureImportOptions {
@@ -314,6 +345,9 @@ impl Default for TextureImportOptions {
             t_wrap_mode: TextureWrapMode::Repeat,
             anisotropy: 16.0,
             compression: CompressionOptions::default(),
+            normal_map: NormalMapMode::default(),
+            flip_normal_map_y: false,
+            channel_packing: None,
         }
     }
 }
@@ -402,6 +436,261 @@ impl TextureImportOptions {
     pub fn set_compression(&mut self, compression: CompressionOptions) {
         self.compression = compression;
     }
+
+    /// Sets a mode, that defines whether imported textures are normal maps.
+    pub fn with_normal_map(mut self, normal_map: NormalMapMode) -> Self {
+        self.normal_map = normal_map;
+        self
+    }
+
+    /// Sets a mode, that defines whether imported textures are normal maps.
+    pub fn set_normal_map(&mut self, normal_map: NormalMapMode) {
+        self.normal_map = normal_map;
+    }
+
+    /// Sets whether the green (Y) channel of normal maps should be inverted on import. It is needed to
+    /// use normal maps made for DirectX-style (Y-down) engines, Fyrox uses OpenGL-style (Y-up) normal maps.
+    pub fn with_flip_normal_map_y(mut self, flip: bool) -> Self {
+        self.flip_normal_map_y = flip;
+        self
+    }
+
+    /// Sets whether the green (Y) channel of normal maps should be inverted on import. It is needed to
+    /// use normal maps made for DirectX-style (Y-down) engines, Fyrox uses OpenGL-style (Y-up) normal maps.
+    pub fn set_flip_normal_map_y(&mut self, flip: bool) {
+        self.flip_normal_map_y = flip;
+    }
+
+    /// Sets channel packing options. If set, the texture is built from a set of other textures.
+    pub fn with_channel_packing(mut self, channel_packing: Option<ChannelPackingOptions>) -> Self {
+        self.channel_packing = channel_packing;
+        self
+    }
+
+    /// Sets channel packing options. If set, the texture is built from a set of other textures.
+    pub fn set_channel_packing(&mut self, channel_packing: Option<ChannelPackingOptions>) {
+        self.channel_packing = channel_packing;
+    }
+}
+
+/// Defines whether an imported texture is a normal map. Normal maps are flagged (see
+/// [`TextureData::is_normal_map`]) and their green channel could be inverted on import (see
+/// [`TextureImportOptions::set_flip_normal_map_y`]).
+#[derive(
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Debug,
+    Reflect,
+    EnumVariantNames,
+    EnumString,
+    AsRefStr,
+)]
+pub enum NormalMapMode {
+    /// A texture is a normal map if its file name ends with one of the common normal map suffixes
+    /// (`_n`, `_nm`, `_nrm`, `_normal`, etc.) or if its pixels look like a tangent-space normal map.
+    Auto,
+    /// Every texture is a normal map.
+    Always,
+    /// A texture is never a normal map.
+    Never,
+}
+
+impl Default for NormalMapMode {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+const NORMAL_MAP_SUFFIXES: [&str; 8] = [
+    "_n",
+    "_nm",
+    "_nor",
+    "_nrm",
+    "_norm",
+    "_normal",
+    "_normals",
+    "_normalmap",
+];
+
+impl NormalMapMode {
+    fn is_normal_map(self, path: &Path, image: Option<&DynamicImage>) -> bool {
+        match self {
+            NormalMapMode::Always => true,
+            NormalMapMode::Never => false,
+            NormalMapMode::Auto => {
+                let by_name = path.file_stem().map_or(false, |stem| {
+                    let stem = stem.to_string_lossy().to_lowercase();
+                    NORMAL_MAP_SUFFIXES
+                        .iter()
+                        .any(|suffix| stem.ends_with(suffix))
+                });
+                by_name || image.map_or(false, looks_like_normal_map)
+            }
+        }
+    }
+}
+
+// Checks whether most of the pixels of the image are unit vectors pointing outwards of a surface.
+fn looks_like_normal_map(image: &DynamicImage) -> bool {
+    if !image.color().has_color() || image.width() == 0 || image.height() == 0 {
+        return false;
+    }
+
+    let samples_per_axis = 32;
+    let mut normals = 0;
+    let mut total = 0;
+    for sy in 0..samples_per_axis {
+        for sx in 0..samples_per_axis {
+            let x = (sx * image.width() / samples_per_axis).min(image.width() - 1);
+            let y = (sy * image.height() / samples_per_axis).min(image.height() - 1);
+            let pixel = image.get_pixel(x, y);
+            let [nx, ny, nz] = [pixel[0], pixel[1], pixel[2]].map(|c| c as f32 / 255.0 * 2.0 - 1.0);
+            let length = (nx * nx + ny * ny + nz * nz).sqrt();
+            if nz > 0.0 && (length - 1.0).abs() < 0.2 {
+                normals += 1;
+            }
+            total += 1;
+        }
+    }
+
+    normals * 10 >= total * 9
+}
+
+fn flip_green_channel(image: &mut DynamicImage) {
+    match image {
+        DynamicImage::ImageRgb8(img) => img.pixels_mut().for_each(|p| p[1] = u8::MAX - p[1]),
+        DynamicImage::ImageRgba8(img) => img.pixels_mut().for_each(|p| p[1] = u8::MAX - p[1]),
+        DynamicImage::ImageRgb16(img) => img.pixels_mut().for_each(|p| p[1] = u16::MAX - p[1]),
+        DynamicImage::ImageRgba16(img) => img.pixels_mut().for_each(|p| p[1] = u16::MAX - p[1]),
+        DynamicImage::ImageRgb32F(img) => img.pixels_mut().for_each(|p| p[1] = 1.0 - p[1]),
+        DynamicImage::ImageRgba32F(img) => img.pixels_mut().for_each(|p| p[1] = 1.0 - p[1]),
+        // Grayscale images have no green channel.
+        _ => (),
+    }
+}
+
+/// Layout of a packed texture. Every layout stores a single material property per channel, so one
+/// texture could be used instead of three separate textures.
+#[derive(
+    Copy,
+    Clone,
+    Deserialize,
+    Serialize,
+    PartialEq,
+    Eq,
+    Debug,
+    Reflect,
+    EnumVariantNames,
+    EnumString,
+    AsRefStr,
+)]
+pub enum ChannelPackingTemplate {
+    /// Red - ambient occlusion, green - roughness, blue - metallic. This layout is used by glTF and
+    /// many content creation tools.
+    OcclusionRoughnessMetallic,
+    /// Red - metallic, green - roughness, blue - ambient occlusion. This layout matches the layout of the
+    /// material parameters of the standard shader.
+    MetallicRoughnessOcclusion,
+}
+
+impl Default for ChannelPackingTemplate {
+    fn default() -> Self {
+        Self::OcclusionRoughnessMetallic
+    }
+}
+
+/// Channel packing options allow you to combine metallic, roughness and ambient occlusion textures into
+/// a single texture on import. Every source is converted to grayscale and stored in a channel defined by
+/// the packing template. Sources are resized to the size of the largest source. Missing sources are
+/// replaced with default values: zero metallic, full roughness and no occlusion.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq, Eq)]
+pub struct ChannelPackingOptions {
+    /// Layout of the packed texture.
+    #[serde(default)]
+    pub template: ChannelPackingTemplate,
+    /// Path to a metallic texture. Relative paths are resolved relative to the directory of the packed
+    /// texture.
+    #[serde(default)]
+    pub metallic: Option<PathBuf>,
+    /// Path to a roughness texture. Relative paths are resolved relative to the directory of the packed
+    /// texture.
+    #[serde(default)]
+    pub roughness: Option<PathBuf>,
+    /// Path to an ambient occlusion texture. Relative paths are resolved relative to the directory of the
+    /// packed texture.
+    #[serde(default)]
+    pub ambient_occlusion: Option<PathBuf>,
+}
+
+impl ChannelPackingOptions {
+    /// Loads every source and packs them into a single RGB image. Relative paths of the sources are
+    /// resolved relative to the given directory. The result could be saved to a file, for example, to
+    /// pack textures offline.
+    pub async fn pack(&self, base_dir: &Path) -> Result<DynamicImage, TextureError> {
+        async fn load_source(
+            base_dir: &Path,
+            path: &Option<PathBuf>,
+        ) -> Result<Option<GrayImage>, TextureError> {
+            match path {
+                Some(path) => {
+                    let data = io::load_file(&base_dir.join(path)).await?;
+                    Ok(Some(decode_image(&data)?.to_luma8()))
+                }
+                None => Ok(None),
+            }
+        }
+
+        let metallic = load_source(base_dir, &self.metallic).await?;
+        let roughness = load_source(base_dir, &self.roughness).await?;
+        let occlusion = load_source(base_dir, &self.ambient_occlusion).await?;
+
+        Ok(self.pack_images(metallic, roughness, occlusion))
+    }
+
+    fn pack_images(
+        &self,
+        metallic: Option<GrayImage>,
+        roughness: Option<GrayImage>,
+        occlusion: Option<GrayImage>,
+    ) -> DynamicImage {
+        let (width, height) = [&metallic, &roughness, &occlusion]
+            .iter()
+            .filter_map(|source| source.as_ref())
+            .fold((1, 1), |(w, h), source| {
+                (w.max(source.width()), h.max(source.height()))
+            });
+
+        let prepare = |source: Option<GrayImage>, default: u8| match source {
+            Some(source) if source.dimensions() == (width, height) => source,
+            Some(source) => imageops::resize(&source, width, height, FilterType::Triangle),
+            None => GrayImage::from_pixel(width, height, Luma([default])),
+        };
+
+        let metallic = prepare(metallic, 0);
+        let roughness = prepare(roughness, u8::MAX);
+        let occlusion = prepare(occlusion, u8::MAX);
+
+        let (red, green, blue) = match self.template {
+            ChannelPackingTemplate::OcclusionRoughnessMetallic => {
+                (&occlusion, &roughness, &metallic)
+            }
+            ChannelPackingTemplate::MetallicRoughnessOcclusion => {
+                (&metallic, &roughness, &occlusion)
+            }
+        };
+
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            Rgb([
+                red.get_pixel(x, y)[0],
+                green.get_pixel(x, y)[0],
+                blue.get_pixel(x, y)[0],
+            ])
+        }))
+    }
 }
 
 define_new_resource!(
@@ -434,6 +723,7 @@ impl Texture {
             serialize_content: false,
             data_hash: 0,
             is_render_target: true,
+            is_normal_map: false,
         })))
     }
 
@@ -887,6 +1177,14 @@ fn compress_rg8_bc4<T: tbc::color::ColorRedGreen8>(
     tbc::encode_image_bc4_rg8_conv_u8::<T>(transmute_slice::<T>(bytes), width, height)
 }
 
+fn decode_image(data: &[u8]) -> Result<DynamicImage, TextureError> {
+    Ok(image::load_from_memory(data)
+        // Try to load as TGA, this is needed because TGA is badly designed format and does not
+        // have an identifier in the beginning of the file (so called "magic") that allows quickly
+        // check if the file is really contains expected data.
+        .or_else(|_| image::load_from_memory_with_format(data, ImageFormat::Tga))?)
+}
+
 fn data_hash(data: &[u8]) -> u64 {
     let mut hasher = FxHasher::default();
     data.hash(&mut hasher);
@@ -1022,172 +1320,178 @@ impl TextureData {
     ) -> Result<Self, TextureError> {
         // DDS is special. It can contain various kinds of textures as well as textures with
         // various pixel formats.
-        //
-        // TODO: Add support for DXGI formats.
         if let Ok(dds) = ddsfile::Dds::read(&mut Cursor::new(data)) {
-            let d3dformat = dds
-                .get_d3d_format()
-                .ok_or(TextureError::UnsupportedFormat)?;
-            let mip_count = dds.get_num_mipmap_levels();
-            let mut bytes = dds.data;
-
-            // Try to use as much formats as possible.
-            let pixel_kind = match d3dformat {
-                D3DFormat::DXT1 => TexturePixelKind::DXT1RGBA,
-                D3DFormat::DXT3 => TexturePixelKind::DXT3RGBA,
-                D3DFormat::DXT5 => TexturePixelKind::DXT5RGBA,
-                D3DFormat::L8 | D3DFormat::A8 => TexturePixelKind::R8,
-                D3DFormat::L16 => TexturePixelKind::R16,
-                D3DFormat::R8G8B8 => TexturePixelKind::RGB8,
-                D3DFormat::A8L8 => TexturePixelKind::RG8,
-                D3DFormat::A8R8G8B8 => {
-                    // // ARGB8 -> RGBA8
-                    // assert_eq!(bytes.len() % 4, 0);
-                    // for chunk in bytes.chunks_exact_mut(4) {
-                    //     let a = chunk[0];
-                    //     let r = chunk[1];
-                    //     let g = chunk[2];
-                    //     let b = chunk[3];
-                    //     chunk[0] = r;
-                    //     chunk[1] = g;
-                    //     chunk[2] = b;
-                    //     chunk[3] = a;
-                    // }
-                    TexturePixelKind::RGBA8
+            Self::from_dds(dds)
+        } else {
+            Ok(Self::from_dynamic_image(
+                decode_image(data)?,
+                compression,
+                gen_mip_maps,
+            ))
+        }
+    }
+
+    // TODO: Add support for DXGI formats.
+    fn from_dds(dds: ddsfile::Dds) -> Result<Self, TextureError> {
+        let d3dformat = dds
+            .get_d3d_format()
+            .ok_or(TextureError::UnsupportedFormat)?;
+        let mip_count = dds.get_num_mipmap_levels();
+        let mut bytes = dds.data;
+
+        // Try to use as much formats as possible.
+        let pixel_kind = match d3dformat {
+            D3DFormat::DXT1 => TexturePixelKind::DXT1RGBA,
+            D3DFormat::DXT3 => TexturePixelKind::DXT3RGBA,
+            D3DFormat::DXT5 => TexturePixelKind::DXT5RGBA,
+            D3DFormat::L8 | D3DFormat::A8 => TexturePixelKind::R8,
+            D3DFormat::L16 => TexturePixelKind::R16,
+            D3DFormat::R8G8B8 => TexturePixelKind::RGB8,
+            D3DFormat::A8L8 => TexturePixelKind::RG8,
+            D3DFormat::A8R8G8B8 => {
+                // // ARGB8 -> RGBA8
+                // assert_eq!(bytes.len() % 4, 0);
+                // for chunk in bytes.chunks_exact_mut(4) {
+                //     let a = chunk[0];
+                //     let r = chunk[1];
+                //     let g = chunk[2];
+                //     let b = chunk[3];
+                //     chunk[0] = r;
+                //     chunk[1] = g;
+                //     chunk[2] = b;
+                //     chunk[3] = a;
+                // }
+                TexturePixelKind::RGBA8
+            }
+            D3DFormat::G16R16 => {
+                // GR16 -> RG16
+                assert_eq!(bytes.len() % 4, 0);
+                for chunk in bytes.chunks_exact_mut(4) {
+                    // Red Hi + Lo bytes
+                    let gh = chunk[0];
+                    let gl = chunk[1];
+                    // Green Hi + Lo bytes
+                    let rh = chunk[2];
+                    let rl = chunk[3];
+                    // Swap
+                    chunk[0] = rh;
+                    chunk[1] = rl;
+                    chunk[2] = gh;
+                    chunk[3] = gl;
                 }
-                D3DFormat::G16R16 => {
-                    // GR16 -> RG16
-                    assert_eq!(bytes.len() % 4, 0);
-                    for chunk in bytes.chunks_exact_mut(4) {
-                        // Red Hi + Lo bytes
-                        let gh = chunk[0];
-                        let gl = chunk[1];
-                        // Green Hi + Lo bytes
-                        let rh = chunk[2];
-                        let rl = chunk[3];
-                        // Swap
-                        chunk[0] = rh;
-                        chunk[1] = rl;
-                        chunk[2] = gh;
-                        chunk[3] = gl;
-                    }
-                    TexturePixelKind::RG16
+                TexturePixelKind::RG16
+            }
+            _ => return Err(TextureError::UnsupportedFormat),
+        };
+
+        Ok(Self {
+            pixel_kind,
+            data_hash: data_hash(&bytes),
+            minification_filter: TextureMinificationFilter::LinearMipMapLinear,
+            magnification_filter: TextureMagnificationFilter::Linear,
+            s_wrap_mode: TextureWrapMode::Repeat,
+            t_wrap_mode: TextureWrapMode::Repeat,
+            mip_count,
+            bytes: bytes.into(),
+            kind: if dds.header.caps2 & Caps2::CUBEMAP == Caps2::CUBEMAP {
+                TextureKind::Cube {
+                    width: dds.header.width,
+                    height: dds.header.height,
                 }
-                _ => return Err(TextureError::UnsupportedFormat),
-            };
+            } else if dds.header.caps2 & Caps2::VOLUME == Caps2::VOLUME {
+                TextureKind::Volume {
+                    width: dds.header.width,
+                    height: dds.header.height,
+                    depth: dds.header.depth.unwrap(),
+                }
+            } else {
+                TextureKind::Rectangle {
+                    width: dds.header.width,
+                    height: dds.header.height,
+                }
+            },
+            ..Default::default()
+        })
+    }
 
-            Ok(Self {
-                pixel_kind,
-                data_hash: data_hash(&bytes),
-                minification_filter: TextureMinificationFilter::LinearMipMapLinear,
-                magnification_filter: TextureMagnificationFilter::Linear,
-                s_wrap_mode: TextureWrapMode::Repeat,
-                t_wrap_mode: TextureWrapMode::Repeat,
-                mip_count,
-                bytes: bytes.into(),
-                kind: if dds.header.caps2 & Caps2::CUBEMAP == Caps2::CUBEMAP {
-                    TextureKind::Cube {
-                        width: dds.header.width,
-                        height: dds.header.height,
-                    }
-                } else if dds.header.caps2 & Caps2::VOLUME == Caps2::VOLUME {
-                    TextureKind::Volume {
-                        width: dds.header.width,
-                        height: dds.header.height,
-                        depth: dds.header.depth.unwrap(),
-                    }
-                } else {
-                    TextureKind::Rectangle {
-                        width: dds.header.width,
-                        height: dds.header.height,
-                    }
-                },
-                ..Default::default()
-            })
-        } else {
-            // Commonly used formats are all rectangle textures.
-            let dyn_img = image::load_from_memory(data)
-                // Try to load as TGA, this is needed because TGA is badly designed format and does not
-                // have an identifier in the beginning of the file (so called "magic") that allows quickly
-                // check if the file is really contains expected data.
-                .or_else(|_| image::load_from_memory_with_format(data, ImageFormat::Tga))?;
-
-            let width = dyn_img.width();
-            let height = dyn_img.height();
-
-            let mut pixel_kind = match dyn_img {
-                DynamicImage::ImageLuma8(_) => TexturePixelKind::Luminance8,
-                DynamicImage::ImageLumaA8(_) => TexturePixelKind::LuminanceAlpha8,
-                DynamicImage::ImageRgb8(_) => TexturePixelKind::RGB8,
-                DynamicImage::ImageRgba8(_) => TexturePixelKind::RGBA8,
-                DynamicImage::ImageLuma16(_) => TexturePixelKind::Luminance16,
-                DynamicImage::ImageLumaA16(_) => TexturePixelKind::LuminanceAlpha16,
-                DynamicImage::ImageRgb16(_) => TexturePixelKind::RGB16,
-                DynamicImage::ImageRgba16(_) => TexturePixelKind::RGBA16,
-                DynamicImage::ImageRgb32F(_) => TexturePixelKind::RGB32F,
-                DynamicImage::ImageRgba32F(_) => TexturePixelKind::RGBA32F,
-                _ => unreachable!(),
-            };
+    fn from_dynamic_image(
+        dyn_img: DynamicImage,
+        compression: CompressionOptions,
+        gen_mip_maps: bool,
+    ) -> Self {
+        // Commonly used formats are all rectangle textures.
+        let width = dyn_img.width();
+        let height = dyn_img.height();
+
+        let mut pixel_kind = match dyn_img {
+            DynamicImage::ImageLuma8(_) => TexturePixelKind::Luminance8,
+            DynamicImage::ImageLumaA8(_) => TexturePixelKind::LuminanceAlpha8,
+            DynamicImage::ImageRgb8(_) => TexturePixelKind::RGB8,
+            DynamicImage::ImageRgba8(_) => TexturePixelKind::RGBA8,
+            DynamicImage::ImageLuma16(_) => TexturePixelKind::Luminance16,
+            DynamicImage::ImageLumaA16(_) => TexturePixelKind::LuminanceAlpha16,
+            DynamicImage::ImageRgb16(_) => TexturePixelKind::RGB16,
+            DynamicImage::ImageRgba16(_) => TexturePixelKind::RGBA16,
+            DynamicImage::ImageRgb32F(_) => TexturePixelKind::RGB32F,
+            DynamicImage::ImageRgba32F(_) => TexturePixelKind::RGBA32F,
+            _ => unreachable!(),
+        };
 
-            let mut mip_count = 0;
-            let mut bytes = Vec::new();
-
-            if gen_mip_maps {
-                let mut level_width = width;
-                let mut level_height = height;
-                let mut current_level = dyn_img;
-
-                while level_width != 0 && level_height != 0 {
-                    if mip_count != 0 {
-                        current_level = current_level.resize_exact(
-                            level_width,
-                            level_height,
-                            FilterType::Lanczos3,
-                        );
-                    }
-
-                    mip_count += 1;
-
-                    if compression == CompressionOptions::NoCompression {
-                        bytes.extend_from_slice(current_level.as_bytes())
-                    } else if let Some((compressed_data, new_pixel_kind)) = try_compress(
-                        &current_level,
-                        level_width as usize,
-                        level_height as usize,
-                        compression,
-                    ) {
-                        pixel_kind = new_pixel_kind;
-                        bytes.extend_from_slice(&compressed_data);
-                    } else {
-                        bytes.extend_from_slice(current_level.as_bytes())
-                    }
-
-                    level_width = level_width.checked_shr(1).unwrap_or_default();
-                    level_height = level_height.checked_shr(1).unwrap_or_default();
+        let mut mip_count = 0;
+        let mut bytes = Vec::new();
+
+        if gen_mip_maps {
+            let mut level_width = width;
+            let mut level_height = height;
+            let mut current_level = dyn_img;
+
+            while level_width != 0 && level_height != 0 {
+                if mip_count != 0 {
+                    current_level =
+                        current_level.resize_exact(level_width, level_height, FilterType::Lanczos3);
                 }
-            } else {
-                mip_count = 1;
+
+                mip_count += 1;
 
                 if compression == CompressionOptions::NoCompression {
-                    bytes.extend_from_slice(dyn_img.as_bytes());
-                } else if let Some((compressed_data, new_pixel_kind)) =
-                    try_compress(&dyn_img, width as usize, height as usize, compression)
-                {
+                    bytes.extend_from_slice(current_level.as_bytes())
+                } else if let Some((compressed_data, new_pixel_kind)) = try_compress(
+                    &current_level,
+                    level_width as usize,
+                    level_height as usize,
+                    compression,
+                ) {
                     pixel_kind = new_pixel_kind;
                     bytes.extend_from_slice(&compressed_data);
                 } else {
-                    bytes.extend_from_slice(dyn_img.as_bytes())
+                    bytes.extend_from_slice(current_level.as_bytes())
                 }
+
+                level_width = level_width.checked_shr(1).unwrap_or_default();
+                level_height = level_height.checked_shr(1).unwrap_or_default();
+            }
+        } else {
+            mip_count = 1;
+
+            if compression == CompressionOptions::NoCompression {
+                bytes.extend_from_slice(dyn_img.as_bytes());
+            } else if let Some((compressed_data, new_pixel_kind)) =
+                try_compress(&dyn_img, width as usize, height as usize, compression)
+            {
+                pixel_kind = new_pixel_kind;
+                bytes.extend_from_slice(&compressed_data);
+            } else {
+                bytes.extend_from_slice(dyn_img.as_bytes())
             }
+        }
 
-            Ok(Self {
-                pixel_kind,
-                kind: TextureKind::Rectangle { width, height },
-                data_hash: data_hash(&bytes),
-                bytes: bytes.into(),
-                mip_count,
-                ..Default::default()
-            })
+        Self {
+            pixel_kind,
+            kind: TextureKind::Rectangle { width, height },
+            data_hash: data_hash(&bytes),
+            bytes: bytes.into(),
+            mip_count,
+            ..Default::default()
         }
     }
 
@@ -1199,12 +1503,40 @@ impl TextureData {
     /// resources.
     pub(crate) async fn load_from_file<P: AsRef<Path>>(
         path: P,
-        compression: CompressionOptions,
-        gen_mip_maps: bool,
+        import_options: &TextureImportOptions,
     ) -> Result<Self, TextureError> {
-        let data = io::load_file(path.as_ref()).await?;
-        let mut texture = Self::load_from_memory(&data, compression, gen_mip_maps)?;
-        texture.path = path.as_ref().to_path_buf();
+        let path = path.as_ref();
+        let gen_mip_maps = import_options.minification_filter.is_using_mip_mapping();
+
+        let (mut image, is_normal_map) = match import_options.channel_packing {
+            Some(ref channel_packing) => (
+                channel_packing
+                    .pack(path.parent().unwrap_or_else(|| Path::new("")))
+                    .await?,
+                false,
+            ),
+            None => {
+                let data = io::load_file(path).await?;
+                if let Ok(dds) = ddsfile::Dds::read(&mut Cursor::new(&data)) {
+                    // DDS textures are stored as is, so they could be flagged only by name.
+                    let mut texture = Self::from_dds(dds)?;
+                    texture.path = path.to_path_buf();
+                    texture.is_normal_map = import_options.normal_map.is_normal_map(path, None);
+                    return Ok(texture);
+                }
+                let image = decode_image(&data)?;
+                let is_normal_map = import_options.normal_map.is_normal_map(path, Some(&image));
+                (image, is_normal_map)
+            }
+        };
+
+        if is_normal_map && import_options.flip_normal_map_y {
+            flip_green_channel(&mut image);
+        }
+
+        let mut texture = Self::from_dynamic_image(image, import_options.compression, gen_mip_maps);
+        texture.path = path.to_path_buf();
+        texture.is_normal_map = is_normal_map;
         Ok(texture)
     }
 
@@ -1333,6 +1665,17 @@ impl TextureData {
         self.anisotropy
     }
 
+    /// Marks the texture as a normal map. Textures loaded through the resource manager are flagged
+    /// automatically, see [`NormalMapMode`].
+    pub fn set_normal_map(&mut self, is_normal_map: bool) {
+        self.is_normal_map = is_normal_map;
+    }
+
+    /// Returns `true` if the texture is a normal map.
+    pub fn is_normal_map(&self) -> bool {
+        self.is_normal_map
+    }
+
     /// Sets new path to source file.
     pub fn set_path<P: AsRef<Path>>(&mut self, path: P) {
         self.path = path.as_ref().to_owned();
@@ -1419,7 +1762,12 @@ impl<'a> TextureDataRefMut<'a> {
 
 #[cfg(test)]
 pub mod test {
-    use crate::resource::texture::{Texture, TextureKind, TexturePixelKind};
+    use crate::resource::texture::{
+        flip_green_channel, ChannelPackingOptions, ChannelPackingTemplate, NormalMapMode, Texture,
+        TextureKind, TexturePixelKind,
+    };
+    use image::{DynamicImage, GrayImage, Luma, RgbImage};
+    use std::path::Path;
 
     pub fn create_test_texture() -> Texture {
         Texture::from_bytes(
@@ -1433,4 +1781,48 @@ pub mod test {
         )
         .unwrap()
     }
+
+    #[test]
+    fn test_normal_map_detection() {
+        let flat_normals =
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, [128, 128, 255].into()));
+        let gray = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, [128, 128, 128].into()));
+
+        let auto = NormalMapMode::Auto;
+        assert!(auto.is_normal_map(Path::new("rock.png"), Some(&flat_normals)));
+        assert!(!auto.is_normal_map(Path::new("rock.png"), Some(&gray)));
+        assert!(auto.is_normal_map(Path::new("data/Rock_NRM.dds"), None));
+        assert!(auto.is_normal_map(Path::new("rock_normal.png"), Some(&gray)));
+        assert!(!auto.is_normal_map(Path::new("rock_diffuse.png"), None));
+        assert!(!NormalMapMode::Never.is_normal_map(Path::new("rock_n.png"), Some(&flat_normals)));
+        assert!(NormalMapMode::Always.is_normal_map(Path::new("rock.png"), Some(&gray)));
+    }
+
+    #[test]
+    fn test_flip_green_channel() {
+        let mut image = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, [10, 20, 30].into()));
+        flip_green_channel(&mut image);
+        assert_eq!(image.as_bytes(), &[10, 235, 30]);
+    }
+
+    #[test]
+    fn test_channel_packing() {
+        let metallic = GrayImage::from_pixel(2, 2, Luma([10]));
+        let roughness = GrayImage::from_pixel(4, 4, Luma([20]));
+
+        let orm = ChannelPackingOptions {
+            template: ChannelPackingTemplate::OcclusionRoughnessMetallic,
+            ..Default::default()
+        }
+        .pack_images(Some(metallic.clone()), Some(roughness.clone()), None);
+        assert_eq!(orm.to_rgb8().dimensions(), (4, 4));
+        assert_eq!(orm.to_rgb8().get_pixel(3, 3).0, [255, 20, 10]);
+
+        let mro = ChannelPackingOptions {
+            template: ChannelPackingTemplate::MetallicRoughnessOcclusion,
+            ..Default::default()
+        }
+        .pack_images(Some(metallic), Some(roughness), None);
+        assert_eq!(mro.to_rgb8().get_pixel(0, 0).0, [10, 20, 255]);
+    }
 }
@@ -0,0 +1,422 @@
+//! Visual script resource holds a [`VisualScriptGraph`] - a graph of nodes, that defines game logic
+//! without writing any code. The graph is executed by [`crate::script::visual::VisualScript`], which
+//! can be attached to scene nodes as any other script.
+//!
+//! # Structure
+//!
+//! Every node of the graph has a [`VisualNodeKind`], a set of data inputs and a set of execution
+//! outputs. Execution starts at event nodes (for example [`VisualNodeKind::OnUpdate`]) and goes from
+//! one node to another along execution outputs. Data inputs are either constant values, or links to
+//! data outputs of other nodes. Nodes without execution outputs (math, property getters, etc.) are
+//! evaluated on demand, when some other node needs their value. See docs of [`VisualNodeKind`] for
+//! the inputs and outputs of every node.
+//!
+//! # Example
+//!
+//! The following graph moves the node of the script up on every frame, until it has moved 100 times.
+//!
+//! ```rust
+//! use fyrox::{
+//!     core::algebra::Vector3,
+//!     resource::visual_script::{CompareOp, VisualNodeKind, VisualScriptGraph, VisualValue},
+//! };
+//!
+//! fn make_graph() -> VisualScriptGraph {
+//!     let mut graph = VisualScriptGraph::default();
+//!
+//!     // if steps < 100
+//!     let on_update = graph.add_node(VisualNodeKind::OnUpdate);
+//!     let steps = graph.add_node(VisualNodeKind::GetVariable {
+//!         name: "steps".to_string(),
+//!     });
+//!     let compare = graph.add_node(VisualNodeKind::Compare {
+//!         op: CompareOp::Less,
+//!     });
+//!     graph.link(steps, 0, compare, 0);
+//!     graph.set_input_value(compare, 1, VisualValue::Number(100.0));
+//!     let branch = graph.add_node(VisualNodeKind::Branch);
+//!     graph.link(compare, 0, branch, 0);
+//!     graph.connect(on_update, 0, branch);
+//!
+//!     // position = position + (0, 0.1, 0)
+//!     let this = graph.add_node(VisualNodeKind::SelfNode);
+//!     let position = graph.add_node(VisualNodeKind::GetPosition);
+//!     graph.link(this, 0, position, 0);
+//!     let new_position = graph.add_node(VisualNodeKind::Add);
+//!     graph.link(position, 0, new_position, 0);
+//!     graph.set_input_value(new_position, 1, VisualValue::Vector(Vector3::new(0.0, 0.1, 0.0)));
+//!     let set_position = graph.add_node(VisualNodeKind::SetPosition);
+//!     graph.link(this, 0, set_position, 0);
+//!     graph.link(new_position, 0, set_position, 1);
+//!     graph.connect(branch, 0, set_position);
+//!
+//!     // steps = steps + 1
+//!     let increment = graph.add_node(VisualNodeKind::Add);
+//!     graph.link(steps, 0, increment, 0);
+//!     graph.set_input_value(increment, 1, VisualValue::Number(1.0));
+//!     let set_steps = graph.add_node(VisualNodeKind::SetVariable {
+//!         name: "steps".to_string(),
+//!     });
+//!     graph.link(increment, 0, set_steps, 0);
+//!     graph.connect(set_position, 0, set_steps);
+//!
+//!     graph
+//! }
+//! ```
+
+use crate::{
+    asset::{define_new_resource, Resource, ResourceData, ResourceState},
+    core::{
+        algebra::{Vector2, Vector3},
+        io::FileLoadError,
+        pool::Handle,
+        reflect::prelude::*,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::options::ImportOptions,
+    scene::node::Node,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+/// An error that may occur during visual script resource loading.
+#[derive(Debug)]
+pub enum VisualScriptResourceError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+}
+
+impl Display for VisualScriptResourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VisualScriptResourceError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            VisualScriptResourceError::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for VisualScriptResourceError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for VisualScriptResourceError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+/// A value, that flows between nodes of a visual script.
+#[derive(Clone, Debug, Visit, Reflect, PartialEq)]
+pub enum VisualValue {
+    /// A boolean value.
+    Bool(bool),
+    /// A number.
+    Number(f32),
+    /// A 3D vector.
+    Vector(Vector3<f32>),
+    /// A string.
+    String(String),
+    /// A handle of a scene node.
+    Node(Handle<Node>),
+}
+
+impl Default for VisualValue {
+    fn default() -> Self {
+        Self::Number(0.0)
+    }
+}
+
+impl Display for VisualValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VisualValue::Bool(v) => write!(f, "{v}"),
+            VisualValue::Number(v) => write!(f, "{v}"),
+            VisualValue::Vector(v) => write!(f, "({}; {}; {})", v.x, v.y, v.z),
+            VisualValue::String(v) => write!(f, "{v}"),
+            VisualValue::Node(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// Comparison operator of [`VisualNodeKind::Compare`] node.
+#[derive(Copy, Clone, Debug, Visit, Reflect, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    /// `a == b`, works with any values.
+    Equal,
+    /// `a != b`, works with any values.
+    NotEqual,
+    /// `a < b`, works with numbers only.
+    Less,
+    /// `a <= b`, works with numbers only.
+    LessOrEqual,
+    /// `a > b`, works with numbers only.
+    Greater,
+    /// `a >= b`, works with numbers only.
+    GreaterOrEqual,
+}
+
+impl Default for CompareOp {
+    fn default() -> Self {
+        Self::Equal
+    }
+}
+
+/// Kind of a visual script node. Inputs and outputs are numbered from zero, "exec" means an execution
+/// output (see [`VisualNode::outputs`]), every other input or output is a data one.
+#[derive(Clone, Debug, Visit, Reflect, PartialEq)]
+pub enum VisualNodeKind {
+    /// An event, that happens once, when the script is started. Exec: 0 - next.
+    OnStart,
+    /// An event, that happens every frame. Outputs: 0 - time step (number). Exec: 0 - next.
+    OnUpdate,
+    /// An event, that happens when the node of the script receives a message with the given name,
+    /// see [`VisualNodeKind::SendMessage`]. Outputs: 0 - sender (node), 1 - payload. Exec: 0 - next.
+    OnMessage {
+        /// Name of the message.
+        name: String,
+    },
+    /// Chooses an execution path depending on a condition. Inputs: 0 - condition (bool). Exec: 0 -
+    /// `true`, 1 - `false`.
+    Branch,
+    /// Inputs: 0 - a, 1 - b. Outputs: 0 - `a + b`. Works with numbers, vectors and strings.
+    Add,
+    /// Inputs: 0 - a, 1 - b. Outputs: 0 - `a - b`. Works with numbers and vectors.
+    Subtract,
+    /// Inputs: 0 - a, 1 - b. Outputs: 0 - `a * b`. Works with numbers, vectors can be multiplied by
+    /// numbers.
+    Multiply,
+    /// Inputs: 0 - a, 1 - b. Outputs: 0 - `a / b`. Works with numbers, vectors can be divided by
+    /// numbers.
+    Divide,
+    /// Inputs: 0 - a, 1 - b. Outputs: 0 - result of comparison (bool).
+    Compare {
+        /// Comparison operator.
+        op: CompareOp,
+    },
+    /// Inputs: 0 - a (bool), 1 - b (bool). Outputs: 0 - `a && b`.
+    And,
+    /// Inputs: 0 - a (bool), 1 - b (bool). Outputs: 0 - `a || b`.
+    Or,
+    /// Inputs: 0 - a (bool). Outputs: 0 - `!a`.
+    Not,
+    /// Outputs: 0 - the node of the script.
+    SelfNode,
+    /// Outputs: 0 - first node with the given name in the scene, or a `NONE` handle.
+    FindNode {
+        /// Name of the node.
+        name: String,
+    },
+    /// Inputs: 0 - node. Outputs: 0 - local position of the node (vector).
+    GetPosition,
+    /// Sets local position of a node. Inputs: 0 - node, 1 - position (vector). Exec: 0 - next.
+    SetPosition,
+    /// Reads a property of a node using reflection. Properties of type `bool`, `f32`, `String`,
+    /// `Vector3<f32>` and `Handle<Node>` are supported. Inputs: 0 - node. Outputs: 0 - value of
+    /// the property.
+    GetProperty {
+        /// Path of the property, for example `base.visibility` or
+        /// `base.local_transform.local_position`.
+        path: String,
+    },
+    /// Writes a property of a node using reflection, see [`VisualNodeKind::GetProperty`] for
+    /// supported types. Inputs: 0 - node, 1 - value. Exec: 0 - next.
+    SetProperty {
+        /// Path of the property.
+        path: String,
+    },
+    /// Reads a variable of the script. Variables that were never set are equal to zero. Outputs: 0 -
+    /// value of the variable.
+    GetVariable {
+        /// Name of the variable.
+        name: String,
+    },
+    /// Writes a variable of the script. Inputs: 0 - value. Exec: 0 - next.
+    SetVariable {
+        /// Name of the variable.
+        name: String,
+    },
+    /// Sends a message with the given name to a node, the message can be handled by
+    /// [`VisualNodeKind::OnMessage`] of a visual script of the node. Inputs: 0 - target node, 1 -
+    /// payload (optional). Exec: 0 - next.
+    SendMessage {
+        /// Name of the message.
+        name: String,
+    },
+    /// Writes a value to the log. Inputs: 0 - value. Exec: 0 - next.
+    Log,
+}
+
+impl Default for VisualNodeKind {
+    fn default() -> Self {
+        Self::OnStart
+    }
+}
+
+impl VisualNodeKind {
+    /// Returns `true` if the node is an event (an entry point of execution).
+    pub fn is_event(&self) -> bool {
+        matches!(
+            self,
+            VisualNodeKind::OnStart | VisualNodeKind::OnUpdate | VisualNodeKind::OnMessage { .. }
+        )
+    }
+}
+
+/// Data input of a visual script node.
+#[derive(Clone, Debug, Visit, Reflect, PartialEq)]
+pub enum VisualInput {
+    /// A constant value.
+    Value(VisualValue),
+    /// A link to a data output of some other node.
+    Link {
+        /// Index of the node.
+        node: u32,
+        /// Index of the output of the node.
+        output: u32,
+    },
+}
+
+impl Default for VisualInput {
+    fn default() -> Self {
+        Self::Value(Default::default())
+    }
+}
+
+/// A single node of a visual script graph.
+#[derive(Clone, Debug, Visit, Reflect, PartialEq, Default)]
+pub struct VisualNode {
+    /// Kind of the node.
+    pub kind: VisualNodeKind,
+    /// Data inputs of the node.
+    pub inputs: Vec<VisualInput>,
+    /// Execution outputs of the node, every output holds an index of a node, that will be executed
+    /// next.
+    pub outputs: Vec<Option<u32>>,
+    /// Position of the node in a graph editor.
+    pub position: Vector2<f32>,
+}
+
+/// A graph of visual script nodes. See module docs for more info.
+#[derive(Clone, Debug, Visit, Reflect, PartialEq, Default)]
+pub struct VisualScriptGraph {
+    /// Nodes of the graph. Nodes refer to each other by indices in this list.
+    pub nodes: Vec<VisualNode>,
+}
+
+impl VisualScriptGraph {
+    /// Adds a new node of the given kind and returns its index.
+    pub fn add_node(&mut self, kind: VisualNodeKind) -> u32 {
+        self.nodes.push(VisualNode {
+            kind,
+            ..Default::default()
+        });
+        (self.nodes.len() - 1) as u32
+    }
+
+    /// Sets a data input of a node. Inputs before the given one are filled with zeros, if needed.
+    pub fn set_input(&mut self, node: u32, input: u32, value: VisualInput) {
+        let inputs = &mut self.nodes[node as usize].inputs;
+        if inputs.len() <= input as usize {
+            inputs.resize(input as usize + 1, Default::default());
+        }
+        inputs[input as usize] = value;
+    }
+
+    /// Sets a data input of a node to a constant value.
+    pub fn set_input_value(&mut self, node: u32, input: u32, value: VisualValue) {
+        self.set_input(node, input, VisualInput::Value(value))
+    }
+
+    /// Links a data output of a node to a data input of some other node.
+    pub fn link(&mut self, from: u32, output: u32, to: u32, input: u32) {
+        self.set_input(to, input, VisualInput::Link { node: from, output })
+    }
+
+    /// Connects an execution output of a node to some other node, which will be executed after it.
+    pub fn connect(&mut self, from: u32, output: u32, to: u32) {
+        let outputs = &mut self.nodes[from as usize].outputs;
+        if outputs.len() <= output as usize {
+            outputs.resize(output as usize + 1, None);
+        }
+        outputs[output as usize] = Some(to);
+    }
+
+    /// Saves the graph to the given file.
+    pub fn save(&mut self, path: &Path) -> VisitResult {
+        let mut visitor = Visitor::new();
+        self.visit("Graph", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+}
+
+/// State of the [`VisualScriptResource`].
+#[derive(Debug, Visit, Default)]
+pub struct VisualScriptResourceState {
+    pub(crate) path: PathBuf,
+    /// Actual graph.
+    pub graph: VisualScriptGraph,
+}
+
+impl ResourceData for VisualScriptResourceState {
+    fn path(&self) -> Cow<Path> {
+        Cow::Borrowed(&self.path)
+    }
+
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+}
+
+impl VisualScriptResourceState {
+    /// Load a visual script resource from the specific file path.
+    pub async fn from_file(path: &Path) -> Result<Self, VisualScriptResourceError> {
+        let mut visitor = Visitor::load_binary(path).await?;
+        let mut graph = VisualScriptGraph::default();
+        graph.visit("Graph", &mut visitor)?;
+        Ok(Self {
+            graph,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+define_new_resource!(
+    /// See module docs.
+    #[derive(Reflect)]
+    #[reflect(hide_all)]
+    VisualScriptResource<VisualScriptResourceState, VisualScriptResourceError>
+);
+
+impl VisualScriptResource {
+    /// Creates a new, embedded, visual script resource with the given graph.
+    pub fn new(graph: VisualScriptGraph) -> Self {
+        Self(Resource::new(ResourceState::Ok(
+            VisualScriptResourceState {
+                path: Default::default(),
+                graph,
+            },
+        )))
+    }
+}
+
+/// Import options for visual script resource.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct VisualScriptImportOptions {}
+
+impl ImportOptions for VisualScriptImportOptions {}
@@ -188,12 +188,41 @@ impl NodeTrait for AnimationBlendingStateMachine {
             // do than instead.
             animation_player.set_auto_apply(false);
 
+            // Follow level-of-detail decisions of the animation player. If the player was updated before the
+            // state machine, its decision for current frame is used, otherwise - the decision from the previous
+            // frame.
+            let dt = if animation_player.lod().enabled {
+                match animation_player.lod_time_step() {
+                    Some(dt) => dt,
+                    None => return,
+                }
+            } else {
+                context.dt
+            };
+
+            // Keep animations of synchronization groups phase-aligned.
+            let weights = self.machine.animation_weights();
+            animation_player
+                .animations_mut()
+                .get_value_mut_silent()
+                .synchronize(&weights);
+
+            let skipped_nodes = animation_player.take_lod_skipped_nodes();
+
             let pose = self
                 .machine
                 .get_value_mut_silent()
-                .evaluate_pose(&animation_player.animations, context.dt);
+                .evaluate_pose(&animation_player.animations, dt);
+
+            pose.apply_internal_filtered(context.nodes, &skipped_nodes);
 
-            pose.apply_internal(context.nodes);
+            if let Some(animation_player) = context
+                .nodes
+                .try_borrow_mut(*self.animation_player)
+                .and_then(|n| n.query_component_mut::<AnimationPlayer>())
+            {
+                animation_player.set_lod_skipped_nodes(skipped_nodes);
+            }
         }
     }
 
@@ -0,0 +1,211 @@
+//! Animation level-of-detail (LOD) allows you to reduce animation update rate and skip bone chains of characters
+//! that are far from observers or off-screen. See [`AnimationLod`] docs for more info.
+
+use crate::{
+    core::{algebra::Vector3, pool::Handle, reflect::prelude::*, visitor::prelude::*},
+    scene::{
+        graph::{NodePool, Observer},
+        node::Node,
+    },
+};
+use fxhash::FxHashSet;
+
+/// A single level of [`AnimationLod`].
+#[derive(Visit, Reflect, Clone, Debug, PartialEq, Default)]
+pub struct AnimationLodLevel {
+    /// Distance (from the nearest observer) at which the level becomes active.
+    pub distance: f32,
+    /// Amount of frames that will be skipped between two animation updates. Skipped time is accumulated and
+    /// applied on the next update, so animations stay in sync.
+    pub frame_skip: u32,
+    /// Roots of bone chains (for example fingers or face bones) that won't be animated on this level. Every
+    /// descendant of a chain root is skipped as well.
+    pub skipped_chains: Vec<Handle<Node>>,
+}
+
+/// Animation LOD defines how often animations are updated depending on the distance to the nearest observer (an
+/// enabled camera) and the visibility of the animated character. It is disabled by default, which means that
+/// animations are updated every frame.
+///
+/// # Levels
+///
+/// Levels must be sorted by distance in ascending order. If the distance to the nearest observer is less than
+/// the distance of the first level, animations are updated at full rate. Switching between levels uses hysteresis
+/// to prevent flickering when a character stays near the border of a level.
+///
+/// # Off-screen characters
+///
+/// A character is considered off-screen when a sphere with [`AnimationLod::bounding_radius`] around the animation
+/// node is not visible by any observer. Off-screen characters use the largest frame skip of the current level and
+/// [`AnimationLod::off_screen_frame_skip`].
+#[derive(Visit, Reflect, Clone, Debug, PartialEq)]
+pub struct AnimationLod {
+    /// Whether the LOD is enabled or not.
+    pub enabled: bool,
+    /// A set of LOD levels sorted by distance in ascending order.
+    pub levels: Vec<AnimationLodLevel>,
+    /// Amount of frames that will be skipped between two animation updates when the character is off-screen.
+    pub off_screen_frame_skip: u32,
+    /// Radius of a bounding sphere of the animated character, it is used for visibility checks.
+    pub bounding_radius: f32,
+    /// Distance margin that must be passed before switching to another level.
+    pub hysteresis: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    current_level: usize,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    skipped_frames: u32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    accumulated_dt: f32,
+}
+
+impl Default for AnimationLod {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            levels: Default::default(),
+            off_screen_frame_skip: 8,
+            bounding_radius: 2.0,
+            hysteresis: 1.0,
+            current_level: 0,
+            skipped_frames: 0,
+            accumulated_dt: 0.0,
+        }
+    }
+}
+
+impl AnimationLod {
+    /// Returns current level of the LOD. `None` means that animations are updated at full rate.
+    pub fn current_level(&self) -> Option<&AnimationLodLevel> {
+        self.current_level
+            .checked_sub(1)
+            .and_then(|i| self.levels.get(i))
+    }
+
+    /// Selects a level using the position of the animated character and observers, and decides whether animations
+    /// must be updated on this frame. Returns a time step for animations if they must be updated, `None` -
+    /// otherwise.
+    pub fn evaluate(
+        &mut self,
+        position: Vector3<f32>,
+        observers: &[Observer],
+        dt: f32,
+    ) -> Option<f32> {
+        if !self.enabled || observers.is_empty() {
+            self.current_level = 0;
+            self.skipped_frames = 0;
+            let dt = dt + self.accumulated_dt;
+            self.accumulated_dt = 0.0;
+            return Some(dt);
+        }
+
+        let distance = observers
+            .iter()
+            .map(|o| o.position.metric_distance(&position))
+            .fold(f32::MAX, f32::min);
+
+        let mut level = self.current_level.min(self.levels.len());
+        while level < self.levels.len() && distance > self.levels[level].distance + self.hysteresis
+        {
+            level += 1;
+        }
+        while level > 0 && distance < self.levels[level - 1].distance - self.hysteresis {
+            level -= 1;
+        }
+        self.current_level = level;
+
+        let mut frame_skip = self.current_level().map_or(0, |l| l.frame_skip);
+        if !observers
+            .iter()
+            .any(|o| o.is_sphere_visible(position, self.bounding_radius))
+        {
+            frame_skip = frame_skip.max(self.off_screen_frame_skip);
+        }
+
+        self.accumulated_dt += dt;
+        if self.skipped_frames >= frame_skip {
+            self.skipped_frames = 0;
+            let dt = self.accumulated_dt;
+            self.accumulated_dt = 0.0;
+            Some(dt)
+        } else {
+            self.skipped_frames += 1;
+            None
+        }
+    }
+
+    /// Collects every node of skipped bone chains of the current level.
+    pub fn collect_skipped_nodes(&self, nodes: &NodePool, skipped: &mut FxHashSet<Handle<Node>>) {
+        skipped.clear();
+        if let Some(level) = self.current_level() {
+            let mut stack = level.skipped_chains.clone();
+            while let Some(handle) = stack.pop() {
+                if let Some(node) = nodes.try_borrow(handle) {
+                    if skipped.insert(handle) {
+                        stack.extend_from_slice(node.children());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            animation::lod::{AnimationLod, AnimationLodLevel},
+            graph::Observer,
+        },
+    };
+
+    fn updates(lod: &mut AnimationLod, distance: f32, frames: usize) -> usize {
+        let observers = [Observer {
+            position: Vector3::new(0.0, 0.0, distance),
+            frustum: None,
+        }];
+        (0..frames)
+            .filter(|_| lod.evaluate(Vector3::default(), &observers, 0.1).is_some())
+            .count()
+    }
+
+    #[test]
+    fn test_animation_lod() {
+        let mut lod = AnimationLod {
+            enabled: true,
+            levels: vec![
+                AnimationLodLevel {
+                    distance: 10.0,
+                    frame_skip: 1,
+                    skipped_chains: vec![],
+                },
+                AnimationLodLevel {
+                    distance: 20.0,
+                    frame_skip: 3,
+                    skipped_chains: vec![],
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(updates(&mut lod, 5.0, 12), 12);
+        assert_eq!(updates(&mut lod, 15.0, 12), 6);
+        assert_eq!(lod.current_level(), Some(&lod.levels[0]));
+        assert_eq!(updates(&mut lod, 25.0, 12), 3);
+
+        // Hysteresis keeps the level near its border.
+        updates(&mut lod, 19.5, 1);
+        assert_eq!(lod.current_level(), Some(&lod.levels[1]));
+        updates(&mut lod, 18.5, 1);
+        assert_eq!(lod.current_level(), Some(&lod.levels[0]));
+
+        // Skipped time is accumulated.
+        let observers = [Observer::default()];
+        lod.enabled = false;
+        let dt = lod.evaluate(Vector3::default(), &observers, 0.1).unwrap();
+        assert!(dt >= 0.1);
+    }
+}
@@ -13,14 +13,17 @@ use crate::{
     },
     engine::resource_manager::ResourceManager,
     scene::{
+        animation::lod::AnimationLod,
         base::{Base, BaseBuilder},
         graph::Graph,
         node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
     },
 };
+use fxhash::FxHashSet;
 use std::ops::{Deref, DerefMut};
 
 pub mod absm;
+pub mod lod;
 
 /// Animation player is a node that contains multiple animations. It updates and plays all the animations.
 /// The node could be a source of animations for animation blending state machines. To learn more about
@@ -96,6 +99,14 @@ pub struct AnimationPlayer {
     base: Base,
     animations: InheritableVariable<AnimationContainer>,
     auto_apply: bool,
+    #[visit(optional)]
+    lod: InheritableVariable<AnimationLod>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    skipped_nodes: FxHashSet<Handle<Node>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    lod_dt: Option<f32>,
 }
 
 impl Default for AnimationPlayer {
@@ -104,6 +115,9 @@ impl Default for AnimationPlayer {
             base: Default::default(),
             animations: Default::default(),
             auto_apply: true,
+            lod: Default::default(),
+            skipped_nodes: Default::default(),
+            lod_dt: Some(0.0),
         }
     }
 }
@@ -138,6 +152,30 @@ impl AnimationPlayer {
     pub fn set_animations(&mut self, animations: AnimationContainer) {
         self.animations.set_value_and_mark_modified(animations);
     }
+
+    /// Sets new animation level-of-detail settings. See [`AnimationLod`] docs for more info.
+    pub fn set_lod(&mut self, lod: AnimationLod) {
+        self.lod.set_value_and_mark_modified(lod);
+    }
+
+    /// Returns current animation level-of-detail settings.
+    pub fn lod(&self) -> &AnimationLod {
+        &self.lod
+    }
+
+    /// Returns a time step that was used to update animations on the last frame, `None` if the update was
+    /// skipped because of level-of-detail settings.
+    pub fn lod_time_step(&self) -> Option<f32> {
+        self.lod_dt
+    }
+
+    pub(crate) fn take_lod_skipped_nodes(&mut self) -> FxHashSet<Handle<Node>> {
+        std::mem::take(&mut self.skipped_nodes)
+    }
+
+    pub(crate) fn set_lod_skipped_nodes(&mut self, skipped_nodes: FxHashSet<Handle<Node>>) {
+        self.skipped_nodes = skipped_nodes;
+    }
 }
 
 impl TypeUuidProvider for AnimationPlayer {
@@ -180,11 +218,33 @@ impl NodeTrait for AnimationPlayer {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
-        self.animations.get_value_mut_silent().update_animations(
-            context.nodes,
-            self.auto_apply,
-            context.dt,
-        );
+        let position = self.global_position();
+        self.lod_dt =
+            self.lod
+                .get_value_mut_silent()
+                .evaluate(position, context.observers, context.dt);
+
+        let dt = match self.lod_dt {
+            Some(dt) => dt,
+            None => return,
+        };
+
+        self.lod
+            .collect_skipped_nodes(context.nodes, &mut self.skipped_nodes);
+
+        for animation in self
+            .animations
+            .get_value_mut_silent()
+            .iter_mut()
+            .filter(|a| a.is_enabled())
+        {
+            animation.tick(dt);
+            if self.auto_apply {
+                animation
+                    .pose()
+                    .apply_internal_filtered(context.nodes, &self.skipped_nodes);
+            }
+        }
     }
 }
 
@@ -193,6 +253,7 @@ pub struct AnimationPlayerBuilder {
     base_builder: BaseBuilder,
     animations: AnimationContainer,
     auto_apply: bool,
+    lod: AnimationLod,
 }
 
 impl AnimationPlayerBuilder {
@@ -202,6 +263,7 @@ impl AnimationPlayerBuilder {
             base_builder,
             animations: AnimationContainer::new(),
             auto_apply: true,
+            lod: Default::default(),
         }
     }
 
@@ -217,12 +279,21 @@ impl AnimationPlayerBuilder {
         self
     }
 
+    /// Sets desired animation level-of-detail settings. See [`AnimationLod`] docs for more info.
+    pub fn with_lod(mut self, lod: AnimationLod) -> Self {
+        self.lod = lod;
+        self
+    }
+
     /// Creates an instance of [`AnimationPlayer`] node.
     pub fn build_node(self) -> Node {
         Node::new(AnimationPlayer {
             base: self.base_builder.build_base(),
             animations: self.animations.into(),
             auto_apply: self.auto_apply,
+            lod: self.lod.into(),
+            skipped_nodes: Default::default(),
+            lod_dt: Some(0.0),
         })
     }
 
@@ -273,6 +273,14 @@ pub enum NodeScriptMessage {
     },
 }
 
+// An active smooth change of local opacity of a node, see `Base::fade_to`.
+#[derive(Debug, Clone, PartialEq)]
+struct OpacityFade {
+    target: f32,
+    // Opacity units per second.
+    speed: f32,
+}
+
 /// Unique id of the node. It can be shared across multiple resources (read - prefabs), to preserve parent-child
 /// links. It is useful to create various resources that can bind to any instance of the node. For example, an
 /// animation resource could be made for a specific node, but with the `instance_id` it can be retargetted to any
@@ -324,6 +332,13 @@ pub struct Base {
     #[reflect(setter = "set_visibility")]
     visibility: InheritableVariable<bool>,
 
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[reflect(setter = "set_visibility_opacity")]
+    visibility_opacity: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    opacity_fade: Option<OpacityFade>,
+
     #[reflect(
         description = "Maximum amount of Some(time) that node will \"live\" or None if the node has unlimited lifetime."
     )]
@@ -354,6 +369,12 @@ pub struct Base {
     #[reflect(setter = "set_frustum_culling")]
     frustum_culling: InheritableVariable<bool>,
 
+    #[reflect(setter = "set_occluder")]
+    occluder: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_occludee")]
+    occludee: InheritableVariable<bool>,
+
     #[reflect(hidden)]
     pub(crate) transform_modified: Cell<bool>,
 
@@ -366,6 +387,9 @@ pub struct Base {
     #[reflect(hidden)]
     pub(crate) global_visibility: Cell<bool>,
 
+    #[reflect(hidden)]
+    pub(crate) global_opacity: Cell<f32>,
+
     #[reflect(hidden)]
     pub(crate) parent: Handle<Node>,
 
@@ -408,6 +432,14 @@ pub struct Base {
 
     #[reflect(hidden)]
     pub(crate) global_enabled: Cell<bool>,
+
+    // Ticks of the graph at which global transform and global visibility (or enabled state) were
+    // changed last time. Zero means that the node was never updated.
+    #[reflect(hidden)]
+    pub(crate) transform_change_tick: Cell<u64>,
+
+    #[reflect(hidden)]
+    pub(crate) visibility_change_tick: Cell<u64>,
 }
 
 impl Drop for Base {
@@ -557,6 +589,80 @@ impl Base {
         *self.visibility
     }
 
+    /// Sets local opacity of a node in `[0; 1]` range, it allows you to fade whole objects in and
+    /// out, for example to hide geometry that blocks the view of a camera. Opacity of a node is
+    /// multiplied by the opacity of its parent, see [`Self::global_opacity`]. Objects with zero
+    /// opacity are not rendered at all. The deferred renderer does not support blending, so it uses
+    /// screen-door transparency (dithering) for partially transparent objects. Any active fade (see
+    /// [`Self::fade_to`]) is cancelled.
+    #[inline]
+    pub fn set_visibility_opacity(&mut self, opacity: f32) -> f32 {
+        self.opacity_fade = None;
+        self.visibility_opacity
+            .set_value_and_mark_modified(opacity.clamp(0.0, 1.0))
+    }
+
+    /// Returns local opacity of a node.
+    #[inline]
+    pub fn visibility_opacity(&self) -> f32 {
+        *self.visibility_opacity
+    }
+
+    /// Returns combined opacity of a node - product of the opacities of the node and every its
+    /// ancestor.
+    #[inline]
+    pub fn global_opacity(&self) -> f32 {
+        self.global_opacity.get()
+    }
+
+    /// Smoothly changes local opacity of a node to the given value in the given amount of seconds.
+    /// Could be used for spawn/despawn effects, to remove a node when it fades out, set its lifetime
+    /// to the same duration.
+    #[inline]
+    pub fn fade_to(&mut self, target_opacity: f32, duration: f32) {
+        let target = target_opacity.clamp(0.0, 1.0);
+        if duration <= 0.0 {
+            self.set_visibility_opacity(target);
+        } else {
+            self.opacity_fade = Some(OpacityFade {
+                target,
+                speed: (target - *self.visibility_opacity).abs() / duration,
+            });
+        }
+    }
+
+    /// Smoothly changes local opacity of a node to `1.0` in the given amount of seconds.
+    #[inline]
+    pub fn fade_in(&mut self, duration: f32) {
+        self.fade_to(1.0, duration)
+    }
+
+    /// Smoothly changes local opacity of a node to `0.0` in the given amount of seconds.
+    #[inline]
+    pub fn fade_out(&mut self, duration: f32) {
+        self.fade_to(0.0, duration)
+    }
+
+    /// Returns `true` if the opacity of a node is being changed by [`Self::fade_to`].
+    #[inline]
+    pub fn is_fading(&self) -> bool {
+        self.opacity_fade.is_some()
+    }
+
+    pub(crate) fn update_opacity_fade(&mut self, dt: f32) {
+        if let Some(fade) = self.opacity_fade.as_ref() {
+            let opacity = self.visibility_opacity.get_value_mut_silent();
+            let delta = fade.target - *opacity;
+            let step = fade.speed * dt;
+            if delta.abs() <= step {
+                *opacity = fade.target;
+                self.opacity_fade = None;
+            } else {
+                *opacity += step.copysign(delta);
+            }
+        }
+    }
+
     /// Returns current **local-space** bounding box. Keep in mind that this value is just
     /// a placeholder, because there is not information to calculate actual bounding box.
     #[inline]
@@ -706,6 +812,33 @@ impl Base {
             .set_value_and_mark_modified(frustum_culling)
     }
 
+    /// Returns true if the node can hide other nodes behind it when occlusion culling is enabled.
+    #[inline]
+    pub fn is_occluder(&self) -> bool {
+        *self.occluder
+    }
+
+    /// Sets whether the node can hide other nodes behind it when occlusion culling is enabled.
+    /// Large and opaque objects (walls, floors, etc.) are good occluders, small or transparent
+    /// objects should not be occluders. Returns previous value.
+    #[inline]
+    pub fn set_occluder(&mut self, occluder: bool) -> bool {
+        self.occluder.set_value_and_mark_modified(occluder)
+    }
+
+    /// Returns true if the node can be hidden by occluders when occlusion culling is enabled.
+    #[inline]
+    pub fn is_occludee(&self) -> bool {
+        *self.occludee
+    }
+
+    /// Sets whether the node can be hidden by occluders when occlusion culling is enabled. Returns
+    /// previous value.
+    #[inline]
+    pub fn set_occludee(&mut self, occludee: bool) -> bool {
+        self.occludee.set_value_and_mark_modified(occludee)
+    }
+
     /// Returns true if the node should cast shadows, false - otherwise.
     #[inline]
     pub fn cast_shadows(&self) -> bool {
@@ -848,6 +981,21 @@ impl Base {
         self.global_enabled.get()
     }
 
+    /// Returns the change tick of the graph (see [`crate::scene::graph::Graph::change_tick`]) at
+    /// which global transform of the node has changed last time. It could be used to process only
+    /// the nodes that have moved.
+    #[inline]
+    pub fn transform_change_tick(&self) -> u64 {
+        self.transform_change_tick.get()
+    }
+
+    /// Returns the change tick of the graph (see [`crate::scene::graph::Graph::change_tick`]) at
+    /// which global visibility or global enabled state of the node has changed last time.
+    #[inline]
+    pub fn visibility_change_tick(&self) -> u64 {
+        self.visibility_change_tick.get()
+    }
+
     pub(crate) fn restore_resources(&mut self, resource_manager: ResourceManager) {
         if let Some(script) = self.script.as_mut() {
             script.restore_resources(resource_manager);
@@ -906,6 +1054,9 @@ impl Visit for Base {
         self.name.visit("Name", &mut region)?;
         self.local_transform.visit("Transform", &mut region)?;
         self.visibility.visit("Visibility", &mut region)?;
+        let _ = self
+            .visibility_opacity
+            .visit("VisibilityOpacity", &mut region);
         self.parent.visit("Parent", &mut region)?;
         self.children.visit("Children", &mut region)?;
         self.resource.visit("Resource", &mut region)?;
@@ -920,6 +1071,8 @@ impl Visit for Base {
         self.tag.visit("Tag", &mut region)?;
         let _ = self.properties.visit("Properties", &mut region);
         let _ = self.frustum_culling.visit("FrustumCulling", &mut region);
+        let _ = self.occluder.visit("Occluder", &mut region);
+        let _ = self.occludee.visit("Occludee", &mut region);
         let _ = self.cast_shadows.visit("CastShadows", &mut region);
         let _ = self.instance_id.visit("InstanceId", &mut region);
         let _ = self.enabled.visit("Enabled", &mut region);
@@ -948,6 +1101,7 @@ impl Visit for Base {
 pub struct BaseBuilder {
     name: String,
     visibility: bool,
+    visibility_opacity: f32,
     local_transform: Transform,
     children: Vec<Handle<Node>>,
     lifetime: Option<f32>,
@@ -957,6 +1111,8 @@ pub struct BaseBuilder {
     inv_bind_pose_transform: Matrix4<f32>,
     tag: String,
     frustum_culling: bool,
+    occluder: bool,
+    occludee: bool,
     cast_shadows: bool,
     script: Option<Script>,
     instance_id: InstanceId,
@@ -976,6 +1132,7 @@ impl BaseBuilder {
         Self {
             name: Default::default(),
             visibility: true,
+            visibility_opacity: 1.0,
             local_transform: Default::default(),
             children: Default::default(),
             lifetime: None,
@@ -985,6 +1142,8 @@ impl BaseBuilder {
             inv_bind_pose_transform: Matrix4::identity(),
             tag: Default::default(),
             frustum_culling: true,
+            occluder: true,
+            occludee: true,
             cast_shadows: true,
             script: None,
             instance_id: InstanceId(Uuid::new_v4()),
@@ -1013,6 +1172,13 @@ impl BaseBuilder {
         self
     }
 
+    /// Sets desired local opacity, see [`Base::set_visibility_opacity`] for more info.
+    #[inline]
+    pub fn with_visibility_opacity(mut self, opacity: f32) -> Self {
+        self.visibility_opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
     /// Sets desired local transform.
     #[inline]
     pub fn with_local_transform(mut self, transform: Transform) -> Self {
@@ -1082,6 +1248,20 @@ impl BaseBuilder {
         self
     }
 
+    /// Sets whether the node can hide other nodes behind it when occlusion culling is enabled.
+    #[inline]
+    pub fn with_occluder(mut self, occluder: bool) -> Self {
+        self.occluder = occluder;
+        self
+    }
+
+    /// Sets whether the node can be hidden by occluders when occlusion culling is enabled.
+    #[inline]
+    pub fn with_occludee(mut self, occludee: bool) -> Self {
+        self.occludee = occludee;
+        self
+    }
+
     /// Sets whether mesh should cast shadows or not.
     #[inline]
     pub fn with_cast_shadows(mut self, cast_shadows: bool) -> Self {
@@ -1113,7 +1293,10 @@ impl BaseBuilder {
             local_transform: self.local_transform,
             lifetime: self.lifetime.into(),
             visibility: self.visibility.into(),
+            visibility_opacity: self.visibility_opacity.into(),
+            opacity_fade: None,
             global_visibility: Cell::new(true),
+            global_opacity: Cell::new(1.0),
             parent: Handle::NONE,
             global_transform: Cell::new(Matrix4::identity()),
             inv_bind_pose_transform: self.inv_bind_pose_transform,
@@ -1127,11 +1310,15 @@ impl BaseBuilder {
             properties: Default::default(),
             transform_modified: Cell::new(false),
             frustum_culling: self.frustum_culling.into(),
+            occluder: self.occluder.into(),
+            occludee: self.occludee.into(),
             cast_shadows: self.cast_shadows.into(),
             script: self.script,
             instance_id: InstanceId(Uuid::new_v4()),
             enabled: self.enabled.into(),
             global_enabled: Cell::new(true),
+            transform_change_tick: Cell::new(0),
+            visibility_change_tick: Cell::new(0),
         }
     }
 }
@@ -1161,11 +1348,14 @@ pub mod test {
     fn test_base_inheritance() {
         let parent = BaseBuilder::new()
             .with_visibility(false)
+            .with_visibility_opacity(0.5)
             .with_depth_offset(1.0)
             .with_tag("Tag".to_string())
             .with_name("Name")
             .with_lifetime(1.0)
             .with_frustum_culling(false)
+            .with_occluder(false)
+            .with_occludee(false)
             .with_mobility(Mobility::Static)
             .with_lod_group(LodGroup {
                 levels: vec![LevelOfDetail {
@@ -0,0 +1,541 @@
+//! Buoyancy volume is a box-shaped volume of fluid, that applies Archimedes force and drag to rigid
+//! bodies intersecting it. See [`BuoyancyVolume`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    scene::{
+        base::{Base, BaseBuilder},
+        collider::Collider,
+        graph::Graph,
+        node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
+        rigidbody::{RigidBody, RigidBodyType},
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Buoyancy volume is a box-shaped volume of fluid (water, for example), that applies Archimedes
+/// force and drag to dynamic rigid bodies intersecting it. It could be used for boats, floating
+/// debris, swimming characters, etc. The surface of the fluid is the top (local +Y) face of the
+/// volume.
+///
+/// Forces are calculated by sampling shapes of colliders of rigid bodies: every collider is split
+/// in a grid of `resolution³` cells, cells that are inside the shape and inside the volume are
+/// submerged. Every submerged cell "pushes" the body up with the force equal to the weight of the
+/// fluid displaced by the cell, the force is applied at the center of the cell, so bodies will be
+/// rotated naturally by waves of force (boats will stabilize, logs will float horizontally, etc.).
+///
+/// ## Drag
+///
+/// Submerged cells also apply linear drag, which is proportional to the velocity of the cell
+/// relative to the fluid (see [`BuoyancyVolume::set_flow_velocity`]). Angular drag slows down
+/// rotation of the bodies proportionally to the submerged fraction of their volume.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder, buoyancy::BuoyancyVolumeBuilder, graph::Graph, node::Node,
+///         transform::TransformBuilder,
+///     },
+/// };
+///
+/// fn create_lake(graph: &mut Graph) -> Handle<Node> {
+///     BuoyancyVolumeBuilder::new(
+///         BaseBuilder::new().with_local_transform(
+///             TransformBuilder::new()
+///                 .with_local_position(Vector3::new(0.0, -2.0, 0.0))
+///                 .build(),
+///         ),
+///     )
+///     .with_half_extents(Vector3::new(50.0, 2.0, 50.0))
+///     .with_density(1000.0)
+///     .build(graph)
+/// }
+/// ```
+#[derive(Debug, Clone, Visit, Reflect)]
+pub struct BuoyancyVolume {
+    base: Base,
+
+    #[reflect(setter = "set_half_extents")]
+    half_extents: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(min_value = 0.0, step = 10.0, setter = "set_density")]
+    density: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_linear_drag")]
+    linear_drag: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_angular_drag")]
+    angular_drag: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_flow_velocity")]
+    flow_velocity: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(min_value = 1.0, max_value = 16.0, setter = "set_resolution")]
+    resolution: InheritableVariable<u32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    body_cache: BodyCache,
+}
+
+// Handles of rigid bodies of a graph, it allows to not scan the whole graph every frame. The cache
+// is rebuilt when the amount of rigid bodies in the physics world changes or when a cached body
+// was deleted.
+#[derive(Debug, Default)]
+struct BodyCache {
+    bodies: Vec<Handle<Node>>,
+    physics_body_count: usize,
+    is_valid: bool,
+}
+
+// Cloned volume could be in another graph, so the cache must be rebuilt.
+impl Clone for BodyCache {
+    fn clone(&self) -> Self {
+        Default::default()
+    }
+}
+
+impl Default for BuoyancyVolume {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            half_extents: InheritableVariable::new(Vector3::new(1.0, 1.0, 1.0)),
+            density: InheritableVariable::new(1000.0),
+            linear_drag: InheritableVariable::new(1.0),
+            angular_drag: InheritableVariable::new(0.5),
+            flow_velocity: Default::default(),
+            resolution: InheritableVariable::new(4),
+            body_cache: Default::default(),
+        }
+    }
+}
+
+impl Deref for BuoyancyVolume {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for BuoyancyVolume {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for BuoyancyVolume {
+    fn type_uuid() -> Uuid {
+        uuid!("6a8ff4c2-8b5c-4b5e-a2a1-53f2c9d1f0e7")
+    }
+}
+
+impl BuoyancyVolume {
+    /// Sets half extents of the volume (in local coordinates).
+    pub fn set_half_extents(&mut self, half_extents: Vector3<f32>) -> Vector3<f32> {
+        self.half_extents.set_value_and_mark_modified(half_extents)
+    }
+
+    /// Returns half extents of the volume.
+    pub fn half_extents(&self) -> Vector3<f32> {
+        *self.half_extents
+    }
+
+    /// Sets density of the fluid in kg/m³. Default is `1000.0` (fresh water). Bodies with density
+    /// lower than the density of the fluid will float.
+    pub fn set_density(&mut self, density: f32) -> f32 {
+        self.density.set_value_and_mark_modified(density.max(0.0))
+    }
+
+    /// Returns density of the fluid.
+    pub fn density(&self) -> f32 {
+        *self.density
+    }
+
+    /// Sets linear drag coefficient. The larger the value, the faster submerged bodies will reach
+    /// the velocity of the fluid.
+    pub fn set_linear_drag(&mut self, drag: f32) -> f32 {
+        self.linear_drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns linear drag coefficient.
+    pub fn linear_drag(&self) -> f32 {
+        *self.linear_drag
+    }
+
+    /// Sets angular drag coefficient. The larger the value, the faster submerged bodies will stop
+    /// rotating.
+    pub fn set_angular_drag(&mut self, drag: f32) -> f32 {
+        self.angular_drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns angular drag coefficient.
+    pub fn angular_drag(&self) -> f32 {
+        *self.angular_drag
+    }
+
+    /// Sets velocity of the fluid in world coordinates. It could be used to simulate rivers, submerged
+    /// bodies will be dragged by the flow.
+    pub fn set_flow_velocity(&mut self, velocity: Vector3<f32>) -> Vector3<f32> {
+        self.flow_velocity.set_value_and_mark_modified(velocity)
+    }
+
+    /// Returns velocity of the fluid.
+    pub fn flow_velocity(&self) -> Vector3<f32> {
+        *self.flow_velocity
+    }
+
+    /// Sets amount of samples per axis of collider shapes. Total amount of samples per collider is
+    /// `resolution³`, so keep it low. Default is 4.
+    pub fn set_resolution(&mut self, resolution: u32) -> u32 {
+        self.resolution
+            .set_value_and_mark_modified(resolution.clamp(1, 16))
+    }
+
+    /// Returns amount of samples per axis of collider shapes.
+    pub fn resolution(&self) -> u32 {
+        *self.resolution
+    }
+
+    fn local_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_min_max(-*self.half_extents, *self.half_extents)
+    }
+
+    /// Checks whether the given world-space point is inside the volume.
+    fn is_submerged(&self, inv_transform: &Matrix4<f32>, point: &Point3<f32>) -> bool {
+        let local = inv_transform.transform_point(point);
+        let half_extents = *self.half_extents;
+        local.x.abs() <= half_extents.x
+            && local.y.abs() <= half_extents.y
+            && local.z.abs() <= half_extents.z
+    }
+}
+
+struct SubmergedSample {
+    position: Point3<f32>,
+    volume: f32,
+}
+
+impl NodeTrait for BuoyancyVolume {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_box().transform(&self.global_transform())
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let inv_transform = match self.global_transform().try_inverse() {
+            Some(inv_transform) => inv_transform,
+            None => return,
+        };
+        let world_box = self.world_bounding_box();
+        let gravity = context.physics.gravity;
+        let resolution = *self.resolution;
+
+        let physics_body_count = context.physics.body_count();
+        if !self.body_cache.is_valid || self.body_cache.physics_body_count != physics_body_count {
+            self.body_cache.bodies.clear();
+            self.body_cache.bodies.extend(
+                context
+                    .nodes
+                    .pair_iter()
+                    .filter(|(_, node)| node.cast::<RigidBody>().is_some())
+                    .map(|(handle, _)| handle),
+            );
+            self.body_cache.physics_body_count = physics_body_count;
+            self.body_cache.is_valid = true;
+        }
+
+        let mut samples = Vec::new();
+        for &body_handle in self.body_cache.bodies.iter() {
+            let body = match context
+                .nodes
+                .try_borrow(body_handle)
+                .and_then(|node| node.cast::<RigidBody>())
+            {
+                Some(body) => body,
+                None => {
+                    self.body_cache.is_valid = false;
+                    continue;
+                }
+            };
+
+            if body.body_type() != RigidBodyType::Dynamic {
+                continue;
+            }
+
+            let native_body = match context.physics.native_body(body.native.get()) {
+                Some(native_body) if !native_body.is_sleeping() => native_body,
+                _ => continue,
+            };
+
+            samples.clear();
+            let mut total_volume = 0.0;
+
+            for &child in body.children() {
+                let collider = match context.nodes[child].cast::<Collider>() {
+                    Some(collider) if !collider.is_sensor() => collider,
+                    _ => continue,
+                };
+
+                let native_collider = match context.physics.native_collider(collider.native.get()) {
+                    Some(native_collider) => native_collider,
+                    None => continue,
+                };
+
+                let aabb = native_collider.compute_aabb();
+                if !world_box.intersect_aabb(&AxisAlignedBoundingBox::from_min_max(
+                    aabb.mins.coords,
+                    aabb.maxs.coords,
+                )) {
+                    continue;
+                }
+
+                let shape = native_collider.shape();
+                let local_aabb = shape.compute_local_aabb();
+                let cell_size = local_aabb.extents() / resolution as f32;
+                let collider_volume = shape.mass_properties(1.0).mass();
+
+                let mut inside = Vec::new();
+                for i in 0..resolution {
+                    for j in 0..resolution {
+                        for k in 0..resolution {
+                            let local_point = local_aabb.mins
+                                + cell_size.component_mul(&Vector3::new(
+                                    i as f32 + 0.5,
+                                    j as f32 + 0.5,
+                                    k as f32 + 0.5,
+                                ));
+                            if shape.contains_local_point(&local_point) {
+                                inside.push(native_collider.position() * local_point);
+                            }
+                        }
+                    }
+                }
+
+                if inside.is_empty() {
+                    continue;
+                }
+
+                total_volume += collider_volume;
+                let sample_volume = collider_volume / inside.len() as f32;
+                samples.extend(
+                    inside
+                        .into_iter()
+                        .filter(|p| self.is_submerged(&inv_transform, p))
+                        .map(|position| SubmergedSample {
+                            position,
+                            volume: sample_volume,
+                        }),
+                );
+            }
+
+            if samples.is_empty() || total_volume <= 0.0 {
+                continue;
+            }
+
+            let ang_vel = *native_body.angvel();
+            let mass = native_body.mass();
+
+            let mut forces = Vec::with_capacity(samples.len());
+            let mut submerged_volume = 0.0;
+            for sample in samples.iter() {
+                let point_velocity = native_body.velocity_at_point(&sample.position);
+                let relative_velocity = point_velocity - *self.flow_velocity;
+
+                let buoyancy = -gravity * *self.density * sample.volume;
+                let drag = -relative_velocity * *self.linear_drag * *self.density * sample.volume;
+
+                let force = buoyancy + drag;
+                if force != Vector3::default() {
+                    forces.push((force, sample.position.coords));
+                }
+                submerged_volume += sample.volume;
+            }
+
+            let submerged_fraction = (submerged_volume / total_volume).min(1.0);
+            let torque = -ang_vel * *self.angular_drag * submerged_fraction * mass;
+
+            // Every change of a rigid body node forces synchronization with its native body, so
+            // bodies are touched only when there's something to apply.
+            if forces.is_empty() && torque == Vector3::default() {
+                continue;
+            }
+
+            let body = context.nodes[body_handle].as_rigid_body_mut();
+            for (force, point) in forces {
+                body.apply_force_at_point(force, point);
+            }
+            if torque != Vector3::default() {
+                body.apply_torque(torque);
+            }
+            body.wake_up();
+        }
+    }
+}
+
+/// Allows you to create buoyancy volumes in declarative manner.
+pub struct BuoyancyVolumeBuilder {
+    base_builder: BaseBuilder,
+    half_extents: Vector3<f32>,
+    density: f32,
+    linear_drag: f32,
+    angular_drag: f32,
+    flow_velocity: Vector3<f32>,
+    resolution: u32,
+}
+
+impl BuoyancyVolumeBuilder {
+    /// Creates new buoyancy volume builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            half_extents: Vector3::new(1.0, 1.0, 1.0),
+            density: 1000.0,
+            linear_drag: 1.0,
+            angular_drag: 0.5,
+            flow_velocity: Default::default(),
+            resolution: 4,
+        }
+    }
+
+    /// Sets desired half extents of the volume.
+    pub fn with_half_extents(mut self, half_extents: Vector3<f32>) -> Self {
+        self.half_extents = half_extents;
+        self
+    }
+
+    /// Sets desired density of the fluid.
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Sets desired linear drag.
+    pub fn with_linear_drag(mut self, drag: f32) -> Self {
+        self.linear_drag = drag;
+        self
+    }
+
+    /// Sets desired angular drag.
+    pub fn with_angular_drag(mut self, drag: f32) -> Self {
+        self.angular_drag = drag;
+        self
+    }
+
+    /// Sets desired flow velocity.
+    pub fn with_flow_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.flow_velocity = velocity;
+        self
+    }
+
+    /// Sets desired sampling resolution.
+    pub fn with_resolution(mut self, resolution: u32) -> Self {
+        self.resolution = resolution.clamp(1, 16);
+        self
+    }
+
+    /// Creates new buoyancy volume instance.
+    pub fn build_buoyancy_volume(self) -> BuoyancyVolume {
+        BuoyancyVolume {
+            base: self.base_builder.build_base(),
+            half_extents: self.half_extents.into(),
+            density: self.density.into(),
+            linear_drag: self.linear_drag.into(),
+            angular_drag: self.angular_drag.into(),
+            flow_velocity: self.flow_velocity.into(),
+            resolution: self.resolution.into(),
+            body_cache: Default::default(),
+        }
+    }
+
+    /// Creates new buoyancy volume node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_buoyancy_volume())
+    }
+
+    /// Creates new buoyancy volume node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            buoyancy::BuoyancyVolumeBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            rigidbody::RigidBodyBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn simulate(collider_density: f32) -> f32 {
+        let mut graph = Graph::new();
+
+        BuoyancyVolumeBuilder::new(BaseBuilder::new())
+            .with_half_extents(Vector3::new(10.0, 5.0, 10.0))
+            .build(&mut graph);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .with_density(Some(collider_density))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 4.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_mass(0.0)
+        .build(&mut graph);
+
+        for _ in 0..600 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        graph[body].global_position().y
+    }
+
+    #[test]
+    fn test_buoyancy() {
+        // A wooden box floats half-submerged at the surface of water.
+        let wood = simulate(500.0);
+        assert!((wood - 5.0).abs() < 0.25, "{}", wood);
+
+        // A metal box sinks.
+        let metal = simulate(7800.0);
+        assert!(metal < 3.0, "{}", metal);
+    }
+}
@@ -287,6 +287,93 @@ impl Default for Exposure {
     }
 }
 
+/// Bloom is a glow around bright parts of the frame.
+#[derive(Visit, Copy, Clone, PartialEq, Debug, Reflect)]
+pub struct Bloom {
+    /// Whether the effect is enabled or not. Keep in mind that the effect could be disabled
+    /// globally using [`crate::renderer::QualitySettings::use_bloom`].
+    pub enabled: bool,
+    /// Pixels with luminance greater than this value will glow. Default is 1.0.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub threshold: f32,
+    /// Brightness of the glow. Default is 1.0.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub intensity: f32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 1.0,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Vignette darkens edges of the frame.
+#[derive(Visit, Copy, Clone, PartialEq, Debug, Reflect)]
+pub struct Vignette {
+    /// Whether the effect is enabled or not.
+    pub enabled: bool,
+    /// How much the edges are darkened, in `[0; 1]` range. Default is 0.5.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub intensity: f32,
+    /// Distance from the center of the frame (in normalized coordinates) at which darkening starts.
+    /// Default is 0.5.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub radius: f32,
+    /// Width of the transition between unaffected and darkened areas. Default is 0.5.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub smoothness: f32,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.5,
+            radius: 0.5,
+            smoothness: 0.5,
+        }
+    }
+}
+
+/// Chromatic aberration splits color channels of the frame towards its edges, imitating
+/// imperfect camera lens.
+#[derive(Visit, Copy, Clone, PartialEq, Debug, Reflect)]
+pub struct ChromaticAberration {
+    /// Whether the effect is enabled or not.
+    pub enabled: bool,
+    /// Maximum offset of red and blue channels at the edges of the frame (in normalized
+    /// coordinates). Default is 0.005.
+    #[reflect(min_value = 0.0, step = 0.001)]
+    pub strength: f32,
+}
+
+impl Default for ChromaticAberration {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 0.005,
+        }
+    }
+}
+
+/// Post-processing effects of a camera. Along with [`Exposure`] and color grading (see
+/// [`Camera::set_color_grading_lut`]) they define how the final frame of the camera looks, so
+/// every camera could have its own look - for example a camera of a 3D menu could have no bloom and
+/// vignette while a gameplay camera uses them.
+#[derive(Visit, Copy, Clone, PartialEq, Debug, Reflect, Default)]
+pub struct PostProcessing {
+    /// Bloom settings.
+    pub bloom: Bloom,
+    /// Vignette settings.
+    pub vignette: Vignette,
+    /// Chromatic aberration settings.
+    pub chromatic_aberration: ChromaticAberration,
+}
+
 /// See module docs.
 #[derive(Debug, Visit, Reflect, Clone)]
 pub struct Camera {
@@ -316,6 +403,14 @@ pub struct Camera {
     #[reflect(setter = "set_color_grading_enabled")]
     color_grading_enabled: InheritableVariable<bool>,
 
+    #[visit(optional)] // Backward compatibility.
+    #[reflect(setter = "set_render_order")]
+    render_order: InheritableVariable<i32>,
+
+    #[visit(optional)] // Backward compatibility.
+    #[reflect(setter = "set_post_processing")]
+    post_processing: InheritableVariable<PostProcessing>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     view_matrix: Matrix4<f32>,
@@ -414,6 +509,29 @@ impl Camera {
         self.projection_matrix * self.view_matrix
     }
 
+    /// Returns view frustum of the camera in world coordinates. It could be used for custom
+    /// visibility tests, for example to check whether a point or a bounding box is visible from
+    /// the camera.
+    #[inline]
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from(self.view_projection_matrix()).unwrap_or_default()
+    }
+
+    /// Checks whether the node was visible from the camera during the last update of the camera. It
+    /// uses results of frustum culling (see [`VisibilityCache`]), so it is very fast and could be
+    /// used for gameplay logic, for example to move enemies only when they're not seen by the player.
+    ///
+    /// # Notes
+    ///
+    /// There is no occlusion test, a node behind a wall is still considered visible. Nodes with
+    /// disabled frustum culling are always visible, unless they're hidden. Bounding boxes of nodes
+    /// that are updated after the camera are taken from the previous update, so the result could
+    /// be one frame late.
+    #[inline]
+    pub fn is_node_visible(&self, node: Handle<Node>) -> bool {
+        self.visibility_cache.is_visible(node)
+    }
+
     /// Returns current projection matrix.
     #[inline]
     pub fn projection_matrix(&self) -> Matrix4<f32> {
@@ -510,13 +628,22 @@ impl Camera {
         (*self.environment).clone()
     }
 
-    /// Creates picking ray from given screen coordinates.
+    /// Creates picking ray from given screen coordinates. Screen coordinates must be in pixels with
+    /// origin at the left top corner of the frame of the given size (the same coordinate system
+    /// is used by the UI, so mouse position could be passed directly). Viewport of the camera is
+    /// taken into account, so the method works correctly for split-screen views too.
+    ///
+    /// The ray begins at the near clipping plane and ends at the far clipping plane, so its
+    /// direction vector is not normalized. It could be used to pick objects under the cursor,
+    /// for example for click-to-move.
     pub fn make_ray(&self, screen_coord: Vector2<f32>, screen_size: Vector2<f32>) -> Ray {
         let viewport = self.viewport_pixels(screen_size);
-        let nx = screen_coord.x / (viewport.w() as f32) * 2.0 - 1.0;
+        let nx = (screen_coord.x - viewport.x() as f32) / (viewport.w() as f32) * 2.0 - 1.0;
         // Invert y here because OpenGL has origin at left bottom corner,
         // but window coordinates starts from left *upper* corner.
-        let ny = (viewport.h() as f32 - screen_coord.y) / (viewport.h() as f32) * 2.0 - 1.0;
+        let ny = (screen_size.y - screen_coord.y - viewport.y() as f32) / (viewport.h() as f32)
+            * 2.0
+            - 1.0;
         let inv_view_proj = self
             .view_projection_matrix()
             .try_inverse()
@@ -528,7 +655,14 @@ impl Camera {
         Ray::from_two_points(begin, end)
     }
 
-    /// Projects given world space point on screen plane.
+    /// Projects given world space point on screen plane. Returned coordinates are in pixels with
+    /// origin at the left top corner of the frame of the given size, so they could be used directly
+    /// to position UI widgets over scene objects (health bars over heads, markers, etc.). Viewport
+    /// of the camera is taken into account.
+    ///
+    /// Returns `None` if the point is behind the camera or in front of the near clipping plane.
+    /// Points outside of the viewport are still projected, use [`Self::viewport_pixels`] to check
+    /// whether the point is on screen or not.
     pub fn project(
         &self,
         world_pos: Vector3<f32>,
@@ -537,12 +671,11 @@ impl Camera {
         let viewport = self.viewport_pixels(screen_size);
         let proj = self.view_projection_matrix()
             * Vector4::new(world_pos.x, world_pos.y, world_pos.z, 1.0);
-        if proj.w != 0.0 && proj.z >= 0.0 {
+        if proj.w > 0.0 && proj.z >= -proj.w {
             let k = (1.0 / proj.w) * 0.5;
             Some(Vector2::new(
                 viewport.x() as f32 + viewport.w() as f32 * (proj.x * k + 0.5),
-                viewport.h() as f32
-                    - (viewport.y() as f32 + viewport.h() as f32 * (proj.y * k + 0.5)),
+                screen_size.y - (viewport.y() as f32 + viewport.h() as f32 * (proj.y * k + 0.5)),
             ))
         } else {
             None
@@ -578,6 +711,19 @@ impl Camera {
         *self.color_grading_enabled
     }
 
+    /// Sets new render order of the camera. Enabled cameras of a scene are rendered in ascending
+    /// order, so a camera with higher order will be drawn on top of cameras with lower order if
+    /// their viewports overlap. Cameras with the same order are rendered in the order of their
+    /// appearance in the graph. This is useful for split-screen or picture-in-picture views.
+    pub fn set_render_order(&mut self, order: i32) -> i32 {
+        self.render_order.set_value_and_mark_modified(order)
+    }
+
+    /// Returns current render order of the camera. See [`Self::set_render_order`] for more info.
+    pub fn render_order(&self) -> i32 {
+        *self.render_order
+    }
+
     /// Sets new exposure. See `Exposure` struct docs for more info.
     pub fn set_exposure(&mut self, exposure: Exposure) -> Exposure {
         self.exposure.set_value_and_mark_modified(exposure)
@@ -587,6 +733,22 @@ impl Camera {
     pub fn exposure(&self) -> Exposure {
         *self.exposure
     }
+
+    /// Sets new post-processing effects. See [`PostProcessing`] docs for more info.
+    pub fn set_post_processing(&mut self, post_processing: PostProcessing) -> PostProcessing {
+        self.post_processing
+            .set_value_and_mark_modified(post_processing)
+    }
+
+    /// Returns current post-processing effects.
+    pub fn post_processing(&self) -> &PostProcessing {
+        &self.post_processing
+    }
+
+    /// Returns current post-processing effects for modification.
+    pub fn post_processing_mut(&mut self) -> &mut PostProcessing {
+        self.post_processing.get_value_mut_and_mark_modified()
+    }
 }
 
 impl NodeTrait for Camera {
@@ -634,7 +796,7 @@ impl NodeTrait for Camera {
             self.global_position(),
             self.projection().z_near(),
             self.projection().z_far(),
-            Some(&[&Frustum::from(self.view_projection_matrix()).unwrap_or_default()]),
+            Some(&[&self.frustum()]),
         );
     }
 }
@@ -838,6 +1000,8 @@ pub struct CameraBuilder {
     color_grading_lut: Option<ColorGradingLut>,
     color_grading_enabled: bool,
     projection: Projection,
+    render_order: i32,
+    post_processing: PostProcessing,
 }
 
 impl CameraBuilder {
@@ -855,7 +1019,9 @@ impl CameraBuilder {
             exposure: Exposure::Manual(std::f32::consts::E),
             color_grading_lut: None,
             color_grading_enabled: false,
+            render_order: 0,
             projection: Projection::default(),
+            post_processing: Default::default(),
         }
     }
 
@@ -913,12 +1079,24 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets desired render order. See [`Camera::set_render_order`] for more info.
+    pub fn with_render_order(mut self, order: i32) -> Self {
+        self.render_order = order;
+        self
+    }
+
     /// Sets desired exposure options.
     pub fn with_exposure(mut self, exposure: Exposure) -> Self {
         self.exposure = exposure;
         self
     }
 
+    /// Sets desired post-processing effects.
+    pub fn with_post_processing(mut self, post_processing: PostProcessing) -> Self {
+        self.post_processing = post_processing;
+        self
+    }
+
     /// Sets desired projection mode.
     pub fn with_projection(mut self, projection: Projection) -> Self {
         self.projection = projection;
@@ -942,6 +1120,8 @@ impl CameraBuilder {
             exposure: self.exposure.into(),
             color_grading_lut: self.color_grading_lut.into(),
             color_grading_enabled: self.color_grading_enabled.into(),
+            render_order: self.render_order.into(),
+            post_processing: self.post_processing.into(),
         }
     }
 
@@ -1340,3 +1520,45 @@ impl SkyBox {
         self.back.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            math::Rect,
+        },
+        scene::{base::BaseBuilder, camera::CameraBuilder},
+    };
+
+    #[test]
+    fn test_project_and_make_ray() {
+        let screen_size = Vector2::new(800.0, 600.0);
+
+        // Camera in the right half of the screen.
+        let mut camera = CameraBuilder::new(BaseBuilder::new())
+            .with_viewport(Rect::new(0.5, 0.0, 0.5, 1.0))
+            .build_camera();
+        camera.calculate_matrices(screen_size);
+
+        let point = Vector3::new(1.0, 2.0, 10.0);
+        let screen_point = camera.project(point, screen_size).unwrap();
+        assert!(screen_point.x >= 400.0 && screen_point.x <= 800.0);
+        assert!(screen_point.y >= 0.0 && screen_point.y <= 600.0);
+
+        // Point above the camera must be in the upper half of the screen.
+        assert!(screen_point.y < 300.0);
+
+        // Ray through the projected point must pass through the point.
+        let ray = camera.make_ray(screen_point, screen_size);
+        let dir = ray.dir.normalize();
+        let v = point - ray.origin;
+        let distance = (v - dir.scale(v.dot(&dir))).norm();
+        assert!(distance < 0.01);
+
+        // Points behind the camera cannot be projected.
+        assert!(camera
+            .project(Vector3::new(0.0, 0.0, -10.0), screen_size)
+            .is_none());
+    }
+}
@@ -476,6 +476,10 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[reflect(setter = "set_surface_type")]
+    #[visit(optional)] // Backward compatibility
+    pub(crate) surface_type: InheritableVariable<String>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
@@ -494,6 +498,7 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            surface_type: Default::default(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -526,6 +531,7 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            surface_type: self.surface_type.clone(),
             // Do not copy. The copy will have its own native representation (for example - Rapier's collider)
             native: Cell::new(ColliderHandle::invalid()),
         }
@@ -718,6 +724,19 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Sets the new surface type of the collider, for example `metal`, `wood`, `flesh`, etc. Surface
+    /// types are used to define responses (friction, restitution, sounds, particles) of contacts
+    /// between pairs of surfaces. Empty string means that the collider has no surface type. See
+    /// [`crate::scene::graph::surface_response::SurfaceResponseTable`] docs for more info.
+    pub fn set_surface_type(&mut self, surface_type: String) -> String {
+        self.surface_type.set_value_and_mark_modified(surface_type)
+    }
+
+    /// Returns current surface type of the collider.
+    pub fn surface_type(&self) -> &str {
+        &self.surface_type
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -746,6 +765,7 @@ impl Collider {
             || self.solver_groups.need_sync()
             || self.friction_combine_rule.need_sync()
             || self.restitution_combine_rule.need_sync()
+            || self.surface_type.need_sync()
     }
 }
 
@@ -814,6 +834,7 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    surface_type: String,
 }
 
 impl ColliderBuilder {
@@ -830,6 +851,7 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            surface_type: Default::default(),
         }
     }
 
@@ -887,6 +909,12 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets desired surface type.
+    pub fn with_surface_type(mut self, surface_type: String) -> Self {
+        self.surface_type = surface_type;
+        self
+    }
+
     /// Creates collider node, but does not add it to a graph.
     pub fn build_collider(self) -> Collider {
         Collider {
@@ -900,6 +928,7 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            surface_type: self.surface_type.into(),
             native: Cell::new(ColliderHandle::invalid()),
         }
     }
@@ -917,14 +946,18 @@ impl ColliderBuilder {
 
 #[cfg(test)]
 mod test {
-    use crate::core::{algebra::Vector2, reflect::Reflect};
+    use crate::core::{
+        algebra::{Vector2, Vector3},
+        reflect::Reflect,
+    };
     use crate::scene::collider::BitMask;
     use crate::scene::{
         base::{test::check_inheritable_properties_equality, BaseBuilder},
         collider::{Collider, ColliderBuilder, ColliderShape, InteractionGroups},
-        graph::physics::CoefficientCombineRule,
+        graph::physics::{CoefficientCombineRule, ContactEventPhase},
         graph::Graph,
         rigidbody::{RigidBodyBuilder, RigidBodyType},
+        transform::TransformBuilder,
     };
     use fyrox_core::variable::try_inherit_properties;
 
@@ -938,6 +971,7 @@ mod test {
             .with_sensor(true)
             .with_restitution_combine_rule(CoefficientCombineRule::Max)
             .with_friction_combine_rule(CoefficientCombineRule::Max)
+            .with_surface_type("metal".to_string())
             .with_collision_groups(InteractionGroups::new(BitMask(1), BitMask(2)))
             .with_solver_groups(InteractionGroups::new(BitMask(1), BitMask(2)))
             .build_node();
@@ -1013,4 +1047,71 @@ mod test {
                 .count()
         );
     }
+
+    #[test]
+    fn test_contact_events() {
+        let run = |threshold: f32, layers: BitMask| {
+            let mut graph = Graph::new();
+
+            let floor = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(5.0, 0.1, 5.0))
+                .build(&mut graph);
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[floor]))
+                .with_body_type(RigidBodyType::Static)
+                .build(&mut graph);
+
+            let cube = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(0.25, 0.25, 0.25))
+                .build(&mut graph);
+            let body = RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.0, 0.5, 0.0))
+                            .build(),
+                    )
+                    .with_children(&[cube]),
+            )
+            .with_contact_force_threshold(threshold)
+            .build(&mut graph);
+
+            graph.physics.contact_event_filter.enabled = true;
+            graph.physics.contact_event_filter.report_persist = true;
+            graph.physics.contact_event_filter.layers = layers;
+
+            let mut phases = Vec::new();
+            for i in 0..120 {
+                if i == 60 {
+                    // Throw the cube away from the floor.
+                    graph[body]
+                        .as_rigid_body_mut()
+                        .set_lin_vel(Vector3::new(0.0, 50.0, 0.0));
+                }
+                graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+                for event in graph.physics.contact_events() {
+                    assert!(event.involves(cube) && event.involves(floor));
+                    phases.push(event.phase);
+                }
+            }
+            phases
+        };
+
+        let phases = run(0.0, BitMask(u32::MAX));
+        assert_eq!(phases.first(), Some(&ContactEventPhase::Begin));
+        assert!(phases.contains(&ContactEventPhase::Persist));
+        assert_eq!(phases.last(), Some(&ContactEventPhase::End));
+        assert_eq!(
+            phases
+                .iter()
+                .filter(|p| **p == ContactEventPhase::Begin)
+                .count(),
+            1
+        );
+
+        // Cube is too light to exceed the threshold.
+        assert!(run(1000.0, BitMask(u32::MAX)).is_empty());
+
+        // Colliders are not in the layers.
+        assert!(run(0.0, BitMask(0)).is_empty());
+    }
 }
@@ -21,6 +21,32 @@ pub struct Line {
     pub color: Color,
 }
 
+/// Colored line between two points that has constant width in screen space. Width of standard lines
+/// is always one pixel, because wide lines are unreliable on most platforms. Wide lines are drawn
+/// as screen-aligned quads instead.
+#[derive(Clone, Debug)]
+pub struct WideLine {
+    /// Beginning of the line.
+    pub begin: Vector3<f32>,
+    /// End of the line.
+    pub end: Vector3<f32>,
+    /// Color of the line.
+    pub color: Color,
+    /// Width of the line in pixels.
+    pub width: f32,
+}
+
+/// Colored round point that has constant size in screen space.
+#[derive(Clone, Debug)]
+pub struct Point {
+    /// Position of the point.
+    pub position: Vector3<f32>,
+    /// Color of the point.
+    pub color: Color,
+    /// Diameter of the point in pixels.
+    pub size: f32,
+}
+
 /// Drawing context for simple graphics, it allows you to draw simple figures using a set of lines. Most
 /// common use of the context is to draw some debug geometry in your game, draw physics info (contacts,
 /// meshes, shapes, etc.), draw temporary geometry in editor and so on.
@@ -65,10 +91,20 @@ pub struct Line {
 ///
 /// The engine renders the entire set of lines in a single draw call, so it very fast - you should be able to draw
 /// up to few millions of lines without any significant performance issues.
+///
+/// # Wide lines and points
+///
+/// Standard lines are always one pixel wide, which makes dense debug data hard to read. Use [`WideLine`] and
+/// [`Point`] primitives to draw lines and points with constant size in screen space. Wide lines and points
+/// are drawn in one additional draw call.
 #[derive(Default, Clone, Debug)]
 pub struct SceneDrawingContext {
     /// List of lines to draw.
     pub lines: Vec<Line>,
+    /// List of wide lines to draw.
+    pub wide_lines: Vec<WideLine>,
+    /// List of points to draw.
+    pub points: Vec<Point>,
 }
 
 impl rapier2d::pipeline::DebugRenderBackend for SceneDrawingContext {
@@ -1044,9 +1080,42 @@ impl SceneDrawingContext {
         self.lines.push(line);
     }
 
-    /// Removes all lines from internal buffer. For dynamic drawing you should call it
-    /// every update tick of your application.
+    /// Adds single wide line into internal buffer.
+    pub fn add_wide_line(&mut self, line: WideLine) {
+        self.wide_lines.push(line);
+    }
+
+    /// Adds single point into internal buffer.
+    pub fn add_point(&mut self, point: Point) {
+        self.points.push(point);
+    }
+
+    /// Draws a set of points with the same color and size.
+    pub fn draw_points(&mut self, positions: &[Vector3<f32>], color: Color, size: f32) {
+        self.points.extend(positions.iter().map(|&position| Point {
+            position,
+            color,
+            size,
+        }));
+    }
+
+    /// Draws a wide polyline through the given points.
+    pub fn draw_wide_polyline(&mut self, points: &[Vector3<f32>], color: Color, width: f32) {
+        for segment in points.windows(2) {
+            self.add_wide_line(WideLine {
+                begin: segment[0],
+                end: segment[1],
+                color,
+                width,
+            });
+        }
+    }
+
+    /// Removes all lines (including wide ones) and points from internal buffer. For dynamic drawing
+    /// you should call it every update tick of your application.
     pub fn clear_lines(&mut self) {
-        self.lines.clear()
+        self.lines.clear();
+        self.wide_lines.clear();
+        self.points.clear();
     }
 }
@@ -33,6 +33,9 @@ use std::ops::{Deref, DerefMut};
 /// can be rotated as any other scene node. Its final size and orientation is defined by the chain of
 /// transformations of parent nodes.
 ///
+/// The texture is projected along local Y axis of the decal. Surfaces that are almost parallel to the
+/// projection axis are smoothly faded out, so the decal won't be stretched over the sides of objects.
+///
 /// # Masking
 ///
 /// Often you need to ensure that decal will be applied only on desired surfaces. For example a crack on the wall
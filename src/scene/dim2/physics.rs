@@ -22,7 +22,9 @@ use crate::{
         debug::SceneDrawingContext,
         dim2::{self, collider::ColliderShape, joint::JointParams, rigidbody::ApplyAction},
         graph::{
-            physics::{FeatureId, IntegrationParameters, PhysicsPerformanceStatistics},
+            physics::{
+                FeatureId, IntegrationParameters, PhysicsPerformanceStatistics, PhysicsSettings,
+            },
             NodePool,
         },
         node::{Node, NodeTrait},
@@ -295,6 +297,16 @@ pub struct PhysicsWorld {
     /// A set of parameters that define behavior of every rigid body.
     pub integration_parameters: IntegrationParameters,
 
+    /// Sub-stepping, sleeping and solver settings of the simulation. See [`PhysicsSettings`] docs
+    /// for more info.
+    #[visit(optional)] // Backward compatibility
+    pub settings: PhysicsSettings,
+
+    // Settings, that were applied to rigid bodies last time.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    applied_settings: PhysicsSettings,
+
     /// Current gravity vector. Default is (0.0, -9.81)
     pub gravity: Vector2<f32>,
 
@@ -376,6 +388,11 @@ fn calculate_local_frames(
     )
 }
 
+fn apply_sleep_thresholds(settings: &PhysicsSettings, activation: &mut RigidBodyActivation) {
+    activation.linear_threshold = settings.linear_sleep_threshold;
+    activation.angular_threshold = settings.angular_sleep_threshold;
+}
+
 fn u32_to_group(v: u32) -> rapier2d::geometry::Group {
     rapier2d::geometry::Group::from_bits(v).unwrap_or_else(rapier2d::geometry::Group::all)
 }
@@ -388,6 +405,8 @@ impl PhysicsWorld {
             pipeline: PhysicsPipeline::new(),
             gravity: Vector2::new(0.0, -9.81),
             integration_parameters: IntegrationParameters::default(),
+            settings: Default::default(),
+            applied_settings: Default::default(),
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
@@ -419,8 +438,12 @@ impl PhysicsWorld {
         let time = instant::Instant::now();
 
         if self.enabled {
+            self.apply_settings();
+
+            let substeps = self.settings.substeps.max(1);
+
             let integration_parameters = rapier2d::dynamics::IntegrationParameters {
-                dt: self.integration_parameters.dt.unwrap_or(dt),
+                dt: self.integration_parameters.dt.unwrap_or(dt) / substeps as f32,
                 min_ccd_dt: self.integration_parameters.min_ccd_dt,
                 erp: self.integration_parameters.erp,
                 damping_ratio: self.integration_parameters.damping_ratio,
@@ -429,16 +452,9 @@ impl PhysicsWorld {
                 allowed_linear_error: self.integration_parameters.allowed_linear_error,
                 max_penetration_correction: self.integration_parameters.max_penetration_correction,
                 prediction_distance: self.integration_parameters.prediction_distance,
-                max_velocity_iterations: self.integration_parameters.max_velocity_iterations
-                    as usize,
-                max_velocity_friction_iterations: self
-                    .integration_parameters
-                    .max_velocity_friction_iterations
-                    as usize,
-                max_stabilization_iterations: self
-                    .integration_parameters
-                    .max_stabilization_iterations
-                    as usize,
+                max_velocity_iterations: self.settings.velocity_iterations as usize,
+                max_velocity_friction_iterations: self.settings.friction_iterations as usize,
+                max_stabilization_iterations: self.settings.stabilization_iterations as usize,
                 interleave_restitution_and_friction_resolution: self
                     .integration_parameters
                     .interleave_restitution_and_friction_resolution,
@@ -446,25 +462,42 @@ impl PhysicsWorld {
                 max_ccd_substeps: self.integration_parameters.max_ccd_substeps as usize,
             };
 
-            self.pipeline.step(
-                &self.gravity,
-                &integration_parameters,
-                &mut self.islands,
-                &mut self.broad_phase,
-                &mut self.narrow_phase,
-                &mut self.bodies.set,
-                &mut self.colliders.set,
-                &mut self.joints.set,
-                &mut self.multibody_joints.set,
-                &mut self.ccd_solver,
-                &(),
-                &*self.event_handler,
-            );
+            for _ in 0..substeps {
+                self.pipeline.step(
+                    &self.gravity,
+                    &integration_parameters,
+                    &mut self.islands,
+                    &mut self.broad_phase,
+                    &mut self.narrow_phase,
+                    &mut self.bodies.set,
+                    &mut self.colliders.set,
+                    &mut self.joints.set,
+                    &mut self.multibody_joints.set,
+                    &mut self.ccd_solver,
+                    &(),
+                    &*self.event_handler,
+                );
+            }
         }
 
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
+    fn apply_settings(&mut self) {
+        if self.applied_settings == self.settings {
+            return;
+        }
+
+        for (_, body) in self.bodies.set.iter_mut() {
+            // Negative thresholds mean that the body cannot sleep.
+            if body.activation().linear_threshold >= 0.0 {
+                apply_sleep_thresholds(&self.settings, body.activation_mut());
+            }
+        }
+
+        self.applied_settings = self.settings;
+    }
+
     pub(crate) fn add_body(&mut self, owner: Handle<Node>, body: RigidBody) -> RigidBodyHandle {
         let handle = self.bodies.set.insert(body);
         self.bodies.map.insert(handle, owner);
@@ -703,13 +736,11 @@ impl PhysicsWorld {
                     rigid_body_node
                         .ccd_enabled
                         .try_sync_model(|v| native.enable_ccd(v));
+                    let settings = &self.settings;
                     rigid_body_node.can_sleep.try_sync_model(|v| {
-                        let mut activation = native.activation_mut();
+                        let activation = native.activation_mut();
                         if v {
-                            activation.linear_threshold =
-                                RigidBodyActivation::default_linear_threshold();
-                            activation.angular_threshold =
-                                RigidBodyActivation::default_angular_threshold();
+                            apply_sleep_thresholds(settings, activation);
                         } else {
                             activation.sleeping = false;
                             activation.linear_threshold = -1.0;
@@ -774,6 +805,9 @@ impl PhysicsWorld {
             }
 
             let mut body = builder.build();
+            if rigid_body_node.is_can_sleep() {
+                apply_sleep_thresholds(&self.settings, body.activation_mut());
+            }
 
             body.set_enabled_rotations(
                 !rigid_body_node.is_rotation_locked(),
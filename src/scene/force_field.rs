@@ -0,0 +1,497 @@
+//! Force field is a spherical volume that pushes rigid bodies and particles inside it. See
+//! [`ForceField`] docs for more info.
+
+use crate::{
+    asset::ResourceState,
+    core::{
+        algebra::Vector3,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    resource::curve::CurveResource,
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
+        particle_system::ParticleSystem,
+        rigidbody::{RigidBody, RigidBodyType},
+    },
+};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines direction of the force of a force field.
+#[derive(
+    Copy, Clone, Debug, Reflect, Visit, PartialEq, Eq, Hash, AsRefStr, EnumString, EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum ForceFieldKind {
+    /// Wind-like field, pushes everything along look vector (local +Z axis) of the field.
+    Directional = 0,
+    /// Explosion-like field, pushes everything away from the center of the field. Negative
+    /// strength turns the field into an attractor.
+    Radial = 1,
+    /// Whirlwind-like field, pushes everything around up vector (local +Y axis) of the field.
+    Vortex = 2,
+}
+
+impl Default for ForceFieldKind {
+    fn default() -> Self {
+        Self::Directional
+    }
+}
+
+/// Defines how the strength of a force field decreases with distance from its center.
+#[derive(
+    Copy, Clone, Debug, Reflect, Visit, PartialEq, Eq, Hash, AsRefStr, EnumString, EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum ForceFieldFalloff {
+    /// Strength is the same in the whole volume.
+    Constant = 0,
+    /// Strength decreases linearly to zero at the boundary of the volume.
+    Linear = 1,
+    /// Strength decreases quadratically to zero at the boundary of the volume.
+    Quadratic = 2,
+}
+
+impl Default for ForceFieldFalloff {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl ForceFieldFalloff {
+    fn factor(self, t: f32) -> f32 {
+        match self {
+            ForceFieldFalloff::Constant => 1.0,
+            ForceFieldFalloff::Linear => 1.0 - t,
+            ForceFieldFalloff::Quadratic => (1.0 - t) * (1.0 - t),
+        }
+    }
+}
+
+/// Force field is a spherical volume that pushes dynamic rigid bodies and particles of particle
+/// systems inside it. It could be used to simulate wind, explosions, whirlwinds, magnets, etc.
+///
+/// ## Strength
+///
+/// Strength of a field defines acceleration (in m/s²) of objects in the center of the field, which
+/// means that every body will be affected in the same way regardless of its mass. Strength decreases
+/// with distance from the center of the field, the law is defined either by [`ForceFieldFalloff`]
+/// or by an optional falloff curve. The curve is sampled in `[0; 1]` range, where `0` is the center
+/// of the field and `1` is its boundary.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{
+///         base::BaseBuilder,
+///         force_field::{ForceFieldBuilder, ForceFieldKind},
+///         graph::Graph,
+///         node::Node,
+///     },
+/// };
+///
+/// fn create_explosion(graph: &mut Graph) -> Handle<Node> {
+///     ForceFieldBuilder::new(BaseBuilder::new().with_lifetime(0.1))
+///         .with_kind(ForceFieldKind::Radial)
+///         .with_radius(5.0)
+///         .with_strength(300.0)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Clone, Visit, Reflect)]
+pub struct ForceField {
+    base: Base,
+
+    #[reflect(setter = "set_kind")]
+    kind: InheritableVariable<ForceFieldKind>,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_radius")]
+    radius: InheritableVariable<f32>,
+
+    #[reflect(step = 0.1, setter = "set_strength")]
+    strength: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_falloff")]
+    falloff: InheritableVariable<ForceFieldFalloff>,
+
+    #[reflect(setter = "set_falloff_curve")]
+    falloff_curve: InheritableVariable<Option<CurveResource>>,
+
+    #[reflect(setter = "set_affect_rigid_bodies")]
+    affect_rigid_bodies: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_affect_particles")]
+    affect_particles: InheritableVariable<bool>,
+}
+
+impl Default for ForceField {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            kind: Default::default(),
+            radius: InheritableVariable::new(5.0),
+            strength: InheritableVariable::new(10.0),
+            falloff: Default::default(),
+            falloff_curve: Default::default(),
+            affect_rigid_bodies: InheritableVariable::new(true),
+            affect_particles: InheritableVariable::new(true),
+        }
+    }
+}
+
+impl Deref for ForceField {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for ForceField {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for ForceField {
+    fn type_uuid() -> Uuid {
+        uuid!("c1b8f5a4-3f0e-4d5b-9b0c-7e2a6d4f8a13")
+    }
+}
+
+impl ForceField {
+    /// Sets new kind of the field.
+    pub fn set_kind(&mut self, kind: ForceFieldKind) -> ForceFieldKind {
+        self.kind.set_value_and_mark_modified(kind)
+    }
+
+    /// Returns current kind of the field.
+    pub fn kind(&self) -> ForceFieldKind {
+        *self.kind
+    }
+
+    /// Sets new radius of the field.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.max(0.0))
+    }
+
+    /// Returns current radius of the field.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets new strength of the field (acceleration in the center of the field).
+    pub fn set_strength(&mut self, strength: f32) -> f32 {
+        self.strength.set_value_and_mark_modified(strength)
+    }
+
+    /// Returns current strength of the field.
+    pub fn strength(&self) -> f32 {
+        *self.strength
+    }
+
+    /// Sets new falloff of the field. It is ignored if falloff curve is set.
+    pub fn set_falloff(&mut self, falloff: ForceFieldFalloff) -> ForceFieldFalloff {
+        self.falloff.set_value_and_mark_modified(falloff)
+    }
+
+    /// Returns current falloff of the field.
+    pub fn falloff(&self) -> ForceFieldFalloff {
+        *self.falloff
+    }
+
+    /// Sets new falloff curve of the field. The curve overrides [`ForceFieldFalloff`] when it is
+    /// set and loaded.
+    pub fn set_falloff_curve(&mut self, curve: Option<CurveResource>) -> Option<CurveResource> {
+        self.falloff_curve.set_value_and_mark_modified(curve)
+    }
+
+    /// Returns current falloff curve of the field.
+    pub fn falloff_curve(&self) -> Option<CurveResource> {
+        (*self.falloff_curve).clone()
+    }
+
+    /// Defines whether the field should affect dynamic rigid bodies or not.
+    pub fn set_affect_rigid_bodies(&mut self, affect: bool) -> bool {
+        self.affect_rigid_bodies.set_value_and_mark_modified(affect)
+    }
+
+    /// Returns `true` if the field affects dynamic rigid bodies, `false` - otherwise.
+    pub fn is_affecting_rigid_bodies(&self) -> bool {
+        *self.affect_rigid_bodies
+    }
+
+    /// Defines whether the field should affect particles of particle systems or not.
+    pub fn set_affect_particles(&mut self, affect: bool) -> bool {
+        self.affect_particles.set_value_and_mark_modified(affect)
+    }
+
+    /// Returns `true` if the field affects particles of particle systems, `false` - otherwise.
+    pub fn is_affecting_particles(&self) -> bool {
+        *self.affect_particles
+    }
+
+    /// Calculates acceleration at the given point in world coordinates. Returns `None` if the
+    /// point is outside of the field.
+    pub fn acceleration_at(&self, point: Vector3<f32>) -> Option<Vector3<f32>> {
+        let center = self.global_position();
+        let offset = point - center;
+        let distance = offset.norm();
+        let radius = *self.radius;
+        if distance > radius {
+            return None;
+        }
+
+        let t = if radius > 0.0 { distance / radius } else { 0.0 };
+        let factor = match *self.falloff_curve {
+            Some(ref curve) => match *curve.state() {
+                ResourceState::Ok(ref data) => data.curve.value_at(t),
+                _ => self.falloff.factor(t),
+            },
+            None => self.falloff.factor(t),
+        };
+
+        let direction = match *self.kind {
+            ForceFieldKind::Directional => self.look_vector().try_normalize(f32::EPSILON)?,
+            ForceFieldKind::Radial => offset.try_normalize(f32::EPSILON)?,
+            ForceFieldKind::Vortex => self
+                .up_vector()
+                .cross(&offset)
+                .try_normalize(f32::EPSILON)?,
+        };
+
+        Some(direction.scale(*self.strength * factor))
+    }
+}
+
+impl NodeTrait for ForceField {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let radius = *self.radius;
+        AxisAlignedBoundingBox::from_min_max(
+            Vector3::new(-radius, -radius, -radius),
+            Vector3::new(radius, radius, radius),
+        )
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager.clone());
+
+        let mut state = resource_manager.state();
+        let curve_container = &mut state.containers_mut().curves;
+        curve_container.try_restore_inheritable_resource(&mut self.falloff_curve);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        if *self.strength == 0.0 {
+            return;
+        }
+
+        let dt = context.dt;
+
+        for node in context.nodes.iter_mut() {
+            if let Some(body) = node.cast_mut::<RigidBody>() {
+                if !*self.affect_rigid_bodies || body.body_type() != RigidBodyType::Dynamic {
+                    continue;
+                }
+
+                if let Some(acceleration) = self.acceleration_at(body.global_position()) {
+                    let mass = context
+                        .physics
+                        .native_body(body.native.get())
+                        .map_or(body.mass(), |native| native.mass());
+                    body.apply_force(acceleration.scale(mass));
+                    body.wake_up();
+                }
+            } else if let Some(particle_system) = node.cast_mut::<ParticleSystem>() {
+                if *self.affect_particles && particle_system.is_playing() {
+                    particle_system.apply_acceleration_field(dt, |p| self.acceleration_at(p));
+                }
+            }
+        }
+    }
+}
+
+/// Allows you to create force fields in declarative manner.
+pub struct ForceFieldBuilder {
+    base_builder: BaseBuilder,
+    kind: ForceFieldKind,
+    radius: f32,
+    strength: f32,
+    falloff: ForceFieldFalloff,
+    falloff_curve: Option<CurveResource>,
+    affect_rigid_bodies: bool,
+    affect_particles: bool,
+}
+
+impl ForceFieldBuilder {
+    /// Creates new force field builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            kind: Default::default(),
+            radius: 5.0,
+            strength: 10.0,
+            falloff: Default::default(),
+            falloff_curve: None,
+            affect_rigid_bodies: true,
+            affect_particles: true,
+        }
+    }
+
+    /// Sets desired kind of the field.
+    pub fn with_kind(mut self, kind: ForceFieldKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets desired radius of the field.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired strength of the field.
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Sets desired falloff of the field.
+    pub fn with_falloff(mut self, falloff: ForceFieldFalloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Sets desired falloff curve of the field.
+    pub fn with_falloff_curve(mut self, curve: Option<CurveResource>) -> Self {
+        self.falloff_curve = curve;
+        self
+    }
+
+    /// Defines whether the field should affect dynamic rigid bodies or not.
+    pub fn with_affect_rigid_bodies(mut self, affect: bool) -> Self {
+        self.affect_rigid_bodies = affect;
+        self
+    }
+
+    /// Defines whether the field should affect particles of particle systems or not.
+    pub fn with_affect_particles(mut self, affect: bool) -> Self {
+        self.affect_particles = affect;
+        self
+    }
+
+    /// Creates new force field instance.
+    pub fn build_force_field(self) -> ForceField {
+        ForceField {
+            base: self.base_builder.build_base(),
+            kind: self.kind.into(),
+            radius: self.radius.into(),
+            strength: self.strength.into(),
+            falloff: self.falloff.into(),
+            falloff_curve: self.falloff_curve.into(),
+            affect_rigid_bodies: self.affect_rigid_bodies.into(),
+            affect_particles: self.affect_particles.into(),
+        }
+    }
+
+    /// Creates new force field node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_force_field())
+    }
+
+    /// Creates new force field node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            force_field::{ForceFieldBuilder, ForceFieldFalloff, ForceFieldKind},
+            graph::Graph,
+            rigidbody::RigidBodyBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_acceleration_at() {
+        let mut graph = Graph::new();
+        let field = ForceFieldBuilder::new(BaseBuilder::new())
+            .with_kind(ForceFieldKind::Radial)
+            .with_radius(2.0)
+            .with_strength(10.0)
+            .with_falloff(ForceFieldFalloff::Linear)
+            .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let field = graph[field]
+            .query_component_ref::<super::ForceField>()
+            .unwrap();
+        assert_eq!(
+            field.acceleration_at(Vector3::new(1.0, 0.0, 0.0)),
+            Some(Vector3::new(5.0, 0.0, 0.0))
+        );
+        assert_eq!(field.acceleration_at(Vector3::new(3.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_wind_pushes_body() {
+        let mut graph = Graph::new();
+
+        ForceFieldBuilder::new(BaseBuilder::new())
+            .with_kind(ForceFieldKind::Directional)
+            .with_falloff(ForceFieldFalloff::Constant)
+            .with_radius(100.0)
+            .with_strength(5.0)
+            .build(&mut graph);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.0, 1.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_gravity_scale(0.0)
+        .build(&mut graph);
+
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let velocity = graph[body].as_rigid_body().lin_vel();
+        assert!(velocity.z > 4.0 && velocity.z < 6.0, "{:?}", velocity);
+    }
+}
@@ -25,9 +25,9 @@
 use crate::{
     asset::ResourceState,
     core::{
-        algebra::{Matrix4, Rotation3, UnitQuaternion, Vector2, Vector3},
+        algebra::{Matrix4, Point3, Rotation3, UnitQuaternion, Vector2, Vector3},
         instant,
-        math::Matrix4Ext,
+        math::{aabb::AxisAlignedBoundingBox, frustum::Frustum, m4x4_approx_eq, Matrix4Ext},
         pool::{Handle, MultiBorrowContext, Pool, Ticket},
         reflect::prelude::*,
         variable::try_inherit_properties,
@@ -36,6 +36,7 @@ use crate::{
     resource::model::{Model, NodeMapping},
     scene::{
         self,
+        animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
         base::NodeScriptMessage,
         camera::Camera,
         dim2::{self},
@@ -43,10 +44,12 @@ use crate::{
             event::{GraphEvent, GraphEventBroadcaster},
             map::NodeHandleMap,
             physics::{PhysicsPerformanceStatistics, PhysicsWorld},
+            raycast::GeometryRayCastCache,
         },
         mesh::Mesh,
         node::{container::NodeContainer, Node, SyncContext, UpdateContext},
         pivot::Pivot,
+        rigidbody::RigidBody,
         sound::context::SoundContext,
         transform::TransformBuilder,
     },
@@ -65,6 +68,8 @@ use std::{
 pub mod event;
 pub mod map;
 pub mod physics;
+pub mod raycast;
+pub mod surface_response;
 
 /// Graph performance statistics. Allows you to find out "hot" parts of the scene graph, which
 /// parts takes the most time to update.
@@ -86,6 +91,13 @@ pub struct GraphPerformanceStatistics {
 
     /// A time which was required to render sounds.
     pub sound_update_time: Duration,
+
+    /// Amount of time that was needed to update every node of the graph (including animations).
+    pub node_update_time: Duration,
+
+    /// Amount of time that was needed to update animation players and animation blending state
+    /// machines. It is a part of [`Self::node_update_time`].
+    pub animation_time: Duration,
 }
 
 impl GraphPerformanceStatistics {
@@ -96,12 +108,40 @@ impl GraphPerformanceStatistics {
             + self.physics.total()
             + self.physics2d.total()
             + self.sound_update_time
+            + self.node_update_time
     }
 }
 
 /// A helper type alias for node pool.
 pub type NodePool = Pool<Node, NodeContainer>;
 
+/// Observer is a point of view of an enabled camera of the graph. Observers are collected before updating nodes
+/// and could be used by nodes to do level-of-detail decisions (see [`UpdateContext::observers`]).
+#[derive(Clone, Debug, Default)]
+pub struct Observer {
+    /// Position of the observer in world coordinates.
+    pub position: Vector3<f32>,
+    /// View frustum of the observer. It is taken from the previous frame, and it could be `None` if the camera
+    /// wasn't updated yet.
+    pub frustum: Option<Frustum>,
+}
+
+impl Observer {
+    /// Checks whether the given sphere (in world coordinates) could be seen by the observer.
+    pub fn is_sphere_visible(&self, center: Vector3<f32>, radius: f32) -> bool {
+        self.frustum
+            .as_ref()
+            .map_or(true, |frustum| frustum.is_intersects_sphere(center, radius))
+    }
+
+    /// Checks whether the given bounding box (in world coordinates) could be seen by the observer.
+    pub fn is_aabb_visible(&self, aabb: &AxisAlignedBoundingBox) -> bool {
+        self.frustum
+            .as_ref()
+            .map_or(true, |frustum| frustum.is_intersects_aabb(aabb))
+    }
+}
+
 /// See module docs.
 #[derive(Debug, Reflect)]
 pub struct Graph {
@@ -136,6 +176,15 @@ pub struct Graph {
     pub(crate) script_message_sender: Sender<NodeScriptMessage>,
     #[reflect(hidden)]
     pub(crate) script_message_receiver: Receiver<NodeScriptMessage>,
+
+    #[reflect(hidden)]
+    pub(crate) ray_cast_cache: GeometryRayCastCache,
+
+    #[reflect(hidden)]
+    observers: Vec<Observer>,
+
+    #[reflect(hidden)]
+    change_tick: u64,
 }
 
 impl Default for Graph {
@@ -152,7 +201,10 @@ impl Default for Graph {
             performance_statistics: Default::default(),
             event_broadcaster: Default::default(),
             script_message_receiver: rx,
+            ray_cast_cache: Default::default(),
             script_message_sender: tx,
+            observers: Default::default(),
+            change_tick: 0,
         }
     }
 }
@@ -263,7 +315,10 @@ impl Graph {
             performance_statistics: Default::default(),
             event_broadcaster: Default::default(),
             script_message_receiver: rx,
+            ray_cast_cache: Default::default(),
             script_message_sender: tx,
+            observers: Default::default(),
+            change_tick: 0,
         }
     }
 
@@ -272,6 +327,10 @@ impl Graph {
     /// to root node of graph, it is required because graph can contain only one root.
     #[inline]
     pub fn add_node(&mut self, mut node: Node) -> Handle<Node> {
+        // New nodes must be treated as changed.
+        node.transform_change_tick.set(0);
+        node.visibility_change_tick.set(0);
+
         let children = node.children.clone();
         node.children.clear();
         let has_script = node.script.is_some();
@@ -322,6 +381,25 @@ impl Graph {
         self.pool.borrow_four_mut(nodes)
     }
 
+    /// Tries to borrow mutable references to two nodes at the same time. Returns `None` if any of
+    /// the handles is invalid or if the handles point to the same node.
+    pub fn try_get_pair_mut(
+        &mut self,
+        first: Handle<Node>,
+        second: Handle<Node>,
+    ) -> Option<(&mut Node, &mut Node)> {
+        self.pool.try_borrow_two_mut((first, second))
+    }
+
+    /// Tries to borrow mutable references to `N` nodes at the same time. Returns `None` if any of
+    /// the handles is invalid or if some handles point to the same node.
+    pub fn try_get_many_mut<const N: usize>(
+        &mut self,
+        handles: [Handle<Node>; N],
+    ) -> Option<[&mut Node; N]> {
+        self.pool.try_borrow_many_mut(handles)
+    }
+
     /// Returns root node of current graph.
     pub fn get_root(&self) -> Handle<Node> {
         self.root
@@ -959,6 +1037,9 @@ impl Graph {
     /// on each frame. However there is one use case - when you setup complex hierarchy and
     /// need to know global transform of nodes before entering update loop, then you can call
     /// this method.
+    ///
+    /// Every call advances the change tick of the graph (see [`Self::change_tick`]). Nodes, whose
+    /// global transform or global visibility have changed, are marked with the new tick.
     pub fn update_hierarchical_data(&mut self) {
         fn update_recursively(
             nodes: &NodePool,
@@ -966,52 +1047,177 @@ impl Graph {
             physics: &mut PhysicsWorld,
             physics2d: &mut dim2::physics::PhysicsWorld,
             node_handle: Handle<Node>,
+            tick: u64,
         ) {
             let node = &nodes[node_handle];
 
-            let (parent_global_transform, parent_visibility, parent_enabled) =
+            let (parent_global_transform, parent_visibility, parent_opacity, parent_enabled) =
                 if let Some(parent) = nodes.try_borrow(node.parent()) {
                     (
                         parent.global_transform(),
                         parent.global_visibility(),
+                        parent.global_opacity(),
                         parent.is_globally_enabled(),
                     )
                 } else {
-                    (Matrix4::identity(), true, true)
+                    (Matrix4::identity(), true, 1.0, true)
                 };
 
             let new_global_transform = parent_global_transform * node.local_transform().matrix();
 
-            // TODO: Detect changes from user code here.
-            node.sync_transform(
-                &new_global_transform,
-                &mut SyncContext {
-                    nodes,
-                    physics,
-                    physics2d,
-                    sound_context,
-                },
-            );
-
-            node.global_transform.set(new_global_transform);
-            node.global_visibility
-                .set(parent_visibility && node.visibility());
-            node.global_enabled.set(parent_enabled && node.is_enabled());
+            // Nodes that were never updated are considered changed.
+            if node.transform_change_tick.get() == 0
+                || !m4x4_approx_eq(&new_global_transform, &node.global_transform())
+            {
+                node.sync_transform(
+                    &new_global_transform,
+                    &mut SyncContext {
+                        nodes,
+                        physics,
+                        physics2d,
+                        sound_context,
+                    },
+                );
+
+                node.global_transform.set(new_global_transform);
+                node.transform_change_tick.set(tick);
+            }
+
+            let new_global_visibility = parent_visibility && node.visibility();
+            let new_global_enabled = parent_enabled && node.is_enabled();
+            if node.visibility_change_tick.get() == 0
+                || new_global_visibility != node.global_visibility()
+                || new_global_enabled != node.is_globally_enabled()
+            {
+                node.global_visibility.set(new_global_visibility);
+                node.global_enabled.set(new_global_enabled);
+                node.visibility_change_tick.set(tick);
+            }
+
+            node.global_opacity
+                .set(parent_opacity * node.visibility_opacity());
 
             for &child in node.children() {
-                update_recursively(nodes, sound_context, physics, physics2d, child);
+                update_recursively(nodes, sound_context, physics, physics2d, child, tick);
             }
         }
 
+        self.change_tick += 1;
+
         update_recursively(
             &self.pool,
             &mut self.sound_context,
             &mut self.physics,
             &mut self.physics2d,
             self.root,
+            self.change_tick,
         );
     }
 
+    /// Returns current change tick of the graph. The tick is advanced by every
+    /// [`Self::update_hierarchical_data`] call (normally once per frame). It could be used together
+    /// with [`Base::transform_change_tick`] and [`Base::visibility_change_tick`] to process only the
+    /// nodes that have changed since the last time a system has processed them:
+    ///
+    /// ```rust
+    /// use fyrox::scene::graph::Graph;
+    ///
+    /// struct MySystem {
+    ///     last_tick: u64,
+    /// }
+    ///
+    /// impl MySystem {
+    ///     fn update(&mut self, graph: &Graph) {
+    ///         for node in graph.linear_iter() {
+    ///             if node.transform_change_tick() > self.last_tick {
+    ///                 // The node has moved since the last update.
+    ///             }
+    ///         }
+    ///         self.last_tick = graph.change_tick();
+    ///     }
+    /// }
+    /// ```
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick
+    }
+
+    fn record_interpolation_states(&self) {
+        for node in self.pool.iter() {
+            if let Some(rigid_body) = node.cast::<RigidBody>() {
+                rigid_body.record_interpolation_state();
+            }
+        }
+    }
+
+    /// Interpolates (or extrapolates) global transforms of rigid bodies (and their descendants)
+    /// between two last fixed updates. `alpha` is a fraction of the fixed time step, that has passed
+    /// since the last fixed update. It must be called right before rendering, the transforms will be
+    /// restored on next update. Only bodies with enabled interpolation are affected, see
+    /// [`crate::scene::rigidbody::RigidBodyInterpolation`] docs for more info.
+    pub fn interpolate_rigid_bodies(&self, alpha: f32) {
+        fn update_recursively(
+            nodes: &NodePool,
+            node_handle: Handle<Node>,
+            parent_global_transform: &Matrix4<f32>,
+            alpha: f32,
+        ) {
+            let node = &nodes[node_handle];
+
+            let global_transform = node
+                .cast::<RigidBody>()
+                .and_then(|rigid_body| rigid_body.interpolated_transform(alpha))
+                .unwrap_or_else(|| parent_global_transform * node.local_transform().matrix());
+
+            node.global_transform.set(global_transform);
+
+            for &child in node.children() {
+                update_recursively(nodes, child, &global_transform, alpha);
+            }
+        }
+
+        for node in self.pool.iter() {
+            if let Some(global_transform) = node
+                .cast::<RigidBody>()
+                .and_then(|rigid_body| rigid_body.interpolated_transform(alpha))
+            {
+                node.global_transform.set(global_transform);
+
+                for &child in node.children() {
+                    update_recursively(&self.pool, child, &global_transform, alpha);
+                }
+            }
+        }
+    }
+
+    fn collect_observers(&mut self) {
+        self.observers.clear();
+        for node in self.pool.iter() {
+            if let Some(camera) = node.cast::<Camera>() {
+                if camera.is_enabled() && node.is_globally_enabled() {
+                    self.observers.push(Observer {
+                        position: camera.global_position(),
+                        frustum: Frustum::from(camera.view_projection_matrix()),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns a list of observers collected during the last [`Graph::update`] call.
+    pub fn observers(&self) -> &[Observer] {
+        &self.observers
+    }
+
+    /// Checks whether the node is visible by any enabled camera of the graph. See
+    /// [`Camera::is_node_visible`] for more info.
+    pub fn is_node_visible(&self, node: Handle<Node>) -> bool {
+        self.pool.iter().any(|other| {
+            other.cast::<Camera>().map_or(false, |camera| {
+                camera.is_enabled() && other.is_globally_enabled() && camera.is_node_visible(node)
+            })
+        })
+    }
+
     /// Checks whether given node handle is valid or not.
     pub fn is_valid_handle(&self, node_handle: Handle<Node>) -> bool {
         self.pool.is_valid_handle(node_handle)
@@ -1043,6 +1249,14 @@ impl Graph {
             let mut is_alive = node.is_alive();
 
             if node.is_globally_enabled() {
+                let animation_start_time = if node.cast::<AnimationPlayer>().is_some()
+                    || node.cast::<AnimationBlendingStateMachine>().is_some()
+                {
+                    Some(instant::Instant::now())
+                } else {
+                    None
+                };
+
                 node.update(&mut UpdateContext {
                     frame_size,
                     dt,
@@ -1050,8 +1264,16 @@ impl Graph {
                     physics: &mut self.physics,
                     physics2d: &mut self.physics2d,
                     sound_context: &mut self.sound_context,
+                    observers: &self.observers,
                 });
 
+                if let Some(animation_start_time) = animation_start_time {
+                    self.performance_statistics.animation_time +=
+                        instant::Instant::now() - animation_start_time;
+                }
+
+                node.update_opacity_fade(dt);
+
                 if delete_dead_nodes {
                     if let Some(lifetime) = node.lifetime.get_value_mut_silent().as_mut() {
                         *lifetime -= dt;
@@ -1079,6 +1301,7 @@ impl Graph {
     pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
         let last_time = instant::Instant::now();
         self.update_hierarchical_data();
+        self.record_interpolation_states();
         self.performance_statistics.hierarchical_properties_time =
             instant::Instant::now() - last_time;
 
@@ -1086,6 +1309,9 @@ impl Graph {
         self.sync_native();
         self.performance_statistics.sync_time = instant::Instant::now() - last_time;
 
+        #[cfg(feature = "memory-tracking")]
+        let physics_memory_tag = crate::utils::memory::MemoryTag::PHYSICS.enter();
+
         if switches.physics {
             self.physics.performance_statistics.reset();
             self.physics.update(dt);
@@ -1098,12 +1324,22 @@ impl Graph {
             self.performance_statistics.physics2d = self.physics2d.performance_statistics.clone();
         }
 
+        #[cfg(feature = "memory-tracking")]
+        drop(physics_memory_tag);
+
         if switches.sound {
-            self.sound_context.update(&self.pool);
+            #[cfg(feature = "memory-tracking")]
+            let _memory_tag = crate::utils::memory::MemoryTag::SOUND.enter();
+            self.sound_context.update(&self.pool, dt);
             self.performance_statistics.sound_update_time =
                 self.sound_context.full_render_duration();
         }
 
+        self.collect_observers();
+
+        let last_time = instant::Instant::now();
+        self.performance_statistics.animation_time = Duration::default();
+
         if let Some(overrides) = switches.node_overrides.as_ref() {
             for handle in overrides {
                 self.update_node(*handle, frame_size, dt, switches.delete_dead_nodes);
@@ -1118,6 +1354,8 @@ impl Graph {
                 );
             }
         }
+
+        self.performance_statistics.node_update_time = instant::Instant::now() - last_time;
     }
 
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
@@ -1389,6 +1627,75 @@ impl Graph {
         let m = self.global_scale_matrix(node);
         Vector3::new(m[0], m[5], m[10])
     }
+
+    /// Calculates world transformation matrix of a node using current local transforms of the node
+    /// and its ancestors. Unlike [`Base::global_transform`], the result is always up-to-date, even if
+    /// some local transform in the chain was changed after the last call of
+    /// [`Self::update_hierarchical_data`].
+    pub fn calculate_global_transform(&self, node: Handle<Node>) -> Matrix4<f32> {
+        let node = &self[node];
+        let local_transform = node.local_transform().matrix();
+        if node.parent().is_some() {
+            self.calculate_global_transform(node.parent()) * local_transform
+        } else {
+            local_transform
+        }
+    }
+
+    /// Sets position of a node in world coordinates. Local position of the node is calculated
+    /// from current transform of its parent, so the node will be at the given position regardless
+    /// of its place in the hierarchy.
+    ///
+    /// # Deferred propagation
+    ///
+    /// Only local transform of the node is changed, global transforms of the node and its
+    /// descendants will be updated on next [`Self::update_hierarchical_data`] call (which is done
+    /// automatically once per frame). Call it manually if you need new global transforms immediately.
+    pub fn set_global_position(&mut self, node: Handle<Node>, position: Vector3<f32>) {
+        let parent = self[node].parent();
+        let parent_global_transform = if parent.is_some() {
+            self.calculate_global_transform(parent)
+        } else {
+            Matrix4::identity()
+        };
+
+        let local_position = parent_global_transform
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity)
+            .transform_point(&Point3::from(position))
+            .coords;
+
+        let transform = self[node].local_transform_mut();
+        // Translation of local transform may also contain offsets and pivots, so only the
+        // difference is applied.
+        let current_local_position = transform.matrix().position();
+        let new_position = **transform.position() + local_position - current_local_position;
+        transform.set_position(new_position);
+    }
+
+    /// Sets rotation of a node in world coordinates. Local rotation of the node is calculated from
+    /// current rotation of its parent (see [`Self::global_rotation`]), pre- and post-rotations of the
+    /// node are taken into account. Non-uniform scale of ancestors is ignored.
+    ///
+    /// # Deferred propagation
+    ///
+    /// Only local transform of the node is changed, see [`Self::set_global_position`] for more
+    /// info.
+    pub fn set_global_rotation(&mut self, node: Handle<Node>, rotation: UnitQuaternion<f32>) {
+        let parent = self[node].parent();
+        let parent_global_rotation = if parent.is_some() {
+            self.global_rotation(parent)
+        } else {
+            UnitQuaternion::identity()
+        };
+
+        let transform = self[node].local_transform_mut();
+        let local_rotation = transform.pre_rotation().inverse()
+            * parent_global_rotation.inverse()
+            * rotation
+            * transform.post_rotation().inverse();
+        transform.set_rotation(local_rotation);
+    }
 }
 
 impl Index<Handle<Node>> for Graph {
@@ -1474,8 +1781,21 @@ mod test {
     use crate::scene::base::BaseBuilder;
     use crate::scene::pivot::PivotBuilder;
     use crate::{
-        core::pool::Handle,
-        scene::{graph::Graph, node::Node, pivot::Pivot},
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            camera::CameraBuilder,
+            graph::{Graph, GraphUpdateSwitches},
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            node::Node,
+            pivot::Pivot,
+            transform::TransformBuilder,
+        },
     };
 
     #[test]
@@ -1544,4 +1864,189 @@ mod test {
         assert_eq!(result.0, a);
         assert_eq!(result.1, "A");
     }
+
+    #[test]
+    fn test_opacity_fade() {
+        let mut graph = Graph::new();
+
+        let child;
+        let parent = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_visibility_opacity(0.5)
+                .with_children(&[{
+                    child = PivotBuilder::new(BaseBuilder::new().with_visibility_opacity(0.5))
+                        .build(&mut graph);
+                    child
+                }]),
+        )
+        .build(&mut graph);
+
+        graph.update_hierarchical_data();
+        assert_eq!(graph[child].global_opacity(), 0.25);
+
+        let switches = GraphUpdateSwitches {
+            physics2d: false,
+            physics: false,
+            sound: false,
+            node_overrides: None,
+            delete_dead_nodes: true,
+        };
+
+        graph[parent].fade_in(1.0);
+        graph.update(Vector2::new(1.0, 1.0), 0.5, switches.clone());
+        assert!(graph[parent].is_fading());
+        assert_eq!(graph[parent].visibility_opacity(), 0.75);
+
+        graph.update(Vector2::new(1.0, 1.0), 0.5, switches.clone());
+        assert!(!graph[parent].is_fading());
+        assert_eq!(graph[parent].visibility_opacity(), 1.0);
+
+        graph.update_hierarchical_data();
+        assert_eq!(graph[child].global_opacity(), 0.5);
+
+        // Explicit opacity cancels the fade.
+        graph[parent].fade_out(1.0);
+        graph[parent].set_visibility_opacity(1.0);
+        graph.update(Vector2::new(1.0, 1.0), 0.5, switches);
+        assert_eq!(graph[parent].visibility_opacity(), 1.0);
+    }
+
+    #[test]
+    fn test_change_ticks() {
+        let mut graph = Graph::new();
+
+        let child;
+        let parent = PivotBuilder::new(BaseBuilder::new().with_children(&[{
+            child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+            child
+        }]))
+        .build(&mut graph);
+        let other = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        // Every node is changed on first update.
+        graph.update_hierarchical_data();
+        let first_tick = graph.change_tick();
+        for node in [parent, child, other] {
+            assert_eq!(graph[node].transform_change_tick(), first_tick);
+            assert_eq!(graph[node].visibility_change_tick(), first_tick);
+        }
+
+        // Nothing has changed.
+        graph.update_hierarchical_data();
+        assert_eq!(graph.change_tick(), first_tick + 1);
+        for node in [parent, child, other] {
+            assert_eq!(graph[node].transform_change_tick(), first_tick);
+        }
+
+        // Moving the parent must mark its descendants as well.
+        graph[parent]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 0.0, 0.0));
+        graph.update_hierarchical_data();
+        let tick = graph.change_tick();
+        assert_eq!(graph[parent].transform_change_tick(), tick);
+        assert_eq!(graph[child].transform_change_tick(), tick);
+        assert_eq!(graph[other].transform_change_tick(), first_tick);
+        assert_eq!(graph[parent].visibility_change_tick(), first_tick);
+
+        graph[parent].set_visibility(false);
+        graph.update_hierarchical_data();
+        let tick = graph.change_tick();
+        assert_eq!(graph[child].visibility_change_tick(), tick);
+        assert_eq!(graph[other].visibility_change_tick(), first_tick);
+        assert_eq!(graph[child].transform_change_tick(), tick - 1);
+    }
+
+    #[test]
+    fn test_set_global_transform() {
+        let mut graph = Graph::new();
+
+        let child;
+        let parent = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(1.0, 2.0, 3.0))
+                        .with_local_rotation(UnitQuaternion::from_axis_angle(
+                            &Vector3::y_axis(),
+                            std::f32::consts::FRAC_PI_2,
+                        ))
+                        .with_local_scale(Vector3::new(2.0, 2.0, 2.0))
+                        .build(),
+                )
+                .with_children(&[{
+                    child = PivotBuilder::new(
+                        BaseBuilder::new().with_local_transform(
+                            TransformBuilder::new()
+                                .with_local_position(Vector3::new(1.0, 0.0, 0.0))
+                                .with_rotation_offset(Vector3::new(0.0, 1.0, 0.0))
+                                .build(),
+                        ),
+                    )
+                    .build(&mut graph);
+                    child
+                }]),
+        )
+        .build(&mut graph);
+
+        // Parent is moved in the same frame, its new transform must be taken into account.
+        graph[parent]
+            .local_transform_mut()
+            .set_position(Vector3::new(-1.0, 0.0, 5.0));
+
+        let position = Vector3::new(4.0, 5.0, 6.0);
+        graph.set_global_position(child, position);
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.5);
+        graph.set_global_rotation(child, rotation);
+
+        graph.update_hierarchical_data();
+
+        assert!((graph[child].global_position() - position).norm() < 0.0001);
+        assert!(graph.global_rotation(child).angle_to(&rotation) < 0.0001);
+    }
+
+    #[test]
+    fn test_node_visibility() {
+        let mut graph = Graph::new();
+
+        let camera = CameraBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        let make_cube = |graph: &mut Graph, z: f32| {
+            MeshBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.0, z))
+                        .build(),
+                ),
+            )
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(graph)
+        };
+
+        let front = make_cube(&mut graph, 5.0);
+        let behind = make_cube(&mut graph, -5.0);
+
+        // The camera is updated before the cubes, so their bounding boxes are valid for the camera
+        // only on the second update.
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let camera_ref = graph[camera].as_camera();
+        assert!(camera_ref.is_node_visible(front));
+        assert!(!camera_ref.is_node_visible(behind));
+        assert!(graph.is_node_visible(front));
+        assert!(!graph.is_node_visible(behind));
+
+        let frustum = camera_ref.frustum();
+        assert!(frustum.is_contains_aabb(&graph[front].world_bounding_box()));
+        assert!(frustum.is_contains_sphere(Vector3::new(0.0, 0.0, 5.0), 1.0));
+        assert!(!frustum.is_contains_sphere(Vector3::new(0.0, 0.0, -5.0), 1.0));
+
+        graph[camera].as_camera_mut().set_enabled(false);
+        assert!(!graph.is_node_visible(front));
+    }
 }
@@ -20,7 +20,13 @@ use crate::{
         self,
         collider::{self, ColliderShape, GeometrySource},
         debug::SceneDrawingContext,
-        graph::{isometric_global_transform, NodePool},
+        graph::{
+            isometric_global_transform,
+            surface_response::{
+                surface_type_id, SurfaceContactEvent, SurfaceEventCollector, SurfaceResponseTable,
+            },
+            NodePool,
+        },
         joint::JointParams,
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
@@ -35,8 +41,9 @@ use crate::{
         raw_mesh::{RawMeshBuilder, RawVertex},
     },
 };
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::parking_lot::Mutex;
-use rapier3d::pipeline::{DebugRenderPipeline, QueryFilter};
+use rapier3d::pipeline::{ActiveEvents, ActiveHooks, DebugRenderPipeline, QueryFilter};
 use rapier3d::{
     dynamics::{
         CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
@@ -47,7 +54,7 @@ use rapier3d::{
         BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid,
         InteractionGroups, NarrowPhase, Ray, SharedShape,
     },
-    pipeline::{EventHandler, PhysicsPipeline, QueryPipeline},
+    pipeline::{PhysicsPipeline, QueryPipeline},
     prelude::JointAxis,
 };
 use std::{
@@ -333,6 +340,101 @@ pub struct IntersectionPair {
     pub has_any_active_contact: bool,
 }
 
+/// A phase of a contact (or an intersection) between a pair of colliders.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ContactEventPhase {
+    /// Colliders have started touching each other.
+    Begin,
+    /// Colliders are still touching each other.
+    Persist,
+    /// Colliders have stopped touching each other.
+    End,
+}
+
+/// An event of a contact (or an intersection) between a pair of colliders. See [`ContactEventFilter`]
+/// docs for more info.
+#[derive(Clone, Debug)]
+pub struct ContactEvent {
+    /// The first collider involved in the contact.
+    pub collider1: Handle<Node>,
+    /// The second collider involved in the contact.
+    pub collider2: Handle<Node>,
+    /// Phase of the contact.
+    pub phase: ContactEventPhase,
+    /// `true` if at least one of the colliders is a sensor, in this case there is no contact
+    /// information (force, position, normal).
+    pub is_intersection: bool,
+    /// Total force magnitude applied at every contact point of the pair. It is always zero for
+    /// [`ContactEventPhase::End`] events and intersections.
+    pub force: f32,
+    /// World-space position of a contact point.
+    pub position: Point3<f32>,
+    /// World-space contact normal.
+    pub normal: Vector3<f32>,
+}
+
+impl ContactEvent {
+    /// Returns `true` if the given collider is involved in the contact.
+    pub fn involves(&self, collider: Handle<Node>) -> bool {
+        self.collider1 == collider || self.collider2 == collider
+    }
+}
+
+/// Defines which contact events will be reported by the physics world. Without the filter, scripts
+/// could be flooded with thousands of events of "micro-contacts" (for example, of bodies resting on
+/// a floor) every frame.
+///
+/// Events are reported only for contacts of colliders, that belong to [`Self::layers`] (see
+/// [`collider::InteractionGroups::memberships`]). A contact of rigid bodies will be reported
+/// only if its total force is greater than contact force thresholds of both bodies (see
+/// [`scene::rigidbody::RigidBody::set_contact_force_threshold`]). Once a contact has began,
+/// it will be reported as ended when the colliders stop touching each other, even if the force
+/// drops below the thresholds.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct ContactEventFilter {
+    /// Whether contact events are reported or not. Disabled by default, since event reporting
+    /// requires to check every contact pair every simulation step.
+    pub enabled: bool,
+    /// Colliders, that belong to at least one of the layers will generate contact events.
+    pub layers: collider::BitMask,
+    /// Whether [`ContactEventPhase::Begin`] events should be reported or not.
+    pub report_begin: bool,
+    /// Whether [`ContactEventPhase::Persist`] events should be reported or not. These events are
+    /// reported every simulation step for every active contact, so they are disabled by default.
+    pub report_persist: bool,
+    /// Whether [`ContactEventPhase::End`] events should be reported or not.
+    pub report_end: bool,
+    /// Whether intersections (contacts with sensors) should be reported or not.
+    pub report_intersections: bool,
+    /// Maximum amount of events per simulation step, the rest will be discarded.
+    #[reflect(min_value = 0.0)]
+    pub max_events: u32,
+}
+
+impl Default for ContactEventFilter {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            layers: collider::BitMask(u32::MAX),
+            report_begin: true,
+            report_persist: false,
+            report_end: true,
+            report_intersections: true,
+            max_events: 256,
+        }
+    }
+}
+
+impl ContactEventFilter {
+    fn accepts_phase(&self, phase: ContactEventPhase) -> bool {
+        match phase {
+            ContactEventPhase::Begin => self.report_begin,
+            ContactEventPhase::Persist => self.report_persist,
+            ContactEventPhase::End => self.report_end,
+        }
+    }
+}
+
 pub(super) struct Container<S, A>
 where
     A: Hash + Eq + Clone,
@@ -761,28 +863,6 @@ pub struct IntegrationParameters {
     )]
     pub prediction_distance: f32,
 
-    /// Maximum number of iterations performed by the velocity constraints solver (default: `4`).
-    #[reflect(
-        min_value = 0.0,
-        description = "Maximum number of iterations performed by the \
-    velocity constraints solver (default: `4`)."
-    )]
-    pub max_velocity_iterations: u32,
-
-    /// Maximum number of iterations performed to solve friction constraints (default: `8`).
-    #[reflect(
-        min_value = 0.0,
-        description = "Maximum number of iterations performed to solve friction constraints (default: `8`)"
-    )]
-    pub max_velocity_friction_iterations: u32,
-
-    /// Maximum number of iterations performed to remove the energy introduced by penetration corrections  (default: `1`).
-    #[reflect(
-        min_value = 0.0,
-        description = "Maximum number of iterations performed to remove the energy introduced by penetration corrections  (default: `1`)."
-    )]
-    pub max_stabilization_iterations: u32,
-
     /// If `false`, friction and non-penetration constraints will be solved in the same loop. Otherwise,
     /// non-penetration constraints are solved first, and friction constraints are solved after (default: `true`).
     #[reflect(
@@ -818,9 +898,6 @@ impl Default for IntegrationParameters {
             allowed_linear_error: 0.001,
             max_penetration_correction: f32::MAX,
             prediction_distance: 0.002,
-            max_velocity_iterations: 4,
-            max_velocity_friction_iterations: 8,
-            max_stabilization_iterations: 1,
             interleave_restitution_and_friction_resolution: true,
             min_island_size: 128,
             max_ccd_substeps: 1,
@@ -828,6 +905,90 @@ impl Default for IntegrationParameters {
     }
 }
 
+/// Per-scene settings of the physics simulation, that allows to tune stability of the simulation
+/// without rebuilding the engine. For example, stacks of small boxes could jitter with default
+/// settings, in this case increase amount of sub-steps and/or solver iterations. The settings are
+/// used by both 3D and 2D physics worlds and applied on every update of a scene graph.
+#[derive(Copy, Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct PhysicsSettings {
+    /// Amount of simulation steps per single update of the physics world, each step simulates
+    /// `dt / substeps` seconds. Larger values make the simulation more stable (less jitter, less
+    /// tunneling), but increase CPU usage proportionally. Default is 1.
+    #[reflect(
+        min_value = 1.0,
+        max_value = 64.0,
+        description = "Amount of simulation steps per single update (default: 1)"
+    )]
+    pub substeps: u32,
+
+    /// Linear velocity below which a rigid body can be put to sleep (default: `0.4`). Applied to
+    /// every rigid body, that can sleep.
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Linear velocity below which a rigid body can be put to sleep (default: 0.4)"
+    )]
+    pub linear_sleep_threshold: f32,
+
+    /// Angular velocity below which a rigid body can be put to sleep (default: `0.5`). Applied to
+    /// every rigid body, that can sleep.
+    #[reflect(
+        min_value = 0.0,
+        step = 0.05,
+        description = "Angular velocity below which a rigid body can be put to sleep (default: 0.5)"
+    )]
+    pub angular_sleep_threshold: f32,
+
+    /// Maximum number of iterations performed by the velocity constraints solver (default: `4`).
+    /// More iterations make contacts and joints stiffer.
+    #[reflect(
+        min_value = 1.0,
+        description = "Maximum number of iterations performed by the velocity constraints solver \
+        (default: 4)"
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub velocity_iterations: u32,
+
+    /// Maximum number of iterations performed to solve friction constraints (default: `8`).
+    #[reflect(
+        min_value = 1.0,
+        description = "Maximum number of iterations performed to solve friction constraints \
+        (default: 8)"
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub friction_iterations: u32,
+
+    /// Maximum number of iterations performed to remove the energy introduced by penetration
+    /// corrections (default: `1`).
+    #[reflect(
+        min_value = 0.0,
+        description = "Maximum number of iterations performed to remove the energy introduced \
+        by penetration corrections (default: 1)"
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub stabilization_iterations: u32,
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            substeps: 1,
+            linear_sleep_threshold: RigidBodyActivation::default_linear_threshold(),
+            angular_sleep_threshold: RigidBodyActivation::default_angular_threshold(),
+            velocity_iterations: 4,
+            friction_iterations: 8,
+            stabilization_iterations: 1,
+        }
+    }
+}
+
+impl PhysicsSettings {
+    fn apply_sleep_thresholds(&self, activation: &mut RigidBodyActivation) {
+        activation.linear_threshold = self.linear_sleep_threshold;
+        activation.angular_threshold = self.angular_sleep_threshold;
+    }
+}
+
 /// Physics world is responsible for physics simulation in the engine. There is a very few public
 /// methods, mostly for ray casting. You should add physical entities using scene graph nodes, such
 /// as RigidBody, Collider, Joint.
@@ -839,6 +1000,16 @@ pub struct PhysicsWorld {
     /// A set of parameters that define behavior of every rigid body.
     pub integration_parameters: IntegrationParameters,
 
+    /// Sub-stepping and sleeping settings of the simulation. See [`PhysicsSettings`] docs for
+    /// more info.
+    #[visit(optional)] // Backward compatibility
+    pub settings: PhysicsSettings,
+
+    // Settings, that were applied to rigid bodies last time.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    applied_settings: PhysicsSettings,
+
     /// Current gravity vector. Default is (0.0, -9.81, 0.0)
     pub gravity: Vector3<f32>,
 
@@ -847,6 +1018,18 @@ pub struct PhysicsWorld {
     #[reflect(hidden)]
     pub performance_statistics: PhysicsPerformanceStatistics,
 
+    /// Defines which contact events will be reported. See [`ContactEventFilter`] docs for more
+    /// info.
+    #[visit(optional)] // Backward compatibility
+    pub contact_event_filter: ContactEventFilter,
+
+    /// A table, that defines responses of contacts between pairs of surface types. It is not saved
+    /// with the scene, because it is meant to be shared across the whole project. See
+    /// [`SurfaceResponseTable`] docs for more info.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub surface_responses: SurfaceResponseTable,
+
     // Current physics pipeline.
     #[visit(skip)]
     #[reflect(hidden)]
@@ -884,10 +1067,26 @@ pub struct PhysicsWorld {
     #[visit(skip)]
     #[reflect(hidden)]
     multibody_joints: Container<MultibodyJointSet, MultibodyJointHandle>,
-    // Event handler collects info about contacts and proximity events.
+    // Event handler collects info about contacts of colliders with surface types.
     #[visit(skip)]
     #[reflect(hidden)]
-    event_handler: Box<dyn EventHandler>,
+    event_handler: SurfaceEventCollector,
+    // Contact events of the last simulation step.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    surface_contact_events: Vec<SurfaceContactEvent>,
+    // Contact events of the last simulation step.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    contact_events: Vec<ContactEvent>,
+    // Pairs of colliders, that are touching each other and have reported beginning of the contact.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    active_contact_pairs: FxHashSet<(ColliderHandle, ColliderHandle)>,
+    // Surface type ids mapped to their names.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    surface_names: FxHashMap<u64, String>,
     #[visit(skip)]
     #[reflect(hidden)]
     query: RefCell<QueryPipeline>,
@@ -921,6 +1120,18 @@ fn calculate_local_frames(
     )
 }
 
+fn set_surface_type_id(native: &mut Collider, id: u64) {
+    native.user_data = id as u128;
+    // Colliders without surface types do not need contact modification and events.
+    if id == 0 {
+        native.set_active_hooks(ActiveHooks::empty());
+        native.set_active_events(ActiveEvents::empty());
+    } else {
+        native.set_active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
+        native.set_active_events(ActiveEvents::COLLISION_EVENTS);
+    }
+}
+
 fn u32_to_group(v: u32) -> rapier3d::geometry::Group {
     rapier3d::geometry::Group::from_bits(v).unwrap_or_else(rapier3d::geometry::Group::all)
 }
@@ -933,6 +1144,8 @@ impl PhysicsWorld {
             pipeline: PhysicsPipeline::new(),
             gravity: Vector3::new(0.0, -9.81, 0.0),
             integration_parameters: IntegrationParameters::default(),
+            settings: Default::default(),
+            applied_settings: Default::default(),
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
@@ -953,9 +1166,15 @@ impl PhysicsWorld {
                 set: MultibodyJointSet::new(),
                 map: Default::default(),
             },
-            event_handler: Box::new(()),
+            event_handler: Default::default(),
+            surface_contact_events: Default::default(),
+            surface_names: Default::default(),
+            contact_event_filter: Default::default(),
+            contact_events: Default::default(),
+            active_contact_pairs: Default::default(),
             query: RefCell::new(Default::default()),
             performance_statistics: Default::default(),
+            surface_responses: Default::default(),
             debug_render_pipeline: Default::default(),
         }
     }
@@ -963,9 +1182,16 @@ impl PhysicsWorld {
     pub(super) fn update(&mut self, dt: f32) {
         let time = instant::Instant::now();
 
+        self.surface_contact_events.clear();
+        self.contact_events.clear();
+
         if self.enabled {
+            self.apply_settings();
+
+            let substeps = self.settings.substeps.max(1);
+
             let integration_parameters = rapier3d::dynamics::IntegrationParameters {
-                dt: self.integration_parameters.dt.unwrap_or(dt),
+                dt: self.integration_parameters.dt.unwrap_or(dt) / substeps as f32,
                 min_ccd_dt: self.integration_parameters.min_ccd_dt,
                 erp: self.integration_parameters.erp,
                 damping_ratio: self.integration_parameters.damping_ratio,
@@ -974,16 +1200,9 @@ impl PhysicsWorld {
                 allowed_linear_error: self.integration_parameters.allowed_linear_error,
                 max_penetration_correction: self.integration_parameters.max_penetration_correction,
                 prediction_distance: self.integration_parameters.prediction_distance,
-                max_velocity_iterations: self.integration_parameters.max_velocity_iterations
-                    as usize,
-                max_velocity_friction_iterations: self
-                    .integration_parameters
-                    .max_velocity_friction_iterations
-                    as usize,
-                max_stabilization_iterations: self
-                    .integration_parameters
-                    .max_stabilization_iterations
-                    as usize,
+                max_velocity_iterations: self.settings.velocity_iterations as usize,
+                max_velocity_friction_iterations: self.settings.friction_iterations as usize,
+                max_stabilization_iterations: self.settings.stabilization_iterations as usize,
                 interleave_restitution_and_friction_resolution: self
                     .integration_parameters
                     .interleave_restitution_and_friction_resolution,
@@ -991,25 +1210,255 @@ impl PhysicsWorld {
                 max_ccd_substeps: self.integration_parameters.max_ccd_substeps as usize,
             };
 
-            self.pipeline.step(
-                &self.gravity,
-                &integration_parameters,
-                &mut self.islands,
-                &mut self.broad_phase,
-                &mut self.narrow_phase,
-                &mut self.bodies.set,
-                &mut self.colliders.set,
-                &mut self.joints.set,
-                &mut self.multibody_joints.set,
-                &mut self.ccd_solver,
-                &(),
-                &*self.event_handler,
-            );
+            for _ in 0..substeps {
+                self.pipeline.step(
+                    &self.gravity,
+                    &integration_parameters,
+                    &mut self.islands,
+                    &mut self.broad_phase,
+                    &mut self.narrow_phase,
+                    &mut self.bodies.set,
+                    &mut self.colliders.set,
+                    &mut self.joints.set,
+                    &mut self.multibody_joints.set,
+                    &mut self.ccd_solver,
+                    &self.surface_responses,
+                    &self.event_handler,
+                );
+            }
+
+            self.collect_surface_contact_events();
+
+            if self.contact_event_filter.enabled {
+                self.collect_contact_events(integration_parameters.dt);
+            } else {
+                self.active_contact_pairs.clear();
+            }
         }
 
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
+    fn collect_surface_contact_events(&mut self) {
+        for contact in self.event_handler.contacts.lock().drain(..) {
+            let response = self
+                .surface_responses
+                .response_by_id(contact.surface1, contact.surface2);
+
+            if response.map_or(false, |r| contact.velocity < r.min_velocity) {
+                continue;
+            }
+
+            let node_of = |collider| {
+                self.colliders
+                    .map
+                    .value_of(&collider)
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let name_of = |id| self.surface_names.get(&id).cloned().unwrap_or_default();
+
+            self.surface_contact_events.push(SurfaceContactEvent {
+                collider1: node_of(contact.collider1),
+                collider2: node_of(contact.collider2),
+                surface1: name_of(contact.surface1),
+                surface2: name_of(contact.surface2),
+                position: contact.position,
+                normal: contact.normal,
+                velocity: contact.velocity,
+                response: response.cloned(),
+            });
+        }
+    }
+
+    fn apply_settings(&mut self) {
+        if self.applied_settings == self.settings {
+            return;
+        }
+
+        for (_, body) in self.bodies.set.iter_mut() {
+            // Negative thresholds mean that the body cannot sleep.
+            if body.activation().linear_threshold >= 0.0 {
+                self.settings.apply_sleep_thresholds(body.activation_mut());
+            }
+        }
+
+        self.applied_settings = self.settings;
+    }
+
+    fn contact_force_threshold(&self, collider: ColliderHandle) -> f32 {
+        self.colliders
+            .set
+            .get(collider)
+            .and_then(|c| c.parent())
+            .and_then(|b| self.bodies.set.get(b))
+            .map_or(0.0, |b| f32::from_bits(b.user_data as u32))
+    }
+
+    fn is_in_event_layers(&self, collider: ColliderHandle) -> bool {
+        self.colliders.set.get(collider).map_or(false, |c| {
+            c.collision_groups().memberships.bits() & self.contact_event_filter.layers.0 != 0
+        })
+    }
+
+    fn push_contact_event(&mut self, event: ContactEvent) {
+        if self.contact_event_filter.accepts_phase(event.phase)
+            && self.contact_events.len() < self.contact_event_filter.max_events as usize
+        {
+            self.contact_events.push(event);
+        }
+    }
+
+    fn collect_contact_events(&mut self, dt: f32) {
+        let mut current_pairs = FxHashSet::default();
+        let mut events = Vec::new();
+
+        let node_of = |collider| {
+            self.colliders
+                .map
+                .value_of(&collider)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        for pair in self.narrow_phase.contact_pairs() {
+            if !pair.has_any_active_contact
+                || (!self.is_in_event_layers(pair.collider1)
+                    && !self.is_in_event_layers(pair.collider2))
+            {
+                continue;
+            }
+
+            let total_impulse = pair
+                .manifolds
+                .iter()
+                .flat_map(|m| m.points.iter())
+                .map(|p| p.data.impulse)
+                .sum::<f32>();
+            let force = if dt > 0.0 { total_impulse / dt } else { 0.0 };
+            let threshold = self
+                .contact_force_threshold(pair.collider1)
+                .max(self.contact_force_threshold(pair.collider2));
+
+            let key = (pair.collider1, pair.collider2);
+            let is_active = self.active_contact_pairs.contains(&key);
+            if is_active || force >= threshold {
+                current_pairs.insert(key);
+            }
+            if force < threshold {
+                continue;
+            }
+
+            let (position, normal) = pair
+                .manifolds
+                .iter()
+                .find_map(|m| {
+                    m.data
+                        .solver_contacts
+                        .first()
+                        .map(|c| (c.point, m.data.normal))
+                })
+                .unwrap_or_default();
+
+            events.push(ContactEvent {
+                collider1: node_of(pair.collider1),
+                collider2: node_of(pair.collider2),
+                phase: if is_active {
+                    ContactEventPhase::Persist
+                } else {
+                    ContactEventPhase::Begin
+                },
+                is_intersection: false,
+                force,
+                position,
+                normal,
+            });
+        }
+
+        if self.contact_event_filter.report_intersections {
+            for (collider1, collider2, intersecting) in self.narrow_phase.intersection_pairs() {
+                if !intersecting
+                    || (!self.is_in_event_layers(collider1) && !self.is_in_event_layers(collider2))
+                {
+                    continue;
+                }
+
+                let key = (collider1, collider2);
+                current_pairs.insert(key);
+                events.push(ContactEvent {
+                    collider1: node_of(collider1),
+                    collider2: node_of(collider2),
+                    phase: if self.active_contact_pairs.contains(&key) {
+                        ContactEventPhase::Persist
+                    } else {
+                        ContactEventPhase::Begin
+                    },
+                    is_intersection: true,
+                    force: 0.0,
+                    position: Default::default(),
+                    normal: Default::default(),
+                });
+            }
+        }
+
+        for &(collider1, collider2) in self.active_contact_pairs.iter() {
+            if !current_pairs.contains(&(collider1, collider2)) {
+                let is_sensor = |c| self.colliders.set.get(c).map_or(false, |c| c.is_sensor());
+                events.push(ContactEvent {
+                    collider1: node_of(collider1),
+                    collider2: node_of(collider2),
+                    phase: ContactEventPhase::End,
+                    is_intersection: is_sensor(collider1) || is_sensor(collider2),
+                    force: 0.0,
+                    position: Default::default(),
+                    normal: Default::default(),
+                });
+            }
+        }
+
+        self.active_contact_pairs = current_pairs;
+
+        for event in events {
+            self.push_contact_event(event);
+        }
+    }
+
+    /// Returns contact events, that were generated during the last simulation step. Events are
+    /// reported only if [`Self::contact_event_filter`] is enabled. See [`ContactEventFilter`] docs
+    /// for more info.
+    pub fn contact_events(&self) -> &[ContactEvent] {
+        &self.contact_events
+    }
+
+    /// Returns an iterator over contact events of the last simulation step, that involve the given
+    /// collider.
+    pub fn contact_events_of(
+        &self,
+        collider: Handle<Node>,
+    ) -> impl Iterator<Item = &ContactEvent> + '_ {
+        self.contact_events
+            .iter()
+            .filter(move |e| e.involves(collider))
+    }
+
+    /// Returns contact events, that were generated during the last simulation step. An event is
+    /// generated when two colliders start touching each other and at least one of them has a
+    /// surface type (see [`scene::collider::Collider::set_surface_type`]). Events could be used to
+    /// play sounds, spawn particles, etc. See [`SurfaceResponseTable`] docs for more info.
+    pub fn surface_contact_events(&self) -> &[SurfaceContactEvent] {
+        &self.surface_contact_events
+    }
+
+    fn register_surface_type(&mut self, surface_type: &str) -> u64 {
+        let id = surface_type_id(surface_type);
+        if id != 0 {
+            self.surface_names
+                .entry(id)
+                .or_insert_with(|| surface_type.to_owned());
+        }
+        id
+    }
+
     pub(super) fn add_body(&mut self, owner: Handle<Node>, body: RigidBody) -> RigidBodyHandle {
         let handle = self.bodies.set.insert(body);
         self.bodies.map.insert(handle, owner);
@@ -1062,6 +1511,24 @@ impl PhysicsWorld {
         }
     }
 
+    pub(crate) fn body_count(&self) -> usize {
+        self.bodies.set.len()
+    }
+
+    pub(crate) fn native_body(
+        &self,
+        handle: RigidBodyHandle,
+    ) -> Option<&rapier3d::dynamics::RigidBody> {
+        self.bodies.set.get(handle)
+    }
+
+    pub(crate) fn native_collider(
+        &self,
+        handle: ColliderHandle,
+    ) -> Option<&rapier3d::geometry::Collider> {
+        self.colliders.set.get(handle)
+    }
+
     pub(super) fn add_joint(
         &mut self,
         owner: Handle<Node>,
@@ -1250,13 +1717,11 @@ impl PhysicsWorld {
                     rigid_body_node
                         .ccd_enabled
                         .try_sync_model(|v| native.enable_ccd(v));
+                    let settings = &self.settings;
                     rigid_body_node.can_sleep.try_sync_model(|v| {
-                        let mut activation = native.activation_mut();
+                        let activation = native.activation_mut();
                         if v {
-                            activation.linear_threshold =
-                                RigidBodyActivation::default_linear_threshold();
-                            activation.angular_threshold =
-                                RigidBodyActivation::default_angular_threshold();
+                            settings.apply_sleep_thresholds(activation);
                         } else {
                             activation.sleeping = false;
                             activation.linear_threshold = -1.0;
@@ -1296,6 +1761,10 @@ impl PhysicsWorld {
                     rigid_body_node
                         .gravity_scale
                         .try_sync_model(|v| native.set_gravity_scale(v, false));
+                    // Threshold is stored in user data, it is used only to filter contact events.
+                    rigid_body_node
+                        .contact_force_threshold
+                        .try_sync_model(|v| native.user_data = v.to_bits() as u128);
 
                     // We must reset any forces applied at previous update step, otherwise physics engine
                     // will keep pushing the rigid body infinitely.
@@ -1336,6 +1805,7 @@ impl PhysicsWorld {
                 .sleeping(rigid_body_node.is_sleeping())
                 .dominance_group(rigid_body_node.dominance())
                 .gravity_scale(rigid_body_node.gravity_scale())
+                .user_data(rigid_body_node.contact_force_threshold().to_bits() as u128)
                 .enabled_rotations(
                     !rigid_body_node.is_x_rotation_locked(),
                     !rigid_body_node.is_y_rotation_locked(),
@@ -1346,9 +1816,13 @@ impl PhysicsWorld {
                 builder = builder.lock_translations();
             }
 
-            rigid_body_node
-                .native
-                .set(self.add_body(handle, builder.build()));
+            let mut native = builder.build();
+            if rigid_body_node.is_can_sleep() {
+                self.settings
+                    .apply_sleep_thresholds(native.activation_mut());
+            }
+
+            rigid_body_node.native.set(self.add_body(handle, native));
 
             Log::writeln(
                 MessageKind::Information,
@@ -1382,6 +1856,7 @@ impl PhysicsWorld {
         //    and a lot of other stuff, this is why we need `anything_changed` flag.
         if collider_node.native.get() != ColliderHandle::invalid() {
             if anything_changed {
+                let mut new_surface_type = None;
                 if let Some(native) = self.colliders.set.get_mut(collider_node.native.get()) {
                     if collider_node.transform_modified.get() {
                         native.set_position_wrt_parent(Isometry3 {
@@ -1432,6 +1907,13 @@ impl PhysicsWorld {
                     collider_node
                         .restitution_combine_rule
                         .try_sync_model(|v| native.set_restitution_combine_rule(v.into()));
+                    collider_node.surface_type.try_sync_model(|v| {
+                        set_surface_type_id(native, surface_type_id(&v));
+                        new_surface_type = Some(v);
+                    });
+                }
+                if let Some(surface_type) = new_surface_type {
+                    self.register_surface_type(&surface_type);
                 }
             }
         } else if let Some(parent_body) = nodes
@@ -1474,8 +1956,13 @@ impl PhysicsWorld {
                         builder = builder.density(density);
                     }
 
-                    let native_handle =
-                        self.add_collider(handle, rigid_body_native, builder.build());
+                    let mut native = builder.build();
+                    set_surface_type_id(
+                        &mut native,
+                        self.register_surface_type(collider_node.surface_type()),
+                    );
+
+                    let native_handle = self.add_collider(handle, rigid_body_native, native);
 
                     collider_node.native.set(native_handle);
 
@@ -1674,3 +2161,58 @@ impl Debug for PhysicsWorld {
         write!(f, "PhysicsWorld")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            rigidbody::RigidBodyBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_physics_settings() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 10.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .build(&mut graph);
+
+        graph.physics.settings.substeps = 4;
+        graph.physics.settings.linear_sleep_threshold = 0.1;
+        graph.physics.settings.velocity_iterations = 8;
+        graph.physics.settings.stabilization_iterations = 2;
+
+        let dt = 1.0 / 60.0;
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+        }
+
+        // Sub-steps must not change the amount of simulated time.
+        let fallen = 10.0 - graph[body].local_transform().position().y;
+        assert!((fallen - 0.5 * 9.81).abs() < 0.2, "{}", fallen);
+
+        let native = graph
+            .physics
+            .bodies
+            .set
+            .get(graph[body].as_rigid_body().native.get())
+            .unwrap();
+        assert_eq!(native.activation().linear_threshold, 0.1);
+    }
+}
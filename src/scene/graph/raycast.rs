@@ -0,0 +1,489 @@
+//! Ray casting against actual geometry of meshes (not colliders). See [`crate::scene::graph::Graph::cast_ray_geometry`]
+//! docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::ray::Ray,
+        octree::Octree,
+        parking_lot::Mutex,
+        pool::Handle,
+    },
+    scene::{
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::{Surface, SurfaceData},
+            Mesh,
+        },
+        node::Node,
+    },
+};
+use fxhash::FxHashMap;
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Formatter},
+    sync::Weak,
+};
+
+/// A set of options for the geometry ray cast.
+#[derive(Clone, Debug)]
+pub struct GeometryRayCastOptions {
+    /// A ray origin.
+    pub ray_origin: Point3<f32>,
+
+    /// A ray direction. Can be non-normalized.
+    pub ray_direction: Vector3<f32>,
+
+    /// Maximum distance of cast.
+    pub max_len: f32,
+
+    /// Whether to skip triangles, that are facing in the same direction as the ray.
+    pub ignore_back_faces: bool,
+
+    /// Whether to skip invisible meshes or not.
+    pub ignore_invisible: bool,
+
+    /// Whether to sort intersections from closest to farthest.
+    pub sort_results: bool,
+}
+
+/// A result of the geometry ray cast.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeometryIntersection {
+    /// A handle of the mesh with which intersection was detected.
+    pub node: Handle<Node>,
+
+    /// An index of the surface of the mesh.
+    pub surface: usize,
+
+    /// An index of the triangle in the surface.
+    pub triangle: usize,
+
+    /// A position of the intersection in world coordinates.
+    pub position: Point3<f32>,
+
+    /// A normal (in world coordinates) of the triangle at the intersection position.
+    pub normal: Vector3<f32>,
+
+    /// Distance from the ray origin.
+    pub toi: f32,
+}
+
+struct CachedSurface {
+    // Cache entry is removed when the data is destroyed.
+    data: Weak<Mutex<SurfaceData>>,
+    vertex_hash: u64,
+    triangle_hash: u64,
+    // Triangles in local coordinates of a surface.
+    triangles: Vec<[Vector3<f32>; 3]>,
+    octree: Octree,
+}
+
+/// Octrees of surfaces, that were used in the geometry ray casts. Octrees are built on demand and
+/// re-built when the geometry of a surface changes.
+#[derive(Default)]
+pub(crate) struct GeometryRayCastCache {
+    surfaces: Mutex<FxHashMap<u64, CachedSurface>>,
+}
+
+impl Debug for GeometryRayCastCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GeometryRayCastCache")
+    }
+}
+
+impl GeometryRayCastCache {
+    fn remove_unused(&self) {
+        self.surfaces
+            .lock()
+            .retain(|_, entry| entry.data.strong_count() > 0);
+    }
+}
+
+fn read_triangles(
+    data: &SurfaceData,
+    transform: &dyn Fn(usize) -> Vector3<f32>,
+) -> Vec<[Vector3<f32>; 3]> {
+    data.geometry_buffer
+        .iter()
+        .map(|triangle| {
+            [
+                transform(triangle[0] as usize),
+                transform(triangle[1] as usize),
+                transform(triangle[2] as usize),
+            ]
+        })
+        .collect()
+}
+
+fn read_position(data: &SurfaceData, index: usize) -> Vector3<f32> {
+    data.vertex_buffer
+        .get(index)
+        .and_then(|vertex| vertex.read_3_f32(VertexAttributeUsage::Position).ok())
+        .unwrap_or_default()
+}
+
+// Precise (but slow) test of skinned surface, its triangles must be transformed by the bones first.
+fn cast_ray_skinned_surface(
+    graph: &Graph,
+    surface: &Surface,
+    ray: &Ray,
+    ignore_back_faces: bool,
+) -> Option<(f32, usize, [Vector3<f32>; 3])> {
+    let bone_matrices = surface
+        .bones()
+        .iter()
+        .map(|&bone| {
+            graph.try_get(bone).map_or(Matrix4::identity(), |bone| {
+                bone.global_transform() * bone.inv_bind_pose_transform()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let data = surface.data();
+    let data = data.lock();
+
+    let skin = |index: usize| {
+        let vertex = match data.vertex_buffer.get(index) {
+            Some(vertex) => vertex,
+            None => return Vector3::default(),
+        };
+        let position = Point3::from(
+            vertex
+                .read_3_f32(VertexAttributeUsage::Position)
+                .unwrap_or_default(),
+        );
+        match (
+            vertex.read_4_u8(VertexAttributeUsage::BoneIndices),
+            vertex.read_4_f32(VertexAttributeUsage::BoneWeight),
+        ) {
+            (Ok(indices), Ok(weights)) => {
+                let mut result = Vector3::default();
+                for (&bone_index, &weight) in indices.iter().zip(weights.iter()) {
+                    if let Some(matrix) = bone_matrices.get(bone_index as usize) {
+                        result += matrix.transform_point(&position).coords.scale(weight);
+                    }
+                }
+                result
+            }
+            _ => position.coords,
+        }
+    };
+
+    closest_triangle(
+        read_triangles(&data, &skin).iter().enumerate(),
+        ray,
+        ignore_back_faces,
+    )
+}
+
+fn closest_triangle<'a, I>(
+    triangles: I,
+    ray: &Ray,
+    ignore_back_faces: bool,
+) -> Option<(f32, usize, [Vector3<f32>; 3])>
+where
+    I: Iterator<Item = (usize, &'a [Vector3<f32>; 3])>,
+{
+    let mut closest: Option<(f32, usize, [Vector3<f32>; 3])> = None;
+    for (index, triangle) in triangles {
+        if ignore_back_faces {
+            let normal = (triangle[1] - triangle[0]).cross(&(triangle[2] - triangle[0]));
+            if normal.dot(&ray.dir) >= 0.0 {
+                continue;
+            }
+        }
+
+        if let Some((t, _)) = ray.triangle_intersection(triangle) {
+            if closest.map_or(true, |(closest_t, _, _)| t < closest_t) {
+                closest = Some((t, index, *triangle));
+            }
+        }
+    }
+    closest
+}
+
+impl Graph {
+    fn cast_ray_static_surface(
+        &self,
+        surface: &Surface,
+        local_ray: &Ray,
+        ignore_back_faces: bool,
+        query_buffer: &mut Vec<u32>,
+    ) -> Option<(f32, usize, [Vector3<f32>; 3])> {
+        let shared_data = surface.data();
+
+        let mut surfaces = self.ray_cast_cache.surfaces.lock();
+
+        let data = shared_data.lock();
+        let vertex_hash = data.vertex_buffer.data_hash();
+        let triangle_hash = data.geometry_buffer.data_hash();
+
+        let entry = surfaces
+            .entry(shared_data.key())
+            .or_insert_with(|| CachedSurface {
+                data: shared_data.downgrade(),
+                // Force the octree to be built.
+                vertex_hash: !vertex_hash,
+                triangle_hash,
+                triangles: Default::default(),
+                octree: Default::default(),
+            });
+
+        if entry.vertex_hash != vertex_hash || entry.triangle_hash != triangle_hash {
+            entry.data = shared_data.downgrade();
+            entry.vertex_hash = vertex_hash;
+            entry.triangle_hash = triangle_hash;
+            entry.triangles = read_triangles(&data, &|index| read_position(&data, index));
+            entry.octree = Octree::new(&entry.triangles, 32);
+        }
+
+        drop(data);
+
+        entry.octree.ray_query(local_ray, query_buffer);
+        // Triangle could be in multiple leafs of the octree.
+        query_buffer.sort_unstable();
+        query_buffer.dedup();
+
+        closest_triangle(
+            query_buffer
+                .iter()
+                .filter_map(|&i| entry.triangles.get(i as usize).map(|t| (i as usize, t))),
+            local_ray,
+            ignore_back_faces,
+        )
+    }
+
+    /// Casts a ray against actual triangles of a single mesh node (including skinned meshes with their
+    /// current pose). The ray must be in world coordinates, its direction vector defines the length
+    /// of the ray. Returns closest intersection, or `None` if there is no intersection or the node is
+    /// not a mesh. Static surfaces use octrees, that are built on demand and cached in the graph.
+    pub fn cast_ray_mesh(
+        &self,
+        node: Handle<Node>,
+        ray: &Ray,
+        ignore_back_faces: bool,
+    ) -> Option<GeometryIntersection> {
+        let mesh = self.try_get(node)?.query_component_ref::<Mesh>()?;
+
+        let global_transform = mesh.global_transform();
+        let inv_global_transform = global_transform.try_inverse()?;
+        let local_ray = ray.transform(inv_global_transform);
+        // Mirroring transform changes winding order of triangles.
+        let mirrored = global_transform.determinant() < 0.0;
+
+        let mut query_buffer = Vec::new();
+        let mut closest: Option<(f32, usize, usize, [Vector3<f32>; 3])> = None;
+
+        for (surface_index, surface) in mesh.surfaces().iter().enumerate() {
+            let result = if surface.bones().is_empty() {
+                self.cast_ray_static_surface(
+                    surface,
+                    &local_ray,
+                    ignore_back_faces && !mirrored,
+                    &mut query_buffer,
+                )
+                .map(|(t, index, triangle)| {
+                    (
+                        t,
+                        index,
+                        triangle.map(|v| global_transform.transform_point(&Point3::from(v)).coords),
+                    )
+                })
+            } else {
+                cast_ray_skinned_surface(self, surface, ray, ignore_back_faces)
+            };
+
+            if let Some((t, triangle_index, triangle)) = result {
+                if closest.map_or(true, |(closest_t, _, _, _)| t < closest_t) {
+                    closest = Some((t, surface_index, triangle_index, triangle));
+                }
+            }
+        }
+
+        closest.map(|(t, surface, triangle_index, triangle)| {
+            let position = ray.get_point(t);
+            GeometryIntersection {
+                node,
+                surface,
+                triangle: triangle_index,
+                position: Point3::from(position),
+                normal: (triangle[1] - triangle[0])
+                    .cross(&(triangle[2] - triangle[0]))
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default(),
+                toi: position.metric_distance(&ray.origin),
+            }
+        })
+    }
+
+    /// Casts a ray against actual triangles of every mesh in the graph (including skinned meshes with
+    /// their current pose), independently of colliders. It is useful for precise hit detection, decal
+    /// placement, picking, etc. Only one (closest) intersection per mesh is reported.
+    ///
+    /// # Performance
+    ///
+    /// Meshes are culled by their bounding boxes first, then static surfaces are tested using octrees,
+    /// that are built on first use and cached until the geometry changes. Skinned surfaces are tested
+    /// triangle-by-triangle, because their geometry changes every frame.
+    pub fn cast_ray_geometry(
+        &self,
+        opts: GeometryRayCastOptions,
+        query_buffer: &mut Vec<GeometryIntersection>,
+    ) {
+        query_buffer.clear();
+
+        self.ray_cast_cache.remove_unused();
+
+        let ray = Ray::new(
+            opts.ray_origin.coords,
+            opts.ray_direction
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_default()
+                .scale(opts.max_len),
+        );
+
+        for (handle, node) in self.pair_iter() {
+            let mesh = match node.query_component_ref::<Mesh>() {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+
+            if opts.ignore_invisible && !mesh.global_visibility() {
+                continue;
+            }
+
+            // Bounding box will be invalid if the graph was not updated yet after the mesh was
+            // created, skip coarse test in this case.
+            let bounds = mesh.world_bounding_box();
+            if bounds.min.x <= bounds.max.x
+                && ray.box_intersection(&bounds.min, &bounds.max).is_none()
+            {
+                continue;
+            }
+
+            if let Some(intersection) = self.cast_ray_mesh(handle, &ray, opts.ignore_back_faces) {
+                query_buffer.push(intersection);
+            }
+        }
+
+        if opts.sort_results {
+            query_buffer.sort_by(|a, b| a.toi.partial_cmp(&b.toi).unwrap_or(Ordering::Equal));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Point3, Vector3},
+            math::ray::Ray,
+        },
+        scene::{
+            base::BaseBuilder,
+            graph::{raycast::GeometryRayCastOptions, Graph},
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            transform::TransformBuilder,
+        },
+    };
+
+    fn make_cube(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+    ) -> crate::core::pool::Handle<crate::scene::node::Node> {
+        MeshBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+            SurfaceData::make_cube(Matrix4::identity()),
+        ))
+        .build()])
+        .build(graph)
+    }
+
+    #[test]
+    fn test_cast_ray_geometry() {
+        let mut graph = Graph::new();
+        let near = make_cube(&mut graph, Vector3::new(0.0, 0.0, 5.0));
+        let far = make_cube(&mut graph, Vector3::new(0.0, 0.0, 10.0));
+        let aside = make_cube(&mut graph, Vector3::new(5.0, 0.0, 5.0));
+        graph.update_hierarchical_data();
+
+        let mut results = Vec::new();
+        graph.cast_ray_geometry(
+            GeometryRayCastOptions {
+                ray_origin: Point3::origin(),
+                ray_direction: Vector3::z(),
+                max_len: 100.0,
+                ignore_back_faces: false,
+                ignore_invisible: true,
+                sort_results: true,
+            },
+            &mut results,
+        );
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].node, near);
+        assert_eq!(results[1].node, far);
+        assert!(results.iter().all(|r| r.node != aside));
+        // Unit cube, so the closest face is at 4.5.
+        assert!((results[0].toi - 4.5).abs() < 0.001);
+        assert!((results[0].position.z - 4.5).abs() < 0.001);
+        assert!((results[0].normal.z.abs() - 1.0).abs() < 0.001);
+
+        // Too short ray.
+        graph.cast_ray_geometry(
+            GeometryRayCastOptions {
+                ray_origin: Point3::origin(),
+                ray_direction: Vector3::z(),
+                max_len: 4.0,
+                ignore_back_faces: false,
+                ignore_invisible: true,
+                sort_results: true,
+            },
+            &mut results,
+        );
+        assert!(results.is_empty());
+
+        // Moved mesh must be hit at new location.
+        graph[near]
+            .local_transform_mut()
+            .set_position(Vector3::new(0.0, 0.0, 2.0));
+        graph.update_hierarchical_data();
+        let hit = graph
+            .cast_ray_mesh(
+                near,
+                &Ray::new(Vector3::default(), Vector3::new(0.0, 0.0, 100.0)),
+                false,
+            )
+            .unwrap();
+        assert!((hit.toi - 1.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_geometry_change() {
+        let mut graph = Graph::new();
+        let cube = make_cube(&mut graph, Vector3::new(0.0, 0.0, 5.0));
+        graph.update_hierarchical_data();
+
+        let ray = Ray::new(Vector3::default(), Vector3::new(0.0, 0.0, 100.0));
+        assert!((graph.cast_ray_mesh(cube, &ray, false).unwrap().toi - 4.5).abs() < 0.001);
+
+        // Scale geometry of the surface, octree must be re-built.
+        graph[cube].as_mesh_mut().surfaces_mut()[0]
+            .data()
+            .lock()
+            .transform_geometry(&Matrix4::new_scaling(2.0))
+            .unwrap();
+        assert!((graph.cast_ray_mesh(cube, &ray, false).unwrap().toi - 4.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,479 @@
+//! Physical surface types and per-pair contact responses. See [`SurfaceResponseTable`] docs for
+//! more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        futures::executor::block_on,
+        pool::Handle,
+        visitor::{prelude::*, VisitError},
+    },
+    scene::node::Node,
+};
+use fxhash::FxHashMap;
+use fyrox_core::parking_lot::Mutex;
+use rapier3d::{
+    dynamics::{RigidBodyHandle, RigidBodySet},
+    geometry::{ColliderHandle, ColliderSet, CollisionEvent, ContactPair},
+    pipeline::{ContactModificationContext, EventHandler, PhysicsHooks},
+};
+use std::path::{Path, PathBuf};
+
+/// Returns a numeric identifier of the given surface type. Empty surface type has `0` id, which
+/// means "no surface type".
+pub(crate) fn surface_type_id(surface_type: &str) -> u64 {
+    if surface_type.is_empty() {
+        0
+    } else {
+        // Zero is reserved for "no surface type", collisions with it are practically impossible.
+        fxhash::hash64(surface_type).max(1)
+    }
+}
+
+/// Defines how a pair of surface types responds to contacts.
+#[derive(Clone, Debug, Default, PartialEq, Visit)]
+pub struct SurfaceResponse {
+    /// Friction coefficient, that will be used for contacts between the surfaces. `None` - friction
+    /// is calculated from friction values of colliders using their combine rules.
+    pub friction: Option<f32>,
+    /// Restitution coefficient, that will be used for contacts between the surfaces. `None` -
+    /// restitution is calculated from restitution values of colliders using their combine rules.
+    pub restitution: Option<f32>,
+    /// A path to a sound, that should be played when the surfaces start touching each other.
+    pub sound: Option<PathBuf>,
+    /// A path to a particle effect prefab, that should be spawned when the surfaces start touching
+    /// each other.
+    pub particles: Option<PathBuf>,
+    /// Minimal relative velocity (along contact normal) of the surfaces at which contact events will
+    /// be generated. It is used to filter out weak contacts (for example, a box that slowly slides on
+    /// a floor should not make any sounds).
+    pub min_velocity: f32,
+    /// Arbitrary user-defined identifier of a gameplay reaction, for example `"ricochet"`.
+    pub tag: String,
+}
+
+impl SurfaceResponse {
+    /// Sets desired friction.
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = Some(friction);
+        self
+    }
+
+    /// Sets desired restitution.
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = Some(restitution);
+        self
+    }
+
+    /// Sets desired sound path.
+    pub fn with_sound<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.sound = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets desired particle effect path.
+    pub fn with_particles<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.particles = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets minimal relative velocity of contacts.
+    pub fn with_min_velocity(mut self, min_velocity: f32) -> Self {
+        self.min_velocity = min_velocity;
+        self
+    }
+
+    /// Sets user-defined tag.
+    pub fn with_tag(mut self, tag: String) -> Self {
+        self.tag = tag;
+        self
+    }
+}
+
+/// A response for a pair of surface types. The order of surface types does not matter.
+#[derive(Clone, Debug, Default, PartialEq, Visit)]
+pub struct SurfacePairResponse {
+    /// First surface type.
+    pub surface_a: String,
+    /// Second surface type.
+    pub surface_b: String,
+    /// Response of the pair.
+    pub response: SurfaceResponse,
+}
+
+fn pair_key(a: u64, b: u64) -> (u64, u64) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// A table, that maps pairs of surface types (see [`crate::scene::collider::Collider::surface_type`])
+/// to contact responses. A response defines friction and restitution of contacts between the
+/// surfaces (overriding values of the colliders) and gameplay reactions, such as sounds and
+/// particles, that should be played when the surfaces start touching each other.
+///
+/// The table is meant to be configured per project, stored in a file and assigned to
+/// [`super::physics::PhysicsWorld::surface_responses`] of every scene. Gameplay reactions are
+/// delivered via [`super::physics::PhysicsWorld::surface_contact_events`].
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::{
+///     graph::surface_response::{SurfaceResponse, SurfaceResponseTable},
+///     Scene,
+/// };
+///
+/// fn setup_surfaces(scene: &mut Scene) {
+///     let mut table = SurfaceResponseTable::default();
+///     table.set_response(
+///         "metal",
+///         "wood",
+///         SurfaceResponse::default()
+///             .with_friction(0.4)
+///             .with_sound("data/sounds/metal_wood_hit.ogg"),
+///     );
+///     table.set_response(
+///         "flesh",
+///         "metal",
+///         SurfaceResponse::default().with_restitution(0.0),
+///     );
+///     scene.graph.physics.surface_responses = table;
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SurfaceResponseTable {
+    pairs: Vec<SurfacePairResponse>,
+    lookup: FxHashMap<(u64, u64), usize>,
+}
+
+impl Visit for SurfaceResponseTable {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.pairs.visit("Pairs", &mut region)?;
+
+        if region.is_reading() {
+            self.rebuild_lookup();
+        }
+
+        Ok(())
+    }
+}
+
+impl SurfaceResponseTable {
+    fn rebuild_lookup(&mut self) {
+        self.lookup = self
+            .pairs
+            .iter()
+            .enumerate()
+            .map(|(i, pair)| {
+                (
+                    pair_key(
+                        surface_type_id(&pair.surface_a),
+                        surface_type_id(&pair.surface_b),
+                    ),
+                    i,
+                )
+            })
+            .collect();
+    }
+
+    /// Sets a response for the given pair of surface types. The order of surface types does not
+    /// matter. Returns previous response of the pair, if any.
+    pub fn set_response<A: AsRef<str>, B: AsRef<str>>(
+        &mut self,
+        surface_a: A,
+        surface_b: B,
+        response: SurfaceResponse,
+    ) -> Option<SurfaceResponse> {
+        let key = pair_key(
+            surface_type_id(surface_a.as_ref()),
+            surface_type_id(surface_b.as_ref()),
+        );
+        if let Some(&index) = self.lookup.get(&key) {
+            Some(std::mem::replace(&mut self.pairs[index].response, response))
+        } else {
+            self.lookup.insert(key, self.pairs.len());
+            self.pairs.push(SurfacePairResponse {
+                surface_a: surface_a.as_ref().to_owned(),
+                surface_b: surface_b.as_ref().to_owned(),
+                response,
+            });
+            None
+        }
+    }
+
+    /// Removes a response of the given pair of surface types.
+    pub fn remove_response(&mut self, surface_a: &str, surface_b: &str) -> Option<SurfaceResponse> {
+        let key = pair_key(surface_type_id(surface_a), surface_type_id(surface_b));
+        let index = self.lookup.get(&key).cloned()?;
+        let pair = self.pairs.remove(index);
+        self.rebuild_lookup();
+        Some(pair.response)
+    }
+
+    /// Returns a response of the given pair of surface types.
+    pub fn response(&self, surface_a: &str, surface_b: &str) -> Option<&SurfaceResponse> {
+        self.response_by_id(surface_type_id(surface_a), surface_type_id(surface_b))
+    }
+
+    pub(crate) fn response_by_id(&self, a: u64, b: u64) -> Option<&SurfaceResponse> {
+        self.lookup
+            .get(&pair_key(a, b))
+            .map(|&index| &self.pairs[index].response)
+    }
+
+    /// Returns a slice with every pair of the table.
+    pub fn pairs(&self) -> &[SurfacePairResponse] {
+        &self.pairs
+    }
+
+    /// Returns `true` if the table has no responses.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Loads the table from the given file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, VisitError> {
+        let mut table = Self::default();
+        let mut visitor = block_on(Visitor::load_binary(path))?;
+        table.visit("SurfaceResponseTable", &mut visitor)?;
+        Ok(table)
+    }
+
+    /// Saves the table to the given file.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<(), VisitError> {
+        let mut visitor = Visitor::new();
+        self.visit("SurfaceResponseTable", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+}
+
+impl PhysicsHooks for SurfaceResponseTable {
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+        let (a, b) = match (
+            context.colliders.get(context.collider1),
+            context.colliders.get(context.collider2),
+        ) {
+            (Some(a), Some(b)) => (a.user_data as u64, b.user_data as u64),
+            _ => return,
+        };
+
+        if let Some(response) = self.response_by_id(a, b) {
+            for contact in context.solver_contacts.iter_mut() {
+                if let Some(friction) = response.friction {
+                    contact.friction = friction;
+                }
+                if let Some(restitution) = response.restitution {
+                    contact.restitution = restitution;
+                }
+            }
+        }
+    }
+}
+
+/// An event, that is generated when two colliders with surface types start touching each other.
+#[derive(Clone, Debug)]
+pub struct SurfaceContactEvent {
+    /// First collider of the contact.
+    pub collider1: Handle<Node>,
+    /// Second collider of the contact.
+    pub collider2: Handle<Node>,
+    /// Surface type of the first collider.
+    pub surface1: String,
+    /// Surface type of the second collider.
+    pub surface2: String,
+    /// World-space position of the contact.
+    pub position: Point3<f32>,
+    /// World-space normal of the contact (from the first collider to the second).
+    pub normal: Vector3<f32>,
+    /// Relative velocity of the colliders along the contact normal at the moment of contact.
+    pub velocity: f32,
+    /// Response of the pair of surfaces, `None` if the table has no response for the pair.
+    pub response: Option<SurfaceResponse>,
+}
+
+pub(crate) struct RawSurfaceContact {
+    pub collider1: ColliderHandle,
+    pub collider2: ColliderHandle,
+    pub surface1: u64,
+    pub surface2: u64,
+    pub position: Point3<f32>,
+    pub normal: Vector3<f32>,
+    pub velocity: f32,
+}
+
+/// Collects contact events of colliders with surface types.
+#[derive(Default)]
+pub(crate) struct SurfaceEventCollector {
+    pub contacts: Mutex<Vec<RawSurfaceContact>>,
+}
+
+impl EventHandler for SurfaceEventCollector {
+    fn handle_collision_event(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: CollisionEvent,
+        contact_pair: Option<&ContactPair>,
+    ) {
+        let (contact_pair, collider1, collider2) = match (event, contact_pair) {
+            (CollisionEvent::Started(collider1, collider2, _), Some(contact_pair)) => {
+                (contact_pair, collider1, collider2)
+            }
+            // Intersections of sensors and stopped contacts do not generate any reactions.
+            _ => return,
+        };
+
+        let (surface1, surface2) = match (colliders.get(collider1), colliders.get(collider2)) {
+            (Some(a), Some(b)) => (a.user_data as u64, b.user_data as u64),
+            _ => return,
+        };
+
+        let manifold = match contact_pair
+            .manifolds
+            .iter()
+            .find(|m| !m.data.solver_contacts.is_empty())
+        {
+            Some(manifold) => manifold,
+            None => return,
+        };
+
+        let position = manifold.data.solver_contacts[0].point;
+        let normal = manifold.data.normal;
+
+        let velocity_of = |body: Option<RigidBodyHandle>| {
+            body.and_then(|h| bodies.get(h))
+                .map_or_else(Vector3::default, |b| b.velocity_at_point(&position))
+        };
+        let velocity = (velocity_of(manifold.data.rigid_body1)
+            - velocity_of(manifold.data.rigid_body2))
+        .dot(&normal)
+        .abs();
+
+        self.contacts.lock().push(RawSurfaceContact {
+            collider1,
+            collider2,
+            surface1,
+            surface2,
+            position,
+            normal,
+            velocity,
+        });
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: f32,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &ContactPair,
+        _total_force_magnitude: f32,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            visitor::prelude::*,
+        },
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::{
+                surface_response::{SurfaceResponse, SurfaceResponseTable},
+                Graph,
+            },
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_surface_response_table() {
+        let mut table = SurfaceResponseTable::default();
+        assert!(table
+            .set_response(
+                "metal",
+                "wood",
+                SurfaceResponse::default().with_friction(0.3)
+            )
+            .is_none());
+        table.set_response("flesh", "metal", SurfaceResponse::default());
+
+        // Order does not matter.
+        assert_eq!(table.response("wood", "metal").unwrap().friction, Some(0.3));
+        assert!(table.response("wood", "wood").is_none());
+
+        let mut visitor = Visitor::new();
+        table.visit("Table", &mut visitor).unwrap();
+        let bytes = visitor.save_binary_to_vec().unwrap();
+
+        let mut loaded = SurfaceResponseTable::default();
+        let mut visitor = Visitor::load_from_memory(bytes).unwrap();
+        loaded.visit("Table", &mut visitor).unwrap();
+        assert_eq!(loaded.pairs(), table.pairs());
+        assert_eq!(
+            loaded.response("metal", "wood").unwrap().friction,
+            Some(0.3)
+        );
+
+        assert!(loaded.remove_response("metal", "wood").is_some());
+        assert!(loaded.response("metal", "wood").is_none());
+        assert!(loaded.response("metal", "flesh").is_some());
+    }
+
+    #[test]
+    fn test_surface_contact_events() {
+        let mut graph = Graph::new();
+
+        let floor = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 0.1, 5.0))
+            .with_surface_type("wood".to_string())
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[floor]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let crate_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.25, 0.25, 0.25))
+            .with_surface_type("metal".to_string())
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.5, 0.0))
+                        .build(),
+                )
+                .with_children(&[crate_collider]),
+        )
+        .build(&mut graph);
+
+        graph.physics.surface_responses.set_response(
+            "metal",
+            "wood",
+            SurfaceResponse::default().with_tag("clang".to_string()),
+        );
+
+        let mut events = Vec::new();
+        for _ in 0..120 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            events.extend(graph.physics.surface_contact_events().iter().cloned());
+        }
+
+        let event = events.first().expect("Crate must hit the floor!");
+        assert!(
+            (event.collider1 == floor && event.collider2 == crate_collider)
+                || (event.collider1 == crate_collider && event.collider2 == floor)
+        );
+        assert!(event.velocity > 0.0);
+        assert_eq!(event.response.as_ref().unwrap().tag, "clang");
+    }
+}
@@ -0,0 +1,489 @@
+//! Gravity zone is a volume that overrides gravity of the scene for rigid bodies inside it. See
+//! [`GravityZone`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector3},
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
+        rigidbody::{RigidBody, RigidBodyType},
+    },
+};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Defines direction of the gravity of a gravity zone.
+#[derive(
+    Copy, Clone, Debug, Reflect, Visit, PartialEq, Eq, Hash, AsRefStr, EnumString, EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum GravityZoneKind {
+    /// Gravity is directed along negative up vector (local -Y axis) of the zone. Rotate the zone
+    /// to change direction of the gravity, it is useful for rotating space stations, walking on
+    /// walls, etc.
+    Directional = 0,
+    /// Gravity is directed to the center of the zone, it is useful for planets.
+    Radial = 1,
+}
+
+impl Default for GravityZoneKind {
+    fn default() -> Self {
+        Self::Directional
+    }
+}
+
+/// Defines bounds of a gravity zone in local coordinates of the zone.
+#[derive(Clone, Debug, Reflect, Visit, PartialEq, AsRefStr, EnumString, EnumVariantNames)]
+pub enum GravityZoneShape {
+    /// Spherical zone.
+    Sphere {
+        /// Radius of the sphere.
+        radius: f32,
+    },
+    /// Box-shaped zone.
+    Cuboid {
+        /// Half of the size of the box along each axis.
+        half_extents: Vector3<f32>,
+    },
+}
+
+impl Default for GravityZoneShape {
+    fn default() -> Self {
+        Self::Cuboid {
+            half_extents: Vector3::new(5.0, 5.0, 5.0),
+        }
+    }
+}
+
+impl GravityZoneShape {
+    /// Checks whether the given point in local coordinates of a zone is inside the shape or not.
+    pub fn contains_local_point(&self, point: Vector3<f32>) -> bool {
+        match self {
+            GravityZoneShape::Sphere { radius } => point.norm() <= *radius,
+            GravityZoneShape::Cuboid { half_extents } => {
+                point.x.abs() <= half_extents.x
+                    && point.y.abs() <= half_extents.y
+                    && point.z.abs() <= half_extents.z
+            }
+        }
+    }
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let half_extents = match self {
+            GravityZoneShape::Sphere { radius } => Vector3::repeat(*radius),
+            GravityZoneShape::Cuboid { half_extents } => *half_extents,
+        };
+        AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+    }
+}
+
+/// Gravity zone is a volume that overrides gravity of the scene (see
+/// [`crate::scene::graph::physics::PhysicsWorld::gravity`]) for dynamic rigid bodies inside it.
+/// It could be used to create planets, space stations, anti-gravity rooms and so on.
+///
+/// ## Overlapping zones
+///
+/// When a body is inside of multiple zones, only the zone with the highest priority affects the
+/// body.
+///
+/// ## Gravity scale
+///
+/// Gravity of a zone is multiplied by the gravity scale of a body (see
+/// [`RigidBody::set_gravity_scale`]), the same as the gravity of the scene.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{
+///         base::BaseBuilder,
+///         gravity_zone::{GravityZoneBuilder, GravityZoneKind, GravityZoneShape},
+///         graph::Graph,
+///         node::Node,
+///     },
+/// };
+///
+/// fn create_planet_gravity(graph: &mut Graph) -> Handle<Node> {
+///     GravityZoneBuilder::new(BaseBuilder::new())
+///         .with_kind(GravityZoneKind::Radial)
+///         .with_shape(GravityZoneShape::Sphere { radius: 100.0 })
+///         .with_strength(3.7)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Clone, Visit, Reflect)]
+pub struct GravityZone {
+    base: Base,
+
+    #[reflect(setter = "set_kind")]
+    kind: InheritableVariable<GravityZoneKind>,
+
+    #[reflect(setter = "set_shape")]
+    shape: InheritableVariable<GravityZoneShape>,
+
+    #[reflect(step = 0.1, setter = "set_strength")]
+    strength: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_priority")]
+    priority: InheritableVariable<i32>,
+}
+
+impl Default for GravityZone {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            kind: Default::default(),
+            shape: Default::default(),
+            strength: InheritableVariable::new(9.81),
+            priority: Default::default(),
+        }
+    }
+}
+
+impl Deref for GravityZone {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for GravityZone {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for GravityZone {
+    fn type_uuid() -> Uuid {
+        uuid!("5f0c6f0e-8d7b-4a8e-b3a4-2c9d1e7f6b52")
+    }
+}
+
+impl GravityZone {
+    /// Sets new kind of the zone.
+    pub fn set_kind(&mut self, kind: GravityZoneKind) -> GravityZoneKind {
+        self.kind.set_value_and_mark_modified(kind)
+    }
+
+    /// Returns current kind of the zone.
+    pub fn kind(&self) -> GravityZoneKind {
+        *self.kind
+    }
+
+    /// Sets new shape of the zone.
+    pub fn set_shape(&mut self, shape: GravityZoneShape) -> GravityZoneShape {
+        self.shape.set_value_and_mark_modified(shape)
+    }
+
+    /// Returns current shape of the zone.
+    pub fn shape(&self) -> &GravityZoneShape {
+        &self.shape
+    }
+
+    /// Sets new strength of the gravity (acceleration in m/s²) of the zone.
+    pub fn set_strength(&mut self, strength: f32) -> f32 {
+        self.strength.set_value_and_mark_modified(strength)
+    }
+
+    /// Returns current strength of the gravity of the zone.
+    pub fn strength(&self) -> f32 {
+        *self.strength
+    }
+
+    /// Sets new priority of the zone. When zones overlap, only the zone with the highest priority
+    /// affects bodies in the overlapping area.
+    pub fn set_priority(&mut self, priority: i32) -> i32 {
+        self.priority.set_value_and_mark_modified(priority)
+    }
+
+    /// Returns current priority of the zone.
+    pub fn priority(&self) -> i32 {
+        *self.priority
+    }
+
+    /// Checks whether the given point in world coordinates is inside the zone or not.
+    pub fn contains_point(&self, point: Vector3<f32>) -> bool {
+        contains_point(&self.shape, &self.global_transform(), point)
+    }
+
+    /// Calculates gravity at the given point in world coordinates. Returns `None` if the point is
+    /// outside of the zone.
+    pub fn gravity_at(&self, point: Vector3<f32>) -> Option<Vector3<f32>> {
+        if !self.contains_point(point) {
+            return None;
+        }
+
+        let direction = match *self.kind {
+            GravityZoneKind::Directional => -self.up_vector().try_normalize(f32::EPSILON)?,
+            // Body in the center of radial zone is weightless.
+            GravityZoneKind::Radial => (self.global_position() - point)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_default(),
+        };
+
+        Some(direction.scale(*self.strength))
+    }
+
+    // Checks whether the zone affects bodies at the given point, taking other zones into account.
+    fn is_dominant_at(&self, point: Vector3<f32>, rivals: &[Rival]) -> bool {
+        let self_index = self.self_handle.index();
+        !rivals.iter().any(|rival| {
+            (rival.priority > *self.priority
+                || (rival.priority == *self.priority && rival.index < self_index))
+                && contains_point(&rival.shape, &rival.transform, point)
+        })
+    }
+}
+
+fn contains_point(shape: &GravityZoneShape, transform: &Matrix4<f32>, point: Vector3<f32>) -> bool {
+    transform.try_inverse().map_or(false, |inv_transform| {
+        shape.contains_local_point(inv_transform.transform_point(&Point3::from(point)).coords)
+    })
+}
+
+struct Rival {
+    index: u32,
+    priority: i32,
+    transform: Matrix4<f32>,
+    shape: GravityZoneShape,
+}
+
+impl NodeTrait for GravityZone {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.shape.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        // Other zones that could override this one.
+        let rivals = context
+            .nodes
+            .pair_iter()
+            .filter_map(|(handle, node)| {
+                node.cast::<GravityZone>()
+                    .filter(|zone| zone.is_globally_enabled())
+                    .map(|zone| Rival {
+                        index: handle.index(),
+                        priority: *zone.priority,
+                        transform: zone.global_transform(),
+                        shape: (*zone.shape).clone(),
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let scene_gravity = context.physics.gravity;
+
+        for node in context.nodes.iter_mut() {
+            if let Some(body) = node.cast_mut::<RigidBody>() {
+                if body.body_type() != RigidBodyType::Dynamic {
+                    continue;
+                }
+
+                let position = body.global_position();
+                if let Some(gravity) = self.gravity_at(position) {
+                    if !self.is_dominant_at(position, &rivals) {
+                        continue;
+                    }
+
+                    // Scene gravity is applied by the physics engine, so it must be compensated.
+                    // Mass of the body is unknown until its colliders are attached to it.
+                    let mass = match context.physics.native_body(body.native.get()) {
+                        Some(native) if !native.colliders().is_empty() => native.mass(),
+                        _ => continue,
+                    };
+                    body.apply_force((gravity - scene_gravity).scale(mass * body.gravity_scale()));
+                    body.wake_up();
+                }
+            }
+        }
+    }
+}
+
+/// Allows you to create gravity zones in declarative manner.
+pub struct GravityZoneBuilder {
+    base_builder: BaseBuilder,
+    kind: GravityZoneKind,
+    shape: GravityZoneShape,
+    strength: f32,
+    priority: i32,
+}
+
+impl GravityZoneBuilder {
+    /// Creates new gravity zone builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            kind: Default::default(),
+            shape: Default::default(),
+            strength: 9.81,
+            priority: 0,
+        }
+    }
+
+    /// Sets desired kind of the zone.
+    pub fn with_kind(mut self, kind: GravityZoneKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets desired shape of the zone.
+    pub fn with_shape(mut self, shape: GravityZoneShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets desired strength of the gravity of the zone.
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// Sets desired priority of the zone.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Creates new gravity zone instance.
+    pub fn build_gravity_zone(self) -> GravityZone {
+        GravityZone {
+            base: self.base_builder.build_base(),
+            kind: self.kind.into(),
+            shape: self.shape.into(),
+            strength: self.strength.into(),
+            priority: self.priority.into(),
+        }
+    }
+
+    /// Creates new gravity zone node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_gravity_zone())
+    }
+
+    /// Creates new gravity zone node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            gravity_zone::{GravityZone, GravityZoneBuilder, GravityZoneKind, GravityZoneShape},
+            node::Node,
+            rigidbody::RigidBodyBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn make_body(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .build(graph)
+    }
+
+    #[test]
+    fn test_gravity_at() {
+        let mut graph = Graph::new();
+        let zone = GravityZoneBuilder::new(BaseBuilder::new())
+            .with_kind(GravityZoneKind::Radial)
+            .with_shape(GravityZoneShape::Sphere { radius: 2.0 })
+            .with_strength(10.0)
+            .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let zone = graph[zone].query_component_ref::<GravityZone>().unwrap();
+        assert_eq!(
+            zone.gravity_at(Vector3::new(1.0, 0.0, 0.0)),
+            Some(Vector3::new(-10.0, 0.0, 0.0))
+        );
+        assert_eq!(zone.gravity_at(Vector3::new(3.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_zone_overrides_scene_gravity() {
+        let mut graph = Graph::new();
+
+        // Zero gravity room with a higher priority zone with upside-down gravity inside it.
+        GravityZoneBuilder::new(BaseBuilder::new())
+            .with_strength(0.0)
+            .with_shape(GravityZoneShape::Cuboid {
+                half_extents: Vector3::new(10.0, 10.0, 10.0),
+            })
+            .build(&mut graph);
+        GravityZoneBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(5.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_strength(-5.0)
+        .with_priority(1)
+        .with_shape(GravityZoneShape::Sphere { radius: 4.0 })
+        .build(&mut graph);
+
+        let weightless = make_body(&mut graph, Vector3::new(-5.0, 0.0, 0.0));
+        let upside_down = make_body(&mut graph, Vector3::new(5.0, 0.0, 0.0));
+        let outside = make_body(&mut graph, Vector3::new(50.0, 0.0, 0.0));
+
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        // Colliders are attached to the bodies on the second physics step, zones affect the bodies
+        // starting from the next one.
+        let velocity = graph[weightless].as_rigid_body().lin_vel();
+        assert!(velocity.norm() < 0.4, "{:?}", velocity);
+
+        let velocity = graph[upside_down].as_rigid_body().lin_vel();
+        assert!(velocity.y > 4.0 && velocity.y < 6.0, "{:?}", velocity);
+
+        let velocity = graph[outside].as_rigid_body().lin_vel();
+        assert!(velocity.y < -9.0, "{:?}", velocity);
+    }
+}
@@ -2,10 +2,10 @@
 //! excellent example in real life - Sun. It does not have position,
 //! only direction which defined by parent light scene node.
 //!
-//! # Notes
+//! # Shadows
 //!
-//! Current directional light does *not* support shadows, it is still
-//! on list of features that should be implemented.
+//! Directional light uses cascaded shadow maps, see [`CsmOptions`] for
+//! per-light settings of the cascades.
 
 use crate::{
     core::{
@@ -55,6 +55,17 @@ pub enum FrustumSplitOptions {
         /// sub-frustum will be relative to camera's frustum.
         fractions: [f32; CSM_NUM_CASCADES],
     },
+    /// Camera frustum will be split using so called "practical split scheme", which is a mix of
+    /// logarithmic and uniform splits. Logarithmic splits give better quality near the camera,
+    /// while uniform splits distribute quality evenly along the whole frustum.
+    ///
+    /// This option does not require any tweaking of individual cascades and adapts to the current
+    /// near and far planes of a camera.
+    Logarithmic {
+        /// A blend factor in `[0; 1]` range between uniform (`0.0`) and logarithmic (`1.0`)
+        /// splits. The value of `0.5..0.9` usually gives good results.
+        lambda: f32,
+    },
 }
 
 impl Default for FrustumSplitOptions {
@@ -71,30 +82,99 @@ pub struct CsmOptions {
     /// See [`FrustumSplitOptions`].
     pub split_options: FrustumSplitOptions,
 
+    #[reflect(min_value = 1.0, max_value = 3.0, step = 1.0)]
+    #[visit(optional)] // Backward compatibility.
+    cascade_count: u32,
+
     #[reflect(min_value = 0.0, step = 0.000025)]
-    shadow_bias: f32,
+    #[visit(optional)] // Backward compatibility.
+    shadow_biases: [f32; CSM_NUM_CASCADES],
+
+    #[visit(optional)] // Backward compatibility.
+    stabilize: bool,
 }
 
 impl Default for CsmOptions {
     fn default() -> Self {
         Self {
             split_options: Default::default(),
-            shadow_bias: 0.00025,
+            cascade_count: CSM_NUM_CASCADES as u32,
+            shadow_biases: [0.00025; CSM_NUM_CASCADES],
+            stabilize: false,
         }
     }
 }
 
 impl CsmOptions {
-    /// Sets new shadow bias value. Shadow bias allows you to prevent "shadow-acne" effect by
-    /// shifting values fetched from shadow map by a certain value. "Shadow acne" occur due to
-    /// insufficient precision.
+    /// Sets new shadow bias value for every cascade. Shadow bias allows you to prevent
+    /// "shadow-acne" effect by shifting values fetched from shadow map by a certain value.
+    /// "Shadow acne" occur due to insufficient precision.
     pub fn set_shadow_bias(&mut self, bias: f32) {
-        self.shadow_bias = bias.max(0.0);
+        self.shadow_biases = [bias.max(0.0); CSM_NUM_CASCADES];
     }
 
-    /// Returns current shadow bias value.
+    /// Returns shadow bias value of the first cascade.
     pub fn shadow_bias(&self) -> f32 {
-        self.shadow_bias
+        self.shadow_biases[0]
+    }
+
+    /// Sets new shadow bias value for a cascade with the given index. Distant cascades cover larger
+    /// area with the same amount of texels, so they usually need larger bias. Does nothing if the
+    /// index is out of bounds.
+    pub fn set_cascade_shadow_bias(&mut self, cascade: usize, bias: f32) {
+        if let Some(shadow_bias) = self.shadow_biases.get_mut(cascade) {
+            *shadow_bias = bias.max(0.0);
+        }
+    }
+
+    /// Returns shadow bias values of every cascade.
+    pub fn shadow_biases(&self) -> &[f32; CSM_NUM_CASCADES] {
+        &self.shadow_biases
+    }
+
+    /// Sets new amount of cascades. The value will be clamped to `[1; CSM_NUM_CASCADES]` range.
+    /// Lesser amount of cascades improves performance, but gives worse quality of distant shadows.
+    pub fn set_cascade_count(&mut self, count: usize) {
+        self.cascade_count = count.clamp(1, CSM_NUM_CASCADES) as u32;
+    }
+
+    /// Returns current amount of cascades.
+    pub fn cascade_count(&self) -> usize {
+        (self.cascade_count as usize).clamp(1, CSM_NUM_CASCADES)
+    }
+
+    /// Enables or disables stabilization of cascades. Stabilized cascades have constant size and
+    /// move in texel-sized increments, this removes shimmering of shadow edges when the camera moves
+    /// or rotates at the cost of slightly lower effective resolution of shadow maps.
+    pub fn set_stabilize(&mut self, stabilize: bool) {
+        self.stabilize = stabilize;
+    }
+
+    /// Returns `true` if the cascades are stabilized, `false` - otherwise.
+    pub fn is_stabilized(&self) -> bool {
+        self.stabilize
+    }
+
+    /// Calculates distances to the far planes of every cascade for a camera with given near and far
+    /// planes. Only first [`Self::cascade_count`] values are meaningful, the rest are set to zero.
+    pub fn cascade_far_planes(&self, z_near: f32, z_far: f32) -> [f32; CSM_NUM_CASCADES] {
+        let count = self.cascade_count();
+        let mut far_planes = [0.0; CSM_NUM_CASCADES];
+        for (i, far_plane) in far_planes.iter_mut().enumerate().take(count) {
+            *far_plane = match self.split_options {
+                FrustumSplitOptions::Absolute { far_planes } => far_planes[i],
+                FrustumSplitOptions::Relative { fractions } => z_far * fractions[i],
+                FrustumSplitOptions::Logarithmic { lambda } => {
+                    let fraction = (i + 1) as f32 / count as f32;
+                    let z_near = z_near.max(f32::EPSILON);
+                    let logarithmic = z_near * (z_far / z_near).powf(fraction);
+                    let uniform = z_near + (z_far - z_near) * fraction;
+                    let lambda = lambda.clamp(0.0, 1.0);
+                    lambda * logarithmic + (1.0 - lambda) * uniform
+                }
+            };
+        }
+        far_planes
     }
 }
 
@@ -217,6 +297,7 @@ mod test {
         light::{
             directional::{
                 CsmOptions, DirectionalLight, DirectionalLightBuilder, FrustumSplitOptions,
+                CSM_NUM_CASCADES,
             },
             BaseLightBuilder,
         },
@@ -233,7 +314,9 @@ mod test {
             split_options: FrustumSplitOptions::Absolute {
                 far_planes: [1.0, 2.0, 4.0],
             },
-            shadow_bias: 0.0,
+            cascade_count: 2,
+            shadow_biases: [0.0; CSM_NUM_CASCADES],
+            stabilize: true,
         })
         .build_node();
 
@@ -248,4 +331,33 @@ mod test {
         check_inheritable_properties_equality(&child.base_light, &parent.base_light);
         check_inheritable_properties_equality(&child, parent);
     }
+
+    #[test]
+    fn test_cascade_far_planes() {
+        let mut options = CsmOptions {
+            split_options: FrustumSplitOptions::Relative {
+                fractions: [0.1, 0.5, 1.0],
+            },
+            ..Default::default()
+        };
+        assert_eq!(options.cascade_far_planes(0.1, 100.0), [10.0, 50.0, 100.0]);
+
+        options.set_cascade_count(2);
+        assert_eq!(options.cascade_far_planes(0.1, 100.0), [10.0, 50.0, 0.0]);
+
+        // Uniform splits.
+        options.split_options = FrustumSplitOptions::Logarithmic { lambda: 0.0 };
+        assert_eq!(options.cascade_far_planes(0.0, 100.0)[1], 100.0);
+
+        // Logarithmic splits.
+        options.set_cascade_count(3);
+        options.split_options = FrustumSplitOptions::Logarithmic { lambda: 1.0 };
+        let far_planes = options.cascade_far_planes(1.0, 1000.0);
+        assert!((far_planes[0] - 10.0).abs() < 0.001);
+        assert!((far_planes[1] - 100.0).abs() < 0.01);
+        assert!((far_planes[2] - 1000.0).abs() < 0.1);
+
+        options.set_cascade_count(10);
+        assert_eq!(options.cascade_count(), CSM_NUM_CASCADES);
+    }
 }
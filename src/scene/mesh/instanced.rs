@@ -0,0 +1,399 @@
+//! Instanced mesh is a mesh that renders the same set of surfaces multiple times using hardware
+//! instancing. See [`InstancedMesh`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::Surface,
+            RenderPath,
+        },
+        node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
+    },
+};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+/// A single instance of an instanced mesh. Transform of an instance is relative to the instanced
+/// mesh node.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct MeshInstance {
+    /// Position of the instance.
+    pub position: Vector3<f32>,
+    /// Rotation of the instance.
+    pub rotation: UnitQuaternion<f32>,
+    /// Scale of the instance.
+    pub scale: Vector3<f32>,
+    /// Color of the instance, it is multiplied with the diffuse color of the material.
+    pub color: Color,
+}
+
+impl Default for MeshInstance {
+    fn default() -> Self {
+        Self {
+            position: Default::default(),
+            rotation: UnitQuaternion::identity(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+            color: Color::WHITE,
+        }
+    }
+}
+
+impl MeshInstance {
+    /// Creates new instance at the given position.
+    pub fn new(position: Vector3<f32>) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+
+    /// Sets rotation of the instance.
+    pub fn with_rotation(mut self, rotation: UnitQuaternion<f32>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets scale of the instance.
+    pub fn with_scale(mut self, scale: Vector3<f32>) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets color of the instance.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns transformation matrix of the instance (relative to the instanced mesh node).
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::new_translation(&self.position)
+            * self.rotation.to_homogeneous()
+            * Matrix4::new_nonuniform_scaling(&self.scale)
+    }
+}
+
+/// Instanced mesh renders the same set of surfaces multiple times, each instance has its own
+/// transform and color. It is useful to render lots of identical objects (rocks, grass, trees,
+/// etc.), since every visible instance of a surface is rendered in a single draw call (when
+/// [`crate::renderer::QualitySettings::use_instancing`] is on).
+///
+/// Instanced mesh is a single scene node, it means that it is culled as a whole (except shadows,
+/// which are culled per instance). Split large amounts of instances in multiple nodes to let the
+/// engine cull invisible instances.
+///
+/// ## Limitations
+///
+/// Skinning is not supported, bones of surfaces are ignored. Colors of instances are applied only
+/// by shaders that support instancing (see [`crate::material::shader::ShaderDefinition`] docs),
+/// the standard shaders support it.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::{Matrix4, Vector3}, color::Color, pool::Handle},
+///     scene::{
+///         base::BaseBuilder,
+///         graph::Graph,
+///         mesh::{
+///             instanced::{InstancedMeshBuilder, MeshInstance},
+///             surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+///         },
+///         node::Node,
+///     },
+/// };
+///
+/// fn create_rocks(graph: &mut Graph) -> Handle<Node> {
+///     let instances = (0..1000)
+///         .map(|i| {
+///             MeshInstance::new(Vector3::new((i % 100) as f32, 0.0, (i / 100) as f32))
+///                 .with_color(Color::opaque(120, 120, 120))
+///         })
+///         .collect();
+///
+///     InstancedMeshBuilder::new(BaseBuilder::new())
+///         .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+///             SurfaceData::make_sphere(8, 8, 0.5, &Matrix4::identity()),
+///         ))
+///         .build()])
+///         .with_instances(instances)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Reflect, Clone, Visit)]
+pub struct InstancedMesh {
+    base: Base,
+
+    #[reflect(setter = "set_surfaces")]
+    surfaces: InheritableVariable<Vec<Surface>>,
+
+    #[reflect(setter = "set_instances")]
+    instances: InheritableVariable<Vec<MeshInstance>>,
+
+    #[reflect(setter = "set_render_path")]
+    render_path: InheritableVariable<RenderPath>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    surfaces_bounding_box: Cell<AxisAlignedBoundingBox>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    surfaces_bounding_box_dirty: Cell<bool>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    world_bounding_box: Cell<AxisAlignedBoundingBox>,
+}
+
+impl Default for InstancedMesh {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            surfaces: Default::default(),
+            instances: Default::default(),
+            render_path: InheritableVariable::new(RenderPath::Deferred),
+            surfaces_bounding_box: Default::default(),
+            surfaces_bounding_box_dirty: Cell::new(true),
+            world_bounding_box: Default::default(),
+        }
+    }
+}
+
+impl Deref for InstancedMesh {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for InstancedMesh {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for InstancedMesh {
+    fn type_uuid() -> Uuid {
+        uuid!("5f0e3c2a-8f0b-4d3e-9a51-7c2b1e6d4a90")
+    }
+}
+
+impl InstancedMesh {
+    /// Sets surfaces for the mesh.
+    pub fn set_surfaces(&mut self, surfaces: Vec<Surface>) -> Vec<Surface> {
+        self.surfaces_bounding_box_dirty.set(true);
+        self.surfaces.set_value_and_mark_modified(surfaces)
+    }
+
+    /// Returns shared reference to array of surfaces.
+    #[inline]
+    pub fn surfaces(&self) -> &[Surface] {
+        &self.surfaces
+    }
+
+    /// Returns mutable reference to array of surfaces.
+    #[inline]
+    pub fn surfaces_mut(&mut self) -> &mut [Surface] {
+        self.surfaces_bounding_box_dirty.set(true);
+        self.surfaces.get_value_mut_silent()
+    }
+
+    /// Sets new instances of the mesh.
+    pub fn set_instances(&mut self, instances: Vec<MeshInstance>) -> Vec<MeshInstance> {
+        self.instances.set_value_and_mark_modified(instances)
+    }
+
+    /// Returns shared reference to array of instances.
+    #[inline]
+    pub fn instances(&self) -> &[MeshInstance] {
+        &self.instances
+    }
+
+    /// Returns mutable reference to array of instances. It could be used to move, add or remove
+    /// instances.
+    #[inline]
+    pub fn instances_mut(&mut self) -> &mut Vec<MeshInstance> {
+        self.instances.get_value_mut_and_mark_modified()
+    }
+
+    /// Sets new render path for the mesh.
+    pub fn set_render_path(&mut self, render_path: RenderPath) -> RenderPath {
+        self.render_path.set_value_and_mark_modified(render_path)
+    }
+
+    /// Returns current render path of the mesh.
+    pub fn render_path(&self) -> RenderPath {
+        *self.render_path
+    }
+
+    /// Returns bounding box of the surfaces of the mesh (the bounding box of a single instance
+    /// without its transform).
+    pub fn surfaces_bounding_box(&self) -> AxisAlignedBoundingBox {
+        if self.surfaces_bounding_box_dirty.get() {
+            let mut bounding_box = AxisAlignedBoundingBox::default();
+            for surface in self.surfaces.iter() {
+                let data = surface.data();
+                let data = data.lock();
+                for view in data.vertex_buffer.iter() {
+                    bounding_box
+                        .add_point(view.read_3_f32(VertexAttributeUsage::Position).unwrap());
+                }
+            }
+            self.surfaces_bounding_box.set(bounding_box);
+            self.surfaces_bounding_box_dirty.set(false);
+        }
+
+        self.surfaces_bounding_box.get()
+    }
+}
+
+impl NodeTrait for InstancedMesh {
+    crate::impl_query_component!();
+
+    /// Returns bounding box of every instance in *local coordinates*.
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let surfaces_bounding_box = self.surfaces_bounding_box();
+
+        let mut bounding_box = AxisAlignedBoundingBox::default();
+        for instance in self.instances.iter() {
+            bounding_box.add_box(surfaces_bounding_box.transform(&instance.matrix()));
+        }
+        bounding_box
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.world_bounding_box.get()
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager.clone());
+
+        for surface in self.surfaces_mut() {
+            surface.material().lock().resolve(resource_manager.clone());
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, _context: &mut UpdateContext) {
+        self.world_bounding_box.set(
+            self.local_bounding_box()
+                .transform(&self.global_transform()),
+        );
+    }
+}
+
+/// Allows you to create instanced meshes in declarative manner.
+pub struct InstancedMeshBuilder {
+    base_builder: BaseBuilder,
+    surfaces: Vec<Surface>,
+    instances: Vec<MeshInstance>,
+    render_path: RenderPath,
+}
+
+impl InstancedMeshBuilder {
+    /// Creates new instanced mesh builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            surfaces: Default::default(),
+            instances: Default::default(),
+            render_path: RenderPath::Deferred,
+        }
+    }
+
+    /// Sets desired surfaces for the mesh.
+    pub fn with_surfaces(mut self, surfaces: Vec<Surface>) -> Self {
+        self.surfaces = surfaces;
+        self
+    }
+
+    /// Sets desired instances of the mesh.
+    pub fn with_instances(mut self, instances: Vec<MeshInstance>) -> Self {
+        self.instances = instances;
+        self
+    }
+
+    /// Sets desired render path.
+    pub fn with_render_path(mut self, render_path: RenderPath) -> Self {
+        self.render_path = render_path;
+        self
+    }
+
+    /// Creates new instanced mesh.
+    pub fn build_node(self) -> Node {
+        Node::new(InstancedMesh {
+            base: self.base_builder.build_base(),
+            surfaces: self.surfaces.into(),
+            instances: self.instances.into(),
+            render_path: self.render_path.into(),
+            surfaces_bounding_box: Default::default(),
+            surfaces_bounding_box_dirty: Cell::new(true),
+            world_bounding_box: Default::default(),
+        })
+    }
+
+    /// Creates new instanced mesh and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            mesh::{
+                instanced::{InstancedMeshBuilder, MeshInstance},
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+            },
+        },
+    };
+
+    #[test]
+    fn test_instanced_mesh_bounding_box() {
+        let mut graph = Graph::new();
+
+        let mesh = InstancedMeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .with_instances(vec![
+                MeshInstance::new(Vector3::new(-5.0, 0.0, 0.0)),
+                MeshInstance::new(Vector3::new(5.0, 0.0, 0.0))
+                    .with_scale(Vector3::new(2.0, 2.0, 2.0)),
+            ])
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let aabb = graph[mesh].world_bounding_box();
+        assert!((aabb.min - Vector3::new(-5.5, -1.0, -1.0)).norm() < 0.0001);
+        assert!((aabb.max - Vector3::new(6.0, 1.0, 1.0)).norm() < 0.0001);
+    }
+}
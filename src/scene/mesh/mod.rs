@@ -19,6 +19,7 @@ use crate::{
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::ResourceManager,
+    material::MaterialPropertyBlock,
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
@@ -36,6 +37,7 @@ use std::{
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 pub mod buffer;
+pub mod instanced;
 pub mod surface;
 pub mod vertex;
 
@@ -98,6 +100,14 @@ pub struct Mesh {
     #[reflect(setter = "set_decal_layer_index")]
     decal_layer_index: InheritableVariable<u8>,
 
+    #[reflect(setter = "set_lightmap_texel_scale", min_value = 0.0)]
+    #[visit(optional)]
+    lightmap_texel_scale: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    #[visit(optional)]
+    property_block: MaterialPropertyBlock,
+
     #[reflect(hidden)]
     #[visit(skip)]
     local_bounding_box: Cell<AxisAlignedBoundingBox>,
@@ -121,6 +131,8 @@ impl Default for Mesh {
             local_bounding_box_dirty: Cell::new(true),
             render_path: InheritableVariable::new(RenderPath::Deferred),
             decal_layer_index: InheritableVariable::new(0),
+            lightmap_texel_scale: InheritableVariable::new(1.0),
+            property_block: Default::default(),
         }
     }
 }
@@ -259,6 +271,40 @@ impl Mesh {
     pub fn decal_layer_index(&self) -> u8 {
         *self.decal_layer_index
     }
+
+    /// Sets new lightmap texel scale. It is a multiplier of the lightmap resolution (see
+    /// [`crate::utils::lightmap::LightmapSettings::texels_per_unit`]) for this mesh, it allows you
+    /// to increase quality of lightmaps for important meshes and decrease it for small or distant
+    /// ones.
+    pub fn set_lightmap_texel_scale(&mut self, scale: f32) -> f32 {
+        self.lightmap_texel_scale
+            .set_value_and_mark_modified(scale.max(0.0))
+    }
+
+    /// Returns current lightmap texel scale.
+    pub fn lightmap_texel_scale(&self) -> f32 {
+        *self.lightmap_texel_scale
+    }
+
+    /// Returns a reference to the material property block of the mesh. See [`MaterialPropertyBlock`]
+    /// docs for more info.
+    pub fn property_block(&self) -> &MaterialPropertyBlock {
+        &self.property_block
+    }
+
+    /// Returns a reference to the material property block of the mesh. Overrides of the block are
+    /// applied to materials of every surface of the mesh, without changing the materials themselves.
+    pub fn property_block_mut(&mut self) -> &mut MaterialPropertyBlock {
+        &mut self.property_block
+    }
+
+    /// Sets new material property block of the mesh and returns the old one.
+    pub fn set_property_block(
+        &mut self,
+        property_block: MaterialPropertyBlock,
+    ) -> MaterialPropertyBlock {
+        std::mem::replace(&mut self.property_block, property_block)
+    }
 }
 
 impl NodeTrait for Mesh {
@@ -332,6 +378,7 @@ pub struct MeshBuilder {
     surfaces: Vec<Surface>,
     render_path: RenderPath,
     decal_layer_index: u8,
+    lightmap_texel_scale: f32,
 }
 
 impl MeshBuilder {
@@ -342,6 +389,7 @@ impl MeshBuilder {
             surfaces: Default::default(),
             render_path: RenderPath::Deferred,
             decal_layer_index: 0,
+            lightmap_texel_scale: 1.0,
         }
     }
 
@@ -364,6 +412,12 @@ impl MeshBuilder {
         self
     }
 
+    /// Sets desired lightmap texel scale.
+    pub fn with_lightmap_texel_scale(mut self, scale: f32) -> Self {
+        self.lightmap_texel_scale = scale;
+        self
+    }
+
     /// Creates new mesh.
     pub fn build_node(self) -> Node {
         Node::new(Mesh {
@@ -373,6 +427,8 @@ impl MeshBuilder {
             local_bounding_box_dirty: Cell::new(true),
             render_path: self.render_path.into(),
             decal_layer_index: self.decal_layer_index.into(),
+            lightmap_texel_scale: self.lightmap_texel_scale.into(),
+            property_block: Default::default(),
             world_bounding_box: Default::default(),
         })
     }
@@ -31,7 +31,10 @@ use crate::{
     utils::raw_mesh::{RawMesh, RawMeshBuilder},
 };
 use fxhash::FxHasher;
-use std::{hash::Hasher, sync::Arc};
+use std::{
+    hash::Hasher,
+    sync::{Arc, Weak},
+};
 
 /// Data source of a surface. Each surface can share same data source, this is used
 /// in instancing technique to render multiple instances of same model at different
@@ -994,6 +997,10 @@ impl SurfaceSharedData {
     pub fn use_count(&self) -> usize {
         Arc::strong_count(&self.0)
     }
+
+    pub(crate) fn downgrade(&self) -> Weak<Mutex<SurfaceData>> {
+        Arc::downgrade(&self.0)
+    }
 }
 
 /// See module docs.
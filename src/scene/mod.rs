@@ -7,12 +7,15 @@
 pub mod accel;
 pub mod animation;
 pub mod base;
+pub mod buoyancy;
 pub mod camera;
 pub mod collider;
 pub mod debug;
 pub mod decal;
 pub mod dim2;
+pub mod force_field;
 pub mod graph;
+pub mod gravity_zone;
 pub mod joint;
 pub mod light;
 pub mod loader;
@@ -21,16 +24,19 @@ pub mod node;
 pub mod particle_system;
 pub mod pivot;
 pub mod rigidbody;
+pub mod rope;
 pub mod sound;
+pub mod spawn_point;
 pub mod sprite;
 pub mod terrain;
+pub mod text3d;
 pub mod transform;
 pub mod visibility;
 
 use crate::scene::graph::GraphUpdateSwitches;
 use crate::{
     core::{
-        algebra::Vector2,
+        algebra::{Vector2, Vector3},
         color::Color,
         futures::future::join_all,
         pool::{Handle, Pool, Ticket},
@@ -40,18 +46,24 @@ use crate::{
     },
     engine::{resource_manager::ResourceManager, SerializationContext},
     material::{shader::SamplerFallback, PropertyValue},
+    plugin::settings::PluginSettingsContainer,
+    rand::Rng,
     resource::texture::Texture,
     scene::{
         camera::Camera,
+        collider::Collider,
         debug::SceneDrawingContext,
         graph::{map::NodeHandleMap, Graph, GraphPerformanceStatistics},
+        light::BaseLight,
         mesh::buffer::{
             VertexAttributeDataType, VertexAttributeDescriptor, VertexAttributeUsage,
             VertexWriteTrait,
         },
         mesh::Mesh,
         node::Node,
-        sound::SoundEngine,
+        particle_system::ParticleSystem,
+        rigidbody::RigidBody,
+        sound::{event::SoundEventLibrary, Sound, SoundEngine},
     },
     utils::{lightmap::Lightmap, log::Log, log::MessageKind, navmesh::Navmesh},
 };
@@ -61,6 +73,7 @@ use std::{
     ops::{Index, IndexMut},
     path::Path,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 /// A container for navigational meshes.
@@ -185,6 +198,14 @@ pub struct Scene {
     /// to false for menu's scene and when you need to open a menu - set it to true and
     /// set `enabled` flag to false for level's scene.
     pub enabled: bool,
+
+    /// Serialized settings of plugins, see [`crate::plugin::Plugin::settings`] for more info.
+    #[reflect(hidden)]
+    pub plugin_settings: PluginSettingsContainer,
+
+    /// A named set of sound events of the scene, see [`Scene::play_sound_event`] for more info.
+    #[reflect(hidden)]
+    pub sound_events: SoundEventLibrary,
 }
 
 impl Default for Scene {
@@ -198,15 +219,86 @@ impl Default for Scene {
             performance_statistics: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
             enabled: true,
+            plugin_settings: Default::default(),
+            sound_events: Default::default(),
         }
     }
 }
 
+/// Amount of entities of specific kinds in a scene.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct EntityStatistics {
+    /// Total amount of nodes.
+    pub nodes: usize,
+    /// Amount of meshes.
+    pub meshes: usize,
+    /// Amount of lights of any kind.
+    pub lights: usize,
+    /// Amount of 3D and 2D rigid bodies.
+    pub rigid_bodies: usize,
+    /// Amount of 3D and 2D colliders.
+    pub colliders: usize,
+    /// Amount of sound sources.
+    pub sounds: usize,
+    /// Amount of particle systems.
+    pub particle_systems: usize,
+    /// Amount of nodes with scripts.
+    pub scripts: usize,
+}
+
+impl EntityStatistics {
+    /// Counts entities of the given graph.
+    pub fn collect(graph: &Graph) -> Self {
+        let mut statistics = Self::default();
+        for node in graph.linear_iter() {
+            statistics.nodes += 1;
+            if node.cast::<Mesh>().is_some() {
+                statistics.meshes += 1;
+            }
+            if node.query_component_ref::<BaseLight>().is_some() {
+                statistics.lights += 1;
+            }
+            if node.cast::<RigidBody>().is_some()
+                || node.cast::<dim2::rigidbody::RigidBody>().is_some()
+            {
+                statistics.rigid_bodies += 1;
+            }
+            if node.cast::<Collider>().is_some()
+                || node.cast::<dim2::collider::Collider>().is_some()
+            {
+                statistics.colliders += 1;
+            }
+            if node.cast::<Sound>().is_some() {
+                statistics.sounds += 1;
+            }
+            if node.cast::<ParticleSystem>().is_some() {
+                statistics.particle_systems += 1;
+            }
+            if node.script.is_some() {
+                statistics.scripts += 1;
+            }
+        }
+        statistics
+    }
+}
+
 /// A structure that holds times that specific update step took.
 #[derive(Clone, Default, Debug)]
 pub struct PerformanceStatistics {
     /// Graph performance statistics.
     pub graph: GraphPerformanceStatistics,
+    /// Amount of time that was needed to process scripts of the scene (initialization, update,
+    /// messages, etc.).
+    pub script_time: Duration,
+    /// Amount of entities in the scene.
+    pub entities: EntityStatistics,
+}
+
+impl PerformanceStatistics {
+    /// Returns total amount of time that was needed to update the scene.
+    pub fn total(&self) -> Duration {
+        self.graph.total() + self.script_time
+    }
 }
 
 impl Display for PerformanceStatistics {
@@ -222,7 +314,11 @@ impl Display for PerformanceStatistics {
             \tPhysics 2D: {:?}\n\
             \t\tSimulation: {:?}\n\
             \t\tRay cast: {:?}\n\
-            \tHierarchy: {:?}",
+            \tHierarchy: {:?}\n\
+            \tNodes: {:?}\n\
+            \t\tAnimation: {:?}\n\
+            Scripts: {:?}\n\
+            Entities: {:?}",
             self.graph.total(),
             self.graph.sync_time,
             self.graph.sound_update_time,
@@ -233,6 +329,10 @@ impl Display for PerformanceStatistics {
             self.graph.physics2d.step_time,
             self.graph.physics2d.total_ray_cast_time.get(),
             self.graph.hierarchical_properties_time,
+            self.graph.node_update_time,
+            self.graph.animation_time,
+            self.script_time,
+            self.entities,
         )
     }
 }
@@ -311,6 +411,8 @@ impl SceneLoader {
             }
         }
 
+        scene.sound_events.restore_resources(&resource_manager);
+
         // TODO: Move into Camera::restore_resources?
         // We have to wait until skybox textures are all loaded, because we need to read their data
         // to re-create cube map.
@@ -350,6 +452,8 @@ impl Scene {
             performance_statistics: Default::default(),
             ambient_lighting_color: Color::opaque(100, 100, 100),
             enabled: true,
+            plugin_settings: Default::default(),
+            sound_events: Default::default(),
         }
     }
 
@@ -487,12 +591,34 @@ impl Scene {
         Ok(std::mem::replace(&mut self.lightmap, Some(lightmap)))
     }
 
+    /// Returns current lightmap of the scene (if any).
+    pub fn lightmap(&self) -> Option<&Lightmap> {
+        self.lightmap.as_ref()
+    }
+
     /// Performs single update tick with given delta time from last frame. Internally
     /// it updates physics, animations, and each graph node. In most cases there is
     /// no need to call it directly, engine automatically updates all available scenes.
     pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
         self.graph.update(frame_size, dt, switches);
         self.performance_statistics.graph = self.graph.performance_statistics.clone();
+        self.performance_statistics.entities = EntityStatistics::collect(&self.graph);
+    }
+
+    /// Plays a sound event with the given name from [`Scene::sound_events`] at the given position.
+    /// `time` is current time in seconds (for example [`crate::script::ScriptContext::elapsed_time`]),
+    /// it is used to track cooldowns of the event. Returns a handle of a new sound node or
+    /// [`Handle::NONE`] if there is no such event, it is not loaded yet or it is cooling down. See
+    /// [`crate::resource::sound_event`] module docs for more info.
+    pub fn play_sound_event<S: AsRef<str>, R: Rng + ?Sized>(
+        &mut self,
+        name: S,
+        position: Vector3<f32>,
+        time: f32,
+        rng: &mut R,
+    ) -> Handle<Node> {
+        self.sound_events
+            .play(name, &mut self.graph, position, time, rng)
     }
 
     /// Creates deep copy of a scene, filter predicate allows you to filter out nodes
@@ -515,6 +641,8 @@ impl Scene {
                 performance_statistics: Default::default(),
                 ambient_lighting_color: self.ambient_lighting_color,
                 enabled: self.enabled,
+                plugin_settings: self.plugin_settings.clone(),
+                sound_events: self.sound_events.clone(),
             },
             old_new_map,
         )
@@ -529,6 +657,8 @@ impl Scene {
         self.ambient_lighting_color
             .visit("AmbientLightingColor", &mut region)?;
         self.enabled.visit("Enabled", &mut region)?;
+        let _ = self.plugin_settings.visit("PluginSettings", &mut region);
+        let _ = self.sound_events.visit("SoundEvents", &mut region);
 
         Ok(())
     }
@@ -657,3 +787,34 @@ impl IndexMut<Handle<Scene>> for SceneContainer {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        light::{point::PointLightBuilder, BaseLightBuilder},
+        mesh::MeshBuilder,
+        pivot::PivotBuilder,
+        EntityStatistics,
+    };
+
+    #[test]
+    fn test_entity_statistics() {
+        let mut graph = Graph::new();
+        PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        MeshBuilder::new(BaseBuilder::new()).build(&mut graph);
+        PointLightBuilder::new(BaseLightBuilder::new(BaseBuilder::new())).build(&mut graph);
+
+        assert_eq!(
+            EntityStatistics::collect(&graph),
+            EntityStatistics {
+                // Including the root.
+                nodes: 4,
+                meshes: 1,
+                lights: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
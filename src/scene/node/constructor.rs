@@ -5,17 +5,23 @@ use crate::{
     scene::{
         self,
         animation::{absm::AnimationBlendingStateMachine, AnimationPlayer},
+        buoyancy::BuoyancyVolume,
         camera::Camera,
         decal::Decal,
         dim2::{self, rectangle::Rectangle},
+        force_field::ForceField,
+        gravity_zone::GravityZone,
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
-        mesh::Mesh,
+        mesh::{instanced::InstancedMesh, Mesh},
         node::{Node, NodeTrait, TypeUuidProvider},
         particle_system::ParticleSystem,
         pivot::Pivot,
+        rope::Rope,
         sound::{listener::Listener, Sound},
+        spawn_point::SpawnPoint,
         sprite::Sprite,
         terrain::Terrain,
+        text3d::Text3D,
     },
 };
 use fxhash::FxHashMap;
@@ -42,6 +48,7 @@ impl NodeConstructorContainer {
         container.add::<PointLight>();
         container.add::<SpotLight>();
         container.add::<Mesh>();
+        container.add::<InstancedMesh>();
         container.add::<ParticleSystem>();
         container.add::<Sound>();
         container.add::<Listener>();
@@ -50,9 +57,15 @@ impl NodeConstructorContainer {
         container.add::<Decal>();
         container.add::<scene::joint::Joint>();
         container.add::<Pivot>();
+        container.add::<BuoyancyVolume>();
+        container.add::<ForceField>();
+        container.add::<GravityZone>();
+        container.add::<Rope>();
         container.add::<scene::rigidbody::RigidBody>();
+        container.add::<SpawnPoint>();
         container.add::<Sprite>();
         container.add::<Terrain>();
+        container.add::<Text3D>();
         container.add::<AnimationPlayer>();
         container.add::<AnimationBlendingStateMachine>();
 
@@ -89,6 +102,11 @@ impl NodeConstructorContainer {
         self.map.lock().get_mut(type_uuid).map(|c| (c)())
     }
 
+    /// Returns type UUIDs of every registered constructor.
+    pub fn type_uuids(&self) -> Vec<Uuid> {
+        self.map.lock().keys().cloned().collect()
+    }
+
     /// Returns total amount of constructors.
     pub fn len(&self) -> usize {
         self.map.lock().len()
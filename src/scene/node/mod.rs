@@ -28,6 +28,7 @@ use crate::{
         sound::{context::SoundContext, listener::Listener, Sound},
         sprite::Sprite,
         terrain::Terrain,
+        text3d::Text3D,
     },
 };
 use std::{
@@ -88,6 +89,8 @@ pub struct UpdateContext<'a> {
     pub physics2d: &'a mut dim2::physics::PhysicsWorld,
     /// A mutable reference to sound context.
     pub sound_context: &'a mut SoundContext,
+    /// A list of observers (enabled cameras) of the graph, it could be used to do level-of-detail decisions.
+    pub observers: &'a [graph::Observer],
 }
 
 /// Implements [`NodeTrait::query_component_ref`] and [`NodeTrait::query_component_mut`] in a much
@@ -417,6 +420,7 @@ impl Node {
     define_is_as!(ParticleSystem => fn is_particle_system, fn as_particle_system, fn as_particle_system_mut);
     define_is_as!(Sprite  => fn is_sprite, fn as_sprite, fn as_sprite_mut);
     define_is_as!(Terrain  => fn is_terrain, fn as_terrain, fn as_terrain_mut);
+    define_is_as!(Text3D => fn is_text3d, fn as_text3d, fn as_text3d_mut);
     define_is_as!(Decal => fn is_decal, fn as_decal, fn as_decal_mut);
     define_is_as!(Rectangle => fn is_rectangle, fn as_rectangle, fn as_rectangle_mut);
     define_is_as!(scene::rigidbody::RigidBody  => fn is_rigid_body, fn as_rigid_body, fn as_rigid_body_mut);
@@ -72,7 +72,7 @@
 
 use crate::{
     core::{
-        algebra::{Vector2, Vector3},
+        algebra::{Point3, Vector2, Vector3},
         color::Color,
         color_gradient::ColorGradient,
         math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
@@ -236,6 +236,29 @@ impl ParticleSystem {
         }
     }
 
+    /// Accelerates alive particles by the given field. The field takes world-space position of a
+    /// particle and returns world-space acceleration for it.
+    pub(crate) fn apply_acceleration_field<F>(&mut self, dt: f32, mut field: F)
+    where
+        F: FnMut(Vector3<f32>) -> Option<Vector3<f32>>,
+    {
+        let transform = self.global_transform();
+        let inv_transform = match transform.try_inverse() {
+            Some(inv_transform) => inv_transform,
+            None => return,
+        };
+
+        for particle in self.particles.iter_mut().filter(|p| p.alive) {
+            let position = transform
+                .transform_point(&Point3::from(particle.position))
+                .coords;
+            if let Some(acceleration) = field(position) {
+                // Velocity of particles is stored as offset per update tick.
+                particle.velocity += inv_transform.transform_vector(&acceleration.scale(dt * dt));
+            }
+        }
+    }
+
     /// Generates new draw data for current frame. Should not be used directly, unless you
     /// absolutely need draw data before rendering. It is automatically called by renderer.
     pub fn generate_draw_data(
@@ -10,7 +10,7 @@
 //! [`RigidBody::set_can_sleep`] with `false` value.
 use crate::{
     core::{
-        algebra::{Matrix4, Vector3},
+        algebra::{Isometry3, Matrix3, Matrix4, Translation3, UnitQuaternion, Vector3},
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         parking_lot::Mutex,
         pool::Handle,
@@ -103,6 +103,67 @@ impl From<RigidBodyType> for rapier2d::dynamics::RigidBodyType {
     }
 }
 
+/// Defines how the rendered transform of a rigid body is calculated between physics steps. It is
+/// useful when the rendering rate is higher than the rate of fixed updates, in this case bodies
+/// will move in visible steps without interpolation.
+///
+/// Interpolation (and extrapolation) changes only global transforms of the rigid body and its
+/// descendants for rendering, local transforms (and thus gameplay logic) are never affected.
+#[derive(
+    Copy, Clone, Debug, Reflect, Visit, PartialEq, Eq, Hash, AsRefStr, EnumString, EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum RigidBodyInterpolation {
+    /// The body is rendered at its last simulated position.
+    Disabled = 0,
+    /// The body is rendered between its two last simulated positions. This mode is smooth, but adds
+    /// a latency of a single fixed update.
+    Interpolate = 1,
+    /// The body is rendered ahead of its last simulated position, the position is predicted using
+    /// the body's movement during the last fixed update. This mode has no latency, but may produce
+    /// visible "corrections" when the body changes its direction abruptly (i.e. on collisions).
+    Extrapolate = 2,
+}
+
+impl Default for RigidBodyInterpolation {
+    fn default() -> Self {
+        Self::Disabled
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct InterpolationState {
+    previous: Isometry3<f32>,
+    current: Isometry3<f32>,
+    scale: Vector3<f32>,
+}
+
+fn decompose_transform(transform: &Matrix4<f32>) -> (Isometry3<f32>, Vector3<f32>) {
+    let basis = transform.fixed_slice::<3, 3>(0, 0).into_owned();
+    let scale = Vector3::new(
+        basis.column(0).norm(),
+        basis.column(1).norm(),
+        basis.column(2).norm(),
+    );
+    let rotation_matrix = Matrix3::from_columns(&[
+        basis.column(0) / scale.x.max(f32::EPSILON),
+        basis.column(1) / scale.y.max(f32::EPSILON),
+        basis.column(2) / scale.z.max(f32::EPSILON),
+    ]);
+    (
+        Isometry3 {
+            translation: Translation3::new(transform[12], transform[13], transform[14]),
+            rotation: UnitQuaternion::from_matrix_eps(
+                &rotation_matrix,
+                f32::EPSILON,
+                16,
+                UnitQuaternion::identity(),
+            ),
+        },
+        scale,
+    )
+}
+
 #[derive(Debug)]
 pub(crate) enum ApplyAction {
     Force(Vector3<f32>),
@@ -174,6 +235,14 @@ pub struct RigidBody {
     #[reflect(setter = "set_gravity_scale")]
     pub(crate) gravity_scale: InheritableVariable<f32>,
 
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_contact_force_threshold")]
+    #[visit(optional)] // Backward compatibility
+    pub(crate) contact_force_threshold: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_interpolation")]
+    #[visit(optional)] // Backward compatibility
+    pub(crate) interpolation: InheritableVariable<RigidBodyInterpolation>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) sleeping: bool,
@@ -183,6 +252,9 @@ pub struct RigidBody {
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) actions: Mutex<VecDeque<ApplyAction>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) interpolation_state: Cell<Option<InterpolationState>>,
 }
 
 impl Debug for RigidBody {
@@ -210,8 +282,11 @@ impl Default for RigidBody {
             can_sleep: InheritableVariable::new(true),
             dominance: Default::default(),
             gravity_scale: InheritableVariable::new(1.0),
+            contact_force_threshold: Default::default(),
+            interpolation: Default::default(),
             native: Cell::new(RigidBodyHandle::invalid()),
             actions: Default::default(),
+            interpolation_state: Default::default(),
         }
     }
 }
@@ -249,9 +324,12 @@ impl Clone for RigidBody {
             can_sleep: self.can_sleep.clone(),
             dominance: self.dominance.clone(),
             gravity_scale: self.gravity_scale.clone(),
+            contact_force_threshold: self.contact_force_threshold.clone(),
+            interpolation: self.interpolation.clone(),
             // Do not copy. The copy will have its own native representation.
             native: Cell::new(RigidBodyHandle::invalid()),
             actions: Default::default(),
+            interpolation_state: Default::default(),
         }
     }
 }
@@ -473,11 +551,94 @@ impl RigidBody {
         *self.can_sleep
     }
 
+    /// Sets minimal total force of contacts of the rigid body, at which contact events will be
+    /// reported. It allows you to filter out weak contacts (for example, a body resting on a floor)
+    /// that are not interesting for gameplay. Default is zero (every contact is reported). See
+    /// [`crate::scene::graph::physics::ContactEventFilter`] docs for more info.
+    pub fn set_contact_force_threshold(&mut self, threshold: f32) -> f32 {
+        self.contact_force_threshold
+            .set_value_and_mark_modified(threshold)
+    }
+
+    /// Returns current contact force threshold of the rigid body.
+    pub fn contact_force_threshold(&self) -> f32 {
+        *self.contact_force_threshold
+    }
+
     /// Wakes up rigid body, forcing it to return to participate in the simulation.
     pub fn wake_up(&mut self) {
         self.actions.get_mut().push_back(ApplyAction::WakeUp)
     }
 
+    /// Sets the new interpolation mode of the rigid body. See [`RigidBodyInterpolation`] docs for
+    /// more info.
+    pub fn set_interpolation(
+        &mut self,
+        interpolation: RigidBodyInterpolation,
+    ) -> RigidBodyInterpolation {
+        self.interpolation_state.set(None);
+        self.interpolation
+            .set_value_and_mark_modified(interpolation)
+    }
+
+    /// Returns current interpolation mode of the rigid body.
+    pub fn interpolation(&self) -> RigidBodyInterpolation {
+        *self.interpolation
+    }
+
+    /// Resets interpolation history of the rigid body. Must be called when the body is teleported,
+    /// otherwise it will be rendered "flying" between old and new positions for one fixed update.
+    pub fn reset_interpolation(&self) {
+        self.interpolation_state.set(None);
+    }
+
+    pub(crate) fn record_interpolation_state(&self) {
+        if *self.interpolation == RigidBodyInterpolation::Disabled {
+            self.interpolation_state.set(None);
+            return;
+        }
+
+        let (current, scale) = decompose_transform(&self.global_transform());
+        let previous = self
+            .interpolation_state
+            .get()
+            .map_or(current, |state| state.current);
+
+        self.interpolation_state.set(Some(InterpolationState {
+            previous,
+            current,
+            scale,
+        }));
+    }
+
+    /// Returns global transform of the rigid body for rendering. `alpha` is a fraction of the fixed
+    /// time step, that has passed since the last fixed update.
+    pub(crate) fn interpolated_transform(&self, alpha: f32) -> Option<Matrix4<f32>> {
+        let state = self.interpolation_state.get()?;
+
+        let t = match *self.interpolation {
+            RigidBodyInterpolation::Disabled => return None,
+            RigidBodyInterpolation::Interpolate => alpha.clamp(0.0, 1.0),
+            RigidBodyInterpolation::Extrapolate => 1.0 + alpha.clamp(0.0, 1.0),
+        };
+
+        let previous = &state.previous;
+        let current = &state.current;
+
+        let translation = previous.translation.vector
+            + (current.translation.vector - previous.translation.vector) * t;
+        let rotation = previous.rotation.rotation_to(&current.rotation).powf(t) * previous.rotation;
+
+        Some(
+            Isometry3 {
+                translation: Translation3::from(translation),
+                rotation,
+            }
+            .to_homogeneous()
+                * Matrix4::new_nonuniform_scaling(&state.scale),
+        )
+    }
+
     pub(crate) fn need_sync_model(&self) -> bool {
         self.lin_vel.need_sync()
             || self.ang_vel.need_sync()
@@ -493,6 +654,7 @@ impl RigidBody {
             || self.can_sleep.need_sync()
             || self.dominance.need_sync()
             || self.gravity_scale.need_sync()
+            || self.contact_force_threshold.need_sync()
     }
 }
 
@@ -580,6 +742,8 @@ pub struct RigidBodyBuilder {
     can_sleep: bool,
     dominance: i8,
     gravity_scale: f32,
+    contact_force_threshold: f32,
+    interpolation: RigidBodyInterpolation,
 }
 
 impl RigidBodyBuilder {
@@ -602,6 +766,8 @@ impl RigidBodyBuilder {
             can_sleep: true,
             dominance: 0,
             gravity_scale: 1.0,
+            contact_force_threshold: 0.0,
+            interpolation: Default::default(),
         }
     }
 
@@ -703,6 +869,18 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets desired contact force threshold.
+    pub fn with_contact_force_threshold(mut self, threshold: f32) -> Self {
+        self.contact_force_threshold = threshold;
+        self
+    }
+
+    /// Sets desired interpolation mode.
+    pub fn with_interpolation(mut self, interpolation: RigidBodyInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
     /// Creates RigidBody node but does not add it to the graph.
     pub fn build_rigid_body(self) -> RigidBody {
         RigidBody {
@@ -722,8 +900,11 @@ impl RigidBodyBuilder {
             can_sleep: self.can_sleep.into(),
             dominance: self.dominance.into(),
             gravity_scale: self.gravity_scale.into(),
+            contact_force_threshold: self.contact_force_threshold.into(),
+            interpolation: self.interpolation.into(),
             native: Cell::new(RigidBodyHandle::invalid()),
             actions: Default::default(),
+            interpolation_state: Default::default(),
         }
     }
 
@@ -743,10 +924,13 @@ mod test {
     use crate::core::reflect::Reflect;
     use crate::core::variable::try_inherit_properties;
     use crate::{
-        core::algebra::Vector3,
+        core::algebra::{Vector2, Vector3},
         scene::{
             base::{test::check_inheritable_properties_equality, BaseBuilder},
-            rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+            graph::Graph,
+            pivot::PivotBuilder,
+            rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyInterpolation, RigidBodyType},
+            transform::TransformBuilder,
         },
     };
 
@@ -765,6 +949,8 @@ mod test {
             .with_lin_damping(0.1)
             .with_ang_damping(0.1)
             .with_dominance(123)
+            .with_contact_force_threshold(10.0)
+            .with_interpolation(RigidBodyInterpolation::Interpolate)
             .with_translation_locked(true)
             .build_node();
 
@@ -776,4 +962,50 @@ mod test {
 
         check_inheritable_properties_equality(&child, parent);
     }
+
+    #[test]
+    fn test_rigid_body_interpolation() {
+        let mut graph = Graph::new();
+
+        let child = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[child]))
+            .with_body_type(RigidBodyType::KinematicPositionBased)
+            .with_interpolation(RigidBodyInterpolation::Interpolate)
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        graph[body]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 0.0, 0.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        graph.interpolate_rigid_bodies(0.5);
+        assert_eq!(graph[body].global_position(), Vector3::new(0.5, 0.0, 0.0));
+        assert_eq!(graph[child].global_position(), Vector3::new(0.5, 1.0, 0.0));
+
+        graph[body]
+            .as_rigid_body_mut()
+            .set_interpolation(RigidBodyInterpolation::Extrapolate);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        graph[body]
+            .local_transform_mut()
+            .set_position(Vector3::new(2.0, 0.0, 0.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        graph.interpolate_rigid_bodies(0.5);
+        assert_eq!(graph[body].global_position(), Vector3::new(2.5, 0.0, 0.0));
+
+        // Logical transform must stay untouched.
+        assert_eq!(
+            **graph[body].local_transform().position(),
+            Vector3::new(2.0, 0.0, 0.0)
+        );
+    }
 }
@@ -0,0 +1,731 @@
+//! Rope is a chain of Verlet particles, that could be used for cables, chains, grappling hooks, etc.
+//! See [`Rope`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector3},
+        math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    material::{Material, SharedMaterial},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        mesh::{
+            buffer::{TriangleBuffer, VertexBuffer},
+            surface::{SurfaceData, SurfaceSharedData},
+            vertex::StaticVertex,
+        },
+        node::{Node, NodeTrait, TypeUuidProvider, UpdateContext},
+        rigidbody::{RigidBody, RigidBodyType},
+    },
+};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+/// Rope is a chain of Verlet particles connected by distance constraints. It could be used to
+/// simulate cables, chains, grappling hooks, etc. Rope is rendered as a tube mesh with the given
+/// material.
+///
+/// ## Attachments
+///
+/// Both ends of a rope could be attached to scene nodes. The start of the rope is pinned to the
+/// position of the rope node itself when there's no start attachment, the end of the rope is free
+/// when there's no end attachment. When a rope is attached to a dynamic rigid body and the rope is
+/// stretched, the body will be pulled by the rope with a force proportional to the stretch (see
+/// [`Rope::set_attachment_stiffness`]).
+///
+/// ## Simulation
+///
+/// Rope simulation does not interact with other physical objects (except attachments), it is a
+/// simple and cheap visual effect. Accuracy of the simulation depends on the amount of solver
+/// iterations, the more iterations - the less stretchy the rope will be.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{base::BaseBuilder, graph::Graph, node::Node, rope::RopeBuilder},
+/// };
+///
+/// fn create_rope(graph: &mut Graph, hook: Handle<Node>) -> Handle<Node> {
+///     RopeBuilder::new(BaseBuilder::new())
+///         .with_length(10.0)
+///         .with_segment_count(32)
+///         .with_end_attachment(hook)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Visit, Reflect)]
+pub struct Rope {
+    base: Base,
+
+    #[reflect(min_value = 0.0, step = 0.1, setter = "set_length")]
+    length: InheritableVariable<f32>,
+
+    #[reflect(min_value = 1.0, setter = "set_segment_count")]
+    segment_count: InheritableVariable<u32>,
+
+    #[reflect(min_value = 0.0, step = 0.01, setter = "set_radius")]
+    radius: InheritableVariable<f32>,
+
+    #[reflect(min_value = 3.0, setter = "set_sides")]
+    sides: InheritableVariable<u32>,
+
+    #[reflect(min_value = 1.0, setter = "set_iterations")]
+    iterations: InheritableVariable<u32>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01, setter = "set_damping")]
+    damping: InheritableVariable<f32>,
+
+    #[reflect(step = 0.1, setter = "set_gravity_scale")]
+    gravity_scale: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_start_attachment")]
+    start_attachment: InheritableVariable<Handle<Node>>,
+
+    #[reflect(setter = "set_end_attachment")]
+    end_attachment: InheritableVariable<Handle<Node>>,
+
+    #[reflect(min_value = 0.0, step = 1.0, setter = "set_attachment_stiffness")]
+    attachment_stiffness: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_material")]
+    material: InheritableVariable<SharedMaterial>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    points: Vec<Vector3<f32>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    prev_points: Vec<Vector3<f32>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    surface_data: SurfaceSharedData,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    world_bounding_box: Cell<AxisAlignedBoundingBox>,
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        RopeBuilder::new(BaseBuilder::new()).build_rope()
+    }
+}
+
+impl Clone for Rope {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            length: self.length.clone(),
+            segment_count: self.segment_count.clone(),
+            radius: self.radius.clone(),
+            sides: self.sides.clone(),
+            iterations: self.iterations.clone(),
+            damping: self.damping.clone(),
+            gravity_scale: self.gravity_scale.clone(),
+            start_attachment: self.start_attachment.clone(),
+            end_attachment: self.end_attachment.clone(),
+            attachment_stiffness: self.attachment_stiffness.clone(),
+            material: self.material.clone(),
+            points: self.points.clone(),
+            prev_points: self.prev_points.clone(),
+            // Every rope must have its own geometry.
+            surface_data: SurfaceSharedData::new(self.surface_data.lock().clone()),
+            world_bounding_box: self.world_bounding_box.clone(),
+        }
+    }
+}
+
+impl Deref for Rope {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Rope {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Rope {
+    fn type_uuid() -> Uuid {
+        uuid!("0f3c3b0e-5f6c-4b38-9a7e-2d8c6c1d5e42")
+    }
+}
+
+impl Rope {
+    /// Sets new rest length of the rope.
+    pub fn set_length(&mut self, length: f32) -> f32 {
+        self.length.set_value_and_mark_modified(length.max(0.0))
+    }
+
+    /// Returns current rest length of the rope.
+    pub fn length(&self) -> f32 {
+        *self.length
+    }
+
+    /// Sets new amount of segments of the rope. Changing the amount of segments resets the
+    /// simulation.
+    pub fn set_segment_count(&mut self, count: u32) -> u32 {
+        self.segment_count.set_value_and_mark_modified(count.max(1))
+    }
+
+    /// Returns current amount of segments of the rope.
+    pub fn segment_count(&self) -> u32 {
+        *self.segment_count
+    }
+
+    /// Sets new radius of the tube mesh of the rope.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.max(0.0))
+    }
+
+    /// Returns current radius of the tube mesh of the rope.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets new amount of sides of the tube mesh of the rope.
+    pub fn set_sides(&mut self, sides: u32) -> u32 {
+        self.sides.set_value_and_mark_modified(sides.max(3))
+    }
+
+    /// Returns current amount of sides of the tube mesh of the rope.
+    pub fn sides(&self) -> u32 {
+        *self.sides
+    }
+
+    /// Sets new amount of solver iterations.
+    pub fn set_iterations(&mut self, iterations: u32) -> u32 {
+        self.iterations
+            .set_value_and_mark_modified(iterations.max(1))
+    }
+
+    /// Returns current amount of solver iterations.
+    pub fn iterations(&self) -> u32 {
+        *self.iterations
+    }
+
+    /// Sets new velocity damping factor in `[0; 1]` range.
+    pub fn set_damping(&mut self, damping: f32) -> f32 {
+        self.damping
+            .set_value_and_mark_modified(damping.clamp(0.0, 1.0))
+    }
+
+    /// Returns current velocity damping factor.
+    pub fn damping(&self) -> f32 {
+        *self.damping
+    }
+
+    /// Sets new gravity scale of the rope.
+    pub fn set_gravity_scale(&mut self, scale: f32) -> f32 {
+        self.gravity_scale.set_value_and_mark_modified(scale)
+    }
+
+    /// Returns current gravity scale of the rope.
+    pub fn gravity_scale(&self) -> f32 {
+        *self.gravity_scale
+    }
+
+    /// Attaches the start of the rope to the given node.
+    pub fn set_start_attachment(&mut self, handle: Handle<Node>) -> Handle<Node> {
+        self.start_attachment.set_value_and_mark_modified(handle)
+    }
+
+    /// Returns a handle of the node, to which the start of the rope is attached.
+    pub fn start_attachment(&self) -> Handle<Node> {
+        *self.start_attachment
+    }
+
+    /// Attaches the end of the rope to the given node.
+    pub fn set_end_attachment(&mut self, handle: Handle<Node>) -> Handle<Node> {
+        self.end_attachment.set_value_and_mark_modified(handle)
+    }
+
+    /// Returns a handle of the node, to which the end of the rope is attached.
+    pub fn end_attachment(&self) -> Handle<Node> {
+        *self.end_attachment
+    }
+
+    /// Sets new stiffness (in N/m) of attachments to dynamic rigid bodies.
+    pub fn set_attachment_stiffness(&mut self, stiffness: f32) -> f32 {
+        self.attachment_stiffness
+            .set_value_and_mark_modified(stiffness.max(0.0))
+    }
+
+    /// Returns current stiffness of attachments to dynamic rigid bodies.
+    pub fn attachment_stiffness(&self) -> f32 {
+        *self.attachment_stiffness
+    }
+
+    /// Sets new material of the tube mesh of the rope.
+    pub fn set_material(&mut self, material: SharedMaterial) -> SharedMaterial {
+        self.material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns current material of the tube mesh of the rope.
+    pub fn material(&self) -> &SharedMaterial {
+        &self.material
+    }
+
+    /// Returns current positions (in world coordinates) of the points of the rope.
+    pub fn points(&self) -> &[Vector3<f32>] {
+        &self.points
+    }
+
+    /// Returns geometry of the tube mesh of the rope. Vertices are in world coordinates.
+    pub fn surface_data(&self) -> &SurfaceSharedData {
+        &self.surface_data
+    }
+
+    /// Resets the simulation, the rope will be straightened on next update.
+    pub fn reset(&mut self) {
+        self.points.clear();
+        self.prev_points.clear();
+    }
+
+    fn reset_points(&mut self, start: Vector3<f32>, end: Option<Vector3<f32>>) {
+        let count = *self.segment_count as usize + 1;
+        let segment_length = *self.length / *self.segment_count as f32;
+        let direction = end
+            .and_then(|end| (end - start).try_normalize(f32::EPSILON))
+            .unwrap_or_else(|| Vector3::new(0.0, -1.0, 0.0));
+
+        self.points = (0..count)
+            .map(|i| start + direction.scale(segment_length * i as f32))
+            .collect();
+        self.prev_points = self.points.clone();
+    }
+
+    fn simulate(
+        &mut self,
+        dt: f32,
+        gravity: Vector3<f32>,
+        start: Vector3<f32>,
+        end: Option<Vector3<f32>>,
+    ) {
+        if self.points.len() != *self.segment_count as usize + 1 {
+            self.reset_points(start, end);
+        }
+
+        let acceleration_offset = gravity.scale(*self.gravity_scale * dt * dt);
+        let velocity_scale = 1.0 - *self.damping;
+        for (point, prev) in self.points.iter_mut().zip(self.prev_points.iter_mut()) {
+            let velocity = (*point - *prev).scale(velocity_scale);
+            *prev = *point;
+            *point += velocity + acceleration_offset;
+        }
+
+        let segment_length = *self.length / *self.segment_count as f32;
+        let last = self.points.len() - 1;
+        for _ in 0..*self.iterations {
+            self.points[0] = start;
+            if let Some(end) = end {
+                self.points[last] = end;
+            }
+
+            for i in 0..last {
+                let delta = self.points[i + 1] - self.points[i];
+                let distance = delta.norm();
+                if distance <= f32::EPSILON {
+                    continue;
+                }
+                let correction = delta.scale((distance - segment_length) / distance);
+
+                let a_pinned = i == 0;
+                let b_pinned = i + 1 == last && end.is_some();
+                match (a_pinned, b_pinned) {
+                    (true, true) => (),
+                    (true, false) => self.points[i + 1] -= correction,
+                    (false, true) => self.points[i] += correction,
+                    (false, false) => {
+                        self.points[i] += correction.scale(0.5);
+                        self.points[i + 1] -= correction.scale(0.5);
+                    }
+                }
+            }
+        }
+
+        self.points[0] = start;
+        if let Some(end) = end {
+            self.points[last] = end;
+        }
+    }
+
+    /// Calculates a force, that the rope applies to an attachment at `point`, `neighbour` is the
+    /// nearest point of the rope.
+    fn attachment_force(
+        &self,
+        point: Vector3<f32>,
+        neighbour: Vector3<f32>,
+        stretch: f32,
+    ) -> Vector3<f32> {
+        (neighbour - point)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default()
+            .scale(stretch * *self.attachment_stiffness)
+    }
+
+    fn current_length(&self) -> f32 {
+        self.points.windows(2).map(|w| (w[1] - w[0]).norm()).sum()
+    }
+
+    fn update_geometry(&mut self) {
+        let sides = *self.sides as usize;
+        let ring_size = sides + 1;
+        let radius = *self.radius;
+        let count = self.points.len();
+
+        let mut bounds = AxisAlignedBoundingBox::default();
+        let mut vertices = Vec::with_capacity(count * ring_size);
+        let mut normal = Vector3::new(0.0, 0.0, 1.0);
+        let mut v = 0.0;
+        for i in 0..count {
+            let point = self.points[i];
+            let prev = self.points[i.saturating_sub(1)];
+            let next = self.points[(i + 1).min(count - 1)];
+            let tangent = (next - prev)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(|| Vector3::new(0.0, -1.0, 0.0));
+
+            // Parallel transport of the frame along the rope to prevent twisting.
+            normal = (normal - tangent.scale(normal.dot(&tangent)))
+                .try_normalize(f32::EPSILON)
+                .or_else(|| {
+                    tangent
+                        .cross(&Vector3::new(1.0, 0.0, 0.0))
+                        .try_normalize(f32::EPSILON)
+                })
+                .or_else(|| {
+                    tangent
+                        .cross(&Vector3::new(0.0, 0.0, 1.0))
+                        .try_normalize(f32::EPSILON)
+                })
+                .unwrap_or_default();
+            let binormal = tangent.cross(&normal);
+
+            if i > 0 {
+                v += (point - prev).norm();
+            }
+
+            for j in 0..ring_size {
+                let angle = std::f32::consts::TAU * j as f32 / sides as f32;
+                let direction = normal.scale(angle.cos()) + binormal.scale(angle.sin());
+                let position = point + direction.scale(radius);
+                bounds.add_point(position);
+                vertices.push(StaticVertex::from_pos_uv_normal(
+                    position,
+                    Vector2::new(j as f32 / sides as f32, v),
+                    direction,
+                ));
+            }
+        }
+
+        self.world_bounding_box.set(bounds);
+
+        let mut data = self.surface_data.lock();
+        if data.vertex_buffer.vertex_count() as usize == vertices.len() {
+            let mut vertex_buffer = data.vertex_buffer.modify();
+            if let Ok(dest) = vertex_buffer.cast_data_mut::<StaticVertex>() {
+                dest.copy_from_slice(&vertices);
+            }
+        } else {
+            let mut triangles = Vec::with_capacity((count - 1) * sides * 2);
+            for i in 0..(count - 1) {
+                for j in 0..sides {
+                    let a = (i * ring_size + j) as u32;
+                    let b = a + 1;
+                    let c = a + ring_size as u32;
+                    let d = c + 1;
+                    triangles.push(TriangleDefinition([a, c, b]));
+                    triangles.push(TriangleDefinition([b, c, d]));
+                }
+            }
+
+            *data = SurfaceData::new(
+                VertexBuffer::new(vertices.len(), StaticVertex::layout(), vertices).unwrap(),
+                TriangleBuffer::new(triangles),
+                true,
+            );
+        }
+        data.calculate_tangents().unwrap();
+    }
+}
+
+impl NodeTrait for Rope {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        match self.global_transform().try_inverse() {
+            Some(inv_transform) => self.world_bounding_box.get().transform(&inv_transform),
+            None => Default::default(),
+        }
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.world_bounding_box.get()
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager.clone());
+
+        self.material.lock().resolve(resource_manager);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let start = context
+            .nodes
+            .try_borrow(*self.start_attachment)
+            .map_or_else(|| self.global_position(), |node| node.global_position());
+        let end = context
+            .nodes
+            .try_borrow(*self.end_attachment)
+            .map(|node| node.global_position());
+
+        self.simulate(context.dt, context.physics.gravity, start, end);
+
+        // Pull dynamic rigid bodies attached to a stretched rope.
+        let stretch = self.current_length() - *self.length;
+        if stretch > 0.0 && *self.attachment_stiffness > 0.0 {
+            let last = self.points.len() - 1;
+            for (attachment, point, neighbour) in [
+                (*self.start_attachment, self.points[0], self.points[1]),
+                (
+                    *self.end_attachment,
+                    self.points[last],
+                    self.points[last - 1],
+                ),
+            ] {
+                let force = self.attachment_force(point, neighbour, stretch);
+                if let Some(body) = context
+                    .nodes
+                    .try_borrow_mut(attachment)
+                    .and_then(|node| node.cast_mut::<RigidBody>())
+                {
+                    if body.body_type() == RigidBodyType::Dynamic {
+                        body.apply_force(force);
+                        body.wake_up();
+                    }
+                }
+            }
+        }
+
+        self.update_geometry();
+    }
+}
+
+/// Allows you to create ropes in declarative manner.
+pub struct RopeBuilder {
+    base_builder: BaseBuilder,
+    length: f32,
+    segment_count: u32,
+    radius: f32,
+    sides: u32,
+    iterations: u32,
+    damping: f32,
+    gravity_scale: f32,
+    start_attachment: Handle<Node>,
+    end_attachment: Handle<Node>,
+    attachment_stiffness: f32,
+    material: SharedMaterial,
+}
+
+impl RopeBuilder {
+    /// Creates new rope builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            length: 5.0,
+            segment_count: 16,
+            radius: 0.025,
+            sides: 8,
+            iterations: 16,
+            damping: 0.01,
+            gravity_scale: 1.0,
+            start_attachment: Default::default(),
+            end_attachment: Default::default(),
+            attachment_stiffness: 500.0,
+            material: SharedMaterial::new(Material::standard()),
+        }
+    }
+
+    /// Sets desired rest length of the rope.
+    pub fn with_length(mut self, length: f32) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Sets desired amount of segments of the rope.
+    pub fn with_segment_count(mut self, count: u32) -> Self {
+        self.segment_count = count.max(1);
+        self
+    }
+
+    /// Sets desired radius of the tube mesh of the rope.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired amount of sides of the tube mesh of the rope.
+    pub fn with_sides(mut self, sides: u32) -> Self {
+        self.sides = sides.max(3);
+        self
+    }
+
+    /// Sets desired amount of solver iterations.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Sets desired velocity damping factor.
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Sets desired gravity scale.
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = scale;
+        self
+    }
+
+    /// Sets desired node to which the start of the rope will be attached.
+    pub fn with_start_attachment(mut self, handle: Handle<Node>) -> Self {
+        self.start_attachment = handle;
+        self
+    }
+
+    /// Sets desired node to which the end of the rope will be attached.
+    pub fn with_end_attachment(mut self, handle: Handle<Node>) -> Self {
+        self.end_attachment = handle;
+        self
+    }
+
+    /// Sets desired stiffness of attachments to dynamic rigid bodies.
+    pub fn with_attachment_stiffness(mut self, stiffness: f32) -> Self {
+        self.attachment_stiffness = stiffness;
+        self
+    }
+
+    /// Sets desired material of the tube mesh of the rope.
+    pub fn with_material(mut self, material: SharedMaterial) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Creates new rope instance.
+    pub fn build_rope(self) -> Rope {
+        Rope {
+            base: self.base_builder.build_base(),
+            length: self.length.into(),
+            segment_count: self.segment_count.into(),
+            radius: self.radius.into(),
+            sides: self.sides.into(),
+            iterations: self.iterations.into(),
+            damping: self.damping.into(),
+            gravity_scale: self.gravity_scale.into(),
+            start_attachment: self.start_attachment.into(),
+            end_attachment: self.end_attachment.into(),
+            attachment_stiffness: self.attachment_stiffness.into(),
+            material: self.material.into(),
+            points: Default::default(),
+            prev_points: Default::default(),
+            surface_data: SurfaceSharedData::new(SurfaceData::new(
+                VertexBuffer::new::<StaticVertex>(0, StaticVertex::layout(), vec![]).unwrap(),
+                TriangleBuffer::default(),
+                true,
+            )),
+            world_bounding_box: Default::default(),
+        }
+    }
+
+    /// Creates new rope node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_rope())
+    }
+
+    /// Creates new rope node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder, graph::Graph, pivot::PivotBuilder, rope::Rope, rope::RopeBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_rope() {
+        let mut graph = Graph::new();
+
+        let hanging = RopeBuilder::new(BaseBuilder::new())
+            .with_length(4.0)
+            .with_segment_count(8)
+            .with_sides(6)
+            .build(&mut graph);
+
+        let anchor = PivotBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(3.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+        let sagging = RopeBuilder::new(BaseBuilder::new())
+            .with_length(4.0)
+            .with_segment_count(8)
+            .with_end_attachment(anchor)
+            .build(&mut graph);
+
+        for _ in 0..300 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let hanging = graph[hanging].query_component_ref::<Rope>().unwrap();
+        let end = *hanging.points().last().unwrap();
+        assert!(
+            (end - Vector3::new(0.0, -4.0, 0.0)).norm() < 0.1,
+            "{:?}",
+            end
+        );
+        assert_eq!(
+            hanging.surface_data().lock().vertex_buffer.vertex_count(),
+            9 * 7
+        );
+
+        let sagging = graph[sagging].query_component_ref::<Rope>().unwrap();
+        assert_eq!(
+            *sagging.points().last().unwrap(),
+            Vector3::new(3.0, 0.0, 0.0)
+        );
+        assert!(sagging.points()[4].y < -0.5);
+    }
+}
@@ -2,6 +2,8 @@
 
 use crate::{
     core::{
+        algebra::{Matrix3, UnitQuaternion, Vector3},
+        math::Matrix4Ext,
         pool::{Handle, Pool, Ticket},
         reflect::prelude::*,
         visitor::prelude::*,
@@ -9,7 +11,8 @@ use crate::{
     resource::model::Model,
     scene::{
         graph::{map::NodeHandleMap, NodePool},
-        sound::{self, effect::Effect, Sound},
+        node::Node,
+        sound::{self, effect::Effect, listener::Listener, Sound},
     },
     utils::log::{Log, MessageKind},
 };
@@ -21,6 +24,14 @@ use fyrox_sound::{
 };
 use std::time::Duration;
 
+#[derive(Debug, Clone)]
+struct ListenerTransition {
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    elapsed: f32,
+    duration: f32,
+}
+
 /// Sound context.
 #[derive(Debug, Visit, Reflect)]
 pub struct SoundContext {
@@ -28,6 +39,22 @@ pub struct SoundContext {
     renderer: Renderer,
     distance_model: DistanceModel,
     paused: bool,
+    #[visit(optional)] // Backward compatibility
+    #[reflect(hidden)]
+    active_listener: Handle<Node>,
+    #[visit(optional)] // Backward compatibility
+    #[reflect(hidden)]
+    split_screen_listeners: Vec<Handle<Node>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    listener_transition: Option<ListenerTransition>,
+    // Transform of the native listener, that was set on last update.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    listener_transform: Option<(Vector3<f32>, Matrix3<f32>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    sound_positions_remapped: bool,
     #[reflect(hidden)]
     pub(crate) effects: Pool<Effect>,
     #[reflect(read_only)]
@@ -45,6 +72,11 @@ impl Default for SoundContext {
             renderer: Default::default(),
             distance_model: Default::default(),
             paused: false,
+            active_listener: Default::default(),
+            split_screen_listeners: Default::default(),
+            listener_transition: None,
+            listener_transform: None,
+            sound_positions_remapped: false,
             effects: Default::default(),
             resource: None,
             native: fyrox_sound::context::SoundContext::new(),
@@ -167,7 +199,160 @@ impl SoundContext {
         self.master_gain
     }
 
-    pub(crate) fn update(&mut self, nodes: &NodePool) {
+    /// Returns a recording source, that could be used to record output of this context only. See
+    /// [`sound::SoundEngine::start_recording`] for more info.
+    pub fn recording_source(&self) -> sound::RecordingSource {
+        sound::RecordingSource::Context(self.native.clone())
+    }
+
+    /// Makes the given listener active, every other listener will be ignored. If the handle is not
+    /// valid or the listener is disabled, the last enabled listener in the graph is used.
+    pub fn set_active_listener(&mut self, listener: Handle<Node>) {
+        self.active_listener = listener;
+        self.listener_transition = None;
+    }
+
+    /// Makes the given listener active and smoothly moves the "ears" of the scene from the current
+    /// listener to the new one during the given amount of time (in seconds). It prevents abrupt
+    /// jumps of panning and attenuation when switching between cameras, for example when a
+    /// cutscene starts.
+    pub fn switch_listener(&mut self, listener: Handle<Node>, crossfade_duration: f32) {
+        self.active_listener = listener;
+        self.listener_transition = match self.listener_transform {
+            Some((position, basis)) if crossfade_duration > 0.0 => Some(ListenerTransition {
+                position,
+                rotation: rotation_from_basis(&basis),
+                elapsed: 0.0,
+                duration: crossfade_duration,
+            }),
+            _ => None,
+        };
+    }
+
+    /// Returns a handle of the active listener. It could be [`Handle::NONE`], which means that the
+    /// last enabled listener in the graph is used.
+    pub fn active_listener(&self) -> Handle<Node> {
+        self.active_listener
+    }
+
+    /// Returns `true` if the context is switching between listeners.
+    pub fn is_switching_listener(&self) -> bool {
+        self.listener_transition.is_some()
+    }
+
+    /// Sets a set of listeners for split-screen games - every sound will be heard as if it was heard
+    /// by the closest listener from the set. Pass an empty set to disable split-screen mode.
+    pub fn set_split_screen_listeners(&mut self, listeners: Vec<Handle<Node>>) {
+        self.split_screen_listeners = listeners;
+    }
+
+    /// Returns current set of split-screen listeners.
+    pub fn split_screen_listeners(&self) -> &[Handle<Node>] {
+        &self.split_screen_listeners
+    }
+
+    fn find_listener<'a>(&self, nodes: &'a NodePool) -> Option<&'a Listener> {
+        nodes
+            .try_borrow(self.active_listener)
+            .and_then(|n| n.cast::<Listener>())
+            .filter(|l| l.is_globally_enabled())
+            .or_else(|| {
+                nodes
+                    .iter()
+                    .filter_map(|n| n.cast::<Listener>())
+                    .filter(|l| l.is_globally_enabled())
+                    .last()
+            })
+    }
+
+    fn update_listener(&mut self, nodes: &NodePool, dt: f32) {
+        let listener = match self.find_listener(nodes) {
+            Some(listener) => listener,
+            None => return,
+        };
+
+        let mut position = listener.global_position();
+        let mut basis = listener.global_transform().basis();
+
+        if let Some(transition) = self.listener_transition.as_mut() {
+            transition.elapsed += dt;
+            let t = (transition.elapsed / transition.duration).min(1.0);
+            // Smooth step for ease-in/ease-out.
+            let t = t * t * (3.0 - 2.0 * t);
+            position = transition.position.lerp(&position, t);
+            basis = transition
+                .rotation
+                .try_slerp(&rotation_from_basis(&basis), t, f32::EPSILON)
+                .unwrap_or_else(|| rotation_from_basis(&basis))
+                .to_rotation_matrix()
+                .into_inner();
+            if transition.elapsed >= transition.duration {
+                self.listener_transition = None;
+            }
+        }
+
+        let mut state = self.native.state();
+        let native = state.listener_mut();
+        native.set_position(position);
+        native.set_basis(basis);
+        self.listener_transform = Some((position, basis));
+    }
+
+    // Remaps positions of sounds for split-screen mode: a sound is moved to the place relative to the
+    // active listener, where it is relative to the closest split-screen listener.
+    fn remap_sound_positions(&mut self, nodes: &NodePool) {
+        let listeners = self
+            .split_screen_listeners
+            .iter()
+            .filter_map(|l| nodes.try_borrow(*l).and_then(|n| n.cast::<Listener>()))
+            .filter(|l| l.is_globally_enabled())
+            .map(|l| {
+                (
+                    l.global_position(),
+                    rotation_from_basis(&l.global_transform().basis()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let (active_position, active_basis) = match self.listener_transform {
+            Some(transform) if !listeners.is_empty() => transform,
+            _ => {
+                if self.sound_positions_remapped {
+                    // Restore actual positions.
+                    for sound in nodes.iter().filter_map(|n| n.cast::<Sound>()) {
+                        self.set_sound_position(sound);
+                    }
+                    self.sound_positions_remapped = false;
+                }
+                return;
+            }
+        };
+        let active_rotation = rotation_from_basis(&active_basis);
+
+        let mut state = self.native.state();
+        for sound in nodes.iter().filter_map(|n| n.cast::<Sound>()) {
+            if let Some(source) = state.try_get_source_mut(sound.native.get()) {
+                let sound_position = sound.global_position();
+                let (listener_position, listener_rotation) = listeners
+                    .iter()
+                    .min_by(|(a, _), (b, _)| {
+                        a.metric_distance(&sound_position)
+                            .partial_cmp(&b.metric_distance(&sound_position))
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .unwrap();
+                let relative = listener_rotation
+                    .inverse_transform_vector(&(sound_position - listener_position));
+                source.set_position(active_position + active_rotation.transform_vector(&relative));
+            }
+        }
+        self.sound_positions_remapped = true;
+    }
+
+    pub(crate) fn update(&mut self, nodes: &NodePool, dt: f32) {
+        self.update_listener(nodes, dt);
+        self.remap_sound_positions(nodes);
+
         let mut state = self.native.state();
 
         fn sync_effect_inputs(
@@ -351,3 +536,7 @@ impl SoundContext {
         }
     }
 }
+
+fn rotation_from_basis(basis: &Matrix3<f32>) -> UnitQuaternion<f32> {
+    UnitQuaternion::from_matrix_eps(basis, f32::EPSILON, 16, UnitQuaternion::identity())
+}
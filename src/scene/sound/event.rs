@@ -0,0 +1,118 @@
+//! Sound event library allows you to play sound events by their names. See [`SoundEventLibrary`]
+//! docs for more info.
+
+use crate::{
+    asset::ResourceState,
+    core::{algebra::Vector3, pool::Handle, visitor::prelude::*},
+    engine::resource_manager::ResourceManager,
+    rand::Rng,
+    resource::sound_event::SoundEventResource,
+    scene::{graph::Graph, node::Node},
+};
+use fxhash::FxHashMap;
+
+/// A named set of sound events of a scene. It is saved along with the scene, so scripts could play
+/// sound events by their names (see [`crate::scene::Scene::play_sound_event`]) without storing
+/// resources by themselves. See [`crate::resource::sound_event`] module docs for more info.
+#[derive(Default, Clone, Debug, Visit)]
+pub struct SoundEventLibrary {
+    events: FxHashMap<String, SoundEventResource>,
+}
+
+impl SoundEventLibrary {
+    /// Adds a new sound event with the given name, replacing existing one (if any). Returns the
+    /// previous sound event with the same name.
+    pub fn add<S: AsRef<str>>(
+        &mut self,
+        name: S,
+        event: SoundEventResource,
+    ) -> Option<SoundEventResource> {
+        self.events.insert(name.as_ref().to_owned(), event)
+    }
+
+    /// Removes a sound event with the given name.
+    pub fn remove<S: AsRef<str>>(&mut self, name: S) -> Option<SoundEventResource> {
+        self.events.remove(name.as_ref())
+    }
+
+    /// Returns a sound event with the given name.
+    pub fn get<S: AsRef<str>>(&self, name: S) -> Option<&SoundEventResource> {
+        self.events.get(name.as_ref())
+    }
+
+    /// Returns an iterator over names and sound events of the library.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &SoundEventResource)> {
+        self.events.iter()
+    }
+
+    /// Returns `true` if the library is empty.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Plays a sound event with the given name at the given position. See
+    /// [`crate::resource::sound_event::SoundEvent::play`] for more info. Returns [`Handle::NONE`]
+    /// if there is no such event, it is not loaded yet or it is cooling down.
+    pub fn play<S: AsRef<str>, R: Rng + ?Sized>(
+        &self,
+        name: S,
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        time: f32,
+        rng: &mut R,
+    ) -> Handle<Node> {
+        if let Some(event) = self.events.get(name.as_ref()) {
+            if let ResourceState::Ok(ref mut state) = *event.state() {
+                return state.event.play(graph, position, time, rng);
+            }
+        }
+        Handle::NONE
+    }
+
+    pub(crate) fn restore_resources(&mut self, resource_manager: &ResourceManager) {
+        let mut state = resource_manager.state();
+        let container = &mut state.containers_mut().sound_events;
+        for event in self.events.values_mut() {
+            container.try_restore_resource(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Vector3, pool::Handle},
+        rand::{rngs::StdRng, SeedableRng},
+        resource::sound_event::{SoundEvent, SoundEventResource},
+        scene::{
+            graph::Graph,
+            sound::{event::SoundEventLibrary, DataSource, SoundBufferResource},
+        },
+    };
+
+    #[test]
+    fn test_sound_event_library() {
+        let mut rng = StdRng::seed_from_u64(123);
+        let mut graph = Graph::new();
+        let mut library = SoundEventLibrary::default();
+
+        let buffer = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: 44100,
+            channel_count: 1,
+            samples: vec![0.0; 441],
+        })
+        .unwrap();
+
+        let mut event = SoundEvent::default();
+        event.buffers.push(Some(buffer));
+        library.add("Footsteps", SoundEventResource::new(event));
+
+        assert!(library
+            .play("Footsteps", &mut graph, Vector3::default(), 0.0, &mut rng)
+            .is_some());
+        assert_eq!(
+            library.play("Unknown", &mut graph, Vector3::default(), 0.0, &mut rng),
+            Handle::NONE
+        );
+    }
+}
@@ -5,7 +5,7 @@
 
 use crate::{
     core::{
-        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
+        math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
         reflect::prelude::*,
         uuid::{uuid, Uuid},
@@ -15,7 +15,7 @@ use crate::{
     scene::{
         base::{Base, BaseBuilder},
         graph::Graph,
-        node::{Node, NodeTrait, SyncContext, TypeUuidProvider},
+        node::{Node, NodeTrait, TypeUuidProvider},
     },
 };
 use std::ops::{Deref, DerefMut};
@@ -26,8 +26,12 @@ use std::ops::{Deref, DerefMut};
 /// basis's side-vector defines ear axis where -X is for left ear and +X for right. Look vector (Z+)
 /// defines "face" of the listener.
 ///
-/// There can be only one listener at a time, if you create multiple listeners, the last one will
-/// have priority.
+/// A scene can have multiple listeners, but only one of them is active at a time. By default, the
+/// last enabled listener is active, use [`crate::scene::sound::context::SoundContext::set_active_listener`]
+/// to select one explicitly or [`crate::scene::sound::context::SoundContext::switch_listener`] to
+/// smoothly switch to another listener (for example, when a cutscene camera takes over). Split-screen
+/// games could use [`crate::scene::sound::context::SoundContext::set_split_screen_listeners`] to
+/// make every sound heard by the closest listener.
 ///
 /// Usually listener is attached to the main camera, however there might be some other rare cases
 /// and you can attach listener to any node you like.
@@ -82,17 +86,6 @@ impl NodeTrait for Listener {
     fn id(&self) -> Uuid {
         Self::type_uuid()
     }
-
-    fn sync_native(&self, _self_handle: Handle<Node>, context: &mut SyncContext) {
-        if !self.is_globally_enabled() {
-            return;
-        }
-
-        let mut state = context.sound_context.native.state();
-        let native = state.listener_mut();
-        native.set_position(self.global_position());
-        native.set_basis(self.global_transform().basis());
-    }
 }
 
 /// Allows you to create listener in declarative manner.
@@ -126,13 +119,94 @@ impl ListenerBuilder {
 
 #[cfg(test)]
 mod test {
-    use crate::core::reflect::Reflect;
-    use crate::core::variable::try_inherit_properties;
+    use crate::core::{
+        algebra::{Vector2, Vector3},
+        pool::Handle,
+        reflect::Reflect,
+        variable::try_inherit_properties,
+    };
     use crate::scene::{
         base::{test::check_inheritable_properties_equality, BaseBuilder},
-        sound::listener::{Listener, ListenerBuilder},
+        graph::Graph,
+        node::Node,
+        sound::{
+            listener::{Listener, ListenerBuilder},
+            SoundBuilder,
+        },
+        transform::TransformBuilder,
     };
 
+    fn add_listener(graph: &mut Graph, position: Vector3<f32>) -> Handle<Node> {
+        ListenerBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(position)
+                    .build(),
+            ),
+        )
+        .build(graph)
+    }
+
+    fn listener_position(graph: &Graph) -> Vector3<f32> {
+        graph.sound_context.native.state().listener().position()
+    }
+
+    fn update(graph: &mut Graph, dt: f32) {
+        graph.update(Vector2::new(800.0, 600.0), dt, Default::default());
+    }
+
+    #[test]
+    fn test_listener_switching() {
+        let mut graph = Graph::new();
+        let a = add_listener(&mut graph, Vector3::new(0.0, 0.0, 0.0));
+        let b = add_listener(&mut graph, Vector3::new(10.0, 0.0, 0.0));
+
+        // The last listener is active by default.
+        update(&mut graph, 0.1);
+        assert_eq!(listener_position(&graph), Vector3::new(10.0, 0.0, 0.0));
+
+        graph.sound_context.set_active_listener(a);
+        update(&mut graph, 0.1);
+        assert_eq!(listener_position(&graph), Vector3::new(0.0, 0.0, 0.0));
+
+        graph.sound_context.switch_listener(b, 1.0);
+        update(&mut graph, 0.5);
+        assert_eq!(listener_position(&graph), Vector3::new(5.0, 0.0, 0.0));
+        assert!(graph.sound_context.is_switching_listener());
+
+        update(&mut graph, 0.5);
+        assert_eq!(listener_position(&graph), Vector3::new(10.0, 0.0, 0.0));
+        assert!(!graph.sound_context.is_switching_listener());
+    }
+
+    #[test]
+    fn test_split_screen_listeners() {
+        let mut graph = Graph::new();
+        let a = add_listener(&mut graph, Vector3::new(0.0, 0.0, 0.0));
+        let b = add_listener(&mut graph, Vector3::new(100.0, 0.0, 0.0));
+        let sound = SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(101.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .build(&mut graph);
+
+        graph.sound_context.set_active_listener(a);
+        graph.sound_context.set_split_screen_listeners(vec![a, b]);
+        update(&mut graph, 0.1);
+
+        let native = graph[sound].as_sound().native.get();
+        let position = |graph: &Graph| graph.sound_context.native.state().source(native).position();
+        // The sound is closer to the second listener, so it is heard as if it is near the active one.
+        assert_eq!(position(&graph), Vector3::new(1.0, 0.0, 0.0));
+
+        graph.sound_context.set_split_screen_listeners(vec![]);
+        update(&mut graph, 0.1);
+        assert_eq!(position(&graph), Vector3::new(101.0, 0.0, 0.0));
+    }
+
     #[test]
     fn test_listener_inheritance() {
         let parent = ListenerBuilder::new(BaseBuilder::new()).build_node();
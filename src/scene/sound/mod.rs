@@ -28,8 +28,10 @@ pub use fyrox_sound::{
     engine::SoundEngine,
     error::SoundError,
     hrtf::HrirSphere,
+    recorder::RecordingSource,
     renderer::{hrtf::HrtfRenderer, Renderer},
     source::Status,
+    voice,
 };
 
 use fyrox_sound::source::SoundSource;
@@ -41,6 +43,7 @@ use std::{
 
 pub mod context;
 pub mod effect;
+pub mod event;
 pub mod listener;
 
 /// Sound source.
@@ -0,0 +1,249 @@
+//! Spawn point is a marker node that defines a place where players or other actors should appear.
+//! See [`SpawnPoint`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::UnitQuaternion,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait, TypeUuidProvider},
+    },
+};
+use std::ops::{Deref, DerefMut};
+
+/// Spawn point is a marker node that defines a position and orientation (look vector, local +Z
+/// axis) at which players, enemies, items, etc. should be spawned. It does nothing by itself, but
+/// it standardizes the most common marker pattern: a game just queries spawn points of a scene
+/// using [`spawn_points`], [`spawn_points_of_team`] or [`spawn_points_with_tag`] and spawns its
+/// actors there.
+///
+/// Every spawn point has a team index and an arbitrary tag, which could be used to separate spawn
+/// points of different teams in multiplayer games or spawn points of different kinds of actors.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{node::Node, spawn_point, Scene},
+/// };
+///
+/// fn spawn_player(scene: &mut Scene, player: Handle<Node>, team: u32) {
+///     let transform = spawn_point::spawn_points_of_team(&scene.graph, team)
+///         .next()
+///         .map(|(_, spawn_point)| (spawn_point.global_position(), spawn_point.global_rotation()));
+///
+///     if let Some((position, rotation)) = transform {
+///         scene.graph[player]
+///             .local_transform_mut()
+///             .set_position(position)
+///             .set_rotation(rotation);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Visit, Reflect)]
+pub struct SpawnPoint {
+    base: Base,
+
+    #[reflect(setter = "set_team")]
+    team: InheritableVariable<u32>,
+
+    #[reflect(setter = "set_tag")]
+    tag: InheritableVariable<String>,
+}
+
+impl Deref for SpawnPoint {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for SpawnPoint {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for SpawnPoint {
+    fn type_uuid() -> Uuid {
+        uuid!("4f3d8b21-7c0a-4e5f-9a6b-2d1c8e7f0a94")
+    }
+}
+
+impl SpawnPoint {
+    /// Sets new team index of the spawn point.
+    pub fn set_team(&mut self, team: u32) -> u32 {
+        self.team.set_value_and_mark_modified(team)
+    }
+
+    /// Returns current team index of the spawn point.
+    pub fn team(&self) -> u32 {
+        *self.team
+    }
+
+    /// Sets new tag of the spawn point.
+    pub fn set_tag(&mut self, tag: String) -> String {
+        self.tag.set_value_and_mark_modified(tag)
+    }
+
+    /// Returns current tag of the spawn point.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns global rotation of the spawn point, it could be used to orient spawned actors.
+    pub fn global_rotation(&self) -> UnitQuaternion<f32> {
+        UnitQuaternion::face_towards(&self.look_vector(), &self.up_vector())
+    }
+}
+
+impl NodeTrait for SpawnPoint {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager)
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Returns an iterator over every enabled spawn point in the given graph.
+pub fn spawn_points(graph: &Graph) -> impl Iterator<Item = (Handle<Node>, &SpawnPoint)> {
+    graph.pair_iter().filter_map(|(handle, node)| {
+        node.cast::<SpawnPoint>()
+            .filter(|spawn_point| spawn_point.is_globally_enabled())
+            .map(|spawn_point| (handle, spawn_point))
+    })
+}
+
+/// Returns an iterator over every enabled spawn point of the given team in the given graph.
+pub fn spawn_points_of_team(
+    graph: &Graph,
+    team: u32,
+) -> impl Iterator<Item = (Handle<Node>, &SpawnPoint)> {
+    spawn_points(graph).filter(move |(_, spawn_point)| spawn_point.team() == team)
+}
+
+/// Returns an iterator over every enabled spawn point with the given tag in the given graph.
+pub fn spawn_points_with_tag<'a>(
+    graph: &'a Graph,
+    tag: &'a str,
+) -> impl Iterator<Item = (Handle<Node>, &'a SpawnPoint)> {
+    spawn_points(graph).filter(move |(_, spawn_point)| spawn_point.tag() == tag)
+}
+
+/// Allows you to create spawn points in declarative manner.
+pub struct SpawnPointBuilder {
+    base_builder: BaseBuilder,
+    team: u32,
+    tag: String,
+}
+
+impl SpawnPointBuilder {
+    /// Creates new spawn point builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            team: 0,
+            tag: Default::default(),
+        }
+    }
+
+    /// Sets desired team index of the spawn point.
+    pub fn with_team(mut self, team: u32) -> Self {
+        self.team = team;
+        self
+    }
+
+    /// Sets desired tag of the spawn point.
+    pub fn with_tag<S: AsRef<str>>(mut self, tag: S) -> Self {
+        self.tag = tag.as_ref().to_owned();
+        self
+    }
+
+    /// Creates new spawn point instance.
+    pub fn build_spawn_point(self) -> SpawnPoint {
+        SpawnPoint {
+            base: self.base_builder.build_base(),
+            team: self.team.into(),
+            tag: self.tag.into(),
+        }
+    }
+
+    /// Creates new spawn point node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_spawn_point())
+    }
+
+    /// Creates new spawn point node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        spawn_point::{self, SpawnPointBuilder},
+    };
+
+    #[test]
+    fn test_spawn_point_queries() {
+        let mut graph = Graph::new();
+
+        let red = SpawnPointBuilder::new(BaseBuilder::new())
+            .with_team(1)
+            .with_tag("Player")
+            .build(&mut graph);
+        let blue = SpawnPointBuilder::new(BaseBuilder::new())
+            .with_team(2)
+            .with_tag("Player")
+            .build(&mut graph);
+        let item = SpawnPointBuilder::new(BaseBuilder::new())
+            .with_tag("Item")
+            .build(&mut graph);
+        let disabled = SpawnPointBuilder::new(BaseBuilder::new().with_enabled(false))
+            .with_team(1)
+            .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let all = spawn_point::spawn_points(&graph)
+            .map(|(h, _)| h)
+            .collect::<Vec<_>>();
+        assert_eq!(all, vec![red, blue, item]);
+        assert!(!all.contains(&disabled));
+
+        let team = spawn_point::spawn_points_of_team(&graph, 2)
+            .map(|(h, _)| h)
+            .collect::<Vec<_>>();
+        assert_eq!(team, vec![blue]);
+
+        let tagged = spawn_point::spawn_points_with_tag(&graph, "Player")
+            .map(|(h, _)| h)
+            .collect::<Vec<_>>();
+        assert_eq!(tagged, vec![red, blue]);
+    }
+}
@@ -0,0 +1,442 @@
+//! Contains all structures and methods to create and manage 3D text.
+//!
+//! For more info see [`Text3D`].
+
+use crate::{
+    core::{
+        algebra::Vector2,
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    engine::resource_manager::ResourceManager,
+    gui::ttf::{FontBuilder, SharedFont},
+    scene::{
+        base::{Base, BaseBuilder},
+        graph::Graph,
+        node::{Node, NodeTrait, TypeUuidProvider},
+    },
+};
+use lazy_static::lazy_static;
+use std::ops::{Deref, DerefMut};
+
+lazy_static! {
+    static ref DEFAULT_FONT: SharedFont = SharedFont::new(
+        FontBuilder::new()
+            .with_height(Text3D::DEFAULT_FONT_HEIGHT)
+            .build_builtin()
+            .unwrap()
+    );
+}
+
+/// Text3D is a piece of text that is placed in the 3D world. It could either always face the camera
+/// (billboard mode) or be oriented using its own transform, which is useful for damage numbers, labels
+/// above characters, diegetic user interface (signs, screens) and so on.
+///
+/// # Rendering
+///
+/// The text is rendered using signed distance fields (SDF) generated from glyphs of the font, so it
+/// stays sharp at any distance and allows to have outline and drop shadow almost for free. Text is
+/// rendered after tone mapping and anti-aliasing, so it is not affected by lighting and post effects.
+///
+/// Text is centered around the position of the node, multiple lines could be separated by `\n`.
+///
+/// # Fonts
+///
+/// By default the built-in font is used. A custom font could be set using [`Text3D::set_font`], but
+/// it is not serialized and must be set again after a scene is loaded. The font should be rasterized at
+/// relatively large height (32-64 pixels), because it is used only as the source for distance fields,
+/// the size of text in the world is defined by [`Text3D::set_font_size`].
+///
+/// # Depth sorting
+///
+/// Text nodes are **not** depth-sorted, so there could be some blending issues if multiple pieces of
+/// text are overlapping.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{color::Color, pool::Handle},
+///     scene::{base::BaseBuilder, graph::Graph, node::Node, text3d::Text3DBuilder},
+/// };
+///
+/// fn create_damage_number(graph: &mut Graph, damage: u32) -> Handle<Node> {
+///     Text3DBuilder::new(BaseBuilder::new())
+///         .with_text(damage.to_string())
+///         .with_color(Color::opaque(255, 60, 60))
+///         .with_outline(Color::BLACK, 0.5)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Debug, Reflect, Clone, Visit)]
+pub struct Text3D {
+    base: Base,
+
+    #[reflect(setter = "set_text")]
+    text: InheritableVariable<String>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_font_size")]
+    font_size: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_color")]
+    color: InheritableVariable<Color>,
+
+    #[reflect(setter = "set_billboard")]
+    billboard: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_outline_color")]
+    outline_color: InheritableVariable<Color>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[reflect(setter = "set_outline_width")]
+    outline_width: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_shadow_enabled")]
+    shadow_enabled: InheritableVariable<bool>,
+
+    #[reflect(setter = "set_shadow_color")]
+    shadow_color: InheritableVariable<Color>,
+
+    #[reflect(setter = "set_shadow_offset")]
+    shadow_offset: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(setter = "set_draw_on_top")]
+    draw_on_top: InheritableVariable<bool>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    font: SharedFont,
+}
+
+impl Deref for Text3D {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Text3D {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl Default for Text3D {
+    fn default() -> Self {
+        Text3DBuilder::new(BaseBuilder::new()).build_text3d()
+    }
+}
+
+impl TypeUuidProvider for Text3D {
+    fn type_uuid() -> Uuid {
+        uuid!("5c6a3a2e-2f3b-4b8e-9a52-0c1c9d0f7e41")
+    }
+}
+
+impl Text3D {
+    /// Height (in pixels) of the built-in font that is used by default.
+    pub const DEFAULT_FONT_HEIGHT: f32 = 48.0;
+
+    /// Sets new text. Lines could be separated by `\n`.
+    pub fn set_text(&mut self, text: String) -> String {
+        self.text.set_value_and_mark_modified(text)
+    }
+
+    /// Returns current text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Sets height of a line of text in world units. Default is 0.25.
+    pub fn set_font_size(&mut self, font_size: f32) -> f32 {
+        self.font_size.set_value_and_mark_modified(font_size)
+    }
+
+    /// Returns height of a line of text in world units.
+    pub fn font_size(&self) -> f32 {
+        *self.font_size
+    }
+
+    /// Sets new color of the text. Default is White.
+    pub fn set_color(&mut self, color: Color) -> Color {
+        self.color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of the text.
+    pub fn color(&self) -> Color {
+        *self.color
+    }
+
+    /// Enables or disables billboard mode. In billboard mode the text always faces the camera,
+    /// otherwise it lies in local XY plane of the node (the text is readable when looking along
+    /// local Z axis). Default is `true`.
+    pub fn set_billboard(&mut self, billboard: bool) -> bool {
+        self.billboard.set_value_and_mark_modified(billboard)
+    }
+
+    /// Returns `true` if the text is in billboard mode.
+    pub fn is_billboard(&self) -> bool {
+        *self.billboard
+    }
+
+    /// Sets new color of the outline. Default is Black.
+    pub fn set_outline_color(&mut self, color: Color) -> Color {
+        self.outline_color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of the outline.
+    pub fn outline_color(&self) -> Color {
+        *self.outline_color
+    }
+
+    /// Sets relative width of the outline in `[0; 1]` range, where 0 means no outline and 1 -
+    /// maximum width that is supported by the distance field. Default is 0.0.
+    pub fn set_outline_width(&mut self, width: f32) -> f32 {
+        self.outline_width
+            .set_value_and_mark_modified(width.clamp(0.0, 1.0))
+    }
+
+    /// Returns relative width of the outline.
+    pub fn outline_width(&self) -> f32 {
+        *self.outline_width
+    }
+
+    /// Enables or disables drop shadow. Default is `false`.
+    pub fn set_shadow_enabled(&mut self, enabled: bool) -> bool {
+        self.shadow_enabled.set_value_and_mark_modified(enabled)
+    }
+
+    /// Returns `true` if drop shadow is enabled.
+    pub fn is_shadow_enabled(&self) -> bool {
+        *self.shadow_enabled
+    }
+
+    /// Sets new color of the drop shadow. Default is semi-transparent black.
+    pub fn set_shadow_color(&mut self, color: Color) -> Color {
+        self.shadow_color.set_value_and_mark_modified(color)
+    }
+
+    /// Returns current color of the drop shadow.
+    pub fn shadow_color(&self) -> Color {
+        *self.shadow_color
+    }
+
+    /// Sets offset of the drop shadow in the plane of the text (in world units). Default is
+    /// `(0.01, -0.01)`.
+    pub fn set_shadow_offset(&mut self, offset: Vector2<f32>) -> Vector2<f32> {
+        self.shadow_offset.set_value_and_mark_modified(offset)
+    }
+
+    /// Returns offset of the drop shadow.
+    pub fn shadow_offset(&self) -> Vector2<f32> {
+        *self.shadow_offset
+    }
+
+    /// Sets whether the text should be drawn on top of everything (depth test is disabled).
+    /// Default is `false`.
+    pub fn set_draw_on_top(&mut self, draw_on_top: bool) -> bool {
+        self.draw_on_top.set_value_and_mark_modified(draw_on_top)
+    }
+
+    /// Returns `true` if the text is drawn on top of everything.
+    pub fn is_draw_on_top(&self) -> bool {
+        *self.draw_on_top
+    }
+
+    /// Sets new font. Keep in mind that the font is not serialized, see [`Text3D`] docs for more info.
+    pub fn set_font(&mut self, font: SharedFont) -> SharedFont {
+        std::mem::replace(&mut self.font, font)
+    }
+
+    /// Returns current font.
+    pub fn font(&self) -> &SharedFont {
+        &self.font
+    }
+}
+
+impl NodeTrait for Text3D {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        let longest_line = self
+            .text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default();
+        let line_count = self.text.lines().count().max(1);
+        // Rough estimation, average glyph is not wider than a half of line height.
+        let half_width = 0.25 * *self.font_size * longest_line as f32;
+        let half_height = 0.5 * *self.font_size * line_count as f32;
+        AxisAlignedBoundingBox::from_radius(half_width.max(half_height))
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        self.base.restore_resources(resource_manager);
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+/// Text3D builder allows you to construct 3D text in declarative manner.
+/// This is typical implementation of Builder pattern.
+pub struct Text3DBuilder {
+    base_builder: BaseBuilder,
+    text: String,
+    font_size: f32,
+    color: Color,
+    billboard: bool,
+    outline_color: Color,
+    outline_width: f32,
+    shadow_enabled: bool,
+    shadow_color: Color,
+    shadow_offset: Vector2<f32>,
+    draw_on_top: bool,
+    font: Option<SharedFont>,
+}
+
+impl Text3DBuilder {
+    /// Creates new builder with default state (empty white billboard text without outline and shadow).
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            text: Default::default(),
+            font_size: 0.25,
+            color: Color::WHITE,
+            billboard: true,
+            outline_color: Color::BLACK,
+            outline_width: 0.0,
+            shadow_enabled: false,
+            shadow_color: Color::from_rgba(0, 0, 0, 160),
+            shadow_offset: Vector2::new(0.01, -0.01),
+            draw_on_top: false,
+            font: None,
+        }
+    }
+
+    /// Sets desired text.
+    pub fn with_text<S: AsRef<str>>(mut self, text: S) -> Self {
+        self.text = text.as_ref().to_owned();
+        self
+    }
+
+    /// Sets desired height of a line of text in world units.
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Sets desired color.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets whether the text should always face the camera or not.
+    pub fn with_billboard(mut self, billboard: bool) -> Self {
+        self.billboard = billboard;
+        self
+    }
+
+    /// Sets desired outline color and relative width (in `[0; 1]` range).
+    pub fn with_outline(mut self, color: Color, width: f32) -> Self {
+        self.outline_color = color;
+        self.outline_width = width.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Enables drop shadow with the given color and offset.
+    pub fn with_shadow(mut self, color: Color, offset: Vector2<f32>) -> Self {
+        self.shadow_enabled = true;
+        self.shadow_color = color;
+        self.shadow_offset = offset;
+        self
+    }
+
+    /// Sets whether the text should be drawn on top of everything.
+    pub fn with_draw_on_top(mut self, draw_on_top: bool) -> Self {
+        self.draw_on_top = draw_on_top;
+        self
+    }
+
+    /// Sets desired font.
+    pub fn with_font(mut self, font: SharedFont) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    fn build_text3d(self) -> Text3D {
+        Text3D {
+            base: self.base_builder.build_base(),
+            text: self.text.into(),
+            font_size: self.font_size.into(),
+            color: self.color.into(),
+            billboard: self.billboard.into(),
+            outline_color: self.outline_color.into(),
+            outline_width: self.outline_width.into(),
+            shadow_enabled: self.shadow_enabled.into(),
+            shadow_color: self.shadow_color.into(),
+            shadow_offset: self.shadow_offset.into(),
+            draw_on_top: self.draw_on_top.into(),
+            font: self.font.unwrap_or_else(|| DEFAULT_FONT.clone()),
+        }
+    }
+
+    /// Creates new 3D text instance.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_text3d())
+    }
+
+    /// Creates new 3D text instance and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::reflect::Reflect;
+    use crate::core::variable::try_inherit_properties;
+    use crate::{
+        core::{algebra::Vector2, color::Color},
+        scene::{
+            base::{test::check_inheritable_properties_equality, BaseBuilder},
+            text3d::{Text3D, Text3DBuilder},
+        },
+    };
+
+    #[test]
+    fn test_text3d_inheritance() {
+        let parent = Text3DBuilder::new(BaseBuilder::new())
+            .with_text("Foo\nBar")
+            .with_font_size(1.5)
+            .with_color(Color::opaque(1, 2, 3))
+            .with_billboard(false)
+            .with_outline(Color::opaque(4, 5, 6), 0.5)
+            .with_shadow(Color::opaque(7, 8, 9), Vector2::new(0.1, 0.2))
+            .with_draw_on_top(true)
+            .build_node();
+
+        let mut child = Text3DBuilder::new(BaseBuilder::new()).build_text3d();
+
+        try_inherit_properties(child.as_reflect_mut(), parent.as_reflect()).unwrap();
+
+        let parent = parent.cast::<Text3D>().unwrap();
+
+        check_inheritable_properties_equality(&child.base, &parent.base);
+        check_inheritable_properties_equality(&child, parent)
+    }
+}
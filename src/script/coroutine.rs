@@ -0,0 +1,289 @@
+//! Coroutines allow you to write sequenced gameplay logic (wait 2 seconds, move, wait for an animation to
+//! end, etc.) in a linear, `yield`-like style, instead of a state machine. See [`CoroutineScheduler`] docs
+//! for more info.
+
+use crate::{
+    core::{futures::task::noop_waker, pool::Handle},
+    scene::node::Node,
+    script::ScriptContext,
+    utils::log::Log,
+};
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Formatter},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+// A request of a coroutine, which must be evaluated by the scheduler with script context. Returns `true`
+// when the request is satisfied and the coroutine can continue its execution.
+type Request = Box<dyn FnMut(&mut ScriptContext) -> bool>;
+
+#[derive(Default)]
+struct SharedState {
+    request: Option<Request>,
+}
+
+/// Maximum amount of requests, that a single coroutine could satisfy in a single frame. It prevents the
+/// engine from hanging in case of infinite loops without waiting in coroutines.
+const MAX_STEPS_PER_FRAME: usize = 1024;
+
+struct RequestFuture {
+    state: Rc<RefCell<SharedState>>,
+    request: Option<Request>,
+}
+
+impl Future for RequestFuture {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(request) = self.request.take() {
+            // Put the request to the scheduler and wait until it is satisfied.
+            self.state.borrow_mut().request = Some(request);
+            Poll::Pending
+        } else if self.state.borrow().request.is_some() {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+/// Coroutine context is passed to a coroutine body, it provides a set of "awaitable" methods, that could
+/// be used to suspend the coroutine until some condition is met. See [`CoroutineScheduler`] docs for
+/// more info.
+#[derive(Clone)]
+pub struct CoroutineContext {
+    state: Rc<RefCell<SharedState>>,
+}
+
+impl Debug for CoroutineContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CoroutineContext")
+    }
+}
+
+impl CoroutineContext {
+    fn request(&self, request: Request) -> impl Future<Output = ()> {
+        RequestFuture {
+            state: self.state.clone(),
+            request: Some(request),
+        }
+    }
+
+    /// Suspends the coroutine until the given amount of seconds passes. Time is measured using
+    /// [`ScriptContext::elapsed_time`].
+    pub fn wait_seconds(&self, seconds: f32) -> impl Future<Output = ()> {
+        let mut end_time = None;
+        self.request(Box::new(move |ctx: &mut ScriptContext| {
+            ctx.elapsed_time >= *end_time.get_or_insert(ctx.elapsed_time + seconds)
+        }))
+    }
+
+    /// Suspends the coroutine for the given amount of script updates. `wait_frames(1)` resumes the
+    /// coroutine on the next update.
+    pub fn wait_frames(&self, frames: usize) -> impl Future<Output = ()> {
+        let mut counter = 0;
+        self.request(Box::new(move |_: &mut ScriptContext| {
+            let done = counter >= frames;
+            counter += 1;
+            done
+        }))
+    }
+
+    /// Suspends the coroutine until the given predicate returns `true`. The predicate is checked once
+    /// per script update.
+    pub fn wait_until<F>(&self, predicate: F) -> impl Future<Output = ()>
+    where
+        F: FnMut(&mut ScriptContext) -> bool + 'static,
+    {
+        self.request(Box::new(predicate))
+    }
+
+    /// Executes the given function with script context. It should be used to access a scene and other
+    /// parts of the engine from coroutines. The function is executed immediately, without waiting for
+    /// the next update.
+    pub fn with<F>(&self, func: F) -> impl Future<Output = ()>
+    where
+        F: FnOnce(&mut ScriptContext) + 'static,
+    {
+        let mut func = Some(func);
+        self.request(Box::new(move |ctx: &mut ScriptContext| {
+            if let Some(func) = func.take() {
+                func(ctx);
+            }
+            true
+        }))
+    }
+}
+
+struct Coroutine {
+    owner: Handle<Node>,
+    state: Rc<RefCell<SharedState>>,
+    future: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+impl Coroutine {
+    // Returns `true` if the coroutine is still running.
+    fn resume(&mut self, ctx: &mut ScriptContext, cx: &mut Context) -> bool {
+        for _ in 0..MAX_STEPS_PER_FRAME {
+            let request = self.state.borrow_mut().request.take();
+            if let Some(mut request) = request {
+                if !request(ctx) {
+                    self.state.borrow_mut().request = Some(request);
+                    return true;
+                }
+            }
+
+            match self.future.as_mut().poll(cx) {
+                Poll::Ready(()) => return false,
+                Poll::Pending => {
+                    if self.state.borrow().request.is_none() {
+                        // The coroutine waits for something that is not controlled by the scheduler.
+                        return true;
+                    }
+                }
+            }
+        }
+
+        Log::warn(format!(
+            "Coroutine of {} node made too many steps in one frame! Most likely it has an infinite \
+            loop without waiting.",
+            self.owner
+        ));
+
+        true
+    }
+}
+
+/// Coroutine scheduler holds coroutines of every script of a scene and resumes them once per frame, just
+/// before [`crate::script::ScriptTrait::on_update`] of scripts. A coroutine is an `async` block, that can
+/// wait for some time or for some condition using methods of [`CoroutineContext`]. It can access the
+/// scene (and other parts of the engine) only via [`CoroutineContext::with`] and
+/// [`CoroutineContext::wait_until`], that provide [`ScriptContext`] of the node that started the
+/// coroutine:
+///
+/// ```rust
+/// use fyrox::{
+///     core::algebra::Vector3,
+///     script::ScriptContext,
+/// };
+///
+/// fn on_start(ctx: &mut ScriptContext) {
+///     ctx.start_coroutine(|co| async move {
+///         co.wait_seconds(2.0).await;
+///
+///         co.with(|ctx| {
+///             ctx.scene.graph[ctx.handle]
+///                 .local_transform_mut()
+///                 .offset(Vector3::new(0.0, 1.0, 0.0));
+///         })
+///         .await;
+///
+///         co.wait_until(|ctx| ctx.scene.graph[ctx.handle].global_position().y > 10.0)
+///             .await;
+///     });
+/// }
+/// ```
+///
+/// Coroutines of a node are stopped automatically when the node or its script is destroyed. Coroutines of
+/// disabled nodes are paused.
+#[derive(Default)]
+pub struct CoroutineScheduler {
+    coroutines: Vec<Coroutine>,
+    // Owners of coroutines, that were stopped while the coroutines were resumed.
+    stop_list: Vec<Handle<Node>>,
+    resuming: bool,
+}
+
+impl Debug for CoroutineScheduler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CoroutineScheduler {{ {} coroutines }}",
+            self.coroutines.len()
+        )
+    }
+}
+
+impl CoroutineScheduler {
+    /// Starts a new coroutine, that belongs to the given node. The coroutine will be resumed for the first
+    /// time on the next frame.
+    pub fn start<F, Fut>(&mut self, owner: Handle<Node>, func: F)
+    where
+        F: FnOnce(CoroutineContext) -> Fut,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let state = Rc::new(RefCell::new(SharedState::default()));
+        let future = func(CoroutineContext {
+            state: state.clone(),
+        });
+        self.coroutines.push(Coroutine {
+            owner,
+            state,
+            future: Box::pin(future),
+        });
+    }
+
+    /// Stops every coroutine of the given node.
+    pub fn stop_all(&mut self, owner: Handle<Node>) {
+        self.coroutines.retain(|c| c.owner != owner);
+        if self.resuming {
+            self.stop_list.push(owner);
+        }
+    }
+
+    /// Returns amount of running coroutines of the given node.
+    pub fn count_of(&self, owner: Handle<Node>) -> usize {
+        self.coroutines.iter().filter(|c| c.owner == owner).count()
+    }
+
+    /// Returns total amount of running coroutines.
+    pub fn len(&self) -> usize {
+        self.coroutines.len()
+    }
+
+    /// Returns `true` if there are no running coroutines.
+    pub fn is_empty(&self) -> bool {
+        self.coroutines.is_empty()
+    }
+}
+
+// Resumes every coroutine of the scheduler, that is stored in the context.
+pub(crate) fn resume_coroutines(ctx: &mut ScriptContext) {
+    if ctx.coroutines.is_empty() {
+        return;
+    }
+
+    // Take the coroutines, so they could start new coroutines while running.
+    let mut coroutines = std::mem::take(&mut ctx.coroutines.coroutines);
+    ctx.coroutines.resuming = true;
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    coroutines.retain_mut(|coroutine| {
+        if ctx.coroutines.stop_list.contains(&coroutine.owner) {
+            return false;
+        }
+
+        match ctx.scene.graph.try_get(coroutine.owner) {
+            Some(node) => {
+                if !node.is_globally_enabled() {
+                    return true;
+                }
+            }
+            None => return false,
+        }
+
+        ctx.handle = coroutine.owner;
+        coroutine.resume(ctx, &mut cx)
+    });
+
+    ctx.coroutines.resuming = false;
+    ctx.coroutines.stop_list.clear();
+    coroutines.append(&mut ctx.coroutines.coroutines);
+    ctx.coroutines.coroutines = coroutines;
+}
@@ -0,0 +1,435 @@
+//! Lua scripting backend. It allows you to write game logic in Lua, without recompilation of the game.
+//! Requires `lua-scripting` feature.
+//!
+//! # Usage
+//!
+//! [`LuaScript`] is an ordinary script, that executes a Lua file loaded through the resource manager
+//! (see [`ScriptSource`]). Since the source is a resource, it is hot-reloaded when the file changes: the
+//! new code is executed in the same Lua state, so global variables of the script survive the reload.
+//!
+//! ```rust,no_run
+//! use fyrox::{
+//!     engine::resource_manager::ResourceManager,
+//!     scene::{base::BaseBuilder, pivot::PivotBuilder, Scene},
+//!     script::{lua::LuaScript, Script},
+//! };
+//!
+//! fn create_node(scene: &mut Scene, resource_manager: &ResourceManager) {
+//!     PivotBuilder::new(BaseBuilder::new().with_script(Script::new(LuaScript::new(
+//!         resource_manager.request_script_source("data/scripts/door.lua"),
+//!     ))))
+//!     .build(&mut scene.graph);
+//! }
+//! ```
+//!
+//! # Callbacks
+//!
+//! Every callback is an optional global function of the script:
+//!
+//! - `on_init()` - called once, before any other callback.
+//! - `on_start()` - called once, after every script of the scene is initialized.
+//! - `on_update(dt)` - called every frame with fixed time step.
+//! - `on_message(text, sender)` - called for every [`LuaMessage`] sent to the node. Messages are
+//!   delivered at the beginning of the next `on_update`, so the engine API is available in the callback.
+//! - `on_deinit()` - called when the script is destroyed. The engine API is **not** available in the
+//!   callback.
+//!
+//! # Engine API
+//!
+//! The engine functions are stored in the global `fyrox` table. Scene nodes are passed as integers,
+//! functions that take a node raise an error if the node does not exist.
+//!
+//! - `fyrox.handle()` - returns the node of the script.
+//! - `fyrox.dt()`, `fyrox.elapsed_time()` - returns time step and elapsed time in seconds.
+//! - `fyrox.log(text)` - writes a message to the log.
+//! - `fyrox.find_node(name)` - returns a node with the given name or `nil`.
+//! - `fyrox.get_position(node)`, `fyrox.set_position(node, x, y, z)` - local position of a node.
+//! - `fyrox.get_rotation(node)`, `fyrox.set_rotation(node, x, y, z)` - local rotation of a node as
+//!   Euler angles in radians.
+//! - `fyrox.get_scale(node)`, `fyrox.set_scale(node, x, y, z)` - local scale of a node.
+//! - `fyrox.set_enabled(node, enabled)` - enables or disables a node.
+//! - `fyrox.load_model(path)` - starts loading of a model, returns `true` if the model is loaded.
+//! - `fyrox.instantiate_model(path)` - instantiates a loaded model, returns its root or `nil` if the
+//!   model is not loaded yet.
+//! - `fyrox.send_message(node, text)` - sends a [`LuaMessage`] to the given node.
+//! - `fyrox.broadcast_message(text)` - sends a [`LuaMessage`] to every descendant of the node of the
+//!   script (including the node itself).
+
+use crate::{
+    asset::ResourceState,
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    impl_component_provider,
+    resource::{model::Model, script_source::ScriptSource},
+    scene::node::{Node, TypeUuidProvider},
+    script::{
+        RoutingStrategy, ScriptContext, ScriptDeinitContext, ScriptMessageContext,
+        ScriptMessagePayload, ScriptTrait,
+    },
+    utils::log::Log,
+};
+use mlua::{Lua, Scope, Value};
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Formatter},
+};
+
+/// A message, that could be sent from one Lua script to another. See module docs for more info.
+#[derive(Clone, Debug)]
+pub struct LuaMessage {
+    /// A node, that sent the message.
+    pub sender: Handle<Node>,
+    /// Contents of the message.
+    pub text: String,
+}
+
+struct LuaRuntime {
+    lua: Lua,
+    // Revision of the source, that was executed by the runtime.
+    revision: u64,
+}
+
+/// A script, that executes Lua code. See module docs for more info.
+#[derive(Visit, Reflect, Default)]
+pub struct LuaScript {
+    /// Lua source of the script.
+    pub source: Option<ScriptSource>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    runtime: Option<LuaRuntime>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    messages: Vec<LuaMessage>,
+}
+
+impl Debug for LuaScript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LuaScript")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl Clone for LuaScript {
+    fn clone(&self) -> Self {
+        // Lua state cannot be cloned, the clone will create its own state on first use.
+        Self {
+            source: self.source.clone(),
+            runtime: None,
+            messages: Default::default(),
+        }
+    }
+}
+
+impl TypeUuidProvider for LuaScript {
+    fn type_uuid() -> Uuid {
+        uuid!("3c2c4e3a-0f0a-4c8e-9e53-7f6b3c1d5a27")
+    }
+}
+
+impl_component_provider!(LuaScript);
+
+fn encode_handle(handle: Handle<Node>) -> i64 {
+    (((handle.generation() as u64) << 32) | handle.index() as u64) as i64
+}
+
+fn decode_handle(value: i64) -> Handle<Node> {
+    let value = value as u64;
+    Handle::new(value as u32, (value >> 32) as u32)
+}
+
+fn node_mut<'a>(ctx: &'a mut ScriptContext, node: i64) -> mlua::Result<&'a mut Node> {
+    ctx.scene
+        .graph
+        .try_get_mut(decode_handle(node))
+        .ok_or_else(|| mlua::Error::RuntimeError(format!("Invalid node {}!", node)))
+}
+
+fn register_api<'lua, 'scope, 's, 'a, 'b, 'c>(
+    lua: &'lua Lua,
+    scope: &Scope<'lua, 'scope>,
+    ctx: &'scope RefCell<&'s mut ScriptContext<'a, 'b, 'c>>,
+) -> mlua::Result<()> {
+    let api = lua.create_table()?;
+
+    api.set(
+        "handle",
+        scope.create_function(move |_, ()| Ok(encode_handle(ctx.borrow().handle)))?,
+    )?;
+    api.set(
+        "dt",
+        scope.create_function(move |_, ()| Ok(ctx.borrow().dt))?,
+    )?;
+    api.set(
+        "elapsed_time",
+        scope.create_function(move |_, ()| Ok(ctx.borrow().elapsed_time))?,
+    )?;
+    api.set(
+        "log",
+        scope.create_function(move |_, text: String| {
+            Log::info(text);
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "find_node",
+        scope.create_function(move |_, name: String| {
+            Ok(ctx
+                .borrow()
+                .scene
+                .graph
+                .find_by_name_from_root(&name)
+                .map(|(handle, _)| encode_handle(handle)))
+        })?,
+    )?;
+    api.set(
+        "get_position",
+        scope.create_function(move |_, node: i64| {
+            let mut ctx = ctx.borrow_mut();
+            let position = **node_mut(&mut ctx, node)?.local_transform().position();
+            Ok((position.x, position.y, position.z))
+        })?,
+    )?;
+    api.set(
+        "set_position",
+        scope.create_function(move |_, (node, x, y, z): (i64, f32, f32, f32)| {
+            let mut ctx = ctx.borrow_mut();
+            node_mut(&mut ctx, node)?
+                .local_transform_mut()
+                .set_position(Vector3::new(x, y, z));
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "get_rotation",
+        scope.create_function(move |_, node: i64| {
+            let mut ctx = ctx.borrow_mut();
+            Ok(node_mut(&mut ctx, node)?
+                .local_transform()
+                .rotation()
+                .euler_angles())
+        })?,
+    )?;
+    api.set(
+        "set_rotation",
+        scope.create_function(move |_, (node, x, y, z): (i64, f32, f32, f32)| {
+            let mut ctx = ctx.borrow_mut();
+            node_mut(&mut ctx, node)?
+                .local_transform_mut()
+                .set_rotation(UnitQuaternion::from_euler_angles(x, y, z));
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "get_scale",
+        scope.create_function(move |_, node: i64| {
+            let mut ctx = ctx.borrow_mut();
+            let scale = **node_mut(&mut ctx, node)?.local_transform().scale();
+            Ok((scale.x, scale.y, scale.z))
+        })?,
+    )?;
+    api.set(
+        "set_scale",
+        scope.create_function(move |_, (node, x, y, z): (i64, f32, f32, f32)| {
+            let mut ctx = ctx.borrow_mut();
+            node_mut(&mut ctx, node)?
+                .local_transform_mut()
+                .set_scale(Vector3::new(x, y, z));
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "set_enabled",
+        scope.create_function(move |_, (node, enabled): (i64, bool)| {
+            let mut ctx = ctx.borrow_mut();
+            node_mut(&mut ctx, node)?.set_enabled(enabled);
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "load_model",
+        scope.create_function(move |_, path: String| {
+            let model = ctx.borrow().resource_manager.request_model(path);
+            let is_loaded = matches!(*model.state(), ResourceState::Ok(_));
+            Ok(is_loaded)
+        })?,
+    )?;
+    api.set(
+        "instantiate_model",
+        scope.create_function(move |_, path: String| {
+            let mut ctx = ctx.borrow_mut();
+            let model: Model = ctx.resource_manager.request_model(path);
+            let is_loaded = matches!(*model.state(), ResourceState::Ok(_));
+            Ok(if is_loaded {
+                Some(encode_handle(model.instantiate(ctx.scene)))
+            } else {
+                None
+            })
+        })?,
+    )?;
+    api.set(
+        "send_message",
+        scope.create_function(move |_, (node, text): (i64, String)| {
+            let ctx = ctx.borrow();
+            ctx.send_message(
+                decode_handle(node),
+                LuaMessage {
+                    sender: ctx.handle,
+                    text,
+                },
+            );
+            Ok(())
+        })?,
+    )?;
+    api.set(
+        "broadcast_message",
+        scope.create_function(move |_, text: String| {
+            let ctx = ctx.borrow();
+            ctx.broadcast_message(
+                RoutingStrategy::Down,
+                LuaMessage {
+                    sender: ctx.handle,
+                    text,
+                },
+            );
+            Ok(())
+        })?,
+    )?;
+
+    lua.globals().set("fyrox", api)
+}
+
+fn call_callback<'lua, A>(lua: &'lua Lua, name: &str, args: A) -> mlua::Result<()>
+where
+    A: mlua::ToLuaMulti<'lua>,
+{
+    match lua.globals().get::<_, Value>(name)? {
+        Value::Function(function) => function.call::<_, ()>(args),
+        _ => Ok(()),
+    }
+}
+
+impl LuaScript {
+    /// Creates a new script, that executes the given source.
+    pub fn new(source: ScriptSource) -> Self {
+        Self {
+            source: Some(source),
+            ..Default::default()
+        }
+    }
+
+    // Executes the given function with the engine API, (re)executes the source if it was changed. Does
+    // nothing if the source is not loaded yet.
+    fn run<F>(&mut self, ctx: &mut ScriptContext, func: F)
+    where
+        F: FnOnce(&Lua) -> mlua::Result<()>,
+    {
+        let (source, revision) = match self.source.as_ref() {
+            Some(source) => match *source.state() {
+                ResourceState::Ok(ref state) => {
+                    let is_actual = self
+                        .runtime
+                        .as_ref()
+                        .map_or(false, |r| r.revision == state.revision());
+                    let code = if is_actual {
+                        None
+                    } else {
+                        Some(state.source.clone())
+                    };
+                    (code, state.revision())
+                }
+                _ => return,
+            },
+            None => return,
+        };
+
+        let runtime = self.runtime.get_or_insert_with(|| LuaRuntime {
+            lua: Lua::new(),
+            revision,
+        });
+        runtime.revision = revision;
+
+        let handle = ctx.handle;
+        let ctx = RefCell::new(ctx);
+        let lua = &runtime.lua;
+        let result = lua.scope(|scope| {
+            register_api(lua, scope, &ctx)?;
+            if let Some(source) = source {
+                lua.load(&source).exec()?;
+            }
+            func(lua)
+        });
+
+        if let Err(error) = result {
+            Log::err(format!("Lua script of node {} failed: {}", handle, error));
+        }
+    }
+}
+
+impl ScriptTrait for LuaScript {
+    fn on_init(&mut self, ctx: &mut ScriptContext) {
+        self.run(ctx, |lua| call_callback(lua, "on_init", ()));
+    }
+
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.subscribe_to::<LuaMessage>();
+
+        self.run(ctx, |lua| call_callback(lua, "on_start", ()));
+    }
+
+    fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+        if let Some(runtime) = self.runtime.as_ref() {
+            if let Err(error) = call_callback(&runtime.lua, "on_deinit", ()) {
+                Log::err(format!(
+                    "Lua script of node {} failed: {}",
+                    ctx.node_handle, error
+                ));
+            }
+        }
+    }
+
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let messages = std::mem::take(&mut self.messages);
+        let dt = ctx.dt;
+        self.run(ctx, |lua| {
+            for message in messages {
+                call_callback(
+                    lua,
+                    "on_message",
+                    (message.text, encode_handle(message.sender)),
+                )?;
+            }
+            call_callback(lua, "on_update", dt)
+        });
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        resource_manager
+            .state()
+            .containers_mut()
+            .script_sources
+            .try_restore_optional_resource(&mut self.source);
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        _ctx: &mut ScriptMessageContext,
+    ) {
+        if let Some(message) = message.downcast_ref::<LuaMessage>() {
+            self.messages.push(message.clone());
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
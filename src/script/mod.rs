@@ -5,24 +5,37 @@
 use crate::{
     core::{
         pool::Handle,
+        rand::rngs::StdRng,
         reflect::{FieldInfo, Reflect, ReflectArray, ReflectList},
         uuid::Uuid,
         visitor::{Visit, VisitResult, Visitor},
     },
-    engine::{resource_manager::ResourceManager, ScriptMessageDispatcher},
+    engine::{
+        resource_manager::ResourceManager, services::EngineServices, ScriptMessageDispatcher,
+    },
     event::Event,
+    gui::platform::PlatformServices,
     plugin::Plugin,
-    scene::{node::Node, Scene},
+    scene::{graph::physics::ContactEvent, node::Node, Scene},
+    script::coroutine::{CoroutineContext, CoroutineScheduler},
     utils::{component::ComponentProvider, log::Log},
 };
 use std::{
     any::{Any, TypeId},
     fmt::{Debug, Formatter},
+    future::Future,
     ops::{Deref, DerefMut},
     sync::mpsc::Sender,
+    time::Duration,
 };
 
 pub mod constructor;
+pub mod coroutine;
+#[cfg(all(feature = "lua-scripting", not(target_arch = "wasm32")))]
+pub mod lua;
+pub mod occlusion_fade;
+pub mod profiler;
+pub mod visual;
 
 /// A script message's payload.
 pub trait ScriptMessagePayload: Any + Send {
@@ -174,6 +187,16 @@ pub struct ScriptContext<'a, 'b, 'c> {
     /// which the engine "ticks" and this delta time affects elapsed time.
     pub elapsed_time: f32,
 
+    /// Amount of fixed update ticks that passed from creation of the engine. Use this value instead of
+    /// [`std::time::Instant::now`] when you need a time stamp in your game logic, it is guaranteed to be
+    /// the same across multiple runs of the game with the same input.
+    pub tick_count: u64,
+
+    /// A seeded random number generator of the scene. Use it instead of `thread_rng` and other sources of
+    /// randomness, so the logic of your scripts will be reproducible (for replays, tests, networking, etc).
+    /// The seed can be set via [`crate::engine::Engine::set_script_rng_seed`].
+    pub rng: &'c mut StdRng,
+
     /// A reference to the plugin which the script instance belongs to. You can use it to access plugin data
     /// inside script methods. For example you can store some "global" data in the plugin - for example a
     /// controls configuration, some entity managers and so on.
@@ -203,6 +226,68 @@ pub struct ScriptContext<'a, 'b, 'c> {
     /// A message dispatcher. If you need to receive messages of a particular type, you must subscribe to a type
     /// explicitly. See [`ScriptTrait::on_message`] for more examples.
     pub message_dispatcher: &'c mut ScriptMessageDispatcher,
+
+    /// Coroutine scheduler of the scene. Use [`ScriptContext::start_coroutine`] to start a new coroutine
+    /// of the script. See [`CoroutineScheduler`] docs for more info.
+    pub coroutines: &'c mut CoroutineScheduler,
+
+    /// Platform services - system clipboard and native file dialogs. It could be used, for example, to
+    /// copy a level-sharing code to the clipboard:
+    ///
+    /// ```rust
+    /// # use fyrox::script::ScriptContext;
+    /// # fn foo(context: ScriptContext) {
+    /// context.platform.set_clipboard_text("LEVEL-CODE-1234");
+    /// # }
+    /// ```
+    pub platform: &'a PlatformServices,
+
+    /// Engine services - force feedback, achievements, telemetry, debug overlay, settings and
+    /// Steam. See [`EngineServices`] docs for more info.
+    pub services: &'a EngineServices,
+}
+
+impl<'a, 'b, 'c> ScriptContext<'a, 'b, 'c> {
+    /// Starts a new coroutine of the node of the script. See [`CoroutineScheduler`] docs for more info.
+    pub fn start_coroutine<F, Fut>(&mut self, func: F)
+    where
+        F: FnOnce(CoroutineContext) -> Fut,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.coroutines.start(self.handle, func)
+    }
+
+    /// Stops every coroutine of the node of the script.
+    pub fn stop_coroutines(&mut self) {
+        self.coroutines.stop_all(self.handle)
+    }
+
+    /// Subscribes the node of the script to receive messages of the given type `T`. It is a shortcut
+    /// for [`ScriptMessageDispatcher::subscribe_to`] with [`Self::handle`] as a receiver.
+    pub fn subscribe_to<T: 'static>(&mut self) {
+        self.message_dispatcher.subscribe_to::<T>(self.handle)
+    }
+
+    /// Sends a message to a script of the given node. The message is queued and will be delivered to
+    /// [`ScriptTrait::on_message`] of the receiver later in the same tick, if the receiver is subscribed
+    /// to messages of the type `T`.
+    pub fn send_message<T>(&self, target: Handle<Node>, payload: T)
+    where
+        T: 'static + Send,
+    {
+        self.message_sender.send_to_target(target, payload)
+    }
+
+    /// Broadcasts a message along the hierarchy of the node of the script, using the given routing
+    /// strategy. The node of the script will receive the message too, if it is subscribed to messages
+    /// of the type `T`.
+    pub fn broadcast_message<T>(&self, routing: RoutingStrategy, payload: T)
+    where
+        T: 'static + Send,
+    {
+        self.message_sender
+            .send_hierarchical(self.handle, routing, payload)
+    }
 }
 
 /// A set of data, that provides contextual information for script methods.
@@ -215,6 +300,13 @@ pub struct ScriptMessageContext<'a, 'b, 'c> {
     /// which the engine "ticks" and this delta time affects elapsed time.
     pub elapsed_time: f32,
 
+    /// Amount of fixed update ticks that passed from creation of the engine. See
+    /// [`ScriptContext::tick_count`] for more info.
+    pub tick_count: u64,
+
+    /// A seeded random number generator of the scene. See [`ScriptContext::rng`] for more info.
+    pub rng: &'c mut StdRng,
+
     /// A reference to the plugin which the script instance belongs to. You can use it to access plugin data
     /// inside script methods. For example you can store some "global" data in the plugin - for example a
     /// controls configuration, some entity managers and so on.
@@ -242,6 +334,26 @@ pub struct ScriptMessageContext<'a, 'b, 'c> {
     pub message_sender: &'c ScriptMessageSender,
 }
 
+impl<'a, 'b, 'c> ScriptMessageContext<'a, 'b, 'c> {
+    /// Sends a message to a script of the given node. See [`ScriptContext::send_message`] for more info.
+    pub fn send_message<T>(&self, target: Handle<Node>, payload: T)
+    where
+        T: 'static + Send,
+    {
+        self.message_sender.send_to_target(target, payload)
+    }
+
+    /// Broadcasts a message along the hierarchy of the node of the script. See
+    /// [`ScriptContext::broadcast_message`] for more info.
+    pub fn broadcast_message<T>(&self, routing: RoutingStrategy, payload: T)
+    where
+        T: 'static + Send,
+    {
+        self.message_sender
+            .send_hierarchical(self.handle, routing, payload)
+    }
+}
+
 /// A set of data that will be passed to a script instance just before its destruction.
 pub struct ScriptDeinitContext<'a, 'b, 'c> {
     /// Amount of time (in seconds) that passed from creation of the engine. Keep in mind, that
@@ -249,6 +361,13 @@ pub struct ScriptDeinitContext<'a, 'b, 'c> {
     /// which the engine "ticks" and this delta time affects elapsed time.
     pub elapsed_time: f32,
 
+    /// Amount of fixed update ticks that passed from creation of the engine. See
+    /// [`ScriptContext::tick_count`] for more info.
+    pub tick_count: u64,
+
+    /// A seeded random number generator of the scene. See [`ScriptContext::rng`] for more info.
+    pub rng: &'c mut StdRng,
+
     /// A reference to the plugin which the script instance belongs to. You can use it to access plugin data
     /// inside script methods. For example you can store some "global" data in the plugin - for example a
     /// controls configuration, some entity managers and so on.
@@ -272,6 +391,15 @@ pub struct ScriptDeinitContext<'a, 'b, 'c> {
 
 /// Script is a set predefined methods that are called on various stages by the engine. It is used to add
 /// custom behaviour to game entities.
+///
+/// # Determinism
+///
+/// Scripts should **not** use [`std::time::Instant::now`], `thread_rng` or any other source of time and
+/// randomness that is external to the engine. Use [`ScriptContext::tick_count`] (or `elapsed_time`) and
+/// [`ScriptContext::rng`] instead, these values are controlled by the engine and the game logic that uses
+/// them will be reproducible - this is essential for replays, automated tests and lockstep networking.
+/// Projects generated by `fyrox-template` contain a `clippy.toml` file that forbids such calls in game code,
+/// you can copy it to your project if you created it manually.
 pub trait ScriptTrait: BaseScript + ComponentProvider {
     /// The method is called when the script wasn't initialized yet. It is guaranteed to be called once,
     /// and before any other methods of the script.
@@ -308,6 +436,39 @@ pub trait ScriptTrait: BaseScript + ComponentProvider {
     /// 60 times per second (this may change in future releases).
     fn on_update(&mut self, #[allow(unused_variables)] ctx: &mut ScriptContext) {}
 
+    /// Called when a collider of the node (or any collider attached to the node, if the node is a rigid
+    /// body) has started touching another collider. The method is called once per frame for every event of
+    /// the last physics step, before [`ScriptTrait::on_update`]. Events are reported by the physics world
+    /// only if [`ContactEventFilter::enabled`](crate::scene::graph::physics::ContactEventFilter::enabled)
+    /// is set, see [`crate::scene::graph::physics::ContactEventFilter`] docs for more info.
+    fn on_collision_started(
+        &mut self,
+        #[allow(unused_variables)] event: &ContactEvent,
+        #[allow(unused_variables)] ctx: &mut ScriptContext,
+    ) {
+    }
+
+    /// Called when a collider of the node (or any collider attached to the node, if the node is a rigid
+    /// body) has stopped touching another collider. See [`ScriptTrait::on_collision_started`] for more
+    /// info.
+    fn on_collision_ended(
+        &mut self,
+        #[allow(unused_variables)] event: &ContactEvent,
+        #[allow(unused_variables)] ctx: &mut ScriptContext,
+    ) {
+    }
+
+    /// Called every physics step while a collider of the node (or any collider attached to the node, if
+    /// the node is a rigid body) is touching another collider. These events are reported only if
+    /// [`ContactEventFilter::report_persist`](crate::scene::graph::physics::ContactEventFilter::report_persist)
+    /// is set. See [`ScriptTrait::on_collision_started`] for more info.
+    fn on_contact(
+        &mut self,
+        #[allow(unused_variables)] event: &ContactEvent,
+        #[allow(unused_variables)] ctx: &mut ScriptContext,
+    ) {
+    }
+
     /// Allows you to restore resources after deserialization.
     ///
     /// # Motivation
@@ -346,7 +507,7 @@ pub trait ScriptTrait: BaseScript + ComponentProvider {
     /// impl ScriptTrait for MyScript {
     ///     fn on_start(&mut self, ctx: &mut ScriptContext) {
     ///         // Subscription is mandatory to receive any message of the type!
-    ///         ctx.message_dispatcher.subscribe_to::<Message>(ctx.handle)
+    ///         ctx.subscribe_to::<Message>()
     ///     }
     ///
     ///     fn on_message(
@@ -424,6 +585,10 @@ pub struct Script {
     instance: Box<dyn ScriptTrait>,
     pub(crate) initialized: bool,
     pub(crate) started: bool,
+    enabled: bool,
+    // Time spent in methods of the script since the last collection of script costs, see
+    // `ScriptCostProfiler` for more info.
+    pub(crate) frame_time: Duration,
 }
 
 impl Reflect for Script {
@@ -515,6 +680,7 @@ impl Visit for Script {
         if self.instance.visit("Data", &mut region_guard).is_ok() {
             // Visit flags.
             self.initialized.visit("Initialized", &mut region_guard)?;
+            let _ = self.enabled.visit("Enabled", &mut region_guard);
         } else {
             Log::warn(format!(
                 "Unable to load script instance of id {} in new format! Trying to load in old format...",
@@ -542,6 +708,8 @@ impl Clone for Script {
             instance: self.instance.clone_box(),
             initialized: false,
             started: false,
+            enabled: self.enabled,
+            frame_time: Default::default(),
         }
     }
 }
@@ -554,9 +722,26 @@ impl Script {
             instance: Box::new(script_object),
             initialized: false,
             started: false,
+            enabled: true,
+            frame_time: Default::default(),
         }
     }
 
+    /// Enables or disables the script. Disabled scripts are not updated - [`ScriptTrait::on_update`] and
+    /// [`ScriptTrait::on_os_event`] are not called, but the script keeps its state and it is still
+    /// serialized. Every other method of the script is called as usual. Scripts are enabled by default.
+    #[inline]
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns `true` if the script is enabled, `false` - otherwise. See [`Self::set_enabled`] for more
+    /// info.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     /// Performs downcasting to a particular type.
     #[inline]
     pub fn cast<T: ScriptTrait>(&self) -> Option<&T> {
@@ -0,0 +1,239 @@
+//! Automatic fading of geometry, that blocks the view from a camera to its target. See
+//! [`CameraOcclusionFader`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Point3,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+    },
+    impl_component_provider,
+    scene::{
+        base::Mobility,
+        graph::{
+            raycast::{GeometryIntersection, GeometryRayCastOptions},
+            Graph,
+        },
+        node::{Node, TypeUuidProvider},
+    },
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+/// A script for third-person camera rigs, that casts a ray from its node (usually a camera) to a
+/// target (usually a player) every frame and fades out every mesh, that blocks the view. Opacity of
+/// the meshes is restored when they stop blocking the view. Fading is done using per-object opacity,
+/// see [`crate::scene::base::Base::fade_to`].
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{base::BaseBuilder, camera::CameraBuilder, graph::Graph, node::Node},
+///     script::{occlusion_fade::CameraOcclusionFader, Script},
+/// };
+///
+/// fn create_camera(graph: &mut Graph, player: Handle<Node>) -> Handle<Node> {
+///     CameraBuilder::new(BaseBuilder::new().with_script(Script::new(CameraOcclusionFader {
+///         target: player,
+///         ..Default::default()
+///     })))
+///     .build(graph)
+/// }
+/// ```
+///
+/// Meshes of the target and the node of the script (including their descendants) are never faded.
+/// Opacity of restored meshes is always set to `1.0`.
+#[derive(Visit, Reflect, Debug, Clone)]
+pub struct CameraOcclusionFader {
+    /// A node, that must stay visible from the node of the script.
+    pub target: Handle<Node>,
+
+    /// Opacity of meshes, that block the view.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    pub occluded_opacity: f32,
+
+    /// Time (in seconds) of fading in and out.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub fade_duration: f32,
+
+    /// If `true`, only meshes with [`Mobility::Static`] will be faded.
+    pub static_only: bool,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    faded: Vec<Handle<Node>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    query_buffer: Vec<GeometryIntersection>,
+}
+
+impl Default for CameraOcclusionFader {
+    fn default() -> Self {
+        Self {
+            target: Default::default(),
+            occluded_opacity: 0.25,
+            fade_duration: 0.25,
+            static_only: true,
+            faded: Default::default(),
+            query_buffer: Default::default(),
+        }
+    }
+}
+
+impl TypeUuidProvider for CameraOcclusionFader {
+    fn type_uuid() -> Uuid {
+        uuid!("8d4b6a1e-2f7c-4e3b-9a5d-0c1e7f3b2d64")
+    }
+}
+
+impl_component_provider!(CameraOcclusionFader);
+
+fn is_in_hierarchy_of(graph: &Graph, mut node: Handle<Node>, root: Handle<Node>) -> bool {
+    while let Some(node_ref) = graph.try_get(node) {
+        if node == root {
+            return true;
+        }
+        node = node_ref.parent();
+    }
+    false
+}
+
+impl CameraOcclusionFader {
+    /// Returns a list of meshes, that are faded out at the moment.
+    pub fn faded(&self) -> &[Handle<Node>] {
+        &self.faded
+    }
+
+    fn find_occluders(&mut self, graph: &Graph, owner: Handle<Node>) -> Vec<Handle<Node>> {
+        let (origin, end) = match (graph.try_get(owner), graph.try_get(self.target)) {
+            (Some(owner), Some(target)) => (owner.global_position(), target.global_position()),
+            _ => return Vec::new(),
+        };
+
+        let direction = end - origin;
+
+        graph.cast_ray_geometry(
+            GeometryRayCastOptions {
+                ray_origin: Point3::from(origin),
+                ray_direction: direction,
+                max_len: direction.norm(),
+                ignore_back_faces: false,
+                ignore_invisible: true,
+                sort_results: false,
+            },
+            &mut self.query_buffer,
+        );
+
+        self.query_buffer
+            .iter()
+            .map(|intersection| intersection.node)
+            .filter(|&node| {
+                (!self.static_only || graph[node].mobility() == Mobility::Static)
+                    && !is_in_hierarchy_of(graph, node, self.target)
+                    && !is_in_hierarchy_of(graph, node, owner)
+            })
+            .collect()
+    }
+}
+
+impl ScriptTrait for CameraOcclusionFader {
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let occluders = self.find_occluders(&ctx.scene.graph, ctx.handle);
+
+        for &node in self.faded.iter() {
+            if !occluders.contains(&node) {
+                if let Some(node) = ctx.scene.graph.try_get_mut(node) {
+                    node.fade_in(self.fade_duration);
+                }
+            }
+        }
+
+        for &node in occluders.iter() {
+            if !self.faded.contains(&node) {
+                ctx.scene.graph[node].fade_to(self.occluded_opacity, self.fade_duration);
+            }
+        }
+
+        self.faded = occluders;
+    }
+
+    fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+        for node in self.faded.drain(..) {
+            if let Some(node) = ctx.scene.graph.try_get_mut(node) {
+                node.set_visibility_opacity(1.0);
+            }
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::{BaseBuilder, Mobility},
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            node::Node,
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+        script::occlusion_fade::CameraOcclusionFader,
+    };
+
+    fn make_cube(graph: &mut Graph, position: Vector3<f32>, mobility: Mobility) -> Handle<Node> {
+        MeshBuilder::new(
+            BaseBuilder::new()
+                .with_mobility(mobility)
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+            SurfaceData::make_cube(Matrix4::identity()),
+        ))
+        .build()])
+        .build(graph)
+    }
+
+    #[test]
+    fn test_find_occluders() {
+        let mut graph = Graph::new();
+        let camera = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let target = make_cube(&mut graph, Vector3::new(0.0, 0.0, 10.0), Mobility::Dynamic);
+        let wall = make_cube(&mut graph, Vector3::new(0.0, 0.0, 5.0), Mobility::Static);
+        let dynamic_wall = make_cube(&mut graph, Vector3::new(0.0, 0.0, 3.0), Mobility::Dynamic);
+        let behind_target = make_cube(&mut graph, Vector3::new(0.0, 0.0, 15.0), Mobility::Static);
+        let aside = make_cube(&mut graph, Vector3::new(5.0, 0.0, 5.0), Mobility::Static);
+        graph.update_hierarchical_data();
+
+        let mut fader = CameraOcclusionFader {
+            target,
+            ..Default::default()
+        };
+
+        let occluders = fader.find_occluders(&graph, camera);
+        assert_eq!(occluders, vec![wall]);
+        assert!(!occluders.contains(&behind_target));
+        assert!(!occluders.contains(&aside));
+
+        fader.static_only = false;
+        let mut occluders = fader.find_occluders(&graph, camera);
+        occluders.sort_by_key(|h| h.index());
+        assert_eq!(occluders, vec![wall, dynamic_wall]);
+    }
+}
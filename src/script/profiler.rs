@@ -0,0 +1,227 @@
+//! Per-node script costs over a short period of time. See [`ScriptCostProfiler`] docs for more info.
+
+use crate::{
+    core::pool::Handle,
+    scene::{graph::Graph, node::Node},
+};
+use fxhash::FxHashMap;
+use std::{collections::VecDeque, fmt::Write, time::Duration};
+
+/// A prefix of every line produced by [`ScriptCostProfiler::report`].
+pub const SCRIPT_COST_REPORT_PREFIX: &str = "[ScriptCosts]";
+
+struct ProfiledFrame {
+    time: f32,
+    costs: Vec<(Handle<Node>, Duration)>,
+}
+
+/// Script cost profiler tracks how much time scripts of every node took over a sliding window of time
+/// (one second by default). The engine has a profiler for every scene with scripts, it could be fetched
+/// using [`crate::engine::Engine::script_cost_profiler`]. The time includes every method of a script,
+/// not just [`crate::script::ScriptTrait::on_update`].
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{core::pool::Handle, engine::Engine, scene::Scene, utils::log::Log};
+///
+/// fn print_slowest_script(engine: &Engine, scene: Handle<Scene>) {
+///     if let Some(profiler) = engine.script_cost_profiler(scene) {
+///         if let Some((node, cost)) = profiler.costs().iter().max_by_key(|(_, cost)| **cost) {
+///             Log::info(format!(
+///                 "{}: {:.3} ms",
+///                 engine.scenes[scene].graph[*node].name(),
+///                 cost.as_secs_f64() * 1000.0
+///             ));
+///         }
+///     }
+/// }
+/// ```
+pub struct ScriptCostProfiler {
+    window: f32,
+    frames: VecDeque<ProfiledFrame>,
+    costs: FxHashMap<Handle<Node>, Duration>,
+}
+
+impl Default for ScriptCostProfiler {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl ScriptCostProfiler {
+    /// Creates new profiler, that accumulates script costs over the given period of time (in seconds).
+    pub fn new(window: f32) -> Self {
+        Self {
+            window: window.max(0.0),
+            frames: Default::default(),
+            costs: Default::default(),
+        }
+    }
+
+    /// Returns length of the sliding window (in seconds).
+    pub fn window(&self) -> f32 {
+        self.window
+    }
+
+    /// Takes the time spent by scripts of every node of the graph since the last call and adds it to
+    /// the sliding window. `time` is the current time in seconds, frames older than `time - window`
+    /// are discarded.
+    pub fn collect(&mut self, graph: &mut Graph, time: f32) {
+        let mut costs = Vec::new();
+        for (handle, node) in graph.pair_iter_mut() {
+            if let Some(script) = node.script.as_mut() {
+                let cost = std::mem::take(&mut script.frame_time);
+                if !cost.is_zero() {
+                    costs.push((handle, cost));
+                }
+            }
+        }
+
+        for &(handle, cost) in costs.iter() {
+            *self.costs.entry(handle).or_default() += cost;
+        }
+        self.frames.push_back(ProfiledFrame { time, costs });
+
+        while let Some(frame) = self.frames.front() {
+            if time - frame.time < self.window {
+                break;
+            }
+
+            for (handle, cost) in self.frames.pop_front().unwrap().costs {
+                if let Some(total) = self.costs.get_mut(&handle) {
+                    *total = total.saturating_sub(cost);
+                    if total.is_zero() {
+                        self.costs.remove(&handle);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the time spent by scripts of the given node over the sliding window.
+    pub fn cost(&self, node: Handle<Node>) -> Duration {
+        self.costs.get(&node).cloned().unwrap_or_default()
+    }
+
+    /// Returns costs of every node, that has spent some time in scripts over the sliding window.
+    pub fn costs(&self) -> &FxHashMap<Handle<Node>, Duration> {
+        &self.costs
+    }
+
+    /// Removes every collected sample.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.costs.clear();
+    }
+
+    /// Writes current costs in a single line of text, prefixed with [`SCRIPT_COST_REPORT_PREFIX`].
+    /// The line could be parsed back using [`parse_script_cost_report`]. It is used by the editor to
+    /// fetch script costs from a running game.
+    pub fn report(&self) -> String {
+        let mut report = SCRIPT_COST_REPORT_PREFIX.to_owned();
+        for (handle, cost) in self.costs.iter() {
+            write!(
+                report,
+                " {}:{}={:.4}",
+                handle.index(),
+                handle.generation(),
+                cost.as_secs_f64() * 1000.0
+            )
+            .unwrap();
+        }
+        report
+    }
+}
+
+/// Parses a line produced by [`ScriptCostProfiler::report`]. Returns `None` if the line is not a
+/// script cost report. Malformed entries are skipped.
+pub fn parse_script_cost_report(line: &str) -> Option<Vec<(Handle<Node>, Duration)>> {
+    let entries = line.trim().strip_prefix(SCRIPT_COST_REPORT_PREFIX)?;
+
+    Some(
+        entries
+            .split_whitespace()
+            .filter_map(|entry| {
+                let (handle, cost) = entry.split_once('=')?;
+                let (index, generation) = handle.split_once(':')?;
+                let cost = cost.parse::<f64>().ok()?;
+                if !cost.is_finite() || cost < 0.0 {
+                    return None;
+                }
+                Some((
+                    Handle::new(index.parse().ok()?, generation.parse().ok()?),
+                    Duration::from_secs_f64(cost / 1000.0),
+                ))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{pool::Handle, reflect::prelude::*, uuid::Uuid, visitor::prelude::*},
+        impl_component_provider,
+        scene::{base::BaseBuilder, graph::Graph, node::Node, pivot::PivotBuilder},
+        script::{
+            profiler::{parse_script_cost_report, ScriptCostProfiler},
+            Script, ScriptTrait,
+        },
+    };
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, Default, Reflect, Visit)]
+    struct EmptyScript;
+
+    impl_component_provider!(EmptyScript);
+
+    impl ScriptTrait for EmptyScript {
+        fn id(&self) -> Uuid {
+            Uuid::new_v4()
+        }
+    }
+
+    fn set_frame_time(graph: &mut Graph, node: Handle<Node>, ms: u64) {
+        graph[node].script.as_mut().unwrap().frame_time = Duration::from_millis(ms);
+    }
+
+    #[test]
+    fn test_script_cost_profiler() {
+        let mut graph = Graph::new();
+        let a = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(EmptyScript)))
+            .build(&mut graph);
+        let b = PivotBuilder::new(BaseBuilder::new().with_script(Script::new(EmptyScript)))
+            .build(&mut graph);
+        let mut profiler = ScriptCostProfiler::new(1.0);
+
+        set_frame_time(&mut graph, a, 2);
+        profiler.collect(&mut graph, 0.0);
+        set_frame_time(&mut graph, a, 3);
+        set_frame_time(&mut graph, b, 1);
+        profiler.collect(&mut graph, 0.5);
+        assert_eq!(profiler.cost(a), Duration::from_millis(5));
+        assert_eq!(profiler.cost(b), Duration::from_millis(1));
+
+        // Frame times must be taken by the profiler.
+        profiler.collect(&mut graph, 0.9);
+        assert_eq!(profiler.cost(a), Duration::from_millis(5));
+
+        // The first frame leaves the window.
+        profiler.collect(&mut graph, 1.2);
+        assert_eq!(profiler.cost(a), Duration::from_millis(3));
+
+        // Every frame leaves the window.
+        profiler.collect(&mut graph, 3.0);
+        assert!(profiler.costs().is_empty());
+
+        set_frame_time(&mut graph, b, 4);
+        profiler.collect(&mut graph, 3.1);
+        let report = parse_script_cost_report(&profiler.report()).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].0, b);
+        assert!((report[0].1.as_secs_f64() - 0.004).abs() < 1.0e-6);
+
+        assert!(parse_script_cost_report("Some log message").is_none());
+    }
+}
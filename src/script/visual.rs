@@ -0,0 +1,502 @@
+//! Visual scripting runtime. [`VisualScript`] is an ordinary script, that executes a graph of a
+//! [`VisualScriptResource`], see [`crate::resource::visual_script`] docs for more info about graphs.
+//!
+//! ```rust
+//! use fyrox::{
+//!     engine::resource_manager::ResourceManager,
+//!     scene::{base::BaseBuilder, pivot::PivotBuilder, Scene},
+//!     script::{visual::VisualScript, Script},
+//! };
+//!
+//! fn create_node(scene: &mut Scene, resource_manager: &ResourceManager) {
+//!     PivotBuilder::new(BaseBuilder::new().with_script(Script::new(VisualScript::new(
+//!         resource_manager.request_visual_script("data/scripts/door.vs"),
+//!     ))))
+//!     .build(&mut scene.graph);
+//! }
+//! ```
+//!
+//! Errors in a graph (wrong types of values, missing inputs, etc.) stop execution of the current event
+//! and are written to the log.
+
+use crate::{
+    asset::ResourceState,
+    core::{
+        algebra::Vector3,
+        pool::Handle,
+        reflect::{prelude::*, ResolvePath},
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+    },
+    engine::resource_manager::ResourceManager,
+    impl_component_provider,
+    resource::visual_script::{
+        CompareOp, VisualInput, VisualNode, VisualNodeKind, VisualScriptGraph,
+        VisualScriptResource, VisualValue,
+    },
+    scene::{
+        node::{Node, TypeUuidProvider},
+        Scene,
+    },
+    script::{
+        ScriptContext, ScriptMessageContext, ScriptMessagePayload, ScriptMessageSender, ScriptTrait,
+    },
+    utils::log::Log,
+};
+use fxhash::FxHashMap;
+
+/// Maximum amount of nodes, that could be executed by a single event. It prevents the engine from
+/// hanging in case of infinite loops in a graph.
+const MAX_EXECUTION_STEPS: usize = 4096;
+
+/// Maximum depth of data dependencies between nodes. It prevents stack overflow in case of cyclic
+/// links in a graph.
+const MAX_EVALUATION_DEPTH: usize = 256;
+
+/// A message, that could be sent from one visual script to another. See
+/// [`VisualNodeKind::SendMessage`] and [`VisualNodeKind::OnMessage`] for more info.
+#[derive(Clone, Debug)]
+pub struct VisualScriptMessage {
+    /// Name of the message.
+    pub name: String,
+    /// A node, that sent the message.
+    pub sender: Handle<Node>,
+    /// Payload of the message.
+    pub payload: VisualValue,
+}
+
+/// A script, that executes a visual script graph. See module docs for more info.
+#[derive(Visit, Reflect, Default, Debug, Clone)]
+pub struct VisualScript {
+    /// A graph of the script.
+    pub graph: Option<VisualScriptResource>,
+
+    #[reflect(hidden)]
+    variables: FxHashMap<String, VisualValue>,
+}
+
+impl TypeUuidProvider for VisualScript {
+    fn type_uuid() -> Uuid {
+        uuid!("5e0f3c7a-9b1d-4d6e-8a2f-6c4b1e9d3f70")
+    }
+}
+
+impl_component_provider!(VisualScript);
+
+struct Interpreter<'a, 'b> {
+    graph: &'a VisualScriptGraph,
+    variables: &'a mut FxHashMap<String, VisualValue>,
+    scene: &'b mut Scene,
+    handle: Handle<Node>,
+    message_sender: &'b ScriptMessageSender,
+    // Index of the event node, that started the execution, and its outputs.
+    event: usize,
+    event_outputs: Vec<VisualValue>,
+}
+
+fn type_error(node: usize, values: &[&VisualValue]) -> String {
+    format!(
+        "Node {} does not support values {}",
+        node,
+        values
+            .iter()
+            .map(|v| format!("{v:?}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn as_bool(node: usize, value: VisualValue) -> Result<bool, String> {
+    match value {
+        VisualValue::Bool(v) => Ok(v),
+        _ => Err(type_error(node, &[&value])),
+    }
+}
+
+fn as_node(node: usize, value: VisualValue) -> Result<Handle<Node>, String> {
+    match value {
+        VisualValue::Node(v) => Ok(v),
+        _ => Err(type_error(node, &[&value])),
+    }
+}
+
+fn arithmetic(kind: &VisualNodeKind, a: &VisualValue, b: &VisualValue) -> Option<VisualValue> {
+    use VisualValue::*;
+
+    Some(match (kind, a, b) {
+        (VisualNodeKind::Add, Number(a), Number(b)) => Number(a + b),
+        (VisualNodeKind::Add, Vector(a), Vector(b)) => Vector(a + b),
+        (VisualNodeKind::Add, String(a), b) => String(format!("{a}{b}")),
+        (VisualNodeKind::Subtract, Number(a), Number(b)) => Number(a - b),
+        (VisualNodeKind::Subtract, Vector(a), Vector(b)) => Vector(a - b),
+        (VisualNodeKind::Multiply, Number(a), Number(b)) => Number(a * b),
+        (VisualNodeKind::Multiply, Vector(a), Number(b)) => Vector(a.scale(*b)),
+        (VisualNodeKind::Multiply, Number(a), Vector(b)) => Vector(b.scale(*a)),
+        (VisualNodeKind::Divide, Number(a), Number(b)) => Number(a / b),
+        (VisualNodeKind::Divide, Vector(a), Number(b)) => Vector(a.unscale(*b)),
+        _ => return None,
+    })
+}
+
+fn compare(op: CompareOp, a: &VisualValue, b: &VisualValue) -> Option<bool> {
+    match (op, a, b) {
+        (CompareOp::Equal, a, b) => Some(a == b),
+        (CompareOp::NotEqual, a, b) => Some(a != b),
+        (CompareOp::Less, VisualValue::Number(a), VisualValue::Number(b)) => Some(a < b),
+        (CompareOp::LessOrEqual, VisualValue::Number(a), VisualValue::Number(b)) => Some(a <= b),
+        (CompareOp::Greater, VisualValue::Number(a), VisualValue::Number(b)) => Some(a > b),
+        (CompareOp::GreaterOrEqual, VisualValue::Number(a), VisualValue::Number(b)) => Some(a >= b),
+        _ => None,
+    }
+}
+
+fn reflect_to_value(value: &dyn Reflect) -> Option<VisualValue> {
+    if let Some(v) = value.downcast_ref::<bool>() {
+        Some(VisualValue::Bool(*v))
+    } else if let Some(v) = value.downcast_ref::<f32>() {
+        Some(VisualValue::Number(*v))
+    } else if let Some(v) = value.downcast_ref::<Vector3<f32>>() {
+        Some(VisualValue::Vector(*v))
+    } else if let Some(v) = value.downcast_ref::<String>() {
+        Some(VisualValue::String(v.clone()))
+    } else {
+        value
+            .downcast_ref::<Handle<Node>>()
+            .map(|v| VisualValue::Node(*v))
+    }
+}
+
+fn value_to_reflect(value: VisualValue) -> Box<dyn Reflect> {
+    match value {
+        VisualValue::Bool(v) => Box::new(v),
+        VisualValue::Number(v) => Box::new(v),
+        VisualValue::Vector(v) => Box::new(v),
+        VisualValue::String(v) => Box::new(v),
+        VisualValue::Node(v) => Box::new(v),
+    }
+}
+
+impl<'a, 'b> Interpreter<'a, 'b> {
+    fn node(&self, index: usize) -> Result<&'a VisualNode, String> {
+        self.graph
+            .nodes
+            .get(index)
+            .ok_or_else(|| format!("There is no node {index}"))
+    }
+
+    fn input(&mut self, node: usize, input: usize, depth: usize) -> Result<VisualValue, String> {
+        match self.node(node)?.inputs.get(input) {
+            Some(VisualInput::Value(value)) => Ok(value.clone()),
+            Some(VisualInput::Link {
+                node: source,
+                output,
+            }) => self.output(*source as usize, *output as usize, depth + 1),
+            None => Err(format!("Input {input} of node {node} is not set")),
+        }
+    }
+
+    fn input_or_default(
+        &mut self,
+        node: usize,
+        input: usize,
+        depth: usize,
+    ) -> Result<VisualValue, String> {
+        if self.node(node)?.inputs.len() > input {
+            self.input(node, input, depth)
+        } else {
+            Ok(Default::default())
+        }
+    }
+
+    fn output(&mut self, index: usize, output: usize, depth: usize) -> Result<VisualValue, String> {
+        if depth > MAX_EVALUATION_DEPTH {
+            return Err(format!(
+                "Evaluation of node {index} is too deep, most likely there is a cyclic link!"
+            ));
+        }
+
+        let node = self.node(index)?;
+
+        if node.kind.is_event() {
+            return if index == self.event {
+                self.event_outputs
+                    .get(output)
+                    .cloned()
+                    .ok_or_else(|| format!("There is no output {output} of node {index}"))
+            } else {
+                Err(format!(
+                    "Outputs of event node {index} are available only in its execution chain"
+                ))
+            };
+        }
+
+        match node.kind {
+            VisualNodeKind::Add
+            | VisualNodeKind::Subtract
+            | VisualNodeKind::Multiply
+            | VisualNodeKind::Divide => {
+                let a = self.input(index, 0, depth)?;
+                let b = self.input(index, 1, depth)?;
+                arithmetic(&node.kind, &a, &b).ok_or_else(|| type_error(index, &[&a, &b]))
+            }
+            VisualNodeKind::Compare { op } => {
+                let a = self.input(index, 0, depth)?;
+                let b = self.input(index, 1, depth)?;
+                compare(op, &a, &b)
+                    .map(VisualValue::Bool)
+                    .ok_or_else(|| type_error(index, &[&a, &b]))
+            }
+            VisualNodeKind::And => {
+                let a = as_bool(index, self.input(index, 0, depth)?)?;
+                let b = as_bool(index, self.input(index, 1, depth)?)?;
+                Ok(VisualValue::Bool(a && b))
+            }
+            VisualNodeKind::Or => {
+                let a = as_bool(index, self.input(index, 0, depth)?)?;
+                let b = as_bool(index, self.input(index, 1, depth)?)?;
+                Ok(VisualValue::Bool(a || b))
+            }
+            VisualNodeKind::Not => {
+                let a = as_bool(index, self.input(index, 0, depth)?)?;
+                Ok(VisualValue::Bool(!a))
+            }
+            VisualNodeKind::SelfNode => Ok(VisualValue::Node(self.handle)),
+            VisualNodeKind::FindNode { ref name } => Ok(VisualValue::Node(
+                self.scene
+                    .graph
+                    .find_by_name_from_root(name)
+                    .map(|(handle, _)| handle)
+                    .unwrap_or_default(),
+            )),
+            VisualNodeKind::GetPosition => {
+                let target = as_node(index, self.input(index, 0, depth)?)?;
+                let target = self
+                    .scene
+                    .graph
+                    .try_get(target)
+                    .ok_or_else(|| format!("Node {index}: node {target} does not exist"))?;
+                Ok(VisualValue::Vector(**target.local_transform().position()))
+            }
+            VisualNodeKind::GetProperty { ref path } => {
+                let target = as_node(index, self.input(index, 0, depth)?)?;
+                let target = self
+                    .scene
+                    .graph
+                    .try_get(target)
+                    .ok_or_else(|| format!("Node {index}: node {target} does not exist"))?;
+                let property = target
+                    .as_reflect()
+                    .resolve_path(path)
+                    .map_err(|e| format!("Node {index}: unable to find {path} property. {e:?}"))?;
+                reflect_to_value(property).ok_or_else(|| {
+                    format!("Node {index}: type of {path} property is not supported")
+                })
+            }
+            VisualNodeKind::GetVariable { ref name } => {
+                Ok(self.variables.get(name).cloned().unwrap_or_default())
+            }
+            _ => Err(format!(
+                "Node {index} must be executed, its output cannot be linked"
+            )),
+        }
+    }
+
+    // Executes a node and returns the index of the next node to execute.
+    fn execute(&mut self, index: usize) -> Result<Option<u32>, String> {
+        let node = self.node(index)?;
+
+        let mut next = 0;
+
+        match node.kind {
+            VisualNodeKind::Branch => {
+                if !as_bool(index, self.input(index, 0, 0)?)? {
+                    next = 1;
+                }
+            }
+            VisualNodeKind::SetPosition => {
+                let target = as_node(index, self.input(index, 0, 0)?)?;
+                let position = match self.input(index, 1, 0)? {
+                    VisualValue::Vector(v) => v,
+                    value => return Err(type_error(index, &[&value])),
+                };
+                self.scene
+                    .graph
+                    .try_get_mut(target)
+                    .ok_or_else(|| format!("Node {index}: node {target} does not exist"))?
+                    .local_transform_mut()
+                    .set_position(position);
+            }
+            VisualNodeKind::SetProperty { ref path } => {
+                let target = as_node(index, self.input(index, 0, 0)?)?;
+                let value = self.input(index, 1, 0)?;
+                let target = self
+                    .scene
+                    .graph
+                    .try_get_mut(target)
+                    .ok_or_else(|| format!("Node {index}: node {target} does not exist"))?;
+                target
+                    .as_reflect_mut()
+                    .resolve_path_mut(path)
+                    .map_err(|e| format!("Node {index}: unable to find {path} property. {e:?}"))?
+                    .set(value_to_reflect(value))
+                    .map_err(|_| format!("Node {index}: type mismatch of {path} property"))?;
+            }
+            VisualNodeKind::SetVariable { ref name } => {
+                let value = self.input(index, 0, 0)?;
+                self.variables.insert(name.clone(), value);
+            }
+            VisualNodeKind::SendMessage { ref name } => {
+                let target = as_node(index, self.input(index, 0, 0)?)?;
+                let payload = self.input_or_default(index, 1, 0)?;
+                self.message_sender.send_to_target(
+                    target,
+                    VisualScriptMessage {
+                        name: name.clone(),
+                        sender: self.handle,
+                        payload,
+                    },
+                );
+            }
+            VisualNodeKind::Log => {
+                let value = self.input(index, 0, 0)?;
+                Log::info(format!("Visual script of node {}: {}", self.handle, value));
+            }
+            ref kind if kind.is_event() => (),
+            _ => {
+                return Err(format!(
+                    "Node {index} cannot be executed, it must be linked to an input"
+                ))
+            }
+        }
+
+        Ok(node.outputs.get(next).cloned().flatten())
+    }
+
+    fn run(&mut self, event: usize, event_outputs: Vec<VisualValue>) -> Result<(), String> {
+        self.event = event;
+        self.event_outputs = event_outputs;
+
+        let mut current = Some(event as u32);
+        for _ in 0..MAX_EXECUTION_STEPS {
+            match current {
+                Some(index) => current = self.execute(index as usize)?,
+                None => return Ok(()),
+            }
+        }
+
+        Err("Too many nodes were executed, most likely there is an infinite loop!".to_string())
+    }
+}
+
+impl VisualScript {
+    /// Creates a new visual script, that executes the given graph.
+    pub fn new(graph: VisualScriptResource) -> Self {
+        Self {
+            graph: Some(graph),
+            variables: Default::default(),
+        }
+    }
+
+    /// Returns a value of the given variable of the script, if any.
+    pub fn variable(&self, name: &str) -> Option<&VisualValue> {
+        self.variables.get(name)
+    }
+
+    /// Sets a value of the given variable of the script.
+    pub fn set_variable<S: Into<String>>(&mut self, name: S, value: VisualValue) {
+        self.variables.insert(name.into(), value);
+    }
+
+    // Runs every event node of the graph, that satisfies the given predicate.
+    fn run_events<F>(
+        &mut self,
+        scene: &mut Scene,
+        handle: Handle<Node>,
+        message_sender: &ScriptMessageSender,
+        mut filter: F,
+    ) where
+        F: FnMut(&VisualNodeKind) -> Option<Vec<VisualValue>>,
+    {
+        let resource = match self.graph.as_ref() {
+            Some(resource) => resource.clone(),
+            None => return,
+        };
+
+        let state = resource.state();
+        let graph = match *state {
+            ResourceState::Ok(ref state) => &state.graph,
+            _ => return,
+        };
+
+        let mut interpreter = Interpreter {
+            graph,
+            variables: &mut self.variables,
+            scene,
+            handle,
+            message_sender,
+            event: 0,
+            event_outputs: Default::default(),
+        };
+
+        for (index, node) in graph.nodes.iter().enumerate() {
+            if let Some(outputs) = filter(&node.kind) {
+                if let Err(error) = interpreter.run(index, outputs) {
+                    Log::err(format!(
+                        "Visual script of node {} failed: {}",
+                        handle, error
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl ScriptTrait for VisualScript {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.subscribe_to::<VisualScriptMessage>();
+
+        self.run_events(ctx.scene, ctx.handle, ctx.message_sender, |kind| {
+            matches!(kind, VisualNodeKind::OnStart).then(Vec::new)
+        });
+    }
+
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let dt = ctx.dt;
+        self.run_events(ctx.scene, ctx.handle, ctx.message_sender, |kind| {
+            matches!(kind, VisualNodeKind::OnUpdate).then(|| vec![VisualValue::Number(dt)])
+        });
+    }
+
+    fn restore_resources(&mut self, resource_manager: ResourceManager) {
+        resource_manager
+            .state()
+            .containers_mut()
+            .visual_scripts
+            .try_restore_optional_resource(&mut self.graph);
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        ctx: &mut ScriptMessageContext,
+    ) {
+        if let Some(message) = message.downcast_ref::<VisualScriptMessage>() {
+            self.run_events(
+                ctx.scene,
+                ctx.handle,
+                ctx.message_sender,
+                |kind| match kind {
+                    VisualNodeKind::OnMessage { name } if *name == message.name => Some(vec![
+                        VisualValue::Node(message.sender),
+                        message.payload.clone(),
+                    ]),
+                    _ => None,
+                },
+            );
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+}
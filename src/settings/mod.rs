@@ -0,0 +1,353 @@
+//! Persistent engine and user settings. See [`Settings`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::core::parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display, Formatter},
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+};
+
+/// Name of the section with graphics quality settings ([`crate::renderer::QualitySettings`]).
+pub const GRAPHICS_SECTION: &str = "Graphics";
+
+/// Name of the section with key bindings ([`crate::input::map::InputMap`]).
+pub const INPUT_SECTION: &str = "Input";
+
+/// Name of the settings file in a config directory, see [`Settings::open`].
+pub const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+/// An error that may occur when working with settings.
+#[derive(Debug)]
+pub enum SettingsError {
+    /// Unable to read or write the settings file.
+    Io(std::io::Error),
+    /// Unable to serialize or deserialize a value.
+    Ron(ron::Error),
+    /// There is no path to save the settings to, see [`Settings::set_path`].
+    NoPath,
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::Io(v) => write!(f, "Settings i/o error: {}", v),
+            SettingsError::Ron(v) => write!(f, "Settings serialization error: {}", v),
+            SettingsError::NoPath => write!(f, "Settings file path is not set."),
+        }
+    }
+}
+
+impl From<std::io::Error> for SettingsError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ron::Error> for SettingsError {
+    fn from(e: ron::Error) -> Self {
+        Self::Ron(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for SettingsError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        Self::Ron(e.code)
+    }
+}
+
+/// An event that is sent to every subscriber of [`Settings`] (see [`Settings::subscribe`]).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsEvent {
+    /// A value with the given key was added or changed.
+    Changed(String),
+    /// A value with the given key was removed.
+    Removed(String),
+    /// Settings were loaded from a file, any value could be changed.
+    Loaded,
+}
+
+/// Returns platform-specific directory for configuration files of an application with the given
+/// name:
+///
+/// - Windows - `%APPDATA%\<app_name>`
+/// - macOS - `$HOME/Library/Application Support/<app_name>`
+/// - Other Unix-like systems - `$XDG_CONFIG_HOME/<app_name>` or `$HOME/.config/<app_name>`
+///
+/// Returns `None` if the directory cannot be determined (for example, on WebAssembly).
+pub fn config_dir(app_name: &str) -> Option<PathBuf> {
+    fn env_path(name: &str) -> Option<PathBuf> {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    }
+
+    let base = if cfg!(target_arch = "wasm32") {
+        None
+    } else if cfg!(target_os = "windows") {
+        env_path("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_path("XDG_CONFIG_HOME").or_else(|| env_path("HOME").map(|home| home.join(".config")))
+    };
+
+    base.map(|base| base.join(app_name))
+}
+
+#[derive(Default)]
+struct State {
+    values: BTreeMap<String, String>,
+    path: Option<PathBuf>,
+    subscribers: Vec<Sender<SettingsEvent>>,
+    modified: bool,
+}
+
+impl State {
+    fn notify(&mut self, event: SettingsEvent) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
+
+/// Settings is a persistent key-value storage for game options (volume, difficulty, etc.) and for
+/// persisted state of engine subsystems (graphics quality, key bindings, etc.). A value could be
+/// anything that implements `serde` traits - a primitive or a whole structure, structures are
+/// usually stored in "sections" with well-known names (see [`GRAPHICS_SECTION`] and
+/// [`INPUT_SECTION`]).
+///
+/// Settings are stored in a human-readable file, that is usually placed in a platform-specific
+/// config directory (see [`Settings::open`] and [`config_dir`]). Changes are not written to the file
+/// until [`Settings::save`] is called, the executor saves modified settings automatically when the
+/// game is closed.
+///
+/// Clones share the same set of values. Every change is reported to subscribers (see [`Settings::subscribe`]), so subsystems
+/// could react to changes made by other parts of a game (an options menu, for example).
+///
+/// ```rust
+/// use fyrox::{
+///     renderer::{QualitySettings, Renderer},
+///     settings::{Settings, GRAPHICS_SECTION},
+/// };
+///
+/// fn load_settings(settings: &Settings, renderer: &mut Renderer) {
+///     let _ = settings.use_config_dir("MyGame");
+///
+///     let _volume = settings.get_or_default::<f32>("Volume");
+///
+///     if let Some(quality) = settings.get::<QualitySettings>(GRAPHICS_SECTION) {
+///         let _ = renderer.set_quality_settings(&quality);
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct Settings {
+    state: Arc<Mutex<State>>,
+}
+
+impl Debug for Settings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Settings")
+            .field("values", &state.values)
+            .field("path", &state.path)
+            .field("modified", &state.modified)
+            .finish()
+    }
+}
+
+impl Settings {
+    /// Creates new empty settings, that are not bound to any file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new settings bound to [`SETTINGS_FILE_NAME`] file in the config directory of the
+    /// application with the given name (see [`config_dir`]). Existing settings are loaded from the
+    /// file.
+    pub fn open(app_name: &str) -> Result<Self, SettingsError> {
+        let settings = Self::new();
+        settings.use_config_dir(app_name)?;
+        Ok(settings)
+    }
+
+    /// Binds the settings to [`SETTINGS_FILE_NAME`] file in the config directory of the application
+    /// with the given name (see [`config_dir`]). Existing settings are loaded from the file.
+    pub fn use_config_dir(&self, app_name: &str) -> Result<(), SettingsError> {
+        let dir = config_dir(app_name).ok_or(SettingsError::NoPath)?;
+        self.set_path(dir.join(SETTINGS_FILE_NAME))
+    }
+
+    /// Sets a path to the file, that will be used to store the settings. If the file exists, its
+    /// values will be loaded and will replace current values with the same keys.
+    pub fn set_path<P: AsRef<Path>>(&self, path: P) -> Result<(), SettingsError> {
+        let mut state = self.state.lock();
+        let path = path.as_ref().to_owned();
+        let result = if path.exists() {
+            std::fs::read_to_string(&path)
+                .map_err(SettingsError::from)
+                .and_then(|text| {
+                    ron::from_str::<BTreeMap<String, String>>(&text).map_err(SettingsError::from)
+                })
+                .map(|values| {
+                    state.values.extend(values);
+                    state.notify(SettingsEvent::Loaded);
+                })
+        } else {
+            Ok(())
+        };
+        state.path = Some(path);
+        result
+    }
+
+    /// Returns a path to the settings file, if any.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.state.lock().path.clone()
+    }
+
+    /// Sets a value with the given key. Subscribers will be notified only if the value was actually
+    /// changed.
+    pub fn set<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result<(), SettingsError> {
+        let text = ron::to_string(value)?;
+        let mut state = self.state.lock();
+        if state.values.get(key) != Some(&text) {
+            state.values.insert(key.to_owned(), text);
+            state.modified = true;
+            state.notify(SettingsEvent::Changed(key.to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Returns a value with the given key. `None` is returned if there is no such value or if the
+    /// stored value cannot be converted to the requested type.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.state
+            .lock()
+            .values
+            .get(key)
+            .and_then(|text| ron::from_str(text).ok())
+    }
+
+    /// Returns a value with the given key or the default value of the type if there is no such value.
+    pub fn get_or_default<T: DeserializeOwned + Default>(&self, key: &str) -> T {
+        self.get(key).unwrap_or_default()
+    }
+
+    /// Returns `true` if there is a value with the given key.
+    pub fn contains(&self, key: &str) -> bool {
+        self.state.lock().values.contains_key(key)
+    }
+
+    /// Removes a value with the given key, returns `true` if the value existed.
+    pub fn remove(&self, key: &str) -> bool {
+        let mut state = self.state.lock();
+        if state.values.remove(key).is_some() {
+            state.modified = true;
+            state.notify(SettingsEvent::Removed(key.to_owned()));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns keys of every stored value in alphabetical order.
+    pub fn keys(&self) -> Vec<String> {
+        self.state.lock().values.keys().cloned().collect()
+    }
+
+    /// Returns a receiver, that will receive every change of the settings.
+    pub fn subscribe(&self) -> Receiver<SettingsEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.state.lock().subscribers.push(sender);
+        receiver
+    }
+
+    /// Returns `true` if there are changes, that were not saved yet.
+    pub fn is_modified(&self) -> bool {
+        self.state.lock().modified
+    }
+
+    /// Writes the settings to the file (see [`Self::set_path`]). Missing directories will be created.
+    pub fn save(&self) -> Result<(), SettingsError> {
+        let mut state = self.state.lock();
+        let path = state.path.clone().ok_or(SettingsError::NoPath)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(&state.values, Default::default())?;
+        std::fs::write(path, text)?;
+        state.modified = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        renderer::QualitySettings,
+        settings::{Settings, SettingsEvent, GRAPHICS_SECTION},
+    };
+
+    #[test]
+    fn test_settings_values_and_events() {
+        let settings = Settings::new();
+        let events = settings.subscribe();
+
+        settings.set("Volume", &0.5f32).unwrap();
+        settings.set("Volume", &0.5f32).unwrap();
+        settings.set("Name", "Player").unwrap();
+
+        assert_eq!(settings.get::<f32>("Volume"), Some(0.5));
+        assert_eq!(settings.get::<String>("Name"), Some("Player".to_owned()));
+        // Wrong type.
+        assert_eq!(settings.get::<bool>("Volume"), None);
+        assert!(!settings.get_or_default::<bool>("Missing"));
+        assert!(settings.remove("Name"));
+        assert!(!settings.remove("Name"));
+
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                SettingsEvent::Changed("Volume".to_owned()),
+                SettingsEvent::Changed("Name".to_owned()),
+                SettingsEvent::Removed("Name".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_settings_save_load() {
+        let path = std::env::temp_dir()
+            .join("fyrox_settings_test")
+            .join("settings.ron");
+        let _ = std::fs::remove_file(&path);
+
+        let quality = QualitySettings::low();
+
+        let settings = Settings::new();
+        settings.set_path(&path).unwrap();
+        settings.set("Volume", &0.25f32).unwrap();
+        settings.set(GRAPHICS_SECTION, &quality).unwrap();
+        assert!(settings.is_modified());
+        settings.save().unwrap();
+        assert!(!settings.is_modified());
+
+        let loaded = Settings::new();
+        let events = loaded.subscribe();
+        loaded.set_path(&path).unwrap();
+        assert_eq!(loaded.get::<f32>("Volume"), Some(0.25));
+        assert_eq!(
+            loaded.get::<QualitySettings>(GRAPHICS_SECTION),
+            Some(quality)
+        );
+        assert_eq!(events.try_recv(), Ok(SettingsEvent::Loaded));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,356 @@
+//! Steam integration. It is available only when `steam` feature is enabled and it is not available
+//! on WebAssembly.
+//!
+//! The integration is built on top of [`steamworks`] crate, which links with Steamworks
+//! redistributable library (`steam_api64.dll`, `libsteam_api.so`, `libsteam_api.dylib`). The
+//! library is copied next to the build artifacts by the crate, it must be shipped together with the
+//! executable of the game. Parts of Steamworks API, that are not wrapped by the crate (Steam Input,
+//! achievement progress, clearing of rich presence), are called via its raw bindings.
+//!
+//! The engine initializes Steam automatically on creation (see
+//! [`crate::engine::services::EngineServices::steam`]) and runs Steam callbacks every frame, so the
+//! only thing left is to use the [`Steam`] service from your plugins and scripts:
+//!
+//! ```rust,no_run
+//! use fyrox::steam::Steam;
+//!
+//! fn on_level_completed(steam: &Steam, level: u32) {
+//!     steam.unlock_achievement("ACH_FIRST_LEVEL");
+//!     steam.set_stat_i32("levels_completed", level as i32);
+//!     steam.store_stats();
+//!     steam.set_rich_presence("status", &format!("Playing level {}", level + 1));
+//! }
+//! ```
+//!
+//! During development Steam requires `steam_appid.txt` file with the id of your app in the working
+//! directory of the game.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::algebra::Vector2,
+    input::force_feedback::{
+        ForceFeedbackBackend, ForceFeedbackCapabilities, GamepadId, MotorSpeeds, Trigger,
+        TriggerEffect,
+    },
+};
+use std::{
+    ffi::CString,
+    fmt::{Display, Formatter},
+};
+use steamworks::sys;
+
+/// An error that may occur during Steam initialization.
+#[derive(Debug)]
+pub enum SteamError {
+    /// Steam API initialization failed. Most likely Steam client is not running or the app id is
+    /// unknown.
+    InitFailed(steamworks::SteamError),
+}
+
+impl Display for SteamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SteamError::InitFailed(e) => {
+                write!(
+                    f,
+                    "Unable to initialize Steam API, make sure that Steam client is running. \
+                    Reason: {}",
+                    e
+                )
+            }
+        }
+    }
+}
+
+/// A handle of a controller connected via Steam Input.
+pub type InputHandle = u64;
+
+/// A handle of Steam Input action or action set.
+pub type ActionHandle = u64;
+
+// Steam Input interface is created by Steam API initialization, so the pointer is valid as long as
+// there is at least one instance of `steamworks::Client`.
+fn steam_input() -> *mut sys::ISteamInput {
+    unsafe { sys::SteamAPI_SteamInput_v006() }
+}
+
+// Steam Input expects motor speeds in the full range of `u16`.
+fn motor_speed(speed: f32) -> u16 {
+    (speed.clamp(0.0, 1.0) * u16::MAX as f32) as u16
+}
+
+/// Dispatches Steam callbacks. Unlike [`Steam`] it is bound to the thread, that initialized Steam
+/// API, the engine keeps it and runs callbacks every frame.
+pub struct SteamCallbacks {
+    single: steamworks::SingleClient,
+}
+
+impl SteamCallbacks {
+    /// Dispatches Steam callbacks and updates Steam Input state. It is called by the engine
+    /// automatically every frame.
+    pub fn run(&self) {
+        self.single.run_callbacks();
+    }
+}
+
+/// Steam service, that provides access to achievements, stats, rich presence and Steam Input. Every
+/// clone refers to the same Steam API instance, which is shut down when the last clone is dropped.
+/// See [module docs](self) for more info.
+#[derive(Clone)]
+pub struct Steam {
+    client: steamworks::Client,
+    input: bool,
+}
+
+impl std::fmt::Debug for Steam {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Steam").field("input", &self.input).finish()
+    }
+}
+
+impl Steam {
+    /// Initializes Steam API and Steam Input. Returns the service and a dispatcher of callbacks,
+    /// that must be run periodically on the same thread (see [`SteamCallbacks::run`]).
+    pub fn init() -> Result<(Self, SteamCallbacks), SteamError> {
+        let (client, single) = steamworks::Client::init().map_err(SteamError::InitFailed)?;
+
+        // Steam Input state is updated by `SteamAPI_RunCallbacks`, there is no need to run frames
+        // explicitly.
+        let input = unsafe { sys::SteamAPI_ISteamInput_Init(steam_input(), false) };
+
+        Ok((Self { client, input }, SteamCallbacks { single }))
+    }
+
+    /// Unlocks an achievement with the given API name. Call [`Self::store_stats`] to send the changes
+    /// to the server.
+    pub fn unlock_achievement(&self, name: &str) -> bool {
+        self.client.user_stats().achievement(name).set().is_ok()
+    }
+
+    /// Locks an achievement with the given API name (useful for testing).
+    pub fn clear_achievement(&self, name: &str) -> bool {
+        self.client.user_stats().achievement(name).clear().is_ok()
+    }
+
+    /// Returns `Some(true)` if an achievement is unlocked, `None` if there is no such achievement.
+    pub fn is_achievement_unlocked(&self, name: &str) -> Option<bool> {
+        self.client.user_stats().achievement(name).get().ok()
+    }
+
+    /// Shows achievement progress notification to the user.
+    pub fn indicate_achievement_progress(&self, name: &str, current: u32, max: u32) -> bool {
+        match CString::new(name) {
+            Ok(name) => unsafe {
+                sys::SteamAPI_ISteamUserStats_IndicateAchievementProgress(
+                    sys::SteamAPI_SteamUserStats_v012(),
+                    name.as_ptr(),
+                    current,
+                    max,
+                )
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Sets a value of an integer stat. Call [`Self::store_stats`] to send the changes to the server.
+    pub fn set_stat_i32(&self, name: &str, value: i32) -> bool {
+        self.client.user_stats().set_stat_i32(name, value).is_ok()
+    }
+
+    /// Sets a value of a float stat. Call [`Self::store_stats`] to send the changes to the server.
+    pub fn set_stat_f32(&self, name: &str, value: f32) -> bool {
+        self.client.user_stats().set_stat_f32(name, value).is_ok()
+    }
+
+    /// Returns a value of an integer stat.
+    pub fn stat_i32(&self, name: &str) -> Option<i32> {
+        self.client.user_stats().get_stat_i32(name).ok()
+    }
+
+    /// Returns a value of a float stat.
+    pub fn stat_f32(&self, name: &str) -> Option<f32> {
+        self.client.user_stats().get_stat_f32(name).ok()
+    }
+
+    /// Sends changed stats and achievements to the server.
+    pub fn store_stats(&self) -> bool {
+        self.client.user_stats().store_stats().is_ok()
+    }
+
+    /// Sets rich presence key/value pair for the current user. Rich presence is shown to the friends
+    /// of the user.
+    pub fn set_rich_presence(&self, key: &str, value: &str) -> bool {
+        self.client.friends().set_rich_presence(key, Some(value))
+    }
+
+    /// Clears every rich presence key of the current user.
+    pub fn clear_rich_presence(&self) {
+        unsafe { sys::SteamAPI_ISteamFriends_ClearRichPresence(sys::SteamAPI_SteamFriends_v017()) }
+    }
+
+    /// Returns `true` if Steam Input is available.
+    pub fn has_input(&self) -> bool {
+        self.input
+    }
+
+    /// Returns a list of controllers connected via Steam Input.
+    pub fn connected_controllers(&self) -> Vec<InputHandle> {
+        if self.input {
+            let mut handles = [0; sys::STEAM_INPUT_MAX_COUNT as usize];
+            let count = unsafe {
+                sys::SteamAPI_ISteamInput_GetConnectedControllers(
+                    steam_input(),
+                    handles.as_mut_ptr(),
+                )
+            };
+            handles[..count.clamp(0, handles.len() as i32) as usize].to_vec()
+        } else {
+            Default::default()
+        }
+    }
+
+    /// Returns a handle of an action set with the given name, zero if there is no such set.
+    pub fn action_set_handle(&self, name: &str) -> ActionHandle {
+        match CString::new(name) {
+            Ok(name) if self.input => unsafe {
+                sys::SteamAPI_ISteamInput_GetActionSetHandle(steam_input(), name.as_ptr())
+            },
+            _ => 0,
+        }
+    }
+
+    /// Activates an action set for the given controller.
+    pub fn activate_action_set(&self, controller: InputHandle, action_set: ActionHandle) {
+        if self.input {
+            unsafe {
+                sys::SteamAPI_ISteamInput_ActivateActionSet(steam_input(), controller, action_set)
+            }
+        }
+    }
+
+    /// Returns a handle of a digital action with the given name, zero if there is no such action.
+    pub fn digital_action_handle(&self, name: &str) -> ActionHandle {
+        match CString::new(name) {
+            Ok(name) if self.input => unsafe {
+                sys::SteamAPI_ISteamInput_GetDigitalActionHandle(steam_input(), name.as_ptr())
+            },
+            _ => 0,
+        }
+    }
+
+    /// Returns `true` if the digital action is active and pressed.
+    pub fn is_digital_action_pressed(&self, controller: InputHandle, action: ActionHandle) -> bool {
+        if self.input {
+            let data = unsafe {
+                sys::SteamAPI_ISteamInput_GetDigitalActionData(steam_input(), controller, action)
+            };
+            data.bActive && data.bState
+        } else {
+            false
+        }
+    }
+
+    /// Returns a handle of an analog action with the given name, zero if there is no such action.
+    pub fn analog_action_handle(&self, name: &str) -> ActionHandle {
+        match CString::new(name) {
+            Ok(name) if self.input => unsafe {
+                sys::SteamAPI_ISteamInput_GetAnalogActionHandle(steam_input(), name.as_ptr())
+            },
+            _ => 0,
+        }
+    }
+
+    /// Returns a value of the analog action, `None` if the action is not active.
+    pub fn analog_action(
+        &self,
+        controller: InputHandle,
+        action: ActionHandle,
+    ) -> Option<Vector2<f32>> {
+        if self.input {
+            // The struct is packed, so its fields are copied instead of borrowing them.
+            let data = unsafe {
+                sys::SteamAPI_ISteamInput_GetAnalogActionData(steam_input(), controller, action)
+            };
+            let (active, x, y) = (data.bActive, data.x, data.y);
+            if active {
+                Some(Vector2::new(x, y))
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Sets speeds (in `[0; 1]` range) of the left (strong) and right (weak) rumble motors of the
+    /// given controller.
+    pub fn trigger_vibration(&self, controller: InputHandle, left: f32, right: f32) {
+        if self.input {
+            unsafe {
+                sys::SteamAPI_ISteamInput_TriggerVibration(
+                    steam_input(),
+                    controller,
+                    motor_speed(left),
+                    motor_speed(right),
+                );
+            }
+        }
+    }
+}
+
+/// Force feedback backend that uses Steam Input, [`GamepadId`] is an index of a controller in the
+/// list of connected controllers. Use it with [`crate::input::force_feedback::ForceFeedback::set_backend`].
+pub struct SteamForceFeedbackBackend {
+    steam: Steam,
+}
+
+impl SteamForceFeedbackBackend {
+    /// Creates new backend.
+    pub fn new(steam: Steam) -> Self {
+        Self { steam }
+    }
+
+    fn controller(&self, gamepad: GamepadId) -> Option<InputHandle> {
+        self.steam.connected_controllers().get(gamepad.0).cloned()
+    }
+}
+
+impl ForceFeedbackBackend for SteamForceFeedbackBackend {
+    fn capabilities(&self, gamepad: GamepadId) -> ForceFeedbackCapabilities {
+        ForceFeedbackCapabilities {
+            rumble: self.controller(gamepad).is_some(),
+            ..Default::default()
+        }
+    }
+
+    fn set_motor_speeds(&mut self, gamepad: GamepadId, speeds: MotorSpeeds) {
+        if let Some(controller) = self.controller(gamepad) {
+            self.steam
+                .trigger_vibration(controller, speeds.strong, speeds.weak);
+        }
+    }
+
+    fn set_trigger_effect(
+        &mut self,
+        _gamepad: GamepadId,
+        _trigger: Trigger,
+        _effect: TriggerEffect,
+    ) {
+        // Steam Input does not expose adaptive triggers.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::steam::motor_speed;
+
+    #[test]
+    fn test_motor_speed() {
+        assert_eq!(motor_speed(0.0), 0);
+        assert_eq!(motor_speed(1.0), u16::MAX);
+        assert_eq!(motor_speed(2.0), u16::MAX);
+        assert_eq!(motor_speed(-1.0), 0);
+        assert_eq!(motor_speed(0.5), u16::MAX / 2);
+    }
+}
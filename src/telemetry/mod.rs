@@ -0,0 +1,659 @@
+//! Opt-in telemetry (analytics) for playtests. See [`Telemetry`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::parking_lot::Mutex,
+    rand::{thread_rng, Rng},
+    utils::log::Log,
+};
+use std::{
+    fmt::{Debug, Display, Formatter, Write as FmtWrite},
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+use std::{
+    sync::mpsc::{self, Sender},
+    time::Duration,
+};
+
+/// A value of an event property.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    /// Boolean value.
+    Bool(bool),
+    /// Integer value.
+    Int(i64),
+    /// Floating-point value.
+    Float(f64),
+    /// String value.
+    String(String),
+}
+
+macro_rules! impl_property_from {
+    ($($ty:ty => $variant:ident as $as:ty),*) => {
+        $(
+            impl From<$ty> for PropertyValue {
+                fn from(v: $ty) -> Self {
+                    Self::$variant(v as $as)
+                }
+            }
+        )*
+    };
+}
+
+impl_property_from!(
+    bool => Bool as bool,
+    i32 => Int as i64,
+    i64 => Int as i64,
+    u32 => Int as i64,
+    usize => Int as i64,
+    f32 => Float as f64,
+    f64 => Float as f64
+);
+
+impl From<&str> for PropertyValue {
+    fn from(v: &str) -> Self {
+        Self::String(v.to_owned())
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(v: String) -> Self {
+        Self::String(v)
+    }
+}
+
+fn write_json_string(out: &mut String, str: &str) {
+    out.push('"');
+    for c in str.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl PropertyValue {
+    fn write_json(&self, out: &mut String) {
+        match self {
+            PropertyValue::Bool(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            PropertyValue::Int(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            PropertyValue::Float(v) => {
+                if v.is_finite() {
+                    let _ = write!(out, "{}", v);
+                } else {
+                    out.push_str("null");
+                }
+            }
+            PropertyValue::String(v) => write_json_string(out, v),
+        }
+    }
+}
+
+/// Name of an event, that is recorded by [`Telemetry::start_session`].
+pub const SESSION_START_EVENT: &str = "session_start";
+
+/// Name of an event, that is recorded by [`Telemetry::end_session`].
+pub const SESSION_END_EVENT: &str = "session_end";
+
+/// Telemetry event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TelemetryEvent {
+    /// Name of the event.
+    pub name: String,
+    /// Time stamp of the event in milliseconds since UNIX epoch.
+    pub timestamp: u64,
+    /// Id of the session in which the event was recorded.
+    pub session_id: u64,
+    /// A set of properties of the event.
+    pub properties: Vec<(String, PropertyValue)>,
+}
+
+impl TelemetryEvent {
+    /// Creates new event with the given name and current time stamp.
+    pub fn new<S: AsRef<str>>(name: S) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+            session_id: 0,
+            properties: Default::default(),
+        }
+    }
+
+    /// Adds new property to the event.
+    pub fn with_property<S: AsRef<str>, V: Into<PropertyValue>>(
+        mut self,
+        name: S,
+        value: V,
+    ) -> Self {
+        self.properties
+            .push((name.as_ref().to_owned(), value.into()));
+        self
+    }
+
+    /// Returns a value of a property with the given name.
+    pub fn property(&self, name: &str) -> Option<&PropertyValue> {
+        self.properties
+            .iter()
+            .find_map(|(n, v)| if n == name { Some(v) } else { None })
+    }
+
+    /// Writes the event as a JSON object.
+    pub fn write_json(&self, out: &mut String) {
+        out.push_str("{\"name\":");
+        write_json_string(out, &self.name);
+        let _ = write!(
+            out,
+            ",\"timestamp\":{},\"session_id\":{},\"properties\":{{",
+            self.timestamp, self.session_id
+        );
+        for (i, (name, value)) in self.properties.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_string(out, name);
+            out.push(':');
+            value.write_json(out);
+        }
+        out.push_str("}}");
+    }
+
+    /// Returns the event as a JSON object.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+}
+
+/// An error that may occur when sending telemetry.
+#[derive(Debug)]
+pub enum TelemetryError {
+    /// An i/o error.
+    Io(std::io::Error),
+    /// A sink rejected a batch of events.
+    Rejected(String),
+}
+
+impl Display for TelemetryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TelemetryError::Io(v) => write!(f, "Telemetry i/o error: {}", v),
+            TelemetryError::Rejected(v) => write!(f, "Telemetry batch was rejected: {}", v),
+        }
+    }
+}
+
+impl From<std::io::Error> for TelemetryError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Telemetry sink receives batches of events and stores them somewhere.
+pub trait TelemetrySink: Send {
+    /// Sends a batch of events. The method is called on the main thread, so it should not block for
+    /// a long time.
+    fn send(&mut self, batch: &[TelemetryEvent]) -> Result<(), TelemetryError>;
+}
+
+/// A sink that appends events to a file, one JSON object per line.
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    /// Creates new sink that writes events to the given file.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+}
+
+impl TelemetrySink for FileSink {
+    fn send(&mut self, batch: &[TelemetryEvent]) -> Result<(), TelemetryError> {
+        let mut out = String::new();
+        for event in batch {
+            event.write_json(&mut out);
+            out.push('\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(out.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+fn post(agent: &ureq::Agent, url: &str, body: &str) -> Result<(), TelemetryError> {
+    match agent
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(body)
+    {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, response)) => Err(TelemetryError::Rejected(format!(
+            "{} {}",
+            code,
+            response.status_text()
+        ))),
+        Err(ureq::Error::Transport(transport)) => Err(TelemetryError::Io(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            transport.to_string(),
+        ))),
+    }
+}
+
+// A batch is sent at most this amount of times, then it is dropped.
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+const HTTP_MAX_ATTEMPTS: u32 = 5;
+
+// Returns a delay before the next attempt to send a batch (exponential backoff: 1, 2, 4, 8 seconds),
+// `None` means that the batch should be dropped. Only transient errors (network errors, overloaded
+// or unavailable server) are worth retrying, the server will reject the same batch again otherwise.
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+fn retry_delay(error: &TelemetryError, attempt: u32) -> Option<Duration> {
+    let transient = match error {
+        TelemetryError::Io(_) => true,
+        TelemetryError::Rejected(status) => status
+            .split_whitespace()
+            .next()
+            .map_or(false, |code| code == "429" || code.starts_with('5')),
+    };
+    if transient && attempt + 1 < HTTP_MAX_ATTEMPTS {
+        Some(Duration::from_secs(1 << attempt))
+    } else {
+        None
+    }
+}
+
+/// A sink that sends batches of events as JSON arrays to an HTTP(S) endpoint using `POST` requests.
+/// Requests are sent from a background thread, so the sink never blocks the game. A batch, that
+/// failed to send because of a network error or a server error, is sent again a few times with
+/// increasing delays, then it is dropped.
+///
+/// Requires `telemetry-http` feature. The sink is not available on WebAssembly.
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+pub struct HttpSink {
+    sender: Sender<String>,
+}
+
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+impl HttpSink {
+    /// Creates new sink that sends events to the given URL (for example,
+    /// `https://example.com/events`). Returns an error if the background thread could not be
+    /// created.
+    pub fn new<S: AsRef<str>>(url: S) -> Result<Self, TelemetryError> {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let url = url.as_ref().to_owned();
+        std::thread::Builder::new()
+            .name("TelemetryHttpSink".to_owned())
+            .spawn(move || {
+                let agent = ureq::AgentBuilder::new()
+                    .timeout(Duration::from_secs(10))
+                    .build();
+                while let Ok(body) = receiver.recv() {
+                    let mut attempt = 0;
+                    while let Err(e) = post(&agent, &url, &body) {
+                        match retry_delay(&e, attempt) {
+                            Some(delay) => {
+                                std::thread::sleep(delay);
+                                attempt += 1;
+                            }
+                            None => {
+                                Log::warn(format!(
+                                    "Unable to send telemetry to {}, the batch is dropped. {}",
+                                    url, e
+                                ));
+                                break;
+                            }
+                        }
+                    }
+                }
+            })?;
+        Ok(Self { sender })
+    }
+}
+
+#[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+impl TelemetrySink for HttpSink {
+    fn send(&mut self, batch: &[TelemetryEvent]) -> Result<(), TelemetryError> {
+        let mut body = String::from("[");
+        for (i, event) in batch.iter().enumerate() {
+            if i > 0 {
+                body.push(',');
+            }
+            event.write_json(&mut body);
+        }
+        body.push(']');
+        self.sender
+            .send(body)
+            .map_err(|_| TelemetryError::Rejected("sender thread is dead".to_owned()))
+    }
+}
+
+struct State {
+    enabled: bool,
+    sinks: Vec<Box<dyn TelemetrySink>>,
+    queue: Vec<TelemetryEvent>,
+    session_id: u64,
+    batch_size: usize,
+    flush_interval: f32,
+    timer: f32,
+}
+
+impl State {
+    fn flush(&mut self) {
+        if self.queue.is_empty() {
+            return;
+        }
+        for sink in self.sinks.iter_mut() {
+            if let Err(e) = sink.send(&self.queue) {
+                Log::warn(format!("Unable to send telemetry. {}", e));
+            }
+        }
+        self.queue.clear();
+        self.timer = 0.0;
+    }
+}
+
+/// Telemetry service collects events and sends them in batches to a set of sinks (see [`FileSink`],
+/// `HttpSink` (requires `telemetry-http` feature), or implement [`TelemetrySink`]). Telemetry is opt-in, it is disabled by default and
+/// every event is discarded until it is enabled via [`Self::set_enabled`] (for example, after the
+/// player has agreed to share playtest data).
+///
+/// Events are batched, a batch is sent when it is full or when flush interval has passed. The engine
+/// updates the service automatically.
+///
+/// Clones share the same batch and the same set of sinks, so events could be recorded from worker
+/// threads as well.
+///
+/// ```rust
+/// use fyrox::telemetry::{FileSink, Telemetry, TelemetryEvent};
+///
+/// fn setup(telemetry: &Telemetry) {
+///     telemetry.add_sink(Box::new(FileSink::new("playtest.jsonl")));
+///     telemetry.set_enabled(true);
+///     telemetry.start_session(vec![("build".to_owned(), "0.1.0".into())]);
+/// }
+///
+/// fn on_level_completed(telemetry: &Telemetry, level: u32, time: f32) {
+///     telemetry.record(
+///         TelemetryEvent::new("level_completed")
+///             .with_property("level", level)
+///             .with_property("time", time),
+///     );
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Telemetry {
+    state: Arc<Mutex<State>>,
+}
+
+impl Debug for Telemetry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("Telemetry")
+            .field("enabled", &state.enabled)
+            .field("sinks", &state.sinks.len())
+            .field("queue", &state.queue.len())
+            .field("session_id", &state.session_id)
+            .finish()
+    }
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Telemetry {
+    /// Creates new disabled telemetry service without any sinks.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                enabled: false,
+                sinks: Default::default(),
+                queue: Default::default(),
+                session_id: 0,
+                batch_size: 64,
+                flush_interval: 30.0,
+                timer: 0.0,
+            })),
+        }
+    }
+
+    /// Enables or disables telemetry. Queued events are discarded when telemetry is disabled.
+    pub fn set_enabled(&self, enabled: bool) {
+        let mut state = self.state.lock();
+        state.enabled = enabled;
+        if !enabled {
+            state.queue.clear();
+        }
+    }
+
+    /// Returns `true` if telemetry is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.state.lock().enabled
+    }
+
+    /// Adds new sink.
+    pub fn add_sink(&self, sink: Box<dyn TelemetrySink>) {
+        self.state.lock().sinks.push(sink);
+    }
+
+    /// Removes every sink.
+    pub fn clear_sinks(&self) {
+        self.state.lock().sinks.clear();
+    }
+
+    /// Sets maximum amount of events in a batch (default is 64).
+    pub fn set_batch_size(&self, batch_size: usize) {
+        self.state.lock().batch_size = batch_size.max(1);
+    }
+
+    /// Sets maximum amount of time (in seconds) that an event could wait in the queue (default is 30
+    /// seconds).
+    pub fn set_flush_interval(&self, interval: f32) {
+        self.state.lock().flush_interval = interval;
+    }
+
+    /// Starts new session with random id and records [`SESSION_START_EVENT`] with the given
+    /// properties (build version, platform, etc.). Returns id of the session.
+    pub fn start_session(&self, properties: Vec<(String, PropertyValue)>) -> u64 {
+        let session_id = thread_rng().gen_range(1..u64::MAX);
+        self.state.lock().session_id = session_id;
+        self.record(TelemetryEvent {
+            properties,
+            ..TelemetryEvent::new(SESSION_START_EVENT)
+        });
+        session_id
+    }
+
+    /// Records [`SESSION_END_EVENT`] and sends every queued event.
+    pub fn end_session(&self) {
+        self.record(TelemetryEvent::new(SESSION_END_EVENT));
+        let mut state = self.state.lock();
+        state.flush();
+        state.session_id = 0;
+    }
+
+    /// Returns id of current session, zero if there is no session.
+    pub fn session_id(&self) -> u64 {
+        self.state.lock().session_id
+    }
+
+    /// Records an event. The event is discarded if telemetry is disabled.
+    pub fn record(&self, mut event: TelemetryEvent) {
+        let mut state = self.state.lock();
+        if state.enabled {
+            event.session_id = state.session_id;
+            state.queue.push(event);
+            if state.queue.len() >= state.batch_size {
+                state.flush();
+            }
+        }
+    }
+
+    /// Returns amount of queued events.
+    pub fn queue_len(&self) -> usize {
+        self.state.lock().queue.len()
+    }
+
+    /// Sends every queued event to the sinks.
+    pub fn flush(&self) {
+        self.state.lock().flush();
+    }
+
+    /// Sends queued events if flush interval has passed. It is called by the engine automatically.
+    pub fn update(&self, dt: f32) {
+        let mut state = self.state.lock();
+        if state.queue.is_empty() {
+            state.timer = 0.0;
+            return;
+        }
+        state.timer += dt;
+        if state.timer >= state.flush_interval {
+            state.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::telemetry::{
+        Telemetry, TelemetryError, TelemetryEvent, TelemetrySink, SESSION_START_EVENT,
+    };
+    use std::sync::mpsc::{self, Sender};
+
+    struct ChannelSink(Sender<Vec<TelemetryEvent>>);
+
+    impl TelemetrySink for ChannelSink {
+        fn send(&mut self, batch: &[TelemetryEvent]) -> Result<(), TelemetryError> {
+            self.0.send(batch.to_vec()).unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let mut event = TelemetryEvent::new("death")
+            .with_property("cause", "fall \"damage\"")
+            .with_property("level", 3)
+            .with_property("alive", false);
+        event.timestamp = 10;
+        event.session_id = 5;
+        assert_eq!(
+            event.to_json(),
+            r#"{"name":"death","timestamp":10,"session_id":5,"properties":{"cause":"fall \"damage\"","level":3,"alive":false}}"#
+        );
+    }
+
+    #[test]
+    fn test_batching() {
+        let (sender, receiver) = mpsc::channel();
+        let telemetry = Telemetry::new();
+        telemetry.add_sink(Box::new(ChannelSink(sender)));
+        telemetry.set_batch_size(3);
+        telemetry.set_flush_interval(1.0);
+
+        // Telemetry is opt-in.
+        telemetry.record(TelemetryEvent::new("ignored"));
+        assert_eq!(telemetry.queue_len(), 0);
+
+        telemetry.set_enabled(true);
+        let session = telemetry.start_session(vec![("build".to_owned(), "1".into())]);
+        telemetry.record(TelemetryEvent::new("a"));
+        assert!(receiver.try_recv().is_err());
+        telemetry.record(TelemetryEvent::new("b"));
+
+        let batch = receiver.try_recv().unwrap();
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch[0].name, SESSION_START_EVENT);
+        assert!(batch[0].property("build").is_some());
+        assert!(batch.iter().all(|e| e.session_id == session));
+
+        telemetry.record(TelemetryEvent::new("c"));
+        telemetry.update(0.5);
+        assert!(receiver.try_recv().is_err());
+        telemetry.update(0.6);
+        assert_eq!(receiver.try_recv().unwrap().len(), 1);
+    }
+
+    #[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_http_sink() {
+        use crate::telemetry::HttpSink;
+        use std::{
+            io::{Read, Write},
+            net::TcpListener,
+            time::Duration,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"]") {
+                let count = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..count]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+            sender.send(String::from_utf8(request).unwrap()).unwrap();
+        });
+
+        let mut sink = HttpSink::new(format!("http://127.0.0.1:{}/events", port)).unwrap();
+        sink.send(&[TelemetryEvent::new("test")]).unwrap();
+
+        let request = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(request.starts_with("POST /events HTTP/1.1"));
+        assert!(request.contains(r#"[{"name":"test""#));
+    }
+
+    #[cfg(all(feature = "telemetry-http", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_retry_delay() {
+        use crate::telemetry::retry_delay;
+        use std::time::Duration;
+
+        let io = TelemetryError::Io(std::io::ErrorKind::ConnectionRefused.into());
+        assert_eq!(retry_delay(&io, 0), Some(Duration::from_secs(1)));
+        assert_eq!(retry_delay(&io, 3), Some(Duration::from_secs(8)));
+        assert_eq!(retry_delay(&io, 4), None);
+
+        let unavailable = TelemetryError::Rejected("503 Service Unavailable".to_owned());
+        assert_eq!(retry_delay(&unavailable, 1), Some(Duration::from_secs(2)));
+
+        let bad_request = TelemetryError::Rejected("400 Bad Request".to_owned());
+        assert_eq!(retry_delay(&bad_request, 0), None);
+    }
+}
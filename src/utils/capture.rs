@@ -0,0 +1,800 @@
+//! Offline capturing of scene frames to an image sequence or to a video file (using `ffmpeg`), and
+//! capturing of cube maps and 360 degrees panoramas. See [`MovieCapture`] and [`capture_panorama`]
+//! docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        pool::Handle,
+    },
+    engine::Engine,
+    event_loop::ControlFlow,
+    renderer::framework::error::FrameworkError,
+    resource::texture::{Texture, TextureKind, TexturePixelKind},
+    scene::{
+        base::BaseBuilder,
+        camera::{Camera, CameraBuilder, SkyBox, SkyBoxBuilder, SkyBoxError},
+        transform::TransformBuilder,
+        Scene,
+    },
+};
+use image::{ImageError, ImageFormat, Rgba, RgbaImage};
+use std::{
+    fmt::{Display, Formatter},
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+
+/// An error that may occur during frame capturing.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// An i/o error has occurred (failed to create a file, ffmpeg pipe is broken, etc.)
+    Io(std::io::Error),
+    /// Failed to encode a frame.
+    Image(ImageError),
+    /// Rendering error.
+    Framework(FrameworkError),
+    /// There is no frame for the scene. It means that the scene is disabled, it has no cameras or the
+    /// engine is headless.
+    NoFrame,
+    /// `ffmpeg` process has finished with an error.
+    FFmpeg(Option<i32>),
+}
+
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::Io(e) => write!(f, "I/O error: {}", e),
+            CaptureError::Image(e) => write!(f, "Image error: {}", e),
+            CaptureError::Framework(e) => write!(f, "Rendering error: {:?}", e),
+            CaptureError::NoFrame => write!(f, "There is no rendered frame for the scene."),
+            CaptureError::FFmpeg(code) => write!(f, "ffmpeg has finished with code {:?}", code),
+        }
+    }
+}
+
+impl From<std::io::Error> for CaptureError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<ImageError> for CaptureError {
+    fn from(e: ImageError) -> Self {
+        Self::Image(e)
+    }
+}
+
+impl From<FrameworkError> for CaptureError {
+    fn from(e: FrameworkError) -> Self {
+        Self::Framework(e)
+    }
+}
+
+/// Defines where captured frames will be written to.
+#[derive(Clone, Debug)]
+pub enum CaptureOutput {
+    /// Every frame will be saved as a separate image in the given directory. File names will be
+    /// `<prefix><frame number>.<extension>`, frame number is padded with zeros to six digits.
+    ImageSequence {
+        /// A directory to write the images to. It will be created if does not exist.
+        directory: PathBuf,
+        /// File name prefix.
+        prefix: String,
+        /// Format of the images.
+        format: ImageFormat,
+    },
+    /// Raw frames will be piped to `ffmpeg` process, which will encode them into a video file.
+    /// `ffmpeg` must be installed.
+    FFmpeg {
+        /// A path to `ffmpeg` executable. Could be just `ffmpeg` if it is in `PATH`.
+        executable: PathBuf,
+        /// A path to the output video file.
+        output: PathBuf,
+        /// Additional output arguments (codec, quality, etc.). For example:
+        /// `["-c:v", "libx264", "-pix_fmt", "yuv420p"]`.
+        arguments: Vec<String>,
+    },
+}
+
+impl Default for CaptureOutput {
+    fn default() -> Self {
+        Self::ImageSequence {
+            directory: PathBuf::from("capture"),
+            prefix: "frame".to_string(),
+            format: ImageFormat::Png,
+        }
+    }
+}
+
+impl CaptureOutput {
+    /// Creates output that encodes frames into H.264 video file using `ffmpeg` from `PATH`.
+    pub fn ffmpeg<P: Into<PathBuf>>(output: P) -> Self {
+        Self::FFmpeg {
+            executable: PathBuf::from("ffmpeg"),
+            output: output.into(),
+            arguments: ["-c:v", "libx264", "-pix_fmt", "yuv420p"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+enum FrameWriterKind {
+    ImageSequence {
+        directory: PathBuf,
+        prefix: String,
+        format: ImageFormat,
+    },
+    FFmpeg(Child),
+}
+
+/// Writes frames of fixed size to a [`CaptureOutput`].
+pub struct FrameWriter {
+    kind: FrameWriterKind,
+    width: u32,
+    height: u32,
+    frame: usize,
+}
+
+impl FrameWriter {
+    /// Prepares the output for writing: creates the output directory or spawns `ffmpeg` process.
+    pub fn new(
+        output: &CaptureOutput,
+        width: u32,
+        height: u32,
+        frame_rate: f32,
+    ) -> Result<Self, CaptureError> {
+        let kind = match output {
+            CaptureOutput::ImageSequence {
+                directory,
+                prefix,
+                format,
+            } => {
+                std::fs::create_dir_all(directory)?;
+                FrameWriterKind::ImageSequence {
+                    directory: directory.clone(),
+                    prefix: prefix.clone(),
+                    format: *format,
+                }
+            }
+            CaptureOutput::FFmpeg {
+                executable,
+                output,
+                arguments,
+            } => {
+                let child = Command::new(executable)
+                    .args(["-y", "-f", "rawvideo", "-pixel_format", "rgba"])
+                    .arg("-video_size")
+                    .arg(format!("{}x{}", width, height))
+                    .arg("-framerate")
+                    .arg(frame_rate.to_string())
+                    .args(["-i", "-"])
+                    .args(arguments)
+                    .arg(output)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn()?;
+                FrameWriterKind::FFmpeg(child)
+            }
+        };
+
+        Ok(Self {
+            kind,
+            width,
+            height,
+            frame: 0,
+        })
+    }
+
+    /// Returns a path of an image for the given frame. Returns `None` if the output is not an
+    /// image sequence.
+    pub fn frame_path(&self, frame: usize) -> Option<PathBuf> {
+        match self.kind {
+            FrameWriterKind::ImageSequence {
+                ref directory,
+                ref prefix,
+                format,
+            } => Some(directory.join(format!(
+                "{}{:06}.{}",
+                prefix,
+                frame,
+                format.extensions_str().first().cloned().unwrap_or("img")
+            ))),
+            FrameWriterKind::FFmpeg(_) => None,
+        }
+    }
+
+    /// Returns total amount of written frames.
+    pub fn written_frames(&self) -> usize {
+        self.frame
+    }
+
+    /// Writes next frame. The frame will be resized if its size does not match the size of the
+    /// writer.
+    pub fn write(&mut self, image: &RgbaImage) -> Result<(), CaptureError> {
+        let resized;
+        let image = if image.width() != self.width || image.height() != self.height {
+            resized = image::imageops::resize(
+                image,
+                self.width,
+                self.height,
+                image::imageops::FilterType::Triangle,
+            );
+            &resized
+        } else {
+            image
+        };
+
+        if let Some(path) = self.frame_path(self.frame) {
+            if let FrameWriterKind::ImageSequence { format, .. } = self.kind {
+                image.save_with_format(path, format)?;
+            }
+        } else if let FrameWriterKind::FFmpeg(ref mut child) = self.kind {
+            if let Some(stdin) = child.stdin.as_mut() {
+                stdin.write_all(image.as_raw())?;
+            }
+        }
+
+        self.frame += 1;
+
+        Ok(())
+    }
+
+    /// Finishes writing. For `ffmpeg` output it closes the pipe and waits until the video is encoded.
+    pub fn finish(self) -> Result<(), CaptureError> {
+        if let FrameWriterKind::FFmpeg(mut child) = self.kind {
+            // Close the pipe, so ffmpeg will know that there are no more frames.
+            drop(child.stdin.take());
+            let status = child.wait()?;
+            if !status.success() {
+                return Err(CaptureError::FFmpeg(status.code()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Settings of [`MovieCapture`].
+#[derive(Clone, Debug)]
+pub struct CaptureSettings {
+    /// Width of the frames in pixels. It is not limited by the size of the window.
+    pub width: u32,
+    /// Height of the frames in pixels. It is not limited by the size of the window.
+    pub height: u32,
+    /// Amount of frames per second of the resulting sequence. Defines fixed time step of the engine
+    /// updates during capturing.
+    pub frame_rate: f32,
+    /// Total amount of frames to capture.
+    pub frame_count: usize,
+    /// Destination of the frames.
+    pub output: CaptureOutput,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            frame_rate: 60.0,
+            frame_count: 60,
+            output: Default::default(),
+        }
+    }
+}
+
+impl CaptureSettings {
+    /// Sets new size of the frames.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets new frame rate.
+    pub fn with_frame_rate(mut self, frame_rate: f32) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// Sets new amount of frames to capture.
+    pub fn with_frame_count(mut self, frame_count: usize) -> Self {
+        self.frame_count = frame_count;
+        self
+    }
+
+    /// Sets total duration (in seconds) of the capture. Amount of frames is calculated using current
+    /// frame rate, so set the frame rate first.
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.frame_count = (duration * self.frame_rate).ceil().max(0.0) as usize;
+        self
+    }
+
+    /// Sets new output of the frames.
+    pub fn with_output(mut self, output: CaptureOutput) -> Self {
+        self.output = output;
+        self
+    }
+}
+
+/// Movie capture renders a scene with fixed time step into an off-screen render target of arbitrary
+/// size and writes every frame to an image sequence or pipes them to `ffmpeg`. Time step of the engine
+/// is defined by the frame rate of the capture and does not depend on real time, which means that
+/// the scene could be heavier than it could be rendered in real-time (high resolution, max quality
+/// settings, etc.). It is useful for trailers and marketing shots.
+///
+/// ## Example
+///
+/// ```no_run
+/// use fyrox::{
+///     core::pool::Handle,
+///     engine::Engine,
+///     scene::Scene,
+///     utils::capture::{CaptureOutput, CaptureSettings, MovieCapture},
+/// };
+///
+/// fn capture_trailer(engine: &mut Engine, scene: Handle<Scene>) {
+///     let settings = CaptureSettings::default()
+///         .with_size(3840, 2160)
+///         .with_frame_rate(30.0)
+///         .with_duration(10.0)
+///         .with_output(CaptureOutput::ffmpeg("trailer.mp4"));
+///
+///     MovieCapture::new(engine, scene, settings)
+///         .unwrap()
+///         .run(engine)
+///         .unwrap();
+/// }
+/// ```
+///
+/// ## Limitations
+///
+/// Maximum size of the frames is limited by the maximum texture size supported by the GPU.
+/// The capture renders the whole engine (scene updates, plugins, scripts, UI), but only the given
+/// scene is written to the output.
+pub struct MovieCapture {
+    scene: Handle<Scene>,
+    settings: CaptureSettings,
+    writer: FrameWriter,
+    prev_render_target: Option<Texture>,
+}
+
+impl MovieCapture {
+    /// Prepares the given scene for capturing: replaces its render target with an off-screen render
+    /// target of the requested size, and prepares the output. Previous render target will be restored
+    /// in [`Self::finish`].
+    pub fn new(
+        engine: &mut Engine,
+        scene: Handle<Scene>,
+        settings: CaptureSettings,
+    ) -> Result<Self, CaptureError> {
+        let width = settings.width.max(1);
+        let height = settings.height.max(1);
+
+        let writer = FrameWriter::new(&settings.output, width, height, settings.frame_rate)?;
+
+        let prev_render_target = engine.scenes[scene]
+            .render_target
+            .replace(Texture::new_render_target(width, height));
+
+        Ok(Self {
+            scene,
+            settings,
+            writer,
+            prev_render_target,
+        })
+    }
+
+    /// Returns fixed time step (in seconds) of the capture.
+    pub fn time_step(&self) -> f32 {
+        1.0 / self.settings.frame_rate.max(f32::EPSILON)
+    }
+
+    /// Returns amount of captured frames.
+    pub fn captured_frames(&self) -> usize {
+        self.writer.written_frames()
+    }
+
+    /// Returns `true` if all requested frames were captured.
+    pub fn is_finished(&self) -> bool {
+        self.captured_frames() >= self.settings.frame_count
+    }
+
+    /// Returns current settings of the capture.
+    pub fn settings(&self) -> &CaptureSettings {
+        &self.settings
+    }
+
+    /// Updates the engine with fixed time step, renders a frame and writes it to the output. Returns
+    /// `true` if there are more frames to capture.
+    pub fn capture_frame(&mut self, engine: &mut Engine) -> Result<bool, CaptureError> {
+        if self.is_finished() {
+            return Ok(false);
+        }
+
+        engine.update(
+            self.time_step(),
+            &mut ControlFlow::Poll,
+            &mut 0.0,
+            Default::default(),
+        );
+        engine.render()?;
+
+        let frame = engine
+            .renderer
+            .as_mut()
+            .and_then(|renderer| renderer.read_scene_frame(self.scene))
+            .ok_or(CaptureError::NoFrame)?;
+        self.writer.write(&frame)?;
+
+        Ok(!self.is_finished())
+    }
+
+    /// Captures all remaining frames and finishes the capture.
+    pub fn run(mut self, engine: &mut Engine) -> Result<(), CaptureError> {
+        while self.capture_frame(engine)? {}
+        self.finish(engine)
+    }
+
+    /// Restores previous render target of the scene and finishes writing of the output.
+    pub fn finish(self, engine: &mut Engine) -> Result<(), CaptureError> {
+        if let Some(scene) = engine.scenes.try_get_mut(self.scene) {
+            scene.render_target = self.prev_render_target;
+        }
+        self.writer.finish()
+    }
+}
+
+/// Six faces of a cube map, captured by [`capture_cube_map`]. Every face is an image in the same
+/// orientation as a viewer inside the cube sees it. Faces are named in the same way as faces of
+/// [`SkyBox`]: left is `+X`, right is `-X`, top is `+Y`, bottom is `-Y`, front is `+Z` and back is
+/// `-Z`.
+#[derive(Clone, Debug)]
+pub struct CubeMapImages {
+    /// Left (`+X`) face.
+    pub left: RgbaImage,
+    /// Right (`-X`) face.
+    pub right: RgbaImage,
+    /// Top (`+Y`) face.
+    pub top: RgbaImage,
+    /// Bottom (`-Y`) face.
+    pub bottom: RgbaImage,
+    /// Front (`+Z`) face.
+    pub front: RgbaImage,
+    /// Back (`-Z`) face.
+    pub back: RgbaImage,
+}
+
+/// Look and up vectors for each face, in the order of [`CubeMapImages::faces`].
+fn cube_map_face_orientations() -> [(Vector3<f32>, Vector3<f32>); 6] {
+    [
+        (Vector3::x(), Vector3::y()),
+        (-Vector3::x(), Vector3::y()),
+        (Vector3::y(), -Vector3::z()),
+        (-Vector3::y(), Vector3::z()),
+        (Vector3::z(), Vector3::y()),
+        (-Vector3::z(), Vector3::y()),
+    ]
+}
+
+impl CubeMapImages {
+    /// Returns faces in the following order: left, right, top, bottom, front, back. It is the same
+    /// order as in [`SkyBox::textures`].
+    pub fn faces(&self) -> [&RgbaImage; 6] {
+        [
+            &self.left,
+            &self.right,
+            &self.top,
+            &self.bottom,
+            &self.front,
+            &self.back,
+        ]
+    }
+
+    /// Saves every face as `<prefix>_<face name>.<extension>` image in the given directory.
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        directory: P,
+        prefix: &str,
+        format: ImageFormat,
+    ) -> Result<(), CaptureError> {
+        std::fs::create_dir_all(directory.as_ref())?;
+        let extension = format.extensions_str().first().cloned().unwrap_or("img");
+        for (name, face) in ["left", "right", "top", "bottom", "front", "back"]
+            .iter()
+            .zip(self.faces())
+        {
+            face.save_with_format(
+                directory
+                    .as_ref()
+                    .join(format!("{}_{}.{}", prefix, name, extension)),
+                format,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns a color of the cube map in the given direction (nearest sample).
+    pub fn sample(&self, direction: Vector3<f32>) -> Rgba<u8> {
+        let faces = self.faces();
+
+        let mut best = 0;
+        let mut best_dot = f32::MIN;
+        for (i, (look, _)) in cube_map_face_orientations().iter().enumerate() {
+            let dot = look.dot(&direction);
+            if dot > best_dot {
+                best_dot = dot;
+                best = i;
+            }
+        }
+
+        let (look, up) = cube_map_face_orientations()[best];
+        let right = look.cross(&up);
+        let depth = direction.dot(&look).max(f32::EPSILON);
+        let u = (direction.dot(&right) / depth).clamp(-1.0, 1.0);
+        let v = (direction.dot(&up) / depth).clamp(-1.0, 1.0);
+
+        let face = faces[best];
+        let x = ((u + 1.0) * 0.5 * face.width() as f32) as u32;
+        let y = ((1.0 - v) * 0.5 * face.height() as f32) as u32;
+        *face.get_pixel(
+            x.min(face.width().saturating_sub(1)),
+            y.min(face.height().saturating_sub(1)),
+        )
+    }
+
+    /// Creates 360 degrees equirectangular panorama of the given size. Center of the panorama looks
+    /// at the front (`+Z`) face. Usually, width of the panorama is two times larger than its height.
+    pub fn to_equirectangular(&self, width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * std::f32::consts::PI;
+            let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * std::f32::consts::PI;
+            self.sample(Vector3::new(
+                -longitude.sin() * latitude.cos(),
+                latitude.sin(),
+                longitude.cos() * latitude.cos(),
+            ))
+        })
+    }
+
+    /// Creates a skybox from the faces. It could be used, for example, to bake distant parts of a
+    /// scene into a skybox.
+    pub fn to_skybox(&self) -> Result<SkyBox, SkyBoxError> {
+        let make_texture = |face: &RgbaImage| {
+            // Cube map faces are stored mirrored relative to what a viewer inside the cube sees.
+            let face = image::imageops::flip_horizontal(face);
+            Texture::from_bytes(
+                TextureKind::Rectangle {
+                    width: face.width(),
+                    height: face.height(),
+                },
+                TexturePixelKind::RGBA8,
+                face.into_raw(),
+                true,
+            )
+            .ok_or(SkyBoxError::UnableToBuildCubeMap)
+        };
+
+        SkyBoxBuilder {
+            left: Some(make_texture(&self.left)?),
+            right: Some(make_texture(&self.right)?),
+            top: Some(make_texture(&self.top)?),
+            bottom: Some(make_texture(&self.bottom)?),
+            front: Some(make_texture(&self.front)?),
+            back: Some(make_texture(&self.back)?),
+        }
+        .build()
+    }
+}
+
+/// Renders six faces of a cube map of the given size from the given point of the scene. Skybox of the
+/// first enabled camera of the scene is used for the capture. Other cameras of the scene are disabled
+/// during the capture. See [`CubeMapImages`] docs for more info about the result.
+pub fn capture_cube_map(
+    engine: &mut Engine,
+    scene: Handle<Scene>,
+    position: Vector3<f32>,
+    face_size: u32,
+) -> Result<CubeMapImages, CaptureError> {
+    let face_size = face_size.max(1);
+
+    let scene_ref = &mut engine.scenes[scene];
+
+    let mut disabled_cameras = Vec::new();
+    let mut skybox = None;
+    for (handle, node) in scene_ref.graph.pair_iter_mut() {
+        if let Some(camera) = node.cast_mut::<Camera>() {
+            if camera.is_enabled() {
+                if skybox.is_none() {
+                    skybox = camera.skybox_ref().cloned();
+                }
+                camera.set_enabled(false);
+                disabled_cameras.push(handle);
+            }
+        }
+    }
+
+    let mut camera_builder = CameraBuilder::new(
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(position)
+                .build(),
+        ),
+    )
+    .with_fov(std::f32::consts::FRAC_PI_2);
+    if let Some(skybox) = skybox {
+        camera_builder = camera_builder.with_skybox(skybox);
+    }
+    let camera = camera_builder.build(&mut scene_ref.graph);
+
+    let prev_render_target = scene_ref
+        .render_target
+        .replace(Texture::new_render_target(face_size, face_size));
+
+    let mut faces = Vec::with_capacity(6);
+    let result: Result<(), CaptureError> =
+        cube_map_face_orientations()
+            .iter()
+            .try_for_each(|(look, up)| {
+                let graph = &mut engine.scenes[scene].graph;
+                graph[camera]
+                    .local_transform_mut()
+                    .set_rotation(UnitQuaternion::face_towards(look, up));
+                graph.update_hierarchical_data();
+                graph[camera]
+                    .as_camera_mut()
+                    .calculate_matrices(Vector2::new(face_size as f32, face_size as f32));
+
+                engine.render()?;
+
+                faces.push(
+                    engine
+                        .renderer
+                        .as_mut()
+                        .and_then(|renderer| renderer.read_scene_frame(scene))
+                        .ok_or(CaptureError::NoFrame)?,
+                );
+
+                Ok(())
+            });
+
+    // Bring the scene back to its previous state.
+    let scene_ref = &mut engine.scenes[scene];
+    scene_ref.render_target = prev_render_target;
+    scene_ref.graph.remove_node(camera);
+    for handle in disabled_cameras {
+        scene_ref.graph[handle].as_camera_mut().set_enabled(true);
+    }
+
+    result?;
+
+    let mut faces = faces.into_iter();
+    let mut next = || faces.next().ok_or(CaptureError::NoFrame);
+    Ok(CubeMapImages {
+        left: next()?,
+        right: next()?,
+        top: next()?,
+        bottom: next()?,
+        front: next()?,
+        back: next()?,
+    })
+}
+
+/// Renders 360 degrees equirectangular panorama of the given size from the given point of the scene.
+/// Center of the panorama looks at `+Z` direction. See [`capture_cube_map`] for more info.
+pub fn capture_panorama(
+    engine: &mut Engine,
+    scene: Handle<Scene>,
+    position: Vector3<f32>,
+    width: u32,
+    height: u32,
+) -> Result<RgbaImage, CaptureError> {
+    // Quarter of the width gives roughly one-to-one texel density at the equator.
+    let face_size = (width / 4).max(height / 2).max(1);
+    capture_cube_map(engine, scene, position, face_size)
+        .map(|cube_map| cube_map.to_equirectangular(width, height))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        utils::capture::{CaptureOutput, CaptureSettings, CubeMapImages, FrameWriter},
+    };
+    use image::{ImageFormat, Rgba, RgbaImage};
+
+    #[test]
+    fn test_image_sequence() {
+        let directory = std::env::temp_dir().join("fyrox_capture_test");
+        let _ = std::fs::remove_dir_all(&directory);
+
+        let output = CaptureOutput::ImageSequence {
+            directory: directory.clone(),
+            prefix: "frame".to_string(),
+            format: ImageFormat::Png,
+        };
+
+        let mut writer = FrameWriter::new(&output, 4, 2, 30.0).unwrap();
+        writer
+            .write(&RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255])))
+            .unwrap();
+        // Mismatched frame must be resized.
+        writer
+            .write(&RgbaImage::from_pixel(8, 8, Rgba([0, 255, 0, 255])))
+            .unwrap();
+        assert_eq!(writer.written_frames(), 2);
+
+        let first = writer.frame_path(0).unwrap();
+        let second = writer.frame_path(1).unwrap();
+        assert_eq!(first, directory.join("frame000000.png"));
+        writer.finish().unwrap();
+
+        let first = image::open(first).unwrap().to_rgba8();
+        assert_eq!(first.dimensions(), (4, 2));
+        assert_eq!(first.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+
+        let second = image::open(second).unwrap().to_rgba8();
+        assert_eq!(second.dimensions(), (4, 2));
+        assert_eq!(second.get_pixel(3, 1), &Rgba([0, 255, 0, 255]));
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_settings() {
+        let settings = CaptureSettings::default()
+            .with_frame_rate(24.0)
+            .with_duration(2.5);
+        assert_eq!(settings.frame_count, 60);
+    }
+
+    #[test]
+    fn test_cube_map_to_panorama() {
+        let solid = |r, g, b| RgbaImage::from_pixel(8, 8, Rgba([r, g, b, 255]));
+
+        // Left half of the front face is red, right half is blue.
+        let mut front = solid(255, 0, 0);
+        for x in 4..8 {
+            for y in 0..8 {
+                front.put_pixel(x, y, Rgba([0, 0, 255, 255]));
+            }
+        }
+
+        let cube_map = CubeMapImages {
+            left: solid(10, 0, 0),
+            right: solid(20, 0, 0),
+            top: solid(30, 0, 0),
+            bottom: solid(40, 0, 0),
+            front,
+            back: solid(50, 0, 0),
+        };
+
+        // +X is on the left when looking at +Z.
+        assert_eq!(
+            cube_map.sample(Vector3::new(0.5, 0.0, 1.0)),
+            Rgba([255, 0, 0, 255])
+        );
+        assert_eq!(
+            cube_map.sample(Vector3::new(-0.5, 0.0, 1.0)),
+            Rgba([0, 0, 255, 255])
+        );
+        assert_eq!(cube_map.sample(Vector3::x()), Rgba([10, 0, 0, 255]));
+        assert_eq!(cube_map.sample(-Vector3::y()), Rgba([40, 0, 0, 255]));
+
+        let panorama = cube_map.to_equirectangular(64, 32);
+        assert_eq!(panorama.dimensions(), (64, 32));
+        assert_eq!(panorama.get_pixel(0, 16), &Rgba([50, 0, 0, 255]));
+        assert_eq!(panorama.get_pixel(16, 16), &Rgba([10, 0, 0, 255]));
+        assert_eq!(panorama.get_pixel(30, 16), &Rgba([255, 0, 0, 255]));
+        assert_eq!(panorama.get_pixel(33, 16), &Rgba([0, 0, 255, 255]));
+        assert_eq!(panorama.get_pixel(48, 16), &Rgba([20, 0, 0, 255]));
+        assert_eq!(panorama.get_pixel(10, 0), &Rgba([30, 0, 0, 255]));
+        assert_eq!(panorama.get_pixel(10, 31), &Rgba([40, 0, 0, 255]));
+
+        let skybox = cube_map.to_skybox().unwrap();
+        assert!(skybox.cubemap().is_some());
+    }
+}
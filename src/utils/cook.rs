@@ -0,0 +1,711 @@
+//! Cooking is a process of preparing scenes for shipping builds. Cooked scenes are stored in a
+//! packed archive (see [`crate::engine::resource_manager::archive`]) together with every resource
+//! they depend on. Cooking consists of the following steps:
+//!
+//! - Nodes, that are marked with [`CookSettings::editor_only_tag`], are removed with their
+//!   descendants. This could be used for helpers, that are needed only while editing a scene.
+//! - Static meshes are merged into batches - one mesh per unique combination of material and
+//!   rendering options. Geometry of batches is pre-transformed to world space and stored in the scene
+//!   as is, so the engine does not need to process source models to get it. See
+//!   [`batch_static_geometry`] for more info.
+//! - Textures are compressed and stored in DDS format with all their mip levels, so there is no need
+//!   to decode, compress and generate mips for them at runtime.
+//! - Current asset registry (see [`crate::asset::registry`]) is stored in the archive, so references
+//!   to resources could be resolved by their GUIDs.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! use fyrox::{
+//!     core::futures::executor::block_on,
+//!     engine::{resource_manager::archive::ArchiveWriter, SerializationContext},
+//!     utils::cook::{cook_scene, CookSettings},
+//! };
+//! use std::sync::Arc;
+//!
+//! let mut archive = ArchiveWriter::new();
+//! let statistics = block_on(cook_scene(
+//!     "data/level1.rgs",
+//!     &mut archive,
+//!     &CookSettings::default(),
+//!     Arc::new(SerializationContext::new()),
+//! ))
+//! .unwrap();
+//! println!("{:?}", statistics);
+//! archive.save("data.pak").unwrap();
+//! ```
+//!
+//! # Important notes
+//!
+//! Batched meshes are left in the scene without surfaces, so scripts could still access them, but
+//! changing their visibility or transform won't affect the batches. Only visible static meshes without
+//! scripts, LODs, skinning and lightmaps are batched.
+//!
+//! Files are collected by recording every file, that was read while the scene was loading, so the
+//! cooker temporarily replaces current resource IO (see [`crate::core::io::set_resource_io`]).
+
+use crate::{
+    asset::{
+        registry::{asset_registry, ASSET_REGISTRY_PATH},
+        ResourceData, ResourceState,
+    },
+    core::{
+        futures::executor::block_on,
+        io::{self, FileLoadError, ResourceIo},
+        math::TriangleDefinition,
+        parking_lot::Mutex,
+        pool::Handle,
+        visitor::{VisitError, Visitor},
+    },
+    engine::{
+        resource_manager::{
+            archive::ArchiveWriter, options::try_get_import_settings, ResourceManager,
+        },
+        SerializationContext,
+    },
+    material::SharedMaterial,
+    resource::texture::{
+        CompressionOptions, Texture, TextureData, TextureImportOptions, TextureKind,
+        TexturePixelKind,
+    },
+    scene::{
+        base::{BaseBuilder, Mobility},
+        mesh::{
+            buffer::{TriangleBuffer, VertexAttributeDescriptor, VertexBuffer},
+            surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+            Mesh, MeshBuilder, RenderPath,
+        },
+        node::Node,
+        Scene, SceneLoader,
+    },
+    utils::log::Log,
+};
+use ddsfile::{D3DFormat, Dds, NewD3dParams};
+use fxhash::{FxHashMap, FxHasher64};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Cooking settings.
+#[derive(Clone, Debug)]
+pub struct CookSettings {
+    /// Nodes with this tag are removed from cooked scenes (with their descendants).
+    pub editor_only_tag: String,
+    /// Whether static meshes should be merged into batches or not.
+    pub batch_static_geometry: bool,
+    /// Whether uncompressed textures (except normal maps) should be compressed or not. Textures, that
+    /// are already compressed by their import options, are stored in DDS format regardless of this
+    /// flag.
+    pub compress_textures: bool,
+}
+
+impl Default for CookSettings {
+    fn default() -> Self {
+        Self {
+            editor_only_tag: "EditorOnly".to_string(),
+            batch_static_geometry: true,
+            compress_textures: true,
+        }
+    }
+}
+
+/// Statistics of a cooked scene.
+#[derive(Clone, Debug, Default)]
+pub struct CookStatistics {
+    /// Amount of nodes, that were removed as editor-only.
+    pub stripped_nodes: usize,
+    /// Amount of meshes, that were merged into batches.
+    pub batched_meshes: usize,
+    /// Amount of textures, that were stored in DDS format.
+    pub compressed_textures: usize,
+    /// Amount of files, that were added to the archive.
+    pub files: usize,
+}
+
+/// An error that may occur during cooking.
+#[derive(Debug)]
+pub enum CookError {
+    /// Scene cannot be loaded or saved.
+    Visit(VisitError),
+    /// A file cannot be read.
+    FileLoad(FileLoadError),
+    /// A path of a scene cannot be stored in the archive (only relative paths inside of the working
+    /// directory are allowed).
+    InvalidPath(PathBuf),
+}
+
+impl Display for CookError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CookError::Visit(v) => {
+                write!(f, "Unable to load or save the scene. Reason: {v}")
+            }
+            CookError::FileLoad(v) => {
+                write!(f, "Unable to read a file. Reason: {v:?}")
+            }
+            CookError::InvalidPath(v) => {
+                write!(f, "Path {} cannot be stored in the archive.", v.display())
+            }
+        }
+    }
+}
+
+impl From<VisitError> for CookError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+impl From<FileLoadError> for CookError {
+    fn from(e: FileLoadError) -> Self {
+        Self::FileLoad(e)
+    }
+}
+
+// Resource IO, that records paths of every file, that was successfully read.
+struct RecordingResourceIo {
+    inner: Option<Arc<dyn ResourceIo>>,
+    files: Mutex<BTreeSet<PathBuf>>,
+}
+
+impl ResourceIo for RecordingResourceIo {
+    fn load_file(&self, path: &Path) -> Result<Vec<u8>, FileLoadError> {
+        let data = match self.inner.as_ref() {
+            Some(inner) => inner.load_file(path)?,
+            None => std::fs::read(path)?,
+        };
+        self.files.lock().insert(path.to_path_buf());
+        Ok(data)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.inner.as_ref() {
+            Some(inner) => inner.exists(path),
+            None => path.exists(),
+        }
+    }
+}
+
+/// Removes every node with the given tag (with its descendants) from the scene. Returns amount of
+/// removed nodes.
+pub fn strip_editor_only_nodes(scene: &mut Scene, tag: &str) -> usize {
+    let node_count = scene.graph.node_count();
+
+    let editor_only = scene
+        .graph
+        .pair_iter()
+        .filter_map(|(handle, node)| {
+            if node.tag() == tag {
+                Some(handle)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for handle in editor_only {
+        // The node could be removed already as a descendant of another editor-only node.
+        if scene.graph.is_valid_handle(handle) {
+            scene.graph.remove_node(handle);
+        }
+    }
+
+    (node_count - scene.graph.node_count()) as usize
+}
+
+#[derive(PartialOrd, Ord, PartialEq, Eq)]
+struct BatchKey {
+    material: u64,
+    layout: u64,
+    render_path: RenderPath,
+    decal_layer_index: u8,
+    cast_shadows: bool,
+}
+
+fn layout_hash(vertex_buffer: &VertexBuffer) -> u64 {
+    let mut hasher = FxHasher64::default();
+    for attribute in vertex_buffer.layout() {
+        attribute.usage.hash(&mut hasher);
+        attribute.data_type.hash(&mut hasher);
+        attribute.size.hash(&mut hasher);
+        attribute.divisor.hash(&mut hasher);
+        attribute.offset.hash(&mut hasher);
+        attribute.shader_location.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn is_batchable(scene: &Scene, handle: Handle<Node>, mesh: &Mesh) -> bool {
+    mesh.mobility() == Mobility::Static
+        && mesh.global_visibility()
+        && mesh.is_globally_enabled()
+        && mesh.script().is_none()
+        && mesh.lod_group().is_none()
+        && mesh.visibility_opacity() >= 1.0
+        && mesh.depth_offset_factor() == 0.0
+        && mesh.property_block().is_empty()
+        && !mesh.surfaces().is_empty()
+        && mesh.surfaces().iter().all(|s| s.bones().is_empty())
+        && scene
+            .lightmap()
+            .map_or(true, |lightmap| !lightmap.map.contains_key(&handle))
+}
+
+fn merge(parts: &[SurfaceData]) -> Option<SurfaceData> {
+    let layout = parts
+        .first()?
+        .vertex_buffer
+        .layout()
+        .iter()
+        .map(|attribute| VertexAttributeDescriptor {
+            usage: attribute.usage,
+            data_type: attribute.data_type,
+            size: attribute.size,
+            divisor: attribute.divisor,
+            shader_location: attribute.shader_location,
+        })
+        .collect::<Vec<_>>();
+
+    let mut bytes = Vec::new();
+    let mut triangles = Vec::new();
+    let mut vertex_count = 0;
+    for part in parts {
+        bytes.extend_from_slice(part.vertex_buffer.raw_data());
+        triangles.extend(part.geometry_buffer.iter().map(|triangle| {
+            TriangleDefinition([
+                triangle.0[0] + vertex_count,
+                triangle.0[1] + vertex_count,
+                triangle.0[2] + vertex_count,
+            ])
+        }));
+        vertex_count += part.vertex_buffer.vertex_count();
+    }
+
+    let vertex_buffer = VertexBuffer::new(vertex_count as usize, &layout, bytes).ok()?;
+
+    Some(SurfaceData::new(
+        vertex_buffer,
+        TriangleBuffer::new(triangles),
+        true,
+    ))
+}
+
+/// Merges static meshes of the scene into batches - one mesh per unique combination of material,
+/// vertex layout and rendering options. Geometry of batches is stored in world space. Merged meshes
+/// are left in the scene, but their surfaces are removed. Returns amount of merged meshes.
+///
+/// Only visible and enabled meshes with [`Mobility::Static`] are merged, except meshes with scripts,
+/// LODs, skinning, opacity, depth offset, material property overrides and lightmaps.
+pub fn batch_static_geometry(scene: &mut Scene) -> usize {
+    scene.graph.update_hierarchical_data();
+
+    let mut batches = BTreeMap::<BatchKey, (SharedMaterial, Vec<SurfaceData>)>::new();
+    let mut merged_meshes = Vec::new();
+
+    for (handle, node) in scene.graph.pair_iter() {
+        let mesh = match node.cast::<Mesh>() {
+            Some(mesh) if is_batchable(scene, handle, mesh) => mesh,
+            _ => continue,
+        };
+
+        let transform = mesh.global_transform();
+
+        let mut parts = Vec::with_capacity(mesh.surfaces().len());
+        for surface in mesh.surfaces() {
+            let mut data = surface.data().lock().clone();
+            // Geometry without normals or tangents cannot be transformed, so the mesh is left as is.
+            if data.transform_geometry(&transform).is_err() {
+                break;
+            }
+            let key = BatchKey {
+                material: surface.material_id(),
+                layout: layout_hash(&data.vertex_buffer),
+                render_path: mesh.render_path(),
+                decal_layer_index: mesh.decal_layer_index(),
+                cast_shadows: mesh.cast_shadows(),
+            };
+            parts.push((key, surface.material().clone(), data));
+        }
+
+        if parts.len() != mesh.surfaces().len() {
+            continue;
+        }
+
+        for (key, material, data) in parts {
+            batches
+                .entry(key)
+                .or_insert_with(|| (material, Vec::new()))
+                .1
+                .push(data);
+        }
+
+        merged_meshes.push(handle);
+    }
+
+    for (index, (key, (material, parts))) in batches.into_iter().enumerate() {
+        if let Some(data) = merge(&parts) {
+            MeshBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("StaticBatch{}", index))
+                    .with_mobility(Mobility::Static)
+                    .with_cast_shadows(key.cast_shadows),
+            )
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(data))
+                .with_material(material)
+                .build()])
+            .with_render_path(key.render_path)
+            .with_decal_layer_index(key.decal_layer_index)
+            .build(&mut scene.graph);
+        }
+    }
+
+    for &handle in merged_meshes.iter() {
+        if let Some(mesh) = scene.graph[handle].cast_mut::<Mesh>() {
+            mesh.set_surfaces(Vec::new());
+        }
+    }
+
+    merged_meshes.len()
+}
+
+fn make_dds(texture: &TextureData) -> Option<Vec<u8>> {
+    let format = match texture.pixel_kind() {
+        TexturePixelKind::DXT1RGB | TexturePixelKind::DXT1RGBA => D3DFormat::DXT1,
+        TexturePixelKind::DXT3RGBA => D3DFormat::DXT3,
+        TexturePixelKind::DXT5RGBA => D3DFormat::DXT5,
+        _ => return None,
+    };
+
+    let (width, height) = match texture.kind() {
+        TextureKind::Rectangle { width, height } => (width, height),
+        _ => return None,
+    };
+
+    let mut dds = Dds::new_d3d(NewD3dParams {
+        height,
+        width,
+        depth: None,
+        format,
+        mipmap_levels: if texture.mip_count() > 1 {
+            Some(texture.mip_count())
+        } else {
+            None
+        },
+        caps2: None,
+    })
+    .ok()?;
+    dds.data = texture.data().to_vec();
+
+    let mut bytes = Vec::new();
+    dds.write(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+// Returns DDS version of the texture if the texture is (or could be) compressed.
+async fn cook_texture(texture: &Texture, settings: &CookSettings) -> Option<(PathBuf, Vec<u8>)> {
+    let path = {
+        let state = texture.state();
+        let data = match *state {
+            ResourceState::Ok(ref data) => data,
+            _ => return None,
+        };
+
+        let path = data.path().to_path_buf();
+
+        if let Some(dds) = make_dds(data) {
+            return Some((path, dds));
+        }
+
+        let is_compressible = settings.compress_textures
+            && !data.is_normal_map()
+            && matches!(
+                data.pixel_kind(),
+                TexturePixelKind::RGB8 | TexturePixelKind::RGBA8
+            )
+            && matches!(data.kind(), TextureKind::Rectangle { .. });
+        if !is_compressible {
+            return None;
+        }
+
+        path
+    };
+
+    let mut options = try_get_import_settings::<TextureImportOptions>(&path)
+        .await
+        .unwrap_or_default();
+    options.compression = CompressionOptions::Quality;
+
+    let data = TextureData::load_from_file(&path, &options).await.ok()?;
+    make_dds(&data).map(|dds| (path, dds))
+}
+
+async fn wait_for_resources(resource_manager: &ResourceManager) {
+    // Loaded resources could request other resources, so wait until there is nothing to load.
+    while resource_manager.state().count_pending_resources() > 0 {
+        let textures = resource_manager
+            .state()
+            .containers()
+            .textures
+            .wait_concurrent();
+        textures.await;
+        let models = resource_manager
+            .state()
+            .containers()
+            .models
+            .wait_concurrent();
+        models.await;
+        let sound_buffers = resource_manager
+            .state()
+            .containers()
+            .sound_buffers
+            .wait_concurrent();
+        sound_buffers.await;
+        let shaders = resource_manager
+            .state()
+            .containers()
+            .shaders
+            .wait_concurrent();
+        shaders.await;
+        let curves = resource_manager
+            .state()
+            .containers()
+            .curves
+            .wait_concurrent();
+        curves.await;
+        let sound_events = resource_manager
+            .state()
+            .containers()
+            .sound_events
+            .wait_concurrent();
+        sound_events.await;
+        let script_sources = resource_manager
+            .state()
+            .containers()
+            .script_sources
+            .wait_concurrent();
+        script_sources.await;
+        let visual_scripts = resource_manager
+            .state()
+            .containers()
+            .visual_scripts
+            .wait_concurrent();
+        visual_scripts.await;
+    }
+}
+
+async fn cook_scene_internal(
+    scene_path: &Path,
+    archive: &mut ArchiveWriter,
+    settings: &CookSettings,
+    serialization_context: Arc<SerializationContext>,
+    recorder: &RecordingResourceIo,
+) -> Result<CookStatistics, CookError> {
+    let mut statistics = CookStatistics::default();
+
+    // Fresh resource manager forces every resource to be loaded (and recorded) again.
+    let resource_manager = ResourceManager::new(serialization_context.clone());
+
+    let mut scene = SceneLoader::from_file(scene_path, serialization_context)
+        .await?
+        .finish(resource_manager.clone())
+        .await;
+
+    wait_for_resources(&resource_manager).await;
+
+    statistics.stripped_nodes = strip_editor_only_nodes(&mut scene, &settings.editor_only_tag);
+
+    if settings.batch_static_geometry {
+        statistics.batched_meshes = batch_static_geometry(&mut scene);
+    }
+
+    let mut visitor = Visitor::new();
+    scene.save("Scene", &mut visitor)?;
+    let scene_data = visitor.save_binary_to_vec()?;
+
+    let files = std::mem::take(&mut *recorder.files.lock());
+
+    let mut cooked_textures = FxHashMap::default();
+    let textures = resource_manager.state().containers().textures.resources();
+    for texture in textures.iter() {
+        if let Some((path, dds)) = cook_texture(texture, settings).await {
+            cooked_textures.insert(path, dds);
+        }
+    }
+
+    for path in files.iter() {
+        if path == scene_path {
+            continue;
+        }
+
+        let data = match cooked_textures.remove(path) {
+            Some(dds) => {
+                statistics.compressed_textures += 1;
+                dds
+            }
+            None => recorder.load_file(path)?,
+        };
+
+        if archive.add_file(path, data) {
+            statistics.files += 1;
+        } else {
+            Log::warn(format!(
+                "File {} cannot be stored in the archive, it will be loaded from the file system.",
+                path.display()
+            ));
+        }
+    }
+
+    if !archive.add_file(scene_path, scene_data) {
+        return Err(CookError::InvalidPath(scene_path.to_path_buf()));
+    }
+    statistics.files += 1;
+
+    // Cooked scenes keep GUIDs of their resources, so the registry is needed to resolve them.
+    if let Some(registry) = asset_registry().as_ref() {
+        archive.add_file(ASSET_REGISTRY_PATH, registry.to_string().into_bytes());
+    }
+
+    Ok(statistics)
+}
+
+/// Cooks a scene at the given path and adds it with every file it depends on to the archive. See
+/// module docs for more info. Scene path should be relative to the working directory, the scene is
+/// stored in the archive under the same path.
+pub async fn cook_scene<P: AsRef<Path>>(
+    scene_path: P,
+    archive: &mut ArchiveWriter,
+    settings: &CookSettings,
+    serialization_context: Arc<SerializationContext>,
+) -> Result<CookStatistics, CookError> {
+    let previous_io = io::resource_io();
+
+    let recorder = Arc::new(RecordingResourceIo {
+        inner: previous_io.clone(),
+        files: Default::default(),
+    });
+    io::set_resource_io(Some(recorder.clone()));
+
+    let result = cook_scene_internal(
+        scene_path.as_ref(),
+        archive,
+        settings,
+        serialization_context,
+        &recorder,
+    )
+    .await;
+
+    io::set_resource_io(previous_io);
+
+    result
+}
+
+/// Blocking version of [`cook_scene`].
+pub fn cook_scene_blocking<P: AsRef<Path>>(
+    scene_path: P,
+    archive: &mut ArchiveWriter,
+    settings: &CookSettings,
+    serialization_context: Arc<SerializationContext>,
+) -> Result<CookStatistics, CookError> {
+    block_on(cook_scene(
+        scene_path,
+        archive,
+        settings,
+        serialization_context,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Vector3},
+        material::{Material, SharedMaterial},
+        scene::{
+            base::{BaseBuilder, Mobility},
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                Mesh, MeshBuilder,
+            },
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+            Scene,
+        },
+        utils::cook::{batch_static_geometry, strip_editor_only_nodes},
+    };
+
+    #[test]
+    fn test_strip_editor_only_nodes() {
+        let mut scene = Scene::new();
+
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let helper = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_tag("EditorOnly".to_string())
+                .with_children(&[child]),
+        )
+        .build(&mut scene.graph);
+        let regular = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+
+        assert_eq!(strip_editor_only_nodes(&mut scene, "EditorOnly"), 2);
+        assert!(!scene.graph.is_valid_handle(helper));
+        assert!(!scene.graph.is_valid_handle(child));
+        assert!(scene.graph.is_valid_handle(regular));
+    }
+
+    #[test]
+    fn test_batch_static_geometry() {
+        let mut scene = Scene::new();
+
+        let material = SharedMaterial::new(Material::standard());
+
+        let mut make_cube = |position: Vector3<f32>, mobility: Mobility| {
+            MeshBuilder::new(
+                BaseBuilder::new()
+                    .with_mobility(mobility)
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(position)
+                            .build(),
+                    ),
+            )
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .with_material(material.clone())
+            .build()])
+            .build(&mut scene.graph)
+        };
+        let a = make_cube(Vector3::new(-2.0, 0.0, 0.0), Mobility::Static);
+        let b = make_cube(Vector3::new(2.0, 0.0, 0.0), Mobility::Static);
+        let dynamic = make_cube(Vector3::new(0.0, 2.0, 0.0), Mobility::Dynamic);
+
+        let cube_vertex_count = SurfaceData::make_cube(Matrix4::identity())
+            .vertex_buffer
+            .vertex_count();
+
+        assert_eq!(batch_static_geometry(&mut scene), 2);
+
+        assert!(scene.graph[a].as_mesh().surfaces().is_empty());
+        assert!(scene.graph[b].as_mesh().surfaces().is_empty());
+        assert_eq!(scene.graph[dynamic].as_mesh().surfaces().len(), 1);
+
+        let batches = scene
+            .graph
+            .linear_iter()
+            .filter_map(|n| n.cast::<Mesh>())
+            .filter(|m| m.name().starts_with("StaticBatch"))
+            .collect::<Vec<_>>();
+        assert_eq!(batches.len(), 1);
+
+        let batch = batches[0];
+        assert_eq!(batch.surfaces().len(), 1);
+        let data = batch.surfaces()[0].data();
+        let data = data.lock();
+        assert!(data.is_procedural());
+        assert_eq!(data.vertex_buffer.vertex_count(), 2 * cube_vertex_count);
+        assert_eq!(data.geometry_buffer.len(), 24);
+    }
+}
@@ -0,0 +1,526 @@
+//! Immediate-mode debug overlay for quick tuning of game parameters. See [`DebugUi`] docs for more
+//! info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{algebra::Vector2, color::Color, math::Rect, parking_lot::Mutex},
+    gui::{
+        brush::Brush,
+        draw::{CommandTexture, Draw, DrawingContext},
+        formatted_text::FormattedTextBuilder,
+        message::{ButtonState, MouseButton, OsEvent},
+        ttf::SharedFont,
+        VerticalAlignment,
+    },
+};
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+const ORIGIN_X: f32 = 10.0;
+const ORIGIN_Y: f32 = 10.0;
+const WIDTH: f32 = 280.0;
+const PADDING: f32 = 4.0;
+const SPACING: f32 = 2.0;
+const ROW_HEIGHT: f32 = 20.0;
+const GRAPH_HEIGHT: f32 = 48.0;
+const CHECK_BOX_SIZE: f32 = 14.0;
+const LABEL_FRACTION: f32 = 0.4;
+
+const BACKGROUND_COLOR: Color = Color::from_rgba(20, 20, 20, 200);
+const WIDGET_COLOR: Color = Color::opaque(60, 60, 60);
+const ACCENT_COLOR: Color = Color::opaque(80, 160, 230);
+const TEXT_COLOR: Color = Color::WHITE;
+
+enum Primitive {
+    Rect {
+        rect: Rect<f32>,
+        color: Color,
+    },
+    Frame {
+        rect: Rect<f32>,
+        color: Color,
+    },
+    Polyline {
+        points: Vec<Vector2<f32>>,
+        color: Color,
+    },
+    Text {
+        rect: Rect<f32>,
+        text: String,
+    },
+}
+
+struct State {
+    visible: bool,
+    cursor_position: Vector2<f32>,
+    mouse_down: bool,
+    mouse_pressed: bool,
+    press_position: Vector2<f32>,
+    active_slider: Option<String>,
+    cursor_y: f32,
+    primitives: Vec<Primitive>,
+}
+
+impl State {
+    fn allocate(&mut self, height: f32) -> Rect<f32> {
+        let rect = Rect::new(
+            ORIGIN_X + PADDING,
+            self.cursor_y,
+            WIDTH - 2.0 * PADDING,
+            height,
+        );
+        self.cursor_y += height + SPACING;
+        rect
+    }
+
+    fn panel_bounds(&self) -> Rect<f32> {
+        Rect::new(
+            ORIGIN_X,
+            ORIGIN_Y,
+            WIDTH,
+            self.cursor_y - SPACING + PADDING - ORIGIN_Y,
+        )
+    }
+
+    fn text(&mut self, rect: Rect<f32>, text: String) {
+        self.primitives.push(Primitive::Text { rect, text });
+    }
+
+    fn is_clicked(&mut self, rect: &Rect<f32>) -> bool {
+        if self.mouse_pressed && rect.contains(self.press_position) {
+            // Consume the click, so overlapping widgets won't react on it twice.
+            self.mouse_pressed = false;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Immediate-mode debug overlay. Unlike the retained-mode [`crate::gui::UserInterface`], it does
+/// not store any widgets - every widget must be "declared" every frame by calling respective method
+/// and the method itself returns the result of an interaction. This makes the overlay very handy for
+/// quick tuning of game parameters directly from scripts and plugins, without building any UI.
+///
+/// The overlay is drawn on top of everything (including the user interface) as a vertical panel in
+/// the top-left corner of the screen. The engine clears the overlay before each update, so widgets
+/// must be declared in `on_update` methods of scripts or plugins.
+///
+/// Clones share the same panel, so widgets declared by different scripts and plugins are drawn
+/// together.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::utils::debug_ui::DebugUi;
+///
+/// struct Player {
+///     speed: f32,
+///     god_mode: bool,
+///     speed_history: Vec<f32>,
+/// }
+///
+/// impl Player {
+///     fn tune(&mut self, debug_ui: &DebugUi) {
+///         debug_ui.text(format!("Speed: {:.2}", self.speed));
+///         debug_ui.slider("Max Speed", &mut self.speed, 0.0, 10.0);
+///         if debug_ui.checkbox("God Mode", &mut self.god_mode) {
+///             println!("God mode is {}", self.god_mode);
+///         }
+///         debug_ui.graph("Speed History", &self.speed_history);
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct DebugUi {
+    state: Arc<Mutex<State>>,
+}
+
+impl Debug for DebugUi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        f.debug_struct("DebugUi")
+            .field("visible", &state.visible)
+            .field("primitives", &state.primitives.len())
+            .finish()
+    }
+}
+
+impl Default for DebugUi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugUi {
+    /// Creates new visible debug overlay.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State {
+                visible: true,
+                cursor_position: Default::default(),
+                mouse_down: false,
+                mouse_pressed: false,
+                press_position: Default::default(),
+                active_slider: None,
+                cursor_y: ORIGIN_Y + PADDING,
+                primitives: Default::default(),
+            })),
+        }
+    }
+
+    /// Shows or hides the overlay. Widgets of hidden overlay do not react on input.
+    pub fn set_visible(&self, visible: bool) {
+        let mut state = self.state.lock();
+        state.visible = visible;
+        if !visible {
+            state.primitives.clear();
+            state.active_slider = None;
+        }
+    }
+
+    /// Returns `true` if the overlay is visible.
+    pub fn is_visible(&self) -> bool {
+        self.state.lock().visible
+    }
+
+    /// Adds a line of text.
+    pub fn text<S: AsRef<str>>(&self, text: S) {
+        let mut state = self.state.lock();
+        if state.visible {
+            let rect = state.allocate(ROW_HEIGHT);
+            state.text(rect, text.as_ref().to_owned());
+        }
+    }
+
+    /// Adds a check box with the given label. Returns `true` if the value was changed by a click
+    /// on the check box.
+    pub fn checkbox<S: AsRef<str>>(&self, label: S, value: &mut bool) -> bool {
+        let mut state = self.state.lock();
+        if !state.visible {
+            return false;
+        }
+
+        let rect = state.allocate(ROW_HEIGHT);
+        let changed = state.is_clicked(&rect);
+        if changed {
+            *value = !*value;
+        }
+
+        let check_box = Rect::new(
+            rect.x(),
+            rect.y() + (rect.h() - CHECK_BOX_SIZE) * 0.5,
+            CHECK_BOX_SIZE,
+            CHECK_BOX_SIZE,
+        );
+        state.primitives.push(Primitive::Rect {
+            rect: check_box,
+            color: WIDGET_COLOR,
+        });
+        if *value {
+            state.primitives.push(Primitive::Rect {
+                rect: check_box.deflate(3.0, 3.0),
+                color: ACCENT_COLOR,
+            });
+        }
+        state.text(
+            Rect::new(
+                rect.x() + CHECK_BOX_SIZE + PADDING,
+                rect.y(),
+                rect.w() - CHECK_BOX_SIZE - PADDING,
+                rect.h(),
+            ),
+            label.as_ref().to_owned(),
+        );
+
+        changed
+    }
+
+    /// Adds a horizontal slider with the given label, the value can be changed by dragging the
+    /// slider within `[min; max]` range. Returns `true` if the value was changed. The label is
+    /// used to identify the slider across frames, so it must be unique.
+    pub fn slider<S: AsRef<str>>(&self, label: S, value: &mut f32, min: f32, max: f32) -> bool {
+        let mut state = self.state.lock();
+        if !state.visible {
+            return false;
+        }
+
+        let label = label.as_ref();
+        let rect = state.allocate(ROW_HEIGHT);
+        let label_width = rect.w() * LABEL_FRACTION;
+        let bar = Rect::new(
+            rect.x() + label_width,
+            rect.y() + 2.0,
+            rect.w() - label_width,
+            rect.h() - 4.0,
+        );
+
+        if state.is_clicked(&bar) {
+            state.active_slider = Some(label.to_owned());
+        }
+
+        let mut changed = false;
+        if state.mouse_down && state.active_slider.as_deref() == Some(label) && bar.w() > 0.0 {
+            let t = ((state.cursor_position.x - bar.x()) / bar.w()).clamp(0.0, 1.0);
+            let new_value = min + (max - min) * t;
+            if new_value != *value {
+                *value = new_value;
+                changed = true;
+            }
+        }
+
+        let t = if max > min {
+            ((*value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        state.text(
+            Rect::new(rect.x(), rect.y(), label_width, rect.h()),
+            label.to_owned(),
+        );
+        state.primitives.push(Primitive::Rect {
+            rect: bar,
+            color: WIDGET_COLOR,
+        });
+        state.primitives.push(Primitive::Rect {
+            rect: Rect::new(bar.x(), bar.y(), bar.w() * t, bar.h()),
+            color: ACCENT_COLOR,
+        });
+        state.text(
+            bar.translate(Vector2::new(PADDING, 0.0)),
+            format!("{:.3}", *value),
+        );
+
+        changed
+    }
+
+    /// Adds a graph of the given values with a label. The graph is scaled automatically to fit
+    /// every value.
+    pub fn graph<S: AsRef<str>>(&self, label: S, values: &[f32]) {
+        let mut state = self.state.lock();
+        if !state.visible {
+            return;
+        }
+
+        let label = match values.last() {
+            Some(last) => format!("{}: {:.3}", label.as_ref(), last),
+            None => label.as_ref().to_owned(),
+        };
+        let label_rect = state.allocate(ROW_HEIGHT);
+        state.text(label_rect, label);
+
+        let rect = state.allocate(GRAPH_HEIGHT);
+        state.primitives.push(Primitive::Frame {
+            rect,
+            color: WIDGET_COLOR,
+        });
+
+        if values.len() > 1 {
+            let (mut min, mut max) = values.iter().fold((f32::MAX, f32::MIN), |(min, max), &v| {
+                (min.min(v), max.max(v))
+            });
+            if max - min <= f32::EPSILON {
+                min -= 0.5;
+                max += 0.5;
+            }
+
+            let step = rect.w() / (values.len() - 1) as f32;
+            let points = values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| {
+                    Vector2::new(
+                        rect.x() + i as f32 * step,
+                        rect.y() + rect.h() * (1.0 - (v - min) / (max - min)),
+                    )
+                })
+                .collect();
+            state.primitives.push(Primitive::Polyline {
+                points,
+                color: ACCENT_COLOR,
+            });
+        }
+    }
+
+    /// Passes an OS event to the overlay, so its widgets could react on mouse input. It is called
+    /// by [`crate::engine::executor::Executor`] automatically, if you have your own game loop, you
+    /// should call it manually.
+    pub fn process_os_event(&self, event: &OsEvent) {
+        let mut state = self.state.lock();
+        match *event {
+            OsEvent::CursorMoved { position } => state.cursor_position = position,
+            OsEvent::MouseInput {
+                button: MouseButton::Left,
+                state: button_state,
+            } => match button_state {
+                ButtonState::Pressed => {
+                    state.mouse_down = true;
+                    state.mouse_pressed = true;
+                    state.press_position = state.cursor_position;
+                }
+                ButtonState::Released => {
+                    state.mouse_down = false;
+                    state.active_slider = None;
+                }
+            },
+            _ => (),
+        }
+    }
+
+    /// Returns `true` if the mouse cursor is above the overlay. It could be used to prevent game
+    /// logic from reacting on clicks on the overlay.
+    pub fn is_cursor_over(&self) -> bool {
+        let state = self.state.lock();
+        state.visible
+            && !state.primitives.is_empty()
+            && state.panel_bounds().contains(state.cursor_position)
+    }
+
+    pub(crate) fn begin_frame(&self) {
+        let mut state = self.state.lock();
+        state.primitives.clear();
+        state.cursor_y = ORIGIN_Y + PADDING;
+    }
+
+    pub(crate) fn end_frame(&self) {
+        self.state.lock().mouse_pressed = false;
+    }
+
+    pub(crate) fn draw(
+        &self,
+        drawing_context: &mut DrawingContext,
+        font: SharedFont,
+        screen_size: Vector2<f32>,
+    ) {
+        let state = self.state.lock();
+        if !state.visible || state.primitives.is_empty() {
+            return;
+        }
+
+        let clip_bounds = Rect::new(0.0, 0.0, screen_size.x, screen_size.y);
+
+        drawing_context.push_rect_filled(&state.panel_bounds(), None);
+        drawing_context.commit(
+            clip_bounds,
+            Brush::Solid(BACKGROUND_COLOR),
+            CommandTexture::None,
+            None,
+        );
+
+        for primitive in state.primitives.iter() {
+            match primitive {
+                Primitive::Rect { rect, color } => {
+                    drawing_context.push_rect_filled(rect, None);
+                    drawing_context.commit(
+                        clip_bounds,
+                        Brush::Solid(*color),
+                        CommandTexture::None,
+                        None,
+                    );
+                }
+                Primitive::Frame { rect, color } => {
+                    drawing_context.push_rect(rect, 1.0);
+                    drawing_context.commit(
+                        clip_bounds,
+                        Brush::Solid(*color),
+                        CommandTexture::None,
+                        None,
+                    );
+                }
+                Primitive::Polyline { points, color } => {
+                    for segment in points.windows(2) {
+                        drawing_context.push_line(segment[0], segment[1], 1.5);
+                    }
+                    drawing_context.commit(
+                        clip_bounds,
+                        Brush::Solid(*color),
+                        CommandTexture::None,
+                        None,
+                    );
+                }
+                Primitive::Text { rect, text } => {
+                    let mut formatted_text = FormattedTextBuilder::new(font.clone())
+                        .with_text(text.clone())
+                        .with_constraint(rect.size)
+                        .with_vertical_alignment(VerticalAlignment::Center)
+                        .with_brush(Brush::Solid(TEXT_COLOR))
+                        .build();
+                    formatted_text.build();
+                    drawing_context.draw_text(*rect, rect.position, &formatted_text);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector2,
+        gui::message::{ButtonState, MouseButton, OsEvent},
+        utils::debug_ui::DebugUi,
+    };
+
+    fn click(debug_ui: &DebugUi, position: Vector2<f32>, state: ButtonState) {
+        debug_ui.process_os_event(&OsEvent::CursorMoved { position });
+        debug_ui.process_os_event(&OsEvent::MouseInput {
+            button: MouseButton::Left,
+            state,
+        });
+    }
+
+    #[test]
+    fn test_debug_ui_widgets() {
+        let debug_ui = DebugUi::new();
+
+        let mut flag = false;
+        let mut value = 0.0;
+
+        // Nothing is pressed - nothing changes.
+        debug_ui.begin_frame();
+        assert!(!debug_ui.checkbox("Flag", &mut flag));
+        assert!(!debug_ui.slider("Value", &mut value, 0.0, 10.0));
+        debug_ui.end_frame();
+        assert!(!flag);
+
+        // First row is the check box.
+        click(&debug_ui, Vector2::new(20.0, 20.0), ButtonState::Pressed);
+        debug_ui.begin_frame();
+        assert!(debug_ui.checkbox("Flag", &mut flag));
+        assert!(!debug_ui.slider("Value", &mut value, 0.0, 10.0));
+        debug_ui.end_frame();
+        assert!(flag);
+        assert!(debug_ui.is_cursor_over());
+
+        // The click must be processed only once.
+        debug_ui.begin_frame();
+        assert!(!debug_ui.checkbox("Flag", &mut flag));
+        debug_ui.end_frame();
+        assert!(flag);
+        click(&debug_ui, Vector2::new(20.0, 20.0), ButtonState::Released);
+
+        // Second row is the slider, drag it to the right end.
+        click(&debug_ui, Vector2::new(200.0, 40.0), ButtonState::Pressed);
+        debug_ui.process_os_event(&OsEvent::CursorMoved {
+            position: Vector2::new(1000.0, 40.0),
+        });
+        debug_ui.begin_frame();
+        debug_ui.checkbox("Flag", &mut flag);
+        assert!(debug_ui.slider("Value", &mut value, 0.0, 10.0));
+        debug_ui.end_frame();
+        assert_eq!(value, 10.0);
+
+        // Hidden overlay ignores input.
+        debug_ui.set_visible(false);
+        click(&debug_ui, Vector2::new(20.0, 20.0), ButtonState::Pressed);
+        debug_ui.begin_frame();
+        assert!(!debug_ui.checkbox("Flag", &mut flag));
+        debug_ui.end_frame();
+        assert!(flag);
+        assert!(!debug_ui.is_cursor_over());
+    }
+}
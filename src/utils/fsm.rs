@@ -0,0 +1,332 @@
+//! Generic finite state machine for game logic (AI, game flow, etc.). See [`LogicMachine`] docs for more info.
+
+use crate::{
+    animation::machine::{Machine, Parameter, State},
+    core::{
+        pool::Handle,
+        visitor::{Visit, VisitError, VisitResult, Visitor},
+    },
+};
+use fxhash::FxHashMap;
+use std::{
+    fmt::{Debug, Formatter},
+    path::Path,
+};
+
+/// Recommended extension for files with logic machine definitions.
+pub const LOGIC_MACHINE_EXTENSION: &str = "fsm";
+
+/// Logic of a state of [`LogicMachine`]. Every method is optional, `C` is a user-defined context that is
+/// passed to every method (for example it could be a script context or a plugin context).
+pub trait LogicState<C> {
+    /// The method is called when the state becomes active.
+    fn on_enter(&mut self, #[allow(unused_variables)] context: &mut C) {}
+
+    /// The method is called on every update of the machine while the state is active.
+    fn on_update(
+        &mut self,
+        #[allow(unused_variables)] context: &mut C,
+        #[allow(unused_variables)] dt: f32,
+    ) {
+    }
+
+    /// The method is called when the state becomes inactive.
+    fn on_leave(&mut self, #[allow(unused_variables)] context: &mut C) {}
+}
+
+/// Logic machine is a finite state machine that drives arbitrary logic states, it is mostly used for AI and
+/// game flow (main menu, loading, gameplay, etc.).
+///
+/// # Definition
+///
+/// The machine uses the same graph as the animation blending state machine ([`Machine`]), which means that it
+/// could be made in the ABSM editor and then exported to a file (see [`LOGIC_MACHINE_EXTENSION`]). Pose nodes
+/// of states are not used, so they could be left empty. Each layer of the definition is a separate state graph
+/// with its own active state, all layers are updated in parallel.
+///
+/// Transitions are instant, they're activated by `Rule` parameters in the same way as in the animation blending
+/// state machine. Transition time is ignored. At most one transition per layer is performed on each update.
+///
+/// # Logic
+///
+/// Logic of the states is bound by state names using [`LogicMachine::bind`]. States without bound logic are
+/// still valid, they just do nothing.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     animation::machine::{Machine, Parameter, State, Transition},
+///     core::pool::Handle,
+///     utils::fsm::{LogicMachine, LogicState},
+/// };
+///
+/// struct Bot {
+///     speed: f32,
+/// }
+///
+/// struct Patrol;
+///
+/// impl LogicState<Bot> for Patrol {
+///     fn on_enter(&mut self, bot: &mut Bot) {
+///         bot.speed = 1.0;
+///     }
+/// }
+///
+/// struct Chase;
+///
+/// impl LogicState<Bot> for Chase {
+///     fn on_update(&mut self, bot: &mut Bot, dt: f32) {
+///         bot.speed += dt;
+///     }
+/// }
+///
+/// let mut definition = Machine::new();
+/// let layer = &mut definition.layers_mut()[0];
+/// let patrol = layer.add_state(State::new("Patrol", Handle::NONE));
+/// let chase = layer.add_state(State::new("Chase", Handle::NONE));
+/// layer.add_transition(Transition::new("Patrol->Chase", patrol, chase, 0.0, "SeePlayer"));
+/// layer.set_entry_state(patrol);
+///
+/// let mut machine = LogicMachine::new(definition);
+/// machine.bind("Patrol", Patrol).bind("Chase", Chase);
+///
+/// let mut bot = Bot { speed: 0.0 };
+/// machine.update(&mut bot, 0.1);
+/// assert_eq!(bot.speed, 1.0);
+///
+/// machine
+///     .definition_mut()
+///     .set_parameter("SeePlayer", Parameter::Rule(true));
+/// machine.update(&mut bot, 0.1);
+/// assert_eq!(machine.active_state_name(0), Some("Chase"));
+/// ```
+pub struct LogicMachine<C> {
+    definition: Machine,
+    active_states: Vec<Handle<State>>,
+    logic: FxHashMap<String, Box<dyn LogicState<C>>>,
+}
+
+impl<C> Debug for LogicMachine<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogicMachine")
+            .field("definition", &self.definition)
+            .field("active_states", &self.active_states)
+            .finish()
+    }
+}
+
+impl<C> LogicMachine<C> {
+    /// Creates a new logic machine using the given definition.
+    pub fn new(definition: Machine) -> Self {
+        Self {
+            active_states: vec![Handle::NONE; definition.layers().len()],
+            definition,
+            logic: Default::default(),
+        }
+    }
+
+    /// Loads a definition of the machine from the given file and creates a new logic machine.
+    pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, VisitError> {
+        let mut visitor = Visitor::load_binary(path).await?;
+        let mut definition = Machine::default();
+        definition.visit("Machine", &mut visitor)?;
+        Ok(Self::new(definition))
+    }
+
+    /// Saves the given definition to a file, so it could be loaded later using [`Self::from_file`].
+    pub fn save_definition<P: AsRef<Path>>(definition: &Machine, path: P) -> VisitResult {
+        let mut visitor = Visitor::new();
+        definition.clone().visit("Machine", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+
+    /// Binds logic to every state with the given name (in every layer). Previously bound logic will be
+    /// replaced. The method returns a reference to the machine, so the calls could be chained.
+    pub fn bind<S, L>(&mut self, state_name: S, logic: L) -> &mut Self
+    where
+        S: AsRef<str>,
+        L: LogicState<C> + 'static,
+    {
+        self.logic
+            .insert(state_name.as_ref().to_owned(), Box::new(logic));
+        self
+    }
+
+    /// Returns a reference to the definition of the machine.
+    pub fn definition(&self) -> &Machine {
+        &self.definition
+    }
+
+    /// Returns a reference to the definition of the machine. It could be used to modify parameters of the
+    /// machine.
+    pub fn definition_mut(&mut self) -> &mut Machine {
+        &mut self.definition
+    }
+
+    /// Returns a handle of the active state of the given layer.
+    pub fn active_state(&self, layer_index: usize) -> Handle<State> {
+        self.active_states
+            .get(layer_index)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns a name of the active state of the given layer.
+    pub fn active_state_name(&self, layer_index: usize) -> Option<&str> {
+        self.definition
+            .layers()
+            .get(layer_index)
+            .and_then(|layer| layer.states().try_borrow(self.active_state(layer_index)))
+            .map(|state| state.name.as_str())
+    }
+
+    fn state_logic(
+        &mut self,
+        layer_index: usize,
+        state: Handle<State>,
+    ) -> Option<&mut Box<dyn LogicState<C>>> {
+        let name = &self
+            .definition
+            .layers()
+            .get(layer_index)?
+            .states()
+            .try_borrow(state)?
+            .name;
+        self.logic.get_mut(name)
+    }
+
+    /// Deactivates every active state (calling [`LogicState::on_leave`]), so the entry states will be entered
+    /// again on the next update.
+    pub fn reset(&mut self, context: &mut C) {
+        for layer_index in 0..self.active_states.len() {
+            let active = std::mem::take(&mut self.active_states[layer_index]);
+            if let Some(logic) = self.state_logic(layer_index, active) {
+                logic.on_leave(context);
+            }
+        }
+    }
+
+    /// Updates the machine. Enters entry states on the first update, checks transitions of the active states
+    /// and updates logic of the active states.
+    pub fn update(&mut self, context: &mut C, dt: f32) {
+        self.active_states
+            .resize(self.definition.layers().len(), Handle::NONE);
+
+        for layer_index in 0..self.active_states.len() {
+            let layer = &self.definition.layers()[layer_index];
+            let active = self.active_states[layer_index];
+
+            let next = if layer.states().is_valid_handle(active) {
+                layer
+                    .transitions()
+                    .iter()
+                    .find(|transition| {
+                        transition.source() == active
+                            && transition.dest() != active
+                            && matches!(
+                                self.definition.parameters().get(transition.rule()),
+                                Some(Parameter::Rule(rule)) if *rule != transition.is_invert_rule()
+                            )
+                    })
+                    .map(|transition| transition.dest())
+            } else if layer.states().is_valid_handle(layer.entry_state()) {
+                Some(layer.entry_state())
+            } else {
+                None
+            };
+
+            if let Some(next) = next {
+                if let Some(logic) = self.state_logic(layer_index, active) {
+                    logic.on_leave(context);
+                }
+                self.active_states[layer_index] = next;
+                if let Some(logic) = self.state_logic(layer_index, next) {
+                    logic.on_enter(context);
+                }
+            }
+
+            let active = self.active_states[layer_index];
+            if let Some(logic) = self.state_logic(layer_index, active) {
+                logic.on_update(context, dt);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        animation::machine::{Machine, Parameter, State, Transition},
+        core::pool::Handle,
+        utils::fsm::{LogicMachine, LogicState},
+    };
+
+    struct Logger(&'static str);
+
+    impl LogicState<Vec<String>> for Logger {
+        fn on_enter(&mut self, log: &mut Vec<String>) {
+            log.push(format!("enter {}", self.0));
+        }
+
+        fn on_update(&mut self, log: &mut Vec<String>, _dt: f32) {
+            log.push(format!("update {}", self.0));
+        }
+
+        fn on_leave(&mut self, log: &mut Vec<String>) {
+            log.push(format!("leave {}", self.0));
+        }
+    }
+
+    #[test]
+    fn test_logic_machine() {
+        let mut definition = Machine::new();
+        let layer = &mut definition.layers_mut()[0];
+        let idle = layer.add_state(State::new("Idle", Handle::NONE));
+        let attack = layer.add_state(State::new("Attack", Handle::NONE));
+        layer.add_transition(Transition::new("Idle->Attack", idle, attack, 1.0, "Attack"));
+        let mut back = Transition::new("Attack->Idle", attack, idle, 1.0, "Attack");
+        back.set_invert_rule(true);
+        layer.add_transition(back);
+        layer.set_entry_state(idle);
+        definition.set_parameter("Attack", Parameter::Rule(false));
+
+        let mut machine = LogicMachine::new(definition);
+        machine
+            .bind("Idle", Logger("idle"))
+            .bind("Attack", Logger("attack"));
+
+        let mut log = Vec::new();
+        machine.update(&mut log, 0.1);
+        assert_eq!(log, ["enter idle", "update idle"]);
+        assert_eq!(machine.active_state(0), idle);
+
+        log.clear();
+        machine
+            .definition_mut()
+            .set_parameter("Attack", Parameter::Rule(true));
+        machine.update(&mut log, 0.1);
+        machine.update(&mut log, 0.1);
+        assert_eq!(
+            log,
+            [
+                "leave idle",
+                "enter attack",
+                "update attack",
+                "update attack"
+            ]
+        );
+
+        log.clear();
+        machine
+            .definition_mut()
+            .set_parameter("Attack", Parameter::Rule(false));
+        machine.update(&mut log, 0.1);
+        assert_eq!(log, ["leave attack", "enter idle", "update idle"]);
+
+        log.clear();
+        machine.reset(&mut log);
+        assert_eq!(log, ["leave idle"]);
+        assert_eq!(machine.active_state_name(0), None);
+    }
+}
@@ -0,0 +1,476 @@
+//! Impact effects - decals and particles, that are spawned at hit points (bullet holes, footsteps,
+//! etc.). See [`ImpactEffectTable`] docs for more info.
+
+use crate::{
+    asset::ResourceState,
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        color::Color,
+        futures::executor::block_on,
+        pool::Handle,
+        rand::{thread_rng, Rng},
+        visitor::{prelude::*, VisitError},
+    },
+    engine::resource_manager::ResourceManager,
+    resource::{model::Model, texture::Texture},
+    scene::{
+        base::BaseBuilder, collider::Collider, decal::DecalBuilder,
+        graph::raycast::GeometryIntersection, graph::Graph, mesh::Mesh, node::Node,
+        transform::TransformBuilder, Scene,
+    },
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// Describes how a surface responds to a hit: which decal should be placed and which particle effect
+/// should be spawned.
+#[derive(Clone, Debug, Visit)]
+pub struct ImpactEffect {
+    /// A path to the diffuse texture of the decal. Decal will not be spawned if there is no texture.
+    pub decal_diffuse_texture: Option<PathBuf>,
+    /// A path to the normal texture of the decal.
+    pub decal_normal_texture: Option<PathBuf>,
+    /// Color of the decal.
+    pub decal_color: Color,
+    /// Width and height of the decal.
+    pub decal_size: f32,
+    /// Depth of the decal (along the normal of the surface). Small values prevent the decal to be
+    /// projected on nearby surfaces.
+    pub decal_depth: f32,
+    /// Lifetime of the decal in seconds. `None` - the decal will live forever.
+    pub decal_lifetime: Option<f32>,
+    /// Whether the decal should be randomly rotated around the normal of the surface or not.
+    pub random_rotation: bool,
+    /// A path to a particle effect prefab. The prefab will be instantiated at the hit point, its
+    /// `Y` axis will be aligned with the normal of the surface.
+    pub particles: Option<PathBuf>,
+    /// Lifetime of the particle effect in seconds. `None` - the effect will live forever.
+    pub particles_lifetime: Option<f32>,
+
+    #[visit(skip)]
+    decal_diffuse_texture_resource: Option<Texture>,
+    #[visit(skip)]
+    decal_normal_texture_resource: Option<Texture>,
+    #[visit(skip)]
+    particles_resource: Option<Model>,
+}
+
+impl Default for ImpactEffect {
+    fn default() -> Self {
+        Self {
+            decal_diffuse_texture: None,
+            decal_normal_texture: None,
+            decal_color: Color::WHITE,
+            decal_size: 0.2,
+            decal_depth: 0.1,
+            decal_lifetime: Some(30.0),
+            random_rotation: true,
+            particles: None,
+            particles_lifetime: Some(5.0),
+            decal_diffuse_texture_resource: None,
+            decal_normal_texture_resource: None,
+            particles_resource: None,
+        }
+    }
+}
+
+impl ImpactEffect {
+    /// Sets a path to the diffuse texture of the decal.
+    pub fn with_decal_diffuse_texture<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.decal_diffuse_texture = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets a path to the normal texture of the decal.
+    pub fn with_decal_normal_texture<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.decal_normal_texture = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets color of the decal.
+    pub fn with_decal_color(mut self, color: Color) -> Self {
+        self.decal_color = color;
+        self
+    }
+
+    /// Sets size of the decal.
+    pub fn with_decal_size(mut self, size: f32) -> Self {
+        self.decal_size = size;
+        self
+    }
+
+    /// Sets lifetime of the decal.
+    pub fn with_decal_lifetime(mut self, lifetime: Option<f32>) -> Self {
+        self.decal_lifetime = lifetime;
+        self
+    }
+
+    /// Sets a path to the particle effect prefab.
+    pub fn with_particles<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.particles = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets lifetime of the particle effect.
+    pub fn with_particles_lifetime(mut self, lifetime: Option<f32>) -> Self {
+        self.particles_lifetime = lifetime;
+        self
+    }
+
+    fn resolve(&mut self, resource_manager: &ResourceManager) {
+        self.decal_diffuse_texture_resource = self
+            .decal_diffuse_texture
+            .as_ref()
+            .map(|path| resource_manager.request_texture(path));
+        self.decal_normal_texture_resource = self
+            .decal_normal_texture
+            .as_ref()
+            .map(|path| resource_manager.request_texture(path));
+        self.particles_resource = self
+            .particles
+            .as_ref()
+            .map(|path| resource_manager.request_model(path));
+    }
+}
+
+/// Nodes, that were spawned by [`ImpactEffectTable::spawn`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpawnedImpact {
+    /// A handle of the decal. Could be [`Handle::NONE`] if the effect has no decal.
+    pub decal: Handle<Node>,
+    /// A handle of the root of the particle effect. Could be [`Handle::NONE`] if the effect has no
+    /// particles or the prefab is not loaded yet.
+    pub particles: Handle<Node>,
+}
+
+/// A table, that maps surface types to impact effects. Surface type of a node is defined by surface
+/// type of a collider (see [`Collider::surface_type`]), that is either the node itself, its child
+/// or a child of its closest ancestor with such collider. Usually a mesh and its collider are
+/// children of the same rigid body. If there is no effect for a surface type, the default effect
+/// is used.
+///
+/// The table is meant to be configured per project and stored in a file, use [`Self::save`] and
+/// [`Self::load`] for that.
+///
+/// ## Example
+///
+/// ```no_run
+/// use fyrox::{
+///     core::algebra::{Point3, Vector3},
+///     engine::resource_manager::ResourceManager,
+///     scene::{graph::raycast::GeometryRayCastOptions, Scene},
+///     utils::impact::{ImpactEffect, ImpactEffectTable},
+/// };
+///
+/// fn shoot(scene: &mut Scene, table: &ImpactEffectTable, origin: Point3<f32>, dir: Vector3<f32>) {
+///     let mut hits = Vec::new();
+///     scene.graph.cast_ray_geometry(
+///         GeometryRayCastOptions {
+///             ray_origin: origin,
+///             ray_direction: dir,
+///             max_len: 100.0,
+///             ignore_back_faces: true,
+///             ignore_invisible: true,
+///             sort_results: true,
+///         },
+///         &mut hits,
+///     );
+///     if let Some(hit) = hits.first() {
+///         table.spawn(scene, hit);
+///     }
+/// }
+///
+/// fn make_table(resource_manager: &ResourceManager) -> ImpactEffectTable {
+///     let mut table = ImpactEffectTable::default();
+///     table.set_effect(
+///         "metal",
+///         ImpactEffect::default()
+///             .with_decal_diffuse_texture("data/decals/metal_hole.png")
+///             .with_particles("data/effects/sparks.rgs"),
+///     );
+///     table.resolve(resource_manager);
+///     table
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Visit)]
+pub struct ImpactEffectTable {
+    effects: HashMap<String, ImpactEffect>,
+    default_effect: Option<ImpactEffect>,
+}
+
+impl ImpactEffectTable {
+    /// Sets an effect for the given surface type.
+    pub fn set_effect<S: AsRef<str>>(&mut self, surface_type: S, effect: ImpactEffect) {
+        self.effects
+            .insert(surface_type.as_ref().to_owned(), effect);
+    }
+
+    /// Removes an effect of the given surface type.
+    pub fn remove_effect(&mut self, surface_type: &str) -> Option<ImpactEffect> {
+        self.effects.remove(surface_type)
+    }
+
+    /// Sets an effect, that will be used for surfaces without their own effect.
+    pub fn set_default_effect(&mut self, effect: Option<ImpactEffect>) {
+        self.default_effect = effect;
+    }
+
+    /// Returns an effect for the given surface type, or the default effect if there is no such.
+    pub fn effect(&self, surface_type: &str) -> Option<&ImpactEffect> {
+        self.effects
+            .get(surface_type)
+            .or(self.default_effect.as_ref())
+    }
+
+    /// Requests every resource (textures and prefabs) of every effect. Must be called after the table
+    /// was loaded or modified, otherwise effects will not have resources to spawn.
+    pub fn resolve(&mut self, resource_manager: &ResourceManager) {
+        for effect in self
+            .effects
+            .values_mut()
+            .chain(self.default_effect.as_mut())
+        {
+            effect.resolve(resource_manager);
+        }
+    }
+
+    /// Returns surface type of the given node. See [`ImpactEffectTable`] docs for more info.
+    pub fn surface_type_of(graph: &Graph, node: Handle<Node>) -> &str {
+        let surface_type = move |handle: Handle<Node>| {
+            graph
+                .try_get(handle)
+                .and_then(|node| node.cast::<Collider>())
+                .map(|collider| collider.surface_type())
+                .filter(|surface_type| !surface_type.is_empty())
+        };
+
+        let mut current = node;
+        while let Some(node) = graph.try_get(current) {
+            if let Some(surface_type) = surface_type(current).or_else(|| {
+                node.children()
+                    .iter()
+                    .find_map(|&child| surface_type(child))
+            }) {
+                return surface_type;
+            }
+            current = node.parent();
+        }
+        ""
+    }
+
+    /// Spawns an effect for the given hit, surface type is taken from the node of the hit.
+    pub fn spawn(&self, scene: &mut Scene, hit: &GeometryIntersection) -> SpawnedImpact {
+        let surface_type = Self::surface_type_of(&scene.graph, hit.node).to_owned();
+        self.spawn_for_surface(scene, hit, &surface_type)
+    }
+
+    /// Spawns an effect of the given surface type for the given hit.
+    pub fn spawn_for_surface(
+        &self,
+        scene: &mut Scene,
+        hit: &GeometryIntersection,
+        surface_type: &str,
+    ) -> SpawnedImpact {
+        let effect = match self.effect(surface_type) {
+            Some(effect) => effect,
+            None => return Default::default(),
+        };
+
+        let mut orientation = UnitQuaternion::rotation_between(&Vector3::y(), &hit.normal)
+            .unwrap_or_else(|| {
+                // The normal is looking in opposite direction.
+                UnitQuaternion::from_axis_angle(&Vector3::x_axis(), std::f32::consts::PI)
+            });
+        if effect.random_rotation {
+            orientation *= UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                thread_rng().gen_range(0.0..std::f32::consts::TAU),
+            );
+        }
+
+        let mut spawned = SpawnedImpact::default();
+
+        if let Some(diffuse_texture) = effect.decal_diffuse_texture_resource.clone() {
+            // Apply the decal only on the surfaces with the same layer as the hit mesh.
+            let layer = scene
+                .graph
+                .try_get(hit.node)
+                .and_then(|node| node.query_component_ref::<Mesh>())
+                .map_or(0, |mesh| mesh.decal_layer_index());
+
+            let mut base_builder = BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(hit.position.coords)
+                    .with_local_rotation(orientation)
+                    .with_local_scale(Vector3::new(
+                        effect.decal_size,
+                        effect.decal_depth,
+                        effect.decal_size,
+                    ))
+                    .build(),
+            );
+            if let Some(lifetime) = effect.decal_lifetime {
+                base_builder = base_builder.with_lifetime(lifetime);
+            }
+
+            let mut decal_builder = DecalBuilder::new(base_builder)
+                .with_diffuse_texture(diffuse_texture)
+                .with_color(effect.decal_color)
+                .with_layer(layer);
+            if let Some(normal_texture) = effect.decal_normal_texture_resource.clone() {
+                decal_builder = decal_builder.with_normal_texture(normal_texture);
+            }
+
+            spawned.decal = decal_builder.build(&mut scene.graph);
+        }
+
+        if let Some(particles) = effect.particles_resource.as_ref() {
+            // Prefab could be still loading, skip particles in this case, it is better than stalling.
+            let is_loaded = matches!(*particles.state(), ResourceState::Ok(_));
+            if is_loaded {
+                let root = particles.instantiate(scene);
+                let node = &mut scene.graph[root];
+                node.local_transform_mut()
+                    .set_position(hit.position.coords)
+                    .set_rotation(orientation);
+                node.set_lifetime(effect.particles_lifetime);
+                spawned.particles = root;
+            }
+        }
+
+        spawned
+    }
+
+    /// Loads the table from the given file. Call [`Self::resolve`] after loading.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, VisitError> {
+        let mut table = Self::default();
+        let mut visitor = block_on(Visitor::load_binary(path))?;
+        table.visit("ImpactEffectTable", &mut visitor)?;
+        Ok(table)
+    }
+
+    /// Saves the table to the given file.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<(), VisitError> {
+        let mut visitor = Visitor::new();
+        self.visit("ImpactEffectTable", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Point3, Vector3},
+            pool::Handle,
+        },
+        resource::texture::Texture,
+        scene::{
+            base::BaseBuilder, collider::ColliderBuilder, decal::Decal,
+            graph::raycast::GeometryIntersection, pivot::PivotBuilder, rigidbody::RigidBodyBuilder,
+            Scene,
+        },
+        utils::impact::{ImpactEffect, ImpactEffectTable},
+    };
+
+    #[test]
+    fn test_spawn_decal() {
+        let mut scene = Scene::new();
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_surface_type("metal".to_string())
+            .build(&mut scene.graph);
+        let wall = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        scene.graph.link_nodes(child, wall);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider, wall]))
+            .build(&mut scene.graph);
+
+        assert_eq!(
+            ImpactEffectTable::surface_type_of(&scene.graph, collider),
+            "metal"
+        );
+        assert_eq!(
+            ImpactEffectTable::surface_type_of(&scene.graph, wall),
+            "metal"
+        );
+
+        assert_eq!(
+            ImpactEffectTable::surface_type_of(&scene.graph, child),
+            "metal"
+        );
+
+        let effect = ImpactEffect {
+            random_rotation: false,
+            decal_size: 0.5,
+            decal_diffuse_texture_resource: Some(Texture::new_render_target(1, 1)),
+            ..Default::default()
+        };
+
+        let mut table = ImpactEffectTable::default();
+        table.set_effect("metal", effect);
+
+        let hit = GeometryIntersection {
+            node: child,
+            surface: 0,
+            triangle: 0,
+            position: Point3::new(1.0, 2.0, 3.0),
+            normal: Vector3::x(),
+            toi: 1.0,
+        };
+
+        let spawned = table.spawn(&mut scene, &hit);
+        assert!(spawned.particles.is_none());
+
+        let decal = &scene.graph[spawned.decal];
+        assert!(decal.cast::<Decal>().is_some());
+        assert_eq!(
+            **decal.local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(
+            **decal.local_transform().scale(),
+            Vector3::new(0.5, 0.1, 0.5)
+        );
+        // Projection axis (Y) of the decal must be aligned with the normal.
+        let up = decal
+            .local_transform()
+            .rotation()
+            .transform_vector(&Vector3::y());
+        assert!((up - Vector3::x()).norm() < 0.001);
+
+        // There is no effect for unknown surfaces.
+        let spawned = table.spawn_for_surface(&mut scene, &hit, "wood");
+        assert!(spawned.decal.is_none() && spawned.particles == Handle::NONE);
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join("fyrox_impact_effects_test.bin");
+
+        let mut table = ImpactEffectTable::default();
+        table.set_effect(
+            "wood",
+            ImpactEffect::default()
+                .with_decal_diffuse_texture("wood_hole.png")
+                .with_decal_size(0.3),
+        );
+        table.set_default_effect(Some(ImpactEffect::default().with_particles("dust.rgs")));
+        table.save(&path).unwrap();
+
+        let loaded = ImpactEffectTable::load(&path).unwrap();
+        let wood = loaded.effect("wood").unwrap();
+        assert_eq!(
+            wood.decal_diffuse_texture.as_deref(),
+            Some(std::path::Path::new("wood_hole.png"))
+        );
+        assert_eq!(wood.decal_size, 0.3);
+        assert_eq!(
+            loaded.effect("stone").unwrap().particles.as_deref(),
+            Some(std::path::Path::new("dust.rgs"))
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,496 @@
+//! GPU backend of the lightmapper. Surfaces are rasterized in the lightmap space, so every fragment
+//! is a texel of a lightmap and its lighting is calculated in a fragment shader. Shadows are
+//! calculated using shadow maps, that are rendered once per light (or per cube face for point
+//! lights) and shared between every lightmap.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, Point3, Vector2, Vector3, Vector4},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, Rect},
+        sstorage::ImmutableString,
+    },
+    renderer::framework::{
+        error::FrameworkError,
+        framebuffer::{Attachment, AttachmentKind, DrawParameters, FrameBuffer},
+        geometry_buffer::{
+            AttributeDefinition, AttributeKind, BufferBuilder, ElementKind, GeometryBuffer,
+            GeometryBufferBuilder, GeometryBufferKind,
+        },
+        gpu_program::{GpuProgram, GpuProgramBinding, UniformLocation},
+        gpu_texture::{
+            Coordinate, GpuTexture, GpuTextureKind, MagnificationFilter, MinificationFilter,
+            PixelKind, WrapMode,
+        },
+        state::{ColorMask, PipelineState},
+    },
+    utils::lightmap::{
+        CancellationToken, Instance, LightDefinition, LightmapGenerationError, LightmapSettings,
+        ProgressIndicator, ProgressStage,
+    },
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// Depth bias for shadow map comparisons.
+const SHADOW_BIAS: f32 = 0.0005;
+
+#[repr(C)]
+struct BakeVertex {
+    position: Vector3<f32>,
+    normal: Vector3<f32>,
+    second_tex_coord: Vector2<f32>,
+}
+
+struct ShadowShader {
+    program: GpuProgram,
+    light_view_projection: UniformLocation,
+}
+
+impl ShadowShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/shadow_fs.glsl");
+        let vertex_source = include_str!("shaders/shadow_vs.glsl");
+        let program = GpuProgram::from_source(
+            state,
+            "LightmapShadowShader",
+            vertex_source,
+            fragment_source,
+        )?;
+        Ok(Self {
+            light_view_projection: program
+                .uniform_location(state, &ImmutableString::new("lightViewProjection"))?,
+            program,
+        })
+    }
+}
+
+struct LightShader {
+    program: GpuProgram,
+    light_kind: UniformLocation,
+    light_position: UniformLocation,
+    light_direction: UniformLocation,
+    light_color: UniformLocation,
+    light_sqr_radius: UniformLocation,
+    spot_edge0: UniformLocation,
+    spot_edge1: UniformLocation,
+    face_axis: UniformLocation,
+    use_face_axis: UniformLocation,
+    light_view_projection: UniformLocation,
+    shadow_map: UniformLocation,
+    shadow_map_inv_size: UniformLocation,
+    shadow_bias: UniformLocation,
+    normal_offset: UniformLocation,
+}
+
+impl LightShader {
+    fn new(state: &mut PipelineState) -> Result<Self, FrameworkError> {
+        let fragment_source = include_str!("shaders/light_fs.glsl");
+        let vertex_source = include_str!("shaders/light_vs.glsl");
+        let program =
+            GpuProgram::from_source(state, "LightmapLightShader", vertex_source, fragment_source)?;
+        Ok(Self {
+            light_kind: program.uniform_location(state, &ImmutableString::new("lightKind"))?,
+            light_position: program
+                .uniform_location(state, &ImmutableString::new("lightPosition"))?,
+            light_direction: program
+                .uniform_location(state, &ImmutableString::new("lightDirection"))?,
+            light_color: program.uniform_location(state, &ImmutableString::new("lightColor"))?,
+            light_sqr_radius: program
+                .uniform_location(state, &ImmutableString::new("lightSqrRadius"))?,
+            spot_edge0: program.uniform_location(state, &ImmutableString::new("spotEdge0"))?,
+            spot_edge1: program.uniform_location(state, &ImmutableString::new("spotEdge1"))?,
+            face_axis: program.uniform_location(state, &ImmutableString::new("faceAxis"))?,
+            use_face_axis: program.uniform_location(state, &ImmutableString::new("useFaceAxis"))?,
+            light_view_projection: program
+                .uniform_location(state, &ImmutableString::new("lightViewProjection"))?,
+            shadow_map: program.uniform_location(state, &ImmutableString::new("shadowMap"))?,
+            shadow_map_inv_size: program
+                .uniform_location(state, &ImmutableString::new("shadowMapInvSize"))?,
+            shadow_bias: program.uniform_location(state, &ImmutableString::new("shadowBias"))?,
+            normal_offset: program
+                .uniform_location(state, &ImmutableString::new("normalOffset"))?,
+            program,
+        })
+    }
+
+    fn bind(
+        &self,
+        program_binding: &mut GpuProgramBinding,
+        pass: &LightPass,
+        shadow_map: &Rc<RefCell<GpuTexture>>,
+        shadow_map_size: usize,
+        normal_offset: f32,
+    ) {
+        let (kind, position, direction, color, sqr_radius, edge0, edge1) = match pass.light {
+            LightDefinition::Directional(v) => (
+                0,
+                Vector3::default(),
+                v.direction,
+                v.color.scale(v.intensity),
+                1.0,
+                0.0,
+                0.0,
+            ),
+            LightDefinition::Spot(v) => (
+                1,
+                v.position,
+                v.direction,
+                v.color.scale(v.intensity),
+                v.sqr_distance,
+                v.edge0,
+                v.edge1,
+            ),
+            LightDefinition::Point(v) => (
+                2,
+                v.position,
+                Vector3::default(),
+                v.color.scale(v.intensity),
+                v.sqr_radius,
+                0.0,
+                0.0,
+            ),
+        };
+
+        program_binding
+            .set_i32(&self.light_kind, kind)
+            .set_vector3(&self.light_position, &position)
+            .set_vector3(&self.light_direction, &direction)
+            .set_vector3(&self.light_color, &color)
+            .set_f32(&self.light_sqr_radius, sqr_radius)
+            .set_f32(&self.spot_edge0, edge0)
+            .set_f32(&self.spot_edge1, edge1)
+            .set_vector3(&self.face_axis, &pass.face_axis.unwrap_or_default())
+            .set_bool(&self.use_face_axis, pass.face_axis.is_some())
+            .set_matrix4(&self.light_view_projection, &pass.view_projection)
+            .set_texture(&self.shadow_map, shadow_map)
+            .set_f32(&self.shadow_map_inv_size, 1.0 / shadow_map_size as f32)
+            .set_f32(&self.shadow_bias, SHADOW_BIAS)
+            .set_f32(&self.normal_offset, normal_offset);
+    }
+}
+
+/// A single shadow map of a light source.
+struct LightPass<'a> {
+    light: &'a LightDefinition,
+    view_projection: Matrix4<f32>,
+    /// Axis of a cube face of a point light.
+    face_axis: Option<Vector3<f32>>,
+}
+
+impl<'a> LightPass<'a> {
+    fn affects(&self, bounds: &AxisAlignedBoundingBox) -> bool {
+        match self.light {
+            LightDefinition::Directional(_) => true,
+            LightDefinition::Spot(v) => bounds.is_intersects_sphere(v.position, v.distance),
+            LightDefinition::Point(v) => bounds.is_intersects_sphere(v.position, v.radius),
+        }
+    }
+}
+
+fn look_at(eye: Vector3<f32>, direction: Vector3<f32>) -> Matrix4<f32> {
+    let up = if direction.y.abs() > 0.99 {
+        Vector3::z()
+    } else {
+        Vector3::y()
+    };
+    Matrix4::look_at_rh(&Point3::from(eye), &Point3::from(eye + direction), &up)
+}
+
+fn make_light_passes<'a>(
+    lights: &'a [LightDefinition],
+    scene_bounds: &AxisAlignedBoundingBox,
+) -> Vec<LightPass<'a>> {
+    let z_near = 0.01;
+    let mut passes = Vec::new();
+    for light in lights {
+        match light {
+            LightDefinition::Directional(v) => {
+                // Shadow map covers the whole scene.
+                let center = scene_bounds.center();
+                let radius = scene_bounds.half_extents().norm().max(z_near);
+                let view = look_at(center + v.direction.scale(radius), -v.direction);
+                let projection =
+                    Matrix4::new_orthographic(-radius, radius, -radius, radius, 0.0, 2.0 * radius);
+                passes.push(LightPass {
+                    light,
+                    view_projection: projection * view,
+                    face_axis: None,
+                });
+            }
+            LightDefinition::Spot(v) => {
+                let fov = (2.0 * v.edge0.clamp(-1.0, 1.0).acos()).clamp(0.01, 3.1);
+                let projection =
+                    Matrix4::new_perspective(1.0, fov, z_near, v.distance.max(2.0 * z_near));
+                passes.push(LightPass {
+                    light,
+                    view_projection: projection * look_at(v.position, -v.direction),
+                    face_axis: None,
+                });
+            }
+            LightDefinition::Point(v) => {
+                let projection = Matrix4::new_perspective(
+                    1.0,
+                    std::f32::consts::FRAC_PI_2,
+                    z_near,
+                    v.radius.max(2.0 * z_near),
+                );
+                for axis in [
+                    Vector3::x(),
+                    -Vector3::x(),
+                    Vector3::y(),
+                    -Vector3::y(),
+                    Vector3::z(),
+                    -Vector3::z(),
+                ] {
+                    passes.push(LightPass {
+                        light,
+                        view_projection: projection * look_at(v.position, axis),
+                        face_axis: Some(axis),
+                    });
+                }
+            }
+        }
+    }
+    passes
+}
+
+fn make_geometry(
+    state: &mut PipelineState,
+    instance: &Instance,
+) -> Result<GeometryBuffer, FrameworkError> {
+    let data = instance.data();
+
+    let vertices = data
+        .vertices
+        .iter()
+        .map(|v| BakeVertex {
+            position: v.world_position,
+            normal: v.world_normal,
+            second_tex_coord: v.second_tex_coord,
+        })
+        .collect::<Vec<_>>();
+
+    let geometry = GeometryBufferBuilder::new(ElementKind::Triangle)
+        .with_buffer_builder(
+            BufferBuilder::new(GeometryBufferKind::StaticDraw, Some(vertices.as_slice()))
+                .with_attribute(AttributeDefinition {
+                    location: 0,
+                    kind: AttributeKind::Float3,
+                    normalized: false,
+                    divisor: 0,
+                })
+                .with_attribute(AttributeDefinition {
+                    location: 1,
+                    kind: AttributeKind::Float3,
+                    normalized: false,
+                    divisor: 0,
+                })
+                .with_attribute(AttributeDefinition {
+                    location: 2,
+                    kind: AttributeKind::Float2,
+                    normalized: false,
+                    divisor: 0,
+                }),
+        )
+        .build(state)?;
+
+    geometry.bind(state).set_triangles(&data.triangles);
+
+    Ok(geometry)
+}
+
+fn make_shadow_framebuffer(
+    state: &mut PipelineState,
+    size: usize,
+) -> Result<FrameBuffer, FrameworkError> {
+    let mut depth = GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle {
+            width: size,
+            height: size,
+        },
+        PixelKind::D32F,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        None,
+    )?;
+    depth
+        .bind_mut(state, 0)
+        .set_wrap(Coordinate::T, WrapMode::ClampToEdge)
+        .set_wrap(Coordinate::S, WrapMode::ClampToEdge);
+
+    FrameBuffer::new(
+        state,
+        Some(Attachment {
+            kind: AttachmentKind::Depth,
+            texture: Rc::new(RefCell::new(depth)),
+        }),
+        vec![],
+    )
+}
+
+fn make_light_framebuffer(
+    state: &mut PipelineState,
+    size: usize,
+) -> Result<FrameBuffer, FrameworkError> {
+    let color = GpuTexture::new(
+        state,
+        GpuTextureKind::Rectangle {
+            width: size,
+            height: size,
+        },
+        PixelKind::RGBA16F,
+        MinificationFilter::Nearest,
+        MagnificationFilter::Nearest,
+        1,
+        None,
+    )?;
+
+    FrameBuffer::new(
+        state,
+        None,
+        vec![Attachment {
+            kind: AttachmentKind::Color,
+            texture: Rc::new(RefCell::new(color)),
+        }],
+    )
+}
+
+/// Calculates lighting of texels of lightmaps of given targets (pairs of instance index and atlas
+/// size). Returned texels have the same layout as the texels calculated by the CPU backend.
+pub(super) fn calculate_texels(
+    state: &mut PipelineState,
+    instances: &[Instance],
+    targets: &[(usize, u32)],
+    lights: &[LightDefinition],
+    settings: &LightmapSettings,
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<Vec<Vec<Vector4<f32>>>, LightmapGenerationError> {
+    let mut texels = targets
+        .iter()
+        .map(|&(_, atlas_size)| vec![Vector4::default(); (atlas_size * atlas_size) as usize])
+        .collect::<Vec<_>>();
+
+    if targets.is_empty() {
+        return Ok(texels);
+    }
+
+    // Every instance is an occluder, so geometry of the whole scene must be uploaded.
+    let geometry = instances
+        .iter()
+        .map(|instance| make_geometry(state, instance))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut scene_bounds = AxisAlignedBoundingBox::default();
+    for instance in instances {
+        scene_bounds.add_box(instance.data().bounds);
+    }
+
+    let passes = make_light_passes(lights, &scene_bounds);
+
+    progress_indicator.set_stage(
+        ProgressStage::CalculatingLight,
+        (passes.len() * targets.len()) as u32,
+    );
+
+    let shadow_shader = ShadowShader::new(state)?;
+    let light_shader = LightShader::new(state)?;
+
+    let shadow_map_size = settings.shadow_map_size.max(1);
+    let mut shadow_framebuffer = make_shadow_framebuffer(state, shadow_map_size)?;
+    let shadow_viewport = Rect::new(0, 0, shadow_map_size as i32, shadow_map_size as i32);
+    let shadow_draw_params = DrawParameters {
+        // Surfaces could be seen from both sides.
+        cull_face: None,
+        color_write: ColorMask::all(false),
+        depth_write: true,
+        stencil_test: None,
+        depth_test: true,
+        blend: None,
+        stencil_op: Default::default(),
+    };
+
+    let max_atlas_size = targets
+        .iter()
+        .map(|&(_, atlas_size)| atlas_size)
+        .max()
+        .unwrap_or(1);
+    let mut light_framebuffer = make_light_framebuffer(state, max_atlas_size as usize)?;
+    let light_draw_params = DrawParameters {
+        cull_face: None,
+        color_write: Default::default(),
+        depth_write: false,
+        stencil_test: None,
+        depth_test: false,
+        blend: None,
+        stencil_op: Default::default(),
+    };
+
+    for pass in passes.iter() {
+        if cancellation_token.is_cancelled() {
+            return Err(LightmapGenerationError::Cancelled);
+        }
+
+        shadow_framebuffer.clear(state, shadow_viewport, None, Some(1.0), None);
+        for geometry in geometry.iter() {
+            shadow_framebuffer.draw(
+                geometry,
+                state,
+                shadow_viewport,
+                &shadow_shader.program,
+                &shadow_draw_params,
+                |mut program_binding| {
+                    program_binding
+                        .set_matrix4(&shadow_shader.light_view_projection, &pass.view_projection);
+                },
+            );
+        }
+        let shadow_map = shadow_framebuffer
+            .depth_attachment()
+            .unwrap()
+            .texture
+            .clone();
+
+        for (&(index, atlas_size), texels) in targets.iter().zip(texels.iter_mut()) {
+            let instance = &instances[index];
+            if pass.affects(&instance.data().bounds) {
+                let viewport = Rect::new(0, 0, atlas_size as i32, atlas_size as i32);
+                // Roughly a half of a texel in world units.
+                let normal_offset =
+                    0.5 / (settings.texels_per_unit.max(1) as f32 * instance.texel_scale.max(0.01));
+
+                light_framebuffer.clear(state, viewport, Some(Color::TRANSPARENT), None, None);
+                light_framebuffer.draw(
+                    &geometry[index],
+                    state,
+                    viewport,
+                    &light_shader.program,
+                    &light_draw_params,
+                    |mut program_binding| {
+                        light_shader.bind(
+                            &mut program_binding,
+                            pass,
+                            &shadow_map,
+                            shadow_map_size,
+                            normal_offset,
+                        );
+                    },
+                );
+
+                let pixels = light_framebuffer.read_pixels_f32(state, viewport);
+                for (texel, pixel) in texels.iter_mut().zip(pixels.chunks_exact(4)) {
+                    texel.x += pixel[0];
+                    texel.y += pixel[1];
+                    texel.z += pixel[2];
+                    texel.w = texel.w.max(pixel[3]);
+                }
+            }
+
+            progress_indicator.advance_progress();
+        }
+    }
+
+    Ok(texels)
+}
@@ -2,7 +2,10 @@
 //!
 //! # Performance
 //!
-//! This is CPU lightmapper, its performance is linear with core count of your CPU.
+//! There are two backends (see [`LightmapBackend`]). CPU backend is a ray tracer, its performance
+//! is linear with core count of your CPU. GPU backend rasterizes surfaces and uses shadow maps, it
+//! is orders of magnitude faster, but precision of its shadows is limited by the size of shadow
+//! maps. Lightmaps could be re-baked incrementally - only for changed nodes, see [`Lightmap::rebake`].
 //!
 //! WARNING: There is still work-in-progress, so it is not advised to use lightmapper
 //! now!
@@ -14,12 +17,17 @@ use crate::{
     core::{
         algebra::{Matrix3, Matrix4, Point3, Vector2, Vector3, Vector4},
         arrayvec::ArrayVec,
-        math::{self, ray::Ray, Matrix4Ext, Rect, TriangleDefinition, Vector2Ext},
+        hash_combine,
+        math::{
+            self, aabb::AxisAlignedBoundingBox, ray::Ray, Matrix4Ext, Rect, TriangleDefinition,
+            Vector2Ext,
+        },
         octree::{Octree, OctreeNode},
         pool::Handle,
         visitor::{Visit, VisitResult, Visitor},
     },
     engine::resource_manager::{ResourceManager, TextureRegistrationError},
+    renderer::{framework::error::FrameworkError, Renderer},
     resource::texture::{Texture, TextureData, TextureKind, TexturePixelKind, TextureState},
     scene::{
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
@@ -33,10 +41,11 @@ use crate::{
     },
     utils::{uvgen, uvgen::SurfaceDataPatch},
 };
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet, FxHasher64};
 use rayon::prelude::*;
 use std::fmt::{Display, Formatter};
 use std::{
+    hash::{Hash, Hasher},
     ops::Deref,
     path::Path,
     sync::{
@@ -45,6 +54,11 @@ use std::{
     },
 };
 
+mod gpu;
+
+/// Max size of a lightmap texture, larger textures may not be supported by some GPUs.
+pub const MAX_LIGHTMAP_SIZE: u32 = 4096;
+
 ///
 #[derive(Default, Clone, Debug, Visit)]
 pub struct LightmapEntry {
@@ -70,6 +84,19 @@ pub struct Lightmap {
     /// List of surface data patches. Each patch will be applied to corresponding
     /// surface data on resolve stage.
     pub patches: FxHashMap<u64, SurfaceDataPatch>,
+
+    /// Fingerprints of baked nodes, they are used to find changed nodes on incremental re-bake.
+    #[visit(optional)]
+    fingerprints: FxHashMap<Handle<Node>, u64>,
+
+    /// Content hash of surface data with generated secondary texture coordinates to id of its patch
+    /// mapping. It prevents generating texture coordinates for the same data twice.
+    #[visit(optional)]
+    generated_data: FxHashMap<u64, u64>,
+
+    /// Fingerprint of light sources and settings, that were used to bake the lightmap.
+    #[visit(optional)]
+    lights_fingerprint: u64,
 }
 
 struct WorldVertex {
@@ -83,6 +110,8 @@ struct InstanceData {
     vertices: Vec<WorldVertex>,
     triangles: Vec<TriangleDefinition>,
     octree: Octree,
+    /// World-space bounds.
+    bounds: AxisAlignedBoundingBox,
 }
 
 struct Instance {
@@ -90,12 +119,23 @@ struct Instance {
     source_data: SurfaceSharedData,
     data: Option<InstanceData>,
     transform: Matrix4<f32>,
+    texel_scale: f32,
 }
 
 impl Instance {
     pub fn data(&self) -> &InstanceData {
         self.data.as_ref().unwrap()
     }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = FxHasher64::default();
+        for v in self.transform.iter() {
+            v.to_bits().hash(&mut hasher);
+        }
+        self.texel_scale.to_bits().hash(&mut hasher);
+        self.source_data.lock().content_hash().hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 /// Small helper that allows you stop lightmap generation in any time.
@@ -204,6 +244,8 @@ pub enum LightmapGenerationError {
     Cancelled,
     /// Vertex buffer of a mesh lacks required data.
     InvalidData(VertexFetchError),
+    /// An error of the GPU backend.
+    Gpu(FrameworkError),
 }
 
 impl Display for LightmapGenerationError {
@@ -215,6 +257,9 @@ impl Display for LightmapGenerationError {
             LightmapGenerationError::InvalidData(v) => {
                 write!(f, "Vertex buffer of a mesh lacks required data {v}.")
             }
+            LightmapGenerationError::Gpu(v) => {
+                write!(f, "GPU lightmapper failed. Reason: {v}")
+            }
         }
     }
 }
@@ -225,6 +270,62 @@ impl From<VertexFetchError> for LightmapGenerationError {
     }
 }
 
+impl From<FrameworkError> for LightmapGenerationError {
+    fn from(e: FrameworkError) -> Self {
+        Self::Gpu(e)
+    }
+}
+
+/// Backend of the lightmapper, it defines how lighting of lightmap texels is calculated.
+pub enum LightmapBackend<'a> {
+    /// Multithreaded CPU ray tracer. It produces precise shadows, but it is very slow on scenes
+    /// with lots of geometry.
+    Cpu,
+    /// GPU rasterizer, that calculates shadows using shadow maps (see [`LightmapSettings::shadow_map_size`]).
+    /// It is much faster than the CPU backend, but must be used on the main thread, because it
+    /// uses graphics context of the renderer.
+    Gpu(&'a mut Renderer),
+}
+
+/// Settings of lightmap generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightmapSettings {
+    /// Resolution of lightmaps - amount of texels per unit of world space. The higher value is,
+    /// the more quality lightmap will be generated, but also it will be slow to generate. It could
+    /// be adjusted per mesh, see [`Mesh::set_lightmap_texel_scale`].
+    pub texels_per_unit: u32,
+    /// Spacing between charts of secondary texture coordinates in `[0; 1]` range.
+    pub spacing: f32,
+    /// Whether to remove noise and aliasing of shadows using edge-preserving filter or not.
+    pub denoise: bool,
+    /// Radius of the denoising filter in texels.
+    pub denoise_radius: u32,
+    /// Size of shadow maps of the GPU backend. Larger shadow maps give more precise shadows.
+    pub shadow_map_size: usize,
+}
+
+impl Default for LightmapSettings {
+    fn default() -> Self {
+        Self {
+            texels_per_unit: 64,
+            spacing: 0.005,
+            denoise: true,
+            denoise_radius: 2,
+            shadow_map_size: 2048,
+        }
+    }
+}
+
+impl LightmapSettings {
+    fn fingerprint(&self, hasher: &mut FxHasher64) {
+        // Spacing is not included, because secondary texture coordinates are never re-generated.
+        self.texels_per_unit.hash(hasher);
+        self.denoise.hash(hasher);
+        self.denoise_radius.hash(hasher);
+        self.shadow_map_size.hash(hasher);
+    }
+}
+
 impl Lightmap {
     /// Generates lightmap for given scene. This method **automatically** generates secondary
     /// texture coordinates! This method is blocking, however internally it uses massive parallelism
@@ -234,83 +335,98 @@ impl Lightmap {
     /// lightmap will be generated, but also it will be slow to generate.
     /// `progress_indicator` allows you to get info about current progress.
     /// `cancellation_token` allows you to stop generation in any time.
+    ///
+    /// This method uses CPU backend with default settings, see [`Lightmap::bake`] for more flexible
+    /// version.
     pub fn new(
         scene: &mut Scene,
         texels_per_unit: u32,
         cancellation_token: CancellationToken,
         progress_indicator: ProgressIndicator,
     ) -> Result<Self, LightmapGenerationError> {
-        scene.graph.update_hierarchical_data();
-
-        // Extract info about lights first. We need it to be in separate array because
-        // it won't be possible to store immutable references to light sources and at the
-        // same time modify meshes. Also it precomputes a lot of things for faster calculations.
-        let mut light_count = 0;
-        for node in scene.graph.linear_iter() {
-            if node.cast::<PointLight>().is_some()
-                || node.cast::<SpotLight>().is_some()
-                || node.cast::<DirectionalLight>().is_some()
-            {
-                light_count += 1;
-            }
-        }
+        Self::bake(
+            scene,
+            &LightmapSettings {
+                texels_per_unit,
+                ..Default::default()
+            },
+            LightmapBackend::Cpu,
+            cancellation_token,
+            progress_indicator,
+        )
+    }
 
-        progress_indicator.set_stage(ProgressStage::LightsCaching, light_count);
+    /// Generates lightmap for given scene using given settings and backend. This method
+    /// **automatically** generates secondary texture coordinates! This method is blocking.
+    ///
+    /// ```rust,no_run
+    /// use fyrox::{
+    ///     renderer::Renderer,
+    ///     scene::Scene,
+    ///     utils::lightmap::{Lightmap, LightmapBackend, LightmapSettings},
+    /// };
+    ///
+    /// fn bake(scene: &mut Scene, renderer: &mut Renderer) {
+    ///     let lightmap = Lightmap::bake(
+    ///         scene,
+    ///         &LightmapSettings::default(),
+    ///         LightmapBackend::Gpu(renderer),
+    ///         Default::default(),
+    ///         Default::default(),
+    ///     )
+    ///     .unwrap();
+    ///     scene.set_lightmap(lightmap).unwrap();
+    /// }
+    /// ```
+    pub fn bake(
+        scene: &mut Scene,
+        settings: &LightmapSettings,
+        backend: LightmapBackend,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<Self, LightmapGenerationError> {
+        let mut lightmap = Self::default();
+        lightmap.rebake(
+            scene,
+            settings,
+            backend,
+            cancellation_token,
+            progress_indicator,
+        )?;
+        Ok(lightmap)
+    }
 
-        let mut lights = Vec::with_capacity(light_count as usize);
+    /// Re-bakes lightmaps only for the nodes that were changed (moved, got new geometry or texel
+    /// scale) or added since the last bake, lightmaps of removed nodes are removed. If light sources
+    /// or settings were changed, every node is re-baked. Secondary texture coordinates are never
+    /// re-generated for surfaces, that already have them. Returns the amount of re-baked nodes.
+    ///
+    /// Keep in mind, that shadows cast by changed nodes on unchanged ones are not updated, use
+    /// [`Lightmap::bake`] to get final quality. The lightmap must be applied to the scene again
+    /// using [`Scene::set_lightmap`].
+    pub fn rebake(
+        &mut self,
+        scene: &mut Scene,
+        settings: &LightmapSettings,
+        backend: LightmapBackend,
+        cancellation_token: CancellationToken,
+        progress_indicator: ProgressIndicator,
+    ) -> Result<usize, LightmapGenerationError> {
+        scene.graph.update_hierarchical_data();
 
-        for (handle, light) in scene.graph.pair_iter() {
-            if cancellation_token.is_cancelled() {
-                return Err(LightmapGenerationError::Cancelled);
-            }
+        let lights = collect_lights(scene, &cancellation_token, &progress_indicator)?;
 
-            if !light.is_globally_enabled() {
-                continue;
+        let lights_fingerprint = {
+            let mut hasher = FxHasher64::default();
+            for light in lights.iter() {
+                light.fingerprint(&mut hasher);
             }
-
-            if let Some(point) = light.cast::<PointLight>() {
-                lights.push(LightDefinition::Point(PointLightDefinition {
-                    handle,
-                    intensity: 1.0,
-                    position: light.global_position(),
-                    color: point.base_light_ref().color().srgb_to_linear().as_frgb(),
-                    radius: point.radius(),
-                    sqr_radius: point.radius() * point.radius(),
-                }))
-            } else if let Some(spot) = light.cast::<SpotLight>() {
-                lights.push(LightDefinition::Spot(SpotLightDefinition {
-                    handle,
-                    intensity: 1.0,
-                    edge0: ((spot.hotspot_cone_angle() + spot.falloff_angle_delta()) * 0.5).cos(),
-                    edge1: (spot.hotspot_cone_angle() * 0.5).cos(),
-                    color: spot.base_light_ref().color().srgb_to_linear().as_frgb(),
-                    direction: light
-                        .up_vector()
-                        .try_normalize(std::f32::EPSILON)
-                        .unwrap_or_else(Vector3::y),
-                    position: light.global_position(),
-                    distance: spot.distance(),
-                    sqr_distance: spot.distance() * spot.distance(),
-                }))
-            } else if let Some(directional) = light.cast::<DirectionalLight>() {
-                lights.push(LightDefinition::Directional(DirectionalLightDefinition {
-                    handle,
-                    intensity: 1.0,
-                    direction: light
-                        .up_vector()
-                        .try_normalize(std::f32::EPSILON)
-                        .unwrap_or_else(Vector3::y),
-                    color: directional
-                        .base_light_ref()
-                        .color()
-                        .srgb_to_linear()
-                        .as_frgb(),
-                }))
-            } else {
-                continue;
-            };
-
-            progress_indicator.advance_progress()
+            settings.fingerprint(&mut hasher);
+            hasher.finish()
+        };
+        if lights_fingerprint != self.lights_fingerprint {
+            // Every node must be re-baked.
+            self.fingerprints.clear();
         }
 
         let mut instances = Vec::new();
@@ -326,12 +442,16 @@ impl Lightmap {
                     // Gather unique "list" of surface data to generate UVs for.
                     let data = surface.data();
                     let key = &*data.lock() as *const _ as u64;
-                    data_set.entry(key).or_insert_with(|| surface.data());
+                    let content_hash = data.lock().content_hash();
+                    if !self.generated_data.contains_key(&content_hash) {
+                        data_set.entry(key).or_insert_with(|| surface.data());
+                    }
 
                     instances.push(Instance {
                         owner: handle,
                         source_data: data.clone(),
                         transform: global_transform,
+                        texel_scale: mesh.lightmap_texel_scale(),
                         // Calculated down below.
                         data: None,
                     });
@@ -341,19 +461,36 @@ impl Lightmap {
 
         progress_indicator.set_stage(ProgressStage::UvGeneration, data_set.len() as u32);
 
-        let patches = data_set
+        let generated = data_set
             .into_par_iter()
             .map(|(_, data)| {
                 if cancellation_token.is_cancelled() {
                     Err(LightmapGenerationError::Cancelled)
                 } else {
                     let mut data = data.lock();
-                    let patch = uvgen::generate_uvs(&mut data, 0.005)?;
+                    let patch = uvgen::generate_uvs(&mut data, settings.spacing)?;
                     progress_indicator.advance_progress();
-                    Ok((patch.data_id, patch))
+                    Ok((data.content_hash(), patch))
                 }
             })
-            .collect::<Result<FxHashMap<_, _>, LightmapGenerationError>>()?;
+            .collect::<Result<Vec<_>, LightmapGenerationError>>()?;
+
+        for (content_hash, patch) in generated {
+            self.generated_data.insert(content_hash, patch.data_id);
+            self.patches.insert(patch.data_id, patch);
+        }
+
+        let mut fingerprints = FxHashMap::<Handle<Node>, u64>::default();
+        for instance in instances.iter() {
+            let fingerprint = fingerprints.entry(instance.owner).or_default();
+            *fingerprint = hash_combine(*fingerprint, instance.fingerprint());
+        }
+
+        let dirty_nodes = fingerprints
+            .iter()
+            .filter(|(handle, fingerprint)| self.fingerprints.get(*handle) != Some(*fingerprint))
+            .map(|(handle, _)| *handle)
+            .collect::<FxHashSet<_>>();
 
         progress_indicator.set_stage(ProgressStage::GeometryCaching, instances.len() as u32);
 
@@ -408,10 +545,16 @@ impl Lightmap {
                         })
                         .collect::<Vec<_>>();
 
+                    let mut bounds = AxisAlignedBoundingBox::default();
+                    for vertex in world_vertices.iter() {
+                        bounds.add_point(vertex.world_position);
+                    }
+
                     instance.data = Some(InstanceData {
                         vertices: world_vertices,
                         triangles: data.geometry_buffer.triangles_ref().to_vec(),
                         octree: Octree::new(&world_triangles, 64),
+                        bounds,
                     });
 
                     progress_indicator.advance_progress();
@@ -421,24 +564,82 @@ impl Lightmap {
             })
             .collect::<Result<(), LightmapGenerationError>>()?;
 
-        progress_indicator.set_stage(ProgressStage::CalculatingLight, instances.len() as u32);
+        let targets = instances
+            .iter()
+            .enumerate()
+            .filter(|(_, instance)| dirty_nodes.contains(&instance.owner))
+            .map(|(index, instance)| {
+                (
+                    index,
+                    estimate_size(
+                        instance.data(),
+                        settings.texels_per_unit,
+                        instance.texel_scale,
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
 
-        let mut map: FxHashMap<Handle<Node>, Vec<LightmapEntry>> = FxHashMap::default();
-        for instance in instances.iter() {
-            if cancellation_token.is_cancelled() {
-                return Err(LightmapGenerationError::Cancelled);
-            }
+        let textures = match backend {
+            LightmapBackend::Cpu => {
+                progress_indicator.set_stage(ProgressStage::CalculatingLight, targets.len() as u32);
 
-            let lightmap = generate_lightmap(instance, &instances, &lights, texels_per_unit);
-            map.entry(instance.owner).or_default().push(LightmapEntry {
-                texture: Some(Texture(Resource::new(TextureState::Ok(lightmap)))),
-                lights: lights.iter().map(|light| light.handle()).collect(),
-            });
+                let mut textures = Vec::with_capacity(targets.len());
+                for &(index, atlas_size) in targets.iter() {
+                    if cancellation_token.is_cancelled() {
+                        return Err(LightmapGenerationError::Cancelled);
+                    }
+
+                    let texels =
+                        calculate_texels(&instances[index], &instances, &lights, atlas_size);
+                    textures.push(make_texture(texels, atlas_size, settings));
 
-            progress_indicator.advance_progress();
+                    progress_indicator.advance_progress();
+                }
+                textures
+            }
+            LightmapBackend::Gpu(renderer) => gpu::calculate_texels(
+                renderer.pipeline_state(),
+                &instances,
+                &targets,
+                &lights,
+                settings,
+                &cancellation_token,
+                &progress_indicator,
+            )?
+            .into_iter()
+            .zip(targets.iter())
+            .map(|(texels, &(_, atlas_size))| make_texture(texels, atlas_size, settings))
+            .collect::<Vec<_>>(),
+        };
+
+        // Lightmaps of changed nodes will be replaced, lightmaps of removed nodes are not needed.
+        self.map
+            .retain(|handle, _| fingerprints.contains_key(handle) && !dirty_nodes.contains(handle));
+        for (&(index, _), texture) in targets.iter().zip(textures) {
+            self.map
+                .entry(instances[index].owner)
+                .or_default()
+                .push(LightmapEntry {
+                    texture: Some(Texture(Resource::new(TextureState::Ok(texture)))),
+                    lights: lights.iter().map(|light| light.handle()).collect(),
+                });
         }
 
-        Ok(Self { map, patches })
+        // Forget patches of surface data, that is not used anymore.
+        let used_data = instances
+            .iter()
+            .map(|instance| instance.source_data.lock().content_hash())
+            .collect::<FxHashSet<_>>();
+        self.generated_data
+            .retain(|content_hash, _| used_data.contains(content_hash));
+        let used_patches = self.generated_data.values().collect::<FxHashSet<_>>();
+        self.patches.retain(|id, _| used_patches.contains(id));
+
+        self.fingerprints = fingerprints;
+        self.lights_fingerprint = lights_fingerprint;
+
+        Ok(dirty_nodes.len())
     }
 
     /// Saves lightmap textures into specified folder.
@@ -463,6 +664,85 @@ impl Lightmap {
     }
 }
 
+/// Extracts info about lights. We need it to be in separate array because it won't be possible to
+/// store immutable references to light sources and at the same time modify meshes. Also it
+/// precomputes a lot of things for faster calculations.
+fn collect_lights(
+    scene: &Scene,
+    cancellation_token: &CancellationToken,
+    progress_indicator: &ProgressIndicator,
+) -> Result<Vec<LightDefinition>, LightmapGenerationError> {
+    let mut light_count = 0;
+    for node in scene.graph.linear_iter() {
+        if node.cast::<PointLight>().is_some()
+            || node.cast::<SpotLight>().is_some()
+            || node.cast::<DirectionalLight>().is_some()
+        {
+            light_count += 1;
+        }
+    }
+
+    progress_indicator.set_stage(ProgressStage::LightsCaching, light_count);
+
+    let mut lights = Vec::with_capacity(light_count as usize);
+
+    for (handle, light) in scene.graph.pair_iter() {
+        if cancellation_token.is_cancelled() {
+            return Err(LightmapGenerationError::Cancelled);
+        }
+
+        if !light.is_globally_enabled() {
+            continue;
+        }
+
+        if let Some(point) = light.cast::<PointLight>() {
+            lights.push(LightDefinition::Point(PointLightDefinition {
+                handle,
+                intensity: 1.0,
+                position: light.global_position(),
+                color: point.base_light_ref().color().srgb_to_linear().as_frgb(),
+                radius: point.radius(),
+                sqr_radius: point.radius() * point.radius(),
+            }))
+        } else if let Some(spot) = light.cast::<SpotLight>() {
+            lights.push(LightDefinition::Spot(SpotLightDefinition {
+                handle,
+                intensity: 1.0,
+                edge0: ((spot.hotspot_cone_angle() + spot.falloff_angle_delta()) * 0.5).cos(),
+                edge1: (spot.hotspot_cone_angle() * 0.5).cos(),
+                color: spot.base_light_ref().color().srgb_to_linear().as_frgb(),
+                direction: light
+                    .up_vector()
+                    .try_normalize(std::f32::EPSILON)
+                    .unwrap_or_else(Vector3::y),
+                position: light.global_position(),
+                distance: spot.distance(),
+                sqr_distance: spot.distance() * spot.distance(),
+            }))
+        } else if let Some(directional) = light.cast::<DirectionalLight>() {
+            lights.push(LightDefinition::Directional(DirectionalLightDefinition {
+                handle,
+                intensity: 1.0,
+                direction: light
+                    .up_vector()
+                    .try_normalize(std::f32::EPSILON)
+                    .unwrap_or_else(Vector3::y),
+                color: directional
+                    .base_light_ref()
+                    .color()
+                    .srgb_to_linear()
+                    .as_frgb(),
+            }))
+        } else {
+            continue;
+        };
+
+        progress_indicator.advance_progress()
+    }
+
+    Ok(lights)
+}
+
 /// Directional light is a light source with parallel rays. Example: Sun.
 pub struct DirectionalLightDefinition {
     /// A handle of light in the scene.
@@ -531,11 +811,64 @@ impl LightDefinition {
             LightDefinition::Point(v) => v.handle,
         }
     }
+
+    fn fingerprint(&self, hasher: &mut FxHasher64) {
+        let (kind, values) = match self {
+            LightDefinition::Directional(v) => (
+                0u8,
+                vec![
+                    v.intensity,
+                    v.direction.x,
+                    v.direction.y,
+                    v.direction.z,
+                    v.color.x,
+                    v.color.y,
+                    v.color.z,
+                ],
+            ),
+            LightDefinition::Spot(v) => (
+                1u8,
+                vec![
+                    v.intensity,
+                    v.color.x,
+                    v.color.y,
+                    v.color.z,
+                    v.direction.x,
+                    v.direction.y,
+                    v.direction.z,
+                    v.position.x,
+                    v.position.y,
+                    v.position.z,
+                    v.distance,
+                    v.edge0,
+                    v.edge1,
+                ],
+            ),
+            LightDefinition::Point(v) => (
+                2u8,
+                vec![
+                    v.intensity,
+                    v.position.x,
+                    v.position.y,
+                    v.position.z,
+                    v.color.x,
+                    v.color.y,
+                    v.color.z,
+                    v.radius,
+                ],
+            ),
+        };
+        kind.hash(hasher);
+        self.handle().hash(hasher);
+        for value in values {
+            value.to_bits().hash(hasher);
+        }
+    }
 }
 
 /// Computes total area of triangles in surface data and returns size of square
 /// in which triangles can fit.
-fn estimate_size(data: &InstanceData, texels_per_unit: u32) -> u32 {
+fn estimate_size(data: &InstanceData, texels_per_unit: u32, texel_scale: f32) -> u32 {
     let mut area = 0.0;
     for triangle in data.triangles.iter() {
         let a = data.vertices[triangle[0] as usize].world_position;
@@ -543,7 +876,7 @@ fn estimate_size(data: &InstanceData, texels_per_unit: u32) -> u32 {
         let c = data.vertices[triangle[2] as usize].world_position;
         area += math::triangle_area(a, b, c);
     }
-    area.sqrt().ceil() as u32 * texels_per_unit
+    ((area.sqrt().ceil() * texels_per_unit as f32 * texel_scale) as u32).clamp(1, MAX_LIGHTMAP_SIZE)
 }
 
 /// Calculates distance attenuation for a point using given distance to the point and
@@ -669,33 +1002,34 @@ fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
     k * k * (3.0 - 2.0 * k)
 }
 
-/// Generates lightmap for given surface data with specified transform.
+/// Calculates lighting of every texel of the lightmap of given instance using ray tracing. Returned
+/// texels contain linear color in `xyz` and coverage in `w` - it is non-zero for texels that are
+/// covered by triangles.
 ///
 /// # Performance
 ///
 /// This method is has linear complexity - the more complex mesh you pass, the more
 /// time it will take. Required time increases drastically if you enable shadows and
 /// global illumination (TODO), because in this case your data will be raytraced.
-fn generate_lightmap(
+fn calculate_texels(
     instance: &Instance,
     other_instances: &[Instance],
     lights: &[LightDefinition],
-    texels_per_unit: u32,
-) -> TextureData {
+    atlas_size: u32,
+) -> Vec<Vector4<f32>> {
     // We have to re-generate new set of world-space vertices because UV generator
     // may add new vertices on seams.
-    let atlas_size = estimate_size(instance.data(), texels_per_unit);
     let scale = 1.0 / atlas_size as f32;
     let grid = Grid::new(instance.data(), (atlas_size / 32).max(4) as usize);
 
-    let mut pixels: Vec<Vector4<u8>> =
-        vec![Vector4::new(0, 0, 0, 0); (atlas_size * atlas_size) as usize];
+    let mut pixels: Vec<Vector4<f32>> =
+        vec![Vector4::default(); (atlas_size * atlas_size) as usize];
 
     let half_pixel = scale * 0.5;
     pixels
         .par_iter_mut()
         .enumerate()
-        .for_each(|(i, pixel): (usize, &mut Vector4<u8>)| {
+        .for_each(|(i, pixel): (usize, &mut Vector4<f32>)| {
             let x = i as u32 % atlas_size;
             let y = i as u32 / atlas_size;
 
@@ -775,97 +1109,120 @@ fn generate_lightmap(
                     pixel_color += light_color.scale(attenuation);
                 }
 
-                *pixel = Vector4::new(
-                    (pixel_color.x.clamp(0.0, 1.0) * 255.0) as u8,
-                    (pixel_color.y.clamp(0.0, 1.0) * 255.0) as u8,
-                    (pixel_color.z.clamp(0.0, 1.0) * 255.0) as u8,
-                    255, // Indicates that this pixel was "filled"
-                );
+                // Non-zero w indicates that this pixel was "filled".
+                *pixel = Vector4::new(pixel_color.x, pixel_color.y, pixel_color.z, 1.0);
             }
         });
 
-    // Prepare light map for bilinear filtration. This step is mandatory to prevent bleeding.
-    let mut rgb_pixels: Vec<Vector3<u8>> = Vec::with_capacity((atlas_size * atlas_size) as usize);
-    for y in 0..(atlas_size as i32) {
-        for x in 0..(atlas_size as i32) {
-            let fetch = |dx: i32, dy: i32| -> Option<Vector3<u8>> {
-                pixels
-                    .get(((y + dy) * (atlas_size as i32) + x + dx) as usize)
-                    .and_then(|p| {
-                        if p.w != 0 {
-                            Some(Vector3::new(p.x, p.y, p.z))
-                        } else {
-                            None
-                        }
-                    })
-            };
+    pixels
+}
 
-            let src_pixel = pixels[(y * (atlas_size as i32) + x) as usize];
-            if src_pixel.w == 0 {
-                // Check neighbour pixels marked as "filled" and use it as value.
-                if let Some(west) = fetch(-1, 0) {
-                    rgb_pixels.push(west);
-                } else if let Some(east) = fetch(1, 0) {
-                    rgb_pixels.push(east);
-                } else if let Some(north) = fetch(0, -1) {
-                    rgb_pixels.push(north);
-                } else if let Some(south) = fetch(0, 1) {
-                    rgb_pixels.push(south);
-                } else if let Some(north_west) = fetch(-1, -1) {
-                    rgb_pixels.push(north_west);
-                } else if let Some(north_east) = fetch(1, -1) {
-                    rgb_pixels.push(north_east);
-                } else if let Some(south_east) = fetch(1, 1) {
-                    rgb_pixels.push(south_east);
-                } else if let Some(south_west) = fetch(-1, 1) {
-                    rgb_pixels.push(south_west);
-                } else {
-                    rgb_pixels.push(Vector3::new(0, 0, 0));
+/// Removes noise and aliasing of shadows using edge-preserving (bilateral) filter. Only filled
+/// texels are taken into account, so empty space between charts does not bleed into lighting.
+fn denoise(texels: &[Vector4<f32>], size: u32, radius: u32) -> Vec<Vector4<f32>> {
+    let size = size as i32;
+    let radius = radius as i32;
+    let spatial_sigma = (radius as f32 * 0.5).max(0.5);
+    let spatial_factor = -1.0 / (2.0 * spatial_sigma * spatial_sigma);
+    // Colors are in [0; 1] range, so larger differences are considered as edges and preserved.
+    let range_sigma = 0.1;
+    let range_factor = -1.0 / (2.0 * range_sigma * range_sigma);
+
+    texels
+        .par_iter()
+        .enumerate()
+        .map(|(i, center)| {
+            if center.w <= 0.0 {
+                return *center;
+            }
+
+            let x = i as i32 % size;
+            let y = i as i32 / size;
+
+            let mut sum = Vector3::default();
+            let mut weight_sum = 0.0;
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || ny < 0 || nx >= size || ny >= size {
+                        continue;
+                    }
+
+                    let neighbour = texels[(ny * size + nx) as usize];
+                    if neighbour.w <= 0.0 {
+                        continue;
+                    }
+
+                    let color_difference = (neighbour.xyz() - center.xyz()).norm_squared();
+                    let weight = ((dx * dx + dy * dy) as f32 * spatial_factor
+                        + color_difference * range_factor)
+                        .exp();
+                    sum += neighbour.xyz().scale(weight);
+                    weight_sum += weight;
                 }
-            } else {
-                rgb_pixels.push(Vector3::new(src_pixel.x, src_pixel.y, src_pixel.z))
             }
-        }
+
+            // Weight sum is never zero, because the center texel is always taken into account.
+            let color = sum.scale(1.0 / weight_sum);
+            Vector4::new(color.x, color.y, color.z, center.w)
+        })
+        .collect()
+}
+
+/// Creates lightmap texture from calculated texels. Texels are clamped, denoised (if enabled) and
+/// empty texels are filled with colors of neighbour texels.
+fn make_texture(
+    mut texels: Vec<Vector4<f32>>,
+    atlas_size: u32,
+    settings: &LightmapSettings,
+) -> TextureData {
+    for texel in texels.iter_mut() {
+        texel.x = texel.x.clamp(0.0, 1.0);
+        texel.y = texel.y.clamp(0.0, 1.0);
+        texel.z = texel.z.clamp(0.0, 1.0);
+    }
+
+    if settings.denoise && settings.denoise_radius > 0 {
+        texels = denoise(&texels, atlas_size, settings.denoise_radius);
     }
 
-    // Blur lightmap using simplest box filter.
+    // Prepare light map for bilinear filtration. This step is mandatory to prevent bleeding.
+    let size = atlas_size as i32;
     let mut bytes = Vec::with_capacity((atlas_size * atlas_size * 3) as usize);
-    for y in 0..(atlas_size as i32) {
-        for x in 0..(atlas_size as i32) {
-            if x < 1 || y < 1 || x + 1 == atlas_size as i32 || y + 1 == atlas_size as i32 {
-                bytes.extend_from_slice(
-                    rgb_pixels[(y * (atlas_size as i32) + x) as usize].as_slice(),
-                );
-            } else {
-                let fetch = |dx: i32, dy: i32| -> Vector3<i16> {
-                    let u8_pixel = rgb_pixels[((y + dy) * (atlas_size as i32) + x + dx) as usize];
-                    Vector3::new(u8_pixel.x as i16, u8_pixel.y as i16, u8_pixel.z as i16)
-                };
-
-                let north_west = fetch(-1, -1);
-                let north = fetch(0, -1);
-                let north_east = fetch(1, -1);
-                let west = fetch(-1, 0);
-                let center = fetch(0, 0);
-                let east = fetch(1, 0);
-                let south_west = fetch(-1, 1);
-                let south = fetch(0, 1);
-                let south_east = fetch(-1, 1);
-
-                let sum = north_west
-                    + north
-                    + north_east
-                    + west
-                    + center
-                    + east
-                    + south_west
-                    + south
-                    + south_east;
-
-                bytes.push((sum.x / 9).clamp(0, 255) as u8);
-                bytes.push((sum.y / 9).clamp(0, 255) as u8);
-                bytes.push((sum.z / 9).clamp(0, 255) as u8);
-            }
+    for y in 0..size {
+        for x in 0..size {
+            let fetch = |dx: i32, dy: i32| -> Option<Vector3<f32>> {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= size || ny >= size {
+                    return None;
+                }
+                let texel = texels[(ny * size + nx) as usize];
+                if texel.w > 0.0 {
+                    Some(texel.xyz())
+                } else {
+                    None
+                }
+            };
+
+            // Check neighbour pixels marked as "filled" and use it as value.
+            let color = [
+                (0, 0),
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (1, 1),
+                (-1, 1),
+            ]
+            .iter()
+            .find_map(|&(dx, dy)| fetch(dx, dy))
+            .unwrap_or_default();
+
+            bytes.push((color.x * 255.0) as u8);
+            bytes.push((color.y * 255.0) as u8);
+            bytes.push((color.z * 255.0) as u8);
         }
     }
 
@@ -887,7 +1244,7 @@ fn generate_lightmap(
 mod test {
     use crate::scene::mesh::surface::SurfaceSharedData;
     use crate::{
-        core::algebra::{Matrix4, Vector3},
+        core::algebra::{Matrix4, Vector3, Vector4},
         scene::{
             base::BaseBuilder,
             light::{point::PointLightBuilder, BaseLightBuilder},
@@ -898,7 +1255,7 @@ mod test {
             transform::TransformBuilder,
             Scene,
         },
-        utils::lightmap::Lightmap,
+        utils::lightmap::{denoise, Lightmap, LightmapBackend, LightmapSettings},
     };
 
     #[test]
@@ -941,4 +1298,86 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_incremental_rebake() {
+        let mut scene = Scene::new();
+
+        let mut make_mesh = |position: Vector3<f32>| {
+            MeshBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+            )
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut scene.graph)
+        };
+        let a = make_mesh(Vector3::new(-1.0, 0.0, 0.0));
+        let b = make_mesh(Vector3::new(1.0, 0.0, 0.0));
+
+        PointLightBuilder::new(BaseLightBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                    .build(),
+            ),
+        ))
+        .with_radius(4.0)
+        .build(&mut scene.graph);
+
+        let settings = LightmapSettings {
+            texels_per_unit: 8,
+            ..Default::default()
+        };
+        let rebake = |lightmap: &mut Lightmap, scene: &mut Scene| {
+            lightmap
+                .rebake(
+                    scene,
+                    &settings,
+                    LightmapBackend::Cpu,
+                    Default::default(),
+                    Default::default(),
+                )
+                .unwrap()
+        };
+
+        let mut lightmap = Lightmap::default();
+        assert_eq!(rebake(&mut lightmap, &mut scene), 2);
+        let patch_count = lightmap.patches.len();
+
+        // Nothing has changed.
+        assert_eq!(rebake(&mut lightmap, &mut scene), 0);
+        assert_eq!(lightmap.patches.len(), patch_count);
+
+        scene.graph[b]
+            .local_transform_mut()
+            .set_position(Vector3::new(2.0, 0.0, 0.0));
+        assert_eq!(rebake(&mut lightmap, &mut scene), 1);
+        assert_eq!(lightmap.map.len(), 2);
+
+        scene.graph.remove_node(a);
+        assert_eq!(rebake(&mut lightmap, &mut scene), 0);
+        assert!(!lightmap.map.contains_key(&a));
+    }
+
+    #[test]
+    fn test_denoise() {
+        // 3x3 texels, the center one is noisy, the right column is empty.
+        let empty = Vector4::new(0.0, 0.0, 0.0, 0.0);
+        let lit = Vector4::new(0.5, 0.5, 0.5, 1.0);
+        let noisy = Vector4::new(0.55, 0.55, 0.55, 1.0);
+        let texels = vec![lit, lit, empty, lit, noisy, empty, lit, lit, empty];
+
+        let denoised = denoise(&texels, 3, 1);
+
+        assert!((denoised[4].x - 0.5).abs() < (noisy.x - 0.5).abs());
+        // Empty texels must stay empty and must not affect filled ones.
+        assert_eq!(denoised[2], empty);
+        assert!(denoised[0].x >= 0.5);
+    }
 }
@@ -0,0 +1,80 @@
+// Kinds of lights: 0 - directional, 1 - spot, 2 - point.
+uniform int lightKind;
+uniform vec3 lightPosition;
+uniform vec3 lightDirection;
+uniform vec3 lightColor;
+uniform float lightSqrRadius;
+uniform float spotEdge0;
+uniform float spotEdge1;
+// Every face of a point light shadow cube lights only the part of space where its axis is dominant.
+uniform vec3 faceAxis;
+uniform bool useFaceAxis;
+uniform mat4 lightViewProjection;
+uniform sampler2D shadowMap;
+uniform float shadowMapInvSize;
+uniform float shadowBias;
+uniform float normalOffset;
+
+in vec3 worldPosition;
+in vec3 worldNormal;
+
+out vec4 FragColor;
+
+float DistanceAttenuation(float distance, float sqrRadius)
+{
+    float attenuation = clamp(1.0 - distance * distance / sqrRadius, 0.0, 1.0);
+    return attenuation * attenuation;
+}
+
+void main()
+{
+    vec3 normal = normalize(worldNormal);
+
+    vec3 lightVector;
+    float attenuation;
+
+    if (lightKind == 0)
+    {
+        lightVector = lightDirection;
+        attenuation = 1.0;
+    }
+    else
+    {
+        vec3 toLight = lightPosition - worldPosition;
+        float distance = length(toLight);
+        lightVector = toLight / max(distance, 0.00001);
+        attenuation = DistanceAttenuation(distance, lightSqrRadius);
+
+        if (lightKind == 1)
+        {
+            attenuation *= smoothstep(spotEdge0, spotEdge1, dot(lightVector, lightDirection));
+        }
+        else if (useFaceAxis)
+        {
+            vec3 toFragment = -toLight;
+            float major = max(abs(toFragment.x), max(abs(toFragment.y), abs(toFragment.z)));
+            if (dot(toFragment, faceAxis) < major)
+            {
+                attenuation = 0.0;
+            }
+        }
+    }
+
+    attenuation *= max(dot(normal, lightVector), 0.0);
+
+    if (attenuation > 0.0)
+    {
+        // Offset along the normal prevents self-shadowing.
+        attenuation *= S_SpotShadowFactor(
+            true,
+            true,
+            shadowBias,
+            worldPosition + normal * normalOffset,
+            lightViewProjection,
+            shadowMapInvSize,
+            shadowMap);
+    }
+
+    // Alpha marks texels that are covered by triangles.
+    FragColor = vec4(lightColor * attenuation, 1.0);
+}
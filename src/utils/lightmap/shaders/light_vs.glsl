@@ -0,0 +1,16 @@
+layout(location = 0) in vec3 vertexPosition;
+layout(location = 1) in vec3 vertexNormal;
+layout(location = 2) in vec2 vertexSecondTexCoord;
+
+out vec3 worldPosition;
+out vec3 worldNormal;
+
+void main()
+{
+    // Vertices are already in world space.
+    worldPosition = vertexPosition;
+    worldNormal = vertexNormal;
+
+    // Triangles are rasterized in the lightmap space, so every fragment is a texel of the lightmap.
+    gl_Position = vec4(vertexSecondTexCoord * 2.0 - 1.0, 0.0, 1.0);
+}
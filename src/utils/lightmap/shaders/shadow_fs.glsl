@@ -0,0 +1,8 @@
+// Shadow maps contain only depth, color output is masked out.
+
+out vec4 FragColor;
+
+void main()
+{
+    FragColor = vec4(1.0);
+}
@@ -0,0 +1,9 @@
+layout(location = 0) in vec3 vertexPosition;
+
+uniform mat4 lightViewProjection;
+
+void main()
+{
+    // Vertices are already in world space.
+    gl_Position = lightViewProjection * vec4(vertexPosition, 1.0);
+}
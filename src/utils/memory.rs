@@ -0,0 +1,387 @@
+//! Instrumented memory allocator, that tracks allocations per subsystem (tag) and reports leaks and
+//! high-water marks on shutdown. It is available only with `memory-tracking` feature.
+//!
+//! To use it, install [`TrackingAllocator`] as global allocator of your executable:
+//!
+//! ```rust,no_run
+//! use fyrox::utils::memory::TrackingAllocator;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+//! ```
+//!
+//! Every allocation is attributed to the tag, that is active on the current thread at the moment of
+//! allocation (see [`MemoryTag::enter`]). The engine marks its subsystems (renderer, scenes, physics,
+//! scripts, etc.) by itself, custom tags could be registered using [`MemoryTag::register`]. Current
+//! statistics could be fetched at any time using [`MemoryReport::collect`], it is also printed to the
+//! log when the engine is destroyed. Memory of engine's tags that is still allocated after the engine
+//! was destroyed is reported as leaked.
+//!
+//! Every allocation has a small header to remember its tag, so the allocator is meant to be used
+//! only for memory budgeting and leak hunting and not in production builds.
+
+use crate::utils::log::Log;
+use lazy_static::lazy_static;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::{Display, Formatter},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+/// Maximum amount of tags (including built-in ones).
+pub const MAX_MEMORY_TAGS: usize = 64;
+
+const BUILT_IN_TAG_NAMES: [&str; 9] = [
+    "Untagged",
+    "Renderer",
+    "Scene",
+    "Physics",
+    "Sound",
+    "UI",
+    "Resources",
+    "Scripts",
+    "Plugins",
+];
+
+struct TagCounters {
+    allocated: AtomicUsize,
+    peak: AtomicUsize,
+    live_allocations: AtomicUsize,
+    total_allocations: AtomicUsize,
+}
+
+impl TagCounters {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: TagCounters = TagCounters {
+        allocated: AtomicUsize::new(0),
+        peak: AtomicUsize::new(0),
+        live_allocations: AtomicUsize::new(0),
+        total_allocations: AtomicUsize::new(0),
+    };
+
+    fn on_alloc(&self, size: usize) {
+        let allocated = self.allocated.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak.fetch_max(allocated, Ordering::Relaxed);
+        self.live_allocations.fetch_add(1, Ordering::Relaxed);
+        self.total_allocations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_dealloc(&self, size: usize) {
+        self.allocated.fetch_sub(size, Ordering::Relaxed);
+        self.live_allocations.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn on_realloc(&self, old_size: usize, new_size: usize) {
+        if new_size >= old_size {
+            let growth = new_size - old_size;
+            let allocated = self.allocated.fetch_add(growth, Ordering::Relaxed) + growth;
+            self.peak.fetch_max(allocated, Ordering::Relaxed);
+        } else {
+            self.allocated
+                .fetch_sub(old_size - new_size, Ordering::Relaxed);
+        }
+    }
+}
+
+static COUNTERS: [TagCounters; MAX_MEMORY_TAGS] = [TagCounters::NEW; MAX_MEMORY_TAGS];
+
+lazy_static! {
+    static ref CUSTOM_TAG_NAMES: Mutex<Vec<&'static str>> = Default::default();
+}
+
+thread_local! {
+    static CURRENT_TAG: Cell<usize> = Cell::new(0);
+}
+
+fn current_tag() -> usize {
+    // Thread-local storage could be already destroyed when a thread is exiting.
+    CURRENT_TAG.try_with(|tag| tag.get()).unwrap_or(0)
+}
+
+/// A tag of a subsystem, that owns allocated memory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MemoryTag(usize);
+
+impl MemoryTag {
+    /// Memory, that was allocated outside of any tagged scope.
+    pub const UNTAGGED: Self = Self(0);
+    /// Memory of the renderer and its caches.
+    pub const RENDERER: Self = Self(1);
+    /// Memory of scene graphs (nodes, animations, etc.).
+    pub const SCENE: Self = Self(2);
+    /// Memory of 2D and 3D physics.
+    pub const PHYSICS: Self = Self(3);
+    /// Memory of sound sources and effects.
+    pub const SOUND: Self = Self(4);
+    /// Memory of the user interface.
+    pub const UI: Self = Self(5);
+    /// Memory of the resource manager and loaded resources.
+    pub const RESOURCES: Self = Self(6);
+    /// Memory, allocated by scripts.
+    pub const SCRIPTS: Self = Self(7);
+    /// Memory, allocated by plugins.
+    pub const PLUGINS: Self = Self(8);
+
+    /// Registers a new tag with the given name. Returns `None` if there are already
+    /// [`MAX_MEMORY_TAGS`] tags.
+    pub fn register(name: &'static str) -> Option<Self> {
+        let mut names = CUSTOM_TAG_NAMES.lock().unwrap();
+        let index = BUILT_IN_TAG_NAMES.len() + names.len();
+        if index < MAX_MEMORY_TAGS {
+            names.push(name);
+            Some(Self(index))
+        } else {
+            None
+        }
+    }
+
+    /// Returns name of the tag.
+    pub fn name(self) -> &'static str {
+        BUILT_IN_TAG_NAMES.get(self.0).cloned().unwrap_or_else(|| {
+            CUSTOM_TAG_NAMES
+                .lock()
+                .unwrap()
+                .get(self.0 - BUILT_IN_TAG_NAMES.len())
+                .cloned()
+                .unwrap_or("Unknown")
+        })
+    }
+
+    /// Makes the tag active on the current thread until the returned scope is dropped. Scopes
+    /// could be nested, the previous tag is restored when a scope is dropped.
+    ///
+    /// ```rust
+    /// use fyrox::utils::memory::MemoryTag;
+    ///
+    /// let _scope = MemoryTag::RENDERER.enter();
+    /// // Every allocation here is attributed to the renderer.
+    /// let buffer = vec![0u8; 1024];
+    /// ```
+    #[must_use = "the tag is active only while the scope is alive"]
+    pub fn enter(self) -> MemoryTagScope {
+        MemoryTagScope {
+            previous: CURRENT_TAG.try_with(|tag| tag.replace(self.0)).unwrap_or(0),
+        }
+    }
+
+    /// Returns the tag, that is currently active on this thread.
+    pub fn current() -> Self {
+        Self(current_tag())
+    }
+}
+
+/// A scope of a memory tag, see [`MemoryTag::enter`].
+pub struct MemoryTagScope {
+    previous: usize,
+}
+
+impl Drop for MemoryTagScope {
+    fn drop(&mut self) {
+        let _ = CURRENT_TAG.try_with(|tag| tag.set(self.previous));
+    }
+}
+
+/// Global allocator, that tracks allocations per [`MemoryTag`]. See [module docs](self) for more
+/// info.
+pub struct TrackingAllocator;
+
+// Every allocation is prefixed with a header, that holds an index of the tag of the allocation. The
+// header is placed right before the returned pointer and the offset keeps requested alignment.
+fn header_layout(layout: Layout) -> Option<(Layout, usize)> {
+    let align = layout.align().max(std::mem::align_of::<usize>());
+    let offset = align.max(std::mem::size_of::<usize>());
+    let size = layout.size().checked_add(offset)?;
+    Layout::from_size_align(size, align)
+        .ok()
+        .map(|layout| (layout, offset))
+}
+
+unsafe fn tag_ptr(ptr: *mut u8) -> *mut usize {
+    ptr.sub(std::mem::size_of::<usize>()) as *mut usize
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let (full_layout, offset) = match header_layout(layout) {
+            Some(header_layout) => header_layout,
+            None => return std::ptr::null_mut(),
+        };
+
+        let base = System.alloc(full_layout);
+        if base.is_null() {
+            return base;
+        }
+
+        let tag = current_tag();
+        let ptr = base.add(offset);
+        tag_ptr(ptr).write(tag);
+        COUNTERS[tag].on_alloc(layout.size());
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // The layout was already validated in `alloc`.
+        let (full_layout, offset) = header_layout(layout).unwrap();
+        COUNTERS[tag_ptr(ptr).read()].on_dealloc(layout.size());
+        System.dealloc(ptr.sub(offset), full_layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let (full_layout, offset) = header_layout(layout).unwrap();
+        let new_full_size = match new_size.checked_add(offset) {
+            Some(size) => size,
+            None => return std::ptr::null_mut(),
+        };
+
+        // Reallocated memory keeps its original tag, the header is moved together with the data.
+        let tag = tag_ptr(ptr).read();
+        let base = System.realloc(ptr.sub(offset), full_layout, new_full_size);
+        if base.is_null() {
+            return base;
+        }
+
+        COUNTERS[tag].on_realloc(layout.size(), new_size);
+        base.add(offset)
+    }
+}
+
+/// Memory statistics of a single tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagMemoryStatistics {
+    /// A tag of the statistics.
+    pub tag: MemoryTag,
+    /// Name of the tag.
+    pub name: &'static str,
+    /// Amount of bytes, that is currently allocated.
+    pub allocated: usize,
+    /// Maximum amount of bytes, that was allocated at once (high-water mark).
+    pub peak: usize,
+    /// Amount of allocations, that are not freed yet.
+    pub live_allocations: usize,
+    /// Total amount of allocations.
+    pub total_allocations: usize,
+}
+
+/// Memory statistics of every tag, that has at least one allocation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    /// Statistics of the tags.
+    pub tags: Vec<TagMemoryStatistics>,
+}
+
+impl MemoryReport {
+    /// Collects current statistics. The report is empty if [`TrackingAllocator`] is not used as
+    /// global allocator.
+    pub fn collect() -> Self {
+        let tag_count = BUILT_IN_TAG_NAMES.len() + CUSTOM_TAG_NAMES.lock().unwrap().len();
+
+        Self {
+            tags: COUNTERS[..tag_count]
+                .iter()
+                .enumerate()
+                .filter(|(_, counters)| counters.total_allocations.load(Ordering::Relaxed) > 0)
+                .map(|(index, counters)| TagMemoryStatistics {
+                    tag: MemoryTag(index),
+                    name: MemoryTag(index).name(),
+                    allocated: counters.allocated.load(Ordering::Relaxed),
+                    peak: counters.peak.load(Ordering::Relaxed),
+                    live_allocations: counters.live_allocations.load(Ordering::Relaxed),
+                    total_allocations: counters.total_allocations.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns statistics of the given tag.
+    pub fn tag(&self, tag: MemoryTag) -> Option<&TagMemoryStatistics> {
+        self.tags.iter().find(|s| s.tag == tag)
+    }
+
+    /// Returns total amount of bytes, that is currently allocated.
+    pub fn total_allocated(&self) -> usize {
+        self.tags.iter().map(|s| s.allocated).sum()
+    }
+}
+
+impl Display for MemoryReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Memory Report")?;
+        for s in self.tags.iter() {
+            writeln!(
+                f,
+                "\t{}: {:.3} Mb ({} allocations), Peak: {:.3} Mb, Total Allocations: {}",
+                s.name,
+                s.allocated as f32 / 1024.0 / 1024.0,
+                s.live_allocations,
+                s.peak as f32 / 1024.0 / 1024.0,
+                s.total_allocations
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes memory report to the log when dropped. The engine holds it as the last field, so the
+/// report shows memory that outlives the engine.
+#[derive(Default)]
+pub(crate) struct ShutdownReport;
+
+impl Drop for ShutdownReport {
+    fn drop(&mut self) {
+        let report = MemoryReport::collect();
+        if report.tags.is_empty() {
+            return;
+        }
+
+        Log::info(report.to_string());
+
+        // Untagged memory belongs to the user, it is not a leak of the engine.
+        for s in report
+            .tags
+            .iter()
+            .filter(|s| s.tag != MemoryTag::UNTAGGED && s.live_allocations > 0)
+        {
+            Log::warn(format!(
+                "Leak: {} bytes ({} allocations) of {} are still allocated after the engine was destroyed.",
+                s.allocated, s.live_allocations, s.name
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::memory::{MemoryTag, COUNTERS};
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn test_memory_tag_scope() {
+        let tag = MemoryTag::register("Test").unwrap();
+        assert_eq!(tag.name(), "Test");
+
+        assert_eq!(MemoryTag::current(), MemoryTag::UNTAGGED);
+        {
+            let _scope = MemoryTag::PHYSICS.enter();
+            {
+                let _scope = tag.enter();
+                assert_eq!(MemoryTag::current(), tag);
+            }
+            assert_eq!(MemoryTag::current(), MemoryTag::PHYSICS);
+        }
+        assert_eq!(MemoryTag::current(), MemoryTag::UNTAGGED);
+
+        let counters = &COUNTERS[tag.0];
+        counters.on_alloc(100);
+        counters.on_realloc(100, 300);
+        counters.on_alloc(50);
+        counters.on_dealloc(300);
+        assert_eq!(counters.allocated.load(Ordering::Relaxed), 50);
+        assert_eq!(counters.peak.load(Ordering::Relaxed), 350);
+        assert_eq!(counters.live_allocations.load(Ordering::Relaxed), 1);
+        assert_eq!(counters.total_allocations.load(Ordering::Relaxed), 2);
+    }
+}
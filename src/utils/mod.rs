@@ -4,20 +4,31 @@
 
 pub mod astar;
 pub mod behavior;
+pub mod capture;
 pub mod component;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cook;
+pub mod debug_ui;
+pub mod fsm;
+pub mod impact;
 pub mod lightmap;
 pub mod log;
+#[cfg(feature = "memory-tracking")]
+pub mod memory;
 pub mod navmesh;
+pub mod options;
+pub mod preview;
 pub mod raw_mesh;
 pub mod uvgen;
 pub mod watcher;
+pub mod world_anchor;
 
 use crate::{
     core::algebra::Vector2,
-    event::{ElementState, ModifiersState, MouseScrollDelta, VirtualKeyCode, WindowEvent},
+    event::{ElementState, Ime, ModifiersState, MouseScrollDelta, VirtualKeyCode, WindowEvent},
     gui::{
         draw,
-        message::{ButtonState, KeyCode, KeyboardModifiers, OsEvent},
+        message::{ButtonState, ImeEvent, KeyCode, KeyboardModifiers, OsEvent},
     },
     resource::texture::Texture,
 };
@@ -255,6 +266,15 @@ pub fn translate_state(state: ElementState) -> ButtonState {
 pub fn translate_event(event: &WindowEvent) -> Option<OsEvent> {
     match event {
         WindowEvent::ReceivedCharacter(c) => Some(OsEvent::Character(*c)),
+        WindowEvent::Ime(ime) => Some(OsEvent::Ime(match ime {
+            Ime::Enabled => ImeEvent::Enabled,
+            Ime::Preedit(text, cursor) => ImeEvent::Preedit {
+                text: text.clone(),
+                cursor: *cursor,
+            },
+            Ime::Commit(text) => ImeEvent::Commit(text.clone()),
+            Ime::Disabled => ImeEvent::Disabled,
+        })),
         WindowEvent::KeyboardInput { input, .. } => {
             input.virtual_keycode.map(|key| OsEvent::KeyboardInput {
                 button: translate_key(key),
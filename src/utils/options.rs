@@ -0,0 +1,481 @@
+//! Ready-made options menu bound to engine settings. See [`OptionsMenu`] docs for more info.
+
+use crate::{
+    core::pool::Handle,
+    dpi::PhysicalSize,
+    gui::{
+        border::BorderBuilder,
+        check_box::{CheckBoxBuilder, CheckBoxMessage},
+        decorator::DecoratorBuilder,
+        dropdown_list::{DropdownListBuilder, DropdownListMessage},
+        grid::{Column, GridBuilder, Row},
+        key::{KeyBindingEditorBuilder, KeyBindingEditorMessage},
+        message::{MessageDirection, UiMessage},
+        scroll_bar::{ScrollBarBuilder, ScrollBarMessage},
+        text::TextBuilder,
+        widget::WidgetBuilder,
+        BuildContext, HorizontalAlignment, Thickness, UiNode, VerticalAlignment,
+    },
+    input::map::InputMap,
+    plugin::PluginContext,
+    renderer::QualitySettings,
+    scene::Scene,
+    settings::{GRAPHICS_SECTION, INPUT_SECTION},
+    utils::log::Log,
+    window::Fullscreen,
+};
+
+const ROW_HEIGHT: f32 = 26.0;
+
+/// Predefined set of graphics quality settings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QualityPreset {
+    /// See [`QualitySettings::low`].
+    Low,
+    /// See [`QualitySettings::medium`].
+    Medium,
+    /// See [`QualitySettings::high`].
+    High,
+    /// See [`QualitySettings::ultra`].
+    Ultra,
+}
+
+impl QualityPreset {
+    /// Every preset from the lowest to the highest quality.
+    pub const ALL: [QualityPreset; 4] = [
+        QualityPreset::Low,
+        QualityPreset::Medium,
+        QualityPreset::High,
+        QualityPreset::Ultra,
+    ];
+
+    /// Returns a human-readable name of the preset.
+    pub fn name(self) -> &'static str {
+        match self {
+            QualityPreset::Low => "Low",
+            QualityPreset::Medium => "Medium",
+            QualityPreset::High => "High",
+            QualityPreset::Ultra => "Ultra",
+        }
+    }
+
+    /// Returns quality settings of the preset.
+    pub fn settings(self) -> QualitySettings {
+        match self {
+            QualityPreset::Low => QualitySettings::low(),
+            QualityPreset::Medium => QualitySettings::medium(),
+            QualityPreset::High => QualitySettings::high(),
+            QualityPreset::Ultra => QualitySettings::ultra(),
+        }
+    }
+
+    /// Returns a preset that matches the given settings exactly, `None` for custom settings.
+    pub fn from_settings(settings: &QualitySettings) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .cloned()
+            .find(|preset| preset.settings() == *settings)
+    }
+}
+
+/// A volume slider of the options menu.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VolumeChannel {
+    /// Master gain of the sound engine, it affects every sound of every scene.
+    Master,
+    /// Master gain of the sound context of the given scene. It could be used to control volume of a
+    /// particular group of sounds (for example, a scene with music and a scene with gameplay).
+    Scene(Handle<Scene>),
+}
+
+/// Options menu is a set of widgets bound to engine settings, so a game could have a functional options
+/// screen out of the box. It contains:
+///
+/// - Resolution selector and fullscreen switch of the main window.
+/// - Graphics quality preset selector (see [`QualityPreset`]).
+/// - Volume sliders for the sound engine and sound contexts of scenes (see [`VolumeChannel`]).
+/// - Rebind buttons for every action of an [`InputMap`].
+///
+/// Every change is applied immediately when the respective widget is changed. Graphics quality settings
+/// and key bindings are also stored in [`crate::settings::Settings`] (see [`GRAPHICS_SECTION`] and
+/// [`INPUT_SECTION`]), so they could be restored on next run. The menu is just a grid of labels and
+/// widgets, its root could be placed anywhere (for example, in a window or in a main menu).
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     gui::{key::KeyBinding, message::{KeyCode, UiMessage}},
+///     input::map::InputMap,
+///     plugin::PluginContext,
+///     utils::options::{OptionsMenu, OptionsMenuBuilder, VolumeChannel},
+/// };
+///
+/// fn create_options_menu(context: &mut PluginContext) -> OptionsMenu {
+///     let mut input_map = InputMap::default();
+///     input_map.bind("Jump", KeyBinding::Some(KeyCode::Space));
+///
+///     OptionsMenuBuilder::new()
+///         .with_volume_channel("Master Volume", VolumeChannel::Master)
+///         .with_input_map(input_map)
+///         .build(context)
+/// }
+///
+/// // Must be called from `Plugin::on_ui_message`.
+/// fn on_ui_message(menu: &mut OptionsMenu, context: &mut PluginContext, message: &UiMessage) {
+///     menu.handle_ui_message(context, message);
+/// }
+/// ```
+pub struct OptionsMenu {
+    /// A handle of the root widget of the menu.
+    pub root: Handle<UiNode>,
+    resolution: Handle<UiNode>,
+    resolutions: Vec<PhysicalSize<u32>>,
+    fullscreen: Handle<UiNode>,
+    quality: Handle<UiNode>,
+    volumes: Vec<(Handle<UiNode>, VolumeChannel)>,
+    key_bindings: Vec<(Handle<UiNode>, String)>,
+    input_map: InputMap,
+}
+
+/// Options menu builder creates [`OptionsMenu`] using current engine settings as initial values of the
+/// widgets.
+pub struct OptionsMenuBuilder {
+    widget_builder: WidgetBuilder,
+    volume_channels: Vec<(String, VolumeChannel)>,
+    input_map: InputMap,
+}
+
+impl Default for OptionsMenuBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn make_label(ctx: &mut BuildContext, text: &str, row: usize) -> Handle<UiNode> {
+    TextBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(0)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_text(text)
+    .with_vertical_text_alignment(VerticalAlignment::Center)
+    .build(ctx)
+}
+
+fn make_dropdown_item(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
+    DecoratorBuilder::new(BorderBuilder::new(
+        WidgetBuilder::new().with_height(ROW_HEIGHT).with_child(
+            TextBuilder::new(WidgetBuilder::new())
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                .with_text(text)
+                .build(ctx),
+        ),
+    ))
+    .build(ctx)
+}
+
+fn make_dropdown(
+    ctx: &mut BuildContext,
+    items: &[String],
+    selected: Option<usize>,
+    row: usize,
+) -> Handle<UiNode> {
+    let items = items
+        .iter()
+        .map(|item| make_dropdown_item(ctx, item))
+        .collect();
+    DropdownListBuilder::new(
+        WidgetBuilder::new()
+            .on_row(row)
+            .on_column(1)
+            .with_margin(Thickness::uniform(1.0)),
+    )
+    .with_items(items)
+    .with_opt_selected(selected)
+    .build(ctx)
+}
+
+impl OptionsMenuBuilder {
+    /// Creates new options menu builder.
+    pub fn new() -> Self {
+        Self {
+            widget_builder: WidgetBuilder::new(),
+            volume_channels: Default::default(),
+            input_map: Default::default(),
+        }
+    }
+
+    /// Sets a widget builder that will be used to create the root widget of the menu.
+    pub fn with_widget_builder(mut self, widget_builder: WidgetBuilder) -> Self {
+        self.widget_builder = widget_builder;
+        self
+    }
+
+    /// Adds a new volume slider with the given label.
+    pub fn with_volume_channel<S: AsRef<str>>(mut self, label: S, channel: VolumeChannel) -> Self {
+        self.volume_channels
+            .push((label.as_ref().to_owned(), channel));
+        self
+    }
+
+    /// Sets an input map, every action of the map will have its own rebind button. Bindings stored in
+    /// [`crate::settings::Settings`] (see [`INPUT_SECTION`]) override the bindings of the map, when
+    /// the menu is built.
+    pub fn with_input_map(mut self, input_map: InputMap) -> Self {
+        self.input_map = input_map;
+        self
+    }
+
+    /// Creates the menu in the user interface of the given context.
+    pub fn build(mut self, context: &mut PluginContext) -> OptionsMenu {
+        if let Some(stored) = context.services.settings.get::<InputMap>(INPUT_SECTION) {
+            for (action, binding) in stored.bindings() {
+                if self.input_map.binding(action).is_some() {
+                    self.input_map.bind(action, binding.clone());
+                }
+            }
+        }
+
+        let current_size = context.window.inner_size();
+        let mut resolutions = context
+            .window
+            .current_monitor()
+            .map(|monitor| {
+                monitor
+                    .video_modes()
+                    .map(|mode| mode.size())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if !resolutions.contains(&current_size) {
+            resolutions.push(current_size);
+        }
+        resolutions.sort_by_key(|size| std::cmp::Reverse((size.width, size.height)));
+        resolutions.dedup();
+
+        let quality_settings = context
+            .renderer
+            .as_ref()
+            .map(|renderer| renderer.get_quality_settings())
+            .unwrap_or_default();
+        let fullscreen = context.window.fullscreen().is_some();
+
+        let gains = self
+            .volume_channels
+            .iter()
+            .map(|(_, channel)| match channel {
+                VolumeChannel::Master => context.sound_engine.sound_gain(),
+                VolumeChannel::Scene(scene) => context
+                    .scenes
+                    .try_get(*scene)
+                    .map_or(1.0, |scene| scene.graph.sound_context.master_gain()),
+            })
+            .collect::<Vec<_>>();
+
+        let ctx = &mut context.user_interface.build_ctx();
+
+        let mut children = Vec::new();
+        let mut row = 0;
+
+        children.push(make_label(ctx, "Resolution", row));
+        let resolution = make_dropdown(
+            ctx,
+            &resolutions
+                .iter()
+                .map(|size| format!("{}x{}", size.width, size.height))
+                .collect::<Vec<_>>(),
+            resolutions.iter().position(|size| *size == current_size),
+            row,
+        );
+        children.push(resolution);
+        row += 1;
+
+        children.push(make_label(ctx, "Fullscreen", row));
+        let fullscreen = CheckBoxBuilder::new(
+            WidgetBuilder::new()
+                .on_row(row)
+                .on_column(1)
+                .with_margin(Thickness::uniform(1.0))
+                .with_horizontal_alignment(HorizontalAlignment::Left),
+        )
+        .checked(Some(fullscreen))
+        .build(ctx);
+        children.push(fullscreen);
+        row += 1;
+
+        children.push(make_label(ctx, "Graphics Quality", row));
+        let quality = make_dropdown(
+            ctx,
+            &QualityPreset::ALL
+                .iter()
+                .map(|preset| preset.name().to_owned())
+                .collect::<Vec<_>>(),
+            QualityPreset::from_settings(&quality_settings)
+                .and_then(|preset| QualityPreset::ALL.iter().position(|p| *p == preset)),
+            row,
+        );
+        children.push(quality);
+        row += 1;
+
+        let mut volumes = Vec::new();
+        for ((label, channel), gain) in self.volume_channels.iter().zip(gains) {
+            children.push(make_label(ctx, label, row));
+            let slider = ScrollBarBuilder::new(
+                WidgetBuilder::new()
+                    .on_row(row)
+                    .on_column(1)
+                    .with_margin(Thickness::uniform(1.0)),
+            )
+            .with_min(0.0)
+            .with_max(1.0)
+            .with_step(0.05)
+            .with_value(gain)
+            .show_value(true)
+            .build(ctx);
+            children.push(slider);
+            volumes.push((slider, *channel));
+            row += 1;
+        }
+
+        let mut key_bindings = Vec::new();
+        for (action, binding) in self.input_map.bindings() {
+            children.push(make_label(ctx, action, row));
+            let editor = KeyBindingEditorBuilder::new(
+                WidgetBuilder::new()
+                    .on_row(row)
+                    .on_column(1)
+                    .with_margin(Thickness::uniform(1.0)),
+            )
+            .with_value(binding.clone())
+            .build(ctx);
+            children.push(editor);
+            key_bindings.push((editor, action.to_owned()));
+            row += 1;
+        }
+
+        let root = GridBuilder::new(self.widget_builder.with_children(children))
+            .add_column(Column::strict(200.0))
+            .add_column(Column::stretch())
+            .add_rows((0..row).map(|_| Row::strict(ROW_HEIGHT)).collect())
+            .build(ctx);
+
+        OptionsMenu {
+            root,
+            resolution,
+            resolutions,
+            fullscreen,
+            quality,
+            volumes,
+            key_bindings,
+            input_map: self.input_map,
+        }
+    }
+}
+
+impl OptionsMenu {
+    /// Returns current input map with every rebound action.
+    pub fn input_map(&self) -> &InputMap {
+        &self.input_map
+    }
+
+    /// Applies changes made in the widgets of the menu. The method must be called from
+    /// [`crate::plugin::Plugin::on_ui_message`]. Returns `true` if the message was related to the menu.
+    pub fn handle_ui_message(&mut self, context: &mut PluginContext, message: &UiMessage) -> bool {
+        if message.direction() != MessageDirection::FromWidget {
+            return false;
+        }
+
+        let destination = message.destination();
+
+        if let Some(DropdownListMessage::SelectionChanged(Some(index))) = message.data() {
+            if destination == self.resolution {
+                if let Some(size) = self.resolutions.get(*index) {
+                    context.window.set_inner_size(*size);
+                }
+                return true;
+            } else if destination == self.quality {
+                if let Some(preset) = QualityPreset::ALL.get(*index) {
+                    let quality_settings = preset.settings();
+                    if let Some(renderer) = context.renderer.as_mut() {
+                        if let Err(e) = renderer.set_quality_settings(&quality_settings) {
+                            Log::err(format!("Unable to set quality settings. Reason: {:?}", e));
+                        }
+                    }
+                    if let Err(e) = context
+                        .services
+                        .settings
+                        .set(GRAPHICS_SECTION, &quality_settings)
+                    {
+                        Log::err(format!("Unable to store quality settings. Reason: {}", e));
+                    }
+                }
+                return true;
+            }
+        } else if let Some(CheckBoxMessage::Check(Some(value))) = message.data() {
+            if destination == self.fullscreen {
+                context.window.set_fullscreen(if *value {
+                    Some(Fullscreen::Borderless(None))
+                } else {
+                    None
+                });
+                return true;
+            }
+        } else if let Some(ScrollBarMessage::Value(value)) = message.data() {
+            if let Some((_, channel)) = self
+                .volumes
+                .iter()
+                .find(|(slider, _)| *slider == destination)
+            {
+                match channel {
+                    VolumeChannel::Master => context.sound_engine.set_sound_gain(*value),
+                    VolumeChannel::Scene(scene) => {
+                        if let Some(scene) = context.scenes.try_get_mut(*scene) {
+                            scene.graph.sound_context.set_master_gain(*value);
+                        }
+                    }
+                }
+                return true;
+            }
+        } else if let Some(KeyBindingEditorMessage::Value(binding)) = message.data() {
+            if let Some((_, action)) = self
+                .key_bindings
+                .iter()
+                .find(|(editor, _)| *editor == destination)
+            {
+                self.input_map.bind(action, binding.clone());
+                if let Err(e) = context
+                    .services
+                    .settings
+                    .set(INPUT_SECTION, &self.input_map)
+                {
+                    Log::err(format!("Unable to store key bindings. Reason: {}", e));
+                }
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{renderer::QualitySettings, utils::options::QualityPreset};
+
+    #[test]
+    fn test_quality_preset_from_settings() {
+        for preset in QualityPreset::ALL.iter() {
+            assert_eq!(
+                QualityPreset::from_settings(&preset.settings()),
+                Some(*preset)
+            );
+        }
+
+        let mut custom = QualitySettings::ultra();
+        custom.use_ssao = !custom.use_ssao;
+        assert_eq!(QualityPreset::from_settings(&custom), None);
+    }
+}
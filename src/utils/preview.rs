@@ -0,0 +1,348 @@
+//! Off-screen preview renderer, that renders thumbnails of models, materials, particle systems, etc.
+//! See [`PreviewRenderer`] docs for more info.
+
+use crate::{
+    asset::ResourceState,
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+    },
+    material::SharedMaterial,
+    renderer::Renderer,
+    resource::{
+        model::Model,
+        texture::{
+            Texture, TextureData, TextureError, TextureKind, TexturePixelKind, TextureState,
+        },
+    },
+    scene::{
+        base::BaseBuilder,
+        camera::{CameraBuilder, Projection},
+        light::{directional::DirectionalLightBuilder, BaseLightBuilder},
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+            MeshBuilder,
+        },
+        node::Node,
+        transform::TransformBuilder,
+        Scene, SceneContainer,
+    },
+};
+use std::collections::VecDeque;
+
+/// A source of a preview.
+#[derive(Clone, Debug)]
+pub enum PreviewSource {
+    /// A model (or a prefab) will be instantiated and rendered. Particle systems are rendered as well,
+    /// use [`PreviewRenderer::set_warmup_frames`] to give them some time to emit particles.
+    Model(Model),
+    /// A material will be rendered on a sphere.
+    Material(SharedMaterial),
+}
+
+struct PreviewRequest {
+    source: PreviewSource,
+    texture: Texture,
+}
+
+struct ActivePreview {
+    request: PreviewRequest,
+    root: Handle<Node>,
+    placed_at_frame: u64,
+}
+
+/// Preview renderer renders small previews (thumbnails) of models, materials, particle systems, etc.
+/// into textures. It is used by the editor's asset browser, and could be used by games to render
+/// item previews for inventories, shops and so on.
+///
+/// Previews are rendered asynchronously one-by-one: [`Self::request`] returns a texture immediately,
+/// the texture is in pending state until its preview is rendered. The texture could be awaited or
+/// it could be put to an image widget right away - it will be shown once it is ready. To make it
+/// work you must call [`Self::update`] every frame.
+///
+/// The renderer owns a separate scene, which is disabled while there is nothing to render, so it
+/// does not consume any resources when idle.
+pub struct PreviewRenderer {
+    scene: Handle<Scene>,
+    camera: Handle<Node>,
+    size: u32,
+    warmup_frames: u64,
+    queue: VecDeque<PreviewRequest>,
+    active: Option<ActivePreview>,
+}
+
+impl PreviewRenderer {
+    /// Creates new preview renderer, that will render previews of the given size (in pixels).
+    pub fn new(scenes: &mut SceneContainer, size: u32) -> Self {
+        let size = size.max(1);
+
+        let mut scene = Scene::new();
+
+        let camera = CameraBuilder::new(BaseBuilder::new())
+            .with_fov(40.0f32.to_radians())
+            .build(&mut scene.graph);
+
+        DirectionalLightBuilder::new(
+            BaseLightBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_rotation(
+                            UnitQuaternion::from_axis_angle(
+                                &Vector3::y_axis(),
+                                45.0f32.to_radians(),
+                            ) * UnitQuaternion::from_axis_angle(
+                                &Vector3::x_axis(),
+                                45.0f32.to_radians(),
+                            ),
+                        )
+                        .build(),
+                ),
+            )
+            .cast_shadows(false),
+        )
+        .build(&mut scene.graph);
+
+        scene.ambient_lighting_color = Color::opaque(80, 80, 80);
+        scene.render_target = Some(Texture::new_render_target(size, size));
+        scene.enabled = false;
+
+        Self {
+            scene: scenes.add(scene),
+            camera,
+            size,
+            warmup_frames: 1,
+            queue: Default::default(),
+            active: None,
+        }
+    }
+
+    /// Returns a handle of the scene, that is used to render previews. It could be used to modify
+    /// lighting, background, etc.
+    pub fn scene(&self) -> Handle<Scene> {
+        self.scene
+    }
+
+    /// Returns size (in pixels) of the previews.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Sets amount of frames the preview scene will be rendered before its preview will be taken.
+    /// Larger values are useful for particle systems, so they will have enough time to emit
+    /// particles. Default is 1.
+    pub fn set_warmup_frames(&mut self, frames: u64) {
+        self.warmup_frames = frames.max(1);
+    }
+
+    /// Returns amount of warmup frames. See [`Self::set_warmup_frames`].
+    pub fn warmup_frames(&self) -> u64 {
+        self.warmup_frames
+    }
+
+    /// Adds a new preview to the queue. Returns a texture that is in pending state until the preview
+    /// is rendered.
+    pub fn request(&mut self, source: PreviewSource) -> Texture {
+        let texture = Texture(crate::asset::Resource::new(TextureState::new_pending(
+            Default::default(),
+        )));
+
+        self.queue.push_back(PreviewRequest {
+            source,
+            texture: texture.clone(),
+        });
+
+        texture
+    }
+
+    /// Returns amount of previews that are not rendered yet.
+    pub fn pending_count(&self) -> usize {
+        self.queue.len() + self.active.as_ref().map_or(0, |_| 1)
+    }
+
+    /// Removes every queued preview. Textures of removed previews will stay in pending state.
+    pub fn clear(&mut self, scenes: &mut SceneContainer) {
+        self.queue.clear();
+        if let Some(active) = self.active.take() {
+            if let Some(scene) = scenes.try_get_mut(self.scene) {
+                scene.graph.remove_node(active.root);
+            }
+        }
+    }
+
+    /// Reads back finished previews and prepares next ones. Must be called every frame.
+    pub fn update(&mut self, scenes: &mut SceneContainer, renderer: &mut Renderer) {
+        let scene = match scenes.try_get_mut(self.scene) {
+            Some(scene) => scene,
+            None => return,
+        };
+
+        if let Some(active) = self.active.as_ref() {
+            if renderer.frame_number() < active.placed_at_frame + self.warmup_frames {
+                // Preview is not rendered yet.
+                return;
+            }
+
+            let active = self.active.take().unwrap();
+
+            scene.graph.remove_node(active.root);
+
+            let result = renderer
+                .read_scene_frame(self.scene)
+                .and_then(|image| {
+                    TextureData::from_bytes(
+                        TextureKind::Rectangle {
+                            width: image.width(),
+                            height: image.height(),
+                        },
+                        TexturePixelKind::RGBA8,
+                        image.into_raw(),
+                        false,
+                    )
+                })
+                .ok_or(TextureError::UnsupportedFormat);
+
+            let mut state = active.request.texture.state();
+            match result {
+                Ok(data) => state.commit_ok(data),
+                Err(error) => state.commit_error(Default::default(), error),
+            }
+        }
+
+        while let Some(request) = self.queue.pop_front() {
+            let root = match request.source {
+                PreviewSource::Model(ref model) => {
+                    let state = model.state();
+                    match *state {
+                        ResourceState::Pending { .. } => {
+                            drop(state);
+                            // Wait until the model is loaded.
+                            self.queue.push_front(request);
+                            break;
+                        }
+                        ResourceState::LoadError { ref error, .. } => {
+                            let error = TextureError::Io(std::io::Error::new(
+                                std::io::ErrorKind::Other,
+                                format!("Unable to render a preview of the model: {:?}", error),
+                            ));
+                            drop(state);
+                            request
+                                .texture
+                                .state()
+                                .commit_error(Default::default(), error);
+                            continue;
+                        }
+                        ResourceState::Ok(_) => {
+                            drop(state);
+                            model.instantiate(scene)
+                        }
+                    }
+                }
+                PreviewSource::Material(ref material) => MeshBuilder::new(BaseBuilder::new())
+                    .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                        SurfaceData::make_sphere(32, 32, 0.5, &Matrix4::identity()),
+                    ))
+                    .with_material(material.clone())
+                    .build()])
+                    .build(&mut scene.graph),
+            };
+
+            self.fit_camera(scene, root);
+
+            self.active = Some(ActivePreview {
+                request,
+                root,
+                placed_at_frame: renderer.frame_number(),
+            });
+
+            break;
+        }
+
+        scene.enabled = self.active.is_some();
+    }
+
+    fn fit_camera(&self, scene: &mut Scene, root: Handle<Node>) {
+        let graph = &mut scene.graph;
+
+        graph.update_hierarchical_data();
+
+        let mut bounding_box = AxisAlignedBoundingBox::default();
+        for node in graph.traverse_iter(root) {
+            let local_bounding_box = node.local_bounding_box();
+            if is_valid(&local_bounding_box) {
+                bounding_box.add_box(local_bounding_box.transform(&node.global_transform()));
+            }
+        }
+        if !is_valid(&bounding_box) {
+            bounding_box = AxisAlignedBoundingBox::unit();
+        }
+
+        let fov = match graph[self.camera].as_camera().projection() {
+            Projection::Perspective(perspective) => perspective.fov,
+            Projection::Orthographic(_) => 40.0f32.to_radians(),
+        };
+
+        let radius = bounding_box.half_extents().norm().max(0.001);
+        let distance = radius / (fov * 0.5).sin();
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 225.0f32.to_radians())
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 30.0f32.to_radians());
+        let position = bounding_box.center() - rotation * Vector3::new(0.0, 0.0, distance);
+
+        let camera = graph[self.camera].as_camera_mut();
+        camera
+            .local_transform_mut()
+            .set_position(position)
+            .set_rotation(rotation);
+        if let Projection::Perspective(perspective) = camera.projection_mut() {
+            perspective.z_near = (distance - radius).max(0.01) * 0.5;
+            perspective.z_far = (distance + radius) * 2.0;
+        }
+    }
+}
+
+fn is_valid(bounding_box: &AxisAlignedBoundingBox) -> bool {
+    bounding_box.min.x <= bounding_box.max.x
+        && bounding_box.min.y <= bounding_box.max.y
+        && bounding_box.min.z <= bounding_box.max.z
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        material::{Material, SharedMaterial},
+        resource::texture::TextureKind,
+        scene::SceneContainer,
+        utils::preview::{PreviewRenderer, PreviewSource},
+    };
+
+    #[test]
+    fn test_preview_requests() {
+        let mut scenes = SceneContainer::new(Default::default());
+        let mut previews = PreviewRenderer::new(&mut scenes, 64);
+
+        // Preview scene must not be rendered while there is nothing to preview.
+        assert!(!scenes[previews.scene()].enabled);
+        assert!(matches!(
+            scenes[previews.scene()]
+                .render_target
+                .as_ref()
+                .unwrap()
+                .data_ref()
+                .kind(),
+            TextureKind::Rectangle {
+                width: 64,
+                height: 64
+            }
+        ));
+
+        let texture = previews.request(PreviewSource::Material(SharedMaterial::new(
+            Material::standard(),
+        )));
+        assert!(texture.is_loading());
+        assert_eq!(previews.pending_count(), 1);
+
+        previews.clear(&mut scenes);
+        assert_eq!(previews.pending_count(), 0);
+    }
+}
@@ -31,7 +31,8 @@ impl FileSystemWatcher {
         })
     }
 
-    pub(crate) fn try_get_event(&self) -> Option<Event> {
+    /// Tries to fetch next file system event, returns `None` if there is no pending events.
+    pub fn try_get_event(&self) -> Option<Event> {
         if let Ok(Ok(evt)) = self.receiver.try_recv() {
             return Some(evt);
         }
@@ -0,0 +1,341 @@
+//! Anchoring of UI widgets to scene nodes. See [`WorldAnchor`] docs for more info.
+
+#![warn(missing_docs)]
+
+use crate::{
+    core::{
+        algebra::{Vector2, Vector3, Vector4},
+        pool::Handle,
+    },
+    gui::{message::MessageDirection, widget::WidgetMessage, UiNode, UserInterface},
+    scene::{camera::Camera, graph::Graph, node::Node},
+};
+
+/// Describes where an anchor point was placed on screen.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AnchorPlacement {
+    /// Anchor point is inside the viewport of the camera. Contains screen position of the point.
+    OnScreen(Vector2<f32>),
+    /// Anchor point is outside the viewport of the camera or behind it.
+    OffScreen {
+        /// Position at the edge of the viewport (minus the edge margin), that is closest to the
+        /// direction to the anchor point.
+        position: Vector2<f32>,
+        /// Normalized screen-space direction from the center of the viewport to the anchor point.
+        /// It could be used to rotate an off-screen indicator (an arrow, for example).
+        direction: Vector2<f32>,
+    },
+    /// Anchored node or the camera does not exist.
+    Hidden,
+}
+
+/// World anchor keeps a UI widget over a scene node, it is used for health bars over heads of
+/// characters, name plates, quest markers and so on. The widget should be a child of a canvas
+/// (or the root), because its position is set by [`WidgetMessage::DesiredPosition`] messages.
+///
+/// Position of the node is projected on screen using given camera (see [`crate::scene::camera::Camera::project`])
+/// on every [`WorldAnchor::update`] call. When the node is not visible on screen, the widget could
+/// be either hidden or clamped to the edges of the viewport. Optional off-screen indicator widget
+/// is shown at the edge of the viewport in the direction of the node, while the node is not on
+/// screen.
+///
+/// # Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{
+///         algebra::{Vector2, Vector3},
+///         pool::Handle,
+///     },
+///     gui::{UiNode, UserInterface},
+///     scene::{node::Node, Scene},
+///     utils::world_anchor::WorldAnchor,
+/// };
+///
+/// fn update_health_bar(
+///     anchor: &WorldAnchor,
+///     scene: &Scene,
+///     camera: Handle<Node>,
+///     ui: &mut UserInterface,
+///     screen_size: Vector2<f32>,
+/// ) {
+///     anchor.update(&scene.graph, camera, ui, screen_size);
+/// }
+///
+/// fn make_anchor(character: Handle<Node>, health_bar: Handle<UiNode>) -> WorldAnchor {
+///     WorldAnchor::new(character, health_bar)
+///         // Above the head.
+///         .with_world_offset(Vector3::new(0.0, 2.0, 0.0))
+///         .with_pivot(Vector2::new(0.5, 1.0))
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldAnchor {
+    /// A handle of the scene node to follow.
+    pub node: Handle<Node>,
+    /// A handle of the widget that will be placed over the node.
+    pub widget: Handle<UiNode>,
+    /// World-space offset from the global position of the node.
+    pub world_offset: Vector3<f32>,
+    /// Normalized point of the widget that will be placed at the anchor point. `(0.0, 0.0)` - left
+    /// top corner, `(0.5, 0.5)` - center, `(0.5, 1.0)` - center of the bottom edge.
+    pub pivot: Vector2<f32>,
+    /// Whether to keep the widget at the edges of the viewport when the node is off screen or to
+    /// hide it.
+    pub clamp_to_edges: bool,
+    /// Distance (in pixels) from the edges of the viewport for clamped widgets and off-screen
+    /// indicators.
+    pub edge_margin: f32,
+    /// Optional widget that is shown at the edge of the viewport, when the node is off screen.
+    pub off_screen_indicator: Handle<UiNode>,
+}
+
+impl WorldAnchor {
+    /// Creates new anchor for the given node and widget. The widget is centered over the node and
+    /// hidden when the node is off screen.
+    pub fn new(node: Handle<Node>, widget: Handle<UiNode>) -> Self {
+        Self {
+            node,
+            widget,
+            world_offset: Default::default(),
+            pivot: Vector2::new(0.5, 0.5),
+            clamp_to_edges: false,
+            edge_margin: 0.0,
+            off_screen_indicator: Default::default(),
+        }
+    }
+
+    /// Sets world-space offset from the global position of the node.
+    pub fn with_world_offset(mut self, offset: Vector3<f32>) -> Self {
+        self.world_offset = offset;
+        self
+    }
+
+    /// Sets normalized point of the widget that will be placed at the anchor point.
+    pub fn with_pivot(mut self, pivot: Vector2<f32>) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    /// Sets whether to keep the widget at the edges of the viewport when the node is off screen.
+    pub fn with_clamp_to_edges(mut self, clamp: bool) -> Self {
+        self.clamp_to_edges = clamp;
+        self
+    }
+
+    /// Sets distance (in pixels) from the edges of the viewport.
+    pub fn with_edge_margin(mut self, margin: f32) -> Self {
+        self.edge_margin = margin;
+        self
+    }
+
+    /// Sets a widget that is shown at the edge of the viewport, when the node is off screen.
+    pub fn with_off_screen_indicator(mut self, indicator: Handle<UiNode>) -> Self {
+        self.off_screen_indicator = indicator;
+        self
+    }
+
+    /// Calculates screen position of the anchor point as seen from the given camera. Screen size
+    /// must be the size of the frame the camera renders to (usually the size of the window).
+    pub fn placement(
+        &self,
+        graph: &Graph,
+        camera: Handle<Node>,
+        screen_size: Vector2<f32>,
+    ) -> AnchorPlacement {
+        let (node, camera) = match (
+            graph.try_get(self.node),
+            graph.try_get(camera).and_then(|c| c.cast::<Camera>()),
+        ) {
+            (Some(node), Some(camera)) => (node, camera),
+            _ => return AnchorPlacement::Hidden,
+        };
+
+        let point = node.global_position() + self.world_offset;
+
+        // Viewport in screen coordinates (origin at left top corner).
+        let viewport = camera.viewport_pixels(screen_size);
+        let viewport_position = Vector2::new(
+            viewport.x() as f32,
+            screen_size.y - (viewport.y() + viewport.h()) as f32,
+        );
+        let viewport_size = Vector2::new(viewport.w() as f32, viewport.h() as f32);
+
+        if let Some(position) = camera.project(point, screen_size) {
+            if position.x >= viewport_position.x
+                && position.y >= viewport_position.y
+                && position.x <= viewport_position.x + viewport_size.x
+                && position.y <= viewport_position.y + viewport_size.y
+            {
+                return AnchorPlacement::OnScreen(position);
+            }
+        }
+
+        // The point is either outside the viewport or behind the camera. Projected coordinates of
+        // points behind the camera are mirrored, so they must be flipped to get correct direction.
+        let clip = camera.view_projection_matrix() * Vector4::new(point.x, point.y, point.z, 1.0);
+        let mut ndc = Vector2::new(clip.x, clip.y);
+        if clip.w.abs() > f32::EPSILON {
+            ndc /= clip.w.abs();
+        }
+        // Y axis of the screen points down.
+        let direction = Vector2::new(ndc.x, -ndc.y)
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(|| Vector2::new(0.0, 1.0));
+
+        let half_size = (viewport_size.scale(0.5) - Vector2::repeat(self.edge_margin))
+            .sup(&Vector2::repeat(0.0));
+        let t = if direction.x.abs() * half_size.y > direction.y.abs() * half_size.x {
+            half_size.x / direction.x.abs()
+        } else {
+            half_size.y / direction.y.abs()
+        };
+
+        AnchorPlacement::OffScreen {
+            position: viewport_position + viewport_size.scale(0.5) + direction.scale(t),
+            direction,
+        }
+    }
+
+    /// Calculates placement of the anchor point (see [`Self::placement`]) and moves the widget
+    /// and the off-screen indicator accordingly. The method should be called every frame after
+    /// the camera was updated.
+    pub fn update(
+        &self,
+        graph: &Graph,
+        camera: Handle<Node>,
+        ui: &mut UserInterface,
+        screen_size: Vector2<f32>,
+    ) -> AnchorPlacement {
+        let placement = self.placement(graph, camera, screen_size);
+
+        let (widget_position, indicator_position) = match placement {
+            AnchorPlacement::OnScreen(position) => (Some(position), None),
+            AnchorPlacement::OffScreen { position, .. } => (
+                if self.clamp_to_edges {
+                    Some(position)
+                } else {
+                    None
+                },
+                Some(position),
+            ),
+            AnchorPlacement::Hidden => (None, None),
+        };
+
+        place_widget(ui, self.widget, widget_position, self.pivot);
+        place_widget(
+            ui,
+            self.off_screen_indicator,
+            indicator_position,
+            Vector2::new(0.5, 0.5),
+        );
+
+        placement
+    }
+}
+
+fn place_widget(
+    ui: &mut UserInterface,
+    widget: Handle<UiNode>,
+    position: Option<Vector2<f32>>,
+    pivot: Vector2<f32>,
+) {
+    let visibility = if let Some(widget_ref) = ui.try_get_node(widget) {
+        widget_ref.visibility()
+    } else {
+        return;
+    };
+
+    if let Some(position) = position {
+        let size = ui.node(widget).actual_local_size();
+        ui.send_message(WidgetMessage::desired_position(
+            widget,
+            MessageDirection::ToWidget,
+            position - size.component_mul(&pivot),
+        ));
+    }
+
+    if visibility != position.is_some() {
+        ui.send_message(WidgetMessage::visibility(
+            widget,
+            MessageDirection::ToWidget,
+            position.is_some(),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            math::Rect,
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder, camera::CameraBuilder, graph::Graph, pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+        utils::world_anchor::{AnchorPlacement, WorldAnchor},
+    };
+
+    #[test]
+    fn test_world_anchor_placement() {
+        let screen_size = Vector2::new(800.0, 600.0);
+
+        let mut graph = Graph::new();
+        let camera = CameraBuilder::new(BaseBuilder::new())
+            .with_viewport(Rect::new(0.0, 0.0, 1.0, 1.0))
+            .build(&mut graph);
+        let make_pivot = |graph: &mut Graph, position: Vector3<f32>| {
+            PivotBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                ),
+            )
+            .build(graph)
+        };
+        let in_front = make_pivot(&mut graph, Vector3::new(0.0, 0.0, 10.0));
+        let behind = make_pivot(&mut graph, Vector3::new(0.0, 0.0, -10.0));
+        let far_above = make_pivot(&mut graph, Vector3::new(0.0, 100.0, 10.0));
+
+        graph.update_hierarchical_data();
+        graph[camera]
+            .as_camera_mut()
+            .calculate_matrices(screen_size);
+
+        match WorldAnchor::new(in_front, Handle::NONE).placement(&graph, camera, screen_size) {
+            AnchorPlacement::OnScreen(position) => {
+                assert!((position - Vector2::new(400.0, 300.0)).norm() < 0.5)
+            }
+            placement => panic!("unexpected placement {:?}", placement),
+        }
+
+        assert!(matches!(
+            WorldAnchor::new(behind, Handle::NONE).placement(&graph, camera, screen_size),
+            AnchorPlacement::OffScreen { .. }
+        ));
+
+        match WorldAnchor::new(far_above, Handle::NONE)
+            .with_edge_margin(10.0)
+            .placement(&graph, camera, screen_size)
+        {
+            AnchorPlacement::OffScreen {
+                position,
+                direction,
+            } => {
+                // Clamped to the top edge.
+                assert!((position.y - 10.0).abs() < 0.5);
+                assert!(direction.y < 0.0);
+            }
+            placement => panic!("unexpected placement {:?}", placement),
+        }
+
+        assert_eq!(
+            WorldAnchor::new(Handle::NONE, Handle::NONE).placement(&graph, camera, screen_size),
+            AnchorPlacement::Hidden
+        );
+    }
+}
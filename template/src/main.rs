@@ -119,6 +119,20 @@ fyrox = "0.29""#,
         ),
     );
 
+    // Write clippy.toml - it forbids non-deterministic sources of time and randomness in game code.
+    // Scripts should use `ScriptContext::tick_count` and `ScriptContext::rng` instead.
+    write_file(
+        base_path.join("game/clippy.toml"),
+        r#"disallowed-methods = [
+    { path = "std::time::Instant::now", reason = "use `ScriptContext::tick_count` or `ScriptContext::elapsed_time` instead" },
+    { path = "std::time::SystemTime::now", reason = "use `ScriptContext::tick_count` or `ScriptContext::elapsed_time` instead" },
+    { path = "fyrox::core::instant::Instant::now", reason = "use `ScriptContext::tick_count` or `ScriptContext::elapsed_time` instead" },
+    { path = "fyrox::rand::thread_rng", reason = "use `ScriptContext::rng` instead" },
+    { path = "fyrox::rand::random", reason = "use `ScriptContext::rng` instead" },
+]
+"#,
+    );
+
     // Write lib.rs
     write_file(
         base_path.join("game/src/lib.rs"),